@@ -0,0 +1,105 @@
+use bevy::prelude::*;
+use std::io::BufRead;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Mutex;
+
+/// 试玩终端命令
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlaytestCommand {
+    /// `note <文字>`：给当前会话添加备注
+    Note(String),
+    /// `export`：立即导出
+    Export,
+    /// `stream <场景> [速度,...]`：开始区块流式加载压测，可覆盖场景的速度
+    StreamTest {
+        scenario: String,
+        speeds: Option<Vec<f32>>,
+    },
+    /// `log [目标=级别|reset]`：查看、临时设置或清除日志过滤规则
+    Log(Option<String>),
+    /// `preview [半径] [缩放]`：导出玩家周围的地图预览图
+    MapPreview { radius: u32, scale: u32 },
+}
+
+impl PlaytestCommand {
+    /// 解析一行终端输入，无法识别时返回 None
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        match command {
+            "note" | "n" if !rest.trim().is_empty() => {
+                Some(PlaytestCommand::Note(rest.trim().to_string()))
+            }
+            "export" => Some(PlaytestCommand::Export),
+            "stream" => {
+                let mut parts = rest.split_whitespace();
+                let scenario = parts.next().unwrap_or("fast_travel").to_string();
+                let speeds = parts
+                    .flat_map(|part| part.split(','))
+                    .filter(|part| !part.is_empty())
+                    .map(|part| part.parse::<f32>().ok())
+                    .collect::<Option<Vec<f32>>>()?;
+                Some(PlaytestCommand::StreamTest {
+                    scenario,
+                    speeds: (!speeds.is_empty()).then_some(speeds),
+                })
+            }
+            "preview" => {
+                let mut parts = rest.split_whitespace().map(|part| part.parse::<u32>().ok());
+                let radius = parts.next().unwrap_or(Some(256))?;
+                let scale = parts.next().unwrap_or(Some(1))?;
+                Some(PlaytestCommand::MapPreview { radius, scale })
+            }
+            "log" => Some(PlaytestCommand::Log(
+                (!rest.trim().is_empty()).then(|| rest.trim().to_string()),
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// 从游戏内输入的终端命令，例如聊天框中以斜杠开头的内容，与终端读到的行一样处理
+#[derive(Event, Debug, Clone)]
+pub struct ConsoleInput(pub String);
+
+/// 试玩终端
+///
+/// 后台线程逐行读取标准输入，游戏线程每帧取出已读到的行；
+/// 测试者在启动游戏的终端里输入命令即可，不需要游戏内控制台
+#[derive(Resource)]
+pub struct PlaytestConsole {
+    lines: Mutex<Receiver<String>>,
+}
+
+impl PlaytestConsole {
+    /// 启动读取标准输入的后台线程
+    pub fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let stdin = std::io::stdin();
+            for line in stdin.lock().lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            lines: Mutex::new(receiver),
+        }
+    }
+
+    /// 取出目前已输入的全部行
+    pub fn drain(&self) -> Vec<String> {
+        let Ok(receiver) = self.lines.lock() else {
+            return Vec::new();
+        };
+        let mut lines = Vec::new();
+        while let Ok(line) = receiver.try_recv() {
+            lines.push(line);
+        }
+        lines
+    }
+}
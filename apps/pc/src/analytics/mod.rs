@@ -0,0 +1,19 @@
+/// 试玩数据模块
+///
+/// 可选开启的试玩记录器，把死亡、任务用时、金钱收支、技能使用等数据写入本地文件，
+/// 供平衡性分析使用，不依赖网络
+///
+/// # 模块组成
+/// 1. sample：试玩样本定义，其他系统通过样本事件上报数据
+/// 2. recorder：记录器与 JSON/CSV 导出
+/// 3. console：终端命令，用于给会话添加备注、发起区块压测
+/// 4. systems：试玩插件及采集系统
+mod console;
+mod recorder;
+mod sample;
+mod systems;
+
+pub use console::*;
+pub use recorder::*;
+pub use sample::*;
+pub use systems::PlaytestPlugin;
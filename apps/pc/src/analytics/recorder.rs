@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+use chrono::Local;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::PlaytestSample;
+
+/// 试玩记录配置
+#[derive(Resource, Debug, Clone)]
+pub struct PlaytestSettings {
+    /// 是否记录，对应游戏设置中的分析选项，默认关闭
+    pub enabled: bool,
+    /// 导出目录
+    pub output_dir: String,
+    /// 自动导出的间隔（秒）
+    pub flush_interval: f32,
+}
+
+impl Default for PlaytestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            output_dir: "playtest".to_string(),
+            flush_interval: 60.0,
+        }
+    }
+}
+
+/// 带时间戳的样本
+#[derive(Debug, Clone, Serialize)]
+pub struct TimedSample {
+    /// 会话开始后的真实时间（秒）
+    pub time: f64,
+    #[serde(flatten)]
+    pub sample: PlaytestSample,
+}
+
+/// 导出的会话文件格式
+#[derive(Serialize)]
+struct PlaytestSessionFile<'a> {
+    session: &'a str,
+    started_at: &'a str,
+    duration_secs: f64,
+    skill_usage: &'a BTreeMap<String, u32>,
+    samples: &'a [TimedSample],
+}
+
+/// 试玩记录器
+///
+/// # 设计思路
+/// 1. 样本只追加在内存中，定期与退出时整体重写到本地文件
+/// 2. 技能使用次数只做汇总计数，不逐条记录
+/// 3. 同一会话导出两份：JSON 保留完整结构，CSV 便于表格工具分析
+#[derive(Resource, Debug)]
+pub struct PlaytestRecorder {
+    /// 会话编号，也是导出文件名
+    pub session: String,
+    started_at: String,
+    samples: Vec<TimedSample>,
+    skill_usage: BTreeMap<String, u32>,
+    /// 最近一次记录的时间
+    last_time: f64,
+    /// 上次导出后是否有新数据
+    dirty: bool,
+}
+
+impl Default for PlaytestRecorder {
+    fn default() -> Self {
+        let now = Local::now();
+        Self {
+            session: now.format("%Y%m%d-%H%M%S").to_string(),
+            started_at: now.to_rfc3339(),
+            samples: Vec::new(),
+            skill_usage: BTreeMap::new(),
+            last_time: 0.0,
+            dirty: false,
+        }
+    }
+}
+
+impl PlaytestRecorder {
+    /// 记录一个样本
+    pub fn record(&mut self, time: f64, sample: PlaytestSample) {
+        self.samples.push(TimedSample { time, sample });
+        self.last_time = self.last_time.max(time);
+        self.dirty = true;
+    }
+
+    /// 技能使用计数加一
+    pub fn count_skill(&mut self, skill: &str) {
+        *self.skill_usage.entry(skill.to_string()).or_default() += 1;
+        self.dirty = true;
+    }
+
+    /// 是否有尚未导出的数据
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// 导出到指定目录，返回 JSON 文件路径
+    pub fn export(&mut self, dir: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let json_path = self.write_to(dir)?;
+        self.dirty = false;
+        Ok(json_path)
+    }
+
+    /// 把当前数据写到指定目录，不改变导出状态，供错误报告包附带一份副本
+    pub fn write_to(&self, dir: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        fs::create_dir_all(dir)?;
+        let json_path = Path::new(dir).join(format!("{}.json", self.session));
+        let csv_path = Path::new(dir).join(format!("{}.csv", self.session));
+
+        let file = PlaytestSessionFile {
+            session: &self.session,
+            started_at: &self.started_at,
+            duration_secs: self.last_time,
+            skill_usage: &self.skill_usage,
+            samples: &self.samples,
+        };
+        fs::write(&json_path, serde_json::to_string_pretty(&file)?)?;
+        fs::write(&csv_path, self.to_csv())?;
+        Ok(json_path)
+    }
+
+    /// 生成 CSV，技能使用次数以 skill_usage 行附在末尾
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("time,type,subject,detail,value,x,y\n");
+        for timed in &self.samples {
+            let (subject, detail, value, position) = timed.sample.csv_fields();
+            let value = value.map(|value| value.to_string()).unwrap_or_default();
+            let (x, y) = position
+                .map(|(x, y)| (x.to_string(), y.to_string()))
+                .unwrap_or_default();
+            csv.push_str(&format!(
+                "{:.3},{},{},{},{},{},{}\n",
+                timed.time,
+                timed.sample.kind(),
+                csv_escape(&subject),
+                csv_escape(&detail),
+                value,
+                x,
+                y
+            ));
+        }
+        for (skill, count) in &self.skill_usage {
+            csv.push_str(&format!(
+                "{:.3},skill_usage,{},,{},,\n",
+                self.last_time,
+                csv_escape(skill),
+                count
+            ));
+        }
+        csv
+    }
+}
+
+/// 含逗号、引号或换行的字段加引号转义
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
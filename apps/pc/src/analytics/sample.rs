@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 试玩样本
+///
+/// 死亡与技能使用由记录器直接从战斗事件采集；
+/// 任务、金钱等由对应系统发送样本事件上报
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PlaytestSample {
+    /// 角色死亡
+    Death {
+        victim: String,
+        /// 是否为玩家
+        player: bool,
+        /// 最后一击的来源
+        cause: String,
+        /// 最后一击使用的技能
+        skill: Option<String>,
+        x: f32,
+        y: f32,
+    },
+    /// 完成任务
+    QuestCompleted {
+        quest: String,
+        /// 从接取到完成的用时（秒）
+        duration_secs: f32,
+    },
+    /// 获得金钱
+    GoldEarned { amount: u32, source: String },
+    /// 花费金钱
+    GoldSpent { amount: u32, sink: String },
+    /// 测试者添加的备注
+    Annotation { text: String },
+}
+
+impl PlaytestSample {
+    /// CSV 中的类型列
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PlaytestSample::Death { .. } => "death",
+            PlaytestSample::QuestCompleted { .. } => "quest_completed",
+            PlaytestSample::GoldEarned { .. } => "gold_earned",
+            PlaytestSample::GoldSpent { .. } => "gold_spent",
+            PlaytestSample::Annotation { .. } => "annotation",
+        }
+    }
+
+    /// CSV 中的 (对象, 详情, 数值, 坐标) 列
+    pub fn csv_fields(&self) -> (String, String, Option<f32>, Option<(f32, f32)>) {
+        match self {
+            PlaytestSample::Death {
+                victim,
+                player,
+                cause,
+                skill,
+                x,
+                y,
+            } => {
+                let detail = match skill {
+                    Some(skill) => format!("{}/{}", cause, skill),
+                    None => cause.clone(),
+                };
+                let subject = if *player {
+                    format!("player:{}", victim)
+                } else {
+                    victim.clone()
+                };
+                (subject, detail, None, Some((*x, *y)))
+            }
+            PlaytestSample::QuestCompleted {
+                quest,
+                duration_secs,
+            } => (quest.clone(), String::new(), Some(*duration_secs), None),
+            PlaytestSample::GoldEarned { amount, source } => {
+                (source.clone(), String::new(), Some(*amount as f32), None)
+            }
+            PlaytestSample::GoldSpent { amount, sink } => {
+                (sink.clone(), String::new(), Some(*amount as f32), None)
+            }
+            PlaytestSample::Annotation { text } => (String::new(), text.clone(), None, None),
+        }
+    }
+}
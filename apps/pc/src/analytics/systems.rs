@@ -0,0 +1,266 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+use super::{
+    ConsoleInput, PlaytestCommand, PlaytestConsole, PlaytestRecorder, PlaytestSample,
+    PlaytestSettings,
+};
+use crate::combat::{CombatActionEvent, CombatHistory, DeathEvent};
+use crate::logging::{GameLogger, LogDirective, LogLevel};
+use crate::world::chunk::StreamTestRequest;
+use crate::world::entity::{Character, Player};
+use crate::world::map::MapPreviewRequest;
+
+/// 试玩数据插件
+pub struct PlaytestPlugin;
+
+impl Plugin for PlaytestPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlaytestSample>()
+            .add_event::<ConsoleInput>();
+
+        app.init_resource::<PlaytestSettings>()
+            .init_resource::<PlaytestRecorder>();
+
+        app.add_systems(Startup, start_playtest_session)
+            .add_systems(
+                Update,
+                (
+                    record_deaths,
+                    record_skill_usage,
+                    record_samples,
+                    handle_console_commands,
+                    flush_playtest_log,
+                )
+                    .chain()
+                    .run_if(playtest_enabled),
+            )
+            .add_systems(Last, export_on_exit.run_if(playtest_enabled));
+    }
+}
+
+fn playtest_enabled(settings: Res<PlaytestSettings>) -> bool {
+    settings.enabled
+}
+
+/// 开启记录时启动终端命令读取
+fn start_playtest_session(
+    mut commands: Commands,
+    settings: Res<PlaytestSettings>,
+    recorder: Res<PlaytestRecorder>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    if !settings.enabled {
+        return;
+    }
+
+    commands.insert_resource(PlaytestConsole::spawn());
+    if let Some(logger) = logger.as_mut() {
+        logger.log(
+            LogLevel::Info,
+            &format!(
+                "试玩记录已开启，会话 {}，在终端输入 note <备注> 添加备注，export 立即导出",
+                recorder.session
+            ),
+        );
+    }
+}
+
+/// 记录死亡的来源、技能与位置
+fn record_deaths(
+    time: Res<Time<Real>>,
+    game_time: Res<Time>,
+    history: Res<CombatHistory>,
+    mut recorder: ResMut<PlaytestRecorder>,
+    mut deaths: EventReader<DeathEvent>,
+    characters: Query<(&Character, &Transform, Has<Player>)>,
+) {
+    for death in deaths.read() {
+        let Ok((character, transform, player)) = characters.get(death.entity) else {
+            continue;
+        };
+
+        // 最后一击的技能从战斗历史中取
+        let skill = history
+            .recent(death.entity, game_time.elapsed_secs(), 1.0)
+            .last()
+            .and_then(|record| record.skill.clone());
+
+        recorder.record(
+            time.elapsed_secs_f64(),
+            PlaytestSample::Death {
+                victim: character.name.clone(),
+                player,
+                cause: death.killer_name.clone(),
+                skill,
+                x: transform.translation.x,
+                y: transform.translation.y,
+            },
+        );
+    }
+}
+
+/// 统计玩家的技能使用次数，没有技能名的动作按动作类型计
+fn record_skill_usage(
+    mut recorder: ResMut<PlaytestRecorder>,
+    mut actions: EventReader<CombatActionEvent>,
+    players: Query<(), With<Player>>,
+) {
+    for action in actions.read() {
+        if !players.contains(action.entity) {
+            continue;
+        }
+        match &action.skill {
+            Some(skill) => recorder.count_skill(skill),
+            None => recorder.count_skill(&format!("{:?}", action.action)),
+        }
+    }
+}
+
+/// 记录其他系统上报的样本
+fn record_samples(
+    time: Res<Time<Real>>,
+    mut recorder: ResMut<PlaytestRecorder>,
+    mut samples: EventReader<PlaytestSample>,
+) {
+    for sample in samples.read() {
+        recorder.record(time.elapsed_secs_f64(), sample.clone());
+    }
+}
+
+/// 处理终端与游戏内输入的试玩命令
+#[allow(clippy::too_many_arguments)]
+fn handle_console_commands(
+    time: Res<Time<Real>>,
+    settings: Res<PlaytestSettings>,
+    console: Option<Res<PlaytestConsole>>,
+    mut inputs: EventReader<ConsoleInput>,
+    mut recorder: ResMut<PlaytestRecorder>,
+    mut stream_tests: Option<ResMut<Events<StreamTestRequest>>>,
+    mut map_previews: Option<ResMut<Events<MapPreviewRequest>>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let mut lines = console.map(|console| console.drain()).unwrap_or_default();
+    lines.extend(inputs.read().map(|input| input.0.clone()));
+
+    for line in lines {
+        match PlaytestCommand::parse(&line) {
+            Some(PlaytestCommand::Note(text)) => {
+                recorder.record(
+                    time.elapsed_secs_f64(),
+                    PlaytestSample::Annotation { text: text.clone() },
+                );
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(LogLevel::Info, &format!("试玩备注：{}", text));
+                }
+            }
+            Some(PlaytestCommand::Export) => {
+                export(&mut recorder, &settings, logger.as_deref_mut());
+            }
+            // 区块系统没有启用时收不到压测请求
+            Some(PlaytestCommand::StreamTest { scenario, speeds }) => match stream_tests.as_mut() {
+                Some(stream_tests) => {
+                    stream_tests.send(StreamTestRequest {
+                        scenario,
+                        speeds,
+                        exit_on_finish: false,
+                    });
+                }
+                None => {
+                    if let Some(logger) = logger.as_mut() {
+                        logger.log(LogLevel::Info, "区块系统未启用，无法进行压测");
+                    }
+                }
+            },
+            Some(PlaytestCommand::MapPreview { radius, scale }) => match map_previews.as_mut() {
+                Some(map_previews) => {
+                    map_previews.send(MapPreviewRequest { radius, scale });
+                }
+                None => {
+                    if let Some(logger) = logger.as_mut() {
+                        logger.log(LogLevel::Info, "地图系统未启用，无法导出预览");
+                    }
+                }
+            },
+            Some(PlaytestCommand::Log(directive)) => {
+                if let Some(logger) = logger.as_mut() {
+                    apply_log_command(logger, directive.as_deref());
+                }
+            }
+            None => {
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(
+                        LogLevel::Info,
+                        &format!("无法识别的试玩命令：{}（可用：note <备注>、export、stream <场景> [速度]、preview [半径] [缩放]、log [目标=级别|reset]）", line),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// 查看或调整日志过滤规则，临时规则不写入配置，重启后失效
+fn apply_log_command(logger: &mut GameLogger, directive: Option<&str>) {
+    match directive {
+        None => {}
+        Some("reset") => logger.clear_filter_overrides(),
+        Some(directive) => match LogDirective::parse(directive) {
+            Ok(directive) => logger.set_filter_override(directive),
+            Err(e) => {
+                logger.log(LogLevel::Info, &e);
+                return;
+            }
+        },
+    }
+    let directives = logger.filter_directives();
+    logger.log(LogLevel::Info, &format!("当前日志过滤规则：{}", directives));
+}
+
+/// 定期导出，避免异常退出时丢失整场数据
+fn flush_playtest_log(
+    time: Res<Time<Real>>,
+    settings: Res<PlaytestSettings>,
+    mut recorder: ResMut<PlaytestRecorder>,
+    mut elapsed: Local<f32>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    *elapsed += time.delta_secs();
+    if *elapsed < settings.flush_interval {
+        return;
+    }
+    *elapsed = 0.0;
+
+    if recorder.is_dirty() {
+        export(&mut recorder, &settings, logger.as_deref_mut());
+    }
+}
+
+/// 退出游戏时导出
+fn export_on_exit(
+    settings: Res<PlaytestSettings>,
+    mut recorder: ResMut<PlaytestRecorder>,
+    mut exits: EventReader<AppExit>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    if exits.read().last().is_some() && recorder.is_dirty() {
+        export(&mut recorder, &settings, logger.as_deref_mut());
+    }
+}
+
+fn export(
+    recorder: &mut PlaytestRecorder,
+    settings: &PlaytestSettings,
+    logger: Option<&mut GameLogger>,
+) {
+    let result = recorder.export(&settings.output_dir);
+    let Some(logger) = logger else {
+        return;
+    };
+    match result {
+        Ok(path) => logger.log(
+            LogLevel::Debug,
+            &format!("试玩数据已导出到 {}", path.display()),
+        ),
+        Err(e) => logger.log(LogLevel::Error, &format!("导出试玩数据失败: {}", e)),
+    }
+}
@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::resources::GameState;
+use crate::world::entity::NpcType;
+use crate::world::map::Zone;
+
+/// 资源分组
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetGroup {
+    /// 启动画面
+    Splash,
+    /// 界面图标与装饰
+    Ui,
+    /// 玩家、NPC与坐骑的角色贴图
+    Characters,
+    /// 各气候区域的地形瓦片
+    Tilesets,
+}
+
+impl AssetGroup {
+    pub const ALL: [AssetGroup; 4] = [
+        AssetGroup::Splash,
+        AssetGroup::Ui,
+        AssetGroup::Characters,
+        AssetGroup::Tilesets,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AssetGroup::Splash => "启动画面",
+            AssetGroup::Ui => "界面",
+            AssetGroup::Characters => "角色",
+            AssetGroup::Tilesets => "地形瓦片",
+        }
+    }
+
+    /// 进入这个状态时开始预载
+    ///
+    /// 启动画面与界面在登录时就要用到；角色与地形只在游戏中出现，放到加载界面里预载
+    pub fn preload_state(self) -> GameState {
+        match self {
+            AssetGroup::Splash | AssetGroup::Ui => GameState::Login,
+            AssetGroup::Characters | AssetGroup::Tilesets => GameState::Loading,
+        }
+    }
+}
+
+/// 资源清单：每个分组包含的资源路径（相对于 assets）
+#[derive(Resource, Debug, Clone)]
+pub struct AssetManifest {
+    pub groups: HashMap<AssetGroup, Vec<String>>,
+}
+
+impl AssetManifest {
+    pub fn paths(&self, group: AssetGroup) -> &[String] {
+        self.groups.get(&group).map_or(&[], Vec::as_slice)
+    }
+}
+
+fn paths(paths: impl IntoIterator<Item = &'static str>) -> Vec<String> {
+    paths.into_iter().map(str::to_string).collect()
+}
+
+impl Default for AssetManifest {
+    fn default() -> Self {
+        let characters = [
+            "textures/characters/player.png",
+            "textures/characters/mule.png",
+        ]
+        .into_iter()
+        .chain(NpcType::ALL.map(NpcType::texture_path));
+
+        Self {
+            groups: HashMap::from([
+                (
+                    AssetGroup::Splash,
+                    paths(["textures/ui/splash.png", "textures/ui/logo.png"]),
+                ),
+                (
+                    AssetGroup::Ui,
+                    paths([
+                        "textures/ui/icons.png",
+                        "textures/ui/compass.png",
+                        "textures/ui/cursor.png",
+                    ]),
+                ),
+                (AssetGroup::Characters, paths(characters)),
+                (
+                    AssetGroup::Tilesets,
+                    paths(Zone::ALL.iter().map(Zone::tileset_path)),
+                ),
+            ]),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use bevy::asset::{LoadState, UntypedHandle};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::AssetGroup;
+use crate::resources::GameState;
+
+/// 单个资源的加载状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum AssetStatus {
+    Loading,
+    Loaded,
+    /// 文件不存在或无法解码
+    Missing,
+}
+
+/// 跟踪中的资源，句柄保持资源常驻
+#[derive(Debug)]
+struct TrackedAsset {
+    path: String,
+    handle: UntypedHandle,
+    status: AssetStatus,
+}
+
+/// 加载失败的资源
+#[derive(Debug, Clone)]
+pub struct MissingAsset {
+    pub group: AssetGroup,
+    pub path: String,
+    pub reason: String,
+}
+
+/// 资源管理器
+///
+/// # 设计思路
+/// 1. 每个分组只请求一次，之后一直持有句柄，切换状态时不会重复加载
+/// 2. 加载状态由资源服务器轮询得到，不需要各个玩法系统上报
+/// 3. 加载失败的资源算作完成，不会卡住加载界面，但会记下来并报出路径与原因
+#[derive(Resource, Debug, Default)]
+pub struct AssetManager {
+    groups: HashMap<AssetGroup, Vec<TrackedAsset>>,
+}
+
+impl AssetManager {
+    pub fn is_requested(&self, group: AssetGroup) -> bool {
+        self.groups.contains_key(&group)
+    }
+
+    /// 开始加载一个分组，已请求过的分组不再重复加载
+    pub fn request(&mut self, group: AssetGroup, paths: &[String], asset_server: &AssetServer) {
+        if self.is_requested(group) {
+            return;
+        }
+        let assets = paths
+            .iter()
+            .map(|path| TrackedAsset {
+                path: path.clone(),
+                handle: asset_server.load_untyped(path.as_str()).untyped(),
+                status: AssetStatus::Loading,
+            })
+            .collect();
+        self.groups.insert(group, assets);
+    }
+
+    /// 刷新仍在加载的资源，返回这次发现的缺失资源
+    pub fn poll(&mut self, asset_server: &AssetServer) -> Vec<MissingAsset> {
+        let mut missing = Vec::new();
+        for (group, assets) in self.groups.iter_mut() {
+            for asset in assets
+                .iter_mut()
+                .filter(|asset| asset.status == AssetStatus::Loading)
+            {
+                match asset_server.get_load_state(asset.handle.id()) {
+                    Some(LoadState::Loaded) => asset.status = AssetStatus::Loaded,
+                    Some(LoadState::Failed(error)) => {
+                        asset.status = AssetStatus::Missing;
+                        missing.push(MissingAsset {
+                            group: *group,
+                            path: asset.path.clone(),
+                            reason: error.to_string(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+        missing
+    }
+
+    /// 分组的加载进度，未请求的分组为 0，空分组为 1
+    pub fn group_progress(&self, group: AssetGroup) -> f32 {
+        let Some(assets) = self.groups.get(&group) else {
+            return 0.0;
+        };
+        if assets.is_empty() {
+            return 1.0;
+        }
+        let finished = assets
+            .iter()
+            .filter(|asset| asset.status != AssetStatus::Loading)
+            .count();
+        finished as f32 / assets.len() as f32
+    }
+
+    /// 进入某个状态时预载的各分组的平均进度
+    pub fn state_progress(&self, state: GameState) -> f32 {
+        let groups: Vec<_> = AssetGroup::ALL
+            .into_iter()
+            .filter(|group| group.preload_state() == state)
+            .collect();
+        if groups.is_empty() {
+            return 1.0;
+        }
+        groups
+            .iter()
+            .map(|group| self.group_progress(*group))
+            .sum::<f32>()
+            / groups.len() as f32
+    }
+
+    /// 已发现的缺失资源数量
+    pub fn missing_count(&self) -> usize {
+        self.groups
+            .values()
+            .flatten()
+            .filter(|asset| asset.status == AssetStatus::Missing)
+            .count()
+    }
+}
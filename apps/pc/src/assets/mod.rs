@@ -0,0 +1,16 @@
+/// 资源管理模块
+///
+/// 按分组声明要预载的贴图等资源，进入对应的游戏状态时开始加载，
+/// 跟踪每个资源的加载状态供加载界面显示进度，缺失的资源明确报出路径
+///
+/// # 模块组成
+/// 1. groups：资源分组与资源清单
+/// 2. manager：分组加载状态的跟踪
+/// 3. systems：资源管理插件
+mod groups;
+mod manager;
+mod systems;
+
+pub use groups::*;
+pub use manager::*;
+pub use systems::AssetManagerPlugin;
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+
+use super::{AssetGroup, AssetManager, AssetManifest};
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::GameState;
+
+/// 资源管理插件
+///
+/// 游戏状态切换时开始预载该状态的资源分组，之后每帧刷新加载状态并报告缺失的资源
+pub struct AssetManagerPlugin;
+
+impl Plugin for AssetManagerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AssetManifest>()
+            .init_resource::<AssetManager>()
+            .add_systems(Update, (preload_state_groups, track_asset_loads).chain());
+    }
+}
+
+/// 进入新状态时请求该状态要预载的分组
+fn preload_state_groups(
+    state: Res<State<GameState>>,
+    manifest: Res<AssetManifest>,
+    asset_server: Res<AssetServer>,
+    mut manager: ResMut<AssetManager>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    if !state.is_changed() {
+        return;
+    }
+    for group in AssetGroup::ALL {
+        if group.preload_state() != *state.get() || manager.is_requested(group) {
+            continue;
+        }
+        let paths = manifest.paths(group);
+        manager.request(group, paths, &asset_server);
+        if let Some(logger) = logger.as_mut() {
+            logger.log(
+                LogLevel::Info,
+                &format!("开始预载{}资源（{} 项）", group.label(), paths.len()),
+            );
+        }
+    }
+}
+
+/// 刷新加载状态，缺失的资源逐个报出，不让它们变成无声的错误贴图
+fn track_asset_loads(
+    asset_server: Res<AssetServer>,
+    mut manager: ResMut<AssetManager>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for missing in manager.poll(&asset_server) {
+        if let Some(logger) = logger.as_mut() {
+            logger.log(
+                LogLevel::Error,
+                &format!(
+                    "资源缺失：{}（{}分组）：{}",
+                    missing.path,
+                    missing.group.label(),
+                    missing.reason
+                ),
+            );
+        }
+    }
+}
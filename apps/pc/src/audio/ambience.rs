@@ -0,0 +1,277 @@
+use bevy::audio::{AudioSinkPlayback, Volume};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::{tile_at, AudioVolumes, SoundLibrary};
+use crate::housing::CurrentInterior;
+use crate::resources::GameState;
+use crate::time::{DayNightState, IgnoreTimeDilation, TimeOfDay};
+use crate::world::chunk::{Chunk, ChunkManager, TILE_PIXELS};
+use crate::world::entity::Player;
+use crate::world::map::{TileType, Zone};
+use crate::world::weather::{WeatherKind, WeatherState};
+
+/// 环境音配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmbienceDef {
+    /// 一层环境音从静音到满音量所需的时长（秒）
+    #[serde(default = "default_fade")]
+    pub fade: f32,
+    /// 采样玩家周围地形的半径（瓦片），按各类地形所占比例混合，走过边界时渐变
+    #[serde(default = "default_sample_radius")]
+    pub sample_radius: i32,
+    /// 重新采样周围地形的间隔（秒）
+    #[serde(default = "default_sample_interval")]
+    pub sample_interval: f32,
+    #[serde(default)]
+    pub layers: Vec<AmbienceLayerDef>,
+}
+
+fn default_fade() -> f32 {
+    2.5
+}
+
+fn default_sample_radius() -> i32 {
+    4
+}
+
+fn default_sample_interval() -> f32 {
+    0.5
+}
+
+impl Default for AmbienceDef {
+    fn default() -> Self {
+        Self {
+            fade: default_fade(),
+            sample_radius: default_sample_radius(),
+            sample_interval: default_sample_interval(),
+            layers: Vec::new(),
+        }
+    }
+}
+
+/// 一层环境音，例如山巅的风声、林间的鸟鸣
+///
+/// 各项条件为空表示不限；同时满足时播放，音量按条件的满足程度缩放
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmbienceLayerDef {
+    /// 循环播放的音效编号，对应 sounds
+    pub sound: String,
+    #[serde(default = "default_layer_volume")]
+    pub volume: f32,
+    /// 所在气候区，音量按周围属于这些气候区的瓦片比例缩放
+    #[serde(default)]
+    pub zones: Vec<Zone>,
+    /// 周围的地形，音量按这些瓦片所占比例缩放
+    #[serde(default)]
+    pub tiles: Vec<TileType>,
+    /// 天气，音量随天气强度缩放
+    #[serde(default)]
+    pub weather: Vec<WeatherKind>,
+    /// 时段
+    #[serde(default)]
+    pub times: Vec<TimeOfDay>,
+}
+
+fn default_layer_volume() -> f32 {
+    1.0
+}
+
+/// 玩家周围的环境：各类地形与气候区所占的比例
+///
+/// 周围区块都未加载时保留上一次的结果，避免环境音忽然中断
+#[derive(Resource, Debug, Default)]
+pub struct AmbienceEnvironment {
+    pub tiles: HashMap<TileType, f32>,
+    pub zones: HashMap<Zone, f32>,
+    /// 距离下次采样的剩余时间（秒）
+    cooldown: f32,
+}
+
+impl AmbienceEnvironment {
+    /// 给定地形所占的比例，列表为空时视为满足
+    pub fn tile_share(&self, tiles: &[TileType]) -> f32 {
+        if tiles.is_empty() {
+            return 1.0;
+        }
+        tiles.iter().filter_map(|tile| self.tiles.get(tile)).sum()
+    }
+
+    /// 给定气候区所占的比例，列表为空时视为满足
+    pub fn zone_share(&self, zones: &[Zone]) -> f32 {
+        if zones.is_empty() {
+            return 1.0;
+        }
+        zones.iter().filter_map(|zone| self.zones.get(zone)).sum()
+    }
+}
+
+/// 正在播放的一层环境音
+#[derive(Component, Debug, Clone)]
+pub struct AmbienceLayer {
+    /// 对应配置中 layers 的下标
+    pub index: usize,
+    /// 当前音量
+    pub level: f32,
+}
+
+/// 按间隔采样玩家周围的瓦片，统计各类地形与气候区的比例
+pub fn sample_ambience_environment(
+    time: Res<Time<Real>>,
+    library: Res<SoundLibrary>,
+    chunk_manager: Option<Res<ChunkManager>>,
+    chunks: Query<&Chunk>,
+    players: Query<&Transform, With<Player>>,
+    mut environment: ResMut<AmbienceEnvironment>,
+) {
+    environment.cooldown -= time.delta_secs();
+    if environment.cooldown > 0.0 {
+        return;
+    }
+    environment.cooldown = library.ambience.sample_interval;
+
+    let (Some(chunk_manager), Ok(player)) = (chunk_manager, players.get_single()) else {
+        return;
+    };
+    let radius = library.ambience.sample_radius.max(0);
+    let mut tiles: HashMap<TileType, f32> = HashMap::new();
+    let mut zones: HashMap<Zone, f32> = HashMap::new();
+    let mut samples = 0.0;
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            let offset = Vec3::new(dx as f32 * TILE_PIXELS, dy as f32 * TILE_PIXELS, 0.0);
+            let Some((tile, zone)) = tile_at(player.translation + offset, &chunk_manager, &chunks)
+            else {
+                continue;
+            };
+            samples += 1.0;
+            *tiles.entry(tile).or_default() += 1.0;
+            if let Some(zone) = zone {
+                *zones.entry(zone).or_default() += 1.0;
+            }
+        }
+    }
+    if samples == 0.0 {
+        return;
+    }
+
+    tiles.values_mut().for_each(|share| *share /= samples);
+    zones.values_mut().for_each(|share| *share /= samples);
+    environment.tiles = tiles;
+    environment.zones = zones;
+}
+
+/// 各层环境音的目标音量
+///
+/// 主菜单、加载界面与室内不播放野外的环境音
+fn layer_targets(
+    library: &SoundLibrary,
+    environment: &AmbienceEnvironment,
+    weather: Option<&WeatherState>,
+    time_of_day: TimeOfDay,
+    outdoors: bool,
+) -> Vec<f32> {
+    library
+        .ambience
+        .layers
+        .iter()
+        .map(|layer| {
+            if !outdoors {
+                return 0.0;
+            }
+            let weather_factor = if layer.weather.is_empty() {
+                1.0
+            } else {
+                weather
+                    .filter(|weather| layer.weather.contains(&weather.kind))
+                    .map_or(0.0, |weather| weather.intensity.clamp(0.0, 1.0))
+            };
+            let time_factor = if layer.times.is_empty() || layer.times.contains(&time_of_day) {
+                1.0
+            } else {
+                0.0
+            };
+            layer.volume
+                * environment.zone_share(&layer.zones).min(1.0)
+                * environment.tile_share(&layer.tiles).min(1.0)
+                * weather_factor
+                * time_factor
+        })
+        .collect()
+}
+
+/// 按环境调整各层环境音
+///
+/// 需要响起的层从静音开始循环播放，条件不再满足的层淡出后销毁；
+/// 音量按真实时间渐变，穿过区域边界或天气变化时平滑过渡
+#[allow(clippy::too_many_arguments)]
+pub fn update_ambience(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    asset_server: Res<AssetServer>,
+    library: Res<SoundLibrary>,
+    environment: Res<AmbienceEnvironment>,
+    volumes: Res<AudioVolumes>,
+    global: Res<GlobalVolume>,
+    game_state: Res<State<GameState>>,
+    day_night: Res<DayNightState>,
+    weather: Option<Res<WeatherState>>,
+    interior: Option<Res<CurrentInterior>>,
+    mut layers: Query<(Entity, &mut AmbienceLayer, Option<&AudioSink>)>,
+) {
+    let outdoors = matches!(game_state.get(), GameState::InGame | GameState::Paused)
+        && interior.is_none_or(|interior| interior.home_id.is_none());
+    let targets = layer_targets(
+        &library,
+        &environment,
+        weather.as_deref(),
+        day_night.time_of_day,
+        outdoors,
+    );
+
+    let step = time.delta_secs() / library.ambience.fade.max(0.01);
+    let scale = volumes.sfx * global.volume.get();
+    let mut playing = vec![false; targets.len()];
+    for (entity, mut layer, sink) in layers.iter_mut() {
+        let target = targets.get(layer.index).copied().unwrap_or(0.0);
+        if let Some(slot) = playing.get_mut(layer.index) {
+            *slot = true;
+        }
+        layer.level = if layer.level < target {
+            (layer.level + step).min(target)
+        } else {
+            (layer.level - step).max(target)
+        };
+        if target <= 0.0 && layer.level <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if let Some(sink) = sink {
+            let volume = layer.level * scale;
+            if (sink.volume() - volume).abs() > 0.001 {
+                sink.set_volume(volume);
+            }
+        }
+    }
+
+    for (index, target) in targets.iter().enumerate() {
+        if playing[index] || *target <= 0.001 {
+            continue;
+        }
+        let def = &library.ambience.layers[index];
+        let Some(track) = library
+            .get(&def.sound)
+            .and_then(|sound| sound.tracks.first())
+        else {
+            continue;
+        };
+        commands.spawn((
+            AmbienceLayer { index, level: 0.0 },
+            Name::new(format!("Ambience: {}", def.sound)),
+            AudioPlayer::<AudioSource>::new(asset_server.load(track.as_str())),
+            PlaybackSettings::LOOP.with_volume(Volume::new(0.0)),
+            IgnoreTimeDilation,
+        ));
+    }
+}
@@ -0,0 +1,219 @@
+use bevy::prelude::*;
+use std::f32::consts::FRAC_PI_4;
+
+use super::{DialogueAudio, SoundCue};
+use crate::ui::glyph;
+use crate::world::entity::Player;
+
+/// 八方向箭头，从正右方开始逆时针排列
+const DIRECTION_ARROWS: [char; 8] = ['→', '↗', '↑', '↖', '←', '↙', '↓', '↘'];
+
+/// 字幕配置
+#[derive(Resource, Debug, Clone)]
+pub struct CaptionSettings {
+    /// 是否显示字幕，对应游戏设置中的无障碍选项
+    pub enabled: bool,
+    /// 同时显示的最多行数
+    pub max_lines: usize,
+    /// 声音提示的显示时长（秒）
+    pub cue_duration: f32,
+    /// 提示身后敌人的距离
+    pub enemy_cue_range: f32,
+    /// 同一个敌人两次提示的间隔（秒）
+    pub enemy_cue_cooldown: f32,
+    /// 提示水声的距离
+    pub water_cue_range: f32,
+}
+
+impl Default for CaptionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_lines: 4,
+            cue_duration: 3.0,
+            enemy_cue_range: 200.0,
+            enemy_cue_cooldown: 8.0,
+            water_cue_range: 256.0,
+        }
+    }
+}
+
+/// 一行字幕
+#[derive(Debug, Clone)]
+pub struct Caption {
+    pub text: String,
+    pub source: Option<Vec3>,
+    pub important: bool,
+    pub dialogue: bool,
+    /// 当前指向声源的箭头
+    pub arrow: Option<char>,
+    /// 消失的时刻（真实时间，秒）
+    pub expires_at: f64,
+}
+
+/// 正在显示的字幕，最新的在最后
+#[derive(Resource, Debug, Default)]
+pub struct CaptionLog {
+    pub lines: Vec<Caption>,
+}
+
+/// 字幕界面根节点
+#[derive(Component)]
+pub struct CaptionUi;
+
+/// 从玩家指向声源的箭头
+fn direction_arrow(from: Vec2, to: Vec2) -> Option<char> {
+    let offset = to - from;
+    if offset.length_squared() <= f32::EPSILON {
+        return None;
+    }
+    let sector = (offset.y.atan2(offset.x) / FRAC_PI_4).round() as i32;
+    Some(DIRECTION_ARROWS[sector.rem_euclid(8) as usize])
+}
+
+/// 收集对白与声音提示，移除过期字幕并刷新方向箭头
+pub fn collect_captions(
+    time: Res<Time<Real>>,
+    settings: Res<CaptionSettings>,
+    mut log: ResMut<CaptionLog>,
+    mut cues: EventReader<SoundCue>,
+    mut dialogues: EventReader<DialogueAudio>,
+    players: Query<&Transform, With<Player>>,
+) {
+    if !settings.enabled {
+        cues.clear();
+        dialogues.clear();
+        if !log.lines.is_empty() {
+            log.lines.clear();
+        }
+        return;
+    }
+
+    let now = time.elapsed_secs_f64();
+    let player = players
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation.truncate());
+
+    if log.lines.iter().any(|line| line.expires_at <= now) {
+        log.lines.retain(|line| line.expires_at > now);
+    }
+
+    for dialogue in dialogues.read() {
+        log.lines.push(Caption {
+            text: format!("{}：{}", dialogue.speaker, dialogue.text),
+            source: None,
+            important: false,
+            dialogue: true,
+            arrow: None,
+            expires_at: now + dialogue.duration.max(settings.cue_duration) as f64,
+        });
+    }
+    for cue in cues.read() {
+        // 同样的提示仍在显示时只延长时间
+        if let Some(line) = log
+            .lines
+            .iter_mut()
+            .find(|line| !line.dialogue && line.text == cue.caption)
+        {
+            line.source = cue.source;
+            line.expires_at = now + settings.cue_duration as f64;
+            continue;
+        }
+        log.lines.push(Caption {
+            text: cue.caption.clone(),
+            source: cue.source,
+            important: cue.important,
+            dialogue: false,
+            arrow: None,
+            expires_at: now + settings.cue_duration as f64,
+        });
+    }
+
+    let overflow = log.lines.len().saturating_sub(settings.max_lines);
+    if overflow > 0 {
+        log.lines.drain(..overflow);
+    }
+
+    // 玩家移动后箭头可能改变，只在确实变化时标记修改
+    let Some(player) = player else {
+        return;
+    };
+    let arrows: Vec<Option<char>> = log
+        .lines
+        .iter()
+        .map(|line| {
+            line.source
+                .and_then(|source| direction_arrow(player, source.truncate()))
+        })
+        .collect();
+    let changed = log
+        .lines
+        .iter()
+        .zip(&arrows)
+        .any(|(line, arrow)| line.arrow != *arrow);
+    if changed {
+        for (line, arrow) in log.lines.iter_mut().zip(arrows) {
+            line.arrow = arrow;
+        }
+    }
+}
+
+/// 字幕变化时重建字幕界面
+pub fn update_caption_ui(
+    mut commands: Commands,
+    log: Res<CaptionLog>,
+    ui: Query<Entity, With<CaptionUi>>,
+) {
+    if !log.is_changed() {
+        return;
+    }
+
+    for entity in ui.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if log.lines.is_empty() {
+        return;
+    }
+
+    commands
+        .spawn((
+            CaptionUi,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                bottom: Val::Px(48.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+        ))
+        .with_children(|root| {
+            for line in &log.lines {
+                let text = match (line.dialogue, line.arrow) {
+                    (true, _) => line.text.clone(),
+                    (false, Some(arrow)) => format!("[{} {}]", arrow, line.text),
+                    (false, None) => format!("[{}]", line.text),
+                };
+                let color = if line.important {
+                    Color::srgb(1.0, 0.55, 0.35)
+                } else if line.dialogue {
+                    Color::WHITE
+                } else {
+                    Color::srgb(0.8, 0.85, 0.9)
+                };
+
+                root.spawn((
+                    Node {
+                        padding: UiRect::axes(Val::Px(8.0), Val::Px(2.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                ))
+                .with_children(|row| {
+                    row.spawn(glyph(text, 16.0, color));
+                });
+            }
+        });
+}
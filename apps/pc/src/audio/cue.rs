@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use super::CaptionSettings;
+use crate::world::chunk::WaterfallEffect;
+use crate::world::entity::{AiState, Character, Npc, NpcType, Player};
+
+/// 声音提示事件
+///
+/// 播放重要音效时一并发出，字幕系统据此显示文字提示
+#[derive(Event, Debug, Clone)]
+pub struct SoundCue {
+    pub caption: String,
+    /// 声源的世界坐标，有坐标的提示显示方向箭头
+    pub source: Option<Vec3>,
+    /// 重要提示以醒目颜色显示
+    pub important: bool,
+}
+
+/// 对白语音事件
+#[derive(Event, Debug, Clone)]
+pub struct DialogueAudio {
+    pub speaker: String,
+    pub text: String,
+    /// 语音时长（秒），字幕显示同样长的时间
+    pub duration: f32,
+}
+
+/// 追击中的敌人从玩家身后逼近时发出提示
+///
+/// 玩家静止时沿用最后一次移动的朝向；同一个敌人提示后有冷却
+pub fn emit_enemy_cues(
+    time: Res<Time>,
+    settings: Res<CaptionSettings>,
+    players: Query<(&Character, &Transform), With<Player>>,
+    enemies: Query<(Entity, &Npc, &Transform), Without<Player>>,
+    mut facing: Local<Vec2>,
+    mut warned: Local<HashMap<Entity, f32>>,
+    mut cues: EventWriter<SoundCue>,
+) {
+    let delta = time.delta_secs();
+    warned.retain(|_, remaining| {
+        *remaining -= delta;
+        *remaining > 0.0
+    });
+
+    if !settings.enabled {
+        return;
+    }
+    let Ok((character, player)) = players.get_single() else {
+        return;
+    };
+    if character.direction != Vec2::ZERO {
+        *facing = character.direction.normalize();
+    } else if *facing == Vec2::ZERO {
+        *facing = Vec2::Y;
+    }
+    let position = player.translation.truncate();
+
+    for (entity, npc, transform) in enemies.iter() {
+        let hostile = matches!(npc.npc_type, NpcType::Enemy | NpcType::Boss)
+            && matches!(npc.ai_state, AiState::Chase | AiState::Attack);
+        if !hostile || warned.contains_key(&entity) {
+            continue;
+        }
+
+        let offset = transform.translation.truncate() - position;
+        let distance = offset.length();
+        if distance > settings.enemy_cue_range || distance <= f32::EPSILON {
+            continue;
+        }
+        if facing.dot(offset / distance) > -0.3 {
+            continue;
+        }
+
+        cues.send(SoundCue {
+            caption: "身后有敌人逼近".to_string(),
+            source: Some(transform.translation),
+            important: true,
+        });
+        warned.insert(entity, settings.enemy_cue_cooldown);
+    }
+}
+
+/// 走近瀑布时提示水声，离开一段距离后才会再次提示
+pub fn emit_water_cues(
+    settings: Res<CaptionSettings>,
+    players: Query<&Transform, With<Player>>,
+    waterfalls: Query<(Entity, &GlobalTransform), With<WaterfallEffect>>,
+    mut heard: Local<HashSet<Entity>>,
+    mut cues: EventWriter<SoundCue>,
+) {
+    if !settings.enabled {
+        heard.clear();
+        return;
+    }
+    let Ok(player) = players.get_single() else {
+        return;
+    };
+    let position = player.translation.truncate();
+
+    let mut in_range = HashSet::new();
+    for (entity, transform) in waterfalls.iter() {
+        let distance = transform.translation().truncate().distance(position);
+        if distance <= settings.water_cue_range {
+            if !heard.contains(&entity) {
+                cues.send(SoundCue {
+                    caption: "水声湍急".to_string(),
+                    source: Some(transform.translation()),
+                    important: false,
+                });
+            }
+            in_range.insert(entity);
+        } else if distance <= settings.water_cue_range * 1.5 && heard.contains(&entity) {
+            in_range.insert(entity);
+        }
+    }
+    *heard = in_range;
+}
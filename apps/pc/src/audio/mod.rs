@@ -0,0 +1,31 @@
+/// 音频模块
+///
+/// # 模块组成
+/// 1. moment：值得纪念的时刻（发现区域、完成任务、首领换阶段、山巅日出）
+/// 2. stinger：时刻短乐的数据配置、冷却管理与播放
+/// 3. cue：声音提示与对白语音事件
+/// 4. captions：字幕与带方向箭头的声音提示
+/// 5. sound：音效库、`PlaySound` 事件、随距离衰减的音效与持续发声的实体
+/// 6. music：按场景与气候区挑选背景音乐，换曲时交叉淡入淡出
+/// 7. triggers：战斗受击、脚步与瀑布等游戏事件对应的音效
+/// 8. ambience：按周围地形、天气与时段叠加的环境音
+/// 9. systems：音频插件
+mod ambience;
+mod captions;
+mod cue;
+mod moment;
+mod music;
+mod sound;
+mod stinger;
+mod systems;
+mod triggers;
+
+pub use ambience::*;
+pub use captions::*;
+pub use cue::*;
+pub use moment::*;
+pub use music::*;
+pub use sound::*;
+pub use stinger::*;
+pub use systems::GameAudioPlugin;
+pub use triggers::*;
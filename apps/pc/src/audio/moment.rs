@@ -0,0 +1,178 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::combat::HealthChanged;
+use crate::time::{TimeOfDay, TimeOfDayChanged};
+use crate::world::chunk::{Chunk, ChunkCoord, ChunkManager, CHUNK_SIZE, TILE_PIXELS};
+use crate::world::entity::{Character, Npc, NpcType, Player};
+use crate::world::map::{TileType, Zone};
+
+/// 时刻类型，每种类型在数据文件中配置各自的短乐
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MomentKind {
+    /// 首次踏入某个区域
+    RegionDiscovered,
+    /// 完成任务
+    QuestCompleted,
+    /// 首领进入新阶段
+    BossPhaseChanged,
+    /// 在山巅迎来日出
+    SunriseOverPeak,
+}
+
+/// 时刻事件
+///
+/// 任务、战斗等系统在相应时刻发出，由短乐系统决定是否播放
+#[derive(Event, Debug, Clone)]
+pub struct MomentEvent {
+    pub kind: MomentKind,
+    /// 附带说明，如区域或任务名称，用于日志与字幕
+    pub label: Option<String>,
+}
+
+impl MomentEvent {
+    pub fn new(kind: MomentKind) -> Self {
+        Self { kind, label: None }
+    }
+
+    pub fn with_label(kind: MomentKind, label: impl Into<String>) -> Self {
+        Self {
+            kind,
+            label: Some(label.into()),
+        }
+    }
+}
+
+/// 首领生命比例跌破这些值时进入下一阶段
+const BOSS_PHASE_THRESHOLDS: [f32; 2] = [2.0 / 3.0, 1.0 / 3.0];
+
+/// 首领所处的阶段，从 0 开始，生命比例每跌破一个阈值进入下一阶段
+pub fn boss_phase(health: f32, max_health: f32) -> usize {
+    let ratio = health / max_health.max(1.0);
+    BOSS_PHASE_THRESHOLDS
+        .iter()
+        .filter(|&&threshold| ratio <= threshold)
+        .count()
+}
+
+/// 玩家已经到过的气候区
+#[derive(Resource, Debug, Default)]
+pub struct DiscoveredRegions {
+    pub zones: HashSet<Zone>,
+}
+
+/// 世界坐标处瓦片的类型与气候区，区块未加载时返回 None
+pub(super) fn tile_at(
+    position: Vec3,
+    chunk_manager: &ChunkManager,
+    chunks: &Query<&Chunk>,
+) -> Option<(TileType, Option<Zone>)> {
+    let chunk_size = CHUNK_SIZE as i32;
+    let tile_x = (position.x / TILE_PIXELS).floor() as i32;
+    let tile_y = (position.y / TILE_PIXELS).floor() as i32;
+    let coord = ChunkCoord {
+        x: tile_x.div_euclid(chunk_size),
+        y: tile_y.div_euclid(chunk_size),
+    };
+
+    let data = chunk_manager
+        .get_chunk_entity(coord)
+        .and_then(|entity| chunks.get(entity).ok())
+        .and_then(|chunk| chunk.data.as_ref())?;
+
+    let local_x = tile_x.rem_euclid(chunk_size) as usize;
+    let local_y = tile_y.rem_euclid(chunk_size) as usize;
+    let tile = data
+        .get_tile(local_x, local_y)
+        .and_then(TileType::from_u8)?;
+    Some((tile, data.get_climate_zone(local_x, local_y)))
+}
+
+/// 玩家首次进入某个气候区时发出发现区域事件
+///
+/// 出生所在的气候区直接记为已发现，不触发短乐
+pub fn detect_region_discovery(
+    chunk_manager: Res<ChunkManager>,
+    chunks: Query<&Chunk>,
+    players: Query<&Transform, With<Player>>,
+    mut discovered: ResMut<DiscoveredRegions>,
+    mut moments: EventWriter<MomentEvent>,
+) {
+    let Ok(player) = players.get_single() else {
+        return;
+    };
+    let Some((_, Some(zone))) = tile_at(player.translation, &chunk_manager, &chunks) else {
+        return;
+    };
+    if discovered.zones.contains(&zone) {
+        return;
+    }
+
+    let first = discovered.zones.is_empty();
+    discovered.zones.insert(zone);
+    if !first {
+        moments.send(MomentEvent::with_label(
+            MomentKind::RegionDiscovered,
+            zone.name(),
+        ));
+    }
+}
+
+/// 站在山岭或雪峰上迎来拂晓时发出日出事件
+pub fn detect_sunrise_over_peak(
+    mut time_of_day_events: EventReader<TimeOfDayChanged>,
+    chunk_manager: Res<ChunkManager>,
+    chunks: Query<&Chunk>,
+    players: Query<&Transform, With<Player>>,
+    mut moments: EventWriter<MomentEvent>,
+) {
+    let dawn = time_of_day_events
+        .read()
+        .any(|event| event.current == TimeOfDay::Dawn);
+    if !dawn {
+        return;
+    }
+    let Ok(player) = players.get_single() else {
+        return;
+    };
+
+    let on_peak = tile_at(player.translation, &chunk_manager, &chunks)
+        .is_some_and(|(tile, _)| matches!(tile, TileType::Mountain | TileType::Snow));
+    if on_peak {
+        moments.send(MomentEvent::new(MomentKind::SunriseOverPeak));
+    }
+}
+
+/// 首领受伤进入新阶段时发出首领换阶段事件
+///
+/// 阶段只进不退，首领回血后再次跌破同一阈值不会重复触发；倒下不算换阶段
+pub fn detect_boss_phase_change(
+    mut health_events: EventReader<HealthChanged>,
+    bosses: Query<(&Npc, &Character)>,
+    mut phases: Local<HashMap<Entity, usize>>,
+    mut moments: EventWriter<MomentEvent>,
+) {
+    for event in health_events.read() {
+        let Ok((npc, character)) = bosses.get(event.entity) else {
+            continue;
+        };
+        if npc.npc_type != NpcType::Boss {
+            continue;
+        }
+        if character.health <= 0.0 {
+            phases.remove(&event.entity);
+            continue;
+        }
+        let phase = boss_phase(character.health, character.max_health);
+        let reached = phases.entry(event.entity).or_default();
+        if phase > *reached {
+            *reached = phase;
+            moments.send(MomentEvent::with_label(
+                MomentKind::BossPhaseChanged,
+                character.name.clone(),
+            ));
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use bevy::audio::{AudioSinkPlayback, Volume};
+use bevy::prelude::*;
+
+use super::{tile_at, AudioVolumes, SoundLibrary};
+use crate::housing::CurrentInterior;
+use crate::resources::GameState;
+use crate::time::IgnoreTimeDilation;
+use crate::world::chunk::{Chunk, ChunkManager};
+use crate::world::entity::Player;
+use crate::world::map::Zone;
+
+/// 背景音乐状态
+///
+/// - track: 当前应当播放的曲目，为空时静默
+/// - zone: 玩家最后所在的气候区，区块未加载时沿用，避免边走边换曲
+#[derive(Resource, Debug, Default)]
+pub struct MusicState {
+    pub track: Option<String>,
+    pub zone: Option<Zone>,
+}
+
+/// 正在播放的背景音乐
+///
+/// 换曲时旧曲淡出后销毁，新曲从静音淡入，两者同时存在一段时间
+#[derive(Component, Debug, Clone)]
+pub struct MusicTrack {
+    pub track: String,
+    /// 当前淡入程度 (0.0-1.0)
+    pub fade: f32,
+    /// 为 true 时淡入，为 false 时淡出
+    pub fading_in: bool,
+}
+
+/// 按场景与玩家所在的气候区挑选背景音乐，曲目变化时开始交叉淡入淡出
+///
+/// 登录、主菜单与加载界面播放菜单曲，室内播放宅院曲，野外按气候区挑选
+#[allow(clippy::too_many_arguments)]
+pub fn select_music(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    library: Res<SoundLibrary>,
+    game_state: Res<State<GameState>>,
+    interior: Option<Res<CurrentInterior>>,
+    chunk_manager: Option<Res<ChunkManager>>,
+    chunks: Query<&Chunk>,
+    players: Query<&Transform, With<Player>>,
+    mut state: ResMut<MusicState>,
+    mut tracks: Query<&mut MusicTrack>,
+) {
+    let music = &library.music;
+    if let (Some(chunk_manager), Ok(player)) = (chunk_manager.as_ref(), players.get_single()) {
+        if let Some((_, Some(zone))) = tile_at(player.translation, chunk_manager, &chunks) {
+            state.zone = Some(zone);
+        }
+    }
+
+    let desired = match game_state.get() {
+        GameState::Login | GameState::MainMenu | GameState::Loading => music.menu.clone(),
+        GameState::InGame | GameState::Paused => {
+            if interior.is_some_and(|interior| interior.home_id.is_some()) {
+                music.interior.clone()
+            } else {
+                state
+                    .zone
+                    .and_then(|zone| music.zones.get(&zone).cloned())
+                    .or_else(|| music.default.clone())
+            }
+        }
+    };
+    if desired == state.track {
+        return;
+    }
+
+    for mut track in tracks.iter_mut() {
+        track.fading_in = desired.as_ref() == Some(&track.track);
+    }
+    // 旧曲还在淡出时切回来，直接让它重新淡入
+    let resumed = tracks.iter().any(|track| track.fading_in);
+    if let (Some(track), false) = (desired.as_ref(), resumed) {
+        commands.spawn((
+            MusicTrack {
+                track: track.clone(),
+                fade: 0.0,
+                fading_in: true,
+            },
+            Name::new(format!("Music: {}", track)),
+            AudioPlayer::<AudioSource>::new(asset_server.load(track.as_str())),
+            PlaybackSettings::LOOP.with_volume(Volume::new(0.0)),
+            IgnoreTimeDilation,
+        ));
+    }
+    state.track = desired;
+}
+
+/// 推进交叉淡入淡出并设置音乐音量，淡出完毕的曲目销毁
+///
+/// 按真实时间计算，暂停与慢动作时照常淡入淡出
+pub fn crossfade_music(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    library: Res<SoundLibrary>,
+    volumes: Res<AudioVolumes>,
+    global: Res<GlobalVolume>,
+    mut tracks: Query<(Entity, &mut MusicTrack, Option<&AudioSink>)>,
+) {
+    let step = time.delta_secs() / library.music.crossfade.max(0.01);
+    let full = library.music.volume * volumes.music * global.volume.get();
+    for (entity, mut track, sink) in tracks.iter_mut() {
+        track.fade = if track.fading_in {
+            (track.fade + step).min(1.0)
+        } else {
+            (track.fade - step).max(0.0)
+        };
+        if !track.fading_in && track.fade <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if let Some(sink) = sink {
+            let volume = full * track.fade;
+            if (sink.volume() - volume).abs() > 0.001 {
+                sink.set_volume(volume);
+            }
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use bevy::audio::{AudioSinkPlayback, Volume};
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use super::AmbienceDef;
+use crate::world::entity::Player;
+use crate::world::map::{TileType, Zone};
+
+/// 音频数据文件路径
+pub const AUDIO_DATA_PATH: &str = "src/config/audio.json";
+
+/// 一种音效的配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoundDef {
+    /// 候选音源，每次随机挑选一个，避免同一声音反复响起显得呆板
+    pub tracks: Vec<String>,
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+/// 声音随距离衰减的范围（像素）
+///
+/// 近于 min_distance 时音量不衰减，远于 max_distance 时听不到
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoundFalloff {
+    pub min_distance: f32,
+    pub max_distance: f32,
+}
+
+impl Default for SoundFalloff {
+    fn default() -> Self {
+        Self {
+            min_distance: 96.0,
+            max_distance: 800.0,
+        }
+    }
+}
+
+impl SoundFalloff {
+    /// 按与听者的距离求音量系数，平方衰减让远处的声音淡得更快
+    pub fn attenuation(&self, distance: f32) -> f32 {
+        if distance <= self.min_distance {
+            return 1.0;
+        }
+        let range = (self.max_distance - self.min_distance).max(1.0);
+        let t = (1.0 - (distance - self.min_distance) / range).clamp(0.0, 1.0);
+        t * t
+    }
+}
+
+/// 背景音乐配置
+///
+/// 室内与菜单优先，野外按玩家所在的气候区挑选，没有配置的区域播放 default
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MusicDef {
+    /// 换曲时新旧两首交叉淡入淡出的时长（秒）
+    #[serde(default = "default_crossfade")]
+    pub crossfade: f32,
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+    #[serde(default)]
+    pub menu: Option<String>,
+    #[serde(default)]
+    pub interior: Option<String>,
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub zones: HashMap<Zone, String>,
+}
+
+fn default_crossfade() -> f32 {
+    3.0
+}
+
+/// 脚步声配置，按脚下的瓦片类型选择音效
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FootstepDef {
+    /// 没有单独配置的瓦片使用的音效
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub tiles: HashMap<TileType, String>,
+    /// 没有动画事件的角色每走这么远（像素）响一次脚步
+    #[serde(default = "default_stride")]
+    pub stride: f32,
+}
+
+fn default_stride() -> f32 {
+    40.0
+}
+
+/// 音频数据文件格式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AudioDataFile {
+    #[serde(default)]
+    falloff: SoundFalloff,
+    #[serde(default)]
+    music: MusicDef,
+    #[serde(default)]
+    footsteps: FootstepDef,
+    #[serde(default)]
+    ambience: AmbienceDef,
+    #[serde(default)]
+    sounds: HashMap<String, SoundDef>,
+}
+
+/// 音效库：音效、背景音乐、脚步声与环境音的配置
+#[derive(Resource, Debug, Clone)]
+pub struct SoundLibrary {
+    pub falloff: SoundFalloff,
+    pub music: MusicDef,
+    pub footsteps: FootstepDef,
+    pub ambience: AmbienceDef,
+    sounds: HashMap<String, SoundDef>,
+}
+
+impl Default for SoundLibrary {
+    fn default() -> Self {
+        Self {
+            falloff: SoundFalloff::default(),
+            music: MusicDef {
+                crossfade: default_crossfade(),
+                volume: default_volume(),
+                ..default()
+            },
+            footsteps: FootstepDef {
+                stride: default_stride(),
+                ..default()
+            },
+            ambience: AmbienceDef::default(),
+            sounds: HashMap::new(),
+        }
+    }
+}
+
+impl SoundLibrary {
+    /// 从数据文件加载音效库
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let data: AudioDataFile = serde_json::from_str(&content)?;
+        Ok(Self {
+            falloff: data.falloff,
+            music: data.music,
+            footsteps: data.footsteps,
+            ambience: data.ambience,
+            sounds: data.sounds,
+        })
+    }
+
+    /// 获取音效配置
+    pub fn get(&self, id: &str) -> Option<&SoundDef> {
+        self.sounds.get(id)
+    }
+
+    /// 某种瓦片上的脚步声
+    pub fn footstep(&self, tile: Option<TileType>) -> Option<&str> {
+        tile.and_then(|tile| self.footsteps.tiles.get(&tile))
+            .or(self.footsteps.default.as_ref())
+            .map(String::as_str)
+    }
+}
+
+/// 音乐与音效的分类音量，总音量由 `GlobalVolume` 负责
+///
+/// 由设置套用，播放中的声音每帧按它重新计算音量，调节后立即生效
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct AudioVolumes {
+    pub music: f32,
+    pub sfx: f32,
+}
+
+impl Default for AudioVolumes {
+    fn default() -> Self {
+        Self {
+            music: 1.0,
+            sfx: 1.0,
+        }
+    }
+}
+
+/// 播放音效事件
+///
+/// 各系统只需发出事件，音效的音源、音量与距离衰减由音频系统处理
+#[derive(Event, Debug, Clone)]
+pub struct PlaySound {
+    /// 音效编号，对应数据文件中的 sounds
+    pub sound: String,
+    /// 声源的世界坐标，为空时不随距离衰减（界面音效等）
+    pub position: Option<Vec3>,
+    /// 额外的音量系数
+    pub volume: f32,
+}
+
+impl PlaySound {
+    pub fn new(sound: impl Into<String>) -> Self {
+        Self {
+            sound: sound.into(),
+            position: None,
+            volume: 1.0,
+        }
+    }
+
+    /// 在世界中某处发出的声音
+    pub fn at(sound: impl Into<String>, position: Vec3) -> Self {
+        Self {
+            position: Some(position),
+            ..Self::new(sound)
+        }
+    }
+}
+
+/// 正在播放的一次性音效，播放结束后自动销毁
+#[derive(Component, Debug, Clone)]
+pub struct SoundEffect;
+
+/// 持续发声的实体，例如瀑布
+///
+/// 循环播放配置的音效，音量随与听者的距离每帧调整
+#[derive(Component, Debug, Clone)]
+pub struct SoundEmitter {
+    pub sound: String,
+    pub volume: f32,
+}
+
+impl SoundEmitter {
+    pub fn new(sound: impl Into<String>) -> Self {
+        Self {
+            sound: sound.into(),
+            volume: 1.0,
+        }
+    }
+}
+
+/// 听者位置：优先取玩家，没有玩家时（菜单、观战）取摄像机
+pub(super) fn listener_position(
+    players: &Query<&GlobalTransform, With<Player>>,
+    cameras: &Query<&GlobalTransform, (With<Camera2d>, Without<Player>)>,
+) -> Option<Vec2> {
+    players
+        .get_single()
+        .or_else(|_| cameras.get_single())
+        .ok()
+        .map(|transform| transform.translation().truncate())
+}
+
+/// 按声源与听者的距离求音量系数，声源或听者未知时不衰减
+fn attenuation(falloff: &SoundFalloff, listener: Option<Vec2>, position: Option<Vec3>) -> f32 {
+    match (listener, position) {
+        (Some(listener), Some(position)) => {
+            falloff.attenuation(listener.distance(position.truncate()))
+        }
+        _ => 1.0,
+    }
+}
+
+/// 播放音效事件
+///
+/// 远到听不见的声音直接丢弃，不生成实体
+pub fn play_sounds(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    library: Res<SoundLibrary>,
+    volumes: Res<AudioVolumes>,
+    mut events: EventReader<PlaySound>,
+    players: Query<&GlobalTransform, With<Player>>,
+    cameras: Query<&GlobalTransform, (With<Camera2d>, Without<Player>)>,
+) {
+    if events.is_empty() {
+        return;
+    }
+    let listener = listener_position(&players, &cameras);
+    let mut rng = rand::thread_rng();
+
+    for event in events.read() {
+        let Some(def) = library.get(&event.sound) else {
+            continue;
+        };
+        let volume = def.volume
+            * event.volume
+            * volumes.sfx
+            * attenuation(&library.falloff, listener, event.position);
+        if volume <= 0.001 {
+            continue;
+        }
+        let Some(track) = def.tracks.choose(&mut rng) else {
+            continue;
+        };
+
+        commands.spawn((
+            SoundEffect,
+            AudioPlayer::<AudioSource>::new(asset_server.load(track.as_str())),
+            PlaybackSettings::DESPAWN.with_volume(Volume::new(volume)),
+        ));
+    }
+}
+
+/// 为新的发声实体挂上循环播放的音源，初始静音，由 `update_sound_emitters` 调整音量
+pub fn attach_sound_emitters(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    library: Res<SoundLibrary>,
+    emitters: Query<(Entity, &SoundEmitter), Added<SoundEmitter>>,
+) {
+    for (entity, emitter) in emitters.iter() {
+        let Some(track) = library
+            .get(&emitter.sound)
+            .and_then(|def| def.tracks.first())
+        else {
+            continue;
+        };
+        commands.entity(entity).insert((
+            AudioPlayer::<AudioSource>::new(asset_server.load(track.as_str())),
+            PlaybackSettings::LOOP.with_volume(Volume::new(0.0)),
+        ));
+    }
+}
+
+/// 发声实体的音量随与听者的距离变化，走近瀑布时水声渐响
+///
+/// 直接设置的音量不经过 `GlobalVolume`，这里一并乘上总音量
+pub fn update_sound_emitters(
+    library: Res<SoundLibrary>,
+    volumes: Res<AudioVolumes>,
+    global: Res<GlobalVolume>,
+    players: Query<&GlobalTransform, With<Player>>,
+    cameras: Query<&GlobalTransform, (With<Camera2d>, Without<Player>)>,
+    emitters: Query<(&SoundEmitter, &GlobalTransform, &AudioSink)>,
+) {
+    let listener = listener_position(&players, &cameras);
+    for (emitter, transform, sink) in emitters.iter() {
+        let base = library.get(&emitter.sound).map_or(1.0, |def| def.volume);
+        let volume = base
+            * emitter.volume
+            * volumes.sfx
+            * global.volume.get()
+            * attenuation(&library.falloff, listener, Some(transform.translation()));
+        if (sink.volume() - volume).abs() > 0.001 {
+            sink.set_volume(volume);
+        }
+    }
+}
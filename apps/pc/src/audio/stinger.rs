@@ -0,0 +1,212 @@
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use super::{MomentEvent, MomentKind, SoundCue};
+use crate::logging::{GameLogger, LogLevel};
+use crate::time::IgnoreTimeDilation;
+
+/// 短乐数据文件路径
+pub const STINGER_DATA_PATH: &str = "src/config/stingers.json";
+
+/// 一种时刻的短乐配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StingerDef {
+    /// 候选曲目，每次随机挑选一首
+    pub tracks: Vec<String>,
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+    /// 同类短乐的最短间隔（秒）
+    pub cooldown: f32,
+    /// 优先级，短乐被占用时只保留优先级最高的一个等待播放
+    #[serde(default)]
+    pub priority: u32,
+    /// 开启字幕时显示的文字
+    #[serde(default)]
+    pub caption: Option<String>,
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+/// 短乐数据文件格式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StingerDataFile {
+    global_cooldown: f32,
+    max_delay: f32,
+    stingers: HashMap<MomentKind, StingerDef>,
+}
+
+/// 短乐库
+#[derive(Resource, Debug, Clone)]
+pub struct StingerLibrary {
+    /// 任意两首短乐之间的最短间隔（秒）
+    pub global_cooldown: f32,
+    /// 等待播放的最长时间（秒），超时丢弃，避免时过境迁才响起
+    pub max_delay: f32,
+    stingers: HashMap<MomentKind, StingerDef>,
+}
+
+impl Default for StingerLibrary {
+    fn default() -> Self {
+        Self {
+            global_cooldown: 12.0,
+            max_delay: 4.0,
+            stingers: HashMap::new(),
+        }
+    }
+}
+
+impl StingerLibrary {
+    /// 从数据文件加载短乐库
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let data: StingerDataFile = serde_json::from_str(&content)?;
+        Ok(Self {
+            global_cooldown: data.global_cooldown,
+            max_delay: data.max_delay,
+            stingers: data.stingers,
+        })
+    }
+
+    /// 获取某种时刻的短乐配置
+    pub fn get(&self, kind: MomentKind) -> Option<&StingerDef> {
+        self.stingers.get(&kind)
+    }
+}
+
+/// 短乐冷却管理
+///
+/// 同一时间只播放一首短乐；同类短乐在冷却内再次触发直接丢弃，
+/// 正在播放或处于全局冷却时只保留优先级最高的一个等待
+#[derive(Resource, Debug, Default)]
+pub struct StingerCooldowns {
+    /// 全局冷却剩余时间
+    global: f32,
+    /// 各类短乐的冷却剩余时间
+    per_kind: HashMap<MomentKind, f32>,
+    /// 等待播放的时刻与已等待的时间
+    pending: Option<(MomentEvent, f32)>,
+}
+
+impl StingerCooldowns {
+    /// 推进冷却与等待计时
+    pub fn tick(&mut self, delta: f32) {
+        self.global = (self.global - delta).max(0.0);
+        for remaining in self.per_kind.values_mut() {
+            *remaining = (*remaining - delta).max(0.0);
+        }
+        if let Some((_, waited)) = self.pending.as_mut() {
+            *waited += delta;
+        }
+    }
+
+    /// 某类短乐是否已过冷却
+    pub fn is_ready(&self, kind: MomentKind) -> bool {
+        self.per_kind
+            .get(&kind)
+            .is_none_or(|remaining| *remaining <= 0.0)
+    }
+
+    /// 提交一个时刻，冷却中的直接丢弃，否则与等待中的比较优先级
+    pub fn submit(&mut self, library: &StingerLibrary, moment: MomentEvent) {
+        let Some(def) = library.get(moment.kind) else {
+            return;
+        };
+        if !self.is_ready(moment.kind) {
+            return;
+        }
+
+        let replace = self.pending.as_ref().is_none_or(|(pending, _)| {
+            library
+                .get(pending.kind)
+                .is_none_or(|pending_def| def.priority >= pending_def.priority)
+        });
+        if replace {
+            self.pending = Some((moment, 0.0));
+        }
+    }
+
+    /// 取出可以立即播放的时刻，并开始相应的冷却
+    pub fn take_ready(&mut self, library: &StingerLibrary, busy: bool) -> Option<MomentEvent> {
+        if self
+            .pending
+            .as_ref()
+            .is_some_and(|(_, waited)| *waited > library.max_delay)
+        {
+            self.pending = None;
+        }
+        if busy || self.global > 0.0 {
+            return None;
+        }
+
+        let (moment, _) = self.pending.take()?;
+        let def = library.get(moment.kind)?;
+        self.global = library.global_cooldown;
+        self.per_kind.insert(moment.kind, def.cooldown);
+        Some(moment)
+    }
+}
+
+/// 正在播放的短乐，播放结束后自动销毁
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Stinger;
+
+/// 收集时刻事件并按冷却规则播放短乐
+///
+/// 冷却按真实时间计算，不受慢动作与暂停影响
+#[allow(clippy::too_many_arguments)]
+pub fn play_moment_stingers(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    asset_server: Res<AssetServer>,
+    library: Res<StingerLibrary>,
+    mut cooldowns: ResMut<StingerCooldowns>,
+    mut moments: EventReader<MomentEvent>,
+    playing: Query<(), With<Stinger>>,
+    mut cues: EventWriter<SoundCue>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    cooldowns.tick(time.delta_secs());
+    for moment in moments.read() {
+        cooldowns.submit(&library, moment.clone());
+    }
+
+    let Some(moment) = cooldowns.take_ready(&library, !playing.is_empty()) else {
+        return;
+    };
+    let Some(def) = library.get(moment.kind) else {
+        return;
+    };
+    let Some(track) = def.tracks.choose(&mut rand::thread_rng()) else {
+        return;
+    };
+
+    commands.spawn((
+        Stinger,
+        Name::new(format!("Stinger: {:?}", moment.kind)),
+        AudioPlayer::<AudioSource>::new(asset_server.load(track.as_str())),
+        PlaybackSettings::DESPAWN.with_volume(Volume::new(def.volume)),
+        IgnoreTimeDilation,
+    ));
+
+    if let Some(caption) = &def.caption {
+        cues.send(SoundCue {
+            caption: caption.clone(),
+            source: None,
+            important: false,
+        });
+    }
+
+    if let Some(logger) = logger.as_mut() {
+        let label = moment.label.as_deref().unwrap_or("");
+        logger.log(
+            LogLevel::Debug,
+            &format!("播放短乐 {:?} {}: {}", moment.kind, label, track),
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use bevy::prelude::*;
+
+use super::{
+    attach_sound_emitters, attach_waterfall_sounds, collect_captions, crossfade_music,
+    detect_boss_phase_change, detect_region_discovery, detect_sunrise_over_peak, emit_enemy_cues,
+    emit_water_cues, play_combat_sounds, play_footsteps, play_moment_stingers, play_sounds,
+    sample_ambience_environment, select_music, update_ambience, update_caption_ui,
+    update_sound_emitters, AmbienceEnvironment, AudioVolumes, CaptionLog, CaptionSettings,
+    DialogueAudio, DiscoveredRegions, MomentEvent, MusicState, PlaySound, SoundCue, SoundLibrary,
+    StingerCooldowns, StingerLibrary, AUDIO_DATA_PATH, STINGER_DATA_PATH,
+};
+use crate::config::resolve_data_path;
+use crate::logging::{GameLogger, LogLevel};
+
+/// 音频插件
+///
+/// # 设计思路
+/// 1. 各系统在值得纪念的时刻发出 `MomentEvent`，不关心是否真的播放
+/// 2. 短乐按数据文件逐类配置，冷却管理保证短乐不重叠、不刷屏
+/// 3. 播放对白或重要音效时同时发出文字事件，开启字幕后显示在画面下方
+/// 4. 音效统一经由 `PlaySound` 事件播放，按与玩家的距离衰减；瀑布等持续的声音逐帧调整音量
+/// 5. 背景音乐按场景与气候区挑选，换曲时两首交叉淡入淡出，不会突兀地切断
+/// 6. 音乐与音效音量分开调节，由设置套用到 `AudioVolumes`
+/// 7. 环境音按玩家周围各类地形所占比例、天气强度与时段分层混合，边走边渐变
+pub struct GameAudioPlugin;
+
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StingerCooldowns>()
+            .init_resource::<DiscoveredRegions>()
+            .init_resource::<CaptionSettings>()
+            .init_resource::<CaptionLog>()
+            .init_resource::<AudioVolumes>()
+            .init_resource::<MusicState>()
+            .init_resource::<AmbienceEnvironment>()
+            .add_event::<MomentEvent>()
+            .add_event::<PlaySound>()
+            .add_event::<SoundCue>()
+            .add_event::<DialogueAudio>();
+
+        app.add_systems(PreStartup, (load_stinger_library, load_sound_library))
+            .add_systems(
+                Update,
+                (
+                    detect_region_discovery,
+                    detect_sunrise_over_peak,
+                    detect_boss_phase_change,
+                    play_moment_stingers,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    (attach_waterfall_sounds, play_combat_sounds, play_footsteps),
+                    play_sounds,
+                    attach_sound_emitters,
+                    update_sound_emitters,
+                    select_music,
+                    crossfade_music,
+                    sample_ambience_environment,
+                    update_ambience,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    (emit_enemy_cues, emit_water_cues),
+                    collect_captions,
+                    update_caption_ui,
+                )
+                    .chain()
+                    .after(play_sounds),
+            );
+    }
+}
+
+/// 加载短乐库，失败时使用空库（不播放任何短乐）
+fn load_stinger_library(mut commands: Commands, mut logger: Option<ResMut<GameLogger>>) {
+    let library = match StingerLibrary::load(&resolve_data_path(STINGER_DATA_PATH)) {
+        Ok(library) => library,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("短乐数据加载失败: {}", e));
+            }
+            StingerLibrary::default()
+        }
+    };
+    commands.insert_resource(library);
+}
+
+/// 加载音效库，失败时使用空库（没有音乐与音效）
+fn load_sound_library(mut commands: Commands, mut logger: Option<ResMut<GameLogger>>) {
+    let library = match SoundLibrary::load(&resolve_data_path(AUDIO_DATA_PATH)) {
+        Ok(library) => library,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("音频数据加载失败: {}", e));
+            }
+            SoundLibrary::default()
+        }
+    };
+    commands.insert_resource(library);
+}
@@ -0,0 +1,118 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::{tile_at, PlaySound, SoundEmitter, SoundLibrary};
+use crate::combat::{CombatEffectKind, DamageEvent, ParryEvent};
+use crate::render::animation::{AnimationFrameEvent, AnimationSet};
+use crate::world::chunk::{Chunk, ChunkManager, WaterfallEffect};
+use crate::world::entity::{Character, CharacterState};
+
+/// 动画中标记落脚的帧事件名
+const FOOTSTEP_EVENT: &str = "footstep";
+
+/// 瀑布生成时挂上循环水声，水流越急声音越大
+pub fn attach_waterfall_sounds(
+    mut commands: Commands,
+    waterfalls: Query<(Entity, &WaterfallEffect), Added<WaterfallEffect>>,
+) {
+    for (entity, waterfall) in waterfalls.iter() {
+        commands.entity(entity).insert(SoundEmitter {
+            volume: waterfall.flow_strength.clamp(0.3, 1.0),
+            ..SoundEmitter::new("waterfall")
+        });
+    }
+}
+
+/// 受击、治疗与招架时在目标处发出音效
+pub fn play_combat_sounds(
+    mut damage_events: EventReader<DamageEvent>,
+    mut parry_events: EventReader<ParryEvent>,
+    transforms: Query<&GlobalTransform>,
+    mut sounds: EventWriter<PlaySound>,
+) {
+    for event in damage_events.read() {
+        let sound = match event.kind {
+            CombatEffectKind::Damage => "hit",
+            CombatEffectKind::Heal => "heal",
+            CombatEffectKind::Status(_) => continue,
+        };
+        if let Ok(transform) = transforms.get(event.target) {
+            sounds.send(PlaySound::at(sound, transform.translation()));
+        }
+    }
+    for event in parry_events.read() {
+        if let Ok(transform) = transforms.get(event.defender) {
+            sounds.send(PlaySound::at("parry", transform.translation()));
+        }
+    }
+}
+
+/// 按脚下的瓦片类型选择脚步声，区块未加载时使用默认脚步声
+fn footstep_sound(
+    library: &SoundLibrary,
+    position: Vec3,
+    chunk_manager: Option<&ChunkManager>,
+    chunks: &Query<&Chunk>,
+) -> Option<PlaySound> {
+    let tile = chunk_manager
+        .and_then(|chunk_manager| tile_at(position, chunk_manager, chunks))
+        .map(|(tile, _)| tile);
+    library
+        .footstep(tile)
+        .map(|sound| PlaySound::at(sound, position))
+}
+
+/// 播放脚步声
+///
+/// 有帧动画的角色在动画的落脚帧响起，与画面同步；
+/// 其余角色在行走或奔跑时每走一个步长响一次
+pub fn play_footsteps(
+    library: Res<SoundLibrary>,
+    chunk_manager: Option<Res<ChunkManager>>,
+    chunks: Query<&Chunk>,
+    mut frame_events: EventReader<AnimationFrameEvent>,
+    characters: Query<(Entity, &Character, &GlobalTransform, Has<AnimationSet>)>,
+    mut walked: Local<HashMap<Entity, (Vec2, f32)>>,
+    mut sounds: EventWriter<PlaySound>,
+) {
+    let chunk_manager = chunk_manager.as_deref();
+    for event in frame_events.read() {
+        if event.event != FOOTSTEP_EVENT {
+            continue;
+        }
+        let Ok((_, _, transform, _)) = characters.get(event.entity) else {
+            continue;
+        };
+        if let Some(sound) =
+            footstep_sound(&library, transform.translation(), chunk_manager, &chunks)
+        {
+            sounds.send(sound);
+        }
+    }
+
+    let stride = library.footsteps.stride.max(1.0);
+    walked.retain(|entity, _| characters.contains(*entity));
+    for (entity, character, transform, animated) in characters.iter() {
+        let position = transform.translation().truncate();
+        let walking = matches!(
+            character.state,
+            CharacterState::Walking | CharacterState::Running
+        );
+        if animated || !walking {
+            walked.remove(&entity);
+            continue;
+        }
+
+        let (last, distance) = walked.entry(entity).or_insert((position, 0.0));
+        *distance += last.distance(position);
+        *last = position;
+        if *distance >= stride {
+            *distance = 0.0;
+            if let Some(sound) =
+                footstep_sound(&library, transform.translation(), chunk_manager, &chunks)
+            {
+                sounds.send(sound);
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// 聊天频道
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ChatChannel {
+    /// 世界频道，所有在线玩家可见
+    #[default]
+    World,
+    /// 队伍频道
+    Party,
+    /// 私聊
+    Whisper,
+    /// 系统提示，只在本地显示，不能发送
+    System,
+}
+
+impl ChatChannel {
+    /// 输入框中可以切换的频道，私聊须用 `/w` 指定对象
+    pub const SENDABLE: [ChatChannel; 2] = [ChatChannel::World, ChatChannel::Party];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ChatChannel::World => "世界",
+            ChatChannel::Party => "队伍",
+            ChatChannel::Whisper => "私聊",
+            ChatChannel::System => "系统",
+        }
+    }
+}
+
+/// 一条聊天消息
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatMessage {
+    pub channel: ChatChannel,
+    pub sender: String,
+    /// 私聊的对象；为空时是别人发给自己的私聊
+    pub target: Option<String>,
+    pub text: String,
+    /// 收到时的真实时间（秒），用于淡出
+    pub time: f64,
+}
+
+impl ChatMessage {
+    /// 显示在聊天记录中的一行
+    pub fn line(&self) -> String {
+        match (self.channel, &self.target) {
+            (ChatChannel::System, _) => format!("【{}】{}", self.channel.label(), self.text),
+            (ChatChannel::Whisper, Some(target)) => {
+                format!("【私聊】你对 {} 说：{}", target, self.text)
+            }
+            (ChatChannel::Whisper, None) => {
+                format!("【私聊】{} 对你说：{}", self.sender, self.text)
+            }
+            (channel, _) => format!("【{}】{}：{}", channel.label(), self.sender, self.text),
+        }
+    }
+}
+
+/// 聊天设置
+#[derive(Resource, Debug, Clone)]
+pub struct ChatSettings {
+    /// 最多保留的消息数
+    pub history_limit: usize,
+    /// 聊天框关闭时显示的最近消息数
+    pub visible_lines: usize,
+    /// 聊天框打开时显示的消息数
+    pub expanded_lines: usize,
+    /// 聊天框关闭时消息显示多久后隐藏（秒）
+    pub fade_after: f32,
+    /// 一条消息最多的字数
+    pub max_length: usize,
+}
+
+impl Default for ChatSettings {
+    fn default() -> Self {
+        Self {
+            history_limit: 200,
+            visible_lines: 6,
+            expanded_lines: 14,
+            fade_after: 12.0,
+            max_length: 120,
+        }
+    }
+}
+
+/// 聊天记录
+#[derive(Resource, Debug, Default)]
+pub struct ChatLog {
+    messages: VecDeque<ChatMessage>,
+}
+
+impl ChatLog {
+    /// 追加一条消息，超出上限时丢弃最旧的
+    pub fn push(&mut self, message: ChatMessage, limit: usize) {
+        self.messages.push_back(message);
+        while self.messages.len() > limit.max(1) {
+            self.messages.pop_front();
+        }
+    }
+
+    /// 最近的 `count` 条消息，按时间先后排列
+    pub fn recent(&self, count: usize) -> impl Iterator<Item = &ChatMessage> {
+        self.messages
+            .iter()
+            .skip(self.messages.len().saturating_sub(count))
+    }
+}
+
+/// 聊天输入框
+#[derive(Resource, Debug, Default)]
+pub struct ChatInput {
+    pub open: bool,
+    pub text: String,
+    /// 输入法组字中的内容，只显示不发送
+    pub preedit: String,
+    /// 不带斜杠命令时发往的频道
+    pub channel: ChatChannel,
+}
+
+/// 发送聊天
+#[derive(Event, Debug, Clone)]
+pub struct SendChat {
+    pub channel: ChatChannel,
+    /// 私聊对象
+    pub target: Option<String>,
+    pub text: String,
+}
+
+/// 输入框中一行内容的含义
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatLine {
+    /// 发往某个频道
+    Say {
+        channel: ChatChannel,
+        target: Option<String>,
+        text: String,
+    },
+    /// 切换默认频道
+    SwitchChannel(ChatChannel),
+    /// 交给开发终端的命令，不含斜杠
+    Console(String),
+    /// 格式不对，附带提示
+    Invalid(&'static str),
+}
+
+impl ChatLine {
+    /// 解析输入框内容
+    ///
+    /// `/w <名字> <内容>` 私聊，`/s`、`/p` 后接内容时发往世界或队伍频道，不接内容时切换默认频道；
+    /// 其他以斜杠开头的内容交给开发终端
+    pub fn parse(text: &str, channel: ChatChannel) -> Option<Self> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        let Some(command) = text.strip_prefix('/') else {
+            return Some(ChatLine::Say {
+                channel,
+                target: None,
+                text: text.to_string(),
+            });
+        };
+        let (name, rest) = command
+            .split_once(char::is_whitespace)
+            .map_or((command, ""), |(name, rest)| (name, rest.trim()));
+        let channel = match name {
+            "w" | "whisper" => {
+                let Some((target, text)) = rest.split_once(char::is_whitespace) else {
+                    return Some(ChatLine::Invalid("用法：/w <名字> <内容>"));
+                };
+                return Some(ChatLine::Say {
+                    channel: ChatChannel::Whisper,
+                    target: Some(target.to_string()),
+                    text: text.trim().to_string(),
+                });
+            }
+            "s" | "world" => ChatChannel::World,
+            "p" | "party" => ChatChannel::Party,
+            _ => return Some(ChatLine::Console(command.to_string())),
+        };
+        if rest.is_empty() {
+            Some(ChatLine::SwitchChannel(channel))
+        } else {
+            Some(ChatLine::Say {
+                channel,
+                target: None,
+                text: rest.to_string(),
+            })
+        }
+    }
+}
@@ -0,0 +1,16 @@
+/// 聊天模块
+///
+/// 玩家之间的文字聊天：单机时只在本地回显，连上服务器时按世界、队伍与私聊频道收发；
+/// 以斜杠开头的内容可以切换频道或作为命令交给开发终端
+///
+/// # 模块组成
+/// 1. message：聊天频道、消息、记录与输入内容的解析
+/// 2. panel：屏幕左下的聊天记录与输入框
+/// 3. systems：聊天插件，处理输入、发送与接收
+mod message;
+mod panel;
+mod systems;
+
+pub use message::*;
+pub use panel::*;
+pub use systems::ChatPlugin;
@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use super::{ChatChannel, ChatInput, ChatLog, ChatSettings};
+use crate::ui::{label, panel, TextRole};
+
+/// 聊天框距离屏幕左下角的位置，留出状态栏的高度
+const CHAT_LEFT: f32 = 12.0;
+const CHAT_BOTTOM: f32 = 72.0;
+
+/// 聊天框宽度
+const CHAT_WIDTH: f32 = 420.0;
+
+/// 聊天框根节点
+#[derive(Component)]
+pub struct ChatPanel;
+
+/// 进入游戏时生成聊天框，内容由重绘系统填充
+pub fn spawn_chat_panel(mut commands: Commands) {
+    commands.spawn((
+        ChatPanel,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(CHAT_LEFT),
+            bottom: Val::Px(CHAT_BOTTOM),
+            width: Val::Px(CHAT_WIDTH),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(2.0),
+            ..default()
+        },
+        Visibility::Hidden,
+        Name::new("Chat"),
+    ));
+}
+
+/// 离开游戏时移除聊天框
+pub fn despawn_chat_panel(mut commands: Commands, panels: Query<Entity, With<ChatPanel>>) {
+    for panel in panels.iter() {
+        commands.entity(panel).despawn_recursive();
+    }
+}
+
+/// 重绘聊天框
+///
+/// 打开时显示较多记录与输入框；关闭时只显示最近几条，过一段时间后隐藏。
+/// 记录或输入变化时重建，平时每秒检查一次是否有消息到了该隐藏的时候
+#[allow(clippy::too_many_arguments)]
+pub fn redraw_chat_panel(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    settings: Res<ChatSettings>,
+    log: Res<ChatLog>,
+    input: Res<ChatInput>,
+    mut panels: Query<(Entity, Ref<ChatPanel>, &mut Visibility)>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut refresh: Local<f32>,
+) {
+    let Ok((entity, marker, mut visibility)) = panels.get_single_mut() else {
+        return;
+    };
+    *refresh -= time.delta_secs();
+    let changed = marker.is_added() || log.is_changed() || input.is_changed();
+    if !changed && *refresh > 0.0 {
+        return;
+    }
+    *refresh = 1.0;
+
+    if input.is_changed() {
+        if let Ok(mut window) = windows.get_single_mut() {
+            if window.ime_enabled != input.open {
+                window.ime_enabled = input.open;
+            }
+        }
+    }
+
+    let now = time.elapsed_secs_f64();
+    let lines: Vec<_> = if input.open {
+        log.recent(settings.expanded_lines).collect()
+    } else {
+        log.recent(settings.visible_lines)
+            .filter(|message| now - message.time < settings.fade_after as f64)
+            .collect()
+    };
+    let shown = input.open || !lines.is_empty();
+    let target = if shown {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    if *visibility != target {
+        *visibility = target;
+    }
+
+    commands.entity(entity).despawn_descendants();
+    commands.entity(entity).with_children(|root| {
+        for message in lines {
+            let role = if message.channel == ChatChannel::System {
+                TextRole::Muted
+            } else {
+                TextRole::Small
+            };
+            root.spawn(label(message.line(), role));
+        }
+        if !input.open {
+            return;
+        }
+        root.spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(6.0)),
+                row_gap: Val::Px(2.0),
+                ..default()
+            },
+            panel(),
+        ))
+        .with_children(|input_box| {
+            input_box.spawn(label(
+                format!(
+                    "【{}】{}{}_",
+                    input.channel.label(),
+                    input.text,
+                    input.preedit
+                ),
+                TextRole::Body,
+            ));
+            input_box.spawn(label(
+                "回车发送 · Tab 切换频道 · /w 名字 私聊 · Esc 取消",
+                TextRole::Muted,
+            ));
+        });
+    });
+}
@@ -0,0 +1,292 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy::window::Ime;
+
+use super::{
+    despawn_chat_panel, redraw_chat_panel, spawn_chat_panel, ChatChannel, ChatInput, ChatLine,
+    ChatLog, ChatMessage, ChatSettings, SendChat,
+};
+use crate::analytics::{ConsoleInput, PlaytestSettings};
+use crate::events::input::handle_input_events;
+use crate::events::network::NetworkEvent;
+use crate::housing::HousingEditMode;
+use crate::network::{ClientMessage, NetworkClient, ServerMessage, SessionToken};
+use crate::resources::{GameState, InputState};
+use crate::rest::RestMenu;
+use crate::ui::{OptionsMenuState, WorldMapState};
+use crate::world::entity::{Character, Player};
+
+/// 输入框提交的一行内容，由处理系统解析为聊天、切换频道或终端命令
+#[derive(Event, Debug, Clone)]
+pub struct ChatSubmitted(pub String);
+
+/// 聊天插件
+///
+/// # 设计思路
+/// 1. 回车打开输入框，打开期间键盘只用于打字，游戏动作暂停到按键松开为止
+/// 2. 输入框只产出文字，斜杠命令、频道切换与发送由后续系统处理，其他系统也可直接发送 `SendChat`
+/// 3. 连上服务器时按频道发给服务器，服务器不把自己的消息转发回来，因此总在本地回显；单机时只在本地回显
+/// 4. 不属于聊天的斜杠命令交给开发终端，与在启动终端中输入相同
+/// 5. 聊天记录常驻，聊天框随游戏场景生成与移除，关闭时只短暂显示最近的消息
+pub struct ChatPlugin;
+
+impl Plugin for ChatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChatSettings>()
+            .init_resource::<ChatLog>()
+            .init_resource::<ChatInput>()
+            .add_event::<ChatSubmitted>()
+            .add_event::<SendChat>()
+            .add_systems(OnEnter(GameState::InGame), spawn_chat_panel)
+            .add_systems(OnExit(GameState::InGame), (close_chat, despawn_chat_panel))
+            .add_systems(
+                Update,
+                (
+                    edit_chat_input.run_if(
+                        in_state(GameState::InGame)
+                            .and(in_state(OptionsMenuState::Closed))
+                            .and(in_state(WorldMapState::Closed)),
+                    ),
+                    process_chat_lines,
+                    send_chat_messages,
+                    receive_network_chat,
+                    redraw_chat_panel,
+                )
+                    .chain()
+                    // 输入框开关当帧按下的回车、退出键先被动作汇总屏蔽
+                    .after(handle_input_events),
+            );
+    }
+}
+
+fn log_system(log: &mut ChatLog, settings: &ChatSettings, now: f64, text: impl Into<String>) {
+    log.push(
+        ChatMessage {
+            channel: ChatChannel::System,
+            sender: String::new(),
+            target: None,
+            text: text.into(),
+            time: now,
+        },
+        settings.history_limit,
+    );
+}
+
+fn push_text(text: &mut String, addition: &str, max_length: usize) {
+    let room = max_length.saturating_sub(text.chars().count());
+    text.extend(addition.chars().filter(|c| !c.is_control()).take(room));
+}
+
+/// 离开游戏场景时关闭输入框，恢复游戏动作
+fn close_chat(mut input: ResMut<ChatInput>, mut input_state: ResMut<InputState>) {
+    input.open = false;
+    input.text.clear();
+    input.preedit.clear();
+    input_state.typing = false;
+}
+
+/// 聊天框的键盘与输入法输入
+///
+/// 关闭时回车打开；打开时回车提交，退出键取消，Tab 在世界与队伍频道间切换。
+/// 摆放家具或休息菜单打开时回车留给它们
+#[allow(clippy::too_many_arguments)]
+fn edit_chat_input(
+    settings: Res<ChatSettings>,
+    edit_mode: Option<Res<HousingEditMode>>,
+    rest_menu: Option<Res<RestMenu>>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut ime_events: EventReader<Ime>,
+    mut input: ResMut<ChatInput>,
+    mut input_state: ResMut<InputState>,
+    mut submitted: EventWriter<ChatSubmitted>,
+) {
+    if !input.open {
+        ime_events.clear();
+        let busy = edit_mode.is_some_and(|edit_mode| edit_mode.active)
+            || rest_menu.is_some_and(|menu| menu.spot.is_some());
+        let opened = keyboard_events
+            .read()
+            .any(|event| event.state == ButtonState::Pressed && event.logical_key == Key::Enter);
+        if opened && !busy {
+            input.open = true;
+            input_state.typing = true;
+        }
+        return;
+    }
+
+    for event in ime_events.read() {
+        match event {
+            Ime::Preedit { value, .. } => input.preedit = value.clone(),
+            Ime::Commit { value, .. } => {
+                input.preedit.clear();
+                push_text(&mut input.text, value, settings.max_length);
+            }
+            _ => {}
+        }
+    }
+
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        // 组字过程中的按键交给输入法
+        if !input.preedit.is_empty() {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Character(text) => push_text(&mut input.text, text, settings.max_length),
+            Key::Space => push_text(&mut input.text, " ", settings.max_length),
+            Key::Backspace => {
+                input.text.pop();
+            }
+            Key::Tab => {
+                let channels = ChatChannel::SENDABLE;
+                let index = channels
+                    .iter()
+                    .position(|channel| *channel == input.channel)
+                    .map_or(0, |index| (index + 1) % channels.len());
+                input.channel = channels[index];
+            }
+            Key::Enter | Key::Escape => {
+                let text = std::mem::take(&mut input.text);
+                if event.logical_key == Key::Enter {
+                    submitted.send(ChatSubmitted(text));
+                }
+                input.open = false;
+                input_state.typing = false;
+                break;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 解析提交的内容：聊天转为发送请求，切换频道直接生效，其他斜杠命令交给开发终端
+#[allow(clippy::too_many_arguments)]
+fn process_chat_lines(
+    time: Res<Time<Real>>,
+    settings: Res<ChatSettings>,
+    playtest: Res<PlaytestSettings>,
+    mut submitted: EventReader<ChatSubmitted>,
+    mut input: ResMut<ChatInput>,
+    mut log: ResMut<ChatLog>,
+    mut sends: EventWriter<SendChat>,
+    mut console: EventWriter<ConsoleInput>,
+) {
+    let now = time.elapsed_secs_f64();
+    for ChatSubmitted(text) in submitted.read() {
+        match ChatLine::parse(text, input.channel) {
+            None => {}
+            Some(ChatLine::Say {
+                channel,
+                target,
+                text,
+            }) => {
+                sends.send(SendChat {
+                    channel,
+                    target,
+                    text,
+                });
+            }
+            Some(ChatLine::SwitchChannel(channel)) => {
+                input.channel = channel;
+                log_system(
+                    &mut log,
+                    &settings,
+                    now,
+                    format!("切换到{}频道", channel.label()),
+                );
+            }
+            Some(ChatLine::Console(command)) => {
+                if playtest.enabled {
+                    log_system(&mut log, &settings, now, format!("> {}", command));
+                    console.send(ConsoleInput(command));
+                } else {
+                    log_system(&mut log, &settings, now, "开发终端未开启");
+                }
+            }
+            Some(ChatLine::Invalid(hint)) => log_system(&mut log, &settings, now, hint),
+        }
+    }
+}
+
+/// 发送聊天：连上服务器时发给服务器，并在本地回显
+fn send_chat_messages(
+    time: Res<Time<Real>>,
+    settings: Res<ChatSettings>,
+    client: Res<NetworkClient>,
+    session: Option<Res<SessionToken>>,
+    players: Query<&Character, With<Player>>,
+    mut sends: EventReader<SendChat>,
+    mut log: ResMut<ChatLog>,
+) {
+    let now = time.elapsed_secs_f64();
+    let sender = session
+        .map(|session| session.account.clone())
+        .or_else(|| players.get_single().ok().map(|player| player.name.clone()))
+        .unwrap_or_default();
+
+    for send in sends.read() {
+        if send.channel == ChatChannel::System || send.text.trim().is_empty() {
+            continue;
+        }
+        let text: String = send.text.chars().take(settings.max_length).collect();
+        if client.is_connected() {
+            let sent = client.send(ClientMessage::Chat {
+                channel: send.channel,
+                target: send.target.clone(),
+                text: text.clone(),
+            });
+            if !sent {
+                log_system(&mut log, &settings, now, "消息发送失败");
+                continue;
+            }
+        } else if send.channel != ChatChannel::World {
+            log_system(
+                &mut log,
+                &settings,
+                now,
+                format!("未连接服务器，{}消息只在本地显示", send.channel.label()),
+            );
+        }
+        log.push(
+            ChatMessage {
+                channel: send.channel,
+                sender: sender.clone(),
+                target: send.target.clone(),
+                text,
+                time: now,
+            },
+            settings.history_limit,
+        );
+    }
+}
+
+/// 把服务器转发的聊天加入记录
+fn receive_network_chat(
+    time: Res<Time<Real>>,
+    settings: Res<ChatSettings>,
+    mut events: EventReader<NetworkEvent>,
+    mut log: ResMut<ChatLog>,
+) {
+    for event in events.read() {
+        if let NetworkEvent::Message(ServerMessage::Chat {
+            channel,
+            sender,
+            text,
+        }) = event
+        {
+            log.push(
+                ChatMessage {
+                    channel: *channel,
+                    sender: sender.clone(),
+                    target: None,
+                    text: text.clone(),
+                    time: time.elapsed_secs_f64(),
+                },
+                settings.history_limit,
+            );
+        }
+    }
+}
@@ -0,0 +1,19 @@
+/// 闲谈模块
+///
+/// 城镇中相邻的 NPC 按数据文件中的脚本互相搭话，内容随天气、时段、季节与近期发生的事变化，
+/// 以头顶气泡显示，不进入完整的对话系统
+///
+/// # 模块组成
+/// 1. script：闲谈脚本定义、触发条件与脚本库
+/// 2. world_events：近期世界事件记录，供触发条件查询
+/// 3. session：闲谈的发起与推进，台词交给界面模块的气泡显示
+/// 4. systems：闲谈插件
+mod script;
+mod session;
+mod systems;
+mod world_events;
+
+pub use script::*;
+pub use session::*;
+pub use systems::ChatterPlugin;
+pub use world_events::*;
@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use super::{RecentWorldEvents, WorldEventTag};
+use crate::time::TimeOfDay;
+use crate::world::entity::NpcType;
+use crate::world::map::Season;
+use crate::world::weather::WeatherKind;
+
+/// 闲谈脚本文件路径
+pub const CHATTER_DATA_PATH: &str = "src/config/chatter.json";
+
+/// 一句台词
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatterLine {
+    /// 说话人，0 为发起者，1 为应答者
+    pub speaker: usize,
+    pub text: String,
+}
+
+/// 触发条件，未填写的条件不限制
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatterConditions {
+    #[serde(default)]
+    pub weather: Vec<WeatherKind>,
+    #[serde(default)]
+    pub time_of_day: Vec<TimeOfDay>,
+    #[serde(default)]
+    pub seasons: Vec<Season>,
+    /// 需要近期发生过的世界事件
+    #[serde(default)]
+    pub recent_event: Option<WorldEventTag>,
+}
+
+/// 判断触发条件时的世界状态
+#[derive(Debug, Clone, Copy)]
+pub struct ChatterContext {
+    /// 当前天气，没有天气系统时为 None
+    pub weather: Option<WeatherKind>,
+    pub time_of_day: TimeOfDay,
+    pub season: Season,
+    /// 当前游戏时间（秒）
+    pub now: f64,
+}
+
+impl ChatterConditions {
+    /// 当前世界状态是否满足条件
+    pub fn matches(
+        &self,
+        context: &ChatterContext,
+        events: &RecentWorldEvents,
+        event_window: f32,
+    ) -> bool {
+        let weather_ok = self.weather.is_empty()
+            || context
+                .weather
+                .is_some_and(|weather| self.weather.contains(&weather));
+        let time_ok =
+            self.time_of_day.is_empty() || self.time_of_day.contains(&context.time_of_day);
+        let season_ok = self.seasons.is_empty() || self.seasons.contains(&context.season);
+        let event_ok = self
+            .recent_event
+            .is_none_or(|tag| events.happened_within(tag, event_window, context.now));
+        weather_ok && time_ok && season_ok && event_ok
+    }
+}
+
+/// 闲谈脚本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatterDef {
+    pub id: String,
+    /// 发起者与应答者的 NPC 类型，None 为任意非敌对 NPC
+    #[serde(default)]
+    pub speakers: [Option<NpcType>; 2],
+    #[serde(default)]
+    pub conditions: ChatterConditions,
+    pub lines: Vec<ChatterLine>,
+    /// 同一脚本两次触发的最短间隔（游戏秒数）
+    #[serde(default = "default_cooldown")]
+    pub cooldown: f32,
+    /// 多个脚本同时满足条件时的权重
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+}
+
+fn default_cooldown() -> f32 {
+    300.0
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+impl ChatterDef {
+    /// 按脚本的说话人要求排列两个 NPC，不满足时返回 None
+    pub fn cast<T: Copy>(&self, a: (T, NpcType), b: (T, NpcType)) -> Option<[T; 2]> {
+        let fits = |role: Option<NpcType>, npc_type: NpcType| role.is_none_or(|r| r == npc_type);
+        if fits(self.speakers[0], a.1) && fits(self.speakers[1], b.1) {
+            Some([a.0, b.0])
+        } else if fits(self.speakers[0], b.1) && fits(self.speakers[1], a.1) {
+            Some([b.0, a.0])
+        } else {
+            None
+        }
+    }
+}
+
+/// 闲谈脚本文件格式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChatterDataFile {
+    event_window: f32,
+    chatters: Vec<ChatterDef>,
+}
+
+/// 闲谈脚本库
+#[derive(Resource, Debug, Clone)]
+pub struct ChatterLibrary {
+    /// 世界事件在多长时间内算作"近期"（游戏秒数）
+    pub event_window: f32,
+    pub chatters: Vec<ChatterDef>,
+}
+
+impl Default for ChatterLibrary {
+    fn default() -> Self {
+        Self {
+            event_window: 600.0,
+            chatters: Vec::new(),
+        }
+    }
+}
+
+impl ChatterLibrary {
+    /// 从数据文件加载，丢弃说话人编号越界或没有台词的脚本
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let data: ChatterDataFile = serde_json::from_str(&content)?;
+        let chatters = data
+            .chatters
+            .into_iter()
+            .filter(|def| !def.lines.is_empty() && def.lines.iter().all(|line| line.speaker < 2))
+            .collect();
+        Ok(Self {
+            event_window: data.event_window,
+            chatters,
+        })
+    }
+}
@@ -0,0 +1,291 @@
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashMap;
+
+use super::{ChatterContext, ChatterLibrary, RecentWorldEvents};
+use crate::logging::{GameLogger, LogLevel};
+use crate::time::{DayNightState, GameCalendar};
+use crate::ui::{BubbleKind, ShowSpeechBubble};
+use crate::world::entity::{AiState, Character, Npc, NpcType, Player};
+use crate::world::weather::WeatherState;
+
+/// 闲谈配置
+#[derive(Resource, Debug, Clone)]
+pub struct ChatterSettings {
+    /// 两个 NPC 之间可以搭话的距离
+    pub pair_range: f32,
+    /// 只在玩家附近发起闲谈
+    pub player_range: f32,
+    /// 寻找闲谈对象的间隔（秒）
+    pub check_interval: f32,
+    /// 任意两次闲谈开始之间的最短间隔（秒）
+    pub global_cooldown: f32,
+    /// 同一个 NPC 两次参与闲谈的最短间隔（秒）
+    pub speaker_cooldown: f32,
+    /// 每句台词的显示时长（秒）
+    pub line_duration: f32,
+    /// 两句台词之间的停顿（秒）
+    pub line_gap: f32,
+    /// 同时进行的闲谈数量上限
+    pub max_active: usize,
+}
+
+impl Default for ChatterSettings {
+    fn default() -> Self {
+        Self {
+            pair_range: 96.0,
+            player_range: 480.0,
+            check_interval: 2.0,
+            global_cooldown: 8.0,
+            speaker_cooldown: 45.0,
+            line_duration: 3.0,
+            line_gap: 0.6,
+            max_active: 2,
+        }
+    }
+}
+
+/// 进行中的一段闲谈
+#[derive(Debug, Clone)]
+pub struct ActiveChatter {
+    /// 脚本在脚本库中的下标
+    pub script: usize,
+    /// 发起者与应答者
+    pub speakers: [Entity; 2],
+    /// 下一句台词的序号
+    pub line: usize,
+    /// 下一句台词的开始时间
+    pub next_line_at: f64,
+}
+
+/// 闲谈状态
+///
+/// 所有时间均为游戏时间秒数，暂停时闲谈一并暂停
+#[derive(Resource, Debug, Default)]
+pub struct ChatterState {
+    pub active: Vec<ActiveChatter>,
+    /// 各脚本可再次触发的时间
+    script_ready_at: HashMap<usize, f64>,
+    /// 各 NPC 可再次参与闲谈的时间
+    speaker_ready_at: HashMap<Entity, f64>,
+    next_check_at: f64,
+    global_ready_at: f64,
+}
+
+impl ChatterState {
+    /// NPC 是否正在闲谈
+    pub fn is_talking(&self, entity: Entity) -> bool {
+        self.active
+            .iter()
+            .any(|chatter| chatter.speakers.contains(&entity))
+    }
+}
+
+/// 能否参与闲谈：非敌对且没有在追逐、战斗或逃跑
+fn is_idle_townsfolk(npc: &Npc) -> bool {
+    !matches!(npc.npc_type, NpcType::Enemy | NpcType::Boss)
+        && matches!(
+            npc.ai_state,
+            AiState::Idle | AiState::Wander | AiState::Patrol
+        )
+}
+
+/// 定期在玩家附近寻找相邻的 NPC，挑选满足条件的脚本开始闲谈
+#[allow(clippy::too_many_arguments)]
+pub fn start_chatter(
+    time: Res<Time>,
+    settings: Res<ChatterSettings>,
+    library: Res<ChatterLibrary>,
+    events: Res<RecentWorldEvents>,
+    calendar: Res<GameCalendar>,
+    day_night: Res<DayNightState>,
+    weather: Option<Res<WeatherState>>,
+    mut state: ResMut<ChatterState>,
+    players: Query<&Transform, With<Player>>,
+    mut npcs: Query<(Entity, &mut Npc, &mut Character, &Transform), Without<Player>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let now = time.elapsed_secs_f64();
+    if now < state.next_check_at {
+        return;
+    }
+    state.next_check_at = now + settings.check_interval as f64;
+    if library.chatters.is_empty()
+        || state.active.len() >= settings.max_active
+        || now < state.global_ready_at
+    {
+        return;
+    }
+    let Ok(player) = players.get_single() else {
+        return;
+    };
+    let player_pos = player.translation.truncate();
+
+    let candidates: Vec<(Entity, NpcType, Vec2)> = npcs
+        .iter()
+        .filter(|(entity, npc, _, transform)| {
+            is_idle_townsfolk(npc)
+                && !state.is_talking(*entity)
+                && state
+                    .speaker_ready_at
+                    .get(entity)
+                    .is_none_or(|ready| *ready <= now)
+                && transform.translation.truncate().distance(player_pos) <= settings.player_range
+        })
+        .map(|(entity, npc, _, transform)| (entity, npc.npc_type, transform.translation.truncate()))
+        .collect();
+
+    let mut pairs = Vec::new();
+    for (i, a) in candidates.iter().enumerate() {
+        for b in &candidates[i + 1..] {
+            if a.2.distance(b.2) <= settings.pair_range {
+                pairs.push((a, b));
+            }
+        }
+    }
+    if pairs.is_empty() {
+        return;
+    }
+
+    let context = ChatterContext {
+        weather: weather.map(|weather| weather.kind),
+        time_of_day: day_night.time_of_day,
+        season: calendar.season(),
+        now,
+    };
+    let mut rng = rand::thread_rng();
+    pairs.shuffle(&mut rng);
+
+    for (a, b) in pairs {
+        let options: Vec<(usize, [Entity; 2], f32)> = library
+            .chatters
+            .iter()
+            .enumerate()
+            .filter(|(index, def)| {
+                state
+                    .script_ready_at
+                    .get(index)
+                    .is_none_or(|ready| *ready <= now)
+                    && def
+                        .conditions
+                        .matches(&context, &events, library.event_window)
+            })
+            .filter_map(|(index, def)| {
+                def.cast((a.0, a.1), (b.0, b.1))
+                    .map(|speakers| (index, speakers, def.weight.max(0.0)))
+            })
+            .collect();
+
+        let total: f32 = options.iter().map(|option| option.2).sum();
+        if total <= 0.0 {
+            continue;
+        }
+        let mut roll = rng.gen_range(0.0..total);
+        let Some(&(script, speakers, _)) = options.iter().find(|option| {
+            roll -= option.2;
+            roll < 0.0
+        }) else {
+            continue;
+        };
+
+        // 闲谈期间停下脚步
+        for speaker in speakers {
+            if let Ok((_, mut npc, mut character, _)) = npcs.get_mut(speaker) {
+                npc.ai_state = AiState::Talk;
+                character.direction = Vec2::ZERO;
+            }
+        }
+
+        let def = &library.chatters[script];
+        state
+            .script_ready_at
+            .insert(script, now + def.cooldown as f64);
+        state.global_ready_at = now + settings.global_cooldown as f64;
+        state.active.push(ActiveChatter {
+            script,
+            speakers,
+            line: 0,
+            next_line_at: now,
+        });
+
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Debug, &format!("NPC 闲谈开始: {}", def.id));
+        }
+        return;
+    }
+}
+
+/// 逐句推进闲谈并显示气泡，说话人走散、离开或被打断时提前结束
+pub fn advance_chatter(
+    time: Res<Time>,
+    settings: Res<ChatterSettings>,
+    library: Res<ChatterLibrary>,
+    mut state: ResMut<ChatterState>,
+    mut npcs: Query<(&mut Npc, &Transform)>,
+    mut bubbles: EventWriter<ShowSpeechBubble>,
+) {
+    let now = time.elapsed_secs_f64();
+    let mut finished = Vec::new();
+
+    for (index, chatter) in state.active.iter_mut().enumerate() {
+        let Some(def) = library.chatters.get(chatter.script) else {
+            finished.push(index);
+            continue;
+        };
+
+        // 两人都还在原地交谈才继续
+        let positions: Vec<Option<Vec2>> = chatter
+            .speakers
+            .iter()
+            .map(|speaker| {
+                npcs.get(*speaker)
+                    .ok()
+                    .filter(|(npc, _)| npc.ai_state == AiState::Talk)
+                    .map(|(_, transform)| transform.translation.truncate())
+            })
+            .collect();
+        let together = match (positions[0], positions[1]) {
+            (Some(a), Some(b)) => a.distance(b) <= settings.pair_range * 1.5,
+            _ => false,
+        };
+        if !together {
+            finished.push(index);
+            continue;
+        }
+        if now < chatter.next_line_at {
+            continue;
+        }
+        // 最后一句显示完毕后结束
+        if chatter.line >= def.lines.len() {
+            finished.push(index);
+            continue;
+        }
+
+        let line = &def.lines[chatter.line];
+        let speaker = chatter.speakers[line.speaker];
+        bubbles.send(
+            ShowSpeechBubble::new(speaker, line.text.clone(), BubbleKind::Chatter)
+                .with_duration(settings.line_duration),
+        );
+
+        chatter.line += 1;
+        chatter.next_line_at = now + (settings.line_duration + settings.line_gap) as f64;
+    }
+
+    // 结束的闲谈让说话人恢复空闲并进入冷却
+    for index in finished.into_iter().rev() {
+        let chatter = state.active.remove(index);
+        for speaker in chatter.speakers {
+            if let Ok((mut npc, _)) = npcs.get_mut(speaker) {
+                if npc.ai_state == AiState::Talk {
+                    npc.ai_state = AiState::Idle;
+                }
+            }
+            state
+                .speaker_ready_at
+                .insert(speaker, now + settings.speaker_cooldown as f64);
+        }
+    }
+    state.speaker_ready_at.retain(|_, ready| *ready > now);
+}
@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+
+use super::{
+    advance_chatter, record_world_events, start_chatter, ChatterLibrary, ChatterSettings,
+    ChatterState, RecentWorldEvents, CHATTER_DATA_PATH,
+};
+use crate::config::resolve_data_path;
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::gameplay_running;
+
+/// 闲谈插件
+///
+/// # 设计思路
+/// 1. 脚本完全由数据文件定义，新增闲谈不需要改代码
+/// 2. 世界事件只记录类别与时间，脚本通过条件引用，不直接依赖各系统
+/// 3. 只在玩家附近发起，多重冷却避免同一句话反复出现
+pub struct ChatterPlugin;
+
+impl Plugin for ChatterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChatterSettings>()
+            .init_resource::<ChatterState>()
+            .init_resource::<RecentWorldEvents>();
+
+        app.add_systems(PreStartup, load_chatter_library)
+            .add_systems(
+                Update,
+                (record_world_events, start_chatter, advance_chatter)
+                    .chain()
+                    .run_if(gameplay_running),
+            );
+    }
+}
+
+/// 加载闲谈脚本，失败时 NPC 不闲谈
+fn load_chatter_library(mut commands: Commands, mut logger: Option<ResMut<GameLogger>>) {
+    let library = match ChatterLibrary::load(&resolve_data_path(CHATTER_DATA_PATH)) {
+        Ok(library) => library,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("闲谈脚本加载失败: {}", e));
+            }
+            ChatterLibrary::default()
+        }
+    };
+    commands.insert_resource(library);
+}
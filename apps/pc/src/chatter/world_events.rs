@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::audio::{MomentEvent, MomentKind};
+use crate::combat::DeathEvent;
+use crate::rest::RestFinished;
+use crate::time::SeasonChanged;
+use crate::world::entity::{Npc, NpcType, Player};
+
+/// 可被闲谈引用的世界事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorldEventTag {
+    /// 玩家倒下
+    PlayerDefeated,
+    /// 有敌人被击败
+    EnemySlain,
+    /// 首领被击败
+    BossSlain,
+    /// 野外露宿遭遇夜袭
+    Ambush,
+    /// 换季
+    SeasonChanged,
+    /// 完成任务
+    QuestCompleted,
+    /// 发现新区域
+    RegionDiscovered,
+}
+
+/// 近期世界事件
+///
+/// 每类事件只记录最近一次发生的时间（游戏秒数）
+#[derive(Resource, Debug, Default)]
+pub struct RecentWorldEvents {
+    last_seen: HashMap<WorldEventTag, f64>,
+}
+
+impl RecentWorldEvents {
+    /// 记录一次事件
+    pub fn record(&mut self, tag: WorldEventTag, now: f64) {
+        self.last_seen.insert(tag, now);
+    }
+
+    /// 事件是否在最近 `window` 秒内发生过
+    pub fn happened_within(&self, tag: WorldEventTag, window: f32, now: f64) -> bool {
+        self.last_seen
+            .get(&tag)
+            .is_some_and(|time| now - *time <= window as f64)
+    }
+}
+
+/// 从各系统的事件中收集近期世界事件
+#[allow(clippy::too_many_arguments)]
+pub fn record_world_events(
+    time: Res<Time>,
+    mut recent: ResMut<RecentWorldEvents>,
+    mut deaths: EventReader<DeathEvent>,
+    mut rests: EventReader<RestFinished>,
+    mut seasons: EventReader<SeasonChanged>,
+    mut moments: EventReader<MomentEvent>,
+    players: Query<(), With<Player>>,
+    npcs: Query<&Npc>,
+) {
+    let now = time.elapsed_secs_f64();
+
+    for death in deaths.read() {
+        if players.contains(death.entity) {
+            recent.record(WorldEventTag::PlayerDefeated, now);
+            continue;
+        }
+        match npcs.get(death.entity).map(|npc| npc.npc_type) {
+            Ok(NpcType::Boss) => recent.record(WorldEventTag::BossSlain, now),
+            Ok(NpcType::Enemy) => recent.record(WorldEventTag::EnemySlain, now),
+            _ => {}
+        }
+    }
+    if rests.read().any(|rest| rest.interrupted) {
+        recent.record(WorldEventTag::Ambush, now);
+    }
+    if seasons.read().last().is_some() {
+        recent.record(WorldEventTag::SeasonChanged, now);
+    }
+    for moment in moments.read() {
+        match moment.kind {
+            MomentKind::QuestCompleted => recent.record(WorldEventTag::QuestCompleted, now),
+            MomentKind::RegionDiscovered => recent.record(WorldEventTag::RegionDiscovered, now),
+            _ => {}
+        }
+    }
+}
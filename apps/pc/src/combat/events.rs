@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+use crate::events::input::GameAction;
+
+/// 伤害/效果类型
+#[derive(Debug, Clone, PartialEq)]
+pub enum CombatEffectKind {
+    /// 直接伤害
+    Damage,
+    /// 治疗
+    Heal,
+    /// 状态效果（中毒、流血等），附带效果名称
+    Status(String),
+}
+
+/// 伤害事件
+///
+/// 所有对角色生命值的改动都应通过该事件完成，
+/// 这样战斗历史才能完整记录每一次受击
+#[derive(Event, Debug, Clone)]
+pub struct DamageEvent {
+    /// 受击目标
+    pub target: Entity,
+    /// 伤害来源实体（环境伤害等可以为空）
+    pub source: Option<Entity>,
+    /// 来源显示名称
+    pub source_name: String,
+    /// 数值（治疗为正向恢复量）
+    pub amount: f32,
+    /// 造成伤害的技能名称
+    pub skill: Option<String>,
+    /// 效果类型
+    pub kind: CombatEffectKind,
+}
+
+impl DamageEvent {
+    /// 创建一次普通伤害
+    pub fn damage(target: Entity, source: Option<Entity>, source_name: &str, amount: f32) -> Self {
+        Self {
+            target,
+            source,
+            source_name: source_name.to_string(),
+            amount,
+            skill: None,
+            kind: CombatEffectKind::Damage,
+        }
+    }
+
+    /// 指定技能名称
+    pub fn with_skill(mut self, skill: &str) -> Self {
+        self.skill = Some(skill.to_string());
+        self
+    }
+}
+
+/// 死亡事件
+#[derive(Event, Debug, Clone)]
+pub struct DeathEvent {
+    /// 死亡的实体
+    pub entity: Entity,
+    /// 最后一击的来源
+    pub killer: Option<Entity>,
+    /// 最后一击的来源名称
+    pub killer_name: String,
+}
+
+/// 生命值变化事件
+///
+/// 伤害与治疗结算后发出，飘字等界面据此显示变化量，不必每帧比较角色生命值
+#[derive(Event, Debug, Clone)]
+pub struct HealthChanged {
+    pub entity: Entity,
+    /// 本次变化量，受伤为负
+    pub delta: f32,
+}
+
+/// 战斗动作开始事件
+///
+/// 由输入缓冲在固定步长中发出，动画、判定框等系统据此开始各自的处理
+#[derive(Event, Debug, Clone)]
+pub struct CombatActionEvent {
+    /// 执行动作的实体
+    pub entity: Entity,
+    /// 动作类型
+    pub action: GameAction,
+    /// 动作对应的技能名
+    pub skill: Option<String>,
+}
+
+/// 招架成功事件
+#[derive(Event, Debug, Clone)]
+pub struct ParryEvent {
+    /// 招架方
+    pub defender: Entity,
+    /// 被招架的攻击方
+    pub attacker: Entity,
+}
@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+use super::CombatEffectKind;
+
+/// 单条战斗记录
+#[derive(Debug, Clone)]
+pub struct CombatRecord {
+    /// 记录时间（游戏运行秒数）
+    pub time: f32,
+    pub source_name: String,
+    pub amount: f32,
+    pub skill: Option<String>,
+    pub kind: CombatEffectKind,
+}
+
+/// 战斗事件历史
+///
+/// # 设计思路
+/// 1. 每个实体一个环形缓冲区，容量固定，旧记录自动淘汰
+/// 2. 只保留最近一段时间的数据，不做长期存档
+/// 3. 过期记录定期丢弃，记录清空的实体随之移除，避免无限增长
+#[derive(Resource, Debug)]
+pub struct CombatHistory {
+    /// 每个实体最多保留的记录条数
+    pub capacity: usize,
+    /// 记录保留时长（秒）
+    pub retention: f32,
+    records: HashMap<Entity, VecDeque<CombatRecord>>,
+}
+
+impl Default for CombatHistory {
+    fn default() -> Self {
+        Self {
+            capacity: 64,
+            retention: 30.0,
+            records: HashMap::new(),
+        }
+    }
+}
+
+impl CombatHistory {
+    /// 记录一次受击
+    pub fn push(&mut self, target: Entity, record: CombatRecord) {
+        let capacity = self.capacity;
+        let buffer = self
+            .records
+            .entry(target)
+            .or_insert_with(|| VecDeque::with_capacity(capacity));
+
+        if buffer.len() >= capacity {
+            buffer.pop_front();
+        }
+        buffer.push_back(record);
+    }
+
+    /// 获取目标在最近 `window` 秒内的记录，按时间先后排列
+    pub fn recent(&self, target: Entity, now: f32, window: f32) -> Vec<&CombatRecord> {
+        self.records
+            .get(&target)
+            .map(|buffer| {
+                buffer
+                    .iter()
+                    .filter(|record| now - record.time <= window)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 丢弃过期记录
+    pub fn prune(&mut self, now: f32) {
+        let retention = self.retention;
+        self.records.retain(|_, buffer| {
+            while buffer
+                .front()
+                .is_some_and(|record| now - record.time > retention)
+            {
+                buffer.pop_front();
+            }
+            !buffer.is_empty()
+        });
+    }
+}
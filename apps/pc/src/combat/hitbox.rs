@@ -0,0 +1,255 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::{
+    ActionState, ApplyStatus, DamageEvent, Invulnerable, Knockback, ParryEvent, StatusKind,
+};
+use crate::render::components::AnimationComponent;
+use crate::world::entity::{Character, CharacterState, Npc};
+use crate::world::physics::{ColliderRect, MovementBody};
+
+/// 没有移动体的角色按这个半径判定受击
+const DEFAULT_HURT_RADIUS: f32 = 12.0;
+
+/// 攻击动画中生成判定框的一帧
+#[derive(Debug, Clone)]
+pub struct HitboxFrame {
+    /// 动画帧序号
+    pub frame: usize,
+    /// 判定框中心沿朝向离角色的距离（像素）
+    pub reach: f32,
+    /// 判定框半宽与半高（像素）
+    pub half_size: Vec2,
+    pub damage: f32,
+    /// 击退初速度（像素/秒）
+    pub knockback: f32,
+    /// 命中时施加的状态效果
+    pub status: Option<StatusKind>,
+}
+
+/// 一个技能的攻击判定
+#[derive(Debug, Clone)]
+pub struct AttackProfile {
+    /// 角色没有动画组件时使用的每帧时长（秒）
+    pub frame_time: f32,
+    pub frames: Vec<HitboxFrame>,
+}
+
+/// 攻击判定配置
+///
+/// # 参数说明
+/// - profiles: 以技能名为键的攻击判定
+/// - hit_invulnerability: 受击后的无敌时长，防止同一招多个判定框连续命中
+/// - dodge_invulnerability: 闪避开始后的无敌时长
+/// - parry_window: 闪避开始后多久之内被击中算作招架
+/// - knockback_duration: 击退持续时长
+#[derive(Resource, Debug, Clone)]
+pub struct HitboxSettings {
+    pub profiles: HashMap<String, AttackProfile>,
+    pub hit_invulnerability: f32,
+    pub dodge_invulnerability: f32,
+    pub parry_window: f32,
+    pub knockback_duration: f32,
+}
+
+impl Default for HitboxSettings {
+    fn default() -> Self {
+        let mut profiles = HashMap::new();
+        // 普通攻击在第三帧挥出
+        profiles.insert(
+            "attack".to_string(),
+            AttackProfile {
+                frame_time: 0.1,
+                frames: vec![HitboxFrame {
+                    frame: 2,
+                    reach: 18.0,
+                    half_size: Vec2::new(14.0, 12.0),
+                    damage: 12.0,
+                    knockback: 140.0,
+                    status: None,
+                }],
+            },
+        );
+
+        Self {
+            profiles,
+            hit_invulnerability: 0.3,
+            dodge_invulnerability: 0.3,
+            parry_window: 0.12,
+            knockback_duration: 0.15,
+        }
+    }
+}
+
+/// 攻击判定框
+///
+/// 独立于攻击者的实体，只存活一帧动画的时长，同一判定框对每个目标只命中一次
+#[derive(Component, Debug, Clone)]
+pub struct AttackHitbox {
+    pub owner: Entity,
+    pub owner_name: String,
+    /// 攻击者是否是 NPC，NPC 之间不会互相误伤
+    pub owner_is_npc: bool,
+    pub skill: String,
+    pub half_size: Vec2,
+    pub damage: f32,
+    pub knockback: f32,
+    pub status: Option<StatusKind>,
+    /// 击退方向
+    pub direction: Vec2,
+    /// 剩余存活时长（秒）
+    pub remaining: f32,
+    /// 已命中的目标
+    pub hit: Vec<Entity>,
+}
+
+/// 按攻击动画的帧生成判定框
+///
+/// 动作进度取自动作状态，帧时长优先取角色的动画组件，保证判定与画面同步
+#[allow(clippy::type_complexity)]
+pub fn spawn_attack_hitboxes(
+    mut commands: Commands,
+    settings: Res<HitboxSettings>,
+    mut attackers: Query<(
+        Entity,
+        &mut ActionState,
+        &Character,
+        &Transform,
+        Option<&AnimationComponent>,
+        Has<Npc>,
+    )>,
+) {
+    for (entity, mut action_state, character, transform, animation, is_npc) in attackers.iter_mut()
+    {
+        let Some(active) = action_state.current.as_mut() else {
+            continue;
+        };
+        let Some(profile) = settings.profiles.get(&active.skill) else {
+            continue;
+        };
+
+        let frame_time = animation
+            .map(|animation| animation.frame_time.as_secs_f32())
+            .filter(|frame_time| *frame_time > 0.0)
+            .unwrap_or(profile.frame_time);
+        let current_frame = (active.elapsed / frame_time) as usize;
+        if current_frame < active.hitbox_frames {
+            continue;
+        }
+
+        let direction = character.direction.normalize_or(Vec2::X);
+        for frame in profile
+            .frames
+            .iter()
+            .filter(|frame| (active.hitbox_frames..=current_frame).contains(&frame.frame))
+        {
+            let center = transform.translation.truncate() + direction * frame.reach;
+            commands.spawn((
+                AttackHitbox {
+                    owner: entity,
+                    owner_name: character.name.clone(),
+                    owner_is_npc: is_npc,
+                    skill: active.skill.clone(),
+                    half_size: frame.half_size,
+                    damage: frame.damage,
+                    knockback: frame.knockback,
+                    status: frame.status,
+                    direction,
+                    remaining: frame_time,
+                    hit: Vec::new(),
+                },
+                Transform::from_translation(center.extend(0.0)),
+            ));
+        }
+        active.hitbox_frames = current_frame + 1;
+    }
+}
+
+/// 结算判定框的命中
+///
+/// 命中时发出伤害事件，目标获得短暂无敌并被击退；
+/// 目标正处于闪避的无敌中则不受伤，闪避刚开始时被击中算作招架
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+pub fn resolve_attack_hitboxes(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<HitboxSettings>,
+    mut hitboxes: Query<(Entity, &mut AttackHitbox, &Transform)>,
+    targets: Query<
+        (
+            Entity,
+            &Character,
+            &Transform,
+            Option<&MovementBody>,
+            Option<&Invulnerable>,
+            Has<Npc>,
+        ),
+        Without<AttackHitbox>,
+    >,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut parry_events: EventWriter<ParryEvent>,
+    mut status_events: EventWriter<ApplyStatus>,
+) {
+    for (hitbox_entity, mut hitbox, transform) in hitboxes.iter_mut() {
+        let rect = ColliderRect {
+            center: transform.translation.truncate(),
+            half_size: hitbox.half_size,
+        };
+
+        for (target, character, target_transform, body, invulnerable, is_npc) in targets.iter() {
+            if target == hitbox.owner
+                || character.state == CharacterState::Dead
+                || (hitbox.owner_is_npc && is_npc)
+                || hitbox.hit.contains(&target)
+            {
+                continue;
+            }
+            let radius = body.map_or(DEFAULT_HURT_RADIUS, |body| body.radius);
+            if !rect.overlaps_circle(target_transform.translation.truncate(), radius) {
+                continue;
+            }
+            hitbox.hit.push(target);
+
+            if let Some(invulnerable) = invulnerable {
+                if invulnerable.can_parry() {
+                    parry_events.send(ParryEvent {
+                        defender: target,
+                        attacker: hitbox.owner,
+                    });
+                }
+                continue;
+            }
+
+            damage_events.send(
+                DamageEvent::damage(
+                    target,
+                    Some(hitbox.owner),
+                    &hitbox.owner_name,
+                    hitbox.damage,
+                )
+                .with_skill(&hitbox.skill),
+            );
+            if let Some(kind) = hitbox.status {
+                status_events.send(ApplyStatus::new(
+                    target,
+                    Some(hitbox.owner),
+                    &hitbox.owner_name,
+                    kind,
+                ));
+            }
+            commands.entity(target).insert((
+                Invulnerable::new(settings.hit_invulnerability),
+                Knockback::new(
+                    hitbox.direction * hitbox.knockback,
+                    settings.knockback_duration,
+                ),
+            ));
+        }
+
+        hitbox.remaining -= time.delta_secs();
+        if hitbox.remaining <= 0.0 {
+            commands.entity(hitbox_entity).despawn();
+        }
+    }
+}
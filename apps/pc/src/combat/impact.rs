@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+
+use super::{CombatEffectKind, DamageEvent, DeathEvent, ParryEvent};
+use crate::render::camera::{CameraShake, FinisherCamera};
+use crate::time::TimeDilation;
+use crate::world::entity::Player;
+
+/// 打击感参数
+///
+/// # 参数说明
+/// - heavy_hit_threshold: 单次伤害达到该值视为重击，触发顿帧
+/// - hitstop_duration: 顿帧时长（真实时间秒数）
+/// - heavy_hit_shake: 重击时的震屏强度（创伤值）
+/// - parry_*: 招架成功时的慢动作
+/// - finisher_*: 玩家击杀时的慢动作与镜头
+#[derive(Resource, Debug, Clone)]
+pub struct ImpactSettings {
+    pub heavy_hit_threshold: f32,
+    pub hitstop_duration: f32,
+    pub heavy_hit_shake: f32,
+    pub parry_slow_scale: f32,
+    pub parry_slow_duration: f32,
+    pub finisher_slow_scale: f32,
+    pub finisher_slow_duration: f32,
+}
+
+impl Default for ImpactSettings {
+    fn default() -> Self {
+        Self {
+            heavy_hit_threshold: 30.0,
+            hitstop_duration: 0.08,
+            heavy_hit_shake: 0.4,
+            parry_slow_scale: 0.4,
+            parry_slow_duration: 0.35,
+            finisher_slow_scale: 0.25,
+            finisher_slow_duration: 0.8,
+        }
+    }
+}
+
+/// 根据战斗事件触发顿帧、震屏、慢动作与终结技镜头
+///
+/// 只处理与玩家有关的事件，NPC 之间的战斗不打断玩家的节奏
+#[allow(clippy::too_many_arguments)]
+pub fn trigger_impact_effects(
+    settings: Res<ImpactSettings>,
+    mut dilation: ResMut<TimeDilation>,
+    mut finisher: ResMut<FinisherCamera>,
+    mut shakes: EventWriter<CameraShake>,
+    mut damage_events: EventReader<DamageEvent>,
+    mut parry_events: EventReader<ParryEvent>,
+    mut death_events: EventReader<DeathEvent>,
+    players: Query<(), With<Player>>,
+) {
+    let involves_player = |entity: Option<Entity>| entity.is_some_and(|e| players.contains(e));
+
+    for event in damage_events.read() {
+        if event.kind == CombatEffectKind::Damage
+            && event.amount >= settings.heavy_hit_threshold
+            && (involves_player(event.source) || involves_player(Some(event.target)))
+        {
+            dilation.hitstop(settings.hitstop_duration);
+            shakes.send(CameraShake::new(settings.heavy_hit_shake));
+        }
+    }
+
+    for event in parry_events.read() {
+        if involves_player(Some(event.defender)) || involves_player(Some(event.attacker)) {
+            dilation.slow_motion(settings.parry_slow_scale, settings.parry_slow_duration);
+        }
+    }
+
+    for event in death_events.read() {
+        if involves_player(event.killer) {
+            dilation.slow_motion(
+                settings.finisher_slow_scale,
+                settings.finisher_slow_duration,
+            );
+            finisher.trigger(event.entity, settings.finisher_slow_duration);
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+use super::{CombatActionEvent, SkillBook, SkillCastFailed, SkillDatabase};
+use crate::events::input::GameAction;
+use crate::render::animation::AnimationFinished;
+use crate::render::components::AnimationComponent;
+use crate::resources::InputState;
+use crate::time::{LocalTimeScale, TimeDilation};
+use crate::world::entity::{Character, CharacterState};
+
+/// 缓冲中的一次按键
+#[derive(Debug, Clone, Copy)]
+pub struct BufferedAction {
+    pub action: GameAction,
+    /// 按下时刻（虚拟时间秒数）
+    pub pressed_at: f32,
+}
+
+/// 输入缓冲组件
+///
+/// 动作执行期间提前按下的攻击/闪避不会丢失，
+/// 而是排队等待当前动作结束或进入可取消窗口后执行
+#[derive(Component, Debug, Default)]
+pub struct InputBuffer {
+    pub queue: VecDeque<BufferedAction>,
+}
+
+/// 正在执行的战斗动作
+#[derive(Debug, Clone)]
+pub struct ActiveAction {
+    /// 动作对应的技能名，用于查找取消规则
+    pub skill: String,
+    /// 已执行时长
+    pub elapsed: f32,
+    /// 动作总时长
+    pub duration: f32,
+    /// 已经处理过判定的动画帧数
+    pub hitbox_frames: usize,
+}
+
+/// 角色当前的战斗动作状态
+#[derive(Component, Debug, Default)]
+pub struct ActionState {
+    pub current: Option<ActiveAction>,
+}
+
+impl ActionState {
+    /// 当前是否没有动作在执行
+    pub fn is_idle(&self) -> bool {
+        self.current.is_none()
+    }
+}
+
+/// 单个技能的取消规则
+#[derive(Debug, Clone)]
+pub struct CancelRule {
+    /// 动作开始多久之后允许被取消（秒）
+    pub cancel_after: f32,
+    /// 可以取消该动作的输入
+    pub cancelable_by: Vec<GameAction>,
+}
+
+/// 输入缓冲配置
+///
+/// # 参数说明
+/// - default_window: 未单独配置的动作使用的缓冲时长
+/// - windows: 各动作的缓冲时长，闪避通常比攻击更宽容
+/// - durations: 各动作的执行时长，动作结束后才会消费下一个缓冲
+/// - cancel_rules: 以技能名为键的取消规则
+#[derive(Resource, Debug, Clone)]
+pub struct InputBufferSettings {
+    pub default_window: f32,
+    pub windows: HashMap<GameAction, f32>,
+    pub durations: HashMap<GameAction, f32>,
+    pub cancel_rules: HashMap<String, CancelRule>,
+    /// 缓冲队列最大长度
+    pub capacity: usize,
+}
+
+impl Default for InputBufferSettings {
+    fn default() -> Self {
+        let mut windows = HashMap::new();
+        windows.insert(GameAction::Attack, 0.2);
+        windows.insert(GameAction::Dodge, 0.25);
+
+        let mut durations = HashMap::new();
+        durations.insert(GameAction::Attack, 0.4);
+        durations.insert(GameAction::Dodge, 0.35);
+
+        let mut cancel_rules = HashMap::new();
+        // 普通攻击后摇可以被闪避取消
+        cancel_rules.insert(
+            "attack".to_string(),
+            CancelRule {
+                cancel_after: 0.15,
+                cancelable_by: vec![GameAction::Dodge],
+            },
+        );
+        // 闪避不可取消
+        cancel_rules.insert(
+            "dodge".to_string(),
+            CancelRule {
+                cancel_after: f32::MAX,
+                cancelable_by: Vec::new(),
+            },
+        );
+
+        Self {
+            default_window: 0.15,
+            windows,
+            durations,
+            cancel_rules,
+            capacity: 4,
+        }
+    }
+}
+
+impl InputBufferSettings {
+    /// 动作的缓冲时长
+    pub fn window_for(&self, action: GameAction) -> f32 {
+        self.windows
+            .get(&action)
+            .copied()
+            .unwrap_or(self.default_window)
+    }
+
+    /// 动作的执行时长
+    pub fn duration_for(&self, action: GameAction) -> f32 {
+        self.durations.get(&action).copied().unwrap_or(0.3)
+    }
+
+    /// 判断正在执行的动作能否被某个输入取消
+    pub fn can_cancel(&self, active: &ActiveAction, by: GameAction) -> bool {
+        self.cancel_rules.get(&active.skill).is_some_and(|rule| {
+            active.elapsed >= rule.cancel_after && rule.cancelable_by.contains(&by)
+        })
+    }
+}
+
+/// 会进入缓冲的动作
+const BUFFERED_ACTIONS: [GameAction; 6] = [
+    GameAction::Attack,
+    GameAction::Dodge,
+    GameAction::Skill1,
+    GameAction::Skill2,
+    GameAction::Skill3,
+    GameAction::Skill4,
+];
+
+/// 动作对应的默认技能名
+fn skill_for_action(action: GameAction) -> &'static str {
+    match action {
+        GameAction::Dodge => "dodge",
+        _ => "attack",
+    }
+}
+
+/// 记录本帧按下的战斗输入
+///
+/// 在 Update 中运行，保证每一次按键都能被捕获，
+/// 即使两次 FixedUpdate 之间有多帧渲染
+pub fn buffer_combat_inputs(
+    time: Res<Time<Virtual>>,
+    input_state: Res<InputState>,
+    settings: Res<InputBufferSettings>,
+    mut buffers: Query<&mut InputBuffer>,
+) {
+    let now = time.elapsed_secs();
+
+    for mut buffer in buffers.iter_mut() {
+        for action in BUFFERED_ACTIONS {
+            if !input_state.is_action_just_pressed(action) {
+                continue;
+            }
+
+            if buffer.queue.len() >= settings.capacity {
+                buffer.queue.pop_front();
+            }
+            buffer.queue.push_back(BufferedAction {
+                action,
+                pressed_at: now,
+            });
+        }
+    }
+}
+
+/// 动作的动画播完时提前结束动作
+///
+/// 配置的动作时长只是没有图集动画时的兜底，有动画的角色收招以画面为准。
+/// 只认角色当前正在播放的动画，已被新动作替换的旧动画不会误结束新动作
+pub fn finish_actions_with_animation(
+    mut finished: EventReader<AnimationFinished>,
+    mut query: Query<(&mut ActionState, &mut Character, &AnimationComponent)>,
+) {
+    for event in finished.read() {
+        let Ok((mut action_state, mut character, animation)) = query.get_mut(event.entity) else {
+            continue;
+        };
+        if action_state.is_idle() || animation.current_animation != event.animation {
+            continue;
+        }
+        action_state.current = None;
+        if matches!(
+            character.state,
+            CharacterState::Attacking | CharacterState::Defending
+        ) {
+            character.state = CharacterState::Idle;
+        }
+    }
+}
+
+/// 在固定步长中推进动作并消费缓冲
+///
+/// 技能栏槽位的输入按技能书施展，技能名与时长取自技能数据
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+pub fn execute_buffered_actions(
+    fixed_time: Res<Time>,
+    virtual_time: Res<Time<Virtual>>,
+    settings: Res<InputBufferSettings>,
+    dilation: Res<TimeDilation>,
+    skills: Res<SkillDatabase>,
+    mut action_events: EventWriter<CombatActionEvent>,
+    mut cast_failures: EventWriter<SkillCastFailed>,
+    mut query: Query<(
+        Entity,
+        &mut InputBuffer,
+        &mut ActionState,
+        &mut Character,
+        Option<&mut SkillBook>,
+        Option<&LocalTimeScale>,
+    )>,
+) {
+    let delta = fixed_time.delta_secs();
+    let now = virtual_time.elapsed_secs();
+
+    for (entity, mut buffer, mut action_state, mut character, mut book, local_scale) in
+        query.iter_mut()
+    {
+        // 1. 推进当前动作，实体自身的时间倍率只影响动作进度
+        let delta = local_scale.map_or(delta, |local| local.apply(delta, &dilation));
+        if let Some(active) = action_state.current.as_mut() {
+            active.elapsed += delta;
+            if active.elapsed >= active.duration {
+                action_state.current = None;
+                if matches!(
+                    character.state,
+                    CharacterState::Attacking | CharacterState::Defending
+                ) {
+                    character.state = CharacterState::Idle;
+                }
+            }
+        }
+
+        // 2. 丢弃超出缓冲窗口的输入
+        buffer
+            .queue
+            .retain(|buffered| now - buffered.pressed_at <= settings.window_for(buffered.action));
+
+        if character.state == CharacterState::Dead || !character.can_move {
+            buffer.queue.clear();
+            continue;
+        }
+
+        // 3. 尝试执行最早的缓冲输入
+        let Some(next) = buffer.queue.front().copied() else {
+            continue;
+        };
+
+        let can_start = match &action_state.current {
+            None => true,
+            Some(active) => settings.can_cancel(active, next.action),
+        };
+        if !can_start {
+            continue;
+        }
+
+        buffer.queue.pop_front();
+
+        let (skill, duration) = if next.action.is_skill_slot() {
+            let Some(book) = book.as_deref_mut() else {
+                continue;
+            };
+            match book.cast(next.action, &skills, &mut character) {
+                Ok(def) => (def.id.clone(), def.duration),
+                Err(error) => {
+                    cast_failures.send(SkillCastFailed {
+                        entity,
+                        slot: next.action,
+                        error,
+                    });
+                    continue;
+                }
+            }
+        } else {
+            (
+                skill_for_action(next.action).to_string(),
+                settings.duration_for(next.action),
+            )
+        };
+        action_state.current = Some(ActiveAction {
+            skill: skill.clone(),
+            elapsed: 0.0,
+            duration,
+            hitbox_frames: 0,
+        });
+
+        character.state = match next.action {
+            GameAction::Attack => CharacterState::Attacking,
+            GameAction::Dodge => CharacterState::Defending,
+            action if action.is_skill_slot() => CharacterState::Attacking,
+            _ => character.state,
+        };
+
+        action_events.send(CombatActionEvent {
+            entity,
+            action: next.action,
+            skill: Some(skill),
+        });
+    }
+}
@@ -0,0 +1,44 @@
+/// 战斗模块
+///
+/// 负责伤害结算、战斗事件记录以及基于战斗记录的反馈功能（如死亡回顾）
+///
+/// # 模块划分
+/// 1. events：战斗相关事件定义，是各系统之间的唯一通信方式
+/// 2. history：战斗事件历史环形缓冲区，供回顾、统计使用
+/// 3. recap：玩家死亡回顾界面
+/// 4. input_buffer：战斗输入缓冲与动作排队
+/// 5. impact：顿帧、慢动作等打击感效果
+/// 6. taunt：敌人与玩家交手时的头顶嘲讽
+/// 7. hitbox：按攻击动画帧生成的判定框与命中结算
+/// 8. reaction：受击无敌、闪避无敌与击退
+/// 9. npc_attack：NPC 攻击状态下的出招节奏
+/// 10. skill：数据驱动的武学技能，包括轻功、剑法与掌法
+/// 11. status：中毒、流血、迟缓等状态效果的叠加、计时与周期结算
+/// 12. skill_panel：花费技能点学习武学的面板
+mod events;
+mod history;
+mod hitbox;
+mod impact;
+mod input_buffer;
+mod npc_attack;
+mod reaction;
+mod recap;
+mod skill;
+mod skill_panel;
+mod status;
+mod systems;
+mod taunt;
+
+pub use events::*;
+pub use history::*;
+pub use hitbox::*;
+pub use impact::*;
+pub use input_buffer::*;
+pub use npc_attack::*;
+pub use reaction::*;
+pub use recap::*;
+pub use skill::*;
+pub use skill_panel::*;
+pub use status::*;
+pub use systems::CombatPlugin;
+pub use taunt::*;
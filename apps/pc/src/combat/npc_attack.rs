@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+
+use super::{ActionState, ActiveAction, CombatActionEvent, InputBufferSettings};
+use crate::events::input::GameAction;
+use crate::world::entity::{AiState, Character, CharacterState, Npc};
+
+/// NPC 的出招节奏
+#[derive(Component, Debug, Clone)]
+pub struct NpcCombat {
+    /// 两次出招的间隔（秒）
+    pub cooldown: f32,
+    /// 距离下次可以出招的剩余时间（秒）
+    pub remaining: f32,
+}
+
+impl Default for NpcCombat {
+    fn default() -> Self {
+        Self {
+            cooldown: 1.2,
+            // 刚进入攻击状态时稍作停顿，给玩家反应时间
+            remaining: 0.4,
+        }
+    }
+}
+
+/// 驱动处于攻击状态的 NPC 出招
+///
+/// NPC 没有输入缓冲，这里替它推进动作并按冷却发起普通攻击，
+/// 之后与玩家一样由判定框系统按动画帧结算命中
+pub fn drive_npc_attacks(
+    time: Res<Time>,
+    settings: Res<InputBufferSettings>,
+    mut action_events: EventWriter<CombatActionEvent>,
+    mut npcs: Query<(
+        Entity,
+        &Npc,
+        &mut Character,
+        &mut ActionState,
+        &mut NpcCombat,
+    )>,
+) {
+    let delta = time.delta_secs();
+
+    for (entity, npc, mut character, mut action_state, mut combat) in npcs.iter_mut() {
+        combat.remaining = (combat.remaining - delta).max(0.0);
+
+        if let Some(active) = action_state.current.as_mut() {
+            active.elapsed += delta;
+            if active.elapsed >= active.duration {
+                action_state.current = None;
+                if character.state == CharacterState::Attacking {
+                    character.state = CharacterState::Idle;
+                }
+            }
+            continue;
+        }
+
+        if npc.ai_state != AiState::Attack
+            || combat.remaining > 0.0
+            || character.state == CharacterState::Dead
+            || !character.can_move
+        {
+            continue;
+        }
+
+        let skill = "attack".to_string();
+        action_state.current = Some(ActiveAction {
+            skill: skill.clone(),
+            elapsed: 0.0,
+            duration: settings.duration_for(GameAction::Attack),
+            hitbox_frames: 0,
+        });
+        combat.remaining = combat.cooldown;
+        character.state = CharacterState::Attacking;
+
+        action_events.send(CombatActionEvent {
+            entity,
+            action: GameAction::Attack,
+            skill: Some(skill),
+        });
+    }
+}
@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+
+use super::{CombatActionEvent, HitboxSettings};
+use crate::events::input::GameAction;
+use crate::world::physics::{move_by, MovementBody};
+
+/// 无敌时间
+#[derive(Component, Debug, Clone)]
+pub struct Invulnerable {
+    /// 剩余时长（秒）
+    pub remaining: f32,
+    /// 已持续时长（秒）
+    pub elapsed: f32,
+    /// 开始后多久之内被击中算作招架，受击无敌为零
+    pub parry_window: f32,
+}
+
+impl Invulnerable {
+    pub fn new(duration: f32) -> Self {
+        Self {
+            remaining: duration,
+            elapsed: 0.0,
+            parry_window: 0.0,
+        }
+    }
+
+    pub fn with_parry_window(mut self, parry_window: f32) -> Self {
+        self.parry_window = parry_window;
+        self
+    }
+
+    /// 此刻被击中是否算作招架
+    pub fn can_parry(&self) -> bool {
+        self.elapsed < self.parry_window
+    }
+}
+
+/// 击退
+///
+/// 速度在持续时间内线性衰减到零，位移交给碰撞系统，击退不会把角色打进墙里
+#[derive(Component, Debug, Clone)]
+pub struct Knockback {
+    pub velocity: Vec2,
+    pub remaining: f32,
+    pub duration: f32,
+}
+
+impl Knockback {
+    pub fn new(velocity: Vec2, duration: f32) -> Self {
+        Self {
+            velocity,
+            remaining: duration,
+            duration,
+        }
+    }
+}
+
+/// 闪避开始时获得无敌
+pub fn grant_dodge_invulnerability(
+    mut commands: Commands,
+    settings: Res<HitboxSettings>,
+    mut actions: EventReader<CombatActionEvent>,
+) {
+    for action in actions.read() {
+        if action.action != GameAction::Dodge {
+            continue;
+        }
+        if let Some(mut entity) = commands.get_entity(action.entity) {
+            entity.insert(
+                Invulnerable::new(settings.dodge_invulnerability)
+                    .with_parry_window(settings.parry_window),
+            );
+        }
+    }
+}
+
+/// 推进无敌时间，结束后移除
+pub fn tick_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Invulnerable)>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut invulnerable) in query.iter_mut() {
+        invulnerable.elapsed += delta;
+        invulnerable.remaining -= delta;
+        if invulnerable.remaining <= 0.0 {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
+
+/// 推进击退位移，结束后移除
+pub fn apply_knockback(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &mut Knockback,
+        &mut Transform,
+        Option<&mut MovementBody>,
+    )>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut knockback, mut transform, mut body) in query.iter_mut() {
+        let strength = (knockback.remaining / knockback.duration.max(f32::EPSILON)).clamp(0.0, 1.0);
+        let movement = knockback.velocity * strength * delta;
+        move_by(&mut transform, body.as_deref_mut(), movement);
+
+        knockback.remaining -= delta;
+        if knockback.remaining <= 0.0 {
+            commands.entity(entity).remove::<Knockback>();
+        }
+    }
+}
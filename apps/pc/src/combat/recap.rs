@@ -0,0 +1,272 @@
+use bevy::prelude::*;
+
+use super::{CombatEffectKind, CombatHistory, DeathEvent};
+use crate::events::input::{GameAction, KeyBindings};
+use crate::interaction::PanelInteraction;
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::InputState;
+use crate::world::entity::{Character, Player};
+
+/// 死亡回顾配置
+#[derive(Resource, Debug, Clone)]
+pub struct RecapSettings {
+    /// 回顾的时间窗口（秒）
+    pub window: f32,
+    /// 界面最多显示的条目数
+    pub max_entries: usize,
+    /// 单次伤害超过最大生命值的该比例时，记录为疑似平衡异常
+    pub outlier_ratio: f32,
+}
+
+impl Default for RecapSettings {
+    fn default() -> Self {
+        Self {
+            window: 10.0,
+            max_entries: 10,
+            outlier_ratio: 0.5,
+        }
+    }
+}
+
+/// 回顾条目：按来源和技能汇总后的伤害
+#[derive(Debug, Clone)]
+pub struct RecapEntry {
+    pub source_name: String,
+    pub skill: Option<String>,
+    pub kind: CombatEffectKind,
+    /// 合计数值
+    pub total: f32,
+    /// 命中次数
+    pub hits: u32,
+    /// 单次最大值
+    pub largest: f32,
+}
+
+/// 最近一次玩家死亡的回顾数据
+#[derive(Resource, Debug, Default)]
+pub struct DeathRecap {
+    /// 条目，按合计伤害从高到低排列
+    pub entries: Vec<RecapEntry>,
+    /// 时间窗口内承受的总伤害
+    pub total_damage: f32,
+    /// 最后一击来源
+    pub killer_name: String,
+    /// 界面是否正在显示
+    pub visible: bool,
+}
+
+/// 死亡回顾界面根节点标记
+#[derive(Component)]
+pub struct DeathRecapUi;
+
+/// 玩家死亡时根据战斗历史生成回顾
+#[allow(clippy::too_many_arguments)]
+pub fn build_death_recap(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<RecapSettings>,
+    history: Res<CombatHistory>,
+    mut recap: ResMut<DeathRecap>,
+    mut death_events: EventReader<DeathEvent>,
+    players: Query<&Character, With<Player>>,
+    existing_ui: Query<Entity, With<DeathRecapUi>>,
+    key_bindings: Res<KeyBindings>,
+    input_state: Res<InputState>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for event in death_events.read() {
+        let Ok(character) = players.get(event.entity) else {
+            continue;
+        };
+
+        let now = time.elapsed_secs();
+        let records = history.recent(event.entity, now, settings.window);
+
+        // 按 (来源, 技能, 类型) 汇总
+        let mut entries: Vec<RecapEntry> = Vec::new();
+        for record in &records {
+            match entries.iter_mut().find(|entry| {
+                entry.source_name == record.source_name
+                    && entry.skill == record.skill
+                    && entry.kind == record.kind
+            }) {
+                Some(entry) => {
+                    entry.total += record.amount;
+                    entry.hits += 1;
+                    entry.largest = entry.largest.max(record.amount);
+                }
+                None => entries.push(RecapEntry {
+                    source_name: record.source_name.clone(),
+                    skill: record.skill.clone(),
+                    kind: record.kind.clone(),
+                    total: record.amount,
+                    hits: 1,
+                    largest: record.amount,
+                }),
+            }
+        }
+        entries.sort_by(|a, b| b.total.total_cmp(&a.total));
+
+        recap.total_damage = entries
+            .iter()
+            .filter(|entry| entry.kind != CombatEffectKind::Heal)
+            .map(|entry| entry.total)
+            .sum();
+        recap.killer_name = event.killer_name.clone();
+        recap.entries = entries;
+        recap.visible = true;
+
+        if let Some(logger) = logger.as_mut() {
+            log_recap(logger, &recap, character.max_health, settings.outlier_ratio);
+        }
+
+        for entity in existing_ui.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        let prompt = key_bindings.prompt(GameAction::Interact, input_state.device);
+        spawn_recap_ui(&mut commands, &recap, settings.max_entries, &prompt);
+    }
+}
+
+/// 按交互键关闭死亡回顾
+pub fn dismiss_death_recap(
+    mut commands: Commands,
+    mut panel_events: EventReader<PanelInteraction>,
+    mut recap: ResMut<DeathRecap>,
+    ui: Query<Entity, With<DeathRecapUi>>,
+) {
+    let pressed = panel_events.read().count() > 0;
+    if !recap.visible || !pressed {
+        return;
+    }
+
+    recap.visible = false;
+    for entity in ui.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// 写入日志，便于开发者发现伤害数值异常
+fn log_recap(logger: &mut GameLogger, recap: &DeathRecap, max_health: f32, outlier_ratio: f32) {
+    logger.log(
+        LogLevel::Info,
+        &format!(
+            "玩家死亡，最后一击来自 {}，{} 条记录合计伤害 {:.1}",
+            recap.killer_name,
+            recap.entries.len(),
+            recap.total_damage
+        ),
+    );
+
+    for entry in &recap.entries {
+        logger.log(
+            LogLevel::Debug,
+            &format!(
+                "  {} [{}] x{} 合计 {:.1} 最大 {:.1}",
+                entry.source_name,
+                entry.skill.as_deref().unwrap_or("普通攻击"),
+                entry.hits,
+                entry.total,
+                entry.largest
+            ),
+        );
+
+        if entry.kind != CombatEffectKind::Heal && entry.largest > max_health * outlier_ratio {
+            logger.log(
+                LogLevel::Info,
+                &format!(
+                    "疑似平衡异常：{} 的 {} 单次伤害 {:.1} 超过最大生命值的 {:.0}%",
+                    entry.source_name,
+                    entry.skill.as_deref().unwrap_or("普通攻击"),
+                    entry.largest,
+                    outlier_ratio * 100.0
+                ),
+            );
+        }
+    }
+}
+
+/// 生成死亡回顾界面
+fn spawn_recap_ui(commands: &mut Commands, recap: &DeathRecap, max_entries: usize, prompt: &str) {
+    commands
+        .spawn((
+            DeathRecapUi,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(16.0)),
+                    row_gap: Val::Px(6.0),
+                    min_width: Val::Px(360.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.1, 0.08, 0.06, 0.9)),
+            ))
+            .with_children(|panel| {
+                panel.spawn((
+                    Text::new("胜败乃兵家常事"),
+                    TextFont {
+                        font_size: 28.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.85, 0.2, 0.2)),
+                ));
+                panel.spawn((
+                    Text::new(format!(
+                        "致命一击：{}    合计伤害：{:.0}",
+                        recap.killer_name, recap.total_damage
+                    )),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                ));
+
+                for entry in recap.entries.iter().take(max_entries) {
+                    let (sign, color) = match entry.kind {
+                        CombatEffectKind::Heal => ("+", Color::srgb(0.3, 0.8, 0.3)),
+                        _ => ("-", Color::srgb(0.9, 0.9, 0.9)),
+                    };
+                    let effect = match &entry.kind {
+                        CombatEffectKind::Status(name) => format!("（{}）", name),
+                        _ => String::new(),
+                    };
+                    panel.spawn((
+                        Text::new(format!(
+                            "{}  {}{}  x{}  {}{:.0}",
+                            entry.source_name,
+                            entry.skill.as_deref().unwrap_or("普通攻击"),
+                            effect,
+                            entry.hits,
+                            sign,
+                            entry.total
+                        )),
+                        TextFont {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                        TextColor(color),
+                    ));
+                }
+
+                panel.spawn((
+                    Text::new(format!("按 {} 继续", prompt)),
+                    TextFont {
+                        font_size: 12.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.6, 0.6, 0.6)),
+                ));
+            });
+        });
+}
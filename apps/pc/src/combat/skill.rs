@@ -0,0 +1,591 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+
+use super::{
+    AttackProfile, CancelRule, CombatActionEvent, HitboxFrame, HitboxSettings, InputBufferSettings,
+    Invulnerable, StatusKind,
+};
+use crate::config::resolve_data_path;
+use crate::events::input::GameAction;
+use crate::logging::{GameLogger, LogLevel};
+use crate::render::components::AnimationComponent;
+use crate::render::lighting::{LightSource, TimedLight};
+use crate::world::entity::{Character, CharacterState, Player};
+use crate::world::physics::{move_by, MovementBody};
+
+/// 技能数据文件路径
+pub const SKILL_DATA_PATH: &str = "src/config/skills.json";
+
+/// 技能类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SkillKind {
+    /// 轻功身法，沿朝向位移
+    Dash,
+    /// 剑法、掌法等招式，按动画帧生成判定框
+    Strike,
+}
+
+/// 技能的一个判定框
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillHitboxDef {
+    pub frame: usize,
+    pub reach: f32,
+    pub half_size: [f32; 2],
+    pub damage: f32,
+    #[serde(default)]
+    pub knockback: f32,
+    /// 命中时施加的状态效果
+    #[serde(default)]
+    pub status: Option<StatusKind>,
+}
+
+/// 技能施展时发出的光，持续整个动作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillLightDef {
+    pub color: [f32; 3],
+    /// 照亮的半径（瓦片）
+    pub radius: f32,
+    #[serde(default = "default_light_intensity")]
+    pub intensity: f32,
+}
+
+fn default_light_intensity() -> f32 {
+    0.8
+}
+
+fn default_frame_time() -> f32 {
+    0.1
+}
+
+fn default_required_level() -> u32 {
+    1
+}
+
+/// 技能定义
+///
+/// # 参数说明
+/// - school: 所属武学门类，例如轻功、剑法、掌法
+/// - slot: 学会后默认放入的技能栏槽位
+/// - qi_cost / stamina_cost: 施展时消耗的内力与体力
+/// - cooldown: 冷却时长（秒）
+/// - duration: 动作时长（秒），期间不能施展其他动作
+/// - cancel_after: 动作开始多久后可以被闪避取消，缺省时不可取消
+/// - animation / frame_time: 施展时播放的动画及每帧时长
+/// - dash_distance: 轻功的位移距离（像素）
+/// - invulnerable: 施展期间是否无敌
+/// - light: 施展时发出的光，夜里出招能照亮周围
+/// - unlock_cost / required_level / requires: 学习所需的技能点、等级与前置技能
+/// - starting: 新角色自带的技能
+/// - carry_bonus: 学会后增加的负重上限，外功练得越扎实背得越多
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillDef {
+    pub id: String,
+    pub name: String,
+    pub school: String,
+    pub kind: SkillKind,
+    pub slot: GameAction,
+    #[serde(default)]
+    pub qi_cost: f32,
+    #[serde(default)]
+    pub stamina_cost: f32,
+    pub cooldown: f32,
+    pub duration: f32,
+    #[serde(default)]
+    pub cancel_after: Option<f32>,
+    pub animation: String,
+    #[serde(default = "default_frame_time")]
+    pub frame_time: f32,
+    #[serde(default)]
+    pub hitboxes: Vec<SkillHitboxDef>,
+    #[serde(default)]
+    pub dash_distance: f32,
+    #[serde(default)]
+    pub invulnerable: bool,
+    #[serde(default)]
+    pub light: Option<SkillLightDef>,
+    #[serde(default)]
+    pub unlock_cost: u32,
+    #[serde(default = "default_required_level")]
+    pub required_level: u32,
+    #[serde(default)]
+    pub requires: Vec<String>,
+    #[serde(default)]
+    pub starting: bool,
+    #[serde(default)]
+    pub carry_bonus: f32,
+}
+
+impl SkillDef {
+    /// 技能的攻击判定，没有判定框的技能返回 None
+    pub fn attack_profile(&self) -> Option<AttackProfile> {
+        if self.hitboxes.is_empty() {
+            return None;
+        }
+        Some(AttackProfile {
+            frame_time: self.frame_time,
+            frames: self
+                .hitboxes
+                .iter()
+                .map(|hitbox| HitboxFrame {
+                    frame: hitbox.frame,
+                    reach: hitbox.reach,
+                    half_size: Vec2::from_array(hitbox.half_size),
+                    damage: hitbox.damage,
+                    knockback: hitbox.knockback,
+                    status: hitbox.status,
+                })
+                .collect(),
+        })
+    }
+}
+
+/// 技能数据文件格式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SkillDataFile {
+    skills: Vec<SkillDef>,
+}
+
+/// 技能数据库
+///
+/// 启动时从数据文件加载，技能的判定框与取消规则同时登记到战斗配置中，
+/// 之后与普通攻击走同一套判定与取消流程
+#[derive(Resource, Debug, Clone, Default)]
+pub struct SkillDatabase {
+    skills: HashMap<String, SkillDef>,
+}
+
+impl SkillDatabase {
+    /// 从数据文件加载技能数据库
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let data: SkillDataFile = serde_json::from_str(&content)?;
+
+        let mut database = Self::default();
+        for skill in data.skills {
+            if !skill.slot.is_skill_slot() {
+                return Err(
+                    format!("技能 {} 的槽位 {:?} 不是技能栏槽位", skill.id, skill.slot).into(),
+                );
+            }
+            database.skills.insert(skill.id.clone(), skill);
+        }
+        Ok(database)
+    }
+
+    /// 获取技能定义
+    pub fn get(&self, id: &str) -> Option<&SkillDef> {
+        self.skills.get(id)
+    }
+
+    /// 全部技能定义
+    pub fn iter(&self) -> impl Iterator<Item = &SkillDef> {
+        self.skills.values()
+    }
+
+    /// 把技能的判定框与取消规则登记到战斗配置
+    pub fn register_combat(&self, hitboxes: &mut HitboxSettings, buffer: &mut InputBufferSettings) {
+        for skill in self.skills.values() {
+            if let Some(profile) = skill.attack_profile() {
+                hitboxes.profiles.insert(skill.id.clone(), profile);
+            }
+            buffer.cancel_rules.insert(
+                skill.id.clone(),
+                CancelRule {
+                    cancel_after: skill.cancel_after.unwrap_or(f32::MAX),
+                    cancelable_by: vec![GameAction::Dodge],
+                },
+            );
+        }
+    }
+}
+
+/// 施展技能失败的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkillCastError {
+    /// 槽位上没有技能
+    EmptySlot,
+    /// 技能还在冷却，附带剩余时长
+    Cooldown(f32),
+    NotEnoughQi,
+    NotEnoughStamina,
+}
+
+impl fmt::Display for SkillCastError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SkillCastError::EmptySlot => write!(f, "技能栏槽位为空"),
+            SkillCastError::Cooldown(remaining) => write!(f, "冷却中，还需 {:.1} 秒", remaining),
+            SkillCastError::NotEnoughQi => write!(f, "内力不足"),
+            SkillCastError::NotEnoughStamina => write!(f, "体力不足"),
+        }
+    }
+}
+
+/// 学习技能失败的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkillUnlockError {
+    UnknownSkill,
+    AlreadyLearned,
+    /// 等级不足，附带要求的等级
+    LevelTooLow(u32),
+    /// 缺少前置技能
+    MissingPrerequisite(String),
+    /// 技能点不足，附带需要的点数
+    NotEnoughPoints(u32),
+}
+
+impl fmt::Display for SkillUnlockError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SkillUnlockError::UnknownSkill => write!(f, "没有这个技能"),
+            SkillUnlockError::AlreadyLearned => write!(f, "已经学会"),
+            SkillUnlockError::LevelTooLow(level) => write!(f, "需要达到 {} 级", level),
+            SkillUnlockError::MissingPrerequisite(skill) => write!(f, "需要先学会 {}", skill),
+            SkillUnlockError::NotEnoughPoints(points) => write!(f, "需要 {} 个技能点", points),
+        }
+    }
+}
+
+/// 技能书组件
+///
+/// 记录角色学会的技能、技能栏的绑定与各技能的冷却
+#[derive(Component, Debug, Clone, Default)]
+pub struct SkillBook {
+    pub learned: Vec<String>,
+    /// 技能栏槽位绑定的技能
+    pub slots: HashMap<GameAction, String>,
+    /// 各技能剩余的冷却时长（秒）
+    pub cooldowns: HashMap<String, f32>,
+    /// 距离体力开始恢复的剩余时间（秒）
+    pub stamina_delay: f32,
+}
+
+impl SkillBook {
+    /// 是否学会了某个技能
+    pub fn knows(&self, skill: &str) -> bool {
+        self.learned.iter().any(|learned| learned == skill)
+    }
+
+    /// 技能剩余的冷却时长
+    pub fn cooldown(&self, skill: &str) -> f32 {
+        self.cooldowns.get(skill).copied().unwrap_or(0.0)
+    }
+
+    /// 检查能否学习技能
+    pub fn check_unlock(&self, def: &SkillDef, player: &Player) -> Result<(), SkillUnlockError> {
+        if self.knows(&def.id) {
+            return Err(SkillUnlockError::AlreadyLearned);
+        }
+        if player.level < def.required_level {
+            return Err(SkillUnlockError::LevelTooLow(def.required_level));
+        }
+        if let Some(missing) = def.requires.iter().find(|skill| !self.knows(skill)) {
+            return Err(SkillUnlockError::MissingPrerequisite(missing.clone()));
+        }
+        if player.skill_points < def.unlock_cost {
+            return Err(SkillUnlockError::NotEnoughPoints(def.unlock_cost));
+        }
+        Ok(())
+    }
+
+    /// 学会技能，默认槽位空着时顺便放入技能栏
+    pub fn learn(&mut self, def: &SkillDef) {
+        if self.knows(&def.id) {
+            return;
+        }
+        self.learned.push(def.id.clone());
+        self.slots.entry(def.slot).or_insert_with(|| def.id.clone());
+    }
+
+    /// 施展槽位上的技能
+    ///
+    /// 检查冷却与消耗，成功时扣除内力与体力并开始冷却
+    pub fn cast<'a>(
+        &mut self,
+        slot: GameAction,
+        database: &'a SkillDatabase,
+        character: &mut Character,
+    ) -> Result<&'a SkillDef, SkillCastError> {
+        let def = self
+            .slots
+            .get(&slot)
+            .and_then(|skill| database.get(skill))
+            .ok_or(SkillCastError::EmptySlot)?;
+
+        let remaining = self.cooldown(&def.id);
+        if remaining > 0.0 {
+            return Err(SkillCastError::Cooldown(remaining));
+        }
+        if character.qi < def.qi_cost {
+            return Err(SkillCastError::NotEnoughQi);
+        }
+        if character.stamina < def.stamina_cost {
+            return Err(SkillCastError::NotEnoughStamina);
+        }
+
+        character.qi -= def.qi_cost;
+        character.stamina -= def.stamina_cost;
+        self.cooldowns.insert(def.id.clone(), def.cooldown);
+        Ok(def)
+    }
+}
+
+/// 技能恢复配置
+///
+/// # 参数说明
+/// - stamina_regen: 每秒恢复的体力
+/// - stamina_delay: 消耗体力后多久开始恢复（秒）
+/// - qi_regen: 每秒恢复的内力，内力主要靠打坐休息恢复，这里只是缓慢回复
+#[derive(Resource, Debug, Clone)]
+pub struct SkillSettings {
+    pub stamina_regen: f32,
+    pub stamina_delay: f32,
+    pub qi_regen: f32,
+}
+
+impl Default for SkillSettings {
+    fn default() -> Self {
+        Self {
+            stamina_regen: 20.0,
+            stamina_delay: 1.0,
+            qi_regen: 0.5,
+        }
+    }
+}
+
+/// 请求学习技能，由技能界面或剧情发出
+#[derive(Event, Debug, Clone)]
+pub struct SkillUnlockRequest {
+    pub entity: Entity,
+    pub skill: String,
+}
+
+/// 学会了新技能
+#[derive(Event, Debug, Clone)]
+pub struct SkillUnlocked {
+    pub entity: Entity,
+    pub skill: String,
+}
+
+/// 施展技能失败，供界面提示
+#[derive(Event, Debug, Clone)]
+pub struct SkillCastFailed {
+    pub entity: Entity,
+    pub slot: GameAction,
+    pub error: SkillCastError,
+}
+
+/// 轻功位移
+#[derive(Component, Debug, Clone)]
+pub struct SkillDash {
+    pub velocity: Vec2,
+    pub remaining: f32,
+}
+
+/// 加载技能数据并登记判定框与取消规则，失败时使用空数据库
+pub fn load_skill_database(
+    mut commands: Commands,
+    mut hitboxes: ResMut<HitboxSettings>,
+    mut buffer: ResMut<InputBufferSettings>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let database = match SkillDatabase::load(&resolve_data_path(SKILL_DATA_PATH)) {
+        Ok(database) => database,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("技能数据加载失败: {}", e));
+            }
+            SkillDatabase::default()
+        }
+    };
+    database.register_combat(&mut hitboxes, &mut buffer);
+    commands.insert_resource(database);
+}
+
+/// 新角色学会自带的技能
+pub fn grant_starting_skills(
+    database: Res<SkillDatabase>,
+    mut books: Query<&mut SkillBook, Added<SkillBook>>,
+) {
+    for mut book in books.iter_mut() {
+        for def in database.iter().filter(|def| def.starting) {
+            book.learn(def);
+        }
+    }
+}
+
+/// 处理学习技能的请求，扣除技能点
+pub fn unlock_skills(
+    database: Res<SkillDatabase>,
+    mut requests: EventReader<SkillUnlockRequest>,
+    mut unlocked: EventWriter<SkillUnlocked>,
+    mut players: Query<(&mut Player, &mut SkillBook)>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for request in requests.read() {
+        let Ok((mut player, mut book)) = players.get_mut(request.entity) else {
+            continue;
+        };
+        let result = database
+            .get(&request.skill)
+            .ok_or(SkillUnlockError::UnknownSkill)
+            .and_then(|def| book.check_unlock(def, &player).map(|_| def));
+
+        match result {
+            Ok(def) => {
+                player.skill_points -= def.unlock_cost;
+                book.learn(def);
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(
+                        LogLevel::Info,
+                        &format!("学会了{}「{}」", def.school, def.name),
+                    );
+                }
+                unlocked.send(SkillUnlocked {
+                    entity: request.entity,
+                    skill: def.id.clone(),
+                });
+            }
+            Err(e) => {
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(
+                        LogLevel::Info,
+                        &format!("无法学习技能 {}: {}", request.skill, e),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// 技能开始施展时播放动画、开始轻功位移、给予无敌并发光
+pub fn start_skill_effects(
+    mut commands: Commands,
+    database: Res<SkillDatabase>,
+    mut actions: EventReader<CombatActionEvent>,
+    mut casters: Query<(&Character, Option<&mut AnimationComponent>)>,
+) {
+    for action in actions.read() {
+        let Some(def) = action
+            .skill
+            .as_deref()
+            .and_then(|skill| database.get(skill))
+        else {
+            continue;
+        };
+        let Ok((character, animation)) = casters.get_mut(action.entity) else {
+            continue;
+        };
+
+        if let Some(mut animation) = animation {
+            if !animation.animations.contains(&def.animation) {
+                animation.animations.push(def.animation.clone());
+            }
+            animation.current_animation = def.animation.clone();
+            animation.current_frame = 0;
+            animation.timer.reset();
+        }
+
+        let mut entity = commands.entity(action.entity);
+        if def.kind == SkillKind::Dash && def.duration > 0.0 {
+            let direction = character.direction.normalize_or(Vec2::X);
+            entity.insert(SkillDash {
+                velocity: direction * def.dash_distance / def.duration,
+                remaining: def.duration,
+            });
+        }
+        if def.invulnerable {
+            entity.insert(Invulnerable::new(def.duration));
+        }
+        if let Some(light) = &def.light {
+            let [r, g, b] = light.color;
+            entity.with_children(|caster| {
+                caster.spawn((
+                    Name::new(format!("SkillLight: {}", def.id)),
+                    LightSource {
+                        color: Color::srgb(r, g, b),
+                        intensity: light.intensity,
+                        radius: light.radius,
+                        flicker: 0.0,
+                    },
+                    TimedLight {
+                        remaining: def.duration,
+                    },
+                    Transform::default(),
+                ));
+            });
+        }
+    }
+}
+
+/// 推进轻功位移，位移交给碰撞系统，撞墙时停下
+pub fn apply_skill_dash(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &mut SkillDash,
+        &mut Transform,
+        Option<&mut MovementBody>,
+    )>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut dash, mut transform, mut body) in query.iter_mut() {
+        let step = delta.min(dash.remaining);
+        move_by(&mut transform, body.as_deref_mut(), dash.velocity * step);
+
+        dash.remaining -= delta;
+        let blocked = body.is_some_and(|body| body.blocked);
+        if dash.remaining <= 0.0 || blocked {
+            commands.entity(entity).remove::<SkillDash>();
+        }
+    }
+}
+
+/// 推进技能冷却，恢复体力与内力
+pub fn regenerate_skill_resources(
+    time: Res<Time>,
+    settings: Res<SkillSettings>,
+    database: Res<SkillDatabase>,
+    mut actions: EventReader<CombatActionEvent>,
+    mut query: Query<(&mut Character, &mut SkillBook)>,
+) {
+    let delta = time.delta_secs();
+
+    // 刚消耗了体力的角色重新等待恢复
+    for action in actions.read() {
+        let costs_stamina = action
+            .skill
+            .as_deref()
+            .and_then(|skill| database.get(skill))
+            .is_some_and(|def| def.stamina_cost > 0.0);
+        if !costs_stamina {
+            continue;
+        }
+        if let Ok((_, mut book)) = query.get_mut(action.entity) {
+            book.stamina_delay = settings.stamina_delay;
+        }
+    }
+
+    for (mut character, mut book) in query.iter_mut() {
+        book.cooldowns.retain(|_, remaining| {
+            *remaining -= delta;
+            *remaining > 0.0
+        });
+
+        if character.state == CharacterState::Dead {
+            continue;
+        }
+        character.qi = (character.qi + settings.qi_regen * delta).min(character.max_qi);
+
+        if book.stamina_delay > 0.0 {
+            book.stamina_delay = (book.stamina_delay - delta).max(0.0);
+            continue;
+        }
+        character.stamina =
+            (character.stamina + settings.stamina_regen * delta).min(character.max_stamina);
+    }
+}
@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+
+use super::{SkillBook, SkillDatabase, SkillUnlockRequest, SkillUnlocked};
+use crate::events::input::GameAction;
+use crate::resources::InputState;
+use crate::world::entity::Player;
+
+/// 武学面板是否打开
+#[derive(Resource, Debug, Default)]
+pub struct SkillPanel {
+    pub open: bool,
+}
+
+/// 武学面板界面标记
+#[derive(Component)]
+pub struct SkillPanelUi;
+
+/// 学习技能按钮，附带技能ID
+#[derive(Component, Debug, Clone)]
+pub struct SkillUnlockButton(pub String);
+
+/// 按武学键开关武学面板
+pub fn toggle_skill_panel(input_state: Res<InputState>, mut panel: ResMut<SkillPanel>) {
+    if input_state.is_action_just_pressed(GameAction::OpenSkills) {
+        panel.open = !panel.open;
+    }
+}
+
+/// 点击可学习的技能时发出学习请求，是否满足条件由学习系统检查
+pub fn handle_skill_panel_clicks(
+    panel: Res<SkillPanel>,
+    buttons: Query<(&Interaction, &SkillUnlockButton), Changed<Interaction>>,
+    players: Query<Entity, With<Player>>,
+    mut requests: EventWriter<SkillUnlockRequest>,
+) {
+    if !panel.open {
+        return;
+    }
+    let Ok(player) = players.get_single() else {
+        return;
+    };
+
+    for (interaction, button) in buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            requests.send(SkillUnlockRequest {
+                entity: player,
+                skill: button.0.clone(),
+            });
+        }
+    }
+}
+
+/// 面板开关、技能点或学会新技能时重建武学面板
+///
+/// 按门类与要求等级排列，已学会与条件不足的技能只显示原因，不能点击
+pub fn update_skill_panel_ui(
+    mut commands: Commands,
+    panel: Res<SkillPanel>,
+    database: Res<SkillDatabase>,
+    mut unlocked: EventReader<SkillUnlocked>,
+    players: Query<(Ref<Player>, &SkillBook)>,
+    ui: Query<Entity, With<SkillPanelUi>>,
+) {
+    let learned = unlocked.read().count() > 0;
+    let Ok((player, book)) = players.get_single() else {
+        return;
+    };
+    if !panel.is_changed() && !player.is_changed() && !learned {
+        return;
+    }
+
+    for entity in ui.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if !panel.open {
+        return;
+    }
+
+    let mut skills: Vec<_> = database.iter().collect();
+    skills.sort_by(|a, b| {
+        (&a.school, a.required_level, &a.id).cmp(&(&b.school, b.required_level, &b.id))
+    });
+    let entries: Vec<(String, Option<SkillUnlockButton>)> = skills
+        .into_iter()
+        .map(|def| {
+            let label = format!("{}「{}」", def.school, def.name);
+            match book.check_unlock(def, &player) {
+                Ok(()) => (
+                    format!("{}  学习（{} 点）", label, def.unlock_cost),
+                    Some(SkillUnlockButton(def.id.clone())),
+                ),
+                Err(reason) => (format!("{}  {}", label, reason), None),
+            }
+        })
+        .collect();
+
+    commands
+        .spawn((
+            SkillPanelUi,
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(24.0),
+                top: Val::Px(80.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(12.0)),
+                row_gap: Val::Px(4.0),
+                min_width: Val::Px(280.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.08, 0.06, 0.9)),
+        ))
+        .with_children(|column| {
+            column.spawn((
+                Text::new(format!("武学（剩余技能点 {}）", player.skill_points)),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+            ));
+
+            for (text, button) in entries {
+                let label = (
+                    Text::new(text),
+                    TextFont {
+                        font_size: 14.0,
+                        ..default()
+                    },
+                );
+                match button {
+                    Some(button) => {
+                        column
+                            .spawn((
+                                Button,
+                                button,
+                                Node {
+                                    padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                                    ..default()
+                                },
+                                BackgroundColor(Color::srgba(0.25, 0.2, 0.15, 0.8)),
+                            ))
+                            .with_children(|button| {
+                                button.spawn(label);
+                            });
+                    }
+                    None => {
+                        column
+                            .spawn(Node {
+                                padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                                ..default()
+                            })
+                            .with_children(|row| {
+                                row.spawn((
+                                    label.0,
+                                    label.1,
+                                    TextColor(Color::srgb(0.6, 0.6, 0.6)),
+                                ));
+                            });
+                    }
+                }
+            }
+        });
+}
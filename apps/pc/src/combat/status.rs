@@ -0,0 +1,452 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::{CombatEffectKind, DamageEvent};
+use crate::housing::CurrentInterior;
+use crate::world::entity::{Character, CharacterState};
+use crate::world::map::TileType;
+use crate::world::physics::MovementBody;
+use crate::world::weather::{GroundCondition, WeatherKind, WeatherState};
+
+/// 状态效果类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StatusKind {
+    /// 中毒：持续掉血，可叠加
+    Poison,
+    /// 流血：持续掉血，可叠加，层数上限较低
+    Bleed,
+    /// 迟缓：踩在雪地上移动变慢
+    Slow,
+    /// 湿身：落水或淋雨后移动略慢
+    Wet,
+    /// 调息：持续恢复内力
+    QiRegen,
+}
+
+impl StatusKind {
+    /// 显示在状态栏上的图标
+    pub fn icon(&self) -> &'static str {
+        match self {
+            StatusKind::Poison => "毒",
+            StatusKind::Bleed => "血",
+            StatusKind::Slow => "缓",
+            StatusKind::Wet => "湿",
+            StatusKind::QiRegen => "息",
+        }
+    }
+
+    /// 图标颜色
+    pub fn icon_color(&self) -> Color {
+        match self {
+            StatusKind::Poison => Color::srgb(0.45, 0.85, 0.3),
+            StatusKind::Bleed => Color::srgb(0.9, 0.2, 0.2),
+            StatusKind::Slow => Color::srgb(0.75, 0.9, 1.0),
+            StatusKind::Wet => Color::srgb(0.35, 0.6, 0.95),
+            StatusKind::QiRegen => Color::srgb(0.95, 0.8, 0.35),
+        }
+    }
+
+    /// 是否是有害效果，界面上据此区分边框
+    pub fn is_harmful(&self) -> bool {
+        !matches!(self, StatusKind::QiRegen)
+    }
+}
+
+/// 重复施加同一效果时的叠加规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackRule {
+    /// 只刷新持续时间
+    Refresh,
+    /// 增加一层并刷新持续时间，层数越多每跳效果越强
+    Stack,
+    /// 在剩余时间上累加持续时间
+    Extend,
+}
+
+/// 周期生效的内容
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusTick {
+    None,
+    /// 每层每跳造成的伤害
+    Damage(f32),
+    /// 每层每跳恢复的内力
+    Qi(f32),
+}
+
+/// 状态效果定义
+///
+/// # 参数说明
+/// - name: 显示名称，也是伤害记录中的效果名
+/// - stacking / max_stacks: 叠加规则与层数上限
+/// - duration: 单次施加的持续时间（秒）；按 Extend 叠加时剩余时间不超过它的 max_stacks 倍
+/// - tick_interval / tick: 周期效果的间隔与内容
+/// - speed_multiplier: 带有该效果时的移速倍率
+#[derive(Debug, Clone)]
+pub struct StatusDef {
+    pub name: String,
+    pub stacking: StackRule,
+    pub max_stacks: u32,
+    pub duration: f32,
+    pub tick_interval: f32,
+    pub tick: StatusTick,
+    pub speed_multiplier: f32,
+}
+
+/// 状态效果配置
+///
+/// # 参数说明
+/// - defs: 各类效果的定义
+/// - environment_linger: 离开雪地、上岸或雨停后环境效果的残留时间（秒）
+#[derive(Resource, Debug, Clone)]
+pub struct StatusEffectSettings {
+    pub defs: HashMap<StatusKind, StatusDef>,
+    pub environment_linger: f32,
+}
+
+impl StatusEffectSettings {
+    /// 某类效果的定义
+    pub fn get(&self, kind: StatusKind) -> Option<&StatusDef> {
+        self.defs.get(&kind)
+    }
+}
+
+impl Default for StatusEffectSettings {
+    fn default() -> Self {
+        let defs = [
+            (
+                StatusKind::Poison,
+                StatusDef {
+                    name: "中毒".to_string(),
+                    stacking: StackRule::Stack,
+                    max_stacks: 5,
+                    duration: 8.0,
+                    tick_interval: 1.0,
+                    tick: StatusTick::Damage(2.0),
+                    speed_multiplier: 1.0,
+                },
+            ),
+            (
+                StatusKind::Bleed,
+                StatusDef {
+                    name: "流血".to_string(),
+                    stacking: StackRule::Stack,
+                    max_stacks: 3,
+                    duration: 5.0,
+                    tick_interval: 0.5,
+                    tick: StatusTick::Damage(1.5),
+                    speed_multiplier: 1.0,
+                },
+            ),
+            (
+                StatusKind::Slow,
+                StatusDef {
+                    name: "迟缓".to_string(),
+                    stacking: StackRule::Refresh,
+                    max_stacks: 1,
+                    duration: 1.0,
+                    tick_interval: 0.0,
+                    tick: StatusTick::None,
+                    speed_multiplier: 0.7,
+                },
+            ),
+            (
+                StatusKind::Wet,
+                StatusDef {
+                    name: "湿身".to_string(),
+                    stacking: StackRule::Refresh,
+                    max_stacks: 1,
+                    duration: 10.0,
+                    tick_interval: 0.0,
+                    tick: StatusTick::None,
+                    speed_multiplier: 0.9,
+                },
+            ),
+            (
+                StatusKind::QiRegen,
+                StatusDef {
+                    name: "调息".to_string(),
+                    stacking: StackRule::Extend,
+                    max_stacks: 3,
+                    duration: 20.0,
+                    tick_interval: 1.0,
+                    tick: StatusTick::Qi(3.0),
+                    speed_multiplier: 1.0,
+                },
+            ),
+        ];
+
+        Self {
+            defs: defs.into_iter().collect(),
+            environment_linger: 1.0,
+        }
+    }
+}
+
+/// 一个生效中的状态效果
+#[derive(Debug, Clone)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub stacks: u32,
+    /// 剩余时间（秒）
+    pub remaining: f32,
+    /// 距下一跳的时间（秒）
+    pub tick_timer: f32,
+    /// 施加者，周期伤害记在它名下
+    pub source: Option<Entity>,
+    pub source_name: String,
+}
+
+/// 角色身上的状态效果
+#[derive(Component, Debug, Clone, Default)]
+pub struct StatusEffects {
+    pub effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    /// 按叠加规则施加效果，`duration` 为空时取定义中的持续时间
+    pub fn apply(
+        &mut self,
+        kind: StatusKind,
+        def: &StatusDef,
+        duration: Option<f32>,
+        source: Option<Entity>,
+        source_name: &str,
+    ) {
+        let duration = duration.unwrap_or(def.duration);
+        let max_stacks = def.max_stacks.max(1);
+
+        let Some(effect) = self.effects.iter_mut().find(|effect| effect.kind == kind) else {
+            self.effects.push(StatusEffect {
+                kind,
+                stacks: 1,
+                remaining: duration,
+                tick_timer: def.tick_interval,
+                source,
+                source_name: source_name.to_string(),
+            });
+            return;
+        };
+
+        match def.stacking {
+            StackRule::Refresh => {
+                effect.remaining = effect.remaining.max(duration);
+            }
+            StackRule::Stack => {
+                effect.stacks = (effect.stacks + 1).min(max_stacks);
+                effect.remaining = effect.remaining.max(duration);
+            }
+            StackRule::Extend => {
+                effect.remaining =
+                    (effect.remaining + duration).min(def.duration * max_stacks as f32);
+            }
+        }
+        // 周期伤害记在最近一次施加者名下
+        if source.is_some() {
+            effect.source = source;
+            effect.source_name = source_name.to_string();
+        }
+    }
+
+    /// 所有效果合计的移速倍率
+    pub fn speed_multiplier(&self, settings: &StatusEffectSettings) -> f32 {
+        self.effects
+            .iter()
+            .filter_map(|effect| settings.get(effect.kind))
+            .map(|def| def.speed_multiplier)
+            .product()
+    }
+}
+
+/// 施加状态效果
+///
+/// 战斗命中、道具与剧情都通过该事件施加，目标没有状态组件时自动添加
+#[derive(Event, Debug, Clone)]
+pub struct ApplyStatus {
+    pub target: Entity,
+    pub source: Option<Entity>,
+    pub source_name: String,
+    pub kind: StatusKind,
+    /// 覆盖定义中的持续时间
+    pub duration: Option<f32>,
+}
+
+impl ApplyStatus {
+    /// 按定义的持续时间施加
+    pub fn new(
+        target: Entity,
+        source: Option<Entity>,
+        source_name: &str,
+        kind: StatusKind,
+    ) -> Self {
+        Self {
+            target,
+            source,
+            source_name: source_name.to_string(),
+            kind,
+            duration: None,
+        }
+    }
+}
+
+/// 处理施加请求
+pub fn apply_status_requests(
+    mut commands: Commands,
+    settings: Res<StatusEffectSettings>,
+    mut requests: EventReader<ApplyStatus>,
+    mut targets: Query<(&Character, Option<&mut StatusEffects>)>,
+) {
+    // 同一帧对没有状态组件的目标多次施加时先攒在这里，最后一并插入
+    let mut added: HashMap<Entity, StatusEffects> = HashMap::new();
+
+    for request in requests.read() {
+        let Some(def) = settings.get(request.kind) else {
+            continue;
+        };
+        let Ok((character, effects)) = targets.get_mut(request.target) else {
+            continue;
+        };
+        if character.state == CharacterState::Dead {
+            continue;
+        }
+
+        let effects = match effects {
+            Some(effects) => effects.into_inner(),
+            None => added.entry(request.target).or_default(),
+        };
+        effects.apply(
+            request.kind,
+            def,
+            request.duration,
+            request.source,
+            &request.source_name,
+        );
+    }
+
+    for (entity, effects) in added {
+        commands.entity(entity).insert(effects);
+    }
+}
+
+/// 按环境施加状态效果
+///
+/// 站在雪地上迟缓，落水或淋雨时湿身；效果会在离开后残留片刻
+#[allow(clippy::type_complexity)]
+pub fn apply_environment_statuses(
+    mut commands: Commands,
+    settings: Res<StatusEffectSettings>,
+    weather: Option<Res<WeatherState>>,
+    interior: Option<Res<CurrentInterior>>,
+    mut walkers: Query<(
+        Entity,
+        &Character,
+        Option<&GroundCondition>,
+        Option<&MovementBody>,
+        Option<&mut StatusEffects>,
+    )>,
+) {
+    // 在室内淋不到雨
+    let indoors = interior
+        .as_ref()
+        .is_some_and(|interior| interior.home_id.is_some());
+    let raining = !indoors
+        && weather
+            .as_ref()
+            .is_some_and(|weather| weather.kind == WeatherKind::Rain);
+    let linger = Some(settings.environment_linger);
+
+    for (entity, character, ground, body, effects) in walkers.iter_mut() {
+        if character.state == CharacterState::Dead {
+            continue;
+        }
+
+        let on_snow = ground.is_some_and(|ground| ground.tile == Some(TileType::Snow));
+        let in_water = body.is_some_and(|body| body.in_water);
+        // 只有踩在已加载地面上的角色才会淋雨
+        let rained_on = raining && ground.is_some_and(|ground| ground.tile.is_some());
+        if !on_snow && !in_water && !rained_on {
+            continue;
+        }
+
+        let mut inserted = None;
+        let effects = match effects {
+            Some(effects) => effects.into_inner(),
+            None => inserted.insert(StatusEffects::default()),
+        };
+        if on_snow {
+            if let Some(def) = settings.get(StatusKind::Slow) {
+                effects.apply(StatusKind::Slow, def, linger, None, "雪地");
+            }
+        }
+        if in_water || rained_on {
+            // 湿身在离开水面后还要一阵子才会干
+            if let Some(def) = settings.get(StatusKind::Wet) {
+                let source_name = if in_water { "落水" } else { "淋雨" };
+                effects.apply(StatusKind::Wet, def, None, None, source_name);
+            }
+        }
+        if let Some(effects) = inserted {
+            commands.entity(entity).insert(effects);
+        }
+    }
+}
+
+/// 推进状态效果的计时
+///
+/// # 设计思路
+/// 1. 每个效果独立计时，到点按层数结算一跳
+/// 2. 伤害通过伤害事件结算，战斗历史与死亡回顾能看到是什么效果造成的
+/// 3. 角色死亡时清空所有效果
+pub fn tick_status_effects(
+    time: Res<Time>,
+    settings: Res<StatusEffectSettings>,
+    mut query: Query<(Entity, &mut Character, &mut StatusEffects)>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    let delta = time.delta_secs();
+
+    for (entity, mut character, mut effects) in query.iter_mut() {
+        if effects.effects.is_empty() {
+            continue;
+        }
+        if character.state == CharacterState::Dead {
+            effects.effects.clear();
+            continue;
+        }
+
+        for effect in effects.effects.iter_mut() {
+            let Some(def) = settings.get(effect.kind) else {
+                effect.remaining = 0.0;
+                continue;
+            };
+            effect.remaining -= delta;
+
+            if def.tick_interval <= 0.0 || def.tick == StatusTick::None {
+                continue;
+            }
+            effect.tick_timer -= delta;
+            while effect.tick_timer <= 0.0 {
+                effect.tick_timer += def.tick_interval;
+                let stacks = effect.stacks as f32;
+                match def.tick {
+                    StatusTick::Damage(amount) => {
+                        damage_events.send(DamageEvent {
+                            target: entity,
+                            source: effect.source,
+                            source_name: effect.source_name.clone(),
+                            amount: amount * stacks,
+                            skill: None,
+                            kind: CombatEffectKind::Status(def.name.clone()),
+                        });
+                    }
+                    StatusTick::Qi(amount) => {
+                        character.qi = (character.qi + amount * stacks).min(character.max_qi);
+                    }
+                    StatusTick::None => {}
+                }
+            }
+        }
+
+        effects.effects.retain(|effect| effect.remaining > 0.0);
+    }
+}
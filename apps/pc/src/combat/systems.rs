@@ -0,0 +1,217 @@
+use bevy::prelude::*;
+
+use super::{
+    apply_environment_statuses, apply_knockback, apply_skill_dash, apply_status_requests,
+    buffer_combat_inputs, build_death_recap, dismiss_death_recap, drive_npc_attacks,
+    execute_buffered_actions, finish_actions_with_animation, grant_dodge_invulnerability,
+    grant_starting_skills, handle_skill_panel_clicks, load_skill_database,
+    regenerate_skill_resources, resolve_attack_hitboxes, spawn_attack_hitboxes,
+    start_skill_effects, tick_invulnerability, tick_status_effects, toggle_skill_panel,
+    trigger_combat_taunts, trigger_impact_effects, unlock_skills, update_skill_panel_ui,
+    ApplyStatus, CombatActionEvent, CombatEffectKind, CombatHistory, CombatRecord, DamageEvent,
+    DeathEvent, DeathRecap, HealthChanged, HitboxSettings, ImpactSettings, InputBufferSettings,
+    ParryEvent, RecapSettings, SkillCastFailed, SkillDatabase, SkillPanel, SkillSettings,
+    SkillUnlockRequest, SkillUnlocked, StatusEffectSettings, TauntCooldowns, TauntSettings,
+};
+use crate::events::input::handle_input_events;
+use crate::items::{DurabilitySettings, Equipment, ItemDatabase};
+use crate::resources::{gameplay_running, DifficultyModifiers};
+use crate::world::entity::{Character, CharacterState, Player};
+
+/// 战斗系统插件
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        // 注册事件
+        app.add_event::<DamageEvent>()
+            .add_event::<DeathEvent>()
+            .add_event::<CombatActionEvent>()
+            .add_event::<ParryEvent>()
+            .add_event::<HealthChanged>()
+            .add_event::<SkillUnlockRequest>()
+            .add_event::<SkillUnlocked>()
+            .add_event::<SkillCastFailed>()
+            .add_event::<ApplyStatus>();
+
+        // 注册资源
+        app.init_resource::<CombatHistory>()
+            .init_resource::<RecapSettings>()
+            .init_resource::<DeathRecap>()
+            .init_resource::<InputBufferSettings>()
+            .init_resource::<ImpactSettings>()
+            .init_resource::<TauntSettings>()
+            .init_resource::<TauntCooldowns>()
+            .init_resource::<HitboxSettings>()
+            .init_resource::<SkillSettings>()
+            .init_resource::<SkillDatabase>()
+            .init_resource::<SkillPanel>()
+            .init_resource::<StatusEffectSettings>();
+
+        // 注册系统
+        app.add_systems(
+            Update,
+            (
+                apply_damage_events,
+                trigger_impact_effects,
+                trigger_combat_taunts,
+                build_death_recap,
+                dismiss_death_recap,
+                prune_combat_history,
+            )
+                .chain()
+                .run_if(gameplay_running),
+        );
+
+        // 技能数据在启动时加载，学习技能与冷却、体力恢复每帧处理
+        app.add_systems(Startup, load_skill_database).add_systems(
+            Update,
+            (
+                grant_starting_skills,
+                toggle_skill_panel,
+                handle_skill_panel_clicks,
+                unlock_skills,
+                update_skill_panel_ui,
+                regenerate_skill_resources,
+            )
+                .chain()
+                .run_if(gameplay_running),
+        );
+
+        // 状态效果在伤害结算之前施加与计时，周期伤害当帧入账
+        app.add_systems(
+            Update,
+            (
+                apply_environment_statuses,
+                apply_status_requests,
+                tick_status_effects,
+            )
+                .chain()
+                .run_if(gameplay_running)
+                .before(apply_damage_events),
+        );
+
+        // 输入在每帧记录，动作、判定与受击反应在固定步长中执行
+        app.add_systems(
+            Update,
+            buffer_combat_inputs
+                .after(handle_input_events)
+                .run_if(gameplay_running),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                finish_actions_with_animation,
+                execute_buffered_actions,
+                drive_npc_attacks,
+                grant_dodge_invulnerability,
+                start_skill_effects,
+                tick_invulnerability,
+                spawn_attack_hitboxes,
+                resolve_attack_hitboxes,
+                apply_knockback,
+                apply_skill_dash,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// 结算伤害事件
+///
+/// 修改角色生命值、写入战斗历史，并在生命值归零时发出死亡事件。
+/// 玩家受到的伤害按难度的受伤倍率放大或缩小
+#[allow(clippy::too_many_arguments)]
+fn apply_damage_events(
+    time: Res<Time>,
+    difficulty: Res<DifficultyModifiers>,
+    mut damage_events: EventReader<DamageEvent>,
+    mut death_events: EventWriter<DeathEvent>,
+    mut health_events: EventWriter<HealthChanged>,
+    mut history: ResMut<CombatHistory>,
+    database: Res<ItemDatabase>,
+    durability: Res<DurabilitySettings>,
+    mut characters: Query<(&mut Character, Option<&Equipment>, Has<Player>)>,
+) {
+    let now = time.elapsed_secs();
+
+    for event in damage_events.read() {
+        // 攻击者的武器加成同样按耐久折算，只作用于直接伤害
+        let attack_bonus = match (&event.kind, event.source) {
+            (CombatEffectKind::Damage, Some(source)) => characters
+                .get(source)
+                .ok()
+                .and_then(|(_, equipment, _)| equipment)
+                .map_or(0.0, |equipment| {
+                    equipment.attack_power(&database, &durability)
+                }),
+            _ => 0.0,
+        };
+
+        let Ok((mut character, equipment, is_player)) = characters.get_mut(event.target) else {
+            continue;
+        };
+
+        // 已死亡的角色不再结算
+        if character.state == CharacterState::Dead {
+            continue;
+        }
+
+        // 护甲只减免直接伤害，减免量受耐久影响
+        let amount = event.amount + attack_bonus;
+        let amount = match (&event.kind, equipment) {
+            (CombatEffectKind::Damage, Some(equipment)) => {
+                equipment.mitigate(amount, &database, &durability)
+            }
+            _ => amount,
+        };
+        let amount = match event.kind {
+            CombatEffectKind::Damage | CombatEffectKind::Status(_) if is_player => {
+                amount * difficulty.damage_taken
+            }
+            _ => amount,
+        };
+
+        history.push(
+            event.target,
+            CombatRecord {
+                time: now,
+                source_name: event.source_name.clone(),
+                amount,
+                skill: event.skill.clone(),
+                kind: event.kind.clone(),
+            },
+        );
+
+        let previous = character.health;
+        match event.kind {
+            CombatEffectKind::Heal => {
+                character.health = (character.health + amount).min(character.max_health);
+            }
+            CombatEffectKind::Damage | CombatEffectKind::Status(_) => {
+                character.health = (character.health - amount).max(0.0);
+            }
+        }
+        if character.health != previous {
+            health_events.send(HealthChanged {
+                entity: event.target,
+                delta: character.health - previous,
+            });
+        }
+
+        if character.health <= 0.0 {
+            character.state = CharacterState::Dead;
+            character.can_move = false;
+            death_events.send(DeathEvent {
+                entity: event.target,
+                killer: event.source,
+                killer_name: event.source_name.clone(),
+            });
+        }
+    }
+}
+
+/// 定期清理过期的战斗记录
+fn prune_combat_history(time: Res<Time>, mut history: ResMut<CombatHistory>) {
+    history.prune(time.elapsed_secs());
+}
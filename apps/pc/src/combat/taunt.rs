@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashMap;
+
+use super::{CombatEffectKind, DamageEvent, ParryEvent};
+use crate::ui::{BubbleKind, ShowSpeechBubble};
+use crate::world::entity::{Npc, NpcType, Player};
+
+/// 命中玩家时的嘲讽
+const HIT_TAUNTS: &[&str] = &[
+    "就这点本事？",
+    "再来啊！",
+    "小子，你还嫩了点。",
+    "躲得了一时，躲不了一世！",
+];
+
+/// 被玩家招架时的嘲讽
+const PARRIED_TAUNTS: &[&str] = &["好身手……", "有点意思。", "哼，侥幸罢了！"];
+
+/// 首领专属的嘲讽
+const BOSS_TAUNTS: &[&str] = &["不自量力！", "今日便是你的死期。", "区区蝼蚁，也敢放肆？"];
+
+/// 嘲讽参数
+///
+/// # 参数说明
+/// - hit_chance: 敌人命中玩家时喊话的概率
+/// - parried_chance: 敌人被玩家招架时喊话的概率
+/// - cooldown: 同一个敌人两次喊话的最短间隔（秒）
+#[derive(Resource, Debug, Clone)]
+pub struct TauntSettings {
+    pub hit_chance: f64,
+    pub parried_chance: f64,
+    pub cooldown: f32,
+}
+
+impl Default for TauntSettings {
+    fn default() -> Self {
+        Self {
+            hit_chance: 0.3,
+            parried_chance: 0.6,
+            cooldown: 6.0,
+        }
+    }
+}
+
+/// 各敌人可再次喊话的时间
+#[derive(Resource, Debug, Default)]
+pub struct TauntCooldowns {
+    ready_at: HashMap<Entity, f64>,
+}
+
+/// 敌人与玩家交手时在头顶喊话
+///
+/// 只处理与玩家有关的战斗，按概率触发并带冷却，避免每一击都冒气泡
+#[allow(clippy::too_many_arguments)]
+pub fn trigger_combat_taunts(
+    time: Res<Time>,
+    settings: Res<TauntSettings>,
+    mut cooldowns: ResMut<TauntCooldowns>,
+    mut damage_events: EventReader<DamageEvent>,
+    mut parry_events: EventReader<ParryEvent>,
+    mut bubbles: EventWriter<ShowSpeechBubble>,
+    npcs: Query<&Npc>,
+    players: Query<(), With<Player>>,
+) {
+    let now = time.elapsed_secs_f64();
+    let mut rng = rand::thread_rng();
+
+    let mut candidates = Vec::new();
+    for event in damage_events.read() {
+        if event.kind != CombatEffectKind::Damage || !players.contains(event.target) {
+            continue;
+        }
+        if let Some(source) = event.source {
+            candidates.push((source, HIT_TAUNTS, settings.hit_chance));
+        }
+    }
+    for event in parry_events.read() {
+        if players.contains(event.defender) {
+            candidates.push((event.attacker, PARRIED_TAUNTS, settings.parried_chance));
+        }
+    }
+
+    for (speaker, lines, chance) in candidates {
+        let Ok(npc) = npcs.get(speaker) else {
+            continue;
+        };
+        let lines = match npc.npc_type {
+            NpcType::Boss => BOSS_TAUNTS,
+            NpcType::Enemy => lines,
+            _ => continue,
+        };
+        if cooldowns
+            .ready_at
+            .get(&speaker)
+            .is_some_and(|ready| *ready > now)
+            || !rng.gen_bool(chance.clamp(0.0, 1.0))
+        {
+            continue;
+        }
+        let Some(line) = lines.choose(&mut rng) else {
+            continue;
+        };
+
+        bubbles.send(ShowSpeechBubble::new(speaker, *line, BubbleKind::Taunt));
+        cooldowns
+            .ready_at
+            .insert(speaker, now + settings.cooldown as f64);
+    }
+    cooldowns.ready_at.retain(|_, ready| *ready > now);
+}
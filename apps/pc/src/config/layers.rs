@@ -0,0 +1,273 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::path::PathBuf;
+
+/// 环境变量覆盖的前缀，层级之间用双下划线分隔，例如 `CHIVALRY_WINDOW__WIDTH=1920`
+pub const ENV_PREFIX: &str = "CHIVALRY_";
+
+/// 环境变量中配置项层级的分隔符，单下划线留给配置项名本身
+const ENV_SEPARATOR: &str = "__";
+
+/// 配置来源，按优先级从低到高排列
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// 编译进程序的默认值
+    Defaults,
+    /// 编译进程序的运行模式配置，记录模式名
+    Embedded(&'static str),
+    /// 按运行模式选择的外部配置文件
+    ModeFile(PathBuf),
+    /// 用户配置目录下的配置文件，选项菜单也写这里
+    UserFile(PathBuf),
+    /// 环境变量，记录变量名
+    Env(String),
+    /// 命令行参数，记录参数原文
+    Cli(String),
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigSource::Defaults => write!(f, "默认值"),
+            ConfigSource::Embedded(mode) => write!(f, "内置的 {} 配置", mode),
+            ConfigSource::ModeFile(path) => write!(f, "配置文件 {}", path.display()),
+            ConfigSource::UserFile(path) => write!(f, "用户配置 {}", path.display()),
+            ConfigSource::Env(name) => write!(f, "环境变量 {}", name),
+            ConfigSource::Cli(arg) => write!(f, "命令行参数 {}", arg),
+        }
+    }
+}
+
+/// 单个配置项的覆盖，键为点分隔的路径，例如 `window.width`
+#[derive(Debug, Clone)]
+pub struct ConfigOverride {
+    pub key: String,
+    pub value: String,
+    pub source: ConfigSource,
+}
+
+/// 读取以 [`ENV_PREFIX`] 开头的环境变量
+pub fn env_overrides() -> Vec<ConfigOverride> {
+    let mut overrides: Vec<_> = env::vars()
+        .filter_map(|(name, value)| {
+            let key = name
+                .strip_prefix(ENV_PREFIX)?
+                .to_lowercase()
+                .replace(ENV_SEPARATOR, ".");
+            Some(ConfigOverride {
+                key,
+                value,
+                source: ConfigSource::Env(name),
+            })
+        })
+        .collect();
+    // 环境变量的顺序不固定，排序后同一环境下的报错总是同一条
+    overrides.sort_by(|a, b| a.key.cmp(&b.key));
+    overrides
+}
+
+/// 从命令行参数中分出配置项覆盖
+///
+/// 形如 `--window.width=1920` 或 `--window.width 1920` 的参数视为覆盖，
+/// 其余参数原样返回交给命令行解析；`--` 之后的参数不再检查
+pub fn split_cli_overrides(
+    args: impl IntoIterator<Item = String>,
+) -> (Vec<String>, Vec<ConfigOverride>) {
+    let mut rest = Vec::new();
+    let mut overrides = Vec::new();
+    let mut args = args.into_iter().peekable();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            rest.push(arg);
+            rest.extend(args.by_ref());
+            break;
+        }
+        let Some(flag) = arg.strip_prefix("--") else {
+            rest.push(arg);
+            continue;
+        };
+        let (key, value) = match flag.split_once('=') {
+            Some((key, value)) => (key.to_string(), Some(value.to_string())),
+            None => (flag.to_string(), None),
+        };
+        if !key.contains('.') {
+            rest.push(arg);
+            continue;
+        }
+        let value = match value {
+            Some(value) => value,
+            None => match args.next_if(|next| !next.starts_with("--")) {
+                Some(value) => value,
+                // 只写了配置项没有值，当作开关打开
+                None => "true".to_string(),
+            },
+        };
+        overrides.push(ConfigOverride {
+            key,
+            value,
+            source: ConfigSource::Cli(arg),
+        });
+    }
+    (rest, overrides)
+}
+
+/// 配置项错误，带上出错的配置项与来源
+fn key_error(key: &str, source: &ConfigSource, message: impl fmt::Display) -> String {
+    format!("配置项 {}（来自{}）: {}", key, source, message)
+}
+
+/// 值的类型名，用于报错
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "空值",
+        Value::Bool(_) => "布尔值",
+        Value::Number(n) if n.is_f64() => "数字",
+        Value::Number(_) => "整数",
+        Value::String(_) => "字符串",
+        Value::Array(_) => "数组",
+        Value::Object(_) => "对象",
+    }
+}
+
+/// 新值能否替换旧值：类型一致，整数项不接受小数与负数
+fn same_kind(old: &Value, new: &Value) -> bool {
+    match (old, new) {
+        (Value::Number(old), Value::Number(new)) => {
+            if old.is_u64() {
+                new.is_u64()
+            } else if old.is_i64() {
+                new.is_i64() || new.is_u64()
+            } else {
+                true
+            }
+        }
+        (Value::Bool(_), Value::Bool(_))
+        | (Value::String(_), Value::String(_))
+        | (Value::Array(_), Value::Array(_))
+        | (Value::Object(_), Value::Object(_)) => true,
+        _ => false,
+    }
+}
+
+/// 逐层合并的配置
+///
+/// # 设计思路
+/// 1. 以默认值序列化出的 JSON 为底，之后每一层只覆盖自己写到的配置项
+/// 2. 默认值里没有的配置项一律视为拼写错误，类型不符的值在合并时就报错，报错带上配置项与来源
+/// 3. 记录每个配置项最后由哪一层写入，最终校验失败时也能指出该改哪里
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    value: Value,
+    origins: HashMap<String, ConfigSource>,
+}
+
+impl LayeredConfig {
+    pub fn new(defaults: Value) -> Self {
+        Self {
+            value: defaults,
+            origins: HashMap::new(),
+        }
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// 配置项最后由哪一层写入
+    pub fn origin(&self, key: &str) -> &ConfigSource {
+        self.origins.get(key).unwrap_or(&ConfigSource::Defaults)
+    }
+
+    /// 合并一整层配置，例如一个配置文件
+    pub fn merge(&mut self, layer: &Value, source: &ConfigSource) -> Result<(), String> {
+        let Value::Object(layer) = layer else {
+            return Err(format!("{} 的顶层必须是对象", source));
+        };
+        let mut origins = Vec::new();
+        merge_object(&mut self.value, layer, "", source, &mut origins)?;
+        for key in origins {
+            self.origins.insert(key, source.clone());
+        }
+        Ok(())
+    }
+
+    /// 按点分隔的路径覆盖单个配置项，值按该项现有的类型解析
+    pub fn set(&mut self, entry: &ConfigOverride) -> Result<(), String> {
+        let key = entry.key.as_str();
+        let source = &entry.source;
+        let mut slot = &mut self.value;
+        for part in key.split('.') {
+            slot = slot
+                .as_object_mut()
+                .and_then(|object| object.get_mut(part))
+                .ok_or_else(|| key_error(key, source, "没有这个配置项"))?;
+        }
+
+        let raw = entry.value.trim();
+        let parsed = match &*slot {
+            Value::Bool(_) => raw.parse::<bool>().ok().map(Value::Bool),
+            Value::Number(n) if n.is_u64() => raw.parse::<u64>().ok().map(Value::from),
+            Value::Number(n) if n.is_i64() => raw.parse::<i64>().ok().map(Value::from),
+            Value::Number(_) => raw.parse::<f64>().ok().map(Value::from),
+            Value::String(_) => Some(Value::String(entry.value.clone())),
+            Value::Object(_) => {
+                return Err(key_error(key, source, "是一组配置，请指定其中的某一项"))
+            }
+            _ => serde_json::from_str(raw).ok(),
+        };
+        let parsed = parsed
+            .filter(|parsed| same_kind(slot, parsed))
+            .ok_or_else(|| {
+                key_error(
+                    key,
+                    source,
+                    format!("需要{}，实际为 \"{}\"", type_name(slot), entry.value),
+                )
+            })?;
+        *slot = parsed;
+        self.origins.insert(entry.key.clone(), source.clone());
+        Ok(())
+    }
+}
+
+fn merge_object(
+    base: &mut Value,
+    layer: &Map<String, Value>,
+    prefix: &str,
+    source: &ConfigSource,
+    origins: &mut Vec<String>,
+) -> Result<(), String> {
+    for (name, value) in layer {
+        let key = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+        let slot = base
+            .as_object_mut()
+            .and_then(|object| object.get_mut(name))
+            .ok_or_else(|| key_error(&key, source, "没有这个配置项"))?;
+        if let Value::Object(inner) = value {
+            if slot.is_object() {
+                merge_object(slot, inner, &key, source, origins)?;
+                continue;
+            }
+        }
+        if !same_kind(slot, value) {
+            return Err(key_error(
+                &key,
+                source,
+                format!("需要{}，实际为{}", type_name(slot), type_name(value)),
+            ));
+        }
+        // 小数项写成整数时仍存成小数，之后的覆盖照样可以写小数
+        *slot = match value.as_f64() {
+            Some(float) if slot.is_f64() => Value::from(float),
+            _ => value.clone(),
+        };
+        origins.push(key);
+    }
+    Ok(())
+}
@@ -1,7 +1,22 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::path::PathBuf;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::logging::LogDirective;
+
+mod layers;
+mod paths;
+mod reload;
+mod systems;
+mod user;
+
+pub use layers::*;
+pub use paths::*;
+pub use reload::*;
+pub use systems::SettingsPlugin;
+pub use user::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowSettings {
     pub title: String,
     pub width: u32,
@@ -10,86 +25,623 @@ pub struct WindowSettings {
     pub vsync: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            title: "侠义江湖".to_string(),
+            width: 1280,
+            height: 720,
+            fullscreen: false,
+            vsync: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphicsSettings {
     pub render_distance: u32,
     pub shadow_quality: String,
     pub particle_limit: u32,
     pub debug_rendering: bool,
+    /// 灯火等动态光照，低端机器上可关闭
+    #[serde(default = "default_dynamic_lighting")]
+    pub dynamic_lighting: bool,
+    /// 地形贴图，关闭时地形画成纯色方块
+    #[serde(default = "default_tile_textures")]
+    pub tile_textures: bool,
+}
+
+fn default_dynamic_lighting() -> bool {
+    true
+}
+
+fn default_tile_textures() -> bool {
+    true
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self {
+            render_distance: 800,
+            shadow_quality: "medium".to_string(),
+            particle_limit: 5000,
+            debug_rendering: false,
+            dynamic_lighting: true,
+            tile_textures: true,
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// 画质档位，依次为低、中、高
+pub const GRAPHICS_QUALITY_LEVELS: [&str; 3] = ["low", "medium", "high"];
+
+impl GraphicsSettings {
+    /// 按画质档位设置阴影、粒子上限、视距与动态光照，未知档位按中档处理
+    pub fn apply_quality(&mut self, quality: &str) {
+        let (render_distance, particle_limit) = match quality {
+            "low" => (480, 1000),
+            "high" => (1280, 10000),
+            _ => (800, 5000),
+        };
+        self.shadow_quality = if GRAPHICS_QUALITY_LEVELS.contains(&quality) {
+            quality.to_string()
+        } else {
+            "medium".to_string()
+        };
+        self.render_distance = render_distance;
+        self.particle_limit = particle_limit;
+        self.dynamic_lighting = quality != "low";
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PhysicsSettings {
     pub timestep: f32,
     pub gravity: f32,
     pub debug_draw: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Default for PhysicsSettings {
+    fn default() -> Self {
+        Self {
+            timestep: 0.016,
+            gravity: -9.81,
+            debug_draw: false,
+        }
+    }
+}
+
+/// 网络选项
+///
+/// # 参数说明
+/// - tick_rate: 每秒分发服务器消息的次数
+/// - interpolation_delay: 远程实体插值的延迟（秒）
+/// - server_address: 游戏服务器地址
+/// - auto_connect: 启动后是否自动连接服务器
+/// - offline_login: 跳过登录直接进入主菜单，供离线游玩与开发使用
+/// - heartbeat_interval: 心跳间隔（秒）
+/// - timeout: 多久收不到任何消息视为断线（秒）
+/// - reconnect_delay: 首次重连前的等待（秒），之后每次翻倍
+/// - max_reconnect_delay: 重连等待的上限（秒）
+/// - reconnect_attempts: 连续重连失败多少次后放弃
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct NetworkSettings {
     pub tick_rate: u32,
     pub interpolation_delay: f32,
     pub debug_overlay: bool,
+    pub server_address: String,
+    pub auto_connect: bool,
+    pub offline_login: bool,
+    pub heartbeat_interval: f32,
+    pub timeout: f32,
+    pub reconnect_delay: f32,
+    pub max_reconnect_delay: f32,
+    pub reconnect_attempts: u32,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            tick_rate: 64,
+            interpolation_delay: 0.1,
+            debug_overlay: false,
+            server_address: "127.0.0.1:7600".to_string(),
+            auto_connect: false,
+            offline_login: false,
+            heartbeat_interval: 2.0,
+            timeout: 10.0,
+            reconnect_delay: 1.0,
+            max_reconnect_delay: 30.0,
+            reconnect_attempts: 5,
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoggingSettings {
     pub level: String,
     pub file_output: bool,
     pub console_output: bool,
+    /// 按模块的过滤规则，例如 `mmorpg_game::world::chunk=debug`，优先于默认级别
+    #[serde(default)]
+    pub filters: Vec<String>,
+    #[serde(default)]
+    pub rotation: LogRotationSettings,
+}
+
+/// 可用的日志级别
+pub const LOG_LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "verbose"];
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            file_output: true,
+            console_output: false,
+            filters: Vec::new(),
+            rotation: LogRotationSettings::default(),
+        }
+    }
+}
+
+/// 日志文件轮换，各项为 0 时不限制
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogRotationSettings {
+    /// 单个日志文件的大小上限（MB）
+    pub max_file_mb: u64,
+    /// 旧日志保留的天数
+    pub max_age_days: u64,
+    /// 旧日志保留的文件数
+    pub max_files: usize,
+    /// 是否压缩旧日志
+    pub compress: bool,
+}
+
+impl Default for LogRotationSettings {
+    fn default() -> Self {
+        Self {
+            max_file_mb: 10,
+            max_age_days: 14,
+            max_files: 20,
+            compress: true,
+        }
+    }
+}
+
+/// 声音选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    /// 总音量 (0.0-1.0)
+    pub master_volume: f32,
+    /// 背景音乐音量 (0.0-1.0)，与总音量相乘
+    pub music_volume: f32,
+    /// 音效音量 (0.0-1.0)，与总音量相乘
+    pub sfx_volume: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 0.8,
+            sfx_volume: 1.0,
+        }
+    }
+}
+
+/// 单个动作的改键，动作与按键都用名字记录，例如 `{"action": "Jump", "inputs": ["Space", "MouseRight"]}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BindingOverride {
+    pub action: String,
+    /// 为空表示该动作不绑定任何按键
+    pub inputs: Vec<String>,
+}
+
+/// 操作选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ControlsSettings {
+    /// 与默认按键不同的动作，由改键界面写入
+    pub bindings: Vec<BindingOverride>,
+    /// 手柄摇杆的死区 (0.0-1.0)，推动幅度小于它时不移动
+    pub stick_deadzone: f32,
+}
+
+impl Default for ControlsSettings {
+    fn default() -> Self {
+        Self {
+            bindings: Vec::new(),
+            stick_deadzone: 0.2,
+        }
+    }
+}
+
+/// 无障碍选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccessibilitySettings {
+    /// 显示对白字幕与重要声音提示
+    pub captions: bool,
+}
+
+/// 隐私选项
+///
+/// 控制收集哪些诊断数据，关闭的内容既不写入本地也不放进错误报告包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrivacySettings {
+    /// 把日志写入本地文件
+    pub file_logs: bool,
+    /// 允许记录试玩数据，试玩记录仍需在分析选项中单独开启
+    pub playtest_data: bool,
+    /// 错误报告包中附带操作系统等系统信息
+    pub system_info: bool,
+}
+
+impl Default for PrivacySettings {
+    fn default() -> Self {
+        Self {
+            file_logs: true,
+            playtest_data: true,
+            system_info: true,
+        }
+    }
+}
+
+/// 开发选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DevelopmentSettings {
+    /// 监听 assets 目录，文件修改后自动重新加载任务等资源
+    pub hot_reload: bool,
+    /// 开启性能分析面板与帧时间录制
+    pub profiler: bool,
+}
+
+/// 崩溃处理选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrashOptions {
+    /// 崩溃后弹出对话框告知崩溃报告的位置
+    pub show_dialog: bool,
+    /// 崩溃时把上次存档后的区块修改写成恢复存档
+    pub recover_chunks: bool,
+}
+
+impl Default for CrashOptions {
+    fn default() -> Self {
+        Self {
+            show_dialog: true,
+            recover_chunks: true,
+        }
+    }
+}
+
+/// 运行指标选项，默认关闭，数据只留在本机
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsOptions {
+    /// 开启指标采集
+    pub enabled: bool,
+    /// 本机 Prometheus 端点的端口，0 表示不开启
+    pub prometheus_port: u16,
+    /// JSON 记录的写出间隔（秒），0 表示不写
+    pub dump_interval: f32,
+    /// JSON 记录的目录
+    pub dump_dir: String,
+}
+
+impl Default for MetricsOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            prometheus_port: 9464,
+            dump_interval: 60.0,
+            dump_dir: "metrics".to_string(),
+        }
+    }
 }
 
-pub struct RenderingMode {
-    pub isometric_enabled: bool,
-    pub isometric_angle: f32,
-    pub shadow_enabled: bool,
-    pub z_scale: f32,
+/// 联机选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CoopOptions {
+    /// 联机时显示的名字
+    pub player_name: String,
+    /// 开设会话时监听的端口
+    pub port: u16,
+    /// 含主机在内的人数上限
+    pub max_players: usize,
+    /// 任务共享规则：host_only、shared 或 individual
+    pub quest_share: String,
+    /// 允许客人进入旁观模式
+    pub allow_spectators: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Default for CoopOptions {
+    fn default() -> Self {
+        Self {
+            player_name: "侠客".to_string(),
+            port: 7457,
+            max_players: 2,
+            quest_share: "host_only".to_string(),
+            allow_spectators: false,
+        }
+    }
+}
+
+/// 自动存档选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutosaveOptions {
+    pub enabled: bool,
+    /// 定时自动存档的间隔（秒），0 为不定时存档
+    pub interval: f32,
+    /// 进入场景时存档
+    pub on_scene_enter: bool,
+    /// 首领战开始前存档
+    pub before_boss: bool,
+    /// 轮流覆盖的自动存档槽数量
+    pub slots: usize,
+}
+
+impl Default for AutosaveOptions {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: 600.0,
+            on_scene_enter: true,
+            before_boss: true,
+            slots: 3,
+        }
+    }
+}
+
+/// 界面选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InterfaceSettings {
+    /// 界面主题编号，为空时使用主题数据中的默认主题
+    #[serde(default)]
+    pub theme: String,
+}
+
+/// 试玩数据选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnalyticsSettings {
+    /// 记录试玩数据到本地文件，供平衡性分析
+    pub playtest: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GameSettings {
     pub window: WindowSettings,
     pub graphics: GraphicsSettings,
     pub physics: PhysicsSettings,
     pub network: NetworkSettings,
     pub logging: LoggingSettings,
+    #[serde(default)]
+    pub audio: AudioSettings,
+    #[serde(default)]
+    pub controls: ControlsSettings,
+    #[serde(default)]
+    pub accessibility: AccessibilitySettings,
+    #[serde(default)]
+    pub analytics: AnalyticsSettings,
+    #[serde(default)]
+    pub interface: InterfaceSettings,
+    #[serde(default)]
+    pub privacy: PrivacySettings,
+    #[serde(default)]
+    pub coop: CoopOptions,
+    #[serde(default)]
+    pub development: DevelopmentSettings,
+    #[serde(default)]
+    pub autosave: AutosaveOptions,
+    #[serde(default)]
+    pub crash: CrashOptions,
+    #[serde(default)]
+    pub metrics: MetricsOptions,
 }
 
 impl GameSettings {
-    /// 从指定路径加载游戏配置
-    pub fn load(config_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let config_file = fs::read_to_string(config_path)?;
-        let settings: GameSettings = serde_json::from_str(&config_file)?;
-        Ok(settings)
-    }
-
-    /// 加载调试配置
-    pub fn load_debug() -> Result<Self, Box<dyn std::error::Error>> {
-        Self::load("src/config/debug/game_settings.json")
-    }
-
-    /// 加载开发配置
-    pub fn load_dev() -> Result<Self, Box<dyn std::error::Error>> {
-        Self::load("src/config/dev/game_settings.json")
+    /// 检查取值范围，返回第一个不合法的配置项及原因
+    pub fn validate(&self) -> Result<(), (&'static str, String)> {
+        if self.window.width == 0 || self.window.height == 0 {
+            let key = if self.window.width == 0 {
+                "window.width"
+            } else {
+                "window.height"
+            };
+            return Err((key, "窗口尺寸必须大于 0".to_string()));
+        }
+        if !GRAPHICS_QUALITY_LEVELS.contains(&self.graphics.shadow_quality.as_str()) {
+            return Err((
+                "graphics.shadow_quality",
+                format!("可选值为 {}", GRAPHICS_QUALITY_LEVELS.join("、")),
+            ));
+        }
+        if self.physics.timestep <= 0.0 {
+            return Err(("physics.timestep", "必须大于 0".to_string()));
+        }
+        if self.network.tick_rate == 0 {
+            return Err(("network.tick_rate", "必须大于 0".to_string()));
+        }
+        if !LOG_LEVELS.contains(&self.logging.level.to_lowercase().as_str()) {
+            return Err((
+                "logging.level",
+                format!("可选值为 {}", LOG_LEVELS.join("、")),
+            ));
+        }
+        for filter in &self.logging.filters {
+            if let Err(e) = LogDirective::parse(filter) {
+                return Err(("logging.filters", e));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.audio.master_volume) {
+            return Err(("audio.master_volume", "取值范围为 0 到 1".to_string()));
+        }
+        if !(0.0..1.0).contains(&self.controls.stick_deadzone) {
+            return Err((
+                "controls.stick_deadzone",
+                "取值范围为 0 到 1，不含 1".to_string(),
+            ));
+        }
+        if self.autosave.interval < 0.0 {
+            return Err(("autosave.interval", "不能为负数".to_string()));
+        }
+        if self.autosave.slots == 0 {
+            return Err(("autosave.slots", "至少需要 1 个存档槽".to_string()));
+        }
+        if self.metrics.dump_interval < 0.0 {
+            return Err(("metrics.dump_interval", "不能为负数".to_string()));
+        }
+        Ok(())
     }
 }
 
+/// 运行模式对应的配置
+///
+/// - Debug、Dev：开发时使用，可直接读取源码目录下的配置
+/// - Pre：发布前的测试包，配置同发布版，日志更详细
+/// - Release：发布版
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConfigType {
     Debug,
     Dev,
+    Pre,
+    Release,
 }
 
+impl ConfigType {
+    /// 配置目录名
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConfigType::Debug => "debug",
+            ConfigType::Dev => "dev",
+            ConfigType::Pre => "pre",
+            ConfigType::Release => "release",
+        }
+    }
+
+    /// 编译进程序的配置，打包后找不到外部配置文件时照样可以启动
+    pub fn embedded_settings(&self) -> &'static str {
+        match self {
+            ConfigType::Debug => include_str!("debug/game_settings.json"),
+            ConfigType::Dev => include_str!("dev/game_settings.json"),
+            ConfigType::Pre => include_str!("pre/game_settings.json"),
+            ConfigType::Release => include_str!("release/game_settings.json"),
+        }
+    }
+
+    /// 外部配置文件，按以下顺序取第一个存在的：
+    /// 1. 可执行文件旁的 `assets/config/<模式>/game_settings.json`
+    /// 2. 开发模式下当前目录的 `src/config/<模式>/game_settings.json`
+    pub fn settings_path(&self) -> Option<PathBuf> {
+        let relative = PathBuf::from(self.name()).join(SETTINGS_FILE);
+        let mut candidates: Vec<PathBuf> = external_config_dir()
+            .map(|dir| dir.join(&relative))
+            .into_iter()
+            .collect();
+        if matches!(self, ConfigType::Debug | ConfigType::Dev) {
+            candidates.push(PathBuf::from(SOURCE_CONFIG_DIR).join(&relative));
+        }
+        candidates.into_iter().find(|path| path.is_file())
+    }
+}
+
+/// 游戏配置管理
+///
+/// # 设计思路
+/// 配置按以下顺序逐层覆盖，后面的优先：
+/// 1. 编译进程序的默认值，配置文件缺项时由它补齐
+/// 2. 编译进程序的该模式配置
+/// 3. 该模式的外部配置文件，见 [`ConfigType::settings_path`]
+/// 4. 用户配置目录下的配置文件，即选项菜单保存的设置；读不出来时跳过，不影响启动
+/// 5. 以 `CHIVALRY_` 开头的环境变量，例如 `CHIVALRY_WINDOW__WIDTH=1920`
+/// 6. 命令行参数，例如 `--window.width=1920`
+///
+/// 除用户配置外，任何一层出错都会中止启动，错误信息指出配置项与来源。
+/// 运行中重新加载时按同样的顺序再合并一遍，环境变量与命令行参数依旧优先
 pub struct ConfigManager {
+    config_type: ConfigType,
+    cli_overrides: Vec<ConfigOverride>,
     settings: GameSettings,
 }
 
 impl ConfigManager {
-    pub fn new(config_type: ConfigType) -> Result<Self, Box<dyn std::error::Error>> {
-        let settings = match config_type {
-            ConfigType::Debug => GameSettings::load_debug()?,
-            ConfigType::Dev => GameSettings::load_dev()?,
-        };
-        Ok(Self { settings })
+    pub fn new(
+        config_type: ConfigType,
+        cli_overrides: Vec<ConfigOverride>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let settings = Self::load(config_type, &cli_overrides)?;
+        Ok(Self {
+            config_type,
+            cli_overrides,
+            settings,
+        })
+    }
+
+    /// 重新合并各层配置，出错时保留原来的配置
+    pub fn reload(&mut self) -> Result<&GameSettings, Box<dyn std::error::Error>> {
+        self.settings = Self::load(self.config_type, &self.cli_overrides)?;
+        Ok(&self.settings)
+    }
+
+    /// 当前运行模式的外部配置文件
+    pub fn settings_path(&self) -> Option<PathBuf> {
+        self.config_type.settings_path()
+    }
+
+    pub fn config_type(&self) -> ConfigType {
+        self.config_type
+    }
+
+    fn load(
+        config_type: ConfigType,
+        cli_overrides: &[ConfigOverride],
+    ) -> Result<GameSettings, Box<dyn std::error::Error>> {
+        let mut config = LayeredConfig::new(serde_json::to_value(GameSettings::default())?);
+
+        let source = ConfigSource::Embedded(config_type.name());
+        let layer = serde_json::from_str(config_type.embedded_settings())
+            .map_err(|e| format!("{} 不是有效的 JSON: {}", source, e))?;
+        config.merge(&layer, &source)?;
+
+        if let Some(mode_path) = config_type.settings_path() {
+            let content = fs::read_to_string(&mode_path)
+                .map_err(|e| format!("读取配置文件 {} 失败: {}", mode_path.display(), e))?;
+            let source = ConfigSource::ModeFile(mode_path);
+            let layer = serde_json::from_str(&content)
+                .map_err(|e| format!("{} 不是有效的 JSON: {}", source, e))?;
+            config.merge(&layer, &source)?;
+        }
+
+        // 用户配置坏了不应让游戏无法启动，合并失败时整层跳过
+        if let Some(path) = user_settings_path().filter(|path| path.is_file()) {
+            let source = ConfigSource::UserFile(path.clone());
+            let mut merged = config.clone();
+            let result = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+                .and_then(|layer| merged.merge(&layer, &source));
+            match result {
+                Ok(()) => config = merged,
+                Err(e) => eprintln!("{} 读取失败，已忽略: {}", source, e),
+            }
+        }
+
+        for entry in env_overrides().iter().chain(cli_overrides) {
+            config.set(entry)?;
+        }
+
+        let settings: GameSettings = serde_json::from_value(config.value().clone())
+            .map_err(|e| format!("配置无效: {}", e))?;
+        settings.validate().map_err(|(key, reason)| {
+            format!("配置项 {}（来自{}）: {}", key, config.origin(key), reason)
+        })?;
+        Ok(settings)
     }
 
     pub fn get_settings(&self) -> &GameSettings {
@@ -0,0 +1,43 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// 游戏配置文件名，各运行模式目录与用户配置目录下同名
+pub const SETTINGS_FILE: &str = "game_settings.json";
+
+/// 可执行文件旁的外部配置目录
+pub const EXTERNAL_CONFIG_DIR: &str = "assets/config";
+
+/// 源码中的配置目录，只在开发时从仓库目录运行才用得到
+pub const SOURCE_CONFIG_DIR: &str = "src/config";
+
+/// 可执行文件所在目录
+pub fn executable_dir() -> Option<PathBuf> {
+    env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(PathBuf::from))
+}
+
+/// 可执行文件旁的外部配置目录，打包后随游戏发布的配置放在这里
+pub fn external_config_dir() -> Option<PathBuf> {
+    executable_dir().map(|dir| dir.join(EXTERNAL_CONFIG_DIR))
+}
+
+/// 解析源码配置目录下的数据文件路径，物品、技能、商铺等数据文件都经由这里读取
+///
+/// `src/config/...` 形式的路径按以下顺序取第一个存在的：
+/// 1. 可执行文件旁的 `assets/config/...`，打包后随游戏发布的数据放在这里
+/// 2. 当前目录下的原路径，从仓库目录运行时使用
+///
+/// 都不存在或不在源码配置目录下时原样返回，读取失败的错误信息指向原路径
+pub fn resolve_data_path(path: &str) -> String {
+    let Ok(relative) = Path::new(path).strip_prefix(SOURCE_CONFIG_DIR) else {
+        return path.to_string();
+    };
+    external_config_dir()
+        .map(|dir| dir.join(relative))
+        .filter(|external| external.is_file())
+        .map_or_else(
+            || path.to_string(),
+            |external| external.to_string_lossy().into_owned(),
+        )
+}
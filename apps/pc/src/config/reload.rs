@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::{ConfigManager, Settings};
+use crate::logging::{GameLogger, LogDirective, LogLevel, LogRotation};
+use crate::resources::GlobalGameState;
+
+/// 检查配置文件修改时间的间隔（秒）
+const WATCH_INTERVAL: f32 = 0.5;
+
+/// 配置文件重新加载完成
+#[derive(Event, Debug, Clone)]
+pub struct SettingsReloaded;
+
+/// 监听当前运行模式的配置文件
+///
+/// 只在开启热重载且找得到外部配置文件时插入，文件修改后按启动时的顺序重新合并各层配置
+#[derive(Resource)]
+pub struct SettingsWatcher {
+    config: ConfigManager,
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    timer: Timer,
+}
+
+impl SettingsWatcher {
+    /// 没有外部配置文件可监听时返回 `None`
+    pub fn new(config: ConfigManager) -> Option<Self> {
+        let path = config.settings_path()?;
+        let modified = modified_time(&path);
+        Some(Self {
+            config,
+            path,
+            modified,
+            timer: Timer::from_seconds(WATCH_INTERVAL, TimerMode::Repeating),
+        })
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// 配置文件修改后重新加载
+///
+/// 用修改时间轮询，不依赖平台的文件通知；编辑器保存到一半读到坏文件时只记录错误，
+/// 保存完成后修改时间再次变化会重新加载
+pub fn watch_settings_file(
+    time: Res<Time>,
+    watcher: Option<ResMut<SettingsWatcher>>,
+    mut settings: ResMut<Settings>,
+    mut reloaded: EventWriter<SettingsReloaded>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let Some(mut watcher) = watcher else {
+        return;
+    };
+    if !watcher.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let modified = modified_time(&watcher.path);
+    if modified.is_none() || modified == watcher.modified {
+        return;
+    }
+    watcher.modified = modified;
+
+    match watcher.config.reload() {
+        Ok(game) => {
+            settings.replace(game.clone());
+            reloaded.send(SettingsReloaded);
+            if let Some(logger) = logger.as_mut() {
+                logger.log(
+                    LogLevel::Info,
+                    &format!("已重新加载配置 {}", watcher.path.display()),
+                );
+            }
+        }
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(
+                    LogLevel::Error,
+                    &format!("配置重新加载失败，沿用原配置: {}", e),
+                );
+            }
+        }
+    }
+}
+
+/// 设置变化时套用日志选项
+///
+/// 启动后的第一帧也会套用一次；隐私选项不允许时不写文件日志
+pub fn apply_logging_settings(settings: Res<Settings>, logger: Option<ResMut<GameLogger>>) {
+    let Some(mut logger) = logger else {
+        return;
+    };
+    if !settings.is_changed() {
+        return;
+    }
+    let game = &settings.game;
+    logger.set_min_level(LogLevel::from_str(&game.logging.level));
+    // 配置检查已拒绝格式不对的规则
+    logger.set_filters(
+        game.logging
+            .filters
+            .iter()
+            .filter_map(|filter| LogDirective::parse(filter).ok())
+            .collect(),
+    );
+    logger.set_console_output(game.logging.console_output);
+    let rotation = &game.logging.rotation;
+    logger.set_rotation(LogRotation {
+        max_file_size: rotation.max_file_mb * 1024 * 1024,
+        max_age_days: rotation.max_age_days,
+        max_files: rotation.max_files,
+        compress: rotation.compress,
+    });
+    let file_output = game.logging.file_output && game.privacy.file_logs;
+    // 每次打开文件日志都会重新打开文件，没有变化时不动
+    if logger.file_output() != file_output {
+        logger.set_file_output(file_output);
+    }
+}
+
+/// 重新加载后套用只在启动时读取的选项
+pub fn apply_reloaded_settings(
+    mut reloaded: EventReader<SettingsReloaded>,
+    settings: Res<Settings>,
+    mut state: ResMut<GlobalGameState>,
+) {
+    if reloaded.read().count() == 0 {
+        return;
+    }
+    state.is_debug = settings.game.graphics.debug_rendering;
+}
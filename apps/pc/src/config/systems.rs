@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+
+use super::{
+    apply_logging_settings, apply_reloaded_settings, watch_settings_file, SettingsReloaded,
+};
+
+/// 设置插件
+///
+/// # 设计思路
+/// 1. 运行时的设置都集中在 `Settings` 资源，选项菜单与热重载只改这一处
+/// 2. 窗口、音量与视距由界面插件在资源变化时套用，这里只负责日志与调试开关
+/// 3. 配置文件的监听由是否插入 `SettingsWatcher` 决定，发布版不插入即不监听
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SettingsReloaded>().add_systems(
+            Update,
+            (
+                watch_settings_file,
+                apply_reloaded_settings,
+                apply_logging_settings,
+            )
+                .chain(),
+        );
+    }
+}
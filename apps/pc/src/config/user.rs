@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+use serde_json::{Map, Value};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use super::{GameSettings, SETTINGS_FILE};
+
+/// 用户配置目录下的应用目录名
+const APP_DIR_NAME: &str = "chivalry";
+
+/// 平台的用户配置目录
+///
+/// Windows 为 `%APPDATA%`，macOS 为 `~/Library/Application Support`，
+/// 其余平台为 `$XDG_CONFIG_HOME`，未设置时为 `~/.config`
+pub fn user_config_dir() -> Option<PathBuf> {
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    base.map(|base| base.join(APP_DIR_NAME))
+}
+
+/// 用户配置文件路径
+pub fn user_settings_path() -> Option<PathBuf> {
+    user_config_dir().map(|dir| dir.join(SETTINGS_FILE))
+}
+
+/// 运行时的游戏设置
+///
+/// # 设计思路
+/// 1. 启动时由配置文件生成，选项菜单直接修改这里，各系统在资源变化时套用
+/// 2. 修改只写入用户配置目录，不改动随游戏发布的配置文件
+/// 3. 用户配置只记玩家改过的配置项，没改的继续跟随默认值与模式配置，环境变量与命令行参数的临时覆盖也不会写进去
+/// 4. 找不到用户配置目录时照常运行，只是设置不会保留到下次启动
+#[derive(Resource, Debug, Clone)]
+pub struct Settings {
+    pub game: GameSettings,
+    /// 保存的位置，为空时不保存
+    pub user_path: Option<PathBuf>,
+    /// 上次加载或保存时的设置，与它不同的配置项才算玩家改过
+    baseline: GameSettings,
+}
+
+impl Settings {
+    pub fn new(game: GameSettings) -> Self {
+        Self {
+            baseline: game.clone(),
+            game,
+            user_path: user_settings_path(),
+        }
+    }
+
+    /// 换成重新加载的配置，之后以它为准判断哪些配置项改过
+    pub fn replace(&mut self, game: GameSettings) {
+        self.baseline = game.clone();
+        self.game = game;
+    }
+
+    /// 把改过的配置项写入用户配置
+    ///
+    /// 用户配置里原有的配置项保留，读不出来的用户配置整个换掉
+    pub fn save(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(path) = &self.user_path else {
+            return Err("找不到用户配置目录".into());
+        };
+        let mut layer = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            .filter(Value::is_object)
+            .unwrap_or_else(|| Value::Object(Map::new()));
+        let changes = changed_values(
+            &serde_json::to_value(&self.game)?,
+            &serde_json::to_value(&self.baseline)?,
+        );
+        if let Some(changes) = &changes {
+            overlay(&mut layer, changes);
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&layer)?)?;
+        self.baseline = self.game.clone();
+        Ok(())
+    }
+}
+
+/// 找出与基准不同的配置项，对象逐项比较，其余的值整体比较
+fn changed_values(current: &Value, baseline: &Value) -> Option<Value> {
+    match (current, baseline) {
+        (Value::Object(current), Value::Object(baseline)) => {
+            let changes: Map<String, Value> = current
+                .iter()
+                .filter_map(|(name, value)| {
+                    let changed = match baseline.get(name) {
+                        Some(old) => changed_values(value, old)?,
+                        None => value.clone(),
+                    };
+                    Some((name.clone(), changed))
+                })
+                .collect();
+            (!changes.is_empty()).then_some(Value::Object(changes))
+        }
+        _ => (current != baseline).then(|| current.clone()),
+    }
+}
+
+/// 把改过的配置项写进用户配置，同一组里没改的配置项保持原样
+fn overlay(target: &mut Value, changes: &Value) {
+    let (Value::Object(target), Value::Object(changes)) = (target, changes) else {
+        return;
+    };
+    for (name, value) in changes {
+        match target.get_mut(name) {
+            Some(slot) if slot.is_object() && value.is_object() => overlay(slot, value),
+            _ => {
+                target.insert(name.clone(), value.clone());
+            }
+        }
+    }
+}
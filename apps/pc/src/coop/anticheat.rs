@@ -0,0 +1,117 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use super::{CoopMessage, CoopSession, PeerId};
+use crate::events::input::GameAction;
+use crate::logging::{GameLogger, LogLevel};
+use crate::server::{
+    ClientAction, ClientId, ClientRequest, Moderation, ModerationEvent, ValidationContext,
+    ValidationPipeline, Verdict,
+};
+use crate::world::chunk::NavGrid;
+
+/// 主机对客人的反作弊校验
+///
+/// 联机中主机就是权威服务器：客人上报的状态先经校验管线再采纳与转发，
+/// 客人编号即校验与处罚使用的客户端编号，玩家名视为账号
+#[derive(SystemParam)]
+pub struct GuestValidation<'w> {
+    time: Res<'w, Time>,
+    pipeline: Option<ResMut<'w, ValidationPipeline>>,
+    moderation: Option<ResMut<'w, Moderation>>,
+    nav_grid: Option<Res<'w, NavGrid>>,
+}
+
+impl GuestValidation<'_> {
+    /// 玩家名被封禁时返回拒绝加入的原因
+    pub fn ban_reason(&self, name: &str) -> Option<String> {
+        let ban = self.moderation.as_ref()?.active_ban(name)?;
+        Some(format!("已被封禁：{}", ban.reason))
+    }
+
+    /// 登记刚加入的客人，`spawn` 为主机安排的出生点
+    pub fn register(&mut self, peer: PeerId, name: &str, spawn: Vec2) {
+        if let Some(moderation) = self.moderation.as_mut() {
+            moderation.register_client(peer as ClientId, name);
+        }
+        let now = self.time.elapsed_secs_f64();
+        if let Some(pipeline) = self.pipeline.as_mut() {
+            pipeline.reset(peer as ClientId, spawn, now);
+        }
+    }
+
+    /// 客人离开后清理校验状态与账号登记
+    pub fn forget(&mut self, peer: PeerId) {
+        if let Some(pipeline) = self.pipeline.as_mut() {
+            pipeline.forget(peer as ClientId);
+        }
+        if let Some(moderation) = self.moderation.as_mut() {
+            moderation.unregister_client(peer as ClientId);
+        }
+    }
+
+    /// 校验客人上报的位置，`speed` 为其角色的基础移速
+    pub fn check_move(&mut self, peer: PeerId, position: Vec2, speed: f32) -> Verdict {
+        let timestamp = self.time.elapsed_secs_f64();
+        self.check(
+            peer,
+            ClientAction::Move {
+                position,
+                timestamp,
+            },
+            speed,
+        )
+    }
+
+    /// 校验客人发起的按键动作
+    pub fn check_action(&mut self, peer: PeerId, action: GameAction) -> Verdict {
+        self.check(peer, ClientAction::Action(action), 0.0)
+    }
+
+    fn check(&mut self, peer: PeerId, action: ClientAction, speed: f32) -> Verdict {
+        let Some(pipeline) = self.pipeline.as_mut() else {
+            return Verdict::Accept;
+        };
+        pipeline.check(
+            &ClientRequest {
+                client: peer as ClientId,
+                action,
+            },
+            &ValidationContext {
+                now: self.time.elapsed_secs_f64(),
+                nav_grid: self.nav_grid.as_deref(),
+                speed,
+            },
+        )
+    }
+}
+
+/// 被反作弊处罚的客人：告知原因后断开，角色由主机的断线处理移除
+pub fn disconnect_punished_guests(
+    session: Res<CoopSession>,
+    mut events: EventReader<ModerationEvent>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for event in events.read() {
+        let (client, reason) = match event {
+            ModerationEvent::Kicked { client, reason } => (*client, format!("被踢出：{}", reason)),
+            ModerationEvent::Banned { client, reason } => (*client, format!("被封禁：{}", reason)),
+        };
+        let Some(peer) = PeerId::try_from(client)
+            .ok()
+            .and_then(|peer| session.peers.get(&peer))
+        else {
+            continue;
+        };
+        let Some(connection) = &peer.connection else {
+            continue;
+        };
+        connection.send(&CoopMessage::Reject {
+            reason: reason.clone(),
+        });
+        connection.close();
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Info, &format!("{} {}", peer.name, reason));
+        }
+    }
+}
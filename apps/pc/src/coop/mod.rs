@@ -0,0 +1,28 @@
+/// 联机模块
+///
+/// 局域网内的小规模联机：主机开设会话并广播，其他玩家搜索或输入地址加入，
+/// 共享主机的世界，各自控制自己的角色
+///
+/// # 模块组成
+/// 1. protocol：联机消息与会话广播的格式
+/// 2. transport：TCP 连接、监听与 UDP 会话广播，均在后台线程收发
+/// 3. session：会话状态、联机设置与任务共享规则
+/// 4. profile：客人的角色档案，离开时写回
+/// 5. systems：联机插件与各项系统
+/// 6. anticheat：开启 `server` 特性时主机对客人的反作弊校验
+#[cfg(feature = "server")]
+mod anticheat;
+mod profile;
+mod protocol;
+mod session;
+mod systems;
+mod transport;
+
+pub use profile::*;
+pub use protocol::*;
+pub use session::*;
+pub use systems::CoopPlugin;
+pub use transport::*;
+
+#[cfg(feature = "server")]
+use anticheat::{disconnect_punished_guests, GuestValidation};
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::items::{Equipment, Inventory};
+use crate::world::entity::{Character, Player};
+
+/// 以客人身份联机时的角色存档
+pub const COOP_PROFILE_PATH: &str = "saves/coop_profile.json";
+
+/// 客人的角色档案
+///
+/// # 设计思路
+/// 1. 世界属于主机，客人只带着自己的角色进出，加入时读取档案覆盖本地角色
+/// 2. 离开、断线或退出游戏时把角色写回档案，下次加入任意主机都能接着玩
+/// 3. 位置不保存，每次加入都出现在主机指定的出生点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoopProfile {
+    pub name: String,
+    pub level: u32,
+    pub experience: u32,
+    pub skill_points: u32,
+    pub health: f32,
+    pub max_health: f32,
+    pub qi: f32,
+    pub max_qi: f32,
+    #[serde(default)]
+    pub inventory: Inventory,
+    #[serde(default)]
+    pub equipment: Equipment,
+}
+
+impl CoopProfile {
+    /// 记录本地角色的当前状态
+    pub fn capture(
+        player: &Player,
+        character: &Character,
+        inventory: Option<&Inventory>,
+        equipment: Option<&Equipment>,
+    ) -> Self {
+        Self {
+            name: character.name.clone(),
+            level: player.level,
+            experience: player.experience,
+            skill_points: player.skill_points,
+            health: character.health,
+            max_health: character.max_health,
+            qi: character.qi,
+            max_qi: character.max_qi,
+            inventory: inventory.cloned().unwrap_or_default(),
+            equipment: equipment.cloned().unwrap_or_default(),
+        }
+    }
+
+    /// 把档案应用到本地角色
+    pub fn apply(
+        &self,
+        player: &mut Player,
+        character: &mut Character,
+        inventory: Option<&mut Inventory>,
+        equipment: Option<&mut Equipment>,
+    ) {
+        player.level = self.level;
+        player.experience = self.experience;
+        player.skill_points = self.skill_points;
+        character.name = self.name.clone();
+        character.max_health = self.max_health;
+        // 阵亡时离开的角色以少量气血重新加入
+        character.health = self.health.clamp(1.0, self.max_health);
+        character.max_qi = self.max_qi;
+        character.qi = self.qi.clamp(0.0, self.max_qi);
+        if let Some(inventory) = inventory {
+            *inventory = self.inventory.clone();
+        }
+        if let Some(equipment) = equipment {
+            *equipment = self.equipment.clone();
+        }
+    }
+
+    /// 读取档案
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 写入档案
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+
+use super::QuestShareRule;
+use crate::world::entity::CharacterState;
+
+/// 主机监听联机连接的默认端口
+pub const COOP_PORT: u16 = 7457;
+/// 主机广播会话、客人搜索会话的端口
+pub const DISCOVERY_PORT: u16 = 7458;
+/// 联机协议版本，双方不一致时拒绝加入
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// 联机中玩家的编号，主机固定为 0
+pub type PeerId = u32;
+
+/// 主机自己的编号
+pub const HOST_PEER: PeerId = 0;
+
+/// 会话中一名玩家的公开信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerInfo {
+    pub peer: PeerId,
+    pub name: String,
+    pub position: [f32; 2],
+}
+
+/// 联机消息
+///
+/// 每条消息序列化为一行 JSON，经 TCP 连接收发；
+/// 客人只和主机通信，其他客人的消息由主机转发
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CoopMessage {
+    /// 客人请求加入
+    Hello { version: u32, name: String },
+    /// 主机接受加入，附带世界种子与会话规则
+    Welcome {
+        peer: PeerId,
+        host_name: String,
+        seed: u32,
+        spawn: [f32; 2],
+        quest_share: QuestShareRule,
+        /// 是否允许客人进入旁观模式
+        #[serde(default)]
+        allow_spectate: bool,
+        peers: Vec<PeerInfo>,
+    },
+    /// 主机拒绝加入
+    Reject { reason: String },
+    /// 有玩家中途加入
+    PlayerJoined(PeerInfo),
+    /// 有玩家离开
+    PlayerLeft { peer: PeerId },
+    /// 玩家位置与状态，客人发送时编号由主机填写
+    PlayerState {
+        peer: PeerId,
+        position: [f32; 2],
+        direction: [f32; 2],
+        health: f32,
+        max_health: f32,
+        state: CharacterState,
+    },
+    /// 主机不采纳客人上报的位置，客人退回到这里
+    Correction { position: [f32; 2] },
+    /// 任务进度
+    QuestProgress {
+        peer: PeerId,
+        quest_id: String,
+        stage: u32,
+        completed: bool,
+    },
+    /// 主动离开会话
+    Leave,
+}
+
+/// 主机在局域网内广播的会话信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionAnnouncement {
+    pub version: u32,
+    pub host_name: String,
+    /// 主机接受连接的 TCP 端口
+    pub port: u16,
+    pub players: usize,
+    pub max_players: usize,
+}
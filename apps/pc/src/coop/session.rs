@@ -0,0 +1,238 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+use super::{
+    CoopConnection, CoopConnector, CoopListener, CoopMessage, PeerId, SessionBeacon,
+    SessionBrowser, COOP_PORT, HOST_PEER,
+};
+
+/// 多久没收到广播的会话从列表中移除（秒）
+const SESSION_EXPIRY: f64 = 5.0;
+
+/// 任务进度的共享规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestShareRule {
+    /// 只有主机推进任务，客人跟随主机的进度
+    #[default]
+    HostOnly,
+    /// 任何一方推进任务，所有人同步
+    Shared,
+    /// 各自完成自己的任务，互不同步
+    Individual,
+}
+
+impl QuestShareRule {
+    /// 按配置中的名字取得规则
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "host_only" => Some(QuestShareRule::HostOnly),
+            "shared" => Some(QuestShareRule::Shared),
+            "individual" => Some(QuestShareRule::Individual),
+            _ => None,
+        }
+    }
+
+    /// 某个玩家推进的任务进度是否同步给其他人
+    pub fn shares_from(&self, peer: PeerId) -> bool {
+        match self {
+            QuestShareRule::HostOnly => peer == HOST_PEER,
+            QuestShareRule::Shared => true,
+            QuestShareRule::Individual => false,
+        }
+    }
+}
+
+/// 联机设置
+///
+/// # 参数说明
+/// - player_name: 在会话列表与其他玩家头顶显示的名字
+/// - port: 主机监听的端口
+/// - max_players: 含主机在内的人数上限
+/// - sync_interval: 同步位置的间隔（秒）
+/// - quest_share: 主机开设会话时使用的任务共享规则
+/// - allow_spectators: 主机是否允许客人进入旁观模式
+#[derive(Resource, Debug, Clone)]
+pub struct CoopSettings {
+    pub player_name: String,
+    pub port: u16,
+    pub max_players: usize,
+    pub sync_interval: f32,
+    pub quest_share: QuestShareRule,
+    pub allow_spectators: bool,
+}
+
+impl Default for CoopSettings {
+    fn default() -> Self {
+        Self {
+            player_name: "侠客".to_string(),
+            port: COOP_PORT,
+            max_players: 2,
+            sync_interval: 0.1,
+            quest_share: QuestShareRule::default(),
+            allow_spectators: false,
+        }
+    }
+}
+
+/// 联机指令
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub enum CoopCommand {
+    /// 开设会话
+    Host,
+    /// 加入指定地址的会话
+    Join(String),
+    /// 离开或关闭会话
+    Leave,
+    /// 开始搜索局域网内的会话
+    Browse,
+}
+
+/// 本地玩家推进了任务，由任务系统发出，联机模块按共享规则转发
+#[derive(Event, Debug, Clone)]
+pub struct LocalQuestProgress {
+    pub quest_id: String,
+    pub stage: u32,
+    pub completed: bool,
+}
+
+/// 其他玩家推进的任务进度，按共享规则应同步到本地
+#[derive(Event, Debug, Clone)]
+pub struct RemoteQuestProgress {
+    pub quest_id: String,
+    pub stage: u32,
+    pub completed: bool,
+}
+
+/// 本地在会话中的身份
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoopRole {
+    #[default]
+    Offline,
+    Hosting,
+    /// 正在连接主机，或已连上等待主机接受
+    Joining,
+    Joined,
+}
+
+/// 会话中的其他玩家
+pub struct CoopPeer {
+    pub name: String,
+    /// 与该玩家的直接连接；客人只连主机，其他客人的连接为空
+    pub connection: Option<CoopConnection>,
+    /// 代表该玩家的实体
+    pub entity: Option<Entity>,
+}
+
+/// 其他玩家在本地的角色
+#[derive(Component, Debug, Clone)]
+pub struct RemotePlayer {
+    /// 最近一次同步到的位置，角色平滑移动过去
+    pub target: Vec2,
+}
+
+/// 联机会话
+///
+/// # 设计思路
+/// 1. 主机拥有世界，客人加入时按主机的种子重新生成地形，两边的世界一致
+/// 2. 每个玩家在本地只控制自己的角色，其他玩家以远程角色显示，位置按间隔同步
+/// 3. 客人之间不直接连接，消息都经主机转发，小规模联机足够
+#[derive(Resource, Default)]
+pub struct CoopSession {
+    pub role: CoopRole,
+    /// 本地玩家的编号
+    pub local_peer: PeerId,
+    pub quest_share: QuestShareRule,
+    /// 主机是否允许本地玩家旁观，主机自己总是允许
+    pub allow_spectate: bool,
+    pub peers: HashMap<PeerId, CoopPeer>,
+    /// 已连上但还没有打招呼的客人
+    pub pending: Vec<CoopConnection>,
+    /// 客人正在后台连接主机
+    pub connector: Option<CoopConnector>,
+    pub listener: Option<CoopListener>,
+    pub beacon: Option<SessionBeacon>,
+    pub next_peer: PeerId,
+    /// 距离下次同步位置的时间（秒）
+    pub sync_timer: f32,
+}
+
+impl CoopSession {
+    /// 是否处于联机中
+    pub fn is_active(&self) -> bool {
+        self.role != CoopRole::Offline
+    }
+
+    /// 含本地玩家在内的人数
+    pub fn player_count(&self) -> usize {
+        self.peers.len() + 1
+    }
+
+    /// 分配新客人的编号
+    pub fn allocate_peer(&mut self) -> PeerId {
+        // 主机占用 0，客人从 1 开始编号
+        self.next_peer += 1;
+        self.next_peer
+    }
+
+    /// 发送给某个玩家，客人发给主机以外的玩家时由主机转发
+    pub fn send_to(&self, peer: PeerId, message: &CoopMessage) -> bool {
+        self.peers
+            .get(&peer)
+            .and_then(|peer| peer.connection.as_ref())
+            .is_some_and(|connection| connection.send(message))
+    }
+
+    /// 发送给所有直接连接的玩家
+    pub fn broadcast(&self, message: &CoopMessage, except: Option<PeerId>) {
+        for (peer, remote) in &self.peers {
+            if Some(*peer) == except {
+                continue;
+            }
+            if let Some(connection) = &remote.connection {
+                connection.send(message);
+            }
+        }
+    }
+
+    /// 断开所有连接并回到单机，返回需要移除的远程角色
+    pub fn reset(&mut self) -> Vec<Entity> {
+        let entities = self
+            .peers
+            .drain()
+            .filter_map(|(_, peer)| peer.entity)
+            .collect();
+        *self = Self::default();
+        entities
+    }
+
+    /// 更新会话广播中的人数
+    pub fn refresh_beacon(&self) {
+        if let Some(beacon) = &self.beacon {
+            beacon.set_players(self.player_count());
+        }
+    }
+}
+
+/// 会话列表
+#[derive(Resource, Default)]
+pub struct CoopSessionList {
+    pub browser: Option<SessionBrowser>,
+    /// 会话地址与最近一次收到广播的时间
+    sessions: HashMap<SocketAddr, f64>,
+}
+
+impl CoopSessionList {
+    /// 记录收到的广播，返回是否是新发现的会话
+    pub fn record(&mut self, address: SocketAddr, now: f64) -> bool {
+        self.sessions.insert(address, now).is_none()
+    }
+
+    /// 移除长时间没有广播的会话
+    pub fn prune(&mut self, now: f64) {
+        self.sessions
+            .retain(|_, last_seen| now - *last_seen <= SESSION_EXPIRY);
+    }
+}
@@ -0,0 +1,901 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+#[cfg(feature = "server")]
+use super::{disconnect_punished_guests, GuestValidation};
+use super::{
+    CoopCommand, CoopConnection, CoopConnector, CoopListener, CoopMessage, CoopPeer, CoopProfile,
+    CoopRole, CoopSession, CoopSessionList, CoopSettings, LocalQuestProgress, PeerId, PeerInfo,
+    RemotePlayer, RemoteQuestProgress, SessionAnnouncement, SessionBeacon, SessionBrowser,
+    COOP_PROFILE_PATH, HOST_PEER, PROTOCOL_VERSION,
+};
+#[cfg(feature = "server")]
+use crate::events::input::GameAction;
+use crate::items::{Equipment, Inventory};
+use crate::logging::{GameLogger, LogLevel};
+#[cfg(feature = "server")]
+use crate::server::Verdict;
+use crate::world::chunk::{ChunkManager, ChunkResident};
+use crate::world::entity::{spawn_character, Character, CharacterState, Player};
+use crate::world::map::MapManager;
+
+/// 远程角色向同步位置靠拢的速率
+const REMOTE_SMOOTHING: f32 = 12.0;
+/// 与同步位置相差超过这个距离时直接瞬移（像素）
+const REMOTE_SNAP_DISTANCE: f32 = 256.0;
+/// 远程角色的贴图
+const REMOTE_TEXTURE: &str = "textures/characters/player.png";
+
+/// 联机插件
+pub struct CoopPlugin;
+
+impl Plugin for CoopPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CoopSettings>()
+            .init_resource::<CoopSession>()
+            .init_resource::<CoopSessionList>()
+            .add_event::<CoopCommand>()
+            .add_event::<LocalQuestProgress>()
+            .add_event::<RemoteQuestProgress>()
+            .add_systems(
+                Update,
+                (
+                    handle_coop_commands,
+                    (accept_coop_guests, receive_as_host).run_if(is_hosting),
+                    (finish_connecting, receive_as_guest).run_if(is_guest),
+                    forward_local_quest_progress,
+                    broadcast_local_state,
+                    smooth_remote_players,
+                )
+                    .chain(),
+            )
+            .add_systems(Update, refresh_session_list)
+            .add_systems(Last, leave_on_exit);
+
+        // 服务器构建中被反作弊处罚的客人由主机断开
+        #[cfg(feature = "server")]
+        app.add_systems(
+            Update,
+            disconnect_punished_guests
+                .run_if(is_hosting)
+                .after(receive_as_host),
+        );
+    }
+}
+
+fn is_hosting(session: Res<CoopSession>) -> bool {
+    session.role == CoopRole::Hosting
+}
+
+fn is_guest(session: Res<CoopSession>) -> bool {
+    matches!(session.role, CoopRole::Joining | CoopRole::Joined)
+}
+
+fn log(logger: &mut Option<ResMut<GameLogger>>, level: LogLevel, message: &str) {
+    if let Some(logger) = logger.as_mut() {
+        logger.log(level, message);
+    }
+}
+
+/// 写回客人的角色档案
+fn save_profile(profile: &CoopProfile, logger: &mut Option<ResMut<GameLogger>>) {
+    match profile.save(COOP_PROFILE_PATH) {
+        Ok(()) => log(logger, LogLevel::Info, "联机角色已写回档案"),
+        Err(e) => log(
+            logger,
+            LogLevel::Error,
+            &format!("写回联机角色档案失败: {}", e),
+        ),
+    }
+}
+
+/// 生成代表其他玩家的角色
+fn spawn_remote_player(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    info: &PeerInfo,
+) -> Entity {
+    let position = Vec2::from_array(info.position);
+    let entity = spawn_character(
+        commands,
+        asset_server,
+        position.extend(0.0),
+        &info.name,
+        REMOTE_TEXTURE,
+    );
+    commands
+        .entity(entity)
+        .insert(RemotePlayer { target: position });
+    entity
+}
+
+/// 把同步到的状态应用到远程角色
+fn apply_remote_state(
+    remote: &mut RemotePlayer,
+    character: &mut Character,
+    position: [f32; 2],
+    direction: [f32; 2],
+    health: f32,
+    max_health: f32,
+    state: CharacterState,
+) {
+    remote.target = Vec2::from_array(position);
+    character.direction = Vec2::from_array(direction);
+    character.max_health = max_health;
+    character.health = health;
+    character.state = state;
+}
+
+/// 处理联机指令
+fn handle_coop_commands(
+    mut commands: Commands,
+    mut events: EventReader<CoopCommand>,
+    settings: Res<CoopSettings>,
+    mut session: ResMut<CoopSession>,
+    mut session_list: ResMut<CoopSessionList>,
+    local: Query<(&Player, &Character, Option<&Inventory>, Option<&Equipment>)>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for command in events.read() {
+        match command {
+            CoopCommand::Host => {
+                if session.is_active() {
+                    log(&mut logger, LogLevel::Info, "已在联机会话中，无法开设会话");
+                    continue;
+                }
+                let listener = match CoopListener::bind(settings.port) {
+                    Ok(listener) => listener,
+                    Err(e) => {
+                        log(
+                            &mut logger,
+                            LogLevel::Error,
+                            &format!("开设联机会话失败: {}", e),
+                        );
+                        continue;
+                    }
+                };
+                // 广播失败时客人仍可以直接输入地址加入
+                let beacon = SessionBeacon::start(SessionAnnouncement {
+                    version: PROTOCOL_VERSION,
+                    host_name: settings.player_name.clone(),
+                    port: listener.port(),
+                    players: 1,
+                    max_players: settings.max_players,
+                });
+                if let Err(e) = &beacon {
+                    log(
+                        &mut logger,
+                        LogLevel::Error,
+                        &format!("无法在局域网广播会话: {}", e),
+                    );
+                }
+                log(
+                    &mut logger,
+                    LogLevel::Info,
+                    &format!("已开设联机会话，端口 {}", listener.port()),
+                );
+
+                session.role = CoopRole::Hosting;
+                session.local_peer = HOST_PEER;
+                session.quest_share = settings.quest_share;
+                session.allow_spectate = true;
+                session.listener = Some(listener);
+                session.beacon = beacon.ok();
+            }
+            CoopCommand::Join(address) => {
+                if session.is_active() {
+                    log(
+                        &mut logger,
+                        LogLevel::Info,
+                        "已在联机会话中，无法加入其他会话",
+                    );
+                    continue;
+                }
+                session.role = CoopRole::Joining;
+                session.connector = Some(CoopConnector::start(address));
+                log(
+                    &mut logger,
+                    LogLevel::Info,
+                    &format!("正在连接 {} 的联机会话", address),
+                );
+            }
+            CoopCommand::Leave => {
+                match session.role {
+                    CoopRole::Offline => continue,
+                    CoopRole::Hosting => {
+                        session.broadcast(&CoopMessage::Leave, None);
+                        log(&mut logger, LogLevel::Info, "已关闭联机会话");
+                    }
+                    CoopRole::Joining | CoopRole::Joined => {
+                        session.send_to(HOST_PEER, &CoopMessage::Leave);
+                        if session.role == CoopRole::Joined {
+                            if let Ok((player, character, inventory, equipment)) =
+                                local.get_single()
+                            {
+                                let profile =
+                                    CoopProfile::capture(player, character, inventory, equipment);
+                                save_profile(&profile, &mut logger);
+                            }
+                        }
+                        log(&mut logger, LogLevel::Info, "已离开联机会话");
+                    }
+                }
+                for entity in session.reset() {
+                    commands.entity(entity).despawn_recursive();
+                }
+            }
+            CoopCommand::Browse => {
+                if session_list.browser.is_some() {
+                    continue;
+                }
+                match SessionBrowser::start() {
+                    Ok(browser) => {
+                        session_list.browser = Some(browser);
+                        log(&mut logger, LogLevel::Info, "正在搜索局域网内的联机会话");
+                    }
+                    Err(e) => log(
+                        &mut logger,
+                        LogLevel::Error,
+                        &format!("无法搜索联机会话: {}", e),
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// 客人连上主机后打招呼，连接失败时回到单机
+fn finish_connecting(
+    settings: Res<CoopSettings>,
+    mut session: ResMut<CoopSession>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let Some(connector) = &session.connector else {
+        return;
+    };
+    let Some(result) = connector.poll() else {
+        return;
+    };
+    let address = connector.address().to_string();
+    session.connector = None;
+
+    match result {
+        Ok(connection) => {
+            connection.send(&CoopMessage::Hello {
+                version: PROTOCOL_VERSION,
+                name: settings.player_name.clone(),
+            });
+            session.peers.insert(
+                HOST_PEER,
+                CoopPeer {
+                    name: address.clone(),
+                    connection: Some(connection),
+                    entity: None,
+                },
+            );
+            log(
+                &mut logger,
+                LogLevel::Info,
+                &format!("已连上主机，正在加入 {} 的联机会话", address),
+            );
+        }
+        Err(e) => {
+            session.reset();
+            log(
+                &mut logger,
+                LogLevel::Error,
+                &format!("连接主机 {} 失败: {}", address, e),
+            );
+        }
+    }
+}
+
+/// 主机接受新连接，等待对方打招呼
+fn accept_coop_guests(mut session: ResMut<CoopSession>, mut logger: Option<ResMut<GameLogger>>) {
+    let Some(listener) = &session.listener else {
+        return;
+    };
+    for stream in listener.drain() {
+        match CoopConnection::from_stream(stream) {
+            Ok(connection) => {
+                log(
+                    &mut logger,
+                    LogLevel::Debug,
+                    &format!("{} 连入联机会话", connection.address()),
+                );
+                session.pending.push(connection);
+            }
+            Err(e) => log(
+                &mut logger,
+                LogLevel::Error,
+                &format!("接受联机连接失败: {}", e),
+            ),
+        }
+    }
+}
+
+/// 主机处理客人的消息
+///
+/// 客人打招呼后分配编号并发送世界种子；客人的状态与任务进度转发给其他客人，
+/// 任务进度只在共享规则允许时生效；客人离开或断线时移除其角色。
+/// 服务器构建中客人的位置与攻击先经反作弊校验，位置被纠正时通知客人退回
+#[allow(clippy::too_many_arguments)]
+fn receive_as_host(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<CoopSettings>,
+    mut session: ResMut<CoopSession>,
+    map_manager: Option<Res<MapManager>>,
+    local: Query<&Transform, (With<Player>, Without<RemotePlayer>)>,
+    mut remotes: Query<(&mut RemotePlayer, &mut Character, &Transform)>,
+    mut quest_events: EventWriter<RemoteQuestProgress>,
+    #[cfg(feature = "server")] mut guests: GuestValidation,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let session = &mut *session;
+    let spawn = local
+        .get_single()
+        .map(|transform| transform.translation.truncate())
+        .unwrap_or(Vec2::ZERO);
+
+    for connection in std::mem::take(&mut session.pending) {
+        let hello = connection
+            .drain()
+            .into_iter()
+            .find_map(|message| match message {
+                CoopMessage::Hello { version, name } => Some((version, name)),
+                _ => None,
+            });
+        let Some((version, name)) = hello else {
+            if !connection.is_closed() {
+                session.pending.push(connection);
+            }
+            continue;
+        };
+
+        let reject = if version != PROTOCOL_VERSION {
+            Some(format!(
+                "版本不一致：主机 {}，客人 {}",
+                PROTOCOL_VERSION, version
+            ))
+        } else if session.player_count() >= settings.max_players {
+            Some("会话已满".to_string())
+        } else if map_manager.is_none() {
+            Some("主机的世界尚未生成".to_string())
+        } else {
+            None
+        };
+        #[cfg(feature = "server")]
+        let reject = reject.or_else(|| guests.ban_reason(&name));
+        if let Some(reason) = reject {
+            log(
+                &mut logger,
+                LogLevel::Info,
+                &format!("拒绝 {} 加入: {}", name, reason),
+            );
+            connection.send(&CoopMessage::Reject { reason });
+            continue;
+        }
+
+        let peer = session.allocate_peer();
+        #[cfg(feature = "server")]
+        guests.register(peer, &name, spawn);
+        let mut peers = vec![PeerInfo {
+            peer: HOST_PEER,
+            name: settings.player_name.clone(),
+            position: spawn.to_array(),
+        }];
+        for (id, remote) in &session.peers {
+            let position = remote
+                .entity
+                .and_then(|entity| remotes.get(entity).ok())
+                .map(|(_, _, transform)| transform.translation.truncate())
+                .unwrap_or(spawn);
+            peers.push(PeerInfo {
+                peer: *id,
+                name: remote.name.clone(),
+                position: position.to_array(),
+            });
+        }
+        connection.send(&CoopMessage::Welcome {
+            peer,
+            host_name: settings.player_name.clone(),
+            seed: map_manager
+                .as_ref()
+                .map_or(0, |map_manager| map_manager.seed),
+            spawn: spawn.to_array(),
+            quest_share: session.quest_share,
+            allow_spectate: settings.allow_spectators,
+            peers,
+        });
+
+        let info = PeerInfo {
+            peer,
+            name: name.clone(),
+            position: spawn.to_array(),
+        };
+        session.broadcast(&CoopMessage::PlayerJoined(info.clone()), None);
+        let entity = spawn_remote_player(&mut commands, &asset_server, &info);
+        session.peers.insert(
+            peer,
+            CoopPeer {
+                name: name.clone(),
+                connection: Some(connection),
+                entity: Some(entity),
+            },
+        );
+        session.refresh_beacon();
+        log(
+            &mut logger,
+            LogLevel::Info,
+            &format!("{} 加入了联机会话", name),
+        );
+    }
+
+    let mut relay = Vec::new();
+    let mut left: Vec<PeerId> = Vec::new();
+    for (id, peer) in &session.peers {
+        let Some(connection) = &peer.connection else {
+            continue;
+        };
+        for message in connection.drain() {
+            match message {
+                CoopMessage::PlayerState {
+                    position,
+                    direction,
+                    health,
+                    max_health,
+                    state,
+                    ..
+                } => {
+                    #[cfg(feature = "server")]
+                    let position = {
+                        let speed = peer
+                            .entity
+                            .and_then(|entity| remotes.get(entity).ok())
+                            .map_or(0.0, |(_, character, _)| character.speed);
+                        match guests.check_move(*id, Vec2::from_array(position), speed) {
+                            Verdict::Accept => position,
+                            Verdict::Correct { position, .. } => {
+                                let position = position.to_array();
+                                connection.send(&CoopMessage::Correction { position });
+                                position
+                            }
+                            Verdict::Reject { .. } => continue,
+                        }
+                    };
+                    // 进入攻击状态视为一次攻击，超出频率的攻击连同这次状态一起丢弃
+                    #[cfg(feature = "server")]
+                    if state == CharacterState::Attacking
+                        && peer
+                            .entity
+                            .and_then(|entity| remotes.get(entity).ok())
+                            .is_some_and(|(_, character, _)| {
+                                character.state != CharacterState::Attacking
+                            })
+                        && !guests.check_action(*id, GameAction::Attack).is_accepted()
+                    {
+                        continue;
+                    }
+                    if let Some(Ok((mut remote, mut character, _))) =
+                        peer.entity.map(|entity| remotes.get_mut(entity))
+                    {
+                        apply_remote_state(
+                            &mut remote,
+                            &mut character,
+                            position,
+                            direction,
+                            health,
+                            max_health,
+                            state,
+                        );
+                    }
+                    relay.push((
+                        *id,
+                        CoopMessage::PlayerState {
+                            peer: *id,
+                            position,
+                            direction,
+                            health,
+                            max_health,
+                            state,
+                        },
+                    ));
+                }
+                CoopMessage::QuestProgress {
+                    quest_id,
+                    stage,
+                    completed,
+                    ..
+                } => {
+                    if !session.quest_share.shares_from(*id) {
+                        continue;
+                    }
+                    quest_events.send(RemoteQuestProgress {
+                        quest_id: quest_id.clone(),
+                        stage,
+                        completed,
+                    });
+                    relay.push((
+                        *id,
+                        CoopMessage::QuestProgress {
+                            peer: *id,
+                            quest_id,
+                            stage,
+                            completed,
+                        },
+                    ));
+                }
+                CoopMessage::Leave => left.push(*id),
+                _ => {}
+            }
+        }
+        if connection.is_closed() {
+            left.push(*id);
+        }
+    }
+
+    for (from, message) in relay {
+        session.broadcast(&message, Some(from));
+    }
+
+    for id in left {
+        let Some(peer) = session.peers.remove(&id) else {
+            continue;
+        };
+        #[cfg(feature = "server")]
+        guests.forget(id);
+        if let Some(entity) = peer.entity {
+            commands.entity(entity).despawn_recursive();
+        }
+        session.broadcast(&CoopMessage::PlayerLeft { peer: id }, None);
+        session.refresh_beacon();
+        log(
+            &mut logger,
+            LogLevel::Info,
+            &format!("{} 离开了联机会话", peer.name),
+        );
+    }
+}
+
+/// 客人处理主机的消息
+///
+/// 被接受后按主机的种子重新生成世界，读取自己的角色档案并出现在主机指定的位置；
+/// 与主机断开时把角色写回档案，回到单机
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+fn receive_as_guest(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut session: ResMut<CoopSession>,
+    mut map_manager: Option<ResMut<MapManager>>,
+    mut chunk_manager: Option<ResMut<ChunkManager>>,
+    residents: Query<Entity, With<ChunkResident>>,
+    mut local: Query<(
+        &mut Player,
+        &mut Character,
+        &mut Transform,
+        Option<&mut Inventory>,
+        Option<&mut Equipment>,
+    )>,
+    mut remotes: Query<(&mut RemotePlayer, &mut Character), Without<Player>>,
+    mut quest_events: EventWriter<RemoteQuestProgress>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let session = &mut *session;
+    let Some(connection) = session
+        .peers
+        .get(&HOST_PEER)
+        .and_then(|host| host.connection.as_ref())
+    else {
+        return;
+    };
+    let messages = connection.drain();
+    let mut disconnected = connection.is_closed();
+
+    for message in messages {
+        match message {
+            CoopMessage::Welcome {
+                peer,
+                host_name,
+                seed,
+                spawn,
+                quest_share,
+                allow_spectate,
+                peers,
+            } => {
+                session.role = CoopRole::Joined;
+                session.local_peer = peer;
+                session.quest_share = quest_share;
+                session.allow_spectate = allow_spectate;
+
+                // 按主机的种子重新生成世界，已加载的区块全部卸载后由区块系统重新加载
+                if let (Some(map_manager), Some(chunk_manager)) =
+                    (map_manager.as_deref_mut(), chunk_manager.as_deref_mut())
+                {
+                    if map_manager.seed != seed {
+                        map_manager.reseed(seed);
+                        chunk_manager.initialize_terrain_generator(map_manager);
+                        for (_, entity) in chunk_manager.chunks.drain() {
+                            commands.entity(entity).despawn_recursive();
+                        }
+                        for resident in residents.iter() {
+                            commands.entity(resident).despawn_recursive();
+                        }
+                    }
+                }
+
+                if let Ok((mut player, mut character, mut transform, inventory, equipment)) =
+                    local.get_single_mut()
+                {
+                    transform.translation.x = spawn[0];
+                    transform.translation.y = spawn[1];
+                    match CoopProfile::load(COOP_PROFILE_PATH) {
+                        Ok(profile) => profile.apply(
+                            &mut player,
+                            &mut character,
+                            inventory.map(Mut::into_inner),
+                            equipment.map(Mut::into_inner),
+                        ),
+                        // 第一次联机没有档案，沿用当前角色，离开时再写入
+                        Err(_) => log(
+                            &mut logger,
+                            LogLevel::Info,
+                            "没有联机角色档案，使用当前角色加入",
+                        ),
+                    }
+                }
+
+                for info in peers {
+                    let entity = spawn_remote_player(&mut commands, &asset_server, &info);
+                    let remote = session.peers.entry(info.peer).or_insert(CoopPeer {
+                        name: info.name.clone(),
+                        connection: None,
+                        entity: None,
+                    });
+                    remote.name = info.name;
+                    remote.entity = Some(entity);
+                }
+                log(
+                    &mut logger,
+                    LogLevel::Info,
+                    &format!("已加入 {} 的联机会话", host_name),
+                );
+            }
+            CoopMessage::Reject { reason } => {
+                log(
+                    &mut logger,
+                    LogLevel::Error,
+                    &format!("主机拒绝加入: {}", reason),
+                );
+                disconnected = true;
+            }
+            CoopMessage::PlayerJoined(info) => {
+                if info.peer == session.local_peer || session.peers.contains_key(&info.peer) {
+                    continue;
+                }
+                let entity = spawn_remote_player(&mut commands, &asset_server, &info);
+                log(
+                    &mut logger,
+                    LogLevel::Info,
+                    &format!("{} 加入了联机会话", info.name),
+                );
+                session.peers.insert(
+                    info.peer,
+                    CoopPeer {
+                        name: info.name,
+                        connection: None,
+                        entity: Some(entity),
+                    },
+                );
+            }
+            CoopMessage::PlayerLeft { peer } => {
+                if peer == HOST_PEER {
+                    continue;
+                }
+                if let Some(remote) = session.peers.remove(&peer) {
+                    if let Some(entity) = remote.entity {
+                        commands.entity(entity).despawn_recursive();
+                    }
+                    log(
+                        &mut logger,
+                        LogLevel::Info,
+                        &format!("{} 离开了联机会话", remote.name),
+                    );
+                }
+            }
+            CoopMessage::PlayerState {
+                peer,
+                position,
+                direction,
+                health,
+                max_health,
+                state,
+            } => {
+                let entity = session.peers.get(&peer).and_then(|remote| remote.entity);
+                if let Some(Ok((mut remote, mut character))) =
+                    entity.map(|entity| remotes.get_mut(entity))
+                {
+                    apply_remote_state(
+                        &mut remote,
+                        &mut character,
+                        position,
+                        direction,
+                        health,
+                        max_health,
+                        state,
+                    );
+                }
+            }
+            CoopMessage::Correction { position } => {
+                if let Ok((_, _, mut transform, _, _)) = local.get_single_mut() {
+                    transform.translation.x = position[0];
+                    transform.translation.y = position[1];
+                }
+            }
+            CoopMessage::QuestProgress {
+                peer,
+                quest_id,
+                stage,
+                completed,
+            } => {
+                // 主机已按共享规则筛选过
+                if peer != session.local_peer {
+                    quest_events.send(RemoteQuestProgress {
+                        quest_id,
+                        stage,
+                        completed,
+                    });
+                }
+            }
+            CoopMessage::Leave => disconnected = true,
+            CoopMessage::Hello { .. } => {}
+        }
+    }
+
+    if !disconnected {
+        return;
+    }
+    if session.role == CoopRole::Joined {
+        if let Ok((player, character, _, inventory, equipment)) = local.get_single() {
+            let profile = CoopProfile::capture(player, character, inventory, equipment);
+            save_profile(&profile, &mut logger);
+        }
+    }
+    log(&mut logger, LogLevel::Error, "与主机的联机已断开");
+    for entity in session.reset() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// 把本地玩家的任务进度按共享规则发给其他玩家
+fn forward_local_quest_progress(
+    mut events: EventReader<LocalQuestProgress>,
+    session: Res<CoopSession>,
+) {
+    for event in events.read() {
+        if !matches!(session.role, CoopRole::Hosting | CoopRole::Joined)
+            || !session.quest_share.shares_from(session.local_peer)
+        {
+            continue;
+        }
+        // 客人只连着主机，由主机决定是否转发
+        session.broadcast(
+            &CoopMessage::QuestProgress {
+                peer: session.local_peer,
+                quest_id: event.quest_id.clone(),
+                stage: event.stage,
+                completed: event.completed,
+            },
+            None,
+        );
+    }
+}
+
+/// 按间隔同步本地玩家的位置与状态
+fn broadcast_local_state(
+    time: Res<Time>,
+    settings: Res<CoopSettings>,
+    mut session: ResMut<CoopSession>,
+    local: Query<(&Character, &Transform), With<Player>>,
+) {
+    if !matches!(session.role, CoopRole::Hosting | CoopRole::Joined) {
+        return;
+    }
+    session.sync_timer -= time.delta_secs();
+    if session.sync_timer > 0.0 {
+        return;
+    }
+    session.sync_timer = settings.sync_interval;
+
+    let Ok((character, transform)) = local.get_single() else {
+        return;
+    };
+    session.broadcast(
+        &CoopMessage::PlayerState {
+            peer: session.local_peer,
+            position: transform.translation.truncate().to_array(),
+            direction: character.direction.to_array(),
+            health: character.health,
+            max_health: character.max_health,
+            state: character.state,
+        },
+        None,
+    );
+}
+
+/// 远程角色平滑移向同步位置
+fn smooth_remote_players(
+    time: Res<Time>,
+    mut remotes: Query<(&RemotePlayer, &mut Character, &mut Transform)>,
+) {
+    let blend = (REMOTE_SMOOTHING * time.delta_secs()).min(1.0);
+    for (remote, mut character, mut transform) in remotes.iter_mut() {
+        let current = transform.translation.truncate();
+        let offset = remote.target - current;
+        let position = if offset.length() > REMOTE_SNAP_DISTANCE {
+            remote.target
+        } else {
+            current + offset * blend
+        };
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+
+        if matches!(
+            character.state,
+            CharacterState::Idle | CharacterState::Walking
+        ) {
+            character.state = if offset.length() > 1.0 {
+                CharacterState::Walking
+            } else {
+                CharacterState::Idle
+            };
+        }
+    }
+}
+
+/// 收集局域网内的会话广播
+fn refresh_session_list(
+    time: Res<Time>,
+    mut session_list: ResMut<CoopSessionList>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let Some(browser) = &session_list.browser else {
+        return;
+    };
+    let found = browser.drain();
+    let now = time.elapsed_secs_f64();
+    for (address, announcement) in found {
+        let summary = format!(
+            "发现联机会话：{}（{}/{}）{}",
+            announcement.host_name, announcement.players, announcement.max_players, address
+        );
+        if session_list.record(address, now) {
+            log(&mut logger, LogLevel::Info, &summary);
+        }
+    }
+    session_list.prune(now);
+}
+
+/// 退出游戏时通知其他玩家，客人写回角色档案
+fn leave_on_exit(
+    mut exits: EventReader<AppExit>,
+    session: Res<CoopSession>,
+    local: Query<(&Player, &Character, Option<&Inventory>, Option<&Equipment>)>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    if exits.read().count() == 0 {
+        return;
+    }
+    match session.role {
+        CoopRole::Hosting => session.broadcast(&CoopMessage::Leave, None),
+        CoopRole::Joined => {
+            session.send_to(HOST_PEER, &CoopMessage::Leave);
+            if let Ok((player, character, inventory, equipment)) = local.get_single() {
+                let profile = CoopProfile::capture(player, character, inventory, equipment);
+                save_profile(&profile, &mut logger);
+            }
+        }
+        CoopRole::Offline | CoopRole::Joining => {}
+    }
+}
@@ -0,0 +1,359 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TryRecvError, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{CoopMessage, SessionAnnouncement, DISCOVERY_PORT};
+
+/// 连接主机的超时时长，联机只面向局域网，超时不宜过长
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+/// 会话广播的间隔
+const BEACON_INTERVAL: Duration = Duration::from_secs(1);
+/// 后台线程检查停止标志的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// 发送队列的长度，按每秒十余条状态同步估算可缓冲十几秒
+const OUTGOING_CAPACITY: usize = 256;
+/// 单次写入的超时，对方停止读取时写入线程不会一直卡住
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 取出通道中目前已有的全部消息
+fn drain_channel<T>(receiver: &Mutex<Receiver<T>>) -> Vec<T> {
+    let Ok(receiver) = receiver.lock() else {
+        return Vec::new();
+    };
+    let mut items = Vec::new();
+    while let Ok(item) = receiver.try_recv() {
+        items.push(item);
+    }
+    items
+}
+
+/// 一条联机连接
+///
+/// 后台线程逐行读取并解析消息，游戏线程每帧取出；
+/// 发送只把消息放进有界队列，由写入线程写出，网络拥塞时不会卡住游戏线程。
+/// 队列写满说明对方长时间收不下消息，按断开处理
+pub struct CoopConnection {
+    address: SocketAddr,
+    outgoing: Mutex<Option<SyncSender<String>>>,
+    incoming: Mutex<Receiver<CoopMessage>>,
+    closed: Arc<AtomicBool>,
+}
+
+impl CoopConnection {
+    /// 连接主机
+    pub fn connect(address: &str) -> io::Result<Self> {
+        let address = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "无法解析主机地址"))?;
+        let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+        Self::from_stream(stream)
+    }
+
+    /// 包装已建立的连接并启动读取线程
+    pub fn from_stream(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        let address = stream.peer_addr()?;
+        let reader = stream.try_clone()?;
+        let closed = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let (outgoing, queued) = mpsc::sync_channel::<String>(OUTGOING_CAPACITY);
+
+        // 发送端被丢弃（关闭连接）后先写完队列里的消息再断开，
+        // 关闭前发出的拒绝、离开等消息不会丢失
+        let writer_closed = closed.clone();
+        let mut writer = stream;
+        std::thread::spawn(move || {
+            for line in queued {
+                if writer.write_all(line.as_bytes()).is_err() {
+                    break;
+                }
+            }
+            let _ = writer.shutdown(std::net::Shutdown::Both);
+            writer_closed.store(true, Ordering::Relaxed);
+        });
+
+        let reader_closed = closed.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                // 无法解析的行直接丢弃，不中断连接
+                let Ok(message) = serde_json::from_str::<CoopMessage>(&line) else {
+                    continue;
+                };
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+            reader_closed.store(true, Ordering::Relaxed);
+        });
+
+        Ok(Self {
+            address,
+            outgoing: Mutex::new(Some(outgoing)),
+            incoming: Mutex::new(receiver),
+            closed,
+        })
+    }
+
+    /// 对方地址
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// 把一条消息放进发送队列，队列已满或连接已关闭时标记为已断开
+    pub fn send(&self, message: &CoopMessage) -> bool {
+        let Ok(mut line) = serde_json::to_string(message) else {
+            return false;
+        };
+        line.push('\n');
+        let sent = match self.outgoing.lock() {
+            Ok(mut outgoing) => match outgoing.as_ref().map(|sender| sender.try_send(line)) {
+                Some(Ok(())) => true,
+                Some(Err(TrySendError::Full(_))) => {
+                    // 丢弃发送端，写入线程写完已排队的消息后断开
+                    *outgoing = None;
+                    false
+                }
+                Some(Err(TrySendError::Disconnected(_))) | None => false,
+            },
+            Err(_) => false,
+        };
+        if !sent {
+            self.closed.store(true, Ordering::Relaxed);
+        }
+        sent
+    }
+
+    /// 取出目前已收到的全部消息
+    pub fn drain(&self) -> Vec<CoopMessage> {
+        drain_channel(&self.incoming)
+    }
+
+    /// 连接是否已断开
+    ///
+    /// 断开前收到的消息仍可以取出，调用方应先处理消息再检查断开
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+
+    /// 关闭连接
+    ///
+    /// 写入线程写完已排队的消息后断开，读取线程随之退出
+    pub fn close(&self) {
+        if let Ok(mut outgoing) = self.outgoing.lock() {
+            *outgoing = None;
+        }
+        self.closed.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for CoopConnection {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// 在后台线程连接主机
+///
+/// 连接最长要等到超时，放在游戏线程会卡住画面；游戏线程每帧检查是否有结果
+pub struct CoopConnector {
+    address: String,
+    result: Mutex<Receiver<io::Result<CoopConnection>>>,
+}
+
+impl CoopConnector {
+    /// 开始连接主机
+    pub fn start(address: &str) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let thread_address = address.to_string();
+        std::thread::spawn(move || {
+            // 连接器已丢弃（例如玩家取消加入）时连接随结果一起丢弃并关闭
+            let _ = sender.send(CoopConnection::connect(&thread_address));
+        });
+
+        Self {
+            address: address.to_string(),
+            result: Mutex::new(receiver),
+        }
+    }
+
+    /// 要连接的主机地址
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// 取出连接结果，仍在连接时返回 None
+    pub fn poll(&self) -> Option<io::Result<CoopConnection>> {
+        let receiver = self.result.lock().ok()?;
+        match receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(io::Error::other("连接线程意外退出"))),
+        }
+    }
+}
+
+/// 主机监听新连接
+pub struct CoopListener {
+    port: u16,
+    accepted: Mutex<Receiver<TcpStream>>,
+    running: Arc<AtomicBool>,
+}
+
+impl CoopListener {
+    /// 在所有网卡的指定端口上监听
+    pub fn bind(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        // 非阻塞监听，后台线程才能按停止标志退出
+        listener.set_nonblocking(true)?;
+        let port = listener.local_addr()?.port();
+        let running = Arc::new(AtomicBool::new(true));
+        let (sender, receiver) = mpsc::channel();
+
+        let thread_running = running.clone();
+        std::thread::spawn(move || {
+            while thread_running.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let _ = stream.set_nonblocking(false);
+                        if sender.send(stream).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                        std::thread::sleep(POLL_INTERVAL)
+                    }
+                    Err(_) => break,
+                }
+            }
+        });
+
+        Ok(Self {
+            port,
+            accepted: Mutex::new(receiver),
+            running,
+        })
+    }
+
+    /// 实际监听的端口
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// 取出目前已接受的新连接
+    pub fn drain(&self) -> Vec<TcpStream> {
+        drain_channel(&self.accepted)
+    }
+}
+
+impl Drop for CoopListener {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+/// 在局域网内定期广播会话信息
+pub struct SessionBeacon {
+    announcement: Arc<Mutex<SessionAnnouncement>>,
+    running: Arc<AtomicBool>,
+}
+
+impl SessionBeacon {
+    pub fn start(announcement: SessionAnnouncement) -> io::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+        socket.set_broadcast(true)?;
+        let announcement = Arc::new(Mutex::new(announcement));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread_announcement = announcement.clone();
+        let thread_running = running.clone();
+        std::thread::spawn(move || {
+            while thread_running.load(Ordering::Relaxed) {
+                let payload = thread_announcement
+                    .lock()
+                    .ok()
+                    .and_then(|announcement| serde_json::to_vec(&*announcement).ok());
+                if let Some(payload) = payload {
+                    let _ = socket.send_to(&payload, ("255.255.255.255", DISCOVERY_PORT));
+                }
+                std::thread::sleep(BEACON_INTERVAL);
+            }
+        });
+
+        Ok(Self {
+            announcement,
+            running,
+        })
+    }
+
+    /// 更新广播的人数
+    pub fn set_players(&self, players: usize) {
+        if let Ok(mut announcement) = self.announcement.lock() {
+            announcement.players = players;
+        }
+    }
+}
+
+impl Drop for SessionBeacon {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+/// 搜索局域网内的会话
+pub struct SessionBrowser {
+    found: Mutex<Receiver<(SocketAddr, SessionAnnouncement)>>,
+    running: Arc<AtomicBool>,
+}
+
+impl SessionBrowser {
+    pub fn start() -> io::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", DISCOVERY_PORT))?;
+        socket.set_read_timeout(Some(POLL_INTERVAL))?;
+        let running = Arc::new(AtomicBool::new(true));
+        let (sender, receiver) = mpsc::channel();
+
+        let thread_running = running.clone();
+        std::thread::spawn(move || {
+            let mut buffer = [0u8; 1024];
+            while thread_running.load(Ordering::Relaxed) {
+                let Ok((len, from)) = socket.recv_from(&mut buffer) else {
+                    continue;
+                };
+                let Ok(announcement) =
+                    serde_json::from_slice::<SessionAnnouncement>(&buffer[..len])
+                else {
+                    continue;
+                };
+                // 广播来源是主机的临时端口，加入时改用会话声明的端口
+                let address = SocketAddr::new(from.ip(), announcement.port);
+                if sender.send((address, announcement)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            found: Mutex::new(receiver),
+            running,
+        })
+    }
+
+    /// 取出目前收到的会话广播
+    pub fn drain(&self) -> Vec<(SocketAddr, SessionAnnouncement)> {
+        drain_channel(&self.found)
+    }
+}
+
+impl Drop for SessionBrowser {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
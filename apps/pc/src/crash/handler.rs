@@ -0,0 +1,182 @@
+use bevy::prelude::*;
+use rfd::{MessageButtons, MessageDialog, MessageLevel};
+use std::fs;
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{ChunkJournal, CrashReport};
+use crate::logging::{BugBundle, DiagnosticsSettings, LogScrubber, LogSinks};
+
+/// 崩溃报告目录中记录上次崩溃的文件，下次启动时读取后删除
+pub const LAST_CRASH_FILE: &str = "last_crash.txt";
+
+/// 等待日志写完的最长时间
+const LOG_FLUSH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 正在处理崩溃，处理过程中再次 panic 或其他线程同时 panic 时直接交给默认处理
+static HANDLING: AtomicBool = AtomicBool::new(false);
+
+/// 崩溃处理设置
+#[derive(Resource, Debug, Clone)]
+pub struct CrashSettings {
+    /// 崩溃报告目录
+    pub dir: String,
+    /// 崩溃后弹出系统对话框告知玩家报告位置
+    pub show_dialog: bool,
+    /// 崩溃时把未存档的区块修改写成恢复存档
+    pub recover_chunks: bool,
+    /// 游戏版本、构建方式与运行模式，写在报告开头
+    pub build: String,
+}
+
+impl Default for CrashSettings {
+    fn default() -> Self {
+        Self {
+            dir: "crashes".to_string(),
+            show_dialog: true,
+            recover_chunks: true,
+            build: format!(
+                "游戏版本：{}（{}构建）",
+                env!("CARGO_PKG_VERSION"),
+                if cfg!(debug_assertions) {
+                    "调试"
+                } else {
+                    "优化"
+                }
+            ),
+        }
+    }
+}
+
+/// panic 钩子在游戏循环之外运行，需要的数据由系统同步到这里
+pub struct CrashContext {
+    pub settings: CrashSettings,
+    /// 主要设置的摘要
+    pub settings_summary: String,
+    pub sinks: Option<LogSinks>,
+    /// 日志目录，文件日志关闭时为空
+    pub log_dir: Option<String>,
+    pub diagnostics: DiagnosticsSettings,
+    pub scrubber: LogScrubber,
+    pub journal: ChunkJournal,
+    /// 存档槽所在目录
+    pub slots_dir: String,
+}
+
+/// 崩溃处理
+///
+/// # 设计思路
+/// 1. 钩子装在原有 panic 钩子之前，处理完仍交给原钩子，控制台照常输出 panic 信息
+/// 2. 先把 panic 写进日志并等日志写完，再借用错误报告包导出本次会话的日志，报告另写一份带调用栈的说明
+/// 3. 未存档的区块修改写成单独的恢复存档，不改动玩家的存档
+/// 4. 每一步失败都只记在报告里，后面的步骤照常进行；最后按设置弹出对话框
+#[derive(Resource, Clone)]
+pub struct CrashHandler {
+    context: Arc<Mutex<CrashContext>>,
+}
+
+impl CrashHandler {
+    pub fn new(context: CrashContext) -> Self {
+        Self {
+            context: Arc::new(Mutex::new(context)),
+        }
+    }
+
+    /// 修改钩子使用的数据
+    pub fn update(&self, update: impl FnOnce(&mut CrashContext)) {
+        if let Ok(mut context) = self.context.lock() {
+            update(&mut context);
+        }
+    }
+
+    /// 装上 panic 钩子，同一进程只需装一次
+    pub fn install(&self) {
+        let context = self.context.clone();
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !HANDLING.swap(true, Ordering::SeqCst) {
+                handle_panic(&context, info);
+            }
+            previous(info);
+        }));
+    }
+}
+
+fn handle_panic(context: &Mutex<CrashContext>, info: &PanicHookInfo) {
+    let mut report = CrashReport::capture(info);
+    error!("游戏崩溃: {}（{}）", report.message, report.location);
+
+    // 崩溃的线程可能正在修改这些数据，拿不到锁时只能写出最基本的报告
+    let Ok(context) = context.try_lock() else {
+        report
+            .notes
+            .push("崩溃处理数据被占用，未附带日志与区块修改".to_string());
+        let dir = crash_dir(&CrashSettings::default().dir, &report);
+        if let Err(e) = write_report(&dir, &report, &LogScrubber::default()) {
+            eprintln!("写入崩溃报告失败: {}", e);
+        }
+        return;
+    };
+
+    report.build = context.settings.build.clone();
+    report.settings = context.settings_summary.clone();
+    if let Some(sinks) = &context.sinks {
+        if !sinks.flush(LOG_FLUSH_TIMEOUT) {
+            report.notes.push("部分日志未能及时写出".to_string());
+        }
+        report.log_tail = sinks.recent_lines();
+    }
+
+    let dir = crash_dir(&context.settings.dir, &report);
+    let bundle = BugBundle {
+        settings: &context.diagnostics,
+        scrubber: &context.scrubber,
+        log_dir: context.log_dir.as_deref(),
+        incidents: &[],
+    };
+    // 试玩数据留在游戏循环里，崩溃时不附带
+    if let Err(e) = bundle.export_to(&dir, None) {
+        report.notes.push(format!("导出日志失败: {}", e));
+    }
+
+    if context.settings.recover_chunks {
+        let note = match context.journal.write_recovery(&context.slots_dir, &dir) {
+            Ok(note) => note,
+            Err(e) => format!("保存区块修改失败: {}", e),
+        };
+        report.notes.push(note);
+    }
+
+    if let Err(e) = write_report(&dir, &report, &context.scrubber) {
+        eprintln!("写入崩溃报告失败: {}", e);
+        return;
+    }
+    let marker = Path::new(&context.settings.dir).join(LAST_CRASH_FILE);
+    let _ = fs::write(marker, dir.to_string_lossy().as_bytes());
+    eprintln!("崩溃报告已写入 {}", dir.display());
+
+    if context.settings.show_dialog {
+        let _ = MessageDialog::new()
+            .set_level(MessageLevel::Error)
+            .set_title("游戏崩溃")
+            .set_description(format!(
+                "游戏遇到错误需要退出。\n\n{}\n\n崩溃报告已保存到：\n{}",
+                report.message,
+                dir.display()
+            ))
+            .set_buttons(MessageButtons::Ok)
+            .show();
+    }
+}
+
+fn crash_dir(root: &str, report: &CrashReport) -> PathBuf {
+    Path::new(root).join(format!("crash-{}", report.time.format("%Y%m%d-%H%M%S")))
+}
+
+fn write_report(dir: &Path, report: &CrashReport, scrubber: &LogScrubber) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join("crash.txt"), scrubber.scrub(&report.render()))
+}
@@ -0,0 +1,18 @@
+/// 崩溃处理模块
+///
+/// 程序崩溃时留下崩溃报告与本次会话的日志，并尽量保住未存档的世界修改
+///
+/// # 模块组成
+/// 1. report：崩溃报告的内容与格式
+/// 2. recovery：记录上次存档后修改过的区块，崩溃时写成恢复存档
+/// 3. handler：panic 钩子，写报告、保存区块修改并提示玩家
+/// 4. systems：崩溃处理插件，把设置、日志与存档位置同步给钩子
+mod handler;
+mod recovery;
+mod report;
+mod systems;
+
+pub use handler::*;
+pub use recovery::*;
+pub use report::*;
+pub use systems::*;
@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+use chrono::Local;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::save::{
+    ChunkEditSave, SaveGame, SaveReason, SaveSlot, GAME_FILE, METADATA_FILE, SCREENSHOT_FILE,
+};
+use crate::world::chunk::{ChunkCoord, ChunkData};
+
+/// 崩溃时写入的恢复存档槽
+pub const CRASH_RECOVERY_SLOT: &str = "crash_recovery";
+
+/// 从未存档过时，区块修改写在崩溃报告目录中的这个文件里
+const UNSAVED_CHUNKS_FILE: &str = "chunks.json";
+
+#[derive(Default)]
+struct JournalState {
+    /// 最近一次存档或读档的槽，新游戏尚未存档时为空
+    slot: Option<String>,
+    /// 之后修改过的区块
+    chunks: HashMap<ChunkCoord, ChunkData>,
+}
+
+/// 上次存档后修改过的区块
+///
+/// # 设计思路
+/// 1. 存档与读档时清空，之后修改过的区块逐个记下，只保留最新的数据
+/// 2. 数据放在共享的锁里，panic 钩子不经过 ECS 也能读到
+/// 3. 崩溃时复制最近的存档槽为恢复存档，再把记下的区块并入，玩家原来的存档不动
+#[derive(Resource, Clone, Default)]
+pub struct ChunkJournal {
+    state: Arc<Mutex<JournalState>>,
+}
+
+impl ChunkJournal {
+    pub fn record(&self, coord: ChunkCoord, data: &ChunkData) {
+        if let Ok(mut state) = self.state.lock() {
+            state.chunks.insert(coord, data.clone());
+        }
+    }
+
+    /// 存档、读档或开始新游戏后重新记录
+    pub fn reset(&self, slot: Option<&str>) {
+        if let Ok(mut state) = self.state.lock() {
+            state.slot = slot.map(str::to_string);
+            state.chunks.clear();
+        }
+    }
+
+    /// 写出记下的区块修改，返回写到了哪里
+    ///
+    /// 崩溃的线程可能正持有锁，拿不到锁时放弃，不能让崩溃处理卡住
+    pub fn write_recovery(
+        &self,
+        slots_dir: &str,
+        crash_dir: &Path,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let state = self.state.try_lock().map_err(|_| "区块修改记录被占用")?;
+        if state.chunks.is_empty() {
+            return Ok("上次存档后没有修改过的区块".to_string());
+        }
+        let chunks: Vec<ChunkEditSave> = state
+            .chunks
+            .iter()
+            .map(|(coord, data)| ChunkEditSave {
+                x: coord.x,
+                y: coord.y,
+                data: data.clone(),
+            })
+            .collect();
+
+        let Some(slot) = &state.slot else {
+            let path = crash_dir.join(UNSAVED_CHUNKS_FILE);
+            fs::write(&path, serde_json::to_string(&chunks)?)?;
+            return Ok(format!(
+                "本局尚未存档，{} 个区块修改写在 {}",
+                chunks.len(),
+                path.display()
+            ));
+        };
+
+        let source = SaveSlot::in_dir(slots_dir, slot)?;
+        let target = SaveSlot::in_dir(slots_dir, CRASH_RECOVERY_SLOT)?;
+        if source != target {
+            target.delete()?;
+            copy_dir(source.dir(), target.dir())?;
+        }
+
+        let mut game = SaveGame::load(&target.file(GAME_FILE))?;
+        game.chunks
+            .retain(|chunk| !state.chunks.contains_key(&chunk.coord()));
+        let count = chunks.len();
+        game.chunks.extend(chunks);
+        game.save(&target.file(GAME_FILE))?;
+
+        let mut metadata = target.metadata()?;
+        metadata.slot = target.name.clone();
+        metadata.saved_at = Local::now().to_rfc3339();
+        metadata.reason = SaveReason::CrashRecovery;
+        metadata.screenshot = metadata
+            .screenshot
+            .map(|_| target.file_str(SCREENSHOT_FILE));
+        metadata.save(&target.file(METADATA_FILE))?;
+
+        Ok(format!(
+            "{} 个区块修改已并入存档槽 {} 的副本 {}",
+            count, slot, target.name
+        ))
+    }
+}
+
+/// 复制存档槽目录，槽内只有文件没有子目录
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let path = entry?.path();
+        if let Some(name) = path.file_name().filter(|_| path.is_file()) {
+            fs::copy(&path, to.join(name))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use chrono::{DateTime, Local};
+use std::backtrace::Backtrace;
+use std::panic::PanicHookInfo;
+
+use crate::config::GameSettings;
+
+/// 崩溃报告
+pub struct CrashReport {
+    pub time: DateTime<Local>,
+    /// panic 信息
+    pub message: String,
+    /// 发生 panic 的源码位置
+    pub location: String,
+    /// 发生 panic 的线程
+    pub thread: String,
+    pub backtrace: String,
+    /// 游戏版本、构建方式与运行模式
+    pub build: String,
+    /// 主要设置
+    pub settings: String,
+    /// 崩溃前最近的日志
+    pub log_tail: Vec<String>,
+    /// 崩溃处理中各步骤的结果，例如区块修改保存到了哪里
+    pub notes: Vec<String>,
+}
+
+impl CrashReport {
+    /// 记下 panic 信息与调用栈，其余内容由崩溃处理补上
+    pub fn capture(info: &PanicHookInfo) -> Self {
+        let message = if let Some(message) = info.payload().downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = info.payload().downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "未知错误".to_string()
+        };
+        let location = info
+            .location()
+            .map(|location| format!("{}:{}", location.file(), location.line()))
+            .unwrap_or_else(|| "未知位置".to_string());
+        let thread = std::thread::current()
+            .name()
+            .unwrap_or("未命名线程")
+            .to_string();
+
+        Self {
+            time: Local::now(),
+            message,
+            location,
+            thread,
+            // 不依赖 RUST_BACKTRACE，崩溃报告总是带上调用栈
+            backtrace: Backtrace::force_capture().to_string(),
+            build: String::new(),
+            settings: String::new(),
+            log_tail: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    /// 报告全文
+    pub fn render(&self) -> String {
+        let mut report = format!(
+            "崩溃报告\n时间：{}\n{}\n\n错误：{}\n位置：{}\n线程：{}\n",
+            self.time.format("%Y-%m-%d %H:%M:%S"),
+            self.build,
+            self.message,
+            self.location,
+            self.thread,
+        );
+        for note in &self.notes {
+            report.push_str(&format!("{}\n", note));
+        }
+        if !self.settings.is_empty() {
+            report.push_str(&format!("\n设置：\n{}\n", self.settings));
+        }
+        report.push_str(&format!("\n调用栈：\n{}\n", self.backtrace));
+        if !self.log_tail.is_empty() {
+            report.push_str(&format!("\n最近的日志（{} 行）：\n", self.log_tail.len()));
+            for line in &self.log_tail {
+                report.push_str(&format!("{}\n", line));
+            }
+        }
+        report
+    }
+}
+
+/// 崩溃报告中列出的主要设置，排查画面与性能问题时最常用
+pub fn settings_summary(settings: &GameSettings) -> String {
+    let window = &settings.window;
+    let graphics = &settings.graphics;
+    format!(
+        "窗口：{}x{}，全屏 {}，垂直同步 {}\n\
+         画面：视距 {}，阴影 {}，粒子上限 {}，动态光照 {}，地形贴图 {}\n\
+         日志：级别 {}，过滤规则 {:?}\n\
+         开发选项：热重载 {}，性能分析 {}",
+        window.width,
+        window.height,
+        window.fullscreen,
+        window.vsync,
+        graphics.render_distance,
+        graphics.shadow_quality,
+        graphics.particle_limit,
+        graphics.dynamic_lighting,
+        graphics.tile_textures,
+        settings.logging.level,
+        settings.logging.filters,
+        settings.development.hot_reload,
+        settings.development.profiler,
+    )
+}
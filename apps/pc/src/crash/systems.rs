@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+use std::fs;
+use std::path::Path;
+
+use super::{
+    settings_summary, ChunkJournal, CrashContext, CrashHandler, CrashSettings, LAST_CRASH_FILE,
+};
+use crate::config::Settings;
+use crate::logging::{DiagnosticsSettings, GameLogger, LogLevel, LogScrubber};
+use crate::save::{LoadEvent, NewGameRequest, SaveEvent, SaveSettings};
+use crate::world::chunk::Chunk;
+
+/// 崩溃处理插件
+///
+/// # 设计思路
+/// 1. 插件建立时就装上 panic 钩子，此后任何线程崩溃都会留下报告
+/// 2. 隐私选项、脱敏器、日志目录与存档位置变化时同步给钩子，钩子只读这份副本
+/// 3. 启动时检查上次运行是否崩溃，提示崩溃报告的位置
+///
+/// 须在日志插件之后加入，崩溃时才能带上日志
+pub struct CrashPlugin;
+
+impl Plugin for CrashPlugin {
+    fn build(&self, app: &mut App) {
+        let journal = ChunkJournal::default();
+        let logger = app.world().get_resource::<GameLogger>();
+        let handler = CrashHandler::new(CrashContext {
+            settings: CrashSettings::default(),
+            settings_summary: String::new(),
+            sinks: logger.map(GameLogger::sinks),
+            log_dir: logger.and_then(|logger| logger.log_dir().map(str::to_string)),
+            diagnostics: DiagnosticsSettings::default(),
+            scrubber: LogScrubber::default(),
+            journal: journal.clone(),
+            slots_dir: SaveSettings::default().slots_dir,
+        });
+        handler.install();
+
+        app.init_resource::<CrashSettings>()
+            .insert_resource(handler)
+            .insert_resource(journal)
+            .add_systems(Startup, report_last_crash)
+            .add_systems(
+                Update,
+                (
+                    sync_crash_context,
+                    (reset_chunk_journal, journal_chunk_edits).chain(),
+                ),
+            );
+    }
+}
+
+/// 把钩子需要的数据同步过去，只在有变化时更新
+fn sync_crash_context(
+    handler: Res<CrashHandler>,
+    crash_settings: Res<CrashSettings>,
+    settings: Option<Res<Settings>>,
+    diagnostics: Option<Res<DiagnosticsSettings>>,
+    scrubber: Option<Res<LogScrubber>>,
+    logger: Option<Res<GameLogger>>,
+    save_settings: Option<Res<SaveSettings>>,
+) {
+    if crash_settings.is_changed() {
+        let crash_settings = crash_settings.clone();
+        handler.update(|context| context.settings = crash_settings);
+    }
+    if let Some(settings) = settings.filter(|settings| settings.is_changed()) {
+        let summary = settings_summary(&settings.game);
+        handler.update(|context| context.settings_summary = summary);
+    }
+    if let Some(diagnostics) = diagnostics.filter(|diagnostics| diagnostics.is_changed()) {
+        handler.update(|context| context.diagnostics = diagnostics.clone());
+    }
+    if let Some(scrubber) = scrubber.filter(|scrubber| scrubber.is_changed()) {
+        handler.update(|context| context.scrubber = scrubber.clone());
+    }
+    if let Some(logger) = logger.filter(|logger| logger.is_changed()) {
+        let log_dir = logger.log_dir().map(str::to_string);
+        handler.update(|context| context.log_dir = log_dir);
+    }
+    if let Some(save_settings) = save_settings.filter(|save_settings| save_settings.is_changed()) {
+        let slots_dir = save_settings.slots_dir.clone();
+        handler.update(|context| context.slots_dir = slots_dir);
+    }
+}
+
+/// 存档、读档与新游戏之后重新记录修改过的区块
+fn reset_chunk_journal(
+    journal: Res<ChunkJournal>,
+    mut saves: EventReader<SaveEvent>,
+    mut loads: EventReader<LoadEvent>,
+    mut new_games: EventReader<NewGameRequest>,
+) {
+    if new_games.read().count() > 0 {
+        journal.reset(None);
+    }
+    if let Some(load) = loads.read().last() {
+        journal.reset(Some(&load.slot));
+    }
+    if let Some(save) = saves.read().last() {
+        journal.reset(Some(&save.slot));
+    }
+}
+
+/// 记下修改过的已加载区块
+fn journal_chunk_edits(journal: Res<ChunkJournal>, chunks: Query<&Chunk, Changed<Chunk>>) {
+    for chunk in chunks.iter() {
+        if let Some(data) = chunk.data.as_ref().filter(|data| data.modified) {
+            journal.record(chunk.coord, data);
+        }
+    }
+}
+
+/// 上次运行崩溃过时提示崩溃报告的位置
+fn report_last_crash(settings: Res<CrashSettings>, mut logger: Option<ResMut<GameLogger>>) {
+    let marker = Path::new(&settings.dir).join(LAST_CRASH_FILE);
+    let Ok(report) = fs::read_to_string(&marker) else {
+        return;
+    };
+    let _ = fs::remove_file(&marker);
+    if let Some(logger) = logger.as_mut() {
+        logger.log(
+            LogLevel::Warn,
+            &format!("上次运行时游戏崩溃，崩溃报告与日志位于 {}", report.trim()),
+        );
+    }
+}
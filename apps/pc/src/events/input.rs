@@ -1,7 +1,12 @@
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
 use bevy::prelude::*;
+use bevy::reflect::{DynamicEnum, DynamicVariant, FromReflect};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::config::{BindingOverride, Settings};
+use crate::logging::{GameLogger, LogLevel};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum GameAction {
     MoveForward,
@@ -10,49 +15,590 @@ pub enum GameAction {
     MoveRight,
     Jump,
     Attack,
+    Dodge,
+    /// 技能栏的四个槽位，绑定的技能由技能书决定
+    Skill1,
+    Skill2,
+    Skill3,
+    Skill4,
+    Sprint,
+    /// 按住施展轻功
+    Qinggong,
+    /// 上马、下马
+    Mount,
     Interact,
+    /// 使用背包里的修理工具修理装备
+    RepairGear,
     OpenInventory,
+    /// 打开、关闭武学面板
+    OpenSkills,
     OpenMap,
+    ToggleMinimap,
     ExitGame,
     ZoomIn,
     ZoomOut,
+    ToggleEditMode,
+    RotateItem,
+    CycleItem,
+    PlaceItem,
+    RemoveItem,
+    ExportBugReport,
+    ToggleSpectator,
+    /// 打开、关闭选项菜单
+    OpenOptions,
+    /// 开关区块流式加载的调试界面
+    ToggleChunkDebug,
+    /// 切换性能面板的显示方式
+    ToggleProfiler,
+    /// 录制一段帧时间
+    CaptureTrace,
+    /// 点击地面寻路走过去
+    ClickMove,
+    /// 点击NPC或资源点，走过去后交谈或采集
+    ContextInteract,
+}
+
+impl GameAction {
+    /// 技能栏槽位
+    pub const SKILL_SLOTS: [GameAction; 4] = [
+        GameAction::Skill1,
+        GameAction::Skill2,
+        GameAction::Skill3,
+        GameAction::Skill4,
+    ];
+
+    /// 选项菜单中按这个顺序列出
+    pub const ALL: [GameAction; 36] = [
+        GameAction::MoveForward,
+        GameAction::MoveBackward,
+        GameAction::MoveLeft,
+        GameAction::MoveRight,
+        GameAction::Jump,
+        GameAction::Attack,
+        GameAction::Dodge,
+        GameAction::Skill1,
+        GameAction::Skill2,
+        GameAction::Skill3,
+        GameAction::Skill4,
+        GameAction::Sprint,
+        GameAction::Qinggong,
+        GameAction::Mount,
+        GameAction::Interact,
+        GameAction::RepairGear,
+        GameAction::OpenInventory,
+        GameAction::OpenSkills,
+        GameAction::OpenMap,
+        GameAction::ToggleMinimap,
+        GameAction::ExitGame,
+        GameAction::ZoomIn,
+        GameAction::ZoomOut,
+        GameAction::ToggleEditMode,
+        GameAction::RotateItem,
+        GameAction::CycleItem,
+        GameAction::PlaceItem,
+        GameAction::RemoveItem,
+        GameAction::ExportBugReport,
+        GameAction::ToggleSpectator,
+        GameAction::OpenOptions,
+        GameAction::ToggleChunkDebug,
+        GameAction::ToggleProfiler,
+        GameAction::CaptureTrace,
+        GameAction::ClickMove,
+        GameAction::ContextInteract,
+    ];
+
+    /// 是否是技能栏槽位
+    pub fn is_skill_slot(self) -> bool {
+        Self::SKILL_SLOTS.contains(&self)
+    }
+
+    /// 显示名
+    pub fn label(self) -> &'static str {
+        match self {
+            GameAction::MoveForward => "向前",
+            GameAction::MoveBackward => "向后",
+            GameAction::MoveLeft => "向左",
+            GameAction::MoveRight => "向右",
+            GameAction::Jump => "跳跃",
+            GameAction::Attack => "攻击",
+            GameAction::Dodge => "闪避",
+            GameAction::Skill1 => "技能一",
+            GameAction::Skill2 => "技能二",
+            GameAction::Skill3 => "技能三",
+            GameAction::Skill4 => "技能四",
+            GameAction::Sprint => "疾跑",
+            GameAction::Qinggong => "轻功",
+            GameAction::Mount => "上马、下马",
+            GameAction::Interact => "交互",
+            GameAction::RepairGear => "修理装备",
+            GameAction::OpenInventory => "背包",
+            GameAction::OpenSkills => "武学",
+            GameAction::OpenMap => "世界地图",
+            GameAction::ToggleMinimap => "小地图",
+            GameAction::ExitGame => "返回",
+            GameAction::ZoomIn => "放大",
+            GameAction::ZoomOut => "缩小",
+            GameAction::ToggleEditMode => "建造模式",
+            GameAction::RotateItem => "旋转物件",
+            GameAction::CycleItem => "切换物件",
+            GameAction::PlaceItem => "放置物件",
+            GameAction::RemoveItem => "拆除物件",
+            GameAction::ExportBugReport => "导出错误报告",
+            GameAction::ToggleSpectator => "旁观模式",
+            GameAction::OpenOptions => "选项",
+            GameAction::ToggleChunkDebug => "区块调试",
+            GameAction::ToggleProfiler => "性能面板",
+            GameAction::CaptureTrace => "录制帧时间",
+            GameAction::ClickMove => "点击移动",
+            GameAction::ContextInteract => "点击交互",
+        }
+    }
+
+    /// 配置文件中使用的名字，与序列化结果一致
+    pub fn name(self) -> String {
+        format!("{:?}", self)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+    }
+}
+
+/// 每个动作最多绑定的按键数，超出时挤掉最早绑定的
+pub const MAX_BINDINGS_PER_ACTION: usize = 3;
+
+/// 鼠标键在配置中的名字前缀，例如 MouseLeft
+const MOUSE_PREFIX: &str = "Mouse";
+
+/// 手柄键在配置中的名字前缀，例如 GamepadSouth
+const GAMEPAD_PREFIX: &str = "Gamepad";
+
+/// 摇杆推过死区后，换算出的力度超过这个值才算按下对应的移动动作
+const STICK_ACTION_THRESHOLD: f32 = 0.35;
+
+/// 当前使用的输入设备，界面按它显示按键提示
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputDevice {
+    #[default]
+    KeyboardMouse,
+    Gamepad,
+}
+
+/// 可以绑定到动作上的按键、鼠标键或手柄键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
 }
 
-#[derive(Debug, Clone, Resource)]
+impl InputBinding {
+    /// 是否按住，没有手柄时手柄键都不算按下
+    pub fn pressed(
+        self,
+        keyboard: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+        gamepad: Option<&Gamepad>,
+    ) -> bool {
+        match self {
+            InputBinding::Key(key) => keyboard.pressed(key),
+            InputBinding::Mouse(button) => mouse.pressed(button),
+            InputBinding::Gamepad(button) => gamepad.is_some_and(|pad| pad.pressed(button)),
+        }
+    }
+
+    /// 属于哪种输入设备
+    pub fn device(self) -> InputDevice {
+        match self {
+            InputBinding::Key(_) | InputBinding::Mouse(_) => InputDevice::KeyboardMouse,
+            InputBinding::Gamepad(_) => InputDevice::Gamepad,
+        }
+    }
+
+    /// 配置文件中使用的名字，按键为变体名，例如 KeyW、F10，
+    /// 鼠标键与手柄键加上前缀，例如 MouseLeft、GamepadSouth
+    pub fn name(self) -> String {
+        match self {
+            InputBinding::Key(key) => format!("{:?}", key),
+            InputBinding::Mouse(button) => format!("{}{:?}", MOUSE_PREFIX, button),
+            InputBinding::Gamepad(button) => format!("{}{:?}", GAMEPAD_PREFIX, button),
+        }
+    }
+
+    /// 按名字解析，只认得不带参数的变体
+    ///
+    /// bevy 没有开启序列化，借反射按变体名构造，不必手写一张按键表
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(button) = name.strip_prefix(MOUSE_PREFIX) {
+            let dynamic = DynamicEnum::new(button.to_string(), DynamicVariant::Unit);
+            if let Some(button) = MouseButton::from_reflect(&dynamic) {
+                return Some(InputBinding::Mouse(button));
+            }
+        }
+        if let Some(button) = name.strip_prefix(GAMEPAD_PREFIX) {
+            let dynamic = DynamicEnum::new(button.to_string(), DynamicVariant::Unit);
+            if let Some(button) = GamepadButton::from_reflect(&dynamic) {
+                return Some(InputBinding::Gamepad(button));
+            }
+        }
+        let dynamic = DynamicEnum::new(name.to_string(), DynamicVariant::Unit);
+        KeyCode::from_reflect(&dynamic).map(InputBinding::Key)
+    }
+
+    /// 显示名
+    pub fn label(self) -> String {
+        match self {
+            InputBinding::Key(key) => {
+                let name = format!("{:?}", key);
+                ["Key", "Digit"]
+                    .iter()
+                    .find_map(|prefix| name.strip_prefix(prefix))
+                    .filter(|rest| rest.chars().count() == 1)
+                    .map_or_else(|| name.clone(), str::to_string)
+            }
+            InputBinding::Mouse(button) => match button {
+                MouseButton::Left => "鼠标左键".to_string(),
+                MouseButton::Right => "鼠标右键".to_string(),
+                MouseButton::Middle => "鼠标中键".to_string(),
+                MouseButton::Back => "鼠标后退键".to_string(),
+                MouseButton::Forward => "鼠标前进键".to_string(),
+                MouseButton::Other(index) => format!("鼠标键 {}", index),
+            },
+            // 按 Xbox 手柄的印字显示，其他手柄键位相同
+            InputBinding::Gamepad(button) => match button {
+                GamepadButton::South => "手柄 A".to_string(),
+                GamepadButton::East => "手柄 B".to_string(),
+                GamepadButton::West => "手柄 X".to_string(),
+                GamepadButton::North => "手柄 Y".to_string(),
+                GamepadButton::LeftTrigger => "手柄 LB".to_string(),
+                GamepadButton::RightTrigger => "手柄 RB".to_string(),
+                GamepadButton::LeftTrigger2 => "手柄 LT".to_string(),
+                GamepadButton::RightTrigger2 => "手柄 RT".to_string(),
+                GamepadButton::Select => "手柄视图键".to_string(),
+                GamepadButton::Start => "手柄菜单键".to_string(),
+                GamepadButton::LeftThumb => "左摇杆按下".to_string(),
+                GamepadButton::RightThumb => "右摇杆按下".to_string(),
+                GamepadButton::DPadUp => "十字键上".to_string(),
+                GamepadButton::DPadDown => "十字键下".to_string(),
+                GamepadButton::DPadLeft => "十字键左".to_string(),
+                GamepadButton::DPadRight => "十字键右".to_string(),
+                other => format!("手柄 {:?}", other),
+            },
+        }
+    }
+}
+
+/// 推过死区的摇杆读数，死区内为零，死区外按剩余行程重新映射到 0 到 1
+pub fn apply_stick_deadzone(stick: Vec2, deadzone: f32) -> Vec2 {
+    let length = stick.length();
+    let deadzone = deadzone.clamp(0.0, 0.99);
+    if length <= deadzone {
+        return Vec2::ZERO;
+    }
+    let strength = ((length - deadzone) / (1.0 - deadzone)).min(1.0);
+    stick / length * strength
+}
+
+/// 动作与按键的绑定
+///
+/// # 设计思路
+/// 1. 一个动作可以绑定多个按键、鼠标键或手柄键，任意一个按住即视为该动作按住
+/// 2. 同一个键绑定到多个动作视为冲突，由改键界面提示玩家替换或取消，这里不自动处理
+/// 3. 只有与默认绑定不同的动作写入用户配置，之后新增的动作照样拿到默认按键
+#[derive(Debug, Clone, PartialEq, Resource)]
 pub struct KeyBindings {
-    pub bindings: HashMap<GameAction, KeyCode>,
+    pub bindings: HashMap<GameAction, Vec<InputBinding>>,
 }
 
 impl Default for KeyBindings {
     fn default() -> Self {
-        let mut bindings = HashMap::new();
-        bindings.insert(GameAction::MoveForward, KeyCode::KeyW);
-        bindings.insert(GameAction::MoveBackward, KeyCode::KeyS);
-        bindings.insert(GameAction::MoveLeft, KeyCode::KeyA);
-        bindings.insert(GameAction::MoveRight, KeyCode::KeyD);
-        bindings.insert(GameAction::Jump, KeyCode::Space);
-        bindings.insert(GameAction::Attack, KeyCode::KeyF);
-        bindings.insert(GameAction::Interact, KeyCode::KeyE);
-        bindings.insert(GameAction::OpenInventory, KeyCode::KeyI);
-        bindings.insert(GameAction::OpenMap, KeyCode::KeyM);
-        bindings.insert(GameAction::ExitGame, KeyCode::Escape);
-        bindings.insert(GameAction::ZoomIn, KeyCode::Equal);
-        bindings.insert(GameAction::ZoomOut, KeyCode::Minus);
+        let defaults = [
+            (GameAction::MoveForward, KeyCode::KeyW),
+            (GameAction::MoveBackward, KeyCode::KeyS),
+            (GameAction::MoveLeft, KeyCode::KeyA),
+            (GameAction::MoveRight, KeyCode::KeyD),
+            (GameAction::Jump, KeyCode::Space),
+            (GameAction::Attack, KeyCode::KeyF),
+            (GameAction::Dodge, KeyCode::ShiftLeft),
+            (GameAction::Skill1, KeyCode::Digit1),
+            (GameAction::Skill2, KeyCode::Digit2),
+            (GameAction::Skill3, KeyCode::Digit3),
+            (GameAction::Skill4, KeyCode::Digit4),
+            (GameAction::Sprint, KeyCode::ControlLeft),
+            (GameAction::Qinggong, KeyCode::KeyQ),
+            (GameAction::Mount, KeyCode::KeyH),
+            (GameAction::Interact, KeyCode::KeyE),
+            (GameAction::RepairGear, KeyCode::KeyG),
+            (GameAction::OpenInventory, KeyCode::KeyI),
+            (GameAction::OpenSkills, KeyCode::KeyK),
+            (GameAction::OpenMap, KeyCode::KeyM),
+            (GameAction::ToggleMinimap, KeyCode::KeyN),
+            (GameAction::ExitGame, KeyCode::Escape),
+            (GameAction::ZoomIn, KeyCode::Equal),
+            (GameAction::ZoomOut, KeyCode::Minus),
+            (GameAction::ToggleEditMode, KeyCode::KeyB),
+            (GameAction::RotateItem, KeyCode::KeyR),
+            (GameAction::CycleItem, KeyCode::Tab),
+            (GameAction::PlaceItem, KeyCode::Enter),
+            (GameAction::RemoveItem, KeyCode::Backspace),
+            (GameAction::ExportBugReport, KeyCode::F8),
+            (GameAction::ToggleSpectator, KeyCode::F9),
+            (GameAction::OpenOptions, KeyCode::F10),
+            (GameAction::ToggleChunkDebug, KeyCode::F3),
+            (GameAction::ToggleProfiler, KeyCode::F6),
+            (GameAction::CaptureTrace, KeyCode::F7),
+        ];
+        let mouse_defaults = [
+            (GameAction::ClickMove, MouseButton::Left),
+            (GameAction::ContextInteract, MouseButton::Right),
+        ];
+        // 移动由左摇杆驱动，不占用手柄键
+        let gamepad_defaults = [
+            (GameAction::Jump, GamepadButton::South),
+            (GameAction::Dodge, GamepadButton::East),
+            (GameAction::Attack, GamepadButton::West),
+            (GameAction::Interact, GamepadButton::North),
+            (GameAction::Skill1, GamepadButton::DPadUp),
+            (GameAction::Skill2, GamepadButton::DPadRight),
+            (GameAction::Skill3, GamepadButton::DPadDown),
+            (GameAction::Skill4, GamepadButton::DPadLeft),
+            (GameAction::Sprint, GamepadButton::LeftThumb),
+            (GameAction::Qinggong, GamepadButton::RightTrigger2),
+            (GameAction::Mount, GamepadButton::LeftTrigger2),
+            (GameAction::OpenMap, GamepadButton::Select),
+            (GameAction::OpenOptions, GamepadButton::Start),
+        ];
+        let mut bindings: HashMap<_, _> = defaults
+            .into_iter()
+            .map(|(action, key)| (action, vec![InputBinding::Key(key)]))
+            .collect();
+        for (action, button) in mouse_defaults {
+            bindings.insert(action, vec![InputBinding::Mouse(button)]);
+        }
+        for (action, button) in gamepad_defaults {
+            bindings
+                .entry(action)
+                .or_insert_with(Vec::new)
+                .push(InputBinding::Gamepad(button));
+        }
         Self { bindings }
     }
 }
 
+impl KeyBindings {
+    /// 动作绑定的按键
+    pub fn inputs(&self, action: GameAction) -> &[InputBinding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// 界面提示用的按键名：优先取当前设备上绑定的第一个键
+    pub fn prompt(&self, action: GameAction, device: InputDevice) -> String {
+        let inputs = self.inputs(action);
+        inputs
+            .iter()
+            .find(|input| input.device() == device)
+            .or_else(|| inputs.first())
+            .map_or_else(|| "未绑定".to_string(), |input| input.label())
+    }
+
+    /// 给动作添加一个按键，已绑定时不变
+    pub fn bind(&mut self, action: GameAction, input: InputBinding) {
+        let inputs = self.bindings.entry(action).or_default();
+        if inputs.contains(&input) {
+            return;
+        }
+        if inputs.len() >= MAX_BINDINGS_PER_ACTION {
+            inputs.remove(0);
+        }
+        inputs.push(input);
+    }
+
+    /// 从动作上移除一个按键
+    pub fn unbind(&mut self, action: GameAction, input: InputBinding) {
+        if let Some(inputs) = self.bindings.get_mut(&action) {
+            inputs.retain(|bound| *bound != input);
+        }
+    }
+
+    /// 清空动作的按键
+    pub fn clear(&mut self, action: GameAction) {
+        self.bindings.insert(action, Vec::new());
+    }
+
+    /// 已经绑定了这个键的其他动作
+    pub fn conflicts(&self, action: GameAction, input: InputBinding) -> Vec<GameAction> {
+        GameAction::ALL
+            .into_iter()
+            .filter(|other| *other != action && self.inputs(*other).contains(&input))
+            .collect()
+    }
+
+    /// 与默认绑定不同的动作，写入用户配置
+    pub fn to_overrides(&self) -> Vec<BindingOverride> {
+        let defaults = Self::default();
+        GameAction::ALL
+            .into_iter()
+            .filter(|action| self.inputs(*action) != defaults.inputs(*action))
+            .map(|action| BindingOverride {
+                action: action.name(),
+                inputs: self
+                    .inputs(action)
+                    .iter()
+                    .map(|input| input.name())
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// 在默认绑定上套用配置中的改键，返回认不出的动作与按键
+    pub fn from_overrides(overrides: &[BindingOverride]) -> (Self, Vec<String>) {
+        let mut bindings = Self::default();
+        let mut unknown = Vec::new();
+        for entry in overrides {
+            let Some(action) = GameAction::from_name(&entry.action) else {
+                unknown.push(format!("未知的动作 {}", entry.action));
+                continue;
+            };
+            let mut inputs = Vec::new();
+            for name in &entry.inputs {
+                match InputBinding::from_name(name) {
+                    Some(input) if !inputs.contains(&input) => inputs.push(input),
+                    Some(_) => {}
+                    None => unknown.push(format!("{} 的按键 {} 无法识别", entry.action, name)),
+                }
+            }
+            inputs.truncate(MAX_BINDINGS_PER_ACTION);
+            bindings.bindings.insert(action, inputs);
+        }
+        (bindings, unknown)
+    }
+}
+
+/// 把键盘、鼠标与手柄的输入汇总成动作
+///
+/// 左摇杆推过死区后给出带力度的移动方向，同时按方向算作对应的移动动作，
+/// 只认数字动作的系统（例如世界地图平移）也能用摇杆操作
 pub fn handle_input_events(
     keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<(Entity, &Gamepad)>,
     key_bindings: Res<KeyBindings>,
+    settings: Res<Settings>,
     mut input_state: ResMut<crate::resources::InputState>,
 ) {
     input_state.previous_actions = input_state.active_actions.clone();
     input_state.active_actions.clear();
 
-    for (action, key_code) in key_bindings.bindings.iter() {
-        if keyboard.pressed(*key_code) {
-            input_state.active_actions.push(*action);
+    // 记录的手柄断开后改用任意一个已连接的手柄
+    let gamepad = input_state
+        .gamepad
+        .and_then(|entity| gamepads.get(entity).ok())
+        .or_else(|| gamepads.iter().next())
+        .map(|(_, gamepad)| gamepad);
+
+    // 输入文字时按下的键直到松开都不算动作，关闭输入框时按住的回车、退出键不会误触发
+    let mut held = Vec::new();
+    for (action, inputs) in key_bindings.bindings.iter() {
+        if !inputs
+            .iter()
+            .any(|input| input.pressed(&keyboard, &mouse, gamepad))
+        {
+            continue;
+        }
+        held.push(*action);
+        if input_state.typing || input_state.blocked_actions.contains(action) {
+            if !input_state.blocked_actions.contains(action) {
+                input_state.blocked_actions.push(*action);
+            }
+            continue;
+        }
+        input_state.active_actions.push(*action);
+    }
+    input_state
+        .blocked_actions
+        .retain(|action| held.contains(action));
+
+    let stick = if input_state.typing {
+        Vec2::ZERO
+    } else {
+        gamepad.map_or(Vec2::ZERO, |gamepad| {
+            apply_stick_deadzone(gamepad.left_stick(), settings.game.controls.stick_deadzone)
+        })
+    };
+    input_state.move_axis = stick;
+    let stick_actions = [
+        (stick.y, GameAction::MoveForward),
+        (-stick.y, GameAction::MoveBackward),
+        (-stick.x, GameAction::MoveLeft),
+        (stick.x, GameAction::MoveRight),
+    ];
+    for (amount, action) in stick_actions {
+        if amount > STICK_ACTION_THRESHOLD && !input_state.active_actions.contains(&action) {
+            input_state.active_actions.push(action);
         }
     }
+
+    // 最后有操作的设备决定按键提示
+    let used_gamepad = stick != Vec2::ZERO
+        || gamepad.is_some_and(|gamepad| gamepad.get_just_pressed().next().is_some());
+    let used_keyboard =
+        keyboard.get_just_pressed().next().is_some() || mouse.get_just_pressed().next().is_some();
+    if used_gamepad && input_state.device != InputDevice::Gamepad {
+        input_state.device = InputDevice::Gamepad;
+    } else if used_keyboard && input_state.device != InputDevice::KeyboardMouse {
+        input_state.device = InputDevice::KeyboardMouse;
+    }
+}
+
+/// 记录手柄的连接与断开
+///
+/// 新连接的手柄成为当前手柄；当前手柄断开时换用其他手柄，都没有了就切回键鼠提示
+pub fn track_gamepads(
+    mut events: EventReader<GamepadConnectionEvent>,
+    gamepads: Query<Entity, With<Gamepad>>,
+    mut input_state: ResMut<crate::resources::InputState>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for event in events.read() {
+        match &event.connection {
+            GamepadConnection::Connected { name, .. } => {
+                input_state.gamepad = Some(event.gamepad);
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(LogLevel::Info, &format!("手柄已连接: {}", name));
+                }
+            }
+            GamepadConnection::Disconnected => {
+                if input_state.gamepad == Some(event.gamepad) {
+                    input_state.gamepad = gamepads.iter().find(|entity| *entity != event.gamepad);
+                }
+                if input_state.gamepad.is_none() {
+                    input_state.device = InputDevice::KeyboardMouse;
+                }
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(LogLevel::Info, "手柄已断开");
+                }
+            }
+        }
+    }
+}
+
+/// 设置变化时按用户配置重建按键绑定，启动与配置热重载都经由这里生效
+pub fn apply_key_binding_settings(
+    settings: Res<Settings>,
+    mut key_bindings: ResMut<KeyBindings>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    let (bindings, unknown) = KeyBindings::from_overrides(&settings.game.controls.bindings);
+    if let Some(logger) = logger.as_mut() {
+        for message in &unknown {
+            logger.log(LogLevel::Error, &format!("按键配置已忽略: {}", message));
+        }
+    }
+    // 改键界面已经同步过时两者相同，不必再触发一次变化
+    if *key_bindings != bindings {
+        *key_bindings = bindings;
+    }
 }
@@ -1,4 +1,5 @@
 use crate::logging::{GameLogger, LogLevel};
+use crate::network::ServerMessage;
 use bevy::prelude::*;
 
 #[derive(Debug, Event)]
@@ -6,8 +7,10 @@ pub enum NetworkEvent {
     ConnectionSuccess,
     ConnectionFailed,
     Disconnection,
-    MessageReceived(String),
-    MessageSent(String),
+    /// 连接意外断开后正在进行第几次重连
+    Reconnecting(u32),
+    /// 按网络频率分发的服务器消息
+    Message(ServerMessage),
 }
 
 #[derive(Resource, Default)]
@@ -23,7 +26,7 @@ pub struct NetworkState {
 pub fn handle_network_events(
     mut events: EventReader<NetworkEvent>,
     mut state: ResMut<NetworkState>,
-    mut logger: ResMut<GameLogger>,
+    logger: ResMut<GameLogger>,
 ) {
     for event in events.read() {
         match event {
@@ -39,11 +42,12 @@ pub fn handle_network_events(
                 state.is_connected = false;
                 logger.log(LogLevel::Error, "网络连接断开");
             }
-            NetworkEvent::MessageReceived(message) => {
-                logger.log(LogLevel::Debug, &format!("收到消息: {}", message));
+            NetworkEvent::Reconnecting(attempt) => {
+                state.is_connected = false;
+                logger.log(LogLevel::Info, &format!("网络断开，第 {} 次重连", attempt));
             }
-            NetworkEvent::MessageSent(message) => {
-                logger.log(LogLevel::Debug, &format!("发送消息: {}", message));
+            NetworkEvent::Message(message) => {
+                logger.log(LogLevel::Verbose, &format!("收到消息: {}", message.kind()));
             }
         }
     }
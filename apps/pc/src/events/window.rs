@@ -9,13 +9,13 @@ pub fn handle_window_events(
     let mut window = windows.single_mut();
 
     // Alt+Enter 或 Alt+Tab 切换全屏
-    if keyboard.just_pressed(KeyCode::Tab) || keyboard.just_pressed(KeyCode::Enter) {
-        if keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) {
-            window.mode = match window.mode {
-                WindowMode::Windowed => WindowMode::BorderlessFullscreen(MonitorSelection::Primary),
-                _ => WindowMode::Windowed,
-            };
-        }
+    if (keyboard.just_pressed(KeyCode::Tab) || keyboard.just_pressed(KeyCode::Enter))
+        && keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight])
+    {
+        window.mode = match window.mode {
+            WindowMode::Windowed => WindowMode::BorderlessFullscreen(MonitorSelection::Primary),
+            _ => WindowMode::Windowed,
+        };
     }
 
     // Esc 切换到窗口模式
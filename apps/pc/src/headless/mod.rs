@@ -0,0 +1,13 @@
+/// 无窗口运行模块
+///
+/// 不开窗口、不渲染，按固定帧时长模拟区块加载、NPC AI 与历法，跑完指定帧数后写出报告并退出，
+/// 用于持续集成、服务器端模拟与压力测试
+///
+/// # 模块组成
+/// 1. report：运行报告，统计区块、NPC 状态与历法推进
+/// 2. systems：无窗口运行插件及模拟系统
+mod report;
+mod systems;
+
+pub use report::*;
+pub use systems::*;
@@ -0,0 +1,84 @@
+use chrono::Local;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::world::chunk::StreamStats;
+
+/// 无窗口运行报告
+#[derive(Debug, Clone, Serialize)]
+pub struct HeadlessReport {
+    pub started_at: String,
+    pub seed: u32,
+    pub ticks: u32,
+    pub tick_rate: f32,
+    /// 模拟的游戏时间（秒）
+    pub simulated_secs: f64,
+    /// 实际耗时（秒）
+    pub wall_secs: f64,
+    /// 每秒实际跑完的帧数
+    pub ticks_per_second: f64,
+    pub chunks_loaded: u64,
+    pub chunks_unloaded: u64,
+    pub chunks_resident: usize,
+    /// 每个区块的生成耗时（毫秒）
+    pub chunk_generate_ms: StreamStats,
+    pub npcs: usize,
+    /// 结束时各 AI 状态的 NPC 数
+    pub ai_states: BTreeMap<String, usize>,
+    pub calendar_start: String,
+    pub calendar_end: String,
+    /// 历法推进的游戏小时数
+    pub game_hours: f64,
+    pub new_days: u32,
+    pub season_changes: u32,
+}
+
+impl HeadlessReport {
+    /// 写入报告目录，返回文件路径
+    pub fn save(&self, dir: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        fs::create_dir_all(dir)?;
+        let stamp = Local::now().format("%Y%m%d-%H%M%S");
+        let path = Path::new(dir).join(format!("headless_{}_{}.json", self.seed, stamp));
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+}
+
+impl fmt::Display for HeadlessReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "无窗口运行（种子 {}）：{} 帧，游戏时间 {:.1} 秒，实际 {:.2} 秒，{:.0} 帧/秒",
+            self.seed, self.ticks, self.simulated_secs, self.wall_secs, self.ticks_per_second
+        )?;
+        writeln!(
+            f,
+            "  区块：加载 {} 卸载 {} 常驻 {}，生成耗时 平均 {:.2} 毫秒 / p95 {:.2} / 最长 {:.2}",
+            self.chunks_loaded,
+            self.chunks_unloaded,
+            self.chunks_resident,
+            self.chunk_generate_ms.mean,
+            self.chunk_generate_ms.p95,
+            self.chunk_generate_ms.max
+        )?;
+        let states = self
+            .ai_states
+            .iter()
+            .map(|(state, count)| format!("{} {}", state, count))
+            .collect::<Vec<_>>()
+            .join("，");
+        writeln!(f, "  NPC：{} 个（{}）", self.npcs, states)?;
+        writeln!(
+            f,
+            "  历法：{} -> {}，推进 {:.1} 小时，新的一天 {} 次，换季 {} 次",
+            self.calendar_start,
+            self.calendar_end,
+            self.game_hours,
+            self.new_days,
+            self.season_changes
+        )
+    }
+}
@@ -0,0 +1,277 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use chrono::Local;
+use std::collections::BTreeMap;
+use std::f32::consts::TAU;
+use std::time::{Duration, Instant};
+
+use super::HeadlessReport;
+use crate::combat::{CombatActionEvent, DamageEvent};
+use crate::logging::{GameLogger, LogLevel};
+use crate::render::animation::AnimationFinished;
+use crate::time::{GameCalendar, NewDay, SeasonChanged};
+use crate::world::chunk::{ChunkManager, StreamStats, TILE_PIXELS};
+use crate::world::entity::{
+    BehaviorTrace, Character, Npc, NpcAiPlugin, NpcType, Perception, Player,
+};
+use crate::world::map::MapManager;
+
+/// 无窗口运行报告的输出目录
+pub const HEADLESS_REPORT_DIR: &str = "reports/headless";
+
+/// 出生点周围依次放置的 NPC 类型
+const NPC_TYPES: [NpcType; 4] = [
+    NpcType::Villager,
+    NpcType::Merchant,
+    NpcType::Guard,
+    NpcType::Enemy,
+];
+
+/// 无窗口运行设置
+///
+/// # 参数说明
+/// - ticks: 模拟的帧数，跑完后退出
+/// - tick_rate: 每秒模拟帧数，每帧推进 1/tick_rate 秒游戏时间，与实际耗时无关
+/// - npcs: 在出生点周围放置的 NPC 数
+/// - travel_speed: 玩家向东移动的速度（瓦片/秒），带动区块加载与卸载
+#[derive(Resource, Debug, Clone)]
+pub struct HeadlessSettings {
+    pub ticks: u32,
+    pub tick_rate: f32,
+    pub seed: u32,
+    pub npcs: usize,
+    pub travel_speed: f32,
+    pub output_dir: String,
+}
+
+impl HeadlessSettings {
+    /// 每帧推进的游戏时间
+    pub fn tick_duration(&self) -> Duration {
+        Duration::from_secs_f32(1.0 / self.tick_rate.max(1.0))
+    }
+}
+
+impl Default for HeadlessSettings {
+    fn default() -> Self {
+        Self {
+            ticks: 3600,
+            tick_rate: 60.0,
+            seed: 42,
+            npcs: 32,
+            travel_speed: 8.0,
+            output_dir: HEADLESS_REPORT_DIR.to_string(),
+        }
+    }
+}
+
+/// 运行中的统计
+#[derive(Resource)]
+struct HeadlessRun {
+    tick: u32,
+    started_at: String,
+    started: Instant,
+    generate_ms: Vec<f64>,
+    calendar_start: String,
+    hours_start: f64,
+    new_days: u32,
+    season_changes: u32,
+}
+
+/// 无窗口运行插件
+///
+/// # 设计思路
+/// 1. 只加入不依赖窗口、渲染与资源加载的部分：NPC AI 直接复用游戏中的插件，历法由时间插件推进
+/// 2. 区块按加载预算只生成数据，不生成瓦片实体，与无窗口压测一致
+/// 3. 固定步长推进时间，同一种子与帧数的结果可复现，实际耗时只写进报告
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        // AI 系统读取的战斗与动画事件，无窗口时没有来源，注册后保持为空
+        app.add_event::<AnimationFinished>()
+            .add_event::<CombatActionEvent>()
+            .add_event::<DamageEvent>()
+            .add_plugins(NpcAiPlugin);
+
+        app.init_resource::<HeadlessSettings>()
+            .init_resource::<ChunkManager>()
+            .add_systems(Startup, setup_headless_world)
+            .add_systems(
+                Update,
+                (
+                    (move_headless_player, stream_headless_chunks).chain(),
+                    count_calendar_events,
+                ),
+            )
+            .add_systems(Last, finish_headless_run);
+    }
+}
+
+/// 按种子生成世界，放置玩家与 NPC
+fn setup_headless_world(
+    mut commands: Commands,
+    settings: Res<HeadlessSettings>,
+    mut chunk_manager: ResMut<ChunkManager>,
+    calendar: Res<GameCalendar>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let map_manager = MapManager::new(settings.seed);
+    chunk_manager.initialize_terrain_generator(&map_manager);
+    commands.insert_resource(map_manager);
+
+    commands.spawn((
+        Player::default(),
+        Character {
+            name: "Player".to_string(),
+            ..default()
+        },
+        Transform::default(),
+    ));
+    // 由近到远分几圈摆放，各类 NPC 都有离玩家近的
+    for index in 0..settings.npcs {
+        let npc_type = NPC_TYPES[index % NPC_TYPES.len()];
+        let angle = index as f32 / settings.npcs as f32 * TAU;
+        let radius = (5 + index % 4 * 2) as f32 * TILE_PIXELS;
+        commands.spawn((
+            Npc {
+                npc_type,
+                ..default()
+            },
+            Character {
+                name: format!("{:?}{}", npc_type, index),
+                ..default()
+            },
+            Transform::from_xyz(angle.cos() * radius, angle.sin() * radius, 0.0),
+            BehaviorTrace::default(),
+            Perception::default(),
+        ));
+    }
+
+    commands.insert_resource(HeadlessRun {
+        tick: 0,
+        started_at: Local::now().to_rfc3339(),
+        started: Instant::now(),
+        generate_ms: Vec::new(),
+        calendar_start: calendar.date_label(),
+        hours_start: calendar.total_hours(),
+        new_days: 0,
+        season_changes: 0,
+    });
+    if let Some(logger) = logger.as_mut() {
+        logger.log(
+            LogLevel::Info,
+            &format!(
+                "无窗口运行开始：种子 {}，{} 帧，{} 个 NPC",
+                settings.seed, settings.ticks, settings.npcs
+            ),
+        );
+    }
+}
+
+fn move_headless_player(
+    time: Res<Time>,
+    settings: Res<HeadlessSettings>,
+    mut players: Query<&mut Transform, With<Player>>,
+) {
+    for mut transform in players.iter_mut() {
+        transform.translation.x += settings.travel_speed * TILE_PIXELS * time.delta_secs();
+    }
+}
+
+/// 按加载预算生成玩家周围的区块数据，卸载视野外的区块
+fn stream_headless_chunks(
+    mut chunk_manager: ResMut<ChunkManager>,
+    map_manager: Res<MapManager>,
+    players: Query<&Transform, With<Player>>,
+    mut run: ResMut<HeadlessRun>,
+) {
+    let Ok(transform) = players.get_single() else {
+        return;
+    };
+    chunk_manager.update_player_position(transform.translation.x, transform.translation.y);
+
+    let budget = chunk_manager.load_budget.max(1);
+    for coord in chunk_manager.get_chunks_to_load().into_iter().take(budget) {
+        let started = Instant::now();
+        chunk_manager.generate_chunk_data(coord, &map_manager);
+        run.generate_ms
+            .push(started.elapsed().as_secs_f64() * 1000.0);
+        chunk_manager.insert_chunk(coord, Entity::PLACEHOLDER);
+    }
+    for coord in chunk_manager.get_chunks_to_unload() {
+        chunk_manager.remove_chunk(coord);
+    }
+}
+
+fn count_calendar_events(
+    mut new_days: EventReader<NewDay>,
+    mut seasons: EventReader<SeasonChanged>,
+    mut run: ResMut<HeadlessRun>,
+) {
+    run.new_days += new_days.read().count() as u32;
+    run.season_changes += seasons.read().count() as u32;
+}
+
+/// 跑完指定帧数后写出报告并退出，报告写入失败时以错误状态退出
+#[allow(clippy::too_many_arguments)]
+fn finish_headless_run(
+    time: Res<Time>,
+    settings: Res<HeadlessSettings>,
+    mut run: ResMut<HeadlessRun>,
+    chunk_manager: Res<ChunkManager>,
+    calendar: Res<GameCalendar>,
+    npcs: Query<&Npc>,
+    mut exits: EventWriter<AppExit>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    run.tick += 1;
+    if run.tick < settings.ticks {
+        return;
+    }
+
+    let wall_secs = run.started.elapsed().as_secs_f64();
+    let stats = chunk_manager.stream_stats();
+    let mut ai_states = BTreeMap::new();
+    for npc in npcs.iter() {
+        *ai_states.entry(format!("{:?}", npc.ai_state)).or_insert(0) += 1;
+    }
+    let report = HeadlessReport {
+        started_at: run.started_at.clone(),
+        seed: settings.seed,
+        ticks: run.tick,
+        tick_rate: settings.tick_rate,
+        simulated_secs: time.elapsed_secs_f64(),
+        wall_secs,
+        ticks_per_second: if wall_secs > 0.0 {
+            run.tick as f64 / wall_secs
+        } else {
+            0.0
+        },
+        chunks_loaded: stats.total_loaded,
+        chunks_unloaded: stats.total_unloaded,
+        chunks_resident: stats.loaded,
+        chunk_generate_ms: StreamStats::from_values(&run.generate_ms),
+        npcs: npcs.iter().count(),
+        ai_states,
+        calendar_start: run.calendar_start.clone(),
+        calendar_end: calendar.date_label(),
+        game_hours: calendar.total_hours() - run.hours_start,
+        new_days: run.new_days,
+        season_changes: run.season_changes,
+    };
+
+    print!("{}", report);
+    let exit = match report.save(&settings.output_dir) {
+        Ok(path) => {
+            println!("报告已写入 {}", path.display());
+            AppExit::Success
+        }
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("写入无窗口运行报告失败: {}", e));
+            }
+            AppExit::error()
+        }
+    };
+    exits.send(exit);
+}
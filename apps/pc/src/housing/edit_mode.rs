@@ -0,0 +1,265 @@
+use bevy::prelude::*;
+
+use super::{
+    can_place_furniture, furniture_def, interior_origin, rotate_footprint, save_housing,
+    CurrentInterior, HomeDatabase, HousingSettings, HousingState, PlacedFurniture,
+    FURNITURE_CELL_PIXELS,
+};
+use crate::events::input::GameAction;
+use crate::items::{Inventory, ItemDatabase, ItemInstance};
+use crate::logging::GameLogger;
+use crate::render::components::{LayerComponent, RenderLayer};
+use crate::resources::InputState;
+use crate::world::entity::{Character, Player};
+
+/// 家具摆放模式
+///
+/// 只能在自己的宅院内开启，开启后角色不能移动，方向键改为移动格子光标
+#[derive(Resource, Debug, Default)]
+pub struct HousingEditMode {
+    pub active: bool,
+    /// 光标所在格子
+    pub cursor: [u32; 2],
+    /// 待摆放家具是否旋转
+    pub rotated: bool,
+    /// 待摆放的家具物品
+    pub selected: Option<String>,
+}
+
+/// 摆放预览
+#[derive(Component)]
+pub struct EditCursor;
+
+/// 开关摆放模式，退出时存档
+pub fn toggle_edit_mode(
+    input_state: Res<InputState>,
+    settings: Res<HousingSettings>,
+    current: Res<CurrentInterior>,
+    housing: Res<HousingState>,
+    mut edit_mode: ResMut<HousingEditMode>,
+    mut players: Query<&mut Character, With<Player>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let in_own_home = current
+        .home_id
+        .as_deref()
+        .is_some_and(|home_id| housing.owns(home_id));
+
+    let toggled = input_state.is_action_just_pressed(GameAction::ToggleEditMode);
+    let should_exit = edit_mode.active && (toggled || !in_own_home);
+    let should_enter = !edit_mode.active && toggled && in_own_home;
+    if !should_exit && !should_enter {
+        return;
+    }
+
+    edit_mode.active = should_enter;
+    if let Ok(mut character) = players.get_single_mut() {
+        character.can_move = !should_enter;
+    }
+
+    if should_exit {
+        save_housing(&housing, &settings, &mut logger);
+    }
+}
+
+/// 摆放模式下的按键：移动光标、切换家具、旋转、摆放与收起
+pub fn handle_edit_actions(
+    input_state: Res<InputState>,
+    database: Res<ItemDatabase>,
+    homes: Res<HomeDatabase>,
+    current: Res<CurrentInterior>,
+    mut housing: ResMut<HousingState>,
+    mut edit_mode: ResMut<HousingEditMode>,
+    mut players: Query<&mut Inventory, With<Player>>,
+) {
+    if !edit_mode.active {
+        return;
+    }
+    let Some(home_id) = current.home_id.clone() else {
+        return;
+    };
+    let Some(home) = homes.get(&home_id) else {
+        return;
+    };
+    let Ok(mut inventory) = players.get_single_mut() else {
+        return;
+    };
+    let grid = home.interior_size;
+
+    // 移动光标
+    let pressed = |action| input_state.is_action_just_pressed(action);
+    let [mut x, mut y] = edit_mode.cursor;
+    if pressed(GameAction::MoveLeft) {
+        x = x.saturating_sub(1);
+    }
+    if pressed(GameAction::MoveRight) {
+        x += 1;
+    }
+    if pressed(GameAction::MoveBackward) {
+        y = y.saturating_sub(1);
+    }
+    if pressed(GameAction::MoveForward) {
+        y += 1;
+    }
+    let cursor = [
+        x.min(grid[0].saturating_sub(1)),
+        y.min(grid[1].saturating_sub(1)),
+    ];
+    if edit_mode.cursor != cursor {
+        edit_mode.cursor = cursor;
+    }
+
+    // 在背包中的家具之间切换
+    let mut owned_furniture: Vec<String> = inventory
+        .slots
+        .iter()
+        .flatten()
+        .filter(|item| furniture_def(&database, &item.item_id).is_some())
+        .map(|item| item.item_id.clone())
+        .collect();
+    owned_furniture.sort();
+    owned_furniture.dedup();
+
+    let selection_valid = edit_mode
+        .selected
+        .as_ref()
+        .is_some_and(|id| owned_furniture.contains(id));
+    if pressed(GameAction::CycleItem) || !selection_valid {
+        let next = match edit_mode
+            .selected
+            .as_ref()
+            .and_then(|id| owned_furniture.iter().position(|other| other == id))
+        {
+            Some(index) if selection_valid => {
+                owned_furniture.get((index + 1) % owned_furniture.len())
+            }
+            _ => owned_furniture.first(),
+        };
+        if edit_mode.selected.as_ref() != next {
+            edit_mode.selected = next.cloned();
+        }
+    }
+
+    if pressed(GameAction::RotateItem) {
+        edit_mode.rotated = !edit_mode.rotated;
+    }
+
+    let Some(owned) = housing.owned.get(&home_id) else {
+        return;
+    };
+
+    if pressed(GameAction::PlaceItem) {
+        let Some(item_id) = edit_mode.selected.clone() else {
+            return;
+        };
+        let Some(def) = furniture_def(&database, &item_id) else {
+            return;
+        };
+        let footprint = rotate_footprint(def.footprint, edit_mode.rotated);
+        if can_place_furniture(&database, grid, &owned.furniture, cursor, footprint)
+            && inventory.remove(&item_id, 1)
+        {
+            if let Some(owned) = housing.owned.get_mut(&home_id) {
+                owned.furniture.push(PlacedFurniture {
+                    item_id,
+                    cell: cursor,
+                    rotated: edit_mode.rotated,
+                });
+            }
+        }
+    } else if pressed(GameAction::RemoveItem) {
+        let Some(index) = owned
+            .furniture
+            .iter()
+            .position(|placed| placed.covers(&database, cursor))
+        else {
+            return;
+        };
+
+        // 背包放不下时保持原样
+        let item_id = owned.furniture[index].item_id.clone();
+        if inventory.add(&database, ItemInstance::new(&database, &item_id, 1)) == 0 {
+            if let Some(owned) = housing.owned.get_mut(&home_id) {
+                owned.furniture.remove(index);
+            }
+        }
+    }
+}
+
+/// 更新摆放预览：可以摆放时为绿色，否则为红色
+pub fn update_edit_cursor(
+    mut commands: Commands,
+    database: Res<ItemDatabase>,
+    homes: Res<HomeDatabase>,
+    current: Res<CurrentInterior>,
+    housing: Res<HousingState>,
+    edit_mode: Res<HousingEditMode>,
+    mut cursors: Query<(Entity, &mut Sprite, &mut Transform), With<EditCursor>>,
+) {
+    let target = edit_mode
+        .active
+        .then_some(current.home_id.as_deref())
+        .flatten()
+        .and_then(|home_id| {
+            let home = homes.get(home_id)?;
+            let index = homes.index_of(home_id)?;
+            let owned = housing.owned.get(home_id)?;
+            Some((home, index, owned))
+        });
+
+    let Some((home, index, owned)) = target else {
+        for (entity, _, _) in cursors.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+
+    let footprint = edit_mode
+        .selected
+        .as_ref()
+        .and_then(|id| furniture_def(&database, id))
+        .map_or([1, 1], |def| {
+            rotate_footprint(def.footprint, edit_mode.rotated)
+        });
+    let placeable = edit_mode.selected.is_some()
+        && can_place_furniture(
+            &database,
+            home.interior_size,
+            &owned.furniture,
+            edit_mode.cursor,
+            footprint,
+        );
+
+    let size = Vec2::new(footprint[0] as f32, footprint[1] as f32) * FURNITURE_CELL_PIXELS;
+    let position = interior_origin(index)
+        + Vec2::new(edit_mode.cursor[0] as f32, edit_mode.cursor[1] as f32) * FURNITURE_CELL_PIXELS
+        + size * 0.5;
+    let color = if placeable {
+        Color::srgba(0.3, 0.9, 0.3, 0.45)
+    } else {
+        Color::srgba(0.9, 0.3, 0.3, 0.45)
+    };
+
+    match cursors.get_single_mut() {
+        Ok((_, mut sprite, mut transform)) => {
+            sprite.color = color;
+            sprite.custom_size = Some(size);
+            transform.translation = position.extend(transform.translation.z);
+        }
+        Err(_) => {
+            commands.spawn((
+                EditCursor,
+                Sprite {
+                    color,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                Transform::from_translation(position.extend(0.0)),
+                LayerComponent {
+                    layer: RenderLayer::Effects,
+                    sub_order: 0,
+                },
+            ));
+        }
+    }
+}
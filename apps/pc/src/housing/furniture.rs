@@ -0,0 +1,179 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::items::{ItemDatabase, StashContainer, StashScope};
+use crate::render::components::{LayerComponent, RenderLayer};
+use crate::rest::RestSpot;
+
+/// 家具格子的像素尺寸
+pub const FURNITURE_CELL_PIXELS: f32 = 32.0;
+
+/// 家具功能
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FurnitureKind {
+    /// 床：休息并推进时间
+    Bed,
+    /// 储物箱：宅院专属仓库
+    Stash,
+    /// 工作台：锻造、炼丹等，按台子类型区分
+    CraftingStation { station: String },
+    /// 摆设
+    Decoration,
+}
+
+/// 家具物品的摆放数据，写在物品定义中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FurnitureDef {
+    pub kind: FurnitureKind,
+    /// 占用的格子数 [宽, 高]
+    #[serde(default = "default_footprint")]
+    pub footprint: [u32; 2],
+}
+
+fn default_footprint() -> [u32; 2] {
+    [1, 1]
+}
+
+/// 已摆放的家具，随存档保存
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlacedFurniture {
+    pub item_id: String,
+    /// 左下角所在格子
+    pub cell: [u32; 2],
+    /// 是否旋转 90 度
+    #[serde(default)]
+    pub rotated: bool,
+}
+
+impl PlacedFurniture {
+    /// 实际占用的格子数，旋转后宽高互换
+    pub fn footprint(&self, database: &ItemDatabase) -> [u32; 2] {
+        let footprint =
+            furniture_def(database, &self.item_id).map_or(default_footprint(), |def| def.footprint);
+        rotate_footprint(footprint, self.rotated)
+    }
+
+    /// 是否覆盖某个格子
+    pub fn covers(&self, database: &ItemDatabase, cell: [u32; 2]) -> bool {
+        let [w, h] = self.footprint(database);
+        cell[0] >= self.cell[0]
+            && cell[0] < self.cell[0] + w
+            && cell[1] >= self.cell[1]
+            && cell[1] < self.cell[1] + h
+    }
+}
+
+/// 查询物品的家具数据
+pub fn furniture_def<'a>(database: &'a ItemDatabase, item_id: &str) -> Option<&'a FurnitureDef> {
+    database.get(item_id).and_then(|def| def.furniture.as_ref())
+}
+
+/// 旋转后的占用尺寸
+pub fn rotate_footprint(footprint: [u32; 2], rotated: bool) -> [u32; 2] {
+    if rotated {
+        [footprint[1], footprint[0]]
+    } else {
+        footprint
+    }
+}
+
+/// 检查家具能否摆在指定位置：不能超出室内范围，也不能与已有家具重叠
+pub fn can_place_furniture(
+    database: &ItemDatabase,
+    grid_size: [u32; 2],
+    placed: &[PlacedFurniture],
+    cell: [u32; 2],
+    footprint: [u32; 2],
+) -> bool {
+    if cell[0] + footprint[0] > grid_size[0] || cell[1] + footprint[1] > grid_size[1] {
+        return false;
+    }
+
+    placed.iter().all(|other| {
+        let [w, h] = other.footprint(database);
+        cell[0] + footprint[0] <= other.cell[0]
+            || other.cell[0] + w <= cell[0]
+            || cell[1] + footprint[1] <= other.cell[1]
+            || other.cell[1] + h <= cell[1]
+    })
+}
+
+/// 家具实体标记
+#[derive(Component, Debug, Clone)]
+pub struct FurnitureEntity;
+
+/// 床，休息由 `RestSpot` 处理
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Bed;
+
+/// 工作台
+#[derive(Component, Debug, Clone)]
+pub struct CraftingStation {
+    pub station: String,
+}
+
+/// 玩家附近可用的工作台，供制作界面查询
+#[derive(Resource, Debug, Default)]
+pub struct NearbyCraftingStations {
+    pub stations: Vec<String>,
+}
+
+/// 在室内生成一件家具
+///
+/// `origin` 为室内左下角格子的世界坐标
+pub fn spawn_furniture(
+    commands: &mut Commands,
+    database: &ItemDatabase,
+    home_id: &str,
+    origin: Vec2,
+    placed: &PlacedFurniture,
+) -> Option<Entity> {
+    let def = furniture_def(database, &placed.item_id)?;
+    let [w, h] = placed.footprint(database);
+    let size = Vec2::new(w as f32, h as f32) * FURNITURE_CELL_PIXELS;
+    let position = origin
+        + Vec2::new(placed.cell[0] as f32, placed.cell[1] as f32) * FURNITURE_CELL_PIXELS
+        + size * 0.5;
+
+    let color = match def.kind {
+        FurnitureKind::Bed => Color::srgb(0.6, 0.35, 0.3),
+        FurnitureKind::Stash => Color::srgb(0.45, 0.3, 0.15),
+        FurnitureKind::CraftingStation { .. } => Color::srgb(0.4, 0.4, 0.45),
+        FurnitureKind::Decoration => Color::srgb(0.55, 0.5, 0.35),
+    };
+
+    let mut entity = commands.spawn((
+        FurnitureEntity,
+        Name::new(format!("Furniture: {}", placed.item_id)),
+        Sprite {
+            color,
+            custom_size: Some(size),
+            ..default()
+        },
+        Transform::from_translation(position.extend(0.0)),
+        LayerComponent {
+            layer: RenderLayer::Decoration,
+            sub_order: 0,
+        },
+    ));
+
+    match &def.kind {
+        FurnitureKind::Bed => {
+            entity.insert((Bed, RestSpot::bed()));
+        }
+        FurnitureKind::Stash => {
+            entity.insert(StashContainer {
+                name: "储物箱".to_string(),
+                scope: StashScope::Location(format!("home:{}", home_id)),
+            });
+        }
+        FurnitureKind::CraftingStation { station } => {
+            entity.insert(CraftingStation {
+                station: station.clone(),
+            });
+        }
+        FurnitureKind::Decoration => {}
+    }
+
+    Some(entity.id())
+}
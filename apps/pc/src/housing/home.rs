@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::PlacedFurniture;
+
+/// 宅院数据文件路径
+pub const HOME_DATA_PATH: &str = "src/config/homes.json";
+
+/// 宅院存档路径
+pub const HOUSING_SAVE_PATH: &str = "saves/housing.json";
+
+/// 宅院定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HomeDef {
+    pub id: String,
+    pub name: String,
+    /// 所在村镇
+    pub village: String,
+    /// 售价（银两）
+    pub price: u32,
+    /// 大门的世界坐标
+    pub entrance: [f32; 2],
+    /// 室内格子数 [宽, 高]
+    pub interior_size: [u32; 2],
+}
+
+/// 宅院数据文件格式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HomeDataFile {
+    homes: Vec<HomeDef>,
+}
+
+/// 宅院数据库
+///
+/// 启动时从数据文件加载，运行期间只读
+#[derive(Resource, Debug, Clone, Default)]
+pub struct HomeDatabase {
+    homes: Vec<HomeDef>,
+}
+
+impl HomeDatabase {
+    /// 从数据文件加载
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let data: HomeDataFile = serde_json::from_str(&content)?;
+        Ok(Self { homes: data.homes })
+    }
+
+    /// 获取宅院定义
+    pub fn get(&self, id: &str) -> Option<&HomeDef> {
+        self.homes.iter().find(|home| home.id == id)
+    }
+
+    /// 宅院在数据文件中的序号，用于分配室内实例的位置
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.homes.iter().position(|home| home.id == id)
+    }
+
+    /// 全部宅院
+    pub fn homes(&self) -> &[HomeDef] {
+        &self.homes
+    }
+}
+
+/// 已购置的宅院
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OwnedHome {
+    /// 摆放的家具
+    pub furniture: Vec<PlacedFurniture>,
+}
+
+/// 玩家的宅院状态，随存档保存
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HousingState {
+    /// 宅院ID -> 宅院
+    pub owned: HashMap<String, OwnedHome>,
+}
+
+impl HousingState {
+    /// 从存档读取
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 写入存档
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// 是否拥有某处宅院
+    pub fn owns(&self, home_id: &str) -> bool {
+        self.owned.contains_key(home_id)
+    }
+}
+
+/// 宅院大门
+#[derive(Component, Debug, Clone)]
+pub struct HomeEntrance {
+    pub home_id: String,
+}
+
+/// 购置宅院请求
+#[derive(Event, Debug, Clone)]
+pub struct PurchaseHomeRequest {
+    /// 付款的实体
+    pub buyer: Entity,
+    pub home_id: String,
+}
@@ -0,0 +1,307 @@
+use bevy::prelude::*;
+
+use super::{
+    spawn_furniture, CraftingStation, FurnitureEntity, HomeDatabase, HomeEntrance, HousingState,
+    NearbyCraftingStations, PurchaseHomeRequest, FURNITURE_CELL_PIXELS,
+};
+use crate::analytics::PlaytestSample;
+use crate::interaction::{Interacted, InteractionKind};
+use crate::items::{Inventory, ItemDatabase};
+use crate::logging::{GameLogger, LogLevel};
+use crate::render::components::{LayerComponent, RenderLayer};
+use crate::render::lighting::LightSource;
+use crate::world::entity::Player;
+
+/// 室内实例的起始位置，远离野外地图
+const INTERIOR_ORIGIN: Vec2 = Vec2::new(-100_000.0, -100_000.0);
+
+/// 相邻两处室内实例的间距
+const INTERIOR_SPACING: f32 = 4096.0;
+
+/// 宅院配置
+#[derive(Resource, Debug, Clone)]
+pub struct HousingSettings {
+    /// 大门、床、工作台的交互距离
+    pub interact_range: f32,
+    /// 存档路径
+    pub save_path: String,
+}
+
+impl Default for HousingSettings {
+    fn default() -> Self {
+        Self {
+            interact_range: 40.0,
+            save_path: super::HOUSING_SAVE_PATH.to_string(),
+        }
+    }
+}
+
+/// 玩家当前所在的室内
+#[derive(Resource, Debug, Default)]
+pub struct CurrentInterior {
+    /// 宅院ID，在野外时为 None
+    pub home_id: Option<String>,
+    /// 离开室内时回到的位置
+    pub return_position: Vec3,
+}
+
+/// 室内场景实体标记，离开时统一销毁
+#[derive(Component)]
+pub struct InteriorRoot;
+
+/// 室内出口
+#[derive(Component)]
+pub struct InteriorExit;
+
+/// 宅院室内左下角的世界坐标
+///
+/// 每处宅院占据一块独立区域，互不重叠
+pub fn interior_origin(index: usize) -> Vec2 {
+    INTERIOR_ORIGIN + Vec2::new(index as f32 * INTERIOR_SPACING, 0.0)
+}
+
+/// 在各宅院门口生成大门
+pub fn spawn_home_entrances(mut commands: Commands, homes: Res<HomeDatabase>) {
+    for home in homes.homes() {
+        commands.spawn((
+            HomeEntrance {
+                home_id: home.id.clone(),
+            },
+            Name::new(format!("HomeEntrance: {}", home.name)),
+            Sprite {
+                color: Color::srgb(0.5, 0.25, 0.1),
+                custom_size: Some(Vec2::new(24.0, 32.0)),
+                ..default()
+            },
+            Transform::from_xyz(home.entrance[0], home.entrance[1], 0.0),
+            LayerComponent {
+                layer: RenderLayer::Decoration,
+                sub_order: 0,
+            },
+            // 门口挂着灯笼
+            LightSource::lantern(),
+        ));
+    }
+}
+
+/// 交互键作用到大门时：已购置则进入，未购置则发起购买；作用到室内出口时离开
+pub fn use_home_entrances(
+    mut interacted: EventReader<Interacted>,
+    homes: Res<HomeDatabase>,
+    housing: Res<HousingState>,
+    mut current: ResMut<CurrentInterior>,
+    mut players: Query<(Entity, &mut Transform), With<Player>>,
+    entrances: Query<&HomeEntrance, Without<Player>>,
+    mut purchases: EventWriter<PurchaseHomeRequest>,
+) {
+    let Some(event) = interacted
+        .read()
+        .filter(|event| {
+            matches!(
+                event.kind,
+                InteractionKind::Home | InteractionKind::LeaveHome
+            )
+        })
+        .last()
+        .copied()
+    else {
+        return;
+    };
+    let Ok((player, mut transform)) = players.get_single_mut() else {
+        return;
+    };
+
+    if event.kind == InteractionKind::LeaveHome {
+        if current.home_id.is_some() {
+            transform.translation = current.return_position;
+            current.home_id = None;
+        }
+        return;
+    }
+
+    let Ok(entrance) = entrances.get(event.entity) else {
+        return;
+    };
+
+    if !housing.owns(&entrance.home_id) {
+        purchases.send(PurchaseHomeRequest {
+            buyer: player,
+            home_id: entrance.home_id.clone(),
+        });
+        return;
+    }
+
+    let (Some(home), Some(index)) = (
+        homes.get(&entrance.home_id),
+        homes.index_of(&entrance.home_id),
+    ) else {
+        return;
+    };
+
+    // 从室内门口（下边中央）进入
+    let spawn_point = interior_origin(index)
+        + Vec2::new(home.interior_size[0] as f32 * 0.5, 0.5) * FURNITURE_CELL_PIXELS;
+    current.return_position = transform.translation - Vec3::Y * FURNITURE_CELL_PIXELS;
+    current.home_id = Some(entrance.home_id.clone());
+    transform.translation = spawn_point.extend(transform.translation.z);
+}
+
+/// 处理购置宅院请求，成功后立即存档
+pub fn process_home_purchases(
+    settings: Res<HousingSettings>,
+    homes: Res<HomeDatabase>,
+    mut housing: ResMut<HousingState>,
+    mut requests: EventReader<PurchaseHomeRequest>,
+    mut inventories: Query<&mut Inventory>,
+    mut samples: EventWriter<PlaytestSample>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for request in requests.read() {
+        let Some(home) = homes.get(&request.home_id) else {
+            continue;
+        };
+        let Ok(mut inventory) = inventories.get_mut(request.buyer) else {
+            continue;
+        };
+
+        let message = if housing.owns(&home.id) {
+            format!("{} 已是你的产业", home.name)
+        } else if inventory.money < home.price {
+            format!(
+                "购置 {} 需要 {} 两银子，尚缺 {} 两",
+                home.name,
+                home.price,
+                home.price - inventory.money
+            )
+        } else {
+            inventory.money -= home.price;
+            samples.send(PlaytestSample::GoldSpent {
+                amount: home.price,
+                sink: format!("home:{}", home.id),
+            });
+            housing.owned.insert(home.id.clone(), Default::default());
+            save_housing(&housing, &settings, &mut logger);
+            format!(
+                "花费 {} 两银子购置了{}的{}",
+                home.price, home.village, home.name
+            )
+        };
+
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Info, &message);
+        }
+    }
+}
+
+/// 进出室内或家具变化时重建室内场景
+#[allow(clippy::type_complexity)]
+pub fn sync_interior(
+    mut commands: Commands,
+    database: Res<ItemDatabase>,
+    homes: Res<HomeDatabase>,
+    housing: Res<HousingState>,
+    current: Res<CurrentInterior>,
+    spawned: Query<Entity, Or<(With<InteriorRoot>, With<FurnitureEntity>)>>,
+) {
+    if !current.is_changed() && !housing.is_changed() {
+        return;
+    }
+
+    for entity in spawned.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some(home_id) = current.home_id.as_deref() else {
+        return;
+    };
+    let (Some(home), Some(index), Some(owned)) = (
+        homes.get(home_id),
+        homes.index_of(home_id),
+        housing.owned.get(home_id),
+    ) else {
+        return;
+    };
+
+    let origin = interior_origin(index);
+    let size = Vec2::new(home.interior_size[0] as f32, home.interior_size[1] as f32)
+        * FURNITURE_CELL_PIXELS;
+
+    // 地板
+    commands.spawn((
+        InteriorRoot,
+        Name::new(format!("Interior: {}", home.name)),
+        Sprite {
+            color: Color::srgb(0.55, 0.42, 0.3),
+            custom_size: Some(size),
+            ..default()
+        },
+        Transform::from_translation((origin + size * 0.5).extend(0.0)),
+        LayerComponent {
+            layer: RenderLayer::Terrain,
+            sub_order: 0,
+        },
+    ));
+
+    // 出口位于下边中央，室外一格
+    commands.spawn((
+        InteriorRoot,
+        InteriorExit,
+        Name::new("InteriorExit"),
+        Sprite {
+            color: Color::srgb(0.5, 0.25, 0.1),
+            custom_size: Some(Vec2::new(24.0, 16.0)),
+            ..default()
+        },
+        Transform::from_translation(
+            (origin + Vec2::new(size.x * 0.5, -FURNITURE_CELL_PIXELS * 0.5)).extend(0.0),
+        ),
+        LayerComponent {
+            layer: RenderLayer::Decoration,
+            sub_order: 0,
+        },
+    ));
+
+    for placed in &owned.furniture {
+        spawn_furniture(&mut commands, &database, home_id, origin, placed);
+    }
+}
+
+/// 更新玩家附近的工作台
+pub fn track_crafting_stations(
+    settings: Res<HousingSettings>,
+    stations: Query<(&CraftingStation, &Transform)>,
+    players: Query<&Transform, With<Player>>,
+    mut nearby: ResMut<NearbyCraftingStations>,
+) {
+    let Ok(player) = players.get_single() else {
+        return;
+    };
+    let position = player.translation.truncate();
+
+    let mut found: Vec<String> = stations
+        .iter()
+        .filter(|(_, transform)| {
+            transform.translation.truncate().distance(position) <= settings.interact_range * 1.5
+        })
+        .map(|(station, _)| station.station.clone())
+        .collect();
+    found.sort();
+    found.dedup();
+
+    if nearby.stations != found {
+        nearby.stations = found;
+    }
+}
+
+/// 写入宅院存档
+pub fn save_housing(
+    housing: &HousingState,
+    settings: &HousingSettings,
+    logger: &mut Option<ResMut<GameLogger>>,
+) {
+    if let Err(e) = housing.save(&settings.save_path) {
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Error, &format!("宅院存档写入失败: {}", e));
+        }
+    }
+}
@@ -0,0 +1,19 @@
+/// 宅院模块
+///
+/// # 模块组成
+/// 1. home：宅院定义、购置与存档
+/// 2. furniture：家具定义、格子摆放规则与功能组件
+/// 3. interior：室内实例的进出与生成，工作台等功能家具
+/// 4. edit_mode：家具摆放模式
+/// 5. systems：宅院插件
+mod edit_mode;
+mod furniture;
+mod home;
+mod interior;
+mod systems;
+
+pub use edit_mode::*;
+pub use furniture::*;
+pub use home::*;
+pub use interior::*;
+pub use systems::HousingPlugin;
@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+use std::path::Path;
+
+use super::{
+    handle_edit_actions, process_home_purchases, spawn_home_entrances, sync_interior,
+    toggle_edit_mode, track_crafting_stations, update_edit_cursor, use_home_entrances,
+    CurrentInterior, HomeDatabase, HousingEditMode, HousingSettings, HousingState,
+    NearbyCraftingStations, PurchaseHomeRequest, HOME_DATA_PATH,
+};
+use crate::config::resolve_data_path;
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::gameplay_running;
+
+/// 宅院插件
+pub struct HousingPlugin;
+
+impl Plugin for HousingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HousingSettings>()
+            .init_resource::<HousingState>()
+            .init_resource::<CurrentInterior>()
+            .init_resource::<HousingEditMode>()
+            .init_resource::<NearbyCraftingStations>()
+            .add_event::<PurchaseHomeRequest>();
+
+        app.add_systems(PreStartup, load_home_database)
+            .add_systems(Startup, (load_housing_state, spawn_home_entrances))
+            .add_systems(
+                Update,
+                (
+                    use_home_entrances,
+                    process_home_purchases,
+                    toggle_edit_mode,
+                    handle_edit_actions,
+                    sync_interior,
+                    update_edit_cursor,
+                    track_crafting_stations,
+                )
+                    .chain()
+                    .run_if(gameplay_running),
+            );
+    }
+}
+
+/// 加载宅院数据，失败时使用空数据库
+fn load_home_database(mut commands: Commands, mut logger: Option<ResMut<GameLogger>>) {
+    let database = match HomeDatabase::load(&resolve_data_path(HOME_DATA_PATH)) {
+        Ok(database) => database,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("宅院数据加载失败: {}", e));
+            }
+            HomeDatabase::default()
+        }
+    };
+    commands.insert_resource(database);
+}
+
+/// 读取宅院存档，没有存档时视为尚未购置任何宅院
+fn load_housing_state(
+    mut commands: Commands,
+    settings: Res<HousingSettings>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    if !Path::new(&settings.save_path).exists() {
+        return;
+    }
+
+    match HousingState::load(&settings.save_path) {
+        Ok(state) => commands.insert_resource(state),
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("宅院存档读取失败: {}", e));
+            }
+        }
+    }
+}
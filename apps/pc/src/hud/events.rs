@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+
+/// 界面配置
+#[derive(Resource, Debug, Clone)]
+pub struct HudSettings {
+    /// 是否显示界面
+    pub visible: bool,
+    /// 快捷栏显示背包前几格
+    pub hotbar_slots: usize,
+    /// 快捷栏格子边长
+    pub slot_size: f32,
+    /// 气血、内力条的长度
+    pub bar_width: f32,
+    /// 气血、内力条的高度
+    pub bar_height: f32,
+    /// 与屏幕边缘的间距
+    pub margin: f32,
+    /// 任务目标与屏幕顶部的距离，留出右上角小地图的位置
+    pub objective_top: f32,
+    pub health_color: Color,
+    pub qi_color: Color,
+}
+
+impl Default for HudSettings {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            hotbar_slots: 8,
+            slot_size: 44.0,
+            bar_width: 200.0,
+            bar_height: 10.0,
+            margin: 12.0,
+            objective_top: 216.0,
+            health_color: Color::srgb(0.75, 0.2, 0.2),
+            qi_color: Color::srgb(0.25, 0.45, 0.8),
+        }
+    }
+}
+
+/// 玩家气血、内力变化事件
+///
+/// 数值有变化时才发出，角色其他字段的改动不会触发
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct PlayerVitalsChanged {
+    pub health: f32,
+    pub max_health: f32,
+    pub qi: f32,
+    pub max_qi: f32,
+}
+
+/// 快捷栏上的一格
+#[derive(Debug, Clone, PartialEq)]
+pub struct HotbarSlot {
+    /// 物品名，找不到定义时为物品ID
+    pub name: String,
+    pub count: u32,
+}
+
+/// 快捷栏内容变化事件，附带全部格子
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct HotbarChanged {
+    pub slots: Vec<Option<HotbarSlot>>,
+}
+
+/// 正在追踪的任务目标
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedObjective {
+    /// 任务名
+    pub title: String,
+    /// 当前阶段的描述，附带需要累计的目标进度
+    pub description: String,
+}
+
+/// 追踪目标变化事件，取消追踪或任务结束时为空
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct ObjectiveChanged(pub Option<TrackedObjective>);
+
+/// 交互提示变化事件，附近没有可交互的对象时为空
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct InteractionPromptChanged(pub Option<String>);
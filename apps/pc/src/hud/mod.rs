@@ -0,0 +1,18 @@
+/// 游戏内界面模块
+///
+/// 游戏中常驻屏幕的玩家信息：气血与内力条、背包快捷栏、正在追踪的任务目标与交互提示
+///
+/// # 模块组成
+/// 1. events：界面配置与驱动界面刷新的事件
+/// 2. sources：比较玩家、背包、任务与周围对象，有变化时发出事件
+/// 3. widgets：界面布局与按事件刷新的系统
+/// 4. systems：游戏内界面插件
+mod events;
+mod sources;
+mod systems;
+mod widgets;
+
+pub use events::*;
+pub use sources::*;
+pub use systems::HudPlugin;
+pub use widgets::*;
@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+
+use super::{
+    HotbarChanged, HotbarSlot, HudSettings, InteractionPromptChanged, ObjectiveChanged,
+    PlayerVitalsChanged, TrackedObjective,
+};
+use crate::events::input::{GameAction, KeyBindings};
+use crate::interaction::InteractionTarget;
+use crate::items::{Inventory, ItemDatabase};
+use crate::resources::InputState;
+use crate::world::entity::{Character, Player};
+use crate::world::map::{QuestManager, QuestMarkers, QuestStatus};
+
+/// 玩家角色变化时比较气血与内力，有变化才发出事件
+pub fn emit_player_vitals(
+    players: Query<&Character, (With<Player>, Changed<Character>)>,
+    mut last: Local<Option<PlayerVitalsChanged>>,
+    mut events: EventWriter<PlayerVitalsChanged>,
+) {
+    let Ok(character) = players.get_single() else {
+        return;
+    };
+    let vitals = PlayerVitalsChanged {
+        health: character.health,
+        max_health: character.max_health,
+        qi: character.qi,
+        max_qi: character.max_qi,
+    };
+    if *last != Some(vitals) {
+        *last = Some(vitals);
+        events.send(vitals);
+    }
+}
+
+/// 玩家背包变化时重建快捷栏内容，格子有变化才发出事件
+pub fn emit_hotbar(
+    settings: Res<HudSettings>,
+    database: Res<ItemDatabase>,
+    players: Query<Ref<Inventory>, With<Player>>,
+    mut last: Local<Option<HotbarChanged>>,
+    mut events: EventWriter<HotbarChanged>,
+) {
+    let Ok(inventory) = players.get_single() else {
+        return;
+    };
+    if !inventory.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    let slots = (0..settings.hotbar_slots)
+        .map(|index| {
+            inventory.slots.get(index).cloned().flatten().map(|item| {
+                let name = database
+                    .get(&item.item_id)
+                    .map_or(item.item_id.clone(), |def| def.name.clone());
+                HotbarSlot {
+                    name,
+                    count: item.count,
+                }
+            })
+        })
+        .collect();
+    let hotbar = HotbarChanged { slots };
+    if last.as_ref() != Some(&hotbar) {
+        *last = Some(hotbar.clone());
+        events.send(hotbar);
+    }
+}
+
+/// 正在追踪的任务的当前目标，任务不在进行中时为空
+fn tracked_objective(manager: &QuestManager, markers: &QuestMarkers) -> Option<TrackedObjective> {
+    let id = markers.tracked()?;
+    let quest = manager.quest(id)?;
+    let state = manager.state(id)?;
+    if state.status != QuestStatus::Active {
+        return None;
+    }
+    let stage = quest.stage(state.stage)?;
+
+    // 只列出需要累计多次的目标，交谈、到达一类的目标由描述说明
+    let mut description = stage.description.clone();
+    for (objective, counter) in stage.all_objectives().zip(state.counters.iter()) {
+        let required = objective.required();
+        if required > 1 {
+            description.push_str(&format!(" ({}/{})", (*counter).min(required), required));
+        }
+    }
+    Some(TrackedObjective {
+        title: quest.title.clone(),
+        description,
+    })
+}
+
+/// 任务进度或追踪的任务变化时重新取目标，有变化才发出事件
+pub fn emit_tracked_objective(
+    manager: Res<QuestManager>,
+    markers: Res<QuestMarkers>,
+    mut last: Local<Option<Option<TrackedObjective>>>,
+    mut events: EventWriter<ObjectiveChanged>,
+) {
+    if !manager.is_changed() && !markers.is_changed() {
+        return;
+    }
+    let objective = tracked_objective(&manager, &markers);
+    if last.as_ref() != Some(&objective) {
+        *last = Some(objective.clone());
+        events.send(ObjectiveChanged(objective));
+    }
+}
+
+/// 按交互键会作用到的对象生成提示，提示文字有变化才发出事件
+///
+/// 对象由交互模块统一找出，提示的就是按键作用的那一个。
+/// 按键名随当前输入设备变化，切换手柄与键盘时提示跟着更新
+pub fn emit_interaction_prompt(
+    input_state: Res<InputState>,
+    key_bindings: Res<KeyBindings>,
+    target: Res<InteractionTarget>,
+    mut last: Local<Option<Option<String>>>,
+    mut events: EventWriter<InteractionPromptChanged>,
+) {
+    let prompt = target.current.as_ref().map(|candidate| {
+        let key = key_bindings.prompt(GameAction::Interact, input_state.device);
+        format!("按 {} {}{}", key, candidate.verb, candidate.name)
+    });
+    if last.as_ref() != Some(&prompt) {
+        *last = Some(prompt.clone());
+        events.send(InteractionPromptChanged(prompt));
+    }
+}
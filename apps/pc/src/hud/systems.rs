@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+
+use super::{
+    apply_hud_visibility, emit_hotbar, emit_interaction_prompt, emit_player_vitals,
+    emit_tracked_objective, setup_hud, update_hotbar, update_interaction_prompt, update_objective,
+    update_vital_bars, HotbarChanged, HudSettings, InteractionPromptChanged, ObjectiveChanged,
+    PlayerVitalsChanged,
+};
+use crate::resources::gameplay_running;
+
+/// 游戏内界面插件
+///
+/// # 设计思路
+/// 1. 界面启动时生成一次，只在游戏中与暂停时显示，不随状态切换重建
+/// 2. 数据来源与显示分开：来源系统比较上次发出的内容，有变化才发事件，显示系统只读事件
+/// 3. 气血内力、背包与任务进度都借助变化检测，角色或资源没改动的帧不做比较
+/// 4. 交互提示沿用各玩法自己的交互距离，切换输入设备时按键名一并更新
+/// 5. 暂停时不找交互对象，恢复后提示照旧
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HudSettings>()
+            .add_event::<PlayerVitalsChanged>()
+            .add_event::<HotbarChanged>()
+            .add_event::<ObjectiveChanged>()
+            .add_event::<InteractionPromptChanged>()
+            .add_systems(Startup, setup_hud)
+            .add_systems(
+                Update,
+                (
+                    (
+                        emit_player_vitals,
+                        emit_hotbar,
+                        emit_tracked_objective,
+                        emit_interaction_prompt.run_if(gameplay_running),
+                    ),
+                    (
+                        apply_hud_visibility,
+                        update_vital_bars,
+                        update_hotbar,
+                        update_objective,
+                        update_interaction_prompt,
+                    ),
+                )
+                    .chain(),
+            );
+    }
+}
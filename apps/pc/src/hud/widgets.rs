@@ -0,0 +1,333 @@
+use bevy::prelude::*;
+
+use super::{
+    HotbarChanged, HudSettings, InteractionPromptChanged, ObjectiveChanged, PlayerVitalsChanged,
+};
+use crate::resources::GameState;
+use crate::ui::{label, panel, MixedText, TextRole, ThemeColor, UiTheme};
+
+/// 界面根节点，各部分都挂在它下面
+#[derive(Component)]
+pub struct HudRoot;
+
+/// 气血或内力条
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VitalKind {
+    Health,
+    Qi,
+}
+
+impl VitalKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            VitalKind::Health => "气血",
+            VitalKind::Qi => "内力",
+        }
+    }
+}
+
+/// 条的填充部分
+#[derive(Component)]
+pub struct VitalBarFill(pub VitalKind);
+
+/// 条旁的数值
+#[derive(Component)]
+pub struct VitalBarText(pub VitalKind);
+
+/// 快捷栏，格子都挂在它下面
+#[derive(Component)]
+pub struct HotbarUi;
+
+/// 任务目标面板
+#[derive(Component)]
+pub struct ObjectivePanel;
+
+/// 任务名
+#[derive(Component)]
+pub struct ObjectiveTitle;
+
+/// 阶段描述
+#[derive(Component)]
+pub struct ObjectiveText;
+
+/// 交互提示面板
+#[derive(Component)]
+pub struct InteractionPromptPanel;
+
+/// 交互提示文字
+#[derive(Component)]
+pub struct InteractionPromptText;
+
+fn vital_line(kind: VitalKind, value: f32, max: f32) -> String {
+    format!("{} {:.0}/{:.0}", kind.label(), value.max(0.0), max)
+}
+
+/// 创建界面，内容等各自的事件到来再填
+pub fn setup_hud(mut commands: Commands, settings: Res<HudSettings>, theme: Res<UiTheme>) {
+    commands
+        .spawn((
+            HudRoot,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                display: Display::None,
+                ..default()
+            },
+        ))
+        .with_children(|root| {
+            // 左上角的气血与内力
+            root.spawn(Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(settings.margin),
+                top: Val::Px(settings.margin),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            })
+            .with_children(|bars| {
+                for (kind, color) in [
+                    (VitalKind::Health, settings.health_color),
+                    (VitalKind::Qi, settings.qi_color),
+                ] {
+                    bars.spawn(Node {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(6.0),
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn((
+                            Node {
+                                width: Val::Px(settings.bar_width),
+                                height: Val::Px(settings.bar_height),
+                                ..default()
+                            },
+                            BackgroundColor(theme.color(ThemeColor::Panel)),
+                        ))
+                        .with_children(|bar| {
+                            bar.spawn((
+                                VitalBarFill(kind),
+                                Node {
+                                    width: Val::Percent(100.0),
+                                    height: Val::Percent(100.0),
+                                    ..default()
+                                },
+                                BackgroundColor(color),
+                            ));
+                        });
+                        row.spawn((VitalBarText(kind), label(kind.label(), TextRole::Small)));
+                    });
+                }
+            });
+
+            // 右侧小地图下方的任务目标
+            root.spawn((
+                ObjectivePanel,
+                Node {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(settings.margin),
+                    top: Val::Px(settings.objective_top),
+                    max_width: Val::Px(240.0),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(8.0)),
+                    row_gap: Val::Px(2.0),
+                    display: Display::None,
+                    ..default()
+                },
+                panel(),
+            ))
+            .with_children(|objective| {
+                objective.spawn((ObjectiveTitle, label("", TextRole::Body)));
+                objective.spawn((ObjectiveText, label("", TextRole::Small)));
+            });
+
+            // 底部居中的交互提示与快捷栏
+            root.spawn(Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                bottom: Val::Px(settings.margin),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(8.0),
+                ..default()
+            })
+            .with_children(|bottom| {
+                bottom
+                    .spawn((
+                        InteractionPromptPanel,
+                        Node {
+                            padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                            display: Display::None,
+                            ..default()
+                        },
+                        panel(),
+                    ))
+                    .with_children(|prompt| {
+                        prompt.spawn((InteractionPromptText, label("", TextRole::Body)));
+                    });
+                bottom.spawn((
+                    HotbarUi,
+                    Node {
+                        column_gap: Val::Px(4.0),
+                        ..default()
+                    },
+                ));
+            });
+        });
+}
+
+/// 在游戏中与暂停时显示界面，切换状态或修改设置时才套用
+pub fn apply_hud_visibility(
+    state: Res<State<GameState>>,
+    settings: Res<HudSettings>,
+    mut roots: Query<&mut Node, With<HudRoot>>,
+) {
+    if !state.is_changed() && !settings.is_changed() {
+        return;
+    }
+    let in_game = matches!(state.get(), GameState::InGame | GameState::Paused);
+    let display = if settings.visible && in_game {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut node in roots.iter_mut() {
+        if node.display != display {
+            node.display = display;
+        }
+    }
+}
+
+/// 按最近一次事件刷新气血与内力条
+pub fn update_vital_bars(
+    mut events: EventReader<PlayerVitalsChanged>,
+    mut fills: Query<(&VitalBarFill, &mut Node)>,
+    mut texts: Query<(&VitalBarText, &mut MixedText)>,
+) {
+    let Some(vitals) = events.read().last() else {
+        return;
+    };
+    let value = |kind: VitalKind| match kind {
+        VitalKind::Health => (vitals.health, vitals.max_health),
+        VitalKind::Qi => (vitals.qi, vitals.max_qi),
+    };
+
+    for (fill, mut node) in fills.iter_mut() {
+        let (current, max) = value(fill.0);
+        let ratio = if max > 0.0 {
+            (current / max).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        node.width = Val::Percent(ratio * 100.0);
+    }
+    for (text, mut mixed) in texts.iter_mut() {
+        let (current, max) = value(text.0);
+        let line = vital_line(text.0, current, max);
+        if mixed.0 != line {
+            mixed.0 = line;
+        }
+    }
+}
+
+/// 快捷栏内容变化时重建格子
+pub fn update_hotbar(
+    mut commands: Commands,
+    mut events: EventReader<HotbarChanged>,
+    settings: Res<HudSettings>,
+    theme: Res<UiTheme>,
+    hotbars: Query<Entity, With<HotbarUi>>,
+) {
+    let Some(hotbar) = events.read().last() else {
+        return;
+    };
+    let Ok(root) = hotbars.get_single() else {
+        return;
+    };
+
+    commands.entity(root).despawn_descendants();
+    commands.entity(root).with_children(|bar| {
+        for (index, slot) in hotbar.slots.iter().enumerate() {
+            bar.spawn((
+                Node {
+                    width: Val::Px(settings.slot_size),
+                    height: Val::Px(settings.slot_size),
+                    border: UiRect::all(Val::Px(1.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::SpaceBetween,
+                    padding: UiRect::all(Val::Px(2.0)),
+                    overflow: Overflow::clip(),
+                    ..default()
+                },
+                BorderColor(theme.color(ThemeColor::PanelBorder)),
+                BackgroundColor(theme.color(ThemeColor::Panel)),
+            ))
+            .with_children(|cell| {
+                cell.spawn(label(format!("{}", index + 1), TextRole::Muted));
+                if let Some(slot) = slot {
+                    // 格子窄，只放得下物品名的头两个字
+                    let short: String = slot.name.chars().take(2).collect();
+                    let text = if slot.count > 1 {
+                        format!("{}×{}", short, slot.count)
+                    } else {
+                        short
+                    };
+                    cell.spawn((Name::new(slot.name.clone()), label(text, TextRole::Small)));
+                }
+            });
+        }
+    });
+}
+
+/// 追踪目标变化时刷新任务面板，没有目标时隐藏
+pub fn update_objective(
+    mut events: EventReader<ObjectiveChanged>,
+    mut panels: Query<&mut Node, With<ObjectivePanel>>,
+    mut titles: Query<&mut MixedText, (With<ObjectiveTitle>, Without<ObjectiveText>)>,
+    mut texts: Query<&mut MixedText, (With<ObjectiveText>, Without<ObjectiveTitle>)>,
+) {
+    let Some(ObjectiveChanged(objective)) = events.read().last() else {
+        return;
+    };
+    let display = if objective.is_some() {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut node in panels.iter_mut() {
+        node.display = display;
+    }
+    let Some(objective) = objective else {
+        return;
+    };
+    for mut title in titles.iter_mut() {
+        title.0 = objective.title.clone();
+    }
+    for mut text in texts.iter_mut() {
+        text.0 = objective.description.clone();
+    }
+}
+
+/// 交互提示变化时刷新文字，没有可交互的对象时隐藏
+pub fn update_interaction_prompt(
+    mut events: EventReader<InteractionPromptChanged>,
+    mut panels: Query<&mut Node, With<InteractionPromptPanel>>,
+    mut texts: Query<&mut MixedText, With<InteractionPromptText>>,
+) {
+    let Some(InteractionPromptChanged(prompt)) = events.read().last() else {
+        return;
+    };
+    let display = if prompt.is_some() {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut node in panels.iter_mut() {
+        node.display = display;
+    }
+    if let Some(prompt) = prompt {
+        for mut text in texts.iter_mut() {
+            text.0 = prompt.clone();
+        }
+    }
+}
@@ -0,0 +1,10 @@
+/// 交互模块
+///
+/// # 模块组成
+/// 1. target：交互对象、当前目标与按键分派事件
+/// 2. systems：找出最近的交互对象并分派交互键
+mod systems;
+mod target;
+
+pub use systems::InteractionPlugin;
+pub use target::*;
@@ -0,0 +1,318 @@
+use bevy::prelude::*;
+
+use super::{
+    Interacted, InteractionCandidate, InteractionKind, InteractionTarget, PanelInteraction,
+};
+use crate::combat::DeathRecap;
+use crate::events::input::{handle_input_events, GameAction};
+use crate::housing::{
+    CurrentInterior, HomeDatabase, HomeEntrance, HousingEditMode, HousingSettings, HousingState,
+    InteriorExit,
+};
+use crate::items::{
+    DurabilitySettings, ItemDatabase, LootDrop, LootSettings, OpenShop, OpenStash, ShopKeeper,
+    ShopSettings, StashContainer, StashSettings,
+};
+use crate::resources::{gameplay_running, InputState};
+use crate::rest::{RestMenu, RestSettings, RestSpot};
+use crate::time::GameCalendar;
+use crate::world::chunk::{ChunkProp, PropSettings};
+use crate::world::entity::{Character, CharacterState, Npc, NpcType, Player};
+use crate::world::harvest::{HarvestRegistry, HarvestSettings, Harvestable, ResourceNodeLibrary};
+use crate::world::map::{ActiveDialogue, QuestManager, QuestSettings};
+
+/// 交互插件
+///
+/// # 设计思路
+/// 1. 交互键只在这里读取：每帧找出玩家附近最近的可交互对象，按下时只发给这一个
+/// 2. 各玩法沿用自己的交互距离，只处理发给自己类型的 `Interacted`，一次按键不会同时打开商铺和对话
+/// 3. 商铺、仓库、休息菜单、对话与死亡回顾打开时，按键交给打开着的那个关闭或翻页
+/// 4. 交互提示读取同一个目标，提示的对象就是按键作用的对象
+/// 5. 暂停与主菜单时不找对象也不分派
+pub struct InteractionPlugin;
+
+impl Plugin for InteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InteractionTarget>()
+            .add_event::<Interacted>()
+            .add_event::<PanelInteraction>()
+            .add_systems(
+                Update,
+                (find_interaction_target, dispatch_interaction)
+                    .chain()
+                    .after(handle_input_events)
+                    .run_if(gameplay_running),
+            );
+    }
+}
+
+/// 找出玩家附近最近的可交互对象
+///
+/// 距离与各玩法自己的交互距离一致；玩家不能移动（休息、受击硬直等）或正在摆放家具时没有对象。
+/// 身上有进行中任务对白的商人与铁匠按交谈处理，其余商人按交易处理，铁匠按修理处理
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+fn find_interaction_target(
+    mut target: ResMut<InteractionTarget>,
+    calendar: Res<GameCalendar>,
+    edit_mode: Option<Res<HousingEditMode>>,
+    npc_sources: (
+        Res<QuestSettings>,
+        Res<QuestManager>,
+        Res<ShopSettings>,
+        Res<DurabilitySettings>,
+    ),
+    item_sources: (Res<ItemDatabase>, Res<LootSettings>, Res<StashSettings>),
+    home_sources: (
+        Res<HousingSettings>,
+        Res<HomeDatabase>,
+        Res<HousingState>,
+        Res<CurrentInterior>,
+    ),
+    world_sources: (
+        Res<RestSettings>,
+        Option<Res<PropSettings>>,
+        Option<Res<HarvestSettings>>,
+        Option<Res<ResourceNodeLibrary>>,
+        Option<Res<HarvestRegistry>>,
+    ),
+    players: Query<(&Character, &Transform), With<Player>>,
+    npcs: Query<
+        (Entity, &Npc, &Character, &Transform, Option<&ShopKeeper>),
+        (With<Npc>, Without<Player>),
+    >,
+    spots: Query<(Entity, &RestSpot, &Transform), Without<Player>>,
+    nodes: Query<(Entity, &Harvestable, &GlobalTransform)>,
+    props: Query<(Entity, &ChunkProp, &GlobalTransform)>,
+    drops: Query<(Entity, &LootDrop, &Transform), Without<Player>>,
+    containers: Query<(Entity, &StashContainer, &Transform), Without<Player>>,
+    entrances: Query<(Entity, &HomeEntrance, &Transform), Without<Player>>,
+    exits: Query<(Entity, &Transform), (With<InteriorExit>, Without<Player>)>,
+) {
+    let (quest_settings, quests, shop_settings, durability_settings) = npc_sources;
+    let (database, loot_settings, stash_settings) = item_sources;
+    let (housing_settings, homes, housing, interior) = home_sources;
+    let (rest_settings, prop_settings, harvest_settings, library, registry) = world_sources;
+
+    let position = players
+        .get_single()
+        .ok()
+        .filter(|(character, _)| character.can_move)
+        .filter(|_| !edit_mode.as_ref().is_some_and(|edit_mode| edit_mode.active))
+        .map(|(_, transform)| transform.translation.truncate());
+    let Some(position) = position else {
+        if target.current.is_some() {
+            target.current = None;
+        }
+        return;
+    };
+
+    let mut candidates: Vec<InteractionCandidate> = Vec::new();
+    let mut consider = |entity, kind, verb, name: &str, at: Vec2, range: f32| {
+        let distance = at.distance(position);
+        if distance <= range {
+            candidates.push(InteractionCandidate {
+                entity,
+                kind,
+                verb,
+                name: name.to_string(),
+                distance,
+            });
+        }
+    };
+
+    for (entity, npc, character, transform, shop) in npcs.iter() {
+        if character.state == CharacterState::Dead {
+            continue;
+        }
+        let at = transform.translation.truncate();
+        let has_quest_lines = !quests.dialogue_for(&character.name).is_empty();
+        match shop {
+            _ if npc.npc_type == NpcType::Blacksmith && !has_quest_lines => consider(
+                entity,
+                InteractionKind::Repair,
+                "修理装备",
+                "",
+                at,
+                durability_settings.blacksmith_range,
+            ),
+            Some(_) if !has_quest_lines => consider(
+                entity,
+                InteractionKind::Trade,
+                "交易",
+                &character.name,
+                at,
+                shop_settings.interact_range,
+            ),
+            _ => consider(
+                entity,
+                InteractionKind::Talk,
+                "交谈",
+                &character.name,
+                at,
+                quest_settings.talk_range,
+            ),
+        }
+    }
+
+    for (entity, spot, transform) in spots.iter() {
+        consider(
+            entity,
+            InteractionKind::Rest,
+            "歇息",
+            &spot.name,
+            transform.translation.truncate(),
+            rest_settings.interact_range,
+        );
+    }
+
+    if let (Some(settings), Some(library), Some(registry)) =
+        (&harvest_settings, &library, &registry)
+    {
+        let now = calendar.total_hours();
+        for (entity, node, transform) in nodes.iter() {
+            if !registry.is_available(node.key, now) {
+                continue;
+            }
+            if let Some(def) = library.get(&node.node_id) {
+                consider(
+                    entity,
+                    InteractionKind::Harvest,
+                    "采集",
+                    &def.name,
+                    transform.translation().truncate(),
+                    settings.interact_range,
+                );
+            }
+        }
+    }
+
+    if let Some(settings) = &prop_settings {
+        for (entity, prop, transform) in props.iter() {
+            if prop.kind.interaction().is_some() {
+                consider(
+                    entity,
+                    InteractionKind::Prop,
+                    prop.kind.interaction_verb(),
+                    prop.kind.name(),
+                    transform.translation().truncate(),
+                    settings.interact_range,
+                );
+            }
+        }
+    }
+
+    for (entity, drop, transform) in drops.iter() {
+        let name = database
+            .get(&drop.item.item_id)
+            .map_or(drop.item.item_id.as_str(), |item| item.name.as_str());
+        consider(
+            entity,
+            InteractionKind::Loot,
+            "拾取",
+            name,
+            transform.translation.truncate(),
+            loot_settings.pickup_range,
+        );
+    }
+
+    for (entity, container, transform) in containers.iter() {
+        consider(
+            entity,
+            InteractionKind::Stash,
+            "打开",
+            &container.name,
+            transform.translation.truncate(),
+            stash_settings.interact_range,
+        );
+    }
+
+    // 在室内只能走出去，在野外才找得到各处宅院的大门
+    if interior.home_id.is_some() {
+        for (entity, transform) in exits.iter() {
+            consider(
+                entity,
+                InteractionKind::LeaveHome,
+                "离开",
+                "宅院",
+                transform.translation.truncate(),
+                housing_settings.interact_range,
+            );
+        }
+    } else {
+        for (entity, entrance, transform) in entrances.iter() {
+            let name = homes
+                .get(&entrance.home_id)
+                .map_or(entrance.home_id.as_str(), |home| home.name.as_str());
+            let verb = if housing.owns(&entrance.home_id) {
+                "进入"
+            } else {
+                "购置"
+            };
+            consider(
+                entity,
+                InteractionKind::Home,
+                verb,
+                name,
+                transform.translation.truncate(),
+                housing_settings.interact_range,
+            );
+        }
+    }
+
+    let nearest = candidates
+        .into_iter()
+        .min_by(|a, b| a.distance.total_cmp(&b.distance));
+    // 只比较对象与动作，站着不动时不因距离的细微变化反复标记修改
+    let same = match (&target.current, &nearest) {
+        (Some(current), Some(nearest)) => {
+            current.entity == nearest.entity
+                && current.kind == nearest.kind
+                && current.verb == nearest.verb
+                && current.name == nearest.name
+        }
+        (None, None) => true,
+        _ => false,
+    };
+    if !same {
+        target.current = nearest;
+    }
+}
+
+/// 按下交互键时发给最近的对象；有面板或对话打开时改为交给它处理
+#[allow(clippy::type_complexity)]
+fn dispatch_interaction(
+    input_state: Res<InputState>,
+    target: Res<InteractionTarget>,
+    panels: (
+        Option<Res<OpenShop>>,
+        Option<Res<OpenStash>>,
+        Option<Res<RestMenu>>,
+        Option<Res<ActiveDialogue>>,
+        Option<Res<DeathRecap>>,
+    ),
+    mut interacted: EventWriter<Interacted>,
+    mut panel_events: EventWriter<PanelInteraction>,
+) {
+    if !input_state.is_action_just_pressed(GameAction::Interact) {
+        return;
+    }
+
+    let (shop, stash, rest, dialogue, recap) = panels;
+    let panel_open = shop.is_some_and(|shop| shop.merchant.is_some())
+        || stash.is_some_and(|stash| stash.container.is_some())
+        || rest.is_some_and(|rest| rest.spot.is_some())
+        || dialogue.is_some_and(|dialogue| dialogue.session.is_some())
+        || recap.is_some_and(|recap| recap.visible);
+    if panel_open {
+        panel_events.send(PanelInteraction);
+        return;
+    }
+
+    if let Some(candidate) = &target.current {
+        interacted.send(Interacted {
+            entity: candidate.entity,
+            kind: candidate.kind,
+        });
+    }
+}
@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+
+/// 交互对象的类型，决定由哪个玩法处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InteractionKind {
+    /// 与NPC交谈
+    Talk,
+    /// 与商人交易
+    Trade,
+    /// 请铁匠修理装备
+    Repair,
+    /// 在床铺、篝火旁歇息
+    Rest,
+    /// 采集资源点
+    Harvest,
+    /// 点灯、熄灯等摆件交互
+    Prop,
+    /// 拾取地上的掉落物
+    Loot,
+    /// 打开仓库
+    Stash,
+    /// 进入或购置宅院
+    Home,
+    /// 离开宅院室内
+    LeaveHome,
+}
+
+/// 交互键当前会作用到的对象
+#[derive(Debug, Clone, PartialEq)]
+pub struct InteractionCandidate {
+    pub entity: Entity,
+    pub kind: InteractionKind,
+    /// 提示中的动作，例如“交易”“点亮”
+    pub verb: &'static str,
+    /// 提示中的对象名
+    pub name: String,
+    pub distance: f32,
+}
+
+/// 玩家附近最近的可交互对象，交互提示与按键分派共用
+///
+/// 玩家不能移动、正在摆放家具或没有可交互对象时为 None
+#[derive(Resource, Debug, Default)]
+pub struct InteractionTarget {
+    pub current: Option<InteractionCandidate>,
+}
+
+/// 按下交互键时发给最近的对象，每次按键只发一个
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Interacted {
+    pub entity: Entity,
+    pub kind: InteractionKind,
+}
+
+/// 商铺、仓库、休息菜单、对话或死亡回顾打开时按下交互键
+///
+/// 打开着的那一个据此关闭或翻页，这一下不再作用到附近的对象
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PanelInteraction;
@@ -0,0 +1,466 @@
+use bevy::prelude::*;
+
+use super::{EquipSlot, Equipment, Inventory, ItemCategory, ItemDatabase};
+use crate::analytics::PlaytestSample;
+use crate::combat::{CombatActionEvent, CombatEffectKind, DamageEvent};
+use crate::events::input::GameAction;
+use crate::interaction::{Interacted, InteractionKind};
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::InputState;
+use crate::world::entity::{Character, CharacterState, Npc, NpcType, Player};
+
+/// 耐久损耗与效能参数
+///
+/// # 参数说明
+/// - weapon_wear_per_attack: 每次出招的武器损耗
+/// - weapon_wear_per_block: 防御状态下受击的武器损耗
+/// - armor_wear_per_damage: 每点伤害造成的护甲损耗，平摊到所有护甲
+/// - thresholds: (耐久比例, 效能) 列表，耐久低于某个比例后使用对应效能
+/// - warning_ratio: 界面提示耐久不足的比例
+/// - blacksmith_range: 与铁匠交互修理的最大距离
+#[derive(Resource, Debug, Clone)]
+pub struct DurabilitySettings {
+    pub weapon_wear_per_attack: f32,
+    pub weapon_wear_per_block: f32,
+    pub armor_wear_per_damage: f32,
+    pub thresholds: Vec<(f32, f32)>,
+    pub warning_ratio: f32,
+    pub blacksmith_range: f32,
+}
+
+impl Default for DurabilitySettings {
+    fn default() -> Self {
+        Self {
+            weapon_wear_per_attack: 0.5,
+            weapon_wear_per_block: 1.0,
+            armor_wear_per_damage: 0.05,
+            thresholds: vec![(0.3, 0.8), (0.1, 0.5), (0.0, 0.2)],
+            warning_ratio: 0.3,
+            blacksmith_range: 64.0,
+        }
+    }
+}
+
+impl DurabilitySettings {
+    /// 根据耐久比例计算效能系数
+    pub fn effectiveness(&self, ratio: f32) -> f32 {
+        let mut result = 1.0;
+        for &(threshold, effectiveness) in &self.thresholds {
+            if ratio <= threshold {
+                result = effectiveness;
+            }
+        }
+        result
+    }
+}
+
+/// 修理方式
+#[derive(Debug, Clone)]
+pub enum RepairMethod {
+    /// 找铁匠修理，花费银两，恢复到满耐久
+    Blacksmith { smith: Entity },
+    /// 使用修理工具，消耗一个工具，恢复固定耐久
+    Kit { item_id: String },
+}
+
+/// 修理请求事件
+#[derive(Event, Debug, Clone)]
+pub struct RepairRequest {
+    /// 请求修理的角色
+    pub entity: Entity,
+    /// 要修理的槽位，None 表示全部
+    pub slot: Option<EquipSlot>,
+    pub method: RepairMethod,
+}
+
+/// 耐久指示界面标记
+#[derive(Component)]
+pub struct DurabilityIndicatorUi;
+
+/// 出招时损耗武器
+pub fn wear_weapons_on_attack(
+    settings: Res<DurabilitySettings>,
+    mut action_events: EventReader<CombatActionEvent>,
+    mut equipment: Query<&mut Equipment>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for event in action_events.read() {
+        if event.action != GameAction::Attack {
+            continue;
+        }
+        let Ok(mut equipment) = equipment.get_mut(event.entity) else {
+            continue;
+        };
+        wear_slot(
+            &mut equipment,
+            EquipSlot::Weapon,
+            settings.weapon_wear_per_attack,
+            logger.as_deref_mut(),
+        );
+    }
+}
+
+/// 受击时损耗护甲，防御状态下由武器格挡
+pub fn wear_equipment_on_damage(
+    settings: Res<DurabilitySettings>,
+    mut damage_events: EventReader<DamageEvent>,
+    mut query: Query<(&Character, &mut Equipment)>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for event in damage_events.read() {
+        if event.kind != CombatEffectKind::Damage {
+            continue;
+        }
+        let Ok((character, mut equipment)) = query.get_mut(event.target) else {
+            continue;
+        };
+
+        if character.state == CharacterState::Defending
+            && equipment.slots.contains_key(&EquipSlot::Weapon)
+        {
+            wear_slot(
+                &mut equipment,
+                EquipSlot::Weapon,
+                settings.weapon_wear_per_block,
+                logger.as_deref_mut(),
+            );
+            continue;
+        }
+
+        let armor_slots: Vec<EquipSlot> = equipment
+            .slots
+            .keys()
+            .copied()
+            .filter(|slot| *slot != EquipSlot::Weapon)
+            .collect();
+        if armor_slots.is_empty() {
+            continue;
+        }
+
+        let wear = event.amount * settings.armor_wear_per_damage / armor_slots.len() as f32;
+        for slot in armor_slots {
+            wear_slot(&mut equipment, slot, wear, logger.as_deref_mut());
+        }
+    }
+}
+
+/// 扣除某个槽位的耐久，耐久归零时记录日志
+fn wear_slot(
+    equipment: &mut Equipment,
+    slot: EquipSlot,
+    amount: f32,
+    logger: Option<&mut GameLogger>,
+) {
+    let Some(item) = equipment.slots.get_mut(&slot) else {
+        return;
+    };
+    let Some(durability) = item.durability.as_mut() else {
+        return;
+    };
+
+    let was_intact = *durability > 0.0;
+    *durability = (*durability - amount).max(0.0);
+
+    if was_intact && *durability <= 0.0 {
+        if let Some(logger) = logger {
+            logger.log(
+                LogLevel::Info,
+                &format!("装备损坏: {} ({:?})", item.item_id, slot),
+            );
+        }
+    }
+}
+
+/// 交互键作用到铁匠时请他修理身上全部装备
+pub fn request_blacksmith_repairs(
+    mut interacted: EventReader<Interacted>,
+    players: Query<Entity, With<Player>>,
+    mut requests: EventWriter<RepairRequest>,
+) {
+    let Ok(player) = players.get_single() else {
+        interacted.clear();
+        return;
+    };
+    for event in interacted.read() {
+        if event.kind == InteractionKind::Repair {
+            requests.send(RepairRequest {
+                entity: player,
+                slot: None,
+                method: RepairMethod::Blacksmith {
+                    smith: event.entity,
+                },
+            });
+        }
+    }
+}
+
+/// 按修理键时用背包里的第一件修理工具修理身上全部装备
+pub fn use_repair_kits(
+    input_state: Res<InputState>,
+    database: Res<ItemDatabase>,
+    players: Query<(Entity, &Inventory), With<Player>>,
+    mut requests: EventWriter<RepairRequest>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    if !input_state.is_action_just_pressed(GameAction::RepairGear) {
+        return;
+    }
+    let Ok((player, inventory)) = players.get_single() else {
+        return;
+    };
+
+    let kit = inventory.slots.iter().flatten().find(|item| {
+        database
+            .get(&item.item_id)
+            .is_some_and(|def| def.category == ItemCategory::RepairKit)
+    });
+    match kit {
+        Some(kit) => {
+            requests.send(RepairRequest {
+                entity: player,
+                slot: None,
+                method: RepairMethod::Kit {
+                    item_id: kit.item_id.clone(),
+                },
+            });
+        }
+        None => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Info, "修理失败: 背包里没有修理工具");
+            }
+        }
+    }
+}
+
+/// 处理修理请求
+pub fn process_repair_requests(
+    settings: Res<DurabilitySettings>,
+    database: Res<ItemDatabase>,
+    mut requests: EventReader<RepairRequest>,
+    mut owners: Query<(&mut Equipment, &mut Inventory, &Transform)>,
+    smiths: Query<(&Npc, &Transform)>,
+    mut samples: EventWriter<PlaytestSample>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for request in requests.read() {
+        let Ok((mut equipment, mut inventory, transform)) = owners.get_mut(request.entity) else {
+            continue;
+        };
+
+        let slots: Vec<EquipSlot> = match request.slot {
+            Some(slot) => vec![slot],
+            None => equipment.slots.keys().copied().collect(),
+        };
+
+        let result = match &request.method {
+            RepairMethod::Blacksmith { smith } => repair_at_blacksmith(
+                &settings,
+                &database,
+                &mut equipment,
+                &mut inventory,
+                transform,
+                smiths.get(*smith).ok(),
+                &slots,
+                &mut samples,
+            ),
+            RepairMethod::Kit { item_id } => {
+                repair_with_kit(&database, &mut equipment, &mut inventory, item_id, &slots)
+            }
+        };
+
+        if let Some(logger) = logger.as_mut() {
+            match result {
+                Ok(message) => logger.log(LogLevel::Info, &message),
+                Err(reason) => logger.log(LogLevel::Info, &format!("修理失败: {}", reason)),
+            }
+        }
+    }
+}
+
+/// 铁匠修理：恢复满耐久，按品阶收费
+#[allow(clippy::too_many_arguments)]
+fn repair_at_blacksmith(
+    settings: &DurabilitySettings,
+    database: &ItemDatabase,
+    equipment: &mut Equipment,
+    inventory: &mut Inventory,
+    owner: &Transform,
+    smith: Option<(&Npc, &Transform)>,
+    slots: &[EquipSlot],
+    samples: &mut EventWriter<PlaytestSample>,
+) -> Result<String, String> {
+    let Some((npc, smith_transform)) = smith else {
+        return Err("找不到铁匠".to_string());
+    };
+    if npc.npc_type != NpcType::Blacksmith {
+        return Err("对方不是铁匠".to_string());
+    }
+    if owner.translation.distance(smith_transform.translation) > settings.blacksmith_range {
+        return Err("离铁匠太远".to_string());
+    }
+
+    // 先计算总价，银两不足则不修理任何装备
+    let mut cost = 0.0;
+    for slot in slots {
+        let Some(item) = equipment.slots.get(slot) else {
+            continue;
+        };
+        let (Some(current), Some(max)) = (item.durability, database.max_durability(&item.item_id))
+        else {
+            continue;
+        };
+        let per_point = database
+            .get(&item.item_id)
+            .and_then(|def| database.tier(def.tier))
+            .map_or(1.0, |tier| tier.repair_cost_per_point);
+        cost += (max - current) * per_point;
+    }
+
+    let cost = cost.ceil() as u32;
+    if cost == 0 {
+        return Err("装备无需修理".to_string());
+    }
+    if inventory.money < cost {
+        return Err(format!("银两不足，需要 {} 两", cost));
+    }
+
+    inventory.money -= cost;
+    samples.send(PlaytestSample::GoldSpent {
+        amount: cost,
+        sink: "repair".to_string(),
+    });
+    for slot in slots {
+        if let Some(item) = equipment.slots.get_mut(slot) {
+            item.durability = database.max_durability(&item.item_id);
+        }
+    }
+
+    Ok(format!("铁匠修理完成，花费 {} 两", cost))
+}
+
+/// 修理工具：消耗一个工具，为每件装备恢复固定耐久
+fn repair_with_kit(
+    database: &ItemDatabase,
+    equipment: &mut Equipment,
+    inventory: &mut Inventory,
+    kit_id: &str,
+    slots: &[EquipSlot],
+) -> Result<String, String> {
+    let amount = database
+        .get(kit_id)
+        .filter(|def| def.category == ItemCategory::RepairKit)
+        .and_then(|def| def.repair_amount)
+        .ok_or_else(|| format!("{} 不是修理工具", kit_id))?;
+
+    let needs_repair = slots.iter().any(|slot| {
+        equipment.slots.get(slot).is_some_and(|item| {
+            matches!(
+                (item.durability, database.max_durability(&item.item_id)),
+                (Some(current), Some(max)) if current < max
+            )
+        })
+    });
+    if !needs_repair {
+        return Err("装备无需修理".to_string());
+    }
+
+    if !inventory.remove(kit_id, 1) {
+        return Err(format!("没有 {}", kit_id));
+    }
+
+    for slot in slots {
+        if let Some(item) = equipment.slots.get_mut(slot) {
+            if let (Some(current), Some(max)) = (
+                item.durability.as_mut(),
+                database.max_durability(&item.item_id),
+            ) {
+                *current = (*current + amount).min(max);
+            }
+        }
+    }
+
+    Ok(format!("使用 {} 修理装备", kit_id))
+}
+
+/// 创建耐久指示界面
+pub fn setup_durability_indicator(mut commands: Commands) {
+    commands.spawn((
+        DurabilityIndicatorUi,
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(12.0),
+            bottom: Val::Px(12.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(2.0),
+            ..default()
+        },
+    ));
+}
+
+/// 玩家装备变化时刷新耐久指示
+pub fn update_durability_indicator(
+    mut commands: Commands,
+    settings: Res<DurabilitySettings>,
+    database: Res<ItemDatabase>,
+    players: Query<&Equipment, (With<Player>, Changed<Equipment>)>,
+    indicator: Query<Entity, With<DurabilityIndicatorUi>>,
+) {
+    let Ok(equipment) = players.get_single() else {
+        return;
+    };
+    let Ok(root) = indicator.get_single() else {
+        return;
+    };
+
+    let mut lines: Vec<(String, f32)> = equipment
+        .slots
+        .iter()
+        .filter_map(|(slot, item)| {
+            let current = item.durability?;
+            let max = database.max_durability(&item.item_id)?;
+            let name = database
+                .get(&item.item_id)
+                .map_or(item.item_id.as_str(), |def| def.name.as_str());
+            Some((
+                format!("{} {} {:.0}/{:.0}", slot_label(*slot), name, current, max),
+                if max > 0.0 { current / max } else { 1.0 },
+            ))
+        })
+        .collect();
+    lines.sort_by(|a, b| a.0.cmp(&b.0));
+
+    commands.entity(root).despawn_descendants();
+    commands.entity(root).with_children(|parent| {
+        for (text, ratio) in lines {
+            // 只显示需要注意的装备，损坏的用红色
+            if ratio > settings.warning_ratio {
+                continue;
+            }
+            let color = if ratio <= 0.0 {
+                Color::srgb(0.9, 0.2, 0.2)
+            } else {
+                Color::srgb(0.95, 0.75, 0.2)
+            };
+            parent.spawn((
+                Text::new(text),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(color),
+            ));
+        }
+    });
+}
+
+/// 槽位显示名称
+fn slot_label(slot: EquipSlot) -> &'static str {
+    match slot {
+        EquipSlot::Weapon => "兵器",
+        EquipSlot::Head => "头部",
+        EquipSlot::Body => "身体",
+        EquipSlot::Legs => "腿部",
+        EquipSlot::Feet => "足部",
+        EquipSlot::Accessory => "饰品",
+    }
+}
@@ -0,0 +1,183 @@
+use bevy::prelude::*;
+
+use super::{Equipment, Inventory, ItemDatabase};
+use crate::combat::{SkillBook, SkillDatabase};
+use crate::resources::DifficultyModifiers;
+use crate::world::entity::Player;
+
+/// 负重等级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EncumbranceLevel {
+    /// 负重正常
+    #[default]
+    Normal,
+    /// 超重：减速，无法疾跑与施展轻功
+    Burdened,
+    /// 严重超重：大幅减速
+    Overloaded,
+}
+
+/// 负重参数
+///
+/// # 参数说明
+/// - overload_ratio: 重量超过上限的该倍数后进入严重超重
+/// - burdened_speed / overloaded_speed: 对应等级的移速系数（难度修正前）
+#[derive(Resource, Debug, Clone)]
+pub struct EncumbranceSettings {
+    pub overload_ratio: f32,
+    pub burdened_speed: f32,
+    pub overloaded_speed: f32,
+}
+
+impl Default for EncumbranceSettings {
+    fn default() -> Self {
+        Self {
+            overload_ratio: 1.25,
+            burdened_speed: 0.7,
+            overloaded_speed: 0.35,
+        }
+    }
+}
+
+/// 负重组件
+#[derive(Component, Debug, Clone)]
+pub struct Encumbrance {
+    /// 基础负重上限
+    pub base_capacity: f32,
+    /// 当前总重量
+    pub weight: f32,
+    /// 当前负重上限（含装备、已学技能与难度）
+    pub capacity: f32,
+    pub level: EncumbranceLevel,
+    /// 当前移速系数
+    pub speed_multiplier: f32,
+}
+
+impl Default for Encumbrance {
+    fn default() -> Self {
+        Self {
+            base_capacity: 60.0,
+            weight: 0.0,
+            capacity: 60.0,
+            level: EncumbranceLevel::Normal,
+            speed_multiplier: 1.0,
+        }
+    }
+}
+
+impl Encumbrance {
+    /// 能否疾跑
+    pub fn can_sprint(&self) -> bool {
+        self.level == EncumbranceLevel::Normal
+    }
+
+    /// 能否施展轻功
+    pub fn can_use_qinggong(&self) -> bool {
+        self.level == EncumbranceLevel::Normal
+    }
+}
+
+/// 负重警告界面标记
+#[derive(Component)]
+pub struct EncumbranceWarningUi;
+
+/// 重新计算负重
+#[allow(clippy::type_complexity)]
+pub fn update_encumbrance(
+    database: Res<ItemDatabase>,
+    skills: Res<SkillDatabase>,
+    settings: Res<EncumbranceSettings>,
+    difficulty: Res<DifficultyModifiers>,
+    mut query: Query<(
+        &mut Encumbrance,
+        Option<&Inventory>,
+        Option<&Equipment>,
+        Option<&SkillBook>,
+    )>,
+) {
+    for (mut encumbrance, inventory, equipment, book) in query.iter_mut() {
+        let weight = inventory.map_or(0.0, |inv| inv.total_weight(&database))
+            + equipment.map_or(0.0, |eq| eq.total_weight(&database));
+
+        let bonus = equipment.map_or(0.0, |eq| eq.carry_bonus(&database))
+            + book.map_or(0.0, |book| {
+                book.learned
+                    .iter()
+                    .filter_map(|skill| skills.get(skill))
+                    .map(|def| def.carry_bonus)
+                    .sum()
+            });
+        let capacity = ((encumbrance.base_capacity + bonus) * difficulty.carry_capacity).max(1.0);
+
+        let level = if weight > capacity * settings.overload_ratio {
+            EncumbranceLevel::Overloaded
+        } else if weight > capacity {
+            EncumbranceLevel::Burdened
+        } else {
+            EncumbranceLevel::Normal
+        };
+
+        // 难度修正放大的是减速幅度，而不是直接乘在系数上
+        let base_speed = match level {
+            EncumbranceLevel::Normal => 1.0,
+            EncumbranceLevel::Burdened => settings.burdened_speed,
+            EncumbranceLevel::Overloaded => settings.overloaded_speed,
+        };
+        let speed_multiplier =
+            (1.0 - (1.0 - base_speed) * difficulty.encumbrance_penalty).clamp(0.1, 1.0);
+
+        // 只在数值变化时写入，避免每帧触发变更检测
+        if encumbrance.weight != weight
+            || encumbrance.capacity != capacity
+            || encumbrance.level != level
+            || encumbrance.speed_multiplier != speed_multiplier
+        {
+            encumbrance.weight = weight;
+            encumbrance.capacity = capacity;
+            encumbrance.level = level;
+            encumbrance.speed_multiplier = speed_multiplier;
+        }
+    }
+}
+
+/// 玩家负重等级变化时显示或隐藏警告
+pub fn update_encumbrance_warning(
+    mut commands: Commands,
+    players: Query<&Encumbrance, (With<Player>, Changed<Encumbrance>)>,
+    existing: Query<Entity, With<EncumbranceWarningUi>>,
+) {
+    let Ok(encumbrance) = players.get_single() else {
+        return;
+    };
+
+    for entity in existing.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let (message, color) = match encumbrance.level {
+        EncumbranceLevel::Normal => return,
+        EncumbranceLevel::Burdened => {
+            ("负重过高：无法疾跑与施展轻功", Color::srgb(0.95, 0.75, 0.2))
+        }
+        EncumbranceLevel::Overloaded => ("严重超重：寸步难行", Color::srgb(0.9, 0.2, 0.2)),
+    };
+
+    commands.spawn((
+        EncumbranceWarningUi,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(12.0),
+            bottom: Val::Px(12.0),
+            ..default()
+        },
+        Text::new(format!(
+            "{}（{:.1}/{:.1}）",
+            message, encumbrance.weight, encumbrance.capacity
+        )),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(color),
+    ));
+}
@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::{DurabilitySettings, EquipSlot, ItemDatabase, ItemInstance};
+
+/// 装备组件
+#[derive(Component, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Equipment {
+    pub slots: HashMap<EquipSlot, ItemInstance>,
+}
+
+impl Equipment {
+    /// 某个槽位装备的效能系数，耐久越低效能越差
+    pub fn effectiveness(
+        &self,
+        slot: EquipSlot,
+        database: &ItemDatabase,
+        settings: &DurabilitySettings,
+    ) -> f32 {
+        let Some(item) = self.slots.get(&slot) else {
+            return 0.0;
+        };
+        match (item.durability, database.max_durability(&item.item_id)) {
+            (Some(current), Some(max)) if max > 0.0 => settings.effectiveness(current / max),
+            _ => 1.0,
+        }
+    }
+
+    /// 计入耐久后的攻击力加成
+    pub fn attack_power(&self, database: &ItemDatabase, settings: &DurabilitySettings) -> f32 {
+        self.slots
+            .iter()
+            .filter_map(|(slot, item)| {
+                let def = database.get(&item.item_id)?;
+                Some(def.attack * self.effectiveness(*slot, database, settings))
+            })
+            .sum()
+    }
+
+    /// 计入耐久后的防御力
+    pub fn defense(&self, database: &ItemDatabase, settings: &DurabilitySettings) -> f32 {
+        self.slots
+            .iter()
+            .filter_map(|(slot, item)| {
+                let def = database.get(&item.item_id)?;
+                Some(def.defense * self.effectiveness(*slot, database, settings))
+            })
+            .sum()
+    }
+
+    /// 已穿戴装备的总重量
+    pub fn total_weight(&self, database: &ItemDatabase) -> f32 {
+        self.slots
+            .values()
+            .filter_map(|item| database.get(&item.item_id))
+            .map(|def| def.weight)
+            .sum()
+    }
+
+    /// 装备提供的负重上限加成
+    pub fn carry_bonus(&self, database: &ItemDatabase) -> f32 {
+        self.slots
+            .values()
+            .filter_map(|item| database.get(&item.item_id))
+            .map(|def| def.carry_bonus)
+            .sum()
+    }
+
+    /// 按防御力减免伤害
+    pub fn mitigate(
+        &self,
+        amount: f32,
+        database: &ItemDatabase,
+        settings: &DurabilitySettings,
+    ) -> f32 {
+        let defense = self.defense(database, settings).max(0.0);
+        amount * 100.0 / (100.0 + defense)
+    }
+}
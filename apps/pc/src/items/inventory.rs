@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{ItemDatabase, ItemInstance};
+
+/// 背包组件
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Inventory {
+    /// 格子，None 为空格
+    pub slots: Vec<Option<ItemInstance>>,
+    /// 银两
+    pub money: u32,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::with_capacity(30)
+    }
+}
+
+impl Inventory {
+    /// 创建指定格数的背包
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: vec![None; capacity],
+            money: 0,
+        }
+    }
+
+    /// 放入物品，返回放不下的数量
+    pub fn add(&mut self, database: &ItemDatabase, mut item: ItemInstance) -> u32 {
+        let max_stack = database
+            .get(&item.item_id)
+            .map_or(1, |def| def.max_stack.max(1));
+
+        // 先叠加到已有的同类物品上
+        if item.durability.is_none() && max_stack > 1 {
+            for existing in self.slots.iter_mut().flatten() {
+                if existing.item_id != item.item_id || existing.count >= max_stack {
+                    continue;
+                }
+                let moved = (max_stack - existing.count).min(item.count);
+                existing.count += moved;
+                item.count -= moved;
+                if item.count == 0 {
+                    return 0;
+                }
+            }
+        }
+
+        // 再放入空格
+        for slot in self.slots.iter_mut() {
+            if slot.is_some() {
+                continue;
+            }
+            let moved = item.count.min(max_stack);
+            *slot = Some(ItemInstance {
+                count: moved,
+                ..item.clone()
+            });
+            item.count -= moved;
+            if item.count == 0 {
+                return 0;
+            }
+        }
+
+        item.count
+    }
+
+    /// 统计某种物品的数量
+    pub fn count(&self, item_id: &str) -> u32 {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|item| item.item_id == item_id)
+            .map(|item| item.count)
+            .sum()
+    }
+
+    /// 移除指定数量的物品，数量不足时不做任何修改并返回 false
+    pub fn remove(&mut self, item_id: &str, count: u32) -> bool {
+        if self.count(item_id) < count {
+            return false;
+        }
+
+        let mut remaining = count;
+        for slot in self.slots.iter_mut() {
+            let Some(item) = slot else {
+                continue;
+            };
+            if item.item_id != item_id {
+                continue;
+            }
+            let taken = item.count.min(remaining);
+            item.count -= taken;
+            remaining -= taken;
+            if item.count == 0 {
+                *slot = None;
+            }
+            if remaining == 0 {
+                break;
+            }
+        }
+        true
+    }
+
+    /// 背包内物品总重量
+    pub fn total_weight(&self, database: &ItemDatabase) -> f32 {
+        self.slots
+            .iter()
+            .flatten()
+            .map(|item| {
+                database.get(&item.item_id).map_or(0.0, |def| def.weight) * item.count as f32
+            })
+            .sum()
+    }
+
+    /// 取出某个格子的物品
+    pub fn take(&mut self, index: usize) -> Option<ItemInstance> {
+        self.slots.get_mut(index).and_then(Option::take)
+    }
+}
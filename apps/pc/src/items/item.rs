@@ -0,0 +1,201 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use crate::housing::FurnitureDef;
+
+/// 物品数据文件路径
+pub const ITEM_DATA_PATH: &str = "src/config/items.json";
+
+/// 物品类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ItemCategory {
+    Weapon,     // 武器
+    Armor,      // 护甲
+    Consumable, // 消耗品
+    RepairKit,  // 修理工具
+    Material,   // 材料
+    Quest,      // 任务物品
+    Furniture,  // 家具
+    Misc,       // 杂物
+}
+
+/// 装备槽位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EquipSlot {
+    Weapon,
+    Head,
+    Body,
+    Legs,
+    Feet,
+    Accessory,
+}
+
+/// 物品品阶
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ItemTier {
+    Common,    // 凡品
+    Fine,      // 良品
+    Rare,      // 珍品
+    Epic,      // 极品
+    Legendary, // 神兵
+}
+
+/// 品阶数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TierDef {
+    /// 该品阶装备的默认耐久上限
+    pub durability: f32,
+    /// 铁匠修理每点耐久的价格
+    pub repair_cost_per_point: f32,
+}
+
+/// 物品定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemDef {
+    pub id: String,
+    pub name: String,
+    pub category: ItemCategory,
+    #[serde(default = "default_tier")]
+    pub tier: ItemTier,
+    /// 可装备的槽位
+    #[serde(default)]
+    pub equip_slot: Option<EquipSlot>,
+    /// 单件重量
+    #[serde(default)]
+    pub weight: f32,
+    /// 基础价格
+    #[serde(default)]
+    pub value: u32,
+    /// 最大堆叠数量
+    #[serde(default = "default_max_stack")]
+    pub max_stack: u32,
+    /// 攻击力加成
+    #[serde(default)]
+    pub attack: f32,
+    /// 防御力加成
+    #[serde(default)]
+    pub defense: f32,
+    /// 装备后增加的负重上限
+    #[serde(default)]
+    pub carry_bonus: f32,
+    /// 耐久上限，未填写时使用品阶默认值
+    #[serde(default)]
+    pub durability: Option<f32>,
+    /// 修理工具每次恢复的耐久
+    #[serde(default)]
+    pub repair_amount: Option<f32>,
+    /// 家具摆放数据
+    #[serde(default)]
+    pub furniture: Option<FurnitureDef>,
+}
+
+fn default_tier() -> ItemTier {
+    ItemTier::Common
+}
+
+fn default_max_stack() -> u32 {
+    1
+}
+
+/// 物品数据文件格式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ItemDataFile {
+    tiers: HashMap<ItemTier, TierDef>,
+    items: Vec<ItemDef>,
+}
+
+/// 物品数据库
+///
+/// 启动时从数据文件加载，运行期间只读
+#[derive(Resource, Debug, Clone)]
+pub struct ItemDatabase {
+    items: HashMap<String, ItemDef>,
+    tiers: HashMap<ItemTier, TierDef>,
+}
+
+impl Default for ItemDatabase {
+    fn default() -> Self {
+        let mut tiers = HashMap::new();
+        for (tier, durability, cost) in [
+            (ItemTier::Common, 60.0, 0.5),
+            (ItemTier::Fine, 100.0, 0.8),
+            (ItemTier::Rare, 150.0, 1.2),
+            (ItemTier::Epic, 220.0, 2.0),
+            (ItemTier::Legendary, 320.0, 3.5),
+        ] {
+            tiers.insert(
+                tier,
+                TierDef {
+                    durability,
+                    repair_cost_per_point: cost,
+                },
+            );
+        }
+
+        Self {
+            items: HashMap::new(),
+            tiers,
+        }
+    }
+}
+
+impl ItemDatabase {
+    /// 从数据文件加载物品数据库
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let data: ItemDataFile = serde_json::from_str(&content)?;
+
+        let mut database = Self::default();
+        database.tiers.extend(data.tiers);
+        for item in data.items {
+            database.items.insert(item.id.clone(), item);
+        }
+        Ok(database)
+    }
+
+    /// 获取物品定义
+    pub fn get(&self, id: &str) -> Option<&ItemDef> {
+        self.items.get(id)
+    }
+
+    /// 获取品阶数据
+    pub fn tier(&self, tier: ItemTier) -> Option<&TierDef> {
+        self.tiers.get(&tier)
+    }
+
+    /// 物品的耐久上限，不可损耗的物品返回 None
+    pub fn max_durability(&self, id: &str) -> Option<f32> {
+        let def = self.get(id)?;
+        if !matches!(def.category, ItemCategory::Weapon | ItemCategory::Armor) {
+            return None;
+        }
+        def.durability
+            .or_else(|| self.tier(def.tier).map(|tier| tier.durability))
+    }
+}
+
+/// 物品实例
+///
+/// 同一定义的可堆叠物品共用一个实例，带耐久的物品各自独立
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemInstance {
+    /// 物品定义ID
+    pub item_id: String,
+    /// 数量
+    pub count: u32,
+    /// 当前耐久，不可损耗的物品为 None
+    pub durability: Option<f32>,
+}
+
+impl ItemInstance {
+    /// 按定义创建新物品，耐久为满值
+    pub fn new(database: &ItemDatabase, item_id: &str, count: u32) -> Self {
+        Self {
+            item_id: item_id.to_string(),
+            count,
+            durability: database.max_durability(item_id),
+        }
+    }
+}
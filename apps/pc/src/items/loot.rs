@@ -0,0 +1,498 @@
+use bevy::asset::{io::Reader, AssetLoader, LoadContext, LoadedFolder};
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
+use std::collections::HashMap;
+use thiserror::Error;
+
+use super::{Inventory, ItemDatabase, ItemInstance, ItemTier};
+use crate::combat::DeathEvent;
+use crate::interaction::{Interacted, InteractionKind};
+use crate::logging::{GameLogger, LogLevel};
+use crate::render::components::{LayerComponent, RenderLayer};
+use crate::time::GameCalendar;
+use crate::world::chunk::{WaterCurrent, TILE_PIXELS};
+use crate::world::entity::{Npc, NpcType, Player};
+use crate::world::map::MapManager;
+
+/// 掉落表所在的资源目录（相对于 assets）
+pub const LOOT_TABLE_FOLDER: &str = "loot";
+
+/// 掉落表中的一项
+#[derive(Debug, Clone, Deserialize)]
+pub struct LootEntry {
+    /// 物品ID
+    pub item_id: String,
+    /// 抽取权重
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    /// 数量范围
+    #[serde(default = "default_count")]
+    pub count: (u32, u32),
+}
+
+/// 必定判定的掉落，每项单独按概率掉落，不占抽取次数
+#[derive(Debug, Clone, Deserialize)]
+pub struct LootGuarantee {
+    pub item_id: String,
+    /// 掉落概率，1.0 为必掉
+    #[serde(default = "default_chance")]
+    pub chance: f32,
+    #[serde(default = "default_count")]
+    pub count: (u32, u32),
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+fn default_count() -> (u32, u32) {
+    (1, 1)
+}
+
+fn default_chance() -> f32 {
+    1.0
+}
+
+/// 掉落表
+///
+/// # 设计思路
+/// 1. 数据驱动：放在 assets/loot 下的 `*.loot.json`，修改后热重载，下一次掉落即生效
+/// 2. 按权重抽取：抽取次数在 rolls 范围内，每次从 entries 中按权重抽一项，
+///    empty_weight 为空手而归的权重
+/// 3. NPC 按类型绑定掉落表，资源点在定义中按 ID 引用
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct LootTable {
+    /// 掉落表ID
+    pub id: String,
+    /// 抽取次数范围
+    #[serde(default = "default_count")]
+    pub rolls: (u32, u32),
+    /// 每次抽取落空的权重
+    #[serde(default)]
+    pub empty_weight: u32,
+    /// 按权重抽取的物品
+    #[serde(default)]
+    pub entries: Vec<LootEntry>,
+    /// 单独判定的物品
+    #[serde(default)]
+    pub guaranteed: Vec<LootGuarantee>,
+    /// 使用该掉落表的NPC类型
+    #[serde(default)]
+    pub npc_types: Vec<NpcType>,
+}
+
+impl LootTable {
+    /// 抽取掉落，同一物品的多次结果合并为一堆
+    pub fn roll(&self, database: &ItemDatabase, rng: &mut impl Rng) -> Vec<ItemInstance> {
+        let mut counts: Vec<(String, u32)> = Vec::new();
+        let mut push = |item_id: &str, count: u32| {
+            if count == 0 {
+                return;
+            }
+            match counts.iter_mut().find(|(id, _)| id == item_id) {
+                Some((_, total)) => *total += count,
+                None => counts.push((item_id.to_string(), count)),
+            }
+        };
+
+        for guarantee in &self.guaranteed {
+            if rng.gen::<f32>() < guarantee.chance {
+                push(&guarantee.item_id, roll_count(guarantee.count, rng));
+            }
+        }
+
+        let total_weight: u32 =
+            self.empty_weight + self.entries.iter().map(|entry| entry.weight).sum::<u32>();
+        if total_weight > 0 {
+            for _ in 0..roll_count(self.rolls, rng) {
+                let mut pick = rng.gen_range(0..total_weight);
+                for entry in &self.entries {
+                    if pick < entry.weight {
+                        push(&entry.item_id, roll_count(entry.count, rng));
+                        break;
+                    }
+                    pick -= entry.weight;
+                }
+            }
+        }
+
+        // 不可堆叠的物品拆成单件，各自带耐久
+        let mut items = Vec::new();
+        for (item_id, count) in counts {
+            if database.max_durability(&item_id).is_some() {
+                for _ in 0..count {
+                    items.push(ItemInstance::new(database, &item_id, 1));
+                }
+            } else {
+                items.push(ItemInstance::new(database, &item_id, count));
+            }
+        }
+        items
+    }
+}
+
+fn roll_count((min, max): (u32, u32), rng: &mut impl Rng) -> u32 {
+    rng.gen_range(min.min(max)..=max.max(min))
+}
+
+/// 掉落表加载错误
+#[derive(Debug, Error)]
+pub enum LootTableLoaderError {
+    #[error("读取掉落表失败: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("解析掉落表失败: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// 掉落表加载器
+#[derive(Default)]
+pub struct LootTableLoader;
+
+impl AssetLoader for LootTableLoader {
+    type Asset = LootTable;
+    type Settings = ();
+    type Error = LootTableLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<LootTable, LootTableLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["loot.json"]
+    }
+}
+
+/// 掉落表注册表
+///
+/// 按掉落表ID索引，随资源事件更新
+#[derive(Resource, Default)]
+pub struct LootTableRegistry {
+    /// 掉落表目录句柄，保持目录中的资源不被卸载
+    pub folder: Option<Handle<LoadedFolder>>,
+    /// 资源ID到掉落表ID的映射，用于处理修改和移除
+    pub ids: HashMap<AssetId<LootTable>, String>,
+    /// 已加载的掉落表
+    pub tables: HashMap<String, LootTable>,
+}
+
+impl LootTableRegistry {
+    /// 按掉落表ID查找
+    pub fn get(&self, id: &str) -> Option<&LootTable> {
+        self.tables.get(id)
+    }
+
+    /// NPC类型对应的掉落表，多个表声明同一类型时取ID最小的，保证结果稳定
+    pub fn for_npc(&self, npc_type: NpcType) -> Option<&LootTable> {
+        self.tables
+            .values()
+            .filter(|table| table.npc_types.contains(&npc_type))
+            .min_by(|a, b| a.id.cmp(&b.id))
+    }
+}
+
+/// 掉落配置
+///
+/// # 参数说明
+/// - pickup_range: 拾取距离（像素）
+/// - lifetime: 掉落物在地上保留的秒数
+/// - scatter: 多件掉落散开的最大半径（像素）
+/// - size: 掉落物精灵边长
+#[derive(Resource, Debug, Clone)]
+pub struct LootSettings {
+    pub pickup_range: f32,
+    pub lifetime: f32,
+    pub scatter: f32,
+    pub size: f32,
+}
+
+impl Default for LootSettings {
+    fn default() -> Self {
+        Self {
+            pickup_range: 40.0,
+            lifetime: 300.0,
+            scatter: 20.0,
+            size: 10.0,
+        }
+    }
+}
+
+/// 按掉落表抽取并生成掉落物
+///
+/// 随机数由世界种子、掉落位置所在瓦片和 salt 决定，
+/// 同一存档中同一处、同一时刻的掉落在各端结果一致
+#[derive(Event, Debug, Clone)]
+pub struct LootRollRequest {
+    pub table_id: String,
+    /// 掉落位置（世界坐标）
+    pub position: Vec2,
+    /// 额外的随机因子，如游戏时间
+    pub salt: u64,
+}
+
+/// 直接把物品丢在地上，如背包放不下的采集物
+#[derive(Event, Debug, Clone)]
+pub struct LootDropRequest {
+    pub item: ItemInstance,
+    pub position: Vec2,
+}
+
+/// 地上的掉落物
+#[derive(Component, Debug, Clone)]
+pub struct LootDrop {
+    pub item: ItemInstance,
+    /// 剩余保留时间（秒）
+    pub remaining: f32,
+}
+
+/// 拾取事件
+#[derive(Event, Debug, Clone)]
+pub struct LootPickedUp {
+    pub picker: Entity,
+    pub item_id: String,
+    pub count: u32,
+}
+
+/// 掉落位置的随机数生成器
+fn loot_rng(seed: u64, table_id: &str, position: Vec2, salt: u64) -> ChaCha8Rng {
+    let tile = (position / TILE_PIXELS).floor().as_ivec2();
+    let mut hash = (tile.x as i64 as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (tile.y as i64 as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ salt.wrapping_mul(0x1656_67B1_9E37_79F9);
+    for byte in table_id.bytes() {
+        hash = hash.rotate_left(5) ^ byte as u64;
+    }
+    ChaCha8Rng::seed_from_u64(seed ^ hash)
+}
+
+/// 开始加载掉落表目录
+pub fn load_loot_tables(asset_server: Res<AssetServer>, mut registry: ResMut<LootTableRegistry>) {
+    registry.folder = Some(asset_server.load_folder(LOOT_TABLE_FOLDER));
+}
+
+/// 根据资源事件更新掉落表注册表
+pub fn sync_loot_tables(
+    mut registry: ResMut<LootTableRegistry>,
+    mut events: EventReader<AssetEvent<LootTable>>,
+    tables: Res<Assets<LootTable>>,
+    database: Res<ItemDatabase>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for event in events.read() {
+        match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                let Some(table) = tables.get(*id) else {
+                    continue;
+                };
+                if let Some(previous) = registry.ids.insert(*id, table.id.clone()) {
+                    registry.tables.remove(&previous);
+                }
+                registry.tables.insert(table.id.clone(), table.clone());
+
+                if let Some(logger) = logger.as_mut() {
+                    let item_ids = table
+                        .entries
+                        .iter()
+                        .map(|entry| &entry.item_id)
+                        .chain(table.guaranteed.iter().map(|entry| &entry.item_id));
+                    for item_id in item_ids {
+                        if database.get(item_id).is_none() {
+                            logger.log(
+                                LogLevel::Error,
+                                &format!("掉落表 {} 引用了不存在的物品: {}", table.id, item_id),
+                            );
+                        }
+                    }
+                    logger.log(LogLevel::Info, &format!("掉落表已加载: {}", table.id));
+                }
+            }
+            AssetEvent::Removed { id } => {
+                if let Some(previous) = registry.ids.remove(id) {
+                    registry.tables.remove(&previous);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// NPC 死亡时按其类型的掉落表掉落
+pub fn roll_death_loot(
+    mut deaths: EventReader<DeathEvent>,
+    registry: Res<LootTableRegistry>,
+    calendar: Option<Res<GameCalendar>>,
+    npcs: Query<(&Npc, &GlobalTransform)>,
+    mut requests: EventWriter<LootRollRequest>,
+) {
+    let salt = calendar.map_or(0, |calendar| calendar.total_hours() as u64);
+    for death in deaths.read() {
+        let Ok((npc, transform)) = npcs.get(death.entity) else {
+            continue;
+        };
+        let Some(table) = registry.for_npc(npc.npc_type) else {
+            continue;
+        };
+        requests.send(LootRollRequest {
+            table_id: table.id.clone(),
+            position: transform.translation().truncate(),
+            salt,
+        });
+    }
+}
+
+/// 处理掉落请求，生成掉落物
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_loot_drops(
+    mut commands: Commands,
+    settings: Res<LootSettings>,
+    registry: Res<LootTableRegistry>,
+    database: Res<ItemDatabase>,
+    map_manager: Option<Res<MapManager>>,
+    mut rolls: EventReader<LootRollRequest>,
+    mut drops: EventReader<LootDropRequest>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let seed = map_manager.map_or(0, |map_manager| map_manager.seed as u64);
+
+    for request in rolls.read() {
+        let Some(table) = registry.get(&request.table_id) else {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(
+                    LogLevel::Error,
+                    &format!("掉落表不存在: {}", request.table_id),
+                );
+            }
+            continue;
+        };
+        let mut rng = loot_rng(seed, &table.id, request.position, request.salt);
+        for item in table.roll(&database, &mut rng) {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let distance = rng.gen_range(0.0..=settings.scatter);
+            let position = request.position + Vec2::from_angle(angle) * distance;
+            spawn_drop(&mut commands, &settings, &database, item, position);
+        }
+    }
+
+    for request in drops.read() {
+        spawn_drop(
+            &mut commands,
+            &settings,
+            &database,
+            request.item.clone(),
+            request.position,
+        );
+    }
+}
+
+/// 生成一件掉落物，颜色随品阶
+fn spawn_drop(
+    commands: &mut Commands,
+    settings: &LootSettings,
+    database: &ItemDatabase,
+    item: ItemInstance,
+    position: Vec2,
+) {
+    let def = database.get(&item.item_id);
+    let color = match def.map_or(ItemTier::Common, |def| def.tier) {
+        ItemTier::Common => Color::srgb(0.85, 0.85, 0.8),
+        ItemTier::Fine => Color::srgb(0.4, 0.8, 0.4),
+        ItemTier::Rare => Color::srgb(0.35, 0.55, 0.95),
+        ItemTier::Epic => Color::srgb(0.7, 0.4, 0.9),
+        ItemTier::Legendary => Color::srgb(0.95, 0.7, 0.2),
+    };
+    let name = def.map_or(item.item_id.clone(), |def| def.name.clone());
+    commands.spawn((
+        LootDrop {
+            item,
+            remaining: settings.lifetime,
+        },
+        Sprite {
+            color,
+            custom_size: Some(Vec2::splat(settings.size)),
+            ..default()
+        },
+        Transform::from_translation(position.extend(0.0))
+            .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+        LayerComponent {
+            layer: RenderLayer::Decoration,
+            sub_order: 0,
+        },
+        // 掉进河里的东西会顺水漂走
+        WaterCurrent::default(),
+        Name::new(name),
+    ));
+}
+
+/// 交互键作用到掉落物时拾取，背包放不下的部分留在地上
+pub fn pick_up_loot(
+    mut commands: Commands,
+    mut interacted: EventReader<Interacted>,
+    database: Res<ItemDatabase>,
+    mut players: Query<(Entity, &mut Inventory), With<Player>>,
+    mut drops: Query<&mut LootDrop>,
+    mut picked: EventWriter<LootPickedUp>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let targets: Vec<Entity> = interacted
+        .read()
+        .filter(|event| event.kind == InteractionKind::Loot)
+        .map(|event| event.entity)
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+    let Ok((player, mut inventory)) = players.get_single_mut() else {
+        return;
+    };
+
+    for entity in targets {
+        let Ok(mut drop) = drops.get_mut(entity) else {
+            continue;
+        };
+        let count = drop.item.count;
+        let leftover = inventory.add(&database, drop.item.clone());
+        let collected = count - leftover;
+        if collected == 0 {
+            continue;
+        }
+        if leftover > 0 {
+            drop.item.count = leftover;
+        } else {
+            commands.entity(entity).despawn();
+        }
+
+        picked.send(LootPickedUp {
+            picker: player,
+            item_id: drop.item.item_id.clone(),
+            count: collected,
+        });
+        if let Some(logger) = logger.as_mut() {
+            let item_name = database
+                .get(&drop.item.item_id)
+                .map_or(drop.item.item_id.as_str(), |item| item.name.as_str());
+            logger.log(LogLevel::Info, &format!("拾取{} x{}", item_name, collected));
+        }
+    }
+}
+
+/// 掉落物到时消失
+pub fn expire_loot_drops(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut drops: Query<(Entity, &mut LootDrop)>,
+) {
+    for (entity, mut drop) in &mut drops {
+        drop.remaining -= time.delta_secs();
+        if drop.remaining <= 0.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,31 @@
+/// 物品模块
+///
+/// # 模块组成
+/// 1. item：物品定义、品阶与物品数据库
+/// 2. inventory：背包
+/// 3. equipment：装备槽位与属性加成
+/// 4. durability：装备耐久的损耗与修理
+/// 5. encumbrance：负重与移速
+/// 6. stash：仓库容器与存取界面
+/// 7. loot：掉落表、死亡掉落与拾取
+/// 8. shop：商人买卖、物价与补货
+/// 9. systems：物品插件
+mod durability;
+mod encumbrance;
+mod equipment;
+mod inventory;
+mod item;
+mod loot;
+mod shop;
+mod stash;
+mod systems;
+
+pub use durability::*;
+pub use encumbrance::*;
+pub use equipment::*;
+pub use inventory::*;
+pub use item::*;
+pub use loot::*;
+pub use shop::*;
+pub use stash::*;
+pub use systems::ItemsPlugin;
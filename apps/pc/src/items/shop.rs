@@ -0,0 +1,748 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::{Inventory, ItemCategory, ItemDatabase, ItemInstance};
+use crate::analytics::PlaytestSample;
+use crate::config::resolve_data_path;
+use crate::interaction::{Interacted, InteractionKind, PanelInteraction};
+use crate::logging::{GameLogger, LogLevel};
+use crate::time::GameCalendar;
+use crate::world::chunk::TILE_PIXELS;
+use crate::world::entity::{Character, Npc, NpcType, Player};
+use crate::world::map::{MapManager, Zone};
+
+/// 商铺数据文件路径
+pub const SHOP_DATA_PATH: &str = "src/config/shops.json";
+
+/// 商铺存档路径
+pub const SHOP_SAVE_PATH: &str = "saves/shops.json";
+
+/// 商铺的一种货品
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShopStockEntry {
+    pub item_id: String,
+    /// 补货后的数量
+    pub count: u32,
+}
+
+/// 商铺定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShopDef {
+    pub id: String,
+    pub name: String,
+    /// 经营该商铺的商人名字，为空时作为没有专属商铺的商人的默认商铺
+    #[serde(default)]
+    pub merchants: Vec<String>,
+    /// 补货间隔（游戏小时）
+    pub restock_hours: f32,
+    /// 收购的物品类别，为空时什么都收
+    #[serde(default)]
+    pub buys: Vec<ItemCategory>,
+    /// 货品
+    pub stock: Vec<ShopStockEntry>,
+}
+
+impl ShopDef {
+    /// 是否收购该类物品
+    pub fn buys(&self, category: ItemCategory) -> bool {
+        self.buys.is_empty() || self.buys.contains(&category)
+    }
+
+    /// 补满后的库存
+    fn full_stock(&self) -> Vec<ShopStockEntry> {
+        self.stock.clone()
+    }
+}
+
+/// 商铺数据文件格式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ShopDataFile {
+    shops: Vec<ShopDef>,
+}
+
+/// 商铺数据库
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ShopLibrary {
+    shops: Vec<ShopDef>,
+}
+
+impl ShopLibrary {
+    /// 从数据文件加载
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let data: ShopDataFile = serde_json::from_str(&content)?;
+        Ok(Self { shops: data.shops })
+    }
+
+    /// 按商铺ID查找
+    pub fn get(&self, id: &str) -> Option<&ShopDef> {
+        self.shops.iter().find(|shop| shop.id == id)
+    }
+
+    /// 商人经营的商铺，没有专属商铺时使用默认商铺
+    pub fn for_merchant(&self, name: &str) -> Option<&ShopDef> {
+        self.shops
+            .iter()
+            .find(|shop| shop.merchants.iter().any(|merchant| merchant == name))
+            .or_else(|| self.shops.iter().find(|shop| shop.merchants.is_empty()))
+    }
+}
+
+/// 商铺配置
+///
+/// # 参数说明
+/// - buy_markup: 玩家买入价相对物品价值的倍率
+/// - sell_ratio: 玩家卖出价相对物品价值的比例
+/// - zone_markup: 各气候区域的物价倍率，偏远苦寒之地货物更贵、收购也更大方
+/// - reputation_step: 在同一摊位累计成交多少银两提升一级熟客
+/// - reputation_bonus: 每级熟客的让利比例
+/// - max_reputation: 熟客等级上限
+///
+/// 服务器按物品价值核对价格，买入价不低于价值，卖出价不高于价值
+#[derive(Resource, Debug, Clone)]
+pub struct ShopSettings {
+    pub interact_range: f32,
+    pub buy_markup: f32,
+    pub sell_ratio: f32,
+    pub zone_markup: HashMap<Zone, f32>,
+    pub reputation_step: u32,
+    pub reputation_bonus: f32,
+    pub max_reputation: u32,
+    pub save_path: String,
+}
+
+impl Default for ShopSettings {
+    fn default() -> Self {
+        Self {
+            interact_range: 48.0,
+            buy_markup: 1.25,
+            sell_ratio: 0.5,
+            zone_markup: HashMap::from([
+                (Zone::Desert, 1.3),
+                (Zone::Polar, 1.4),
+                (Zone::Mountains, 1.2),
+            ]),
+            reputation_step: 500,
+            reputation_bonus: 0.03,
+            max_reputation: 5,
+            save_path: SHOP_SAVE_PATH.to_string(),
+        }
+    }
+}
+
+impl ShopSettings {
+    /// 熟客等级
+    pub fn reputation_level(&self, traded: u32) -> u32 {
+        (traded / self.reputation_step.max(1)).min(self.max_reputation)
+    }
+
+    /// 玩家买入单价
+    pub fn buy_price(&self, value: u32, zone: Option<Zone>, traded: u32) -> u32 {
+        let discount = self.reputation_level(traded) as f32 * self.reputation_bonus;
+        let price = value as f32 * self.buy_markup * self.zone_factor(zone) * (1.0 - discount);
+        (price.round() as u32).max(value)
+    }
+
+    /// 玩家卖出单价
+    pub fn sell_price(&self, value: u32, zone: Option<Zone>, traded: u32) -> u32 {
+        let bonus = self.reputation_level(traded) as f32 * self.reputation_bonus;
+        let price = value as f32 * self.sell_ratio * self.zone_factor(zone) * (1.0 + bonus);
+        (price.floor() as u32).min(value)
+    }
+
+    fn zone_factor(&self, zone: Option<Zone>) -> f32 {
+        zone.and_then(|zone| self.zone_markup.get(&zone))
+            .copied()
+            .unwrap_or(1.0)
+    }
+}
+
+/// 商人组件，商人首次出现时按名字分配商铺
+#[derive(Component, Debug, Clone)]
+pub struct ShopKeeper {
+    pub shop_id: String,
+    /// 摊位标识，同一商铺开在不同地方时各自一份库存
+    pub stall: String,
+    /// 摊位所在的气候区域
+    pub zone: Option<Zone>,
+}
+
+/// 摊位状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StallState {
+    /// 剩余库存
+    pub stock: Vec<ShopStockEntry>,
+    /// 上次补货的游戏小时数
+    pub last_restock: f64,
+    /// 玩家在该摊位累计成交的银两
+    pub traded: u32,
+}
+
+/// 全部摊位的库存与熟客记录，随存档保存
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShopRegistry {
+    stalls: HashMap<String, StallState>,
+}
+
+impl ShopRegistry {
+    /// 从存档读取
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 写入存档
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// 获取摊位状态
+    pub fn get(&self, stall: &str) -> Option<&StallState> {
+        self.stalls.get(stall)
+    }
+
+    /// 获取摊位状态，首次光顾时按商铺定义备货
+    pub fn get_or_create(&mut self, stall: &str, shop: &ShopDef, now: f64) -> &mut StallState {
+        self.stalls
+            .entry(stall.to_string())
+            .or_insert_with(|| StallState {
+                stock: shop.full_stock(),
+                last_restock: now,
+                traded: 0,
+            })
+    }
+}
+
+/// 当前打开的商铺
+#[derive(Resource, Debug, Default)]
+pub struct OpenShop {
+    /// 商人实体
+    pub merchant: Option<Entity>,
+}
+
+/// 交易方向
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TradeKind {
+    /// 买入一件货品
+    Buy { item_id: String },
+    /// 卖出背包某格中的一件
+    Sell { slot: usize },
+}
+
+/// 交易请求
+#[derive(Event, Debug, Clone)]
+pub struct TradeRequest {
+    /// 背包所属实体
+    pub entity: Entity,
+    pub kind: TradeKind,
+}
+
+/// 商铺界面根节点标记
+#[derive(Component)]
+pub struct ShopUi;
+
+/// 商铺界面中的按钮
+#[derive(Component, Debug, Clone)]
+pub struct ShopTradeButton(pub TradeKind);
+
+/// 加载商铺数据，失败时没有商铺
+pub fn load_shop_library(mut commands: Commands, mut logger: Option<ResMut<GameLogger>>) {
+    let library = ShopLibrary::load(&resolve_data_path(SHOP_DATA_PATH)).unwrap_or_else(|e| {
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Error, &format!("商铺数据加载失败: {}", e));
+        }
+        ShopLibrary::default()
+    });
+    commands.insert_resource(library);
+}
+
+/// 读取商铺存档，没有存档时所有摊位按定义备货
+pub fn load_shop_registry(
+    mut commands: Commands,
+    settings: Res<ShopSettings>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let registry = if Path::new(&settings.save_path).exists() {
+        ShopRegistry::load(&settings.save_path).unwrap_or_else(|e| {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("商铺存档读取失败: {}", e));
+            }
+            ShopRegistry::default()
+        })
+    } else {
+        ShopRegistry::default()
+    };
+    commands.insert_resource(registry);
+}
+
+/// 为新出现的商人分配商铺
+pub fn assign_shop_keepers(
+    mut commands: Commands,
+    library: Res<ShopLibrary>,
+    map_manager: Option<Res<MapManager>>,
+    merchants: Query<(Entity, &Npc, &Character, &Transform), Without<ShopKeeper>>,
+) {
+    for (entity, npc, character, transform) in merchants.iter() {
+        if npc.npc_type != NpcType::Merchant {
+            continue;
+        }
+        let Some(shop) = library.for_merchant(&character.name) else {
+            continue;
+        };
+        let tile = (transform.translation.truncate() / TILE_PIXELS)
+            .floor()
+            .as_ivec2();
+        let zone = map_manager.as_ref().map(|map_manager| {
+            let height = map_manager.get_height_at(tile.x, tile.y);
+            map_manager
+                .climate_system()
+                .get_climate_zone(tile.x, tile.y, height)
+        });
+        commands.entity(entity).insert(ShopKeeper {
+            shop_id: shop.id.clone(),
+            stall: format!("{}@{},{}", shop.id, tile.x, tile.y),
+            zone,
+        });
+    }
+}
+
+/// 到时间的摊位补满货品
+pub fn restock_shops(
+    calendar: Res<GameCalendar>,
+    library: Res<ShopLibrary>,
+    mut registry: ResMut<ShopRegistry>,
+) {
+    let now = calendar.total_hours();
+    let due: Vec<String> = registry
+        .stalls
+        .iter()
+        .filter(|(stall, state)| {
+            stall_shop(stall)
+                .and_then(|shop_id| library.get(shop_id))
+                .is_some_and(|shop| now - state.last_restock >= shop.restock_hours as f64)
+        })
+        .map(|(stall, _)| stall.clone())
+        .collect();
+
+    for stall in due {
+        let Some(shop) = stall_shop(&stall).and_then(|shop_id| library.get(shop_id)) else {
+            continue;
+        };
+        if let Some(state) = registry.stalls.get_mut(&stall) {
+            state.stock = shop.full_stock();
+            state.last_restock = now;
+        }
+    }
+}
+
+/// 摊位标识中的商铺ID
+fn stall_shop(stall: &str) -> Option<&str> {
+    stall.split('@').next()
+}
+
+/// 交互键作用到商人时打开商铺，商铺打开时再按交互键或走远则关闭
+///
+/// 关闭商铺时写入存档
+#[allow(clippy::too_many_arguments)]
+pub fn toggle_shop(
+    mut interacted: EventReader<Interacted>,
+    mut panel_events: EventReader<PanelInteraction>,
+    settings: Res<ShopSettings>,
+    calendar: Res<GameCalendar>,
+    library: Res<ShopLibrary>,
+    mut open: ResMut<OpenShop>,
+    mut registry: ResMut<ShopRegistry>,
+    players: Query<&Transform, With<Player>>,
+    merchants: Query<(&ShopKeeper, &Transform)>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let pressed = panel_events.read().count() > 0;
+    let target = interacted
+        .read()
+        .filter(|event| event.kind == InteractionKind::Trade)
+        .last()
+        .map(|event| event.entity);
+    let Ok(player) = players.get_single() else {
+        return;
+    };
+    let player_pos = player.translation.truncate();
+
+    if let Some(merchant) = open.merchant {
+        let out_of_range = merchants.get(merchant).map_or(true, |(_, transform)| {
+            transform.translation.truncate().distance(player_pos) > settings.interact_range
+        });
+        if !out_of_range && !pressed {
+            return;
+        }
+
+        open.merchant = None;
+        if let Err(e) = registry.save(&settings.save_path) {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("商铺存档写入失败: {}", e));
+            }
+        }
+        return;
+    }
+
+    let Some(entity) = target else {
+        return;
+    };
+    let Ok((keeper, _)) = merchants.get(entity) else {
+        return;
+    };
+    let Some(shop) = library.get(&keeper.shop_id) else {
+        return;
+    };
+    registry.get_or_create(&keeper.stall, shop, calendar.total_hours());
+    open.merchant = Some(entity);
+
+    if let Some(logger) = logger.as_mut() {
+        logger.log(LogLevel::Debug, &format!("打开商铺: {}", shop.name));
+    }
+}
+
+/// 点击按钮时发出交易请求
+pub fn handle_shop_clicks(
+    open: Res<OpenShop>,
+    buttons: Query<(&Interaction, &ShopTradeButton), Changed<Interaction>>,
+    players: Query<Entity, With<Player>>,
+    mut requests: EventWriter<TradeRequest>,
+) {
+    if open.merchant.is_none() {
+        return;
+    }
+    let Ok(player) = players.get_single() else {
+        return;
+    };
+
+    for (interaction, button) in buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            requests.send(TradeRequest {
+                entity: player,
+                kind: button.0.clone(),
+            });
+        }
+    }
+}
+
+/// 处理交易，银两或背包空间不足时不成交
+///
+/// 成交的银两收支作为试玩样本上报
+#[allow(clippy::too_many_arguments)]
+pub fn process_trades(
+    settings: Res<ShopSettings>,
+    database: Res<ItemDatabase>,
+    calendar: Res<GameCalendar>,
+    library: Res<ShopLibrary>,
+    open: Res<OpenShop>,
+    mut registry: ResMut<ShopRegistry>,
+    mut requests: EventReader<TradeRequest>,
+    mut inventories: Query<&mut Inventory>,
+    merchants: Query<&ShopKeeper>,
+    mut samples: EventWriter<PlaytestSample>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let Some(keeper) = open
+        .merchant
+        .and_then(|merchant| merchants.get(merchant).ok())
+    else {
+        requests.clear();
+        return;
+    };
+    let Some(shop) = library.get(&keeper.shop_id) else {
+        requests.clear();
+        return;
+    };
+
+    for request in requests.read() {
+        let Ok(mut inventory) = inventories.get_mut(request.entity) else {
+            continue;
+        };
+        let stall = registry.get_or_create(&keeper.stall, shop, calendar.total_hours());
+
+        let result = match &request.kind {
+            TradeKind::Buy { item_id } => {
+                buy_item(&settings, &database, keeper, stall, &mut inventory, item_id)
+            }
+            TradeKind::Sell { slot } => sell_item(
+                &settings,
+                &database,
+                shop,
+                keeper,
+                stall,
+                &mut inventory,
+                *slot,
+            ),
+        };
+
+        match result {
+            Ok((item_id, price)) => {
+                stall.traded = stall.traded.saturating_add(price);
+                let label = format!("shop:{}", shop.id);
+                samples.send(match request.kind {
+                    TradeKind::Buy { .. } => PlaytestSample::GoldSpent {
+                        amount: price,
+                        sink: label,
+                    },
+                    TradeKind::Sell { .. } => PlaytestSample::GoldEarned {
+                        amount: price,
+                        source: label,
+                    },
+                });
+                if let Some(logger) = logger.as_mut() {
+                    let name = database
+                        .get(&item_id)
+                        .map_or(item_id.as_str(), |def| def.name.as_str());
+                    let verb = if matches!(request.kind, TradeKind::Buy { .. }) {
+                        "买入"
+                    } else {
+                        "卖出"
+                    };
+                    logger.log(
+                        LogLevel::Info,
+                        &format!("{}{}，{} 两银子", verb, name, price),
+                    );
+                }
+            }
+            Err(reason) => {
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(LogLevel::Info, &format!("交易失败: {}", reason));
+                }
+            }
+        }
+    }
+}
+
+/// 买入一件货品
+fn buy_item(
+    settings: &ShopSettings,
+    database: &ItemDatabase,
+    keeper: &ShopKeeper,
+    stall: &mut StallState,
+    inventory: &mut Inventory,
+    item_id: &str,
+) -> Result<(String, u32), String> {
+    let Some(def) = database.get(item_id) else {
+        return Err(format!("未知物品 {}", item_id));
+    };
+    let Some(entry) = stall
+        .stock
+        .iter_mut()
+        .find(|entry| entry.item_id == item_id && entry.count > 0)
+    else {
+        return Err(format!("{}已售罄", def.name));
+    };
+    let price = settings.buy_price(def.value, keeper.zone, stall.traded);
+    if inventory.money < price {
+        return Err(format!("银两不足，还差 {} 两", price - inventory.money));
+    }
+    if inventory.add(database, ItemInstance::new(database, item_id, 1)) > 0 {
+        return Err("背包已满".to_string());
+    }
+
+    entry.count -= 1;
+    inventory.money -= price;
+    Ok((item_id.to_string(), price))
+}
+
+/// 卖出背包某格中的一件
+fn sell_item(
+    settings: &ShopSettings,
+    database: &ItemDatabase,
+    shop: &ShopDef,
+    keeper: &ShopKeeper,
+    stall: &mut StallState,
+    inventory: &mut Inventory,
+    slot: usize,
+) -> Result<(String, u32), String> {
+    let Some(Some(item)) = inventory.slots.get(slot).cloned() else {
+        return Err("这一格是空的".to_string());
+    };
+    let Some(def) = database.get(&item.item_id) else {
+        return Err(format!("未知物品 {}", item.item_id));
+    };
+    if !shop.buys(def.category) {
+        return Err(format!("{}不收{}", shop.name, def.name));
+    }
+
+    // 带耐久的装备按剩余耐久折价
+    let wear = match (item.durability, database.max_durability(&item.item_id)) {
+        (Some(current), Some(max)) if max > 0.0 => (current / max).clamp(0.0, 1.0),
+        _ => 1.0,
+    };
+    let price = (settings.sell_price(def.value, keeper.zone, stall.traded) as f32 * wear) as u32;
+
+    if item.count > 1 {
+        inventory.slots[slot] = Some(ItemInstance {
+            count: item.count - 1,
+            ..item.clone()
+        });
+    } else {
+        inventory.take(slot);
+    }
+    inventory.money = inventory.money.saturating_add(price);
+
+    match stall
+        .stock
+        .iter_mut()
+        .find(|entry| entry.item_id == item.item_id)
+    {
+        Some(entry) => entry.count += 1,
+        None => stall.stock.push(ShopStockEntry {
+            item_id: item.item_id.clone(),
+            count: 1,
+        }),
+    }
+    Ok((item.item_id, price))
+}
+
+/// 商铺、摊位或背包变化时重建商铺界面
+#[allow(clippy::too_many_arguments)]
+pub fn update_shop_ui(
+    mut commands: Commands,
+    settings: Res<ShopSettings>,
+    database: Res<ItemDatabase>,
+    library: Res<ShopLibrary>,
+    open: Res<OpenShop>,
+    registry: Res<ShopRegistry>,
+    merchants: Query<&ShopKeeper>,
+    players: Query<Ref<Inventory>, With<Player>>,
+    ui: Query<Entity, With<ShopUi>>,
+) {
+    let Ok(inventory) = players.get_single() else {
+        return;
+    };
+    if !open.is_changed() && !registry.is_changed() && !inventory.is_changed() {
+        return;
+    }
+
+    for entity in ui.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some(keeper) = open
+        .merchant
+        .and_then(|merchant| merchants.get(merchant).ok())
+    else {
+        return;
+    };
+    let (Some(shop), Some(stall)) = (library.get(&keeper.shop_id), registry.get(&keeper.stall))
+    else {
+        return;
+    };
+
+    let level = settings.reputation_level(stall.traded);
+    let title = if level > 0 {
+        format!("{}（熟客 {} 级）", shop.name, level)
+    } else {
+        shop.name.clone()
+    };
+
+    let wares: Vec<(String, ShopTradeButton)> = stall
+        .stock
+        .iter()
+        .filter(|entry| entry.count > 0)
+        .filter_map(|entry| {
+            let def = database.get(&entry.item_id)?;
+            let price = settings.buy_price(def.value, keeper.zone, stall.traded);
+            Some((
+                format!("{} x{}  {} 两", def.name, entry.count, price),
+                ShopTradeButton(TradeKind::Buy {
+                    item_id: entry.item_id.clone(),
+                }),
+            ))
+        })
+        .collect();
+
+    let goods: Vec<(String, ShopTradeButton)> = inventory
+        .slots
+        .iter()
+        .enumerate()
+        .filter_map(|(slot, item)| {
+            let item = item.as_ref()?;
+            let def = database.get(&item.item_id)?;
+            let text = if shop.buys(def.category) {
+                let price = settings.sell_price(def.value, keeper.zone, stall.traded);
+                format!("{} x{}  {} 两", def.name, item.count, price)
+            } else {
+                format!("{} x{}  不收", def.name, item.count)
+            };
+            Some((text, ShopTradeButton(TradeKind::Sell { slot })))
+        })
+        .collect();
+
+    commands
+        .spawn((
+            ShopUi,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(16.0),
+                ..default()
+            },
+        ))
+        .with_children(|root| {
+            spawn_trade_column(root, &format!("行囊（{} 两）", inventory.money), goods);
+            spawn_trade_column(root, &title, wares);
+        });
+}
+
+/// 生成一列交易按钮
+fn spawn_trade_column(
+    parent: &mut ChildBuilder,
+    title: &str,
+    entries: Vec<(String, ShopTradeButton)>,
+) {
+    parent
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(12.0)),
+                row_gap: Val::Px(4.0),
+                min_width: Val::Px(240.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.08, 0.06, 0.9)),
+        ))
+        .with_children(|column| {
+            column.spawn((
+                Text::new(title),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+            ));
+
+            for (text, button) in entries {
+                column
+                    .spawn((
+                        Button,
+                        button,
+                        Node {
+                            padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0.25, 0.2, 0.15, 0.8)),
+                    ))
+                    .with_children(|button| {
+                        button.spawn((
+                            Text::new(text),
+                            TextFont {
+                                font_size: 14.0,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
@@ -0,0 +1,469 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::{Inventory, ItemDatabase, ItemInstance};
+use crate::config::resolve_data_path;
+use crate::interaction::{Interacted, InteractionKind, PanelInteraction};
+use crate::logging::{GameLogger, LogLevel};
+use crate::render::components::{LayerComponent, RenderLayer};
+use crate::world::entity::Player;
+
+/// 仓库存档路径
+pub const STASH_SAVE_PATH: &str = "saves/stashes.json";
+/// 野外仓库数据文件路径
+pub const STASH_DATA_PATH: &str = "src/config/stashes.json";
+
+/// 仓库归属
+///
+/// - Shared: 所有共享仓库（如各地客栈）使用同一份库存
+/// - Location: 每个地点（如自家宅院）单独一份库存，以地点标识区分
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StashScope {
+    Shared,
+    Location(String),
+}
+
+/// 仓库容器组件，放置在宅院、客栈等可进入的场所中
+#[derive(Component, Debug, Clone)]
+pub struct StashContainer {
+    /// 显示名称
+    pub name: String,
+    /// 库存归属
+    pub scope: StashScope,
+}
+
+/// 仓库配置
+#[derive(Resource, Debug, Clone)]
+pub struct StashSettings {
+    /// 每个仓库的格数
+    pub capacity: usize,
+    /// 可交互距离
+    pub interact_range: f32,
+    /// 存档路径
+    pub save_path: String,
+}
+
+impl Default for StashSettings {
+    fn default() -> Self {
+        Self {
+            capacity: 60,
+            interact_range: 48.0,
+            save_path: STASH_SAVE_PATH.to_string(),
+        }
+    }
+}
+
+/// 野外仓库定义，不写地点的是共享仓库
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StashDef {
+    pub name: String,
+    pub position: [f32; 2],
+    #[serde(default)]
+    pub location: Option<String>,
+}
+
+/// 野外仓库数据文件格式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StashDataFile {
+    stashes: Vec<StashDef>,
+}
+
+/// 读取野外仓库列表
+pub fn load_stash_defs(path: &str) -> Result<Vec<StashDef>, Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(path)?;
+    let data: StashDataFile = serde_json::from_str(&content)?;
+    Ok(data.stashes)
+}
+
+/// 全部仓库的库存，随存档保存
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StashRegistry {
+    /// 共享仓库
+    shared: Option<Inventory>,
+    /// 按地点区分的仓库
+    locations: HashMap<String, Inventory>,
+}
+
+impl StashRegistry {
+    /// 从存档读取
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 写入存档
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// 获取仓库库存
+    pub fn get(&self, scope: &StashScope) -> Option<&Inventory> {
+        match scope {
+            StashScope::Shared => self.shared.as_ref(),
+            StashScope::Location(id) => self.locations.get(id),
+        }
+    }
+
+    /// 获取仓库库存，首次使用时创建
+    pub fn get_or_create(&mut self, scope: &StashScope, capacity: usize) -> &mut Inventory {
+        match scope {
+            StashScope::Shared => self
+                .shared
+                .get_or_insert_with(|| Inventory::with_capacity(capacity)),
+            StashScope::Location(id) => self
+                .locations
+                .entry(id.clone())
+                .or_insert_with(|| Inventory::with_capacity(capacity)),
+        }
+    }
+}
+
+/// 当前打开的仓库
+#[derive(Resource, Debug, Default)]
+pub struct OpenStash {
+    /// 仓库容器实体
+    pub container: Option<Entity>,
+    /// 库存归属
+    pub scope: Option<StashScope>,
+}
+
+/// 转移方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferDirection {
+    /// 背包 -> 仓库
+    Deposit,
+    /// 仓库 -> 背包
+    Withdraw,
+}
+
+/// 转移一格物品的请求
+#[derive(Event, Debug, Clone)]
+pub struct StashTransferRequest {
+    /// 背包所属实体
+    pub entity: Entity,
+    pub direction: TransferDirection,
+    /// 来源格子
+    pub slot: usize,
+}
+
+/// 仓库界面根节点标记
+#[derive(Component)]
+pub struct StashUi;
+
+/// 仓库界面中的格子按钮
+#[derive(Component, Debug, Clone, Copy)]
+pub struct StashSlotButton {
+    pub direction: TransferDirection,
+    pub slot: usize,
+}
+
+/// 生成仓库容器实体
+pub fn spawn_stash_container(
+    commands: &mut Commands,
+    position: Vec3,
+    name: &str,
+    scope: StashScope,
+) -> Entity {
+    commands
+        .spawn((
+            StashContainer {
+                name: name.to_string(),
+                scope,
+            },
+            Name::new(format!("Stash: {}", name)),
+            Sprite {
+                color: Color::srgb(0.45, 0.3, 0.15),
+                custom_size: Some(Vec2::new(28.0, 20.0)),
+                ..default()
+            },
+            Transform::from_translation(position),
+            LayerComponent {
+                layer: RenderLayer::Decoration,
+                sub_order: 0,
+            },
+        ))
+        .id()
+}
+
+/// 按数据文件生成驿站、客栈等处的野外仓库
+pub fn spawn_world_stashes(mut commands: Commands, mut logger: Option<ResMut<GameLogger>>) {
+    let stashes = match load_stash_defs(&resolve_data_path(STASH_DATA_PATH)) {
+        Ok(stashes) => stashes,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("仓库数据加载失败: {}", e));
+            }
+            return;
+        }
+    };
+
+    for stash in stashes {
+        let scope = match stash.location {
+            Some(location) => StashScope::Location(location),
+            None => StashScope::Shared,
+        };
+        let position = Vec2::from_array(stash.position).extend(0.0);
+        spawn_stash_container(&mut commands, position, &stash.name, scope);
+    }
+}
+
+/// 读取仓库存档，没有存档时使用空仓库
+pub fn load_stash_registry(
+    mut commands: Commands,
+    settings: Res<StashSettings>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let registry = if Path::new(&settings.save_path).exists() {
+        StashRegistry::load(&settings.save_path).unwrap_or_else(|e| {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("仓库存档读取失败: {}", e));
+            }
+            StashRegistry::default()
+        })
+    } else {
+        StashRegistry::default()
+    };
+    commands.insert_resource(registry);
+}
+
+/// 交互键作用到仓库时打开，仓库打开时再按交互键或走远则关闭
+///
+/// 关闭仓库时写入存档
+#[allow(clippy::too_many_arguments)]
+pub fn toggle_stash(
+    mut interacted: EventReader<Interacted>,
+    mut panel_events: EventReader<PanelInteraction>,
+    settings: Res<StashSettings>,
+    mut open: ResMut<OpenStash>,
+    mut registry: ResMut<StashRegistry>,
+    players: Query<&Transform, With<Player>>,
+    containers: Query<(&StashContainer, &Transform)>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let pressed = panel_events.read().count() > 0;
+    let target = interacted
+        .read()
+        .filter(|event| event.kind == InteractionKind::Stash)
+        .last()
+        .map(|event| event.entity);
+    let Ok(player) = players.get_single() else {
+        return;
+    };
+    let player_pos = player.translation.truncate();
+
+    if let Some(container) = open.container {
+        let out_of_range = containers.get(container).map_or(true, |(_, transform)| {
+            transform.translation.truncate().distance(player_pos) > settings.interact_range
+        });
+        if !out_of_range && !pressed {
+            return;
+        }
+
+        open.container = None;
+        open.scope = None;
+        if let Err(e) = registry.save(&settings.save_path) {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("仓库存档写入失败: {}", e));
+            }
+        }
+        return;
+    }
+
+    let Some(entity) = target else {
+        return;
+    };
+    if let Ok((container, _)) = containers.get(entity) {
+        registry.get_or_create(&container.scope, settings.capacity);
+        open.container = Some(entity);
+        open.scope = Some(container.scope.clone());
+
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Debug, &format!("打开仓库: {}", container.name));
+        }
+    }
+}
+
+/// 点击格子时发出转移请求
+pub fn handle_stash_clicks(
+    open: Res<OpenStash>,
+    buttons: Query<(&Interaction, &StashSlotButton), Changed<Interaction>>,
+    players: Query<Entity, With<Player>>,
+    mut requests: EventWriter<StashTransferRequest>,
+) {
+    if open.container.is_none() {
+        return;
+    }
+    let Ok(player) = players.get_single() else {
+        return;
+    };
+
+    for (interaction, button) in buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            requests.send(StashTransferRequest {
+                entity: player,
+                direction: button.direction,
+                slot: button.slot,
+            });
+        }
+    }
+}
+
+/// 在背包与当前仓库之间转移整格物品，放不下的部分留在原处
+pub fn process_stash_transfers(
+    settings: Res<StashSettings>,
+    database: Res<ItemDatabase>,
+    open: Res<OpenStash>,
+    mut registry: ResMut<StashRegistry>,
+    mut requests: EventReader<StashTransferRequest>,
+    mut inventories: Query<&mut Inventory>,
+) {
+    let Some(scope) = open.scope.as_ref() else {
+        requests.clear();
+        return;
+    };
+
+    for request in requests.read() {
+        let Ok(mut inventory) = inventories.get_mut(request.entity) else {
+            continue;
+        };
+        let stash = registry.get_or_create(scope, settings.capacity);
+
+        let (source, target) = match request.direction {
+            TransferDirection::Deposit => (&mut *inventory, stash),
+            TransferDirection::Withdraw => (stash, &mut *inventory),
+        };
+
+        let Some(item) = source.take(request.slot) else {
+            continue;
+        };
+        let leftover = target.add(&database, item.clone());
+        if leftover > 0 {
+            source.slots[request.slot] = Some(ItemInstance {
+                count: leftover,
+                ..item
+            });
+        }
+    }
+}
+
+/// 仓库或背包变化时重建仓库界面
+pub fn update_stash_ui(
+    mut commands: Commands,
+    database: Res<ItemDatabase>,
+    open: Res<OpenStash>,
+    registry: Res<StashRegistry>,
+    containers: Query<&StashContainer>,
+    players: Query<Ref<Inventory>, With<Player>>,
+    ui: Query<Entity, With<StashUi>>,
+) {
+    let Ok(inventory) = players.get_single() else {
+        return;
+    };
+    if !open.is_changed() && !registry.is_changed() && !inventory.is_changed() {
+        return;
+    }
+
+    for entity in ui.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let (Some(container), Some(scope)) = (open.container, open.scope.as_ref()) else {
+        return;
+    };
+    let Some(stash) = registry.get(scope) else {
+        return;
+    };
+    let title = containers
+        .get(container)
+        .map_or("仓库", |container| container.name.as_str());
+
+    commands
+        .spawn((
+            StashUi,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(16.0),
+                ..default()
+            },
+        ))
+        .with_children(|root| {
+            spawn_inventory_column(
+                root,
+                &database,
+                "行囊",
+                &inventory,
+                TransferDirection::Deposit,
+            );
+            spawn_inventory_column(root, &database, title, stash, TransferDirection::Withdraw);
+        });
+}
+
+/// 生成一列物品格子，点击格子把物品转移到另一侧
+fn spawn_inventory_column(
+    parent: &mut ChildBuilder,
+    database: &ItemDatabase,
+    title: &str,
+    inventory: &Inventory,
+    direction: TransferDirection,
+) {
+    parent
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(12.0)),
+                row_gap: Val::Px(4.0),
+                min_width: Val::Px(220.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.08, 0.06, 0.9)),
+        ))
+        .with_children(|column| {
+            column.spawn((
+                Text::new(title),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+            ));
+
+            for (slot, item) in inventory.slots.iter().enumerate() {
+                let Some(item) = item else {
+                    continue;
+                };
+                let name = database
+                    .get(&item.item_id)
+                    .map_or(item.item_id.as_str(), |def| def.name.as_str());
+
+                column
+                    .spawn((
+                        Button,
+                        StashSlotButton { direction, slot },
+                        Node {
+                            padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0.25, 0.2, 0.15, 0.8)),
+                    ))
+                    .with_children(|button| {
+                        button.spawn((
+                            Text::new(format!("{} x{}", name, item.count)),
+                            TextFont {
+                                font_size: 14.0,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+
+use super::{
+    assign_shop_keepers, expire_loot_drops, handle_shop_clicks, handle_stash_clicks,
+    load_loot_tables, load_shop_library, load_shop_registry, load_stash_registry, pick_up_loot,
+    process_repair_requests, process_stash_transfers, process_trades, request_blacksmith_repairs,
+    restock_shops, roll_death_loot, setup_durability_indicator, spawn_loot_drops,
+    spawn_world_stashes, sync_loot_tables, toggle_shop, toggle_stash, update_durability_indicator,
+    update_encumbrance, update_encumbrance_warning, update_shop_ui, update_stash_ui,
+    use_repair_kits, wear_equipment_on_damage, wear_weapons_on_attack, DurabilitySettings,
+    EncumbranceSettings, ItemDatabase, LootDropRequest, LootPickedUp, LootRollRequest,
+    LootSettings, LootTable, LootTableLoader, LootTableRegistry, OpenShop, OpenStash,
+    RepairRequest, ShopSettings, StashRegistry, StashSettings, StashTransferRequest, TradeRequest,
+    ITEM_DATA_PATH,
+};
+use crate::config::resolve_data_path;
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::gameplay_running;
+
+/// 物品插件
+pub struct ItemsPlugin;
+
+impl Plugin for ItemsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DurabilitySettings>()
+            .init_resource::<EncumbranceSettings>()
+            .init_resource::<StashSettings>()
+            .init_resource::<StashRegistry>()
+            .init_resource::<OpenStash>()
+            .init_resource::<LootSettings>()
+            .init_resource::<ShopSettings>()
+            .init_resource::<OpenShop>()
+            .init_resource::<LootTableRegistry>()
+            .init_asset::<LootTable>()
+            .init_asset_loader::<LootTableLoader>()
+            .add_event::<RepairRequest>()
+            .add_event::<StashTransferRequest>()
+            .add_event::<LootRollRequest>()
+            .add_event::<LootDropRequest>()
+            .add_event::<LootPickedUp>()
+            .add_event::<TradeRequest>();
+
+        app.add_systems(PreStartup, load_item_database)
+            .add_systems(
+                Startup,
+                (
+                    setup_durability_indicator,
+                    load_stash_registry,
+                    spawn_world_stashes,
+                    load_loot_tables,
+                    load_shop_library,
+                    load_shop_registry,
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    wear_weapons_on_attack,
+                    wear_equipment_on_damage,
+                    request_blacksmith_repairs,
+                    use_repair_kits,
+                    process_repair_requests,
+                    update_durability_indicator,
+                    update_encumbrance,
+                    update_encumbrance_warning,
+                )
+                    .chain()
+                    .run_if(gameplay_running),
+            )
+            .add_systems(
+                Update,
+                (
+                    toggle_stash,
+                    handle_stash_clicks,
+                    process_stash_transfers,
+                    update_stash_ui,
+                )
+                    .chain()
+                    .run_if(gameplay_running),
+            )
+            .add_systems(
+                Update,
+                (
+                    sync_loot_tables,
+                    roll_death_loot,
+                    spawn_loot_drops,
+                    pick_up_loot,
+                    expire_loot_drops,
+                )
+                    .chain()
+                    .run_if(gameplay_running),
+            )
+            .add_systems(
+                Update,
+                (
+                    assign_shop_keepers,
+                    restock_shops,
+                    toggle_shop,
+                    handle_shop_clicks,
+                    process_trades,
+                    update_shop_ui,
+                )
+                    .chain()
+                    .run_if(gameplay_running),
+            );
+    }
+}
+
+/// 加载物品数据，失败时使用空数据库
+fn load_item_database(mut commands: Commands, mut logger: Option<ResMut<GameLogger>>) {
+    let database = match ItemDatabase::load(&resolve_data_path(ITEM_DATA_PATH)) {
+        Ok(database) => database,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("物品数据加载失败: {}", e));
+            }
+            ItemDatabase::default()
+        }
+    };
+    commands.insert_resource(database);
+}
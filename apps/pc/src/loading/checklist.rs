@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+
+use crate::world::chunk::ChunkCoord;
+
+/// 进入游戏前要完成的加载任务
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LoadingTask {
+    /// 后台读取主存档，新游戏时直接完成
+    Save,
+    /// 字体、预制场景、任务、对话、掉落表与预载的资源分组
+    Assets,
+    /// 出生点周围的区块生成并加载完毕
+    World,
+}
+
+impl LoadingTask {
+    /// 加载界面按这个顺序列出
+    pub const ALL: [LoadingTask; 3] = [LoadingTask::Save, LoadingTask::Assets, LoadingTask::World];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LoadingTask::Save => "读取存档",
+            LoadingTask::Assets => "预载资源",
+            LoadingTask::World => "生成世界",
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            LoadingTask::Save => 0,
+            LoadingTask::Assets => 1,
+            LoadingTask::World => 2,
+        }
+    }
+}
+
+/// 加载设置
+///
+/// - prefetch_radius: 出生点周围要生成并加载好的区块半径，不超过区块视距
+/// - min_seconds: 加载界面至少显示的时长，加载很快时不会一闪而过
+/// - tip_seconds: 每条提示显示的时长
+#[derive(Resource, Debug, Clone)]
+pub struct LoadingSettings {
+    pub prefetch_radius: i32,
+    pub min_seconds: f32,
+    pub tip_seconds: f32,
+}
+
+impl Default for LoadingSettings {
+    fn default() -> Self {
+        Self {
+            prefetch_radius: 2,
+            min_seconds: 0.8,
+            tip_seconds: 4.0,
+        }
+    }
+}
+
+/// 就绪清单
+///
+/// 每项任务的进度在 0 到 1 之间，全部完成后才进入游戏
+#[derive(Resource, Debug, Default)]
+pub struct LoadingChecklist {
+    progress: [f32; 3],
+    /// 世界预生成的中心区块，存档读完、定下出生点后才有
+    pub spawn: Option<ChunkCoord>,
+    /// 预载中发现的缺失资源数量
+    pub missing_assets: usize,
+    /// 进入加载界面后经过的真实时间
+    pub elapsed: f32,
+    /// 已经发出进入游戏的切换
+    pub finished: bool,
+}
+
+impl LoadingChecklist {
+    pub fn progress(&self, task: LoadingTask) -> f32 {
+        self.progress[task.index()]
+    }
+
+    pub fn set(&mut self, task: LoadingTask, progress: f32) {
+        self.progress[task.index()] = progress.clamp(0.0, 1.0);
+    }
+
+    pub fn is_done(&self, task: LoadingTask) -> bool {
+        self.progress(task) >= 1.0
+    }
+
+    pub fn is_complete(&self) -> bool {
+        LoadingTask::ALL.iter().all(|task| self.is_done(*task))
+    }
+
+    /// 总进度，各项任务平均
+    pub fn overall(&self) -> f32 {
+        self.progress.iter().sum::<f32>() / self.progress.len() as f32
+    }
+}
@@ -0,0 +1,15 @@
+/// 加载模块
+///
+/// 主菜单与游戏之间的加载状态：等待读档、资源与出生点周围的区块就绪后再进入游戏
+///
+/// # 模块组成
+/// 1. checklist：加载任务、就绪清单与加载设置
+/// 2. screen：显示进度、任务状态与提示的加载界面
+/// 3. systems：加载插件
+mod checklist;
+mod screen;
+mod systems;
+
+pub use checklist::*;
+pub use screen::*;
+pub use systems::LoadingPlugin;
@@ -0,0 +1,161 @@
+use bevy::prelude::*;
+
+use super::{LoadingChecklist, LoadingSettings, LoadingTask};
+use crate::ui::{label, overlay, panel, MixedText, TextRole, ThemeColor, UiTheme};
+
+/// 加载时轮流显示的提示
+const TIPS: &[&str] = &[
+    "世界由种子生成，同一种子总能回到同一片江湖",
+    "在选项菜单中可以改键，一个动作可以绑定多个按键",
+    "点击地面自动寻路，右键点击人物或资源点走过去交互",
+    "游玩中会定时自动存档，首领战前也会存一次",
+    "在世界地图上可以放置标注，并选择是否显示在罗盘上",
+    "负重过高会拖慢脚步，带不动的东西可以存进仓库",
+    "营火旁歇息可以恢复体力，也能让时间流逝",
+];
+
+/// 加载界面根节点
+#[derive(Component)]
+pub struct LoadingScreen;
+
+/// 进度条的填充部分
+#[derive(Component)]
+pub struct LoadingBarFill;
+
+/// 一项加载任务的状态文字
+#[derive(Component)]
+pub struct LoadingTaskText(pub LoadingTask);
+
+/// 提示文字
+#[derive(Component)]
+pub struct LoadingTipText;
+
+/// 当前显示的提示
+#[derive(Resource, Debug, Default)]
+pub struct LoadingTip {
+    pub index: usize,
+    pub timer: f32,
+}
+
+fn task_line(checklist: &LoadingChecklist, task: LoadingTask) -> String {
+    let progress = checklist.progress(task);
+    let line = if progress >= 1.0 {
+        format!("✓ {}", task.label())
+    } else {
+        format!("· {} {:.0}%", task.label(), progress * 100.0)
+    };
+    if task == LoadingTask::Assets && checklist.missing_assets > 0 {
+        format!("{}（缺失 {} 项，详见日志）", line, checklist.missing_assets)
+    } else {
+        line
+    }
+}
+
+/// 生成加载界面，随机挑一条提示开始
+pub fn open_loading_screen(
+    mut commands: Commands,
+    theme: Res<UiTheme>,
+    checklist: Res<LoadingChecklist>,
+    mut tip: ResMut<LoadingTip>,
+) {
+    tip.index = rand::random::<usize>() % TIPS.len();
+    tip.timer = 0.0;
+
+    commands
+        .spawn((
+            LoadingScreen,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::End,
+                padding: UiRect::all(Val::Px(48.0)),
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            overlay(),
+            GlobalZIndex(10),
+        ))
+        .with_children(|root| {
+            root.spawn(label("加载中", TextRole::Title));
+            root.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(12.0)),
+                    row_gap: Val::Px(4.0),
+                    min_width: Val::Px(240.0),
+                    ..default()
+                },
+                panel(),
+            ))
+            .with_children(|list| {
+                for task in LoadingTask::ALL {
+                    list.spawn((
+                        LoadingTaskText(task),
+                        label(task_line(&checklist, task), TextRole::Small),
+                    ));
+                }
+            });
+            root.spawn((
+                Node {
+                    width: Val::Percent(60.0),
+                    height: Val::Px(8.0),
+                    ..default()
+                },
+                BackgroundColor(theme.color(ThemeColor::Panel)),
+            ))
+            .with_children(|bar| {
+                bar.spawn((
+                    LoadingBarFill,
+                    Node {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(theme.color(ThemeColor::Accent)),
+                ));
+            });
+            root.spawn((LoadingTipText, label(TIPS[tip.index], TextRole::Muted)));
+        });
+}
+
+/// 离开加载状态时移除界面
+pub fn close_loading_screen(mut commands: Commands, roots: Query<Entity, With<LoadingScreen>>) {
+    for root in roots.iter() {
+        commands.entity(root).despawn_recursive();
+    }
+}
+
+/// 刷新进度条、任务状态与提示
+pub fn update_loading_screen(
+    real_time: Res<Time<Real>>,
+    settings: Res<LoadingSettings>,
+    checklist: Res<LoadingChecklist>,
+    mut tip: ResMut<LoadingTip>,
+    mut fills: Query<&mut Node, With<LoadingBarFill>>,
+    mut task_texts: Query<(&LoadingTaskText, &mut MixedText), Without<LoadingTipText>>,
+    mut tip_texts: Query<&mut MixedText, With<LoadingTipText>>,
+) {
+    if checklist.is_changed() {
+        for mut node in fills.iter_mut() {
+            node.width = Val::Percent(checklist.overall() * 100.0);
+        }
+        for (task, mut text) in task_texts.iter_mut() {
+            let line = task_line(&checklist, task.0);
+            if text.0 != line {
+                text.0 = line;
+            }
+        }
+    }
+
+    tip.timer += real_time.delta_secs();
+    if tip.timer >= settings.tip_seconds {
+        tip.timer = 0.0;
+        tip.index = (tip.index + 1) % TIPS.len();
+        for mut text in tip_texts.iter_mut() {
+            text.0 = TIPS[tip.index].to_string();
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use bevy::asset::{LoadState, LoadedFolder};
+use bevy::prelude::*;
+
+use super::{
+    close_loading_screen, open_loading_screen, update_loading_screen, LoadingChecklist,
+    LoadingSettings, LoadingTask, LoadingTip,
+};
+use crate::assets::AssetManager;
+use crate::items::LootTableRegistry;
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::GameState;
+use crate::save::SaveReader;
+use crate::ui::{ChangeScene, FontService, SceneTransition};
+use crate::world::chunk::{ChunkCoord, ChunkManager, ChunkPrefetch};
+use crate::world::entity::Player;
+use crate::world::map::{DialogueLibrary, MapManager, QuestLibrary, ScenePrefabRegistry};
+
+/// 加载插件
+///
+/// # 设计思路
+/// 1. 加载是主菜单与游戏之间的独立状态，进入时重置就绪清单并生成加载界面，离开时移除
+/// 2. 读档与区块生成都在后台线程进行，加载状态只轮询进度，界面照常刷新
+/// 3. 出生点取自读完的存档，所以世界预生成排在存档之后开始
+/// 4. 所缺的子系统（例如没有区块系统的构建）对应的任务直接算作完成
+/// 5. 清单全部完成、界面显示够时长后才发出进入游戏的切换，只发一次
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LoadingSettings>()
+            .init_resource::<LoadingChecklist>()
+            .init_resource::<LoadingTip>()
+            .add_systems(
+                OnEnter(GameState::Loading),
+                (begin_loading, open_loading_screen).chain(),
+            )
+            .add_systems(OnExit(GameState::Loading), close_loading_screen)
+            .add_systems(
+                Update,
+                (
+                    check_save_loaded,
+                    check_assets_loaded,
+                    prepare_spawn_area,
+                    finish_loading,
+                    update_loading_screen,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Loading)),
+            );
+    }
+}
+
+/// 重置就绪清单
+fn begin_loading(mut checklist: ResMut<LoadingChecklist>) {
+    *checklist = LoadingChecklist::default();
+}
+
+/// 主存档读完并应用后完成
+fn check_save_loaded(reader: Res<SaveReader>, mut checklist: ResMut<LoadingChecklist>) {
+    let progress = if reader.is_pending() { 0.0 } else { 1.0 };
+    if checklist.progress(LoadingTask::Save) != progress {
+        checklist.set(LoadingTask::Save, progress);
+    }
+}
+
+/// 资源目录加载完毕（失败也算完毕，由各自的系统报错）
+fn folder_ready(asset_server: &AssetServer, folder: Option<&Handle<LoadedFolder>>) -> bool {
+    folder.is_some_and(|folder| {
+        matches!(
+            asset_server.get_load_state(folder),
+            Some(LoadState::Loaded | LoadState::Failed(_))
+        )
+    })
+}
+
+/// 统计启动时开始加载的资源与加载界面预载的资源分组
+#[allow(clippy::too_many_arguments)]
+fn check_assets_loaded(
+    asset_server: Res<AssetServer>,
+    assets: Option<Res<AssetManager>>,
+    fonts: Option<Res<FontService>>,
+    prefabs: Option<Res<ScenePrefabRegistry>>,
+    quests: Option<Res<QuestLibrary>>,
+    dialogues: Option<Res<DialogueLibrary>>,
+    loot: Option<Res<LootTableRegistry>>,
+    mut checklist: ResMut<LoadingChecklist>,
+) {
+    let ready = [
+        fonts.is_none_or(|fonts| !fonts.is_loading()),
+        prefabs.is_none_or(|prefabs| prefabs.ready),
+        quests.is_none_or(|quests| folder_ready(&asset_server, quests.folder.as_ref())),
+        dialogues.is_none_or(|dialogues| folder_ready(&asset_server, dialogues.folder.as_ref())),
+        loot.is_none_or(|loot| folder_ready(&asset_server, loot.folder.as_ref())),
+    ];
+    // 资源分组按已完成的比例计入，与其余各项同等权重
+    let groups = assets
+        .as_ref()
+        .map_or(1.0, |assets| assets.state_progress(GameState::Loading));
+    let finished = ready.iter().filter(|ready| **ready).count() as f32 + groups;
+    let progress = finished / (ready.len() + 1) as f32;
+    if checklist.progress(LoadingTask::Assets) != progress {
+        checklist.set(LoadingTask::Assets, progress);
+    }
+    let missing = assets.map_or(0, |assets| assets.missing_count());
+    if checklist.missing_assets != missing {
+        checklist.missing_assets = missing;
+    }
+}
+
+/// 存档读完后以玩家位置为出生点预生成区块，再等区块加载进场景
+///
+/// 预生成与加载各占一半进度
+fn prepare_spawn_area(
+    settings: Res<LoadingSettings>,
+    mut checklist: ResMut<LoadingChecklist>,
+    chunk_manager: Option<ResMut<ChunkManager>>,
+    map_manager: Option<Res<MapManager>>,
+    prefetch: Option<ResMut<ChunkPrefetch>>,
+    players: Query<&Transform, With<Player>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    if !checklist.is_done(LoadingTask::Save) || checklist.is_done(LoadingTask::World) {
+        return;
+    }
+    let (Some(mut chunk_manager), Some(map_manager), Some(mut prefetch)) =
+        (chunk_manager, map_manager, prefetch)
+    else {
+        checklist.set(LoadingTask::World, 1.0);
+        return;
+    };
+    let radius = settings
+        .prefetch_radius
+        .clamp(0, chunk_manager.view_distance);
+
+    let spawn = match checklist.spawn {
+        Some(spawn) => spawn,
+        None => {
+            // 还没有生成玩家时从世界原点出发
+            let position = players
+                .get_single()
+                .map(|transform| transform.translation.truncate())
+                .unwrap_or(Vec2::ZERO);
+            chunk_manager.update_player_position(position.x, position.y);
+            let Some(spawn) = chunk_manager.player_chunk else {
+                return;
+            };
+            prefetch.start(spawn, radius, &chunk_manager, &map_manager);
+            checklist.spawn = Some(spawn);
+            if let Some(logger) = logger.as_mut() {
+                logger.log(
+                    LogLevel::Info,
+                    &format!(
+                        "开始预生成出生点 ({}, {}) 周围的 {} 个区块",
+                        spawn.x, spawn.y, prefetch.total
+                    ),
+                );
+            }
+            spawn
+        }
+    };
+
+    let total = ((radius * 2 + 1) * (radius * 2 + 1)) as f32;
+    let loaded = (-radius..=radius)
+        .flat_map(|dy| {
+            (-radius..=radius).map(move |dx| ChunkCoord {
+                x: spawn.x + dx,
+                y: spawn.y + dy,
+            })
+        })
+        .filter(|coord| chunk_manager.chunks.contains_key(coord))
+        .count() as f32;
+    let progress = (prefetch.progress() + loaded / total) * 0.5;
+    if checklist.progress(LoadingTask::World) != progress {
+        checklist.set(LoadingTask::World, progress);
+    }
+}
+
+/// 清单完成后切换到游戏
+fn finish_loading(
+    real_time: Res<Time<Real>>,
+    settings: Res<LoadingSettings>,
+    transition: Res<SceneTransition>,
+    mut checklist: ResMut<LoadingChecklist>,
+    mut scenes: EventWriter<ChangeScene>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    // 只在这里累计时间，不触发界面刷新
+    checklist.bypass_change_detection().elapsed += real_time.delta_secs();
+    if checklist.finished
+        || !checklist.is_complete()
+        || checklist.elapsed < settings.min_seconds
+        || transition.is_active()
+    {
+        return;
+    }
+
+    checklist.finished = true;
+    scenes.send(ChangeScene(GameState::InGame));
+    if let Some(logger) = logger.as_mut() {
+        logger.log(
+            LogLevel::Info,
+            &format!("加载完成，用时 {:.1} 秒", checklist.elapsed),
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use bevy::prelude::*;
+use chrono::Local;
+use flate2::read::GzDecoder;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use super::{is_log_file, GameLogger, LogLevel, LogScrubber};
+use crate::analytics::{PlaytestRecorder, PlaytestSettings};
+use crate::events::input::GameAction;
+use crate::resources::InputState;
+
+/// 诊断数据的收集范围，由游戏设置中的隐私选项决定
+#[derive(Resource, Debug, Clone)]
+pub struct DiagnosticsSettings {
+    /// 错误报告包的导出目录
+    pub bundle_dir: String,
+    /// 报告包中附带的日志文件数，按日期取最近的几天
+    pub max_log_files: usize,
+    /// 附带操作系统、处理器核数等系统信息
+    pub include_system_info: bool,
+    /// 附带本次会话的试玩数据
+    pub include_playtest: bool,
+}
+
+impl Default for DiagnosticsSettings {
+    fn default() -> Self {
+        Self {
+            bundle_dir: "bug_reports".to_string(),
+            max_log_files: 3,
+            include_system_info: true,
+            include_playtest: true,
+        }
+    }
+}
+
+/// 请求导出错误报告包
+#[derive(Event, Debug, Clone, Copy)]
+pub struct BugBundleRequest;
+
+/// 运行中发生的一次异常，例如显卡设备丢失
+#[derive(Debug, Clone)]
+pub struct DiagnosticIncident {
+    pub time: String,
+    /// 异常类别，例如“渲染”
+    pub category: String,
+    pub detail: String,
+}
+
+/// 本次会话发生过的异常，导出错误报告包时列在说明文件中
+#[derive(Resource, Debug, Default)]
+pub struct DiagnosticIncidents {
+    entries: Vec<DiagnosticIncident>,
+}
+
+impl DiagnosticIncidents {
+    /// 记录一次异常
+    pub fn record(&mut self, category: &str, detail: &str) {
+        self.entries.push(DiagnosticIncident {
+            time: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            category: category.to_string(),
+            detail: detail.to_string(),
+        });
+    }
+
+    /// 所有已记录的异常
+    pub fn entries(&self) -> &[DiagnosticIncident] {
+        &self.entries
+    }
+}
+
+/// 错误报告包
+///
+/// # 设计思路
+/// 1. 报告包是一个目录，包含说明文件、最近的日志与试玩数据，玩家打包后即可分享
+/// 2. 所有文字文件写入前都经过脱敏，去掉账号名、用户目录与 IP 地址
+/// 3. 隐私选项关闭的内容完全不收集，说明文件中列出实际包含的内容
+pub struct BugBundle<'a> {
+    pub settings: &'a DiagnosticsSettings,
+    pub scrubber: &'a LogScrubber,
+    /// 日志目录，文件日志关闭时为空
+    pub log_dir: Option<&'a str>,
+    /// 本次会话发生过的异常
+    pub incidents: &'a [DiagnosticIncident],
+}
+
+impl BugBundle<'_> {
+    /// 导出报告包，返回报告包目录
+    pub fn export(
+        &self,
+        recorder: Option<&PlaytestRecorder>,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let dir = Path::new(&self.settings.bundle_dir)
+            .join(format!("bug-{}", Local::now().format("%Y%m%d-%H%M%S")));
+        self.export_to(&dir, recorder)?;
+        Ok(dir)
+    }
+
+    /// 把报告包写到指定目录，崩溃报告借用这个目录一并存放
+    pub fn export_to(
+        &self,
+        dir: &Path,
+        recorder: Option<&PlaytestRecorder>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let created_at = Local::now();
+        fs::create_dir_all(dir)?;
+
+        let mut contents = Vec::new();
+
+        if let Some(log_dir) = self.log_dir {
+            let logs = recent_logs(log_dir, self.settings.max_log_files)?;
+            if !logs.is_empty() {
+                let logs_dir = dir.join("logs");
+                fs::create_dir_all(&logs_dir)?;
+                for log in &logs {
+                    let Some(name) = log.file_name().and_then(|name| name.to_str()) else {
+                        continue;
+                    };
+                    // 压缩过的日志解压后再脱敏
+                    let name = name.strip_suffix(".gz").unwrap_or(name);
+                    self.copy_scrubbed(log, &logs_dir.join(name))?;
+                }
+                contents.push(format!("日志：最近 {} 个文件", logs.len()));
+            }
+        }
+
+        if let Some(recorder) = recorder.filter(|_| self.settings.include_playtest) {
+            let playtest_dir = dir.join("playtest");
+            let json_path = recorder.write_to(&playtest_dir.to_string_lossy())?;
+            for path in [json_path.clone(), json_path.with_extension("csv")] {
+                if path.exists() {
+                    self.copy_scrubbed(&path, &path)?;
+                }
+            }
+            contents.push(format!("试玩数据：会话 {}", recorder.session));
+        }
+
+        let mut report = format!(
+            "错误报告包\n生成时间：{}\n游戏版本：{}\n",
+            created_at.format("%Y-%m-%d %H:%M:%S"),
+            env!("CARGO_PKG_VERSION"),
+        );
+        if self.settings.include_system_info {
+            let cores = std::thread::available_parallelism()
+                .map(|cores| cores.get().to_string())
+                .unwrap_or_else(|_| "未知".to_string());
+            report.push_str(&format!(
+                "操作系统：{} {}\n处理器核数：{}\n",
+                std::env::consts::OS,
+                std::env::consts::ARCH,
+                cores
+            ));
+            contents.push("系统信息".to_string());
+        }
+        if !self.incidents.is_empty() {
+            report.push_str(&format!(
+                "\n本次会话的异常（{} 次）：\n",
+                self.incidents.len()
+            ));
+            for incident in self.incidents {
+                report.push_str(&format!(
+                    "- [{}] {}：{}\n",
+                    incident.time, incident.category, incident.detail
+                ));
+            }
+        }
+        report.push_str("\n包含内容：\n");
+        if contents.is_empty() {
+            report.push_str("- 无（隐私设置关闭了所有诊断数据）\n");
+        }
+        for item in &contents {
+            report.push_str(&format!("- {}\n", item));
+        }
+        report.push_str("\n账号名、用户目录与 IP 地址已替换为占位符\n");
+        fs::write(dir.join("report.txt"), self.scrubber.scrub(&report))?;
+
+        Ok(())
+    }
+
+    /// 读取文字文件，脱敏后写到目标位置，`.gz` 文件先解压
+    fn copy_scrubbed(&self, from: &Path, to: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let mut bytes = Vec::new();
+        if from.extension().is_some_and(|ext| ext == "gz") {
+            GzDecoder::new(fs::File::open(from)?).read_to_end(&mut bytes)?;
+        } else {
+            bytes = fs::read(from)?;
+        }
+        let text = String::from_utf8_lossy(&bytes);
+        fs::write(to, self.scrubber.scrub(&text))?;
+        Ok(())
+    }
+}
+
+/// 日志目录中最近的日志文件，日志按日期与分段序号命名，文件名排序即时间顺序
+fn recent_logs(log_dir: &str, count: usize) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    if !Path::new(log_dir).exists() {
+        return Ok(Vec::new());
+    }
+    let mut logs: Vec<PathBuf> = fs::read_dir(log_dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| is_log_file(path))
+        .collect();
+    logs.sort();
+    let skip = logs.len().saturating_sub(count);
+    Ok(logs.split_off(skip))
+}
+
+/// 按下导出键时请求导出错误报告包
+pub fn request_bug_bundle(
+    input_state: Res<InputState>,
+    mut requests: EventWriter<BugBundleRequest>,
+) {
+    if input_state.is_action_just_pressed(GameAction::ExportBugReport) {
+        requests.send(BugBundleRequest);
+    }
+}
+
+/// 导出错误报告包
+pub fn export_bug_bundle(
+    mut requests: EventReader<BugBundleRequest>,
+    settings: Res<DiagnosticsSettings>,
+    scrubber: Res<LogScrubber>,
+    playtest: Option<Res<PlaytestSettings>>,
+    recorder: Option<Res<PlaytestRecorder>>,
+    incidents: Res<DiagnosticIncidents>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    // 同一帧的多次请求只导出一次
+    if requests.read().count() == 0 {
+        return;
+    }
+
+    let log_dir = logger
+        .as_ref()
+        .and_then(|logger| logger.log_dir().map(str::to_string));
+    let bundle = BugBundle {
+        settings: &settings,
+        scrubber: &scrubber,
+        log_dir: log_dir.as_deref(),
+        incidents: incidents.entries(),
+    };
+    // 试玩记录未开启时记录器里没有数据
+    let playtest_enabled = playtest.is_some_and(|playtest| playtest.enabled);
+    let recorder = recorder.filter(|_| playtest_enabled);
+
+    let result = bundle.export(recorder.as_deref());
+    if let Some(logger) = logger.as_mut() {
+        match result {
+            Ok(dir) => logger.log(
+                LogLevel::Info,
+                &format!("错误报告包已导出到 {}", dir.display()),
+            ),
+            Err(e) => logger.log(LogLevel::Error, &format!("导出错误报告包失败: {}", e)),
+        }
+    }
+}
@@ -1,7 +1,13 @@
+use bevy::log::Level;
+use std::fmt;
+
+use super::{LogQueue, LogRotation};
+
 /// 日志级别
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LogLevel {
     Error,   // 错误信息
+    Warn,    // 警告信息，主要来自引擎与第三方库
     Info,    // 重要信息
     Debug,   // 调试信息
     Verbose, // 详细信息
@@ -11,19 +17,104 @@ impl LogLevel {
     pub fn as_str(&self) -> &'static str {
         match self {
             LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
             LogLevel::Info => "INFO",
             LogLevel::Debug => "DEBUG",
             LogLevel::Verbose => "VERBOSE",
         }
     }
 
+    /// 解析级别名，不认识时返回 None
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "error" => Some(LogLevel::Error),
+            "warn" => Some(LogLevel::Warn),
+            "info" => Some(LogLevel::Info),
+            "debug" => Some(LogLevel::Debug),
+            "verbose" | "trace" => Some(LogLevel::Verbose),
+            _ => None,
+        }
+    }
+
     pub fn from_str(s: &str) -> Self {
-        match s.to_lowercase().as_str() {
-            "error" => LogLevel::Error,
-            "info" => LogLevel::Info,
-            "debug" => LogLevel::Debug,
-            "verbose" => LogLevel::Verbose,
-            _ => LogLevel::Info,
+        Self::parse(s).unwrap_or(LogLevel::Info)
+    }
+
+    /// 对应的 tracing 级别，详细信息即 TRACE
+    pub fn from_level(level: &Level) -> Self {
+        match *level {
+            Level::ERROR => LogLevel::Error,
+            Level::WARN => LogLevel::Warn,
+            Level::INFO => LogLevel::Info,
+            Level::DEBUG => LogLevel::Debug,
+            _ => LogLevel::Verbose,
+        }
+    }
+
+    /// 对应的 log 级别
+    pub fn to_log(self) -> log::Level {
+        match self {
+            LogLevel::Error => log::Level::Error,
+            LogLevel::Warn => log::Level::Warn,
+            LogLevel::Info => log::Level::Info,
+            LogLevel::Debug => log::Level::Debug,
+            LogLevel::Verbose => log::Level::Trace,
+        }
+    }
+
+    /// 过滤规则中使用的级别名
+    fn filter_name(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Verbose => "trace",
+        }
+    }
+}
+
+/// 一条日志过滤规则
+///
+/// 写作 `目标=级别`，目标是模块路径，例如 `mmorpg_game::world::chunk=debug`，
+/// 对该模块及其子模块生效；只写级别时作为默认级别
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogDirective {
+    pub target: Option<String>,
+    pub level: LogLevel,
+}
+
+impl LogDirective {
+    /// 解析一条规则，格式不对时返回原因
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        let (target, level) = match s.split_once('=') {
+            Some((target, level)) => (Some(target.trim()), level),
+            None => (None, s),
+        };
+        let level =
+            LogLevel::parse(level).ok_or_else(|| format!("无法识别的日志级别：{}", level))?;
+        if let Some(target) = target {
+            let valid = !target.is_empty()
+                && target
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | ':' | '-'));
+            if !valid {
+                return Err(format!("无法识别的日志目标：{}", target));
+            }
+        }
+        Ok(Self {
+            target: target.map(str::to_string),
+            level,
+        })
+    }
+}
+
+impl fmt::Display for LogDirective {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.target {
+            Some(target) => write!(f, "{}={}", target, self.level.filter_name()),
+            None => write!(f, "{}", self.level.filter_name()),
         }
     }
 }
@@ -31,11 +122,12 @@ impl LogLevel {
 /// 日志配置
 #[derive(Clone)]
 pub struct LogConfig {
-    pub file_output: bool,           // 是否输出到文件
-    pub console_output: bool,        // 是否输出到控制台
-    pub min_file_level: LogLevel,    // 文件记录的最低级别
-    pub min_console_level: LogLevel, // 控制台输出的最低级别
-    pub log_dir: String,             // 日志文件目录
+    pub file_output: bool,     // 是否输出到文件
+    pub console_output: bool,  // 是否输出到控制台
+    pub level: LogLevel,       // 没有过滤规则的模块记录的最低级别
+    pub log_dir: String,       // 日志文件目录
+    pub rotation: LogRotation, // 日志文件的轮换规则
+    pub queue: LogQueue,       // 后台写入队列
 }
 
 impl Default for LogConfig {
@@ -43,9 +135,10 @@ impl Default for LogConfig {
         Self {
             file_output: true,
             console_output: true,
-            min_file_level: LogLevel::Info,
-            min_console_level: LogLevel::Info, // 默认控制台也是 Info 级别
+            level: LogLevel::Info,
             log_dir: "logs".to_string(),
+            rotation: LogRotation::default(),
+            queue: LogQueue::default(),
         }
     }
 }
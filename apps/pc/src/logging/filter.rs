@@ -0,0 +1,99 @@
+use bevy::log::tracing_subscriber::layer::Layered;
+use bevy::log::tracing_subscriber::{reload, EnvFilter, Registry};
+use bevy::log::BoxedLayer;
+
+use super::{LogDirective, LogLevel};
+
+/// 引擎依赖的库输出很多，默认只看警告以上
+const DEFAULT_DIRECTIVES: [&str; 2] = ["wgpu=error", "naga=warn"];
+
+/// 过滤层所在的订阅者，自定义层装在过滤层之下
+pub(super) type FilterHandle = reload::Handle<EnvFilter, Layered<Option<BoxedLayer>, Registry>>;
+
+/// 运行时可调整的日志过滤
+///
+/// 规则依次为：默认级别、第三方库的默认规则、配置文件中的规则、终端临时设置的规则、
+/// 环境变量 `RUST_LOG`。同一目标的规则后面的生效
+#[derive(Clone)]
+pub struct LogFilter {
+    level: LogLevel,
+    config: Vec<LogDirective>,
+    overrides: Vec<LogDirective>,
+    handle: Option<FilterHandle>,
+}
+
+impl LogFilter {
+    pub fn new(level: LogLevel) -> Self {
+        Self {
+            level,
+            config: Vec::new(),
+            overrides: Vec::new(),
+            handle: None,
+        }
+    }
+
+    /// 按当前规则生成过滤层
+    pub(super) fn env_filter(&self) -> EnvFilter {
+        EnvFilter::new(self.directives())
+    }
+
+    /// 接上已安装的过滤层，之后的修改立即生效
+    pub(super) fn attach(&mut self, handle: FilterHandle) {
+        self.handle = Some(handle);
+    }
+
+    pub fn set_level(&mut self, level: LogLevel) {
+        self.level = level;
+        self.apply();
+    }
+
+    /// 替换配置文件中的规则
+    pub fn set_config(&mut self, directives: Vec<LogDirective>) {
+        self.config = directives;
+        self.apply();
+    }
+
+    /// 临时设置一条规则，覆盖同一目标之前设置的规则
+    pub fn set_override(&mut self, directive: LogDirective) {
+        self.overrides
+            .retain(|existing| existing.target != directive.target);
+        self.overrides.push(directive);
+        self.apply();
+    }
+
+    /// 清除临时设置的规则
+    pub fn clear_overrides(&mut self) {
+        self.overrides.clear();
+        self.apply();
+    }
+
+    /// 当前生效的全部规则，逗号分隔
+    pub fn directives(&self) -> String {
+        let mut directives = vec![LogDirective {
+            target: None,
+            level: self.level,
+        }
+        .to_string()];
+        directives.extend(
+            DEFAULT_DIRECTIVES
+                .iter()
+                .map(|directive| directive.to_string()),
+        );
+        directives.extend(self.config.iter().map(LogDirective::to_string));
+        directives.extend(self.overrides.iter().map(LogDirective::to_string));
+        if let Ok(env) = std::env::var("RUST_LOG") {
+            if !env.trim().is_empty() {
+                directives.push(env);
+            }
+        }
+        directives.join(",")
+    }
+
+    fn apply(&self) {
+        if let Some(handle) = &self.handle {
+            if let Err(e) = handle.reload(self.env_filter()) {
+                eprintln!("更新日志过滤规则失败: {}", e);
+            }
+        }
+    }
+}
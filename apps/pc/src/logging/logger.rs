@@ -1,76 +1,154 @@
-use super::config::{LogConfig, LogLevel};
+use bevy::log::tracing_subscriber::layer::SubscriberExt;
+use bevy::log::tracing_subscriber::{reload, Registry};
+use bevy::log::BoxedLayer;
 use bevy::prelude::*;
-use chrono::Local;
-use colored::*;
-use std::fs::{self, File, OpenOptions};
-use std::io::Write;
-use std::path::Path;
+use bevy::utils::tracing;
+use std::panic::Location;
+use tracing_log::LogTracer;
+
+use super::config::{LogConfig, LogDirective, LogLevel};
+use super::{GameLogLayer, LogFilter, LogRotation, LogSinks};
+
+/// 调用处源文件对应的模块路径，与 `module_path!()` 的写法一致
+fn module_target(file: &str) -> String {
+    let file = file.replace('\\', "/");
+    let relative = match file.find("/src/") {
+        Some(index) => &file[index + "/src/".len()..],
+        None => file.strip_prefix("src/").unwrap_or(&file),
+    };
+    let module = relative.trim_end_matches(".rs");
+    let module = module.strip_suffix("/mod").unwrap_or(module);
+
+    let crate_name = env!("CARGO_CRATE_NAME");
+    if module == "main" {
+        crate_name.to_string()
+    } else {
+        format!("{}::{}", crate_name, module.replace('/', "::"))
+    }
+}
 
 /// 日志记录器资源
+///
+/// # 设计思路
+/// 1. 日志统一走 tracing：游戏、引擎与第三方库的日志经同一个过滤层后写到控制台与文件
+/// 2. 游戏代码可以直接用 `info!` 等宏附带结构化字段；`log` 方法保留给现有代码，按调用处的源文件取模块路径作为目标
+/// 3. 过滤规则按模块设置，配置文件与终端命令都能在运行时修改
 #[derive(Resource)]
 pub struct GameLogger {
-    log_file: Option<File>,
     config: LogConfig,
+    sinks: LogSinks,
+    filter: LogFilter,
 }
 
 impl GameLogger {
-    pub fn new(config: LogConfig) -> Self {
-        let log_file = if config.file_output {
-            Some(Self::create_log_file(&config.log_dir))
-        } else {
-            None
-        };
-
-        Self { log_file, config }
+    /// 安装全局日志订阅者，`custom_layer` 装在过滤层之下，与引擎日志插件的同名参数一致
+    ///
+    /// 同一进程只能安装一次，已有订阅者时沿用原有输出，运行时调整不再生效
+    pub fn install(config: LogConfig, custom_layer: Option<BoxedLayer>) -> Self {
+        let sinks = LogSinks::new(&config);
+        let mut filter = LogFilter::new(config.level);
+
+        let (filter_layer, handle) = reload::Layer::new(filter.env_filter());
+        let subscriber = Registry::default()
+            .with(custom_layer)
+            .with(filter_layer)
+            .with(GameLogLayer::new(sinks.clone()));
+        match tracing::subscriber::set_global_default(subscriber) {
+            Ok(()) => {
+                filter.attach(handle);
+                // 第三方库经 log 输出的日志转入 tracing
+                if let Err(e) = LogTracer::init() {
+                    eprintln!("无法转接 log 日志: {}", e);
+                }
+            }
+            Err(e) => eprintln!("日志订阅者已存在，沿用原有输出: {}", e),
+        }
+
+        Self {
+            config,
+            sinks,
+            filter,
+        }
     }
 
-    fn create_log_file(log_dir: &str) -> File {
-        fs::create_dir_all(log_dir).expect("Failed to create log directory");
-        let date = Local::now().format("%Y-%m-%d");
-        let log_path = Path::new(log_dir).join(format!("{}.log", date));
+    /// 开关文件日志，隐私设置关闭日志收集时调用
+    pub fn set_file_output(&mut self, enabled: bool) {
+        self.config.file_output = enabled;
+        self.sinks.set_file(enabled);
+    }
 
-        OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(log_path)
-            .expect("Failed to open log file")
+    /// 开关控制台输出
+    pub fn set_console_output(&mut self, enabled: bool) {
+        self.config.console_output = enabled;
+        self.sinks.set_console(enabled);
     }
 
-    pub fn log(&mut self, level: LogLevel, message: &str) {
-        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
-
-        // 控制台输出
-        if self.config.console_output && level <= self.config.min_console_level {
-            let colored_level = match level {
-                LogLevel::Error => level.as_str().red().bold(),
-                LogLevel::Info => level.as_str().green(),
-                LogLevel::Debug => level.as_str().yellow(),
-                LogLevel::Verbose => level.as_str().blue(),
-            };
-
-            let colored_message = match level {
-                LogLevel::Error => message.red(),
-                LogLevel::Info => message.white(),
-                LogLevel::Debug => message.yellow(),
-                LogLevel::Verbose => message.blue(),
-            };
-
-            println!(
-                "[{}] [{}] {}",
-                timestamp.to_string().white(),
-                colored_level,
-                colored_message
-            );
-        }
+    /// 设置没有过滤规则的模块记录的最低级别
+    pub fn set_min_level(&mut self, level: LogLevel) {
+        self.config.level = level;
+        self.filter.set_level(level);
+    }
 
-        // 文件输出
-        if self.config.file_output && level <= self.config.min_file_level {
-            if let Some(file) = &mut self.log_file {
-                let entry = format!("[{}] [{}] {}\n", timestamp, level.as_str(), message);
-                file.write_all(entry.as_bytes())
-                    .expect("Failed to write to log file");
-                file.flush().expect("Failed to flush log file");
-            }
+    /// 修改日志文件的大小、保留天数、保留个数与压缩设置
+    pub fn set_rotation(&mut self, rotation: LogRotation) {
+        if self.config.rotation == rotation {
+            return;
         }
+        self.config.rotation = rotation;
+        self.sinks.set_rotation(rotation);
+    }
+
+    /// 替换配置文件中的过滤规则
+    pub fn set_filters(&mut self, directives: Vec<LogDirective>) {
+        self.filter.set_config(directives);
+    }
+
+    /// 临时设置一条过滤规则，例如终端命令
+    pub fn set_filter_override(&mut self, directive: LogDirective) {
+        self.filter.set_override(directive);
+    }
+
+    /// 清除临时设置的过滤规则
+    pub fn clear_filter_overrides(&mut self) {
+        self.filter.clear_overrides();
+    }
+
+    /// 当前生效的过滤规则
+    pub fn filter_directives(&self) -> String {
+        self.filter.directives()
+    }
+
+    /// 是否正在写文件日志
+    pub fn file_output(&self) -> bool {
+        self.config.file_output
+    }
+
+    /// 日志文件所在目录，未写文件日志时为空
+    pub fn log_dir(&self) -> Option<&str> {
+        self.config
+            .file_output
+            .then_some(self.config.log_dir.as_str())
+    }
+
+    /// 日志输出，供崩溃处理在游戏循环之外写出日志
+    pub fn sinks(&self) -> LogSinks {
+        self.sinks.clone()
+    }
+
+    /// 以调用处所在模块为目标记录一条日志
+    #[track_caller]
+    pub fn log(&self, level: LogLevel, message: &str) {
+        let location = Location::caller();
+        let target = module_target(location.file());
+        log::logger().log(
+            &log::Record::builder()
+                .level(level.to_log())
+                .target(&target)
+                .module_path(Some(&target))
+                .file(Some(location.file()))
+                .line(Some(location.line()))
+                .args(format_args!("{}", message))
+                .build(),
+        );
     }
 }
@@ -1,5 +1,17 @@
+mod bundle;
 mod config;
+mod filter;
 mod logger;
+mod rotation;
+mod scrub;
+mod sink;
+mod writer;
 
+pub use bundle::*;
 pub use config::*;
+pub use filter::*;
 pub use logger::*;
+pub use rotation::*;
+pub use scrub::*;
+pub use sink::*;
+pub use writer::*;
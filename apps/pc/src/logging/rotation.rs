@@ -0,0 +1,232 @@
+use chrono::{Local, NaiveDate};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::cmp::Reverse;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// 日志文件的扩展名
+const LOG_EXTENSION: &str = "log";
+/// 压缩后追加的扩展名
+const COMPRESSED_EXTENSION: &str = "gz";
+
+static TIDY_LOCK: Mutex<()> = Mutex::new(());
+
+/// 日志轮换规则，各项为 0 时不限制
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogRotation {
+    /// 单个日志文件的大小上限（字节），超过后另起一个文件
+    pub max_file_size: u64,
+    /// 日志保留的天数，按文件修改时间计算
+    pub max_age_days: u64,
+    /// 保留的旧日志文件数，不含正在写的文件
+    pub max_files: usize,
+    /// 压缩不再写入的日志
+    pub compress: bool,
+}
+
+impl Default for LogRotation {
+    fn default() -> Self {
+        Self {
+            max_file_size: 10 * 1024 * 1024,
+            max_age_days: 14,
+            max_files: 20,
+            compress: true,
+        }
+    }
+}
+
+/// 按日期与大小轮换的日志文件
+///
+/// # 设计思路
+/// 1. 正在写的文件按日期命名，跨天时换成新日期的文件
+/// 2. 超过大小上限时把当前文件改名为 `日期.序号.log`，再重新打开当天的文件，文件名排序即时间顺序
+/// 3. 写入先进缓冲，由调用方在一批日志写完后刷新
+/// 4. 换下来的文件在后台线程中压缩，再按天数与文件数清理旧日志，不阻塞游戏线程
+pub struct RotatingLogFile {
+    dir: PathBuf,
+    rotation: LogRotation,
+    date: NaiveDate,
+    /// 轮换时先关闭再改名，部分系统不允许改名打开着的文件
+    file: Option<BufWriter<File>>,
+    size: u64,
+}
+
+impl RotatingLogFile {
+    /// 打开当天的日志文件，顺带整理之前留下的旧日志
+    pub fn open(dir: &str, rotation: LogRotation) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let date = Local::now().date_naive();
+        let (file, size) = open_append(&current_path(Path::new(dir), date))?;
+        let mut log = Self {
+            dir: PathBuf::from(dir),
+            rotation,
+            date,
+            file: Some(BufWriter::new(file)),
+            size,
+        };
+        if log.over_size(0) {
+            log.rotate(date)?;
+        } else {
+            log.tidy();
+        }
+        Ok(log)
+    }
+
+    /// 修改轮换规则，当前文件已超过新的上限时立即轮换
+    pub fn set_rotation(&mut self, rotation: LogRotation) -> io::Result<()> {
+        self.rotation = rotation;
+        if self.over_size(0) {
+            self.rotate(self.date)?;
+        }
+        Ok(())
+    }
+
+    /// 写入一条日志，写入前检查是否需要轮换；写入的内容在刷新后才落盘
+    pub fn write_entry(&mut self, entry: &[u8]) -> io::Result<()> {
+        let today = Local::now().date_naive();
+        if today != self.date || (self.size > 0 && self.over_size(entry.len() as u64)) {
+            self.rotate(today)?;
+        }
+        let Some(file) = self.file.as_mut() else {
+            return Ok(());
+        };
+        file.write_all(entry)?;
+        self.size += entry.len() as u64;
+        Ok(())
+    }
+
+    /// 把缓冲中的日志写入文件
+    pub fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn over_size(&self, extra: u64) -> bool {
+        self.rotation.max_file_size > 0 && self.size + extra > self.rotation.max_file_size
+    }
+
+    /// 换到 `date` 的日志文件；同一天内先把当前文件改名让位
+    fn rotate(&mut self, date: NaiveDate) -> io::Result<()> {
+        let current = current_path(&self.dir, self.date);
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+        if date == self.date {
+            fs::rename(&current, next_part_path(&self.dir, self.date))?;
+        }
+        let (file, size) = open_append(&current_path(&self.dir, date))?;
+        self.file = Some(BufWriter::new(file));
+        self.size = size;
+        self.date = date;
+        self.tidy();
+        Ok(())
+    }
+
+    /// 在后台压缩换下来的文件并清理旧日志
+    fn tidy(&self) {
+        let dir = self.dir.clone();
+        let current = current_path(&self.dir, self.date);
+        let rotation = self.rotation;
+        std::thread::spawn(move || {
+            // 连续轮换时排队整理，避免同时压缩同一个文件
+            let _guard = TIDY_LOCK.lock();
+            if let Err(e) = tidy_logs(&dir, &current, rotation) {
+                eprintln!("整理旧日志失败: {}", e);
+            }
+        });
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<(File, u64)> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let size = file.metadata()?.len();
+    Ok((file, size))
+}
+
+/// 正在写的日志文件
+fn current_path(dir: &Path, date: NaiveDate) -> PathBuf {
+    dir.join(format!("{}.{}", date.format("%Y-%m-%d"), LOG_EXTENSION))
+}
+
+/// 当天下一个未被占用的分段文件名，压缩过的分段也算占用
+fn next_part_path(dir: &Path, date: NaiveDate) -> PathBuf {
+    let date = date.format("%Y-%m-%d");
+    (1..)
+        .map(|part| dir.join(format!("{}.{:03}.{}", date, part, LOG_EXTENSION)))
+        .find(|path| !path.exists() && !compressed_path(path).exists())
+        .expect("日志分段序号用尽")
+}
+
+fn compressed_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(COMPRESSED_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// 日志目录中的日志文件，包括压缩过的
+pub fn is_log_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    name.ends_with(&format!(".{}", LOG_EXTENSION))
+        || name.ends_with(&format!(".{}.{}", LOG_EXTENSION, COMPRESSED_EXTENSION))
+}
+
+/// 把一个日志文件压缩为 `.gz`，成功后删除原文件
+///
+/// 压缩文件沿用原文件的修改时间，清理时按原来的时间计算
+fn compress_file(path: &Path) -> io::Result<()> {
+    let modified = fs::metadata(path)?.modified()?;
+    let target = compressed_path(path);
+    let mut encoder = GzEncoder::new(File::create(&target)?, Compression::default());
+    io::copy(&mut File::open(path)?, &mut encoder)?;
+    encoder.finish()?.set_modified(modified)?;
+    fs::remove_file(path)
+}
+
+/// 压缩正在写的文件以外的日志，再按天数与文件数删除旧日志
+fn tidy_logs(dir: &Path, current: &Path, rotation: LogRotation) -> io::Result<()> {
+    let old_logs = || -> io::Result<Vec<PathBuf>> {
+        Ok(fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.as_path() != current && is_log_file(path))
+            .collect())
+    };
+
+    if rotation.compress {
+        for path in old_logs()? {
+            if path.extension().is_some_and(|ext| ext == LOG_EXTENSION) {
+                compress_file(&path)?;
+            }
+        }
+    }
+
+    let mut logs: Vec<(PathBuf, SystemTime)> = old_logs()?
+        .into_iter()
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect();
+    // 新的在前
+    logs.sort_by_key(|log| Reverse(log.1));
+
+    let max_age = Duration::from_secs(rotation.max_age_days * 24 * 60 * 60);
+    let now = SystemTime::now();
+    for (index, (path, modified)) in logs.iter().enumerate() {
+        let too_many = rotation.max_files > 0 && index >= rotation.max_files;
+        let too_old = rotation.max_age_days > 0
+            && now.duration_since(*modified).is_ok_and(|age| age > max_age);
+        if too_many || too_old {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
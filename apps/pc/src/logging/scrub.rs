@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+
+/// 账号名的替换文字
+const ACCOUNT_MASK: &str = "<账号>";
+/// 用户目录的替换文字
+const HOME_MASK: &str = "<用户目录>";
+/// IP 地址的替换文字
+const IP_MASK: &str = "<IP>";
+
+/// 日志脱敏器
+///
+/// # 设计思路
+/// 1. 只在导出错误报告包时使用，本地日志保持原样，方便开发者自己排查
+/// 2. 登录时输入的账号名由登录流程登记，导出时逐字替换
+/// 3. 用户目录、IPv4 与 IPv6 地址按字符扫描识别，不依赖正则库
+#[derive(Resource, Debug, Clone)]
+pub struct LogScrubber {
+    /// 需要替换的账号名等敏感字符串
+    secrets: Vec<String>,
+    /// 本机用户目录，按环境变量取得
+    home_dirs: Vec<String>,
+}
+
+impl Default for LogScrubber {
+    fn default() -> Self {
+        let home_dirs = ["HOME", "USERPROFILE"]
+            .iter()
+            .filter_map(|key| std::env::var(key).ok())
+            .map(|dir| dir.trim_end_matches(['/', '\\']).to_string())
+            // 根目录之类过短的路径替换后反而破坏日志
+            .filter(|dir| dir.len() > 3)
+            .collect();
+        Self {
+            secrets: Vec::new(),
+            home_dirs,
+        }
+    }
+}
+
+impl LogScrubber {
+    /// 登记需要替换的敏感字符串，例如登录时输入的账号名
+    pub fn register_secret(&mut self, secret: &str) {
+        let secret = secret.trim();
+        // 单个字符的账号名会误伤大量正常文字
+        if secret.chars().count() < 2 || self.secrets.iter().any(|known| known == secret) {
+            return;
+        }
+        self.secrets.push(secret.to_string());
+        // 长的先替换，避免短账号名截断长账号名
+        self.secrets
+            .sort_by_key(|known| std::cmp::Reverse(known.len()));
+    }
+
+    /// 对一段文字脱敏
+    pub fn scrub(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for dir in &self.home_dirs {
+            text = text.replace(dir.as_str(), HOME_MASK);
+        }
+        text = scrub_user_paths(&text);
+        for secret in &self.secrets {
+            text = text.replace(secret.as_str(), ACCOUNT_MASK);
+        }
+        scrub_addresses(&text)
+    }
+}
+
+/// 替换其他机器上常见的用户目录写法，例如崩溃堆栈中的编译路径
+fn scrub_user_paths(text: &str) -> String {
+    const PREFIXES: [&str; 4] = ["/home/", "/Users/", "C:\\Users\\", "C:/Users/"];
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    'outer: while !rest.is_empty() {
+        for prefix in PREFIXES {
+            if rest.starts_with(prefix) {
+                let after = &rest[prefix.len()..];
+                let name_len = after
+                    .find(|c: char| matches!(c, '/' | '\\') || c.is_whitespace() || c == '"')
+                    .unwrap_or(after.len());
+                if name_len > 0 {
+                    result.push_str(HOME_MASK);
+                    rest = &after[name_len..];
+                    continue 'outer;
+                }
+            }
+        }
+        let c = rest.chars().next().unwrap_or_default();
+        result.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    result
+}
+
+/// 替换 IPv4 与 IPv6 地址
+///
+/// 按数字、十六进制字母、点与冒号切出候选片段后逐个判断，
+/// 日志时间戳（14:03:22.123）与版本号（0.15.1）不会被当成地址
+fn scrub_addresses(text: &str) -> String {
+    let is_candidate = |c: char| c.is_ascii_hexdigit() || c == '.' || c == ':';
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(is_candidate) {
+        result.push_str(&rest[..start]);
+        let token = &rest[start..];
+        let len = token.find(|c| !is_candidate(c)).unwrap_or(token.len());
+        let (token, after) = token.split_at(len);
+        // 片段两端的标点属于句子，不属于地址
+        let trimmed = token.trim_end_matches(['.', ':']);
+        let trailing = &token[trimmed.len()..];
+        // 片段前后紧挨着字母时是普通单词的一部分
+        let inside_word = result.chars().last().is_some_and(|c| c.is_alphanumeric())
+            || after.chars().next().is_some_and(|c| c.is_alphanumeric());
+
+        if !inside_word && (is_ipv4(trimmed) || is_ipv6(trimmed) || is_ipv4_with_port(trimmed)) {
+            result.push_str(IP_MASK);
+            result.push_str(trailing);
+        } else {
+            result.push_str(token);
+        }
+        rest = after;
+    }
+    result.push_str(rest);
+    result
+}
+
+fn is_ipv4(token: &str) -> bool {
+    let parts: Vec<&str> = token.split('.').collect();
+    parts.len() == 4
+        && parts.iter().all(|part| {
+            !part.is_empty()
+                && part.len() <= 3
+                && part.chars().all(|c| c.is_ascii_digit())
+                && part.parse::<u16>().is_ok_and(|value| value <= 255)
+        })
+}
+
+fn is_ipv4_with_port(token: &str) -> bool {
+    token.split_once(':').is_some_and(|(address, port)| {
+        is_ipv4(address) && !port.is_empty() && port.chars().all(|c| c.is_ascii_digit())
+    })
+}
+
+/// IPv6 地址需要至少两个冒号，并且含有 `::` 或十六进制字母，以排除时间
+fn is_ipv6(token: &str) -> bool {
+    let colons = token.matches(':').count();
+    if !(2..=7).contains(&colons) {
+        return false;
+    }
+    let compressed = token.contains("::");
+    let has_letter = token.chars().any(|c| c.is_ascii_alphabetic());
+    if !compressed && !has_letter {
+        return false;
+    }
+    // 末段可以是内嵌的 IPv4 地址
+    let groups = match token.rsplit_once(':') {
+        Some((head, tail)) if tail.contains('.') => {
+            if !is_ipv4(tail) {
+                return false;
+            }
+            head
+        }
+        _ => token,
+    };
+    groups
+        .split(':')
+        .all(|group| group.len() <= 4 && group.chars().all(|c| c.is_ascii_hexdigit()))
+}
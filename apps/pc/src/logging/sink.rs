@@ -0,0 +1,161 @@
+use bevy::log::tracing_subscriber::layer::{Context, Layer};
+use bevy::utils::tracing::field::{Field, Visit};
+use bevy::utils::tracing::{Event, Subscriber};
+use chrono::Local;
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing_log::NormalizeEvent;
+
+use super::config::{LogConfig, LogLevel};
+use super::writer::{LogEntry, LogWriter, WriterCommand};
+use super::{LogOverflow, LogRotation};
+
+/// 日志输出，由日志层写入，日志资源开关
+///
+/// 实际输出在后台写入线程完成，这里只负责把日志放进队列
+#[derive(Clone)]
+pub struct LogSinks {
+    sender: SyncSender<WriterCommand>,
+    overflow: LogOverflow,
+    dropped: Arc<AtomicU64>,
+    tail: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl LogSinks {
+    pub fn new(config: &LogConfig) -> Self {
+        let dropped = Arc::new(AtomicU64::new(0));
+        let tail = Arc::new(Mutex::new(VecDeque::new()));
+        let writer = LogWriter::new(config, dropped.clone(), tail.clone());
+        let (sender, receiver) = mpsc::sync_channel(config.queue.capacity.max(1));
+        std::thread::Builder::new()
+            .name("log-writer".to_string())
+            .spawn(move || writer.run(receiver))
+            .expect("Failed to spawn log writer thread");
+        Self {
+            sender,
+            overflow: config.queue.overflow,
+            dropped,
+            tail,
+        }
+    }
+
+    pub fn set_console(&self, enabled: bool) {
+        self.send(WriterCommand::SetConsole(enabled));
+    }
+
+    /// 开关文件日志，每次打开都重新打开当天的日志文件
+    pub fn set_file(&self, enabled: bool) {
+        self.send(WriterCommand::SetFile(enabled));
+    }
+
+    /// 修改日志轮换规则
+    pub fn set_rotation(&self, rotation: LogRotation) {
+        self.send(WriterCommand::SetRotation(rotation));
+    }
+
+    /// 等待队列中的日志全部写出，最多等待 `timeout`，返回是否写完
+    pub fn flush(&self, timeout: Duration) -> bool {
+        let (done, finished) = mpsc::channel();
+        self.send(WriterCommand::Flush(done));
+        finished.recv_timeout(timeout).is_ok()
+    }
+
+    /// 写入线程最近输出的日志，不带颜色
+    pub fn recent_lines(&self) -> Vec<String> {
+        self.tail
+            .lock()
+            .map(|tail| tail.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// 设置类指令很少，总是等待入队
+    fn send(&self, command: WriterCommand) {
+        // 写入线程退出后不再有输出，忽略即可
+        let _ = self.sender.send(command);
+    }
+
+    fn write(&self, level: LogLevel, target: &str, message: String) {
+        let entry = WriterCommand::Entry(LogEntry {
+            timestamp: Local::now(),
+            level,
+            target: target.to_string(),
+            message,
+        });
+        if level == LogLevel::Error || self.overflow == LogOverflow::Block {
+            self.send(entry);
+            return;
+        }
+        if let Err(TrySendError::Full(_)) = self.sender.try_send(entry) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// 取出事件的消息与其余字段，字段按 `键=值` 接在消息后面
+#[derive(Default)]
+struct EventVisitor {
+    message: String,
+    fields: String,
+}
+
+impl EventVisitor {
+    fn push_field(&mut self, field: &Field, value: fmt::Arguments) {
+        // 从 log 转来的事件带有来源信息字段，目标已从中还原
+        if field.name().starts_with("log.") {
+            return;
+        }
+        let _ = write!(self.fields, " {}={}", field.name(), value);
+    }
+}
+
+impl Visit for EventVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            self.push_field(field, format_args!("{}", value));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            self.push_field(field, format_args!("{:?}", value));
+        }
+    }
+}
+
+/// 把通过过滤的事件写到控制台与文件
+///
+/// 游戏自己的日志、引擎的日志与经 log 转来的第三方库日志都从这里输出
+pub struct GameLogLayer {
+    sinks: LogSinks,
+}
+
+impl GameLogLayer {
+    pub fn new(sinks: LogSinks) -> Self {
+        Self { sinks }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for GameLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let normalized = event.normalized_metadata();
+        let metadata = normalized.as_ref().unwrap_or_else(|| event.metadata());
+
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+        visitor.message.push_str(&visitor.fields);
+
+        self.sinks.write(
+            LogLevel::from_level(metadata.level()),
+            metadata.target(),
+            visitor.message,
+        );
+    }
+}
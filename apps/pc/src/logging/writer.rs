@@ -0,0 +1,234 @@
+use chrono::{DateTime, Local};
+use colored::*;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use super::config::{LogConfig, LogLevel};
+use super::{LogRotation, RotatingLogFile};
+
+/// 内存中保留的最近日志行数，崩溃报告附带这些日志
+const TAIL_LINES: usize = 200;
+
+/// 队列满时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogOverflow {
+    /// 丢弃新的日志，之后补记丢弃的条数
+    DropNewest,
+    /// 等待写入线程腾出位置
+    Block,
+}
+
+/// 后台写入线程的队列设置
+#[derive(Debug, Clone, Copy)]
+pub struct LogQueue {
+    /// 队列最多积压的日志条数
+    pub capacity: usize,
+    /// 写入线程一次最多取出的日志条数，取完一批才刷新输出
+    pub batch_size: usize,
+    /// 队列满时的处理方式，错误日志总是等待入队
+    pub overflow: LogOverflow,
+}
+
+impl Default for LogQueue {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            batch_size: 256,
+            // 调试版本宁可等待也不丢日志，发布版本不让日志拖慢游戏
+            overflow: if cfg!(debug_assertions) {
+                LogOverflow::Block
+            } else {
+                LogOverflow::DropNewest
+            },
+        }
+    }
+}
+
+/// 一条待输出的日志，时间取记录时的时间
+pub(super) struct LogEntry {
+    pub timestamp: DateTime<Local>,
+    pub level: LogLevel,
+    pub target: String,
+    pub message: String,
+}
+
+/// 发给写入线程的指令
+pub(super) enum WriterCommand {
+    Entry(LogEntry),
+    SetConsole(bool),
+    SetFile(bool),
+    SetRotation(LogRotation),
+    /// 写完之前的日志后回复
+    Flush(Sender<()>),
+}
+
+/// 后台写入线程，控制台与文件输出都在这里完成
+///
+/// # 设计思路
+/// 1. 记录日志只是把一条日志放进有界队列，游戏系统不会等待文件读写
+/// 2. 写入线程一次取出一批日志，控制台一次输出，文件一次刷新
+/// 3. 队列满时按设置丢弃或等待，丢弃的条数记在共享计数里，下一批补记一条警告
+/// 4. 最近的日志另在内存中保留一份，文件日志关闭时崩溃报告也能附上
+pub(super) struct LogWriter {
+    console: bool,
+    file: Option<RotatingLogFile>,
+    log_dir: String,
+    rotation: LogRotation,
+    batch_size: usize,
+    dropped: Arc<AtomicU64>,
+    tail: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl LogWriter {
+    pub fn new(
+        config: &LogConfig,
+        dropped: Arc<AtomicU64>,
+        tail: Arc<Mutex<VecDeque<String>>>,
+    ) -> Self {
+        let file = config
+            .file_output
+            .then(|| open_log_file(&config.log_dir, config.rotation))
+            .flatten();
+        Self {
+            console: config.console_output,
+            file,
+            log_dir: config.log_dir.clone(),
+            rotation: config.rotation,
+            batch_size: config.queue.batch_size.max(1),
+            dropped,
+            tail,
+        }
+    }
+
+    /// 处理指令直到所有发送端都关闭
+    pub fn run(mut self, receiver: Receiver<WriterCommand>) {
+        while let Ok(command) = receiver.recv() {
+            let mut console = String::new();
+            let mut flushed = Vec::new();
+            self.handle(command, &mut console, &mut flushed);
+            for command in receiver.try_iter().take(self.batch_size - 1) {
+                self.handle(command, &mut console, &mut flushed);
+            }
+            self.report_dropped(&mut console);
+
+            if !console.is_empty() {
+                print!("{}", console);
+            }
+            if let Some(file) = self.file.as_mut() {
+                let _ = file.flush();
+            }
+            for done in flushed {
+                let _ = done.send(());
+            }
+        }
+    }
+
+    fn handle(
+        &mut self,
+        command: WriterCommand,
+        console: &mut String,
+        flushed: &mut Vec<Sender<()>>,
+    ) {
+        match command {
+            WriterCommand::Entry(entry) => self.write(&entry, console),
+            WriterCommand::SetConsole(enabled) => self.console = enabled,
+            WriterCommand::SetFile(enabled) => {
+                // 先关闭原来的文件，重新打开时才不会被当作旧日志压缩
+                if let Some(mut file) = self.file.take() {
+                    let _ = file.flush();
+                }
+                self.file = enabled
+                    .then(|| open_log_file(&self.log_dir, self.rotation))
+                    .flatten();
+            }
+            WriterCommand::SetRotation(rotation) => {
+                self.rotation = rotation;
+                if let Some(file) = self.file.as_mut() {
+                    if let Err(e) = file.set_rotation(rotation) {
+                        eprintln!("日志轮换失败: {}", e);
+                    }
+                }
+            }
+            WriterCommand::Flush(done) => flushed.push(done),
+        }
+    }
+
+    fn write(&mut self, entry: &LogEntry, console: &mut String) {
+        let timestamp = entry.timestamp.format("%Y-%m-%d %H:%M:%S%.3f");
+        let level = entry.level;
+        let message = entry.message.as_str();
+
+        // 控制台输出，攒到一批结束时一起输出
+        if self.console {
+            let colored_level = match level {
+                LogLevel::Error => level.as_str().red().bold(),
+                LogLevel::Warn => level.as_str().magenta(),
+                LogLevel::Info => level.as_str().green(),
+                LogLevel::Debug => level.as_str().yellow(),
+                LogLevel::Verbose => level.as_str().blue(),
+            };
+
+            let colored_message = match level {
+                LogLevel::Error => message.red(),
+                LogLevel::Warn => message.magenta(),
+                LogLevel::Info => message.white(),
+                LogLevel::Debug => message.yellow(),
+                LogLevel::Verbose => message.blue(),
+            };
+
+            let _ = writeln!(
+                console,
+                "[{}] [{}] {} {}",
+                timestamp.to_string().white(),
+                colored_level,
+                entry.target.dimmed(),
+                colored_message
+            );
+        }
+
+        let line = format!(
+            "[{}] [{}] {} {}",
+            timestamp,
+            level.as_str(),
+            entry.target,
+            message
+        );
+
+        // 文件输出，写入失败时放弃这一条，不能让日志拖垮游戏
+        if let Some(file) = &mut self.file {
+            let _ = file.write_entry(format!("{}\n", line).as_bytes());
+        }
+
+        if let Ok(mut tail) = self.tail.lock() {
+            if tail.len() >= TAIL_LINES {
+                tail.pop_front();
+            }
+            tail.push_back(line);
+        }
+    }
+
+    /// 补记队列满时丢弃的日志条数
+    fn report_dropped(&mut self, console: &mut String) {
+        let dropped = self.dropped.swap(0, Ordering::Relaxed);
+        if dropped == 0 {
+            return;
+        }
+        let entry = LogEntry {
+            timestamp: Local::now(),
+            level: LogLevel::Warn,
+            target: module_path!().to_string(),
+            message: format!("日志队列已满，丢弃了 {} 条日志", dropped),
+        };
+        self.write(&entry, console);
+    }
+}
+
+/// 打不开日志文件时只在控制台提示，游戏照常运行
+fn open_log_file(log_dir: &str, rotation: LogRotation) -> Option<RotatingLogFile> {
+    RotatingLogFile::open(log_dir, rotation)
+        .map_err(|e| eprintln!("无法打开日志文件: {}", e))
+        .ok()
+}
@@ -1,20 +1,70 @@
+mod analytics;
+mod assets;
+mod audio;
+mod chat;
+mod chatter;
+mod combat;
 mod config;
+mod coop;
+mod crash;
 mod events;
+mod headless;
+mod housing;
+mod hud;
+mod interaction;
+mod items;
+mod loading;
 mod logging;
+mod metrics;
+mod network;
 mod plugins;
+mod profiler;
+mod render;
 mod resources;
+mod rest;
+mod save;
+mod scripting;
+#[cfg(feature = "server")]
+mod server;
+mod time;
+mod ui;
 mod world;
 
+use bevy::app::AppExit;
 use clap::builder::EnumValueParser;
-use clap::{Parser, ValueEnum};
-use config::{ConfigManager, ConfigType};
+use clap::{Parser, Subcommand, ValueEnum};
+use config::{resolve_data_path, split_cli_overrides, ConfigManager, ConfigType};
+use coop::CoopCommand;
+use headless::HeadlessSettings;
 use plugins::GamePluginManager;
 use std::fmt;
+use world::chunk::{
+    pregenerate_region, run_headless_stream_test, ChunkCoord, ChunkStore, PregenPreview,
+    PregenRegion, SnapshotSpec, StreamTestSettings, WorldSnapshot, CHUNK_STORE_DIR,
+    WORLDGEN_PREVIEW_DIR, WORLD_SNAPSHOT_BASELINE_PATH,
+};
+#[cfg(feature = "worldgen-verify")]
+use world::chunk::{verify_worldgen, WorldgenGolden, WORLDGEN_GOLDEN_PATH};
+use world::map::PreviewLayer;
 
 #[derive(Clone, Debug, ValueEnum)]
 enum Mode {
     Debug,
     Dev,
+    /// 发布前的测试包
+    Pre,
+    Release,
+}
+
+impl Mode {
+    /// 未指定模式时，调试构建用 Dev，优化构建用 Release
+    fn for_build() -> Self {
+        if cfg!(debug_assertions) {
+            Mode::Dev
+        } else {
+            Mode::Release
+        }
+    }
 }
 
 impl fmt::Display for Mode {
@@ -22,27 +72,309 @@ impl fmt::Display for Mode {
         match self {
             Mode::Debug => write!(f, "debug"),
             Mode::Dev => write!(f, "dev"),
+            Mode::Pre => write!(f, "pre"),
+            Mode::Release => write!(f, "release"),
         }
     }
 }
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    after_help = "配置项可用 --<分组>.<配置项>=<值> 临时覆盖，例如 --window.width=1920；\n\
+                  也可用环境变量 CHIVALRY_<分组>__<配置项>，例如 CHIVALRY_WINDOW__WIDTH=1920"
+)]
 struct Args {
-    #[arg(short, long, value_parser = EnumValueParser::<Mode>::new(), default_value_t = Mode::Dev)]
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[arg(short, long, value_parser = EnumValueParser::<Mode>::new(), default_value_t = Mode::for_build())]
     mode: Mode,
+
+    /// 生成世界快照并与基准比较，有差异时以非零状态退出
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 0..=1,
+        default_missing_value = WORLD_SNAPSHOT_BASELINE_PATH
+    )]
+    world_snapshot: Option<String>,
+
+    /// 用当前的生成结果覆盖世界快照基准
+    #[arg(long, requires = "world_snapshot")]
+    update_snapshot: bool,
+
+    /// 检查世界生成是否确定，并与基准哈希比较，不一致时以非零状态退出
+    #[cfg(feature = "worldgen-verify")]
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 0..=1,
+        default_missing_value = WORLDGEN_GOLDEN_PATH
+    )]
+    verify_worldgen: Option<String>,
+
+    /// 用当前的生成结果覆盖世界生成基准哈希
+    #[cfg(feature = "worldgen-verify")]
+    #[arg(long, requires = "verify_worldgen")]
+    update_worldgen_golden: bool,
+
+    /// 开设局域网联机会话
+    #[arg(long, conflicts_with_all = ["join", "browse"])]
+    host: bool,
+
+    /// 加入指定地址的联机会话，例如 192.168.1.5:7457
+    #[arg(long, value_name = "ADDR", conflicts_with = "browse")]
+    join: Option<String>,
+
+    /// 搜索局域网内的联机会话，结果写入日志
+    #[arg(long)]
+    browse: bool,
+
+    /// 不启动游戏跑一遍区块流式加载压测场景，报告写入 reports/streaming
+    #[arg(
+        long,
+        value_name = "SCENARIO",
+        num_args = 0..=1,
+        default_missing_value = "fast_travel"
+    )]
+    stream_test: Option<String>,
+
+    /// 覆盖压测场景的速度（瓦片/秒），逗号分隔
+    #[arg(long, value_delimiter = ',', requires = "stream_test")]
+    stream_speeds: Vec<f32>,
+
+    /// 压测使用的世界种子
+    #[arg(long, default_value_t = 42, requires = "stream_test")]
+    stream_seed: u32,
+
+    /// 不开窗口运行：模拟区块加载、NPC AI 与历法，跑完指定帧数后写出报告并退出
+    #[arg(long, conflicts_with_all = ["host", "join", "browse"])]
+    headless: bool,
+
+    /// 无窗口运行模拟的帧数
+    #[arg(long, default_value_t = 3600, requires = "headless")]
+    ticks: u32,
+
+    /// 无窗口运行使用的世界种子
+    #[arg(long, default_value_t = 42, requires = "headless")]
+    headless_seed: u32,
+}
+
+/// 不启动游戏的工具命令
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// 预生成一片区块写入区块存储，可同时导出高度、气候带与水深预览图
+    Worldgen(WorldgenArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct WorldgenArgs {
+    /// 世界种子
+    #[arg(long, default_value_t = 42)]
+    seed: u32,
+
+    /// 区域半径（区块数），生成以中心区块为中心、边长 2 * 半径 + 1 的正方形
+    #[arg(long, default_value_t = 8)]
+    radius: u32,
+
+    /// 中心区块坐标
+    #[arg(
+        long,
+        value_name = "X,Y",
+        value_delimiter = ',',
+        allow_hyphen_values = true,
+        default_value = "0,0"
+    )]
+    center: Vec<i32>,
+
+    /// 区块存储目录
+    #[arg(long, value_name = "DIR", default_value = CHUNK_STORE_DIR)]
+    out: String,
+
+    /// 导出预览图到指定目录
+    #[arg(
+        long,
+        value_name = "DIR",
+        num_args = 0..=1,
+        default_missing_value = WORLDGEN_PREVIEW_DIR
+    )]
+    preview: Option<String>,
+
+    /// 预览图每个像素代表的瓦片边长
+    #[arg(long, default_value_t = 1)]
+    preview_scale: u32,
+
+    /// 导出的预览图层：height、temperature、moisture、biome、water
+    #[arg(
+        long,
+        value_name = "LAYER,...",
+        value_delimiter = ',',
+        default_value = "height,temperature,moisture,biome,water"
+    )]
+    layers: Vec<String>,
+}
+
+impl Args {
+    /// 启动时执行的联机指令
+    fn coop_command(&self) -> Option<CoopCommand> {
+        if self.host {
+            Some(CoopCommand::Host)
+        } else if let Some(address) = &self.join {
+            Some(CoopCommand::Join(address.clone()))
+        } else if self.browse {
+            Some(CoopCommand::Browse)
+        } else {
+            None
+        }
+    }
+}
+
+/// 生成世界快照并与基准比较，或更新基准
+fn run_world_snapshot(path: &str, update: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let snapshot = WorldSnapshot::generate(&SnapshotSpec::default());
+    if update {
+        snapshot.save(path)?;
+        println!(
+            "已更新世界快照基准: {}（{} 个区块）",
+            path,
+            snapshot.chunks.len()
+        );
+        return Ok(());
+    }
+
+    let baseline = WorldSnapshot::load(path).map_err(|e| {
+        format!(
+            "读取世界快照基准 {} 失败: {}，可用 --update-snapshot 生成",
+            path, e
+        )
+    })?;
+    let diff = snapshot.diff(&baseline);
+    print!("{}", diff);
+    if !diff.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// 检查世界生成的确定性并与基准哈希比较，或更新基准
+#[cfg(feature = "worldgen-verify")]
+fn run_verify_worldgen(path: &str, update: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if update {
+        let golden = WorldgenGolden::generate();
+        golden.save(path)?;
+        println!(
+            "已更新世界生成基准: {}（{} 个区块）",
+            path,
+            golden.hashes.len()
+        );
+        return Ok(());
+    }
+
+    let golden = WorldgenGolden::load(path).map_err(|e| {
+        format!(
+            "读取世界生成基准 {} 失败: {}，可用 --update-worldgen-golden 生成",
+            path, e
+        )
+    })?;
+    let report = verify_worldgen(&golden);
+    print!("{}", report);
+    if !report.passed() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// 预生成区块并按需导出预览图
+fn run_worldgen(args: &WorldgenArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let [x, y] = args.center[..] else {
+        return Err(format!("中心区块须写成 X,Y，收到 {:?}", args.center).into());
+    };
+    let region = PregenRegion {
+        seed: args.seed,
+        center: ChunkCoord { x, y },
+        radius: args.radius,
+    };
+    let preview = match &args.preview {
+        Some(dir) => {
+            let layers = args
+                .layers
+                .iter()
+                .map(|name| {
+                    PreviewLayer::from_name(name).ok_or_else(|| format!("未知的预览图层：{}", name))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Some(PregenPreview {
+                dir: dir.into(),
+                scale: args.preview_scale,
+                layers,
+            })
+        }
+        None => None,
+    };
+    let store = ChunkStore::new(&args.out);
+    let report = pregenerate_region(region, &store, preview.as_ref())?;
+    print!("{}", report);
+    Ok(())
+}
+
+/// 跑一遍区块流式加载压测并写出报告
+fn run_stream_test(
+    scenario: &str,
+    speeds: &[f32],
+    seed: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let settings = StreamTestSettings::default();
+    let speeds = (!speeds.is_empty()).then(|| speeds.to_vec());
+    let report = run_headless_stream_test(scenario, speeds, seed, &settings)?;
+    print!("{}", report);
+    let path = report.save(&settings.output_dir)?;
+    println!("报告已写入 {}", path.display());
+    Ok(())
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
+    // 配置项覆盖不是预先声明的参数，先分出来再交给命令行解析
+    let (args, config_overrides) = split_cli_overrides(std::env::args());
+    let args = Args::parse_from(args);
+    if let Some(Command::Worldgen(worldgen)) = &args.command {
+        return run_worldgen(worldgen);
+    }
+    if let Some(path) = &args.world_snapshot {
+        return run_world_snapshot(&resolve_data_path(path), args.update_snapshot);
+    }
+    #[cfg(feature = "worldgen-verify")]
+    if let Some(path) = &args.verify_worldgen {
+        return run_verify_worldgen(&resolve_data_path(path), args.update_worldgen_golden);
+    }
+    if let Some(scenario) = &args.stream_test {
+        return run_stream_test(scenario, &args.stream_speeds, args.stream_seed);
+    }
+
     let config_type = match args.mode {
         Mode::Debug => ConfigType::Debug,
         Mode::Dev => ConfigType::Dev,
+        Mode::Pre => ConfigType::Pre,
+        Mode::Release => ConfigType::Release,
     };
-    let config_manager = ConfigManager::new(config_type)?;
-    let settings = config_manager.get_settings();
+    let config_manager = ConfigManager::new(config_type, config_overrides)?;
+
+    if args.headless {
+        let headless = HeadlessSettings {
+            ticks: args.ticks,
+            seed: args.headless_seed,
+            ..Default::default()
+        };
+        if let AppExit::Error(code) = GamePluginManager::run_headless(config_manager, headless) {
+            std::process::exit(code.get().into());
+        }
+        return Ok(());
+    }
 
-    GamePluginManager::run(settings);
+    GamePluginManager::run(config_manager, args.coop_command());
 
     Ok(())
 }
@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+use chrono::Local;
+use serde::Serialize;
+use serde_json::json;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::MetricsRegistry;
+
+/// 读取请求的最长等待时间，避免一个不发请求的连接占住端点
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 构建信息，写在每条 JSON 记录里，区分不同版本的数据
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    /// 调试或优化构建
+    pub profile: &'static str,
+    /// 运行模式，即配置目录名
+    pub mode: String,
+}
+
+impl BuildInfo {
+    pub fn new(mode: &str) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            profile: if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            },
+            mode: mode.to_string(),
+        }
+    }
+}
+
+/// 本机的 Prometheus 端点
+///
+/// 只监听回环地址，后台线程逐个应答 `GET /metrics`，页面由采集系统定时更新
+#[derive(Clone)]
+pub struct PrometheusEndpoint {
+    page: Arc<Mutex<String>>,
+    pub address: SocketAddr,
+}
+
+impl PrometheusEndpoint {
+    pub fn spawn(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        let address = listener.local_addr()?;
+        let page = Arc::new(Mutex::new(String::new()));
+        let shared = page.clone();
+        std::thread::Builder::new()
+            .name("metrics-endpoint".to_string())
+            .spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    // 应答失败只影响这一次抓取
+                    let _ = respond(stream, &shared);
+                }
+            })?;
+        Ok(Self { page, address })
+    }
+
+    /// 更新端点返回的页面
+    pub fn publish(&self, page: String) {
+        if let Ok(mut current) = self.page.lock() {
+            *current = page;
+        }
+    }
+}
+
+fn respond(mut stream: TcpStream, page: &Mutex<String>) -> io::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut buffer = [0u8; 1024];
+    let read = stream.read(&mut buffer)?;
+    let request = String::from_utf8_lossy(&buffer[..read]);
+    let mut parts = request.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics" | "/")) => (
+            "200 OK",
+            page.lock().map(|page| page.clone()).unwrap_or_default(),
+        ),
+        _ => ("404 Not Found", String::new()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// 定时写出的 JSON 记录，每次启动一个文件，每行一条
+pub struct MetricsDump {
+    path: PathBuf,
+    build: BuildInfo,
+}
+
+impl MetricsDump {
+    pub fn create(dir: &str, build: BuildInfo) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = PathBuf::from(dir).join(format!(
+            "metrics-{}.jsonl",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        Ok(Self { path, build })
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// 追加一条记录，`uptime` 为启动以来的秒数
+    pub fn append(&self, registry: &MetricsRegistry, uptime: f64) -> io::Result<()> {
+        let record = json!({
+            "time": Local::now().to_rfc3339(),
+            "uptime": uptime,
+            "build": self.build,
+            "metrics": registry.to_json(),
+        });
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", record)
+    }
+}
+
+/// 正在使用的导出方式
+#[derive(Resource, Default)]
+pub struct MetricsExporters {
+    pub endpoint: Option<PrometheusEndpoint>,
+    pub dump: Option<MetricsDump>,
+}
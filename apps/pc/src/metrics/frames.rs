@@ -0,0 +1,48 @@
+use std::collections::VecDeque;
+
+/// 最近若干帧的帧时间
+///
+/// 帧率分位数按帧时间换算：帧率的 1% 分位对应帧时间的 99% 分位，即最慢的那 1% 帧
+#[derive(Debug, Clone)]
+pub struct FrameTimeWindow {
+    capacity: usize,
+    /// 帧时间（秒）
+    frames: VecDeque<f32>,
+}
+
+impl FrameTimeWindow {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            frames: VecDeque::with_capacity(capacity.max(1)),
+        }
+    }
+
+    pub fn push(&mut self, frame_time: f32) {
+        if frame_time <= 0.0 {
+            return;
+        }
+        if self.frames.len() >= self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame_time);
+    }
+
+    /// 帧率的分位数，`quantile` 为 0 到 1，窗口为空时为空
+    pub fn fps_quantile(&self, quantile: f32) -> Option<f32> {
+        if self.frames.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<f32> = self.frames.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        // 帧时间从短到长排列，帧率低的一端在后面
+        let rank = ((1.0 - quantile.clamp(0.0, 1.0)) * (sorted.len() - 1) as f32).round();
+        Some(1.0 / sorted[rank as usize])
+    }
+
+    /// 窗口内的平均帧率
+    pub fn average_fps(&self) -> Option<f32> {
+        let total: f32 = self.frames.iter().sum();
+        (total > 0.0).then(|| self.frames.len() as f32 / total)
+    }
+}
@@ -0,0 +1,19 @@
+/// 运行指标模块
+///
+/// 可选开启，汇总帧率分位数、区块加载速度、存档耗时与网络延迟等指标，
+/// 通过本机的 Prometheus 端点或定时写出的 JSON 文件导出，用于对比不同版本的性能
+///
+/// # 模块组成
+/// 1. registry：指标登记表，生成 Prometheus 文本格式与 JSON
+/// 2. frames：帧时间窗口与帧率分位数
+/// 3. export：本机 Prometheus 端点与 JSON 定时导出
+/// 4. systems：指标插件及采集系统
+mod export;
+mod frames;
+mod registry;
+mod systems;
+
+pub use export::*;
+pub use frames::*;
+pub use registry::*;
+pub use systems::*;
@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+/// 指标类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    /// 只增不减的累计值
+    Counter,
+    /// 随时变化的当前值
+    Gauge,
+}
+
+impl MetricKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+        }
+    }
+}
+
+/// 同名指标，按标签分为多条序列
+#[derive(Debug, Clone)]
+struct MetricFamily {
+    kind: MetricKind,
+    help: &'static str,
+    /// 标签的文本形式，例如 `quantile="0.99"`，无标签时为空
+    series: BTreeMap<String, f64>,
+}
+
+/// 指标登记表
+///
+/// # 设计思路
+/// 1. 指标先登记名称、类型与说明，之后按名称与标签写入数值，未登记的名称直接忽略
+/// 2. 标签按 Prometheus 的文本格式保存，导出时不必再转换
+/// 3. 名称与序列都有序保存，每次导出的顺序一致，便于比较前后两份 JSON
+#[derive(Resource, Debug, Default)]
+pub struct MetricsRegistry {
+    families: BTreeMap<&'static str, MetricFamily>,
+}
+
+impl MetricsRegistry {
+    /// 登记一个指标
+    pub fn describe(&mut self, name: &'static str, kind: MetricKind, help: &'static str) {
+        self.families.entry(name).or_insert(MetricFamily {
+            kind,
+            help,
+            series: BTreeMap::new(),
+        });
+    }
+
+    /// 设置当前值
+    pub fn set(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        if let Some(family) = self.families.get_mut(name) {
+            family.series.insert(render_labels(labels), value);
+        }
+    }
+
+    /// 累加，计数器只接受非负的增量
+    pub fn add(&mut self, name: &str, labels: &[(&str, &str)], delta: f64) {
+        let Some(family) = self.families.get_mut(name) else {
+            return;
+        };
+        if family.kind == MetricKind::Counter && delta < 0.0 {
+            return;
+        }
+        *family.series.entry(render_labels(labels)).or_insert(0.0) += delta;
+    }
+
+    /// 取当前值，没有写入过时为空
+    pub fn get(&self, name: &str, labels: &[(&str, &str)]) -> Option<f64> {
+        self.families
+            .get(name)?
+            .series
+            .get(&render_labels(labels))
+            .copied()
+    }
+
+    /// Prometheus 文本格式
+    pub fn render_prometheus(&self) -> String {
+        let mut text = String::new();
+        for (name, family) in &self.families {
+            if family.series.is_empty() {
+                continue;
+            }
+            let _ = writeln!(text, "# HELP {} {}", name, family.help);
+            let _ = writeln!(text, "# TYPE {} {}", name, family.kind.as_str());
+            for (labels, value) in &family.series {
+                if labels.is_empty() {
+                    let _ = writeln!(text, "{} {}", name, value);
+                } else {
+                    let _ = writeln!(text, "{}{{{}}} {}", name, labels, value);
+                }
+            }
+        }
+        text
+    }
+
+    /// JSON 形式，每条序列以 Prometheus 的写法为键
+    pub fn to_json(&self) -> Value {
+        let mut metrics = Map::new();
+        for (name, family) in &self.families {
+            for (labels, value) in &family.series {
+                let key = if labels.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{}{{{}}}", name, labels)
+                };
+                metrics.insert(key, Value::from(*value));
+            }
+        }
+        Value::Object(metrics)
+    }
+}
+
+fn render_labels(labels: &[(&str, &str)]) -> String {
+    labels
+        .iter()
+        .map(|(key, value)| {
+            format!(
+                "{}=\"{}\"",
+                key,
+                value.replace('\\', "\\\\").replace('"', "\\\"")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -0,0 +1,291 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+use super::{
+    BuildInfo, FrameTimeWindow, MetricKind, MetricsDump, MetricsExporters, MetricsRegistry,
+    PrometheusEndpoint,
+};
+use crate::logging::{GameLogger, LogLevel};
+use crate::network::NetworkClient;
+use crate::save::{SaveCompleted, SaveReason};
+use crate::world::chunk::ChunkManager;
+
+const BUILD_INFO: &str = "game_build_info";
+const FPS: &str = "game_fps";
+const FPS_AVERAGE: &str = "game_fps_average";
+const CHUNKS_LOADED_TOTAL: &str = "game_chunks_loaded_total";
+const CHUNKS_LOADED_PER_SECOND: &str = "game_chunks_loaded_per_second";
+const CHUNKS_RESIDENT: &str = "game_chunks_resident";
+const SAVE_DURATION_SUM: &str = "game_save_duration_seconds_sum";
+const SAVE_DURATION_COUNT: &str = "game_save_duration_seconds_count";
+const SAVE_DURATION_MAX: &str = "game_save_duration_seconds_max";
+const SAVE_FAILURES: &str = "game_save_failures_total";
+const NETWORK_RTT: &str = "game_network_rtt_seconds";
+const NETWORK_PACKET_LOSS: &str = "game_network_packet_loss";
+
+/// 导出的帧率分位数：最慢的 1%、5% 帧与中位数
+const FPS_QUANTILES: [(&str, f32); 3] = [("0.01", 0.01), ("0.05", 0.05), ("0.5", 0.5)];
+
+/// 指标设置
+#[derive(Resource, Debug, Clone)]
+pub struct MetricsSettings {
+    /// Prometheus 端点的端口，只监听本机，0 表示不开启
+    pub prometheus_port: u16,
+    /// JSON 记录的写出间隔（秒），0 表示不写
+    pub dump_interval: f32,
+    /// JSON 记录的目录
+    pub dump_dir: String,
+    /// 汇总指标的间隔（秒）
+    pub sample_interval: f32,
+    /// 计算帧率分位数的帧数
+    pub frame_window: usize,
+    /// 运行模式，写在构建信息里
+    pub mode: String,
+}
+
+impl Default for MetricsSettings {
+    fn default() -> Self {
+        Self {
+            prometheus_port: 9464,
+            dump_interval: 60.0,
+            dump_dir: "metrics".to_string(),
+            sample_interval: 1.0,
+            frame_window: 600,
+            mode: String::new(),
+        }
+    }
+}
+
+/// 采集状态
+#[derive(Resource, Debug)]
+pub struct MetricsState {
+    frames: FrameTimeWindow,
+    sample_timer: Timer,
+    dump_timer: Timer,
+    /// 上次汇总时的累计加载区块数与时间
+    last_chunks: Option<(u64, f64)>,
+}
+
+/// 指标插件
+///
+/// # 设计思路
+/// 1. 默认不加入，玩家或测试机在设置中开启后才采集，数据只留在本机
+/// 2. 帧时间与存档耗时随事件记录，其余指标按固定间隔从各子系统读取，不改动被采集的系统
+/// 3. 每次汇总后更新 Prometheus 页面；JSON 记录另按较长的间隔追加，退出时再写一条，便于对比不同版本
+pub struct MetricsPlugin;
+
+impl Plugin for MetricsPlugin {
+    fn build(&self, app: &mut App) {
+        let mut registry = MetricsRegistry::default();
+        registry.describe(BUILD_INFO, MetricKind::Gauge, "构建信息，值恒为 1");
+        registry.describe(FPS, MetricKind::Gauge, "帧率分位数，按最近的帧时间计算");
+        registry.describe(FPS_AVERAGE, MetricKind::Gauge, "最近的平均帧率");
+        registry.describe(CHUNKS_LOADED_TOTAL, MetricKind::Counter, "累计加载的区块数");
+        registry.describe(
+            CHUNKS_LOADED_PER_SECOND,
+            MetricKind::Gauge,
+            "每秒加载的区块数",
+        );
+        registry.describe(CHUNKS_RESIDENT, MetricKind::Gauge, "已加载的区块数");
+        registry.describe(SAVE_DURATION_SUM, MetricKind::Counter, "存档累计耗时（秒）");
+        registry.describe(SAVE_DURATION_COUNT, MetricKind::Counter, "存档次数");
+        registry.describe(
+            SAVE_DURATION_MAX,
+            MetricKind::Gauge,
+            "单次存档的最长耗时（秒）",
+        );
+        registry.describe(SAVE_FAILURES, MetricKind::Counter, "写入不完整的存档次数");
+        registry.describe(NETWORK_RTT, MetricKind::Gauge, "平滑后的网络往返延迟（秒）");
+        registry.describe(
+            NETWORK_PACKET_LOSS,
+            MetricKind::Gauge,
+            "平滑后的心跳丢失比例",
+        );
+
+        app.insert_resource(registry)
+            .init_resource::<MetricsSettings>()
+            .init_resource::<MetricsExporters>()
+            .add_systems(Startup, start_metrics_export)
+            .add_systems(
+                Update,
+                (record_frame_time, record_save_durations, sample_metrics).chain(),
+            )
+            .add_systems(Last, dump_metrics_on_exit);
+    }
+}
+
+/// 按设置开启导出，并写入构建信息
+fn start_metrics_export(
+    mut commands: Commands,
+    settings: Res<MetricsSettings>,
+    mut registry: ResMut<MetricsRegistry>,
+    mut exporters: ResMut<MetricsExporters>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let build = BuildInfo::new(&settings.mode);
+    registry.set(
+        BUILD_INFO,
+        &[
+            ("version", build.version),
+            ("profile", build.profile),
+            ("mode", &build.mode),
+        ],
+        1.0,
+    );
+    commands.insert_resource(MetricsState {
+        frames: FrameTimeWindow::new(settings.frame_window),
+        sample_timer: Timer::from_seconds(settings.sample_interval.max(0.1), TimerMode::Repeating),
+        // 到时后保持完成状态，等下一次汇总时写出再重新计时
+        dump_timer: Timer::from_seconds(settings.dump_interval.max(1.0), TimerMode::Once),
+        last_chunks: None,
+    });
+
+    let mut messages = Vec::new();
+    if settings.prometheus_port > 0 {
+        match PrometheusEndpoint::spawn(settings.prometheus_port) {
+            Ok(endpoint) => {
+                messages.push((
+                    LogLevel::Info,
+                    format!("指标端点已开启: http://{}/metrics", endpoint.address),
+                ));
+                exporters.endpoint = Some(endpoint);
+            }
+            Err(e) => messages.push((
+                LogLevel::Error,
+                format!("无法开启指标端点 {}: {}", settings.prometheus_port, e),
+            )),
+        }
+    }
+    if settings.dump_interval > 0.0 {
+        match MetricsDump::create(&settings.dump_dir, build) {
+            Ok(dump) => {
+                messages.push((
+                    LogLevel::Info,
+                    format!("指标记录写入 {}", dump.path().display()),
+                ));
+                exporters.dump = Some(dump);
+            }
+            Err(e) => messages.push((
+                LogLevel::Error,
+                format!("无法创建指标记录目录 {}: {}", settings.dump_dir, e),
+            )),
+        }
+    }
+
+    if let Some(logger) = logger.as_mut() {
+        for (level, message) in messages {
+            logger.log(level, &message);
+        }
+    }
+}
+
+fn record_frame_time(time: Res<Time<Real>>, state: Option<ResMut<MetricsState>>) {
+    if let Some(mut state) = state {
+        state.frames.push(time.delta_secs());
+    }
+}
+
+fn record_save_durations(
+    mut completed: EventReader<SaveCompleted>,
+    mut registry: ResMut<MetricsRegistry>,
+) {
+    for save in completed.read() {
+        let reason = [("reason", save_reason_label(save.reason))];
+        let seconds = save.duration.as_secs_f64();
+        registry.add(SAVE_DURATION_SUM, &reason, seconds);
+        registry.add(SAVE_DURATION_COUNT, &reason, 1.0);
+        let max = registry.get(SAVE_DURATION_MAX, &reason).unwrap_or(0.0);
+        registry.set(SAVE_DURATION_MAX, &reason, max.max(seconds));
+        if !save.complete {
+            registry.add(SAVE_FAILURES, &reason, 1.0);
+        }
+    }
+}
+
+/// 按间隔汇总指标、更新端点，并按更长的间隔写出 JSON 记录
+fn sample_metrics(
+    time: Res<Time<Real>>,
+    state: Option<ResMut<MetricsState>>,
+    mut registry: ResMut<MetricsRegistry>,
+    exporters: Res<MetricsExporters>,
+    chunk_manager: Option<Res<ChunkManager>>,
+    client: Option<Res<NetworkClient>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let Some(mut state) = state else {
+        return;
+    };
+    state.dump_timer.tick(time.delta());
+    if !state.sample_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for (label, quantile) in FPS_QUANTILES {
+        if let Some(fps) = state.frames.fps_quantile(quantile) {
+            registry.set(FPS, &[("quantile", label)], fps as f64);
+        }
+    }
+    if let Some(fps) = state.frames.average_fps() {
+        registry.set(FPS_AVERAGE, &[], fps as f64);
+    }
+
+    if let Some(chunk_manager) = &chunk_manager {
+        let stats = chunk_manager.stream_stats();
+        let now = time.elapsed_secs_f64();
+        if let Some((loaded, at)) = state.last_chunks {
+            let elapsed = now - at;
+            if elapsed > 0.0 {
+                let rate = stats.total_loaded.saturating_sub(loaded) as f64 / elapsed;
+                registry.set(CHUNKS_LOADED_PER_SECOND, &[], rate);
+            }
+        }
+        state.last_chunks = Some((stats.total_loaded, now));
+        registry.set(CHUNKS_LOADED_TOTAL, &[], stats.total_loaded as f64);
+        registry.set(CHUNKS_RESIDENT, &[], stats.loaded as f64);
+    }
+
+    if let Some(client) = client.filter(|client| client.is_connected()) {
+        registry.set(NETWORK_RTT, &[], client.latency as f64);
+        registry.set(NETWORK_PACKET_LOSS, &[], client.packet_loss as f64);
+    }
+
+    if let Some(endpoint) = &exporters.endpoint {
+        endpoint.publish(registry.render_prometheus());
+    }
+    if state.dump_timer.finished() {
+        state.dump_timer.reset();
+        if let Some(dump) = &exporters.dump {
+            if let Err(e) = dump.append(&registry, time.elapsed_secs_f64()) {
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(LogLevel::Warn, &format!("写入指标记录失败: {}", e));
+                }
+            }
+        }
+    }
+}
+
+/// 退出时补写一条记录，短时间的测试运行也有数据
+fn dump_metrics_on_exit(
+    mut exits: EventReader<AppExit>,
+    time: Res<Time<Real>>,
+    registry: Res<MetricsRegistry>,
+    exporters: Res<MetricsExporters>,
+) {
+    if exits.read().count() == 0 {
+        return;
+    }
+    if let Some(dump) = &exporters.dump {
+        let _ = dump.append(&registry, time.elapsed_secs_f64());
+    }
+}
+
+fn save_reason_label(reason: SaveReason) -> &'static str {
+    match reason {
+        SaveReason::Manual => "manual",
+        SaveReason::Interval => "interval",
+        SaveReason::SceneEnter => "scene_enter",
+        SaveReason::BeforeBoss => "before_boss",
+        SaveReason::CrashRecovery => "crash_recovery",
+        SaveReason::RenderFailure => "render_failure",
+    }
+}
@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+
+use super::{ClientMessage, NetworkClient, NetworkCommand, ServerMessage};
+use crate::events::network::NetworkEvent;
+use crate::logging::{GameLogger, LogLevel, LogScrubber};
+
+/// 等待服务器回应登录的最长时间（秒）
+const LOGIN_TIMEOUT: f32 = 10.0;
+
+/// 请求登录
+#[derive(Event, Debug, Clone)]
+pub struct LoginRequest {
+    pub account: String,
+    pub password: String,
+}
+
+/// 登录结果
+#[derive(Event, Debug, Clone, PartialEq)]
+pub enum LoginResult {
+    Success {
+        account: String,
+    },
+    /// 失败原因，直接显示给玩家
+    Failure(String),
+}
+
+/// 登录成功后的会话
+///
+/// 服务器签发的令牌交给网络客户端，断线重连时随握手发给服务器以恢复会话，不必重新输入密码
+#[derive(Resource, Debug, Clone)]
+pub struct SessionToken {
+    pub account: String,
+}
+
+/// 进行中的登录
+///
+/// 密码只保留到发出登录消息为止
+#[derive(Resource, Debug, Default)]
+pub struct PendingLogin {
+    request: Option<LoginRequest>,
+    account: String,
+    /// 已发出登录消息，等待回应
+    sent: bool,
+    /// 已等待的时间（秒）
+    elapsed: f32,
+}
+
+impl PendingLogin {
+    /// 是否有登录在进行
+    pub fn is_active(&self) -> bool {
+        self.request.is_some() || self.sent
+    }
+
+    fn finish(&mut self) {
+        *self = Self::default();
+    }
+}
+
+fn send_login(client: &NetworkClient, request: LoginRequest) -> bool {
+    client.send(ClientMessage::Login {
+        account: request.account,
+        password: request.password,
+    })
+}
+
+/// 处理登录请求：未连接时先连接服务器，握手成功后再发出账号与密码
+pub fn process_login_requests(
+    time: Res<Time<Real>>,
+    client: Res<NetworkClient>,
+    mut requests: EventReader<LoginRequest>,
+    mut pending: ResMut<PendingLogin>,
+    mut commands: EventWriter<NetworkCommand>,
+    mut results: EventWriter<LoginResult>,
+) {
+    for request in requests.read() {
+        if pending.is_active() {
+            continue;
+        }
+        pending.account = request.account.clone();
+        pending.request = Some(request.clone());
+        pending.elapsed = 0.0;
+        if !client.is_connected() {
+            commands.send(NetworkCommand::Connect(None));
+        }
+    }
+    if !pending.is_active() {
+        return;
+    }
+
+    if client.is_connected() {
+        if let Some(request) = pending.request.take() {
+            if send_login(&client, request) {
+                pending.sent = true;
+            } else {
+                pending.finish();
+                results.send(LoginResult::Failure("发送登录请求失败".to_string()));
+                return;
+            }
+        }
+    }
+
+    pending.elapsed += time.delta_secs();
+    if pending.elapsed > LOGIN_TIMEOUT {
+        pending.finish();
+        results.send(LoginResult::Failure(
+            "服务器没有回应，请稍后再试".to_string(),
+        ));
+    }
+}
+
+/// 处理服务器对登录的回应与登录期间的连接失败
+///
+/// 登录成功后保存会话令牌，并把账号名登记到日志脱敏器
+pub fn handle_login_responses(
+    mut commands: Commands,
+    mut events: EventReader<NetworkEvent>,
+    mut client: ResMut<NetworkClient>,
+    mut pending: ResMut<PendingLogin>,
+    mut results: EventWriter<LoginResult>,
+    mut scrubber: Option<ResMut<LogScrubber>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for event in events.read() {
+        if !pending.is_active() {
+            continue;
+        }
+        match event {
+            NetworkEvent::ConnectionFailed => {
+                pending.finish();
+                results.send(LoginResult::Failure("无法连接服务器".to_string()));
+            }
+            NetworkEvent::Disconnection if pending.sent => {
+                pending.finish();
+                results.send(LoginResult::Failure("登录时与服务器断开".to_string()));
+            }
+            NetworkEvent::Message(ServerMessage::LoginAccepted { account, token }) => {
+                if let Some(scrubber) = scrubber.as_mut() {
+                    scrubber.register_secret(account);
+                    if account != &pending.account {
+                        scrubber.register_secret(&pending.account);
+                    }
+                }
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(LogLevel::Info, "登录成功");
+                }
+                client.session_token = Some(token.clone());
+                commands.insert_resource(SessionToken {
+                    account: account.clone(),
+                });
+                pending.finish();
+                results.send(LoginResult::Success {
+                    account: account.clone(),
+                });
+            }
+            NetworkEvent::Message(ServerMessage::LoginDenied { reason }) => {
+                pending.finish();
+                results.send(LoginResult::Failure(reason.clone()));
+            }
+            _ => {}
+        }
+    }
+}
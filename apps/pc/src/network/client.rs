@@ -0,0 +1,300 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use super::{ClientMessage, LinkStatus, ServerConnection, ServerMessage, NETWORK_PROTOCOL_VERSION};
+
+/// 连接阶段
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectionPhase {
+    /// 未连接
+    Disconnected,
+    /// 正在建立 TCP 连接
+    Connecting,
+    /// 已连上，等待服务器回应握手
+    Handshaking,
+    /// 握手成功，可以正常收发
+    Connected,
+    /// 连接意外断开，等待 `wait` 秒后进行第 `attempt` 次重连
+    Reconnecting { attempt: u32, wait: f32 },
+}
+
+/// 客户端在一帧内发生的连接变化，由系统转换为 `NetworkEvent`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientNotice {
+    Connected,
+    Failed,
+    Disconnected,
+    Reconnecting(u32),
+}
+
+/// 网络客户端
+///
+/// 负责与游戏服务器的连接生命周期：建立连接、握手、心跳与断线重连；
+/// 收到的消息先放入收件箱，由系统按配置的频率分发
+#[derive(Resource)]
+pub struct NetworkClient {
+    phase: ConnectionPhase,
+    connection: Option<ServerConnection>,
+    address: String,
+    /// 服务器分配的客户端编号
+    pub client_id: Option<String>,
+    /// 会话令牌，握手与重连时发给服务器以恢复会话
+    pub session_token: Option<String>,
+    /// 已收到、尚未分发的消息
+    inbox: VecDeque<ServerMessage>,
+    /// 距离下次心跳的剩余时间（秒）
+    heartbeat: f32,
+    /// 距离上次收到消息的时间（秒）
+    silence: f32,
+    /// 已发出、尚未收到回应的心跳的发送时间
+    pending_pings: VecDeque<f64>,
+    /// 平滑后的往返延迟（秒）
+    pub latency: f32,
+    /// 平滑后的心跳丢失比例 (0.0-1.0)
+    pub packet_loss: f32,
+    /// 最近一次收到心跳回应的时间
+    pub last_pong: f64,
+    /// 上次握手成功以来连续重连失败的次数
+    failures: u32,
+    notices: Vec<ClientNotice>,
+}
+
+impl Default for NetworkClient {
+    fn default() -> Self {
+        Self {
+            phase: ConnectionPhase::Disconnected,
+            connection: None,
+            address: String::new(),
+            client_id: None,
+            session_token: None,
+            inbox: VecDeque::new(),
+            heartbeat: 0.0,
+            silence: 0.0,
+            pending_pings: VecDeque::new(),
+            latency: 0.0,
+            packet_loss: 0.0,
+            last_pong: 0.0,
+            failures: 0,
+            notices: Vec::new(),
+        }
+    }
+}
+
+/// 连接生命周期的时间参数（秒）
+#[derive(Debug, Clone, Copy)]
+pub struct LifecycleTimings {
+    pub heartbeat_interval: f32,
+    pub timeout: f32,
+    pub reconnect_delay: f32,
+    pub max_reconnect_delay: f32,
+    pub reconnect_attempts: u32,
+}
+
+impl NetworkClient {
+    /// 当前连接阶段
+    pub fn phase(&self) -> ConnectionPhase {
+        self.phase
+    }
+
+    /// 是否已握手成功
+    pub fn is_connected(&self) -> bool {
+        self.phase == ConnectionPhase::Connected
+    }
+
+    /// 连接服务器，已有的连接先断开
+    pub fn connect(&mut self, address: &str) {
+        self.close_connection();
+        self.address = address.to_string();
+        self.phase = ConnectionPhase::Disconnected;
+        self.failures = 0;
+        self.open();
+    }
+
+    /// 发送消息，未握手成功时丢弃并返回 false
+    pub fn send(&self, message: ClientMessage) -> bool {
+        if !self.is_connected() {
+            return false;
+        }
+        self.connection
+            .as_ref()
+            .is_some_and(|connection| connection.send(message))
+    }
+
+    /// 取出待分发的消息，最多 `limit` 条
+    pub fn take_inbox(&mut self, limit: usize) -> Vec<ServerMessage> {
+        let count = limit.min(self.inbox.len());
+        self.inbox.drain(..count).collect()
+    }
+
+    /// 取出本帧发生的连接变化
+    pub fn take_notices(&mut self) -> Vec<ClientNotice> {
+        std::mem::take(&mut self.notices)
+    }
+
+    fn open(&mut self) {
+        self.connection = Some(ServerConnection::open(&self.address));
+        self.phase = match self.phase {
+            ConnectionPhase::Reconnecting { attempt, .. } => {
+                ConnectionPhase::Reconnecting { attempt, wait: 0.0 }
+            }
+            _ => ConnectionPhase::Connecting,
+        };
+        self.silence = 0.0;
+        self.pending_pings.clear();
+    }
+
+    fn close_connection(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            connection.close();
+        }
+    }
+
+    /// 推进连接生命周期
+    ///
+    /// `now` 为单调递增的真实时间，用于心跳往返计时
+    pub fn update(&mut self, delta: f32, now: f64, timings: &LifecycleTimings) {
+        if let ConnectionPhase::Reconnecting { attempt, wait } = self.phase {
+            if self.connection.is_none() {
+                let wait = wait - delta;
+                if wait > 0.0 {
+                    self.phase = ConnectionPhase::Reconnecting { attempt, wait };
+                    return;
+                }
+                self.open();
+            }
+        }
+
+        let Some(connection) = &self.connection else {
+            return;
+        };
+        let messages = connection.drain();
+        let status = connection.status();
+        if !messages.is_empty() {
+            self.silence = 0.0;
+        }
+        for message in messages {
+            self.receive(message, now);
+        }
+        if self.phase == ConnectionPhase::Disconnected {
+            // 握手被拒或被踢出
+            return;
+        }
+
+        match status {
+            LinkStatus::Connecting => {}
+            LinkStatus::Open => {
+                if matches!(
+                    self.phase,
+                    ConnectionPhase::Connecting | ConnectionPhase::Reconnecting { .. }
+                ) {
+                    self.phase = ConnectionPhase::Handshaking;
+                    if let Some(connection) = &self.connection {
+                        connection.send(ClientMessage::Hello {
+                            version: NETWORK_PROTOCOL_VERSION,
+                            token: self.session_token.clone(),
+                        });
+                    }
+                }
+                self.silence += delta;
+                if self.silence > timings.timeout {
+                    self.connection_lost(timings);
+                    return;
+                }
+                if self.is_connected() {
+                    self.tick_heartbeat(delta, now, timings);
+                }
+            }
+            LinkStatus::Failed | LinkStatus::Closed => self.connection_lost(timings),
+        }
+    }
+
+    fn receive(&mut self, message: ServerMessage, now: f64) {
+        match &message {
+            ServerMessage::Welcome { client_id, .. } => {
+                self.client_id = Some(client_id.clone());
+                self.phase = ConnectionPhase::Connected;
+                self.heartbeat = 0.0;
+                self.failures = 0;
+                self.notices.push(ClientNotice::Connected);
+            }
+            ServerMessage::Rejected { .. } => {
+                self.close_connection();
+                self.phase = ConnectionPhase::Disconnected;
+                self.notices.push(ClientNotice::Failed);
+            }
+            ServerMessage::Kicked { .. } => {
+                self.close_connection();
+                self.phase = ConnectionPhase::Disconnected;
+                self.notices.push(ClientNotice::Disconnected);
+            }
+            ServerMessage::Pong { sent_at } => {
+                if let Some(index) = self.pending_pings.iter().position(|ping| ping == sent_at) {
+                    self.pending_pings.remove(index);
+                    let rtt = (now - sent_at).max(0.0) as f32;
+                    self.latency = if self.latency == 0.0 {
+                        rtt
+                    } else {
+                        self.latency * 0.8 + rtt * 0.2
+                    };
+                    self.packet_loss *= 0.9;
+                    self.last_pong = now;
+                }
+                // 心跳回应只用于计时，不分发
+                return;
+            }
+            _ => {}
+        }
+        self.inbox.push_back(message);
+    }
+
+    /// 按间隔发送心跳，超时未回应的心跳计入丢失比例
+    fn tick_heartbeat(&mut self, delta: f32, now: f64, timings: &LifecycleTimings) {
+        let timeout = timings.timeout as f64;
+        while self
+            .pending_pings
+            .front()
+            .is_some_and(|sent_at| now - sent_at > timeout)
+        {
+            self.pending_pings.pop_front();
+            self.packet_loss = self.packet_loss * 0.9 + 0.1;
+        }
+
+        self.heartbeat -= delta;
+        if self.heartbeat > 0.0 {
+            return;
+        }
+        self.heartbeat = timings.heartbeat_interval;
+        if let Some(connection) = &self.connection {
+            connection.send(ClientMessage::Ping { sent_at: now });
+            self.pending_pings.push_back(now);
+        }
+    }
+
+    /// 连接意外断开：按指数退避安排重连，次数用尽后放弃
+    fn connection_lost(&mut self, timings: &LifecycleTimings) {
+        self.close_connection();
+        match self.phase {
+            ConnectionPhase::Disconnected => return,
+            // 从未连上时直接报告失败，不重连
+            ConnectionPhase::Connecting => {
+                self.phase = ConnectionPhase::Disconnected;
+                self.notices.push(ClientNotice::Failed);
+                return;
+            }
+            ConnectionPhase::Connected => self.notices.push(ClientNotice::Disconnected),
+            ConnectionPhase::Handshaking | ConnectionPhase::Reconnecting { .. } => {}
+        }
+
+        self.failures += 1;
+        if self.failures > timings.reconnect_attempts {
+            self.phase = ConnectionPhase::Disconnected;
+            self.notices.push(ClientNotice::Failed);
+            return;
+        }
+        let attempt = self.failures;
+        let wait = (timings.reconnect_delay * 2f32.powi(attempt as i32 - 1))
+            .min(timings.max_reconnect_delay);
+        self.phase = ConnectionPhase::Reconnecting { attempt, wait };
+        self.notices.push(ClientNotice::Reconnecting(attempt));
+    }
+}
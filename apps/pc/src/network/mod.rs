@@ -0,0 +1,24 @@
+/// 网络模块
+///
+/// 与专用游戏服务器的连接，与局域网联机（coop）相互独立
+///
+/// # 模块组成
+/// 1. protocol：客户端与服务器的消息格式与分帧
+/// 2. transport：TCP 连接，在后台线程建立连接与收发
+/// 3. client：连接生命周期（连接、握手、心跳、断线重连）与收件箱
+/// 4. auth：账号登录与会话令牌
+/// 5. replication：快照复制、远程实体插值与本地玩家的预测校正
+/// 6. systems：网络插件，按网络频率分发收到的消息
+mod auth;
+mod client;
+mod protocol;
+mod replication;
+mod systems;
+mod transport;
+
+pub use auth::*;
+pub use client::*;
+pub use protocol::*;
+pub use replication::*;
+pub use systems::{NetworkCommand, NetworkPlugin};
+pub use transport::*;
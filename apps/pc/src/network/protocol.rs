@@ -0,0 +1,140 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+
+use crate::chat::ChatChannel;
+use crate::world::entity::{CharacterState, NpcType};
+
+/// 与游戏服务器通信的协议版本，双方不一致时服务器拒绝握手
+pub const NETWORK_PROTOCOL_VERSION: u32 = 1;
+
+/// 单帧的最大长度（字节），超过视为数据损坏并断开连接
+pub const MAX_FRAME_LEN: usize = 1 << 20;
+
+/// 客户端发往服务器的消息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClientMessage {
+    /// 连接建立后的握手，重连时附带会话令牌以恢复会话
+    Hello { version: u32, token: Option<String> },
+    /// 心跳，服务器原样带回发送时间用于计算延迟
+    Ping { sent_at: f64 },
+    /// 以账号密码登录，须在握手成功后发送
+    Login { account: String, password: String },
+    /// 本地玩家一个网络节拍内的移动，服务器按序号应用并在快照中确认
+    Input {
+        sequence: u32,
+        movement: [f32; 2],
+        direction: [f32; 2],
+        state: CharacterState,
+    },
+    /// 聊天，私聊时 `target` 为对方名字
+    Chat {
+        channel: ChatChannel,
+        target: Option<String>,
+        text: String,
+    },
+    /// 主动断开
+    Goodbye,
+}
+
+/// 服务器发往客户端的消息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ServerMessage {
+    /// 握手成功，附带服务器分配的客户端编号
+    Welcome { client_id: String, tick_rate: u32 },
+    /// 握手被拒绝，例如协议版本不一致或令牌失效
+    Rejected { reason: String },
+    /// 心跳回应
+    Pong { sent_at: f64 },
+    /// 被服务器踢出，不再自动重连
+    Kicked { reason: String },
+    /// 登录成功，附带会话令牌
+    LoginAccepted { account: String, token: String },
+    /// 登录失败，原因直接显示给玩家
+    LoginDenied { reason: String },
+    /// 服务器按网络频率下发的世界快照
+    Snapshot(WorldSnapshot),
+    /// 其他玩家的聊天，自己发出的消息不会转发回来
+    Chat {
+        channel: ChatChannel,
+        sender: String,
+        text: String,
+    },
+}
+
+/// 网络实体的种类，决定客户端生成什么样的角色
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ReplicatedKind {
+    Player { name: String },
+    Npc { npc_type: NpcType, name: String },
+}
+
+/// 一个网络实体在某一时刻的状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntitySnapshot {
+    /// 服务器分配的实体编号，在一次会话内不变
+    pub net_id: u64,
+    pub kind: ReplicatedKind,
+    pub position: [f32; 2],
+    pub direction: [f32; 2],
+    pub state: CharacterState,
+    pub health: f32,
+    pub max_health: f32,
+}
+
+/// 世界快照
+///
+/// 包含客户端关注范围内的全部网络实体，不在其中的实体视为已离开
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    /// 服务器节拍序号，旧于已收到的快照时丢弃
+    pub tick: u64,
+    /// 服务器生成快照时的时间（秒）
+    pub server_time: f64,
+    /// 本客户端控制的实体编号
+    pub local: Option<u64>,
+    /// 服务器已处理的最后一条输入的序号，为 0 表示还没有处理任何输入
+    pub ack: u32,
+    pub entities: Vec<EntitySnapshot>,
+}
+
+impl ServerMessage {
+    /// 消息类型名，写日志时只记类型，避免令牌等内容落盘
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ServerMessage::Welcome { .. } => "welcome",
+            ServerMessage::Rejected { .. } => "rejected",
+            ServerMessage::Pong { .. } => "pong",
+            ServerMessage::Kicked { .. } => "kicked",
+            ServerMessage::LoginAccepted { .. } => "login_accepted",
+            ServerMessage::LoginDenied { .. } => "login_denied",
+            ServerMessage::Snapshot(_) => "snapshot",
+            ServerMessage::Chat { .. } => "chat",
+        }
+    }
+}
+
+/// 写入一帧：4 字节大端长度后接 bincode 编码的消息
+pub fn write_frame<T: Serialize>(writer: &mut impl Write, message: &T) -> io::Result<()> {
+    let payload =
+        bincode::serialize(message).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    if payload.len() > MAX_FRAME_LEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "消息过长"));
+    }
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(&payload)?;
+    writer.flush()
+}
+
+/// 读取一帧并解码，连接关闭时返回 `UnexpectedEof`
+pub fn read_frame<T: DeserializeOwned>(reader: &mut impl Read) -> io::Result<T> {
+    let mut header = [0u8; 4];
+    reader.read_exact(&mut header)?;
+    let len = u32::from_be_bytes(header) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "消息过长"));
+    }
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload)?;
+    bincode::deserialize(&payload).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,427 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::{
+    ClientMessage, ConnectionPhase, EntitySnapshot, NetworkClient, ReplicatedKind, ServerMessage,
+    WorldSnapshot,
+};
+use crate::config::Settings;
+use crate::events::network::NetworkEvent;
+use crate::resources::GameState;
+use crate::world::entity::{spawn_character, Character, CharacterState, Player};
+
+/// 远程玩家的角色贴图
+const REMOTE_PLAYER_TEXTURE: &str = "textures/characters/player.png";
+
+/// 每个远程实体最多缓存的状态样本数
+const MAX_BUFFERED_SAMPLES: usize = 32;
+
+/// 最多保留的未确认输入数，服务器长时间不确认时丢弃最旧的
+const MAX_PENDING_INPUTS: usize = 256;
+
+/// 预测与服务器结果相差不超过该距离时不校正
+const RECONCILE_TOLERANCE: f32 = 2.0;
+
+/// 预测与服务器结果相差超过该距离时直接拉回，不再平滑
+const RECONCILE_SNAP_DISTANCE: f32 = 128.0;
+
+/// 校正偏移每秒消除的比例
+const CORRECTION_RATE: f32 = 10.0;
+
+/// 网络实体
+///
+/// 由服务器快照生成或认领的实体，编号在一次会话内不变
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Replicated {
+    pub net_id: u64,
+}
+
+/// 远程实体在某一服务器时刻的状态
+#[derive(Debug, Clone, Copy)]
+struct ReplicaSample {
+    time: f64,
+    position: Vec2,
+    direction: Vec2,
+    state: CharacterState,
+}
+
+/// 远程实体收到的状态样本，按服务器时间排列，插值时在两个样本之间取值
+#[derive(Component, Debug, Default)]
+pub struct SnapshotBuffer {
+    samples: VecDeque<ReplicaSample>,
+}
+
+impl SnapshotBuffer {
+    fn push(&mut self, sample: ReplicaSample) {
+        if self
+            .samples
+            .back()
+            .is_some_and(|last| last.time >= sample.time)
+        {
+            return;
+        }
+        self.samples.push_back(sample);
+        while self.samples.len() > MAX_BUFFERED_SAMPLES {
+            self.samples.pop_front();
+        }
+    }
+
+    /// 取 `time` 时刻的状态：落在两个样本之间时线性插值，晚于最新样本时停在最新样本
+    fn sample(&mut self, time: f64) -> Option<ReplicaSample> {
+        // 只保留一个早于 `time` 的样本作为插值起点
+        while self.samples.len() > 1 && self.samples[1].time <= time {
+            self.samples.pop_front();
+        }
+        let from = *self.samples.front()?;
+        let Some(to) = self.samples.get(1) else {
+            return Some(from);
+        };
+        if time <= from.time {
+            return Some(from);
+        }
+        let t = ((time - from.time) / (to.time - from.time)).clamp(0.0, 1.0) as f32;
+        Some(ReplicaSample {
+            time,
+            position: from.position.lerp(to.position, t),
+            direction: if t < 0.5 {
+                from.direction
+            } else {
+                to.direction
+            },
+            state: from.state,
+        })
+    }
+}
+
+/// 复制状态
+///
+/// 记录网络编号与本地实体的对应关系，并估计服务器时钟
+#[derive(Resource, Debug, Default)]
+pub struct ReplicationState {
+    entities: HashMap<u64, Entity>,
+    /// 本客户端控制的实体编号
+    pub local: Option<u64>,
+    last_tick: Option<u64>,
+    /// 服务器时间减本地时间，平滑后的估计
+    clock_offset: Option<f64>,
+}
+
+impl ReplicationState {
+    /// 网络编号对应的本地实体
+    pub fn entity(&self, net_id: u64) -> Option<Entity> {
+        self.entities.get(&net_id).copied()
+    }
+
+    /// 由本地时间估计的服务器时间，尚未收到快照时为空
+    pub fn server_time(&self, now: f64) -> Option<f64> {
+        self.clock_offset.map(|offset| now + offset)
+    }
+
+    fn sync_clock(&mut self, server_time: f64, now: f64) {
+        let offset = server_time - now;
+        self.clock_offset = Some(match self.clock_offset {
+            // 消息按节拍分发会带来抖动，平滑后再用
+            Some(previous) => previous * 0.9 + offset * 0.1,
+            None => offset,
+        });
+    }
+}
+
+/// 本地玩家已发出、尚未被服务器确认的一次移动
+#[derive(Debug, Clone, Copy)]
+struct PendingInput {
+    sequence: u32,
+    movement: Vec2,
+}
+
+/// 本地玩家的预测记录
+///
+/// 本地玩家照常由输入直接移动，同时把每个网络节拍的位移编号发给服务器；
+/// 收到快照时以服务器位置重放未确认的位移，与本地位置不符时校正
+#[derive(Resource, Debug, Default)]
+pub struct PredictionHistory {
+    /// 最近一次发出的输入序号
+    last_sequence: u32,
+    pending: VecDeque<PendingInput>,
+    /// 上一帧记录时本地玩家的位置
+    last_position: Option<Vec2>,
+    /// 本节拍内累计、尚未发出的位移
+    unsent: Vec2,
+    accumulator: f32,
+    /// 尚未消除的校正偏移
+    correction: Vec2,
+}
+
+impl PredictionHistory {
+    fn reset(&mut self) {
+        *self = Self {
+            last_sequence: self.last_sequence,
+            ..default()
+        };
+    }
+
+    /// 以服务器位置重放未确认的位移，得到本地应在的位置
+    fn predict(&mut self, ack: u32, server_position: Vec2) -> Vec2 {
+        while self
+            .pending
+            .front()
+            .is_some_and(|input| input.sequence <= ack)
+        {
+            self.pending.pop_front();
+        }
+        server_position
+            + self
+                .pending
+                .iter()
+                .map(|input| input.movement)
+                .sum::<Vec2>()
+            + self.unsent
+    }
+}
+
+/// 是否在游戏场景中，只有游戏中才生成远程实体
+pub fn in_game_world(state: Res<State<GameState>>) -> bool {
+    matches!(state.get(), GameState::InGame | GameState::Paused)
+}
+
+/// 生成远程实体的角色
+fn spawn_replica(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    snapshot: &EntitySnapshot,
+    time: f64,
+) -> Entity {
+    let (name, texture) = match &snapshot.kind {
+        ReplicatedKind::Player { name } => (name.as_str(), REMOTE_PLAYER_TEXTURE),
+        ReplicatedKind::Npc { npc_type, name } => (name.as_str(), npc_type.texture_path()),
+    };
+    let position = Vec2::from_array(snapshot.position);
+    let entity = spawn_character(commands, asset_server, position.extend(0.0), name, texture);
+    let mut buffer = SnapshotBuffer::default();
+    buffer.push(ReplicaSample {
+        time,
+        position,
+        direction: Vec2::from_array(snapshot.direction),
+        state: snapshot.state,
+    });
+    commands.entity(entity).insert((
+        Replicated {
+            net_id: snapshot.net_id,
+        },
+        buffer,
+    ));
+    entity
+}
+
+/// 应用服务器快照：生成、更新与移除远程实体，并校正本地玩家的预测
+#[allow(clippy::too_many_arguments)]
+pub fn apply_snapshots(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    time: Res<Time<Real>>,
+    mut events: EventReader<NetworkEvent>,
+    mut state: ResMut<ReplicationState>,
+    mut history: ResMut<PredictionHistory>,
+    mut replicas: Query<(&mut SnapshotBuffer, &mut Character), Without<Player>>,
+    mut local: Query<(Entity, &mut Character, &mut Transform, Option<&Replicated>), With<Player>>,
+) {
+    for event in events.read() {
+        let NetworkEvent::Message(ServerMessage::Snapshot(snapshot)) = event else {
+            continue;
+        };
+        if state.last_tick.is_some_and(|tick| snapshot.tick <= tick) {
+            continue;
+        }
+        state.last_tick = Some(snapshot.tick);
+        state.sync_clock(snapshot.server_time, time.elapsed_secs_f64());
+        state.local = snapshot.local;
+
+        let mut seen = HashSet::new();
+        for entity in &snapshot.entities {
+            if Some(entity.net_id) == snapshot.local {
+                reconcile_local(&mut commands, snapshot, entity, &mut history, &mut local);
+                continue;
+            }
+            seen.insert(entity.net_id);
+            let existing = state
+                .entity(entity.net_id)
+                .and_then(|id| replicas.get_mut(id).ok());
+            match existing {
+                Some((mut buffer, mut character)) => {
+                    buffer.push(ReplicaSample {
+                        time: snapshot.server_time,
+                        position: Vec2::from_array(entity.position),
+                        direction: Vec2::from_array(entity.direction),
+                        state: entity.state,
+                    });
+                    character.max_health = entity.max_health;
+                    character.health = entity.health;
+                }
+                None => {
+                    let id =
+                        spawn_replica(&mut commands, &asset_server, entity, snapshot.server_time);
+                    state.entities.insert(entity.net_id, id);
+                }
+            }
+        }
+
+        // 不在快照中的实体已离开关注范围
+        state.entities.retain(|net_id, entity| {
+            if seen.contains(net_id) {
+                return true;
+            }
+            if let Some(entity) = commands.get_entity(*entity) {
+                entity.despawn_recursive();
+            }
+            false
+        });
+    }
+}
+
+/// 认领本地玩家，并以服务器结果校正预测
+fn reconcile_local(
+    commands: &mut Commands,
+    snapshot: &WorldSnapshot,
+    entity: &EntitySnapshot,
+    history: &mut PredictionHistory,
+    local: &mut Query<(Entity, &mut Character, &mut Transform, Option<&Replicated>), With<Player>>,
+) {
+    let Ok((player, mut character, mut transform, replicated)) = local.get_single_mut() else {
+        return;
+    };
+    if replicated.map(|replicated| replicated.net_id) != Some(entity.net_id) {
+        commands.entity(player).insert(Replicated {
+            net_id: entity.net_id,
+        });
+    }
+    character.max_health = entity.max_health;
+    character.health = entity.health;
+
+    let predicted = history.predict(snapshot.ack, Vec2::from_array(entity.position));
+    let current = transform.translation.truncate();
+    let error = predicted - current;
+    if error.length() > RECONCILE_SNAP_DISTANCE {
+        transform.translation.x = predicted.x;
+        transform.translation.y = predicted.y;
+        history.last_position = Some(predicted);
+        history.correction = Vec2::ZERO;
+    } else if error.length() > RECONCILE_TOLERANCE {
+        history.correction = error;
+    } else {
+        history.correction = Vec2::ZERO;
+    }
+}
+
+/// 远程实体按插值延迟回放服务器状态
+///
+/// 渲染时刻比估计的服务器时间晚 `interpolation_delay` 秒，通常已有前后两个快照可以插值
+pub fn interpolate_replicas(
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    state: Res<ReplicationState>,
+    mut replicas: Query<(&mut SnapshotBuffer, &mut Character, &mut Transform), Without<Player>>,
+) {
+    let Some(server_time) = state.server_time(time.elapsed_secs_f64()) else {
+        return;
+    };
+    let render_time = server_time - settings.game.network.interpolation_delay.max(0.0) as f64;
+    for (mut buffer, mut character, mut transform) in replicas.iter_mut() {
+        let Some(sample) = buffer.sample(render_time) else {
+            continue;
+        };
+        transform.translation.x = sample.position.x;
+        transform.translation.y = sample.position.y;
+        if sample.direction != Vec2::ZERO {
+            character.direction = sample.direction;
+        }
+        if character.state != sample.state {
+            character.state = sample.state;
+        }
+    }
+}
+
+/// 记录本地玩家的位移并按网络频率发给服务器，同时逐帧消除校正偏移
+#[allow(clippy::type_complexity)]
+pub fn record_local_input(
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    client: Res<NetworkClient>,
+    state: Res<ReplicationState>,
+    mut history: ResMut<PredictionHistory>,
+    mut local: Query<(&Character, &mut Transform), (With<Player>, With<Replicated>)>,
+) {
+    let Ok((character, mut transform)) = local.get_single_mut() else {
+        return;
+    };
+    if !client.is_connected() || state.local.is_none() {
+        history.reset();
+        return;
+    }
+
+    let position = transform.translation.truncate();
+    let movement = history
+        .last_position
+        .map_or(Vec2::ZERO, |last| position - last);
+    history.unsent += movement;
+
+    let step = history.correction * (CORRECTION_RATE * time.delta_secs()).min(1.0);
+    history.correction -= step;
+    transform.translation.x += step.x;
+    transform.translation.y += step.y;
+    history.last_position = Some(transform.translation.truncate());
+
+    let interval = 1.0 / settings.game.network.tick_rate.max(1) as f32;
+    history.accumulator += time.delta_secs();
+    if history.accumulator < interval {
+        return;
+    }
+    history.accumulator = (history.accumulator - interval).min(interval);
+
+    // 序号从 1 开始，服务器确认 0 表示还没有处理任何输入
+    history.last_sequence = history.last_sequence.wrapping_add(1);
+    let input = PendingInput {
+        sequence: history.last_sequence,
+        movement: history.unsent,
+    };
+    let sent = client.send(ClientMessage::Input {
+        sequence: input.sequence,
+        movement: input.movement.to_array(),
+        direction: character.direction.to_array(),
+        state: character.state,
+    });
+    if !sent {
+        return;
+    }
+    history.unsent = Vec2::ZERO;
+    history.pending.push_back(input);
+    while history.pending.len() > MAX_PENDING_INPUTS {
+        history.pending.pop_front();
+    }
+}
+
+/// 断开连接或离开游戏场景时移除远程实体，本地玩家不再视为网络实体
+pub fn clear_replicas(
+    mut commands: Commands,
+    client: Res<NetworkClient>,
+    game_state: Res<State<GameState>>,
+    mut state: ResMut<ReplicationState>,
+    mut history: ResMut<PredictionHistory>,
+    local: Query<Entity, (With<Player>, With<Replicated>)>,
+) {
+    let offline = client.phase() == ConnectionPhase::Disconnected;
+    let in_world = in_game_world(game_state);
+    if (!offline && in_world) || (state.local.is_none() && state.entities.is_empty()) {
+        return;
+    }
+    for (_, entity) in state.entities.drain() {
+        if let Some(entity) = commands.get_entity(entity) {
+            entity.despawn_recursive();
+        }
+    }
+    for player in local.iter() {
+        commands.entity(player).remove::<Replicated>();
+    }
+    // 重新连上后服务器会从新的节拍开始
+    *state = ReplicationState::default();
+    history.reset();
+}
@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+use super::{
+    apply_snapshots, clear_replicas, handle_login_responses, in_game_world, interpolate_replicas,
+    process_login_requests, record_local_input, ClientNotice, LifecycleTimings, LoginRequest,
+    LoginResult, NetworkClient, PendingLogin, PredictionHistory, ReplicationState,
+};
+use crate::config::{NetworkSettings, Settings};
+use crate::events::network::{NetworkEvent, NetworkState};
+use crate::logging::{GameLogger, LogLevel};
+
+/// 每次分发最多处理的消息数，避免积压的消息在一帧内全部涌出
+const MAX_MESSAGES_PER_TICK: usize = 256;
+
+/// 网络命令
+///
+/// 界面与其他系统通过命令连接服务器，不直接操作客户端
+#[derive(Event, Debug, Clone)]
+pub enum NetworkCommand {
+    /// 连接服务器，地址为空时使用设置中的地址
+    Connect(Option<String>),
+}
+
+/// 网络插件
+///
+/// # 设计思路
+/// 1. 连接、读取与写入都在后台线程完成，游戏线程只通过通道收发，网络卡顿不影响帧率
+/// 2. 消息以长度前缀分帧、bincode 编码，协议版本在握手时核对
+/// 3. 握手成功后按间隔发送心跳，统计延迟与丢失；长时间收不到消息视为断线
+/// 4. 意外断线按指数退避自动重连，重连时带上会话令牌恢复会话
+/// 5. 收到的消息按配置的网络频率分发为 `NetworkEvent`，与服务器的节拍一致
+/// 6. 登录在握手之后进行，成功后保存会话令牌，界面只收发登录事件
+/// 7. 远程实体按快照生成，延后 `interpolation_delay` 秒在前后快照间插值；
+///    本地玩家先行移动，收到快照后重放未确认的输入并平滑校正
+pub struct NetworkPlugin;
+
+impl Plugin for NetworkPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NetworkClient>()
+            .init_resource::<PendingLogin>()
+            .init_resource::<ReplicationState>()
+            .init_resource::<PredictionHistory>()
+            .add_event::<NetworkCommand>()
+            .add_event::<LoginRequest>()
+            .add_event::<LoginResult>()
+            .add_systems(Startup, auto_connect)
+            .add_systems(
+                Update,
+                (
+                    handle_network_commands,
+                    update_network_client,
+                    dispatch_network_messages,
+                    process_login_requests,
+                    handle_login_responses,
+                    apply_snapshots.run_if(in_game_world),
+                    clear_replicas,
+                )
+                    .chain(),
+            )
+            // 在本帧所有移动之后、变换传播之前插值与记录位移
+            .add_systems(
+                PostUpdate,
+                (
+                    interpolate_replicas.run_if(in_game_world),
+                    record_local_input,
+                )
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+fn timings(settings: &NetworkSettings) -> LifecycleTimings {
+    LifecycleTimings {
+        heartbeat_interval: settings.heartbeat_interval.max(0.1),
+        timeout: settings.timeout.max(1.0),
+        reconnect_delay: settings.reconnect_delay.max(0.1),
+        max_reconnect_delay: settings.max_reconnect_delay,
+        reconnect_attempts: settings.reconnect_attempts,
+    }
+}
+
+/// 设置中开启自动连接时，启动后即连接服务器
+fn auto_connect(settings: Res<Settings>, mut commands: EventWriter<NetworkCommand>) {
+    if settings.game.network.auto_connect {
+        commands.send(NetworkCommand::Connect(None));
+    }
+}
+
+fn handle_network_commands(
+    settings: Res<Settings>,
+    mut commands: EventReader<NetworkCommand>,
+    mut client: ResMut<NetworkClient>,
+    mut state: ResMut<NetworkState>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for command in commands.read() {
+        match command {
+            NetworkCommand::Connect(address) => {
+                let address = address
+                    .clone()
+                    .unwrap_or_else(|| settings.game.network.server_address.clone());
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(LogLevel::Info, &format!("连接服务器 {}", address));
+                }
+                client.connect(&address);
+                state.server_address = address;
+            }
+        }
+    }
+}
+
+/// 推进连接生命周期，把连接变化转换为网络事件，并更新延迟统计
+fn update_network_client(
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    mut client: ResMut<NetworkClient>,
+    mut state: ResMut<NetworkState>,
+    mut events: EventWriter<NetworkEvent>,
+) {
+    let timings = timings(&settings.game.network);
+    client.update(time.delta_secs(), time.elapsed_secs_f64(), &timings);
+
+    for notice in client.take_notices() {
+        events.send(match notice {
+            ClientNotice::Connected => NetworkEvent::ConnectionSuccess,
+            ClientNotice::Failed => NetworkEvent::ConnectionFailed,
+            ClientNotice::Disconnected => NetworkEvent::Disconnection,
+            ClientNotice::Reconnecting(attempt) => NetworkEvent::Reconnecting(attempt),
+        });
+    }
+
+    if client.is_connected() {
+        state.client_id = client.client_id.clone().unwrap_or_default();
+        state.latency = client.latency;
+        state.packet_loss = client.packet_loss;
+        state.last_ping = client.last_pong as f32;
+    }
+}
+
+/// 按网络频率把收到的消息分发为 `NetworkEvent::Message`
+fn dispatch_network_messages(
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    mut client: ResMut<NetworkClient>,
+    mut accumulator: Local<f32>,
+    mut events: EventWriter<NetworkEvent>,
+) {
+    let interval = 1.0 / settings.game.network.tick_rate.max(1) as f32;
+    *accumulator += time.delta_secs();
+    if *accumulator < interval {
+        return;
+    }
+    // 帧率低于网络频率时一帧内补齐，但不累积超过一个节拍
+    *accumulator = (*accumulator - interval).min(interval);
+
+    for message in client.take_inbox(MAX_MESSAGES_PER_TICK) {
+        events.send(NetworkEvent::Message(message));
+    }
+}
@@ -0,0 +1,180 @@
+use std::io::{self, BufReader, BufWriter};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{read_frame, write_frame, ClientMessage, ServerMessage};
+
+/// 连接服务器的超时时长
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 连接的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkStatus {
+    /// 正在后台建立连接
+    Connecting,
+    /// 连接已建立，可以收发
+    Open,
+    /// 连接未能建立
+    Failed,
+    /// 连接建立后断开
+    Closed,
+}
+
+impl LinkStatus {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => LinkStatus::Connecting,
+            1 => LinkStatus::Open,
+            2 => LinkStatus::Failed,
+            _ => LinkStatus::Closed,
+        }
+    }
+}
+
+/// 与游戏服务器的一条 TCP 连接
+///
+/// 建立连接、读取与写入都在后台线程进行，游戏线程只与通道交互，不会因网络卡顿；
+/// 发送的消息排队后由写入线程按顺序写出
+pub struct ServerConnection {
+    outgoing: Sender<ClientMessage>,
+    incoming: Mutex<Receiver<ServerMessage>>,
+    status: Arc<AtomicU8>,
+    stream: Arc<Mutex<Option<TcpStream>>>,
+}
+
+impl ServerConnection {
+    /// 开始连接服务器，立即返回，通过 `status` 查看是否连上
+    pub fn open(address: &str) -> Self {
+        let status = Arc::new(AtomicU8::new(LinkStatus::Connecting as u8));
+        let stream_slot: Arc<Mutex<Option<TcpStream>>> = Arc::new(Mutex::new(None));
+        let (outgoing, outgoing_receiver) = mpsc::channel::<ClientMessage>();
+        let (incoming_sender, incoming) = mpsc::channel();
+
+        let thread_address = address.to_string();
+        let thread_status = status.clone();
+        let thread_slot = stream_slot.clone();
+        std::thread::spawn(move || {
+            let stream = match connect(&thread_address) {
+                Ok(stream) => stream,
+                Err(_) => {
+                    thread_status.store(LinkStatus::Failed as u8, Ordering::Relaxed);
+                    return;
+                }
+            };
+            let (Ok(reader), Ok(writer)) = (stream.try_clone(), stream.try_clone()) else {
+                thread_status.store(LinkStatus::Failed as u8, Ordering::Relaxed);
+                return;
+            };
+            if let Ok(mut slot) = thread_slot.lock() {
+                *slot = Some(stream);
+            }
+            // 连接期间已被关闭时不再启动读写
+            if thread_status
+                .compare_exchange(
+                    LinkStatus::Connecting as u8,
+                    LinkStatus::Open as u8,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                )
+                .is_err()
+            {
+                shutdown(&thread_slot);
+                return;
+            }
+
+            let reader_status = thread_status.clone();
+            std::thread::spawn(move || {
+                let mut reader = BufReader::new(reader);
+                while let Ok(message) = read_frame::<ServerMessage>(&mut reader) {
+                    if incoming_sender.send(message).is_err() {
+                        break;
+                    }
+                }
+                reader_status.store(LinkStatus::Closed as u8, Ordering::Relaxed);
+            });
+
+            // 连接对象销毁时发送端随之关闭，写入线程退出
+            let mut writer = BufWriter::new(writer);
+            for message in outgoing_receiver {
+                if write_frame(&mut writer, &message).is_err() {
+                    break;
+                }
+            }
+            shutdown(&thread_slot);
+            thread_status.store(LinkStatus::Closed as u8, Ordering::Relaxed);
+        });
+
+        Self {
+            outgoing,
+            incoming: Mutex::new(incoming),
+            status,
+            stream: stream_slot,
+        }
+    }
+
+    /// 连接状态
+    pub fn status(&self) -> LinkStatus {
+        LinkStatus::from_u8(self.status.load(Ordering::Relaxed))
+    }
+
+    /// 排队发送一条消息，连接尚未建立时在建立后发出
+    pub fn send(&self, message: ClientMessage) -> bool {
+        self.outgoing.send(message).is_ok()
+    }
+
+    /// 取出目前已收到的全部消息
+    ///
+    /// 断开前收到的消息仍可以取出，调用方应先处理消息再检查状态
+    pub fn drain(&self) -> Vec<ServerMessage> {
+        let Ok(receiver) = self.incoming.lock() else {
+            return Vec::new();
+        };
+        let mut messages = Vec::new();
+        while let Ok(message) = receiver.try_recv() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    /// 关闭连接，读写线程随之退出
+    pub fn close(&self) {
+        shutdown(&self.stream);
+        if self.status() != LinkStatus::Failed {
+            self.status
+                .store(LinkStatus::Closed as u8, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for ServerConnection {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// 关闭套接字，阻塞中的读取随之返回
+fn shutdown(slot: &Mutex<Option<TcpStream>>) {
+    if let Ok(slot) = slot.lock() {
+        if let Some(stream) = slot.as_ref() {
+            let _ = stream.shutdown(std::net::Shutdown::Both);
+        }
+    }
+}
+
+/// 解析地址并带超时地连接，依次尝试解析出的每个地址
+fn connect(address: &str) -> io::Result<TcpStream> {
+    let mut last_error = io::Error::new(io::ErrorKind::InvalidInput, "无法解析服务器地址");
+    for address in address.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&address, CONNECT_TIMEOUT) {
+            Ok(stream) => {
+                stream.set_nodelay(true)?;
+                return Ok(stream);
+            }
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
@@ -1,44 +1,102 @@
-use crate::config::GameSettings;
+use crate::analytics::{PlaytestPlugin, PlaytestSettings};
+use crate::assets::AssetManagerPlugin;
+use crate::audio::{CaptionSettings, GameAudioPlugin};
+use crate::chat::ChatPlugin;
+use crate::chatter::ChatterPlugin;
+use crate::combat::CombatPlugin;
+use crate::config::{ConfigManager, Settings, SettingsPlugin, SettingsWatcher};
+use crate::coop::{CoopCommand, CoopPlugin, CoopSettings, QuestShareRule};
+use crate::crash::{CrashPlugin, CrashSettings};
 use crate::events::{input::*, network::*, window::*};
-use crate::resources::{GameState, GlobalGameState, InputState};
+use crate::headless::{HeadlessPlugin, HeadlessSettings};
+use crate::housing::HousingPlugin;
+use crate::hud::HudPlugin;
+use crate::interaction::InteractionPlugin;
+use crate::items::ItemsPlugin;
+use crate::loading::LoadingPlugin;
+use crate::logging::{DiagnosticsSettings, GameLogger, LogSinks};
+use crate::metrics::{MetricsPlugin, MetricsSettings};
+use crate::network::NetworkPlugin;
+use crate::profiler::{system_span_layer, ProfilerPlugin};
+use crate::render::GameRenderPlugin;
+use crate::resources::{
+    DifficultyModifiers, GameState, GlobalGameState, InputState, PauseSettings,
+};
+use crate::rest::RestPlugin;
+use crate::save::{AutosaveSettings, SavePlugin};
+use crate::scripting::ScriptPlugin;
+use crate::time::GameTimePlugin;
+use crate::ui::{GameUiPlugin, UiThemeSettings};
+use crate::world::map::{DialoguePlugin, QuestPlugin, WorldConfig};
+use crate::world::WorldPlugin;
+use crate::world::physics::PhysicsOptions;
+use bevy::app::{AppExit, ScheduleRunnerPlugin};
+use bevy::log::LogPlugin;
 use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
 use bevy::window::WindowMode;
+use std::time::Duration;
 
 use super::logging_plugin::LoggingPlugin;
 
 pub struct GamePluginManager;
 
 impl GamePluginManager {
-    pub fn run(settings: &GameSettings) {
+    pub fn run(config: ConfigManager, coop: Option<CoopCommand>) {
+        let settings = config.get_settings().clone();
+        let config_type = config.config_type();
         let mut app = App::new();
 
+        // 日志最先加入，引擎插件初始化时的日志也能收到
+        app.add_plugins(LoggingPlugin {
+            // 收集系统耗时供性能分析使用，未开启性能分析时不记录
+            custom_layer: system_span_layer,
+        });
+        // 紧接着装上崩溃处理，之后的崩溃都能附带日志
+        app.add_plugins(CrashPlugin);
+
         // 添加基础插件组
-        app.add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: settings.window.title.clone(),
-                resolution: (settings.window.width as f32, settings.window.height as f32).into(),
-                present_mode: if settings.window.vsync {
-                    bevy::window::PresentMode::AutoVsync
-                } else {
-                    bevy::window::PresentMode::AutoNoVsync
-                },
-                mode: if settings.window.fullscreen {
-                    WindowMode::BorderlessFullscreen(MonitorSelection::Primary)
-                } else {
-                    WindowMode::Windowed
-                },
-                resizable: true,
-                ..default()
-            }),
-            ..default()
-        }));
+        app.add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: settings.window.title.clone(),
+                        resolution: (settings.window.width as f32, settings.window.height as f32).into(),
+                        present_mode: if settings.window.vsync {
+                            bevy::window::PresentMode::AutoVsync
+                        } else {
+                            bevy::window::PresentMode::AutoNoVsync
+                        },
+                        mode: if settings.window.fullscreen {
+                            WindowMode::BorderlessFullscreen(MonitorSelection::Primary)
+                        } else {
+                            WindowMode::Windowed
+                        },
+                        resizable: true,
+                        ..default()
+                    }),
+                    ..default()
+                })
+                .set(AssetPlugin {
+                    // 开发模式下监听资源文件，任务等数据保存后即时生效
+                    watch_for_changes_override: Some(settings.development.hot_reload),
+                    ..default()
+                })
+                // 日志由自己的日志插件接管
+                .disable::<LogPlugin>(),
+        );
 
         // 添加状态
         app.init_state::<GameState>();
 
         // 添加资源
-        app.init_resource::<GlobalGameState>()
+        app.insert_resource(Settings::new(settings.clone()))
+            .init_resource::<GlobalGameState>()
             .init_resource::<InputState>()
+            .init_resource::<DifficultyModifiers>()
+            .init_resource::<PauseSettings>()
+            .insert_resource(WorldConfig::default_wuxia())
             .init_resource::<NetworkState>()
             .init_resource::<KeyBindings>();
 
@@ -50,6 +108,8 @@ impl GamePluginManager {
             Update,
             (
                 handle_window_events,
+                track_gamepads,
+                apply_key_binding_settings,
                 handle_input_events,
                 handle_network_events,
             )
@@ -58,9 +118,73 @@ impl GamePluginManager {
 
         // 添加游戏核心插件
         app.add_plugins((
-            LoggingPlugin::default(),
+            GameTimePlugin,
+            GameRenderPlugin,
+            GameUiPlugin,
+            ItemsPlugin,
+            HousingPlugin,
+            RestPlugin,
+            GameAudioPlugin,
+            CombatPlugin,
+            ChatterPlugin,
+            PlaytestPlugin,
+            CoopPlugin,
+            QuestPlugin,
+            DialoguePlugin,
+            ScriptPlugin,
+            SavePlugin,
         ));
 
+        // 物理选项在物理插件之前放入，插件不会再用默认值覆盖
+        app.insert_resource(PhysicsOptions {
+            timestep: settings.physics.timestep,
+            gravity: settings.physics.gravity,
+            debug_draw: settings.physics.debug_draw,
+        });
+
+        // 地图、区块、天气、NPC、物理等世界系统
+        app.add_plugins(WorldPlugin);
+
+        // 与游戏服务器的连接
+        app.add_plugins(NetworkPlugin);
+
+        // 本地与联网聊天
+        app.add_plugins(ChatPlugin);
+
+        // 主菜单与游戏之间的加载状态
+        app.add_plugins(LoadingPlugin);
+
+        // 按游戏状态分组预载贴图，跟踪加载进度并报告缺失的资源
+        app.add_plugins(AssetManagerPlugin);
+
+        // 交互键统一找出最近的一个对象再分派给对应玩法
+        app.add_plugins(InteractionPlugin);
+
+        // 游戏中常驻屏幕的气血、快捷栏、任务目标与交互提示
+        app.add_plugins(HudPlugin);
+
+        // 开发与调试时的性能面板和帧时间录制
+        if settings.development.profiler {
+            app.add_plugins(ProfilerPlugin);
+        }
+
+        // 玩家或测试机开启后才采集运行指标
+        if settings.metrics.enabled {
+            app.add_plugins(MetricsPlugin);
+        }
+
+        // 运行时设置与配置热重载
+        app.add_plugins(SettingsPlugin);
+        if settings.development.hot_reload {
+            if let Some(watcher) = SettingsWatcher::new(config) {
+                app.insert_resource(watcher);
+            }
+        }
+
+        // 服务器构建才校验客户端请求
+        #[cfg(feature = "server")]
+        app.add_plugins(crate::server::AntiCheatPlugin);
+
         // 设置调试标志
         if settings.graphics.debug_rendering {
             if let Some(mut state) = app.world_mut().get_resource_mut::<GlobalGameState>() {
@@ -68,7 +192,128 @@ impl GamePluginManager {
             }
         }
 
+        // 无障碍选项
+        if let Some(mut captions) = app.world_mut().get_resource_mut::<CaptionSettings>() {
+            captions.enabled = settings.accessibility.captions;
+        }
+
+        // 界面主题
+        if let Some(mut theme) = app.world_mut().get_resource_mut::<UiThemeSettings>() {
+            theme.theme = settings.interface.theme.clone();
+        }
+
+        // 试玩数据记录，隐私选项不允许时不记录
+        if let Some(mut playtest) = app.world_mut().get_resource_mut::<PlaytestSettings>() {
+            playtest.enabled = settings.analytics.playtest && settings.privacy.playtest_data;
+        }
+
+        // 隐私选项
+        if !settings.privacy.file_logs {
+            if let Some(mut logger) = app.world_mut().get_resource_mut::<GameLogger>() {
+                logger.set_file_output(false);
+            }
+        }
+        if let Some(mut diagnostics) = app.world_mut().get_resource_mut::<DiagnosticsSettings>() {
+            diagnostics.include_system_info = settings.privacy.system_info;
+            diagnostics.include_playtest = settings.privacy.playtest_data;
+        }
+
+        // 自动存档选项
+        if let Some(mut autosave) = app.world_mut().get_resource_mut::<AutosaveSettings>() {
+            autosave.enabled = settings.autosave.enabled;
+            autosave.interval = settings.autosave.interval.max(0.0);
+            autosave.on_scene_enter = settings.autosave.on_scene_enter;
+            autosave.before_boss = settings.autosave.before_boss;
+            autosave.slots = settings.autosave.slots.max(1);
+        }
+
+        // 崩溃处理选项
+        if let Some(mut crash) = app.world_mut().get_resource_mut::<CrashSettings>() {
+            crash.show_dialog = settings.crash.show_dialog;
+            crash.recover_chunks = settings.crash.recover_chunks;
+            crash.build = format!("{}，运行模式：{}", crash.build, config_type.name());
+        }
+
+        // 运行指标选项，未开启时没有这个资源
+        if let Some(mut metrics) = app.world_mut().get_resource_mut::<MetricsSettings>() {
+            metrics.prometheus_port = settings.metrics.prometheus_port;
+            metrics.dump_interval = settings.metrics.dump_interval;
+            metrics.dump_dir = settings.metrics.dump_dir.clone();
+            metrics.mode = config_type.name().to_string();
+        }
+
+        // 联机选项
+        if let Some(mut coop_settings) = app.world_mut().get_resource_mut::<CoopSettings>() {
+            coop_settings.player_name = settings.coop.player_name.clone();
+            coop_settings.port = settings.coop.port;
+            coop_settings.max_players = settings.coop.max_players.max(2);
+            coop_settings.quest_share =
+                QuestShareRule::from_name(&settings.coop.quest_share).unwrap_or_default();
+            coop_settings.allow_spectators = settings.coop.allow_spectators;
+        }
+
+        // 启动参数指定的联机指令在第一帧执行
+        if let Some(command) = coop {
+            app.world_mut().send_event(command);
+        }
+
         // 运行游戏
+        let sinks = log_sinks(&app);
         app.run();
+        flush_logs(sinks);
+    }
+
+    /// 不开窗口运行模拟，跑完指定帧数后返回
+    pub fn run_headless(config: ConfigManager, headless: HeadlessSettings) -> AppExit {
+        let settings = config.get_settings().clone();
+        let mut app = App::new();
+
+        app.add_plugins(LoggingPlugin::default());
+
+        // 不开窗口也不渲染，帧与帧之间不等待
+        app.add_plugins((
+            MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::ZERO)),
+            StatesPlugin,
+        ));
+        // 每帧推进固定的游戏时间，结果与机器快慢无关
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            headless.tick_duration(),
+        ));
+
+        // 直接处于游戏中，AI 与历法按游戏状态推进
+        app.insert_state(GameState::InGame);
+
+        app.insert_resource(Settings::new(settings.clone()))
+            .init_resource::<GlobalGameState>()
+            .init_resource::<InputState>();
+
+        app.add_plugins((GameTimePlugin, HeadlessPlugin));
+        app.insert_resource(headless);
+
+        // 隐私选项
+        if !settings.privacy.file_logs {
+            if let Some(mut logger) = app.world_mut().get_resource_mut::<GameLogger>() {
+                logger.set_file_output(false);
+            }
+        }
+
+        let sinks = log_sinks(&app);
+        let exit = app.run();
+        flush_logs(sinks);
+        exit
+    }
+}
+
+/// 运行前取出日志输出，运行结束后应用的世界已交给运行器，取不到日志资源
+fn log_sinks(app: &App) -> Option<LogSinks> {
+    app.world().get_resource::<GameLogger>().map(GameLogger::sinks)
+}
+
+/// 日志在后台线程写出，退出前等它写完，最多等待两秒
+fn flush_logs(sinks: Option<LogSinks>) {
+    if let Some(sinks) = sinks {
+        if !sinks.flush(Duration::from_secs(2)) {
+            eprintln!("退出时仍有日志未写完");
+        }
     }
 }
@@ -1,17 +1,34 @@
-use crate::logging::{GameLogger, LogConfig};
+use crate::logging::{
+    export_bug_bundle, request_bug_bundle, BugBundleRequest, DiagnosticIncidents,
+    DiagnosticsSettings, GameLogger, LogConfig, LogScrubber,
+};
+use bevy::log::BoxedLayer;
 use bevy::prelude::*;
 
 /// 日志系统插件
-pub struct LoggingPlugin;
+///
+/// 取代引擎自带的日志插件，须在其他插件之前加入，引擎初始化时的日志才能被收到
+pub struct LoggingPlugin {
+    /// 装在日志过滤层之下的自定义层，例如性能分析收集系统耗时
+    pub custom_layer: fn(&mut App) -> Option<BoxedLayer>,
+}
 
 impl Plugin for LoggingPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(GameLogger::new(LogConfig::default()));
+        let custom_layer = (self.custom_layer)(app);
+        app.insert_resource(GameLogger::install(LogConfig::default(), custom_layer))
+            .init_resource::<LogScrubber>()
+            .init_resource::<DiagnosticsSettings>()
+            .init_resource::<DiagnosticIncidents>()
+            .add_event::<BugBundleRequest>()
+            .add_systems(Update, (request_bug_bundle, export_bug_bundle).chain());
     }
 }
 
 impl Default for LoggingPlugin {
     fn default() -> Self {
-        Self
+        Self {
+            custom_layer: |_| None,
+        }
     }
 }
@@ -0,0 +1,188 @@
+use bevy::diagnostic::{
+    DiagnosticPath, DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin,
+};
+use bevy::prelude::*;
+
+use super::{ProfilerSettings, ProfilerState, ARCHETYPE_COUNT};
+use crate::events::input::GameAction;
+use crate::resources::InputState;
+use crate::ui::{label, panel, TextRole};
+
+/// 面板文字的刷新间隔（秒）
+const HUD_REFRESH: f32 = 0.5;
+
+/// 性能面板的显示方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProfilerHudMode {
+    #[default]
+    Hidden,
+    /// 只显示帧率、实体与原型数
+    Collapsed,
+    /// 另外列出最耗时的系统
+    Expanded,
+}
+
+impl ProfilerHudMode {
+    fn next(self) -> Self {
+        match self {
+            ProfilerHudMode::Hidden => ProfilerHudMode::Collapsed,
+            ProfilerHudMode::Collapsed => ProfilerHudMode::Expanded,
+            ProfilerHudMode::Expanded => ProfilerHudMode::Hidden,
+        }
+    }
+}
+
+/// 性能面板
+#[derive(Resource, Debug, Default)]
+pub struct ProfilerHud {
+    pub mode: ProfilerHudMode,
+}
+
+/// 性能面板根节点
+#[derive(Component)]
+pub struct ProfilerPanel;
+
+/// 去掉系统名中的模块路径，泛型参数里的路径一并去掉
+fn short_system_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut segment = String::new();
+    for c in name.chars() {
+        match c {
+            '<' | '>' | ',' | ' ' | '(' | ')' => {
+                short.push_str(&segment);
+                segment.clear();
+                short.push(c);
+            }
+            ':' => segment.clear(),
+            _ => segment.push(c),
+        }
+    }
+    short.push_str(&segment);
+    short
+}
+
+/// 按键在隐藏、收起、展开之间切换
+pub fn toggle_profiler_hud(
+    mut commands: Commands,
+    input_state: Res<InputState>,
+    mut hud: ResMut<ProfilerHud>,
+    panels: Query<Entity, With<ProfilerPanel>>,
+) {
+    if !input_state.is_action_just_pressed(GameAction::ToggleProfiler) {
+        return;
+    }
+    hud.mode = hud.mode.next();
+    if hud.mode == ProfilerHudMode::Hidden {
+        for panel in panels.iter() {
+            commands.entity(panel).despawn_recursive();
+        }
+    } else if panels.is_empty() {
+        commands.spawn((
+            ProfilerPanel,
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(12.0),
+                bottom: Val::Px(12.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(8.0)),
+                row_gap: Val::Px(2.0),
+                ..default()
+            },
+            panel(),
+            GlobalZIndex(20),
+            Name::new("Profiler"),
+        ));
+    }
+}
+
+/// 按间隔刷新面板，切换显示方式时立即刷新
+#[allow(clippy::too_many_arguments)]
+pub fn update_profiler_hud(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    settings: Res<ProfilerSettings>,
+    hud: Res<ProfilerHud>,
+    state: Res<ProfilerState>,
+    store: Res<DiagnosticsStore>,
+    panels: Query<Entity, With<ProfilerPanel>>,
+    mut elapsed: Local<f32>,
+) {
+    let Ok(panel) = panels.get_single() else {
+        return;
+    };
+    *elapsed += time.delta_secs();
+    if *elapsed < HUD_REFRESH && !hud.is_changed() {
+        return;
+    }
+    *elapsed = 0.0;
+
+    let smoothed = |path: &DiagnosticPath| {
+        store
+            .get(path)
+            .and_then(|diagnostic| diagnostic.smoothed())
+            .unwrap_or_default()
+    };
+    let mut lines = vec![
+        (
+            format!(
+                "帧率 {:.0} · 帧时间 {:.2} 毫秒",
+                smoothed(&FrameTimeDiagnosticsPlugin::FPS),
+                smoothed(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+            ),
+            TextRole::Body,
+        ),
+        (
+            format!(
+                "实体 {:.0} · 原型 {:.0}",
+                smoothed(&EntityCountDiagnosticsPlugin::ENTITY_COUNT),
+                smoothed(&ARCHETYPE_COUNT)
+            ),
+            TextRole::Body,
+        ),
+    ];
+    if let Some(capture) = state.capture() {
+        lines.push((
+            format!("录制帧时间中，还剩 {} 帧", capture.frames_left()),
+            TextRole::Small,
+        ));
+    }
+
+    if hud.mode == ProfilerHudMode::Expanded {
+        let mut systems: Vec<(&str, f64)> = state
+            .system_paths()
+            .filter_map(|(name, path)| {
+                store
+                    .get(path)
+                    .and_then(|diagnostic| diagnostic.average())
+                    .map(|average| (name, average))
+            })
+            .collect();
+        systems.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        lines.push((
+            format!("系统耗时（近 {} 帧平均，毫秒）", settings.history),
+            TextRole::Small,
+        ));
+        if systems.is_empty() {
+            lines.push((
+                "没有系统耗时，需开启 profiling 功能编译".to_string(),
+                TextRole::Muted,
+            ));
+        }
+        for (name, average) in systems.into_iter().take(settings.top_systems) {
+            lines.push((
+                format!("{:>7.3}  {}", average, short_system_name(name)),
+                TextRole::Small,
+            ));
+        }
+    }
+    lines.push(("F6 展开或收起 · F7 录制帧时间".to_string(), TextRole::Muted));
+
+    commands.entity(panel).despawn_descendants();
+    commands.entity(panel).with_children(|panel| {
+        panel.spawn(label("性能分析", TextRole::Title));
+        for (text, role) in lines {
+            panel.spawn(label(text, role));
+        }
+    });
+}
@@ -0,0 +1,19 @@
+/// 性能分析模块
+///
+/// 开发与调试时使用：统计每个系统的耗时、实体与原型数，在可收起的面板中显示，
+/// 并可按需把一段帧时间录制成 chrome tracing 文件
+///
+/// # 模块组成
+/// 1. spans：挂在日志插件上的追踪层，收集调度器为每个系统建立的 span 耗时
+/// 2. trace：chrome tracing 格式的帧时间录制与导出
+/// 3. hud：屏幕右下的性能面板
+/// 4. systems：性能分析插件，把耗时登记为诊断项并驱动录制
+mod hud;
+mod spans;
+mod systems;
+mod trace;
+
+pub use hud::*;
+pub use spans::*;
+pub use systems::*;
+pub use trace::*;
@@ -0,0 +1,165 @@
+use bevy::log::tracing_subscriber::layer::{Context, Layer};
+use bevy::log::tracing_subscriber::registry::LookupSpan;
+use bevy::log::BoxedLayer;
+use bevy::prelude::*;
+use bevy::utils::tracing::field::{Field, Visit};
+use bevy::utils::tracing::span::{Attributes, Id};
+use bevy::utils::tracing::Subscriber;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 系统运行一次的耗时
+#[derive(Debug, Clone)]
+pub struct SpanRecord {
+    pub name: String,
+    pub start: Instant,
+    pub duration: Duration,
+    /// 运行所在的线程，从 1 开始编号，0 留给帧
+    pub thread: u32,
+}
+
+#[derive(Default)]
+struct SinkInner {
+    enabled: AtomicBool,
+    records: Mutex<Vec<SpanRecord>>,
+}
+
+/// 日志层与性能分析系统共用的耗时记录
+///
+/// 分析插件开启前不收集，避免无人读取时记录越攒越多
+#[derive(Resource, Clone, Default)]
+pub struct SpanSink {
+    inner: Arc<SinkInner>,
+}
+
+impl SpanSink {
+    pub fn set_enabled(&self, enabled: bool) {
+        self.inner.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.inner.enabled.load(Ordering::Relaxed)
+    }
+
+    fn push(&self, record: SpanRecord) {
+        if let Ok(mut records) = self.inner.records.lock() {
+            records.push(record);
+        }
+    }
+
+    /// 取出上次以来的所有记录
+    pub fn drain(&self) -> Vec<SpanRecord> {
+        self.inner
+            .records
+            .lock()
+            .map(|mut records| std::mem::take(&mut *records))
+            .unwrap_or_default()
+    }
+}
+
+/// 挂在系统 span 上的名字与进入时间
+struct SystemSpan {
+    name: String,
+    entered: Option<Instant>,
+}
+
+#[derive(Default)]
+struct NameVisitor(Option<String>);
+
+impl Visit for NameVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "name" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "name" && self.0.is_none() {
+            self.0 = Some(format!("{:?}", value));
+        }
+    }
+}
+
+/// 当前线程的编号
+fn thread_index() -> u32 {
+    static NEXT: AtomicU32 = AtomicU32::new(1);
+    thread_local! {
+        static INDEX: Cell<u32> = const { Cell::new(0) };
+    }
+    INDEX.with(|index| {
+        if index.get() == 0 {
+            index.set(NEXT.fetch_add(1, Ordering::Relaxed));
+        }
+        index.get()
+    })
+}
+
+/// 记录系统耗时的日志层
+///
+/// 调度器在开启 bevy 的 trace 功能时为每个系统建立名为 `system` 的 span，
+/// 每次运行进入、退出一次，两者之差就是这次运行的耗时
+struct SystemSpanLayer {
+    sink: SpanSink,
+}
+
+impl<S> Layer<S> for SystemSpanLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != "system" {
+            return;
+        }
+        let mut visitor = NameVisitor::default();
+        attrs.record(&mut visitor);
+        if let (Some(name), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().insert(SystemSpan {
+                name,
+                entered: None,
+            });
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if !self.sink.is_enabled() {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(system) = extensions.get_mut::<SystemSpan>() {
+            system.entered = Some(Instant::now());
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        let Some(system) = extensions.get_mut::<SystemSpan>() else {
+            return;
+        };
+        let Some(start) = system.entered.take() else {
+            return;
+        };
+        self.sink.push(SpanRecord {
+            name: system.name.clone(),
+            start,
+            duration: start.elapsed(),
+            thread: thread_index(),
+        });
+    }
+}
+
+/// 给日志插件的自定义层，同时把耗时记录放进资源
+///
+/// 未开启 `profiling` 功能编译时调度器不建立系统 span，这一层什么也收不到
+pub fn system_span_layer(app: &mut App) -> Option<BoxedLayer> {
+    let sink = SpanSink::default();
+    app.insert_resource(sink.clone());
+    Some(Box::new(SystemSpanLayer { sink }))
+}
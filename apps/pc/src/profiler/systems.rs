@@ -0,0 +1,197 @@
+use bevy::diagnostic::{
+    Diagnostic, DiagnosticMeasurement, DiagnosticPath, DiagnosticsStore,
+    EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin, RegisterDiagnostic,
+};
+use bevy::ecs::archetype::Archetypes;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::time::Instant;
+
+use super::{toggle_profiler_hud, update_profiler_hud, ProfilerHud, SpanSink, TraceCapture};
+use crate::events::input::GameAction;
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::InputState;
+
+/// 原型数量
+pub const ARCHETYPE_COUNT: DiagnosticPath = DiagnosticPath::const_new("archetype_count");
+
+/// 性能分析设置
+#[derive(Resource, Debug, Clone)]
+pub struct ProfilerSettings {
+    /// 帧时间录制的导出目录
+    pub trace_dir: String,
+    /// 一次录制的帧数
+    pub trace_frames: usize,
+    /// 系统耗时保留的帧数，面板按这些帧求平均
+    pub history: usize,
+    /// 面板展开时列出的系统数
+    pub top_systems: usize,
+}
+
+impl Default for ProfilerSettings {
+    fn default() -> Self {
+        Self {
+            trace_dir: "profiles".to_string(),
+            trace_frames: 300,
+            history: 120,
+            top_systems: 12,
+        }
+    }
+}
+
+/// 性能分析状态
+#[derive(Resource, Debug, Default)]
+pub struct ProfilerState {
+    /// 上一帧开始的时间
+    frame_start: Option<Instant>,
+    /// 已登记诊断项的系统
+    system_paths: HashMap<String, DiagnosticPath>,
+    /// 进行中的帧时间录制
+    capture: Option<TraceCapture>,
+}
+
+impl ProfilerState {
+    /// 已登记诊断项的系统名与诊断路径
+    pub fn system_paths(&self) -> impl Iterator<Item = (&str, &DiagnosticPath)> {
+        self.system_paths
+            .iter()
+            .map(|(name, path)| (name.as_str(), path))
+    }
+
+    pub fn capture(&self) -> Option<&TraceCapture> {
+        self.capture.as_ref()
+    }
+}
+
+/// 性能分析插件
+///
+/// # 设计思路
+/// 1. 帧时间与实体数用 bevy 自带的诊断插件，原型数与每个系统的耗时登记为自定义诊断项，面板统一从诊断中读取
+/// 2. 系统耗时来自调度器的 trace span，由日志层收集后每帧汇总一次；同一帧内多次运行的系统耗时相加
+/// 3. 按键开始录制，录满指定帧数后写成 chrome tracing 文件，录制期间不影响面板
+/// 4. 只在开发与调试配置中开启，系统耗时还需以 `profiling` 功能编译
+pub struct ProfilerPlugin;
+
+impl Plugin for ProfilerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((FrameTimeDiagnosticsPlugin, EntityCountDiagnosticsPlugin))
+            .register_diagnostic(Diagnostic::new(ARCHETYPE_COUNT))
+            .init_resource::<ProfilerSettings>()
+            .init_resource::<ProfilerState>()
+            .init_resource::<ProfilerHud>()
+            .add_systems(First, collect_frame_profile)
+            .add_systems(
+                Update,
+                (
+                    start_trace_capture,
+                    toggle_profiler_hud,
+                    update_profiler_hud,
+                )
+                    .chain(),
+            );
+
+        // 日志层在插件加入前就已建立，此时才开始收集
+        if let Some(sink) = app.world().get_resource::<SpanSink>() {
+            sink.set_enabled(true);
+        }
+    }
+}
+
+/// 按下录制键时开始录制帧时间
+fn start_trace_capture(
+    input_state: Res<InputState>,
+    settings: Res<ProfilerSettings>,
+    mut state: ResMut<ProfilerState>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    if !input_state.is_action_just_pressed(GameAction::CaptureTrace) || state.capture.is_some() {
+        return;
+    }
+    state.capture = Some(TraceCapture::new(settings.trace_frames));
+    if let Some(logger) = logger.as_mut() {
+        logger.log(
+            LogLevel::Info,
+            &format!("开始录制帧时间，共 {} 帧", settings.trace_frames),
+        );
+    }
+}
+
+/// 每帧开始时汇总上一帧：登记系统耗时与原型数，录制中时写入帧与系统事件
+fn collect_frame_profile(
+    settings: Res<ProfilerSettings>,
+    sink: Option<Res<SpanSink>>,
+    archetypes: &Archetypes,
+    mut store: ResMut<DiagnosticsStore>,
+    mut state: ResMut<ProfilerState>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let now = Instant::now();
+    let frame = state
+        .frame_start
+        .replace(now)
+        .map(|start| (start, now.duration_since(start)));
+    let records = sink.map(|sink| sink.drain()).unwrap_or_default();
+
+    let mut totals: HashMap<&str, f64> = HashMap::new();
+    for record in &records {
+        *totals.entry(record.name.as_str()).or_default() += record.duration.as_secs_f64() * 1000.0;
+    }
+    for (name, total) in totals {
+        let path = match state.system_paths.get(name) {
+            Some(path) => path.clone(),
+            None => {
+                let path = DiagnosticPath::new(format!("system/{}", name.replace('/', "|")));
+                store.add(
+                    Diagnostic::new(path.clone())
+                        .with_suffix("ms")
+                        .with_max_history_length(settings.history),
+                );
+                state.system_paths.insert(name.to_string(), path.clone());
+                path
+            }
+        };
+        if let Some(diagnostic) = store.get_mut(&path) {
+            diagnostic.add_measurement(DiagnosticMeasurement {
+                time: now,
+                value: total,
+            });
+        }
+    }
+    if let Some(diagnostic) = store.get_mut(&ARCHETYPE_COUNT) {
+        diagnostic.add_measurement(DiagnosticMeasurement {
+            time: now,
+            value: archetypes.len() as f64,
+        });
+    }
+
+    let Some(capture) = state.capture.as_mut() else {
+        return;
+    };
+    for record in &records {
+        capture.push(
+            &record.name,
+            "system",
+            record.start,
+            record.duration,
+            record.thread,
+        );
+    }
+    let Some((start, duration)) = frame else {
+        return;
+    };
+    if !capture.push_frame(start, duration) {
+        return;
+    }
+
+    let result = capture.write_to(&settings.trace_dir);
+    state.capture = None;
+    if let Some(logger) = logger.as_mut() {
+        match result {
+            Ok(path) => logger.log(
+                LogLevel::Info,
+                &format!("帧时间录制已导出到 {}", path.display()),
+            ),
+            Err(e) => logger.log(LogLevel::Error, &format!("导出帧时间录制失败: {}", e)),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use chrono::Local;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// chrome tracing 格式中的一个事件，`ph` 为 `X` 表示带时长的完整事件
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceEvent {
+    pub name: String,
+    pub cat: &'static str,
+    pub ph: &'static str,
+    /// 开始时间（微秒）
+    pub ts: f64,
+    /// 时长（微秒）
+    pub dur: f64,
+    pub pid: u32,
+    pub tid: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceFile<'a> {
+    trace_events: &'a [TraceEvent],
+    display_time_unit: &'static str,
+}
+
+/// 一次帧时间录制
+///
+/// 录制指定帧数的帧与系统耗时，结束后写成 chrome tracing 格式的 JSON，
+/// 可以直接拖进 chrome://tracing 或 Perfetto 查看
+#[derive(Debug)]
+pub struct TraceCapture {
+    epoch: Instant,
+    frames_left: usize,
+    events: Vec<TraceEvent>,
+}
+
+impl TraceCapture {
+    pub fn new(frames: usize) -> Self {
+        Self {
+            epoch: Instant::now(),
+            frames_left: frames.max(1),
+            events: Vec::new(),
+        }
+    }
+
+    /// 还没录完的帧数
+    pub fn frames_left(&self) -> usize {
+        self.frames_left
+    }
+
+    /// 录制开始后的事件才记录，开始前还没进入的 span 丢弃
+    pub fn push(
+        &mut self,
+        name: &str,
+        cat: &'static str,
+        start: Instant,
+        duration: Duration,
+        tid: u32,
+    ) {
+        let Some(since) = start.checked_duration_since(self.epoch) else {
+            return;
+        };
+        self.events.push(TraceEvent {
+            name: name.to_string(),
+            cat,
+            ph: "X",
+            ts: since.as_secs_f64() * 1_000_000.0,
+            dur: duration.as_secs_f64() * 1_000_000.0,
+            pid: 1,
+            tid,
+        });
+    }
+
+    /// 记录一帧，返回是否录完
+    pub fn push_frame(&mut self, start: Instant, duration: Duration) -> bool {
+        self.push("frame", "frame", start, duration, 0);
+        self.frames_left = self.frames_left.saturating_sub(1);
+        self.frames_left == 0
+    }
+
+    /// 写入目录，返回文件路径
+    pub fn write_to(&self, dir: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        fs::create_dir_all(dir)?;
+        let path = Path::new(dir).join(format!(
+            "trace-{}.json",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        let file = TraceFile {
+            trace_events: &self.events,
+            display_time_unit: "ms",
+        };
+        fs::write(&path, serde_json::to_string(&file)?)?;
+        Ok(path)
+    }
+}
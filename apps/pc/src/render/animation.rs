@@ -0,0 +1,421 @@
+use bevy::asset::{io::Reader, AssetLoader, LoadContext, LoadedFolder};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use thiserror::Error;
+
+use super::components::{AnimationComponent, SpriteComponent};
+use crate::logging::{GameLogger, LogLevel};
+use crate::time::{LocalTimeScale, TimeDilation};
+
+/// 动画资源所在的目录（相对于 assets）
+pub const ANIMATION_FOLDER: &str = "animations";
+
+fn default_frame_time() -> f32 {
+    0.1
+}
+
+fn default_looping() -> bool {
+    true
+}
+
+/// 动画中的一帧
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnimationFrame {
+    /// 图集中的格子序号
+    pub index: usize,
+    /// 本帧时长（秒），不写时使用实体动画组件的每帧时长
+    #[serde(default)]
+    pub duration: Option<f32>,
+    /// 播放到本帧时发出的事件名，例如脚步声、出招音效
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+/// 一段动画
+///
+/// 帧可以逐个列出，也可以用 range 写图集中连续的格子
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnimationClip {
+    #[serde(default)]
+    pub frames: Vec<AnimationFrame>,
+    /// 首尾格子序号（含），与 frame_time 一起展开为连续帧
+    #[serde(default)]
+    pub range: Option<(usize, usize)>,
+    #[serde(default = "default_frame_time")]
+    pub frame_time: f32,
+    #[serde(default = "default_looping")]
+    pub looping: bool,
+}
+
+impl AnimationClip {
+    /// 把 range 展开为逐帧列表，已逐个列出的帧保持不变
+    fn expand(&mut self) {
+        if !self.frames.is_empty() {
+            return;
+        }
+        if let Some((first, last)) = self.range {
+            self.frames = (first..=last)
+                .map(|index| AnimationFrame {
+                    index,
+                    duration: Some(self.frame_time),
+                    events: Vec::new(),
+                })
+                .collect();
+        }
+    }
+}
+
+/// 动画文件内容
+#[derive(Debug, Deserialize)]
+struct AnimationSetFile {
+    /// 图集贴图，与精灵组件的贴图路径一致时自动套用
+    texture: String,
+    /// 单格尺寸（像素）
+    tile_size: (u32, u32),
+    columns: u32,
+    rows: u32,
+    #[serde(default)]
+    padding: Option<(u32, u32)>,
+    #[serde(default)]
+    offset: Option<(u32, u32)>,
+    clips: HashMap<String, AnimationClip>,
+}
+
+/// 动画资源
+///
+/// # 设计思路
+/// 1. 一张图集一个文件：assets/animations 下的 `*.anim.json`，写明切图方式与各段动画
+/// 2. 以贴图路径关联精灵，生成角色的代码不需要知道动画文件
+/// 3. 每帧可单独设时长与事件，出招的判定帧、脚步声都写在数据里
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct AnimationSetAsset {
+    pub texture_path: String,
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+    pub clips: HashMap<String, AnimationClip>,
+}
+
+impl AnimationSetAsset {
+    pub fn clip(&self, name: &str) -> Option<&AnimationClip> {
+        self.clips.get(name).filter(|clip| !clip.frames.is_empty())
+    }
+}
+
+/// 动画资源加载错误
+#[derive(Debug, Error)]
+pub enum AnimationSetLoaderError {
+    #[error("读取动画失败: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("解析动画失败: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// 动画资源加载器，同时加载贴图并生成图集布局
+#[derive(Default)]
+pub struct AnimationSetLoader;
+
+impl AssetLoader for AnimationSetLoader {
+    type Asset = AnimationSetAsset;
+    type Settings = ();
+    type Error = AnimationSetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<AnimationSetAsset, AnimationSetLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut file: AnimationSetFile = serde_json::from_slice(&bytes)?;
+        for clip in file.clips.values_mut() {
+            clip.expand();
+        }
+
+        let layout = TextureAtlasLayout::from_grid(
+            UVec2::new(file.tile_size.0, file.tile_size.1),
+            file.columns,
+            file.rows,
+            file.padding.map(|(x, y)| UVec2::new(x, y)),
+            file.offset.map(|(x, y)| UVec2::new(x, y)),
+        );
+        let layout = load_context.add_labeled_asset("layout".to_string(), layout);
+        let image = load_context.load(file.texture.clone());
+
+        Ok(AnimationSetAsset {
+            texture_path: file.texture,
+            image,
+            layout,
+            clips: file.clips,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["anim.json"]
+    }
+}
+
+/// 已加载的动画资源
+#[derive(Resource, Default)]
+pub struct AnimationLibrary {
+    /// 动画目录句柄，保持目录中的资源不被卸载
+    pub folder: Option<Handle<LoadedFolder>>,
+    /// 按贴图路径索引的动画资源
+    pub by_texture: HashMap<String, Handle<AnimationSetAsset>>,
+    /// 资源ID到贴图路径的映射，用于处理修改和移除
+    ids: HashMap<AssetId<AnimationSetAsset>, String>,
+}
+
+impl AnimationLibrary {
+    pub fn get(&self, texture_path: &str) -> Option<&Handle<AnimationSetAsset>> {
+        self.by_texture.get(texture_path)
+    }
+}
+
+/// 实体使用的动画资源
+#[derive(Component, Debug, Clone)]
+pub struct AnimationSet(pub Handle<AnimationSetAsset>);
+
+/// 播放进度，记录上次看到的动画名以发现切换
+#[derive(Component, Debug, Default)]
+pub struct AnimationCursor {
+    animation: String,
+    frame: usize,
+    finished: bool,
+    /// 挂上图集时动画组件的每帧时长，未写时长的帧使用它
+    default_frame_time: Option<Duration>,
+}
+
+/// 动画播放到带事件的帧
+#[derive(Event, Debug, Clone)]
+pub struct AnimationFrameEvent {
+    pub entity: Entity,
+    pub event: String,
+}
+
+/// 不循环的动画播放完毕
+#[derive(Event, Debug, Clone)]
+pub struct AnimationFinished {
+    pub entity: Entity,
+    pub animation: String,
+}
+
+/// 开始加载动画目录
+pub fn load_animation_sets(asset_server: Res<AssetServer>, mut library: ResMut<AnimationLibrary>) {
+    library.folder = Some(asset_server.load_folder(ANIMATION_FOLDER));
+}
+
+/// 根据资源事件更新贴图到动画的索引
+pub fn sync_animation_sets(
+    mut library: ResMut<AnimationLibrary>,
+    mut events: EventReader<AssetEvent<AnimationSetAsset>>,
+    mut assets: ResMut<Assets<AnimationSetAsset>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for event in events.read() {
+        match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                let Some(handle) = assets.get_strong_handle(*id) else {
+                    continue;
+                };
+                let Some(asset) = assets.get(*id) else {
+                    continue;
+                };
+                let texture_path = asset.texture_path.clone();
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(
+                        LogLevel::Info,
+                        &format!("动画已加载: {} ({} 段)", texture_path, asset.clips.len()),
+                    );
+                }
+                if let Some(previous) = library.ids.insert(*id, texture_path.clone()) {
+                    library.by_texture.remove(&previous);
+                }
+                library.by_texture.insert(texture_path, handle);
+            }
+            AssetEvent::Removed { id } => {
+                if let Some(previous) = library.ids.remove(id) {
+                    library.by_texture.remove(&previous);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 为贴图有对应动画的实体挂上图集精灵
+///
+/// 动画资源晚于实体加载完成时，下一帧仍会补上
+#[allow(clippy::type_complexity)]
+pub fn attach_animation_sprites(
+    mut commands: Commands,
+    library: Res<AnimationLibrary>,
+    assets: Res<Assets<AnimationSetAsset>>,
+    entities: Query<(Entity, &SpriteComponent), (With<AnimationComponent>, Without<AnimationSet>)>,
+) {
+    for (entity, sprite) in entities.iter() {
+        let Some(handle) = library.get(&sprite.texture_path) else {
+            continue;
+        };
+        let Some(asset) = assets.get(handle) else {
+            continue;
+        };
+        commands.entity(entity).insert((
+            AnimationSet(handle.clone()),
+            AnimationCursor::default(),
+            Sprite {
+                image: asset.image.clone(),
+                texture_atlas: Some(TextureAtlas {
+                    layout: asset.layout.clone(),
+                    index: 0,
+                }),
+                custom_size: Some(sprite.size),
+                flip_x: sprite.flip_x,
+                flip_y: sprite.flip_y,
+                color: sprite.color,
+                ..default()
+            },
+        ));
+    }
+}
+
+/// 把精灵组件上的翻转、着色与显隐同步到图集精灵
+#[allow(clippy::type_complexity)]
+pub fn sync_animation_sprites(
+    mut sprites: Query<
+        (&SpriteComponent, &mut Sprite, &mut Visibility),
+        (With<AnimationSet>, Changed<SpriteComponent>),
+    >,
+) {
+    for (source, mut sprite, mut visibility) in sprites.iter_mut() {
+        sprite.flip_x = source.flip_x;
+        sprite.flip_y = source.flip_y;
+        sprite.color = source.color;
+        sprite.custom_size = Some(source.size);
+        *visibility = if source.visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+/// 播放动画并翻动图集帧
+///
+/// 玩法系统只改动画组件上的动画名，这里发现切换后从第一帧重新播放；
+/// 每帧时长写回动画组件，判定框按同样的节奏出现。
+/// 按虚拟时间推进，暂停时画面停住，实体自身的时间倍率同样生效
+#[allow(clippy::type_complexity)]
+pub fn play_sprite_animations(
+    time: Res<Time<Virtual>>,
+    dilation: Res<TimeDilation>,
+    assets: Res<Assets<AnimationSetAsset>>,
+    mut frame_events: EventWriter<AnimationFrameEvent>,
+    mut finished_events: EventWriter<AnimationFinished>,
+    mut query: Query<(
+        Entity,
+        &AnimationSet,
+        &mut AnimationComponent,
+        &mut AnimationCursor,
+        &mut Sprite,
+        Option<&LocalTimeScale>,
+    )>,
+) {
+    let delta = time.delta_secs();
+
+    for (entity, set, mut animation, mut cursor, mut sprite, local_scale) in query.iter_mut() {
+        let Some(asset) = assets.get(&set.0) else {
+            continue;
+        };
+        let Some(clip) = asset.clip(&animation.current_animation) else {
+            continue;
+        };
+
+        // 动画切换或被重置到第一帧时重新开始
+        let restarted = cursor.animation != animation.current_animation
+            || (animation.current_frame == 0 && cursor.frame != 0);
+        if restarted {
+            cursor.animation = animation.current_animation.clone();
+            cursor.frame = 0;
+            cursor.finished = false;
+            animation.current_frame = 0;
+            animation.is_playing = true;
+            animation.timer.set_mode(TimerMode::Repeating);
+            animation.timer.reset();
+        }
+
+        let frame_duration = |frame: &AnimationFrame, fallback: Duration| {
+            frame
+                .duration
+                .filter(|duration| *duration > 0.0)
+                .map_or(fallback, Duration::from_secs_f32)
+        };
+        let fallback = *cursor
+            .default_frame_time
+            .get_or_insert(animation.frame_time);
+        let mut frame = animation.current_frame.min(clip.frames.len() - 1);
+        animation.total_frames = clip.frames.len();
+        animation.is_looping = clip.looping;
+
+        if restarted {
+            let duration = frame_duration(&clip.frames[frame], fallback);
+            animation.timer.set_duration(duration);
+            for event in &clip.frames[frame].events {
+                frame_events.send(AnimationFrameEvent {
+                    entity,
+                    event: event.clone(),
+                });
+            }
+        }
+
+        if animation.is_playing && !cursor.finished {
+            let delta = local_scale.map_or(delta, |local| local.apply(delta, &dilation));
+            animation
+                .timer
+                .tick(Duration::from_secs_f32(delta.max(0.0)));
+
+            // 单帧较短时一次可能跨过多帧，逐帧推进以免漏掉帧事件
+            let mut elapsed_frames = animation.timer.times_finished_this_tick();
+            while elapsed_frames > 0 {
+                elapsed_frames -= 1;
+                if frame + 1 < clip.frames.len() {
+                    frame += 1;
+                } else if clip.looping {
+                    frame = 0;
+                } else {
+                    cursor.finished = true;
+                    animation.is_playing = false;
+                    finished_events.send(AnimationFinished {
+                        entity,
+                        animation: cursor.animation.clone(),
+                    });
+                    break;
+                }
+
+                let duration = frame_duration(&clip.frames[frame], fallback);
+                if animation.timer.duration() != duration {
+                    animation.timer.set_duration(duration);
+                    elapsed_frames = 0;
+                }
+                for event in &clip.frames[frame].events {
+                    frame_events.send(AnimationFrameEvent {
+                        entity,
+                        event: event.clone(),
+                    });
+                }
+            }
+        }
+
+        animation.current_frame = frame;
+        cursor.frame = frame;
+        animation.frame_time = animation.timer.duration();
+        let index = clip.frames[frame].index;
+        if let Some(atlas) = sprite.texture_atlas.as_mut() {
+            if atlas.index != index {
+                atlas.index = index;
+            }
+        }
+    }
+}
@@ -0,0 +1,415 @@
+use bevy::prelude::*;
+
+use super::spectator::SpectatorState;
+use crate::events::input::GameAction;
+use crate::resources::{GameState, InputState};
+use crate::ui::WorldMapState;
+use crate::world::chunk::TILE_PIXELS;
+use crate::world::entity::Player;
+use crate::world::map::WorldConfig;
+
+/// 相机参数
+///
+/// # 参数说明
+/// - deadzone: 死区的半宽与半高（像素），目标在死区内移动时相机不动
+/// - zoom_levels: 可切换的投影缩放，由近到远排列
+/// - look_ahead_time / look_ahead_distance: 按目标速度向前多看几秒的路程，不超过最大距离
+/// - snap_distance: 目标一帧内移动超过这个距离（传送、读档）时相机直接跳过去
+/// - shake_*: 震屏强度随创伤值的平方增长，创伤值按秒衰减；shake_scale 为 0 时关闭震屏
+#[derive(Resource, Debug, Clone)]
+pub struct CameraSettings {
+    pub deadzone: Vec2,
+    pub zoom_levels: Vec<f32>,
+    pub default_zoom: usize,
+    pub zoom_smoothness: f32,
+    pub look_ahead_time: f32,
+    pub look_ahead_distance: f32,
+    pub look_ahead_smoothness: f32,
+    pub snap_distance: f32,
+    pub shake_max_offset: f32,
+    pub shake_max_angle: f32,
+    pub shake_frequency: f32,
+    pub shake_decay: f32,
+    pub shake_scale: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            deadzone: Vec2::new(24.0, 16.0),
+            zoom_levels: vec![0.5, 0.75, 1.0, 1.5, 2.0],
+            default_zoom: 2,
+            zoom_smoothness: 0.2,
+            look_ahead_time: 0.35,
+            look_ahead_distance: 64.0,
+            look_ahead_smoothness: 0.05,
+            snap_distance: 1024.0,
+            shake_max_offset: 12.0,
+            shake_max_angle: 0.04,
+            shake_frequency: 25.0,
+            shake_decay: 1.5,
+            shake_scale: 1.0,
+        }
+    }
+}
+
+/// 相机控制器
+///
+/// 记录相机需要跟随的目标与跟随状态，具体的跟随逻辑由相机系统负责
+#[derive(Component, Debug, Clone)]
+pub struct CameraController {
+    /// 跟随目标
+    pub target: Option<Entity>,
+    /// 跟随平滑系数 (0.0-1.0)，按每秒 60 帧时每帧靠近的比例计
+    pub smoothness: f32,
+    /// 当前缩放档位，对应 `CameraSettings::zoom_levels`
+    pub zoom_index: usize,
+    /// 当前投影缩放，平滑靠近档位的值
+    pub zoom: f32,
+    /// 创伤值 (0.0-1.0)，决定震屏强度
+    pub trauma: f32,
+    /// 死区中心
+    anchor: Vec2,
+    /// 不含震屏的相机位置
+    position: Vec2,
+    /// 当前的前瞻偏移
+    look_ahead: Vec2,
+    /// 上一帧跟随的目标及其位置
+    followed: Option<(Entity, Vec2)>,
+    /// 上一帧施加的震屏偏移
+    shake_offset: Vec2,
+}
+
+impl Default for CameraController {
+    fn default() -> Self {
+        Self {
+            target: None,
+            smoothness: 0.1,
+            zoom_index: 2,
+            zoom: 1.0,
+            trauma: 0.0,
+            anchor: Vec2::ZERO,
+            position: Vec2::ZERO,
+            look_ahead: Vec2::ZERO,
+            followed: None,
+            shake_offset: Vec2::ZERO,
+        }
+    }
+}
+
+impl CameraController {
+    /// 增加创伤值，多次受击叠加，最多到 1.0
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount.max(0.0)).min(1.0);
+    }
+}
+
+/// 震屏请求
+///
+/// 战斗等玩法只发事件，不直接改相机
+#[derive(Event, Debug, Clone, Copy)]
+pub struct CameraShake {
+    /// 增加的创伤值 (0.0-1.0)
+    pub trauma: f32,
+}
+
+impl CameraShake {
+    pub fn new(trauma: f32) -> Self {
+        Self { trauma }
+    }
+}
+
+/// 按帧率无关的方式换算平滑系数
+fn smoothing(factor: f32, delta: f32) -> f32 {
+    1.0 - (1.0 - factor.clamp(0.0, 1.0)).powf(delta * 60.0)
+}
+
+/// 生成游戏相机
+pub fn spawn_game_camera(mut commands: Commands, settings: Res<CameraSettings>) {
+    let zoom_index = settings
+        .default_zoom
+        .min(settings.zoom_levels.len().saturating_sub(1));
+    commands.spawn((
+        Camera2d,
+        CameraController {
+            zoom_index,
+            zoom: settings.zoom_levels.get(zoom_index).copied().unwrap_or(1.0),
+            ..default()
+        },
+    ));
+}
+
+/// 玩家生成（新游戏、读档）或原目标消失时改为跟随玩家
+///
+/// 旁观与区块压测会有意清空跟随目标，这里不去改回来
+pub fn assign_camera_target(
+    spectator: Res<SpectatorState>,
+    spawned: Query<Entity, Added<Player>>,
+    players: Query<Entity, With<Player>>,
+    targets: Query<(), With<Transform>>,
+    mut cameras: Query<&mut CameraController>,
+) {
+    if spectator.active {
+        return;
+    }
+    let Ok(player) = players.get_single() else {
+        return;
+    };
+    let spawned = spawned.contains(player);
+    for mut controller in cameras.iter_mut() {
+        let lost = controller
+            .target
+            .is_some_and(|target| !targets.contains(target));
+        if spawned || lost {
+            controller.target = Some(player);
+        }
+    }
+}
+
+/// 游戏中按缩放键切换档位，世界地图打开时缩放键留给地图
+pub fn zoom_camera(
+    input_state: Res<InputState>,
+    settings: Res<CameraSettings>,
+    state: Res<State<GameState>>,
+    world_map: Option<Res<State<WorldMapState>>>,
+    mut cameras: Query<&mut CameraController>,
+) {
+    let map_open = world_map.is_some_and(|world_map| *world_map.get() == WorldMapState::Open);
+    if *state.get() != GameState::InGame || map_open {
+        return;
+    }
+    let last = settings.zoom_levels.len().saturating_sub(1);
+    for mut controller in cameras.iter_mut() {
+        if input_state.is_action_just_pressed(GameAction::ZoomIn) && controller.zoom_index > 0 {
+            controller.zoom_index -= 1;
+        }
+        if input_state.is_action_just_pressed(GameAction::ZoomOut) && controller.zoom_index < last {
+            controller.zoom_index += 1;
+        }
+    }
+}
+
+/// 把震屏请求记到相机的创伤值上
+pub fn apply_camera_shakes(
+    settings: Res<CameraSettings>,
+    mut shakes: EventReader<CameraShake>,
+    mut cameras: Query<&mut CameraController>,
+) {
+    for shake in shakes.read() {
+        for mut controller in cameras.iter_mut() {
+            controller.add_trauma(shake.trauma * settings.shake_scale);
+        }
+    }
+}
+
+/// 相机跟随
+///
+/// # 设计思路
+/// 1. 目标先加上按速度推算的前瞻偏移，超出死区时才推动死区中心，相机再平滑靠近死区中心
+/// 2. 终结技镜头播放期间改为跟随聚焦目标，缩放叠加在当前档位上
+/// 3. 视野按当前缩放与窗口大小算出，限制在世界边界内；世界比视野小的方向上居中
+/// 4. 震屏偏移只加在最终位置上，下一帧先扣除，不会让相机漂走
+/// 5. 按真实时间计算，顿帧与慢动作时镜头照常平滑
+#[allow(clippy::too_many_arguments)]
+pub fn follow_camera(
+    real_time: Res<Time<Real>>,
+    settings: Res<CameraSettings>,
+    finisher: Res<FinisherCamera>,
+    world_config: Option<Res<WorldConfig>>,
+    targets: Query<&Transform, Without<CameraController>>,
+    mut cameras: Query<(
+        &Camera,
+        &mut CameraController,
+        &mut Transform,
+        &mut OrthographicProjection,
+    )>,
+) {
+    let delta = real_time.delta_secs();
+    let elapsed = real_time.elapsed_secs();
+
+    for (camera, mut controller, mut transform, mut projection) in cameras.iter_mut() {
+        // 缩放
+        let goal_zoom = settings
+            .zoom_levels
+            .get(controller.zoom_index)
+            .copied()
+            .unwrap_or(1.0);
+        let zoom = controller.zoom
+            + (goal_zoom - controller.zoom) * smoothing(settings.zoom_smoothness, delta);
+        controller.zoom = zoom;
+        let scale = zoom * finisher.current_zoom();
+        if projection.scale != scale {
+            projection.scale = scale;
+        }
+
+        // 扣除上一帧的震屏，旁观等外部移动相机时以当前位置为准
+        let shake_offset = controller.shake_offset;
+        let current = transform.translation.truncate() - shake_offset;
+
+        let target = finisher
+            .focus
+            .filter(|_| finisher.active)
+            .or(controller.target);
+        let Some((target, target_transform)) =
+            target.and_then(|target| Some((target, targets.get(target).ok()?)))
+        else {
+            controller.position = current;
+            controller.anchor = current;
+            controller.followed = None;
+            controller.trauma = 0.0;
+            controller.shake_offset = Vec2::ZERO;
+            transform.translation.x = current.x;
+            transform.translation.y = current.y;
+            transform.rotation = Quat::IDENTITY;
+            continue;
+        };
+        let target_position = target_transform.translation.truncate();
+
+        // 第一次跟随或目标瞬移时直接跳过去；换目标（如终结技聚焦）时平滑移过去，前瞻归零
+        let followed = controller.followed;
+        let snap = followed.is_none_or(|(followed, last)| {
+            followed == target && last.distance(target_position) > settings.snap_distance
+        });
+        if snap {
+            controller.look_ahead = Vec2::ZERO;
+            controller.anchor = target_position;
+            controller.position = target_position;
+        } else if delta > 0.0 {
+            let velocity = match followed {
+                Some((followed, last)) if followed == target => (target_position - last) / delta,
+                _ => Vec2::ZERO,
+            };
+            let goal = (velocity * settings.look_ahead_time)
+                .clamp_length_max(settings.look_ahead_distance);
+            let look_ahead = controller.look_ahead;
+            controller.look_ahead =
+                look_ahead + (goal - look_ahead) * smoothing(settings.look_ahead_smoothness, delta);
+        }
+        controller.followed = Some((target, target_position));
+
+        // 死区
+        let goal = target_position + controller.look_ahead;
+        let mut anchor = controller.anchor;
+        let offset = goal - anchor;
+        for axis in 0..2 {
+            let limit = settings.deadzone[axis].max(0.0);
+            if offset[axis] > limit {
+                anchor[axis] = goal[axis] - limit;
+            } else if offset[axis] < -limit {
+                anchor[axis] = goal[axis] + limit;
+            }
+        }
+        controller.anchor = anchor;
+        let position = controller.position;
+        let mut position = position + (anchor - position) * smoothing(controller.smoothness, delta);
+
+        // 世界边界
+        if let (Some(bounds), Some(viewport)) = (
+            world_config.as_ref().and_then(|config| config.world_bounds),
+            camera.logical_viewport_size(),
+        ) {
+            let bounds = Rect::from_corners(bounds.min * TILE_PIXELS, bounds.max * TILE_PIXELS);
+            let half = viewport * 0.5 * scale;
+            for axis in 0..2 {
+                let (min, max) = (bounds.min[axis] + half[axis], bounds.max[axis] - half[axis]);
+                position[axis] = if min > max {
+                    (bounds.min[axis] + bounds.max[axis]) * 0.5
+                } else {
+                    position[axis].clamp(min, max)
+                };
+            }
+        }
+        controller.position = position;
+
+        // 震屏
+        controller.trauma = (controller.trauma - settings.shake_decay * delta).max(0.0);
+        let strength = controller.trauma * controller.trauma;
+        let phase = elapsed * settings.shake_frequency;
+        let shake_offset = Vec2::new(phase.sin(), (phase * 1.3 + 1.7).sin())
+            * settings.shake_max_offset
+            * strength
+            * scale;
+        let angle = (phase * 0.9 + 3.1).sin() * settings.shake_max_angle * strength;
+        controller.shake_offset = shake_offset;
+
+        transform.translation.x = position.x + shake_offset.x;
+        transform.translation.y = position.y + shake_offset.y;
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
+
+/// 终结技镜头
+///
+/// 触发后相机短暂拉近，结束时恢复原有缩放。
+/// 持续时间按真实时间计算，不受慢动作影响
+#[derive(Resource, Debug, Clone)]
+pub struct FinisherCamera {
+    /// 聚焦目标
+    pub focus: Option<Entity>,
+    /// 拉近时的投影缩放（小于 1.0 为拉近）
+    pub zoom: f32,
+    /// 总时长
+    pub duration: f32,
+    /// 已经过时长
+    pub elapsed: f32,
+    /// 拉近与恢复各自占用的时长
+    pub blend: f32,
+    /// 是否正在播放
+    pub active: bool,
+}
+
+impl Default for FinisherCamera {
+    fn default() -> Self {
+        Self {
+            focus: None,
+            zoom: 0.75,
+            duration: 0.8,
+            elapsed: 0.0,
+            blend: 0.15,
+            active: false,
+        }
+    }
+}
+
+impl FinisherCamera {
+    /// 播放终结技镜头
+    pub fn trigger(&mut self, focus: Entity, duration: f32) {
+        self.focus = Some(focus);
+        self.duration = duration.max(self.blend * 2.0);
+        self.elapsed = 0.0;
+        self.active = true;
+    }
+
+    /// 当前的缩放倍率
+    pub fn current_zoom(&self) -> f32 {
+        if !self.active {
+            return 1.0;
+        }
+
+        let blend = self.blend.max(f32::EPSILON);
+        let t = if self.elapsed < blend {
+            self.elapsed / blend
+        } else if self.elapsed > self.duration - blend {
+            (self.duration - self.elapsed) / blend
+        } else {
+            1.0
+        };
+
+        1.0 + (self.zoom - 1.0) * t.clamp(0.0, 1.0)
+    }
+}
+
+/// 推进终结技镜头
+///
+/// 只负责计时，缩放与聚焦目标由相机跟随叠加到当前档位上
+pub fn update_finisher_camera(real_time: Res<Time<Real>>, mut finisher: ResMut<FinisherCamera>) {
+    if !finisher.active {
+        return;
+    }
+
+    finisher.elapsed += real_time.delta_secs();
+    if finisher.elapsed >= finisher.duration {
+        finisher.active = false;
+        finisher.focus = None;
+    }
+}
@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// 精灵渲染数据
+#[derive(Component, Debug, Clone)]
+pub struct SpriteComponent {
+    /// 贴图路径
+    pub texture_path: String,
+    /// 显示尺寸
+    pub size: Vec2,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    /// 着色
+    pub color: Color,
+    pub visible: bool,
+}
+
+/// 动画状态数据
+#[derive(Component, Debug, Clone)]
+pub struct AnimationComponent {
+    /// 当前播放的动画名称
+    pub current_animation: String,
+    /// 可用动画列表
+    pub animations: Vec<String>,
+    /// 每帧时长
+    pub frame_time: Duration,
+    pub current_frame: usize,
+    pub total_frames: usize,
+    pub is_playing: bool,
+    pub is_looping: bool,
+    pub timer: Timer,
+}
+
+/// 渲染层级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RenderLayer {
+    Terrain,    // 地形
+    Decoration, // 装饰物
+    Character,  // 角色
+    Effects,    // 特效
+    Ui,         // 界面
+}
+
+impl RenderLayer {
+    /// 所在的深度分组，组与组的深度区间互不交叠
+    ///
+    /// 装饰物与角色同组，树木、房屋与人物按纵坐标互相遮挡
+    pub fn depth_group(self) -> u8 {
+        match self {
+            RenderLayer::Terrain => 0,
+            RenderLayer::Decoration | RenderLayer::Character => 1,
+            RenderLayer::Effects => 2,
+            RenderLayer::Ui => 4,
+        }
+    }
+
+    /// 是否按纵坐标排序，界面固定在最上方
+    pub fn y_sorted(self) -> bool {
+        self != RenderLayer::Ui
+    }
+}
+
+/// 渲染层级组件
+#[derive(Component, Debug, Clone, Copy)]
+pub struct LayerComponent {
+    pub layer: RenderLayer,
+    /// 同层内的排序
+    pub sub_order: i32,
+}
+
+/// 粒子发射器
+///
+/// 只描述发射参数，粒子的生成与移动见 `render::particles`
+#[derive(Component, Debug, Clone)]
+pub struct ParticleEmitter {
+    /// 每秒发射数量
+    pub rate: f32,
+    /// 单个粒子存活时间（秒）
+    pub lifetime: f32,
+    /// 初始速度
+    pub velocity: Vec2,
+    /// 发射范围半径
+    pub spread: f32,
+    /// 粒子颜色
+    pub color: Color,
+    pub active: bool,
+}
+
+/// 受昼夜环境光影响的精灵
+///
+/// 记录未受光照影响的基础颜色，环境光变化时据此重新计算 `SpriteComponent::color`
+#[derive(Component, Debug, Clone, Copy)]
+pub struct AmbientTinted {
+    pub base_color: Color,
+}
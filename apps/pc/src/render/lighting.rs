@@ -0,0 +1,372 @@
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use std::collections::HashMap;
+
+use super::components::{LayerComponent, RenderLayer};
+use super::sorting::YSort;
+use crate::time::DayNightState;
+use crate::world::chunk::{world_to_tile, NavGrid};
+use crate::world::entity::Character;
+
+/// 影子贴图的尺寸（像素）
+const SHADOW_TEXTURE_SIZE: UVec2 = UVec2::new(64, 32);
+
+/// 光照配置
+///
+/// # 参数说明
+/// - enabled: 是否计算动态光照，低画质下关闭
+/// - shadows: 是否在角色脚下显示影子
+/// - update_interval: 光照图的重算间隔（秒），光源不动时不重算
+/// - max_radius: 单个光源照亮的最大半径（瓦片）
+/// - shadow_size: 影子的显示尺寸
+/// - shadow_offset: 影子相对角色原点的位置，落在脚底
+/// - shadow_opacity: 白天影子的不透明度，夜里随环境光变淡
+#[derive(Resource, Debug, Clone)]
+pub struct LightingSettings {
+    pub enabled: bool,
+    pub shadows: bool,
+    pub update_interval: f32,
+    pub max_radius: f32,
+    pub shadow_size: Vec2,
+    pub shadow_offset: Vec2,
+    pub shadow_opacity: f32,
+}
+
+impl Default for LightingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            shadows: true,
+            update_interval: 0.1,
+            max_radius: 12.0,
+            shadow_size: Vec2::new(28.0, 10.0),
+            shadow_offset: Vec2::new(0.0, -30.0),
+            shadow_opacity: 0.45,
+        }
+    }
+}
+
+/// 发光的实体，例如灯笼、篝火与技能
+#[derive(Component, Debug, Clone)]
+pub struct LightSource {
+    pub color: Color,
+    /// 光源中心的亮度，1.0 时在全黑的夜里照出原本的颜色
+    pub intensity: f32,
+    /// 照亮的半径（瓦片）
+    pub radius: f32,
+    /// 火光摇曳的幅度 (0.0-1.0)，为 0 时亮度恒定
+    pub flicker: f32,
+}
+
+impl LightSource {
+    /// 灯笼的暖光
+    pub fn lantern() -> Self {
+        Self {
+            color: Color::srgb(1.0, 0.78, 0.45),
+            intensity: 0.8,
+            radius: 5.0,
+            flicker: 0.05,
+        }
+    }
+
+    /// 篝火，比灯笼亮、摇曳更明显
+    pub fn campfire() -> Self {
+        Self {
+            color: Color::srgb(1.0, 0.6, 0.3),
+            intensity: 1.0,
+            radius: 7.0,
+            flicker: 0.15,
+        }
+    }
+}
+
+/// 限时的光源，到时连同实体一起移除
+#[derive(Component, Debug, Clone)]
+pub struct TimedLight {
+    /// 剩余时长（秒）
+    pub remaining: f32,
+}
+
+/// 每个瓦片受到的光照，只记录被照亮的瓦片
+#[derive(Resource, Debug, Default)]
+pub struct LightMap {
+    tiles: HashMap<IVec2, Vec3>,
+    /// 距离下次重算的剩余时间（秒）
+    cooldown: f32,
+}
+
+impl LightMap {
+    /// 瓦片受到的光照，没有光源照到时为零
+    pub fn light_at(&self, tile: IVec2) -> Vec3 {
+        self.tiles.get(&tile).copied().unwrap_or(Vec3::ZERO)
+    }
+
+    /// 世界坐标处受到的光照
+    pub fn light_at_world(&self, position: Vec2) -> Vec3 {
+        self.light_at(world_to_tile(position))
+    }
+}
+
+/// 在环境光着色后的颜色上叠加光照
+///
+/// 光照与环境光相加后不超过原本的颜色，白天几乎看不出灯光，夜里才明显
+pub fn illuminate(unlit: Color, day_night: &DayNightState, light: Vec3) -> Color {
+    if light == Vec3::ZERO {
+        return unlit;
+    }
+    let color = unlit.to_srgba();
+    let channel = |value: f32, ambient: f32, light: f32| {
+        let ambient = ambient.max(0.05);
+        value / ambient * (ambient + light).min(1.0)
+    };
+    Color::srgba(
+        channel(color.red, day_night.ambient[0], light.x),
+        channel(color.green, day_night.ambient[1], light.y),
+        channel(color.blue, day_night.ambient[2], light.z),
+        color.alpha,
+    )
+}
+
+/// 被光照着色的地形与装饰物，记住未受光照的颜色以便光源移开后还原
+#[derive(Component, Debug, Clone, Copy)]
+pub struct LitSprite {
+    unlit: Color,
+    /// 最后一次写入的颜色，与精灵当前颜色不同说明被换季等着色覆盖过
+    written: Color,
+}
+
+/// 角色脚下的影子
+#[derive(Component)]
+pub struct CharacterShadow;
+
+/// 影子贴图
+#[derive(Resource)]
+pub struct ShadowTexture(pub Handle<Image>);
+
+/// 生成中心浓、边缘淡的椭圆影子贴图
+pub fn create_shadow_texture(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let size = SHADOW_TEXTURE_SIZE;
+    let mut data = Vec::with_capacity((size.x * size.y * 4) as usize);
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let u = (x as f32 + 0.5) / size.x as f32 * 2.0 - 1.0;
+            let v = (y as f32 + 0.5) / size.y as f32 * 2.0 - 1.0;
+            let falloff = (1.0 - (u * u + v * v).sqrt()).clamp(0.0, 1.0);
+            let alpha = falloff * falloff * (3.0 - 2.0 * falloff);
+            data.extend_from_slice(&[0, 0, 0, (alpha * 255.0) as u8]);
+        }
+    }
+    let image = Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    commands.insert_resource(ShadowTexture(images.add(image)));
+}
+
+/// 限时光源到时移除
+pub fn expire_timed_lights(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut lights: Query<(Entity, &mut TimedLight)>,
+) {
+    for (entity, mut light) in lights.iter_mut() {
+        light.remaining -= time.delta_secs();
+        if light.remaining <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// 按间隔重算光照图
+///
+/// 每个光源照亮半径内的瓦片，亮度随距离平滑衰减；
+/// 与光源之间隔着墙、岩石、树林等挡视线的瓦片时照不到，挡光的瓦片自身仍被照亮。
+/// 没有导航网格时不计算遮挡
+pub fn update_light_map(
+    time: Res<Time>,
+    settings: Res<LightingSettings>,
+    nav_grid: Option<Res<NavGrid>>,
+    mut light_map: ResMut<LightMap>,
+    lights: Query<(Entity, &LightSource, &GlobalTransform)>,
+) {
+    if !settings.enabled {
+        if !light_map.tiles.is_empty() {
+            light_map.tiles.clear();
+        }
+        return;
+    }
+
+    let map = light_map.bypass_change_detection();
+    map.cooldown -= time.delta_secs();
+    if map.cooldown > 0.0 && !settings.is_changed() {
+        return;
+    }
+    map.cooldown = settings.update_interval;
+
+    let now = time.elapsed_secs();
+    let mut tiles: HashMap<IVec2, Vec3> = HashMap::new();
+    for (entity, light, transform) in lights.iter() {
+        let radius = light.radius.clamp(0.0, settings.max_radius);
+        if radius <= 0.0 || light.intensity <= 0.0 {
+            continue;
+        }
+        // 每个光源的摇曳相位不同，避免成片灯火同步闪烁
+        let phase = entity.index() as f32 * 1.7;
+        let flicker = 1.0 - light.flicker * (0.5 + 0.5 * (now * 9.0 + phase).sin());
+        let color = light.color.to_srgba();
+        let rgb = Vec3::new(color.red, color.green, color.blue) * light.intensity * flicker;
+
+        let center = world_to_tile(transform.translation().truncate());
+        let reach = radius.ceil() as i32;
+        for dy in -reach..=reach {
+            for dx in -reach..=reach {
+                let distance = Vec2::new(dx as f32, dy as f32).length();
+                if distance > radius {
+                    continue;
+                }
+                let tile = center + IVec2::new(dx, dy);
+                if let Some(grid) = &nav_grid {
+                    if !grid.line_of_sight(center, tile) {
+                        continue;
+                    }
+                }
+                let t = 1.0 - distance / radius;
+                *tiles.entry(tile).or_insert(Vec3::ZERO) += rgb * t * t;
+            }
+        }
+    }
+
+    if tiles != light_map.tiles {
+        light_map.tiles = tiles;
+    }
+}
+
+/// 按光照图给地形与装饰物着色
+///
+/// 光照图变化或精灵被换季、昼夜着色覆盖时才重新计算
+pub fn apply_tile_lighting(
+    mut commands: Commands,
+    light_map: Res<LightMap>,
+    day_night: Res<DayNightState>,
+    mut sprites: Query<(
+        Entity,
+        &LayerComponent,
+        &GlobalTransform,
+        &mut Sprite,
+        Option<&mut LitSprite>,
+    )>,
+) {
+    let map_changed = light_map.is_changed();
+    for (entity, layer, transform, mut sprite, lit) in sprites.iter_mut() {
+        if !matches!(layer.layer, RenderLayer::Terrain | RenderLayer::Decoration) {
+            continue;
+        }
+        if !map_changed && !sprite.is_changed() {
+            continue;
+        }
+
+        let light = light_map.light_at_world(transform.translation().truncate());
+        match lit {
+            Some(mut lit) => {
+                if sprite.color != lit.written {
+                    lit.unlit = sprite.color;
+                }
+                if light == Vec3::ZERO {
+                    sprite.color = lit.unlit;
+                    commands.entity(entity).remove::<LitSprite>();
+                    continue;
+                }
+                let color = illuminate(lit.unlit, &day_night, light);
+                if sprite.color != color {
+                    sprite.color = color;
+                }
+                lit.written = color;
+            }
+            None => {
+                if light == Vec3::ZERO {
+                    continue;
+                }
+                let unlit = sprite.color;
+                let color = illuminate(unlit, &day_night, light);
+                sprite.color = color;
+                commands.entity(entity).insert(LitSprite {
+                    unlit,
+                    written: color,
+                });
+            }
+        }
+    }
+}
+
+/// 影子的不透明度，夜里没有日光时变淡
+fn shadow_alpha(settings: &LightingSettings, day_night: &DayNightState) -> f32 {
+    settings.shadow_opacity * day_night.brightness().clamp(0.3, 1.0)
+}
+
+/// 为新角色在脚下挂上影子
+pub fn attach_character_shadows(
+    mut commands: Commands,
+    settings: Res<LightingSettings>,
+    day_night: Res<DayNightState>,
+    texture: Res<ShadowTexture>,
+    characters: Query<Entity, Added<Character>>,
+) {
+    let visibility = if settings.shadows {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for entity in characters.iter() {
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn((
+                CharacterShadow,
+                Sprite {
+                    image: texture.0.clone(),
+                    custom_size: Some(settings.shadow_size),
+                    color: Color::srgba(1.0, 1.0, 1.0, shadow_alpha(&settings, &day_night)),
+                    ..default()
+                },
+                Transform::from_translation(settings.shadow_offset.extend(0.0)),
+                visibility,
+                // 与角色脚底同一排序位置，画在角色后面
+                LayerComponent {
+                    layer: RenderLayer::Character,
+                    sub_order: -2,
+                },
+                YSort {
+                    foot_offset: -2.0,
+                    elevation: 0.0,
+                },
+            ));
+        });
+    }
+}
+
+/// 影子随环境光变淡，关闭影子时隐藏
+pub fn update_character_shadows(
+    settings: Res<LightingSettings>,
+    day_night: Res<DayNightState>,
+    mut shadows: Query<(&mut Sprite, &mut Visibility), With<CharacterShadow>>,
+) {
+    if !settings.is_changed() && !day_night.is_changed() {
+        return;
+    }
+    let visibility = if settings.shadows {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    let alpha = shadow_alpha(&settings, &day_night);
+    for (mut sprite, mut shadow_visibility) in shadows.iter_mut() {
+        shadow_visibility.set_if_neq(visibility);
+        sprite.color = sprite.color.with_alpha(alpha);
+    }
+}
@@ -0,0 +1,17 @@
+/// 渲染模块
+///
+/// 存放与具体渲染后端无关的渲染数据组件，以及相机相关逻辑
+/// （含开发与联机旁观使用的自由飞行相机），以及显卡设备丢失后的恢复；
+/// 帧动画按动画资源切分图集并逐帧播放；各渲染层级的深度与纵坐标排序统一在此计算；
+/// 灯火等光源按瓦片照亮周围，与昼夜环境光叠加；降水等粒子发射器在此模拟
+pub mod animation;
+pub mod camera;
+pub mod components;
+pub mod lighting;
+pub mod particles;
+pub mod recovery;
+pub mod sorting;
+pub mod spectator;
+mod systems;
+
+pub use systems::GameRenderPlugin;
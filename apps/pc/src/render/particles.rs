@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::components::ParticleEmitter;
+
+/// 单个发射器同时存在的粒子上限，防止强度过高时拖慢帧率
+const MAX_PARTICLES_PER_EMITTER: usize = 1500;
+
+/// 粒子
+///
+/// 作为发射器的子实体生成，随发射器（例如挂在相机下的降水层）一起移动
+#[derive(Component, Debug, Clone)]
+pub struct Particle {
+    /// 速度（像素/秒）
+    pub velocity: Vec2,
+    /// 剩余存活时间（秒）
+    pub remaining: f32,
+}
+
+/// 发射器尚未发射的粒子数累计，帧率较高时每帧不足一个的部分留到下一帧
+#[derive(Component, Debug, Default)]
+pub struct EmitterAccumulator(f32);
+
+/// 按发射速率生成粒子
+///
+/// 粒子在发射器左右 `spread` 范围内随机出生，速度带少许随机扰动，雨丝与雪花不会排成一列
+pub fn emit_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut emitters: Query<(
+        Entity,
+        &ParticleEmitter,
+        Option<&mut EmitterAccumulator>,
+        Option<&Children>,
+    )>,
+    particles: Query<(), With<Particle>>,
+) {
+    let mut rng = rand::thread_rng();
+    for (entity, emitter, accumulator, children) in emitters.iter_mut() {
+        let Some(mut accumulator) = accumulator else {
+            commands
+                .entity(entity)
+                .insert(EmitterAccumulator::default());
+            continue;
+        };
+        if !emitter.active {
+            accumulator.0 = 0.0;
+            continue;
+        }
+
+        accumulator.0 += emitter.rate * time.delta_secs();
+        let count = accumulator.0.floor();
+        accumulator.0 -= count;
+
+        let alive = children.map_or(0, |children| {
+            children
+                .iter()
+                .filter(|child| particles.contains(**child))
+                .count()
+        });
+        let count = (count as usize).min(MAX_PARTICLES_PER_EMITTER.saturating_sub(alive));
+        if count == 0 {
+            continue;
+        }
+
+        // 下落越快的粒子拉得越长，雨是细线，雪是圆点
+        let length = (emitter.velocity.length() * 0.02).max(2.0);
+        let size = Vec2::new(2.0, length);
+        let angle = Vec2::NEG_Y.angle_to(emitter.velocity);
+
+        commands.entity(entity).with_children(|parent| {
+            for _ in 0..count {
+                let offset = Vec3::new(rng.gen_range(-emitter.spread..=emitter.spread), 0.0, 0.0);
+                let jitter = rng.gen_range(0.85..1.15);
+                parent.spawn((
+                    Particle {
+                        velocity: emitter.velocity * jitter,
+                        remaining: emitter.lifetime * rng.gen_range(0.7..1.0),
+                    },
+                    Sprite {
+                        color: emitter.color,
+                        custom_size: Some(size),
+                        ..default()
+                    },
+                    Transform::from_translation(offset).with_rotation(Quat::from_rotation_z(angle)),
+                ));
+            }
+        });
+    }
+}
+
+/// 移动粒子并回收到期的粒子
+pub fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particles: Query<(Entity, &mut Particle, &mut Transform)>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut particle, mut transform) in particles.iter_mut() {
+        particle.remaining -= delta;
+        if particle.remaining <= 0.0 {
+            // 连同父实体的子列表一起清理
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        transform.translation += (particle.velocity * delta).extend(0.0);
+    }
+}
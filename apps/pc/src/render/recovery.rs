@@ -0,0 +1,335 @@
+use bevy::prelude::*;
+use bevy::render::renderer::RenderDevice;
+use bevy::window::PrimaryWindow;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Mutex;
+use wgpu::DeviceLostReason;
+
+use crate::logging::{DiagnosticIncidents, GameLogger, LogLevel};
+use crate::save::{
+    next_autosave_slot, AutosaveSettings, SaveGameRequest, SaveReason, SaveSettings,
+};
+use crate::world::entity::Player;
+
+/// 渲染故障类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderIncidentKind {
+    /// 显卡设备丢失，常见于驱动更新、笔记本切换显卡
+    DeviceLost,
+    /// 显存不足，贴图、网格或窗口表面分配失败
+    OutOfMemory,
+    /// 驱动内部错误，多半是达到了系统限制
+    Internal,
+    /// 渲染调用不合法，是渲染代码或资源的问题
+    Validation,
+    /// 设备丢失回调被其他代码替换，之后的设备丢失无法发现
+    WatchReplaced,
+}
+
+impl RenderIncidentKind {
+    /// 故障名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            RenderIncidentKind::DeviceLost => "显卡设备丢失",
+            RenderIncidentKind::OutOfMemory => "显存不足",
+            RenderIncidentKind::Internal => "显卡驱动内部错误",
+            RenderIncidentKind::Validation => "渲染调用错误",
+            RenderIncidentKind::WatchReplaced => "设备监控失效",
+        }
+    }
+
+    /// 是否需要重建渲染表面；渲染调用错误重建后照样会出现，只记录
+    pub fn needs_recovery(&self) -> bool {
+        matches!(
+            self,
+            RenderIncidentKind::DeviceLost
+                | RenderIncidentKind::OutOfMemory
+                | RenderIncidentKind::Internal
+        )
+    }
+}
+
+/// 显卡驱动上报的渲染故障
+#[derive(Debug, Clone)]
+struct RenderIncident {
+    kind: RenderIncidentKind,
+    detail: String,
+}
+
+/// 渲染恢复配置
+///
+/// # 参数说明
+/// - max_attempts: 连续恢复的最多次数，超过后不再尝试，提示玩家重启
+/// - attempt_window: 距上次恢复超过该时长（秒）后重新计数
+#[derive(Resource, Debug, Clone)]
+pub struct RenderRecoverySettings {
+    pub max_attempts: u32,
+    pub attempt_window: f32,
+}
+
+impl Default for RenderRecoverySettings {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            attempt_window: 60.0,
+        }
+    }
+}
+
+/// 恢复流程的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum RecoveryStage {
+    #[default]
+    Idle,
+    /// 等待重建主窗口
+    RecreateSurface(RenderIncidentKind),
+    /// 窗口已重建，等待重新上传资源
+    ReloadAssets(RenderIncidentKind),
+}
+
+/// 渲染健康状态
+///
+/// # 设计思路
+/// 1. 只认驱动上报的设备丢失与错误，帧间隔长短不算故障：拖动窗口、调试断点、联机连接都会让画面停一下
+/// 2. 驱动在渲染线程回调，经通道交给游戏线程处理；任何错误都不中止程序，全部记入诊断信息
+/// 3. 恢复分两步：先按原配置重建主窗口，让渲染器为新窗口创建表面；
+///    新窗口生成后再把所有贴图与网格标记为已修改，渲染器会重新上传到显卡
+/// 4. 游戏状态都在 ECS 中，恢复过程不触碰游戏数据；第一次故障时仍先存一次档，
+///    恢复次数用尽时玩家重启后也不会丢进度
+#[derive(Resource, Default)]
+pub struct RenderHealth {
+    incidents: Option<Mutex<Receiver<RenderIncident>>>,
+    stage: RecoveryStage,
+    /// 最近一段时间内的恢复次数
+    attempts: u32,
+    /// 距上次恢复的时间（秒）
+    since_last: f32,
+    /// 恢复次数用尽后不再尝试
+    gave_up: bool,
+    /// 已为这次故障存过档，之后的故障只记录
+    saved: bool,
+}
+
+impl RenderHealth {
+    /// 取出驱动上报的故障
+    fn drain(&self) -> Vec<RenderIncident> {
+        let Some(Ok(receiver)) = self.incidents.as_ref().map(Mutex::lock) else {
+            return Vec::new();
+        };
+        let mut incidents = Vec::new();
+        while let Ok(incident) = receiver.try_recv() {
+            incidents.push(incident);
+        }
+        incidents
+    }
+}
+
+/// 向显卡驱动注册设备丢失与错误回调
+///
+/// 退出时释放设备也会触发设备丢失回调，那时游戏线程已不再处理故障，
+/// 因此游戏中收到的释放一律按设备丢失恢复
+pub fn watch_render_device(
+    render_device: Option<Res<RenderDevice>>,
+    mut health: ResMut<RenderHealth>,
+) {
+    let Some(render_device) = render_device else {
+        return;
+    };
+    let (sender, receiver) = mpsc::channel();
+    let device = render_device.wgpu_device();
+
+    let lost = sender.clone();
+    device.set_device_lost_callback(move |reason, message| {
+        let (kind, detail) = match reason {
+            DeviceLostReason::Unknown => (RenderIncidentKind::DeviceLost, message),
+            DeviceLostReason::Destroyed => (
+                RenderIncidentKind::DeviceLost,
+                format!("设备被销毁：{}", message),
+            ),
+            DeviceLostReason::Dropped => (
+                RenderIncidentKind::DeviceLost,
+                format!("设备被释放：{}", message),
+            ),
+            DeviceLostReason::ReplacedCallback => (RenderIncidentKind::WatchReplaced, message),
+        };
+        let _ = lost.send(RenderIncident { kind, detail });
+    });
+
+    device.on_uncaptured_error(Box::new(move |error| {
+        let (kind, detail) = match error {
+            wgpu::Error::OutOfMemory { source } => {
+                (RenderIncidentKind::OutOfMemory, source.to_string())
+            }
+            wgpu::Error::Internal { description, .. } => {
+                (RenderIncidentKind::Internal, description)
+            }
+            wgpu::Error::Validation { description, .. } => {
+                (RenderIncidentKind::Validation, description)
+            }
+        };
+        let _ = sender.send(RenderIncident { kind, detail });
+    }));
+
+    health.incidents = Some(Mutex::new(receiver));
+}
+
+/// 处理渲染故障
+///
+/// 每次故障都记入诊断信息与日志；需要恢复的故障在第一次出现时若正在游戏中，
+/// 先存到下一个自动存档槽，再安排重建渲染表面
+pub fn handle_render_incidents(
+    mut health: ResMut<RenderHealth>,
+    save_settings: Res<SaveSettings>,
+    autosave: Res<AutosaveSettings>,
+    players: Query<(), With<Player>>,
+    mut saves: EventWriter<SaveGameRequest>,
+    mut incidents: ResMut<DiagnosticIncidents>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let pending = health.drain();
+    if pending.is_empty() {
+        return;
+    }
+
+    for incident in &pending {
+        incidents.record(
+            "渲染",
+            &format!("{}：{}", incident.kind.name(), incident.detail),
+        );
+        if let Some(logger) = logger.as_mut() {
+            logger.log(
+                LogLevel::Error,
+                &format!("渲染故障：{}（{}）", incident.kind.name(), incident.detail),
+            );
+        }
+    }
+
+    // 同一帧的多个故障合并为一次恢复，以设备丢失为准
+    let Some(kind) = pending
+        .iter()
+        .map(|incident| incident.kind)
+        .filter(RenderIncidentKind::needs_recovery)
+        .min_by_key(|kind| *kind != RenderIncidentKind::DeviceLost)
+    else {
+        return;
+    };
+    if health.stage == RecoveryStage::Idle && !health.gave_up {
+        health.stage = RecoveryStage::RecreateSurface(kind);
+    }
+
+    if health.saved || players.is_empty() {
+        return;
+    }
+    health.saved = true;
+    let slot = next_autosave_slot(&save_settings.slots_dir, autosave.slots);
+    if let Some(logger) = logger.as_mut() {
+        logger.log(
+            LogLevel::Info,
+            &format!("显卡故障，先存档到 {}，再尝试恢复画面", slot),
+        );
+    }
+    saves.send(SaveGameRequest {
+        slot,
+        reason: SaveReason::RenderFailure,
+    });
+}
+
+/// 重建主窗口
+///
+/// 同一帧内移除旧窗口并按原配置生成新窗口，渲染器会为新窗口创建表面；
+/// 相机以主窗口为渲染目标，会自动画到新窗口上
+pub fn recreate_render_surface(
+    mut commands: Commands,
+    real_time: Res<Time<Real>>,
+    settings: Res<RenderRecoverySettings>,
+    mut health: ResMut<RenderHealth>,
+    windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+    mut incidents: ResMut<DiagnosticIncidents>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    health.since_last += real_time.delta_secs();
+    if health.since_last > settings.attempt_window {
+        health.attempts = 0;
+        health.gave_up = false;
+    }
+
+    let RecoveryStage::RecreateSurface(kind) = health.stage else {
+        return;
+    };
+    if health.attempts >= settings.max_attempts {
+        health.stage = RecoveryStage::Idle;
+        health.gave_up = true;
+        incidents.record("渲染", "恢复次数已用尽，需要重启游戏");
+        if let Some(logger) = logger.as_mut() {
+            logger.log(
+                LogLevel::Error,
+                "渲染多次恢复失败，请重启游戏，进度已紧急存档；若反复出现，请更新显卡驱动",
+            );
+        }
+        return;
+    }
+
+    let Ok((entity, window)) = windows.get_single() else {
+        return;
+    };
+    let window = window.clone();
+    commands.entity(entity).despawn_recursive();
+    commands.spawn((window, PrimaryWindow));
+
+    health.attempts += 1;
+    health.since_last = 0.0;
+    health.stage = RecoveryStage::ReloadAssets(kind);
+    if let Some(logger) = logger.as_mut() {
+        logger.log(
+            LogLevel::Info,
+            &format!("正在重建渲染表面（第 {} 次）", health.attempts),
+        );
+    }
+}
+
+/// 窗口重建后把贴图与网格全部标记为已修改，渲染器会重新上传
+pub fn reload_gpu_assets(
+    mut health: ResMut<RenderHealth>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    windows: Query<(), (With<Window>, With<PrimaryWindow>)>,
+    mut incidents: ResMut<DiagnosticIncidents>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let RecoveryStage::ReloadAssets(kind) = health.stage else {
+        return;
+    };
+    // 新窗口生成后才上传
+    if windows.is_empty() {
+        return;
+    }
+
+    let image_ids: Vec<AssetId<Image>> = images.ids().collect();
+    for id in &image_ids {
+        images.get_mut(*id);
+    }
+    let mesh_ids: Vec<AssetId<Mesh>> = meshes.ids().collect();
+    for id in &mesh_ids {
+        meshes.get_mut(*id);
+    }
+
+    health.stage = RecoveryStage::Idle;
+    incidents.record(
+        "渲染",
+        &format!(
+            "已从{}中恢复，重新上传 {} 项资源",
+            kind.name(),
+            image_ids.len() + mesh_ids.len()
+        ),
+    );
+    if let Some(logger) = logger.as_mut() {
+        logger.log(
+            LogLevel::Info,
+            &format!(
+                "渲染已恢复：重新上传 {} 张贴图、{} 个网格",
+                image_ids.len(),
+                mesh_ids.len()
+            ),
+        );
+    }
+}
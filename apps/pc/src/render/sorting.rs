@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+
+use super::components::{LayerComponent, RenderLayer};
+use crate::world::chunk::TILE_PIXELS;
+use crate::world::map::WorldConfig;
+
+/// 深度排序配置
+///
+/// # 参数说明
+/// - group_depth: 每个深度分组占用的区间，地形、场景、特效、界面依次叠放
+/// - sort_depth: 分组内留给纵坐标排序的深度，须小于 group_depth
+/// - sub_order_step: 同一纵坐标上 sub_order 每差 1 的深度差
+/// - fallback_range: 无世界边界时按这个纵向范围（像素）换算深度，超出的压在区间两端
+#[derive(Resource, Debug, Clone)]
+pub struct DepthSortSettings {
+    pub group_depth: f32,
+    pub sort_depth: f32,
+    pub sub_order_step: f32,
+    pub fallback_range: f32,
+}
+
+impl Default for DepthSortSettings {
+    fn default() -> Self {
+        Self {
+            group_depth: 200.0,
+            sort_depth: 190.0,
+            sub_order_step: 0.0001,
+            fallback_range: 65536.0,
+        }
+    }
+}
+
+impl DepthSortSettings {
+    /// 按层级与排序用的纵坐标算出深度，纵坐标越大（越靠画面上方）越靠后
+    pub fn depth(&self, layer: LayerComponent, sort_y: f32, range: (f32, f32)) -> f32 {
+        let base = layer.layer.depth_group() as f32 * self.group_depth;
+        // 同组同一纵坐标上角色压在装饰物前面
+        let tie = if layer.layer == RenderLayer::Character {
+            0.5
+        } else {
+            0.0
+        };
+        let order = (layer.sub_order as f32 + tie) * self.sub_order_step;
+        if !layer.layer.y_sorted() {
+            return base + order;
+        }
+
+        let (min_y, max_y) = range;
+        let t = ((max_y - sort_y) / (max_y - min_y).max(1.0)).clamp(0.0, 1.0);
+        base + t * self.sort_depth + order
+    }
+}
+
+/// 参与纵坐标排序的基准点
+///
+/// 没有这个组件的实体按自身原点排序
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct YSort {
+    /// 基准点（脚底、树根）相对实体原点的纵向偏移
+    pub foot_offset: f32,
+    /// 地形高度造成的画面抬升，排序时扣除，按所在地面的位置遮挡
+    pub elevation: f32,
+}
+
+/// 统一计算所有带渲染层级的实体的深度
+///
+/// 各处生成实体时不再各写一个 z，只声明层级；
+/// 只处理新加入或移动过的实体，子实体按父实体的全局变换换算世界坐标，
+/// 写回本地深度时扣除父实体的深度
+#[allow(clippy::type_complexity)]
+pub fn apply_render_depth(
+    settings: Res<DepthSortSettings>,
+    world_config: Option<Res<WorldConfig>>,
+    parents: Query<&GlobalTransform>,
+    mut sorted: Query<
+        (
+            &LayerComponent,
+            Option<&YSort>,
+            Option<&Parent>,
+            &mut Transform,
+        ),
+        Or<(Changed<Transform>, Changed<LayerComponent>, Changed<YSort>)>,
+    >,
+) {
+    let range = world_config
+        .as_ref()
+        .and_then(|config| config.world_bounds)
+        .map(|bounds| (bounds.min.y * TILE_PIXELS, bounds.max.y * TILE_PIXELS))
+        .unwrap_or((
+            -settings.fallback_range * 0.5,
+            settings.fallback_range * 0.5,
+        ));
+
+    for (layer, y_sort, parent, mut transform) in sorted.iter_mut() {
+        let parent_transform = parent.and_then(|parent| parents.get(parent.get()).ok());
+        let (world_y, parent_z) = match parent_transform {
+            Some(global) => (
+                global.transform_point(transform.translation).y,
+                global.translation().z,
+            ),
+            None => (transform.translation.y, 0.0),
+        };
+        let sort_y = y_sort.map_or(world_y, |y_sort| {
+            world_y + y_sort.foot_offset - y_sort.elevation
+        });
+
+        let z = settings.depth(*layer, sort_y, range) - parent_z;
+        if (transform.translation.z - z).abs() > f32::EPSILON {
+            transform.translation.z = z;
+        }
+    }
+}
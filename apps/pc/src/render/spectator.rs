@@ -0,0 +1,215 @@
+use bevy::prelude::*;
+
+use super::camera::CameraController;
+use crate::coop::{CoopRole, CoopSession};
+use crate::events::input::GameAction;
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::{GlobalGameState, InputState};
+use crate::world::chunk::ChunkManager;
+use crate::world::entity::{Character, Player};
+use crate::world::physics::PhysicsOptions;
+
+/// 旁观模式配置
+///
+/// # 参数说明
+/// - allow_offline: 单机时是否允许旁观，默认只在开发构建中允许
+/// - speed: 飞行速度（像素/秒）
+/// - fast_multiplier / slow_multiplier: 按住疾跑、闪避键时的速度倍率
+/// - reveal_chunks: 旁观时强制加载相机周围的区块
+/// - reveal_radius: 强制加载的区块半径
+/// - debug_overlays: 旁观时打开调试绘制
+#[derive(Resource, Debug, Clone)]
+pub struct SpectatorSettings {
+    pub allow_offline: bool,
+    pub speed: f32,
+    pub fast_multiplier: f32,
+    pub slow_multiplier: f32,
+    pub reveal_chunks: bool,
+    pub reveal_radius: i32,
+    pub debug_overlays: bool,
+}
+
+impl Default for SpectatorSettings {
+    fn default() -> Self {
+        Self {
+            allow_offline: cfg!(debug_assertions),
+            speed: 480.0,
+            fast_multiplier: 4.0,
+            slow_multiplier: 0.25,
+            reveal_chunks: true,
+            reveal_radius: 6,
+            debug_overlays: true,
+        }
+    }
+}
+
+/// 旁观模式状态
+///
+/// 进入时记下被改动的设置，退出时原样恢复
+#[derive(Resource, Debug, Default)]
+pub struct SpectatorState {
+    pub active: bool,
+    /// 进入前的区块视距
+    saved_view_distance: Option<i32>,
+    /// 进入前的调试模式与物理调试绘制
+    saved_debug: Option<(bool, bool)>,
+    /// 进入前玩家能否移动
+    saved_can_move: Option<bool>,
+}
+
+/// 当前是否允许旁观
+///
+/// 联机时主机总是允许，客人需要主机授权；单机按配置决定
+fn spectator_permitted(settings: &SpectatorSettings, session: Option<&CoopSession>) -> bool {
+    match session.map(|session| (session.role, session.allow_spectate)) {
+        Some((CoopRole::Hosting, _)) => true,
+        Some((CoopRole::Joined, allowed)) => allowed,
+        Some((CoopRole::Joining, _)) => false,
+        Some((CoopRole::Offline, _)) | None => settings.allow_offline,
+    }
+}
+
+/// 切换旁观模式，失去权限时自动退出
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+pub fn toggle_spectator(
+    input_state: Res<InputState>,
+    settings: Res<SpectatorSettings>,
+    mut state: ResMut<SpectatorState>,
+    session: Option<Res<CoopSession>>,
+    mut game_state: ResMut<GlobalGameState>,
+    mut physics: Option<ResMut<PhysicsOptions>>,
+    mut chunk_manager: Option<ResMut<ChunkManager>>,
+    mut players: Query<(Entity, &mut Character, &Transform), With<Player>>,
+    mut cameras: Query<(&mut CameraController, &mut Transform), (With<Camera>, Without<Player>)>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let permitted = spectator_permitted(&settings, session.as_deref());
+    let toggled = input_state.is_action_just_pressed(GameAction::ToggleSpectator);
+    let revoked = state.active && !permitted;
+    if !toggled && !revoked {
+        return;
+    }
+
+    if !state.active {
+        if !permitted {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Info, "当前没有旁观权限");
+            }
+            return;
+        }
+
+        state.active = true;
+        if let Ok((_, mut character, _)) = players.get_single_mut() {
+            state.saved_can_move = Some(character.can_move);
+            character.can_move = false;
+        }
+        if let Ok((mut controller, _)) = cameras.get_single_mut() {
+            controller.target = None;
+        }
+        if settings.reveal_chunks {
+            if let Some(chunk_manager) = chunk_manager.as_mut() {
+                state.saved_view_distance = Some(chunk_manager.view_distance);
+                chunk_manager.view_distance =
+                    chunk_manager.view_distance.max(settings.reveal_radius);
+            }
+        }
+        if settings.debug_overlays {
+            let physics_debug = physics.as_ref().is_some_and(|physics| physics.debug_draw);
+            state.saved_debug = Some((game_state.is_debug, physics_debug));
+            game_state.is_debug = true;
+            if let Some(physics) = physics.as_mut() {
+                physics.debug_draw = true;
+            }
+        }
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Info, "进入旁观模式");
+        }
+        return;
+    }
+
+    state.active = false;
+    let player = players.get_single_mut().ok();
+    if let Some((entity, mut character, transform)) = player {
+        if let Some(can_move) = state.saved_can_move.take() {
+            character.can_move = can_move;
+        }
+        // 相机回到玩家身边，区块加载也跟着回去
+        if let Ok((mut controller, mut camera_transform)) = cameras.get_single_mut() {
+            controller.target = Some(entity);
+            camera_transform.translation.x = transform.translation.x;
+            camera_transform.translation.y = transform.translation.y;
+        }
+        if let Some(chunk_manager) = chunk_manager.as_mut() {
+            chunk_manager.update_player_position(transform.translation.x, transform.translation.y);
+        }
+    }
+    if let (Some(view_distance), Some(chunk_manager)) =
+        (state.saved_view_distance.take(), chunk_manager.as_mut())
+    {
+        chunk_manager.view_distance = view_distance;
+    }
+    if let Some((is_debug, physics_debug)) = state.saved_debug.take() {
+        game_state.is_debug = is_debug;
+        if let Some(physics) = physics.as_mut() {
+            physics.debug_draw = physics_debug;
+        }
+    }
+    if let Some(logger) = logger.as_mut() {
+        let message = if revoked {
+            "旁观权限已收回，退出旁观模式"
+        } else {
+            "退出旁观模式"
+        };
+        logger.log(LogLevel::Info, message);
+    }
+}
+
+/// 旁观时自由飞行，区块加载跟随相机
+///
+/// 按真实时间移动，游戏暂停或慢动作时也能正常飞行
+pub fn fly_spectator_camera(
+    real_time: Res<Time<Real>>,
+    input_state: Res<InputState>,
+    settings: Res<SpectatorSettings>,
+    state: Res<SpectatorState>,
+    mut chunk_manager: Option<ResMut<ChunkManager>>,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+) {
+    if !state.active {
+        return;
+    }
+    let Ok(mut transform) = cameras.get_single_mut() else {
+        return;
+    };
+
+    let mut direction = Vec2::ZERO;
+    if input_state.is_action_active(GameAction::MoveForward) {
+        direction.y += 1.0;
+    }
+    if input_state.is_action_active(GameAction::MoveBackward) {
+        direction.y -= 1.0;
+    }
+    if input_state.is_action_active(GameAction::MoveLeft) {
+        direction.x -= 1.0;
+    }
+    if input_state.is_action_active(GameAction::MoveRight) {
+        direction.x += 1.0;
+    }
+
+    let mut speed = settings.speed;
+    if input_state.is_action_active(GameAction::Sprint) {
+        speed *= settings.fast_multiplier;
+    }
+    if input_state.is_action_active(GameAction::Dodge) {
+        speed *= settings.slow_multiplier;
+    }
+
+    let movement = direction.normalize_or_zero() * speed * real_time.delta_secs();
+    transform.translation.x += movement.x;
+    transform.translation.y += movement.y;
+
+    if let Some(chunk_manager) = chunk_manager.as_mut() {
+        chunk_manager.update_player_position(transform.translation.x, transform.translation.y);
+    }
+}
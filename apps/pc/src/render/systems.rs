@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+use super::animation::{
+    attach_animation_sprites, load_animation_sets, play_sprite_animations, sync_animation_sets,
+    sync_animation_sprites, AnimationFinished, AnimationFrameEvent, AnimationLibrary,
+    AnimationSetAsset, AnimationSetLoader,
+};
+use super::camera::{
+    apply_camera_shakes, assign_camera_target, follow_camera, spawn_game_camera,
+    update_finisher_camera, zoom_camera, CameraSettings, CameraShake, FinisherCamera,
+};
+use super::components::{AmbientTinted, SpriteComponent};
+use super::lighting::{
+    apply_tile_lighting, attach_character_shadows, create_shadow_texture, expire_timed_lights,
+    illuminate, update_character_shadows, update_light_map, LightMap, LightingSettings,
+};
+use super::particles::{emit_particles, update_particles};
+use super::recovery::{
+    handle_render_incidents, recreate_render_surface, reload_gpu_assets, watch_render_device,
+    RenderHealth, RenderRecoverySettings,
+};
+use super::sorting::{apply_render_depth, DepthSortSettings};
+use super::spectator::{fly_spectator_camera, toggle_spectator, SpectatorSettings, SpectatorState};
+use crate::time::DayNightState;
+
+/// 渲染插件
+///
+/// 注册帧动画、深度排序、相机跟随与效果、旁观模式、昼夜着色与动态光照、粒子、显卡故障恢复等渲染相关系统
+pub struct GameRenderPlugin;
+
+impl Plugin for GameRenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FinisherCamera>()
+            .init_resource::<SpectatorSettings>()
+            .init_resource::<SpectatorState>()
+            .add_systems(Update, (update_finisher_camera, apply_ambient_tint))
+            .add_systems(Update, (toggle_spectator, fly_spectator_camera).chain());
+
+        // 动画资源加载后为精灵挂上图集；玩法系统在 Update 中切换动画，这里随后翻帧
+        app.init_asset::<AnimationSetAsset>()
+            .init_asset_loader::<AnimationSetLoader>()
+            .init_resource::<AnimationLibrary>()
+            .add_event::<AnimationFrameEvent>()
+            .add_event::<AnimationFinished>()
+            .add_systems(Startup, load_animation_sets)
+            .add_systems(
+                PostUpdate,
+                (
+                    sync_animation_sets,
+                    attach_animation_sprites,
+                    sync_animation_sprites,
+                    play_sprite_animations,
+                )
+                    .chain(),
+            );
+
+        // 光照图按间隔重算，地形与装饰物在换季、昼夜着色之后叠加光照
+        app.init_resource::<LightingSettings>()
+            .init_resource::<LightMap>()
+            .add_systems(Startup, create_shadow_texture)
+            .add_systems(
+                Update,
+                (expire_timed_lights, update_light_map)
+                    .chain()
+                    .before(apply_ambient_tint),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    apply_tile_lighting,
+                    attach_character_shadows,
+                    update_character_shadows,
+                ),
+            );
+
+        // 粒子先移动旧的再发射新的，新粒子在出生的那一帧停在发射点
+        app.add_systems(Update, (update_particles, emit_particles).chain());
+
+        // 深度在所有玩法移动实体之后统一计算，层级之外的 z 不再散落在各处
+        app.init_resource::<DepthSortSettings>().add_systems(
+            PostUpdate,
+            apply_render_depth.before(TransformSystem::TransformPropagate),
+        );
+
+        // 相机在所有玩法移动角色之后、变换传播之前跟随，画面不会晚一帧
+        app.init_resource::<CameraSettings>()
+            .add_event::<CameraShake>()
+            .add_systems(Startup, spawn_game_camera)
+            .add_systems(
+                Update,
+                (assign_camera_target, zoom_camera, apply_camera_shakes)
+                    .after(fly_spectator_camera),
+            )
+            .add_systems(
+                PostUpdate,
+                follow_camera.before(TransformSystem::TransformPropagate),
+            );
+
+        // 显卡设备丢失或显存不足时紧急存档，再重建窗口表面并重新上传资源
+        app.init_resource::<RenderRecoverySettings>()
+            .init_resource::<RenderHealth>()
+            .add_systems(Startup, watch_render_device)
+            .add_systems(
+                Last,
+                (
+                    handle_render_incidents,
+                    recreate_render_surface,
+                    reload_gpu_assets,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// 按昼夜环境光与附近的光源为角色精灵着色
+///
+/// 新生成的精灵立即着色，其余只在环境光、光照图变化或角色移动时刷新
+fn apply_ambient_tint(
+    day_night: Res<DayNightState>,
+    light_map: Res<LightMap>,
+    mut sprites: Query<(
+        Ref<AmbientTinted>,
+        Ref<GlobalTransform>,
+        &mut SpriteComponent,
+    )>,
+) {
+    let ambient_changed = day_night.is_changed() || light_map.is_changed();
+    for (tinted, transform, mut sprite) in sprites.iter_mut() {
+        if ambient_changed || tinted.is_changed() || transform.is_changed() {
+            let light = light_map.light_at_world(transform.translation().truncate());
+            let color = illuminate(day_night.apply(tinted.base_color), &day_night, light);
+            if sprite.color != color {
+                sprite.color = color;
+            }
+        }
+    }
+}
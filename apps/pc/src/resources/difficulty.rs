@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 难度等级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Master,
+}
+
+impl Difficulty {
+    /// 新游戏界面按这个顺序列出
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Master,
+    ];
+
+    /// 显示名
+    pub fn label(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "简单",
+            Difficulty::Normal => "普通",
+            Difficulty::Hard => "困难",
+            Difficulty::Master => "宗师",
+        }
+    }
+}
+
+/// 难度修正
+///
+/// 各系统读取对应的系数调整自身数值，避免难度逻辑散落在各处
+#[derive(Resource, Debug, Clone)]
+pub struct DifficultyModifiers {
+    pub difficulty: Difficulty,
+    /// 受到伤害倍率
+    pub damage_taken: f32,
+    /// 负重上限倍率
+    pub carry_capacity: f32,
+    /// 超重惩罚倍率，越大超重时减速越明显
+    pub encumbrance_penalty: f32,
+}
+
+impl Default for DifficultyModifiers {
+    fn default() -> Self {
+        Self::for_difficulty(Difficulty::default())
+    }
+}
+
+impl DifficultyModifiers {
+    /// 获取难度对应的默认修正
+    pub fn for_difficulty(difficulty: Difficulty) -> Self {
+        let (damage_taken, carry_capacity, encumbrance_penalty) = match difficulty {
+            Difficulty::Easy => (0.75, 1.25, 0.5),
+            Difficulty::Normal => (1.0, 1.0, 1.0),
+            Difficulty::Hard => (1.25, 0.9, 1.25),
+            Difficulty::Master => (1.5, 0.8, 1.5),
+        };
+
+        Self {
+            difficulty,
+            damage_taken,
+            carry_capacity,
+            encumbrance_penalty,
+        }
+    }
+}
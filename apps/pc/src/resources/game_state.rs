@@ -4,30 +4,49 @@ use bevy::prelude::*;
 #[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum GameState {
     #[default]
-    Loading, // 加载中
+    Login, // 登录，启动后首先进入
     MainMenu, // 主菜单
+    Loading,  // 加载中
     InGame,   // 游戏中
     Paused,   // 暂停
 }
 
-// 游戏全局状态资源
-#[derive(Resource)]
-pub struct GlobalGameState {
-    // 是否为调试模式
-    pub is_debug: bool,
-    // 帧计数
-    pub frame_count: u64,
-    // 游戏时间
-    pub game_time: f32,
+/// 暂停设置
+///
+/// - stream_chunks: 暂停时是否继续加载区块，继续加载时恢复游戏后周围已准备好，
+///   关闭可以在暂停时让出处理器
+#[derive(Resource, Debug, Clone)]
+pub struct PauseSettings {
+    pub stream_chunks: bool,
 }
 
-// 游戏全局状态资源默认实现
-impl Default for GlobalGameState {
+impl Default for PauseSettings {
     fn default() -> Self {
         Self {
-            is_debug: false,
-            frame_count: 0,
-            game_time: 0.0,
+            stream_chunks: true,
         }
     }
 }
+
+/// 游戏逻辑是否在推进，用作 AI、物理、历法等系统的运行条件
+///
+/// 只在游戏中推进，主菜单、加载与暂停时冻结；没有注册游戏状态的无界面运行一直推进
+pub fn gameplay_running(state: Option<Res<State<GameState>>>) -> bool {
+    state.is_none_or(|state| *state.get() == GameState::InGame)
+}
+
+/// 区块流式加载是否进行，加载状态需要区块，只有暂停时按设置决定
+pub fn chunk_streaming_running(
+    state: Option<Res<State<GameState>>>,
+    settings: Option<Res<PauseSettings>>,
+) -> bool {
+    let paused = state.is_some_and(|state| *state.get() == GameState::Paused);
+    !paused || settings.is_none_or(|settings| settings.stream_chunks)
+}
+
+// 游戏全局状态资源
+#[derive(Resource, Default)]
+pub struct GlobalGameState {
+    // 是否为调试模式
+    pub is_debug: bool,
+}
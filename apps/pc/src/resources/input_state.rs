@@ -1,4 +1,4 @@
-use crate::events::input::GameAction;
+use crate::events::input::{GameAction, InputDevice};
 use bevy::prelude::*;
 
 // 输入状态资源
@@ -8,10 +8,16 @@ pub struct InputState {
     pub active_actions: Vec<GameAction>,
     // 上一帧的输入动作
     pub previous_actions: Vec<GameAction>,
-    // 鼠标位置
-    pub mouse_position: Vec2,
-    // 鼠标移动量
-    pub mouse_delta: Vec2,
+    // 左摇杆推过死区后的移动方向，长度为力度，没有手柄时为零
+    pub move_axis: Vec2,
+    // 当前手柄
+    pub gamepad: Option<Entity>,
+    // 最后操作的输入设备
+    pub device: InputDevice,
+    // 界面正在接收文字输入（例如聊天框），按键不触发游戏动作
+    pub typing: bool,
+    // 输入文字时按下、尚未松开的动作，松开前不再触发
+    pub blocked_actions: Vec<GameAction>,
 }
 
 // 输入状态资源方法实现
@@ -25,9 +31,4 @@ impl InputState {
     pub fn is_action_just_pressed(&self, action: GameAction) -> bool {
         self.active_actions.contains(&action) && !self.previous_actions.contains(&action)
     }
-
-    // 检查是否刚释放某个动作
-    pub fn is_action_just_released(&self, action: GameAction) -> bool {
-        !self.active_actions.contains(&action) && self.previous_actions.contains(&action)
-    }
 }
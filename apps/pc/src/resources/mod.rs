@@ -1,5 +1,7 @@
+mod difficulty;
 mod game_state;
 mod input_state;
 
+pub use difficulty::*;
 pub use game_state::*;
 pub use input_state::*;
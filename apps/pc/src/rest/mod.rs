@@ -0,0 +1,13 @@
+/// 休息模块
+///
+/// # 模块组成
+/// 1. rest_spot：可休息的地点（床铺、篝火）及其品质
+/// 2. session：选择休息时长、结算恢复与夜袭
+/// 3. systems：休息插件
+mod rest_spot;
+mod session;
+mod systems;
+
+pub use rest_spot::*;
+pub use session::*;
+pub use systems::RestPlugin;
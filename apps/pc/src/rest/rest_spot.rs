@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::render::components::{LayerComponent, ParticleEmitter, RenderLayer};
+use crate::render::lighting::LightSource;
+
+/// 篝火数据文件路径
+pub const CAMPFIRE_DATA_PATH: &str = "src/config/campfires.json";
+
+/// 可休息的地点
+#[derive(Component, Debug, Clone)]
+pub struct RestSpot {
+    pub name: String,
+    /// 休息品质 (0.0-1.0)，决定每小时的气血与内力恢复量
+    pub quality: f32,
+    /// 是否有遮蔽，有遮蔽的地点不会遭遇夜袭
+    pub sheltered: bool,
+    /// 打开休息菜单时预选的时长（小时）
+    pub default_hours: f32,
+}
+
+impl RestSpot {
+    /// 自家宅院中的床铺
+    pub fn bed() -> Self {
+        Self {
+            name: "床铺".to_string(),
+            quality: 1.0,
+            sheltered: true,
+            default_hours: 8.0,
+        }
+    }
+
+    /// 野外篝火
+    pub fn campfire() -> Self {
+        Self {
+            name: "篝火".to_string(),
+            quality: 0.6,
+            sheltered: false,
+            default_hours: 6.0,
+        }
+    }
+}
+
+/// 篝火
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Campfire;
+
+/// 篝火定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampfireDef {
+    pub name: String,
+    pub position: [f32; 2],
+}
+
+/// 篝火数据文件格式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CampfireDataFile {
+    campfires: Vec<CampfireDef>,
+}
+
+/// 读取野外篝火列表
+pub fn load_campfires(path: &str) -> Result<Vec<CampfireDef>, Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(path)?;
+    let data: CampfireDataFile = serde_json::from_str(&content)?;
+    Ok(data.campfires)
+}
+
+/// 生成一处篝火
+pub fn spawn_campfire(commands: &mut Commands, name: &str, position: Vec2) -> Entity {
+    commands
+        .spawn((
+            Campfire,
+            RestSpot {
+                name: name.to_string(),
+                ..RestSpot::campfire()
+            },
+            Name::new(format!("Campfire: {}", name)),
+            Sprite {
+                color: Color::srgb(0.45, 0.28, 0.15),
+                custom_size: Some(Vec2::new(20.0, 12.0)),
+                ..default()
+            },
+            Transform::from_translation(position.extend(0.0)),
+            LayerComponent {
+                layer: RenderLayer::Decoration,
+                sub_order: 0,
+            },
+            LightSource::campfire(),
+        ))
+        .with_children(|fire| {
+            fire.spawn((
+                Name::new("CampfireFlame"),
+                ParticleEmitter {
+                    rate: 24.0,
+                    lifetime: 0.7,
+                    velocity: Vec2::new(0.0, 30.0),
+                    spread: 6.0,
+                    color: Color::srgba(1.0, 0.55, 0.15, 0.8),
+                    active: true,
+                },
+                Transform::from_xyz(0.0, 4.0, 0.1),
+            ));
+        })
+        .id()
+}
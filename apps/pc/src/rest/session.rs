@@ -0,0 +1,331 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::RestSpot;
+use crate::combat::{ApplyStatus, StatusKind};
+use crate::events::input::GameAction;
+use crate::interaction::{Interacted, InteractionKind, PanelInteraction};
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::InputState;
+use crate::time::{DayNightSettings, GameCalendar, TimeOfDay, TimeSkipRequest};
+use crate::world::entity::{spawn_npc, Character, NpcType, Player};
+
+/// 休息配置
+#[derive(Resource, Debug, Clone)]
+pub struct RestSettings {
+    /// 与休息地点的交互距离
+    pub interact_range: f32,
+    /// 单次休息的最短时长（小时）
+    pub min_hours: f32,
+    /// 单次休息的最长时长（小时）
+    pub max_hours: f32,
+    /// 品质为 1.0 的地点每小时恢复的气血、内力比例
+    pub recovery_per_hour: f32,
+    /// 无遮蔽地点每小时遭遇夜袭的基础概率
+    pub ambush_chance_per_hour: f32,
+    /// 入夜后夜袭概率的倍率
+    pub night_ambush_multiplier: f32,
+    /// 每次夜袭的敌人数量
+    pub ambush_enemies: usize,
+    /// 敌人出现位置与玩家的距离
+    pub ambush_radius: f32,
+}
+
+impl Default for RestSettings {
+    fn default() -> Self {
+        Self {
+            interact_range: 40.0,
+            min_hours: 1.0,
+            max_hours: 12.0,
+            recovery_per_hour: 0.15,
+            ambush_chance_per_hour: 0.02,
+            night_ambush_multiplier: 3.0,
+            ambush_enemies: 2,
+            ambush_radius: 80.0,
+        }
+    }
+}
+
+/// 休息菜单状态
+#[derive(Resource, Debug, Default)]
+pub struct RestMenu {
+    /// 正在使用的休息地点，菜单关闭时为 None
+    pub spot: Option<Entity>,
+    /// 选择的休息时长（小时）
+    pub hours: f32,
+}
+
+/// 休息菜单界面标记
+#[derive(Component)]
+pub struct RestMenuUi;
+
+/// 确认休息后发出，由结算系统推进时间并恢复状态
+#[derive(Event, Debug, Clone, Copy)]
+pub struct RestRequest {
+    pub entity: Entity,
+    pub spot: Entity,
+    pub hours: f32,
+}
+
+/// 休息结束事件
+#[derive(Event, Debug, Clone, Copy)]
+pub struct RestFinished {
+    /// 是否被夜袭打断
+    pub interrupted: bool,
+}
+
+/// 交互键作用到休息地点时打开休息菜单，菜单打开时再按交互键或走远则关闭
+pub fn toggle_rest_menu(
+    mut interacted: EventReader<Interacted>,
+    mut panel_events: EventReader<PanelInteraction>,
+    settings: Res<RestSettings>,
+    mut menu: ResMut<RestMenu>,
+    mut players: Query<(&mut Character, &Transform), With<Player>>,
+    spots: Query<(&RestSpot, &Transform), Without<Player>>,
+) {
+    let pressed = panel_events.read().count() > 0;
+    let target = interacted
+        .read()
+        .filter(|event| event.kind == InteractionKind::Rest)
+        .last()
+        .map(|event| event.entity);
+    let Ok((mut character, transform)) = players.get_single_mut() else {
+        return;
+    };
+    let position = transform.translation.truncate();
+
+    if let Some(spot) = menu.spot {
+        let out_of_range = spots.get(spot).map_or(true, |(_, spot)| {
+            spot.translation.truncate().distance(position) > settings.interact_range
+        });
+        if out_of_range || pressed {
+            menu.spot = None;
+            character.can_move = true;
+        }
+        return;
+    }
+
+    let Some(entity) = target else {
+        return;
+    };
+    if let Ok((spot, _)) = spots.get(entity) {
+        menu.spot = Some(entity);
+        menu.hours = spot
+            .default_hours
+            .clamp(settings.min_hours, settings.max_hours);
+        character.can_move = false;
+    }
+}
+
+/// 菜单打开时左右键调整时长，确认键开始休息
+pub fn handle_rest_menu(
+    input_state: Res<InputState>,
+    settings: Res<RestSettings>,
+    mut menu: ResMut<RestMenu>,
+    mut players: Query<(Entity, &mut Character), With<Player>>,
+    mut requests: EventWriter<RestRequest>,
+) {
+    let Some(spot) = menu.spot else {
+        return;
+    };
+
+    let mut hours = menu.hours;
+    if input_state.is_action_just_pressed(GameAction::MoveLeft) {
+        hours -= 1.0;
+    }
+    if input_state.is_action_just_pressed(GameAction::MoveRight) {
+        hours += 1.0;
+    }
+    let hours = hours.clamp(settings.min_hours, settings.max_hours);
+    if hours != menu.hours {
+        menu.hours = hours;
+    }
+
+    if !input_state.is_action_just_pressed(GameAction::PlaceItem) {
+        return;
+    }
+    let Ok((entity, mut character)) = players.get_single_mut() else {
+        return;
+    };
+
+    requests.send(RestRequest {
+        entity,
+        spot,
+        hours,
+    });
+    menu.spot = None;
+    character.can_move = true;
+}
+
+/// 结算休息：逐小时检定夜袭，按实际时长推进时间并恢复气血与内力
+///
+/// 睡足没被打断时醒来带有调息效果
+#[allow(clippy::too_many_arguments)]
+pub fn resolve_rest(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<RestSettings>,
+    calendar: Res<GameCalendar>,
+    day_night: Res<DayNightSettings>,
+    mut requests: EventReader<RestRequest>,
+    spots: Query<&RestSpot>,
+    mut characters: Query<(&mut Character, &Transform)>,
+    mut skip_requests: EventWriter<TimeSkipRequest>,
+    mut finished: EventWriter<RestFinished>,
+    mut status_events: EventWriter<ApplyStatus>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let mut rng = rand::thread_rng();
+
+    for request in requests.read() {
+        let (Ok(spot), Ok((mut character, transform))) =
+            (spots.get(request.spot), characters.get_mut(request.entity))
+        else {
+            continue;
+        };
+
+        // 逐小时检定，被打断时只计入已经睡过的时间
+        let start_hour = calendar.time_of_day * 24.0;
+        let mut rested_hours = request.hours;
+        let mut interrupted = false;
+        if !spot.sheltered {
+            for hour in 0..request.hours.ceil() as u32 {
+                let time_of_day = day_night.time_of_day((start_hour + hour as f32) % 24.0);
+                let chance = if time_of_day == TimeOfDay::Night {
+                    settings.ambush_chance_per_hour * settings.night_ambush_multiplier
+                } else {
+                    settings.ambush_chance_per_hour
+                };
+                if rng.gen::<f32>() < chance {
+                    rested_hours = (hour as f32 + rng.gen::<f32>()).min(request.hours);
+                    interrupted = true;
+                    break;
+                }
+            }
+        }
+
+        let recovery = (settings.recovery_per_hour * spot.quality * rested_hours).min(1.0);
+        character.health =
+            (character.health + character.max_health * recovery).min(character.max_health);
+        character.qi = (character.qi + character.max_qi * recovery).min(character.max_qi);
+
+        skip_requests.send(TimeSkipRequest {
+            hours: rested_hours,
+        });
+
+        if interrupted {
+            let center = transform.translation;
+            for i in 0..settings.ambush_enemies {
+                let angle = std::f32::consts::TAU * i as f32 / settings.ambush_enemies as f32
+                    + rng.gen_range(0.0..1.0);
+                let offset = Vec2::from_angle(angle) * settings.ambush_radius;
+                spawn_npc(
+                    &mut commands,
+                    &asset_server,
+                    center + offset.extend(0.0),
+                    NpcType::Enemy,
+                    "夜袭山贼",
+                );
+            }
+        } else {
+            status_events.send(ApplyStatus::new(
+                request.entity,
+                None,
+                &spot.name,
+                StatusKind::QiRegen,
+            ));
+        }
+
+        finished.send(RestFinished { interrupted });
+
+        if let Some(logger) = logger.as_mut() {
+            let message = if interrupted {
+                format!(
+                    "在{}休息了 {:.1} 个小时，遭遇夜袭被惊醒！",
+                    spot.name, rested_hours
+                )
+            } else {
+                format!(
+                    "在{}休息了 {:.0} 个小时，恢复了 {:.0}% 的气血与内力",
+                    spot.name,
+                    rested_hours,
+                    recovery * 100.0
+                )
+            };
+            logger.log(LogLevel::Info, &message);
+        }
+    }
+}
+
+/// 菜单状态变化时重建休息菜单
+pub fn update_rest_menu_ui(
+    mut commands: Commands,
+    settings: Res<RestSettings>,
+    menu: Res<RestMenu>,
+    spots: Query<&RestSpot>,
+    ui: Query<Entity, With<RestMenuUi>>,
+) {
+    if !menu.is_changed() {
+        return;
+    }
+
+    for entity in ui.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some(spot) = menu.spot.and_then(|entity| spots.get(entity).ok()) else {
+        return;
+    };
+
+    let recovery = (settings.recovery_per_hour * spot.quality * menu.hours).min(1.0);
+    let safety = if spot.sheltered {
+        "此处安全"
+    } else {
+        "野外露宿，夜里可能遭遇袭击"
+    };
+
+    commands
+        .spawn((
+            RestMenuUi,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(6.0),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.1, 0.08, 0.06, 0.85)),
+            ))
+            .with_children(|panel| {
+                for (text, size) in [
+                    (format!("在{}休息", spot.name), 20.0),
+                    (format!("时长：{:.0} 小时", menu.hours), 16.0),
+                    (
+                        format!("预计恢复 {:.0}% 气血与内力", recovery * 100.0),
+                        14.0,
+                    ),
+                    (safety.to_string(), 14.0),
+                    ("A/D 调整时长  Enter 确认  E 取消".to_string(), 12.0),
+                ] {
+                    panel.spawn((
+                        Text::new(text),
+                        TextFont {
+                            font_size: size,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                }
+            });
+        });
+}
@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+
+use super::{
+    handle_rest_menu, load_campfires, resolve_rest, spawn_campfire, toggle_rest_menu,
+    update_rest_menu_ui, RestFinished, RestMenu, RestRequest, RestSettings, CAMPFIRE_DATA_PATH,
+};
+use crate::config::resolve_data_path;
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::gameplay_running;
+
+/// 休息插件
+///
+/// # 设计思路
+/// 1. 床铺、篝火等地点挂载 `RestSpot`，在旁边按交互键打开菜单选择休息时长
+/// 2. 结算时逐小时检定夜袭，按实际休息时长发出 `TimeSkipRequest` 推进世界时钟
+/// 3. 跳过期间的世界变化由各系统订阅 `TimeSkipped` 自行补算
+pub struct RestPlugin;
+
+impl Plugin for RestPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RestSettings>()
+            .init_resource::<RestMenu>()
+            .add_event::<RestRequest>()
+            .add_event::<RestFinished>();
+
+        app.add_systems(Startup, spawn_campfires).add_systems(
+            Update,
+            (
+                toggle_rest_menu,
+                handle_rest_menu,
+                resolve_rest,
+                update_rest_menu_ui,
+            )
+                .chain()
+                .run_if(gameplay_running),
+        );
+    }
+}
+
+/// 按数据文件生成野外篝火
+fn spawn_campfires(mut commands: Commands, mut logger: Option<ResMut<GameLogger>>) {
+    let campfires = match load_campfires(&resolve_data_path(CAMPFIRE_DATA_PATH)) {
+        Ok(campfires) => campfires,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("篝火数据加载失败: {}", e));
+            }
+            return;
+        }
+    };
+
+    for campfire in &campfires {
+        spawn_campfire(
+            &mut commands,
+            &campfire.name,
+            Vec2::from_array(campfire.position),
+        );
+    }
+}
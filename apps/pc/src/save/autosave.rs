@@ -0,0 +1,134 @@
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+use super::{SaveEvent, SaveGameRequest, SaveReason, SaveSettings, SaveSlot};
+use crate::resources::{gameplay_running, GameState};
+use crate::world::entity::{AiState, Character, CharacterState, Npc, NpcType, Player};
+use crate::world::map::SceneTriggerEntered;
+
+/// 自动存档槽名前缀，槽名为 autosave_1、autosave_2……
+pub const AUTOSAVE_SLOT_PREFIX: &str = "autosave";
+
+/// 自动存档设置
+///
+/// - interval: 定时存档的间隔（秒），0 为不定时存档
+/// - slots: 轮流覆盖的自动存档槽数量
+/// - min_gap: 两次自动存档之间至少间隔的秒数，连续进出场景时不会反复存档
+#[derive(Resource, Debug, Clone)]
+pub struct AutosaveSettings {
+    pub enabled: bool,
+    pub interval: f32,
+    pub on_scene_enter: bool,
+    pub before_boss: bool,
+    pub slots: usize,
+    pub min_gap: f32,
+}
+
+impl Default for AutosaveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: 600.0,
+            on_scene_enter: true,
+            before_boss: true,
+            slots: 3,
+            min_gap: 60.0,
+        }
+    }
+}
+
+/// 自动存档槽名
+pub fn autosave_slot_name(index: usize) -> String {
+    format!("{}_{}", AUTOSAVE_SLOT_PREFIX, index + 1)
+}
+
+/// 下一个要写入的自动存档槽：先用空槽，都用过后覆盖最早的
+pub fn next_autosave_slot(slots_dir: &str, count: usize) -> String {
+    let mut oldest: Option<(String, String)> = None;
+    for index in 0..count.max(1) {
+        let name = autosave_slot_name(index);
+        let Ok(slot) = SaveSlot::in_dir(slots_dir, &name) else {
+            continue;
+        };
+        let Ok(metadata) = slot.metadata() else {
+            return name;
+        };
+        if oldest
+            .as_ref()
+            .is_none_or(|(_, saved_at)| metadata.saved_at < *saved_at)
+        {
+            oldest = Some((name, metadata.saved_at));
+        }
+    }
+    oldest.map_or_else(|| autosave_slot_name(0), |(name, _)| name)
+}
+
+/// 定时、进入场景与首领战前发出自动存档请求
+///
+/// 首领从闲逛、巡逻转为追击或攻击时算作开战，脱战后再次开战会重新存档；
+/// 任何存档（包括手动存档）都会重新开始计时；只在游戏中存档，暂停菜单里的手动存档照样重新计时
+#[allow(clippy::too_many_arguments)]
+pub fn request_autosaves(
+    time: Res<Time>,
+    state: Option<Res<State<GameState>>>,
+    settings: Res<AutosaveSettings>,
+    save_settings: Res<SaveSettings>,
+    mut saves: EventReader<SaveEvent>,
+    mut scenes: EventReader<SceneTriggerEntered>,
+    bosses: Query<(Entity, &Npc), Changed<Npc>>,
+    players: Query<&Character, With<Player>>,
+    mut requests: EventWriter<SaveGameRequest>,
+    mut engaged: Local<HashSet<Entity>>,
+    mut since_save: Local<f32>,
+) {
+    *since_save += time.delta_secs();
+    if saves.read().count() > 0 {
+        *since_save = 0.0;
+    }
+    let entered_scene = scenes.read().count() > 0;
+    if !gameplay_running(state) {
+        return;
+    }
+
+    let mut boss_engaged = false;
+    for (entity, npc) in bosses.iter() {
+        if npc.npc_type != NpcType::Boss {
+            continue;
+        }
+        if matches!(npc.ai_state, AiState::Chase | AiState::Attack) {
+            boss_engaged |= engaged.insert(entity);
+        } else {
+            engaged.remove(&entity);
+        }
+    }
+
+    if !settings.enabled {
+        return;
+    }
+    // 倒下时不存档，以免存下一个必死的局面
+    let alive = players
+        .get_single()
+        .is_ok_and(|character| character.state != CharacterState::Dead);
+    if !alive {
+        return;
+    }
+
+    let reason = if boss_engaged && settings.before_boss {
+        SaveReason::BeforeBoss
+    } else if entered_scene && settings.on_scene_enter {
+        SaveReason::SceneEnter
+    } else if settings.interval > 0.0 && *since_save >= settings.interval {
+        SaveReason::Interval
+    } else {
+        return;
+    };
+    if *since_save < settings.min_gap {
+        return;
+    }
+
+    *since_save = 0.0;
+    requests.send(SaveGameRequest {
+        slot: next_autosave_slot(&save_settings.slots_dir, settings.slots),
+        reason,
+    });
+}
@@ -0,0 +1,181 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::combat::SkillBook;
+use crate::events::input::GameAction;
+use crate::items::{Equipment, Inventory};
+use crate::resources::Difficulty;
+use crate::time::GameCalendar;
+use crate::world::chunk::{ChunkCoord, ChunkData};
+use crate::world::entity::{Character, Player};
+
+/// 存档格式版本，存档结构不兼容地变化时递增
+pub const SAVE_VERSION: u32 = 1;
+
+/// 玩家存档
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerSave {
+    pub position: [f32; 3],
+    pub health: f32,
+    pub max_health: f32,
+    pub qi: f32,
+    pub max_qi: f32,
+    pub stamina: f32,
+    pub max_stamina: f32,
+    pub level: u32,
+    pub experience: u32,
+    pub skill_points: u32,
+    pub inventory: Inventory,
+    pub equipment: Equipment,
+    pub learned_skills: Vec<String>,
+    pub skill_slots: HashMap<GameAction, String>,
+}
+
+impl PlayerSave {
+    pub fn capture(
+        transform: &Transform,
+        character: &Character,
+        player: &Player,
+        inventory: Option<&Inventory>,
+        equipment: Option<&Equipment>,
+        skills: Option<&SkillBook>,
+    ) -> Self {
+        Self {
+            position: transform.translation.to_array(),
+            health: character.health,
+            max_health: character.max_health,
+            qi: character.qi,
+            max_qi: character.max_qi,
+            stamina: character.stamina,
+            max_stamina: character.max_stamina,
+            level: player.level,
+            experience: player.experience,
+            skill_points: player.skill_points,
+            inventory: inventory.cloned().unwrap_or_default(),
+            equipment: equipment.cloned().unwrap_or_default(),
+            learned_skills: skills.map(|s| s.learned.clone()).unwrap_or_default(),
+            skill_slots: skills.map(|s| s.slots.clone()).unwrap_or_default(),
+        }
+    }
+
+    /// 把存档写回玩家，冷却与临时状态不恢复
+    pub fn apply(
+        &self,
+        transform: &mut Transform,
+        character: &mut Character,
+        player: &mut Player,
+        inventory: Option<&mut Inventory>,
+        equipment: Option<&mut Equipment>,
+        skills: Option<&mut SkillBook>,
+    ) {
+        transform.translation = Vec3::from_array(self.position);
+        character.max_health = self.max_health;
+        character.health = self.health.min(self.max_health);
+        character.max_qi = self.max_qi;
+        character.qi = self.qi.min(self.max_qi);
+        character.max_stamina = self.max_stamina;
+        character.stamina = self.stamina.min(self.max_stamina);
+        player.level = self.level;
+        player.experience = self.experience;
+        player.skill_points = self.skill_points;
+        if let Some(inventory) = inventory {
+            *inventory = self.inventory.clone();
+        }
+        if let Some(equipment) = equipment {
+            *equipment = self.equipment.clone();
+        }
+        if let Some(skills) = skills {
+            skills.learned = self.learned_skills.clone();
+            skills.slots = self.skill_slots.clone();
+            skills.cooldowns.clear();
+        }
+    }
+}
+
+/// 历法存档，日长等配置不随存档保存
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CalendarSave {
+    pub year: u32,
+    pub month: u32,
+    pub day: u32,
+    pub time_of_day: f32,
+}
+
+impl CalendarSave {
+    pub fn capture(calendar: &GameCalendar) -> Self {
+        Self {
+            year: calendar.year,
+            month: calendar.month,
+            day: calendar.day,
+            time_of_day: calendar.time_of_day,
+        }
+    }
+
+    pub fn apply(&self, calendar: &mut GameCalendar) {
+        calendar.year = self.year.max(1);
+        calendar.month = self.month.clamp(1, calendar.months_per_year());
+        calendar.day = self.day.clamp(1, calendar.days_per_month.max(1));
+        calendar.time_of_day = self.time_of_day.rem_euclid(1.0);
+    }
+}
+
+/// 被修改过的区块
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkEditSave {
+    pub x: i32,
+    pub y: i32,
+    pub data: ChunkData,
+}
+
+impl ChunkEditSave {
+    pub fn coord(&self) -> ChunkCoord {
+        ChunkCoord {
+            x: self.x,
+            y: self.y,
+        }
+    }
+}
+
+/// 主存档文件
+///
+/// 任务、兴趣点、独特NPC等已有存档格式的子系统各自写入槽内的独立文件，
+/// 这里只保存没有独立存档的玩家、历法与修改过的区块
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u32,
+    pub player: Option<PlayerSave>,
+    pub calendar: CalendarSave,
+    #[serde(default)]
+    pub chunks: Vec<ChunkEditSave>,
+    /// 世界种子，旧存档没有记录时沿用当前世界
+    #[serde(default)]
+    pub seed: Option<u32>,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+}
+
+impl SaveGame {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let save: SaveGame = serde_json::from_str(&content)?;
+        if save.version > SAVE_VERSION {
+            return Err(format!(
+                "存档版本 {} 高于游戏支持的版本 {}",
+                save.version, SAVE_VERSION
+            )
+            .into());
+        }
+        Ok(save)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+/// 存档模块
+///
+/// 多槽位保存与读取整个游戏进度
+///
+/// # 模块组成
+/// 1. slot：存档槽目录、槽内文件与存档信息
+/// 2. game：玩家、历法与修改过的区块组成的主存档
+/// 3. autosave：定时、进入场景与首领战前的自动存档，轮流写入几个自动存档槽
+/// 4. reader：在后台线程读取主存档
+/// 5. systems：存档插件、存档流程的阶段与 `SaveEvent`、`SaveCompleted`、`LoadEvent`
+mod autosave;
+mod game;
+mod reader;
+mod slot;
+mod systems;
+
+pub use autosave::*;
+pub use game::*;
+pub use reader::*;
+pub use slot::*;
+pub use systems::*;
@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Mutex;
+
+use super::{SaveGame, SaveSlot, GAME_FILE};
+
+/// 后台读出的主存档
+pub type SaveReadResult = Result<(SaveSlot, SaveGame), String>;
+
+/// 在后台线程读取主存档
+///
+/// 主存档带着全部修改过的区块，体积最大，放到后台线程读取与反序列化，
+/// 加载界面在等待期间照常刷新。各子系统的小文件仍在应用存档时顺带读取
+#[derive(Resource, Default)]
+pub struct SaveReader {
+    /// 正在读取的槽名与结果通道，同时只读一个槽
+    pending: Option<(String, Mutex<Receiver<SaveReadResult>>)>,
+}
+
+impl SaveReader {
+    /// 开始读取存档槽，之前没读完的槽直接丢弃
+    pub fn start(&mut self, slots_dir: &str, slot: &str) {
+        let (sender, receiver) = mpsc::channel();
+        let slots_dir = slots_dir.to_string();
+        let name = slot.to_string();
+        std::thread::spawn(move || {
+            let result = SaveSlot::in_dir(&slots_dir, &name).and_then(|slot| {
+                SaveGame::load(&slot.file(GAME_FILE))
+                    .map(|game| (slot, game))
+                    .map_err(|e| e.to_string())
+            });
+            // 读取期间又开始读别的槽时接收端已丢弃，结果不再需要
+            let _ = sender.send(result);
+        });
+        self.pending = Some((slot.to_string(), Mutex::new(receiver)));
+    }
+
+    /// 是否有存档正在读取
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// 取出读完的存档及其槽名，尚未读完时为空
+    pub fn try_take(&mut self) -> Option<(String, SaveReadResult)> {
+        let (slot, receiver) = self.pending.as_ref()?;
+        let result = match receiver.lock().ok()?.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => Err("读取线程意外退出".to_string()),
+        };
+        let slot = slot.clone();
+        self.pending = None;
+        Some((slot, result))
+    }
+}
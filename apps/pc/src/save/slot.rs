@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::resources::Difficulty;
+
+/// 存档槽所在目录，每个槽一个子目录
+pub const SAVE_SLOTS_DIR: &str = "saves/slots";
+
+/// 槽内各文件名
+pub const METADATA_FILE: &str = "meta.json";
+pub const GAME_FILE: &str = "game.json";
+pub const QUESTS_FILE: &str = "quests.json";
+pub const POI_FILE: &str = "poi.json";
+pub const EXPLORATION_FILE: &str = "exploration.json";
+pub const MAP_PINS_FILE: &str = "map_pins.json";
+pub const POPULATION_FILE: &str = "npc_population.json";
+pub const HOUSING_FILE: &str = "housing.json";
+pub const HARVEST_FILE: &str = "harvest.json";
+pub const SHOPS_FILE: &str = "shops.json";
+pub const REPUTATION_FILE: &str = "reputation.json";
+pub const STASHES_FILE: &str = "stashes.json";
+pub const SCREENSHOT_FILE: &str = "screenshot.png";
+
+/// 存档原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SaveReason {
+    /// 玩家手动存档
+    #[default]
+    Manual,
+    /// 定时自动存档
+    Interval,
+    /// 进入场景时自动存档
+    SceneEnter,
+    /// 首领战前自动存档
+    BeforeBoss,
+    /// 崩溃时把未存档的区块修改并入最近的存档
+    CrashRecovery,
+    /// 显卡设备丢失等无法恢复的渲染故障后紧急存档
+    RenderFailure,
+}
+
+/// 存档槽信息，读档界面据此列出存档，不必读取完整存档
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveMetadata {
+    pub slot: String,
+    /// 保存时的本地时间，RFC 3339 格式
+    pub saved_at: String,
+    /// 累计游玩时长（秒）
+    pub playtime: f64,
+    /// 游戏内日期
+    pub date_label: String,
+    /// 截图路径，截图失败时为空
+    #[serde(default)]
+    pub screenshot: Option<String>,
+    #[serde(default)]
+    pub reason: SaveReason,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+}
+
+impl SaveMetadata {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// 游玩时长文字，如 "3小时25分"
+    pub fn playtime_label(&self) -> String {
+        let minutes = (self.playtime / 60.0) as u64;
+        format!("{}小时{}分", minutes / 60, minutes % 60)
+    }
+}
+
+/// 存档槽
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveSlot {
+    pub name: String,
+    root: PathBuf,
+}
+
+impl SaveSlot {
+    pub fn in_dir(dir: impl AsRef<Path>, name: &str) -> Result<Self, String> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            return Err(format!("存档槽名称无效: {:?}", name));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            root: dir.as_ref().join(name),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.root
+    }
+
+    /// 槽内文件的路径
+    pub fn file(&self, name: &str) -> PathBuf {
+        self.root.join(name)
+    }
+
+    /// 槽内文件路径的字符串形式，供只接受 `&str` 的存档接口使用
+    pub fn file_str(&self, name: &str) -> String {
+        self.file(name).to_string_lossy().into_owned()
+    }
+
+    pub fn metadata(&self) -> Result<SaveMetadata, Box<dyn std::error::Error>> {
+        SaveMetadata::load(&self.file(METADATA_FILE))
+    }
+
+    /// 删除整个槽
+    pub fn delete(&self) -> std::io::Result<()> {
+        if self.root.exists() {
+            fs::remove_dir_all(&self.root)?;
+        }
+        Ok(())
+    }
+}
+
+/// 列出目录下所有存档槽的信息，最近保存的排在前面，读不出信息的槽跳过
+pub fn list_slots(dir: impl AsRef<Path>) -> Vec<SaveMetadata> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut slots: Vec<SaveMetadata> = entries
+        .flatten()
+        .filter_map(|entry| SaveMetadata::load(&entry.path().join(METADATA_FILE)).ok())
+        .collect();
+    slots.sort_by(|a, b| b.saved_at.cmp(&a.saved_at));
+    slots
+}
@@ -0,0 +1,675 @@
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{save_to_disk, Screenshot};
+use chrono::Local;
+use std::time::{Duration, Instant};
+
+use super::{
+    request_autosaves, AutosaveSettings, CalendarSave, ChunkEditSave, PlayerSave, SaveGame,
+    SaveMetadata, SaveReader, SaveReason, SaveSlot, EXPLORATION_FILE, GAME_FILE, HARVEST_FILE,
+    HOUSING_FILE, MAP_PINS_FILE, METADATA_FILE, POI_FILE, POPULATION_FILE, QUESTS_FILE,
+    REPUTATION_FILE, SAVE_SLOTS_DIR, SAVE_VERSION, SCREENSHOT_FILE, SHOPS_FILE, STASHES_FILE,
+};
+use crate::combat::SkillBook;
+use crate::housing::{CurrentInterior, HousingState};
+use crate::items::{Equipment, Inventory, OpenStash, ShopRegistry, StashRegistry};
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::{gameplay_running, Difficulty, DifficultyModifiers};
+use crate::time::GameCalendar;
+use crate::world::chunk::{ChunkEdits, ChunkManager, ChunkResident};
+use crate::world::entity::{spawn_player, update_npc_ai, Character, Player};
+use crate::world::harvest::HarvestRegistry;
+use crate::world::map::{MapManager, QuestManager, Reputation, SceneTriggerEntered};
+use crate::world::poi::{ExploredChunks, MapPins, PoiRegistry};
+use crate::world::population::PopulationRegistry;
+
+/// 存档设置
+///
+/// - slots_dir: 存档槽所在目录
+/// - screenshots: 保存时是否截图
+#[derive(Resource, Debug, Clone)]
+pub struct SaveSettings {
+    pub slots_dir: String,
+    pub screenshots: bool,
+}
+
+impl Default for SaveSettings {
+    fn default() -> Self {
+        Self {
+            slots_dir: SAVE_SLOTS_DIR.to_string(),
+            screenshots: true,
+        }
+    }
+}
+
+/// 累计游玩时长，暂停时不计
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Playtime {
+    pub seconds: f64,
+}
+
+/// 请求保存到指定存档槽
+#[derive(Event, Debug, Clone)]
+pub struct SaveGameRequest {
+    pub slot: String,
+    pub reason: SaveReason,
+}
+
+impl SaveGameRequest {
+    /// 手动存档
+    pub fn manual(slot: &str) -> Self {
+        Self {
+            slot: slot.to_string(),
+            reason: SaveReason::Manual,
+        }
+    }
+}
+
+/// 请求读取指定存档槽
+#[derive(Event, Debug, Clone)]
+pub struct LoadGameRequest {
+    pub slot: String,
+}
+
+/// 请求开始新游戏：按种子重新生成世界，清空上一局的进度
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGameRequest {
+    pub seed: u32,
+    pub difficulty: Difficulty,
+}
+
+/// 即将写入存档
+///
+/// 在 `SaveSet::Flush` 中读取，把缓存在别处、尚未写回各子系统状态的数据写回，
+/// 写入存档的系统在其后运行
+#[derive(Event, Debug, Clone)]
+pub struct SaveEvent {
+    pub slot: String,
+    pub reason: SaveReason,
+}
+
+/// 一次存档写完，耗时不含渲染完成后才写入的截图
+#[derive(Event, Debug, Clone)]
+pub struct SaveCompleted {
+    pub reason: SaveReason,
+    pub duration: Duration,
+    /// 槽内所有文件都写入成功
+    pub complete: bool,
+}
+
+/// 读档完成，各子系统的状态已替换，区块会在本帧稍后重新加载
+#[derive(Event, Debug, Clone)]
+pub struct LoadEvent {
+    pub slot: String,
+}
+
+/// 存档流程的阶段，都在 PreUpdate 中依次运行
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SaveSet {
+    /// 检查存档请求并发出 `SaveEvent`
+    Request,
+    /// 其他系统在这里响应 `SaveEvent`、`LoadEvent`
+    Flush,
+    /// 读档与写入存档
+    Write,
+}
+
+/// 存档插件
+///
+/// # 设计思路
+/// 1. 每个存档槽是一个目录：元信息、主存档、截图，以及各子系统自己格式的存档文件
+/// 2. 任务、兴趣点、独特NPC、宅院、采集点、商铺、声望与仓库等沿用各自的存档读写，这里只负责把它们指向槽内的路径
+/// 3. 读档替换各子系统的状态后卸载全部区块，区块重新加载时按读入的状态登记兴趣点、恢复NPC
+/// 4. 读写在 PreUpdate 中处理，卸载区块的命令在 Update 之前生效
+/// 5. 其他系统通过 `SaveSet::Flush` 挂接存档流程，自动存档只是发出存档请求
+/// 6. 新游戏与读档走同一条路：替换状态后卸载区块；世界种子与难度随主存档保存
+/// 7. 读档请求先交给后台线程读主存档，读完的那一帧再替换状态，加载界面不会因读盘卡住
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SaveSettings>()
+            .init_resource::<Playtime>()
+            .init_resource::<AutosaveSettings>()
+            .init_resource::<SaveReader>()
+            .add_event::<SaveGameRequest>()
+            .add_event::<LoadGameRequest>()
+            .add_event::<NewGameRequest>()
+            .add_event::<SaveEvent>()
+            .add_event::<SaveCompleted>()
+            .add_event::<LoadEvent>()
+            .add_event::<SceneTriggerEntered>()
+            .configure_sets(
+                PreUpdate,
+                (SaveSet::Request, SaveSet::Flush, SaveSet::Write).chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    track_playtime.run_if(gameplay_running),
+                    request_autosaves.after(update_npc_ai),
+                ),
+            )
+            .add_systems(
+                PreUpdate,
+                (begin_saves, begin_save_reads).in_set(SaveSet::Request),
+            )
+            .add_systems(
+                PreUpdate,
+                (start_new_game, load_game, save_game)
+                    .chain()
+                    .in_set(SaveSet::Write),
+            );
+    }
+}
+
+fn track_playtime(time: Res<Time>, mut playtime: ResMut<Playtime>) {
+    playtime.seconds += time.delta_secs_f64();
+}
+
+/// 检查存档请求，槽名有效的发出 `SaveEvent`
+fn begin_saves(
+    settings: Res<SaveSettings>,
+    mut requests: EventReader<SaveGameRequest>,
+    mut saves: EventWriter<SaveEvent>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for request in requests.read() {
+        if let Err(e) = SaveSlot::in_dir(&settings.slots_dir, &request.slot) {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &e);
+            }
+            continue;
+        }
+        saves.send(SaveEvent {
+            slot: request.slot.clone(),
+            reason: request.reason,
+        });
+    }
+}
+
+/// 开始在后台读取请求的存档槽，同一帧多次读档只有最后一次有意义
+fn begin_save_reads(
+    settings: Res<SaveSettings>,
+    mut requests: EventReader<LoadGameRequest>,
+    mut reader: ResMut<SaveReader>,
+) {
+    if let Some(request) = requests.read().last() {
+        reader.start(&settings.slots_dir, &request.slot);
+    }
+}
+
+/// 保存到存档槽
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+fn save_game(
+    mut commands: Commands,
+    settings: Res<SaveSettings>,
+    mut requests: EventReader<SaveEvent>,
+    mut completed: EventWriter<SaveCompleted>,
+    playtime: Res<Playtime>,
+    calendar: Res<GameCalendar>,
+    difficulty: Res<DifficultyModifiers>,
+    map_manager: Option<Res<MapManager>>,
+    quests: Option<Res<QuestManager>>,
+    pois: Option<Res<PoiRegistry>>,
+    mut explored: Option<ResMut<ExploredChunks>>,
+    pins: Option<Res<MapPins>>,
+    mut world_state: (
+        Option<ResMut<PopulationRegistry>>,
+        Option<Res<HousingState>>,
+        Option<Res<HarvestRegistry>>,
+        Option<Res<ShopRegistry>>,
+        Option<Res<Reputation>>,
+        Option<Res<StashRegistry>>,
+    ),
+    edits: Option<Res<ChunkEdits>>,
+    players: Query<(
+        &Transform,
+        &Character,
+        &Player,
+        Option<&Inventory>,
+        Option<&Equipment>,
+        Option<&SkillBook>,
+    )>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for request in requests.read() {
+        let started = Instant::now();
+        let slot = match SaveSlot::in_dir(&settings.slots_dir, &request.slot) {
+            Ok(slot) => slot,
+            Err(e) => {
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(LogLevel::Error, &e);
+                }
+                continue;
+            }
+        };
+
+        let mut errors = Vec::new();
+        if let Some(quests) = &quests {
+            if let Err(e) = quests.save_progress(&slot.file_str(QUESTS_FILE)) {
+                errors.push(format!("任务进度: {}", e));
+            }
+        }
+        if let Some(pois) = &pois {
+            if let Err(e) = pois.save(&slot.file_str(POI_FILE)) {
+                errors.push(format!("兴趣点: {}", e));
+            }
+        }
+        if let Some(explored) = explored.as_mut() {
+            if let Err(e) = explored.save(&slot.file_str(EXPLORATION_FILE)) {
+                errors.push(format!("探索进度: {}", e));
+            }
+        }
+        if let Some(pins) = &pins {
+            if let Err(e) = pins.save(&slot.file_str(MAP_PINS_FILE)) {
+                errors.push(format!("地图标注: {}", e));
+            }
+        }
+        let (population, housing, harvest, shops, reputation, stashes) = &mut world_state;
+        if let Some(population) = population.as_mut() {
+            if let Err(e) = population.save(&slot.file_str(POPULATION_FILE)) {
+                errors.push(format!("独特NPC: {}", e));
+            }
+        }
+        if let Some(housing) = housing {
+            if let Err(e) = housing.save(&slot.file_str(HOUSING_FILE)) {
+                errors.push(format!("宅院: {}", e));
+            }
+        }
+        if let Some(harvest) = harvest {
+            if let Err(e) = harvest.save(&slot.file_str(HARVEST_FILE)) {
+                errors.push(format!("采集点: {}", e));
+            }
+        }
+        if let Some(shops) = shops {
+            if let Err(e) = shops.save(&slot.file_str(SHOPS_FILE)) {
+                errors.push(format!("商铺: {}", e));
+            }
+        }
+        if let Some(reputation) = reputation {
+            if let Err(e) = reputation.save(&slot.file_str(REPUTATION_FILE)) {
+                errors.push(format!("声望: {}", e));
+            }
+        }
+        if let Some(stashes) = stashes {
+            if let Err(e) = stashes.save(&slot.file_str(STASHES_FILE)) {
+                errors.push(format!("仓库: {}", e));
+            }
+        }
+
+        let player = players.get_single().ok().map(
+            |(transform, character, player, inventory, equipment, skills)| {
+                PlayerSave::capture(transform, character, player, inventory, equipment, skills)
+            },
+        );
+        let chunks = edits
+            .as_ref()
+            .map(|edits| {
+                edits
+                    .iter()
+                    .map(|(coord, data)| ChunkEditSave {
+                        x: coord.x,
+                        y: coord.y,
+                        data: data.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let game = SaveGame {
+            version: SAVE_VERSION,
+            player,
+            calendar: CalendarSave::capture(&calendar),
+            chunks,
+            seed: map_manager.as_ref().map(|map_manager| map_manager.seed),
+            difficulty: difficulty.difficulty,
+        };
+        if let Err(e) = game.save(&slot.file(GAME_FILE)) {
+            errors.push(format!("主存档: {}", e));
+        }
+
+        // 截图在渲染完成后才写入磁盘，元信息先记下路径
+        let screenshot = settings.screenshots.then(|| {
+            let path = slot.file(SCREENSHOT_FILE);
+            commands
+                .spawn(Screenshot::primary_window())
+                .observe(save_to_disk(path.clone()));
+            path.to_string_lossy().into_owned()
+        });
+        let metadata = SaveMetadata {
+            slot: slot.name.clone(),
+            saved_at: Local::now().to_rfc3339(),
+            playtime: playtime.seconds,
+            date_label: calendar.date_label(),
+            screenshot,
+            reason: request.reason,
+            difficulty: difficulty.difficulty,
+        };
+        if let Err(e) = metadata.save(&slot.file(METADATA_FILE)) {
+            errors.push(format!("存档信息: {}", e));
+        }
+        completed.send(SaveCompleted {
+            reason: request.reason,
+            duration: started.elapsed(),
+            complete: errors.is_empty(),
+        });
+
+        if let Some(logger) = logger.as_mut() {
+            if errors.is_empty() {
+                logger.log(LogLevel::Info, &format!("已保存到存档槽 {}", slot.name));
+            } else {
+                logger.log(
+                    LogLevel::Error,
+                    &format!("存档槽 {} 保存不完整: {}", slot.name, errors.join("；")),
+                );
+            }
+        }
+    }
+}
+
+/// 应用后台读完的存档槽
+///
+/// 槽内缺少的子系统文件按新游戏处理，只有主存档读不出来时整个读档放弃
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+fn load_game(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut reader: ResMut<SaveReader>,
+    mut playtime: ResMut<Playtime>,
+    mut calendar: ResMut<GameCalendar>,
+    mut difficulty: ResMut<DifficultyModifiers>,
+    mut map_manager: Option<ResMut<MapManager>>,
+    mut quests: Option<ResMut<QuestManager>>,
+    mut chunk_manager: Option<ResMut<ChunkManager>>,
+    mut edits: Option<ResMut<ChunkEdits>>,
+    mut players: Query<(
+        &mut Transform,
+        &mut Character,
+        &mut Player,
+        Option<&mut Inventory>,
+        Option<&mut Equipment>,
+        Option<&mut SkillBook>,
+    )>,
+    residents: Query<Entity, With<ChunkResident>>,
+    mut loaded: EventWriter<LoadEvent>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let Some((name, result)) = reader.try_take() else {
+        return;
+    };
+    let (slot, game) = match result {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("读取存档槽 {} 失败: {}", name, e));
+            }
+            return;
+        }
+    };
+
+    let mut errors = Vec::new();
+    let exists = |file: &str| slot.file(file).is_file();
+
+    game.calendar.apply(&mut calendar);
+    *difficulty = DifficultyModifiers::for_difficulty(game.difficulty);
+    match players.get_single_mut() {
+        Ok((mut transform, mut character, mut player, inventory, equipment, skills)) => {
+            if let Some(save) = &game.player {
+                save.apply(
+                    &mut transform,
+                    &mut character,
+                    &mut player,
+                    inventory.map(Mut::into_inner),
+                    equipment.map(Mut::into_inner),
+                    skills.map(Mut::into_inner),
+                );
+            }
+        }
+        // 从主菜单读档时还没有玩家
+        Err(_) => spawn_saved_player(&mut commands, &asset_server, game.player.as_ref()),
+    }
+    if let Ok(metadata) = slot.metadata() {
+        playtime.seconds = metadata.playtime;
+    }
+
+    if let Some(quests) = quests.as_mut() {
+        if exists(QUESTS_FILE) {
+            if let Err(e) = quests.load_progress(&slot.file_str(QUESTS_FILE)) {
+                errors.push(format!("任务进度: {}", e));
+            }
+        }
+    }
+    let pois = if exists(POI_FILE) {
+        PoiRegistry::load(&slot.file_str(POI_FILE)).unwrap_or_else(|e| {
+            errors.push(format!("兴趣点: {}", e));
+            PoiRegistry::default()
+        })
+    } else {
+        PoiRegistry::default()
+    };
+    commands.insert_resource(pois);
+    let explored = if exists(EXPLORATION_FILE) {
+        ExploredChunks::load(&slot.file_str(EXPLORATION_FILE)).unwrap_or_else(|e| {
+            errors.push(format!("探索进度: {}", e));
+            ExploredChunks::default()
+        })
+    } else {
+        ExploredChunks::default()
+    };
+    commands.insert_resource(explored);
+    let pins = if exists(MAP_PINS_FILE) {
+        MapPins::load(&slot.file_str(MAP_PINS_FILE)).unwrap_or_else(|e| {
+            errors.push(format!("地图标注: {}", e));
+            MapPins::default()
+        })
+    } else {
+        MapPins::default()
+    };
+    commands.insert_resource(pins);
+    let population = if exists(POPULATION_FILE) {
+        PopulationRegistry::load(&slot.file_str(POPULATION_FILE)).unwrap_or_else(|e| {
+            errors.push(format!("独特NPC: {}", e));
+            PopulationRegistry::default()
+        })
+    } else {
+        PopulationRegistry::default()
+    };
+    commands.insert_resource(population);
+    let housing = if exists(HOUSING_FILE) {
+        HousingState::load(&slot.file_str(HOUSING_FILE)).unwrap_or_else(|e| {
+            errors.push(format!("宅院: {}", e));
+            HousingState::default()
+        })
+    } else {
+        HousingState::default()
+    };
+    commands.insert_resource(housing);
+    // 读档后站在存档中的位置，不在任何宅院室内
+    commands.insert_resource(CurrentInterior::default());
+    let harvest = if exists(HARVEST_FILE) {
+        HarvestRegistry::load(&slot.file_str(HARVEST_FILE)).unwrap_or_else(|e| {
+            errors.push(format!("采集点: {}", e));
+            HarvestRegistry::default()
+        })
+    } else {
+        HarvestRegistry::default()
+    };
+    commands.insert_resource(harvest);
+    let shops = if exists(SHOPS_FILE) {
+        ShopRegistry::load(&slot.file_str(SHOPS_FILE)).unwrap_or_else(|e| {
+            errors.push(format!("商铺: {}", e));
+            ShopRegistry::default()
+        })
+    } else {
+        ShopRegistry::default()
+    };
+    commands.insert_resource(shops);
+    let reputation = if exists(REPUTATION_FILE) {
+        Reputation::load(&slot.file_str(REPUTATION_FILE)).unwrap_or_else(|e| {
+            errors.push(format!("声望: {}", e));
+            Reputation::default()
+        })
+    } else {
+        Reputation::default()
+    };
+    commands.insert_resource(reputation);
+    let stashes = if exists(STASHES_FILE) {
+        StashRegistry::load(&slot.file_str(STASHES_FILE)).unwrap_or_else(|e| {
+            errors.push(format!("仓库: {}", e));
+            StashRegistry::default()
+        })
+    } else {
+        StashRegistry::default()
+    };
+    commands.insert_resource(stashes);
+    commands.insert_resource(OpenStash::default());
+
+    // 卸载全部区块与居民，重新加载时使用读入的区块修改、兴趣点与NPC状态
+    if let Some(edits) = edits.as_mut() {
+        edits.replace(
+            game.chunks
+                .into_iter()
+                .map(|chunk| (chunk.coord(), chunk.data)),
+        );
+    }
+    if let Some(chunk_manager) = chunk_manager.as_mut() {
+        // 存档来自另一个种子的世界时按存档的种子重新生成
+        if let (Some(seed), Some(map_manager)) = (game.seed, map_manager.as_mut()) {
+            if map_manager.seed != seed {
+                map_manager.reseed(seed);
+                chunk_manager.initialize_terrain_generator(map_manager);
+            }
+        }
+        for (_, entity) in chunk_manager.chunks.drain() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    for entity in residents.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    loaded.send(LoadEvent {
+        slot: slot.name.clone(),
+    });
+
+    if let Some(logger) = logger.as_mut() {
+        if errors.is_empty() {
+            logger.log(LogLevel::Info, &format!("已读取存档槽 {}", slot.name));
+        } else {
+            logger.log(
+                LogLevel::Error,
+                &format!("存档槽 {} 读取不完整: {}", slot.name, errors.join("；")),
+            );
+        }
+    }
+}
+
+/// 开始新游戏
+///
+/// 与读档一样替换各子系统的状态后卸载全部区块，只是换成新种子与空白进度
+#[allow(clippy::too_many_arguments)]
+fn start_new_game(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut requests: EventReader<NewGameRequest>,
+    mut playtime: ResMut<Playtime>,
+    mut calendar: ResMut<GameCalendar>,
+    mut difficulty: ResMut<DifficultyModifiers>,
+    mut map_manager: Option<ResMut<MapManager>>,
+    mut quests: Option<ResMut<QuestManager>>,
+    mut chunk_manager: Option<ResMut<ChunkManager>>,
+    mut edits: Option<ResMut<ChunkEdits>>,
+    residents: Query<Entity, With<ChunkResident>>,
+    players: Query<Entity, With<Player>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let Some(request) = requests.read().last().copied() else {
+        return;
+    };
+
+    playtime.seconds = 0.0;
+    // 日期回到开局，日长等配置不变
+    CalendarSave::capture(&GameCalendar::default()).apply(&mut calendar);
+    *difficulty = DifficultyModifiers::for_difficulty(request.difficulty);
+    if let Some(quests) = quests.as_mut() {
+        quests.reset_progress();
+    }
+    commands.insert_resource(PoiRegistry::default());
+    commands.insert_resource(ExploredChunks::default());
+    commands.insert_resource(MapPins::default());
+    commands.insert_resource(PopulationRegistry::default());
+    commands.insert_resource(HousingState::default());
+    commands.insert_resource(CurrentInterior::default());
+    commands.insert_resource(HarvestRegistry::default());
+    commands.insert_resource(ShopRegistry::default());
+    commands.insert_resource(Reputation::default());
+    commands.insert_resource(StashRegistry::default());
+    commands.insert_resource(OpenStash::default());
+    if let Some(edits) = edits.as_mut() {
+        edits.replace(std::iter::empty());
+    }
+
+    if let Some(chunk_manager) = chunk_manager.as_mut() {
+        if let Some(map_manager) = map_manager.as_mut() {
+            if map_manager.seed != request.seed {
+                map_manager.reseed(request.seed);
+                chunk_manager.initialize_terrain_generator(map_manager);
+            }
+        }
+        for (_, entity) in chunk_manager.chunks.drain() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    for entity in residents.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    // 上一局的角色连同背包、技能一起换成新角色
+    for entity in players.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    spawn_saved_player(&mut commands, &asset_server, None);
+
+    if let Some(logger) = logger.as_mut() {
+        logger.log(
+            LogLevel::Info,
+            &format!(
+                "开始新游戏，种子 {}，难度{}",
+                request.seed,
+                request.difficulty.label()
+            ),
+        );
+    }
+}
+
+/// 在出生点生成玩家，有存档时换成存档中的位置、属性、背包与技能
+fn spawn_saved_player(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    save: Option<&PlayerSave>,
+) {
+    let entity = spawn_player(commands, asset_server, Vec3::ZERO);
+    let Some(save) = save else {
+        return;
+    };
+
+    let mut transform = Transform::default();
+    let mut character = Character {
+        name: "Player".to_string(),
+        ..default()
+    };
+    let mut player = Player::default();
+    let mut inventory = Inventory::default();
+    let mut equipment = Equipment::default();
+    let mut skills = SkillBook::default();
+    save.apply(
+        &mut transform,
+        &mut character,
+        &mut player,
+        Some(&mut inventory),
+        Some(&mut equipment),
+        Some(&mut skills),
+    );
+    commands
+        .entity(entity)
+        .insert((transform, character, player, inventory, equipment, skills));
+}
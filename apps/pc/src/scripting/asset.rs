@@ -0,0 +1,69 @@
+use bevy::asset::{io::Reader, Asset, AssetId, AssetLoader, Handle, LoadContext, LoadedFolder};
+use bevy::ecs::system::Resource;
+use bevy::reflect::TypePath;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// 脚本所在的目录（相对于 assets）
+pub const SCRIPT_FOLDER: &str = "scripts";
+
+/// 脚本资源
+///
+/// 一个脚本一个 `*.rhai` 文件，文件名（不含扩展名）即脚本ID，
+/// 任务、对话与NPC按脚本ID引用
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct ScriptSource {
+    pub id: String,
+    pub source: String,
+}
+
+/// 脚本资源加载错误
+#[derive(Debug, Error)]
+pub enum ScriptLoaderError {
+    #[error("读取脚本失败: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("脚本不是 UTF-8 文本: {0}")]
+    Utf8(#[from] std::string::FromUtf8Error),
+}
+
+/// 脚本资源加载器，只读取源码，编译交给脚本引擎
+#[derive(Default)]
+pub struct ScriptLoader;
+
+impl AssetLoader for ScriptLoader {
+    type Asset = ScriptSource;
+    type Settings = ();
+    type Error = ScriptLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<ScriptSource, ScriptLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let id = load_context
+            .path()
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok(ScriptSource {
+            id,
+            source: String::from_utf8(bytes)?,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["rhai"]
+    }
+}
+
+/// 已加载的脚本资源
+#[derive(Resource, Default)]
+pub struct ScriptLibrary {
+    /// 脚本目录句柄，保持目录中的资源不被卸载
+    pub folder: Option<Handle<LoadedFolder>>,
+    /// 资源ID到脚本ID的映射，用于处理修改和移除
+    pub ids: HashMap<AssetId<ScriptSource>, String>,
+}
@@ -0,0 +1,492 @@
+use bevy::prelude::*;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::items::Inventory;
+use crate::world::chunk::TILE_PIXELS;
+use crate::world::entity::{AiState, NpcType};
+use crate::world::map::quest::{Effect, QuestManager, QuestStatus};
+use crate::world::map::Reputation;
+use crate::world::weather::{WeatherKind, WeatherState};
+
+/// 脚本设置
+///
+/// - max_operations: 单次执行的操作数上限，写出死循环的脚本会被中止
+/// - npc_interval: NPC行为脚本的执行间隔（秒）
+#[derive(Resource, Debug, Clone)]
+pub struct ScriptSettings {
+    pub max_operations: u64,
+    pub npc_interval: f32,
+}
+
+impl Default for ScriptSettings {
+    fn default() -> Self {
+        Self {
+            max_operations: 50_000,
+            npc_interval: 1.0,
+        }
+    }
+}
+
+/// 脚本提出的指令，由脚本插件在游戏线程中落实
+#[derive(Event, Debug, Clone)]
+pub enum ScriptCommand {
+    /// 任务、物品、银两与提示，交给任务系统执行
+    Quest(Effect),
+    /// 改变在某一方的声望
+    ChangeReputation { faction: String, amount: i32 },
+    /// 在指定瓦片生成NPC
+    SpawnNpc {
+        name: String,
+        npc_type: NpcType,
+        tile: IVec2,
+    },
+    /// 与指定名字的NPC开始一段对话
+    StartDialogue { npc: String, dialogue_id: String },
+    /// 改变天气
+    SetWeather { kind: WeatherKind, intensity: f32 },
+    /// 改变指定名字的NPC的AI状态
+    SetNpcState { npc: String, state: AiState },
+    /// 设定过场节奏（时间流速倍率），为空时恢复正常流速
+    CutscenePace { scale: Option<f32> },
+}
+
+/// 交给脚本读取的游戏状态快照
+///
+/// 脚本执行期间不接触 ECS，只读取快照，所有修改都以指令的形式提出
+#[derive(Debug, Clone, Default)]
+pub struct ScriptWorld {
+    /// 已接取的任务：状态与当前阶段ID
+    quests: HashMap<String, (QuestStatus, String)>,
+    inventory: Option<Inventory>,
+    reputation: Reputation,
+    weather: Option<WeatherKind>,
+    player_tile: Option<IVec2>,
+}
+
+impl ScriptWorld {
+    /// 记录任务进度、背包与声望
+    pub fn capture(
+        quests: &QuestManager,
+        inventory: Option<&Inventory>,
+        reputation: &Reputation,
+    ) -> Self {
+        let quests = quests
+            .states()
+            .map(|(id, state)| {
+                let stage = quests
+                    .quest(id)
+                    .and_then(|quest| quest.stage(state.stage))
+                    .map_or(String::new(), |stage| stage.id.clone());
+                (id.clone(), (state.status, stage))
+            })
+            .collect();
+        Self {
+            quests,
+            inventory: inventory.cloned(),
+            reputation: reputation.clone(),
+            ..default()
+        }
+    }
+
+    /// 再记录天气与玩家位置，对话条件不需要这些
+    pub fn with_surroundings(
+        mut self,
+        weather: Option<&WeatherState>,
+        player: Option<&Transform>,
+    ) -> Self {
+        self.weather = weather.map(|weather| weather.kind);
+        self.player_tile = player.map(|transform| {
+            (transform.translation.truncate() / TILE_PIXELS)
+                .floor()
+                .as_ivec2()
+        });
+        self
+    }
+}
+
+/// 一次脚本执行的上下文，注册给脚本的函数通过它读取快照、记录指令
+#[derive(Default)]
+struct ScriptFrame {
+    world: ScriptWorld,
+    /// 对话条件等只读场合为 false，此时提出指令会报错
+    writable: bool,
+    commands: Vec<ScriptCommand>,
+}
+
+/// 编译好的脚本
+struct CompiledScript {
+    ast: AST,
+    /// 脚本中 `npcs()` 返回的NPC名字，这些NPC按该脚本行动
+    npcs: Vec<String>,
+}
+
+/// 脚本引擎
+///
+/// # 设计思路
+/// 1. 沙箱：脚本不能加载模块、不能 eval，操作数、调用深度与字符串长度都有上限；
+///    能调用的只有下面注册的函数，读取快照或提出指令
+/// 2. 指令由各系统在游戏线程中落实，脚本出错时本次提出的指令全部作废
+/// 3. 脚本随资源热重载重新编译，编译失败时保留旧版本
+#[derive(Resource)]
+pub struct ScriptHost {
+    engine: Engine,
+    frame: Arc<Mutex<ScriptFrame>>,
+    /// 同一时间只执行一个脚本，多个系统并行调用时共用一个上下文
+    running: Mutex<()>,
+    scripts: HashMap<String, CompiledScript>,
+    /// 尚未写入日志的错误
+    errors: Mutex<Vec<String>>,
+}
+
+impl ScriptHost {
+    pub fn new(settings: &ScriptSettings) -> Self {
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(settings.max_operations)
+            .set_max_call_levels(32)
+            .set_max_expr_depths(64, 32)
+            .set_max_string_size(4096)
+            .set_max_array_size(1024)
+            .set_max_map_size(256)
+            .set_module_resolver(DummyModuleResolver::new())
+            .disable_symbol("eval")
+            .on_print(|text| info!("[脚本] {}", text))
+            .on_debug(|text, _, _| debug!("[脚本] {}", text));
+
+        let frame = Arc::new(Mutex::new(ScriptFrame::default()));
+        register_api(&mut engine, &frame);
+        Self {
+            engine,
+            frame,
+            running: Mutex::new(()),
+            scripts: HashMap::new(),
+            errors: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 编译并替换脚本，编译失败时返回错误，旧版本保留
+    pub fn insert(&mut self, id: &str, source: &str) -> Result<(), String> {
+        let ast = self
+            .engine
+            .compile(source)
+            .map_err(|e| format!("脚本 {} 编译失败: {}", id, e))?;
+        let declares_npcs = ast
+            .iter_functions()
+            .any(|function| function.name == "npcs" && function.params.is_empty());
+        let npcs = if declares_npcs {
+            let value = self.call(
+                &ast,
+                Some("npcs"),
+                Vec::new(),
+                ScriptWorld::default(),
+                false,
+            );
+            value
+                .map_err(|e| format!("脚本 {} 的 npcs() 出错: {}", id, e))?
+                .0
+                .into_array()
+                .map_err(|_| format!("脚本 {} 的 npcs() 须返回名字数组", id))?
+                .into_iter()
+                .filter_map(|name| name.into_string().ok())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        self.scripts
+            .insert(id.to_string(), CompiledScript { ast, npcs });
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.scripts.remove(id);
+    }
+
+    /// 指定名字的NPC按哪个脚本行动
+    pub fn script_for_npc(&self, name: &str) -> Option<&str> {
+        self.scripts
+            .iter()
+            .find(|(_, script)| script.npcs.iter().any(|npc| npc == name))
+            .map(|(id, _)| id.as_str())
+    }
+
+    /// 执行脚本，function 为空时执行脚本的顶层语句；出错时记录错误并返回空指令
+    pub fn run(
+        &self,
+        script: &str,
+        function: Option<&str>,
+        args: Vec<Dynamic>,
+        world: ScriptWorld,
+    ) -> Vec<ScriptCommand> {
+        let result = match self.scripts.get(script) {
+            Some(compiled) => self.call(&compiled.ast, function, args, world, true),
+            None => Err("脚本未加载".to_string()),
+        };
+        match result {
+            Ok((_, commands)) => commands,
+            Err(e) => {
+                self.report(format!("脚本 {} 出错: {}", script, e));
+                Vec::new()
+            }
+        }
+    }
+
+    /// 判断脚本条件，脚本只能读取状态，须返回 true 或 false；出错时视为不成立
+    pub fn check(&self, script: &str, function: Option<&str>, world: ScriptWorld) -> bool {
+        let result = match self.scripts.get(script) {
+            Some(compiled) => self.call(&compiled.ast, function, Vec::new(), world, false),
+            None => Err("脚本未加载".to_string()),
+        };
+        match result.and_then(|(value, _)| {
+            value
+                .as_bool()
+                .map_err(|kind| format!("条件须返回 true 或 false，实际为 {}", kind))
+        }) {
+            Ok(holds) => holds,
+            Err(e) => {
+                self.report(format!("脚本条件 {} 出错: {}", script, e));
+                false
+            }
+        }
+    }
+
+    /// 取出尚未写入日志的错误
+    pub fn take_errors(&self) -> Vec<String> {
+        std::mem::take(&mut *lock(&self.errors))
+    }
+
+    fn report(&self, error: String) {
+        lock(&self.errors).push(error);
+    }
+
+    fn call(
+        &self,
+        ast: &AST,
+        function: Option<&str>,
+        args: Vec<Dynamic>,
+        world: ScriptWorld,
+        writable: bool,
+    ) -> Result<(Dynamic, Vec<ScriptCommand>), String> {
+        let _running = lock(&self.running);
+        {
+            let mut frame = lock(&self.frame);
+            frame.world = world;
+            frame.writable = writable;
+            frame.commands.clear();
+        }
+
+        let mut scope = Scope::new();
+        let result = match function {
+            // 只调用函数，不重复执行顶层语句
+            Some(name) => self.engine.call_fn_with_options::<Dynamic>(
+                CallFnOptions::new().eval_ast(false),
+                &mut scope,
+                ast,
+                name,
+                args,
+            ),
+            None => self.engine.eval_ast_with_scope::<Dynamic>(&mut scope, ast),
+        };
+        let commands = std::mem::take(&mut lock(&self.frame).commands);
+        result
+            .map(|value| (value, commands))
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// 注册脚本可以调用的函数
+///
+/// 读取类函数查询快照；指令类函数在只读场合报错，其余情况记入本次执行的指令
+fn register_api(engine: &mut Engine, frame: &Arc<Mutex<ScriptFrame>>) {
+    let shared = frame.clone();
+    engine.register_fn("quest_status", move |id: &str| -> String {
+        let status = match lock(&shared).world.quests.get(id) {
+            None => "none",
+            Some((QuestStatus::Active, _)) => "active",
+            Some((QuestStatus::Completed, _)) => "completed",
+            Some((QuestStatus::Failed, _)) => "failed",
+        };
+        status.to_string()
+    });
+    let shared = frame.clone();
+    engine.register_fn("quest_stage", move |id: &str| -> String {
+        lock(&shared)
+            .world
+            .quests
+            .get(id)
+            .map_or(String::new(), |(_, stage)| stage.clone())
+    });
+    let shared = frame.clone();
+    engine.register_fn("money", move || -> i64 {
+        lock(&shared)
+            .world
+            .inventory
+            .as_ref()
+            .map_or(0, |inventory| inventory.money as i64)
+    });
+    let shared = frame.clone();
+    engine.register_fn("item_count", move |item_id: &str| -> i64 {
+        lock(&shared)
+            .world
+            .inventory
+            .as_ref()
+            .map_or(0, |inventory| inventory.count(item_id) as i64)
+    });
+    let shared = frame.clone();
+    engine.register_fn("reputation", move |faction: &str| -> i64 {
+        lock(&shared).world.reputation.get(faction) as i64
+    });
+    let shared = frame.clone();
+    engine.register_fn("weather", move || -> String {
+        lock(&shared)
+            .world
+            .weather
+            .map_or(String::new(), |kind| format!("{:?}", kind))
+    });
+    let shared = frame.clone();
+    engine.register_fn("player_tile", move || -> Dynamic {
+        lock(&shared)
+            .world
+            .player_tile
+            .map_or(Dynamic::UNIT, |tile| {
+                Dynamic::from_array(vec![
+                    Dynamic::from(tile.x as i64),
+                    Dynamic::from(tile.y as i64),
+                ])
+            })
+    });
+
+    let push = |frame: &Arc<Mutex<ScriptFrame>>| {
+        let frame = frame.clone();
+        move |command: ScriptCommand| -> Result<(), Box<EvalAltResult>> {
+            let mut frame = lock(&frame);
+            if !frame.writable {
+                return Err("条件脚本不能修改游戏状态".into());
+            }
+            frame.commands.push(command);
+            Ok(())
+        }
+    };
+
+    let command = push(frame);
+    engine.register_fn("start_quest", move |quest_id: &str| {
+        command(ScriptCommand::Quest(Effect::StartQuest {
+            quest_id: quest_id.to_string(),
+        }))
+    });
+    let command = push(frame);
+    engine.register_fn("advance_quest", move |quest_id: &str| {
+        command(ScriptCommand::Quest(Effect::AdvanceQuest {
+            quest_id: quest_id.to_string(),
+        }))
+    });
+    let command = push(frame);
+    engine.register_fn("fail_quest", move |quest_id: &str| {
+        command(ScriptCommand::Quest(Effect::FailQuest {
+            quest_id: quest_id.to_string(),
+        }))
+    });
+    let command = push(frame);
+    engine.register_fn("give_item", move |item_id: &str, count: i64| {
+        command(ScriptCommand::Quest(Effect::GiveItem {
+            item_id: item_id.to_string(),
+            count: to_count(count),
+        }))
+    });
+    let command = push(frame);
+    engine.register_fn("take_item", move |item_id: &str, count: i64| {
+        command(ScriptCommand::Quest(Effect::TakeItem {
+            item_id: item_id.to_string(),
+            count: to_count(count),
+        }))
+    });
+    let command = push(frame);
+    engine.register_fn("give_money", move |amount: i64| {
+        command(ScriptCommand::Quest(Effect::GiveMoney {
+            amount: to_count(amount),
+        }))
+    });
+    let command = push(frame);
+    engine.register_fn("message", move |text: &str| {
+        command(ScriptCommand::Quest(Effect::Message {
+            text: text.to_string(),
+        }))
+    });
+    let command = push(frame);
+    engine.register_fn("change_reputation", move |faction: &str, amount: i64| {
+        command(ScriptCommand::ChangeReputation {
+            faction: faction.to_string(),
+            amount: amount.clamp(i32::MIN as i64, i32::MAX as i64) as i32,
+        })
+    });
+    let command = push(frame);
+    engine.register_fn(
+        "spawn_npc",
+        move |name: &str, npc_type: &str, x: i64, y: i64| {
+            command(ScriptCommand::SpawnNpc {
+                name: name.to_string(),
+                npc_type: parse_variant(npc_type, "NPC类型")?,
+                tile: IVec2::new(x as i32, y as i32),
+            })
+        },
+    );
+    let command = push(frame);
+    engine.register_fn("start_dialogue", move |npc: &str, dialogue_id: &str| {
+        command(ScriptCommand::StartDialogue {
+            npc: npc.to_string(),
+            dialogue_id: dialogue_id.to_string(),
+        })
+    });
+    let command = push(frame);
+    engine.register_fn("set_weather", move |kind: &str, intensity: f64| {
+        command(ScriptCommand::SetWeather {
+            kind: parse_variant(kind, "天气")?,
+            intensity: (intensity as f32).clamp(0.0, 1.0),
+        })
+    });
+    let command = push(frame);
+    engine.register_fn("set_npc_state", move |npc: &str, state: &str| {
+        command(ScriptCommand::SetNpcState {
+            npc: npc.to_string(),
+            state: parse_variant(state, "AI状态")?,
+        })
+    });
+    let command = push(frame);
+    engine.register_fn("set_cutscene_pace", move |scale: f64| {
+        command(ScriptCommand::CutscenePace {
+            scale: Some(scale as f32),
+        })
+    });
+    let command = push(frame);
+    engine.register_fn("end_cutscene_pace", move || {
+        command(ScriptCommand::CutscenePace { scale: None })
+    });
+}
+
+/// 脚本中的数量为负数时按 0 处理
+fn to_count(value: i64) -> u32 {
+    value.clamp(0, u32::MAX as i64) as u32
+}
+
+/// 按名字解析枚举，例如 "Guard"、"Rain"、"Flee"
+fn parse_variant<T: DeserializeOwned>(name: &str, kind: &str) -> Result<T, Box<EvalAltResult>> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .map_err(|_| format!("未知的{}：{}", kind, name).into())
+}
+
+/// NPC行为脚本的参数
+pub fn npc_argument(name: &str, state: AiState, tile: IVec2) -> Dynamic {
+    let mut npc = Map::new();
+    npc.insert("name".into(), Dynamic::from(name.to_string()));
+    npc.insert("state".into(), Dynamic::from(format!("{:?}", state)));
+    npc.insert("x".into(), Dynamic::from(tile.x as i64));
+    npc.insert("y".into(), Dynamic::from(tile.y as i64));
+    Dynamic::from_map(npc)
+}
@@ -0,0 +1,13 @@
+/// 脚本模块
+///
+/// # 模块组成
+/// 1. asset：脚本资源与加载器，脚本放在 assets/scripts，开发模式下可热重载
+/// 2. host：脚本引擎与对脚本开放的函数，脚本只能读取游戏状态的快照并提出指令
+/// 3. systems：脚本插件，执行任务、对话与NPC行为引用的脚本并落实指令
+mod asset;
+mod host;
+mod systems;
+
+pub use asset::*;
+pub use host::*;
+pub use systems::*;
@@ -0,0 +1,422 @@
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+use super::{
+    npc_argument, ScriptCommand, ScriptHost, ScriptLibrary, ScriptLoader, ScriptSettings,
+    ScriptSource, ScriptWorld, SCRIPT_FOLDER,
+};
+use crate::items::Inventory;
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::gameplay_running;
+use crate::time::{DilationKind, TimeDilation};
+use crate::world::chunk::TILE_PIXELS;
+use crate::world::entity::{spawn_npc, AiState, Character, Npc, Player};
+use crate::world::map::quest::{QuestEffectRequest, QuestManager};
+use crate::world::map::{
+    ActiveDialogue, DialogueLibrary, DialogueSession, DialogueSettings, Reputation,
+};
+use crate::world::schedule::Dormant;
+use crate::world::weather::{WeatherChanged, WeatherKind, WeatherSettings, WeatherState};
+
+/// 执行脚本的请求，任务与对话中的脚本效果经由任务系统发出
+#[derive(Event, Debug, Clone)]
+pub struct ScriptCall {
+    pub script: String,
+    /// 调用的函数，为空时执行脚本的顶层语句
+    pub function: Option<String>,
+}
+
+/// 按脚本行动的NPC，定期以自身信息调用脚本中的 `update(npc)`
+#[derive(Component, Debug)]
+pub struct NpcScript {
+    pub script: String,
+    timer: Timer,
+}
+
+/// 脚本插件
+///
+/// # 设计思路
+/// 1. 脚本与任务、对话一样放在 assets 下，开发模式下保存即生效
+/// 2. 任务效果、对话条件与效果可以引用脚本，脚本中声明了名字的NPC按脚本行动
+/// 3. 脚本提出的指令分给各个系统落实，每个系统只处理自己负责的那一类
+pub struct ScriptPlugin;
+
+impl Plugin for ScriptPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScriptSettings>();
+        let settings = app.world().resource::<ScriptSettings>().clone();
+
+        app.init_asset::<ScriptSource>()
+            .init_asset_loader::<ScriptLoader>()
+            .init_resource::<ScriptLibrary>()
+            .insert_resource(ScriptHost::new(&settings))
+            .add_event::<ScriptCall>()
+            .add_event::<ScriptCommand>()
+            .add_systems(Startup, load_scripts)
+            .add_systems(
+                Update,
+                (
+                    (sync_scripts, assign_npc_scripts).chain(),
+                    (run_script_calls, run_npc_scripts),
+                    (
+                        apply_script_commands,
+                        spawn_script_npcs,
+                        start_script_dialogues,
+                        apply_script_weather,
+                        set_script_npc_states,
+                        apply_script_cutscene_pace,
+                    ),
+                    report_script_errors,
+                )
+                    .chain()
+                    .run_if(gameplay_running),
+            );
+    }
+}
+
+/// 开始加载脚本目录
+fn load_scripts(asset_server: Res<AssetServer>, mut library: ResMut<ScriptLibrary>) {
+    library.folder = Some(asset_server.load_folder(SCRIPT_FOLDER));
+}
+
+/// 根据资源事件重新编译脚本
+fn sync_scripts(
+    mut library: ResMut<ScriptLibrary>,
+    mut events: EventReader<AssetEvent<ScriptSource>>,
+    assets: Res<Assets<ScriptSource>>,
+    mut host: ResMut<ScriptHost>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for event in events.read() {
+        match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                let Some(source) = assets.get(*id) else {
+                    continue;
+                };
+                if let Some(previous) = library.ids.insert(*id, source.id.clone()) {
+                    if previous != source.id {
+                        host.remove(&previous);
+                    }
+                }
+                let result = host.insert(&source.id, &source.source);
+                if let Some(logger) = logger.as_mut() {
+                    match result {
+                        Ok(()) => logger.log(LogLevel::Info, &format!("脚本已加载: {}", source.id)),
+                        Err(e) => logger.log(LogLevel::Error, &e),
+                    }
+                }
+            }
+            AssetEvent::Removed { id } => {
+                if let Some(previous) = library.ids.remove(id) {
+                    host.remove(&previous);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 按名字给NPC挂上行为脚本，脚本重新加载后重新分配
+fn assign_npc_scripts(
+    mut commands: Commands,
+    host: Res<ScriptHost>,
+    settings: Res<ScriptSettings>,
+    npcs: Query<(Entity, &Character, Ref<Npc>, Option<&NpcScript>)>,
+) {
+    let refresh = host.is_changed();
+    for (entity, character, npc, current) in npcs.iter() {
+        if !(refresh || npc.is_added()) {
+            continue;
+        }
+        match host.script_for_npc(&character.name) {
+            Some(script) if current.is_some_and(|current| current.script == script) => {}
+            Some(script) => {
+                commands.entity(entity).insert(NpcScript {
+                    script: script.to_string(),
+                    timer: Timer::from_seconds(settings.npc_interval, TimerMode::Repeating),
+                });
+            }
+            None if current.is_some() => {
+                commands.entity(entity).remove::<NpcScript>();
+            }
+            None => {}
+        }
+    }
+}
+
+/// 玩家眼中的游戏状态
+fn player_world(
+    quests: &QuestManager,
+    reputation: &Reputation,
+    weather: Option<&WeatherState>,
+    player: Option<(&Transform, &Inventory)>,
+) -> ScriptWorld {
+    ScriptWorld::capture(quests, player.map(|(_, inventory)| inventory), reputation)
+        .with_surroundings(weather, player.map(|(transform, _)| transform))
+}
+
+/// 执行任务与对话请求的脚本
+fn run_script_calls(
+    host: Res<ScriptHost>,
+    quests: Res<QuestManager>,
+    reputation: Res<Reputation>,
+    weather: Option<Res<WeatherState>>,
+    players: Query<(&Transform, &Inventory), With<Player>>,
+    mut calls: EventReader<ScriptCall>,
+    mut commands: EventWriter<ScriptCommand>,
+) {
+    for call in calls.read() {
+        let world = player_world(
+            &quests,
+            &reputation,
+            weather.as_deref(),
+            players.get_single().ok(),
+        );
+        commands.send_batch(host.run(&call.script, call.function.as_deref(), Vec::new(), world));
+    }
+}
+
+/// 定期执行NPC的行为脚本
+///
+/// 交谈中的NPC由对话系统接管，休眠中的NPC不执行
+#[allow(clippy::too_many_arguments)]
+fn run_npc_scripts(
+    time: Res<Time>,
+    host: Res<ScriptHost>,
+    quests: Res<QuestManager>,
+    reputation: Res<Reputation>,
+    weather: Option<Res<WeatherState>>,
+    players: Query<(&Transform, &Inventory), With<Player>>,
+    mut npcs: Query<(&Character, &Npc, &Transform, &mut NpcScript), Without<Dormant>>,
+    mut commands: EventWriter<ScriptCommand>,
+) {
+    // 同一帧的NPC共用一份快照
+    let mut snapshot = None;
+    for (character, npc, transform, mut script) in npcs.iter_mut() {
+        if !script.timer.tick(time.delta()).just_finished() || npc.ai_state == AiState::Talk {
+            continue;
+        }
+        let world = snapshot
+            .get_or_insert_with(|| {
+                player_world(
+                    &quests,
+                    &reputation,
+                    weather.as_deref(),
+                    players.get_single().ok(),
+                )
+            })
+            .clone();
+        let tile = (transform.translation.truncate() / TILE_PIXELS)
+            .floor()
+            .as_ivec2();
+        let argument = npc_argument(&character.name, npc.ai_state, tile);
+        commands.send_batch(host.run(&script.script, Some("update"), vec![argument], world));
+    }
+}
+
+/// 任务类指令交给任务系统，声望直接修改并写入存档
+fn apply_script_commands(
+    dialogue_settings: Option<Res<DialogueSettings>>,
+    mut reputation: ResMut<Reputation>,
+    mut events: EventReader<ScriptCommand>,
+    mut quest_effects: EventWriter<QuestEffectRequest>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let mut reputation_changed = false;
+    for command in events.read() {
+        match command {
+            ScriptCommand::Quest(effect) => {
+                quest_effects.send(QuestEffectRequest {
+                    effect: effect.clone(),
+                });
+            }
+            ScriptCommand::ChangeReputation { faction, amount } => {
+                let value = reputation.change(faction, *amount);
+                reputation_changed = true;
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(LogLevel::Info, &format!("{}声望：{}", faction, value));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let Some(settings) = dialogue_settings.filter(|_| reputation_changed) else {
+        return;
+    };
+    if let Err(e) = reputation.save(&settings.save_path) {
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Error, &format!("声望存档写入失败: {}", e));
+        }
+    }
+}
+
+fn spawn_script_npcs(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut events: EventReader<ScriptCommand>,
+) {
+    for command in events.read() {
+        let ScriptCommand::SpawnNpc {
+            name,
+            npc_type,
+            tile,
+        } = command
+        else {
+            continue;
+        };
+        let position = Vec3::new(
+            tile.x as f32 * TILE_PIXELS,
+            tile.y as f32 * TILE_PIXELS,
+            1.0,
+        );
+        spawn_npc(&mut commands, &asset_server, position, *npc_type, name);
+    }
+}
+
+/// 与指定NPC开始对话，从对话的 start 节点开始；已在对话中时忽略
+fn start_script_dialogues(
+    library: Res<DialogueLibrary>,
+    mut active: ResMut<ActiveDialogue>,
+    mut events: EventReader<ScriptCommand>,
+    mut npcs: Query<(Entity, &Character, &mut Npc)>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for command in events.read() {
+        let ScriptCommand::StartDialogue { npc, dialogue_id } = command else {
+            continue;
+        };
+        if active.session.is_some() {
+            continue;
+        }
+        let target = npcs
+            .iter_mut()
+            .find(|(_, character, _)| &character.name == npc);
+        let (Some(dialogue), Some((entity, _, mut target))) = (library.get(dialogue_id), target)
+        else {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(
+                    LogLevel::Error,
+                    &format!(
+                        "脚本无法开始对话：NPC {} 或对话 {} 不存在",
+                        npc, dialogue_id
+                    ),
+                );
+            }
+            continue;
+        };
+
+        active.session = Some(DialogueSession {
+            npc: entity,
+            dialogue_id: dialogue.id.clone(),
+            node: dialogue.start.clone(),
+            entered: false,
+            revealed: 0.0,
+            resume_state: target.ai_state,
+        });
+        target.ai_state = AiState::Talk;
+    }
+}
+
+/// 脚本设定的天气维持到下次掷骰；天气系统没有启用时忽略
+fn apply_script_weather(
+    settings: Option<Res<WeatherSettings>>,
+    mut weather: Option<ResMut<WeatherState>>,
+    mut events: EventReader<ScriptCommand>,
+    mut changes: Option<ResMut<Events<WeatherChanged>>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for command in events.read() {
+        let ScriptCommand::SetWeather { kind, intensity } = command else {
+            continue;
+        };
+        let (Some(settings), Some(weather), Some(changes)) =
+            (settings.as_ref(), weather.as_mut(), changes.as_mut())
+        else {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Info, "天气系统未启用，无法改变天气");
+            }
+            continue;
+        };
+
+        let previous = weather.kind;
+        weather.kind = *kind;
+        weather.intensity = if *kind == WeatherKind::Clear {
+            0.0
+        } else {
+            *intensity
+        };
+        weather.timer = Timer::from_seconds(settings.roll_interval, TimerMode::Once);
+        changes.send(WeatherChanged {
+            current: *kind,
+            intensity: weather.intensity,
+        });
+
+        if let Some(logger) = logger.as_mut() {
+            logger.log(
+                LogLevel::Info,
+                &format!(
+                    "脚本改变天气：{} -> {}（强度 {:.2}）",
+                    previous.name(),
+                    kind.name(),
+                    weather.intensity
+                ),
+            );
+        }
+    }
+}
+
+/// 改变NPC的AI状态，交谈中的NPC不受影响
+fn set_script_npc_states(
+    mut events: EventReader<ScriptCommand>,
+    mut npcs: Query<(&Character, &mut Npc)>,
+) {
+    for command in events.read() {
+        let ScriptCommand::SetNpcState { npc, state } = command else {
+            continue;
+        };
+        for (character, mut target) in npcs.iter_mut() {
+            if &character.name == npc && target.ai_state != AiState::Talk {
+                target.ai_state = *state;
+            }
+        }
+    }
+}
+
+/// 按脚本设定的过场节奏放慢时间，直到脚本结束过场；时间缩放服务没有启用时忽略
+fn apply_script_cutscene_pace(
+    mut events: EventReader<ScriptCommand>,
+    mut dilation: Option<ResMut<TimeDilation>>,
+) {
+    for command in events.read() {
+        let ScriptCommand::CutscenePace { scale } = command else {
+            continue;
+        };
+        let Some(dilation) = dilation.as_mut() else {
+            continue;
+        };
+        match scale {
+            Some(scale) => dilation.set_cutscene_scale(*scale),
+            None => dilation.clear(DilationKind::Cutscene),
+        }
+    }
+}
+
+/// 把脚本错误写入日志，同样的错误只写一次，脚本重新加载后再写
+fn report_script_errors(
+    host: Res<ScriptHost>,
+    mut reported: Local<HashSet<String>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    if host.is_changed() {
+        reported.clear();
+    }
+    for error in host.take_errors() {
+        if !reported.insert(error.clone()) {
+            continue;
+        }
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Error, &error);
+        }
+    }
+}
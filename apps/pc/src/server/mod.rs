@@ -0,0 +1,22 @@
+/// 服务器模块
+///
+/// 权威服务器上对客户端请求的校验与处罚，只在开启 `server` 特性时编译，
+/// 客户端构建中不包含任何反作弊逻辑
+///
+/// # 模块组成
+/// 1. validation：客户端请求、校验结果与校验中间件接口
+/// 2. movement：移动速度与瞬移检查
+/// 3. rate_limit：动作频率限制
+/// 4. moderation：踢出、封禁与审计日志
+/// 5. systems：反作弊插件
+mod moderation;
+mod movement;
+mod rate_limit;
+mod systems;
+mod validation;
+
+pub use moderation::*;
+pub use movement::*;
+pub use rate_limit::*;
+pub use systems::AntiCheatPlugin;
+pub use validation::*;
@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+use chrono::{Duration, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use super::ClientId;
+
+/// 封禁名单存档路径
+pub const BAN_LIST_PATH: &str = "saves/bans.json";
+
+/// 审计日志路径
+pub const AUDIT_LOG_PATH: &str = "logs/audit.log";
+
+/// 一条封禁
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BanEntry {
+    pub account: String,
+    pub reason: String,
+    /// 执行人，自动处罚为 "anticheat"
+    pub issued_by: String,
+    /// 封禁时间（UTC 时间戳秒）
+    pub issued_at: i64,
+    /// 解封时间，永久封禁为空
+    pub expires_at: Option<i64>,
+}
+
+impl BanEntry {
+    pub fn is_active(&self, now: i64) -> bool {
+        self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+}
+
+/// 处罚事件，网络层据此断开连接并通知客户端
+#[derive(Event, Debug, Clone)]
+pub enum ModerationEvent {
+    Kicked { client: ClientId, reason: String },
+    Banned { client: ClientId, reason: String },
+}
+
+/// 踢出与封禁
+///
+/// # 设计思路
+/// 1. 封禁按账号记录，客户端编号在每次连接时变化，由网络层登录后登记对应的账号
+/// 2. 每一次处罚与反作弊拒绝都追加写入审计日志，日志只在服务器上，不进入玩家的错误报告包
+/// 3. 封禁名单修改后立即保存，服务器重启后仍然有效
+#[derive(Resource, Debug, Default)]
+pub struct Moderation {
+    bans: HashMap<String, BanEntry>,
+    accounts: HashMap<ClientId, String>,
+    pending: Vec<ModerationEvent>,
+}
+
+impl Moderation {
+    /// 读取封禁名单，文件不存在时为空
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        let bans: Vec<BanEntry> = serde_json::from_str(&content)?;
+        Ok(Self {
+            bans: bans
+                .into_iter()
+                .map(|ban| (ban.account.clone(), ban))
+                .collect(),
+            ..default()
+        })
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut bans: Vec<&BanEntry> = self.bans.values().collect();
+        bans.sort_by(|a, b| a.account.cmp(&b.account));
+        fs::write(path, serde_json::to_string_pretty(&bans)?)?;
+        Ok(())
+    }
+
+    /// 登录成功后登记客户端对应的账号，账号已被封禁时返回封禁记录
+    pub fn register_client(&mut self, client: ClientId, account: &str) -> Option<&BanEntry> {
+        self.accounts.insert(client, account.to_string());
+        let now = Utc::now().timestamp();
+        self.bans.get(account).filter(|ban| ban.is_active(now))
+    }
+
+    /// 客户端断开
+    pub fn unregister_client(&mut self, client: ClientId) {
+        self.accounts.remove(&client);
+    }
+
+    pub fn account_of(&self, client: ClientId) -> Option<&str> {
+        self.accounts.get(&client).map(String::as_str)
+    }
+
+    /// 账号当前的封禁
+    pub fn active_ban(&self, account: &str) -> Option<&BanEntry> {
+        let now = Utc::now().timestamp();
+        self.bans.get(account).filter(|ban| ban.is_active(now))
+    }
+
+    /// 踢出客户端
+    pub fn kick(&mut self, client: ClientId, reason: &str, issued_by: &str) {
+        self.audit(&format!(
+            "KICK client={} account={} by={} reason={}",
+            client,
+            self.account_of(client).unwrap_or("-"),
+            issued_by,
+            reason
+        ));
+        self.pending.push(ModerationEvent::Kicked {
+            client,
+            reason: reason.to_string(),
+        });
+    }
+
+    /// 封禁客户端当前登录的账号，`duration` 为空时永久封禁，随后踢出
+    pub fn ban(
+        &mut self,
+        client: ClientId,
+        reason: &str,
+        duration: Option<Duration>,
+        issued_by: &str,
+    ) {
+        let account = self.account_of(client).map(str::to_string);
+        if let Some(account) = &account {
+            self.ban_account(account, reason, duration, issued_by);
+        } else {
+            self.audit(&format!(
+                "BAN client={} 未登录，只踢出 by={} reason={}",
+                client, issued_by, reason
+            ));
+        }
+        self.pending.push(ModerationEvent::Banned {
+            client,
+            reason: reason.to_string(),
+        });
+    }
+
+    /// 按账号封禁，账号不需要在线
+    pub fn ban_account(
+        &mut self,
+        account: &str,
+        reason: &str,
+        duration: Option<Duration>,
+        issued_by: &str,
+    ) {
+        let now = Utc::now();
+        let entry = BanEntry {
+            account: account.to_string(),
+            reason: reason.to_string(),
+            issued_by: issued_by.to_string(),
+            issued_at: now.timestamp(),
+            expires_at: duration.map(|duration| (now + duration).timestamp()),
+        };
+        self.audit(&format!(
+            "BAN account={} by={} until={} reason={}",
+            account,
+            issued_by,
+            entry
+                .expires_at
+                .map_or("永久".to_string(), |expires_at| expires_at.to_string()),
+            reason
+        ));
+        self.bans.insert(account.to_string(), entry);
+        self.persist();
+    }
+
+    /// 取出待网络层执行的处罚
+    pub fn take_events(&mut self) -> Vec<ModerationEvent> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// 追加一行审计日志
+    pub fn audit(&self, line: &str) {
+        let write = || -> std::io::Result<()> {
+            if let Some(parent) = Path::new(AUDIT_LOG_PATH).parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(AUDIT_LOG_PATH)?;
+            writeln!(
+                file,
+                "[{}] {}",
+                Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+                line
+            )
+        };
+        if let Err(e) = write() {
+            error!("写入审计日志失败: {}", e);
+        }
+    }
+
+    fn persist(&self) {
+        if let Err(e) = self.save(BAN_LIST_PATH) {
+            error!("保存封禁名单失败: {}", e);
+        }
+    }
+}
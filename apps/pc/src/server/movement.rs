@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::{
+    ClientAction, ClientId, ClientRequest, ValidationContext, ValidationMiddleware, Verdict,
+};
+use crate::world::chunk::world_to_tile;
+
+/// 移动校验
+///
+/// # 设计思路
+/// 1. 以服务器上一次采纳的位置与客户端时间为起点，按角色移速与目的地瓦片的移动代价算出允许的最大位移
+/// 2. 略微超速按网络抖动处理，纠正位置但只记少量违规分；超出瞬移距离直接判为作弊
+/// 3. 目的地不可行走（墙、岩石）时拉回原位，水面可以游过去
+#[derive(Debug, Clone)]
+pub struct MovementValidator {
+    /// 允许的最大移速倍率，包括疾跑与各种加速效果
+    pub max_speed_multiplier: f32,
+    /// 超速容差，吸收网络抖动与时钟误差
+    pub tolerance: f32,
+    /// 单次上报的最长间隔（秒），更长的间隔按此计算，防止攒时间一次瞬移
+    pub max_interval: f32,
+    /// 超过该距离（像素）的位移视为瞬移
+    pub teleport_distance: f32,
+    last: HashMap<ClientId, (Vec2, f64)>,
+}
+
+impl Default for MovementValidator {
+    fn default() -> Self {
+        Self {
+            max_speed_multiplier: 1.6,
+            tolerance: 1.25,
+            max_interval: 0.5,
+            teleport_distance: 256.0,
+            last: HashMap::new(),
+        }
+    }
+}
+
+impl ValidationMiddleware for MovementValidator {
+    fn name(&self) -> &'static str {
+        "movement"
+    }
+
+    fn validate(&mut self, request: &ClientRequest, context: &ValidationContext) -> Verdict {
+        let ClientAction::Move {
+            position,
+            timestamp,
+        } = request.action
+        else {
+            return Verdict::Accept;
+        };
+
+        let Some((last_position, last_time)) = self.last.get(&request.client).copied() else {
+            // 第一次上报只记录起点
+            self.last.insert(request.client, (position, timestamp));
+            return Verdict::Accept;
+        };
+
+        if timestamp < last_time {
+            return Verdict::reject("移动时间戳倒退", 1.0);
+        }
+
+        let distance = position.distance(last_position);
+        if distance > self.teleport_distance {
+            return Verdict::Correct {
+                position: last_position,
+                reason: format!("瞬移 {:.0} 像素", distance),
+                severity: 5.0,
+            };
+        }
+
+        let destination = world_to_tile(position);
+        let cell = context.nav_grid.and_then(|grid| grid.cell(destination));
+        if cell.is_some_and(|cell| !cell.walkable && !cell.water) {
+            return Verdict::Correct {
+                position: last_position,
+                reason: format!("进入不可行走的瓦片 ({}, {})", destination.x, destination.y),
+                severity: 2.0,
+            };
+        }
+
+        // 移动代价越高越慢，沼泽、深雪等地形上的允许速度随之降低
+        let cost = cell.map_or(1.0, |cell| cell.cost.max(1.0));
+        let interval = ((timestamp - last_time) as f32).min(self.max_interval);
+        let allowed =
+            context.speed * self.max_speed_multiplier * self.tolerance * interval / cost + 1.0;
+        if distance > allowed {
+            return Verdict::Correct {
+                position: last_position,
+                reason: format!("移速异常：{:.0} 像素，允许 {:.0}", distance, allowed),
+                severity: 1.0,
+            };
+        }
+
+        self.last.insert(request.client, (position, timestamp));
+        Verdict::Accept
+    }
+
+    fn forget(&mut self, client: ClientId) {
+        self.last.remove(&client);
+    }
+
+    fn reset(&mut self, client: ClientId, position: Vec2, timestamp: f64) {
+        self.last.insert(client, (position, timestamp));
+    }
+}
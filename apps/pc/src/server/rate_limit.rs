@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use super::{ClientId, ClientRequest, ValidationContext, ValidationMiddleware, Verdict};
+
+/// 一类动作的频率上限
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    /// 每秒允许的次数
+    pub per_second: f32,
+    /// 允许的突发次数
+    pub burst: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f32,
+    updated_at: f64,
+}
+
+/// 动作频率限制
+///
+/// 令牌桶：每类动作按固定速率补充令牌，每次请求消耗一个，
+/// 允许短时间的连按，但持续超速的脚本会被拒绝
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    pub limits: HashMap<&'static str, RateLimit>,
+    buckets: HashMap<(ClientId, &'static str), Bucket>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        let mut limits = HashMap::new();
+        // 客户端按 30 次/秒上报位置，留出网络抖动造成的堆积
+        limits.insert(
+            "move",
+            RateLimit {
+                per_second: 40.0,
+                burst: 20.0,
+            },
+        );
+        // 普通攻击时长 0.4 秒，加上输入缓冲也不会超过每秒 4 次
+        limits.insert(
+            "attack",
+            RateLimit {
+                per_second: 4.0,
+                burst: 3.0,
+            },
+        );
+        limits.insert(
+            "dodge",
+            RateLimit {
+                per_second: 3.0,
+                burst: 2.0,
+            },
+        );
+        limits.insert(
+            "action",
+            RateLimit {
+                per_second: 10.0,
+                burst: 5.0,
+            },
+        );
+        Self {
+            limits,
+            buckets: HashMap::new(),
+        }
+    }
+}
+
+impl ValidationMiddleware for RateLimiter {
+    fn name(&self) -> &'static str {
+        "rate_limit"
+    }
+
+    fn validate(&mut self, request: &ClientRequest, context: &ValidationContext) -> Verdict {
+        let kind = request.action.kind();
+        let Some(limit) = self.limits.get(kind).copied() else {
+            return Verdict::Accept;
+        };
+
+        let bucket = self
+            .buckets
+            .entry((request.client, kind))
+            .or_insert(Bucket {
+                tokens: limit.burst,
+                updated_at: context.now,
+            });
+        let elapsed = (context.now - bucket.updated_at).max(0.0) as f32;
+        bucket.tokens = (bucket.tokens + elapsed * limit.per_second).min(limit.burst);
+        bucket.updated_at = context.now;
+
+        if bucket.tokens < 1.0 {
+            return Verdict::reject(format!("{} 请求过于频繁", kind), 0.5);
+        }
+        bucket.tokens -= 1.0;
+        Verdict::Accept
+    }
+
+    fn forget(&mut self, client: ClientId) {
+        self.buckets.retain(|(owner, _), _| *owner != client);
+    }
+}
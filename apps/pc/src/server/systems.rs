@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+use chrono::Duration;
+
+use super::{Enforcement, Moderation, ModerationEvent, ValidationPipeline, BAN_LIST_PATH};
+use crate::logging::{GameLogger, LogLevel};
+
+/// 自动封禁的执行人名称
+const ANTICHEAT_ISSUER: &str = "anticheat";
+
+/// 自动封禁的天数，更重的处罚由管理员人工决定
+const AUTO_BAN_DAYS: i64 = 7;
+
+/// 反作弊插件
+///
+/// 只注册校验管线与处罚；网络同步层在服务器上处理客户端请求时调用
+/// `ValidationPipeline::check`，再按 `ModerationEvent` 断开连接
+pub struct AntiCheatPlugin;
+
+impl Plugin for AntiCheatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ModerationEvent>()
+            .init_resource::<ValidationPipeline>()
+            .add_systems(Startup, load_ban_list)
+            .add_systems(
+                Update,
+                (
+                    decay_violations,
+                    audit_rejections,
+                    enforce_violations,
+                    dispatch_moderation_events,
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn load_ban_list(mut commands: Commands, mut logger: Option<ResMut<GameLogger>>) {
+    let moderation = match Moderation::load(BAN_LIST_PATH) {
+        Ok(moderation) => moderation,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(
+                    LogLevel::Error,
+                    &format!("加载封禁名单失败: {}，以空名单启动", e),
+                );
+            }
+            Moderation::default()
+        }
+    };
+    commands.insert_resource(moderation);
+}
+
+fn decay_violations(time: Res<Time<Real>>, mut pipeline: ResMut<ValidationPipeline>) {
+    pipeline.decay(time.delta_secs());
+}
+
+/// 把校验拒绝写入审计日志
+fn audit_rejections(moderation: Res<Moderation>, mut pipeline: ResMut<ValidationPipeline>) {
+    for (client, middleware, reason) in pipeline.take_rejections() {
+        moderation.audit(&format!(
+            "REJECT client={} account={} check={} score={:.1} reason={}",
+            client,
+            moderation.account_of(client).unwrap_or("-"),
+            middleware,
+            pipeline.score(client),
+            reason
+        ));
+    }
+}
+
+/// 执行违规分达到阈值后的处罚
+fn enforce_violations(
+    mut moderation: ResMut<Moderation>,
+    mut pipeline: ResMut<ValidationPipeline>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for enforcement in pipeline.take_enforcements() {
+        let (client, reason) = match &enforcement {
+            Enforcement::Kick { client, reason } => {
+                moderation.kick(*client, reason, ANTICHEAT_ISSUER);
+                (*client, reason)
+            }
+            Enforcement::Ban { client, reason } => {
+                moderation.ban(
+                    *client,
+                    reason,
+                    Some(Duration::days(AUTO_BAN_DAYS)),
+                    ANTICHEAT_ISSUER,
+                );
+                (*client, reason)
+            }
+        };
+        pipeline.forget(client);
+        if let Some(logger) = logger.as_mut() {
+            logger.log(
+                LogLevel::Info,
+                &format!("反作弊处罚 客户端 {}：{}", client, reason),
+            );
+        }
+    }
+}
+
+/// 把处罚交给网络层
+fn dispatch_moderation_events(
+    mut moderation: ResMut<Moderation>,
+    mut events: EventWriter<ModerationEvent>,
+) {
+    for event in moderation.take_events() {
+        events.send(event);
+    }
+}
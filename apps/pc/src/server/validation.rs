@@ -0,0 +1,250 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::events::input::GameAction;
+use crate::world::chunk::NavGrid;
+
+/// 客户端编号，由网络层在连接时分配
+pub type ClientId = u64;
+
+/// 客户端请求的动作
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientAction {
+    /// 上报位置，`timestamp` 为客户端的游戏时间（秒）
+    Move { position: Vec2, timestamp: f64 },
+    /// 战斗等按键动作
+    Action(GameAction),
+}
+
+impl ClientAction {
+    /// 频率限制使用的分类
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ClientAction::Move { .. } => "move",
+            ClientAction::Action(GameAction::Attack) => "attack",
+            ClientAction::Action(GameAction::Dodge) => "dodge",
+            ClientAction::Action(_) => "action",
+        }
+    }
+}
+
+/// 一条客户端请求
+#[derive(Debug, Clone)]
+pub struct ClientRequest {
+    pub client: ClientId,
+    pub action: ClientAction,
+}
+
+/// 校验结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    /// 放行
+    Accept,
+    /// 不采纳客户端的位置，改为服务器给出的位置
+    Correct {
+        position: Vec2,
+        reason: String,
+        severity: f32,
+    },
+    /// 丢弃请求，`severity` 计入违规分
+    Reject { reason: String, severity: f32 },
+}
+
+impl Verdict {
+    pub fn reject(reason: impl Into<String>, severity: f32) -> Self {
+        Verdict::Reject {
+            reason: reason.into(),
+            severity,
+        }
+    }
+
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, Verdict::Accept)
+    }
+}
+
+/// 校验时可用的服务器状态
+///
+/// 由网络同步层在处理请求时从权威世界中取出，校验器只读不写
+pub struct ValidationContext<'a> {
+    /// 服务器当前时间（秒）
+    pub now: f64,
+    pub nav_grid: Option<&'a NavGrid>,
+    /// 请求方角色的基础移速（像素/秒）
+    pub speed: f32,
+}
+
+/// 校验中间件
+///
+/// 网络同步层收到客户端请求后依次交给各个中间件，任一中间件不放行即停止
+pub trait ValidationMiddleware: Send + Sync {
+    /// 名称，写入审计日志
+    fn name(&self) -> &'static str;
+
+    fn validate(&mut self, request: &ClientRequest, context: &ValidationContext) -> Verdict;
+
+    /// 客户端断开后清理状态
+    fn forget(&mut self, _client: ClientId) {}
+
+    /// 服务器主动放置角色后以该位置为新的起点
+    fn reset(&mut self, _client: ClientId, _position: Vec2, _timestamp: f64) {}
+}
+
+/// 违规达到处罚阈值时需要执行的处罚
+#[derive(Debug, Clone, PartialEq)]
+pub enum Enforcement {
+    Kick { client: ClientId, reason: String },
+    Ban { client: ClientId, reason: String },
+}
+
+/// 违规记录的参数
+///
+/// # 参数说明
+/// - decay_per_second: 违规分每秒衰减量，偶发的网络抖动不会累积成处罚
+/// - kick_threshold: 违规分达到该值时踢出
+/// - ban_threshold: 同一客户端累计被踢出的次数达到该值时封禁
+#[derive(Debug, Clone)]
+pub struct ViolationSettings {
+    pub decay_per_second: f32,
+    pub kick_threshold: f32,
+    pub ban_threshold: u32,
+}
+
+impl Default for ViolationSettings {
+    fn default() -> Self {
+        Self {
+            decay_per_second: 0.5,
+            kick_threshold: 10.0,
+            ban_threshold: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ViolationRecord {
+    score: f32,
+    kicks: u32,
+}
+
+/// 校验管线
+///
+/// # 设计思路
+/// 1. 作为网络同步层的中间件，同步层只需调用 `check`，不关心具体规则
+/// 2. 中间件按注册顺序执行，便宜的检查（频率限制）放在前面
+/// 3. 被拒绝的请求计入违规分，达到阈值后生成处罚，由反作弊插件交给处罚系统执行
+#[derive(Resource)]
+pub struct ValidationPipeline {
+    middlewares: Vec<Box<dyn ValidationMiddleware>>,
+    pub violations: ViolationSettings,
+    records: HashMap<ClientId, ViolationRecord>,
+    pending: Vec<Enforcement>,
+    /// 最近的拒绝记录，由反作弊插件写入审计日志
+    rejections: Vec<(ClientId, &'static str, String)>,
+}
+
+impl Default for ValidationPipeline {
+    fn default() -> Self {
+        Self::new()
+            .with(super::RateLimiter::default())
+            .with(super::MovementValidator::default())
+    }
+}
+
+impl ValidationPipeline {
+    /// 空管线
+    pub fn new() -> Self {
+        Self {
+            middlewares: Vec::new(),
+            violations: ViolationSettings::default(),
+            records: HashMap::new(),
+            pending: Vec::new(),
+            rejections: Vec::new(),
+        }
+    }
+
+    /// 追加中间件
+    pub fn with(mut self, middleware: impl ValidationMiddleware + 'static) -> Self {
+        self.middlewares.push(Box::new(middleware));
+        self
+    }
+
+    /// 校验一条请求
+    pub fn check(&mut self, request: &ClientRequest, context: &ValidationContext) -> Verdict {
+        for middleware in self.middlewares.iter_mut() {
+            let verdict = middleware.validate(request, context);
+            if verdict.is_accepted() {
+                continue;
+            }
+
+            let (reason, severity) = match &verdict {
+                Verdict::Correct {
+                    reason, severity, ..
+                } => (reason.clone(), *severity),
+                Verdict::Reject { reason, severity } => (reason.clone(), *severity),
+                Verdict::Accept => unreachable!(),
+            };
+            self.rejections
+                .push((request.client, middleware.name(), reason.clone()));
+            self.add_violation(request.client, severity, reason);
+            return verdict;
+        }
+        Verdict::Accept
+    }
+
+    fn add_violation(&mut self, client: ClientId, severity: f32, reason: String) {
+        let record = self.records.entry(client).or_default();
+        record.score += severity;
+        if record.score < self.violations.kick_threshold {
+            return;
+        }
+
+        record.score = 0.0;
+        record.kicks += 1;
+        let enforcement = if record.kicks >= self.violations.ban_threshold {
+            Enforcement::Ban { client, reason }
+        } else {
+            Enforcement::Kick { client, reason }
+        };
+        self.pending.push(enforcement);
+    }
+
+    /// 违规分随时间衰减
+    pub fn decay(&mut self, delta: f32) {
+        let decay = self.violations.decay_per_second * delta;
+        for record in self.records.values_mut() {
+            record.score = (record.score - decay).max(0.0);
+        }
+    }
+
+    /// 客户端当前的违规分
+    pub fn score(&self, client: ClientId) -> f32 {
+        self.records.get(&client).map_or(0.0, |record| record.score)
+    }
+
+    /// 客户端断开后清理各中间件的状态，累计踢出次数保留
+    pub fn forget(&mut self, client: ClientId) {
+        for middleware in self.middlewares.iter_mut() {
+            middleware.forget(client);
+        }
+        if let Some(record) = self.records.get_mut(&client) {
+            record.score = 0.0;
+        }
+    }
+
+    /// 服务器放置角色（加入、传送、复活）后通知各中间件
+    pub fn reset(&mut self, client: ClientId, position: Vec2, timestamp: f64) {
+        for middleware in self.middlewares.iter_mut() {
+            middleware.reset(client, position, timestamp);
+        }
+    }
+
+    /// 取出待执行的处罚
+    pub fn take_enforcements(&mut self) -> Vec<Enforcement> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// 取出最近的拒绝记录：客户端、中间件名称、原因
+    pub fn take_rejections(&mut self) -> Vec<(ClientId, &'static str, String)> {
+        std::mem::take(&mut self.rejections)
+    }
+}
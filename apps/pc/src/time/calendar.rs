@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+
+use crate::world::map::Season;
+
+/// 游戏历法
+///
+/// # 设计思路
+/// 1. 一年四季，每季若干个月，每月若干天，全部可配置
+/// 2. 一天的长度以游戏时间（虚拟时间）秒数表示，暂停与慢动作自然生效
+/// 3. 日期推进只负责发出事件，季节对气候、植被的影响由各自系统处理
+#[derive(Resource, Debug, Clone)]
+pub struct GameCalendar {
+    /// 一天的长度（游戏时间秒数）
+    pub day_length: f32,
+    /// 每月天数
+    pub days_per_month: u32,
+    /// 每季月数
+    pub months_per_season: u32,
+    /// 当前年份，从 1 开始
+    pub year: u32,
+    /// 当前月份，从 1 开始
+    pub month: u32,
+    /// 当前日期，从 1 开始
+    pub day: u32,
+    /// 一天中的时刻 (0.0-1.0)，0.0 为子夜
+    pub time_of_day: f32,
+    /// 是否推进时间
+    pub running: bool,
+}
+
+impl Default for GameCalendar {
+    fn default() -> Self {
+        Self {
+            day_length: 1200.0,
+            days_per_month: 30,
+            months_per_season: 3,
+            year: 1,
+            month: 1,
+            day: 1,
+            // 从清晨开始
+            time_of_day: 0.25,
+            running: true,
+        }
+    }
+}
+
+impl GameCalendar {
+    /// 每年月数
+    pub fn months_per_year(&self) -> u32 {
+        self.months_per_season.max(1) * 4
+    }
+
+    /// 当前季节，一月为春季之始
+    pub fn season(&self) -> Season {
+        match (self.month - 1) / self.months_per_season.max(1) {
+            0 => Season::Spring,
+            1 => Season::Summer,
+            2 => Season::Autumn,
+            _ => Season::Winter,
+        }
+    }
+
+    /// 当前时辰（0-23）
+    pub fn hour(&self) -> u32 {
+        ((self.time_of_day * 24.0) as u32).min(23)
+    }
+
+    /// 自第1年1月1日子夜起经过的游戏小时数，用于跨日期的计时
+    pub fn total_hours(&self) -> f64 {
+        let days_per_year = (self.days_per_month.max(1) * self.months_per_year()) as f64;
+        let days = (self.year - 1) as f64 * days_per_year
+            + ((self.month - 1) * self.days_per_month.max(1)) as f64
+            + (self.day - 1) as f64;
+        (days + self.time_of_day as f64) * 24.0
+    }
+
+    /// 推进时间，返回经过的整天数
+    pub fn advance(&mut self, seconds: f32) -> u32 {
+        if !self.running || self.day_length <= 0.0 {
+            return 0;
+        }
+
+        self.time_of_day += seconds / self.day_length;
+
+        let mut days_passed = 0;
+        while self.time_of_day >= 1.0 {
+            self.time_of_day -= 1.0;
+            self.next_day();
+            days_passed += 1;
+        }
+        days_passed
+    }
+
+    /// 跳过若干小时（如睡眠），返回经过的整天数
+    pub fn skip_hours(&mut self, hours: f32) -> u32 {
+        let was_running = self.running;
+        self.running = true;
+        let days = self.advance(hours / 24.0 * self.day_length);
+        self.running = was_running;
+        days
+    }
+
+    /// 进入下一天
+    fn next_day(&mut self) {
+        self.day += 1;
+        if self.day > self.days_per_month.max(1) {
+            self.day = 1;
+            self.month += 1;
+            if self.month > self.months_per_year() {
+                self.month = 1;
+                self.year += 1;
+            }
+        }
+    }
+
+    /// 之后连续若干天的日期，一次推进多天时逐日发出事件
+    pub fn following_days(&self, days: u32) -> impl Iterator<Item = GameCalendar> {
+        let mut date = self.clone();
+        (0..days).map(move |_| {
+            date.next_day();
+            date.clone()
+        })
+    }
+
+    /// 日期文字，如 "第1年 3月5日"
+    pub fn date_label(&self) -> String {
+        format!("第{}年 {}月{}日", self.year, self.month, self.day)
+    }
+}
+
+/// 季节变化事件
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SeasonChanged {
+    pub current: Season,
+    pub year: u32,
+}
+
+/// 新的一天开始事件，一次推进多天时每天各发一次
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewDay;
+
+/// 跳过时间请求（如睡眠、打坐），由时间插件统一推进历法并发出事件
+#[derive(Event, Debug, Clone, Copy)]
+pub struct TimeSkipRequest {
+    /// 跳过的小时数
+    pub hours: f32,
+}
+
+/// 世界时钟被跳过一段时间后发出
+///
+/// 天气等按时间推进的系统订阅该事件，立即按跳过后的时间刷新
+#[derive(Event, Debug, Clone, Copy)]
+pub struct TimeSkipped;
@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 一天中的时段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TimeOfDay {
+    Dawn,
+    Day,
+    Dusk,
+    Night,
+}
+
+/// 昼夜配置
+///
+/// 一个昼夜的长度即 `GameCalendar::day_length`，这里只描述时段划分与光照曲线
+#[derive(Resource, Debug, Clone)]
+pub struct DayNightSettings {
+    /// 拂晓开始的时刻（小时）
+    pub dawn_start: f32,
+    /// 白昼开始的时刻
+    pub day_start: f32,
+    /// 黄昏开始的时刻
+    pub dusk_start: f32,
+    /// 入夜的时刻
+    pub night_start: f32,
+    /// 环境光关键帧 (小时, [r, g, b])，按小时升序，首尾在午夜处衔接
+    pub ambient_curve: Vec<(f32, [f32; 3])>,
+    /// 环境光变化小于该值时不刷新着色，避免每帧重设全部瓦片
+    pub tint_epsilon: f32,
+}
+
+impl Default for DayNightSettings {
+    fn default() -> Self {
+        Self {
+            dawn_start: 5.0,
+            day_start: 7.0,
+            dusk_start: 17.0,
+            night_start: 19.0,
+            ambient_curve: vec![
+                (0.0, [0.3, 0.35, 0.55]),
+                (4.5, [0.35, 0.38, 0.58]),
+                (6.0, [0.85, 0.65, 0.6]),
+                (8.0, [1.0, 1.0, 1.0]),
+                (16.5, [1.0, 0.97, 0.92]),
+                (18.0, [0.95, 0.65, 0.5]),
+                (20.0, [0.3, 0.35, 0.55]),
+            ],
+            tint_epsilon: 0.01,
+        }
+    }
+}
+
+impl DayNightSettings {
+    /// 某个时刻所处的时段
+    pub fn time_of_day(&self, hour: f32) -> TimeOfDay {
+        if hour >= self.night_start || hour < self.dawn_start {
+            TimeOfDay::Night
+        } else if hour < self.day_start {
+            TimeOfDay::Dawn
+        } else if hour < self.dusk_start {
+            TimeOfDay::Day
+        } else {
+            TimeOfDay::Dusk
+        }
+    }
+
+    /// 按关键帧插值得到某个时刻的环境光
+    pub fn ambient_at(&self, hour: f32) -> [f32; 3] {
+        let curve = &self.ambient_curve;
+        let (Some(first), Some(last)) = (curve.first(), curve.last()) else {
+            return [1.0, 1.0, 1.0];
+        };
+
+        // 找到前后两个关键帧，越过午夜时首尾相接
+        let (from, to) = match curve.iter().position(|(h, _)| *h > hour) {
+            Some(0) => (*last, (first.0 + 24.0, first.1)),
+            Some(i) => (curve[i - 1], curve[i]),
+            None => (*last, (first.0 + 24.0, first.1)),
+        };
+        let hour = if hour < from.0 { hour + 24.0 } else { hour };
+        let span = to.0 - from.0;
+        let t = if span > 0.0 {
+            ((hour - from.0) / span).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        [0, 1, 2].map(|i| from.1[i] + (to.1[i] - from.1[i]) * t)
+    }
+}
+
+/// 当前昼夜状态
+#[derive(Resource, Debug, Clone)]
+pub struct DayNightState {
+    pub time_of_day: TimeOfDay,
+    /// 当前环境光，只在变化超过阈值时更新
+    pub ambient: [f32; 3],
+}
+
+impl Default for DayNightState {
+    fn default() -> Self {
+        Self {
+            time_of_day: TimeOfDay::Day,
+            ambient: [1.0, 1.0, 1.0],
+        }
+    }
+}
+
+impl DayNightState {
+    /// 把环境光叠加到基础颜色上
+    pub fn apply(&self, color: Color) -> Color {
+        let base = color.to_srgba();
+        Color::srgba(
+            base.red * self.ambient[0],
+            base.green * self.ambient[1],
+            base.blue * self.ambient[2],
+            base.alpha,
+        )
+    }
+
+    /// 环境光亮度 (0.0-1.0)
+    pub fn brightness(&self) -> f32 {
+        (self.ambient[0] + self.ambient[1] + self.ambient[2]) / 3.0
+    }
+}
+
+/// 时段变化事件，供日出短乐等订阅
+#[derive(Event, Debug, Clone, Copy)]
+pub struct TimeOfDayChanged {
+    pub current: TimeOfDay,
+}
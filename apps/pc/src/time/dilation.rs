@@ -0,0 +1,176 @@
+use bevy::prelude::*;
+
+/// 时间缩放的来源
+///
+/// 同类效果互相覆盖，不同类效果同时存在时取最慢的一个
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DilationKind {
+    /// 重击顿帧，极短时间内几乎冻结
+    Hitstop,
+    /// 招架、终结技等触发的慢动作
+    SlowMotion,
+    /// 过场演出控制的节奏，需要手动清除
+    Cutscene,
+}
+
+/// 一个正在生效的时间缩放
+#[derive(Debug, Clone)]
+pub struct DilationEffect {
+    pub kind: DilationKind,
+    /// 时间流速倍率 (0.0 为冻结，1.0 为正常)
+    pub scale: f32,
+    /// 剩余时长（真实时间秒数），None 表示直到手动清除
+    pub remaining: Option<f32>,
+}
+
+/// 全局时间缩放服务
+///
+/// # 设计思路
+/// 1. 效果持续时间按真实时间计算，顿帧期间游戏时间冻结也能按时恢复
+/// 2. 最终倍率写入 `Time<Virtual>`，固定步长的累加来自虚拟时间，
+///    FixedUpdate 中的战斗逻辑随之同步变慢，无需单独处理
+/// 3. 音频不直接跟随倍率，而是限制在 `audio_min_speed` 以上，避免顿帧时声音中断
+#[derive(Resource, Debug, Clone)]
+pub struct TimeDilation {
+    /// 基础倍率，例如调试时的整体加速
+    pub base_scale: f32,
+    /// 音频播放速度下限
+    pub audio_min_speed: f32,
+    /// 当前生效的效果
+    effects: Vec<DilationEffect>,
+}
+
+impl Default for TimeDilation {
+    fn default() -> Self {
+        Self {
+            base_scale: 1.0,
+            audio_min_speed: 0.6,
+            effects: Vec::new(),
+        }
+    }
+}
+
+impl TimeDilation {
+    /// 添加或替换同类效果
+    pub fn push(&mut self, kind: DilationKind, scale: f32, duration: Option<f32>) {
+        let effect = DilationEffect {
+            kind,
+            scale: scale.clamp(0.0, 1.0),
+            remaining: duration,
+        };
+
+        match self.effects.iter_mut().find(|e| e.kind == kind) {
+            // 同类效果取更慢的倍率和更长的时长，连续重击不会相互缩短
+            Some(existing) => {
+                existing.scale = existing.scale.min(effect.scale);
+                existing.remaining = match (existing.remaining, effect.remaining) {
+                    (Some(a), Some(b)) => Some(a.max(b)),
+                    _ => None,
+                };
+            }
+            None => self.effects.push(effect),
+        }
+    }
+
+    /// 重击顿帧
+    pub fn hitstop(&mut self, duration: f32) {
+        self.push(DilationKind::Hitstop, 0.0, Some(duration));
+    }
+
+    /// 短暂慢动作
+    pub fn slow_motion(&mut self, scale: f32, duration: f32) {
+        self.push(DilationKind::SlowMotion, scale, Some(duration));
+    }
+
+    /// 设置过场节奏，直到调用 `clear(DilationKind::Cutscene)`
+    pub fn set_cutscene_scale(&mut self, scale: f32) {
+        self.effects.retain(|e| e.kind != DilationKind::Cutscene);
+        self.push(DilationKind::Cutscene, scale, None);
+    }
+
+    /// 清除某类效果
+    pub fn clear(&mut self, kind: DilationKind) {
+        self.effects.retain(|e| e.kind != kind);
+    }
+
+    /// 按真实时间推进效果，移除已结束的效果
+    pub fn tick(&mut self, real_delta: f32) {
+        for effect in self.effects.iter_mut() {
+            if let Some(remaining) = effect.remaining.as_mut() {
+                *remaining -= real_delta;
+            }
+        }
+        self.effects
+            .retain(|e| e.remaining.is_none_or(|remaining| remaining > 0.0));
+    }
+
+    /// 当前的全局倍率
+    pub fn scale(&self) -> f32 {
+        let effect_scale = self.effects.iter().map(|e| e.scale).fold(1.0_f32, f32::min);
+        (self.base_scale * effect_scale).max(0.0)
+    }
+
+    /// 音频应使用的播放速度
+    pub fn audio_speed(&self) -> f32 {
+        self.scale()
+            .clamp(self.audio_min_speed, 1.0_f32.max(self.base_scale))
+    }
+}
+
+/// 实体自身的时间缩放
+///
+/// 在全局倍率之上叠加，用于只让部分角色变慢（如被冻结的敌人），
+/// 或让终结技的施放者不受慢动作影响
+#[derive(Component, Debug, Clone)]
+pub struct LocalTimeScale {
+    /// 实体倍率
+    pub scale: f32,
+    /// 为 true 时忽略全局效果，只按基础倍率流逝
+    pub ignore_global: bool,
+    /// 剩余时长（真实时间秒数），结束后倍率恢复为 1.0
+    pub remaining: Option<f32>,
+}
+
+impl Default for LocalTimeScale {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            ignore_global: false,
+            remaining: None,
+        }
+    }
+}
+
+impl LocalTimeScale {
+    /// 将全局虚拟时间的增量换算为该实体的时间增量
+    ///
+    /// `delta` 已包含全局倍率，忽略全局效果的实体需要还原
+    pub fn apply(&self, delta: f32, dilation: &TimeDilation) -> f32 {
+        if self.ignore_global {
+            let global = dilation.scale();
+            if global > f32::EPSILON {
+                return delta / global * dilation.base_scale * self.scale;
+            }
+            return 0.0;
+        }
+        delta * self.scale
+    }
+}
+
+/// 时间缩放请求事件
+///
+/// 不方便直接访问 `TimeDilation` 的系统通过事件提交请求
+#[derive(Event, Debug, Clone)]
+pub struct TimeDilationEvent {
+    /// 目标实体，None 表示全局
+    pub target: Option<Entity>,
+    pub kind: DilationKind,
+    pub scale: f32,
+    pub duration: Option<f32>,
+}
+
+/// 不受时间缩放影响的标记
+///
+/// 目前用于背景音乐等音源，慢动作时保持原速播放
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct IgnoreTimeDilation;
@@ -0,0 +1,18 @@
+/// 时间模块
+///
+/// 管理游戏时间的流速与推进
+///
+/// # 模块组成
+/// 1. dilation：时间缩放服务，用于顿帧、慢动作与过场节奏
+/// 2. calendar：游戏历法，日期与季节推进
+/// 3. day_night：昼夜时段与环境光
+/// 4. systems：时间插件及相关系统
+mod calendar;
+mod day_night;
+mod dilation;
+mod systems;
+
+pub use calendar::*;
+pub use day_night::*;
+pub use dilation::*;
+pub use systems::GameTimePlugin;
@@ -0,0 +1,209 @@
+use bevy::audio::AudioSink;
+use bevy::prelude::*;
+
+use super::{
+    DayNightSettings, DayNightState, GameCalendar, IgnoreTimeDilation, LocalTimeScale, NewDay,
+    SeasonChanged, TimeDilation, TimeDilationEvent, TimeOfDayChanged, TimeSkipRequest, TimeSkipped,
+};
+use crate::resources::{gameplay_running, GameState};
+
+/// 时间插件
+pub struct GameTimePlugin;
+
+impl Plugin for GameTimePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TimeDilation>()
+            .init_resource::<GameCalendar>()
+            .init_resource::<DayNightSettings>()
+            .init_resource::<DayNightState>()
+            .add_event::<TimeDilationEvent>()
+            .add_event::<SeasonChanged>()
+            .add_event::<NewDay>()
+            .add_event::<TimeSkipRequest>()
+            .add_event::<TimeSkipped>()
+            .add_event::<TimeOfDayChanged>();
+
+        // 在 PreUpdate 中更新倍率，本帧结束前的所有逻辑看到一致的流速；
+        // 效果按真实时间倒计时，暂停期间不倒计时，恢复后剩余时长不变
+        app.add_systems(
+            PreUpdate,
+            (
+                apply_time_dilation_events,
+                (tick_time_dilation, tick_local_time_scales).run_if(gameplay_running),
+                sync_audio_speed,
+            )
+                .chain(),
+        );
+
+        app.add_systems(
+            Update,
+            (
+                apply_time_skips,
+                advance_calendar.run_if(gameplay_running),
+                update_day_night,
+            )
+                .chain(),
+        );
+
+        // 暂停时冻结虚拟时间，按游戏时间计时的计时器与固定步长都停下，恢复时不会补上暂停的时长
+        app.add_systems(OnEnter(GameState::Paused), pause_virtual_time)
+            .add_systems(OnExit(GameState::Paused), resume_virtual_time);
+    }
+}
+
+fn pause_virtual_time(mut virtual_time: ResMut<Time<Virtual>>) {
+    virtual_time.pause();
+}
+
+fn resume_virtual_time(mut virtual_time: ResMut<Time<Virtual>>) {
+    virtual_time.unpause();
+}
+
+/// 推进历法，跨日、换季时发出事件
+fn advance_calendar(
+    time: Res<Time>,
+    mut calendar: ResMut<GameCalendar>,
+    mut new_day_events: EventWriter<NewDay>,
+    mut season_events: EventWriter<SeasonChanged>,
+) {
+    let before = calendar.clone();
+    let days = calendar.advance(time.delta_secs());
+    send_calendar_events(&before, days, &mut new_day_events, &mut season_events);
+}
+
+/// 根据历法时刻更新时段与环境光
+fn update_day_night(
+    calendar: Res<GameCalendar>,
+    settings: Res<DayNightSettings>,
+    mut state: ResMut<DayNightState>,
+    mut events: EventWriter<TimeOfDayChanged>,
+) {
+    let hour = calendar.time_of_day * 24.0;
+
+    let time_of_day = settings.time_of_day(hour);
+    if time_of_day != state.time_of_day {
+        events.send(TimeOfDayChanged {
+            current: time_of_day,
+        });
+        state.time_of_day = time_of_day;
+    }
+
+    let ambient = settings.ambient_at(hour);
+    let changed = ambient
+        .iter()
+        .zip(state.ambient.iter())
+        .any(|(a, b)| (a - b).abs() > settings.tint_epsilon);
+    if changed {
+        state.ambient = ambient;
+    }
+}
+
+/// 处理跳过时间的请求
+fn apply_time_skips(
+    mut requests: EventReader<TimeSkipRequest>,
+    mut calendar: ResMut<GameCalendar>,
+    mut skipped_events: EventWriter<TimeSkipped>,
+    mut new_day_events: EventWriter<NewDay>,
+    mut season_events: EventWriter<SeasonChanged>,
+) {
+    for request in requests.read() {
+        let hours = request.hours.max(0.0);
+        let before = calendar.clone();
+        let days = calendar.skip_hours(hours);
+        skipped_events.send(TimeSkipped);
+        send_calendar_events(&before, days, &mut new_day_events, &mut season_events);
+    }
+}
+
+/// 跨日、换季时发出事件，一次推进多天时每天各发一次
+fn send_calendar_events(
+    before: &GameCalendar,
+    days: u32,
+    new_day_events: &mut EventWriter<NewDay>,
+    season_events: &mut EventWriter<SeasonChanged>,
+) {
+    let mut previous = before.season();
+    for date in before.following_days(days) {
+        new_day_events.send(NewDay);
+
+        let current = date.season();
+        if current != previous {
+            season_events.send(SeasonChanged {
+                current,
+                year: date.year,
+            });
+            previous = current;
+        }
+    }
+}
+
+/// 处理时间缩放请求
+fn apply_time_dilation_events(
+    mut commands: Commands,
+    mut events: EventReader<TimeDilationEvent>,
+    mut dilation: ResMut<TimeDilation>,
+    mut local_scales: Query<&mut LocalTimeScale>,
+) {
+    for event in events.read() {
+        match event.target {
+            None => dilation.push(event.kind, event.scale, event.duration),
+            Some(entity) => {
+                let local = LocalTimeScale {
+                    scale: event.scale.max(0.0),
+                    ignore_global: false,
+                    remaining: event.duration,
+                };
+                match local_scales.get_mut(entity) {
+                    Ok(mut existing) => *existing = local,
+                    Err(_) => {
+                        if let Some(mut entity_commands) = commands.get_entity(entity) {
+                            entity_commands.insert(local);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 推进全局效果并写入虚拟时间
+fn tick_time_dilation(
+    real_time: Res<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut dilation: ResMut<TimeDilation>,
+) {
+    dilation.tick(real_time.delta_secs());
+
+    let scale = dilation.scale();
+    if (virtual_time.relative_speed() - scale).abs() > f32::EPSILON {
+        virtual_time.set_relative_speed(scale);
+    }
+}
+
+/// 推进实体倍率，到期后恢复正常
+fn tick_local_time_scales(real_time: Res<Time<Real>>, mut query: Query<&mut LocalTimeScale>) {
+    let delta = real_time.delta_secs();
+
+    for mut local in query.iter_mut() {
+        let Some(remaining) = local.remaining.as_mut() else {
+            continue;
+        };
+        *remaining -= delta;
+        if *remaining <= 0.0 {
+            *local = LocalTimeScale::default();
+        }
+    }
+}
+
+/// 让音效播放速度跟随时间倍率，音乐等标记了 `IgnoreTimeDilation` 的音源除外
+fn sync_audio_speed(
+    dilation: Res<TimeDilation>,
+    sinks: Query<&AudioSink, Without<IgnoreTimeDilation>>,
+) {
+    let speed = dilation.audio_speed();
+    for sink in sinks.iter() {
+        if (sink.speed() - speed).abs() > f32::EPSILON {
+            sink.set_speed(speed);
+        }
+    }
+}
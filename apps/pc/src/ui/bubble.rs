@@ -0,0 +1,381 @@
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use std::collections::VecDeque;
+
+use super::wrap_text;
+use crate::events::input::{GameAction, KeyBindings};
+use crate::interaction::InteractionTarget;
+use crate::resources::InputState;
+use crate::world::entity::Player;
+
+/// 气泡类别，决定外观与默认显示时长
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BubbleKind {
+    /// 闲谈
+    Chatter,
+    /// 战斗嘲讽
+    Taunt,
+    /// 教学提示
+    Tutorial,
+}
+
+/// 气泡外观
+#[derive(Debug, Clone, Copy)]
+pub struct BubbleStyle {
+    pub background: Color,
+    pub text_color: Color,
+    pub font_size: f32,
+    /// 每行最多列数（全角字符占两列）
+    pub max_columns: usize,
+    /// 默认显示时长（秒）
+    pub duration: f32,
+}
+
+impl BubbleKind {
+    pub fn style(self) -> BubbleStyle {
+        match self {
+            BubbleKind::Chatter => BubbleStyle {
+                background: Color::srgba(0.08, 0.08, 0.1, 0.75),
+                text_color: Color::WHITE,
+                font_size: 14.0,
+                max_columns: 24,
+                duration: 3.0,
+            },
+            BubbleKind::Taunt => BubbleStyle {
+                background: Color::srgba(0.35, 0.05, 0.05, 0.85),
+                text_color: Color::srgb(1.0, 0.85, 0.6),
+                font_size: 16.0,
+                max_columns: 20,
+                duration: 2.0,
+            },
+            BubbleKind::Tutorial => BubbleStyle {
+                background: Color::srgba(0.92, 0.88, 0.75, 0.92),
+                text_color: Color::srgb(0.15, 0.1, 0.05),
+                font_size: 14.0,
+                max_columns: 32,
+                duration: 6.0,
+            },
+        }
+    }
+}
+
+/// 请求在某个角色头顶显示气泡
+///
+/// 同一角色已有气泡时替换其内容，不会叠出多个
+#[derive(Event, Debug, Clone)]
+pub struct ShowSpeechBubble {
+    /// 气泡跟随的实体
+    pub anchor: Entity,
+    pub text: String,
+    pub kind: BubbleKind,
+    /// 显示时长，为空时使用类别默认值
+    pub duration: Option<f32>,
+}
+
+impl ShowSpeechBubble {
+    pub fn new(anchor: Entity, text: impl Into<String>, kind: BubbleKind) -> Self {
+        Self {
+            anchor,
+            text: text.into(),
+            kind,
+            duration: None,
+        }
+    }
+
+    /// 指定显示时长
+    pub fn with_duration(mut self, seconds: f32) -> Self {
+        self.duration = Some(seconds);
+        self
+    }
+}
+
+/// 气泡配置
+#[derive(Resource, Debug, Clone)]
+pub struct SpeechBubbleSettings {
+    /// 气泡底边相对角色中心的高度（像素）
+    pub anchor_offset: f32,
+    /// 淡入时长（秒）
+    pub fade_in: f32,
+    /// 淡出时长（秒）
+    pub fade_out: f32,
+    /// 文字四周留白（像素）
+    pub padding: Vec2,
+    /// 行高与字号之比
+    pub line_spacing: f32,
+    /// 气泡实体池上限，超出时回收最早显示的气泡
+    pub pool_size: usize,
+    /// 随相机缩放补偿的倍率范围，保证拉远时仍可读、拉近时不过大
+    pub min_scale: f32,
+    pub max_scale: f32,
+    /// 气泡的绘制层级
+    pub z: f32,
+}
+
+impl Default for SpeechBubbleSettings {
+    fn default() -> Self {
+        Self {
+            anchor_offset: 44.0,
+            fade_in: 0.15,
+            fade_out: 0.3,
+            padding: Vec2::new(12.0, 8.0),
+            line_spacing: 1.25,
+            pool_size: 16,
+            min_scale: 0.5,
+            max_scale: 2.0,
+            z: 900.0,
+        }
+    }
+}
+
+/// 气泡组件，挂在气泡背景实体上
+#[derive(Component, Debug, Clone)]
+pub struct SpeechBubble {
+    /// 跟随的实体，回收后为空
+    pub anchor: Option<Entity>,
+    pub kind: BubbleKind,
+    /// 显示开始时间
+    pub shown_at: f64,
+    /// 到期时间
+    pub expires_at: f64,
+}
+
+/// 气泡文字，气泡背景的子实体
+#[derive(Component, Debug, Clone, Copy)]
+pub struct SpeechBubbleText;
+
+/// 气泡实体池
+///
+/// 气泡频繁出现又很快消失，启动时按上限一次生成，之后只回收复用，
+/// 不再反复生成文字实体
+#[derive(Resource, Debug, Default)]
+pub struct SpeechBubblePool {
+    /// 空闲的气泡
+    free: Vec<Entity>,
+    /// 显示中的气泡，按显示先后排列
+    active: VecDeque<Entity>,
+}
+
+impl SpeechBubblePool {
+    fn activate(&mut self, entity: Entity) {
+        self.active.retain(|active| *active != entity);
+        self.active.push_back(entity);
+    }
+
+    fn release(&mut self, entity: Entity) {
+        self.active.retain(|active| *active != entity);
+        if !self.free.contains(&entity) {
+            self.free.push(entity);
+        }
+    }
+}
+
+/// 启动时生成全部气泡实体，初始隐藏
+pub fn spawn_speech_bubble_pool(
+    mut commands: Commands,
+    settings: Res<SpeechBubbleSettings>,
+    mut pool: ResMut<SpeechBubblePool>,
+) {
+    for _ in 0..settings.pool_size.max(1) {
+        let entity = commands
+            .spawn((
+                SpeechBubble {
+                    anchor: None,
+                    kind: BubbleKind::Chatter,
+                    shown_at: 0.0,
+                    expires_at: 0.0,
+                },
+                Sprite {
+                    anchor: Anchor::BottomCenter,
+                    ..default()
+                },
+                Transform::default(),
+                Visibility::Hidden,
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    SpeechBubbleText,
+                    Text2d::default(),
+                    TextFont::default(),
+                    TextColor(Color::WHITE),
+                    TextLayout::new_with_justify(JustifyText::Center),
+                    Transform::from_xyz(0.0, 0.0, 0.1),
+                ));
+            })
+            .id();
+        pool.free.push(entity);
+    }
+}
+
+/// 教学提示：本次游戏中第一次出现可交互对象时，在玩家头顶提示交互键
+///
+/// 交互提示栏一直都在，气泡只在第一次点一下，之后不再打扰
+pub fn show_tutorial_callouts(
+    input_state: Res<InputState>,
+    key_bindings: Res<KeyBindings>,
+    target: Res<InteractionTarget>,
+    players: Query<Entity, With<Player>>,
+    mut shown: Local<bool>,
+    mut bubbles: EventWriter<ShowSpeechBubble>,
+) {
+    if *shown {
+        return;
+    }
+    let (Some(candidate), Ok(player)) = (target.current.as_ref(), players.get_single()) else {
+        return;
+    };
+    let key = key_bindings.prompt(GameAction::Interact, input_state.device);
+    bubbles.send(ShowSpeechBubble::new(
+        player,
+        format!(
+            "靠近人或物时按 {} 互动，比如{}{}",
+            key, candidate.verb, candidate.name
+        ),
+        BubbleKind::Tutorial,
+    ));
+    *shown = true;
+}
+
+/// 处理气泡请求
+///
+/// # 设计思路
+/// 1. 同一角色只保留一个气泡，新台词直接替换，不重新淡入
+/// 2. 优先取空闲气泡，没有空闲时回收最早显示的气泡
+/// 3. 折行在这里一次完成，背景大小按折行结果计算
+pub fn show_speech_bubbles(
+    time: Res<Time>,
+    settings: Res<SpeechBubbleSettings>,
+    mut pool: ResMut<SpeechBubblePool>,
+    mut requests: EventReader<ShowSpeechBubble>,
+    anchors: Query<(), With<GlobalTransform>>,
+    mut bubbles: Query<(Entity, &mut SpeechBubble, &mut Sprite, &Children)>,
+    mut texts: Query<
+        (&mut Text2d, &mut TextFont, &mut TextColor, &mut Transform),
+        With<SpeechBubbleText>,
+    >,
+) {
+    let now = time.elapsed_secs_f64();
+
+    for request in requests.read() {
+        if anchors.get(request.anchor).is_err() {
+            continue;
+        }
+        let style = request.kind.style();
+        let duration = request.duration.unwrap_or(style.duration).max(0.1);
+
+        let existing = bubbles
+            .iter()
+            .find(|(_, bubble, ..)| bubble.anchor == Some(request.anchor))
+            .map(|(entity, ..)| entity);
+        let reused = existing.is_some();
+        let Some(entity) = existing
+            .or_else(|| pool.free.pop())
+            .or_else(|| pool.active.pop_front())
+        else {
+            continue;
+        };
+        let Ok((_, mut bubble, mut sprite, children)) = bubbles.get_mut(entity) else {
+            continue;
+        };
+
+        let wrapped = wrap_text(&request.text, style.max_columns);
+        let line_height = style.font_size * settings.line_spacing;
+        // 全角字符宽约等于字号，一列取字号的一半
+        let text_size = Vec2::new(
+            wrapped.columns as f32 * style.font_size * 0.5,
+            wrapped.lines.len() as f32 * line_height,
+        );
+        let size = text_size + settings.padding * 2.0;
+
+        // 同一角色连续说话时保持可见，不重新淡入
+        if !(reused && bubble.kind == request.kind) {
+            bubble.shown_at = now;
+        }
+        bubble.anchor = Some(request.anchor);
+        bubble.kind = request.kind;
+        bubble.expires_at = now + duration as f64;
+
+        sprite.color = style.background;
+        sprite.custom_size = Some(size);
+
+        for child in children.iter() {
+            if let Ok((mut text, mut font, mut color, mut transform)) = texts.get_mut(*child) {
+                text.0 = wrapped.joined();
+                font.font_size = style.font_size;
+                color.0 = style.text_color;
+                transform.translation.y = size.y * 0.5;
+            }
+        }
+
+        pool.activate(entity);
+    }
+}
+
+/// 更新显示中的气泡
+///
+/// 跟随角色移动，按相机缩放补偿大小，处理淡入淡出，
+/// 到期或角色消失时回收到池中
+pub fn update_speech_bubbles(
+    time: Res<Time>,
+    settings: Res<SpeechBubbleSettings>,
+    mut pool: ResMut<SpeechBubblePool>,
+    cameras: Query<&OrthographicProjection, With<Camera2d>>,
+    anchors: Query<&GlobalTransform, Without<SpeechBubble>>,
+    mut bubbles: Query<(
+        Entity,
+        &mut SpeechBubble,
+        &mut Sprite,
+        &mut Transform,
+        &mut Visibility,
+        &Children,
+    )>,
+    mut texts: Query<&mut TextColor, With<SpeechBubbleText>>,
+) {
+    let now = time.elapsed_secs_f64();
+    // 相机拉远时投影缩放变大，气泡同步放大以保持屏幕上的大小
+    let scale = cameras
+        .get_single()
+        .map(|projection| projection.scale)
+        .unwrap_or(1.0)
+        .clamp(settings.min_scale, settings.max_scale);
+
+    for (entity, mut bubble, mut sprite, mut transform, mut visibility, children) in
+        bubbles.iter_mut()
+    {
+        let Some(anchor) = bubble.anchor else {
+            continue;
+        };
+        let anchor_position = anchors.get(anchor).ok().map(|t| t.translation());
+        let Some(anchor_position) = anchor_position.filter(|_| now < bubble.expires_at) else {
+            bubble.anchor = None;
+            *visibility = Visibility::Hidden;
+            pool.release(entity);
+            continue;
+        };
+
+        transform.translation = Vec3::new(
+            anchor_position.x,
+            anchor_position.y + settings.anchor_offset,
+            settings.z,
+        );
+        transform.scale = Vec3::splat(scale);
+
+        let fade_in =
+            ((now - bubble.shown_at) as f32 / settings.fade_in.max(f32::EPSILON)).min(1.0);
+        let fade_out =
+            ((bubble.expires_at - now) as f32 / settings.fade_out.max(f32::EPSILON)).min(1.0);
+        let alpha = fade_in.min(fade_out).max(0.0);
+
+        let style = bubble.kind.style();
+        sprite.color = style
+            .background
+            .with_alpha(style.background.alpha() * alpha);
+        for child in children.iter() {
+            if let Ok(mut color) = texts.get_mut(*child) {
+                color.0 = style
+                    .text_color
+                    .with_alpha(style.text_color.alpha() * alpha);
+            }
+        }
+        *visibility = Visibility::Visible;
+    }
+}
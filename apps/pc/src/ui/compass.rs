@@ -0,0 +1,326 @@
+use bevy::prelude::*;
+
+use super::{glyph, label, panel, TextRole, WorldMapState};
+use crate::world::chunk::TILE_PIXELS;
+use crate::world::entity::Player;
+use crate::world::map::QuestMarkers;
+use crate::world::poi::{MapPins, PoiRegistry};
+
+/// 方位刻度，按顺时针从正北开始
+const CARDINALS: [(&str, f32); 8] = [
+    ("北", 0.0),
+    ("东北", 45.0),
+    ("东", 90.0),
+    ("东南", 135.0),
+    ("南", 180.0),
+    ("西南", 225.0),
+    ("西", 270.0),
+    ("西北", 315.0),
+];
+
+/// 罗盘配置
+#[derive(Resource, Debug, Clone)]
+pub struct CompassSettings {
+    /// 是否显示罗盘
+    pub visible: bool,
+    /// 罗盘条宽度
+    pub width: f32,
+    /// 罗盘条高度
+    pub height: f32,
+    /// 与屏幕顶部的间距
+    pub margin: f32,
+    /// 罗盘条覆盖的视角（度）
+    pub field_of_view: f32,
+    /// 显示追踪中的任务目标
+    pub show_quests: bool,
+    /// 显示附近已发现的兴趣点
+    pub show_waypoints: bool,
+    /// 显示玩家选择上罗盘的地图标注
+    pub show_pins: bool,
+    /// 兴趣点的显示范围（瓦片）
+    pub waypoint_radius: f32,
+    /// 刷新间隔（秒），朝向变化时立即刷新
+    pub refresh_interval: f32,
+    pub quest_color: Color,
+    pub waypoint_color: Color,
+    pub pin_color: Color,
+}
+
+impl Default for CompassSettings {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            width: 480.0,
+            height: 36.0,
+            margin: 8.0,
+            field_of_view: 180.0,
+            show_quests: true,
+            show_waypoints: true,
+            show_pins: true,
+            waypoint_radius: 96.0,
+            refresh_interval: 0.2,
+            quest_color: Color::srgb(0.98, 0.8, 0.25),
+            waypoint_color: Color::srgb(0.47, 0.86, 1.0),
+            pin_color: Color::srgb(0.92, 0.55, 0.4),
+        }
+    }
+}
+
+/// 罗盘刷新状态
+#[derive(Resource, Debug, Default)]
+pub struct CompassState {
+    /// 上次绘制时的相机朝向
+    heading: Option<f32>,
+    /// 上次绘制时玩家所在瓦片
+    tile: Option<IVec2>,
+    /// 距上次刷新的时间
+    since_refresh: f32,
+}
+
+/// 罗盘界面根节点
+#[derive(Component)]
+pub struct CompassUi;
+
+/// 罗盘条，刻度与标记都挂在它下面
+#[derive(Component)]
+pub struct CompassStrip;
+
+/// 从 `from` 指向 `to` 的方位角（度），正北为 0，顺时针增加
+pub fn compass_bearing(from: Vec2, to: Vec2) -> f32 {
+    let delta = to - from;
+    delta.x.atan2(delta.y).to_degrees().rem_euclid(360.0)
+}
+
+/// 相机朝向（度），取相机上方在世界中的方向
+pub fn camera_heading(transform: &Transform) -> f32 {
+    let up = transform.rotation * Vec3::Y;
+    compass_bearing(Vec2::ZERO, up.truncate())
+}
+
+/// 方位角相对朝向的偏移，范围 [-180, 180)
+fn relative_bearing(bearing: f32, heading: f32) -> f32 {
+    (bearing - heading + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// 偏移在罗盘条上的横向百分比，超出视角时为空
+fn strip_percent(relative: f32, field_of_view: f32) -> Option<f32> {
+    let half = field_of_view * 0.5;
+    if relative.abs() > half {
+        return None;
+    }
+    Some(50.0 + relative / field_of_view * 100.0)
+}
+
+/// 创建罗盘界面
+pub fn setup_compass(mut commands: Commands, settings: Res<CompassSettings>) {
+    commands
+        .spawn((
+            CompassUi,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(settings.margin),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                display: if settings.visible {
+                    Display::Flex
+                } else {
+                    Display::None
+                },
+                ..default()
+            },
+        ))
+        .with_children(|root| {
+            root.spawn((
+                CompassStrip,
+                Node {
+                    width: Val::Px(settings.width),
+                    height: Val::Px(settings.height),
+                    overflow: Overflow::clip(),
+                    ..default()
+                },
+                panel(),
+            ));
+        });
+}
+
+/// 按配置与世界地图状态显示或隐藏罗盘
+///
+/// 世界地图打开时罗盘让位于地图
+pub fn apply_compass_visibility(
+    settings: Res<CompassSettings>,
+    map_state: Option<Res<State<WorldMapState>>>,
+    mut state: ResMut<CompassState>,
+    mut roots: Query<&mut Node, With<CompassUi>>,
+) {
+    let map_open = map_state
+        .as_ref()
+        .is_some_and(|map_state| *map_state.get() == WorldMapState::Open);
+    let map_changed = map_state
+        .as_ref()
+        .is_some_and(|map_state| map_state.is_changed());
+    if !settings.is_changed() && !map_changed {
+        return;
+    }
+
+    let display = if settings.visible && !map_open {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut node in roots.iter_mut() {
+        if node.display != display {
+            node.display = display;
+        }
+    }
+    // 配置可能改了视角或分类开关，下次立即重绘
+    state.heading = None;
+}
+
+/// 在罗盘条上放一个标记，`symbol` 是标记本身的文字控件
+fn spawn_compass_mark(strip: &mut ChildBuilder, percent: f32, symbol: impl Bundle, text: &str) {
+    strip
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            left: Val::Percent(percent),
+            top: Val::Px(2.0),
+            width: Val::Px(64.0),
+            // 让标记中心对准方位
+            margin: UiRect::left(Val::Px(-32.0)),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            ..default()
+        })
+        .with_children(|mark| {
+            mark.spawn(symbol);
+            if !text.is_empty() {
+                mark.spawn(label(text, TextRole::Small));
+            }
+        });
+}
+
+/// 重绘罗盘
+///
+/// # 设计思路
+/// 1. 相机转动或玩家跨过瓦片时立即重绘，否则按固定间隔刷新距离
+/// 2. 方位刻度随相机朝向滚动，正前方在罗盘条中央
+/// 3. 追踪中的任务目标超出视角时贴在罗盘两端，始终给出转向提示
+/// 4. 兴趣点只显示范围内已发现的，地图标注只显示玩家勾选上罗盘的
+#[allow(clippy::too_many_arguments)]
+pub fn redraw_compass(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<CompassSettings>,
+    mut state: ResMut<CompassState>,
+    quest_markers: Option<Res<QuestMarkers>>,
+    poi_registry: Option<Res<PoiRegistry>>,
+    map_pins: Option<Res<MapPins>>,
+    players: Query<&Transform, With<Player>>,
+    cameras: Query<&Transform, With<Camera2d>>,
+    strips: Query<Entity, With<CompassStrip>>,
+) {
+    if !settings.visible {
+        return;
+    }
+    let (Ok(player), Ok(strip)) = (players.get_single(), strips.get_single()) else {
+        return;
+    };
+    let heading = cameras.get_single().map_or(0.0, camera_heading);
+    let position = player.translation.truncate() / TILE_PIXELS;
+    let tile = position.floor().as_ivec2();
+
+    state.since_refresh += time.delta_secs();
+    let turned = state
+        .heading
+        .is_none_or(|last| relative_bearing(heading, last).abs() > 0.5);
+    let moved = state.tile != Some(tile);
+    let sources_changed = quest_markers.as_ref().is_some_and(|res| res.is_changed())
+        || map_pins.as_ref().is_some_and(|res| res.is_changed());
+    if !turned && !moved && !sources_changed && state.since_refresh < settings.refresh_interval {
+        return;
+    }
+    state.heading = Some(heading);
+    state.tile = Some(tile);
+    state.since_refresh = 0.0;
+
+    let fov = settings.field_of_view.clamp(30.0, 360.0);
+    let target_center = |target: IVec2| target.as_vec2() + Vec2::splat(0.5);
+
+    commands.entity(strip).despawn_descendants();
+    commands.entity(strip).with_children(|strip| {
+        for (name, bearing) in CARDINALS {
+            let Some(percent) = strip_percent(relative_bearing(bearing, heading), fov) else {
+                continue;
+            };
+            // 四正方位醒目，四隅方位淡一些
+            let role = if name.chars().count() == 1 {
+                TextRole::Body
+            } else {
+                TextRole::Muted
+            };
+            spawn_compass_mark(strip, percent, label(name, role), "");
+        }
+
+        if settings.show_waypoints {
+            if let Some(registry) = poi_registry.as_ref() {
+                for poi in registry.within(tile, settings.waypoint_radius) {
+                    if !poi.is_discovered() || poi.position == tile {
+                        continue;
+                    }
+                    let relative = relative_bearing(
+                        compass_bearing(position, target_center(poi.position)),
+                        heading,
+                    );
+                    if let Some(percent) = strip_percent(relative, fov) {
+                        spawn_compass_mark(
+                            strip,
+                            percent,
+                            glyph("◇", 12.0, settings.waypoint_color),
+                            &poi.name,
+                        );
+                    }
+                }
+            }
+        }
+
+        if settings.show_pins {
+            if let Some(pins) = map_pins.as_ref() {
+                for pin in pins.compass_pins() {
+                    let target = target_center(pin.position);
+                    let relative = relative_bearing(compass_bearing(position, target), heading);
+                    if let Some(percent) = strip_percent(relative, fov) {
+                        let distance = format!("{:.0}步", position.distance(target));
+                        spawn_compass_mark(
+                            strip,
+                            percent,
+                            glyph(pin.icon.glyph(), 13.0, settings.pin_color),
+                            &distance,
+                        );
+                    }
+                }
+            }
+        }
+
+        if settings.show_quests {
+            if let Some(markers) = quest_markers.as_ref() {
+                for marker in markers.tracked_markers() {
+                    let target = target_center(marker.position);
+                    let relative = relative_bearing(compass_bearing(position, target), heading);
+                    // 超出视角的任务目标贴边显示，并用箭头提示转向；
+                    // 稍微往里收一点，免得文字被罗盘条裁掉
+                    let (percent, symbol) = match strip_percent(relative, fov) {
+                        Some(percent) => (percent.clamp(6.0, 94.0), "◆"),
+                        None if relative < 0.0 => (6.0, "◀"),
+                        None => (94.0, "▶"),
+                    };
+                    let text = format!("{} {:.0}步", marker.label, position.distance(target));
+                    spawn_compass_mark(
+                        strip,
+                        percent,
+                        glyph(symbol, 14.0, settings.quest_color),
+                        &text,
+                    );
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,505 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use std::collections::VecDeque;
+
+use super::{FontScript, FontService};
+use crate::combat::{HealthChanged, SkillCastFailed, SkillDatabase, SkillUnlocked};
+use crate::items::{ItemDatabase, LootPickedUp};
+use crate::world::chunk::TILE_PIXELS;
+use crate::world::entity::Player;
+use crate::world::map::{QuestManager, QuestStatus, QuestUpdated};
+use crate::world::poi::{default_poi_name, PoiDiscovered};
+
+/// 飘字类别，决定颜色、字号与动画
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatingTextKind {
+    /// 对敌人造成的伤害
+    Damage,
+    /// 玩家受到的伤害
+    PlayerDamage,
+    /// 治疗
+    Heal,
+    /// 获得修为
+    Experience,
+    /// 拾取物品
+    Pickup,
+    /// 其他提示
+    Info,
+}
+
+/// 飘字外观
+#[derive(Debug, Clone, Copy)]
+pub struct FloatingTextStyle {
+    pub color: Color,
+    pub font_size: f32,
+    /// 整段动画上升的距离（像素）
+    pub rise: f32,
+    /// 默认显示时长（秒）
+    pub duration: f32,
+    /// 出现时的放大倍率，随后弹回原大小
+    pub pop: f32,
+}
+
+impl FloatingTextKind {
+    pub fn style(self) -> FloatingTextStyle {
+        match self {
+            FloatingTextKind::Damage => FloatingTextStyle {
+                color: Color::srgb(1.0, 0.95, 0.8),
+                font_size: 18.0,
+                rise: 36.0,
+                duration: 0.9,
+                pop: 1.4,
+            },
+            FloatingTextKind::PlayerDamage => FloatingTextStyle {
+                color: Color::srgb(0.95, 0.3, 0.25),
+                font_size: 18.0,
+                rise: 36.0,
+                duration: 0.9,
+                pop: 1.3,
+            },
+            FloatingTextKind::Heal => FloatingTextStyle {
+                color: Color::srgb(0.45, 0.9, 0.45),
+                font_size: 16.0,
+                rise: 30.0,
+                duration: 1.0,
+                pop: 1.2,
+            },
+            FloatingTextKind::Experience => FloatingTextStyle {
+                color: Color::srgb(0.85, 0.7, 1.0),
+                font_size: 15.0,
+                rise: 40.0,
+                duration: 1.4,
+                pop: 1.0,
+            },
+            FloatingTextKind::Pickup => FloatingTextStyle {
+                color: Color::srgb(0.95, 0.85, 0.5),
+                font_size: 14.0,
+                rise: 28.0,
+                duration: 1.6,
+                pop: 1.0,
+            },
+            FloatingTextKind::Info => FloatingTextStyle {
+                color: Color::WHITE,
+                font_size: 14.0,
+                rise: 20.0,
+                duration: 2.0,
+                pop: 1.0,
+            },
+        }
+    }
+}
+
+/// 飘字出现的位置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FloatingTextAnchor {
+    /// 实体头顶，取显示时的位置，之后不再跟随
+    Entity(Entity),
+    /// 世界坐标
+    World(Vec2),
+    /// 屏幕坐标（逻辑像素，左上角为原点），不随相机移动
+    Screen(Vec2),
+}
+
+/// 请求显示一条飘字
+#[derive(Event, Debug, Clone)]
+pub struct ShowFloatingText {
+    pub anchor: FloatingTextAnchor,
+    pub text: String,
+    pub kind: FloatingTextKind,
+    /// 显示时长，为空时使用类别默认值
+    pub duration: Option<f32>,
+}
+
+impl ShowFloatingText {
+    pub fn new(
+        anchor: FloatingTextAnchor,
+        text: impl Into<String>,
+        kind: FloatingTextKind,
+    ) -> Self {
+        Self {
+            anchor,
+            text: text.into(),
+            kind,
+            duration: None,
+        }
+    }
+
+    /// 实体头顶的伤害数字
+    pub fn damage(target: Entity, amount: f32) -> Self {
+        Self::new(
+            FloatingTextAnchor::Entity(target),
+            format!("{:.0}", amount),
+            FloatingTextKind::Damage,
+        )
+    }
+
+    /// 实体头顶的治疗数字
+    pub fn heal(target: Entity, amount: f32) -> Self {
+        Self::new(
+            FloatingTextAnchor::Entity(target),
+            format!("+{:.0}", amount),
+            FloatingTextKind::Heal,
+        )
+    }
+
+    /// 指定显示时长
+    pub fn with_duration(mut self, seconds: f32) -> Self {
+        self.duration = Some(seconds);
+        self
+    }
+}
+
+/// 飘字配置
+#[derive(Resource, Debug, Clone)]
+pub struct FloatingTextSettings {
+    /// 实体锚点上方的起始高度（像素）
+    pub anchor_offset: f32,
+    /// 起始位置的水平随机偏移（像素），同时出现的数字不至于叠在一起
+    pub jitter: f32,
+    /// 淡出时长（秒）
+    pub fade_out: f32,
+    /// 出现时弹回原大小的时长（秒）
+    pub pop_duration: f32,
+    /// 飘字实体池上限，超出时回收最早显示的飘字
+    pub pool_size: usize,
+    /// 随相机缩放补偿的倍率范围
+    pub min_scale: f32,
+    pub max_scale: f32,
+    /// 飘字的绘制层级，在气泡之上
+    pub z: f32,
+}
+
+impl Default for FloatingTextSettings {
+    fn default() -> Self {
+        Self {
+            anchor_offset: 36.0,
+            jitter: 12.0,
+            fade_out: 0.35,
+            pop_duration: 0.12,
+            pool_size: 48,
+            min_scale: 0.5,
+            max_scale: 2.0,
+            z: 950.0,
+        }
+    }
+}
+
+/// 飘字显示的起点
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FloatingOrigin {
+    World(Vec2),
+    Screen(Vec2),
+}
+
+/// 飘字组件
+#[derive(Component, Debug, Clone)]
+pub struct FloatingText {
+    /// 起点，回收后为空
+    origin: Option<FloatingOrigin>,
+    pub kind: FloatingTextKind,
+    /// 显示开始时间
+    pub shown_at: f64,
+    /// 到期时间
+    pub expires_at: f64,
+}
+
+/// 飘字实体池
+///
+/// 战斗中一秒可能出现几十个数字，启动时按上限一次生成，之后只回收复用
+#[derive(Resource, Debug, Default)]
+pub struct FloatingTextPool {
+    /// 空闲的飘字
+    free: Vec<Entity>,
+    /// 显示中的飘字，按显示先后排列
+    active: VecDeque<Entity>,
+}
+
+impl FloatingTextPool {
+    fn activate(&mut self, entity: Entity) {
+        self.active.retain(|active| *active != entity);
+        self.active.push_back(entity);
+    }
+
+    fn release(&mut self, entity: Entity) {
+        self.active.retain(|active| *active != entity);
+        if !self.free.contains(&entity) {
+            self.free.push(entity);
+        }
+    }
+}
+
+/// 启动时生成全部飘字实体，初始隐藏
+pub fn spawn_floating_text_pool(
+    mut commands: Commands,
+    settings: Res<FloatingTextSettings>,
+    mut pool: ResMut<FloatingTextPool>,
+) {
+    for _ in 0..settings.pool_size.max(1) {
+        let entity = commands
+            .spawn((
+                FloatingText {
+                    origin: None,
+                    kind: FloatingTextKind::Info,
+                    shown_at: 0.0,
+                    expires_at: 0.0,
+                },
+                Text2d::default(),
+                TextFont::default(),
+                TextColor(Color::WHITE),
+                Transform::default(),
+                Visibility::Hidden,
+            ))
+            .id();
+        pool.free.push(entity);
+    }
+}
+
+/// 生命值变化时在角色头顶显示伤害或治疗数字，玩家受伤用另一种颜色
+pub fn float_health_changes(
+    mut events: EventReader<HealthChanged>,
+    players: Query<(), With<Player>>,
+    mut texts: EventWriter<ShowFloatingText>,
+) {
+    for event in events.read() {
+        if event.delta < 0.0 {
+            let mut text = ShowFloatingText::damage(event.entity, -event.delta);
+            if players.contains(event.entity) {
+                text.kind = FloatingTextKind::PlayerDamage;
+            }
+            texts.send(text);
+        } else if event.delta > 0.0 {
+            texts.send(ShowFloatingText::heal(event.entity, event.delta));
+        }
+    }
+}
+
+/// 拾取物品时在拾取者头顶显示物品名与数量
+pub fn float_loot_pickups(
+    mut events: EventReader<LootPickedUp>,
+    database: Res<ItemDatabase>,
+    mut texts: EventWriter<ShowFloatingText>,
+) {
+    for event in events.read() {
+        let name = database
+            .get(&event.item_id)
+            .map_or(event.item_id.as_str(), |def| def.name.as_str());
+        let text = if event.count > 1 {
+            format!("+{} {}", event.count, name)
+        } else {
+            format!("+{}", name)
+        };
+        texts.send(ShowFloatingText::new(
+            FloatingTextAnchor::Entity(event.picker),
+            text,
+            FloatingTextKind::Pickup,
+        ));
+    }
+}
+
+/// 学会新技能时在角色头顶显示技能名
+pub fn float_skill_unlocks(
+    mut events: EventReader<SkillUnlocked>,
+    database: Res<SkillDatabase>,
+    mut texts: EventWriter<ShowFloatingText>,
+) {
+    for event in events.read() {
+        let name = database
+            .get(&event.skill)
+            .map_or(event.skill.as_str(), |def| def.name.as_str());
+        texts.send(ShowFloatingText::new(
+            FloatingTextAnchor::Entity(event.entity),
+            format!("习得「{}」", name),
+            FloatingTextKind::Experience,
+        ));
+    }
+}
+
+/// 玩家施展技能失败时在头顶提示槽位与原因，NPC 失败不提示
+pub fn float_skill_cast_failures(
+    mut events: EventReader<SkillCastFailed>,
+    players: Query<(), With<Player>>,
+    mut texts: EventWriter<ShowFloatingText>,
+) {
+    for event in events.read() {
+        if !players.contains(event.entity) {
+            continue;
+        }
+        texts.send(
+            ShowFloatingText::new(
+                FloatingTextAnchor::Entity(event.entity),
+                format!("{}：{}", event.slot.label(), event.error),
+                FloatingTextKind::Info,
+            )
+            .with_duration(1.0),
+        );
+    }
+}
+
+/// 首次发现兴趣点时在兴趣点上方显示名称
+pub fn float_poi_discoveries(
+    mut events: EventReader<PoiDiscovered>,
+    mut texts: EventWriter<ShowFloatingText>,
+) {
+    for event in events.read() {
+        // 有专名的场景带上类别，如“寺庙·少林寺”
+        let kind = default_poi_name(event.kind);
+        let text = if event.name == kind {
+            format!("发现{}", kind)
+        } else {
+            format!("发现{}·{}", kind, event.name)
+        };
+        texts.send(
+            ShowFloatingText::new(
+                FloatingTextAnchor::World((event.position.as_vec2() + 0.5) * TILE_PIXELS),
+                text,
+                FloatingTextKind::Experience,
+            )
+            .with_duration(2.5),
+        );
+    }
+}
+
+/// 任务完成时在屏幕上方居中显示任务名，不随相机移动
+pub fn float_quest_completions(
+    mut updates: EventReader<QuestUpdated>,
+    manager: Res<QuestManager>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut texts: EventWriter<ShowFloatingText>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    for update in updates.read() {
+        if update.status != QuestStatus::Completed {
+            continue;
+        }
+        let title = manager
+            .quest(&update.quest_id)
+            .map_or(update.quest_id.as_str(), |quest| quest.title.as_str());
+        texts.send(
+            ShowFloatingText::new(
+                FloatingTextAnchor::Screen(Vec2::new(window.width() / 2.0, window.height() / 4.0)),
+                format!("任务完成：{}", title),
+                FloatingTextKind::Experience,
+            )
+            .with_duration(3.0),
+        );
+    }
+}
+
+/// 处理飘字请求
+///
+/// 优先取空闲飘字，没有空闲时回收最早显示的；实体锚点在这里换成世界坐标，
+/// 之后角色走开数字仍停在受击处
+pub fn show_floating_texts(
+    time: Res<Time>,
+    settings: Res<FloatingTextSettings>,
+    fonts: Res<FontService>,
+    mut pool: ResMut<FloatingTextPool>,
+    mut requests: EventReader<ShowFloatingText>,
+    anchors: Query<&GlobalTransform, Without<FloatingText>>,
+    mut texts: Query<(&mut FloatingText, &mut Text2d, &mut TextFont)>,
+) {
+    let now = time.elapsed_secs_f64();
+    // 中文字体通常也含数字与字母，找不到时退回西文字体
+    let font = fonts
+        .font_for(FontScript::Cjk)
+        .or_else(|| fonts.font_for(FontScript::Latin))
+        .unwrap_or_default();
+
+    for request in requests.read() {
+        let jitter = (rand::random::<f32>() - 0.5) * 2.0 * settings.jitter;
+        let origin = match request.anchor {
+            FloatingTextAnchor::Entity(entity) => {
+                let Ok(transform) = anchors.get(entity) else {
+                    continue;
+                };
+                let position = transform.translation().truncate();
+                FloatingOrigin::World(position + Vec2::new(jitter, settings.anchor_offset))
+            }
+            FloatingTextAnchor::World(position) => {
+                FloatingOrigin::World(position + Vec2::new(jitter, 0.0))
+            }
+            FloatingTextAnchor::Screen(position) => FloatingOrigin::Screen(position),
+        };
+
+        let Some(entity) = pool.free.pop().or_else(|| pool.active.pop_front()) else {
+            continue;
+        };
+        let Ok((mut floating, mut text, mut text_font)) = texts.get_mut(entity) else {
+            continue;
+        };
+
+        let style = request.kind.style();
+        let duration = request.duration.unwrap_or(style.duration).max(0.1);
+        floating.origin = Some(origin);
+        floating.kind = request.kind;
+        floating.shown_at = now;
+        floating.expires_at = now + duration as f64;
+
+        text.0.clone_from(&request.text);
+        text_font.font = font.clone();
+        text_font.font_size = style.font_size;
+
+        pool.activate(entity);
+    }
+}
+
+/// 更新显示中的飘字
+///
+/// 上升先快后慢，出现时弹一下，到期前淡出；屏幕锚点每帧按相机换算到世界坐标，
+/// 世界锚点按相机缩放补偿大小，到期后回收到池中
+pub fn update_floating_texts(
+    time: Res<Time>,
+    settings: Res<FloatingTextSettings>,
+    mut pool: ResMut<FloatingTextPool>,
+    cameras: Query<(&Camera, &GlobalTransform, &OrthographicProjection), With<Camera2d>>,
+    mut texts: Query<(
+        Entity,
+        &mut FloatingText,
+        &mut Transform,
+        &mut TextColor,
+        &mut Visibility,
+    )>,
+) {
+    let now = time.elapsed_secs_f64();
+    let camera = cameras.get_single().ok();
+    let scale = camera
+        .map(|(_, _, projection)| projection.scale)
+        .unwrap_or(1.0)
+        .clamp(settings.min_scale, settings.max_scale);
+
+    for (entity, mut floating, mut transform, mut color, mut visibility) in texts.iter_mut() {
+        let Some(origin) = floating.origin else {
+            continue;
+        };
+        let position = match origin {
+            FloatingOrigin::World(position) => Some(position),
+            FloatingOrigin::Screen(position) => camera.and_then(|(camera, camera_transform, _)| {
+                camera.viewport_to_world_2d(camera_transform, position).ok()
+            }),
+        };
+        let Some(position) = position.filter(|_| now < floating.expires_at) else {
+            floating.origin = None;
+            *visibility = Visibility::Hidden;
+            pool.release(entity);
+            continue;
+        };
+
+        let style = floating.kind.style();
+        let duration = (floating.expires_at - floating.shown_at).max(f64::EPSILON);
+        let elapsed = (now - floating.shown_at) as f32;
+        let progress = (elapsed / duration as f32).clamp(0.0, 1.0);
+        let rise = style.rise * (1.0 - (1.0 - progress) * (1.0 - progress));
+        let pop = 1.0
+            + (style.pop - 1.0)
+                * (1.0 - elapsed / settings.pop_duration.max(f32::EPSILON)).max(0.0);
+        let fade =
+            ((floating.expires_at - now) as f32 / settings.fade_out.max(f32::EPSILON)).min(1.0);
+
+        transform.translation = Vec3::new(position.x, position.y + rise * scale, settings.z);
+        transform.scale = Vec3::splat(scale * pop);
+        color.0 = style.color.with_alpha(style.color.alpha() * fade.max(0.0));
+        *visibility = Visibility::Visible;
+    }
+}
@@ -0,0 +1,370 @@
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use super::UiTheme;
+use crate::config::resolve_data_path;
+use crate::logging::{GameLogger, LogLevel};
+
+/// 字体数据文件路径
+pub const FONT_DATA_PATH: &str = "src/config/fonts.json";
+
+/// 文字所属的字体分区
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FontScript {
+    /// 中日韩文字与全角标点
+    Cjk,
+    /// 拉丁字母、数字与常用标点
+    Latin,
+    /// 箭头、几何图形等符号
+    Symbol,
+}
+
+/// 字符所属的分区，空白与控制字符任何字体都能排版，返回空
+pub fn script_of(c: char) -> Option<FontScript> {
+    if c.is_whitespace() || c.is_control() {
+        return None;
+    }
+    let code = c as u32;
+    let script = match code {
+        0x0020..=0x024F | 0x2000..=0x206F | 0x20A0..=0x20CF => FontScript::Latin,
+        0x2E80..=0x2FDF
+        | 0x3000..=0x30FF
+        | 0x3100..=0x31FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xAC00..=0xD7AF
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFFEF
+        | 0x20000..=0x2FA1F => FontScript::Cjk,
+        _ => FontScript::Symbol,
+    };
+    Some(script)
+}
+
+/// 字体链中的一项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontEntry {
+    pub name: String,
+    /// 完整字体
+    pub path: String,
+    /// 只含常用字的子集字体，启动时先加载它，完整字体在后台加载
+    #[serde(default)]
+    pub subset: Option<String>,
+    /// 覆盖的分区
+    pub scripts: Vec<FontScript>,
+}
+
+/// 字体数据文件格式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FontDataFile {
+    #[serde(default)]
+    use_subsets: bool,
+    #[serde(default = "default_replacement")]
+    replacement: char,
+    fonts: Vec<FontEntry>,
+}
+
+fn default_replacement() -> char {
+    '?'
+}
+
+/// 字体的加载状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SlotState {
+    Loading,
+    Ready,
+    Failed,
+}
+
+/// 字体链中已开始加载的一项
+#[derive(Debug)]
+struct FontSlot {
+    entry: FontEntry,
+    full: Handle<Font>,
+    full_state: SlotState,
+    subset: Option<(Handle<Font>, SlotState)>,
+}
+
+impl FontSlot {
+    /// 可用的字体，完整字体优先
+    fn usable(&self) -> Option<&Handle<Font>> {
+        if self.full_state == SlotState::Ready {
+            return Some(&self.full);
+        }
+        match &self.subset {
+            Some((handle, SlotState::Ready)) => Some(handle),
+            _ => None,
+        }
+    }
+
+    fn covers(&self, script: FontScript) -> bool {
+        self.entry.scripts.contains(&script)
+    }
+}
+
+/// 一段使用同一字体的文字
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontRun {
+    pub text: String,
+    pub font: Handle<Font>,
+}
+
+/// 字体服务
+///
+/// # 设计思路
+/// 1. 按数据文件中的顺序组成回退链，通常是中文字体、西文字体、符号字体
+/// 2. 字体都异步加载，加载完成前文字先用链中后面已就绪的字体或默认字体
+/// 3. 开启子集时先加载体积小的常用字子集，完整字体就绪后替换，缩短启动等待
+/// 4. 所有字体都缺字的字符换成替代符号，不显示方框
+#[derive(Resource, Debug)]
+pub struct FontService {
+    slots: Vec<FontSlot>,
+    /// 缺字时的替代符号
+    pub replacement: char,
+}
+
+impl Default for FontService {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            replacement: default_replacement(),
+        }
+    }
+}
+
+impl FontService {
+    /// 读取字体链并开始加载
+    pub fn load(
+        path: &str,
+        asset_server: &AssetServer,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let data: FontDataFile = serde_json::from_str(&content)?;
+        let slots = data
+            .fonts
+            .into_iter()
+            .map(|entry| {
+                let subset = entry
+                    .subset
+                    .as_ref()
+                    .filter(|_| data.use_subsets)
+                    .map(|subset| (asset_server.load(subset.clone()), SlotState::Loading));
+                FontSlot {
+                    full: asset_server.load(entry.path.clone()),
+                    full_state: SlotState::Loading,
+                    subset,
+                    entry,
+                }
+            })
+            .collect();
+        Ok(Self {
+            slots,
+            replacement: data.replacement,
+        })
+    }
+
+    /// 某个分区可用的字体，按回退链顺序查找
+    pub fn font_for(&self, script: FontScript) -> Option<Handle<Font>> {
+        self.slots
+            .iter()
+            .filter(|slot| slot.covers(script))
+            .find_map(|slot| slot.usable().cloned())
+    }
+
+    /// 是否还有字体在加载
+    pub fn is_loading(&self) -> bool {
+        self.slots.iter().any(|slot| {
+            slot.full_state == SlotState::Loading
+                || matches!(slot.subset, Some((_, SlotState::Loading)))
+        })
+    }
+
+    /// 把文字按字体切成若干段
+    ///
+    /// `primary` 是界面主题指定的字体，优先用于中文与西文；
+    /// 符号总是走回退链，主题字体通常不含这些符号
+    pub fn runs(&self, text: &str, primary: Option<&Handle<Font>>) -> Vec<FontRun> {
+        let cjk = primary.cloned().or_else(|| self.font_for(FontScript::Cjk));
+        let latin = primary
+            .cloned()
+            .or_else(|| self.font_for(FontScript::Latin))
+            .unwrap_or_default();
+        let symbol = self.font_for(FontScript::Symbol);
+
+        let mut runs: Vec<FontRun> = Vec::new();
+        for c in text.chars() {
+            let (c, font) = match script_of(c) {
+                // 空白跟随前一段，不单独切段
+                None => match runs.last_mut() {
+                    Some(run) => {
+                        run.text.push(c);
+                        continue;
+                    }
+                    None => (c, latin.clone()),
+                },
+                Some(FontScript::Latin) => (c, latin.clone()),
+                Some(FontScript::Cjk) => match &cjk {
+                    Some(font) => (c, font.clone()),
+                    None => (self.replacement, latin.clone()),
+                },
+                Some(FontScript::Symbol) => match &symbol {
+                    Some(font) => (c, font.clone()),
+                    None => (self.replacement, latin.clone()),
+                },
+            };
+            match runs.last_mut() {
+                Some(run) if run.font == font => run.text.push(c),
+                _ => runs.push(FontRun {
+                    text: c.to_string(),
+                    font,
+                }),
+            }
+        }
+        runs
+    }
+}
+
+/// 需要按字体回退链排版的文字
+///
+/// 实体上的 `Text` 保持为空，文字按字体切段后放在子实体的 `TextSpan` 中，
+/// 字号与颜色取自实体自身的 `TextFont` 与 `TextColor`
+#[derive(Component, Debug, Clone, Default)]
+pub struct MixedText(pub String);
+
+/// 开始加载字体链
+pub fn load_fonts(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let service = match FontService::load(&resolve_data_path(FONT_DATA_PATH), &asset_server) {
+        Ok(service) => service,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(
+                    LogLevel::Error,
+                    &format!("加载字体配置失败: {}，只使用默认字体", e),
+                );
+            }
+            FontService::default()
+        }
+    };
+    commands.insert_resource(service);
+}
+
+/// 跟踪字体加载进度
+///
+/// 字体就绪或失败时才修改服务，排版系统据此重新切段
+pub fn track_font_loads(
+    asset_server: Res<AssetServer>,
+    mut service: ResMut<FontService>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    if !service.is_loading() {
+        return;
+    }
+
+    let state_of = |handle: &Handle<Font>| match asset_server.get_load_state(handle) {
+        Some(LoadState::Loaded) => SlotState::Ready,
+        Some(LoadState::Failed(_)) => SlotState::Failed,
+        _ => SlotState::Loading,
+    };
+
+    let mut updates = Vec::new();
+    for (index, slot) in service.slots.iter().enumerate() {
+        let full = match slot.full_state {
+            SlotState::Loading => state_of(&slot.full),
+            state => state,
+        };
+        let subset = slot.subset.as_ref().map(|(handle, state)| match state {
+            SlotState::Loading => state_of(handle),
+            state => *state,
+        });
+        let changed = full != slot.full_state || subset != slot.subset.as_ref().map(|s| s.1);
+        if changed {
+            updates.push((index, full, subset));
+        }
+    }
+    if updates.is_empty() {
+        return;
+    }
+
+    for (index, full, subset) in updates {
+        let slot = &mut service.slots[index];
+        slot.full_state = full;
+        if let (Some((_, state)), Some(subset)) = (slot.subset.as_mut(), subset) {
+            *state = subset;
+        }
+        // 完整字体就绪后释放子集
+        if full == SlotState::Ready {
+            slot.subset = None;
+        }
+
+        if let Some(logger) = logger.as_mut() {
+            let name = &slot.entry.name;
+            match (full, subset) {
+                (SlotState::Ready, _) => {
+                    logger.log(LogLevel::Info, &format!("字体已加载: {}", name));
+                }
+                (SlotState::Failed, _) => logger.log(
+                    LogLevel::Error,
+                    &format!(
+                        "字体加载失败: {} ({})，改用回退链中的下一个",
+                        name, slot.entry.path
+                    ),
+                ),
+                (SlotState::Loading, Some(SlotState::Ready)) => {
+                    logger.log(LogLevel::Debug, &format!("常用字子集已加载: {}", name));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// 按字体回退链排版文字
+///
+/// 文字、字号、颜色或可用字体变化时重建文字段
+#[allow(clippy::type_complexity)]
+pub fn layout_mixed_text(
+    mut commands: Commands,
+    service: Res<FontService>,
+    theme: Res<UiTheme>,
+    mut texts: Query<(
+        Entity,
+        Ref<MixedText>,
+        &mut Text,
+        Ref<TextFont>,
+        Ref<TextColor>,
+    )>,
+) {
+    let all = service.is_changed() || theme.is_changed();
+    for (entity, mixed, mut text, font, color) in texts.iter_mut() {
+        if !all && !mixed.is_changed() && !font.is_changed() && !color.is_changed() {
+            continue;
+        }
+        if !text.0.is_empty() {
+            text.0.clear();
+        }
+
+        let primary = (font.font != Handle::default()).then_some(&font.font);
+        commands.entity(entity).despawn_descendants();
+        commands.entity(entity).with_children(|parent| {
+            for run in service.runs(&mixed.0, primary) {
+                parent.spawn((
+                    TextSpan::new(run.text),
+                    TextFont {
+                        font: run.font,
+                        font_size: font.font_size,
+                        ..default()
+                    },
+                    TextColor(color.0),
+                ));
+            }
+        });
+    }
+}
@@ -0,0 +1,311 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+
+use super::{label, overlay, panel, spawn_text_button, ChangeScene, SceneTransition, TextRole};
+use crate::config::Settings;
+use crate::network::{LoginRequest, LoginResult};
+use crate::resources::GameState;
+
+/// 账号最多输入的字数
+const MAX_ACCOUNT_CHARS: usize = 32;
+
+/// 密码最多输入的字数
+const MAX_PASSWORD_CHARS: usize = 64;
+
+/// 登录表单的输入框
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoginField {
+    #[default]
+    Account,
+    Password,
+}
+
+/// 登录进度
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum LoginStatus {
+    #[default]
+    Idle,
+    /// 已提交，等待服务器回应
+    Waiting,
+    /// 失败原因
+    Failed(String),
+}
+
+/// 登录表单
+///
+/// 密码只在提交时随请求发出，登录成功或离开登录界面后清空
+#[derive(Resource, Debug, Default)]
+pub struct LoginForm {
+    pub account: String,
+    pub password: String,
+    pub focus: LoginField,
+    pub status: LoginStatus,
+}
+
+impl LoginForm {
+    /// 提交登录，账号或密码为空时提示而不发出请求
+    fn submit(&mut self, requests: &mut EventWriter<LoginRequest>) {
+        if self.status == LoginStatus::Waiting {
+            return;
+        }
+        let account = self.account.trim();
+        if account.is_empty() || self.password.is_empty() {
+            self.status = LoginStatus::Failed("请输入账号与密码".to_string());
+            return;
+        }
+        requests.send(LoginRequest {
+            account: account.to_string(),
+            password: self.password.clone(),
+        });
+        self.status = LoginStatus::Waiting;
+    }
+}
+
+/// 登录界面根节点
+#[derive(Component)]
+pub struct LoginUi;
+
+/// 登录表单面板，输入或进度变化时整块重建
+#[derive(Component)]
+pub struct LoginPanel;
+
+/// 登录界面上的按钮
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginButton {
+    /// 点击输入框切换焦点
+    Field(LoginField),
+    Login,
+    Quit,
+}
+
+impl LoginButton {
+    pub fn label(self) -> &'static str {
+        match self {
+            LoginButton::Field(LoginField::Account) => "账号",
+            LoginButton::Field(LoginField::Password) => "密码",
+            LoginButton::Login => "登录",
+            LoginButton::Quit => "退出游戏",
+        }
+    }
+}
+
+/// 进入登录界面：设置了离线登录时直接进入主菜单，否则生成表单
+pub fn open_login_screen(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut form: ResMut<LoginForm>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if settings.game.network.offline_login {
+        next_state.set(GameState::MainMenu);
+        return;
+    }
+    form.focus = if form.account.is_empty() {
+        LoginField::Account
+    } else {
+        LoginField::Password
+    };
+    form.status = LoginStatus::Idle;
+    commands
+        .spawn((
+            LoginUi,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(24.0),
+                ..default()
+            },
+            overlay(),
+            GlobalZIndex(10),
+        ))
+        .with_children(|root| {
+            root.spawn(label(settings.game.window.title.clone(), TextRole::Title));
+            root.spawn((
+                LoginPanel,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(16.0)),
+                    row_gap: Val::Px(8.0),
+                    min_width: Val::Px(360.0),
+                    ..default()
+                },
+                panel(),
+            ));
+        });
+}
+
+/// 离开登录界面时移除界面并清空密码
+pub fn close_login_screen(
+    mut commands: Commands,
+    mut form: ResMut<LoginForm>,
+    roots: Query<Entity, With<LoginUi>>,
+) {
+    form.password.clear();
+    for root in roots.iter() {
+        commands.entity(root).despawn_recursive();
+    }
+}
+
+/// 处理登录界面按钮，切换场景期间不响应
+pub fn handle_login_buttons(
+    buttons: Query<(&Interaction, &LoginButton), Changed<Interaction>>,
+    transition: Res<SceneTransition>,
+    mut form: ResMut<LoginForm>,
+    mut requests: EventWriter<LoginRequest>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if transition.is_active() {
+        return;
+    }
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *button {
+            LoginButton::Field(field) => form.focus = field,
+            LoginButton::Login => form.submit(&mut requests),
+            LoginButton::Quit => {
+                exit.send(AppExit::Success);
+            }
+        }
+    }
+}
+
+/// 登录表单的键盘操作：输入账号密码，Tab 切换输入框，回车提交
+pub fn edit_login_input(
+    transition: Res<SceneTransition>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut form: ResMut<LoginForm>,
+    mut requests: EventWriter<LoginRequest>,
+) {
+    if transition.is_active() || form.status == LoginStatus::Waiting {
+        keyboard_events.clear();
+        return;
+    }
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Tab => {
+                form.focus = match form.focus {
+                    LoginField::Account => LoginField::Password,
+                    LoginField::Password => LoginField::Account,
+                };
+            }
+            Key::Enter => {
+                if form.focus == LoginField::Account && form.password.is_empty() {
+                    form.focus = LoginField::Password;
+                } else {
+                    form.submit(&mut requests);
+                    break;
+                }
+            }
+            Key::Backspace => {
+                match form.focus {
+                    LoginField::Account => form.account.pop(),
+                    LoginField::Password => form.password.pop(),
+                };
+            }
+            Key::Character(text) => {
+                let (field, limit) = match form.focus {
+                    LoginField::Account => (&mut form.account, MAX_ACCOUNT_CHARS),
+                    LoginField::Password => (&mut form.password, MAX_PASSWORD_CHARS),
+                };
+                for c in text.chars().filter(|c| !c.is_control()) {
+                    if field.chars().count() < limit {
+                        field.push(c);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 处理登录结果：失败时显示原因，成功后进入主菜单
+pub fn handle_login_results(
+    mut results: EventReader<LoginResult>,
+    mut form: ResMut<LoginForm>,
+    mut scenes: EventWriter<ChangeScene>,
+) {
+    for result in results.read() {
+        match result {
+            LoginResult::Success { .. } => {
+                form.password.clear();
+                form.status = LoginStatus::Idle;
+                scenes.send(ChangeScene(GameState::MainMenu));
+            }
+            LoginResult::Failure(reason) => {
+                form.password.clear();
+                form.focus = LoginField::Password;
+                form.status = LoginStatus::Failed(reason.clone());
+            }
+        }
+    }
+}
+
+/// 输入或进度变化时重建表单内容，密码以圆点显示
+pub fn sync_login_panel(
+    mut commands: Commands,
+    form: Res<LoginForm>,
+    panels: Query<(Entity, Ref<LoginPanel>)>,
+) {
+    let Ok((panel, marker)) = panels.get_single() else {
+        return;
+    };
+    if !marker.is_added() && !form.is_changed() {
+        return;
+    }
+
+    commands.entity(panel).despawn_descendants();
+    commands.entity(panel).with_children(|panel| {
+        panel.spawn(label("登录", TextRole::Title));
+        for field in [LoginField::Account, LoginField::Password] {
+            let mut text = match field {
+                LoginField::Account => form.account.clone(),
+                LoginField::Password => "•".repeat(form.password.chars().count()),
+            };
+            if field == form.focus {
+                text.push('_');
+            }
+            panel
+                .spawn(Node {
+                    column_gap: Val::Px(8.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|row| {
+                    let button = LoginButton::Field(field);
+                    spawn_text_button(row, button, button.label());
+                    row.spawn((
+                        Node {
+                            width: Val::Px(240.0),
+                            ..default()
+                        },
+                        label(text, TextRole::Body),
+                    ));
+                });
+        }
+        match &form.status {
+            LoginStatus::Idle => {
+                panel.spawn(label("Tab 切换输入框，回车登录", TextRole::Muted));
+            }
+            LoginStatus::Waiting => {
+                panel.spawn(label("正在登录…", TextRole::Muted));
+            }
+            LoginStatus::Failed(reason) => {
+                panel.spawn(label(reason.clone(), TextRole::Body));
+            }
+        }
+        for button in [LoginButton::Login, LoginButton::Quit] {
+            spawn_text_button(panel, button, button.label());
+        }
+    });
+}
@@ -0,0 +1,374 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use chrono::DateTime;
+
+use super::{
+    label, overlay, panel, spawn_text_button, ChangeScene, OptionsMenuState, SceneTransition,
+    TextRole,
+};
+use crate::config::Settings;
+use crate::resources::{Difficulty, GameState};
+use crate::save::{
+    list_slots, LoadGameRequest, NewGameRequest, SaveMetadata, SaveReason, SaveSettings,
+};
+
+/// 种子最多输入的字数
+const MAX_SEED_CHARS: usize = 24;
+
+/// 读档页最多列出的存档数
+const MAX_LISTED_SLOTS: usize = 8;
+
+/// 主菜单的分页
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MainMenuPage {
+    #[default]
+    Root,
+    NewGame,
+    LoadGame,
+}
+
+/// 主菜单
+#[derive(Resource, Debug, Default)]
+pub struct MainMenu {
+    pub page: MainMenuPage,
+    /// 新游戏的种子，留空时随机
+    pub seed: String,
+    pub difficulty: Difficulty,
+    /// 读档页列出的存档，进入读档页时重新读取
+    pub slots: Vec<SaveMetadata>,
+}
+
+/// 主菜单根节点
+#[derive(Component)]
+pub struct MainMenuUi;
+
+/// 主菜单内容面板，分页或输入变化时整块重建
+#[derive(Component)]
+pub struct MainMenuPanel;
+
+/// 主菜单上的按钮
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MainMenuButton {
+    NewGame,
+    LoadGame,
+    Options,
+    Quit,
+    Back,
+    Difficulty(Difficulty),
+    RandomSeed,
+    Start,
+    /// 读档页的第几个存档
+    Slot(usize),
+}
+
+impl MainMenuButton {
+    pub fn label(self) -> &'static str {
+        match self {
+            MainMenuButton::NewGame => "新游戏",
+            MainMenuButton::LoadGame => "读取存档",
+            MainMenuButton::Options => "选项",
+            MainMenuButton::Quit => "退出游戏",
+            MainMenuButton::Back => "返回",
+            MainMenuButton::Difficulty(difficulty) => difficulty.label(),
+            MainMenuButton::RandomSeed => "随机",
+            MainMenuButton::Start => "开始",
+            MainMenuButton::Slot(_) => "读取",
+        }
+    }
+}
+
+/// 由输入的文字得到世界种子
+///
+/// 纯数字直接作为种子，其他文字按 FNV-1a 散列，同一段文字总是得到同一个世界；留空时随机
+pub fn seed_from_text(text: &str) -> u32 {
+    let text = text.trim();
+    if text.is_empty() {
+        return rand::random();
+    }
+    if let Ok(seed) = text.parse::<u32>() {
+        return seed;
+    }
+    text.bytes().fold(0x811c_9dc5, |hash: u32, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// 存档的保存时间，按本地时间显示到分钟
+fn saved_at_label(saved_at: &str) -> String {
+    DateTime::parse_from_rfc3339(saved_at)
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| saved_at.to_string())
+}
+
+/// 进入主菜单：回到首页并生成界面
+pub fn open_main_menu(mut commands: Commands, settings: Res<Settings>, mut menu: ResMut<MainMenu>) {
+    menu.page = MainMenuPage::Root;
+    commands
+        .spawn((
+            MainMenuUi,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(24.0),
+                ..default()
+            },
+            overlay(),
+            GlobalZIndex(10),
+        ))
+        .with_children(|root| {
+            root.spawn(label(settings.game.window.title.clone(), TextRole::Title));
+            root.spawn((
+                MainMenuPanel,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(16.0)),
+                    row_gap: Val::Px(8.0),
+                    min_width: Val::Px(360.0),
+                    ..default()
+                },
+                panel(),
+            ));
+        });
+}
+
+/// 离开主菜单时移除界面
+pub fn close_main_menu(mut commands: Commands, roots: Query<Entity, With<MainMenuUi>>) {
+    for root in roots.iter() {
+        commands.entity(root).despawn_recursive();
+    }
+}
+
+/// 处理主菜单按钮，切换场景期间不响应
+#[allow(clippy::too_many_arguments)]
+pub fn handle_main_menu_buttons(
+    buttons: Query<(&Interaction, &MainMenuButton), Changed<Interaction>>,
+    save_settings: Res<SaveSettings>,
+    transition: Res<SceneTransition>,
+    mut menu: ResMut<MainMenu>,
+    mut options: ResMut<NextState<OptionsMenuState>>,
+    mut scenes: EventWriter<ChangeScene>,
+    mut new_games: EventWriter<NewGameRequest>,
+    mut loads: EventWriter<LoadGameRequest>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if transition.is_active() {
+        return;
+    }
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *button {
+            MainMenuButton::NewGame => menu.page = MainMenuPage::NewGame,
+            MainMenuButton::LoadGame => {
+                menu.slots = list_slots(&save_settings.slots_dir);
+                menu.page = MainMenuPage::LoadGame;
+            }
+            MainMenuButton::Options => options.set(OptionsMenuState::Open),
+            MainMenuButton::Quit => {
+                exit.send(AppExit::Success);
+            }
+            MainMenuButton::Back => menu.page = MainMenuPage::Root,
+            MainMenuButton::Difficulty(difficulty) => menu.difficulty = difficulty,
+            MainMenuButton::RandomSeed => menu.seed = rand::random::<u32>().to_string(),
+            MainMenuButton::Start => start_new_game(&menu, &mut new_games, &mut scenes),
+            MainMenuButton::Slot(index) => {
+                let Some(slot) = menu.slots.get(index) else {
+                    continue;
+                };
+                loads.send(LoadGameRequest {
+                    slot: slot.slot.clone(),
+                });
+                scenes.send(ChangeScene(GameState::Loading));
+            }
+        }
+    }
+}
+
+fn start_new_game(
+    menu: &MainMenu,
+    new_games: &mut EventWriter<NewGameRequest>,
+    scenes: &mut EventWriter<ChangeScene>,
+) {
+    new_games.send(NewGameRequest {
+        seed: seed_from_text(&menu.seed),
+        difficulty: menu.difficulty,
+    });
+    scenes.send(ChangeScene(GameState::Loading));
+}
+
+/// 主菜单的键盘操作：新游戏页输入种子，回车开始，退出键返回上一页
+///
+/// 选项菜单打开时按键交给选项菜单
+pub fn edit_main_menu_input(
+    options: Res<State<OptionsMenuState>>,
+    transition: Res<SceneTransition>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut menu: ResMut<MainMenu>,
+    mut new_games: EventWriter<NewGameRequest>,
+    mut scenes: EventWriter<ChangeScene>,
+) {
+    if *options.get() == OptionsMenuState::Open || transition.is_active() {
+        keyboard_events.clear();
+        return;
+    }
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Escape if menu.page != MainMenuPage::Root => menu.page = MainMenuPage::Root,
+            Key::Enter if menu.page == MainMenuPage::NewGame => {
+                start_new_game(&menu, &mut new_games, &mut scenes);
+                break;
+            }
+            Key::Backspace if menu.page == MainMenuPage::NewGame => {
+                menu.seed.pop();
+            }
+            Key::Character(text) if menu.page == MainMenuPage::NewGame => {
+                for c in text.chars().filter(|c| !c.is_control()) {
+                    if menu.seed.chars().count() < MAX_SEED_CHARS {
+                        menu.seed.push(c);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 分页或输入变化时重建面板内容
+pub fn sync_main_menu_panel(
+    mut commands: Commands,
+    menu: Res<MainMenu>,
+    panels: Query<(Entity, Ref<MainMenuPanel>)>,
+) {
+    let Ok((panel, marker)) = panels.get_single() else {
+        return;
+    };
+    if !marker.is_added() && !menu.is_changed() {
+        return;
+    }
+
+    commands.entity(panel).despawn_descendants();
+    commands
+        .entity(panel)
+        .with_children(|panel| match menu.page {
+            MainMenuPage::Root => {
+                for button in [
+                    MainMenuButton::NewGame,
+                    MainMenuButton::LoadGame,
+                    MainMenuButton::Options,
+                    MainMenuButton::Quit,
+                ] {
+                    spawn_text_button(panel, button, button.label());
+                }
+            }
+            MainMenuPage::NewGame => {
+                panel.spawn(label("新游戏", TextRole::Title));
+                panel
+                    .spawn(Node {
+                        column_gap: Val::Px(8.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn(label("种子", TextRole::Body));
+                        let seed = if menu.seed.is_empty() {
+                            "（留空随机）".to_string()
+                        } else {
+                            format!("{}_", menu.seed)
+                        };
+                        row.spawn((
+                            Node {
+                                width: Val::Px(200.0),
+                                ..default()
+                            },
+                            label(seed, TextRole::Body),
+                        ));
+                        let button = MainMenuButton::RandomSeed;
+                        spawn_text_button(row, button, button.label());
+                    });
+                panel
+                    .spawn(Node {
+                        column_gap: Val::Px(8.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn(label("难度", TextRole::Body));
+                        for difficulty in Difficulty::ALL {
+                            let button = MainMenuButton::Difficulty(difficulty);
+                            let text = if difficulty == menu.difficulty {
+                                format!("【{}】", difficulty.label())
+                            } else {
+                                difficulty.label().to_string()
+                            };
+                            spawn_text_button(row, button, &text);
+                        }
+                    });
+                panel.spawn(label(
+                    "输入数字或任意文字作为种子，同一种子生成同一个世界",
+                    TextRole::Muted,
+                ));
+                for button in [MainMenuButton::Start, MainMenuButton::Back] {
+                    spawn_text_button(panel, button, button.label());
+                }
+            }
+            MainMenuPage::LoadGame => {
+                panel.spawn(label("读取存档", TextRole::Title));
+                if menu.slots.is_empty() {
+                    panel.spawn(label("还没有存档", TextRole::Muted));
+                }
+                for (index, slot) in menu.slots.iter().take(MAX_LISTED_SLOTS).enumerate() {
+                    panel
+                        .spawn(Node {
+                            column_gap: Val::Px(12.0),
+                            align_items: AlignItems::Center,
+                            ..default()
+                        })
+                        .with_children(|row| {
+                            let name = match slot.reason {
+                                SaveReason::Manual => slot.slot.clone(),
+                                SaveReason::CrashRecovery => format!("{}（崩溃恢复）", slot.slot),
+                                SaveReason::RenderFailure => format!("{}（紧急存档）", slot.slot),
+                                _ => format!("{}（自动）", slot.slot),
+                            };
+                            row.spawn((
+                                Node {
+                                    width: Val::Px(160.0),
+                                    ..default()
+                                },
+                                label(name, TextRole::Body),
+                            ));
+                            row.spawn((
+                                Node {
+                                    width: Val::Px(320.0),
+                                    ..default()
+                                },
+                                label(
+                                    format!(
+                                        "{} · {} · 游玩{} · {}",
+                                        slot.date_label,
+                                        slot.difficulty.label(),
+                                        slot.playtime_label(),
+                                        saved_at_label(&slot.saved_at)
+                                    ),
+                                    TextRole::Small,
+                                ),
+                            ));
+                            let button = MainMenuButton::Slot(index);
+                            spawn_text_button(row, button, button.label());
+                        });
+                }
+                let button = MainMenuButton::Back;
+                spawn_text_button(panel, button, button.label());
+            }
+        });
+}
@@ -0,0 +1,432 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy::window::{Ime, PrimaryWindow};
+
+use super::{
+    button, canvas_relative, label, panel, MixedText, TextRole, WorldMapCanvas, WorldMapSettings,
+    WorldMapUi, WorldMapView,
+};
+use crate::logging::{GameLogger, LogLevel};
+use crate::world::poi::{
+    clamp_pin_note, MapPins, PinIcon, PoiSettings, MAP_PIN_SAVE_PATH, MAX_PIN_NOTE_CHARS,
+};
+
+/// 编辑中的标注
+#[derive(Debug, Clone)]
+pub struct PinDraft {
+    /// 已有标注的编号，新放置的标注为空
+    pub id: Option<u32>,
+    /// 世界瓦片坐标
+    pub position: IVec2,
+    pub icon: PinIcon,
+    pub note: String,
+    pub on_compass: bool,
+    /// 输入法正在组字的内容，确认前只用于显示
+    pub preedit: String,
+}
+
+/// 标注编辑器
+#[derive(Resource, Debug, Default)]
+pub struct PinEditor {
+    pub draft: Option<PinDraft>,
+    /// 提示信息，例如标注已满
+    pub message: Option<String>,
+}
+
+impl PinEditor {
+    /// 是否正在编辑，编辑时键盘输入都用于打字
+    pub fn is_editing(&self) -> bool {
+        self.draft.is_some()
+    }
+}
+
+/// 世界地图上的玩家标注
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MapPinMarker {
+    pub id: u32,
+}
+
+/// 标注编辑面板
+#[derive(Component)]
+pub struct PinEditorPanel;
+
+/// 编辑面板中显示草稿内容的文字
+#[derive(Component)]
+pub struct PinEditorText;
+
+/// 编辑面板上的按钮
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinEditorButton {
+    CycleIcon,
+    ToggleCompass,
+    Delete,
+    Confirm,
+    Cancel,
+}
+
+impl PinEditorButton {
+    fn label(self) -> &'static str {
+        match self {
+            PinEditorButton::CycleIcon => "换图标",
+            PinEditorButton::ToggleCompass => "罗盘显示",
+            PinEditorButton::Delete => "删除",
+            PinEditorButton::Confirm => "确定",
+            PinEditorButton::Cancel => "取消",
+        }
+    }
+}
+
+/// 右键点击地图放置新标注
+pub fn place_map_pin(
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    settings: Res<WorldMapSettings>,
+    view: Res<WorldMapView>,
+    pins: Option<Res<MapPins>>,
+    mut editor: ResMut<PinEditor>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    canvases: Query<(&ComputedNode, &GlobalTransform), With<WorldMapCanvas>>,
+) {
+    if editor.is_editing() || !mouse_buttons.just_pressed(MouseButton::Right) {
+        return;
+    }
+    let Some(pins) = pins else {
+        return;
+    };
+    let Some(cursor) = windows.get_single().ok().and_then(Window::cursor_position) else {
+        return;
+    };
+    let Ok((node, transform)) = canvases.get_single() else {
+        return;
+    };
+    let Some(relative) = canvas_relative(cursor, node, transform) else {
+        return;
+    };
+
+    if pins.is_full() {
+        editor.message = Some(format!("标注已达上限 {} 个", pins.limit));
+        return;
+    }
+    editor.message = None;
+    editor.draft = Some(PinDraft {
+        id: None,
+        position: view
+            .tile_at(settings.resolution, relative)
+            .floor()
+            .as_ivec2(),
+        icon: PinIcon::default(),
+        note: String::new(),
+        on_compass: false,
+        preedit: String::new(),
+    });
+}
+
+/// 点击已有标注打开编辑
+pub fn select_map_pin(
+    pins: Option<Res<MapPins>>,
+    mut editor: ResMut<PinEditor>,
+    markers: Query<(&Interaction, &MapPinMarker), Changed<Interaction>>,
+) {
+    if editor.is_editing() {
+        return;
+    }
+    let Some(pins) = pins else {
+        return;
+    };
+    for (interaction, marker) in markers.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let Some(pin) = pins.get(marker.id) {
+            editor.message = None;
+            editor.draft = Some(PinDraft {
+                id: Some(pin.id),
+                position: pin.position,
+                icon: pin.icon,
+                note: pin.note.clone(),
+                on_compass: pin.on_compass,
+                preedit: String::new(),
+            });
+        }
+    }
+}
+
+/// 追加文字，超出字数上限的部分丢弃
+fn push_note(note: &mut String, text: &str) {
+    let room = MAX_PIN_NOTE_CHARS.saturating_sub(note.chars().count());
+    note.extend(text.chars().filter(|c| !c.is_control()).take(room));
+}
+
+/// 键盘与输入法输入
+///
+/// 直接输入的字符和输入法确认的文字都写进备注，组字中的内容只显示不保存。
+/// 回车确定，退出键取消，Tab 切换图标
+pub fn edit_pin_note(
+    mut editor: ResMut<PinEditor>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut ime_events: EventReader<Ime>,
+    mut actions: EventWriter<PinEditorAction>,
+) {
+    // 先只读判断，避免不编辑时也把编辑器标记为已修改
+    if !editor.is_editing() {
+        keyboard_events.clear();
+        ime_events.clear();
+        return;
+    }
+    let Some(draft) = editor.draft.as_mut() else {
+        return;
+    };
+
+    for event in ime_events.read() {
+        match event {
+            Ime::Preedit { value, .. } => draft.preedit = value.clone(),
+            Ime::Commit { value, .. } => {
+                draft.preedit.clear();
+                push_note(&mut draft.note, value);
+            }
+            _ => {}
+        }
+    }
+
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        // 组字过程中的按键交给输入法
+        if !draft.preedit.is_empty() {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Character(text) => push_note(&mut draft.note, text),
+            Key::Space => push_note(&mut draft.note, " "),
+            Key::Backspace => {
+                draft.note.pop();
+            }
+            Key::Tab => {
+                draft.icon = draft.icon.next();
+            }
+            Key::Enter => {
+                actions.send(PinEditorAction(PinEditorButton::Confirm));
+            }
+            Key::Escape => {
+                actions.send(PinEditorAction(PinEditorButton::Cancel));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 编辑操作，来自面板按钮或快捷键
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PinEditorAction(pub PinEditorButton);
+
+/// 面板按钮转为编辑操作
+pub fn handle_pin_editor_buttons(
+    buttons: Query<(&Interaction, &PinEditorButton), Changed<Interaction>>,
+    mut actions: EventWriter<PinEditorAction>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            actions.send(PinEditorAction(*button));
+        }
+    }
+}
+
+/// 执行编辑操作，确定与删除后立即写入存档
+pub fn apply_pin_editor_actions(
+    settings: Option<Res<PoiSettings>>,
+    mut editor: ResMut<PinEditor>,
+    mut pins: Option<ResMut<MapPins>>,
+    mut actions: EventReader<PinEditorAction>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for PinEditorAction(action) in actions.read() {
+        let Some(draft) = editor.draft.as_mut() else {
+            continue;
+        };
+        let Some(pins) = pins.as_mut() else {
+            editor.draft = None;
+            continue;
+        };
+
+        let saved = match action {
+            PinEditorButton::CycleIcon => {
+                draft.icon = draft.icon.next();
+                false
+            }
+            PinEditorButton::ToggleCompass => {
+                draft.on_compass = !draft.on_compass;
+                false
+            }
+            PinEditorButton::Cancel => {
+                editor.draft = None;
+                false
+            }
+            PinEditorButton::Delete => {
+                if let Some(id) = draft.id {
+                    pins.remove(id);
+                }
+                editor.draft = None;
+                true
+            }
+            PinEditorButton::Confirm => {
+                let note = clamp_pin_note(&draft.note);
+                let result = match draft.id {
+                    Some(id) => {
+                        pins.update(id, draft.icon, &note, draft.on_compass);
+                        Ok(id)
+                    }
+                    None => pins.add(draft.position, draft.icon, &note).inspect(|&id| {
+                        pins.update(id, draft.icon, &note, draft.on_compass);
+                    }),
+                };
+                match result {
+                    Ok(_) => {
+                        editor.draft = None;
+                        editor.message = None;
+                    }
+                    Err(message) => editor.message = Some(message),
+                }
+                true
+            }
+        };
+
+        if saved {
+            let path = settings.as_ref().map_or(MAP_PIN_SAVE_PATH, |settings| {
+                settings.pin_save_path.as_str()
+            });
+            if let Err(e) = pins.save(path) {
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(LogLevel::Error, &format!("地图标注存档写入失败: {}", e));
+                }
+            }
+        }
+    }
+}
+
+/// 草稿内容的显示文字
+fn draft_summary(draft: &PinDraft) -> String {
+    let cursor = if draft.preedit.is_empty() { "_" } else { "" };
+    format!(
+        "{} {}  坐标 ({}, {})\n备注：{}{}{}\n罗盘显示：{}",
+        draft.icon.glyph(),
+        draft.icon.name(),
+        draft.position.x,
+        draft.position.y,
+        draft.note,
+        draft.preedit,
+        cursor,
+        if draft.on_compass { "开" } else { "关" },
+    )
+}
+
+/// 按编辑状态生成、刷新或移除编辑面板，编辑时打开输入法
+pub fn sync_pin_editor_panel(
+    mut commands: Commands,
+    editor: Res<PinEditor>,
+    roots: Query<Entity, With<WorldMapUi>>,
+    panels: Query<Entity, With<PinEditorPanel>>,
+    mut texts: Query<&mut MixedText, With<PinEditorText>>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !editor.is_changed() {
+        return;
+    }
+    if let Ok(mut window) = windows.get_single_mut() {
+        if window.ime_enabled != editor.is_editing() {
+            window.ime_enabled = editor.is_editing();
+        }
+    }
+
+    let summary = match (&editor.draft, &editor.message) {
+        (Some(draft), Some(message)) => format!("{}\n{}", draft_summary(draft), message),
+        (Some(draft), None) => draft_summary(draft),
+        (None, Some(message)) => message.clone(),
+        (None, None) => {
+            for panel in panels.iter() {
+                commands.entity(panel).despawn_recursive();
+            }
+            return;
+        }
+    };
+
+    if let Ok(mut text) = texts.get_single_mut() {
+        text.0 = summary.clone();
+        // 只剩提示信息时面板上的按钮也要去掉，整块重建
+        if editor.draft.is_some() {
+            return;
+        }
+    }
+    for panel in panels.iter() {
+        commands.entity(panel).despawn_recursive();
+    }
+    let Ok(root) = roots.get_single() else {
+        return;
+    };
+
+    let buttons: &[PinEditorButton] = match &editor.draft {
+        Some(draft) if draft.id.is_some() => &[
+            PinEditorButton::CycleIcon,
+            PinEditorButton::ToggleCompass,
+            PinEditorButton::Delete,
+            PinEditorButton::Confirm,
+            PinEditorButton::Cancel,
+        ],
+        Some(_) => &[
+            PinEditorButton::CycleIcon,
+            PinEditorButton::ToggleCompass,
+            PinEditorButton::Confirm,
+            PinEditorButton::Cancel,
+        ],
+        None => &[],
+    };
+
+    commands.entity(root).with_children(|root| {
+        root.spawn((
+            PinEditorPanel,
+            Node {
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(10.0)),
+                row_gap: Val::Px(6.0),
+                min_width: Val::Px(320.0),
+                ..default()
+            },
+            panel(),
+        ))
+        .with_children(|panel| {
+            panel.spawn((PinEditorText, label(summary, TextRole::Body)));
+            panel
+                .spawn(Node {
+                    column_gap: Val::Px(8.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for action in buttons {
+                        row.spawn((
+                            *action,
+                            button(),
+                            Node {
+                                padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                                ..default()
+                            },
+                        ))
+                        .with_children(|button_node| {
+                            button_node.spawn(label(action.label(), TextRole::Body));
+                        });
+                    }
+                });
+        });
+    });
+}
+
+/// 关闭地图时放弃未确定的编辑并关闭输入法
+pub fn discard_pin_draft(
+    mut editor: ResMut<PinEditor>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    editor.draft = None;
+    editor.message = None;
+    if let Ok(mut window) = windows.get_single_mut() {
+        window.ime_enabled = false;
+    }
+}
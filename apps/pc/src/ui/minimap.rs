@@ -0,0 +1,287 @@
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use std::collections::{HashMap, HashSet};
+
+use super::{frame, PinEditor};
+use crate::events::input::GameAction;
+use crate::resources::InputState;
+use crate::world::chunk::{Chunk, ChunkCoord, ChunkData, CHUNK_SIZE, TILE_PIXELS};
+use crate::world::entity::{Npc, NpcType, Player};
+use crate::world::map::{height_to_color, TileType};
+use crate::world::poi::PoiRegistry;
+
+/// 未加载区域的颜色
+const UNEXPLORED: [u8; 4] = [12, 12, 16, 255];
+
+/// 小地图配置
+#[derive(Resource, Debug, Clone)]
+pub struct MinimapSettings {
+    /// 贴图边长（像素），每个像素对应一个瓦片
+    pub resolution: u32,
+    /// 屏幕上的显示边长
+    pub display_size: f32,
+    /// 与屏幕右上角的间距
+    pub margin: f32,
+    /// 标记刷新间隔（秒），玩家跨过瓦片或区块变化时立即刷新
+    pub refresh_interval: f32,
+    pub player_color: [u8; 4],
+    pub friendly_color: [u8; 4],
+    pub hostile_color: [u8; 4],
+    pub poi_color: [u8; 4],
+}
+
+impl Default for MinimapSettings {
+    fn default() -> Self {
+        Self {
+            resolution: 160,
+            display_size: 192.0,
+            margin: 12.0,
+            refresh_interval: 0.25,
+            player_color: [255, 255, 255, 255],
+            friendly_color: [250, 210, 60, 255],
+            hostile_color: [230, 50, 40, 255],
+            poi_color: [120, 220, 255, 255],
+        }
+    }
+}
+
+/// 小地图状态
+///
+/// 区块加载时把瓦片颜色算好缓存起来，之后只按玩家位置把缓存拷进贴图，
+/// 不再重复解析区块数据
+#[derive(Resource, Debug, Default)]
+pub struct MinimapState {
+    /// 小地图贴图
+    pub image: Handle<Image>,
+    /// 是否显示
+    pub visible: bool,
+    /// 已加载区块的瓦片颜色，按行从下到上排列
+    chunks: HashMap<ChunkCoord, Vec<[u8; 4]>>,
+    /// 上次绘制时玩家所在瓦片
+    center: Option<IVec2>,
+    /// 区块有变化，需要重绘
+    dirty: bool,
+    /// 距上次刷新的时间
+    since_refresh: f32,
+}
+
+/// 小地图界面根节点
+#[derive(Component)]
+pub struct MinimapUi;
+
+/// 瓦片在小地图上的颜色
+///
+/// 自然地形按高度着色，道路、建筑与水面用瓦片本身的颜色，
+/// 否则村镇与河流会淹没在高度色带里
+pub fn minimap_color(tile: Option<TileType>, height: f32) -> [u8; 4] {
+    let (r, g, b) = match tile {
+        Some(TileType::Water) => (30, 110, 200),
+        Some(TileType::Path) => (170, 120, 70),
+        Some(TileType::Floor) => (181, 140, 92),
+        Some(TileType::Wall) => (90, 90, 90),
+        Some(TileType::Door) => (120, 72, 40),
+        Some(TileType::Bamboo) => (70, 150, 60),
+        _ => height_to_color(height),
+    };
+    [r, g, b, 255]
+}
+
+/// 计算区块所有瓦片的颜色
+fn chunk_colors(data: &ChunkData) -> Vec<[u8; 4]> {
+    let mut colors = Vec::with_capacity(CHUNK_SIZE * CHUNK_SIZE);
+    for y in 0..CHUNK_SIZE {
+        for x in 0..CHUNK_SIZE {
+            let tile = data.get_tile(x, y).and_then(TileType::from_u8);
+            colors.push(minimap_color(tile, data.get_height(x, y)));
+        }
+    }
+    colors
+}
+
+/// 创建小地图贴图与界面
+pub fn setup_minimap(
+    mut commands: Commands,
+    settings: Res<MinimapSettings>,
+    mut images: ResMut<Assets<Image>>,
+    mut state: ResMut<MinimapState>,
+) {
+    let size = settings.resolution.max(8);
+    let image = Image::new_fill(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &UNEXPLORED,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    state.image = images.add(image);
+    state.visible = true;
+    state.dirty = true;
+
+    commands.spawn((
+        MinimapUi,
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(settings.margin),
+            top: Val::Px(settings.margin),
+            width: Val::Px(settings.display_size),
+            height: Val::Px(settings.display_size),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        frame(),
+        ImageNode::new(state.image.clone()),
+    ));
+}
+
+/// 区块加载或数据变化时更新颜色缓存，区块卸载时移除
+pub fn cache_minimap_chunks(
+    mut state: ResMut<MinimapState>,
+    changed: Query<&Chunk, Changed<Chunk>>,
+    chunks: Query<&Chunk>,
+    mut removed: RemovedComponents<Chunk>,
+) {
+    for chunk in changed.iter() {
+        if let Some(data) = &chunk.data {
+            state.chunks.insert(chunk.coord, chunk_colors(data));
+            state.dirty = true;
+        }
+    }
+
+    if removed.read().count() > 0 {
+        let loaded: HashSet<ChunkCoord> = chunks.iter().map(|chunk| chunk.coord).collect();
+        state.chunks.retain(|coord, _| loaded.contains(coord));
+        state.dirty = true;
+    }
+}
+
+/// 切换小地图显示
+///
+/// 在地图上编辑标注备注时不响应
+pub fn toggle_minimap(
+    input_state: Res<InputState>,
+    editor: Res<PinEditor>,
+    mut state: ResMut<MinimapState>,
+    mut nodes: Query<&mut Node, With<MinimapUi>>,
+) {
+    if editor.is_editing() || !input_state.is_action_just_pressed(GameAction::ToggleMinimap) {
+        return;
+    }
+    state.visible = !state.visible;
+    state.dirty = true;
+    for mut node in nodes.iter_mut() {
+        node.display = if state.visible {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+/// 以玩家为中心重绘小地图
+///
+/// # 设计思路
+/// 1. 玩家跨过瓦片或区块缓存变化时立即重绘，否则按固定间隔刷新移动中的标记
+/// 2. 底图直接从区块颜色缓存拷贝，未加载的区域保持暗色
+/// 3. 依次叠加已发现的兴趣点、NPC 与玩家，玩家标记始终在最上层
+pub fn redraw_minimap(
+    time: Res<Time>,
+    settings: Res<MinimapSettings>,
+    mut state: ResMut<MinimapState>,
+    mut images: ResMut<Assets<Image>>,
+    poi_registry: Option<Res<PoiRegistry>>,
+    players: Query<&Transform, With<Player>>,
+    npcs: Query<(&Transform, &Npc)>,
+) {
+    if !state.visible {
+        return;
+    }
+    let Ok(player) = players.get_single() else {
+        return;
+    };
+    let center = (player.translation.truncate() / TILE_PIXELS)
+        .floor()
+        .as_ivec2();
+
+    state.since_refresh += time.delta_secs();
+    let moved = state.center != Some(center);
+    if !moved && !state.dirty && state.since_refresh < settings.refresh_interval {
+        return;
+    }
+    state.center = Some(center);
+    state.dirty = false;
+    state.since_refresh = 0.0;
+
+    let Some(image) = images.get_mut(&state.image) else {
+        return;
+    };
+    let size = image.width() as i32;
+    let half = size / 2;
+    // 贴图左下角对应的世界瓦片
+    let origin = center - IVec2::splat(half);
+    let chunk_size = CHUNK_SIZE as i32;
+
+    let put = |data: &mut Vec<u8>, tile: IVec2, color: [u8; 4]| {
+        let local = tile - origin;
+        if local.x < 0 || local.y < 0 || local.x >= size || local.y >= size {
+            return;
+        }
+        // 贴图第一行在最上方，世界坐标 y 轴向上
+        let index = (((size - 1 - local.y) * size + local.x) * 4) as usize;
+        data[index..index + 4].copy_from_slice(&color);
+    };
+
+    for y in 0..size {
+        for x in 0..size {
+            let tile = origin + IVec2::new(x, y);
+            let coord = ChunkCoord {
+                x: tile.x.div_euclid(chunk_size),
+                y: tile.y.div_euclid(chunk_size),
+            };
+            let color = state.chunks.get(&coord).map_or(UNEXPLORED, |colors| {
+                let local_x = tile.x.rem_euclid(chunk_size) as usize;
+                let local_y = tile.y.rem_euclid(chunk_size) as usize;
+                colors[local_y * CHUNK_SIZE + local_x]
+            });
+            put(&mut image.data, tile, color);
+        }
+    }
+
+    if let Some(registry) = poi_registry {
+        let max = origin + IVec2::splat(size - 1);
+        for poi in registry.in_rect(origin, max) {
+            if !poi.is_discovered() {
+                continue;
+            }
+            // 菱形标记
+            for offset in [IVec2::ZERO, IVec2::X, -IVec2::X, IVec2::Y, -IVec2::Y] {
+                put(&mut image.data, poi.position + offset, settings.poi_color);
+            }
+        }
+    }
+
+    for (transform, npc) in npcs.iter() {
+        let tile = (transform.translation.truncate() / TILE_PIXELS)
+            .floor()
+            .as_ivec2();
+        let color = match npc.npc_type {
+            NpcType::Enemy | NpcType::Boss => settings.hostile_color,
+            _ => settings.friendly_color,
+        };
+        put(&mut image.data, tile, color);
+    }
+
+    for y in -1..=1 {
+        for x in -1..=1 {
+            put(
+                &mut image.data,
+                center + IVec2::new(x, y),
+                settings.player_color,
+            );
+        }
+    }
+}
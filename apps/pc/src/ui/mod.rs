@@ -0,0 +1,60 @@
+/// 界面模块
+///
+/// 游戏世界中的界面元素，包括角色头顶的对话气泡、屏幕角落的小地图、顶部罗盘、状态栏、全屏世界地图、选项菜单、登录界面、主菜单、暂停菜单与战斗飘字
+///
+/// # 模块组成
+/// 1. wrap：按显示宽度折行，兼容中日韩文字与标点禁则
+/// 2. bubble：世界空间中的对话气泡及其实体池
+/// 3. minimap：由已加载区块生成的小地图
+/// 4. world_map：带战争迷雾的全屏世界地图
+/// 5. map_pins：世界地图上的玩家标注与编辑面板
+/// 6. compass：屏幕顶部的罗盘条，显示方位、任务目标与附近地点
+/// 7. theme：从数据加载的界面主题，可在设置中切换
+/// 8. widgets：按主题取样式的面板、文字与按钮
+/// 9. fonts：异步加载的字体回退链与混排文字
+/// 10. status_bar：屏幕左下角的状态效果图标
+/// 11. options：选项菜单，修改运行时设置并写入用户配置
+/// 12. rebind：选项菜单的改键页，等待按键、提示冲突
+/// 13. login：登录界面，输入账号密码并等待服务器回应
+/// 14. main_menu：主菜单，新游戏、读档、选项与退出
+/// 15. pause_menu：暂停菜单，继续、选项、存档、离开联机与返回主菜单
+/// 16. transition：场景切换的淡入淡出
+/// 17. floating_text：伤害数字、拾取物品等飘字及其实体池
+/// 18. systems：界面插件
+mod bubble;
+mod compass;
+mod floating_text;
+mod fonts;
+mod login;
+mod main_menu;
+mod map_pins;
+mod minimap;
+mod options;
+mod pause_menu;
+mod rebind;
+mod status_bar;
+mod systems;
+mod theme;
+mod transition;
+mod widgets;
+mod world_map;
+mod wrap;
+
+pub use bubble::*;
+pub use compass::*;
+pub use floating_text::*;
+pub use fonts::*;
+pub use login::*;
+pub use main_menu::*;
+pub use map_pins::*;
+pub use minimap::*;
+pub use options::*;
+pub use pause_menu::*;
+pub use rebind::*;
+pub use status_bar::*;
+pub use systems::GameUiPlugin;
+pub use theme::*;
+pub use transition::*;
+pub use widgets::*;
+pub use world_map::*;
+pub use wrap::*;
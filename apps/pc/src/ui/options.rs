@@ -0,0 +1,583 @@
+use bevy::prelude::*;
+use bevy::window::{PresentMode, PrimaryWindow, WindowMode};
+
+use super::{
+    button, label, overlay, panel, spawn_rebind_page, Rebinding, TextRole, UiSound, UiThemeLibrary,
+    UiThemeSettings,
+};
+use crate::audio::AudioVolumes;
+use crate::config::{Settings, GRAPHICS_QUALITY_LEVELS};
+use crate::events::input::{GameAction, KeyBindings};
+use crate::logging::{GameLogger, LogLevel};
+use crate::render::lighting::LightingSettings;
+use crate::resources::InputState;
+use crate::world::chunk::ChunkManager;
+use crate::world::entity::{Character, Player};
+
+/// 可选的窗口分辨率
+const RESOLUTIONS: [(u32, u32); 5] = [
+    (1280, 720),
+    (1600, 900),
+    (1920, 1080),
+    (2560, 1440),
+    (3840, 2160),
+];
+
+/// 每次调节音量的步长
+const VOLUME_STEP: f32 = 0.1;
+
+/// 配置中的视距（像素）按这个值折算成区块加载圈数，中档的 800 对应 5 圈
+const RENDER_DISTANCE_PER_RING: u32 = 160;
+
+/// 选项菜单状态
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OptionsMenuState {
+    #[default]
+    Closed,
+    Open,
+}
+
+/// 选项菜单的分页
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OptionsPage {
+    #[default]
+    Display,
+    Audio,
+    Graphics,
+    Controls,
+}
+
+impl OptionsPage {
+    pub const ALL: [OptionsPage; 4] = [
+        OptionsPage::Display,
+        OptionsPage::Audio,
+        OptionsPage::Graphics,
+        OptionsPage::Controls,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OptionsPage::Display => "显示",
+            OptionsPage::Audio => "声音",
+            OptionsPage::Graphics => "画质",
+            OptionsPage::Controls => "按键",
+        }
+    }
+}
+
+/// 选项菜单
+#[derive(Resource, Debug, Default)]
+pub struct OptionsMenu {
+    pub page: OptionsPage,
+    /// 有未写入用户配置的修改
+    pub dirty: bool,
+    /// 打开菜单前玩家能否移动，关闭时恢复
+    player_could_move: Option<bool>,
+}
+
+/// 选项菜单根节点
+#[derive(Component)]
+pub struct OptionsMenuUi;
+
+/// 选项内容面板，设置变化时整块重建
+#[derive(Component)]
+pub struct OptionsPanel;
+
+/// 选项菜单上的按钮
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionsButton {
+    Page(OptionsPage),
+    ToggleFullscreen,
+    CycleResolution,
+    ToggleVsync,
+    CycleTheme,
+    VolumeDown,
+    VolumeUp,
+    MusicVolumeDown,
+    MusicVolumeUp,
+    SfxVolumeDown,
+    SfxVolumeUp,
+    CycleQuality,
+    ToggleTileTextures,
+    ResetBindings,
+    Close,
+}
+
+impl OptionsButton {
+    pub fn label(self) -> &'static str {
+        match self {
+            OptionsButton::Page(page) => page.label(),
+            OptionsButton::ToggleFullscreen => "切换",
+            OptionsButton::CycleResolution => "切换",
+            OptionsButton::ToggleVsync => "切换",
+            OptionsButton::CycleTheme => "切换",
+            OptionsButton::VolumeDown
+            | OptionsButton::MusicVolumeDown
+            | OptionsButton::SfxVolumeDown => "－",
+            OptionsButton::VolumeUp | OptionsButton::MusicVolumeUp | OptionsButton::SfxVolumeUp => {
+                "＋"
+            }
+            OptionsButton::CycleQuality => "切换",
+            OptionsButton::ToggleTileTextures => "切换",
+            OptionsButton::ResetBindings => "恢复默认按键",
+            OptionsButton::Close => "关闭",
+        }
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "开"
+    } else {
+        "关"
+    }
+}
+
+/// 设置中的主题编号，为空时取主题库的默认主题
+fn theme_id<'a>(theme: &'a str, themes: &'a UiThemeLibrary) -> &'a str {
+    if theme.is_empty() {
+        &themes.default
+    } else {
+        theme
+    }
+}
+
+fn quality_label(quality: &str) -> &'static str {
+    match quality {
+        "low" => "低",
+        "high" => "高",
+        _ => "中",
+    }
+}
+
+/// 按选项键开关菜单，打开时退出键也可关闭；改键时这两个键都交给改键流程
+pub fn toggle_options_menu(
+    input_state: Res<InputState>,
+    rebinding: Res<Rebinding>,
+    state: Res<State<OptionsMenuState>>,
+    mut next_state: ResMut<NextState<OptionsMenuState>>,
+) {
+    if rebinding.is_active() {
+        return;
+    }
+    let open = *state.get() == OptionsMenuState::Open;
+    if input_state.is_action_just_pressed(GameAction::OpenOptions) {
+        next_state.set(if open {
+            OptionsMenuState::Closed
+        } else {
+            OptionsMenuState::Open
+        });
+    } else if open && input_state.is_action_just_pressed(GameAction::ExitGame) {
+        next_state.set(OptionsMenuState::Closed);
+    }
+}
+
+/// 打开选项菜单：生成遮罩与面板，并让玩家停下
+pub fn open_options_menu(
+    mut commands: Commands,
+    mut menu: ResMut<OptionsMenu>,
+    mut players: Query<&mut Character, With<Player>>,
+    mut sounds: EventWriter<UiSound>,
+) {
+    sounds.send(UiSound::Open);
+    menu.page = OptionsPage::Display;
+    if let Ok(mut character) = players.get_single_mut() {
+        menu.player_could_move = Some(character.can_move);
+        character.can_move = false;
+    }
+
+    commands
+        .spawn((
+            OptionsMenuUi,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            overlay(),
+            GlobalZIndex(20),
+        ))
+        .with_children(|root| {
+            root.spawn((
+                OptionsPanel,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(16.0)),
+                    row_gap: Val::Px(8.0),
+                    min_width: Val::Px(420.0),
+                    ..default()
+                },
+                panel(),
+            ));
+        });
+}
+
+/// 关闭选项菜单，有修改时写入用户配置
+#[allow(clippy::too_many_arguments)]
+pub fn close_options_menu(
+    mut commands: Commands,
+    mut settings: ResMut<Settings>,
+    mut menu: ResMut<OptionsMenu>,
+    mut rebinding: ResMut<Rebinding>,
+    roots: Query<Entity, With<OptionsMenuUi>>,
+    mut players: Query<&mut Character, With<Player>>,
+    mut sounds: EventWriter<UiSound>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    sounds.send(UiSound::Close);
+    rebinding.cancel();
+    for root in roots.iter() {
+        commands.entity(root).despawn_recursive();
+    }
+    if let (Some(could_move), Ok(mut character)) =
+        (menu.player_could_move.take(), players.get_single_mut())
+    {
+        character.can_move = could_move && character.health > 0.0;
+    }
+
+    if !menu.dirty {
+        return;
+    }
+    menu.dirty = false;
+    // 保存只更新比较的基准，设置本身没变，不必让各系统重新套用
+    if let Err(e) = settings.bypass_change_detection().save() {
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Error, &format!("设置保存失败: {}", e));
+        }
+    }
+}
+
+/// 按步长调节音量，结果限制在 0.0-1.0
+fn step_volume(volume: &mut f32, step: f32) {
+    // 按步长取整，避免反复加减后出现 0.30000001 这样的值
+    *volume = (((*volume + step) / VOLUME_STEP).round() * VOLUME_STEP).clamp(0.0, 1.0);
+}
+
+/// 处理选项按钮
+pub fn handle_options_buttons(
+    buttons: Query<(&Interaction, &OptionsButton), Changed<Interaction>>,
+    mut settings: ResMut<Settings>,
+    mut key_bindings: ResMut<KeyBindings>,
+    mut rebinding: ResMut<Rebinding>,
+    mut menu: ResMut<OptionsMenu>,
+    themes: Res<UiThemeLibrary>,
+    mut next_state: ResMut<NextState<OptionsMenuState>>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let game = &mut settings.game;
+        match *button {
+            OptionsButton::Page(page) => {
+                menu.page = page;
+                continue;
+            }
+            OptionsButton::Close => {
+                next_state.set(OptionsMenuState::Closed);
+                continue;
+            }
+            OptionsButton::ToggleFullscreen => {
+                game.window.fullscreen = !game.window.fullscreen;
+            }
+            OptionsButton::CycleResolution => {
+                let current = (game.window.width, game.window.height);
+                let next = RESOLUTIONS
+                    .iter()
+                    .position(|resolution| *resolution == current)
+                    .map_or(0, |index| (index + 1) % RESOLUTIONS.len());
+                (game.window.width, game.window.height) = RESOLUTIONS[next];
+            }
+            OptionsButton::ToggleVsync => {
+                game.window.vsync = !game.window.vsync;
+            }
+            OptionsButton::CycleTheme => {
+                let list = themes.list();
+                if list.is_empty() {
+                    continue;
+                }
+                let current = theme_id(&game.interface.theme, &themes);
+                let next = list
+                    .iter()
+                    .position(|(id, _)| *id == current)
+                    .map_or(0, |index| (index + 1) % list.len());
+                game.interface.theme = list[next].0.to_string();
+            }
+            OptionsButton::VolumeDown => step_volume(&mut game.audio.master_volume, -VOLUME_STEP),
+            OptionsButton::VolumeUp => step_volume(&mut game.audio.master_volume, VOLUME_STEP),
+            OptionsButton::MusicVolumeDown => {
+                step_volume(&mut game.audio.music_volume, -VOLUME_STEP)
+            }
+            OptionsButton::MusicVolumeUp => step_volume(&mut game.audio.music_volume, VOLUME_STEP),
+            OptionsButton::SfxVolumeDown => step_volume(&mut game.audio.sfx_volume, -VOLUME_STEP),
+            OptionsButton::SfxVolumeUp => step_volume(&mut game.audio.sfx_volume, VOLUME_STEP),
+            OptionsButton::CycleQuality => {
+                let next = GRAPHICS_QUALITY_LEVELS
+                    .iter()
+                    .position(|level| *level == game.graphics.shadow_quality)
+                    .map_or(1, |index| (index + 1) % GRAPHICS_QUALITY_LEVELS.len());
+                game.graphics.apply_quality(GRAPHICS_QUALITY_LEVELS[next]);
+            }
+            OptionsButton::ToggleTileTextures => {
+                game.graphics.tile_textures = !game.graphics.tile_textures;
+            }
+            OptionsButton::ResetBindings => {
+                rebinding.cancel();
+                *key_bindings = KeyBindings::default();
+                game.controls.bindings.clear();
+            }
+        }
+        menu.dirty = true;
+    }
+}
+
+/// 一行选项：名称、当前值与调节按钮
+fn spawn_option_row(
+    parent: &mut ChildBuilder,
+    name: &str,
+    value: String,
+    buttons: &[OptionsButton],
+) {
+    parent
+        .spawn(Node {
+            column_gap: Val::Px(8.0),
+            align_items: AlignItems::Center,
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn((
+                Node {
+                    width: Val::Px(120.0),
+                    ..default()
+                },
+                label(name, TextRole::Body),
+            ));
+            row.spawn((
+                Node {
+                    width: Val::Px(140.0),
+                    ..default()
+                },
+                label(value, TextRole::Body),
+            ));
+            for action in buttons {
+                spawn_text_button(row, *action, action.label());
+            }
+        });
+}
+
+/// 带文字的按钮，`marker` 用来区分按钮
+pub fn spawn_text_button(parent: &mut ChildBuilder, marker: impl Component, text: &str) {
+    parent
+        .spawn((
+            marker,
+            button(),
+            Node {
+                padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                ..default()
+            },
+        ))
+        .with_children(|button_node| {
+            button_node.spawn(label(text, TextRole::Body));
+        });
+}
+
+/// 设置或分页变化时重建面板内容
+pub fn sync_options_panel(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    key_bindings: Res<KeyBindings>,
+    rebinding: Res<Rebinding>,
+    menu: Res<OptionsMenu>,
+    themes: Res<UiThemeLibrary>,
+    panels: Query<(Entity, Ref<OptionsPanel>)>,
+) {
+    let Ok((panel, marker)) = panels.get_single() else {
+        return;
+    };
+    if !marker.is_added()
+        && !settings.is_changed()
+        && !menu.is_changed()
+        && !key_bindings.is_changed()
+        && !rebinding.is_changed()
+    {
+        return;
+    }
+
+    let game = &settings.game;
+    commands.entity(panel).despawn_descendants();
+    commands.entity(panel).with_children(|panel| {
+        panel.spawn(label("选项", TextRole::Title));
+        panel
+            .spawn(Node {
+                column_gap: Val::Px(8.0),
+                ..default()
+            })
+            .with_children(|tabs| {
+                for page in OptionsPage::ALL {
+                    let button = OptionsButton::Page(page);
+                    spawn_text_button(tabs, button, button.label());
+                }
+            });
+
+        match menu.page {
+            OptionsPage::Display => {
+                spawn_option_row(
+                    panel,
+                    "全屏",
+                    on_off(game.window.fullscreen).to_string(),
+                    &[OptionsButton::ToggleFullscreen],
+                );
+                spawn_option_row(
+                    panel,
+                    "分辨率",
+                    format!("{}×{}", game.window.width, game.window.height),
+                    &[OptionsButton::CycleResolution],
+                );
+                spawn_option_row(
+                    panel,
+                    "垂直同步",
+                    on_off(game.window.vsync).to_string(),
+                    &[OptionsButton::ToggleVsync],
+                );
+                let theme = theme_id(&game.interface.theme, &themes);
+                spawn_option_row(
+                    panel,
+                    "界面主题",
+                    themes
+                        .get(theme)
+                        .map_or(theme, |preset| preset.name.as_str())
+                        .to_string(),
+                    &[OptionsButton::CycleTheme],
+                );
+            }
+            OptionsPage::Audio => {
+                spawn_option_row(
+                    panel,
+                    "总音量",
+                    format!("{:.0}%", game.audio.master_volume * 100.0),
+                    &[OptionsButton::VolumeDown, OptionsButton::VolumeUp],
+                );
+                spawn_option_row(
+                    panel,
+                    "音乐",
+                    format!("{:.0}%", game.audio.music_volume * 100.0),
+                    &[OptionsButton::MusicVolumeDown, OptionsButton::MusicVolumeUp],
+                );
+                spawn_option_row(
+                    panel,
+                    "音效",
+                    format!("{:.0}%", game.audio.sfx_volume * 100.0),
+                    &[OptionsButton::SfxVolumeDown, OptionsButton::SfxVolumeUp],
+                );
+            }
+            OptionsPage::Graphics => {
+                spawn_option_row(
+                    panel,
+                    "画质",
+                    quality_label(&game.graphics.shadow_quality).to_string(),
+                    &[OptionsButton::CycleQuality],
+                );
+                spawn_option_row(
+                    panel,
+                    "视距",
+                    game.graphics.render_distance.to_string(),
+                    &[],
+                );
+                spawn_option_row(
+                    panel,
+                    "地形贴图",
+                    on_off(game.graphics.tile_textures).to_string(),
+                    &[OptionsButton::ToggleTileTextures],
+                );
+            }
+            OptionsPage::Controls => {
+                spawn_rebind_page(panel, &key_bindings, &rebinding);
+                let button = OptionsButton::ResetBindings;
+                spawn_text_button(panel, button, button.label());
+            }
+        }
+
+        let button = OptionsButton::Close;
+        spawn_text_button(panel, button, button.label());
+    });
+}
+
+/// 设置变化时套用到窗口、音量、区块加载、光照与界面主题，选项菜单与配置热重载都经由这里生效
+///
+/// 启动后的第一帧同样会套用一次，保存过的音量与画质随即生效
+pub fn apply_settings(
+    settings: Res<Settings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut volume: ResMut<GlobalVolume>,
+    mut volumes: ResMut<AudioVolumes>,
+    chunk_manager: Option<ResMut<ChunkManager>>,
+    lighting: Option<ResMut<LightingSettings>>,
+    ui_theme: Option<ResMut<UiThemeSettings>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    let game = &settings.game;
+
+    if let Ok(mut window) = windows.get_single_mut() {
+        if window.title != game.window.title {
+            window.title = game.window.title.clone();
+        }
+        let mode = if game.window.fullscreen {
+            WindowMode::BorderlessFullscreen(MonitorSelection::Primary)
+        } else {
+            WindowMode::Windowed
+        };
+        if window.mode != mode {
+            window.mode = mode;
+        }
+        let present_mode = if game.window.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        };
+        if window.present_mode != present_mode {
+            window.present_mode = present_mode;
+        }
+        let (width, height) = (game.window.width as f32, game.window.height as f32);
+        if window.resolution.width() != width || window.resolution.height() != height {
+            window.resolution.set(width, height);
+        }
+    }
+
+    *volume = GlobalVolume::new(game.audio.master_volume.clamp(0.0, 1.0));
+    *volumes = AudioVolumes {
+        music: game.audio.music_volume.clamp(0.0, 1.0),
+        sfx: game.audio.sfx_volume.clamp(0.0, 1.0),
+    };
+
+    if let Some(mut lighting) = lighting {
+        let shadows = game.graphics.shadow_quality != "low";
+        if lighting.enabled != game.graphics.dynamic_lighting || lighting.shadows != shadows {
+            lighting.enabled = game.graphics.dynamic_lighting;
+            lighting.shadows = shadows;
+        }
+    }
+
+    // 主题变化后打开着的界面随之换肤
+    if let Some(mut ui_theme) = ui_theme {
+        if ui_theme.theme != game.interface.theme {
+            ui_theme.theme = game.interface.theme.clone();
+        }
+    }
+
+    if let Some(mut chunk_manager) = chunk_manager {
+        chunk_manager.view_distance =
+            (game.graphics.render_distance / RENDER_DISTANCE_PER_RING).max(2) as i32;
+        let render_settings = chunk_manager.render_settings_mut();
+        render_settings.shadow_strength = match game.graphics.shadow_quality.as_str() {
+            "low" => 0.0,
+            "high" => 0.45,
+            _ => 0.3,
+        };
+        render_settings.tile_textures = game.graphics.tile_textures;
+    }
+}
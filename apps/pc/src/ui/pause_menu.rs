@@ -0,0 +1,170 @@
+use bevy::prelude::*;
+
+use super::{
+    label, overlay, panel, spawn_text_button, ChangeScene, MixedText, OptionsMenuState,
+    SceneTransition, TextRole, WorldMapState,
+};
+use crate::coop::{CoopCommand, CoopSession};
+use crate::events::input::GameAction;
+use crate::resources::{GameState, InputState};
+use crate::save::SaveGameRequest;
+
+/// 暂停菜单手动存档使用的存档槽
+pub const PAUSE_SAVE_SLOT: &str = "manual";
+
+/// 暂停菜单根节点
+#[derive(Component)]
+pub struct PauseMenuUi;
+
+/// 存档后的提示文字
+#[derive(Component)]
+pub struct PauseMenuMessage;
+
+/// 暂停菜单上的按钮
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseMenuButton {
+    Resume,
+    Options,
+    Save,
+    /// 离开联机会话，只在联机时显示
+    LeaveSession,
+    QuitToMenu,
+}
+
+impl PauseMenuButton {
+    pub const ALL: [PauseMenuButton; 5] = [
+        PauseMenuButton::Resume,
+        PauseMenuButton::Options,
+        PauseMenuButton::Save,
+        PauseMenuButton::LeaveSession,
+        PauseMenuButton::QuitToMenu,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PauseMenuButton::Resume => "继续游戏",
+            PauseMenuButton::Options => "选项",
+            PauseMenuButton::Save => "保存进度",
+            PauseMenuButton::LeaveSession => "离开联机",
+            PauseMenuButton::QuitToMenu => "返回主菜单",
+        }
+    }
+}
+
+/// 退出键暂停与继续
+///
+/// 选项菜单或世界地图打开时退出键先关掉它们，场景切换期间不响应
+pub fn toggle_pause(
+    input_state: Res<InputState>,
+    state: Res<State<GameState>>,
+    options: Res<State<OptionsMenuState>>,
+    world_map: Res<State<WorldMapState>>,
+    transition: Res<SceneTransition>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !input_state.is_action_just_pressed(GameAction::ExitGame)
+        || *options.get() == OptionsMenuState::Open
+        || *world_map.get() == WorldMapState::Open
+        || transition.is_active()
+    {
+        return;
+    }
+    match state.get() {
+        GameState::InGame => next_state.set(GameState::Paused),
+        GameState::Paused => next_state.set(GameState::InGame),
+        _ => {}
+    }
+}
+
+/// 进入暂停时生成菜单，联机时多一个离开联机的按钮
+pub fn open_pause_menu(mut commands: Commands, session: Option<Res<CoopSession>>) {
+    let in_session = session.is_some_and(|session| session.is_active());
+    commands
+        .spawn((
+            PauseMenuUi,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            overlay(),
+            GlobalZIndex(10),
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(16.0)),
+                    row_gap: Val::Px(8.0),
+                    min_width: Val::Px(240.0),
+                    ..default()
+                },
+                panel(),
+            ))
+            .with_children(|panel| {
+                panel.spawn(label("暂停", TextRole::Title));
+                for button in PauseMenuButton::ALL {
+                    if button == PauseMenuButton::LeaveSession && !in_session {
+                        continue;
+                    }
+                    spawn_text_button(panel, button, button.label());
+                }
+                panel.spawn((PauseMenuMessage, label("", TextRole::Muted)));
+            });
+        });
+}
+
+/// 离开暂停时移除菜单
+pub fn close_pause_menu(mut commands: Commands, roots: Query<Entity, With<PauseMenuUi>>) {
+    for root in roots.iter() {
+        commands.entity(root).despawn_recursive();
+    }
+}
+
+/// 处理暂停菜单按钮
+///
+/// 联机中返回主菜单时先离开会话
+#[allow(clippy::too_many_arguments)]
+pub fn handle_pause_menu_buttons(
+    buttons: Query<(&Interaction, &PauseMenuButton), Changed<Interaction>>,
+    transition: Res<SceneTransition>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut options: ResMut<NextState<OptionsMenuState>>,
+    mut saves: EventWriter<SaveGameRequest>,
+    mut scenes: EventWriter<ChangeScene>,
+    mut coop: EventWriter<CoopCommand>,
+    mut messages: Query<&mut MixedText, With<PauseMenuMessage>>,
+) {
+    if transition.is_active() {
+        return;
+    }
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            PauseMenuButton::Resume => next_state.set(GameState::InGame),
+            PauseMenuButton::Options => options.set(OptionsMenuState::Open),
+            PauseMenuButton::Save => {
+                saves.send(SaveGameRequest::manual(PAUSE_SAVE_SLOT));
+                for mut message in messages.iter_mut() {
+                    message.0 = "进度已保存".to_string();
+                }
+            }
+            PauseMenuButton::LeaveSession => {
+                coop.send(CoopCommand::Leave);
+                for mut message in messages.iter_mut() {
+                    message.0 = "已离开联机会话".to_string();
+                }
+            }
+            PauseMenuButton::QuitToMenu => {
+                coop.send(CoopCommand::Leave);
+                scenes.send(ChangeScene(GameState::MainMenu));
+            }
+        }
+    }
+}
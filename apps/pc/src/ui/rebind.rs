@@ -0,0 +1,308 @@
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+
+use super::{label, spawn_text_button, OptionsMenu, TextRole};
+use crate::config::Settings;
+use crate::events::input::{GameAction, InputBinding, KeyBindings};
+
+/// 滚轮滚动一行对应的像素
+const SCROLL_LINE_PIXELS: f32 = 24.0;
+
+/// 等待按键时按下的键与其他动作冲突，等玩家决定替换还是取消
+#[derive(Debug, Clone)]
+pub struct PendingBinding {
+    pub action: GameAction,
+    pub input: InputBinding,
+    pub conflicts: Vec<GameAction>,
+}
+
+/// 改键流程
+///
+/// 点「添加」后等待玩家按键，按下的键没有冲突时直接绑定，
+/// 有冲突时先列出占用它的动作，由玩家选择替换或取消
+#[derive(Resource, Debug, Default)]
+pub struct Rebinding {
+    /// 正在等待按键的动作
+    pub waiting: Option<GameAction>,
+    pub conflict: Option<PendingBinding>,
+    /// 开始等待的那一帧不收键，以免把点按钮的鼠标左键当成新按键
+    armed: bool,
+    /// 按键列表的滚动位置，面板重建后恢复
+    scroll: f32,
+}
+
+impl Rebinding {
+    /// 是否正在改键，此时退出键与选项键都交给改键流程
+    pub fn is_active(&self) -> bool {
+        self.waiting.is_some() || self.conflict.is_some()
+    }
+
+    pub fn start(&mut self, action: GameAction) {
+        self.waiting = Some(action);
+        self.conflict = None;
+        self.armed = false;
+    }
+
+    pub fn cancel(&mut self) {
+        self.waiting = None;
+        self.conflict = None;
+    }
+}
+
+/// 按键页上的按钮
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebindButton {
+    Add(GameAction),
+    Clear(GameAction),
+    Replace,
+    Cancel,
+}
+
+impl RebindButton {
+    pub fn label(self) -> &'static str {
+        match self {
+            RebindButton::Add(_) => "添加",
+            RebindButton::Clear(_) => "清除",
+            RebindButton::Replace => "替换",
+            RebindButton::Cancel => "取消",
+        }
+    }
+}
+
+/// 可滚动的按键列表
+#[derive(Component)]
+pub struct RebindList;
+
+/// 改键后同步到设置，关闭选项菜单时写入用户配置
+fn commit_bindings(key_bindings: &KeyBindings, settings: &mut Settings, menu: &mut OptionsMenu) {
+    settings.game.controls.bindings = key_bindings.to_overrides();
+    menu.dirty = true;
+}
+
+fn action_names(actions: &[GameAction]) -> String {
+    actions
+        .iter()
+        .map(|action| format!("「{}」", action.label()))
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+/// 处理按键页的按钮
+pub fn handle_rebind_buttons(
+    buttons: Query<(&Interaction, &RebindButton), Changed<Interaction>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut key_bindings: ResMut<KeyBindings>,
+    mut settings: ResMut<Settings>,
+    mut menu: ResMut<OptionsMenu>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *button {
+            RebindButton::Add(action) => rebinding.start(action),
+            RebindButton::Clear(action) => {
+                rebinding.cancel();
+                key_bindings.clear(action);
+                commit_bindings(&key_bindings, &mut settings, &mut menu);
+            }
+            RebindButton::Replace => {
+                let Some(pending) = rebinding.conflict.take() else {
+                    continue;
+                };
+                for other in &pending.conflicts {
+                    key_bindings.unbind(*other, pending.input);
+                }
+                key_bindings.bind(pending.action, pending.input);
+                commit_bindings(&key_bindings, &mut settings, &mut menu);
+            }
+            RebindButton::Cancel => rebinding.cancel(),
+        }
+    }
+}
+
+/// 等待按键时收下第一个按下的键盘键、鼠标键或手柄键，退出键取消
+#[allow(clippy::too_many_arguments)]
+pub fn capture_rebind_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    mut rebinding: ResMut<Rebinding>,
+    mut key_bindings: ResMut<KeyBindings>,
+    mut settings: ResMut<Settings>,
+    mut menu: ResMut<OptionsMenu>,
+) {
+    let Some(action) = rebinding.waiting else {
+        return;
+    };
+    if !rebinding.armed {
+        rebinding.bypass_change_detection().armed = true;
+        return;
+    }
+    if keyboard.just_pressed(KeyCode::Escape) {
+        rebinding.cancel();
+        return;
+    }
+    let input = keyboard
+        .get_just_pressed()
+        .next()
+        .map(|key| InputBinding::Key(*key))
+        .or_else(|| {
+            mouse
+                .get_just_pressed()
+                .next()
+                .map(|button| InputBinding::Mouse(*button))
+        })
+        .or_else(|| {
+            gamepads
+                .iter()
+                .find_map(|gamepad| gamepad.get_just_pressed().next())
+                .map(|button| InputBinding::Gamepad(*button))
+        });
+    let Some(input) = input else {
+        return;
+    };
+
+    rebinding.waiting = None;
+    let conflicts = key_bindings.conflicts(action, input);
+    if conflicts.is_empty() {
+        key_bindings.bind(action, input);
+        commit_bindings(&key_bindings, &mut settings, &mut menu);
+    } else {
+        rebinding.conflict = Some(PendingBinding {
+            action,
+            input,
+            conflicts,
+        });
+    }
+}
+
+/// 滚轮滚动按键列表
+pub fn scroll_rebind_list(
+    mut wheel_events: EventReader<MouseWheel>,
+    mut rebinding: ResMut<Rebinding>,
+    mut lists: Query<&mut ScrollPosition, With<RebindList>>,
+) {
+    let delta: f32 = wheel_events
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_LINE_PIXELS,
+            MouseScrollUnit::Pixel => event.y,
+        })
+        .sum();
+    if delta == 0.0 {
+        return;
+    }
+    for mut position in lists.iter_mut() {
+        position.offset_y = (position.offset_y - delta).max(0.0);
+        // 只是记下位置，不触发面板重建
+        rebinding.bypass_change_detection().scroll = position.offset_y;
+    }
+}
+
+/// 生成按键页：改键提示与每个动作的按键
+pub fn spawn_rebind_page(
+    parent: &mut ChildBuilder,
+    key_bindings: &KeyBindings,
+    rebinding: &Rebinding,
+) {
+    if let Some(action) = rebinding.waiting {
+        parent.spawn(label(
+            format!("请按下要绑定到「{}」的键或手柄键，Esc 取消", action.label()),
+            TextRole::Body,
+        ));
+    } else if let Some(pending) = &rebinding.conflict {
+        parent
+            .spawn(Node {
+                column_gap: Val::Px(8.0),
+                align_items: AlignItems::Center,
+                ..default()
+            })
+            .with_children(|row| {
+                row.spawn(label(
+                    format!(
+                        "{} 已用于{}，替换后绑定到「{}」",
+                        pending.input.label(),
+                        action_names(&pending.conflicts),
+                        pending.action.label()
+                    ),
+                    TextRole::Body,
+                ));
+                for button in [RebindButton::Replace, RebindButton::Cancel] {
+                    spawn_text_button(row, button, button.label());
+                }
+            });
+    } else {
+        parent.spawn(label(
+            "点「添加」后按下新的键，可为一个动作绑定多个键",
+            TextRole::Muted,
+        ));
+    }
+
+    parent
+        .spawn((
+            RebindList,
+            Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                max_height: Val::Vh(55.0),
+                overflow: Overflow::scroll_y(),
+                ..default()
+            },
+            ScrollPosition {
+                offset_y: rebinding.scroll,
+                ..default()
+            },
+        ))
+        .with_children(|list| {
+            for action in GameAction::ALL {
+                let inputs = key_bindings.inputs(action);
+                let bound = if inputs.is_empty() {
+                    "未绑定".to_string()
+                } else {
+                    inputs
+                        .iter()
+                        .map(|input| input.label())
+                        .collect::<Vec<_>>()
+                        .join("、")
+                };
+                let conflicted = GameAction::ALL.iter().any(|other| {
+                    *other != action
+                        && inputs
+                            .iter()
+                            .any(|input| key_bindings.inputs(*other).contains(input))
+                });
+                list.spawn(Node {
+                    column_gap: Val::Px(8.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn((
+                        Node {
+                            width: Val::Px(120.0),
+                            ..default()
+                        },
+                        label(action.label(), TextRole::Body),
+                    ));
+                    row.spawn((
+                        Node {
+                            width: Val::Px(200.0),
+                            ..default()
+                        },
+                        label(
+                            if conflicted {
+                                format!("{}（冲突）", bound)
+                            } else {
+                                bound
+                            },
+                            TextRole::Small,
+                        ),
+                    ));
+                    for button in [RebindButton::Add(action), RebindButton::Clear(action)] {
+                        spawn_text_button(row, button, button.label());
+                    }
+                });
+            }
+        });
+}
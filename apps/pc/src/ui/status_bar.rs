@@ -0,0 +1,155 @@
+use bevy::prelude::*;
+
+use super::{glyph, label, TextRole, WorldMapState};
+use crate::combat::{StatusEffectSettings, StatusEffects};
+use crate::world::entity::Player;
+
+/// 状态栏配置
+#[derive(Resource, Debug, Clone)]
+pub struct StatusBarSettings {
+    /// 是否显示状态栏
+    pub visible: bool,
+    /// 图标边长
+    pub icon_size: f32,
+    /// 与屏幕左下角的间距
+    pub margin: f32,
+    /// 剩余时间的刷新间隔（秒），效果增减时立即刷新
+    pub refresh_interval: f32,
+    /// 有害效果的边框颜色
+    pub harmful_border: Color,
+    /// 增益效果的边框颜色
+    pub helpful_border: Color,
+}
+
+impl Default for StatusBarSettings {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            icon_size: 32.0,
+            margin: 12.0,
+            refresh_interval: 0.25,
+            harmful_border: Color::srgb(0.7, 0.2, 0.2),
+            helpful_border: Color::srgb(0.3, 0.65, 0.35),
+        }
+    }
+}
+
+/// 状态栏根节点，图标都挂在它下面
+#[derive(Component)]
+pub struct StatusBarUi;
+
+/// 创建状态栏
+pub fn setup_status_bar(mut commands: Commands, settings: Res<StatusBarSettings>) {
+    commands.spawn((
+        StatusBarUi,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(settings.margin),
+            bottom: Val::Px(settings.margin),
+            column_gap: Val::Px(4.0),
+            ..default()
+        },
+    ));
+}
+
+/// 重绘玩家的状态栏
+///
+/// 效果增减时立即重绘，其余时候按间隔刷新剩余时间；世界地图打开时隐藏
+#[allow(clippy::too_many_arguments)]
+pub fn redraw_status_bar(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<StatusBarSettings>,
+    status_settings: Res<StatusEffectSettings>,
+    map_state: Option<Res<State<WorldMapState>>>,
+    mut since_refresh: Local<f32>,
+    mut shown: Local<usize>,
+    players: Query<&StatusEffects, With<Player>>,
+    mut roots: Query<(Entity, &mut Node), With<StatusBarUi>>,
+) {
+    let Ok((root, mut node)) = roots.get_single_mut() else {
+        return;
+    };
+    let map_open = map_state
+        .as_ref()
+        .is_some_and(|map_state| *map_state.get() == WorldMapState::Open);
+    let display = if settings.visible && !map_open {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    if node.display != display {
+        node.display = display;
+    }
+    if display == Display::None {
+        return;
+    }
+
+    let effects = players.get_single().ok();
+    let count = effects.map_or(0, |effects| effects.effects.len());
+    *since_refresh += time.delta_secs();
+    if count == *shown && *since_refresh < settings.refresh_interval {
+        return;
+    }
+    *since_refresh = 0.0;
+    *shown = count;
+
+    commands.entity(root).despawn_descendants();
+    let Some(effects) = effects else {
+        return;
+    };
+    commands.entity(root).with_children(|bar| {
+        for effect in &effects.effects {
+            let border = if effect.kind.is_harmful() {
+                settings.harmful_border
+            } else {
+                settings.helpful_border
+            };
+            let name = status_settings
+                .get(effect.kind)
+                .map_or("", |def| def.name.as_str());
+            bar.spawn(Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            })
+            .with_children(|slot| {
+                slot.spawn((
+                    Node {
+                        width: Val::Px(settings.icon_size),
+                        height: Val::Px(settings.icon_size),
+                        border: UiRect::all(Val::Px(2.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderColor(border),
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                    Name::new(name.to_string()),
+                ))
+                .with_children(|icon| {
+                    icon.spawn(glyph(
+                        effect.kind.icon(),
+                        settings.icon_size * 0.55,
+                        effect.kind.icon_color(),
+                    ));
+                    if effect.stacks > 1 {
+                        icon.spawn((
+                            Node {
+                                position_type: PositionType::Absolute,
+                                right: Val::Px(1.0),
+                                bottom: Val::Px(0.0),
+                                ..default()
+                            },
+                            glyph(format!("{}", effect.stacks), 10.0, Color::WHITE),
+                        ));
+                    }
+                });
+                slot.spawn(label(
+                    format!("{:.0}", effect.remaining.ceil()),
+                    TextRole::Small,
+                ));
+            });
+        }
+    });
+}
@@ -0,0 +1,232 @@
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use bevy::ui::UiSystem;
+
+use crate::resources::{gameplay_running, GameState};
+
+use super::{
+    apply_compass_visibility, apply_pin_editor_actions, apply_settings, apply_ui_theme_settings,
+    apply_widget_theme, cache_minimap_chunks, capture_rebind_input, close_login_screen,
+    close_main_menu, close_options_menu, close_pause_menu, close_world_map, discard_pin_draft,
+    edit_login_input, edit_main_menu_input, edit_pin_note, float_health_changes,
+    float_loot_pickups, float_poi_discoveries, float_quest_completions, float_skill_cast_failures,
+    float_skill_unlocks, handle_login_buttons, handle_login_results, handle_main_menu_buttons,
+    handle_options_buttons, handle_pause_menu_buttons, handle_pin_editor_buttons,
+    handle_rebind_buttons, layout_mixed_text, load_fonts, load_ui_themes, navigate_world_map,
+    open_login_screen, open_main_menu, open_options_menu, open_pause_menu, open_world_map,
+    place_map_pin, play_ui_sounds, redraw_compass, redraw_minimap, redraw_status_bar,
+    redraw_world_map, run_scene_transition, scroll_rebind_list, select_map_pin, setup_compass,
+    setup_minimap, setup_scene_fade, setup_status_bar, setup_world_map, show_floating_texts,
+    show_speech_bubbles, show_tutorial_callouts, spawn_floating_text_pool,
+    spawn_speech_bubble_pool, sync_login_panel, sync_main_menu_panel, sync_options_panel,
+    sync_pin_editor_panel, toggle_minimap, toggle_options_menu, toggle_pause, toggle_world_map,
+    track_font_loads, update_floating_texts, update_speech_bubbles, update_ui_buttons, ChangeScene,
+    CompassSettings, CompassState, FloatingTextPool, FloatingTextSettings, FontService, LoginForm,
+    MainMenu, MinimapSettings, MinimapState, OptionsMenu, OptionsMenuState, PinEditor,
+    PinEditorAction, Rebinding, SceneTransition, ShowFloatingText, ShowSpeechBubble,
+    SpeechBubblePool, SpeechBubbleSettings, StatusBarSettings, UiSound, UiTheme, UiThemeSettings,
+    WorldMapSettings, WorldMapState, WorldMapView,
+};
+
+/// 界面插件
+///
+/// # 设计思路
+/// 1. 各系统只发出显示请求，不直接生成界面实体
+/// 2. 气泡在角色与相机移动之后、变换传播之前定位，避免跟随时滞后一帧
+/// 3. 小地图在区块加载后增量缓存颜色，绘制时只做拷贝
+/// 4. 世界地图是独立的界面状态，打开时生成、关闭时移除，只在视图变化时重绘
+/// 5. 编辑标注时键盘输入都交给编辑器，地图开关与平移在编辑期间暂停
+/// 6. 罗盘只读取任务标记、兴趣点与标注，世界地图打开时隐藏
+/// 7. 控件样式统一在布局前按主题套用，切换主题不必重建界面
+/// 8. 文字在套用主题之后按字体回退链切段，字体陆续加载完成时重新排版
+/// 9. 状态栏在效果增减时重建，平时只按间隔刷新剩余时间
+/// 10. 选项菜单只改 `Settings` 资源，窗口、音量与视距在资源变化时统一套用，关闭菜单时才写盘
+/// 11. 改键先于面板重建处理按键，等待按键期间退出键与选项键不开关菜单
+/// 12. 主菜单随 `GameState::MainMenu` 生成与移除，场景切换统一经过黑屏淡入淡出，世界地图只在游戏中可开
+/// 13. 退出键在游戏中暂停、暂停中继续；选项菜单或世界地图开着时退出键先关它们
+/// 14. 飘字与气泡一样按上限预先生成、回收复用；伤害与拾取由各自的事件转成飘字请求
+/// 15. 登录界面随 `GameState::Login` 生成与移除，只收发登录事件，登录成功后淡入主菜单
+pub struct GameUiPlugin;
+
+impl Plugin for GameUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiTheme>()
+            .init_resource::<UiThemeSettings>()
+            .add_event::<UiSound>()
+            .init_resource::<FontService>()
+            .add_systems(Startup, (load_ui_themes, load_fonts))
+            .add_systems(
+                Update,
+                (
+                    apply_ui_theme_settings,
+                    track_font_loads,
+                    update_ui_buttons,
+                    play_ui_sounds,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                PostUpdate,
+                (apply_widget_theme, layout_mixed_text)
+                    .chain()
+                    .before(UiSystem::Layout),
+            );
+
+        app.add_event::<ShowSpeechBubble>()
+            .init_resource::<SpeechBubbleSettings>()
+            .init_resource::<SpeechBubblePool>()
+            .init_resource::<MinimapSettings>()
+            .init_resource::<MinimapState>()
+            .init_resource::<WorldMapSettings>()
+            .init_resource::<WorldMapView>()
+            .init_resource::<PinEditor>()
+            .init_resource::<CompassSettings>()
+            .init_resource::<CompassState>()
+            .init_resource::<StatusBarSettings>()
+            .add_event::<PinEditorAction>()
+            .init_state::<WorldMapState>()
+            .init_resource::<OptionsMenu>()
+            .init_resource::<Rebinding>()
+            .init_state::<OptionsMenuState>()
+            .init_resource::<LoginForm>()
+            .init_resource::<MainMenu>()
+            .init_resource::<SceneTransition>()
+            .add_event::<ChangeScene>()
+            .add_event::<ShowFloatingText>()
+            .init_resource::<FloatingTextSettings>()
+            .init_resource::<FloatingTextPool>();
+
+        app.add_systems(
+            Startup,
+            (
+                spawn_speech_bubble_pool,
+                spawn_floating_text_pool,
+                setup_minimap,
+                setup_world_map,
+                setup_compass,
+                setup_status_bar,
+                setup_scene_fade,
+            ),
+        )
+        .add_systems(
+            Update,
+            (cache_minimap_chunks, toggle_minimap, redraw_minimap).chain(),
+        )
+        // 选项菜单打开时按键留给菜单与改键，不开关世界地图
+        .add_systems(
+            Update,
+            toggle_world_map
+                .run_if(in_state(OptionsMenuState::Closed).and(in_state(GameState::InGame))),
+        )
+        .add_systems(Update, redraw_status_bar.after(toggle_world_map))
+        .add_systems(
+            Update,
+            (
+                apply_compass_visibility,
+                redraw_compass.run_if(in_state(WorldMapState::Closed)),
+            )
+                .chain()
+                .after(toggle_world_map),
+        )
+        .add_systems(OnEnter(WorldMapState::Open), open_world_map)
+        .add_systems(
+            OnExit(WorldMapState::Open),
+            (close_world_map, discard_pin_draft),
+        )
+        .add_systems(
+            Update,
+            (
+                place_map_pin,
+                select_map_pin,
+                handle_pin_editor_buttons,
+                edit_pin_note,
+                apply_pin_editor_actions,
+                sync_pin_editor_panel,
+                navigate_world_map,
+                redraw_world_map,
+            )
+                .chain()
+                .after(toggle_world_map)
+                .run_if(in_state(WorldMapState::Open)),
+        )
+        .add_systems(OnEnter(OptionsMenuState::Open), open_options_menu)
+        .add_systems(OnExit(OptionsMenuState::Open), close_options_menu)
+        .add_systems(
+            Update,
+            (
+                toggle_options_menu,
+                (
+                    handle_options_buttons,
+                    handle_rebind_buttons,
+                    capture_rebind_input,
+                    scroll_rebind_list,
+                    sync_options_panel,
+                )
+                    .chain()
+                    .run_if(in_state(OptionsMenuState::Open)),
+                apply_settings,
+            )
+                .chain(),
+        )
+        .add_systems(OnEnter(GameState::Login), open_login_screen)
+        .add_systems(OnExit(GameState::Login), close_login_screen)
+        .add_systems(
+            Update,
+            (
+                handle_login_buttons,
+                edit_login_input,
+                handle_login_results,
+                sync_login_panel,
+            )
+                .chain()
+                .run_if(in_state(GameState::Login)),
+        )
+        .add_systems(OnEnter(GameState::MainMenu), open_main_menu)
+        .add_systems(OnExit(GameState::MainMenu), close_main_menu)
+        .add_systems(
+            Update,
+            (
+                handle_main_menu_buttons,
+                edit_main_menu_input,
+                sync_main_menu_panel,
+            )
+                .chain()
+                .run_if(in_state(GameState::MainMenu)),
+        )
+        .add_systems(OnEnter(GameState::Paused), open_pause_menu)
+        .add_systems(OnExit(GameState::Paused), close_pause_menu)
+        .add_systems(
+            Update,
+            (
+                toggle_pause,
+                handle_pause_menu_buttons.run_if(in_state(GameState::Paused)),
+            )
+                .chain(),
+        )
+        .add_systems(Update, run_scene_transition)
+        .add_systems(Update, show_tutorial_callouts.run_if(gameplay_running))
+        .add_systems(
+            Update,
+            (
+                float_health_changes,
+                float_loot_pickups,
+                float_skill_unlocks,
+                float_skill_cast_failures,
+                float_poi_discoveries,
+                float_quest_completions,
+            ),
+        )
+        .add_systems(
+            PostUpdate,
+            (show_speech_bubbles, update_speech_bubbles)
+                .chain()
+                .before(TransformSystem::TransformPropagate),
+        )
+        .add_systems(
+            PostUpdate,
+            (show_floating_texts, update_floating_texts)
+                .chain()
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
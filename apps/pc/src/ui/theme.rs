@@ -0,0 +1,431 @@
+use bevy::prelude::*;
+use bevy::ui::widget::NodeImageMode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::config::resolve_data_path;
+use crate::logging::{GameLogger, LogLevel};
+
+/// 界面主题数据文件路径
+pub const UI_THEME_DATA_PATH: &str = "src/config/ui_themes.json";
+
+/// 资源根目录，用于检查主题引用的文件是否存在
+const ASSET_ROOT: &str = "assets";
+
+/// 主题字体配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeFonts {
+    /// 正文字体，为空时使用默认字体
+    #[serde(default)]
+    pub body: Option<String>,
+    /// 标题字体，为空时沿用正文字体
+    #[serde(default)]
+    pub title: Option<String>,
+    pub title_size: f32,
+    pub body_size: f32,
+    pub small_size: f32,
+}
+
+/// 面板九宫格配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemePanel {
+    /// 九宫格贴图，为空时只用底色与边框
+    #[serde(default)]
+    pub image: Option<String>,
+    /// 九宫格四角的像素宽度
+    #[serde(default)]
+    pub slice: f32,
+    /// 边框宽度
+    #[serde(default)]
+    pub border_width: f32,
+}
+
+/// 主题颜色，均为 sRGB 加透明度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeColors {
+    /// 全屏界面的遮罩
+    pub overlay: [f32; 4],
+    pub panel: [f32; 4],
+    pub panel_border: [f32; 4],
+    pub text: [f32; 4],
+    pub text_muted: [f32; 4],
+    pub accent: [f32; 4],
+    pub button: [f32; 4],
+    pub button_hover: [f32; 4],
+    pub button_pressed: [f32; 4],
+}
+
+/// 主题音效配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeSounds {
+    #[serde(default)]
+    pub click: Option<String>,
+    #[serde(default)]
+    pub open: Option<String>,
+    #[serde(default)]
+    pub close: Option<String>,
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+/// 一套主题预设
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemePreset {
+    /// 显示名称
+    pub name: String,
+    pub fonts: ThemeFonts,
+    pub panel: ThemePanel,
+    pub colors: ThemeColors,
+    pub sounds: ThemeSounds,
+}
+
+/// 主题数据文件格式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ThemeDataFile {
+    default: String,
+    themes: HashMap<String, ThemePreset>,
+}
+
+/// 主题预设库
+#[derive(Resource, Debug, Clone, Default)]
+pub struct UiThemeLibrary {
+    /// 未指定或指定的主题不存在时使用的主题
+    pub default: String,
+    presets: HashMap<String, ThemePreset>,
+}
+
+impl UiThemeLibrary {
+    /// 从数据文件加载主题库
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let data: ThemeDataFile = serde_json::from_str(&content)?;
+        if !data.themes.contains_key(&data.default) {
+            return Err(format!("默认主题不存在: {}", data.default).into());
+        }
+        Ok(Self {
+            default: data.default,
+            presets: data.themes,
+        })
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ThemePreset> {
+        self.presets.get(id)
+    }
+
+    /// 全部主题的编号与显示名称，按编号排序，供设置界面列出
+    pub fn list(&self) -> Vec<(&str, &str)> {
+        let mut list: Vec<(&str, &str)> = self
+            .presets
+            .iter()
+            .map(|(id, preset)| (id.as_str(), preset.name.as_str()))
+            .collect();
+        list.sort();
+        list
+    }
+}
+
+/// 主题设置
+///
+/// 修改 `theme` 后当前打开的界面会立即换上新主题
+#[derive(Resource, Debug, Clone, Default)]
+pub struct UiThemeSettings {
+    /// 选用的主题编号，为空时使用主题库的默认主题
+    pub theme: String,
+}
+
+/// 文字的用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextRole {
+    Title,
+    Body,
+    /// 小字说明
+    Small,
+    /// 次要提示
+    Muted,
+}
+
+/// 主题颜色的用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThemeColor {
+    Overlay,
+    Panel,
+    PanelBorder,
+    Text,
+    TextMuted,
+    Accent,
+    Button,
+    ButtonHover,
+    ButtonPressed,
+}
+
+/// 界面音效
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UiSound {
+    Click,
+    Open,
+    Close,
+}
+
+/// 当前生效的主题
+///
+/// 由主题预设解析而来，字体、贴图与音效都已转成资源句柄，控件直接取用
+#[derive(Resource, Debug, Clone)]
+pub struct UiTheme {
+    pub preset: ThemePreset,
+    body_font: Handle<Font>,
+    title_font: Handle<Font>,
+    panel_image: Option<Handle<Image>>,
+    sounds: HashMap<UiSound, Handle<AudioSource>>,
+}
+
+impl Default for UiTheme {
+    /// 数据文件缺失时的内置主题，沿用古卷配色与默认字体
+    fn default() -> Self {
+        Self {
+            preset: ThemePreset {
+                name: "默认".to_string(),
+                fonts: ThemeFonts {
+                    body: None,
+                    title: None,
+                    title_size: 20.0,
+                    body_size: 14.0,
+                    small_size: 11.0,
+                },
+                panel: ThemePanel {
+                    image: None,
+                    slice: 0.0,
+                    border_width: 2.0,
+                },
+                colors: ThemeColors {
+                    overlay: [0.0, 0.0, 0.0, 0.85],
+                    panel: [0.1, 0.08, 0.06, 0.92],
+                    panel_border: [0.55, 0.42, 0.25, 1.0],
+                    text: [0.92, 0.88, 0.78, 1.0],
+                    text_muted: [0.6, 0.6, 0.6, 1.0],
+                    accent: [0.95, 0.8, 0.4, 1.0],
+                    button: [0.3, 0.22, 0.14, 1.0],
+                    button_hover: [0.4, 0.3, 0.18, 1.0],
+                    button_pressed: [0.55, 0.42, 0.25, 1.0],
+                },
+                sounds: ThemeSounds {
+                    click: None,
+                    open: None,
+                    close: None,
+                    volume: 1.0,
+                },
+            },
+            body_font: Handle::default(),
+            title_font: Handle::default(),
+            panel_image: None,
+            sounds: HashMap::new(),
+        }
+    }
+}
+
+/// 主题引用的资源文件是否存在
+///
+/// 缺失的字体会让文字整块不显示，因此先检查文件，缺失时退回默认值
+fn asset_exists(path: &str) -> bool {
+    Path::new(ASSET_ROOT).join(path).exists()
+}
+
+impl UiTheme {
+    /// 解析主题预设，缺失的资源文件记入 `missing`
+    pub fn resolve(
+        preset: &ThemePreset,
+        asset_server: &AssetServer,
+        missing: &mut Vec<String>,
+    ) -> Self {
+        let mut load = |path: &Option<String>| -> Option<String> {
+            let path = path.as_ref()?;
+            if asset_exists(path) {
+                Some(path.clone())
+            } else {
+                missing.push(path.clone());
+                None
+            }
+        };
+
+        let body_font = load(&preset.fonts.body)
+            .map(|path| asset_server.load(path))
+            .unwrap_or_default();
+        let title_font = load(&preset.fonts.title)
+            .map(|path| asset_server.load(path))
+            .unwrap_or_else(|| body_font.clone());
+        let panel_image = load(&preset.panel.image).map(|path| asset_server.load(path));
+
+        let mut sounds = HashMap::new();
+        for (key, path) in [
+            (UiSound::Click, &preset.sounds.click),
+            (UiSound::Open, &preset.sounds.open),
+            (UiSound::Close, &preset.sounds.close),
+        ] {
+            if let Some(path) = load(path) {
+                sounds.insert(key, asset_server.load(path));
+            }
+        }
+
+        Self {
+            preset: preset.clone(),
+            body_font,
+            title_font,
+            panel_image,
+            sounds,
+        }
+    }
+
+    pub fn color(&self, color: ThemeColor) -> Color {
+        let colors = &self.preset.colors;
+        let [r, g, b, a] = match color {
+            ThemeColor::Overlay => colors.overlay,
+            ThemeColor::Panel => colors.panel,
+            ThemeColor::PanelBorder => colors.panel_border,
+            ThemeColor::Text => colors.text,
+            ThemeColor::TextMuted => colors.text_muted,
+            ThemeColor::Accent => colors.accent,
+            ThemeColor::Button => colors.button,
+            ThemeColor::ButtonHover => colors.button_hover,
+            ThemeColor::ButtonPressed => colors.button_pressed,
+        };
+        Color::srgba(r, g, b, a)
+    }
+
+    /// 某种用途文字的字体
+    pub fn font(&self, role: TextRole) -> TextFont {
+        let fonts = &self.preset.fonts;
+        let (font, font_size) = match role {
+            TextRole::Title => (self.title_font.clone(), fonts.title_size),
+            TextRole::Body => (self.body_font.clone(), fonts.body_size),
+            TextRole::Small | TextRole::Muted => (self.body_font.clone(), fonts.small_size),
+        };
+        TextFont {
+            font,
+            font_size,
+            ..default()
+        }
+    }
+
+    /// 某种用途文字的颜色
+    pub fn text_color(&self, role: TextRole) -> Color {
+        match role {
+            TextRole::Muted => self.color(ThemeColor::TextMuted),
+            _ => self.color(ThemeColor::Text),
+        }
+    }
+
+    /// 正文字体句柄，供自定字号的符号使用
+    pub fn body_font(&self) -> Handle<Font> {
+        self.body_font.clone()
+    }
+
+    /// 面板的九宫格贴图
+    pub fn panel_image(&self) -> Option<ImageNode> {
+        let image = self.panel_image.clone()?;
+        let slice = self.preset.panel.slice.max(0.0);
+        Some(
+            ImageNode::new(image).with_mode(NodeImageMode::Sliced(TextureSlicer {
+                border: BorderRect::square(slice),
+                center_scale_mode: SliceScaleMode::Stretch,
+                sides_scale_mode: SliceScaleMode::Stretch,
+                max_corner_scale: 1.0,
+            })),
+        )
+    }
+
+    pub fn sound(&self, sound: UiSound) -> Option<Handle<AudioSource>> {
+        self.sounds.get(&sound).cloned()
+    }
+}
+
+/// 加载主题库
+pub fn load_ui_themes(mut commands: Commands, mut logger: Option<ResMut<GameLogger>>) {
+    let library = match UiThemeLibrary::load(&resolve_data_path(UI_THEME_DATA_PATH)) {
+        Ok(library) => library,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(
+                    LogLevel::Error,
+                    &format!("加载界面主题失败: {}，使用内置主题", e),
+                );
+            }
+            UiThemeLibrary::default()
+        }
+    };
+    commands.insert_resource(library);
+}
+
+/// 主题设置变化时重新解析主题
+///
+/// 控件按 `UiTheme` 的变化统一换肤，这里只负责替换主题本身
+pub fn apply_ui_theme_settings(
+    settings: Res<UiThemeSettings>,
+    library: Res<UiThemeLibrary>,
+    asset_server: Res<AssetServer>,
+    mut theme: ResMut<UiTheme>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    if !settings.is_changed() && !library.is_changed() {
+        return;
+    }
+
+    let requested = if settings.theme.is_empty() {
+        library.default.as_str()
+    } else {
+        settings.theme.as_str()
+    };
+    let (id, preset) = match library.get(requested) {
+        Some(preset) => (requested, preset),
+        None => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(
+                    LogLevel::Error,
+                    &format!("界面主题不存在: {}，改用默认主题", requested),
+                );
+            }
+            match library.get(&library.default) {
+                Some(preset) => (library.default.as_str(), preset),
+                None => return,
+            }
+        }
+    };
+    let mut missing = Vec::new();
+    *theme = UiTheme::resolve(preset, &asset_server, &mut missing);
+
+    if let Some(logger) = logger.as_mut() {
+        for path in &missing {
+            logger.log(
+                LogLevel::Error,
+                &format!("界面主题 {} 引用的资源不存在: {}", id, path),
+            );
+        }
+        logger.log(
+            LogLevel::Info,
+            &format!("界面主题切换为 {} ({})", preset.name, id),
+        );
+    }
+}
+
+/// 播放界面音效
+pub fn play_ui_sounds(
+    mut commands: Commands,
+    theme: Res<UiTheme>,
+    mut sounds: EventReader<UiSound>,
+) {
+    for sound in sounds.read() {
+        let Some(source) = theme.sound(*sound) else {
+            continue;
+        };
+        commands.spawn((
+            Name::new(format!("UiSound: {:?}", sound)),
+            AudioPlayer::<AudioSource>::new(source),
+            PlaybackSettings::DESPAWN
+                .with_volume(bevy::audio::Volume::new(theme.preset.sounds.volume)),
+        ));
+    }
+}
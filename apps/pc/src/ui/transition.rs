@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::resources::GameState;
+
+/// 淡出与淡入各自的时长（秒）
+const FADE_SECONDS: f32 = 0.35;
+
+/// 请求切换场景，先淡出到黑屏再切换游戏状态，之后淡入
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ChangeScene(pub GameState);
+
+/// 淡入淡出的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum FadePhase {
+    #[default]
+    Idle,
+    Out,
+    In,
+}
+
+/// 场景切换
+///
+/// 切换期间的请求忽略，避免连点按钮时重复切换
+#[derive(Resource, Debug, Default)]
+pub struct SceneTransition {
+    target: Option<GameState>,
+    phase: FadePhase,
+    elapsed: f32,
+}
+
+impl SceneTransition {
+    /// 是否正在切换
+    pub fn is_active(&self) -> bool {
+        self.phase != FadePhase::Idle
+    }
+}
+
+/// 全屏黑幕，切换时挡住下层界面的点击
+#[derive(Component)]
+pub struct SceneFade;
+
+pub fn setup_scene_fade(mut commands: Commands) {
+    commands.spawn((
+        SceneFade,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.0)),
+        FocusPolicy::Block,
+        GlobalZIndex(100),
+        Visibility::Hidden,
+    ));
+}
+
+/// 推进场景切换
+///
+/// 按真实时间计时，暂停与慢动作不影响切换
+pub fn run_scene_transition(
+    real_time: Res<Time<Real>>,
+    mut requests: EventReader<ChangeScene>,
+    mut transition: ResMut<SceneTransition>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut fades: Query<(&mut BackgroundColor, &mut Visibility), With<SceneFade>>,
+) {
+    if let Some(ChangeScene(target)) = requests.read().last().copied() {
+        if !transition.is_active() {
+            transition.target = Some(target);
+            transition.phase = FadePhase::Out;
+            transition.elapsed = 0.0;
+        }
+    }
+    if !transition.is_active() {
+        return;
+    }
+
+    transition.elapsed += real_time.delta_secs();
+    let progress = (transition.elapsed / FADE_SECONDS).min(1.0);
+    let alpha = match transition.phase {
+        FadePhase::Out => progress,
+        _ => 1.0 - progress,
+    };
+    if let Ok((mut color, mut visibility)) = fades.get_single_mut() {
+        color.0 = Color::BLACK.with_alpha(alpha);
+        *visibility = Visibility::Visible;
+        if transition.phase == FadePhase::In && progress >= 1.0 {
+            *visibility = Visibility::Hidden;
+        }
+    }
+    if progress < 1.0 {
+        return;
+    }
+
+    transition.elapsed = 0.0;
+    match transition.phase {
+        // 黑屏时切换状态，新场景的界面在淡入前就已生成
+        FadePhase::Out => {
+            if let Some(target) = transition.target.take() {
+                next_state.set(target);
+            }
+            transition.phase = FadePhase::In;
+        }
+        _ => transition.phase = FadePhase::Idle,
+    }
+}
@@ -0,0 +1,181 @@
+use bevy::prelude::*;
+
+use super::{MixedText, TextRole, ThemeColor, UiSound, UiTheme};
+
+/// 主题面板：底色、边框与九宫格贴图都取自主题
+#[derive(Component, Debug, Clone, Copy)]
+pub struct UiPanel {
+    /// 底色的用途，普通面板或全屏遮罩
+    pub background: ThemeColor,
+}
+
+/// 主题边框：只染边框，用于自带贴图的节点，例如地图画布
+#[derive(Component, Debug, Clone, Copy)]
+pub struct UiFrame;
+
+/// 主题文字：字体、字号与颜色都按用途取自主题
+#[derive(Component, Debug, Clone, Copy)]
+pub struct UiText(pub TextRole);
+
+/// 自定字号与颜色的符号或提示，只换主题字体
+#[derive(Component, Debug, Clone, Copy)]
+pub struct UiGlyph;
+
+/// 主题按钮：按交互状态换底色，按下时播放点击音效
+#[derive(Component, Debug, Clone, Copy)]
+pub struct UiButton;
+
+/// 面板
+pub fn panel() -> impl Bundle {
+    (
+        UiPanel {
+            background: ThemeColor::Panel,
+        },
+        BackgroundColor::default(),
+        BorderColor::default(),
+    )
+}
+
+/// 全屏遮罩，不用九宫格
+pub fn overlay() -> impl Bundle {
+    (
+        UiPanel {
+            background: ThemeColor::Overlay,
+        },
+        BackgroundColor::default(),
+    )
+}
+
+/// 边框
+pub fn frame() -> impl Bundle {
+    (UiFrame, BorderColor::default())
+}
+
+/// 文字，按字体回退链排版，可以混排中西文与符号
+pub fn label(text: impl Into<String>, role: TextRole) -> impl Bundle {
+    (
+        Text::default(),
+        MixedText(text.into()),
+        UiText(role),
+        TextFont::default(),
+        TextColor::default(),
+    )
+}
+
+/// 符号，同样按字体回退链排版
+pub fn glyph(text: impl Into<String>, font_size: f32, color: Color) -> impl Bundle {
+    (
+        Text::default(),
+        MixedText(text.into()),
+        UiGlyph,
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(color),
+    )
+}
+
+/// 按钮
+pub fn button() -> impl Bundle {
+    (UiButton, Button, BackgroundColor::default())
+}
+
+/// 按交互状态取按钮底色
+fn button_color(theme: &UiTheme, interaction: Interaction) -> Color {
+    match interaction {
+        Interaction::Pressed => theme.color(ThemeColor::ButtonPressed),
+        Interaction::Hovered => theme.color(ThemeColor::ButtonHover),
+        Interaction::None => theme.color(ThemeColor::Button),
+    }
+}
+
+/// 为控件套用主题
+///
+/// # 设计思路
+/// 1. 控件只声明用途，不写死颜色与字体，新生成的控件在布局前套用主题
+/// 2. 主题切换时所有控件重新套用，已打开的界面无需重建
+/// 3. 九宫格贴图按主题增删，没有贴图的主题只用底色与边框
+#[allow(clippy::type_complexity)]
+pub fn apply_widget_theme(
+    mut commands: Commands,
+    theme: Res<UiTheme>,
+    mut panels: Query<(
+        Entity,
+        Ref<UiPanel>,
+        &mut Node,
+        &mut BackgroundColor,
+        Option<&mut BorderColor>,
+    )>,
+    mut frames: Query<(Ref<UiFrame>, &mut BorderColor), Without<UiPanel>>,
+    mut texts: Query<(Ref<UiText>, &mut TextFont, &mut TextColor)>,
+    mut glyphs: Query<(Ref<UiGlyph>, &mut TextFont), Without<UiText>>,
+    mut buttons: Query<(Ref<UiButton>, &Interaction, &mut BackgroundColor), Without<UiPanel>>,
+) {
+    let all = theme.is_changed();
+
+    for (entity, panel, mut node, mut background, border) in panels.iter_mut() {
+        if !all && !panel.is_added() {
+            continue;
+        }
+        background.0 = theme.color(panel.background);
+        if panel.background == ThemeColor::Overlay {
+            continue;
+        }
+        if let Some(mut border) = border {
+            border.0 = theme.color(ThemeColor::PanelBorder);
+            node.border = UiRect::all(Val::Px(theme.preset.panel.border_width));
+        }
+        match theme.panel_image() {
+            Some(image) => {
+                commands.entity(entity).insert(image);
+            }
+            None => {
+                commands.entity(entity).remove::<ImageNode>();
+            }
+        }
+    }
+
+    for (frame, mut border) in frames.iter_mut() {
+        if all || frame.is_added() {
+            border.0 = theme.color(ThemeColor::PanelBorder);
+        }
+    }
+
+    for (text, mut font, mut color) in texts.iter_mut() {
+        if all || text.is_added() {
+            *font = theme.font(text.0);
+            color.0 = theme.text_color(text.0);
+        }
+    }
+
+    for (glyph, mut font) in glyphs.iter_mut() {
+        if all || glyph.is_added() {
+            font.font = theme.body_font();
+        }
+    }
+
+    for (button, interaction, mut background) in buttons.iter_mut() {
+        if all || button.is_added() {
+            background.0 = button_color(&theme, *interaction);
+        }
+    }
+}
+
+/// 按钮交互反馈
+#[allow(clippy::type_complexity)]
+pub fn update_ui_buttons(
+    theme: Res<UiTheme>,
+    mut buttons: Query<
+        (&Interaction, &mut BackgroundColor),
+        (With<UiButton>, Changed<Interaction>),
+    >,
+    mut sounds: EventWriter<UiSound>,
+) {
+    for (interaction, mut background) in buttons.iter_mut() {
+        background.0 = button_color(&theme, *interaction);
+        if *interaction == Interaction::Pressed {
+            sounds.send(UiSound::Click);
+        }
+    }
+}
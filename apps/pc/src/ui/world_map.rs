@@ -0,0 +1,500 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::window::PrimaryWindow;
+
+use super::{
+    frame, glyph, label, minimap_color, overlay, MapPinMarker, PinEditor, TextRole, UiSound,
+};
+use crate::events::input::GameAction;
+use crate::resources::InputState;
+use crate::world::chunk::{ChunkCoord, CHUNK_SIZE, TILE_PIXELS};
+use crate::world::entity::{Character, Player};
+use crate::world::map::{FixedSceneRef, MapManager, QuestMarkers, TileType};
+use crate::world::poi::{default_poi_name, ExploredChunks, MapPins, PoiRegistry};
+
+/// 世界地图界面状态
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WorldMapState {
+    #[default]
+    Closed,
+    Open,
+}
+
+/// 世界地图配置
+#[derive(Resource, Debug, Clone)]
+pub struct WorldMapSettings {
+    /// 地图贴图边长（像素）
+    pub resolution: u32,
+    /// 缩放范围，单位为每个贴图像素对应的瓦片数
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+    /// 打开地图时的缩放
+    pub default_zoom: f32,
+    /// 每次缩放的倍率
+    pub zoom_step: f32,
+    /// 键盘平移速度（贴图像素/秒）
+    pub pan_speed: f32,
+    /// 未探索区域的颜色
+    pub fog_color: [u8; 4],
+}
+
+impl Default for WorldMapSettings {
+    fn default() -> Self {
+        Self {
+            resolution: 384,
+            min_zoom: 0.25,
+            max_zoom: 16.0,
+            default_zoom: 2.0,
+            zoom_step: 1.25,
+            pan_speed: 240.0,
+            fog_color: [28, 26, 24, 255],
+        }
+    }
+}
+
+/// 世界地图视图
+#[derive(Resource, Debug, Default)]
+pub struct WorldMapView {
+    /// 视图中心（世界瓦片坐标）
+    pub center: Vec2,
+    /// 每个贴图像素对应的瓦片数
+    pub zoom: f32,
+    /// 地图贴图
+    pub image: Handle<Image>,
+    /// 视图或数据有变化，需要重绘
+    pub dirty: bool,
+    /// 打开地图前玩家能否移动，关闭时还原
+    player_could_move: Option<bool>,
+}
+
+impl WorldMapView {
+    /// 视图覆盖的瓦片范围，返回左下角与边长
+    pub fn bounds(&self, resolution: u32) -> (Vec2, f32) {
+        let span = resolution as f32 * self.zoom;
+        (self.center - Vec2::splat(span * 0.5), span)
+    }
+
+    /// 世界瓦片坐标在地图上的位置（百分比，原点在左上角），视图外返回 None
+    pub fn to_percent(&self, resolution: u32, tile: Vec2) -> Option<Vec2> {
+        let (min, span) = self.bounds(resolution);
+        let relative = (tile - min) / span;
+        if relative.x < 0.0 || relative.y < 0.0 || relative.x > 1.0 || relative.y > 1.0 {
+            return None;
+        }
+        Some(Vec2::new(relative.x, 1.0 - relative.y) * 100.0)
+    }
+
+    /// 地图上的相对位置（0.0-1.0，原点在左上角）对应的世界瓦片坐标
+    pub fn tile_at(&self, resolution: u32, relative: Vec2) -> Vec2 {
+        let (min, span) = self.bounds(resolution);
+        min + Vec2::new(relative.x, 1.0 - relative.y) * span
+    }
+}
+
+/// 光标在画布上的相对位置（0.0-1.0，原点在左上角），不在画布上时返回 None
+pub fn canvas_relative(
+    cursor: Vec2,
+    node: &ComputedNode,
+    transform: &GlobalTransform,
+) -> Option<Vec2> {
+    // 界面节点的尺寸与位置是物理像素，光标是逻辑像素
+    let scale = node.inverse_scale_factor();
+    let size = node.size() * scale;
+    if size.x <= 0.0 || size.y <= 0.0 {
+        return None;
+    }
+    let top_left = transform.translation().truncate() * scale - size * 0.5;
+    let relative = (cursor - top_left) / size;
+    (relative.cmpge(Vec2::ZERO).all() && relative.cmple(Vec2::ONE).all()).then_some(relative)
+}
+
+/// 世界地图界面根节点
+#[derive(Component)]
+pub struct WorldMapUi;
+
+/// 地图画布，显示地图贴图
+#[derive(Component)]
+pub struct WorldMapCanvas;
+
+/// 标记层，重绘时整层重建
+#[derive(Component)]
+pub struct WorldMapMarkers;
+
+/// 创建地图贴图
+pub fn setup_world_map(
+    settings: Res<WorldMapSettings>,
+    mut images: ResMut<Assets<Image>>,
+    mut view: ResMut<WorldMapView>,
+) {
+    let size = settings.resolution.max(16);
+    view.image = images.add(Image::new_fill(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &settings.fog_color,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    ));
+    view.zoom = settings.default_zoom;
+}
+
+/// 按地图键开关世界地图，打开时退出键也可关闭
+///
+/// 编辑标注备注时按键都用于输入文字，不开关地图
+pub fn toggle_world_map(
+    input_state: Res<InputState>,
+    editor: Res<PinEditor>,
+    state: Res<State<WorldMapState>>,
+    mut next_state: ResMut<NextState<WorldMapState>>,
+) {
+    if editor.is_editing() {
+        return;
+    }
+    let open = *state.get() == WorldMapState::Open;
+    if input_state.is_action_just_pressed(GameAction::OpenMap) {
+        next_state.set(if open {
+            WorldMapState::Closed
+        } else {
+            WorldMapState::Open
+        });
+    } else if open && input_state.is_action_just_pressed(GameAction::ExitGame) {
+        next_state.set(WorldMapState::Closed);
+    }
+}
+
+/// 打开世界地图：以玩家为中心生成界面，并让玩家停下
+pub fn open_world_map(
+    mut commands: Commands,
+    settings: Res<WorldMapSettings>,
+    mut view: ResMut<WorldMapView>,
+    mut players: Query<(&Transform, &mut Character), With<Player>>,
+    mut sounds: EventWriter<UiSound>,
+) {
+    sounds.send(UiSound::Open);
+    if let Ok((transform, mut character)) = players.get_single_mut() {
+        view.center = transform.translation.truncate() / TILE_PIXELS;
+        view.player_could_move = Some(character.can_move);
+        character.can_move = false;
+    }
+    view.zoom = view.zoom.clamp(settings.min_zoom, settings.max_zoom);
+    view.dirty = true;
+
+    commands
+        .spawn((
+            WorldMapUi,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(8.0),
+                ..default()
+            },
+            overlay(),
+            GlobalZIndex(10),
+        ))
+        .with_children(|root| {
+            root.spawn((
+                WorldMapCanvas,
+                Node {
+                    height: Val::Percent(85.0),
+                    aspect_ratio: Some(1.0),
+                    border: UiRect::all(Val::Px(3.0)),
+                    ..default()
+                },
+                frame(),
+                ImageNode::new(view.image.clone()),
+            ))
+            .with_children(|canvas| {
+                canvas.spawn((
+                    WorldMapMarkers,
+                    Node {
+                        position_type: PositionType::Absolute,
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                ));
+            });
+            root.spawn(label(
+                "移动键或拖动平移，滚轮缩放，按地图键关闭",
+                TextRole::Muted,
+            ));
+        });
+}
+
+/// 关闭世界地图：移除界面，还原玩家移动
+pub fn close_world_map(
+    mut commands: Commands,
+    mut view: ResMut<WorldMapView>,
+    roots: Query<Entity, With<WorldMapUi>>,
+    mut players: Query<&mut Character, With<Player>>,
+    mut sounds: EventWriter<UiSound>,
+) {
+    sounds.send(UiSound::Close);
+    for root in roots.iter() {
+        commands.entity(root).despawn_recursive();
+    }
+    if let (Some(could_move), Ok(mut character)) =
+        (view.player_could_move.take(), players.get_single_mut())
+    {
+        character.can_move = could_move && character.health > 0.0;
+    }
+}
+
+/// 平移与缩放
+///
+/// 移动键与鼠标拖动平移，缩放键与滚轮缩放
+#[allow(clippy::too_many_arguments)]
+pub fn navigate_world_map(
+    time: Res<Time>,
+    settings: Res<WorldMapSettings>,
+    input_state: Res<InputState>,
+    editor: Res<PinEditor>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut wheel_events: EventReader<MouseWheel>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    canvases: Query<&ComputedNode, With<WorldMapCanvas>>,
+    mut view: ResMut<WorldMapView>,
+    mut last_cursor: Local<Option<Vec2>>,
+) {
+    // 输入标注备注时移动键与缩放键都用于打字
+    let typing = editor.is_editing();
+    let mut pan = Vec2::ZERO;
+    if !typing {
+        if input_state.is_action_active(GameAction::MoveForward) {
+            pan.y += 1.0;
+        }
+        if input_state.is_action_active(GameAction::MoveBackward) {
+            pan.y -= 1.0;
+        }
+        if input_state.is_action_active(GameAction::MoveLeft) {
+            pan.x -= 1.0;
+        }
+        if input_state.is_action_active(GameAction::MoveRight) {
+            pan.x += 1.0;
+        }
+    }
+    let mut offset = pan.normalize_or_zero() * settings.pan_speed * view.zoom * time.delta_secs();
+
+    // 拖动时地图跟着鼠标走，屏幕像素按画布大小换算成瓦片
+    let cursor = windows.get_single().ok().and_then(Window::cursor_position);
+    if mouse_buttons.pressed(MouseButton::Left) {
+        if let (Some(cursor), Some(last), Ok(canvas)) =
+            (cursor, *last_cursor, canvases.get_single())
+        {
+            let width = canvas.size().x * canvas.inverse_scale_factor();
+            if width > 0.0 {
+                let tiles_per_pixel = settings.resolution as f32 * view.zoom / width;
+                let delta = cursor - last;
+                // 屏幕坐标 y 轴向下
+                offset -= Vec2::new(delta.x, -delta.y) * tiles_per_pixel;
+            }
+        }
+    }
+    *last_cursor = cursor;
+
+    let mut steps = 0.0;
+    if !typing && input_state.is_action_just_pressed(GameAction::ZoomIn) {
+        steps += 1.0;
+    }
+    if !typing && input_state.is_action_just_pressed(GameAction::ZoomOut) {
+        steps -= 1.0;
+    }
+    for event in wheel_events.read() {
+        steps += match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 40.0,
+        };
+    }
+
+    if offset != Vec2::ZERO {
+        view.center += offset;
+        view.dirty = true;
+    }
+    if steps != 0.0 {
+        let zoom = (view.zoom / settings.zoom_step.powf(steps))
+            .clamp(settings.min_zoom, settings.max_zoom);
+        if zoom != view.zoom {
+            view.zoom = zoom;
+            view.dirty = true;
+        }
+    }
+}
+
+/// 在标记层上放一个标记，返回标记实体以便附加组件
+fn spawn_map_marker<'a>(
+    parent: &'a mut ChildBuilder,
+    percent: Vec2,
+    symbol: &str,
+    text: &str,
+    color: Color,
+) -> EntityCommands<'a> {
+    let mut marker = parent.spawn(Node {
+        position_type: PositionType::Absolute,
+        left: Val::Percent(percent.x),
+        top: Val::Percent(percent.y),
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Center,
+        // 大致让符号中心对准坐标
+        margin: UiRect::new(Val::Px(-6.0), Val::ZERO, Val::Px(-8.0), Val::ZERO),
+        ..default()
+    });
+    marker.with_children(|marker| {
+        marker.spawn(glyph(symbol, 14.0, color));
+        if !text.is_empty() {
+            marker.spawn(label(text, TextRole::Small));
+        }
+    });
+    marker
+}
+
+/// 重绘世界地图
+///
+/// # 设计思路
+/// 1. 底图按已探索区块的缩略图着色，未探索区域是迷雾
+/// 2. 固定场景只在所在区块探索过后显示，已发现的兴趣点始终显示
+/// 3. 任务标记与玩家标注不受迷雾影响，方便指引玩家前往未知区域
+/// 4. 玩家标注是按钮，点击后打开编辑面板
+#[allow(clippy::too_many_arguments)]
+pub fn redraw_world_map(
+    mut commands: Commands,
+    settings: Res<WorldMapSettings>,
+    mut view: ResMut<WorldMapView>,
+    mut images: ResMut<Assets<Image>>,
+    explored: Option<Res<ExploredChunks>>,
+    poi_registry: Option<Res<PoiRegistry>>,
+    quest_markers: Option<Res<QuestMarkers>>,
+    pins: Option<Res<MapPins>>,
+    map_manager: Option<Res<MapManager>>,
+    players: Query<&Transform, With<Player>>,
+    layers: Query<Entity, With<WorldMapMarkers>>,
+) {
+    let data_changed = explored.as_ref().is_some_and(|e| e.is_changed())
+        || pins.as_ref().is_some_and(|p| p.is_changed());
+    let Ok(layer) = layers.get_single() else {
+        return;
+    };
+    if !view.dirty && !data_changed {
+        return;
+    }
+    view.dirty = false;
+
+    let Some(image) = images.get_mut(&view.image) else {
+        return;
+    };
+    let size = image.width();
+    let (min, _) = view.bounds(size);
+    let chunk_size = CHUNK_SIZE as i32;
+
+    for y in 0..size {
+        for x in 0..size {
+            let tile = (min + (Vec2::new(x as f32, y as f32) + 0.5) * view.zoom)
+                .floor()
+                .as_ivec2();
+            let coord = ChunkCoord {
+                x: tile.x.div_euclid(chunk_size),
+                y: tile.y.div_euclid(chunk_size),
+            };
+            let color = explored
+                .as_ref()
+                .and_then(|explored| explored.thumbnail(coord))
+                .map_or(settings.fog_color, |thumbnail| {
+                    let local = tile.rem_euclid(IVec2::splat(chunk_size)).as_uvec2();
+                    let (tile, height) = thumbnail.sample(local);
+                    minimap_color(TileType::from_u8(tile), height)
+                });
+            // 贴图第一行在最上方，世界坐标 y 轴向上
+            let index = (((size - 1 - y) * size + x) * 4) as usize;
+            image.data[index..index + 4].copy_from_slice(&color);
+        }
+    }
+
+    let is_explored = |tile: IVec2| {
+        explored.as_ref().is_some_and(|explored| {
+            explored.is_explored(ChunkCoord {
+                x: tile.x.div_euclid(chunk_size),
+                y: tile.y.div_euclid(chunk_size),
+            })
+        })
+    };
+    let center = |tile: IVec2| tile.as_vec2() + 0.5;
+
+    commands.entity(layer).despawn_descendants();
+    commands.entity(layer).with_children(|parent| {
+        if let Some(map_manager) = &map_manager {
+            for (position, scene) in map_manager.fixed_scenes() {
+                if !is_explored(*position) {
+                    continue;
+                }
+                let Some(percent) = view.to_percent(size, center(*position)) else {
+                    continue;
+                };
+                let name = match (poi_registry.as_ref().and_then(|r| r.get(*position)), scene) {
+                    (Some(poi), _) => poi.name.clone(),
+                    (None, FixedSceneRef::Builtin(kind)) => default_poi_name(*kind).to_string(),
+                    (None, FixedSceneRef::Prefab(id)) => id.clone(),
+                };
+                spawn_map_marker(parent, percent, "■", &name, Color::srgb(0.95, 0.8, 0.4));
+            }
+        }
+
+        if let Some(registry) = &poi_registry {
+            for poi in registry.discovered() {
+                let fixed = map_manager
+                    .as_ref()
+                    .is_some_and(|m| m.fixed_scenes().contains_key(&poi.position));
+                if fixed {
+                    continue;
+                }
+                if let Some(percent) = view.to_percent(size, center(poi.position)) {
+                    spawn_map_marker(parent, percent, "◆", &poi.name, Color::srgb(0.5, 0.85, 1.0));
+                }
+            }
+        }
+
+        if let Some(markers) = &quest_markers {
+            for marker in markers.iter() {
+                if let Some(percent) = view.to_percent(size, center(marker.position)) {
+                    let tracked = markers.tracked() == Some(marker.quest_id.as_str());
+                    let color = if tracked {
+                        Color::srgb(1.0, 0.85, 0.1)
+                    } else {
+                        Color::srgb(0.85, 0.7, 0.3)
+                    };
+                    spawn_map_marker(parent, percent, "！", &marker.label, color);
+                }
+            }
+        }
+
+        if let Some(pins) = &pins {
+            for pin in pins.iter() {
+                if let Some(percent) = view.to_percent(size, center(pin.position)) {
+                    spawn_map_marker(
+                        parent,
+                        percent,
+                        pin.icon.glyph(),
+                        &pin.note,
+                        Color::srgb(1.0, 0.55, 0.35),
+                    )
+                    .insert((Button, MapPinMarker { id: pin.id }));
+                }
+            }
+        }
+
+        if let Ok(transform) = players.get_single() {
+            let tile = transform.translation.truncate() / TILE_PIXELS;
+            if let Some(percent) = view.to_percent(size, tile) {
+                spawn_map_marker(parent, percent, "●", "", Color::srgb(0.3, 1.0, 0.4));
+            }
+        }
+    });
+}
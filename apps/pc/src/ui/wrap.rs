@@ -0,0 +1,156 @@
+/// 不能出现在行首的标点（避头），折行时挂在上一行末尾
+const NO_LINE_START: &[char] = &[
+    '，', '。', '、', '；', '：', '！', '？', '）', '」', '』', '》', '〉', '】', '”', '’', '…',
+    '—', ',', '.', ';', ':', '!', '?', ')', ']', '}',
+];
+
+/// 不能出现在行尾的标点（避尾），折行时移到下一行开头
+const NO_LINE_END: &[char] = &['（', '「', '『', '《', '〈', '【', '“', '‘', '(', '[', '{'];
+
+/// 折行结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WrappedText {
+    /// 折好的各行
+    pub lines: Vec<String>,
+    /// 最宽一行的列数（半角字符为 1 列，全角字符为 2 列）
+    pub columns: usize,
+}
+
+impl WrappedText {
+    /// 以换行符拼接各行
+    pub fn joined(&self) -> String {
+        self.lines.join("\n")
+    }
+}
+
+/// 字符占用的列数
+///
+/// 中日韩文字与全角标点占两列，其余占一列。
+/// 省略号和破折号在中文字体中按全角绘制，也算两列
+pub fn char_columns(c: char) -> usize {
+    let code = c as u32;
+    let wide = matches!(
+        code,
+        0x1100..=0x115F
+            | 0x2014
+            | 0x2026
+            | 0x2E80..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+    );
+    if wide {
+        2
+    } else {
+        1
+    }
+}
+
+/// 字符串占用的列数
+pub fn text_columns(text: &str) -> usize {
+    text.chars().map(char_columns).sum()
+}
+
+/// 按列数折行
+///
+/// # 设计思路
+/// 1. 西文单词整体换行，过长的单词才在中间断开
+/// 2. 中日韩文字逐字可断，不依赖空格
+/// 3. 避头标点挂在上一行末尾（允许略超出一列），避尾标点随下一行
+/// 4. 原文中的换行符保留为强制换行
+pub fn wrap_text(text: &str, max_columns: usize) -> WrappedText {
+    let max_columns = max_columns.max(2);
+    let mut lines = Vec::new();
+
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut width = 0;
+
+        for token in tokenize(paragraph) {
+            if token.chars().all(char::is_whitespace) {
+                if !line.is_empty() && width < max_columns {
+                    line.push(' ');
+                    width += 1;
+                }
+                continue;
+            }
+
+            let token_width = text_columns(token);
+            let first = token.chars().next().unwrap_or(' ');
+
+            // 避头标点直接挂在行尾
+            if width + token_width > max_columns
+                && !line.is_empty()
+                && !NO_LINE_START.contains(&first)
+            {
+                let carried = take_line_end(&mut line);
+                lines.push(line.trim_end().to_string());
+                line = carried;
+                width = text_columns(&line);
+            }
+
+            if token_width > max_columns {
+                // 过长的单词按列硬断
+                for c in token.chars() {
+                    let c_width = char_columns(c);
+                    if width + c_width > max_columns && !line.is_empty() {
+                        lines.push(std::mem::take(&mut line));
+                        width = 0;
+                    }
+                    line.push(c);
+                    width += c_width;
+                }
+            } else {
+                line.push_str(token);
+                width += token_width;
+            }
+        }
+
+        lines.push(line.trim_end().to_string());
+    }
+
+    let columns = lines
+        .iter()
+        .map(|line| text_columns(line))
+        .max()
+        .unwrap_or(0);
+    WrappedText { lines, columns }
+}
+
+/// 拆出行尾的避尾标点，带到下一行
+fn take_line_end(line: &mut String) -> String {
+    let mut carried = Vec::new();
+    while let Some(c) = line.chars().last() {
+        if !NO_LINE_END.contains(&c) || line.chars().count() == 1 {
+            break;
+        }
+        line.pop();
+        carried.push(c);
+    }
+    carried.into_iter().rev().collect()
+}
+
+/// 切分为可断行的最小单元：西文单词、连续空白、单个全角字符
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (index, c) in text.char_indices() {
+        let breakable = c.is_whitespace() || char_columns(c) == 2;
+        if breakable {
+            if let Some(begin) = start.take() {
+                tokens.push(&text[begin..index]);
+            }
+            tokens.push(&text[index..index + c.len_utf8()]);
+        } else if start.is_none() {
+            start = Some(index);
+        }
+    }
+    if let Some(begin) = start {
+        tokens.push(&text[begin..]);
+    }
+
+    tokens
+}
@@ -0,0 +1,315 @@
+use bevy::prelude::*;
+
+use super::{CaravanDef, CaravanLibrary, CaravanRoute};
+
+/// 单次模拟最多经过的状态变化次数
+const MAX_SIMULATION_STEPS: usize = 256;
+
+/// 商队状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CaravanStatus {
+    /// 在站点停留，到时出发
+    Resting { until: f64 },
+    /// 在路上
+    Traveling,
+    /// 被劫，到时在起点重新组队
+    Robbed { restock_at: f64 },
+}
+
+/// 单支商队的模拟状态
+///
+/// 位置完全由所在路段与行进距离决定，不依赖实体，
+/// 区块未加载时照常推进，加载后按此状态摆放成员
+#[derive(Debug, Clone)]
+pub struct CaravanState {
+    /// 商队定义下标
+    pub def: usize,
+    /// 商路下标
+    pub route: usize,
+    /// 出发（或停留）的站点
+    pub stop: usize,
+    /// 是否沿站点顺序正向行进
+    pub forward: bool,
+    /// 当前路段已行进的距离（瓦片）
+    pub progress: f32,
+    pub status: CaravanStatus,
+    /// 自上一个停留站点出发后行进的距离（瓦片）
+    pub traveled: f32,
+    /// 其中玩家随行的距离（瓦片）
+    pub escorted: f32,
+    /// 玩家是否在附近，由实体系统每帧更新
+    pub player_nearby: bool,
+    /// 遇袭停下，护卫应战期间不再前进
+    pub halted: bool,
+    /// 已生成的成员实体
+    pub members: Vec<Entity>,
+}
+
+impl CaravanState {
+    fn new(def: usize, route: usize, until: f64) -> Self {
+        Self {
+            def,
+            route,
+            stop: 0,
+            forward: true,
+            progress: 0.0,
+            status: CaravanStatus::Resting { until },
+            traveled: 0.0,
+            escorted: 0.0,
+            player_nearby: false,
+            halted: false,
+            members: Vec::new(),
+        }
+    }
+
+    /// 下一个站点，走到尽头时折返
+    pub fn next_stop(&self, route: &CaravanRoute) -> usize {
+        let last = route.stops.len().saturating_sub(1);
+        match (self.forward, self.stop) {
+            (true, stop) if stop < last => stop + 1,
+            (true, stop) => stop.saturating_sub(1),
+            (false, 0) => last.min(1),
+            (false, stop) => stop - 1,
+        }
+    }
+
+    /// 当前位置（世界瓦片坐标）
+    pub fn position(&self, route: &CaravanRoute) -> Vec2 {
+        match self.status {
+            CaravanStatus::Traveling => {
+                route.point_on_leg(self.stop, self.next_stop(route), self.progress)
+            }
+            _ => route.point_on_leg(self.stop, self.stop, 0.0),
+        }
+    }
+
+    /// 行进方向，停留时朝向下一站
+    pub fn heading(&self, route: &CaravanRoute) -> Vec2 {
+        let from = route.point_on_leg(self.stop, self.stop, 0.0);
+        let to = route.point_on_leg(self.next_stop(route), self.next_stop(route), 0.0);
+        (to - from).try_normalize().unwrap_or(Vec2::X)
+    }
+
+    /// 从 `from` 模拟到 `to`（历法总小时数），可一次跨越多个路段
+    fn simulate(
+        &mut self,
+        def: &CaravanDef,
+        route: &CaravanRoute,
+        from: f64,
+        to: f64,
+        escort_ratio: f32,
+        events: &mut Vec<CaravanEvent>,
+    ) {
+        let mut now = from;
+        let speed = def.speed.max(0.1) as f64;
+
+        // 站点重合时路段长度为 0，限制步数以免空转
+        for _ in 0..MAX_SIMULATION_STEPS {
+            if now >= to {
+                return;
+            }
+            match self.status {
+                CaravanStatus::Robbed { restock_at } => {
+                    if restock_at > to {
+                        return;
+                    }
+                    now = now.max(restock_at);
+                    self.stop = 0;
+                    self.forward = true;
+                    self.progress = 0.0;
+                    self.traveled = 0.0;
+                    self.escorted = 0.0;
+                    self.halted = false;
+                    self.status = CaravanStatus::Resting { until: now };
+                }
+                CaravanStatus::Resting { until } => {
+                    if until > to {
+                        return;
+                    }
+                    now = now.max(until);
+                    self.status = CaravanStatus::Traveling;
+                    self.progress = 0.0;
+                    // 途经路口继续赶路，不算出发
+                    if self.traveled <= 0.0 {
+                        events.push(CaravanEvent::Departed {
+                            caravan: def.id.clone(),
+                            from: route.stops[self.stop].name.clone(),
+                            to: route.stops[self.next_stop(route)].name.clone(),
+                        });
+                    }
+                }
+                CaravanStatus::Traveling => {
+                    if self.halted {
+                        return;
+                    }
+                    let next = self.next_stop(route);
+                    let length = route.leg_length(self.stop, next);
+                    let hours_left = (length - self.progress).max(0.0) as f64 / speed;
+                    if now + hours_left > to {
+                        let moved = ((to - now) * speed) as f32;
+                        self.progress += moved;
+                        self.traveled += moved;
+                        if self.player_nearby {
+                            self.escorted += moved;
+                        }
+                        return;
+                    }
+
+                    now += hours_left;
+                    let moved = (length - self.progress).max(0.0);
+                    self.traveled += moved;
+                    if self.player_nearby {
+                        self.escorted += moved;
+                    }
+
+                    // 走到尽头时折返
+                    let last = route.stops.len() - 1;
+                    if next == last || next == 0 {
+                        self.forward = next == 0;
+                    }
+                    self.stop = next;
+                    self.progress = 0.0;
+                    let stop = &route.stops[next];
+                    self.status = CaravanStatus::Resting {
+                        until: now + stop.rest_hours.max(0.0) as f64,
+                    };
+                    // 途经的路口不算到站
+                    if stop.rest_hours > 0.0 {
+                        events.push(CaravanEvent::Arrived {
+                            caravan: def.id.clone(),
+                            stop: stop.name.clone(),
+                            escorted: self.traveled > 0.0
+                                && self.escorted >= self.traveled * escort_ratio,
+                        });
+                        self.traveled = 0.0;
+                        self.escorted = 0.0;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 所有商队
+#[derive(Resource, Debug, Default)]
+pub struct Caravans {
+    pub caravans: Vec<CaravanState>,
+    /// 上次模拟到的历法总小时数
+    last_hours: Option<f64>,
+}
+
+impl Caravans {
+    /// 按商队数据建立初始状态，各商队错开出发时间
+    pub fn from_library(library: &CaravanLibrary, now: f64) -> Self {
+        let caravans = library
+            .caravans
+            .iter()
+            .enumerate()
+            .filter_map(|(index, def)| {
+                let route = library.route_index(&def.route)?;
+                (library.routes[route].leg_count() > 0)
+                    .then(|| CaravanState::new(index, route, now + index as f64 * 2.0))
+            })
+            .collect();
+        Self {
+            caravans,
+            last_hours: Some(now),
+        }
+    }
+
+    /// 推进到给定时刻，时间跳过（休息、读档）时一次补齐
+    pub fn advance(
+        &mut self,
+        library: &CaravanLibrary,
+        now: f64,
+        escort_ratio: f32,
+    ) -> Vec<CaravanEvent> {
+        let mut events = Vec::new();
+        let from = self.last_hours.unwrap_or(now);
+        self.last_hours = Some(now);
+        if now <= from {
+            return events;
+        }
+
+        for caravan in &mut self.caravans {
+            let def = &library.caravans[caravan.def];
+            let route = &library.routes[caravan.route];
+            caravan.simulate(def, route, from, now, escort_ratio, &mut events);
+        }
+        events
+    }
+
+    /// 商队被劫
+    pub fn rob(&mut self, index: usize, def: &CaravanDef, now: f64) {
+        if let Some(caravan) = self.caravans.get_mut(index) {
+            caravan.status = CaravanStatus::Robbed {
+                restock_at: now + def.restock_hours.max(0.0) as f64,
+            };
+            caravan.halted = false;
+        }
+    }
+}
+
+/// 商队事件，供任务与提示使用
+#[derive(Event, Debug, Clone)]
+pub enum CaravanEvent {
+    /// 从站点出发
+    Departed {
+        caravan: String,
+        from: String,
+        to: String,
+    },
+    /// 到达停留的站点，`escorted` 表示玩家全程随行护送
+    Arrived {
+        caravan: String,
+        stop: String,
+        escorted: bool,
+    },
+}
+
+/// 商队成员职责
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaravanRole {
+    Merchant,
+    Guard,
+    Mule,
+}
+
+/// 商队成员
+#[derive(Component, Debug, Clone, Copy)]
+pub struct CaravanMember {
+    /// 商队下标
+    pub caravan: usize,
+    pub role: CaravanRole,
+    /// 在队形中的位置
+    pub slot: usize,
+}
+
+impl CaravanMember {
+    /// 队形偏移（瓦片），x 沿行进方向，y 指向左侧
+    ///
+    /// 商人居中，骡子跟在后面，护卫在前后两侧交替排开
+    pub fn formation_offset(&self) -> Vec2 {
+        match self.role {
+            CaravanRole::Merchant => Vec2::ZERO,
+            CaravanRole::Mule => Vec2::new(-1.5 * (self.slot + 1) as f32, 0.0),
+            CaravanRole::Guard => {
+                let row = (self.slot / 2) as f32;
+                let side = if self.slot.is_multiple_of(2) {
+                    1.0
+                } else {
+                    -1.0
+                };
+                Vec2::new(1.5 - row * 2.0, side * 1.2)
+            }
+        }
+    }
+}
+
+/// 悬赏
+///
+/// 劫掠商队的角色身上带有悬赏，商队护卫见到会主动出手
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Bounty {
+    pub amount: u32,
+}
@@ -0,0 +1,16 @@
+/// 商队模块
+///
+/// 商人带着护卫与骡子沿商路在城镇之间往返，可以随行护送，也可能被劫
+///
+/// # 模块组成
+/// 1. route：商路、站点与商队定义的数据
+/// 2. caravan：商队的离线模拟状态、成员组件与事件
+/// 3. systems：商队插件，负责模拟、成员生成与遇袭处理
+#[allow(clippy::module_inception)]
+mod caravan;
+mod route;
+mod systems;
+
+pub use caravan::*;
+pub use route::*;
+pub use systems::*;
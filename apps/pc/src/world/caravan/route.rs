@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::fs;
+
+/// 商队数据路径
+pub const CARAVAN_DATA_PATH: &str = "src/config/caravans.json";
+
+/// 路线上的站点
+#[derive(Debug, Clone, Deserialize)]
+pub struct RouteStop {
+    pub name: String,
+    /// 世界瓦片坐标
+    pub position: [i32; 2],
+    /// 在此停留的游戏小时数，途经的路口为 0
+    #[serde(default)]
+    pub rest_hours: f32,
+}
+
+impl RouteStop {
+    pub fn tile(&self) -> Vec2 {
+        Vec2::new(self.position[0] as f32, self.position[1] as f32)
+    }
+}
+
+/// 商路
+///
+/// 按顺序连接的站点，商队沿路往返，站点之间按直线行进
+#[derive(Debug, Clone, Deserialize)]
+pub struct CaravanRoute {
+    pub id: String,
+    pub name: String,
+    pub stops: Vec<RouteStop>,
+}
+
+impl CaravanRoute {
+    /// 路段数量
+    pub fn leg_count(&self) -> usize {
+        self.stops.len().saturating_sub(1)
+    }
+
+    /// 两个站点之间的路段长度（瓦片）
+    pub fn leg_length(&self, from: usize, to: usize) -> f32 {
+        match (self.stops.get(from), self.stops.get(to)) {
+            (Some(a), Some(b)) => a.tile().distance(b.tile()),
+            _ => 0.0,
+        }
+    }
+
+    /// 路段上已行进 `progress` 瓦片处的位置
+    pub fn point_on_leg(&self, from: usize, to: usize, progress: f32) -> Vec2 {
+        match (self.stops.get(from), self.stops.get(to)) {
+            (Some(a), Some(b)) => {
+                let length = a.tile().distance(b.tile());
+                if length <= f32::EPSILON {
+                    a.tile()
+                } else {
+                    a.tile().lerp(b.tile(), (progress / length).clamp(0.0, 1.0))
+                }
+            }
+            (Some(a), None) => a.tile(),
+            _ => Vec2::ZERO,
+        }
+    }
+}
+
+fn default_speed() -> f32 {
+    40.0
+}
+
+fn default_restock_hours() -> f32 {
+    72.0
+}
+
+fn default_bounty() -> u32 {
+    100
+}
+
+/// 商队定义
+#[derive(Debug, Clone, Deserialize)]
+pub struct CaravanDef {
+    pub id: String,
+    /// 所走的商路ID
+    pub route: String,
+    /// 商人名称
+    pub merchant: String,
+    /// 护卫人数
+    #[serde(default)]
+    pub guards: u32,
+    /// 驮货的骡子数量
+    #[serde(default)]
+    pub mules: u32,
+    /// 行进速度（瓦片/游戏小时）
+    #[serde(default = "default_speed")]
+    pub speed: f32,
+    /// 被劫后重新组队出发所需的游戏小时数
+    #[serde(default = "default_restock_hours")]
+    pub restock_hours: f32,
+    /// 劫掠此商队的悬赏金额
+    #[serde(default = "default_bounty")]
+    pub bounty: u32,
+}
+
+/// 商路与商队数据
+#[derive(Resource, Debug, Clone, Default, Deserialize)]
+pub struct CaravanLibrary {
+    pub routes: Vec<CaravanRoute>,
+    pub caravans: Vec<CaravanDef>,
+}
+
+impl CaravanLibrary {
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let library: CaravanLibrary = serde_json::from_str(&content)?;
+        Ok(library)
+    }
+
+    /// 按ID查找商路下标
+    pub fn route_index(&self, id: &str) -> Option<usize> {
+        self.routes.iter().position(|route| route.id == id)
+    }
+}
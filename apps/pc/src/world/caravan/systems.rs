@@ -0,0 +1,359 @@
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+use super::{
+    Bounty, CaravanEvent, CaravanLibrary, CaravanMember, CaravanRole, CaravanStatus, Caravans,
+    CARAVAN_DATA_PATH,
+};
+use crate::combat::{DamageEvent, DeathEvent};
+use crate::config::resolve_data_path;
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::gameplay_running;
+use crate::time::GameCalendar;
+use crate::world::chunk::{Chunk, ChunkCoord, CHUNK_SIZE, TILE_PIXELS};
+use crate::world::entity::{spawn_character, spawn_npc, AiState, Npc, NpcType, Player};
+
+/// 商队配置
+#[derive(Resource, Debug, Clone)]
+pub struct CaravanSettings {
+    /// 玩家与商人相距多远以内算随行护送（像素）
+    pub escort_range: f32,
+    /// 随行距离占全程的比例达到多少算护送成功
+    pub escort_ratio: f32,
+    /// 护卫发现带悬赏角色的距离（像素）
+    pub alert_range: f32,
+    /// 遇袭后玩家离开多远商队恢复行进（像素）
+    pub calm_range: f32,
+}
+
+impl Default for CaravanSettings {
+    fn default() -> Self {
+        Self {
+            escort_range: 160.0,
+            escort_ratio: 0.8,
+            alert_range: 192.0,
+            calm_range: 480.0,
+        }
+    }
+}
+
+/// 商队插件
+///
+/// # 设计思路
+/// 1. 商队位置按历法时间在商路上推算，区块未加载时照常赶路，休息跳过的时间一并补齐
+/// 2. 只有商队所在区块已加载时才生成商人、护卫与骡子，卸载时移除
+/// 3. 遇袭时停下应战，商人遇害即视为被劫，凶手背上悬赏
+pub struct CaravanPlugin;
+
+impl Plugin for CaravanPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CaravanSettings>()
+            .add_event::<CaravanEvent>();
+
+        app.add_systems(PreStartup, load_caravan_library)
+            .add_systems(Startup, setup_caravans)
+            .add_systems(
+                Update,
+                (
+                    simulate_caravans,
+                    handle_caravan_deaths,
+                    alert_caravan_guards,
+                    sync_caravan_members,
+                    move_caravan_members,
+                )
+                    .chain()
+                    .run_if(gameplay_running),
+            );
+    }
+}
+
+/// 加载商路与商队数据，失败时不生成商队
+fn load_caravan_library(mut commands: Commands, mut logger: Option<ResMut<GameLogger>>) {
+    let library = match CaravanLibrary::load(&resolve_data_path(CARAVAN_DATA_PATH)) {
+        Ok(library) => library,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("商队数据加载失败: {}", e));
+            }
+            CaravanLibrary::default()
+        }
+    };
+    for def in &library.caravans {
+        if library.route_index(&def.route).is_none() {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(
+                    LogLevel::Error,
+                    &format!("商队 {} 引用了不存在的商路 {}", def.id, def.route),
+                );
+            }
+        }
+    }
+    commands.insert_resource(library);
+}
+
+/// 建立商队初始状态
+fn setup_caravans(
+    mut commands: Commands,
+    library: Res<CaravanLibrary>,
+    calendar: Res<GameCalendar>,
+) {
+    commands.insert_resource(Caravans::from_library(&library, calendar.total_hours()));
+}
+
+/// 按历法时间推进所有商队
+fn simulate_caravans(
+    calendar: Res<GameCalendar>,
+    settings: Res<CaravanSettings>,
+    library: Res<CaravanLibrary>,
+    mut caravans: ResMut<Caravans>,
+    mut events: EventWriter<CaravanEvent>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let now = calendar.total_hours();
+    for event in caravans.advance(&library, now, settings.escort_ratio) {
+        if let Some(logger) = logger.as_mut() {
+            let message = match &event {
+                CaravanEvent::Departed { caravan, from, to } => {
+                    format!("商队 {} 从{}出发前往{}", caravan, from, to)
+                }
+                CaravanEvent::Arrived {
+                    caravan,
+                    stop,
+                    escorted,
+                } => format!(
+                    "商队 {} 抵达{}{}",
+                    caravan,
+                    stop,
+                    if *escorted { "（玩家护送）" } else { "" }
+                ),
+            };
+            logger.log(LogLevel::Debug, &message);
+        }
+        events.send(event);
+    }
+}
+
+/// 商人遇害即视为被劫，玩家凶手背上悬赏
+#[allow(clippy::too_many_arguments)]
+fn handle_caravan_deaths(
+    mut commands: Commands,
+    calendar: Res<GameCalendar>,
+    library: Res<CaravanLibrary>,
+    mut caravans: ResMut<Caravans>,
+    mut deaths: EventReader<DeathEvent>,
+    members: Query<&CaravanMember>,
+    mut players: Query<Option<&mut Bounty>, With<Player>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let now = calendar.total_hours();
+
+    for death in deaths.read() {
+        let Ok(member) = members.get(death.entity) else {
+            continue;
+        };
+        if member.role != CaravanRole::Merchant {
+            continue;
+        }
+        let Some(state) = caravans.caravans.get(member.caravan) else {
+            continue;
+        };
+        let def = &library.caravans[state.def];
+        let route = &library.routes[state.route];
+        if let Some(logger) = logger.as_mut() {
+            logger.log(
+                LogLevel::Debug,
+                &format!("商队 {} 在{}被劫", def.id, route.name),
+            );
+        }
+        caravans.rob(member.caravan, def, now);
+
+        if let Some(robber) = death.killer {
+            if let Ok(bounty) = players.get_mut(robber) {
+                let amount = match bounty {
+                    Some(mut bounty) => {
+                        bounty.amount += def.bounty;
+                        bounty.amount
+                    }
+                    None => {
+                        commands
+                            .entity(robber)
+                            .insert(Bounty { amount: def.bounty });
+                        def.bounty
+                    }
+                };
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(
+                        LogLevel::Info,
+                        &format!("劫掠商队 {}，悬赏升至 {} 两", def.id, amount),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// 商队遇袭或发现带悬赏的玩家时停下应战，玩家走远后继续赶路
+fn alert_caravan_guards(
+    settings: Res<CaravanSettings>,
+    library: Res<CaravanLibrary>,
+    mut caravans: ResMut<Caravans>,
+    mut damage_events: EventReader<DamageEvent>,
+    players: Query<(Entity, &Transform, Option<&Bounty>), With<Player>>,
+    mut members: Query<(&CaravanMember, &mut Npc)>,
+) {
+    let mut alerted = HashSet::new();
+    for event in damage_events.read() {
+        let from_player = event.source.is_some_and(|source| players.contains(source));
+        if let (true, Ok((member, _))) = (from_player, members.get(event.target)) {
+            alerted.insert(member.caravan);
+        }
+    }
+
+    let player = players.get_single().ok();
+    for (index, caravan) in caravans.caravans.iter_mut().enumerate() {
+        if caravan.members.is_empty() {
+            continue;
+        }
+        let route = &library.routes[caravan.route];
+        let position = caravan.position(route) * TILE_PIXELS;
+        let distance = player
+            .map(|(_, transform, _)| transform.translation.truncate().distance(position))
+            .unwrap_or(f32::INFINITY);
+        let wanted = player.is_some_and(|(_, _, bounty)| bounty.is_some_and(|b| b.amount > 0));
+
+        if alerted.contains(&index) || (wanted && distance <= settings.alert_range) {
+            caravan.halted = true;
+        } else if caravan.halted && distance > settings.calm_range {
+            caravan.halted = false;
+        } else {
+            continue;
+        }
+
+        for (member, mut npc) in members.iter_mut() {
+            if member.caravan != index {
+                continue;
+            }
+            npc.ai_state = match (caravan.halted, member.role) {
+                (true, CaravanRole::Guard) => AiState::Chase,
+                (true, _) => AiState::Flee,
+                (false, _) => AiState::Patrol,
+            };
+        }
+    }
+}
+
+/// 商队所在区块加载时生成成员，区块卸载或商队被劫时移除
+fn sync_caravan_members(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    library: Res<CaravanLibrary>,
+    mut caravans: ResMut<Caravans>,
+    chunks: Query<&Chunk>,
+) {
+    let loaded: HashSet<ChunkCoord> = chunks
+        .iter()
+        .filter(|chunk| chunk.data.is_some())
+        .map(|chunk| chunk.coord)
+        .collect();
+
+    for (index, caravan) in caravans.caravans.iter_mut().enumerate() {
+        let route = &library.routes[caravan.route];
+        let tile = caravan.position(route).floor().as_ivec2();
+        let chunk = ChunkCoord {
+            x: tile.x.div_euclid(CHUNK_SIZE as i32),
+            y: tile.y.div_euclid(CHUNK_SIZE as i32),
+        };
+        let present =
+            !matches!(caravan.status, CaravanStatus::Robbed { .. }) && loaded.contains(&chunk);
+
+        if !present {
+            for member in caravan.members.drain(..) {
+                if let Some(entity) = commands.get_entity(member) {
+                    entity.despawn_recursive();
+                }
+            }
+            caravan.player_nearby = false;
+            continue;
+        }
+        if !caravan.members.is_empty() {
+            continue;
+        }
+
+        let def = &library.caravans[caravan.def];
+        let position = (caravan.position(route) * TILE_PIXELS).extend(1.0);
+        let mut spawn = |commands: &mut Commands, entity: Entity, role, slot| {
+            commands.entity(entity).insert(CaravanMember {
+                caravan: index,
+                role,
+                slot,
+            });
+            caravan.members.push(entity);
+        };
+
+        let merchant = spawn_npc(
+            &mut commands,
+            &asset_server,
+            position,
+            NpcType::Merchant,
+            &def.merchant,
+        );
+        spawn(&mut commands, merchant, CaravanRole::Merchant, 0);
+        for slot in 0..def.guards as usize {
+            let guard = spawn_npc(
+                &mut commands,
+                &asset_server,
+                position,
+                NpcType::Guard,
+                "镖师",
+            );
+            spawn(&mut commands, guard, CaravanRole::Guard, slot);
+        }
+        for slot in 0..def.mules as usize {
+            let mule = spawn_character(
+                &mut commands,
+                &asset_server,
+                position,
+                "骡子",
+                "textures/characters/mule.png",
+            );
+            spawn(&mut commands, mule, CaravanRole::Mule, slot);
+        }
+    }
+}
+
+/// 按商队位置与队形摆放成员，并记录玩家是否随行
+///
+/// 遇袭停下时护卫与商人交给 AI 处理，只有骡子留在原地
+fn move_caravan_members(
+    settings: Res<CaravanSettings>,
+    library: Res<CaravanLibrary>,
+    mut caravans: ResMut<Caravans>,
+    players: Query<&Transform, (With<Player>, Without<CaravanMember>)>,
+    mut members: Query<(&CaravanMember, &mut Transform)>,
+) {
+    let player = players.get_single().ok().map(|t| t.translation.truncate());
+
+    for caravan in caravans.caravans.iter_mut() {
+        if caravan.members.is_empty() {
+            continue;
+        }
+        let route = &library.routes[caravan.route];
+        let center = caravan.position(route);
+        caravan.player_nearby = player
+            .is_some_and(|player| player.distance(center * TILE_PIXELS) <= settings.escort_range);
+        if caravan.halted {
+            continue;
+        }
+
+        let heading = caravan.heading(route);
+        for member in &caravan.members {
+            let Ok((member, mut transform)) = members.get_mut(*member) else {
+                continue;
+            };
+            let offset = member.formation_offset();
+            let tile = center + heading * offset.x + heading.perp() * offset.y;
+            transform.translation.x = tile.x * TILE_PIXELS;
+            transform.translation.y = tile.y * TILE_PIXELS;
+        }
+    }
+}
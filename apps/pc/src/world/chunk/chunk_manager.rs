@@ -1,11 +1,23 @@
+use super::layers::ChunkLayers;
 use super::render::RenderSettings;
-use crate::world::map::{MapManager, TerrainGenerator};
+use super::store::{ChunkStore, CHUNK_STORE_DIR};
+use crate::world::entity::NpcType;
+use crate::world::map::{
+    ClimateChunkSample, MapManager, RiverCell, SceneType, StructureGenerator, TerrainGenerator,
+    TileType, WaterManager, Zone,
+};
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use super::CHUNK_SIZE;
 
+/// 静水坡度到水流强度的换算系数
+const STILL_WATER_FLOW_GAIN: f32 = 4.0;
+
+/// 湖面水流的衰减系数
+const LAKE_FLOW_DAMPING: f32 = 0.2;
+
 /// 区块坐标系统
 /// 使用整数坐标系统的原因：
 /// 1. 精确定位：避免浮点数精度问题
@@ -17,17 +29,58 @@ pub struct ChunkCoord {
     pub y: i32,
 }
 
-/// 区块加载状态
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ChunkLoadState {
-    /// 未加载
-    Unloaded,
-    /// 正在加载
-    Loading,
-    /// 已加载
-    Loaded,
-    /// 正在卸载
-    Unloading,
+/// 区块结构物
+///
+/// 装饰层每个瓦片只能记录一个类型编号，
+/// 需要携带朝向、尺寸等参数的对象放在结构层中随区块一起保存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ChunkStructure {
+    /// 瀑布
+    Waterfall {
+        /// 瀑布顶端的区块内坐标
+        local_x: usize,
+        local_y: usize,
+        /// 瀑布高度
+        height: f32,
+        /// 瀑布宽度（瓦片）
+        width: f32,
+        /// 水流方向（归一化）
+        direction: [f32; 2],
+        /// 水流强度
+        flow_strength: f32,
+        /// 溅水范围
+        splash_range: f32,
+    },
+    /// 村落、城镇等场景中的居民，区块加载时生成对应的NPC
+    Resident {
+        /// 居民所在的区块内坐标
+        local_x: usize,
+        local_y: usize,
+        /// NPC类型
+        npc_type: NpcType,
+        /// NPC名字
+        name: String,
+    },
+    /// 预制场景中的触发区
+    Trigger {
+        /// 触发区中心的区块内坐标
+        local_x: usize,
+        local_y: usize,
+        /// 触发半径（瓦片）
+        radius: f32,
+        /// 触发器ID
+        id: String,
+    },
+    /// 村落、寺庙等场景的中心，供兴趣点登记
+    Landmark {
+        /// 场景中心的区块内坐标
+        local_x: usize,
+        local_y: usize,
+        /// 场景类型
+        scene_type: SceneType,
+        /// 预制场景的显示名称，程序生成的场景为空
+        name: Option<String>,
+    },
 }
 
 /// 区块数据
@@ -39,6 +92,21 @@ pub struct ChunkData {
     heights: Vec<f32>,
     /// 装饰物数据
     decorations: Vec<Option<u8>>,
+    /// 水深数据（海、湖、河），无水为 0
+    #[serde(default)]
+    water_depth: Vec<f32>,
+    /// 水流场，每个瓦片的流向乘以强度 (0.0-1.0)
+    #[serde(default)]
+    flow: Vec<[f32; 2]>,
+    /// 气候区域，生成区块时批量采样得到，反映生成时的季节，供植被、资源等生成步骤使用
+    #[serde(default)]
+    climate_zones: Vec<Option<Zone>>,
+    /// 结构物数据
+    #[serde(default)]
+    structures: Vec<ChunkStructure>,
+    /// 附加数据层，玩法系统按名称存放的逐瓦片数据
+    #[serde(default)]
+    layers: ChunkLayers,
     /// 是否被修改过
     pub modified: bool,
 }
@@ -51,6 +119,11 @@ impl ChunkData {
             tiles: vec![None; size],
             heights: vec![0.0; size],
             decorations: vec![None; size],
+            water_depth: vec![0.0; size],
+            flow: vec![[0.0; 2]; size],
+            climate_zones: vec![None; size],
+            structures: Vec::new(),
+            layers: ChunkLayers::default(),
             modified: false,
         }
     }
@@ -108,6 +181,94 @@ impl ChunkData {
             None
         }
     }
+
+    /// 获取水深
+    pub fn get_water_depth(&self, x: usize, y: usize) -> f32 {
+        if x < CHUNK_SIZE && y < CHUNK_SIZE {
+            let index = y * CHUNK_SIZE + x;
+            // 旧存档没有水深层
+            self.water_depth.get(index).copied().unwrap_or(0.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// 设置水深
+    pub fn set_water_depth(&mut self, x: usize, y: usize, depth: f32) {
+        if x < CHUNK_SIZE && y < CHUNK_SIZE {
+            if self.water_depth.len() != CHUNK_SIZE * CHUNK_SIZE {
+                self.water_depth.resize(CHUNK_SIZE * CHUNK_SIZE, 0.0);
+            }
+            let index = y * CHUNK_SIZE + x;
+            self.water_depth[index] = depth.max(0.0);
+        }
+    }
+
+    /// 获取水流（方向乘以强度）
+    pub fn get_flow(&self, x: usize, y: usize) -> Vec2 {
+        if x < CHUNK_SIZE && y < CHUNK_SIZE {
+            let index = y * CHUNK_SIZE + x;
+            // 旧存档没有水流层
+            self.flow
+                .get(index)
+                .map_or(Vec2::ZERO, |flow| Vec2::from_array(*flow))
+        } else {
+            Vec2::ZERO
+        }
+    }
+
+    /// 设置水流
+    pub fn set_flow(&mut self, x: usize, y: usize, flow: Vec2) {
+        if x < CHUNK_SIZE && y < CHUNK_SIZE {
+            if self.flow.len() != CHUNK_SIZE * CHUNK_SIZE {
+                self.flow.resize(CHUNK_SIZE * CHUNK_SIZE, [0.0; 2]);
+            }
+            let index = y * CHUNK_SIZE + x;
+            self.flow[index] = flow.clamp_length_max(1.0).to_array();
+        }
+    }
+
+    /// 获取气候区域
+    pub fn get_climate_zone(&self, x: usize, y: usize) -> Option<Zone> {
+        if x < CHUNK_SIZE && y < CHUNK_SIZE {
+            let index = y * CHUNK_SIZE + x;
+            // 旧存档没有气候层
+            self.climate_zones.get(index).copied().flatten()
+        } else {
+            None
+        }
+    }
+
+    /// 设置气候区域
+    pub fn set_climate_zone(&mut self, x: usize, y: usize, zone: Zone) {
+        if x < CHUNK_SIZE && y < CHUNK_SIZE {
+            if self.climate_zones.len() != CHUNK_SIZE * CHUNK_SIZE {
+                self.climate_zones.resize(CHUNK_SIZE * CHUNK_SIZE, None);
+            }
+            let index = y * CHUNK_SIZE + x;
+            self.climate_zones[index] = Some(zone);
+        }
+    }
+
+    /// 添加结构物
+    pub fn add_structure(&mut self, structure: ChunkStructure) {
+        self.structures.push(structure);
+    }
+
+    /// 获取全部结构物
+    pub fn structures(&self) -> &[ChunkStructure] {
+        &self.structures
+    }
+
+    /// 获取附加数据层
+    pub fn layers(&self) -> &ChunkLayers {
+        &self.layers
+    }
+
+    /// 获取可修改的附加数据层
+    pub fn layers_mut(&mut self) -> &mut ChunkLayers {
+        &mut self.layers
+    }
 }
 
 impl Default for ChunkData {
@@ -120,21 +281,12 @@ impl Default for ChunkData {
 /// 设计考虑：
 /// 1. 数据分离：将静态数据和动态状态分开存储
 /// 2. 内存优化：使用Option包装大型数据
-/// 3. 状态追踪：记录访问时间和优先级
 #[derive(Debug, Component)]
 pub struct Chunk {
     /// 区块坐标
     pub coord: ChunkCoord,
-    /// 加载状态
-    pub load_state: ChunkLoadState,
     /// 区块数据
     pub data: Option<ChunkData>,
-    /// 实体ID
-    pub entity: Option<Entity>,
-    /// 最后访问时间
-    pub last_accessed: f64,
-    /// 加载优先级
-    pub priority: i32,
 }
 
 /// 区块管理器
@@ -149,22 +301,54 @@ pub struct ChunkManager {
     pub chunks: HashMap<ChunkCoord, Entity>,
     /// 地形生成器
     terrain_generator: Option<TerrainGenerator>,
+    /// 水系管理器（世界空间河网）
+    water_manager: WaterManager,
+    /// 场景结构生成器（村落、城镇、寺庙）
+    structure_generator: StructureGenerator,
     /// 渲染设置
     render_settings: RenderSettings,
     /// 视图距离（以区块为单位）
     pub view_distance: i32,
     /// 玩家当前区块坐标
     pub player_chunk: Option<ChunkCoord>,
-    /// 上次清理时间
-    pub last_cleanup: f64,
     /// 加载队列
     pub loading_queue: Vec<ChunkCoord>,
     /// 内存预算（最大区块数量）
     pub memory_budget: usize,
     /// 每帧加载预算
     pub load_budget: usize,
-    /// 区块大小
-    pub chunk_size: f32,
+    /// 后台预先生成的区块数据，加载区块时优先取用
+    prefetched: HashMap<ChunkCoord, ChunkData>,
+    /// 与当前种子一致的预生成区块存储
+    store: Option<ChunkStore>,
+    /// 启动以来累计加载的区块数
+    total_loaded: u64,
+    /// 启动以来累计卸载的区块数
+    total_unloaded: u64,
+}
+
+/// 区块流式加载的统计，供调试界面显示
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChunkStreamStats {
+    /// 已加载的区块数
+    pub loaded: usize,
+    /// 视野内尚未加载的区块数
+    pub pending: usize,
+    /// 后台预先生成、尚未取用的区块数
+    pub prefetched: usize,
+    /// 内存预算（最大区块数量）
+    pub memory_budget: usize,
+    /// 每帧加载预算
+    pub load_budget: usize,
+    pub total_loaded: u64,
+    pub total_unloaded: u64,
+}
+
+impl ChunkStreamStats {
+    /// 内存预算的占用比例
+    pub fn memory_usage(&self) -> f32 {
+        self.loaded as f32 / self.memory_budget.max(1) as f32
+    }
 }
 
 impl Default for ChunkManager {
@@ -172,14 +356,18 @@ impl Default for ChunkManager {
         Self {
             chunks: HashMap::new(),
             terrain_generator: None,
+            water_manager: WaterManager::default(),
+            structure_generator: StructureGenerator::default(),
             render_settings: RenderSettings::default(),
             view_distance: 5,
             player_chunk: None,
-            last_cleanup: 0.0,
             loading_queue: Vec::new(),
             memory_budget: 100,
-            load_budget: 2,
-            chunk_size: CHUNK_SIZE as f32,
+            load_budget: 5,
+            prefetched: HashMap::new(),
+            store: None,
+            total_loaded: 0,
+            total_unloaded: 0,
         }
     }
 }
@@ -196,13 +384,44 @@ impl ChunkManager {
     /// 初始化地形生成器
     pub fn initialize_terrain_generator(&mut self, map_manager: &MapManager) {
         let terrain_config = map_manager.terrain_config().clone();
+        self.water_manager
+            .set_water_level(terrain_config.water_level);
+        self.water_manager.initialize(map_manager.seed);
         self.terrain_generator = Some(TerrainGenerator::new(map_manager.seed, terrain_config));
+        self.structure_generator =
+            StructureGenerator::new((map_manager.seed as u64).wrapping_add(4));
+        self.structure_generator
+            .set_fixed_scenes(map_manager.fixed_scenes().clone());
+        // 按旧种子预先生成的数据作废，只沿用同一种子预生成的区块存储
+        self.prefetched.clear();
+        self.store = ChunkStore::open(CHUNK_STORE_DIR, map_manager.seed);
 
         // 更新渲染设置
         self.render_settings.enable_2_5d = map_manager.enable_2_5d;
         self.render_settings.height_scale = map_manager.height_scale;
     }
 
+    /// 只带生成器与区块存储的副本，交给后台线程生成区块数据
+    pub fn generator_snapshot(&self) -> Self {
+        Self {
+            terrain_generator: self.terrain_generator.clone(),
+            water_manager: self.water_manager.clone(),
+            structure_generator: self.structure_generator.clone(),
+            store: self.store.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// 放入后台预先生成的区块数据
+    pub fn insert_prefetched(&mut self, coord: ChunkCoord, data: ChunkData) {
+        self.prefetched.insert(coord, data);
+    }
+
+    /// 取出预先生成的区块数据
+    pub fn take_prefetched(&mut self, coord: ChunkCoord) -> Option<ChunkData> {
+        self.prefetched.remove(&coord)
+    }
+
     /// 更新玩家位置
     pub fn update_player_position(&mut self, world_x: f32, world_y: f32) {
         let chunk_x = (world_x / (CHUNK_SIZE as f32 * 32.0)).floor() as i32;
@@ -245,7 +464,7 @@ impl ChunkManager {
         let mut to_unload = Vec::new();
 
         if let Some(player_chunk) = self.player_chunk {
-            for (coord, _) in &self.chunks {
+            for coord in self.chunks.keys() {
                 let dx = (coord.x - player_chunk.x).abs();
                 let dy = (coord.y - player_chunk.y).abs();
 
@@ -259,11 +478,12 @@ impl ChunkManager {
         to_unload
     }
 
-    /// 创建新区块
-    pub fn create_chunk(&mut self, coord: ChunkCoord) -> Entity {
-        let chunk_entity = Entity::from_raw(0); // Placeholder entity, will be replaced later
-        self.chunks.insert(coord, chunk_entity);
-        chunk_entity
+    /// 读取预生成的区块数据，存储中没有或读取失败时现场生成
+    pub fn load_chunk_data(&self, coord: ChunkCoord, map_manager: &MapManager) -> ChunkData {
+        self.store
+            .as_ref()
+            .and_then(|store| store.read(coord).ok())
+            .unwrap_or_else(|| self.generate_chunk_data(coord, map_manager))
     }
 
     /// 生成区块数据
@@ -271,6 +491,11 @@ impl ChunkManager {
         let mut data = ChunkData::new();
 
         if let Some(generator) = &self.terrain_generator {
+            // 整个区块的温湿度一次采样完成，避免逐瓦片查询气候
+            let climate = map_manager
+                .climate_system()
+                .sample_chunk(IVec2::new(coord.x, coord.y), CHUNK_SIZE);
+
             for y in 0..CHUNK_SIZE {
                 for x in 0..CHUNK_SIZE {
                     // 计算世界坐标
@@ -285,6 +510,86 @@ impl ChunkManager {
                     let tile_type =
                         generator.determine_tile_type(height, world_x as f64, world_y as f64);
                     data.set_tile(x, y, tile_type);
+                    data.set_climate_zone(x, y, climate.zone(x, y, height));
+
+                    // 海平面以下记录水深
+                    let water_level = generator.config().water_level;
+                    if height < water_level {
+                        data.set_water_depth(x, y, water_level - height);
+                    }
+                }
+            }
+
+            let mut lake_tiles: HashSet<(usize, usize)> = HashSet::new();
+            let mut river_cells: Vec<RiverCell> = Vec::new();
+
+            // 叠加湖泊，湖面是填充到溢出口的等高面
+            if map_manager.water_config().generate_lakes {
+                let cells = self.water_manager.lakes_in_chunk(
+                    IVec2::new(coord.x, coord.y),
+                    CHUNK_SIZE as i32,
+                    generator,
+                );
+                for cell in cells {
+                    data.set_tile(cell.local_x, cell.local_y, TileType::Water as u8);
+                    data.set_water_depth(cell.local_x, cell.local_y, cell.depth);
+                    lake_tiles.insert((cell.local_x, cell.local_y));
+                }
+            }
+
+            // 叠加世界空间河网，河道跨区块连续
+            if map_manager.water_config().generate_rivers {
+                let river_depth = generator.config().river_depth;
+                let cells = self.water_manager.rivers_in_chunk(
+                    IVec2::new(coord.x, coord.y),
+                    CHUNK_SIZE as i32,
+                    generator,
+                );
+                for cell in cells {
+                    data.set_tile(cell.local_x, cell.local_y, TileType::Water as u8);
+
+                    // 河床中心更深，岸边逐渐变浅
+                    let height = data.get_height(cell.local_x, cell.local_y);
+                    let carve = river_depth * (1.0 - cell.bank_factor * cell.bank_factor);
+                    data.set_height(cell.local_x, cell.local_y, height - carve);
+                    let depth = data.get_water_depth(cell.local_x, cell.local_y).max(carve);
+                    data.set_water_depth(cell.local_x, cell.local_y, depth);
+                    river_cells.push(cell);
+                }
+            }
+
+            Self::compute_flow_field(&mut data, coord, generator, &lake_tiles, &river_cells);
+
+            // 场景建筑在植被之前印入，房屋和街道上不再长树
+            self.stamp_structures(&mut data, coord, generator);
+
+            // 植被装饰在水体叠加之后放置，避免树木长在河湖中
+            Self::place_vegetation(&mut data, coord, map_manager, &climate);
+
+            // 沿河道落差放置瀑布
+            let water_config = map_manager.water_config();
+            if water_config.generate_rivers && water_config.generate_waterfalls {
+                let origin = Vec2::new(
+                    (coord.x * CHUNK_SIZE as i32) as f32,
+                    (coord.y * CHUNK_SIZE as i32) as f32,
+                );
+                let waterfalls = self.water_manager.waterfalls_in_chunk(
+                    IVec2::new(coord.x, coord.y),
+                    CHUNK_SIZE as i32,
+                    generator,
+                    water_config.waterfall_height_threshold,
+                );
+                for placed in waterfalls {
+                    let local = placed.waterfall.position - origin;
+                    data.add_structure(ChunkStructure::Waterfall {
+                        local_x: local.x as usize,
+                        local_y: local.y as usize,
+                        height: placed.height,
+                        width: placed.width,
+                        direction: placed.direction.to_array(),
+                        flow_strength: placed.waterfall.flow_strength,
+                        splash_range: placed.waterfall.splash_range,
+                    });
                 }
             }
         }
@@ -292,6 +597,181 @@ impl ChunkManager {
         data
     }
 
+    /// 按植被系统的结果为区块放置植被装饰
+    ///
+    /// 植被类型由环境决定，是否真正生成再按植被配置中的密度掷骰，
+    /// 两者都以世界坐标为种子，同一区块每次生成结果一致
+    fn place_vegetation(
+        data: &mut ChunkData,
+        coord: ChunkCoord,
+        map_manager: &MapManager,
+        climate: &ClimateChunkSample,
+    ) {
+        let vegetation = map_manager.vegetation_system();
+        let config = map_manager.vegetation_config();
+
+        for y in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let barren = data
+                    .get_tile(x, y)
+                    .and_then(TileType::from_u8)
+                    .is_none_or(|tile| {
+                        matches!(
+                            tile,
+                            TileType::Water
+                                | TileType::Snow
+                                | TileType::Mountain
+                                | TileType::Wall
+                                | TileType::Path
+                                | TileType::Floor
+                                | TileType::Door
+                        )
+                    });
+                // 预制场景指定的装饰物保持不变
+                if barren || data.get_decoration(x, y).is_some() {
+                    continue;
+                }
+
+                let world_x = coord.x * CHUNK_SIZE as i32 + x as i32;
+                let world_y = coord.y * CHUNK_SIZE as i32 + y as i32;
+                let Some(kind) = vegetation.get_vegetation_at(
+                    world_x,
+                    world_y,
+                    data.get_height(x, y),
+                    climate.temperature(x, y),
+                    climate.moisture(x, y),
+                ) else {
+                    continue;
+                };
+
+                if vegetation.placement_roll(world_x, world_y) < config.spawn_chance(kind) {
+                    data.add_decoration(x, y, kind as u8);
+                }
+            }
+        }
+    }
+
+    /// 把与区块相交的场景结构印入区块
+    ///
+    /// 结构布局在世界坐标中生成，跨越多个区块的村镇由每个区块各取自己的部分，
+    /// 水面上的瓦片保持不变，其余瓦片整平到场景的地基高度。
+    /// 居民和触发区只记录在其所在的区块中，避免相邻区块重复生成
+    fn stamp_structures(
+        &self,
+        data: &mut ChunkData,
+        coord: ChunkCoord,
+        generator: &TerrainGenerator,
+    ) {
+        let min = IVec2::new(coord.x, coord.y) * CHUNK_SIZE as i32;
+        let max = min + IVec2::splat(CHUNK_SIZE as i32 - 1);
+
+        for layout in self.structure_generator.layouts_in(min, max, generator) {
+            // 场景中心只记录在所在的区块
+            let origin = layout.placement.origin;
+            if origin.cmpge(min).all() && origin.cmple(max).all() {
+                data.add_structure(ChunkStructure::Landmark {
+                    local_x: (origin.x - min.x) as usize,
+                    local_y: (origin.y - min.y) as usize,
+                    scene_type: layout.placement.scene_type,
+                    name: layout.name.clone(),
+                });
+            }
+
+            for (pos, tile) in layout.tiles_in(min, max) {
+                let (x, y) = ((pos.x - min.x) as usize, (pos.y - min.y) as usize);
+                if data.get_tile(x, y) == Some(TileType::Water as u8) {
+                    continue;
+                }
+                data.set_tile(x, y, tile.tile_type() as u8);
+                data.set_height(x, y, layout.ground_height);
+            }
+
+            for (pos, kind) in layout.decorations_in(min, max) {
+                let (x, y) = ((pos.x - min.x) as usize, (pos.y - min.y) as usize);
+                data.add_decoration(x, y, kind.id());
+            }
+
+            for trigger in layout.triggers_in(min, max) {
+                data.add_structure(ChunkStructure::Trigger {
+                    local_x: (trigger.position.x - min.x) as usize,
+                    local_y: (trigger.position.y - min.y) as usize,
+                    radius: trigger.radius,
+                    id: trigger.id.clone(),
+                });
+            }
+
+            for resident in layout.residents_in(min, max) {
+                data.add_structure(ChunkStructure::Resident {
+                    local_x: (resident.position.x - min.x) as usize,
+                    local_y: (resident.position.y - min.y) as usize,
+                    npc_type: resident.npc_type,
+                    name: resident.name.clone(),
+                });
+            }
+        }
+    }
+
+    /// 计算区块的水流场
+    ///
+    /// 1. 静水（海、湖）沿地形坡度缓慢流动，湖泊再额外衰减
+    /// 2. 河道沿河流方向流动，强度随流量增大、向岸边减弱
+    ///
+    /// 坡度在世界坐标中采样，区块边界两侧的水流连续
+    fn compute_flow_field(
+        data: &mut ChunkData,
+        coord: ChunkCoord,
+        generator: &TerrainGenerator,
+        lake_tiles: &HashSet<(usize, usize)>,
+        river_cells: &[RiverCell],
+    ) {
+        for y in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                if data.get_water_depth(x, y) <= 0.0 {
+                    continue;
+                }
+
+                let world_x = (coord.x * CHUNK_SIZE as i32 + x as i32) as f64;
+                let world_y = (coord.y * CHUNK_SIZE as i32 + y as i32) as f64;
+                let gradient = Vec2::new(
+                    generator.generate_height(world_x + 1.0, world_y)
+                        - generator.generate_height(world_x - 1.0, world_y),
+                    generator.generate_height(world_x, world_y + 1.0)
+                        - generator.generate_height(world_x, world_y - 1.0),
+                ) * 0.5;
+
+                let mut flow = -gradient * STILL_WATER_FLOW_GAIN;
+                if lake_tiles.contains(&(x, y)) {
+                    flow *= LAKE_FLOW_DAMPING;
+                }
+                data.set_flow(x, y, flow);
+            }
+        }
+
+        for cell in river_cells {
+            let strength = cell.flow / (cell.flow + 60.0) * (1.0 - 0.5 * cell.bank_factor);
+            data.set_flow(cell.local_x, cell.local_y, cell.direction * strength);
+        }
+    }
+
+    /// 获取水系管理器
+    pub fn water_manager(&self) -> &WaterManager {
+        &self.water_manager
+    }
+
+    /// 按生成器推算某个世界瓦片是否为静水（海面或湖泊），不需要区块已加载
+    ///
+    /// 结果缓存在水系管理器中，地形生成器尚未初始化时返回 false
+    pub fn has_water_at(&self, tile: IVec2) -> bool {
+        self.terrain_generator
+            .as_ref()
+            .is_some_and(|generator| self.water_manager.has_water_at(tile.x, tile.y, generator))
+    }
+
+    /// 获取可变的场景结构生成器
+    pub fn structure_generator_mut(&mut self) -> &mut StructureGenerator {
+        &mut self.structure_generator
+    }
+
     /// 获取区块实体
     pub fn get_chunk_entity(&self, coord: ChunkCoord) -> Option<Entity> {
         self.chunks.get(&coord).copied()
@@ -302,14 +782,33 @@ impl ChunkManager {
         self.chunks.get(&coord)
     }
 
-    /// 获取可变区块
-    pub fn get_chunk_mut(&mut self, coord: ChunkCoord) -> Option<&mut Entity> {
-        self.chunks.get_mut(&coord)
+    /// 记录已加载的区块
+    pub fn insert_chunk(&mut self, coord: ChunkCoord, entity: Entity) {
+        if self.chunks.insert(coord, entity).is_none() {
+            self.total_loaded += 1;
+        }
     }
 
     /// 移除区块
     pub fn remove_chunk(&mut self, coord: ChunkCoord) -> Option<Entity> {
-        self.chunks.remove(&coord)
+        let removed = self.chunks.remove(&coord);
+        if removed.is_some() {
+            self.total_unloaded += 1;
+        }
+        removed
+    }
+
+    /// 当前的流式加载统计
+    pub fn stream_stats(&self) -> ChunkStreamStats {
+        ChunkStreamStats {
+            loaded: self.chunks.len(),
+            pending: self.get_chunks_to_load().len() + self.loading_queue.len(),
+            prefetched: self.prefetched.len(),
+            memory_budget: self.memory_budget,
+            load_budget: self.load_budget,
+            total_loaded: self.total_loaded,
+            total_unloaded: self.total_unloaded,
+        }
     }
 
     /// 获取渲染设置
@@ -322,17 +821,3 @@ impl ChunkManager {
         &mut self.render_settings
     }
 }
-
-/// 区块相对方向枚举
-/// 用于表示相邻区块的相对位置
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum Direction {
-    North,
-    South,
-    East,
-    West,
-    NorthEast,
-    NorthWest,
-    SouthEast,
-    SouthWest,
-}
@@ -0,0 +1,195 @@
+use bevy::prelude::*;
+
+use super::{world_to_tile, Chunk, ChunkCoord, ChunkManager, CHUNK_SIZE, TILE_PIXELS};
+use crate::events::input::GameAction;
+use crate::resources::InputState;
+use crate::ui::{label, panel, TextRole};
+use crate::world::entity::Player;
+use crate::world::map::MapManager;
+
+/// 区块像素尺寸
+const CHUNK_PIXELS: f32 = CHUNK_SIZE as f32 * TILE_PIXELS;
+
+/// 统计文字的刷新间隔（秒）
+const STATS_REFRESH: f32 = 0.25;
+
+const GRID_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.25);
+const LOADED_COLOR: Color = Color::srgba(0.2, 0.9, 0.4, 0.8);
+const PENDING_COLOR: Color = Color::srgba(0.4, 0.6, 1.0, 0.6);
+const PLAYER_CHUNK_COLOR: Color = Color::srgb(1.0, 1.0, 1.0);
+
+/// 区块调试界面
+#[derive(Resource, Debug, Default)]
+pub struct ChunkDebugOverlay {
+    pub visible: bool,
+}
+
+/// 调试界面中的统计文字面板
+#[derive(Component)]
+pub struct ChunkDebugPanel;
+
+fn chunk_rect(coord: ChunkCoord) -> (Vec2, Vec2) {
+    let size = Vec2::splat(CHUNK_PIXELS);
+    let center = (Vec2::new(coord.x as f32, coord.y as f32) + 0.5) * CHUNK_PIXELS;
+    (center, size)
+}
+
+/// 按键开关调试界面，打开时生成统计面板，关闭时移除
+pub fn toggle_chunk_debug(
+    mut commands: Commands,
+    input_state: Res<InputState>,
+    mut overlay: ResMut<ChunkDebugOverlay>,
+    panels: Query<Entity, With<ChunkDebugPanel>>,
+) {
+    if !input_state.is_action_just_pressed(GameAction::ToggleChunkDebug) {
+        return;
+    }
+    overlay.visible = !overlay.visible;
+    for panel in panels.iter() {
+        commands.entity(panel).despawn_recursive();
+    }
+    if overlay.visible {
+        commands.spawn((
+            ChunkDebugPanel,
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(12.0),
+                top: Val::Px(12.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(8.0)),
+                row_gap: Val::Px(2.0),
+                ..default()
+            },
+            panel(),
+            GlobalZIndex(20),
+            Name::new("Chunk Debug"),
+        ));
+    }
+}
+
+/// 绘制区块网格与各区块的加载状态
+///
+/// 已加载的区块与视野内尚未加载的区块用不同颜色标出，玩家所在区块加粗描边
+pub fn draw_chunk_debug(
+    mut gizmos: Gizmos,
+    chunk_manager: Res<ChunkManager>,
+    chunks: Query<&Chunk>,
+) {
+    let Some(player_chunk) = chunk_manager.player_chunk else {
+        return;
+    };
+
+    // 网格覆盖视野外一圈，能看到即将进入视野的区块边界
+    let range = chunk_manager.view_distance + 1;
+    let min = Vec2::new(
+        (player_chunk.x - range) as f32,
+        (player_chunk.y - range) as f32,
+    ) * CHUNK_PIXELS;
+    let max = Vec2::new(
+        (player_chunk.x + range + 1) as f32,
+        (player_chunk.y + range + 1) as f32,
+    ) * CHUNK_PIXELS;
+    for i in 0..=(range * 2 + 1) {
+        let offset = i as f32 * CHUNK_PIXELS;
+        gizmos.line_2d(
+            Vec2::new(min.x + offset, min.y),
+            Vec2::new(min.x + offset, max.y),
+            GRID_COLOR,
+        );
+        gizmos.line_2d(
+            Vec2::new(min.x, min.y + offset),
+            Vec2::new(max.x, min.y + offset),
+            GRID_COLOR,
+        );
+    }
+
+    for chunk in chunks.iter() {
+        let (center, size) = chunk_rect(chunk.coord);
+        // 向内缩一点，与相邻区块的框线分开
+        gizmos.rect_2d(center, size - Vec2::splat(8.0), LOADED_COLOR);
+    }
+
+    for coord in chunk_manager.get_chunks_to_load() {
+        let (center, size) = chunk_rect(coord);
+        gizmos.rect_2d(center, size - Vec2::splat(8.0), PENDING_COLOR);
+    }
+
+    let (center, size) = chunk_rect(player_chunk);
+    gizmos.rect_2d(center, size, PLAYER_CHUNK_COLOR);
+    gizmos.rect_2d(center, size - Vec2::splat(2.0), PLAYER_CHUNK_COLOR);
+}
+
+/// 按间隔刷新统计文字：玩家所在区块与瓦片、已加载数与内存预算、加载队列长度、气候与水体缓存
+pub fn update_chunk_debug_panel(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    chunk_manager: Res<ChunkManager>,
+    map_manager: Res<MapManager>,
+    players: Query<&Transform, With<Player>>,
+    panels: Query<(Entity, Ref<ChunkDebugPanel>)>,
+    mut elapsed: Local<f32>,
+) {
+    let Ok((panel, marker)) = panels.get_single() else {
+        return;
+    };
+    *elapsed += time.delta_secs();
+    if *elapsed < STATS_REFRESH && !marker.is_added() {
+        return;
+    }
+    *elapsed = 0.0;
+
+    let stats = chunk_manager.stream_stats();
+    let mut lines = Vec::new();
+    match (chunk_manager.player_chunk, players.get_single()) {
+        (Some(chunk), Ok(player)) => {
+            let tile = world_to_tile(player.translation.truncate());
+            let local = IVec2::new(
+                tile.x.rem_euclid(CHUNK_SIZE as i32),
+                tile.y.rem_euclid(CHUNK_SIZE as i32),
+            );
+            lines.push(format!("区块 ({}, {})", chunk.x, chunk.y));
+            lines.push(format!(
+                "瓦片 ({}, {})，区块内 ({}, {})",
+                tile.x, tile.y, local.x, local.y
+            ));
+        }
+        _ => lines.push("玩家不在世界中".to_string()),
+    }
+    lines.push(format!(
+        "已加载 {} / {}（{:.0}%）",
+        stats.loaded,
+        stats.memory_budget,
+        stats.memory_usage() * 100.0
+    ));
+    lines.push(format!(
+        "待加载 {}，每帧最多 {}",
+        stats.pending, stats.load_budget
+    ));
+    lines.push(format!("预生成 {}", stats.prefetched));
+    lines.push(format!(
+        "累计加载 {}，卸载 {}",
+        stats.total_loaded, stats.total_unloaded
+    ));
+    let caches = [
+        ("气候", map_manager.climate_system().cache_stats()),
+        ("水体", chunk_manager.water_manager().cache_stats()),
+    ];
+    for (name, cache) in caches {
+        lines.push(format!(
+            "{}缓存 {} / {}，命中率 {:.0}%，淘汰 {}",
+            name,
+            cache.len,
+            cache.capacity,
+            cache.hit_rate() * 100.0,
+            cache.evictions
+        ));
+    }
+
+    commands.entity(panel).despawn_descendants();
+    commands.entity(panel).with_children(|panel| {
+        panel.spawn(label("区块调试", TextRole::Title));
+        for line in lines {
+            panel.spawn(label(line, TextRole::Small));
+        }
+    });
+}
@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::{Chunk, ChunkCoord, ChunkData};
+use crate::save::SaveEvent;
+
+/// 被修改过的区块数据
+///
+/// # 设计思路
+/// 1. 区块数据随世界种子确定性生成，只有被修改过的区块需要记住
+/// 2. 区块卸载后修改仍保留在这里，重新加载时用它代替生成结果
+/// 3. 存档直接读写这份数据，读档时整体替换
+#[derive(Resource, Debug, Default)]
+pub struct ChunkEdits {
+    chunks: HashMap<ChunkCoord, ChunkData>,
+}
+
+impl ChunkEdits {
+    pub fn get(&self, coord: ChunkCoord) -> Option<&ChunkData> {
+        self.chunks.get(&coord)
+    }
+
+    pub fn record(&mut self, coord: ChunkCoord, data: ChunkData) {
+        self.chunks.insert(coord, data);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ChunkCoord, &ChunkData)> {
+        self.chunks.iter().map(|(coord, data)| (*coord, data))
+    }
+
+    /// 整体替换，读档时使用
+    pub fn replace(&mut self, chunks: impl IntoIterator<Item = (ChunkCoord, ChunkData)>) {
+        self.chunks = chunks.into_iter().collect();
+    }
+}
+
+/// 记录被修改过的已加载区块
+pub fn track_chunk_edits(mut edits: ResMut<ChunkEdits>, chunks: Query<&Chunk, Changed<Chunk>>) {
+    for chunk in chunks.iter() {
+        if let Some(data) = chunk.data.as_ref().filter(|data| data.modified) {
+            edits.record(chunk.coord, data.clone());
+        }
+    }
+}
+
+/// 存档前把所有已加载的修改过的区块写回，不必等到下一次变化检测
+pub fn flush_chunk_edits(
+    mut saves: EventReader<SaveEvent>,
+    mut edits: ResMut<ChunkEdits>,
+    chunks: Query<&Chunk>,
+) {
+    if saves.read().count() == 0 {
+        return;
+    }
+    for chunk in chunks.iter() {
+        if let Some(data) = chunk.data.as_ref().filter(|data| data.modified) {
+            edits.record(chunk.coord, data.clone());
+        }
+    }
+}
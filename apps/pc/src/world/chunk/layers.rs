@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::CHUNK_SIZE;
+
+/// 积雪厚度图层 (0.0-1.0)
+pub const LAYER_SNOW_COVER: &str = "snow_cover";
+
+/// 地面湿度图层 (0.0-1.0)
+pub const LAYER_MOISTURE: &str = "moisture";
+
+/// 附加数据层的取值，按瓦片顺序（先行后列）排列
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LayerValues {
+    /// 归属、土壤肥力等离散取值
+    Bytes(Vec<u8>),
+    /// 湿度、积雪等连续取值
+    Floats(Vec<f32>),
+}
+
+/// 可以存放在附加数据层中的取值类型
+pub trait LayerValue: Copy + Default {
+    /// 创建一个全为默认值的图层
+    fn new_layer(size: usize) -> LayerValues;
+    fn values(layer: &LayerValues) -> Option<&Vec<Self>>;
+    fn values_mut(layer: &mut LayerValues) -> Option<&mut Vec<Self>>;
+}
+
+impl LayerValue for u8 {
+    fn new_layer(size: usize) -> LayerValues {
+        LayerValues::Bytes(vec![0; size])
+    }
+
+    fn values(layer: &LayerValues) -> Option<&Vec<Self>> {
+        match layer {
+            LayerValues::Bytes(values) => Some(values),
+            LayerValues::Floats(_) => None,
+        }
+    }
+
+    fn values_mut(layer: &mut LayerValues) -> Option<&mut Vec<Self>> {
+        match layer {
+            LayerValues::Bytes(values) => Some(values),
+            LayerValues::Floats(_) => None,
+        }
+    }
+}
+
+impl LayerValue for f32 {
+    fn new_layer(size: usize) -> LayerValues {
+        LayerValues::Floats(vec![0.0; size])
+    }
+
+    fn values(layer: &LayerValues) -> Option<&Vec<Self>> {
+        match layer {
+            LayerValues::Floats(values) => Some(values),
+            LayerValues::Bytes(_) => None,
+        }
+    }
+
+    fn values_mut(layer: &mut LayerValues) -> Option<&mut Vec<Self>> {
+        match layer {
+            LayerValues::Floats(values) => Some(values),
+            LayerValues::Bytes(_) => None,
+        }
+    }
+}
+
+/// 区块附加数据层
+///
+/// # 设计思路
+/// 1. 玩法系统按名称存放自己的逐瓦片数据（湿度、归属、土壤肥力、积雪等），不必给 `ChunkData` 加字段
+/// 2. 图层按名称有序存放，序列化结果与写入顺序无关，区块哈希保持稳定
+/// 3. 图层在第一次写入时创建，读取不存在的图层得到默认值；旧存档没有附加数据层，读入后为空
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChunkLayers {
+    layers: BTreeMap<String, LayerValues>,
+}
+
+impl ChunkLayers {
+    /// 读取图层中某个瓦片的值，图层不存在或类型不符时返回默认值
+    pub fn get<T: LayerValue>(&self, name: &str, x: usize, y: usize) -> T {
+        if x >= CHUNK_SIZE || y >= CHUNK_SIZE {
+            return T::default();
+        }
+        self.layers
+            .get(name)
+            .and_then(T::values)
+            .and_then(|values| values.get(y * CHUNK_SIZE + x))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// 写入图层中某个瓦片的值，名称已被另一种类型的图层占用时不写入并返回 false
+    pub fn set<T: LayerValue>(&mut self, name: &str, x: usize, y: usize, value: T) -> bool {
+        if x >= CHUNK_SIZE || y >= CHUNK_SIZE {
+            return false;
+        }
+        match self.layer_mut::<T>(name) {
+            Some(values) => {
+                values[y * CHUNK_SIZE + x] = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 取得整个图层用于批量修改，不存在时创建
+    ///
+    /// 区块尺寸变化后旧图层的长度对不上，按当前尺寸补齐或截断
+    pub fn layer_mut<T: LayerValue>(&mut self, name: &str) -> Option<&mut [T]> {
+        let size = CHUNK_SIZE * CHUNK_SIZE;
+        let values = T::values_mut(
+            self.layers
+                .entry(name.to_string())
+                .or_insert_with(|| T::new_layer(size)),
+        )?;
+        values.resize(size, T::default());
+        Some(values)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.layers.contains_key(name)
+    }
+
+    /// 移除图层，返回被移除的数据
+    pub fn remove(&mut self, name: &str) -> Option<LayerValues> {
+        self.layers.remove(name)
+    }
+
+    /// 按名称顺序遍历全部图层
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &LayerValues)> {
+        self.layers
+            .iter()
+            .map(|(name, values)| (name.as_str(), values))
+    }
+}
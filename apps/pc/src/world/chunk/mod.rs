@@ -6,13 +6,41 @@ mod chunk_loader;
 /// 2. 代码组织：便于维护和扩展
 /// 3. 依赖管理：明确模块间的依赖关系
 mod chunk_manager;
+mod debug;
+mod edits;
+mod layers;
+mod nav_grid;
+mod prefetch;
+mod pregen;
+mod props;
 mod render;
+mod snapshot;
+mod store;
+mod stream_test;
 mod systems;
+mod tileset;
+#[cfg(feature = "worldgen-verify")]
+mod verify;
+mod water_current;
 
 pub use chunk_loader::*;
 pub use chunk_manager::*;
+pub use debug::*;
+pub use edits::*;
+pub use layers::*;
+pub use nav_grid::*;
+pub use prefetch::*;
+pub use pregen::*;
+pub use props::*;
 pub use render::*;
+pub use snapshot::*;
+pub use store::*;
+pub use stream_test::*;
 pub use systems::ChunkSystemPlugin;
+pub use tileset::*;
+#[cfg(feature = "worldgen-verify")]
+pub use verify::*;
+pub use water_current::*;
 
 /// 区块大小常量
 /// 设置为32是因为：
@@ -0,0 +1,283 @@
+use bevy::prelude::*;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use super::{Chunk, ChunkCoord, ChunkData, CHUNK_SIZE, TILE_PIXELS};
+use crate::world::map::{get_tile_physics, TileType};
+
+/// 没有水深记录的水面（旧存档）按这个深度处理，与地形高度同一单位
+const DEFAULT_WATER_DEPTH: f32 = 0.1;
+
+/// 寻路默认的搜索节点上限
+pub const DEFAULT_NAV_SEARCH_LIMIT: usize = 2048;
+
+/// 导航格子
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NavCell {
+    pub walkable: bool,
+    pub blocks_sight: bool,
+    /// 水面，不可行走但可以游过去
+    pub water: bool,
+    /// 岩石、山地等峭壁，施展轻功可以攀上去
+    pub cliff: bool,
+    /// 密林，骑马进不去
+    pub dense_forest: bool,
+    /// 水深，与地形高度同一单位，陆地为 0
+    pub depth: f32,
+    /// 通过这一格的代价，只对可行走的格子有意义
+    pub cost: f32,
+}
+
+impl NavCell {
+    /// 由瓦片物理属性生成，没有瓦片数据的格子按空地处理
+    pub fn from_tile(tile: Option<TileType>) -> Self {
+        let tile = tile.unwrap_or(TileType::Empty);
+        let physics = get_tile_physics(tile);
+        Self {
+            walkable: physics.walkable,
+            blocks_sight: physics.blocks_sight,
+            water: tile == TileType::Water,
+            cliff: matches!(tile, TileType::Rock | TileType::Mountain),
+            dense_forest: tile == TileType::DenseForest,
+            depth: 0.0,
+            cost: physics.movement_cost.max(0.1),
+        }
+    }
+
+    /// 记录水深，只对水面有效
+    pub fn with_depth(mut self, depth: f32) -> Self {
+        if self.water {
+            self.depth = if depth > 0.0 {
+                depth
+            } else {
+                DEFAULT_WATER_DEPTH
+            };
+        }
+        self
+    }
+}
+
+/// 世界坐标所在的瓦片
+pub fn world_to_tile(position: Vec2) -> IVec2 {
+    (position / TILE_PIXELS).floor().as_ivec2()
+}
+
+/// 瓦片所在的区块与区块内坐标
+fn split_tile(tile: IVec2) -> (ChunkCoord, usize) {
+    let size = CHUNK_SIZE as i32;
+    let coord = ChunkCoord {
+        x: tile.x.div_euclid(size),
+        y: tile.y.div_euclid(size),
+    };
+    let index = tile.y.rem_euclid(size) as usize * CHUNK_SIZE + tile.x.rem_euclid(size) as usize;
+    (coord, index)
+}
+
+/// 寻路队列中的节点，代价小的先出队
+#[derive(Debug, Clone, Copy)]
+struct OpenNode {
+    tile: IVec2,
+    estimate: f32,
+}
+
+impl PartialEq for OpenNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl Eq for OpenNode {}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+/// 导航网格
+///
+/// # 设计思路
+/// 1. 按区块保存，与区块同步加载与卸载，只覆盖已加载的区域
+/// 2. 格子由瓦片物理属性生成，瓦片被修改时整块或单格重建
+/// 3. 未加载的区域不可行走、也不遮挡视线，寻路不会走出已加载范围
+#[derive(Resource, Debug, Default)]
+pub struct NavGrid {
+    chunks: HashMap<ChunkCoord, Vec<NavCell>>,
+    /// 区块实体到坐标的映射，区块卸载时只拿得到实体
+    entities: HashMap<Entity, ChunkCoord>,
+}
+
+impl NavGrid {
+    /// 由区块数据生成一个区块的导航格子
+    pub fn insert_chunk(&mut self, coord: ChunkCoord, data: &ChunkData) {
+        let mut cells = Vec::with_capacity(CHUNK_SIZE * CHUNK_SIZE);
+        for y in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let tile = data.get_tile(x, y).and_then(TileType::from_u8);
+                cells.push(NavCell::from_tile(tile).with_depth(data.get_water_depth(x, y)));
+            }
+        }
+        self.chunks.insert(coord, cells);
+    }
+
+    pub fn remove_chunk(&mut self, coord: ChunkCoord) {
+        self.chunks.remove(&coord);
+    }
+
+    pub fn cell(&self, tile: IVec2) -> Option<NavCell> {
+        let (coord, index) = split_tile(tile);
+        self.chunks.get(&coord).map(|cells| cells[index])
+    }
+
+    /// 是否可行走，未加载的瓦片不可行走
+    pub fn is_walkable(&self, tile: IVec2) -> bool {
+        self.cell(tile).is_some_and(|cell| cell.walkable)
+    }
+
+    /// 是否遮挡视线，未加载的瓦片不遮挡
+    pub fn blocks_sight(&self, tile: IVec2) -> bool {
+        self.cell(tile).is_some_and(|cell| cell.blocks_sight)
+    }
+
+    /// 两个瓦片之间是否有视线
+    ///
+    /// 沿 Bresenham 直线检查，两端的瓦片不算，站在墙边的角色仍能互相看见
+    pub fn line_of_sight(&self, from: IVec2, to: IVec2) -> bool {
+        let delta = (to - from).abs();
+        let step = IVec2::new((to.x - from.x).signum(), (to.y - from.y).signum());
+        let mut error = delta.x - delta.y;
+        let mut current = from;
+
+        while current != to {
+            let doubled = error * 2;
+            if doubled > -delta.y {
+                error -= delta.y;
+                current.x += step.x;
+            }
+            if doubled < delta.x {
+                error += delta.x;
+                current.y += step.y;
+            }
+            if current != to && self.blocks_sight(current) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// 从 `from` 走到 `to` 的路径，包含两端
+    ///
+    /// A* 搜索，八方向移动，斜走不能切过不可行走的拐角。
+    /// 搜索超过 `limit` 个节点仍未找到时放弃，避免在大片已加载区域中卡顿
+    pub fn find_path(&self, from: IVec2, to: IVec2, limit: usize) -> Option<Vec<IVec2>> {
+        if !self.is_walkable(to) {
+            return None;
+        }
+        if from == to {
+            return Some(vec![from]);
+        }
+
+        let heuristic = |tile: IVec2| {
+            let delta = (to - tile).abs();
+            let (long, short) = (delta.x.max(delta.y), delta.x.min(delta.y));
+            (long - short) as f32 + short as f32 * std::f32::consts::SQRT_2
+        };
+
+        let mut open = BinaryHeap::new();
+        let mut costs: HashMap<IVec2, f32> = HashMap::new();
+        let mut came_from: HashMap<IVec2, IVec2> = HashMap::new();
+        open.push(OpenNode {
+            tile: from,
+            estimate: heuristic(from),
+        });
+        costs.insert(from, 0.0);
+
+        let mut visited = 0;
+        while let Some(OpenNode { tile, .. }) = open.pop() {
+            if tile == to {
+                let mut path = vec![tile];
+                let mut current = tile;
+                while let Some(previous) = came_from.get(&current) {
+                    current = *previous;
+                    path.push(current);
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            visited += 1;
+            if visited > limit {
+                return None;
+            }
+
+            let cost = costs[&tile];
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if dx == 0 && dy == 0 {
+                        continue;
+                    }
+                    let next = tile + IVec2::new(dx, dy);
+                    let Some(cell) = self.cell(next).filter(|cell| cell.walkable) else {
+                        continue;
+                    };
+                    let diagonal = dx != 0 && dy != 0;
+                    if diagonal
+                        && (!self.is_walkable(tile + IVec2::new(dx, 0))
+                            || !self.is_walkable(tile + IVec2::new(0, dy)))
+                    {
+                        continue;
+                    }
+
+                    let step = if diagonal {
+                        std::f32::consts::SQRT_2
+                    } else {
+                        1.0
+                    };
+                    let next_cost = cost + step * cell.cost;
+                    if costs.get(&next).is_some_and(|known| *known <= next_cost) {
+                        continue;
+                    }
+                    costs.insert(next, next_cost);
+                    came_from.insert(next, tile);
+                    open.push(OpenNode {
+                        tile: next,
+                        estimate: next_cost + heuristic(next),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// 能否从 `from` 走到 `to`
+    pub fn is_reachable(&self, from: IVec2, to: IVec2, limit: usize) -> bool {
+        self.find_path(from, to, limit).is_some()
+    }
+}
+
+/// 区块加载、数据变化或卸载时同步导航网格
+pub fn sync_nav_grid(
+    mut nav_grid: ResMut<NavGrid>,
+    changed: Query<(Entity, &Chunk), Changed<Chunk>>,
+    mut removed: RemovedComponents<Chunk>,
+) {
+    for entity in removed.read() {
+        if let Some(coord) = nav_grid.entities.remove(&entity) {
+            nav_grid.remove_chunk(coord);
+        }
+    }
+
+    for (entity, chunk) in changed.iter() {
+        let Some(data) = &chunk.data else {
+            continue;
+        };
+        nav_grid.insert_chunk(chunk.coord, data);
+        nav_grid.entities.insert(entity, chunk.coord);
+    }
+}
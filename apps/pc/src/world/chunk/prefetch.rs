@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Mutex;
+
+use super::{ChunkCoord, ChunkData, ChunkManager};
+use crate::world::map::MapManager;
+
+/// 后台预先生成区块
+///
+/// 加载界面按出生点开始预生成，后台线程持有生成器的副本，由近到远逐个生成后送回，
+/// 收到的数据放进区块管理器，区块加载时直接取用，不在主线程生成
+#[derive(Resource, Default)]
+pub struct ChunkPrefetch {
+    receiver: Option<Mutex<Receiver<(ChunkCoord, ChunkData)>>>,
+    /// 开始预生成时的世界种子，换了种子后剩下的结果作废
+    seed: u32,
+    /// 本次要生成的区块数
+    pub total: usize,
+    /// 已经收到的区块数
+    pub received: usize,
+}
+
+impl ChunkPrefetch {
+    /// 预生成以 `center` 为中心、`radius` 为半径的方形范围内尚未加载的区块，
+    /// 之前没做完的预生成直接放弃
+    pub fn start(
+        &mut self,
+        center: ChunkCoord,
+        radius: i32,
+        chunk_manager: &ChunkManager,
+        map_manager: &MapManager,
+    ) {
+        let mut coords: Vec<ChunkCoord> = (-radius..=radius)
+            .flat_map(|dy| {
+                (-radius..=radius).map(move |dx| ChunkCoord {
+                    x: center.x + dx,
+                    y: center.y + dy,
+                })
+            })
+            .filter(|coord| !chunk_manager.chunks.contains_key(coord))
+            .collect();
+        coords.sort_by_key(|coord| (coord.x - center.x).abs() + (coord.y - center.y).abs());
+
+        self.seed = map_manager.seed;
+        self.total = coords.len();
+        self.received = 0;
+        if coords.is_empty() {
+            self.receiver = None;
+            return;
+        }
+
+        let generator = chunk_manager.generator_snapshot();
+        let map_manager = map_manager.clone();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for coord in coords {
+                let data = generator.load_chunk_data(coord, &map_manager);
+                // 接收端已丢弃说明预生成被放弃，不必再生成
+                if sender.send((coord, data)).is_err() {
+                    break;
+                }
+            }
+        });
+        self.receiver = Some(Mutex::new(receiver));
+    }
+
+    /// 是否还在生成
+    pub fn is_running(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    /// 完成比例，没有要生成的区块时为1
+    pub fn progress(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.received as f32 / self.total as f32
+        }
+    }
+}
+
+/// 收下后台生成好的区块数据
+pub fn receive_prefetched_chunks(
+    mut prefetch: ResMut<ChunkPrefetch>,
+    mut chunk_manager: ResMut<ChunkManager>,
+    map_manager: Res<MapManager>,
+) {
+    if !prefetch.is_running() {
+        return;
+    }
+    if map_manager.seed != prefetch.seed {
+        prefetch.receiver = None;
+        return;
+    }
+
+    let mut chunks = Vec::new();
+    let mut finished = false;
+    if let Some(Ok(receiver)) = prefetch.receiver.as_ref().map(Mutex::lock) {
+        loop {
+            match receiver.try_recv() {
+                Ok(chunk) => chunks.push(chunk),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    finished = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    prefetch.received += chunks.len();
+    for (coord, data) in chunks {
+        // 等待期间已经按原来的方式加载的区块不再需要
+        if !chunk_manager.chunks.contains_key(&coord) {
+            chunk_manager.insert_prefetched(coord, data);
+        }
+    }
+    if finished {
+        // 线程中途退出时把没送回的区块也算作完成，交给正常加载生成
+        prefetch.received = prefetch.total;
+        prefetch.receiver = None;
+    }
+}
@@ -0,0 +1,158 @@
+use bevy::math::{IVec2, UVec2};
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use super::{ChunkCoord, ChunkManager, ChunkStore, CHUNK_SIZE};
+use crate::world::map::{export_map_preview, MapManager, MapPreviewSpec, PreviewLayer};
+
+/// 预览图的默认输出目录
+pub const WORLDGEN_PREVIEW_DIR: &str = "reports/worldgen";
+
+/// 预生成区域
+///
+/// 以中心区块为中心、边长为 2 * radius + 1 个区块的正方形
+#[derive(Debug, Clone, Copy)]
+pub struct PregenRegion {
+    pub seed: u32,
+    pub center: ChunkCoord,
+    pub radius: u32,
+}
+
+impl PregenRegion {
+    /// 区域边长（区块数）
+    pub fn side(&self) -> usize {
+        self.radius as usize * 2 + 1
+    }
+
+    /// 区域内所有区块，按行从下到上排列
+    pub fn coords(&self) -> Vec<ChunkCoord> {
+        let radius = self.radius as i32;
+        (-radius..=radius)
+            .flat_map(|dy| {
+                (-radius..=radius).map(move |dx| ChunkCoord {
+                    x: self.center.x + dx,
+                    y: self.center.y + dy,
+                })
+            })
+            .collect()
+    }
+}
+
+/// 预生成区域的预览图设置
+///
+/// scale 为每个像素代表的瓦片边长，大半径时加大以控制图片尺寸
+#[derive(Debug, Clone)]
+pub struct PregenPreview {
+    pub dir: PathBuf,
+    pub scale: u32,
+    pub layers: Vec<PreviewLayer>,
+}
+
+/// 预生成结果
+#[derive(Debug, Clone)]
+pub struct PregenReport {
+    pub region: PregenRegion,
+    pub store_dir: PathBuf,
+    pub chunks: usize,
+    pub bytes: usize,
+    pub elapsed_secs: f64,
+    pub previews: Vec<PathBuf>,
+}
+
+impl fmt::Display for PregenReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "已预生成种子 {} 以 ({}, {}) 为中心、半径 {} 的 {} 个区块，共 {:.1} MB，用时 {:.1} 秒",
+            self.region.seed,
+            self.region.center.x,
+            self.region.center.y,
+            self.region.radius,
+            self.chunks,
+            self.bytes as f64 / (1024.0 * 1024.0),
+            self.elapsed_secs
+        )?;
+        writeln!(f, "  区块存储：{}", self.store_dir.display())?;
+        for preview in &self.previews {
+            writeln!(f, "  预览图：{}", preview.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// 预生成一片区块写入区块存储，按需导出预览图
+///
+/// # 设计思路
+/// 1. 区块生成只读地图与生成器，按处理器核数分给多个线程，每个线程生成后直接写文件
+/// 2. 生成后不在内存中保留区块数据，大半径也不会占用过多内存
+/// 3. 预览图交给地图预览导出，与游戏内导出的图层和配色一致
+pub fn pregenerate_region(
+    region: PregenRegion,
+    store: &ChunkStore,
+    preview: Option<&PregenPreview>,
+) -> Result<PregenReport, Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    store.prepare(region.seed)?;
+
+    let map_manager = MapManager::new(region.seed);
+    let mut chunk_manager = ChunkManager::default();
+    chunk_manager.initialize_terrain_generator(&map_manager);
+
+    let coords = region.coords();
+    let workers = std::thread::available_parallelism()
+        .map_or(1, |count| count.get())
+        .min(coords.len().max(1));
+
+    let results = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|worker| {
+                let coords = &coords;
+                let chunk_manager = &chunk_manager;
+                let map_manager = &map_manager;
+                scope.spawn(move || {
+                    let mut bytes = 0;
+                    for coord in coords.iter().skip(worker).step_by(workers) {
+                        let data = chunk_manager.generate_chunk_data(*coord, map_manager);
+                        bytes += store.write(*coord, &data).map_err(|e| {
+                            format!("写入区块 ({}, {}) 失败: {}", coord.x, coord.y, e)
+                        })?;
+                    }
+                    Ok::<_, String>(bytes)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| Err("区块生成线程异常退出".to_string()))
+            })
+            .collect::<Result<Vec<usize>, _>>()
+    })?;
+    let bytes = results.into_iter().sum();
+
+    let previews = match preview {
+        Some(preview) => {
+            let side = (region.side() * CHUNK_SIZE) as u32;
+            let corner = IVec2::new(region.center.x, region.center.y) - region.radius as i32;
+            let spec = MapPreviewSpec {
+                origin: corner * CHUNK_SIZE as i32,
+                size: UVec2::splat(side),
+                scale: preview.scale,
+            };
+            export_map_preview(&map_manager, &spec, &preview.layers, &preview.dir)?
+        }
+        None => Vec::new(),
+    };
+
+    Ok(PregenReport {
+        region,
+        store_dir: store.dir().to_path_buf(),
+        chunks: coords.len(),
+        bytes,
+        elapsed_secs: started.elapsed().as_secs_f64(),
+        previews,
+    })
+}
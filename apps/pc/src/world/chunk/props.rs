@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+
+use super::{Chunk, ChunkProp};
+use crate::interaction::{Interacted, InteractionKind};
+use crate::logging::{GameLogger, LogLevel};
+use crate::time::DayNightState;
+use crate::world::map::PropInteraction;
+
+/// 摆件交互设置
+#[derive(Resource, Debug, Clone)]
+pub struct PropSettings {
+    /// 可交互距离
+    pub interact_range: f32,
+}
+
+impl Default for PropSettings {
+    fn default() -> Self {
+        Self {
+            interact_range: 32.0,
+        }
+    }
+}
+
+/// 交互键作用到摆件时点灯、熄灯或查看
+///
+/// 点灯与熄灯写回区块装饰层并把区块标记为已修改，随区块改动一起存档，
+/// 区块卸载后重新加载仍保持改动后的样子
+pub fn interact_with_props(
+    mut commands: Commands,
+    mut interacted: EventReader<Interacted>,
+    day_night: Res<DayNightState>,
+    props: Query<(&ChunkProp, &Parent)>,
+    mut chunks: Query<&mut Chunk>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let Some(entity) = interacted
+        .read()
+        .filter(|event| event.kind == InteractionKind::Prop)
+        .last()
+        .map(|event| event.entity)
+    else {
+        return;
+    };
+    let Ok((prop, parent)) = props.get(entity) else {
+        return;
+    };
+    let Some(interaction) = prop.kind.interaction() else {
+        return;
+    };
+
+    let message = match interaction {
+        PropInteraction::ToggleLight => {
+            let Some(kind) = prop.kind.toggled() else {
+                return;
+            };
+            let toggled = ChunkProp {
+                kind,
+                base_color: kind.visual().1,
+                ..*prop
+            };
+            toggled.apply(&mut commands.entity(entity), &day_night);
+
+            if let Some(data) = chunks
+                .get_mut(parent.get())
+                .ok()
+                .and_then(|chunk| chunk.into_inner().data.as_mut())
+            {
+                data.add_decoration(prop.local_x, prop.local_y, kind.id());
+                data.modified = true;
+            }
+
+            let verb = if kind.is_lit() { "点亮" } else { "熄灭" };
+            format!("{}了{}", verb, kind.name())
+        }
+        PropInteraction::Examine => format!("{}：{}", prop.kind.name(), prop.kind.description()),
+    };
+    if let Some(logger) = logger.as_mut() {
+        logger.log(LogLevel::Info, &message);
+    }
+}
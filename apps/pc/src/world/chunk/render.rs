@@ -1,6 +1,207 @@
-use super::{Chunk, CHUNK_SIZE};
-use crate::world::map::MapManager;
+use super::{
+    neighbor_types, Chunk, ChunkData, ChunkManager, ChunkStructure, TilesetAsset, TilesetLibrary,
+    CHUNK_SIZE,
+};
+use crate::render::components::{
+    AnimationComponent, LayerComponent, ParticleEmitter, RenderLayer, SpriteComponent,
+};
+use crate::render::lighting::LightSource;
+use crate::render::sorting::YSort;
+use crate::time::{DayNightState, SeasonChanged};
+use crate::world::map::terrain_render::generate_terrain_color;
+use crate::world::map::{
+    MapManager, PropType, SceneTrigger, Season, TileType, VegetationType, Zone,
+};
+use crate::world::physics::StaticCollider;
 use bevy::prelude::*;
+use std::time::Duration;
+
+/// 瓦片像素尺寸
+pub const TILE_PIXELS: f32 = 32.0;
+
+/// 瀑布特效组件
+#[derive(Component, Debug, Clone)]
+pub struct WaterfallEffect {
+    /// 水流强度
+    pub flow_strength: f32,
+}
+
+/// 区块瓦片组件
+///
+/// 保存瓦片类型和未经季节与昼夜调整的基础颜色，换季或光照变化时据此重新着色；
+/// 同时记下所在气候区域、相邻瓦片与变体，贴图集晚于区块加载或切换贴图设置时据此换上贴图
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ChunkTile {
+    pub tile_type: TileType,
+    pub base_color: Color,
+    pub zone: Zone,
+    /// 八个方向的相邻瓦片，区块之外为 None
+    pub neighbors: [Option<TileType>; 8],
+    pub variant: u8,
+}
+
+impl ChunkTile {
+    /// 季节着色前的颜色，有贴图时以白色为底，只叠加季节色调
+    fn unlit_color(&self, textured: bool, season: Season) -> Color {
+        let base = if textured {
+            Color::WHITE
+        } else {
+            self.base_color
+        };
+        seasonal_tile_color(self.tile_type, base, season)
+    }
+}
+
+/// 按世界坐标确定的瓦片变体，同一位置每次加载都一样
+fn tile_variant(x: i32, y: i32) -> u8 {
+    let hash = (x.wrapping_mul(73_856_093) ^ y.wrapping_mul(19_349_663)) as u32;
+    (hash >> 8) as u8
+}
+
+/// 给瓦片精灵换上贴图集中的格子，贴图集缺失或没有这类瓦片时画成纯色方块
+pub fn style_tile_sprite(
+    sprite: &mut Sprite,
+    tile: &ChunkTile,
+    tileset: Option<&TilesetAsset>,
+    season: Season,
+    day_night: &DayNightState,
+) {
+    let atlas = tileset.and_then(|tileset| {
+        let mask = tileset
+            .autotile(tile.tile_type)
+            .map_or(0, |rule| rule.mask(tile.tile_type, &tile.neighbors));
+        tileset
+            .sprite_index(tile.tile_type, mask, tile.variant)
+            .map(|index| (tileset, index))
+    });
+    match atlas {
+        Some((tileset, index)) => {
+            sprite.image = tileset.image.clone();
+            sprite.texture_atlas = Some(TextureAtlas {
+                layout: tileset.layout.clone(),
+                index,
+            });
+        }
+        None => {
+            sprite.image = Handle::default();
+            sprite.texture_atlas = None;
+        }
+    }
+    sprite.color = day_night.apply(tile.unlit_color(atlas.is_some(), season));
+}
+
+/// 按季节调整植被瓦片颜色，非植被瓦片保持基础颜色
+pub fn seasonal_tile_color(tile_type: TileType, base_color: Color, season: Season) -> Color {
+    if !tile_type.is_vegetation() {
+        return base_color;
+    }
+
+    let ([r, g, b], strength) = season.vegetation_tint(tile_type == TileType::Bamboo);
+    let base = base_color.to_srgba();
+    Color::srgba(
+        base.red + (r - base.red) * strength,
+        base.green + (g - base.green) * strength,
+        base.blue + (b - base.blue) * strength,
+        base.alpha,
+    )
+}
+
+/// 区块植被组件
+///
+/// 与瓦片一样保存基础颜色，换季与昼夜变化时重新着色
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ChunkVegetation {
+    pub kind: VegetationType,
+    pub base_color: Color,
+}
+
+/// 植被精灵的基础尺寸（像素）与颜色
+fn vegetation_visual(kind: VegetationType) -> (Vec2, Color) {
+    match kind {
+        VegetationType::Grass => (Vec2::new(12.0, 8.0), Color::srgb(0.35, 0.6, 0.25)),
+        VegetationType::Flower => (Vec2::new(10.0, 10.0), Color::srgb(0.9, 0.55, 0.7)),
+        VegetationType::Bush => (Vec2::new(18.0, 14.0), Color::srgb(0.25, 0.5, 0.2)),
+        VegetationType::Bamboo => (Vec2::new(12.0, 48.0), Color::srgb(0.45, 0.7, 0.3)),
+        VegetationType::Pine => (Vec2::new(28.0, 44.0), Color::srgb(0.1, 0.35, 0.2)),
+        VegetationType::Oak => (Vec2::new(32.0, 40.0), Color::srgb(0.2, 0.45, 0.15)),
+        VegetationType::Maple => (Vec2::new(30.0, 38.0), Color::srgb(0.3, 0.5, 0.15)),
+        VegetationType::Willow => (Vec2::new(32.0, 42.0), Color::srgb(0.45, 0.65, 0.35)),
+        VegetationType::DeadTree => (Vec2::new(20.0, 34.0), Color::srgb(0.4, 0.32, 0.25)),
+    }
+}
+
+/// 树干的碰撞体，草、花与灌木可以穿过
+///
+/// 精灵以底边中点为锚点，树干只取底部一小段，角色可以走到树冠后面
+fn trunk_collider(kind: VegetationType, scale: f32) -> Option<StaticCollider> {
+    let half_width = match kind {
+        VegetationType::Pine | VegetationType::Oak | VegetationType::Maple => 6.0,
+        VegetationType::Willow => 5.0,
+        VegetationType::DeadTree | VegetationType::Bamboo => 4.0,
+        VegetationType::Grass | VegetationType::Flower | VegetationType::Bush => return None,
+    };
+    Some(StaticCollider::new(Vec2::new(half_width * scale, 4.0)).with_offset(Vec2::new(0.0, 4.0)))
+}
+
+/// 按季节调整植被精灵颜色，枯树不随季节变化
+pub fn seasonal_vegetation_color(kind: VegetationType, base_color: Color, season: Season) -> Color {
+    if kind == VegetationType::DeadTree {
+        return base_color;
+    }
+
+    let ([r, g, b], strength) = season.vegetation_tint(kind.is_evergreen());
+    let base = base_color.to_srgba();
+    Color::srgba(
+        base.red + (r - base.red) * strength,
+        base.green + (g - base.green) * strength,
+        base.blue + (b - base.blue) * strength,
+        base.alpha,
+    )
+}
+
+/// 区块摆件组件
+///
+/// 记下所在的区块内坐标，点灯等交互改动时据此写回区块装饰层；
+/// 基础颜色供昼夜变化时重新着色，摆件不随季节变化
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ChunkProp {
+    pub kind: PropType,
+    pub local_x: usize,
+    pub local_y: usize,
+    pub base_color: Color,
+}
+
+impl ChunkProp {
+    /// 按摆件类型换上对应的颜色、碰撞体与光源，点灯与熄灯后也经由这里更新
+    pub fn apply(&self, entity: &mut EntityCommands, day_night: &DayNightState) {
+        let (size, _) = self.kind.visual();
+        entity.insert((
+            *self,
+            Sprite {
+                color: day_night.apply(self.base_color),
+                custom_size: Some(size),
+                anchor: bevy::sprite::Anchor::BottomCenter,
+                ..default()
+            },
+        ));
+        match self.kind.collider_half_width() {
+            Some(half_width) => {
+                entity.insert(
+                    StaticCollider::new(Vec2::new(half_width, 4.0))
+                        .with_offset(Vec2::new(0.0, 4.0)),
+                );
+            }
+            None => {
+                entity.remove::<StaticCollider>();
+            }
+        }
+        if self.kind.is_lit() {
+            entity.insert(LightSource::lantern());
+        } else {
+            entity.remove::<LightSource>();
+        }
+    }
+}
 
 /// 2.5D渲染设置
 #[derive(Resource)]
@@ -13,6 +214,8 @@ pub struct RenderSettings {
     pub tilt_angle: f32,
     /// 阴影强度
     pub shadow_strength: f32,
+    /// 地形使用贴图集，关闭时画成纯色方块
+    pub tile_textures: bool,
 }
 
 impl Default for RenderSettings {
@@ -22,6 +225,7 @@ impl Default for RenderSettings {
             height_scale: 0.5,
             tilt_angle: 0.6, // 约30度
             shadow_strength: 0.3,
+            tile_textures: true,
         }
     }
 }
@@ -39,65 +243,353 @@ pub fn calculate_height_offset(height: f32, settings: &RenderSettings) -> Vec2 {
     Vec2::new(x_offset, y_offset)
 }
 
-/// 根据地图管理器获取高度值
-pub fn get_height_from_map(x: i32, y: i32, map_manager: &MapManager) -> f32 {
-    map_manager.get_height_at(x, y)
-}
-
 /// 为区块中的瓦片应用2.5D效果
+///
+/// 瓦片颜色按当前季节与昼夜环境光着色，高度取自区块数据；
+/// 有贴图集时按瓦片类型与相邻瓦片取图集中的格子
+#[allow(clippy::too_many_arguments)]
 pub fn apply_2_5d_effect(
     chunk: &Chunk,
     chunk_entity: Entity,
-    map_manager: &MapManager,
+    zone: Zone,
+    tileset: Option<&TilesetAsset>,
+    season: Season,
+    day_night: &DayNightState,
     settings: &RenderSettings,
     commands: &mut Commands,
 ) {
     if let Some(chunk_data) = &chunk.data {
         for y in 0..CHUNK_SIZE {
             for x in 0..CHUNK_SIZE {
-                if let Some(tile_type_value) = chunk_data.get_tile(x, y) {
-                    // 计算世界坐标
-                    let world_x = chunk.coord.x * CHUNK_SIZE as i32 + x as i32;
-                    let world_y = chunk.coord.y * CHUNK_SIZE as i32 + y as i32;
-
-                    // 获取高度
-                    let height = get_height_from_map(world_x, world_y, map_manager);
-
-                    // 计算2.5D偏移
-                    let offset = calculate_height_offset(height, settings);
-
-                    // 创建瓦片实体并添加到区块
-                    // 这里只是示例，实际实现会更复杂
-                    let tile_entity = commands
-                        .spawn((
-                            // 位置组件
-                            Transform::from_xyz(
-                                x as f32 * 32.0 + offset.x,
-                                y as f32 * 32.0 + offset.y,
-                                height,
-                            ),
-                            // 其他组件...
-                        ))
-                        .id();
-
-                    // 将瓦片实体添加为区块的子实体
-                    commands.entity(chunk_entity).add_child(tile_entity);
-                }
+                let Some(tile_type) = chunk_data.get_tile(x, y).and_then(TileType::from_u8) else {
+                    continue;
+                };
+
+                // 获取高度
+                let height = chunk_data.get_height(x, y);
+
+                // 计算2.5D偏移
+                let offset = calculate_height_offset(height, settings);
+
+                let tile = ChunkTile {
+                    tile_type,
+                    base_color: generate_terrain_color(height, tile_type),
+                    zone,
+                    neighbors: neighbor_types(chunk_data, x, y),
+                    variant: tile_variant(
+                        chunk.coord.x * CHUNK_SIZE as i32 + x as i32,
+                        chunk.coord.y * CHUNK_SIZE as i32 + y as i32,
+                    ),
+                };
+                let mut sprite = Sprite {
+                    custom_size: Some(Vec2::splat(TILE_PIXELS)),
+                    ..default()
+                };
+                style_tile_sprite(&mut sprite, &tile, tileset, season, day_night);
+
+                // 创建瓦片实体并添加到区块
+                let tile_entity = commands
+                    .spawn((
+                        tile,
+                        sprite,
+                        Transform::from_xyz(
+                            x as f32 * TILE_PIXELS + offset.x,
+                            y as f32 * TILE_PIXELS + offset.y,
+                            0.0,
+                        ),
+                        LayerComponent {
+                            layer: RenderLayer::Terrain,
+                            sub_order: 0,
+                        },
+                        YSort {
+                            foot_offset: 0.0,
+                            elevation: offset.y,
+                        },
+                    ))
+                    .id();
+
+                // 将瓦片实体添加为区块的子实体
+                commands.entity(chunk_entity).add_child(tile_entity);
             }
         }
     }
 }
 
-/// 计算相邻瓦片的高度差，用于生成边缘效果
-pub fn calculate_height_difference(
-    x: i32,
-    y: i32,
-    neighbor_x: i32,
-    neighbor_y: i32,
-    map_manager: &MapManager,
-) -> f32 {
-    let height = map_manager.get_height_at(x, y);
-    let neighbor_height = map_manager.get_height_at(neighbor_x, neighbor_y);
+/// 为区块装饰层中的植被创建精灵实体
+///
+/// 植被锚在瓦片底部，以树根所在的地面与角色按纵坐标互相遮挡，作为区块的子实体随区块一起卸载；
+/// 尺寸带有按位置确定的轻微差异，避免成片树木完全一样
+pub fn spawn_chunk_vegetation(
+    chunk_entity: Entity,
+    data: &ChunkData,
+    season: Season,
+    day_night: &DayNightState,
+    vegetation_size: f32,
+    settings: &RenderSettings,
+    commands: &mut Commands,
+) {
+    for y in 0..CHUNK_SIZE {
+        for x in 0..CHUNK_SIZE {
+            let Some(kind) = data.get_decoration(x, y).and_then(VegetationType::from_u8) else {
+                continue;
+            };
+
+            let height = data.get_height(x, y);
+            let offset = calculate_height_offset(height, settings);
+            let (base_size, base_color) = vegetation_visual(kind);
+            let variation = 0.85 + ((x * 7 + y * 13) % 8) as f32 * 0.05;
+            let size = base_size * vegetation_size * variation;
+
+            let vegetation = commands
+                .spawn((
+                    ChunkVegetation { kind, base_color },
+                    Sprite {
+                        color: day_night.apply(seasonal_vegetation_color(kind, base_color, season)),
+                        custom_size: Some(size),
+                        anchor: bevy::sprite::Anchor::BottomCenter,
+                        ..default()
+                    },
+                    Transform::from_xyz(
+                        x as f32 * TILE_PIXELS + offset.x,
+                        (y as f32 - 0.5) * TILE_PIXELS + offset.y,
+                        0.0,
+                    ),
+                    LayerComponent {
+                        layer: RenderLayer::Decoration,
+                        sub_order: 0,
+                    },
+                    YSort {
+                        foot_offset: 0.0,
+                        elevation: offset.y,
+                    },
+                ))
+                .id();
+
+            if let Some(collider) = trunk_collider(kind, vegetation_size * variation) {
+                commands.entity(vegetation).insert(collider);
+            }
+
+            commands.entity(chunk_entity).add_child(vegetation);
+        }
+    }
+}
+
+/// 为区块装饰层中的摆件创建实体
+///
+/// 与植被一样锚在瓦片底部，作为区块的子实体随区块一起卸载；
+/// 点灯等改动写回区块数据，区块重新加载时按改动后的样子生成
+pub fn spawn_chunk_props(
+    chunk_entity: Entity,
+    data: &ChunkData,
+    day_night: &DayNightState,
+    settings: &RenderSettings,
+    commands: &mut Commands,
+) {
+    for y in 0..CHUNK_SIZE {
+        for x in 0..CHUNK_SIZE {
+            let Some(kind) = data.get_decoration(x, y).and_then(PropType::from_u8) else {
+                continue;
+            };
+
+            let offset = calculate_height_offset(data.get_height(x, y), settings);
+            let prop = ChunkProp {
+                kind,
+                local_x: x,
+                local_y: y,
+                base_color: kind.visual().1,
+            };
+            let mut entity = commands.spawn((
+                Name::new(format!("Prop: {}", kind.name())),
+                Transform::from_xyz(
+                    x as f32 * TILE_PIXELS + offset.x,
+                    (y as f32 - 0.5) * TILE_PIXELS + offset.y,
+                    0.0,
+                ),
+                LayerComponent {
+                    layer: RenderLayer::Decoration,
+                    sub_order: 0,
+                },
+                YSort {
+                    foot_offset: 0.0,
+                    elevation: offset.y,
+                },
+            ));
+            prop.apply(&mut entity, day_night);
+            let prop_entity = entity.id();
+
+            commands.entity(chunk_entity).add_child(prop_entity);
+        }
+    }
+}
+
+/// 换季或昼夜环境光变化时重新着色已加载的瓦片、植被与摆件
+#[allow(clippy::type_complexity)]
+pub fn retint_tiles(
+    mut season_events: EventReader<SeasonChanged>,
+    map_manager: Res<MapManager>,
+    day_night: Res<DayNightState>,
+    mut tiles: Query<(&ChunkTile, &mut Sprite), Without<ChunkVegetation>>,
+    mut vegetation: Query<(&ChunkVegetation, &mut Sprite), Without<ChunkTile>>,
+    mut props: Query<(&ChunkProp, &mut Sprite), (Without<ChunkTile>, Without<ChunkVegetation>)>,
+) {
+    // 换季事件携带新季节，避免读到地图管理器尚未同步的旧值
+    let season_event = season_events.read().last().map(|event| event.current);
+    let season_changed = season_event.is_some() && map_manager.climate_config().enable_seasons;
+    if !season_changed && !day_night.is_changed() {
+        return;
+    }
+
+    let season = season_event
+        .filter(|_| season_changed)
+        .unwrap_or_else(|| map_manager.current_season());
+    for (tile, mut sprite) in tiles.iter_mut() {
+        let textured = sprite.texture_atlas.is_some();
+        sprite.color = day_night.apply(tile.unlit_color(textured, season));
+    }
+    for (plant, mut sprite) in vegetation.iter_mut() {
+        sprite.color = day_night.apply(seasonal_vegetation_color(
+            plant.kind,
+            plant.base_color,
+            season,
+        ));
+    }
+    for (prop, mut sprite) in props.iter_mut() {
+        sprite.color = day_night.apply(prop.base_color);
+    }
+}
+
+/// 贴图集加载或热重载、地形贴图设置切换后，重新给已加载的瓦片换上贴图或纯色方块
+pub fn restyle_chunk_tiles(
+    library: Res<TilesetLibrary>,
+    tilesets: Res<Assets<TilesetAsset>>,
+    chunk_manager: Res<ChunkManager>,
+    map_manager: Res<MapManager>,
+    day_night: Res<DayNightState>,
+    mut textured: Local<Option<bool>>,
+    mut tiles: Query<(&ChunkTile, &mut Sprite)>,
+) {
+    let enabled = chunk_manager.render_settings().tile_textures;
+    if !library.is_changed() && *textured == Some(enabled) {
+        return;
+    }
+    *textured = Some(enabled);
 
-    (height - neighbor_height).abs()
+    let season = map_manager.current_season();
+    for (tile, mut sprite) in tiles.iter_mut() {
+        let tileset = library.get(tile.zone, &tilesets).filter(|_| enabled);
+        style_tile_sprite(&mut sprite, tile, tileset, season, &day_night);
+    }
+}
+
+/// 为区块中的结构物创建实体
+///
+/// 结构物实体作为区块的子实体生成，随区块一起卸载
+pub fn spawn_chunk_structures(
+    chunk_entity: Entity,
+    structures: &[ChunkStructure],
+    commands: &mut Commands,
+) {
+    for structure in structures {
+        match structure {
+            ChunkStructure::Waterfall {
+                local_x,
+                local_y,
+                height,
+                width,
+                direction,
+                flow_strength,
+                splash_range,
+            } => {
+                let direction = Vec2::from_array(*direction);
+                let frame_time = Duration::from_secs_f32((0.12 / flow_strength.max(0.1)).min(0.2));
+
+                let waterfall = commands
+                    .spawn((
+                        WaterfallEffect {
+                            flow_strength: *flow_strength,
+                        },
+                        Name::new("Waterfall"),
+                        Transform::from_xyz(
+                            *local_x as f32 * TILE_PIXELS,
+                            *local_y as f32 * TILE_PIXELS,
+                            0.0,
+                        ),
+                        SpriteComponent {
+                            texture_path: "textures/effects/waterfall.png".to_string(),
+                            size: Vec2::new(width * TILE_PIXELS, height * TILE_PIXELS),
+                            flip_x: false,
+                            flip_y: false,
+                            color: Color::WHITE,
+                            visible: true,
+                        },
+                        AnimationComponent {
+                            current_animation: "flow".to_string(),
+                            animations: vec!["flow".to_string()],
+                            frame_time,
+                            current_frame: 0,
+                            total_frames: 8,
+                            is_playing: true,
+                            is_looping: true,
+                            timer: Timer::new(frame_time, TimerMode::Repeating),
+                        },
+                        LayerComponent {
+                            layer: RenderLayer::Decoration,
+                            sub_order: 0,
+                        },
+                    ))
+                    .id();
+
+                // 瀑布底部的水花
+                let splash = commands
+                    .spawn((
+                        Name::new("WaterfallSplash"),
+                        Transform::from_translation((direction * height * TILE_PIXELS).extend(0.0)),
+                        ParticleEmitter {
+                            rate: 20.0 * flow_strength,
+                            lifetime: 0.8,
+                            velocity: Vec2::new(0.0, 24.0 * flow_strength),
+                            spread: splash_range * TILE_PIXELS,
+                            color: Color::srgba(0.85, 0.92, 1.0, 0.7),
+                            active: true,
+                        },
+                        LayerComponent {
+                            layer: RenderLayer::Effects,
+                            sub_order: 0,
+                        },
+                    ))
+                    .id();
+
+                commands.entity(waterfall).add_child(splash);
+                commands.entity(chunk_entity).add_child(waterfall);
+            }
+            // 居民在世界空间中行走，不挂在区块下，由区块加载系统单独生成
+            ChunkStructure::Resident { .. } => {}
+            // 场景中心只用于登记兴趣点，没有实体
+            ChunkStructure::Landmark { .. } => {}
+            ChunkStructure::Trigger {
+                local_x,
+                local_y,
+                radius,
+                id,
+            } => {
+                let trigger = commands
+                    .spawn((
+                        SceneTrigger {
+                            id: id.clone(),
+                            radius: radius * TILE_PIXELS,
+                            inside: false,
+                        },
+                        Name::new(format!("SceneTrigger {}", id)),
+                        Transform::from_xyz(
+                            *local_x as f32 * TILE_PIXELS,
+                            *local_y as f32 * TILE_PIXELS,
+                            0.0,
+                        ),
+                        Visibility::default(),
+                    ))
+                    .id();
+                commands.entity(chunk_entity).add_child(trigger);
+            }
+        }
+    }
 }
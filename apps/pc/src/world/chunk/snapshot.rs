@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use super::{ChunkCoord, ChunkData, ChunkManager, LayerValues, CHUNK_SIZE};
+use crate::world::map::MapManager;
+
+/// 世界快照基准文件路径
+pub const WORLD_SNAPSHOT_BASELINE_PATH: &str = "src/config/worldgen_snapshot.json";
+
+/// 快照格式版本，快照结构本身变化时递增
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// 快照覆盖范围
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SnapshotSpec {
+    /// 参与快照的世界种子
+    pub seeds: Vec<u32>,
+    /// 以原点区块为中心的半径（区块数）
+    pub radius: i32,
+}
+
+impl Default for SnapshotSpec {
+    fn default() -> Self {
+        Self {
+            seeds: vec![42, 1337, 20240601],
+            radius: 2,
+        }
+    }
+}
+
+/// 单个区块的各层哈希
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChunkSnapshot {
+    pub seed: u32,
+    pub x: i32,
+    pub y: i32,
+    /// 层名称到哈希（十六进制）的映射
+    pub layers: BTreeMap<String, String>,
+}
+
+/// 世界快照
+///
+/// # 设计思路
+/// 1. 只记录每个区块各数据层的哈希，基准文件小，便于随代码一起评审
+/// 2. 哈希使用固定的 FNV-1a 算法，不依赖标准库哈希器的实现细节
+/// 3. 浮点数按位哈希，任何数值变化都会被发现
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorldSnapshot {
+    pub version: u32,
+    pub chunk_size: usize,
+    pub spec: SnapshotSpec,
+    pub chunks: Vec<ChunkSnapshot>,
+}
+
+impl WorldSnapshot {
+    /// 按覆盖范围生成快照，不依赖游戏运行
+    pub fn generate(spec: &SnapshotSpec) -> Self {
+        let mut chunks = Vec::new();
+        for &seed in &spec.seeds {
+            let map_manager = MapManager::new(seed);
+            let mut chunk_manager = ChunkManager::default();
+            chunk_manager.initialize_terrain_generator(&map_manager);
+
+            for y in -spec.radius..=spec.radius {
+                for x in -spec.radius..=spec.radius {
+                    let data = chunk_manager.generate_chunk_data(ChunkCoord { x, y }, &map_manager);
+                    chunks.push(ChunkSnapshot {
+                        seed,
+                        x,
+                        y,
+                        layers: hash_layers(&data),
+                    });
+                }
+            }
+        }
+
+        Self {
+            version: SNAPSHOT_VERSION,
+            chunk_size: CHUNK_SIZE,
+            spec: spec.clone(),
+            chunks,
+        }
+    }
+
+    /// 从文件读取
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 写入文件
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// 与基准比较，列出变化的区块与数据层
+    pub fn diff(&self, baseline: &WorldSnapshot) -> SnapshotDiff {
+        let mut diff = SnapshotDiff {
+            format_changed: self.version != baseline.version
+                || self.chunk_size != baseline.chunk_size,
+            ..Default::default()
+        };
+
+        fn index(snapshot: &WorldSnapshot) -> BTreeMap<(u32, i32, i32), &ChunkSnapshot> {
+            snapshot
+                .chunks
+                .iter()
+                .map(|chunk| ((chunk.seed, chunk.x, chunk.y), chunk))
+                .collect()
+        }
+        let current = index(self);
+        let previous = index(baseline);
+
+        for (key, chunk) in &current {
+            let Some(old) = previous.get(key) else {
+                diff.added.push(*key);
+                continue;
+            };
+            let layers: Vec<String> = chunk
+                .layers
+                .keys()
+                .chain(old.layers.keys())
+                .filter(|layer| chunk.layers.get(*layer) != old.layers.get(*layer))
+                .cloned()
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            if !layers.is_empty() {
+                diff.changed.push(ChunkChange {
+                    seed: key.0,
+                    coord: ChunkCoord { x: key.1, y: key.2 },
+                    layers,
+                });
+            }
+        }
+        diff.removed = previous
+            .keys()
+            .filter(|key| !current.contains_key(*key))
+            .copied()
+            .collect();
+
+        diff
+    }
+}
+
+/// 单个区块的变化
+#[derive(Debug, Clone)]
+pub struct ChunkChange {
+    pub seed: u32,
+    pub coord: ChunkCoord,
+    /// 哈希不同的数据层
+    pub layers: Vec<String>,
+}
+
+/// 快照比较结果
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotDiff {
+    /// 快照版本或区块尺寸不同，逐区块的比较结果仅供参考
+    pub format_changed: bool,
+    pub changed: Vec<ChunkChange>,
+    /// 基准中没有的区块 (种子, x, y)
+    pub added: Vec<(u32, i32, i32)>,
+    /// 当前快照中缺少的区块 (种子, x, y)
+    pub removed: Vec<(u32, i32, i32)>,
+}
+
+impl SnapshotDiff {
+    /// 是否与基准完全一致
+    pub fn is_empty(&self) -> bool {
+        !self.format_changed
+            && self.changed.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+    }
+}
+
+impl fmt::Display for SnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "世界快照与基准一致");
+        }
+        if self.format_changed {
+            writeln!(f, "快照格式或区块尺寸已变化")?;
+        }
+
+        // 按数据层汇总，便于判断改动影响的范围
+        let mut per_layer: BTreeMap<&str, usize> = BTreeMap::new();
+        for change in &self.changed {
+            for layer in &change.layers {
+                *per_layer.entry(layer.as_str()).or_default() += 1;
+            }
+        }
+        writeln!(f, "{} 个区块发生变化", self.changed.len())?;
+        for (layer, count) in &per_layer {
+            writeln!(f, "  {}: {} 个区块", layer, count)?;
+        }
+
+        for change in &self.changed {
+            writeln!(
+                f,
+                "  种子 {} 区块 ({}, {}): {}",
+                change.seed,
+                change.coord.x,
+                change.coord.y,
+                change.layers.join(", ")
+            )?;
+        }
+        for (seed, x, y) in &self.added {
+            writeln!(f, "  新增 种子 {} 区块 ({}, {})", seed, x, y)?;
+        }
+        for (seed, x, y) in &self.removed {
+            writeln!(f, "  缺少 种子 {} 区块 ({}, {})", seed, x, y)?;
+        }
+        Ok(())
+    }
+}
+
+/// 计算区块各数据层的哈希
+pub fn hash_layers(data: &ChunkData) -> BTreeMap<String, String> {
+    let mut tiles = Fnv64::new();
+    let mut heights = Fnv64::new();
+    let mut decorations = Fnv64::new();
+    let mut water = Fnv64::new();
+    let mut flow = Fnv64::new();
+    let mut climate = Fnv64::new();
+
+    for y in 0..CHUNK_SIZE {
+        for x in 0..CHUNK_SIZE {
+            tiles.write_option(data.get_tile(x, y));
+            heights.write(&data.get_height(x, y).to_bits().to_le_bytes());
+            decorations.write_option(data.get_decoration(x, y));
+            water.write(&data.get_water_depth(x, y).to_bits().to_le_bytes());
+            let current = data.get_flow(x, y);
+            flow.write(&current.x.to_bits().to_le_bytes());
+            flow.write(&current.y.to_bits().to_le_bytes());
+            climate.write_option(data.get_climate_zone(x, y).map(|zone| zone as u8));
+        }
+    }
+
+    // 结构物按序列化结果哈希，字段增减同样会体现
+    let mut structures = Fnv64::new();
+    for structure in data.structures() {
+        let encoded = serde_json::to_string(structure).unwrap_or_default();
+        structures.write(encoded.as_bytes());
+    }
+
+    [
+        ("tiles", tiles),
+        ("heights", heights),
+        ("decorations", decorations),
+        ("water_depth", water),
+        ("flow", flow),
+        ("climate_zones", climate),
+        ("structures", structures),
+    ]
+    .into_iter()
+    .map(|(name, hash)| (name.to_string(), format!("{:016x}", hash.finish())))
+    .chain(data.layers().iter().map(|(name, values)| {
+        // 附加数据层各自成为一层，没有写入过的区块不出现
+        let mut hash = Fnv64::new();
+        match values {
+            LayerValues::Bytes(values) => hash.write(values),
+            LayerValues::Floats(values) => {
+                for value in values {
+                    hash.write(&value.to_bits().to_le_bytes());
+                }
+            }
+        }
+        (
+            format!("layers.{}", name),
+            format!("{:016x}", hash.finish()),
+        )
+    }))
+    .collect()
+}
+
+/// FNV-1a 64 位哈希
+pub(super) struct Fnv64(u64);
+
+impl Fnv64 {
+    pub(super) fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub(super) fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// 空值与任何取值都区分开
+    fn write_option(&mut self, value: Option<u8>) {
+        match value {
+            Some(value) => self.write(&[1, value]),
+            None => self.write(&[0]),
+        }
+    }
+
+    pub(super) fn finish(&self) -> u64 {
+        self.0
+    }
+}
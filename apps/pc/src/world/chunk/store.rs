@@ -0,0 +1,110 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{ChunkCoord, ChunkData, CHUNK_SIZE};
+
+/// 区块存储目录，世界生成工具写入、区块加载时读取的位置
+pub const CHUNK_STORE_DIR: &str = "chunks";
+
+/// 存储说明文件名，记录这些区块属于哪个种子
+const MANIFEST_FILE: &str = "world.json";
+
+/// 存储说明
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkStoreManifest {
+    pub seed: u32,
+    pub chunk_size: usize,
+    pub updated_at: String,
+}
+
+/// 区块存储
+///
+/// 每个区块一个 bincode 文件，文件名为 `x_y.dat`；
+/// 不同种子的区块不能混在一个目录里，写入前与读取前都先核对说明文件
+#[derive(Debug, Clone)]
+pub struct ChunkStore {
+    dir: PathBuf,
+}
+
+impl ChunkStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 区块文件路径
+    pub fn path(&self, coord: ChunkCoord) -> PathBuf {
+        self.dir.join(format!("{}_{}.dat", coord.x, coord.y))
+    }
+
+    /// 读取说明文件，目录中还没有区块时为空
+    pub fn manifest(&self) -> Result<Option<ChunkStoreManifest>, Box<dyn std::error::Error>> {
+        let path = self.dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+    }
+
+    /// 打开某个种子的存储，说明文件缺失或种子、区块尺寸不符时为空
+    pub fn open(dir: impl Into<PathBuf>, seed: u32) -> Option<Self> {
+        let store = Self::new(dir);
+        match store.manifest() {
+            Ok(Some(manifest)) if manifest.seed == seed && manifest.chunk_size == CHUNK_SIZE => {
+                Some(store)
+            }
+            _ => None,
+        }
+    }
+
+    /// 准备写入某个种子的区块，目录中已有其他种子或区块尺寸的数据时报错
+    pub fn prepare(&self, seed: u32) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(manifest) = self.manifest()? {
+            if manifest.seed != seed || manifest.chunk_size != CHUNK_SIZE {
+                return Err(format!(
+                    "区块存储 {} 中是种子 {}、区块尺寸 {} 的数据，请换一个目录",
+                    self.dir.display(),
+                    manifest.seed,
+                    manifest.chunk_size
+                )
+                .into());
+            }
+        }
+        fs::create_dir_all(&self.dir)?;
+        let manifest = ChunkStoreManifest {
+            seed,
+            chunk_size: CHUNK_SIZE,
+            updated_at: Local::now().to_rfc3339(),
+        };
+        fs::write(
+            self.dir.join(MANIFEST_FILE),
+            serde_json::to_string_pretty(&manifest)?,
+        )?;
+        Ok(())
+    }
+
+    /// 写入一个区块，返回写入的字节数
+    pub fn write(
+        &self,
+        coord: ChunkCoord,
+        data: &ChunkData,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let bytes = bincode::serialize(data)?;
+        fs::write(self.path(coord), &bytes)?;
+        Ok(bytes.len())
+    }
+
+    /// 读取一个区块，文件缺失或损坏时报错
+    pub fn read(
+        &self,
+        coord: ChunkCoord,
+    ) -> Result<ChunkData, Box<dyn std::error::Error + Send + Sync>> {
+        let bytes = fs::read(self.path(coord))?;
+        Ok(bincode::deserialize(&bytes)?)
+    }
+}
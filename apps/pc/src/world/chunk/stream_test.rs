@@ -0,0 +1,697 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use super::{ChunkCoord, ChunkManager, TILE_PIXELS};
+use crate::config::resolve_data_path;
+use crate::logging::{GameLogger, LogLevel};
+use crate::render::camera::CameraController;
+use crate::world::entity::{Character, Player};
+use crate::world::map::MapManager;
+
+/// 压测场景数据文件路径
+pub const STREAM_TEST_DATA_PATH: &str = "src/config/stream_tests.json";
+/// 压测报告的输出目录
+pub const STREAM_TEST_REPORT_DIR: &str = "reports/streaming";
+
+/// 无窗口压测的模拟帧时长（秒）
+const HEADLESS_FRAME_TIME: f64 = 1.0 / 60.0;
+
+/// 压测场景
+///
+/// # 参数说明
+/// - waypoints: 途经点（瓦片坐标），依次直线移动
+/// - speeds: 每一轮的移动速度（瓦片/秒），每个速度从头跑一遍路径
+/// - settle: 到达终点后等待加载队列清空的最长时间（秒）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamScenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub waypoints: Vec<[f32; 2]>,
+    pub speeds: Vec<f32>,
+    #[serde(default = "default_settle")]
+    pub settle: f32,
+}
+
+fn default_settle() -> f32 {
+    3.0
+}
+
+/// 压测场景库
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StreamScenarioLibrary {
+    pub scenarios: Vec<StreamScenario>,
+}
+
+impl StreamScenarioLibrary {
+    /// 从文件读取
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 按名字取得场景
+    pub fn get(&self, name: &str) -> Option<&StreamScenario> {
+        self.scenarios.iter().find(|scenario| scenario.name == name)
+    }
+
+    /// 所有场景的名字
+    pub fn names(&self) -> Vec<&str> {
+        self.scenarios
+            .iter()
+            .map(|scenario| scenario.name.as_str())
+            .collect()
+    }
+}
+
+/// 按路程取位置的折线路径（像素坐标）
+#[derive(Debug, Clone)]
+pub struct StreamPath {
+    points: Vec<Vec2>,
+    length: f32,
+}
+
+impl StreamPath {
+    /// 由场景的途经点创建
+    pub fn new(waypoints: &[[f32; 2]]) -> Self {
+        let points: Vec<Vec2> = waypoints
+            .iter()
+            .map(|point| Vec2::from_array(*point) * TILE_PIXELS)
+            .collect();
+        let length = points
+            .windows(2)
+            .map(|pair| pair[0].distance(pair[1]))
+            .sum();
+        Self { points, length }
+    }
+
+    /// 全程长度（像素）
+    pub fn length(&self) -> f32 {
+        self.length
+    }
+
+    /// 走过 `distance` 像素后的位置，超出全程时停在终点
+    pub fn position_at(&self, distance: f32) -> Vec2 {
+        let mut remaining = distance.max(0.0);
+        for pair in self.points.windows(2) {
+            let segment = pair[0].distance(pair[1]);
+            if remaining <= segment && segment > 0.0 {
+                return pair[0].lerp(pair[1], remaining / segment);
+            }
+            remaining -= segment;
+        }
+        self.points.last().copied().unwrap_or(Vec2::ZERO)
+    }
+}
+
+/// 一组数值的统计
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct StreamStats {
+    pub count: usize,
+    pub min: f64,
+    pub mean: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+impl StreamStats {
+    /// 统计一组数值
+    pub fn from_values(values: &[f64]) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let p95_index = ((sorted.len() as f64 * 0.95).ceil() as usize).clamp(1, sorted.len()) - 1;
+        Self {
+            count: sorted.len(),
+            min: sorted[0],
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p95: sorted[p95_index],
+            max: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+/// 每帧的采样
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamFrameSample {
+    /// 本轮开始后的时间（秒）
+    pub time: f64,
+    pub frame_ms: f64,
+    /// 本帧处理后仍在排队的区块数
+    pub queue_depth: usize,
+    pub loaded_chunks: usize,
+    pub x: f32,
+    pub y: f32,
+}
+
+/// 一轮压测的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamPassReport {
+    /// 移动速度（瓦片/秒）
+    pub speed: f32,
+    pub duration_secs: f64,
+    pub frames: usize,
+    pub chunks_loaded: usize,
+    pub chunks_unloaded: usize,
+    /// 排队期间就离开视距、没有加载的区块数
+    pub chunks_skipped: usize,
+    /// 到达终点后队列是否在等待时间内清空
+    pub settled: bool,
+    pub load_latency_ms: StreamStats,
+    pub load_latency_frames: StreamStats,
+    pub unload_latency_ms: StreamStats,
+    pub queue_depth: StreamStats,
+    pub frame_ms: StreamStats,
+    /// 超过卡顿阈值的帧数
+    pub hitches: usize,
+    pub samples: Vec<StreamFrameSample>,
+}
+
+/// 压测报告
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamTestReport {
+    pub scenario: String,
+    /// `game` 为游戏内实测，`headless` 为不渲染只生成数据
+    pub mode: String,
+    pub started_at: String,
+    pub view_distance: i32,
+    pub load_budget: usize,
+    pub hitch_threshold_ms: f64,
+    pub passes: Vec<StreamPassReport>,
+}
+
+impl StreamTestReport {
+    /// 写入输出目录，返回报告路径
+    pub fn save(&self, dir: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        fs::create_dir_all(dir)?;
+        let stamp = Local::now().format("%Y%m%d-%H%M%S");
+        let path = Path::new(dir).join(format!("{}_{}_{}.json", self.scenario, self.mode, stamp));
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+}
+
+impl fmt::Display for StreamTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "区块流式加载压测 {}（{}，视距 {}，每帧预算 {}）",
+            self.scenario, self.mode, self.view_distance, self.load_budget
+        )?;
+        for pass in &self.passes {
+            writeln!(
+                f,
+                "  {:>6.0} 瓦片/秒：{:.1} 秒 {} 帧，加载 {} 卸载 {} 跳过 {}{}",
+                pass.speed,
+                pass.duration_secs,
+                pass.frames,
+                pass.chunks_loaded,
+                pass.chunks_unloaded,
+                pass.chunks_skipped,
+                if pass.settled {
+                    ""
+                } else {
+                    "，队列未清空"
+                }
+            )?;
+            writeln!(
+                f,
+                "         加载延迟 平均 {:.1}ms / p95 {:.1}ms / 最大 {:.1}ms（p95 {:.0} 帧）",
+                pass.load_latency_ms.mean,
+                pass.load_latency_ms.p95,
+                pass.load_latency_ms.max,
+                pass.load_latency_frames.p95
+            )?;
+            writeln!(
+                f,
+                "         队列深度 平均 {:.1} / 最大 {:.0}，帧时间 平均 {:.2}ms / p95 {:.2}ms / 最大 {:.2}ms，卡顿 {} 帧",
+                pass.queue_depth.mean,
+                pass.queue_depth.max,
+                pass.frame_ms.mean,
+                pass.frame_ms.p95,
+                pass.frame_ms.max,
+                pass.hitches
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 单轮压测的记录器
+///
+/// # 设计思路
+/// 1. 加载系统运行前记下视距内缺失的区块，运行后看哪些已经加载，差值就是加载延迟
+/// 2. 卸载同理，只是看的是超出视距的区块何时从管理器中移除
+/// 3. 时间由调用方给出：游戏内用真实时间，无窗口压测用模拟时间加上实际生成耗时
+#[derive(Debug, Default)]
+pub struct StreamRecorder {
+    /// 排队等待加载的区块：开始等待的时间与帧序号
+    pending_loads: HashMap<ChunkCoord, (f64, usize)>,
+    pending_unloads: HashMap<ChunkCoord, f64>,
+    frame: usize,
+    load_latency_ms: Vec<f64>,
+    load_latency_frames: Vec<f64>,
+    unload_latency_ms: Vec<f64>,
+    skipped: usize,
+    samples: Vec<StreamFrameSample>,
+}
+
+impl StreamRecorder {
+    /// 加载系统运行前调用，记下需要加载与卸载的区块
+    pub fn before_streaming(&mut self, manager: &ChunkManager, now: f64) {
+        let wanted: HashSet<ChunkCoord> = manager.get_chunks_to_load().into_iter().collect();
+        // 排队中又离开视距的区块不计入延迟
+        let before = self.pending_loads.len();
+        self.pending_loads.retain(|coord, _| wanted.contains(coord));
+        self.skipped += before - self.pending_loads.len();
+        for coord in wanted {
+            self.pending_loads.entry(coord).or_insert((now, self.frame));
+        }
+        for coord in manager.get_chunks_to_unload() {
+            self.pending_unloads.entry(coord).or_insert(now);
+        }
+    }
+
+    /// 加载系统运行后调用，结算本帧完成的加载与卸载
+    pub fn after_streaming(
+        &mut self,
+        manager: &ChunkManager,
+        now: f64,
+        frame_ms: f64,
+        position: Vec2,
+    ) {
+        let frame = self.frame;
+        let (load_ms, load_frames) = (&mut self.load_latency_ms, &mut self.load_latency_frames);
+        self.pending_loads.retain(|coord, (since, since_frame)| {
+            if !manager.chunks.contains_key(coord) {
+                return true;
+            }
+            load_ms.push((now - *since) * 1000.0);
+            load_frames.push((frame - *since_frame) as f64);
+            false
+        });
+        let unload_ms = &mut self.unload_latency_ms;
+        self.pending_unloads.retain(|coord, since| {
+            if manager.chunks.contains_key(coord) {
+                return true;
+            }
+            unload_ms.push((now - *since) * 1000.0);
+            false
+        });
+
+        self.samples.push(StreamFrameSample {
+            time: now,
+            frame_ms,
+            queue_depth: self.pending_loads.len(),
+            loaded_chunks: manager.chunks.len(),
+            x: position.x,
+            y: position.y,
+        });
+        self.frame += 1;
+    }
+
+    /// 加载队列是否已经清空
+    pub fn is_settled(&self) -> bool {
+        self.pending_loads.is_empty()
+    }
+
+    /// 结束本轮，生成结果
+    pub fn finish(self, speed: f32, settled: bool, hitch_threshold_ms: f64) -> StreamPassReport {
+        let frame_ms: Vec<f64> = self.samples.iter().map(|sample| sample.frame_ms).collect();
+        let queue: Vec<f64> = self
+            .samples
+            .iter()
+            .map(|sample| sample.queue_depth as f64)
+            .collect();
+        StreamPassReport {
+            speed,
+            duration_secs: self.samples.last().map_or(0.0, |sample| sample.time),
+            frames: self.samples.len(),
+            chunks_loaded: self.load_latency_ms.len(),
+            chunks_unloaded: self.unload_latency_ms.len(),
+            chunks_skipped: self.skipped,
+            settled,
+            load_latency_ms: StreamStats::from_values(&self.load_latency_ms),
+            load_latency_frames: StreamStats::from_values(&self.load_latency_frames),
+            unload_latency_ms: StreamStats::from_values(&self.unload_latency_ms),
+            queue_depth: StreamStats::from_values(&queue),
+            frame_ms: StreamStats::from_values(&frame_ms),
+            hitches: frame_ms
+                .iter()
+                .filter(|ms| **ms > hitch_threshold_ms)
+                .count(),
+            samples: self.samples,
+        }
+    }
+}
+
+/// 压测配置
+///
+/// # 参数说明
+/// - hitch_threshold_ms: 帧时间超过该值算作一次卡顿
+/// - output_dir: 报告输出目录
+#[derive(Resource, Debug, Clone)]
+pub struct StreamTestSettings {
+    pub hitch_threshold_ms: f64,
+    pub output_dir: String,
+}
+
+impl Default for StreamTestSettings {
+    fn default() -> Self {
+        Self {
+            hitch_threshold_ms: 33.3,
+            output_dir: STREAM_TEST_REPORT_DIR.to_string(),
+        }
+    }
+}
+
+/// 开始一次游戏内压测
+#[derive(Event, Debug, Clone)]
+pub struct StreamTestRequest {
+    pub scenario: String,
+    /// 覆盖场景中的速度列表
+    pub speeds: Option<Vec<f32>>,
+    /// 结束后退出游戏，用于脚本化的重复测试
+    pub exit_on_finish: bool,
+}
+
+/// 进行中的游戏内压测
+#[derive(Resource)]
+pub struct StreamTestRun {
+    scenario: StreamScenario,
+    path: StreamPath,
+    speeds: Vec<f32>,
+    exit_on_finish: bool,
+    /// 当前轮次
+    pass: usize,
+    /// 本轮已走过的路程（像素）
+    travelled: f32,
+    /// 到达终点后已等待的时间
+    settling: f32,
+    pass_started: Instant,
+    recorder: StreamRecorder,
+    report: StreamTestReport,
+    /// 开始前玩家能否移动，结束后恢复
+    saved_can_move: Option<bool>,
+}
+
+impl StreamTestRun {
+    /// 当前位置
+    fn position(&self) -> Vec2 {
+        self.path.position_at(self.travelled)
+    }
+
+    /// 本轮开始后的真实时间
+    fn now(&self) -> f64 {
+        self.pass_started.elapsed().as_secs_f64()
+    }
+}
+
+/// 处理压测请求，已有压测进行时忽略
+#[allow(clippy::too_many_arguments)]
+pub fn start_stream_test(
+    mut commands: Commands,
+    mut requests: EventReader<StreamTestRequest>,
+    run: Option<Res<StreamTestRun>>,
+    settings: Res<StreamTestSettings>,
+    chunk_manager: Res<ChunkManager>,
+    mut players: Query<&mut Character, With<Player>>,
+    mut cameras: Query<&mut CameraController, With<Camera>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let Some(request) = requests.read().last() else {
+        return;
+    };
+    let mut log = |level: LogLevel, message: &str| {
+        if let Some(logger) = logger.as_mut() {
+            logger.log(level, message);
+        }
+    };
+    if run.is_some() {
+        log(LogLevel::Info, "已有区块压测在进行中");
+        return;
+    }
+
+    let library = match StreamScenarioLibrary::load(&resolve_data_path(STREAM_TEST_DATA_PATH)) {
+        Ok(library) => library,
+        Err(e) => {
+            log(LogLevel::Error, &format!("压测场景加载失败: {}", e));
+            return;
+        }
+    };
+    let scenario = match resolve_scenario(&library, &request.scenario, request.speeds.clone()) {
+        Ok(scenario) => scenario,
+        Err(e) => {
+            log(LogLevel::Error, &e);
+            return;
+        }
+    };
+
+    // 压测期间玩家不受输入控制，相机不再跟随，两者都由压测移动
+    let saved_can_move = players.get_single_mut().ok().map(|mut character| {
+        let can_move = character.can_move;
+        character.can_move = false;
+        can_move
+    });
+    if let Ok(mut controller) = cameras.get_single_mut() {
+        controller.target = None;
+    }
+
+    log(
+        LogLevel::Info,
+        &format!(
+            "开始区块压测 {}，共 {} 轮",
+            scenario.name,
+            scenario.speeds.len()
+        ),
+    );
+    commands.insert_resource(StreamTestRun {
+        path: StreamPath::new(&scenario.waypoints),
+        speeds: scenario.speeds.clone(),
+        exit_on_finish: request.exit_on_finish,
+        pass: 0,
+        travelled: 0.0,
+        settling: 0.0,
+        pass_started: Instant::now(),
+        recorder: StreamRecorder::default(),
+        report: StreamTestReport {
+            scenario: scenario.name.clone(),
+            mode: "game".to_string(),
+            started_at: Local::now().to_rfc3339(),
+            view_distance: chunk_manager.view_distance,
+            load_budget: chunk_manager.load_budget,
+            hitch_threshold_ms: settings.hitch_threshold_ms,
+            passes: Vec::new(),
+        },
+        scenario,
+        saved_can_move,
+    });
+}
+
+/// 沿路径移动玩家与相机，并在加载系统运行前记下缺失的区块
+pub fn drive_stream_test(
+    real_time: Res<Time<Real>>,
+    run: Option<ResMut<StreamTestRun>>,
+    mut chunk_manager: ResMut<ChunkManager>,
+    mut players: Query<&mut Transform, With<Player>>,
+    mut cameras: Query<&mut Transform, (With<Camera2d>, Without<Player>)>,
+) {
+    let Some(mut run) = run else {
+        return;
+    };
+
+    let speed = run.speeds[run.pass] * TILE_PIXELS;
+    run.travelled = (run.travelled + speed * real_time.delta_secs()).min(run.path.length());
+    let position = run.position();
+    for mut transform in players.iter_mut().chain(cameras.iter_mut()) {
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+
+    chunk_manager.update_player_position(position.x, position.y);
+    let now = run.now();
+    run.recorder.before_streaming(&chunk_manager, now);
+}
+
+/// 在加载系统运行后结算本帧，整轮结束时换下一个速度，全部结束时写出报告
+#[allow(clippy::too_many_arguments)]
+pub fn record_stream_test(
+    mut commands: Commands,
+    real_time: Res<Time<Real>>,
+    settings: Res<StreamTestSettings>,
+    run: Option<ResMut<StreamTestRun>>,
+    chunk_manager: Res<ChunkManager>,
+    mut players: Query<(Entity, &mut Character), With<Player>>,
+    mut cameras: Query<&mut CameraController, With<Camera>>,
+    mut exits: EventWriter<AppExit>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let Some(mut run) = run else {
+        return;
+    };
+
+    let now = run.now();
+    let position = run.position();
+    let frame_ms = real_time.delta_secs_f64() * 1000.0;
+    run.recorder
+        .after_streaming(&chunk_manager, now, frame_ms, position);
+
+    if run.travelled < run.path.length() {
+        return;
+    }
+    let settled = run.recorder.is_settled();
+    run.settling += real_time.delta_secs();
+    if !settled && run.settling < run.scenario.settle {
+        return;
+    }
+
+    // 本轮结束
+    let speed = run.speeds[run.pass];
+    let recorder = std::mem::take(&mut run.recorder);
+    let pass = recorder.finish(speed, settled, settings.hitch_threshold_ms);
+    run.report.passes.push(pass);
+    run.pass += 1;
+    run.travelled = 0.0;
+    run.settling = 0.0;
+    run.pass_started = Instant::now();
+    if run.pass < run.speeds.len() {
+        return;
+    }
+
+    // 全部结束，恢复玩家与相机
+    if let Ok((entity, mut character)) = players.get_single_mut() {
+        if let Some(can_move) = run.saved_can_move {
+            character.can_move = can_move;
+        }
+        if let Ok(mut controller) = cameras.get_single_mut() {
+            controller.target = Some(entity);
+        }
+    }
+    commands.remove_resource::<StreamTestRun>();
+
+    if let Some(logger) = logger.as_mut() {
+        logger.log(LogLevel::Info, &run.report.to_string());
+        match run.report.save(&settings.output_dir) {
+            Ok(path) => logger.log(
+                LogLevel::Info,
+                &format!("区块压测报告已写入 {}", path.display()),
+            ),
+            Err(e) => logger.log(LogLevel::Error, &format!("写入区块压测报告失败: {}", e)),
+        }
+    }
+    if run.exit_on_finish {
+        exits.send(AppExit::Success);
+    }
+}
+
+/// 取得场景并按需覆盖速度，检查路径与速度是否可用
+fn resolve_scenario(
+    library: &StreamScenarioLibrary,
+    name: &str,
+    speeds: Option<Vec<f32>>,
+) -> Result<StreamScenario, String> {
+    let mut scenario = library.get(name).cloned().ok_or_else(|| {
+        format!(
+            "没有名为 {} 的压测场景（可用：{}）",
+            name,
+            library.names().join("、")
+        )
+    })?;
+    if let Some(speeds) = speeds {
+        scenario.speeds = speeds;
+    }
+    if scenario.waypoints.len() < 2 {
+        return Err(format!("压测场景 {} 至少需要两个途经点", scenario.name));
+    }
+    if scenario.speeds.is_empty() || scenario.speeds.iter().any(|speed| *speed <= 0.0) {
+        return Err(format!("压测场景 {} 的速度必须都大于零", scenario.name));
+    }
+    Ok(scenario)
+}
+
+/// 不启动游戏跑一遍压测场景
+///
+/// 按固定帧时长模拟移动，每帧按加载预算生成区块数据，不生成实体也不渲染；
+/// 帧时间只包含区块生成的实际耗时，用于比较生成开销与加载预算
+pub fn run_headless_stream_test(
+    name: &str,
+    speeds: Option<Vec<f32>>,
+    seed: u32,
+    settings: &StreamTestSettings,
+) -> Result<StreamTestReport, Box<dyn std::error::Error>> {
+    let library = StreamScenarioLibrary::load(&resolve_data_path(STREAM_TEST_DATA_PATH))?;
+    let scenario = resolve_scenario(&library, name, speeds)?;
+    let path = StreamPath::new(&scenario.waypoints);
+    let map_manager = MapManager::new(seed);
+
+    let template = ChunkManager::default();
+    let mut report = StreamTestReport {
+        scenario: scenario.name.clone(),
+        mode: "headless".to_string(),
+        started_at: Local::now().to_rfc3339(),
+        view_distance: template.view_distance,
+        load_budget: template.load_budget,
+        hitch_threshold_ms: settings.hitch_threshold_ms,
+        passes: Vec::new(),
+    };
+
+    for &speed in &scenario.speeds {
+        // 每轮从空世界开始，各轮结果互不影响
+        let mut chunk_manager = ChunkManager::default();
+        chunk_manager.initialize_terrain_generator(&map_manager);
+        let mut recorder = StreamRecorder::default();
+        let mut clock = 0.0;
+        let mut travelled = 0.0;
+        let mut settling = 0.0;
+
+        let settled = loop {
+            travelled =
+                (travelled + speed * TILE_PIXELS * HEADLESS_FRAME_TIME as f32).min(path.length());
+            let position = path.position_at(travelled);
+            chunk_manager.update_player_position(position.x, position.y);
+            recorder.before_streaming(&chunk_manager, clock);
+
+            let started = Instant::now();
+            for coord in chunk_manager
+                .get_chunks_to_load()
+                .into_iter()
+                .take(chunk_manager.load_budget.max(1))
+            {
+                chunk_manager.generate_chunk_data(coord, &map_manager);
+                chunk_manager.insert_chunk(coord, Entity::PLACEHOLDER);
+            }
+            for coord in chunk_manager.get_chunks_to_unload() {
+                chunk_manager.remove_chunk(coord);
+            }
+            let work = started.elapsed().as_secs_f64();
+
+            recorder.after_streaming(&chunk_manager, clock + work, work * 1000.0, position);
+            clock += HEADLESS_FRAME_TIME.max(work);
+
+            if travelled < path.length() {
+                continue;
+            }
+            if recorder.is_settled() {
+                break true;
+            }
+            settling += HEADLESS_FRAME_TIME.max(work);
+            if settling >= scenario.settle as f64 {
+                break false;
+            }
+        };
+        report
+            .passes
+            .push(recorder.finish(speed, settled, settings.hitch_threshold_ms));
+    }
+
+    Ok(report)
+}
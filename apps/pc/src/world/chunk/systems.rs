@@ -1,5 +1,14 @@
-use super::{ChunkLoaderSystem, ChunkManager};
-use crate::world::map::MapManager;
+use super::{
+    apply_water_currents, draw_chunk_debug, drive_stream_test, flush_chunk_edits,
+    interact_with_props, load_tilesets, receive_prefetched_chunks, record_stream_test,
+    restyle_chunk_tiles, retint_tiles, start_stream_test, sync_nav_grid, sync_tilesets,
+    toggle_chunk_debug, track_chunk_edits, update_chunk_debug_panel, ChunkDebugOverlay, ChunkEdits,
+    ChunkLoaderSystem, ChunkManager, ChunkPrefetch, NavGrid, PropSettings, StreamTestRequest,
+    StreamTestSettings, TilesetAsset, TilesetLibrary, TilesetLoader, WaterCurrentSettings,
+};
+use crate::resources::{chunk_streaming_running, gameplay_running};
+use crate::save::SaveSet;
+use crate::world::map::{scene_prefabs_ready, MapManager, ScenePrefabRegistry};
 use bevy::prelude::*;
 
 /// 区块系统插件
@@ -8,27 +17,104 @@ pub struct ChunkSystemPlugin;
 impl Plugin for ChunkSystemPlugin {
     fn build(&self, app: &mut App) {
         // 注册资源
-        app.init_resource::<ChunkManager>();
+        app.init_resource::<ChunkManager>()
+            .init_resource::<WaterCurrentSettings>();
 
         // 注册系统
         app.add_systems(Startup, setup_chunk_system).add_systems(
             Update,
             (
                 // ChunkLoaderSystem::update_player_position,
-                ChunkLoaderSystem::process_chunk_loading,
+                sync_structure_sources,
+                ChunkLoaderSystem::process_chunk_loading
+                    .run_if(scene_prefabs_ready)
+                    .run_if(chunk_streaming_running),
                 // ChunkLoaderSystem::update_chunk_visibility,
             )
                 .chain(),
         );
+
+        // 后台预生成的区块在加载前收下
+        app.init_resource::<ChunkPrefetch>().add_systems(
+            Update,
+            receive_prefetched_chunks.before(ChunkLoaderSystem::process_chunk_loading),
+        );
+
+        // 导航网格跟随区块加载、修改与卸载
+        app.init_resource::<NavGrid>().add_systems(
+            Update,
+            sync_nav_grid.after(ChunkLoaderSystem::process_chunk_loading),
+        );
+
+        // 点灯、熄灯等摆件交互改动区块数据，在记录修改之前进行
+        app.init_resource::<PropSettings>()
+            .add_systems(Update, interact_with_props.before(track_chunk_edits));
+
+        // 记住被修改过的区块，重新加载与存档时使用
+        app.init_resource::<ChunkEdits>()
+            .add_systems(
+                Update,
+                track_chunk_edits.after(ChunkLoaderSystem::process_chunk_loading),
+            )
+            .add_systems(PreUpdate, flush_chunk_edits.in_set(SaveSet::Flush));
+
+        // 区块流式加载压测，移动与记录夹在加载系统前后
+        app.init_resource::<StreamTestSettings>()
+            .add_event::<StreamTestRequest>()
+            .add_systems(
+                Update,
+                (start_stream_test, drive_stream_test)
+                    .chain()
+                    .before(ChunkLoaderSystem::process_chunk_loading),
+            )
+            .add_systems(
+                Update,
+                record_stream_test.after(ChunkLoaderSystem::process_chunk_loading),
+            );
+
+        // 水流推动
+        app.add_systems(Update, apply_water_currents.run_if(gameplay_running));
+
+        // 换季或昼夜变化时重新着色瓦片
+        app.add_systems(Update, retint_tiles);
+
+        // 地形贴图集，加载、热重载或切换贴图设置后给已加载的瓦片换上贴图
+        app.init_asset::<TilesetAsset>()
+            .init_asset_loader::<TilesetLoader>()
+            .init_resource::<TilesetLibrary>()
+            .add_systems(Startup, load_tilesets)
+            .add_systems(
+                Update,
+                (sync_tilesets, restyle_chunk_tiles)
+                    .chain()
+                    .before(ChunkLoaderSystem::process_chunk_loading),
+            );
+
+        // 区块调试界面：网格、加载状态与流式加载统计
+        app.init_resource::<ChunkDebugOverlay>()
+            .add_systems(
+                Update,
+                (
+                    toggle_chunk_debug,
+                    update_chunk_debug_panel.after(ChunkLoaderSystem::process_chunk_loading),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                PostUpdate,
+                draw_chunk_debug
+                    .after(TransformSystem::TransformPropagate)
+                    .run_if(chunk_debug_visible),
+            );
     }
 }
 
+fn chunk_debug_visible(overlay: Res<ChunkDebugOverlay>) -> bool {
+    overlay.visible
+}
+
 /// 设置区块系统
-fn setup_chunk_system(
-    mut commands: Commands,
-    mut chunk_manager: ResMut<ChunkManager>,
-    map_manager: Res<MapManager>,
-) {
+fn setup_chunk_system(mut chunk_manager: ResMut<ChunkManager>, map_manager: Res<MapManager>) {
     // 初始化地形生成器
     chunk_manager.initialize_terrain_generator(&map_manager);
 
@@ -40,3 +126,20 @@ fn setup_chunk_system(
 
     info!("区块系统已初始化");
 }
+
+/// 把固定场景和预制场景同步给结构生成器
+///
+/// 预制场景热重载后只影响之后生成的区块，已加载的区块保持原样
+fn sync_structure_sources(
+    mut chunk_manager: ResMut<ChunkManager>,
+    map_manager: Res<MapManager>,
+    registry: Res<ScenePrefabRegistry>,
+) {
+    if !map_manager.is_changed() && !registry.is_changed() {
+        return;
+    }
+
+    let generator = chunk_manager.structure_generator_mut();
+    generator.set_fixed_scenes(map_manager.fixed_scenes().clone());
+    generator.set_prefabs(registry.prefabs.clone());
+}
@@ -0,0 +1,294 @@
+use bevy::asset::{io::Reader, AssetLoader, LoadContext, LoadedFolder};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use thiserror::Error;
+
+use super::ChunkData;
+use crate::logging::{GameLogger, LogLevel};
+use crate::world::map::{TileType, Zone};
+
+/// 地形贴图集所在的目录（相对于 assets）
+pub const TILESET_FOLDER: &str = "tilesets";
+
+/// 相邻瓦片的方位，掩码中对应的位表示该方向的邻居与自己相连
+const NEIGHBOR_NORTH: u8 = 1;
+const NEIGHBOR_EAST: u8 = 1 << 1;
+const NEIGHBOR_SOUTH: u8 = 1 << 2;
+const NEIGHBOR_WEST: u8 = 1 << 3;
+const NEIGHBOR_NORTH_EAST: u8 = 1 << 4;
+const NEIGHBOR_SOUTH_EAST: u8 = 1 << 5;
+const NEIGHBOR_SOUTH_WEST: u8 = 1 << 6;
+const NEIGHBOR_NORTH_WEST: u8 = 1 << 7;
+
+/// 掩码各位对应的偏移，与上面的位顺序一致
+const NEIGHBOR_OFFSETS: [(i32, i32); 8] = [
+    (0, 1),
+    (1, 0),
+    (0, -1),
+    (-1, 0),
+    (1, 1),
+    (1, -1),
+    (-1, -1),
+    (-1, 1),
+];
+
+/// 边角拼接规则
+///
+/// 按相邻八格中哪些与自己相连挑选贴图，水岸、小路的接缝都由它画出
+/// - first: 拼接贴图在图集中的起始格子
+/// - connects: 视为相连的瓦片类型，自身类型总是相连
+/// - corners: 为否时只看上下左右四边，共 16 格；为是时同时看四角，共 47 格
+#[derive(Debug, Clone, Deserialize)]
+pub struct AutotileRule {
+    pub first: usize,
+    #[serde(default)]
+    pub connects: Vec<TileType>,
+    #[serde(default)]
+    pub corners: bool,
+}
+
+/// 一种瓦片在图集中的贴图
+///
+/// 没有拼接规则时按位置在几个变体中挑一个，成片的草地不会完全一样
+#[derive(Debug, Clone, Deserialize)]
+pub struct TileSprites {
+    #[serde(default)]
+    pub variants: Vec<usize>,
+    #[serde(default)]
+    pub autotile: Option<AutotileRule>,
+}
+
+/// 贴图集文件内容
+#[derive(Debug, Deserialize)]
+struct TilesetFile {
+    zone: Zone,
+    texture: String,
+    /// 单格尺寸（像素）
+    tile_size: (u32, u32),
+    columns: u32,
+    rows: u32,
+    #[serde(default)]
+    padding: Option<(u32, u32)>,
+    #[serde(default)]
+    offset: Option<(u32, u32)>,
+    tiles: HashMap<TileType, TileSprites>,
+}
+
+/// 地形贴图集
+///
+/// # 设计思路
+/// 1. 一个气候区域一个文件：assets/tilesets 下的 `*.tileset.json`，写明切图方式与各类瓦片的格子
+/// 2. 瓦片类型到格子序号的映射写在数据里，换美术资源不需要改代码
+/// 3. 贴图集里没有写到的瓦片类型仍画成纯色方块
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct TilesetAsset {
+    pub zone: Zone,
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+    pub tiles: HashMap<TileType, TileSprites>,
+    /// 八方向掩码到 47 格拼接贴图序号的查找表
+    blob: [u8; 256],
+}
+
+impl TilesetAsset {
+    /// 拼接规则，没有贴图的瓦片类型返回 None
+    pub fn autotile(&self, tile_type: TileType) -> Option<&AutotileRule> {
+        self.tiles
+            .get(&tile_type)
+            .and_then(|sprites| sprites.autotile.as_ref())
+    }
+
+    /// 按相邻掩码与位置挑选瓦片在图集中的格子
+    pub fn sprite_index(&self, tile_type: TileType, mask: u8, variant: u8) -> Option<usize> {
+        let sprites = self.tiles.get(&tile_type)?;
+        match &sprites.autotile {
+            Some(rule) if rule.corners => Some(rule.first + self.blob[mask as usize] as usize),
+            Some(rule) => Some(rule.first + (mask & 0x0f) as usize),
+            None if sprites.variants.is_empty() => None,
+            None => Some(sprites.variants[variant as usize % sprites.variants.len()]),
+        }
+    }
+}
+
+/// 去掉不影响外观的角：只有相邻两边都相连时，角上的邻居才有意义
+fn reduce_corners(mask: u8) -> u8 {
+    let mut reduced = mask & 0x0f;
+    for (corner, sides) in [
+        (NEIGHBOR_NORTH_EAST, NEIGHBOR_NORTH | NEIGHBOR_EAST),
+        (NEIGHBOR_SOUTH_EAST, NEIGHBOR_SOUTH | NEIGHBOR_EAST),
+        (NEIGHBOR_SOUTH_WEST, NEIGHBOR_SOUTH | NEIGHBOR_WEST),
+        (NEIGHBOR_NORTH_WEST, NEIGHBOR_NORTH | NEIGHBOR_WEST),
+    ] {
+        if mask & corner != 0 && mask & sides == sides {
+            reduced |= corner;
+        }
+    }
+    reduced
+}
+
+/// 生成 47 格拼接的查找表，图集中的格子按化简后的掩码从小到大排列
+fn blob_table() -> [u8; 256] {
+    let mut order = [0u8; 256];
+    let mut next = 0u8;
+    for mask in 0..=255u8 {
+        if reduce_corners(mask) == mask {
+            order[mask as usize] = next;
+            next += 1;
+        }
+    }
+    let mut table = [0u8; 256];
+    for mask in 0..=255u8 {
+        table[mask as usize] = order[reduce_corners(mask) as usize];
+    }
+    table
+}
+
+/// 取区块内瓦片八个方向的邻居，顺序与掩码的位一致，区块之外的邻居为 None
+pub fn neighbor_types(data: &ChunkData, x: usize, y: usize) -> [Option<TileType>; 8] {
+    NEIGHBOR_OFFSETS.map(|(dx, dy)| {
+        let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+        if nx < 0 || ny < 0 {
+            return None;
+        }
+        data.get_tile(nx as usize, ny as usize)
+            .and_then(TileType::from_u8)
+    })
+}
+
+impl AutotileRule {
+    /// 计算相邻掩码
+    ///
+    /// 区块之外的邻居取不到，当作相连处理，区块接缝处不会多出一圈岸线
+    pub fn mask(&self, own: TileType, neighbors: &[Option<TileType>; 8]) -> u8 {
+        neighbors
+            .iter()
+            .enumerate()
+            .filter(|(_, neighbor)| {
+                neighbor.is_none_or(|tile| tile == own || self.connects.contains(&tile))
+            })
+            .fold(0, |mask, (bit, _)| mask | 1 << bit)
+    }
+}
+
+/// 贴图集加载错误
+#[derive(Debug, Error)]
+pub enum TilesetLoaderError {
+    #[error("读取贴图集失败: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("解析贴图集失败: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// 贴图集加载器，同时加载贴图并生成图集布局
+#[derive(Default)]
+pub struct TilesetLoader;
+
+impl AssetLoader for TilesetLoader {
+    type Asset = TilesetAsset;
+    type Settings = ();
+    type Error = TilesetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<TilesetAsset, TilesetLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file: TilesetFile = serde_json::from_slice(&bytes)?;
+
+        let layout = TextureAtlasLayout::from_grid(
+            UVec2::new(file.tile_size.0, file.tile_size.1),
+            file.columns,
+            file.rows,
+            file.padding.map(|(x, y)| UVec2::new(x, y)),
+            file.offset.map(|(x, y)| UVec2::new(x, y)),
+        );
+        let layout = load_context.add_labeled_asset("layout".to_string(), layout);
+        let image = load_context.load(file.texture);
+
+        Ok(TilesetAsset {
+            zone: file.zone,
+            image,
+            layout,
+            tiles: file.tiles,
+            blob: blob_table(),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tileset.json"]
+    }
+}
+
+/// 已加载的地形贴图集
+#[derive(Resource, Default)]
+pub struct TilesetLibrary {
+    /// 贴图集目录句柄，保持目录中的资源不被卸载
+    pub folder: Option<Handle<LoadedFolder>>,
+    /// 按气候区域索引的贴图集
+    by_zone: HashMap<Zone, Handle<TilesetAsset>>,
+    /// 资源ID到气候区域的映射，用于处理修改和移除
+    ids: HashMap<AssetId<TilesetAsset>, Zone>,
+}
+
+impl TilesetLibrary {
+    pub fn get<'a>(
+        &self,
+        zone: Zone,
+        assets: &'a Assets<TilesetAsset>,
+    ) -> Option<&'a TilesetAsset> {
+        self.by_zone
+            .get(&zone)
+            .and_then(|handle| assets.get(handle))
+    }
+}
+
+/// 开始加载贴图集目录
+pub fn load_tilesets(asset_server: Res<AssetServer>, mut library: ResMut<TilesetLibrary>) {
+    library.folder = Some(asset_server.load_folder(TILESET_FOLDER));
+}
+
+/// 根据资源事件更新气候区域到贴图集的索引
+pub fn sync_tilesets(
+    mut library: ResMut<TilesetLibrary>,
+    mut events: EventReader<AssetEvent<TilesetAsset>>,
+    mut assets: ResMut<Assets<TilesetAsset>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for event in events.read() {
+        match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                let Some(handle) = assets.get_strong_handle(*id) else {
+                    continue;
+                };
+                let Some(asset) = assets.get(*id) else {
+                    continue;
+                };
+                let zone = asset.zone;
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(
+                        LogLevel::Info,
+                        &format!(
+                            "地形贴图集已加载: {} ({} 种瓦片)",
+                            zone.name(),
+                            asset.tiles.len()
+                        ),
+                    );
+                }
+                if let Some(previous) = library.ids.insert(*id, zone) {
+                    library.by_zone.remove(&previous);
+                }
+                library.by_zone.insert(zone, handle);
+            }
+            AssetEvent::Removed { id } => {
+                if let Some(previous) = library.ids.remove(id) {
+                    library.by_zone.remove(&previous);
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,349 @@
+use bevy::math::IVec2;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use super::snapshot::Fnv64;
+use super::{ChunkCoord, ChunkData, ChunkManager, ChunkStore, CHUNK_SIZE};
+use crate::world::map::MapManager;
+use crate::world::poi::PoiRegistry;
+
+/// 世界生成基准哈希文件路径
+pub const WORLDGEN_GOLDEN_PATH: &str = "src/config/worldgen_golden.json";
+
+/// 基准格式版本，基准结构本身变化时递增
+const GOLDEN_VERSION: u32 = 1;
+
+/// 参与检查的世界种子，包括取值范围的两端
+const VERIFY_SEEDS: [u32; 4] = [0, 42, 1337, u32::MAX];
+
+/// 参与检查的区块：原点附近，以及远离原点、容易暴露浮点精度问题的区块
+const VERIFY_COORDS: [(i32, i32); 8] = [
+    (0, 0),
+    (1, 0),
+    (0, -1),
+    (-1, 1),
+    (37, -12),
+    (-256, 128),
+    (4096, -4096),
+    (-65536, 65535),
+];
+
+/// 兴趣点检查覆盖原点周围多少圈区块，场景稀疏，只看基准区块往往一个也碰不到
+const POI_SCAN_RADIUS: i32 = 12;
+
+/// 世界生成基准哈希
+///
+/// 每个区块记录整个 `ChunkData` 序列化后的哈希，新增数据层也会被覆盖；
+/// 与世界快照按数据层记录不同，这里只回答“是否变了”，变在哪一层用世界快照查看
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorldgenGolden {
+    pub version: u32,
+    pub chunk_size: usize,
+    /// `种子:x,y` 到哈希（十六进制）的映射
+    pub hashes: BTreeMap<String, String>,
+}
+
+impl WorldgenGolden {
+    /// 按固定的种子与区块生成基准
+    pub fn generate() -> Self {
+        Self {
+            version: GOLDEN_VERSION,
+            chunk_size: CHUNK_SIZE,
+            hashes: VERIFY_SEEDS
+                .iter()
+                .flat_map(|&seed| generate_hashes(seed, VERIFY_COORDS.iter().copied()))
+                .collect(),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// 一个区块的检查结果
+#[derive(Debug, Clone)]
+pub enum WorldgenIssue {
+    /// 同一进程内多次生成的结果不同
+    Nondeterministic { key: String, hashes: Vec<String> },
+    /// 与基准不同
+    Changed {
+        key: String,
+        expected: String,
+        actual: String,
+    },
+    /// 基准中没有这个区块
+    Missing { key: String },
+    /// 兴趣点重复登记，或发现状态不是只在首次走近时变化
+    Poi { key: String, detail: &'static str },
+    /// 区块存储写入后读回的数据不同，或种子不符、文件缺失损坏时没有退回现场生成
+    Store { key: String, detail: String },
+}
+
+/// 世界生成检查报告
+#[derive(Debug, Clone, Default)]
+pub struct WorldgenReport {
+    /// 检查的区块数
+    pub checked: usize,
+    /// 检查的兴趣点数
+    pub pois: usize,
+    /// 基准的版本或区块尺寸与当前不同
+    pub format_changed: bool,
+    pub issues: Vec<WorldgenIssue>,
+}
+
+impl WorldgenReport {
+    pub fn passed(&self) -> bool {
+        !self.format_changed && self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for WorldgenReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.passed() {
+            return writeln!(
+                f,
+                "世界生成检查通过，{} 个区块与基准一致，{} 个兴趣点发现状态正常",
+                self.checked, self.pois
+            );
+        }
+        writeln!(
+            f,
+            "世界生成检查未通过（{} 个区块，{} 处问题）",
+            self.checked,
+            self.issues.len()
+        )?;
+        if self.format_changed {
+            writeln!(f, "  基准的格式版本或区块尺寸已变化，需要重新生成基准")?;
+        }
+        for issue in &self.issues {
+            match issue {
+                WorldgenIssue::Nondeterministic { key, hashes } => {
+                    writeln!(f, "  {} 多次生成结果不一致: {}", key, hashes.join(" / "))?
+                }
+                WorldgenIssue::Changed {
+                    key,
+                    expected,
+                    actual,
+                } => writeln!(f, "  {} 与基准不同: {} -> {}", key, expected, actual)?,
+                WorldgenIssue::Missing { key } => writeln!(f, "  {} 不在基准中", key)?,
+                WorldgenIssue::Poi { key, detail } => writeln!(f, "  {} 兴趣点{}", key, detail)?,
+                WorldgenIssue::Store { key, detail } => {
+                    writeln!(f, "  {} 区块存储{}", key, detail)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 检查世界生成的确定性并与基准比较
+///
+/// # 设计思路
+/// 1. 每个种子生成三遍：顺序、倒序、另起一个线程，每遍都重新建立生成器，
+///    生成结果依赖生成顺序、全局状态或线程时都会不一致
+/// 2. 三遍一致后再与基准比较，区分“不确定”与“生成规则变了”两类问题
+/// 3. 基准变化意味着旧存档中未修改的区块会与新生成的不同，须确认后再更新基准
+/// 4. 同时用生成的区块检查兴趣点登记：区块重新加载不重复登记，每个兴趣点只发现一次
+/// 5. 区块在临时目录中写入区块存储再读回，须与生成结果一致；
+///    种子不符、文件缺失或损坏时存储不可用，区块加载退回现场生成
+pub fn verify_worldgen(golden: &WorldgenGolden) -> WorldgenReport {
+    let mut report = WorldgenReport {
+        format_changed: golden.version != GOLDEN_VERSION || golden.chunk_size != CHUNK_SIZE,
+        ..Default::default()
+    };
+
+    for seed in VERIFY_SEEDS {
+        let forward = generate_hashes(seed, VERIFY_COORDS.iter().copied());
+        let reverse = generate_hashes(seed, VERIFY_COORDS.iter().rev().copied());
+        // 生成器只在线程内建立，不要求它能跨线程传递
+        let threaded =
+            std::thread::spawn(move || generate_hashes(seed, VERIFY_COORDS.iter().copied()))
+                .join()
+                .unwrap_or_default();
+
+        for (key, hash) in forward {
+            report.checked += 1;
+            let runs = [Some(&hash), reverse.get(&key), threaded.get(&key)];
+            if runs.iter().any(|run| *run != Some(&hash)) {
+                report.issues.push(WorldgenIssue::Nondeterministic {
+                    hashes: runs
+                        .iter()
+                        .map(|run| run.cloned().unwrap_or_else(|| "缺失".to_string()))
+                        .collect(),
+                    key,
+                });
+                continue;
+            }
+            match golden.hashes.get(&key) {
+                None => report.issues.push(WorldgenIssue::Missing { key }),
+                Some(expected) if *expected != hash => report.issues.push(WorldgenIssue::Changed {
+                    key,
+                    expected: expected.clone(),
+                    actual: hash,
+                }),
+                Some(_) => {}
+            }
+        }
+
+        let (pois, issues) = check_poi_discovery(seed);
+        report.pois += pois;
+        report.issues.extend(issues);
+        report.issues.extend(check_chunk_store(seed));
+    }
+    report
+}
+
+/// 区块写入临时目录中的区块存储再读回，检查数据一致与各种不可用的情形
+fn check_chunk_store(seed: u32) -> Vec<WorldgenIssue> {
+    let dir = std::env::temp_dir().join(format!(
+        "chivalry_chunk_store_{}_{}",
+        std::process::id(),
+        seed
+    ));
+    let issues = chunk_store_issues(seed, &dir);
+    let _ = fs::remove_dir_all(&dir);
+    issues
+}
+
+fn chunk_store_issues(seed: u32, dir: &Path) -> Vec<WorldgenIssue> {
+    let map_manager = MapManager::new(seed);
+    let mut chunk_manager = ChunkManager::default();
+    chunk_manager.initialize_terrain_generator(&map_manager);
+
+    let key = |coord: ChunkCoord| format!("{}:{},{}", seed, coord.x, coord.y);
+    let issue = |coord: ChunkCoord, detail: String| WorldgenIssue::Store {
+        key: key(coord),
+        detail,
+    };
+    let mut issues = Vec::new();
+
+    let store = ChunkStore::new(dir);
+    let written = ChunkCoord { x: 0, y: 0 };
+    let data = chunk_manager.generate_chunk_data(written, &map_manager);
+    if let Err(e) = store.prepare(seed) {
+        return vec![issue(written, format!("无法准备写入：{}", e))];
+    }
+    if let Err(e) = store.write(written, &data) {
+        return vec![issue(written, format!("无法写入：{}", e))];
+    }
+
+    match ChunkStore::open(dir, seed) {
+        None => issues.push(issue(written, "说明文件与种子一致却无法打开".to_string())),
+        Some(store) => match store.read(written) {
+            Ok(read) if chunk_hash(&read) == chunk_hash(&data) => {}
+            Ok(read) => issues.push(issue(
+                written,
+                format!(
+                    "读回的数据与写入的不同: {} -> {}",
+                    chunk_hash(&data),
+                    chunk_hash(&read)
+                ),
+            )),
+            Err(e) => issues.push(issue(written, format!("无法读回：{}", e))),
+        },
+    }
+
+    if ChunkStore::open(dir, seed.wrapping_add(1)).is_some() {
+        issues.push(issue(written, "种子不符时仍被打开".to_string()));
+    }
+
+    let missing = ChunkCoord { x: 1, y: 0 };
+    if store.read(missing).is_ok() {
+        issues.push(issue(missing, "缺失的区块文件读取成功".to_string()));
+    }
+
+    let corrupt = ChunkCoord { x: 2, y: 0 };
+    if fs::write(store.path(corrupt), [0xff; 16]).is_ok() && store.read(corrupt).is_ok() {
+        issues.push(issue(corrupt, "损坏的区块文件读取成功".to_string()));
+    }
+    issues
+}
+
+/// 按区块加载、卸载后再加载的顺序登记兴趣点，再逐个走近两次
+///
+/// 返回检查的兴趣点数与发现的问题；`PoiDiscovered` 事件按 `discover` 的返回值发出，
+/// 这里保证每个兴趣点恰好发出一次
+fn check_poi_discovery(seed: u32) -> (usize, Vec<WorldgenIssue>) {
+    let map_manager = MapManager::new(seed);
+    let mut chunk_manager = ChunkManager::default();
+    chunk_manager.initialize_terrain_generator(&map_manager);
+
+    let mut registry = PoiRegistry::default();
+    let mut issues = Vec::new();
+    let range = -POI_SCAN_RADIUS..=POI_SCAN_RADIUS;
+    for (x, y) in range
+        .clone()
+        .flat_map(|x| range.clone().map(move |y| (x, y)))
+    {
+        let coord = ChunkCoord { x, y };
+        let data = chunk_manager.generate_chunk_data(coord, &map_manager);
+        registry.register_chunk(coord, &data);
+        let registered = registry.iter().count();
+        registry.register_chunk(coord, &data);
+        if registry.iter().count() != registered {
+            issues.push(WorldgenIssue::Poi {
+                key: format!("{}:{},{}", seed, x, y),
+                detail: "在区块重新加载后重复登记",
+            });
+        }
+    }
+
+    let positions: Vec<IVec2> = registry.iter().map(|poi| poi.position).collect();
+    for &position in &positions {
+        let key = format!("{}:({}, {})", seed, position.x, position.y);
+        let first = registry.discover(position, 1.0);
+        let again = registry.discover(position, 2.0);
+        let discovered_at = registry.get(position).and_then(|poi| poi.discovered_at);
+        let detail = if !first {
+            "首次走近未算作发现"
+        } else if again {
+            "再次走近又算作发现"
+        } else if discovered_at != Some(1.0) {
+            "的发现时刻被再次走近覆盖"
+        } else {
+            continue;
+        };
+        issues.push(WorldgenIssue::Poi { key, detail });
+    }
+    (positions.len(), issues)
+}
+
+/// 用新建的生成器按给定顺序生成区块，返回各区块的哈希
+fn generate_hashes(
+    seed: u32,
+    coords: impl Iterator<Item = (i32, i32)>,
+) -> BTreeMap<String, String> {
+    let map_manager = MapManager::new(seed);
+    let mut chunk_manager = ChunkManager::default();
+    chunk_manager.initialize_terrain_generator(&map_manager);
+
+    coords
+        .map(|(x, y)| {
+            let data = chunk_manager.generate_chunk_data(ChunkCoord { x, y }, &map_manager);
+            (format!("{}:{},{}", seed, x, y), chunk_hash(&data))
+        })
+        .collect()
+}
+
+/// 整个区块数据的哈希，按序列化结果计算
+pub fn chunk_hash(data: &ChunkData) -> String {
+    let mut hash = Fnv64::new();
+    match bincode::serialize(data) {
+        Ok(bytes) => hash.write(&bytes),
+        Err(e) => hash.write(e.to_string().as_bytes()),
+    }
+    format!("{:016x}", hash.finish())
+}
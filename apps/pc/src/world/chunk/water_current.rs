@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+
+use super::{Chunk, ChunkCoord, ChunkManager, CHUNK_SIZE, TILE_PIXELS};
+use crate::world::physics::{move_by, MovementBody};
+
+/// 水流推动参数
+#[derive(Resource, Debug, Clone)]
+pub struct WaterCurrentSettings {
+    /// 强度为 1.0 的水流对应的推动速度（像素/秒）
+    pub max_speed: f32,
+}
+
+impl Default for WaterCurrentSettings {
+    fn default() -> Self {
+        Self { max_speed: 96.0 }
+    }
+}
+
+/// 受水流影响的实体
+///
+/// 漂浮物的 `susceptibility` 通常为 1.0，游泳的角色可以自己划水，取值较小
+#[derive(Component, Debug, Clone)]
+pub struct WaterCurrent {
+    /// 受水流影响的程度
+    pub susceptibility: f32,
+    /// 最近一次采样到的水流，不在水中时为零
+    pub current: Vec2,
+}
+
+impl WaterCurrent {
+    /// 游泳的角色，只在深水中被水流带走
+    pub fn swimmer() -> Self {
+        Self {
+            susceptibility: 0.35,
+            ..default()
+        }
+    }
+}
+
+impl Default for WaterCurrent {
+    fn default() -> Self {
+        Self {
+            susceptibility: 1.0,
+            current: Vec2::ZERO,
+        }
+    }
+}
+
+/// 按所在瓦片的水流推动实体
+///
+/// 有移动体的角色只在游泳时被推动，推力计入移动意图交给碰撞系统，不会被冲进岸边的障碍里；
+/// 掉落物等没有移动体的漂浮物直接平移
+pub fn apply_water_currents(
+    time: Res<Time>,
+    settings: Res<WaterCurrentSettings>,
+    chunk_manager: Res<ChunkManager>,
+    chunks: Query<&Chunk>,
+    mut movers: Query<(&mut Transform, &mut WaterCurrent, Option<&mut MovementBody>)>,
+) {
+    let delta = time.delta_secs();
+    let chunk_size = CHUNK_SIZE as i32;
+
+    for (mut transform, mut current, body) in movers.iter_mut() {
+        if body.as_ref().is_some_and(|body| !body.swimming) {
+            current.current = Vec2::ZERO;
+            continue;
+        }
+
+        let tile_x = (transform.translation.x / TILE_PIXELS).floor() as i32;
+        let tile_y = (transform.translation.y / TILE_PIXELS).floor() as i32;
+        let coord = ChunkCoord {
+            x: tile_x.div_euclid(chunk_size),
+            y: tile_y.div_euclid(chunk_size),
+        };
+
+        let flow = chunk_manager
+            .get_chunk_entity(coord)
+            .and_then(|entity| chunks.get(entity).ok())
+            .and_then(|chunk| chunk.data.as_ref())
+            .map_or(Vec2::ZERO, |data| {
+                let local_x = tile_x.rem_euclid(chunk_size) as usize;
+                let local_y = tile_y.rem_euclid(chunk_size) as usize;
+                if data.get_water_depth(local_x, local_y) > 0.0 {
+                    data.get_flow(local_x, local_y)
+                } else {
+                    Vec2::ZERO
+                }
+            });
+
+        current.current = flow;
+        let push = flow * settings.max_speed * current.susceptibility * delta;
+        move_by(&mut transform, body.map(Mut::into_inner), push);
+    }
+}
@@ -0,0 +1,429 @@
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+
+use crate::world::chunk::{world_to_tile, NavGrid, TILE_PIXELS};
+use crate::world::entity::{AiState, Character, CharacterState, Npc, NpcType, Perception};
+use crate::world::physics::{move_by, MovementBody};
+
+/// 行为树数据路径
+pub const BEHAVIOR_DATA_PATH: &str = "src/config/behavior_trees.json";
+
+fn default_speed() -> f32 {
+    1.0
+}
+
+/// 行为树节点
+///
+/// 组合节点决定子节点的执行顺序，条件节点只做判断，动作节点改写AI状态并移动角色。
+/// 速度都是相对角色基础速度的倍率，距离单位为像素
+#[derive(Debug, Clone, Deserialize)]
+pub enum BehaviorNode {
+    /// 依次尝试子节点，直到有一个不失败
+    Selector(Vec<BehaviorNode>),
+    /// 依次执行子节点，直到有一个不成功
+    Sequence(Vec<BehaviorNode>),
+    /// 成功与失败互换
+    Not(Box<BehaviorNode>),
+    /// 当前AI状态是其中之一，用于接续商队、作息等系统设置的状态
+    InState(Vec<AiState>),
+    /// 发现了玩家：在侦测范围内，并且亲眼看见或被同伙示警；已在追击时只看距离
+    PlayerDetected,
+    /// 听到了可疑的声响
+    HeardNoise,
+    /// 玩家在给定距离内
+    PlayerWithin { distance: f32 },
+    /// 生命低于上限的比例
+    HealthBelow { ratio: f32 },
+    /// 原地待着
+    Idle,
+    /// 空闲与闲逛交替，撞到障碍时换个方向
+    Roam,
+    /// 沿巡逻点往返，没有巡逻点时失败
+    Patrol {
+        #[serde(default = "default_speed")]
+        speed: f32,
+    },
+    /// 追击玩家，玩家离开侦测范围时失败
+    Chase {
+        #[serde(default = "default_speed")]
+        speed: f32,
+    },
+    /// 贴身攻击，进入与退出的距离不同，避免在追击与攻击之间来回切换
+    Attack { enter: f32, leave: f32 },
+    /// 远离玩家，拉开到安全距离后失败
+    Flee {
+        #[serde(default = "default_speed")]
+        speed: f32,
+        safe_distance: f32,
+    },
+    /// 与玩家保持在 min 到 max 之间
+    KeepDistance {
+        min: f32,
+        max: f32,
+        #[serde(default = "default_speed")]
+        speed: f32,
+    },
+    /// 刚发现玩家时招呼附近的同伙一起追击
+    CallForHelp { radius: f32 },
+    /// 走到听到声响的地方查看，到达后成功，没听到声响时失败
+    Investigate {
+        #[serde(default = "default_speed")]
+        speed: f32,
+    },
+}
+
+/// 节点执行结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BehaviorStatus {
+    Success,
+    Failure,
+    Running,
+}
+
+/// 呼救，由AI系统在所有NPC更新完后统一处理
+#[derive(Debug, Clone, Copy)]
+pub struct HelpCall {
+    pub caller: Entity,
+    pub npc_type: NpcType,
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+/// 单个NPC执行行为树时用到的数据
+pub struct BehaviorContext<'a> {
+    pub entity: Entity,
+    pub npc: &'a mut Npc,
+    pub character: &'a mut Character,
+    pub transform: &'a mut Transform,
+    pub body: Option<&'a mut MovementBody>,
+    /// 感知状态，没有感知组件的NPC按距离与视线判断
+    pub perception: Option<&'a mut Perception>,
+    /// 玩家位置
+    pub player: Option<Vec2>,
+    /// 天气对侦测范围的倍率
+    pub visibility: f32,
+    pub nav_grid: Option<&'a NavGrid>,
+    pub delta: f32,
+    pub help_calls: &'a mut Vec<HelpCall>,
+    /// 本帧最后执行的动作节点，供调试显示
+    pub active: &'static str,
+}
+
+impl BehaviorContext<'_> {
+    fn position(&self) -> Vec2 {
+        self.transform.translation.truncate()
+    }
+
+    fn player_offset(&self) -> Option<Vec2> {
+        self.player.map(|player| player - self.position())
+    }
+
+    fn detection_range(&self) -> f32 {
+        self.npc.detection_radius * self.visibility
+    }
+
+    fn step(&mut self, direction: Vec2, speed: f32, state: CharacterState) {
+        self.character.state = state;
+        self.character.direction = direction;
+        let movement = direction * self.character.speed * speed * self.delta;
+        move_by(self.transform, self.body.as_deref_mut(), movement);
+    }
+}
+
+impl BehaviorNode {
+    /// 节点名称，调试显示用
+    pub fn label(&self) -> &'static str {
+        match self {
+            BehaviorNode::Selector(_) => "Selector",
+            BehaviorNode::Sequence(_) => "Sequence",
+            BehaviorNode::Not(_) => "Not",
+            BehaviorNode::InState(_) => "InState",
+            BehaviorNode::PlayerDetected => "PlayerDetected",
+            BehaviorNode::HeardNoise => "HeardNoise",
+            BehaviorNode::PlayerWithin { .. } => "PlayerWithin",
+            BehaviorNode::HealthBelow { .. } => "HealthBelow",
+            BehaviorNode::Idle => "Idle",
+            BehaviorNode::Roam => "Roam",
+            BehaviorNode::Patrol { .. } => "Patrol",
+            BehaviorNode::Chase { .. } => "Chase",
+            BehaviorNode::Attack { .. } => "Attack",
+            BehaviorNode::Flee { .. } => "Flee",
+            BehaviorNode::KeepDistance { .. } => "KeepDistance",
+            BehaviorNode::CallForHelp { .. } => "CallForHelp",
+            BehaviorNode::Investigate { .. } => "Investigate",
+        }
+    }
+
+    /// 执行一次节点
+    pub fn tick(&self, ctx: &mut BehaviorContext) -> BehaviorStatus {
+        use BehaviorStatus::*;
+
+        match self {
+            BehaviorNode::Selector(children) => children
+                .iter()
+                .map(|child| child.tick(ctx))
+                .find(|status| *status != Failure)
+                .unwrap_or(Failure),
+            BehaviorNode::Sequence(children) => children
+                .iter()
+                .map(|child| child.tick(ctx))
+                .find(|status| *status != Success)
+                .unwrap_or(Success),
+            BehaviorNode::Not(child) => match child.tick(ctx) {
+                Success => Failure,
+                Failure => Success,
+                Running => Running,
+            },
+            BehaviorNode::InState(states) => status(states.contains(&ctx.npc.ai_state)),
+            BehaviorNode::PlayerDetected => status(detects_player(ctx)),
+            BehaviorNode::HeardNoise => status(
+                ctx.perception
+                    .as_ref()
+                    .is_some_and(|perception| perception.heard.is_some()),
+            ),
+            BehaviorNode::PlayerWithin { distance } => status(
+                ctx.player_offset()
+                    .is_some_and(|offset| offset.length() <= *distance),
+            ),
+            BehaviorNode::HealthBelow { ratio } => {
+                status(ctx.character.health < ctx.character.max_health * ratio)
+            }
+            BehaviorNode::Idle => {
+                ctx.active = self.label();
+                ctx.npc.ai_state = AiState::Idle;
+                ctx.character.state = CharacterState::Idle;
+                Running
+            }
+            BehaviorNode::Roam => {
+                ctx.active = self.label();
+                roam(ctx);
+                Running
+            }
+            BehaviorNode::Patrol { speed } => {
+                if ctx.npc.patrol_points.is_empty() {
+                    return Failure;
+                }
+                ctx.active = self.label();
+                ctx.npc.ai_state = AiState::Patrol;
+                let index = ctx.npc.current_patrol_index % ctx.npc.patrol_points.len();
+                let direction =
+                    (ctx.npc.patrol_points[index] - ctx.transform.translation).truncate();
+                if direction.length() < 10.0 {
+                    // 到达巡逻点，前往下一个
+                    ctx.npc.current_patrol_index = (index + 1) % ctx.npc.patrol_points.len();
+                } else {
+                    ctx.step(direction.normalize(), *speed, CharacterState::Walking);
+                }
+                Running
+            }
+            BehaviorNode::Chase { speed } => {
+                let Some(offset) = ctx.player_offset() else {
+                    return Failure;
+                };
+                if offset.length() > ctx.detection_range() {
+                    return Failure;
+                }
+                ctx.active = self.label();
+                ctx.npc.ai_state = AiState::Chase;
+                if offset != Vec2::ZERO {
+                    ctx.step(offset.normalize(), *speed, CharacterState::Running);
+                }
+                Running
+            }
+            BehaviorNode::Attack { enter, leave } => {
+                let Some(offset) = ctx.player_offset() else {
+                    return Failure;
+                };
+                let range = if ctx.npc.ai_state == AiState::Attack {
+                    *leave
+                } else {
+                    *enter
+                };
+                if offset.length() > range {
+                    return Failure;
+                }
+                // 出招由战斗系统按冷却发起，这里只负责朝向玩家
+                ctx.active = self.label();
+                ctx.npc.ai_state = AiState::Attack;
+                if offset != Vec2::ZERO {
+                    ctx.character.direction = offset.normalize();
+                }
+                Running
+            }
+            BehaviorNode::Flee {
+                speed,
+                safe_distance,
+            } => {
+                let Some(offset) = ctx.player_offset() else {
+                    return Failure;
+                };
+                if offset.length() > *safe_distance {
+                    return Failure;
+                }
+                ctx.active = self.label();
+                ctx.npc.ai_state = AiState::Flee;
+                let away = if offset == Vec2::ZERO {
+                    Vec2::X
+                } else {
+                    -offset.normalize()
+                };
+                ctx.step(away, *speed, CharacterState::Running);
+                Running
+            }
+            BehaviorNode::KeepDistance { min, max, speed } => {
+                let Some(offset) = ctx.player_offset() else {
+                    return Failure;
+                };
+                if offset == Vec2::ZERO {
+                    return Failure;
+                }
+                ctx.active = self.label();
+                ctx.npc.ai_state = AiState::Chase;
+                let distance = offset.length();
+                let toward = offset.normalize();
+                if distance < *min {
+                    ctx.step(-toward, *speed, CharacterState::Walking);
+                    ctx.character.direction = toward;
+                } else if distance > *max {
+                    ctx.step(toward, *speed, CharacterState::Running);
+                } else {
+                    ctx.character.state = CharacterState::Idle;
+                    ctx.character.direction = toward;
+                }
+                Running
+            }
+            BehaviorNode::CallForHelp { radius } => {
+                // 只在刚进入交战时喊一次
+                if !matches!(ctx.npc.ai_state, AiState::Chase | AiState::Attack) {
+                    ctx.help_calls.push(HelpCall {
+                        caller: ctx.entity,
+                        npc_type: ctx.npc.npc_type,
+                        position: ctx.position(),
+                        radius: *radius,
+                    });
+                }
+                Success
+            }
+            BehaviorNode::Investigate { speed } => {
+                let Some(target) = ctx
+                    .perception
+                    .as_ref()
+                    .and_then(|perception| perception.heard)
+                else {
+                    return Failure;
+                };
+                let offset = target - ctx.position();
+                let blocked = ctx.body.as_ref().is_some_and(|body| body.blocked);
+                if offset.length() < 12.0 || blocked {
+                    // 到了或者过不去，都算查看过
+                    if let Some(perception) = ctx.perception.as_deref_mut() {
+                        perception.heard = None;
+                    }
+                    ctx.character.state = CharacterState::Idle;
+                    return Success;
+                }
+                ctx.active = self.label();
+                ctx.npc.ai_state = AiState::Wander;
+                ctx.step(offset.normalize(), *speed, CharacterState::Walking);
+                Running
+            }
+        }
+    }
+}
+
+fn status(condition: bool) -> BehaviorStatus {
+    if condition {
+        BehaviorStatus::Success
+    } else {
+        BehaviorStatus::Failure
+    }
+}
+
+/// 是否发现玩家，隔墙或隔水的玩家不会被发现
+///
+/// 有感知组件时视野与示警由感知系统算好，这里只补上距离判断
+fn detects_player(ctx: &BehaviorContext) -> bool {
+    let Some(player) = ctx.player else {
+        return false;
+    };
+    let position = ctx.position();
+    if player.distance(position) > ctx.detection_range() {
+        return false;
+    }
+    if matches!(ctx.npc.ai_state, AiState::Chase | AiState::Attack) {
+        return true;
+    }
+    if let Some(perception) = ctx.perception.as_ref() {
+        return perception.sees_player || perception.alerted;
+    }
+    ctx.nav_grid.is_none_or(|nav_grid| {
+        let from = world_to_tile(position);
+        let to = world_to_tile(player);
+        // 搜索范围按侦测半径估算，绕路太远的也当作走不到
+        let limit = ((ctx.npc.detection_radius / TILE_PIXELS * 2.0).powi(2) as usize).max(64);
+        nav_grid.line_of_sight(from, to) && nav_grid.is_reachable(from, to, limit)
+    })
+}
+
+/// 空闲与闲逛交替
+fn roam(ctx: &mut BehaviorContext) {
+    if !matches!(ctx.npc.ai_state, AiState::Idle | AiState::Wander) {
+        ctx.npc.ai_state = AiState::Idle;
+    }
+    let mut rng = rand::thread_rng();
+    let timer_finished = ctx.npc.wander_timer.just_finished();
+
+    if ctx.npc.ai_state == AiState::Idle {
+        ctx.character.state = CharacterState::Idle;
+        if timer_finished {
+            ctx.npc.ai_state = AiState::Wander;
+        }
+        return;
+    }
+
+    // 撞到障碍或计时结束时换个方向，计时结束时有概率回到空闲
+    let blocked = ctx.body.as_ref().is_some_and(|body| body.blocked);
+    if timer_finished || blocked {
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        ctx.character.direction = Vec2::new(angle.cos(), angle.sin());
+        if timer_finished && rng.gen::<f32>() < 0.3 {
+            ctx.npc.ai_state = AiState::Idle;
+            return;
+        }
+    }
+    let direction = ctx.character.direction;
+    ctx.step(direction, 0.5, CharacterState::Walking);
+}
+
+/// 没有配置行为树的NPC原地待着
+static FALLBACK_TREE: BehaviorNode = BehaviorNode::Idle;
+
+/// 按NPC类型配置的行为树
+#[derive(Resource, Debug, Clone, Default, Deserialize)]
+pub struct BehaviorTrees {
+    pub trees: HashMap<NpcType, BehaviorNode>,
+    /// 会下水的NPC类型，其余的把水面当作障碍
+    #[serde(default)]
+    pub swimmers: Vec<NpcType>,
+}
+
+impl BehaviorTrees {
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let trees: BehaviorTrees = serde_json::from_str(&content)?;
+        Ok(trees)
+    }
+
+    pub fn tree_for(&self, npc_type: NpcType) -> &BehaviorNode {
+        self.trees.get(&npc_type).unwrap_or(&FALLBACK_TREE)
+    }
+}
+
+/// NPC行为树当前执行的动作节点
+#[derive(Component, Debug, Clone, Default)]
+pub struct BehaviorTrace {
+    pub active: &'static str,
+}
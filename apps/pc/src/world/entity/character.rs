@@ -1,8 +1,11 @@
 use bevy::prelude::*;
-use crate::render::components::{SpriteComponent, AnimationComponent, LayerComponent, RenderLayer};
+use serde::{Deserialize, Serialize};
+use crate::render::components::{SpriteComponent, AnimationComponent, LayerComponent, RenderLayer, AmbientTinted};
+use crate::render::sorting::YSort;
+use crate::world::physics::MovementBody;
 
 /// 角色状态
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CharacterState {
     Idle,
     Walking,
@@ -13,6 +16,12 @@ pub enum CharacterState {
     Defending,
     Hurt,
     Dead,
+    /// 骑马
+    Riding,
+    /// 施展轻功
+    Qinggong,
+    /// 游泳
+    Swimming,
 }
 
 /// 角色组件
@@ -22,6 +31,12 @@ pub struct Character {
     pub state: CharacterState,
     pub health: f32,
     pub max_health: f32,
+    /// 内力
+    pub qi: f32,
+    pub max_qi: f32,
+    /// 体力，轻功与招式消耗，随时间恢复
+    pub stamina: f32,
+    pub max_stamina: f32,
     pub speed: f32,
     pub direction: Vec2,
     pub is_grounded: bool,
@@ -35,6 +50,10 @@ impl Default for Character {
             state: CharacterState::Idle,
             health: 100.0,
             max_health: 100.0,
+            qi: 100.0,
+            max_qi: 100.0,
+            stamina: 100.0,
+            max_stamina: 100.0,
             speed: 100.0,
             direction: Vec2::ZERO,
             is_grounded: true,
@@ -46,13 +65,14 @@ impl Default for Character {
 /// 生成角色实体
 pub fn spawn_character(
     commands: &mut Commands,
-    asset_server: &AssetServer,
+    _asset_server: &AssetServer,
     position: Vec3,
     name: &str,
     texture_path: &str,
 ) -> Entity {
     commands.spawn((
-        SpatialBundle::from_transform(Transform::from_translation(position)),
+        Transform::from_translation(position),
+        Visibility::default(),
         Character {
             name: name.to_string(),
             ..default()
@@ -60,14 +80,15 @@ pub fn spawn_character(
         SpriteComponent {
             texture_path: texture_path.to_string(),
             size: Vec2::new(32.0, 64.0),
-            offset: Vec2::ZERO,
             flip_x: false,
             flip_y: false,
             color: Color::WHITE,
             visible: true,
         },
+        AmbientTinted {
+            base_color: Color::WHITE,
+        },
         AnimationComponent {
-            animation_type: crate::render::components::AnimationType::Sprite,
             current_animation: "idle".to_string(),
             animations: vec![
                 "idle".to_string(),
@@ -85,32 +106,11 @@ pub fn spawn_character(
             layer: RenderLayer::Character,
             sub_order: 0,
         },
+        // 以脚底排序，站在树前还是树后看脚的位置
+        YSort {
+            foot_offset: -32.0,
+            elevation: 0.0,
+        },
+        MovementBody::default(),
     )).id()
 }
-
-/// 更新角色状态系统
-pub fn update_character_state(
-    mut query: Query<(&mut Character, &mut AnimationComponent)>,
-    time: Res<Time>,
-) {
-    for (mut character, mut animation) in query.iter_mut() {
-        // 根据角色状态更新动画
-        let anim_name = match character.state {
-            CharacterState::Idle => "idle",
-            CharacterState::Walking => "walk",
-            CharacterState::Running => "run",
-            CharacterState::Attacking => "attack",
-            CharacterState::Defending => "defend",
-            CharacterState::Hurt => "hurt",
-            CharacterState::Dead => "dead",
-            _ => "idle",
-        };
-        
-        // 如果状态改变，切换动画
-        if animation.current_animation != anim_name {
-            animation.current_animation = anim_name.to_string();
-            animation.current_frame = 0;
-            animation.timer.reset();
-        }
-    }
-} 
\ No newline at end of file
@@ -1,7 +1,20 @@
+/// 实体模块
+///
+/// # 模块组成
+/// 1. character / player / npc：角色、玩家与NPC组件
+/// 2. behavior：NPC行为树，节点与按NPC类型配置的树
+/// 3. perception：NPC的视野、听觉与同伙示警
+/// 4. systems：NPC AI插件与行为树调试显示
+mod behavior;
 mod character;
 mod npc;
+mod perception;
 mod player;
+mod systems;
 
+pub use behavior::*;
 pub use character::*;
 pub use npc::*;
-pub use player::*; 
\ No newline at end of file
+pub use perception::*;
+pub use player::*;
+pub use systems::*;
@@ -1,17 +1,47 @@
 use bevy::prelude::*;
-use rand::Rng;
-use crate::world::entity::{Character, CharacterState};
+use serde::{Deserialize, Serialize};
+use crate::combat::{ActionState, NpcCombat};
+use crate::render::animation::AnimationFinished;
+use crate::world::chunk::{NavGrid, WaterCurrent};
+use crate::world::entity::{BehaviorContext, BehaviorTrace, BehaviorTrees, Character, CharacterState, Perception};
+use crate::world::physics::MovementBody;
+use crate::world::schedule::Dormant;
+use crate::world::weather::{WeatherSettings, WeatherState};
 
 /// NPC类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum NpcType {
     Villager,
     Merchant,
+    Blacksmith,
     Guard,
     Enemy,
     Boss,
 }
 
+impl NpcType {
+    pub const ALL: [NpcType; 6] = [
+        NpcType::Villager,
+        NpcType::Merchant,
+        NpcType::Blacksmith,
+        NpcType::Guard,
+        NpcType::Enemy,
+        NpcType::Boss,
+    ];
+
+    /// 该类NPC的角色贴图
+    pub fn texture_path(self) -> &'static str {
+        match self {
+            NpcType::Villager => "textures/characters/villager.png",
+            NpcType::Merchant => "textures/characters/merchant.png",
+            NpcType::Blacksmith => "textures/characters/blacksmith.png",
+            NpcType::Guard => "textures/characters/guard.png",
+            NpcType::Enemy => "textures/characters/enemy.png",
+            NpcType::Boss => "textures/characters/boss.png",
+        }
+    }
+}
+
 /// NPC组件
 #[derive(Component)]
 pub struct Npc {
@@ -21,12 +51,11 @@ pub struct Npc {
     pub patrol_points: Vec<Vec3>,
     pub current_patrol_index: usize,
     pub detection_radius: f32,
-    pub aggression: f32,
     pub wander_timer: Timer,
 }
 
 /// AI状态
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AiState {
     Idle,
     Patrol,
@@ -46,7 +75,6 @@ impl Default for Npc {
             patrol_points: Vec::new(),
             current_patrol_index: 0,
             detection_radius: 100.0,
-            aggression: 0.0,
             wander_timer: Timer::from_seconds(3.0, TimerMode::Repeating),
         }
     }
@@ -61,154 +89,135 @@ pub fn spawn_npc(
     name: &str,
 ) -> Entity {
     // 创建角色实体
-    let texture_path = match npc_type {
-        NpcType::Villager => "textures/characters/villager.png",
-        NpcType::Merchant => "textures/characters/merchant.png",
-        NpcType::Guard => "textures/characters/guard.png",
-        NpcType::Enemy => "textures/characters/enemy.png",
-        NpcType::Boss => "textures/characters/boss.png",
-    };
-    
     let npc_entity = crate::world::entity::spawn_character(
         commands,
         asset_server,
         position,
         name,
-        texture_path,
+        npc_type.texture_path(),
     );
     
     // 添加NPC组件
-    commands.entity(npc_entity).insert(Npc {
-        npc_type,
-        ..default()
-    });
+    commands.entity(npc_entity).insert((
+        Npc {
+            npc_type,
+            ..default()
+        },
+        ActionState::default(),
+        NpcCombat::default(),
+        BehaviorTrace::default(),
+        Perception::default(),
+        WaterCurrent::swimmer(),
+    ));
     
     npc_entity
 }
 
 /// 更新NPC AI系统
+///
+/// 按NPC类型执行行为树，交谈中的NPC由对话系统接管；
+/// 休眠中的NPC（在家睡觉或所在区块未加载）不更新
+#[allow(clippy::type_complexity)]
 pub fn update_npc_ai(
-    mut npc_query: Query<(&mut Npc, &mut Character, &mut Transform)>,
-    player_query: Query<(&Character, &Transform), (With<crate::world::entity::Player>, Without<Npc>)>,
+    mut npc_query: Query<(Entity, &mut Npc, &mut Character, &mut Transform, Option<&mut MovementBody>, Option<&mut BehaviorTrace>, Option<&mut Perception>), Without<Dormant>>,
+    player_query: Query<&Transform, (With<crate::world::entity::Player>, Without<Npc>)>,
+    trees: Res<BehaviorTrees>,
     time: Res<Time>,
+    weather: Option<Res<WeatherState>>,
+    weather_settings: Option<Res<WeatherSettings>>,
+    nav_grid: Option<Res<NavGrid>>,
 ) {
-    let player = player_query.get_single();
+    let player = player_query.get_single().ok().map(|transform| transform.translation.truncate());
     
-    for (mut npc, mut character, mut transform) in npc_query.iter_mut() {
+    // 雾天等天气会缩小侦测范围
+    let visibility = match (&weather, &weather_settings) {
+        (Some(weather), Some(settings)) => weather.visibility_multiplier(settings),
+        _ => 1.0,
+    };
+    
+    let mut help_calls = Vec::new();
+    for (entity, mut npc, mut character, mut transform, mut body, trace, mut perception) in npc_query.iter_mut() {
+        if npc.ai_state == AiState::Talk {
+            character.state = CharacterState::Idle;
+            continue;
+        }
+        // 受击硬直中不行动，等受击动画播完
+        if character.state == CharacterState::Hurt {
+            continue;
+        }
+        
         // 更新计时器
         npc.wander_timer.tick(time.delta());
         
-        match npc.ai_state {
-            AiState::Idle => {
-                character.state = CharacterState::Idle;
-                
-                // 随机切换到游荡状态
-                if npc.wander_timer.just_finished() {
-                    npc.ai_state = AiState::Wander;
-                }
-            },
-            AiState::Wander => {
-                character.state = CharacterState::Walking;
-                
-                // 随机移动
-                if npc.wander_timer.just_finished() {
-                    let mut rng = rand::thread_rng();
-                    let angle = rng.gen_range(0.0..std::f32::consts::TAU);
-                    character.direction = Vec2::new(angle.cos(), angle.sin());
-                    
-                    // 有概率回到空闲状态
-                    if rng.gen::<f32>() < 0.3 {
-                        npc.ai_state = AiState::Idle;
-                    }
-                }
-                
-                // 应用移动
-                let movement = character.direction * character.speed * 0.5 * time.delta_seconds();
-                transform.translation.x += movement.x;
-                transform.translation.y += movement.y;
-            },
-            AiState::Patrol => {
-                if !npc.patrol_points.is_empty() {
-                    let target = npc.patrol_points[npc.current_patrol_index];
-                    let direction = (target - transform.translation).truncate();
-                    
-                    if direction.length() < 10.0 {
-                        // 到达巡逻点，前往下一个
-                        npc.current_patrol_index = (npc.current_patrol_index + 1) % npc.patrol_points.len();
-                    } else {
-                        // 移向巡逻点
-                        character.state = CharacterState::Walking;
-                        character.direction = direction.normalize();
-                        
-                        let movement = character.direction * character.speed * time.delta_seconds();
-                        transform.translation.x += movement.x;
-                        transform.translation.y += movement.y;
-                    }
-                }
-            },
-            AiState::Chase => {
-                if let Ok((_, player_transform)) = player {
-                    let direction = (player_transform.translation - transform.translation).truncate();
-                    
-                    if direction.length() < 20.0 {
-                        // 足够近，切换到攻击状态
-                        npc.ai_state = AiState::Attack;
-                    } else if direction.length() > npc.detection_radius {
-                        // 超出检测范围，回到游荡状态
-                        npc.ai_state = AiState::Wander;
-                    } else {
-                        // 追逐玩家
-                        character.state = CharacterState::Running;
-                        character.direction = direction.normalize();
-                        
-                        let movement = character.direction * character.speed * 1.5 * time.delta_seconds();
-                        transform.translation.x += movement.x;
-                        transform.translation.y += movement.y;
-                    }
-                }
-            },
-            AiState::Attack => {
-                character.state = CharacterState::Attacking;
-                
-                // 攻击完成后回到追逐状态
-                if character.state == CharacterState::Attacking {
-                    // 这里应该有攻击逻辑
-                    npc.ai_state = AiState::Chase;
-                }
-            },
-            AiState::Flee => {
-                if let Ok((_, player_transform)) = player {
-                    let direction = (transform.translation - player_transform.translation).truncate();
-                    
-                    if direction.length() > npc.detection_radius * 2.0 {
-                        // 已经逃离足够远，回到游荡状态
-                        npc.ai_state = AiState::Wander;
-                    } else {
-                        // 远离玩家
-                        character.state = CharacterState::Running;
-                        character.direction = direction.normalize();
-                        
-                        let movement = character.direction * character.speed * 1.2 * time.delta_seconds();
-                        transform.translation.x += movement.x;
-                        transform.translation.y += movement.y;
-                    }
-                }
-            },
-            AiState::Talk => {
-                character.state = CharacterState::Idle;
-                // 对话逻辑应该在其他系统中处理
-            },
+        let npc_type = npc.npc_type;
+        // 不会水的NPC把水面当作障碍绕开
+        let avoid_water = !trees.swimmers.contains(&npc_type);
+        if let Some(body) = body.as_mut() {
+            if body.avoid_water != avoid_water {
+                body.avoid_water = avoid_water;
+            }
         }
+        let mut ctx = BehaviorContext {
+            entity,
+            npc: &mut npc,
+            character: &mut character,
+            transform: &mut transform,
+            body: body.as_deref_mut(),
+            perception: perception.as_deref_mut(),
+            player,
+            visibility,
+            nav_grid: nav_grid.as_deref(),
+            delta: time.delta_secs(),
+            help_calls: &mut help_calls,
+            active: "",
+        };
+        trees.tree_for(npc_type).tick(&mut ctx);
         
-        // 检测玩家
-        if npc.npc_type == NpcType::Enemy || npc.npc_type == NpcType::Boss {
-            if let Ok((_, player_transform)) = player {
-                let distance = (player_transform.translation - transform.translation).length();
-                
-                if distance < npc.detection_radius && npc.ai_state != AiState::Chase && npc.ai_state != AiState::Attack {
-                    npc.ai_state = AiState::Chase;
-                }
+        let active = ctx.active;
+        if let Some(mut trace) = trace {
+            trace.active = active;
+        }
+    }
+    
+    // 应援：附近空闲的同伙转入追击
+    if help_calls.is_empty() {
+        return;
+    }
+    for (entity, mut npc, _, transform, _, _, _) in npc_query.iter_mut() {
+        if !matches!(npc.ai_state, AiState::Idle | AiState::Wander | AiState::Patrol) {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        let called = help_calls.iter().any(|call| {
+            call.caller != entity
+                && allied(call.npc_type, npc.npc_type)
+                && call.position.distance(position) <= call.radius
+        });
+        if called {
+            npc.ai_state = AiState::Chase;
+        }
+    }
+}
+
+/// 会互相应援的NPC类型
+pub fn allied(a: NpcType, b: NpcType) -> bool {
+    let hostile = |t| matches!(t, NpcType::Enemy | NpcType::Boss);
+    a == b || (hostile(a) && hostile(b))
+}
+
+/// 受击动画播完后NPC回到待机，行为树下一次更新重新接管
+pub fn recover_from_hurt(
+    mut finished: EventReader<AnimationFinished>,
+    mut npcs: Query<&mut Character, With<Npc>>,
+) {
+    for event in finished.read() {
+        if event.animation != "hurt" {
+            continue;
+        }
+        if let Ok(mut character) = npcs.get_mut(event.entity) {
+            if character.state == CharacterState::Hurt {
+                character.state = CharacterState::Idle;
             }
         }
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,227 @@
+use bevy::prelude::*;
+
+use crate::combat::{CombatActionEvent, DamageEvent};
+use crate::world::chunk::{world_to_tile, NavGrid, TILE_PIXELS};
+use crate::world::entity::{allied, Character, CharacterState, Npc, NpcType, Player};
+use crate::world::schedule::Dormant;
+use crate::world::weather::{WeatherSettings, WeatherState};
+
+/// 感知设置
+///
+/// - fov_degrees: 视野张角
+/// - peripheral_range: 这个距离内不论朝向都能察觉（像素）
+/// - running_noise / attack_noise / hit_noise: 奔跑、出招、受击的声音传播距离（像素）
+/// - footstep_interval: 奔跑时发出脚步声的间隔（秒）
+/// - alert_radius: 发现玩家的NPC向同伙示警的距离（像素）
+/// - memory: 示警与听到的声音保留的秒数
+#[derive(Resource, Debug, Clone)]
+pub struct PerceptionSettings {
+    pub fov_degrees: f32,
+    pub peripheral_range: f32,
+    pub running_noise: f32,
+    pub attack_noise: f32,
+    pub hit_noise: f32,
+    pub footstep_interval: f32,
+    pub alert_radius: f32,
+    pub memory: f32,
+}
+
+impl Default for PerceptionSettings {
+    fn default() -> Self {
+        Self {
+            fov_degrees: 120.0,
+            peripheral_range: 40.0,
+            running_noise: 160.0,
+            attack_noise: 224.0,
+            hit_noise: 288.0,
+            footstep_interval: 0.5,
+            alert_radius: 192.0,
+            memory: 6.0,
+        }
+    }
+}
+
+/// 声响事件，附近的NPC按听力判断能否听到
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NoiseEvent {
+    pub source: Entity,
+    pub position: Vec2,
+    /// 传播距离（像素）
+    pub radius: f32,
+}
+
+/// NPC的感知状态，由感知系统每帧更新，供行为树判断
+#[derive(Component, Debug, Clone)]
+pub struct Perception {
+    /// 听力倍率
+    pub hearing: f32,
+    /// 这一帧是否看见玩家
+    pub sees_player: bool,
+    /// 同伙示警，在记忆时间内即使看不见也知道玩家在附近
+    pub alerted: bool,
+    /// 听到的可疑声响位置，查看过或记忆过期后清除
+    pub heard: Option<Vec2>,
+    alerted_until: f64,
+    heard_until: f64,
+}
+
+impl Default for Perception {
+    fn default() -> Self {
+        Self {
+            hearing: 1.0,
+            sees_player: false,
+            alerted: false,
+            heard: None,
+            alerted_until: 0.0,
+            heard_until: 0.0,
+        }
+    }
+}
+
+/// 示警
+struct Alert {
+    npc_type: NpcType,
+    position: Vec2,
+}
+
+/// 玩家奔跑或骑马、任何角色出招或受击时发出声响
+#[allow(clippy::too_many_arguments)]
+pub fn emit_noises(
+    time: Res<Time>,
+    settings: Res<PerceptionSettings>,
+    players: Query<(Entity, &Character, &Transform), With<Player>>,
+    positions: Query<&Transform>,
+    mut actions: EventReader<CombatActionEvent>,
+    mut damages: EventReader<DamageEvent>,
+    mut noises: EventWriter<NoiseEvent>,
+    mut footstep: Local<f32>,
+) {
+    if let Ok((entity, character, transform)) = players.get_single() {
+        *footstep -= time.delta_secs();
+        let loud = matches!(
+            character.state,
+            CharacterState::Running | CharacterState::Riding
+        );
+        if loud && *footstep <= 0.0 {
+            *footstep = settings.footstep_interval;
+            noises.send(NoiseEvent {
+                source: entity,
+                position: transform.translation.truncate(),
+                radius: settings.running_noise,
+            });
+        }
+    }
+
+    for action in actions.read() {
+        if let Ok(transform) = positions.get(action.entity) {
+            noises.send(NoiseEvent {
+                source: action.entity,
+                position: transform.translation.truncate(),
+                radius: settings.attack_noise,
+            });
+        }
+    }
+    for damage in damages.read() {
+        if let Ok(transform) = positions.get(damage.target) {
+            noises.send(NoiseEvent {
+                source: damage.target,
+                position: transform.translation.truncate(),
+                radius: settings.hit_noise,
+            });
+        }
+    }
+}
+
+/// 更新NPC的视觉与听觉，新发现玩家的NPC向附近同伙示警
+///
+/// 视野按角色朝向的扇形判断，贴身时不论朝向都能察觉；
+/// 遮挡视线的瓦片挡住视线，绕路太远走不到的也当作没看见
+#[allow(clippy::too_many_arguments)]
+pub fn update_perception(
+    time: Res<Time>,
+    settings: Res<PerceptionSettings>,
+    weather: Option<Res<WeatherState>>,
+    weather_settings: Option<Res<WeatherSettings>>,
+    nav_grid: Option<Res<NavGrid>>,
+    mut noises: EventReader<NoiseEvent>,
+    players: Query<&Transform, With<Player>>,
+    mut npcs: Query<(Entity, &Npc, &Character, &Transform, &mut Perception), Without<Dormant>>,
+) {
+    let now = time.elapsed_secs_f64();
+    let player = players
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation.truncate());
+    let noises: Vec<NoiseEvent> = noises.read().copied().collect();
+
+    // 雾天等天气会缩小视野
+    let visibility = match (&weather, &weather_settings) {
+        (Some(weather), Some(settings)) => weather.visibility_multiplier(settings),
+        _ => 1.0,
+    };
+    let half_fov = (settings.fov_degrees * 0.5).to_radians();
+
+    let mut alerts = Vec::new();
+    for (entity, npc, character, transform, mut perception) in npcs.iter_mut() {
+        let position = transform.translation.truncate();
+
+        let sees = player.is_some_and(|player| {
+            let offset = player - position;
+            let distance = offset.length();
+            if distance > npc.detection_radius * visibility {
+                return false;
+            }
+            let in_view = distance <= settings.peripheral_range
+                || character.direction == Vec2::ZERO
+                || character.direction.angle_to(offset).abs() <= half_fov;
+            in_view
+                && nav_grid.as_ref().is_none_or(|nav_grid| {
+                    let from = world_to_tile(position);
+                    let to = world_to_tile(player);
+                    // 搜索范围按侦测半径估算
+                    let limit =
+                        ((npc.detection_radius / TILE_PIXELS * 2.0).powi(2) as usize).max(64);
+                    nav_grid.line_of_sight(from, to) && nav_grid.is_reachable(from, to, limit)
+                })
+        });
+        if sees && !perception.sees_player && !perception.alerted {
+            alerts.push(Alert {
+                npc_type: npc.npc_type,
+                position,
+            });
+        }
+        perception.sees_player = sees;
+
+        let heard = noises
+            .iter()
+            .filter(|noise| noise.source != entity)
+            .filter(|noise| noise.position.distance(position) <= noise.radius * perception.hearing)
+            .min_by(|a, b| {
+                a.position
+                    .distance(position)
+                    .total_cmp(&b.position.distance(position))
+            });
+        if let Some(noise) = heard {
+            perception.heard = Some(noise.position);
+            perception.heard_until = now + settings.memory as f64;
+        } else if now > perception.heard_until {
+            perception.heard = None;
+        }
+        perception.alerted = now <= perception.alerted_until;
+    }
+
+    if alerts.is_empty() {
+        return;
+    }
+    for (_, npc, _, transform, mut perception) in npcs.iter_mut() {
+        let position = transform.translation.truncate();
+        let alerted = alerts.iter().any(|alert| {
+            allied(alert.npc_type, npc.npc_type)
+                && alert.position.distance(position) <= settings.alert_radius
+        });
+        if alerted {
+            perception.alerted_until = now + settings.memory as f64;
+            perception.alerted = true;
+        }
+    }
+}
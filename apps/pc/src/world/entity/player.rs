@@ -1,15 +1,24 @@
 use bevy::prelude::*;
-use crate::events::input::{GameAction, InputState};
+use crate::combat::{ActionState, InputBuffer, SkillBook, StatusEffectSettings, StatusEffects};
+use crate::events::input::GameAction;
+use crate::items::{Encumbrance, Equipment, Inventory};
+use crate::resources::InputState;
+use crate::world::chunk::WaterCurrent;
 use crate::world::entity::{Character, CharacterState};
+use crate::world::physics::{move_by, MovementBody, TraversalMode};
+use crate::world::traversal::{Rider, TraversalSettings};
+use crate::world::weather::GroundCondition;
 use crate::render::camera::CameraController;
 
+/// 疾跑时的移速倍率
+const SPRINT_SPEED_MULTIPLIER: f32 = 1.6;
+
 /// 玩家组件
 #[derive(Component)]
 pub struct Player {
     pub experience: u32,
     pub level: u32,
     pub skill_points: u32,
-    pub inventory_capacity: u32,
 }
 
 impl Default for Player {
@@ -18,7 +27,6 @@ impl Default for Player {
             experience: 0,
             level: 1,
             skill_points: 0,
-            inventory_capacity: 20,
         }
     }
 }
@@ -39,19 +47,57 @@ pub fn spawn_player(
     );
     
     // 添加玩家组件
-    commands.entity(player_entity).insert(Player::default());
+    commands
+        .entity(player_entity)
+        .insert((
+            Player::default(),
+            InputBuffer::default(),
+            ActionState::default(),
+            SkillBook::default(),
+            StatusEffects::default(),
+            Inventory::default(),
+            Equipment::default(),
+            Encumbrance::default(),
+            GroundCondition::default(),
+            WaterCurrent::swimmer(),
+        ));
     
     player_entity
 }
 
 /// 处理玩家输入系统
+#[allow(clippy::type_complexity)]
 pub fn handle_player_input(
     input_state: Res<InputState>,
     time: Res<Time>,
-    mut player_query: Query<(&mut Character, &mut Transform), With<Player>>,
+    mut player_query: Query<
+        (
+            Entity,
+            &mut Character,
+            &mut Transform,
+            Option<&mut MovementBody>,
+            Option<&Encumbrance>,
+            Option<&GroundCondition>,
+            Option<&StatusEffects>,
+            Option<&Rider>,
+        ),
+        With<Player>,
+    >,
+    status_settings: Res<StatusEffectSettings>,
+    traversal_settings: Res<TraversalSettings>,
     mut camera_query: Query<&mut CameraController, With<Camera>>,
 ) {
-    if let Ok((mut character, mut transform)) = player_query.get_single_mut() {
+    if let Ok((
+        player_entity,
+        mut character,
+        mut transform,
+        body,
+        encumbrance,
+        ground,
+        statuses,
+        rider,
+    )) = player_query.get_single_mut()
+    {
         if !character.can_move {
             return;
         }
@@ -73,34 +119,67 @@ pub fn handle_player_input(
             direction.x += 1.0;
             character.direction.x = 1.0;
         }
+        // 手柄摇杆给出带力度的方向，轻推时慢走
+        if input_state.move_axis != Vec2::ZERO {
+            direction = input_state.move_axis;
+        }
+        
+        // 超重时减速，且无法疾跑；雨雪天地面湿滑、迟缓与湿身也会减速
+        let speed_multiplier = encumbrance.map_or(1.0, |e| e.speed_multiplier)
+            * ground.map_or(1.0, |g| g.movement_multiplier)
+            * statuses.map_or(1.0, |s| s.speed_multiplier(&status_settings));
+        let sprinting = input_state.is_action_active(GameAction::Sprint)
+            && encumbrance.is_none_or(|e| e.can_sprint());
+        
+        // 骑马与轻功各有速度，步行时才能疾跑，深水里只能游
+        let mode = body.as_ref().map_or(TraversalMode::OnFoot, |body| body.mode);
+        let (moving_state, standing_state, mode_multiplier) = match mode {
+            TraversalMode::Mounted => (
+                CharacterState::Riding,
+                CharacterState::Riding,
+                rider.map_or(1.0, |rider| rider.speed_multiplier),
+            ),
+            TraversalMode::Qinggong => (
+                CharacterState::Qinggong,
+                CharacterState::Idle,
+                traversal_settings.qinggong_speed_multiplier,
+            ),
+            // 游泳的减速由碰撞求解按水深结算，这里不再叠加疾跑
+            TraversalMode::OnFoot if body.as_ref().is_some_and(|body| body.swimming) => {
+                (CharacterState::Swimming, CharacterState::Swimming, 1.0)
+            }
+            TraversalMode::OnFoot if sprinting => {
+                (CharacterState::Running, CharacterState::Idle, SPRINT_SPEED_MULTIPLIER)
+            }
+            TraversalMode::OnFoot => (CharacterState::Walking, CharacterState::Idle, 1.0),
+        };
         
-        // 归一化方向向量
+        // 归一化方向向量；跳跃下落中保持原状态，交给重力系统落地
+        let airborne = matches!(character.state, CharacterState::Jumping | CharacterState::Falling);
         if direction != Vec2::ZERO {
-            direction = direction.normalize();
-            character.state = CharacterState::Walking;
-        } else {
-            character.state = CharacterState::Idle;
+            // 斜向按键归一化，摇杆保留力度
+            direction = direction.clamp_length_max(1.0);
+            if !airborne {
+                character.state = moving_state;
+            }
+        } else if !airborne {
+            character.state = standing_state;
         }
         
         // 应用移动
-        let movement = direction * character.speed * time.delta_seconds();
-        transform.translation.x += movement.x;
-        transform.translation.y += movement.y;
+        let movement = direction
+            * character.speed
+            * speed_multiplier
+            * mode_multiplier
+            * time.delta_secs();
+        // 交给碰撞系统求解，不再直接穿过墙体与水面
+        move_by(&mut transform, body.map(Mut::into_inner), movement);
         
         // 更新相机跟随
         if let Ok(mut controller) = camera_query.get_single_mut() {
-            controller.target = Some(player_query.single().1.entity());
+            controller.target = Some(player_entity);
         }
         
-        // 处理攻击输入
-        if input_state.is_action_just_pressed(GameAction::Attack) {
-            character.state = CharacterState::Attacking;
-            // 这里可以添加攻击逻辑
-        }
-        
-        // 处理交互输入
-        if input_state.is_action_just_pressed(GameAction::Interact) {
-            // 这里可以添加交互逻辑
-        }
+        // 攻击与闪避输入由战斗输入缓冲在固定步长中处理，交互键由交互模块分派
     }
 } 
\ No newline at end of file
@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+
+use super::{
+    emit_noises, handle_player_input, recover_from_hurt, update_npc_ai, update_perception,
+    BehaviorTrace, BehaviorTrees, NoiseEvent, Npc, PerceptionSettings, BEHAVIOR_DATA_PATH,
+};
+use crate::config::resolve_data_path;
+use crate::events::input::handle_input_events;
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::{gameplay_running, GlobalGameState};
+
+/// 调试标签在NPC头顶的高度（像素）
+const LABEL_OFFSET: f32 = 28.0;
+
+/// 行为树调试标签
+#[derive(Component)]
+pub struct BehaviorLabel;
+
+/// NPC AI插件
+///
+/// # 设计思路
+/// 1. 每种NPC的行为树写在数据文件中，新增行为只需组合已有节点
+/// 2. 行为树仍写回 AiState，战斗、音效、商队等按状态判断的系统不受影响
+/// 3. 感知系统先算出视野、听觉与示警，行为树只读取结果
+/// 4. 调试模式下在每个NPC头顶显示正在执行的节点
+/// 5. 感知与行为树只在游戏中运行，暂停或回到主菜单时NPC原地不动
+/// 6. 受击硬直持续到受击动画播完，之后才交还给行为树
+pub struct NpcAiPlugin;
+
+impl Plugin for NpcAiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PerceptionSettings>()
+            .add_event::<NoiseEvent>()
+            .add_systems(PreStartup, load_behavior_trees)
+            .add_systems(
+                Update,
+                (
+                    recover_from_hurt,
+                    emit_noises,
+                    update_perception,
+                    update_npc_ai,
+                )
+                    .chain()
+                    .run_if(gameplay_running),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    draw_behavior_labels.run_if(debug_enabled),
+                    clear_behavior_labels.run_if(not(debug_enabled)),
+                ),
+            );
+    }
+}
+
+/// 玩家操作插件
+///
+/// 玩家实体由存档插件在开始新游戏或读档时生成，这里只把输入转成移动意图，
+/// 暂停或回到主菜单时不响应
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            handle_player_input
+                .after(handle_input_events)
+                .run_if(gameplay_running),
+        );
+    }
+}
+
+fn debug_enabled(state: Res<GlobalGameState>) -> bool {
+    state.is_debug
+}
+
+/// 加载行为树，失败时NPC原地待着
+fn load_behavior_trees(mut commands: Commands, mut logger: Option<ResMut<GameLogger>>) {
+    let trees = match BehaviorTrees::load(&resolve_data_path(BEHAVIOR_DATA_PATH)) {
+        Ok(trees) => trees,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("行为树加载失败: {}", e));
+            }
+            BehaviorTrees::default()
+        }
+    };
+    commands.insert_resource(trees);
+}
+
+/// 在NPC头顶显示行为树正在执行的节点
+fn draw_behavior_labels(
+    mut commands: Commands,
+    npcs: Query<(Entity, &Npc, &BehaviorTrace, Option<&Children>)>,
+    mut labels: Query<&mut Text2d, With<BehaviorLabel>>,
+) {
+    for (entity, npc, trace, children) in npcs.iter() {
+        let text = format!("{:?} / {}", npc.ai_state, trace.active);
+        let label = children.and_then(|children| {
+            children
+                .iter()
+                .find(|child| labels.contains(**child))
+                .copied()
+        });
+
+        match label {
+            Some(label) => {
+                if let Ok(mut label) = labels.get_mut(label) {
+                    if label.0 != text {
+                        label.0 = text;
+                    }
+                }
+            }
+            None => {
+                commands.entity(entity).with_children(|parent| {
+                    parent.spawn((
+                        BehaviorLabel,
+                        Text2d::new(text),
+                        TextFont {
+                            font_size: 10.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.6, 1.0, 0.6)),
+                        Transform::from_xyz(0.0, LABEL_OFFSET, 5.0),
+                    ));
+                });
+            }
+        }
+    }
+}
+
+/// 关闭调试模式后移除标签
+fn clear_behavior_labels(mut commands: Commands, labels: Query<Entity, With<BehaviorLabel>>) {
+    for label in labels.iter() {
+        commands.entity(label).despawn_recursive();
+    }
+}
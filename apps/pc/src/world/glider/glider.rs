@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+
+use crate::world::map::{get_tile_physics, TileType};
+
+/// 滑翔翼物品ID
+pub const GLIDER_ITEM_ID: &str = "flying_kite_glider";
+
+/// 滑翔配置
+///
+/// 高度与地形高度同一尺度（0.0-1.0），水平速度以像素为单位
+#[derive(Resource, Debug, Clone)]
+pub struct GliderSettings {
+    /// 崖边展开所需的最小落差
+    pub cliff_drop: f32,
+    /// 向前探测落差的距离（瓦片）
+    pub cliff_probe: f32,
+    /// 展开所需的最少内力
+    pub min_qi: f32,
+    /// 每秒消耗的内力
+    pub qi_drain: f32,
+    /// 逆风操控时额外消耗的内力倍率
+    pub headwind_drain: f32,
+    /// 重力加速度（每秒²下降的高度）
+    pub gravity: f32,
+    /// 滑翔时的稳定下沉速度
+    pub sink_rate: f32,
+    /// 内力耗尽后的下沉速度
+    pub stall_sink_rate: f32,
+    /// 滑翔速度（像素/秒）
+    pub glide_speed: f32,
+    /// 速度向操控方向靠拢的快慢
+    pub steer_response: f32,
+    /// 每单位风力推动的速度（像素/秒）
+    pub wind_push: f32,
+    /// 超过该下沉速度落地时受伤
+    pub hard_landing_speed: f32,
+    /// 每单位超出速度造成的伤害
+    pub landing_damage: f32,
+    /// 撞上无法落脚的地形时至少受到的伤害
+    pub crash_damage: f32,
+    /// 在起飞点附近多远以内算从峰顶起飞（瓦片）
+    pub launch_radius: f32,
+    /// 起飞点上升气流带来的额外高度
+    pub updraft_lift: f32,
+    /// 起飞点的最低高度
+    pub peak_min_height: f32,
+    /// 起飞点与周围的最小落差
+    pub peak_min_prominence: f32,
+    /// 判断峰顶时比较的范围（瓦片）
+    pub peak_ring: usize,
+}
+
+impl Default for GliderSettings {
+    fn default() -> Self {
+        Self {
+            cliff_drop: 0.12,
+            cliff_probe: 2.0,
+            min_qi: 20.0,
+            qi_drain: 4.0,
+            headwind_drain: 0.5,
+            gravity: 0.12,
+            sink_rate: 0.012,
+            stall_sink_rate: 0.08,
+            glide_speed: 180.0,
+            steer_response: 2.5,
+            wind_push: 14.0,
+            hard_landing_speed: 0.05,
+            landing_damage: 600.0,
+            crash_damage: 15.0,
+            launch_radius: 2.0,
+            updraft_lift: 0.15,
+            peak_min_height: 0.75,
+            peak_min_prominence: 0.1,
+            peak_ring: 3,
+        }
+    }
+}
+
+/// 滑翔状态，挂在滑翔中的角色上
+#[derive(Component, Debug, Clone)]
+pub struct Gliding {
+    /// 当前高度
+    pub altitude: f32,
+    /// 当前下沉速度
+    pub sink_speed: f32,
+    /// 水平速度（像素/秒）
+    pub velocity: Vec2,
+    /// 起飞位置，用于统计滑翔距离
+    pub launched_from: Vec2,
+    /// 上一帧位置，撞上无法落脚的地形时退回这里
+    pub last_position: Vec2,
+}
+
+impl Gliding {
+    pub fn new(position: Vec2, altitude: f32, velocity: Vec2) -> Self {
+        Self {
+            altitude,
+            sink_speed: 0.0,
+            velocity,
+            launched_from: position,
+            last_position: position,
+        }
+    }
+}
+
+/// 着陆结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Landing {
+    /// 平稳落地
+    Safe,
+    /// 落入水中，不会摔伤
+    Splash,
+    /// 撞上无法落脚的地形，退回上一帧位置后跌落
+    Crash,
+}
+
+/// 根据着陆瓦片判定着陆结果
+///
+/// 水面总能接住角色；墙壁、山体等不可行走的瓦片无法落脚
+pub fn landing_for(tile: Option<TileType>) -> Landing {
+    match tile {
+        Some(TileType::Water) => Landing::Splash,
+        Some(tile) if !get_tile_physics(tile).walkable => Landing::Crash,
+        // 区块未加载时按平地处理
+        _ => Landing::Safe,
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::world::chunk::{ChunkData, CHUNK_SIZE};
+use crate::world::map::{SpatialIndex, TileType};
+
+/// 可以成为起飞点的山峰瓦片
+const PEAK_TILES: [TileType; 3] = [TileType::Mountain, TileType::Snow, TileType::Rock];
+
+/// 起飞点
+///
+/// 足够高、四周明显更低的山峰，站在这里展开滑翔翼会借到上升气流，
+/// 比从普通崖边起飞飞得更远，可作为翻山越岭的捷径
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LaunchPoint {
+    /// 峰顶高度
+    pub height: f32,
+    /// 峰顶与周围一圈的平均落差
+    pub prominence: f32,
+}
+
+/// 已发现的起飞点，按世界瓦片坐标索引
+#[derive(Resource, Debug, Default)]
+pub struct LaunchPoints {
+    points: SpatialIndex<LaunchPoint>,
+}
+
+impl LaunchPoints {
+    pub fn insert(&mut self, position: IVec2, point: LaunchPoint) {
+        self.points.replace(position, point);
+    }
+
+    /// 距离给定瓦片最近的起飞点
+    pub fn nearest(&self, tile: IVec2, radius: f32) -> Option<(IVec2, LaunchPoint)> {
+        self.points
+            .query_radius(tile, radius)
+            .into_iter()
+            .min_by_key(|(position, _)| position.distance_squared(tile))
+            .map(|(position, point)| (position, *point))
+    }
+}
+
+/// 在区块中找出起飞点，每个区块至多一个
+///
+/// # 设计思路
+/// 1. 只考虑山地、雪地、岩石瓦片，且高度不低于 `min_height`
+/// 2. 必须是 `ring` 格范围内的最高点，避免一道山脊上连出一串起飞点
+/// 3. 峰顶与外圈的平均落差不低于 `min_prominence`，平缓的高原不算
+/// 4. 区块边缘 `ring` 格内不取点，只读本区块数据，结果与区块加载顺序无关
+pub fn find_launch_point(
+    data: &ChunkData,
+    min_height: f32,
+    min_prominence: f32,
+    ring: usize,
+) -> Option<(UVec2, LaunchPoint)> {
+    let ring = ring.max(1);
+    if CHUNK_SIZE <= ring * 2 {
+        return None;
+    }
+    let mut best: Option<(UVec2, LaunchPoint)> = None;
+
+    for y in ring..CHUNK_SIZE - ring {
+        for x in ring..CHUNK_SIZE - ring {
+            let height = data.get_height(x, y);
+            if height < min_height {
+                continue;
+            }
+            let Some(tile) = data.get_tile(x, y).and_then(TileType::from_u8) else {
+                continue;
+            };
+            if !PEAK_TILES.contains(&tile) {
+                continue;
+            }
+
+            let mut highest = true;
+            let mut outer_sum = 0.0;
+            let mut outer_count = 0;
+            for ny in y - ring..=y + ring {
+                for nx in x - ring..=x + ring {
+                    if (nx, ny) == (x, y) {
+                        continue;
+                    }
+                    let neighbor = data.get_height(nx, ny);
+                    if neighbor > height {
+                        highest = false;
+                    }
+                    if nx.abs_diff(x) == ring || ny.abs_diff(y) == ring {
+                        outer_sum += neighbor;
+                        outer_count += 1;
+                    }
+                }
+            }
+            if !highest {
+                continue;
+            }
+
+            let prominence = height - outer_sum / outer_count.max(1) as f32;
+            if prominence < min_prominence {
+                continue;
+            }
+            if best.is_none_or(|(_, point)| height > point.height) {
+                best = Some((
+                    UVec2::new(x as u32, y as u32),
+                    LaunchPoint { height, prominence },
+                ));
+            }
+        }
+    }
+
+    best
+}
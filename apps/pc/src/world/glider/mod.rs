@@ -0,0 +1,19 @@
+/// 滑翔模块
+///
+/// 后期物品滑翔翼：从崖边或山峰起跳展开，借风滑行，消耗内力，按地形着陆
+///
+/// # 模块组成
+/// 1. wind：由气候配置驱动的风场
+/// 2. launch：山峰起飞点的判定与索引
+/// 3. glider：滑翔配置、滑翔状态与着陆规则
+/// 4. systems：滑翔插件，负责展开、飞行与着陆
+#[allow(clippy::module_inception)]
+mod glider;
+mod launch;
+mod systems;
+mod wind;
+
+pub use glider::*;
+pub use launch::*;
+pub use systems::*;
+pub use wind::*;
@@ -0,0 +1,327 @@
+use bevy::prelude::*;
+
+use super::{
+    find_launch_point, landing_for, weather_wind_multiplier, GliderSettings, Gliding, Landing,
+    LaunchPoints, WindField, GLIDER_ITEM_ID,
+};
+use crate::combat::DamageEvent;
+use crate::events::input::GameAction;
+use crate::items::{Encumbrance, EquipSlot, Equipment};
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::{gameplay_running, InputState};
+use crate::world::chunk::{Chunk, ChunkCoord, ChunkManager, CHUNK_SIZE, TILE_PIXELS};
+use crate::world::entity::{Character, CharacterState, Player};
+use crate::world::map::{MapManager, SceneType, TileType};
+use crate::world::poi::PoiRegistry;
+use crate::world::weather::WeatherState;
+
+/// 滑翔插件
+///
+/// # 设计思路
+/// 1. 装备滑翔翼后，在崖边朝落差方向起跳或站在峰顶起跳即可展开
+/// 2. 滑翔中按重力下沉，水平速度由操控方向与风场共同决定，持续消耗内力
+/// 3. 内力耗尽后失速加速下坠，高度低于地面时按着陆瓦片判定结果
+/// 4. 区块加载时找出高耸的山峰作为起飞点，同时登记为兴趣点供地图标记
+pub struct GliderPlugin;
+
+impl Plugin for GliderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GliderSettings>()
+            .init_resource::<WindField>()
+            .init_resource::<LaunchPoints>();
+
+        app.add_systems(Startup, setup_wind_field).add_systems(
+            Update,
+            (
+                register_launch_points,
+                deploy_glider,
+                update_gliding,
+                land_gliders,
+            )
+                .chain()
+                .run_if(gameplay_running),
+        );
+    }
+}
+
+/// 查询世界坐标所在瓦片的类型与高度，区块未加载时返回 None
+fn sample_tile(
+    position: Vec2,
+    chunk_manager: &ChunkManager,
+    chunks: &Query<&Chunk>,
+) -> Option<(TileType, f32)> {
+    let chunk_size = CHUNK_SIZE as i32;
+    let tile = (position / TILE_PIXELS).floor().as_ivec2();
+    let coord = ChunkCoord {
+        x: tile.x.div_euclid(chunk_size),
+        y: tile.y.div_euclid(chunk_size),
+    };
+
+    let data = chunk_manager
+        .get_chunk_entity(coord)
+        .and_then(|entity| chunks.get(entity).ok())
+        .and_then(|chunk| chunk.data.as_ref())?;
+
+    let local_x = tile.x.rem_euclid(chunk_size) as usize;
+    let local_y = tile.y.rem_euclid(chunk_size) as usize;
+    let tile = data
+        .get_tile(local_x, local_y)
+        .and_then(TileType::from_u8)?;
+    Some((tile, data.get_height(local_x, local_y)))
+}
+
+/// 当前的操控方向
+fn steer_direction(input_state: &InputState) -> Vec2 {
+    let mut direction = Vec2::ZERO;
+    if input_state.is_action_active(GameAction::MoveForward) {
+        direction.y += 1.0;
+    }
+    if input_state.is_action_active(GameAction::MoveBackward) {
+        direction.y -= 1.0;
+    }
+    if input_state.is_action_active(GameAction::MoveLeft) {
+        direction.x -= 1.0;
+    }
+    if input_state.is_action_active(GameAction::MoveRight) {
+        direction.x += 1.0;
+    }
+    direction.normalize_or_zero()
+}
+
+/// 按地图种子与气候配置建立风场
+fn setup_wind_field(mut wind: ResMut<WindField>, map_manager: Option<Res<MapManager>>) {
+    if let Some(map_manager) = map_manager {
+        *wind = WindField::from_climate(map_manager.climate_config(), map_manager.seed);
+    }
+}
+
+/// 区块加载时找出其中的起飞点
+fn register_launch_points(
+    settings: Res<GliderSettings>,
+    mut launch_points: ResMut<LaunchPoints>,
+    mut poi_registry: Option<ResMut<PoiRegistry>>,
+    chunks: Query<&Chunk, Added<Chunk>>,
+) {
+    for chunk in chunks.iter() {
+        let Some(data) = &chunk.data else {
+            continue;
+        };
+        let Some((local, point)) = find_launch_point(
+            data,
+            settings.peak_min_height,
+            settings.peak_min_prominence,
+            settings.peak_ring,
+        ) else {
+            continue;
+        };
+
+        let origin = IVec2::new(chunk.coord.x, chunk.coord.y) * CHUNK_SIZE as i32;
+        let position = origin + local.as_ivec2();
+        launch_points.insert(position, point);
+        if let Some(registry) = poi_registry.as_mut() {
+            registry.register(position, SceneType::Mountain, None);
+        }
+    }
+}
+
+/// 崖边或峰顶起跳时展开滑翔翼
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+fn deploy_glider(
+    mut commands: Commands,
+    settings: Res<GliderSettings>,
+    input_state: Res<InputState>,
+    launch_points: Res<LaunchPoints>,
+    chunk_manager: Res<ChunkManager>,
+    chunks: Query<&Chunk>,
+    mut players: Query<
+        (
+            Entity,
+            &mut Character,
+            &Transform,
+            &Equipment,
+            Option<&Encumbrance>,
+        ),
+        (With<Player>, Without<Gliding>),
+    >,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    // 按住轻功键时起跳是轻功纵跃，不展开滑翔翼
+    if !input_state.is_action_just_pressed(GameAction::Jump)
+        || input_state.is_action_active(GameAction::Qinggong)
+    {
+        return;
+    }
+    let Ok((entity, mut character, transform, equipment, encumbrance)) = players.get_single_mut()
+    else {
+        return;
+    };
+    if !character.can_move || !character.is_grounded || character.qi < settings.min_qi {
+        return;
+    }
+    let equipped = equipment
+        .slots
+        .get(&EquipSlot::Accessory)
+        .is_some_and(|item| item.item_id == GLIDER_ITEM_ID);
+    // 超重时施展不了轻功，也就飞不起来
+    if !equipped || !encumbrance.is_none_or(|e| e.can_use_qinggong()) {
+        return;
+    }
+
+    let position = transform.translation.truncate();
+    let Some((_, height)) = sample_tile(position, &chunk_manager, &chunks) else {
+        return;
+    };
+    let heading = match steer_direction(&input_state) {
+        Vec2::ZERO => character.direction.try_normalize().unwrap_or(Vec2::X),
+        direction => direction,
+    };
+
+    let tile = (position / TILE_PIXELS).floor().as_ivec2();
+    let peak = launch_points.nearest(tile, settings.launch_radius);
+    let lift = match peak {
+        Some(_) => settings.updraft_lift,
+        None => {
+            // 前方落差不够时只是普通起跳
+            let ahead = position + heading * settings.cliff_probe * TILE_PIXELS;
+            let drop = sample_tile(ahead, &chunk_manager, &chunks)
+                .map_or(0.0, |(_, ahead_height)| height - ahead_height);
+            if drop < settings.cliff_drop {
+                return;
+            }
+            0.0
+        }
+    };
+
+    commands.entity(entity).insert(Gliding::new(
+        position,
+        height + lift,
+        heading * settings.glide_speed,
+    ));
+    character.can_move = false;
+    character.is_grounded = false;
+    character.state = CharacterState::Jumping;
+
+    if let Some(logger) = logger.as_mut() {
+        logger.log(
+            LogLevel::Debug,
+            &format!(
+                "展开滑翔翼，高度 {:.2}{}",
+                height + lift,
+                if peak.is_some() {
+                    "（峰顶起飞）"
+                } else {
+                    ""
+                }
+            ),
+        );
+    }
+}
+
+/// 按重力、风场与操控推进滑翔
+fn update_gliding(
+    time: Res<Time>,
+    settings: Res<GliderSettings>,
+    input_state: Res<InputState>,
+    wind: Res<WindField>,
+    weather: Res<WeatherState>,
+    mut gliders: Query<(&mut Gliding, &mut Character, &mut Transform), With<Player>>,
+) {
+    let delta = time.delta_secs();
+    let steer = steer_direction(&input_state);
+    let gust = weather_wind_multiplier(weather.kind, weather.intensity) * settings.wind_push;
+
+    for (mut gliding, mut character, mut transform) in gliders.iter_mut() {
+        let position = transform.translation.truncate();
+        let wind_velocity = wind.sample(position / TILE_PIXELS, time.elapsed_secs_f64()) * gust;
+
+        // 不操控时保持原来的航向
+        let heading = match steer {
+            Vec2::ZERO => gliding.velocity.normalize_or_zero(),
+            steer => steer,
+        };
+        let target = heading * settings.glide_speed + wind_velocity;
+        let response = (settings.steer_response * delta).min(1.0);
+        gliding.velocity = gliding.velocity.lerp(target, response);
+
+        // 逆风操控更费力
+        let headwind = (-steer.dot(wind_velocity) / settings.wind_push.max(f32::EPSILON)).max(0.0);
+        let drain = settings.qi_drain * (1.0 + settings.headwind_drain * headwind);
+        character.qi = (character.qi - drain * delta).max(0.0);
+
+        let terminal = if character.qi > 0.0 {
+            settings.sink_rate
+        } else {
+            settings.stall_sink_rate
+        };
+        gliding.sink_speed = (gliding.sink_speed + settings.gravity * delta).min(terminal);
+        gliding.altitude -= gliding.sink_speed * delta;
+
+        gliding.last_position = position;
+        let movement = gliding.velocity * delta;
+        transform.translation.x += movement.x;
+        transform.translation.y += movement.y;
+        character.state = CharacterState::Falling;
+    }
+}
+
+/// 高度低于地面时着陆
+///
+/// 落地太猛会摔伤，撞上无法落脚的地形时退回上一帧位置并受伤，落水则安然无恙
+fn land_gliders(
+    mut commands: Commands,
+    settings: Res<GliderSettings>,
+    chunk_manager: Res<ChunkManager>,
+    chunks: Query<&Chunk>,
+    mut gliders: Query<(Entity, &Gliding, &mut Character, &mut Transform)>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for (entity, gliding, mut character, mut transform) in gliders.iter_mut() {
+        let position = transform.translation.truncate();
+        // 区块未加载时以最低高度为地面，保证总能落地，按生成器推算是否落在水面
+        let (tile, ground) = sample_tile(position, &chunk_manager, &chunks).map_or_else(
+            || {
+                let tile = (position / TILE_PIXELS).floor().as_ivec2();
+                let water = chunk_manager.has_water_at(tile);
+                (water.then_some(TileType::Water), 0.0)
+            },
+            |(tile, height)| (Some(tile), height),
+        );
+        if gliding.altitude > ground {
+            continue;
+        }
+
+        let landing = landing_for(tile);
+        let overspeed = (gliding.sink_speed - settings.hard_landing_speed).max(0.0);
+        let damage = match landing {
+            Landing::Safe => overspeed * settings.landing_damage,
+            Landing::Splash => 0.0,
+            Landing::Crash => (overspeed * settings.landing_damage).max(settings.crash_damage),
+        };
+        if landing == Landing::Crash {
+            transform.translation.x = gliding.last_position.x;
+            transform.translation.y = gliding.last_position.y;
+        }
+        if damage > 0.0 {
+            damage_events.send(DamageEvent::damage(entity, None, "坠落", damage));
+        }
+
+        commands.entity(entity).remove::<Gliding>();
+        character.is_grounded = true;
+        character.can_move = character.health > 0.0;
+        character.state = CharacterState::Idle;
+
+        let distance = transform
+            .translation
+            .truncate()
+            .distance(gliding.launched_from)
+            / TILE_PIXELS;
+        if let Some(logger) = logger.as_mut() {
+            logger.log(
+                LogLevel::Debug,
+                &format!("滑翔着陆 {:?}，滑行 {:.1} 格", landing, distance),
+            );
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use noise::{NoiseFn, Perlin};
+
+use crate::world::map::Climate;
+use crate::world::weather::WeatherKind;
+
+/// 风向最多偏离主导风向的角度（弧度）
+const MAX_DIRECTION_SWING: f64 = std::f64::consts::FRAC_PI_3;
+
+/// 风场
+///
+/// # 设计思路
+/// 1. 风力与风向由气候配置中的基准值、变化范围与噪声频率决定
+/// 2. 噪声同时在空间与时间上采样，阵风会随时间缓慢推移
+/// 3. 天气只放大或减弱风力，不改变风向
+#[derive(Resource, Debug, Clone)]
+pub struct WindField {
+    strength_noise: Perlin,
+    direction_noise: Perlin,
+    /// 风力基准值
+    pub base: f32,
+    /// 风力变化范围
+    pub range: f32,
+    /// 空间噪声频率
+    pub frequency: f32,
+    /// 主导风向（弧度）
+    pub direction: f32,
+    /// 阵风推移速度（每秒经过的噪声单位）
+    pub drift: f32,
+}
+
+impl Default for WindField {
+    fn default() -> Self {
+        Self::from_climate(&Climate::default(), 0)
+    }
+}
+
+impl WindField {
+    /// 按气候配置建立风场
+    pub fn from_climate(climate: &Climate, seed: u32) -> Self {
+        Self {
+            strength_noise: Perlin::new(seed.wrapping_add(11)),
+            direction_noise: Perlin::new(seed.wrapping_add(12)),
+            base: climate.base_wind,
+            range: climate.wind_range,
+            frequency: climate.wind_frequency,
+            direction: climate.wind_direction,
+            drift: 0.05,
+        }
+    }
+
+    /// 采样风速
+    ///
+    /// `tile` 为世界瓦片坐标，`seconds` 为游戏运行时间，
+    /// 返回值的方向为风向，长度为风力
+    pub fn sample(&self, tile: Vec2, seconds: f64) -> Vec2 {
+        let x = tile.x as f64 * self.frequency as f64;
+        let y = tile.y as f64 * self.frequency as f64;
+        let t = seconds * self.drift as f64;
+
+        // 噪声范围为 -1..1，风力取其正半部分叠加到基准值上
+        let gust = (self.strength_noise.get([x, y, t]) * 0.5 + 0.5) as f32;
+        let strength = (self.base + gust * self.range).max(0.0);
+        let swing = self.direction_noise.get([x + 100.0, y + 100.0, t]) * MAX_DIRECTION_SWING;
+        let angle = self.direction + swing as f32;
+
+        Vec2::from_angle(angle) * strength
+    }
+}
+
+/// 天气对风力的倍率
+pub fn weather_wind_multiplier(kind: WeatherKind, intensity: f32) -> f32 {
+    let factor = match kind {
+        WeatherKind::Clear => 0.0,
+        WeatherKind::Rain => 0.6,
+        WeatherKind::Snow => 0.4,
+        // 起雾时多半无风
+        WeatherKind::Fog => -0.5,
+    };
+    (1.0 + factor * intensity.clamp(0.0, 1.0)).max(0.1)
+}
@@ -0,0 +1,18 @@
+/// 采集模块
+///
+/// 草药、矿脉、竹笋等可采集资源点，按区块环境确定性生成，采集后经过一段游戏时间重新长出
+///
+/// # 模块组成
+/// 1. node：资源点定义与资源点数据库
+/// 2. placement：按区块瓦片、高度、气候与植被放置资源点
+/// 3. registry：已采集资源点的重生时间与存档
+/// 4. systems：采集插件，负责生成、交互与重生表现
+mod node;
+mod placement;
+mod registry;
+mod systems;
+
+pub use node::*;
+pub use placement::*;
+pub use registry::*;
+pub use systems::*;
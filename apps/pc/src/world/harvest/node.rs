@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::world::map::{TileType, VegetationType, Zone};
+
+/// 资源点数据文件路径
+pub const RESOURCE_NODE_DATA_PATH: &str = "src/config/resource_nodes.json";
+
+/// 资源点种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResourceNodeKind {
+    Herb,        // 草药
+    OreVein,     // 矿脉
+    BambooShoot, // 竹笋
+}
+
+impl ResourceNodeKind {
+    /// 资源点精灵的尺寸与颜色
+    pub fn visual(&self) -> (Vec2, Color) {
+        match self {
+            ResourceNodeKind::Herb => (Vec2::new(10.0, 12.0), Color::srgb(0.55, 0.8, 0.35)),
+            ResourceNodeKind::OreVein => (Vec2::new(20.0, 14.0), Color::srgb(0.55, 0.45, 0.4)),
+            ResourceNodeKind::BambooShoot => (Vec2::new(8.0, 12.0), Color::srgb(0.85, 0.75, 0.4)),
+        }
+    }
+}
+
+/// 资源点定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceNodeDef {
+    pub id: String,
+    pub name: String,
+    pub kind: ResourceNodeKind,
+    /// 采集得到的物品
+    pub item_id: String,
+    /// 每次采集的数量范围
+    #[serde(rename = "yield")]
+    pub yield_range: (u32, u32),
+    /// 采集后重新长出所需的游戏小时数
+    pub respawn_hours: f32,
+    /// 符合条件的瓦片上出现的概率
+    pub chance: f32,
+    /// 可出现的瓦片类型
+    pub tiles: Vec<TileType>,
+    /// 可出现的气候区域，为空时不限
+    #[serde(default)]
+    pub zones: Vec<Zone>,
+    /// 高度范围
+    #[serde(default = "default_height")]
+    pub height: (f32, f32),
+    /// 需要相邻的植被，为空时不限
+    #[serde(default)]
+    pub vegetation: Vec<VegetationType>,
+    /// 采集时额外抽取的掉落表
+    #[serde(default)]
+    pub loot_table: Option<String>,
+}
+
+fn default_height() -> (f32, f32) {
+    (0.0, 1.0)
+}
+
+impl ResourceNodeDef {
+    /// 瓦片环境是否适合该资源点，植被条件由调用方另行判断
+    pub fn suits(&self, tile: TileType, height: f32, zone: Option<Zone>) -> bool {
+        self.tiles.contains(&tile)
+            && height >= self.height.0
+            && height <= self.height.1
+            && (self.zones.is_empty() || zone.is_some_and(|zone| self.zones.contains(&zone)))
+    }
+}
+
+/// 资源点数据文件格式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ResourceNodeDataFile {
+    nodes: Vec<ResourceNodeDef>,
+}
+
+/// 资源点数据库
+///
+/// 定义的先后顺序参与确定性放置，调整顺序会改变已有世界中的资源分布
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ResourceNodeLibrary {
+    pub nodes: Vec<ResourceNodeDef>,
+}
+
+impl ResourceNodeLibrary {
+    /// 从数据文件加载
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let data: ResourceNodeDataFile = serde_json::from_str(&content)?;
+        Ok(Self { nodes: data.nodes })
+    }
+
+    /// 按ID查找定义
+    pub fn get(&self, id: &str) -> Option<&ResourceNodeDef> {
+        self.nodes.iter().find(|def| def.id == id)
+    }
+}
+
+/// 可采集组件
+#[derive(Component, Debug, Clone)]
+pub struct Harvestable {
+    /// 资源点位置，也是采集状态的存档键
+    pub key: NodeKey,
+    /// 资源点定义ID
+    pub node_id: String,
+}
+
+/// 资源点位置（世界瓦片坐标）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct NodeKey {
+    pub x: i32,
+    pub y: i32,
+}
@@ -0,0 +1,104 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::{NodeKey, ResourceNodeLibrary};
+use crate::world::chunk::{ChunkCoord, ChunkData, CHUNK_SIZE};
+use crate::world::map::{TileType, VegetationType};
+
+/// 放置在区块中的资源点
+#[derive(Debug, Clone, Copy)]
+pub struct PlacedNode {
+    pub key: NodeKey,
+    pub local_x: usize,
+    pub local_y: usize,
+    /// 资源点定义在数据库中的下标
+    pub def: usize,
+}
+
+/// 按区块数据确定性地放置资源点
+///
+/// # 设计思路
+/// 1. 每个瓦片以世界种子和世界坐标生成独立的随机数，结果与区块加载顺序无关
+/// 2. 一个瓦片只掷一次骰，各定义按出现概率累加分段，避免排在前面的定义挤占后面的
+/// 3. 已有植被的瓦片留给树木，需要植被的资源点出现在植被旁边
+pub fn place_resource_nodes(
+    library: &ResourceNodeLibrary,
+    coord: ChunkCoord,
+    data: &ChunkData,
+    seed: u64,
+) -> Vec<PlacedNode> {
+    let mut placed = Vec::new();
+
+    for y in 0..CHUNK_SIZE {
+        for x in 0..CHUNK_SIZE {
+            let Some(tile) = data.get_tile(x, y).and_then(TileType::from_u8) else {
+                continue;
+            };
+            if tile == TileType::Water || data.get_decoration(x, y).is_some() {
+                continue;
+            }
+
+            let key = NodeKey {
+                x: coord.x * CHUNK_SIZE as i32 + x as i32,
+                y: coord.y * CHUNK_SIZE as i32 + y as i32,
+            };
+            let roll = tile_rng(seed, key).gen::<f32>();
+            let height = data.get_height(x, y);
+            let zone = data.get_climate_zone(x, y);
+
+            let mut threshold = 0.0;
+            for (index, def) in library.nodes.iter().enumerate() {
+                if !def.suits(tile, height, zone) {
+                    continue;
+                }
+                if !def.vegetation.is_empty()
+                    && !has_adjacent_vegetation(data, x, y, &def.vegetation)
+                {
+                    continue;
+                }
+
+                threshold += def.chance;
+                if roll < threshold {
+                    placed.push(PlacedNode {
+                        key,
+                        local_x: x,
+                        local_y: y,
+                        def: index,
+                    });
+                    break;
+                }
+            }
+        }
+    }
+
+    placed
+}
+
+/// 周围八格内是否有指定植被，只检查本区块内的瓦片
+fn has_adjacent_vegetation(data: &ChunkData, x: usize, y: usize, kinds: &[VegetationType]) -> bool {
+    for dy in -1..=1i32 {
+        for dx in -1..=1i32 {
+            let (Some(nx), Some(ny)) = (
+                x.checked_add_signed(dx as isize),
+                y.checked_add_signed(dy as isize),
+            ) else {
+                continue;
+            };
+            let found = data
+                .get_decoration(nx, ny)
+                .and_then(VegetationType::from_u8)
+                .is_some_and(|kind| kinds.contains(&kind));
+            if found {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// 瓦片独立的随机数生成器
+fn tile_rng(seed: u64, key: NodeKey) -> ChaCha8Rng {
+    let hash = (key.x as i64 as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (key.y as i64 as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    ChaCha8Rng::seed_from_u64(seed ^ hash)
+}
@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::NodeKey;
+
+/// 采集状态存档路径
+pub const HARVEST_SAVE_PATH: &str = "saves/harvest.json";
+
+/// 已采集的资源点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DepletedNode {
+    x: i32,
+    y: i32,
+    /// 重新长出的时刻（历法总小时数）
+    respawn_at: f64,
+}
+
+/// 采集状态存档格式
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct HarvestSaveFile {
+    depleted: Vec<DepletedNode>,
+}
+
+/// 采集状态
+///
+/// 只记录尚未重生的资源点，资源点本身随区块确定性生成，不需要存档
+#[derive(Resource, Debug, Clone, Default)]
+pub struct HarvestRegistry {
+    depleted: HashMap<NodeKey, f64>,
+}
+
+impl HarvestRegistry {
+    /// 从存档读取
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let file: HarvestSaveFile = serde_json::from_str(&content)?;
+        Ok(Self {
+            depleted: file
+                .depleted
+                .into_iter()
+                .map(|node| {
+                    (
+                        NodeKey {
+                            x: node.x,
+                            y: node.y,
+                        },
+                        node.respawn_at,
+                    )
+                })
+                .collect(),
+        })
+    }
+
+    /// 写入存档
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = HarvestSaveFile {
+            depleted: self
+                .depleted
+                .iter()
+                .map(|(key, respawn_at)| DepletedNode {
+                    x: key.x,
+                    y: key.y,
+                    respawn_at: *respawn_at,
+                })
+                .collect(),
+        };
+        fs::write(path, serde_json::to_string_pretty(&file)?)?;
+        Ok(())
+    }
+
+    /// 资源点在给定时刻是否可以采集
+    pub fn is_available(&self, key: NodeKey, now: f64) -> bool {
+        self.depleted
+            .get(&key)
+            .is_none_or(|respawn_at| *respawn_at <= now)
+    }
+
+    /// 标记资源点已被采集
+    pub fn deplete(&mut self, key: NodeKey, respawn_at: f64) {
+        self.depleted.insert(key, respawn_at);
+    }
+
+    /// 移除已经重生的记录
+    pub fn prune(&mut self, now: f64) {
+        self.depleted.retain(|_, respawn_at| *respawn_at > now);
+    }
+}
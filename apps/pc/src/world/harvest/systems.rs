@@ -0,0 +1,298 @@
+use bevy::prelude::*;
+use rand::Rng;
+use std::path::Path;
+
+use super::{
+    place_resource_nodes, HarvestRegistry, Harvestable, ResourceNodeLibrary, HARVEST_SAVE_PATH,
+    RESOURCE_NODE_DATA_PATH,
+};
+use crate::config::resolve_data_path;
+use crate::housing::HousingEditMode;
+use crate::interaction::{Interacted, InteractionKind};
+use crate::items::{Inventory, ItemDatabase, ItemInstance, LootDropRequest, LootRollRequest};
+use crate::logging::{GameLogger, LogLevel};
+use crate::render::components::{LayerComponent, RenderLayer};
+use crate::render::sorting::YSort;
+use crate::resources::gameplay_running;
+use crate::time::GameCalendar;
+use crate::world::chunk::{Chunk, TILE_PIXELS};
+use crate::world::entity::Player;
+use crate::world::map::MapManager;
+
+/// 采集后资源点的透明度
+const DEPLETED_ALPHA: f32 = 0.25;
+
+/// 采集配置
+#[derive(Resource, Debug, Clone)]
+pub struct HarvestSettings {
+    /// 可交互距离
+    pub interact_range: f32,
+    /// 存档路径
+    pub save_path: String,
+}
+
+impl Default for HarvestSettings {
+    fn default() -> Self {
+        Self {
+            interact_range: 32.0,
+            save_path: HARVEST_SAVE_PATH.to_string(),
+        }
+    }
+}
+
+/// 请求采集指定的资源点，例如鼠标点选后走到跟前
+#[derive(Event, Debug, Clone, Copy)]
+pub struct HarvestRequested {
+    pub node: Entity,
+}
+
+/// 资源点的基础颜色，采集后据此变淡
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ResourceNodeVisual {
+    pub base_color: Color,
+}
+
+/// 采集插件
+pub struct HarvestPlugin;
+
+impl Plugin for HarvestPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HarvestSettings>()
+            .add_event::<HarvestRequested>();
+
+        app.add_systems(PreStartup, load_resource_node_library)
+            .add_systems(Startup, load_harvest_registry)
+            .add_systems(
+                Update,
+                (
+                    spawn_resource_nodes,
+                    harvest_resource_nodes,
+                    refresh_resource_nodes,
+                )
+                    .chain()
+                    .run_if(gameplay_running),
+            );
+    }
+}
+
+/// 加载资源点数据，失败时不生成任何资源点
+fn load_resource_node_library(mut commands: Commands, mut logger: Option<ResMut<GameLogger>>) {
+    let library = match ResourceNodeLibrary::load(&resolve_data_path(RESOURCE_NODE_DATA_PATH)) {
+        Ok(library) => library,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("资源点数据加载失败: {}", e));
+            }
+            ResourceNodeLibrary::default()
+        }
+    };
+    commands.insert_resource(library);
+}
+
+/// 读取采集存档，没有存档时所有资源点都可采集
+fn load_harvest_registry(
+    mut commands: Commands,
+    settings: Res<HarvestSettings>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let registry = if Path::new(&settings.save_path).exists() {
+        HarvestRegistry::load(&settings.save_path).unwrap_or_else(|e| {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("采集存档读取失败: {}", e));
+            }
+            HarvestRegistry::default()
+        })
+    } else {
+        HarvestRegistry::default()
+    };
+    commands.insert_resource(registry);
+}
+
+/// 新加载的区块生成资源点，作为区块的子实体随区块一起卸载
+fn spawn_resource_nodes(
+    mut commands: Commands,
+    library: Res<ResourceNodeLibrary>,
+    map_manager: Res<MapManager>,
+    calendar: Res<GameCalendar>,
+    registry: Res<HarvestRegistry>,
+    chunks: Query<(Entity, &Chunk), Added<Chunk>>,
+) {
+    let seed = (map_manager.seed as u64).wrapping_add(3);
+    let now = calendar.total_hours();
+
+    for (chunk_entity, chunk) in chunks.iter() {
+        let Some(data) = &chunk.data else {
+            continue;
+        };
+
+        for node in place_resource_nodes(&library, chunk.coord, data, seed) {
+            let def = &library.nodes[node.def];
+            let (size, base_color) = def.kind.visual();
+            let color = if registry.is_available(node.key, now) {
+                base_color
+            } else {
+                base_color.with_alpha(DEPLETED_ALPHA)
+            };
+
+            let entity = commands
+                .spawn((
+                    Harvestable {
+                        key: node.key,
+                        node_id: def.id.clone(),
+                    },
+                    ResourceNodeVisual { base_color },
+                    Name::new(format!("ResourceNode: {}", def.name)),
+                    Sprite {
+                        color,
+                        custom_size: Some(size),
+                        ..default()
+                    },
+                    Transform::from_xyz(
+                        node.local_x as f32 * TILE_PIXELS,
+                        node.local_y as f32 * TILE_PIXELS,
+                        0.0,
+                    ),
+                    LayerComponent {
+                        layer: RenderLayer::Decoration,
+                        sub_order: 0,
+                    },
+                    YSort {
+                        foot_offset: -size.y * 0.5,
+                        elevation: 0.0,
+                    },
+                ))
+                .id();
+            commands.entity(chunk_entity).add_child(entity);
+        }
+    }
+}
+
+/// 交互键作用到资源点或收到采集请求时采集，放入玩家背包并写入存档
+///
+/// 只采作用到或请求的资源点，仍要在可交互距离内
+#[allow(clippy::too_many_arguments)]
+fn harvest_resource_nodes(
+    mut interacted: EventReader<Interacted>,
+    mut requests: EventReader<HarvestRequested>,
+    settings: Res<HarvestSettings>,
+    edit_mode: Res<HousingEditMode>,
+    calendar: Res<GameCalendar>,
+    library: Res<ResourceNodeLibrary>,
+    database: Res<ItemDatabase>,
+    mut registry: ResMut<HarvestRegistry>,
+    mut players: Query<(&Transform, &mut Inventory), With<Player>>,
+    nodes: Query<(Entity, &Harvestable, &GlobalTransform)>,
+    mut loot_rolls: EventWriter<LootRollRequest>,
+    mut loot_drops: EventWriter<LootDropRequest>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let pressed = interacted
+        .read()
+        .filter(|event| event.kind == InteractionKind::Harvest)
+        .last()
+        .map(|event| event.entity);
+    let requested = requests
+        .read()
+        .last()
+        .map(|request| request.node)
+        .or(pressed);
+    let Some(requested) = requested else {
+        return;
+    };
+    if edit_mode.active {
+        return;
+    }
+    let Ok((transform, mut inventory)) = players.get_single_mut() else {
+        return;
+    };
+    let position = transform.translation.truncate();
+    let now = calendar.total_hours();
+
+    let nearest = nodes
+        .iter()
+        .filter(|(entity, node, _)| requested == *entity && registry.is_available(node.key, now))
+        .map(|(_, node, node_transform)| {
+            let node_position = node_transform.translation().truncate();
+            (node, node_position, node_position.distance(position))
+        })
+        .filter(|(_, _, distance)| *distance <= settings.interact_range)
+        .min_by(|a, b| a.2.total_cmp(&b.2));
+    let Some((node, node_position, _)) = nearest else {
+        return;
+    };
+    let Some(def) = library.get(&node.node_id) else {
+        return;
+    };
+
+    let (min, max) = def.yield_range;
+    let count = rand::thread_rng().gen_range(min.min(max)..=max.max(min));
+    let leftover = inventory.add(&database, ItemInstance::new(&database, &def.item_id, count));
+    let collected = count - leftover;
+
+    // 一件也放不下时保留资源点，等背包腾出空间再采
+    if collected == 0 {
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Info, &format!("背包已满，无法采集{}", def.name));
+        }
+        return;
+    }
+
+    registry.deplete(node.key, now + def.respawn_hours as f64);
+    registry.prune(now);
+    if let Err(e) = registry.save(&settings.save_path) {
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Error, &format!("采集存档写入失败: {}", e));
+        }
+    }
+
+    // 放不下的散落在脚边，资源点另有掉落表的额外掉落
+    if leftover > 0 {
+        loot_drops.send(LootDropRequest {
+            item: ItemInstance::new(&database, &def.item_id, leftover),
+            position,
+        });
+    }
+    if let Some(table_id) = &def.loot_table {
+        loot_rolls.send(LootRollRequest {
+            table_id: table_id.clone(),
+            position: node_position,
+            salt: now as u64,
+        });
+    }
+
+    if let Some(logger) = logger.as_mut() {
+        let item_name = database
+            .get(&def.item_id)
+            .map_or(def.item_id.as_str(), |item| item.name.as_str());
+        let message = if leftover > 0 {
+            format!(
+                "采集{}，获得{} x{}，背包已满，{}个散落在地",
+                def.name, item_name, collected, leftover
+            )
+        } else {
+            format!("采集{}，获得{} x{}", def.name, item_name, collected)
+        };
+        logger.log(LogLevel::Info, &message);
+    }
+}
+
+/// 按采集状态更新资源点的表现，已采集的变淡，到时间后恢复
+///
+/// 重生以历法时间计算，休息跳过的时间同样计入
+fn refresh_resource_nodes(
+    calendar: Res<GameCalendar>,
+    registry: Res<HarvestRegistry>,
+    mut nodes: Query<(&Harvestable, &ResourceNodeVisual, &mut Sprite)>,
+) {
+    let now = calendar.total_hours();
+    for (node, visual, mut sprite) in nodes.iter_mut() {
+        let color = if registry.is_available(node.key, now) {
+            visual.base_color
+        } else {
+            visual.base_color.with_alpha(DEPLETED_ALPHA)
+        };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
@@ -1,45 +1,720 @@
-/// 建筑功能
-#[derive(Debug, Clone)]
-pub enum BuildingType {
-    None,
+use bevy::math::IVec2;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::{
+    prefab::ScenePrefab,
+    scene::{FixedSceneRef, SceneType},
+    spatial::SpatialIndex,
+    terrain::TerrainGenerator,
+};
+use crate::world::entity::NpcType;
+use crate::world::map::{Decoration, TileType};
+
+/// 布局的最大半径（瓦片），查询区块时按这个距离向外寻找场景
+const MAX_LAYOUT_RADIUS: i32 = 18;
+
+/// 场景占地范围内允许的最大高度差，超过则视为地势不平不建场景
+const MAX_SITE_SPREAD: f32 = 0.15;
+
+/// 建筑功能，决定里面住的居民
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BuildingType {
     House,
-    Farm,
-    Mine,
+    Shop,
+    Smithy,
+}
+
+/// 结构瓦片
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StructureTile {
+    Wall,
+    Floor,
+    Door,
+    Path,
+}
+
+impl StructureTile {
+    /// 写入区块时对应的瓦片类型
+    pub fn tile_type(self) -> TileType {
+        match self {
+            StructureTile::Wall => TileType::Wall,
+            StructureTile::Floor => TileType::Floor,
+            StructureTile::Door => TileType::Door,
+            StructureTile::Path => TileType::Path,
+        }
+    }
+
+    /// 同一瓦片被多次写入时优先级高的保留，小路不会截断墙和门
+    fn priority(self) -> u8 {
+        match self {
+            StructureTile::Path => 0,
+            StructureTile::Floor => 1,
+            StructureTile::Wall => 2,
+            StructureTile::Door => 3,
+        }
+    }
+}
+
+/// 场景落点
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScenePlacement {
+    /// 场景类型
+    pub scene_type: SceneType,
+    /// 场景中心的世界瓦片坐标
+    pub origin: IVec2,
+    /// 布局随机种子
+    pub seed: u64,
 }
 
-/// 建筑状态
+/// 场景居民
 #[derive(Debug, Clone)]
-pub struct BuildingArea {
-    pub x: f32,
-    pub y: f32,
-    pub width: f32,
-    pub height: f32,
+pub struct ResidentSpawn {
+    /// 世界瓦片坐标
+    pub position: IVec2,
+    pub npc_type: NpcType,
+    pub name: String,
 }
 
-/// 建筑配置
+/// 场景触发区
+#[derive(Debug, Clone)]
+pub struct TriggerSpawn {
+    /// 世界瓦片坐标
+    pub position: IVec2,
+    /// 触发半径（瓦片）
+    pub radius: f32,
+    /// 触发器ID
+    pub id: String,
+}
+
+/// 场景结构布局
+///
+/// 所有坐标都是世界瓦片坐标，布局可以跨越多个区块，
+/// 每个区块生成时只取落在自己范围内的瓦片和居民
+#[derive(Debug, Clone)]
+pub struct StructureLayout {
+    /// 场景落点
+    pub placement: ScenePlacement,
+    /// 显示名称，预制场景使用预制中的名称
+    pub name: Option<String>,
+    /// 占用范围的最小角（含）
+    pub min: IVec2,
+    /// 占用范围的最大角（含）
+    pub max: IVec2,
+    /// 地基高度，结构覆盖的瓦片整平到这个高度
+    pub ground_height: f32,
+    /// 结构瓦片
+    pub tiles: HashMap<IVec2, StructureTile>,
+    /// 居民
+    pub residents: SpatialIndex<ResidentSpawn>,
+    /// 指定的装饰物，覆盖随机植被，每个瓦片一个
+    pub decorations: SpatialIndex<Decoration>,
+    /// 触发区
+    pub triggers: SpatialIndex<TriggerSpawn>,
+}
+
+impl StructureLayout {
+    fn new(placement: ScenePlacement, radius: i32, ground_height: f32) -> Self {
+        Self::with_bounds(
+            placement,
+            placement.origin - IVec2::splat(radius),
+            placement.origin + IVec2::splat(radius),
+            ground_height,
+        )
+    }
+
+    pub(super) fn with_bounds(
+        placement: ScenePlacement,
+        min: IVec2,
+        max: IVec2,
+        ground_height: f32,
+    ) -> Self {
+        Self {
+            placement,
+            name: None,
+            min,
+            max,
+            ground_height,
+            tiles: HashMap::new(),
+            residents: SpatialIndex::default(),
+            decorations: SpatialIndex::default(),
+            triggers: SpatialIndex::default(),
+        }
+    }
+
+    /// 布局是否与给定范围（含边界）相交
+    pub fn intersects(&self, min: IVec2, max: IVec2) -> bool {
+        self.min.x <= max.x && self.max.x >= min.x && self.min.y <= max.y && self.max.y >= min.y
+    }
+
+    /// 落在给定范围内的结构瓦片
+    pub fn tiles_in(
+        &self,
+        min: IVec2,
+        max: IVec2,
+    ) -> impl Iterator<Item = (IVec2, StructureTile)> + '_ {
+        self.tiles
+            .iter()
+            .filter(move |(pos, _)| contains(min, max, **pos))
+            .map(|(pos, tile)| (*pos, *tile))
+    }
+
+    /// 落在给定范围内的居民
+    pub fn residents_in(&self, min: IVec2, max: IVec2) -> impl Iterator<Item = &ResidentSpawn> {
+        self.residents
+            .query_rect(min, max)
+            .into_iter()
+            .map(|(_, resident)| resident)
+    }
+
+    /// 落在给定范围内的装饰物
+    pub fn decorations_in(
+        &self,
+        min: IVec2,
+        max: IVec2,
+    ) -> impl Iterator<Item = (IVec2, Decoration)> + '_ {
+        self.decorations
+            .query_rect(min, max)
+            .into_iter()
+            .map(|(pos, kind)| (pos, *kind))
+    }
+
+    /// 落在给定范围内的触发区
+    pub fn triggers_in(&self, min: IVec2, max: IVec2) -> impl Iterator<Item = &TriggerSpawn> {
+        self.triggers
+            .query_rect(min, max)
+            .into_iter()
+            .map(|(_, trigger)| trigger)
+    }
+
+    pub(super) fn set(&mut self, pos: IVec2, tile: StructureTile) {
+        let keep = self
+            .tiles
+            .get(&pos)
+            .is_some_and(|old| old.priority() > tile.priority());
+        if !keep {
+            self.tiles.insert(pos, tile);
+        }
+    }
+
+    /// 印上一栋建筑：外圈为墙，内部为地板，门开在墙上
+    fn stamp_building(&mut self, min: IVec2, size: IVec2, door: IVec2) {
+        let max = min + size - IVec2::ONE;
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let edge = x == min.x || x == max.x || y == min.y || y == max.y;
+                let tile = if edge {
+                    StructureTile::Wall
+                } else {
+                    StructureTile::Floor
+                };
+                self.set(IVec2::new(x, y), tile);
+            }
+        }
+        self.set(door, StructureTile::Door);
+    }
+
+    /// 先横后竖铺一条小路
+    fn stamp_path(&mut self, from: IVec2, to: IVec2) {
+        for x in from.x.min(to.x)..=from.x.max(to.x) {
+            self.set(IVec2::new(x, from.y), StructureTile::Path);
+        }
+        for y in from.y.min(to.y)..=from.y.max(to.y) {
+            self.set(IVec2::new(to.x, y), StructureTile::Path);
+        }
+    }
+
+    /// 以中心为原点围一圈院墙，城门处开门
+    fn stamp_enclosure(&mut self, half: i32, gates: &[IVec2]) {
+        let origin = self.placement.origin;
+        for i in -half..=half {
+            for pos in [
+                IVec2::new(i, -half),
+                IVec2::new(i, half),
+                IVec2::new(-half, i),
+                IVec2::new(half, i),
+            ] {
+                self.set(origin + pos, StructureTile::Wall);
+            }
+        }
+        for gate in gates {
+            self.set(origin + *gate, StructureTile::Door);
+        }
+    }
+
+    pub(super) fn add_resident(&mut self, position: IVec2, npc_type: NpcType, name: &str) {
+        self.residents.insert(
+            position,
+            ResidentSpawn {
+                position,
+                npc_type,
+                name: name.to_string(),
+            },
+        );
+    }
+}
+
+fn contains(min: IVec2, max: IVec2, pos: IVec2) -> bool {
+    pos.x >= min.x && pos.x <= max.x && pos.y >= min.y && pos.y <= max.y
+}
+
+/// 结构生成配置
+#[derive(Debug, Clone)]
+pub struct StructureConfig {
+    /// 选址网格边长（瓦片），每个网格最多一处场景
+    pub cell_size: i32,
+    /// 网格内出现场景的概率 (0.0-1.0)
+    pub density: f32,
+}
+
+impl Default for StructureConfig {
+    fn default() -> Self {
+        Self {
+            cell_size: 128,
+            density: 0.35,
+        }
+    }
+}
+
+/// 沿街布局的参数，村落和城镇共用
+struct StreetStyle {
+    radius: i32,
+    lot_width: (i32, i32),
+    lot_depth: (i32, i32),
+    fill_chance: f32,
+    walled: bool,
+}
+
+const VILLAGE_STYLE: StreetStyle = StreetStyle {
+    radius: 12,
+    lot_width: (5, 6),
+    lot_depth: (4, 5),
+    fill_chance: 0.7,
+    walled: false,
+};
+
+const TOWN_STYLE: StreetStyle = StreetStyle {
+    radius: 18,
+    lot_width: (5, 7),
+    lot_depth: (4, 6),
+    fill_chance: 0.85,
+    walled: true,
+};
+
+const TEMPLE_RADIUS: i32 = 10;
+
+/// 场景结构生成器
 ///
 /// # 设计思路
-/// 1. 定义建筑的外观和功能
-/// 2. 控制建筑的交互系统
-/// 3. 管理建筑的状态变化
+/// 1. 选址：世界按固定网格划分，每个网格用种子决定是否有场景、场景落在哪里
+/// 2. 定型：根据落点的地形决定村落、城镇或寺庙，地势不平的落点放弃
+/// 3. 布局：在世界坐标中生成墙、地板、门和小路，不受区块边界限制
+/// 4. 一致性：选址和布局只依赖种子与坐标，任意区块单独生成都得到相同的结构
+/// 5. 固定场景：地图规则指定的场景优先，可以是内置类型也可以引用预制场景，
+///    周围的随机场景让位
 #[derive(Debug, Clone)]
-pub struct Building {
-    /// 建筑类型
-    pub building_type: BuildingType,
-    /// 建筑占用区域
-    pub area: BuildingArea,
+pub struct StructureGenerator {
+    seed: u64,
+    config: StructureConfig,
+    /// 固定场景，按场景中心的世界瓦片坐标索引
+    fixed_scenes: SpatialIndex<FixedSceneRef>,
+    /// 固定场景中最大的半径，查询时按这个距离向外扩展
+    max_fixed_radius: i32,
+    /// 已加载的预制场景，键为预制ID
+    prefabs: HashMap<String, ScenePrefab>,
 }
 
-impl Default for Building {
+impl Default for StructureGenerator {
     fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl StructureGenerator {
+    /// 创建结构生成器
+    pub fn new(seed: u64) -> Self {
         Self {
-            building_type: BuildingType::None,
-            area: BuildingArea {
-                x: 0.0,
-                y: 0.0,
-                width: 1.0,
-                height: 1.0,
-            },
+            seed,
+            config: StructureConfig::default(),
+            fixed_scenes: SpatialIndex::default(),
+            max_fixed_radius: 0,
+            prefabs: HashMap::new(),
         }
     }
+
+    /// 设置固定场景
+    pub fn set_fixed_scenes(&mut self, fixed_scenes: HashMap<IVec2, FixedSceneRef>) {
+        self.fixed_scenes = fixed_scenes.into_iter().collect();
+        self.update_max_fixed_radius();
+    }
+
+    /// 设置可供固定场景引用的预制场景
+    pub fn set_prefabs(&mut self, prefabs: HashMap<String, ScenePrefab>) {
+        self.prefabs = prefabs;
+        self.update_max_fixed_radius();
+    }
+
+    /// 固定场景的半径，引用的预制场景尚未加载时为 0
+    fn fixed_radius(&self, scene: &FixedSceneRef) -> i32 {
+        match scene {
+            FixedSceneRef::Builtin(scene_type) => Self::radius(*scene_type),
+            FixedSceneRef::Prefab(id) => self.prefabs.get(id).map_or(0, ScenePrefab::radius),
+        }
+    }
+
+    fn update_max_fixed_radius(&mut self) {
+        self.max_fixed_radius = self
+            .fixed_scenes
+            .iter()
+            .map(|(_, scene)| self.fixed_radius(scene))
+            .max()
+            .unwrap_or(0);
+    }
+
+    /// 与给定范围（世界瓦片坐标，含边界）相交的结构布局
+    pub fn layouts_in(
+        &self,
+        min: IVec2,
+        max: IVec2,
+        terrain: &TerrainGenerator,
+    ) -> Vec<StructureLayout> {
+        let cell_size = self.config.cell_size.max(1);
+        let min_cell = (min - IVec2::splat(MAX_LAYOUT_RADIUS)).div_euclid(IVec2::splat(cell_size));
+        let max_cell = (max + IVec2::splat(MAX_LAYOUT_RADIUS)).div_euclid(IVec2::splat(cell_size));
+
+        let reach = IVec2::splat(self.max_fixed_radius);
+        let mut layouts: Vec<StructureLayout> = self
+            .fixed_scenes
+            .query_rect(min - reach, max + reach)
+            .into_iter()
+            .filter_map(|(origin, scene)| self.fixed_layout(origin, scene, terrain))
+            .filter(|layout| layout.intersects(min, max))
+            .collect();
+
+        for cell_y in min_cell.y..=max_cell.y {
+            for cell_x in min_cell.x..=max_cell.x {
+                let Some(placement) = self.placement_in_cell(IVec2::new(cell_x, cell_y), terrain)
+                else {
+                    continue;
+                };
+                if self.near_fixed_scene(placement.origin) {
+                    continue;
+                }
+                let layout = self.layout(&placement, terrain);
+                if layout.intersects(min, max) {
+                    layouts.push(layout);
+                }
+            }
+        }
+        layouts
+    }
+
+    /// 固定场景的布局，引用的预制场景尚未加载时返回 None
+    fn fixed_layout(
+        &self,
+        origin: IVec2,
+        scene: &FixedSceneRef,
+        terrain: &TerrainGenerator,
+    ) -> Option<StructureLayout> {
+        match scene {
+            FixedSceneRef::Builtin(scene_type) => {
+                let placement = ScenePlacement {
+                    scene_type: *scene_type,
+                    origin,
+                    seed: self.cell_rng(origin).gen(),
+                };
+                Some(self.layout(&placement, terrain))
+            }
+            FixedSceneRef::Prefab(id) => {
+                let prefab = self.prefabs.get(id)?;
+                let ground_height = terrain.generate_height(origin.x as f64, origin.y as f64);
+                Some(prefab.layout(origin, ground_height))
+            }
+        }
+    }
+
+    /// 随机场景是否离固定场景太近
+    fn near_fixed_scene(&self, origin: IVec2) -> bool {
+        let reach = IVec2::splat(self.max_fixed_radius + MAX_LAYOUT_RADIUS + 2);
+        self.fixed_scenes
+            .query_rect(origin - reach, origin + reach)
+            .into_iter()
+            .any(|(fixed, scene)| {
+                let distance = (fixed - origin).abs().max_element();
+                distance <= self.fixed_radius(scene) + MAX_LAYOUT_RADIUS + 2
+            })
+    }
+
+    /// 确定一个选址网格中的场景落点
+    pub fn placement_in_cell(
+        &self,
+        cell: IVec2,
+        terrain: &TerrainGenerator,
+    ) -> Option<ScenePlacement> {
+        let cell_size = self.config.cell_size.max(1);
+        let mut rng = self.cell_rng(cell);
+        if rng.gen::<f32>() >= self.config.density {
+            return None;
+        }
+
+        // 落点离网格边缘留出最大半径，相邻网格的场景不会重叠
+        let margin = MAX_LAYOUT_RADIUS.min(cell_size / 2);
+        let span = (cell_size - margin * 2).max(1);
+        let origin = cell * cell_size
+            + IVec2::new(
+                margin + rng.gen_range(0..span),
+                margin + rng.gen_range(0..span),
+            );
+
+        let scene_type = Self::scene_for_site(origin, terrain, &mut rng)?;
+        Some(ScenePlacement {
+            scene_type,
+            origin,
+            seed: rng.gen(),
+        })
+    }
+
+    /// 根据落点地形决定场景类型
+    fn scene_for_site(
+        origin: IVec2,
+        terrain: &TerrainGenerator,
+        rng: &mut ChaCha8Rng,
+    ) -> Option<SceneType> {
+        let (x, y) = (origin.x as f64, origin.y as f64);
+        let height = terrain.generate_height(x, y);
+        let water_level = terrain.config().water_level;
+        let relative = height - water_level;
+        let tile = TileType::from_u8(terrain.determine_tile_type(height, x, y))?;
+
+        let scene_type = match tile {
+            TileType::Grass | TileType::Plains if relative < 0.2 && rng.gen::<f32>() < 0.35 => {
+                SceneType::Town
+            }
+            TileType::Grass | TileType::Plains => SceneType::Village,
+            TileType::Forest | TileType::DenseForest | TileType::Bamboo | TileType::Mountain
+                if relative > 0.45 =>
+            {
+                SceneType::Temple
+            }
+            _ => return None,
+        };
+
+        // 占地四角和四边中点都要在岸上且高差不大
+        let radius = Self::radius(scene_type);
+        let mut lowest = height;
+        let mut highest = height;
+        for dy in [-radius, 0, radius] {
+            for dx in [-radius, 0, radius] {
+                let sample =
+                    terrain.generate_height((origin.x + dx) as f64, (origin.y + dy) as f64);
+                lowest = lowest.min(sample);
+                highest = highest.max(sample);
+            }
+        }
+        if lowest < water_level + 0.05 || highest - lowest > MAX_SITE_SPREAD {
+            return None;
+        }
+
+        Some(scene_type)
+    }
+
+    /// 场景类型的布局半径
+    fn radius(scene_type: SceneType) -> i32 {
+        match scene_type {
+            SceneType::Village => VILLAGE_STYLE.radius,
+            SceneType::Town => TOWN_STYLE.radius,
+            SceneType::Temple => TEMPLE_RADIUS,
+            _ => 0,
+        }
+    }
+
+    /// 为场景落点生成结构布局
+    pub fn layout(
+        &self,
+        placement: &ScenePlacement,
+        terrain: &TerrainGenerator,
+    ) -> StructureLayout {
+        let ground_height =
+            terrain.generate_height(placement.origin.x as f64, placement.origin.y as f64);
+        let mut rng = ChaCha8Rng::seed_from_u64(placement.seed);
+
+        match placement.scene_type {
+            SceneType::Village => street_layout(placement, &VILLAGE_STYLE, ground_height, &mut rng),
+            SceneType::Town => street_layout(placement, &TOWN_STYLE, ground_height, &mut rng),
+            SceneType::Temple => temple_layout(placement, ground_height, &mut rng),
+            _ => StructureLayout::new(*placement, 0, ground_height),
+        }
+    }
+
+    fn cell_rng(&self, cell: IVec2) -> ChaCha8Rng {
+        let hash = (cell.x as i64 as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+            ^ (cell.y as i64 as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+        ChaCha8Rng::seed_from_u64(self.seed ^ hash)
+    }
+}
+
+/// 沿街布局
+///
+/// 主街东西向穿过中心，城镇再加一条南北向的街，房屋临街排列、门朝街开。
+/// 坐标先在 (u, v) 街道坐标中计算：u 沿街，v 垂直于街，再转换到世界坐标
+fn street_layout(
+    placement: &ScenePlacement,
+    style: &StreetStyle,
+    ground_height: f32,
+    rng: &mut ChaCha8Rng,
+) -> StructureLayout {
+    let radius = style.radius;
+    let mut layout = StructureLayout::new(*placement, radius, ground_height);
+    let origin = placement.origin;
+
+    // 围墙占最外圈，街道和房屋都在墙内
+    let inner = if style.walled { radius - 1 } else { radius };
+    let cross_street = style.walled;
+
+    let to_world = |along_x: bool, u: i32, v: i32| {
+        if along_x {
+            origin + IVec2::new(u, v)
+        } else {
+            origin + IVec2::new(v, u)
+        }
+    };
+
+    let streets: &[bool] = if cross_street {
+        &[true, false]
+    } else {
+        &[true]
+    };
+    for &along_x in streets {
+        layout.stamp_path(to_world(along_x, -inner, 0), to_world(along_x, inner, 0));
+    }
+
+    let (depth_min, depth_max) = style.lot_depth;
+    let depth_max = depth_max.min(inner - 2);
+    for &along_x in streets {
+        // 主街地块让开南北街；南北街只在主街两侧房屋之外布置地块，避免重叠
+        let reserved = match (along_x, cross_street) {
+            (true, false) => None,
+            (true, true) => Some(1),
+            (false, _) => Some(depth_max + 2),
+        };
+
+        for side in [1, -1] {
+            let mut u = -inner + 1;
+            while u < inner {
+                let width = rng.gen_range(style.lot_width.0..=style.lot_width.1);
+                let lot_end = u + width - 1;
+                if lot_end >= inner {
+                    break;
+                }
+                if let Some(reserved) = reserved {
+                    if u <= reserved && lot_end >= -reserved {
+                        u = reserved + 1;
+                        continue;
+                    }
+                }
+
+                if rng.gen::<f32>() < style.fill_chance && depth_min <= depth_max {
+                    let depth = rng.gen_range(depth_min..=depth_max);
+                    let near = 2 * side;
+                    let far = near + (depth - 1) * side;
+                    let door_u = rng.gen_range(u + 1..lot_end);
+
+                    let corner_a = to_world(along_x, u, near);
+                    let corner_b = to_world(along_x, lot_end, far);
+                    let min = corner_a.min(corner_b);
+                    let size = (corner_a - corner_b).abs() + IVec2::ONE;
+                    let door = to_world(along_x, door_u, near);
+                    let building_type = pick_building(placement.scene_type, rng);
+
+                    layout.stamp_building(min, size, door);
+                    layout.set(to_world(along_x, door_u, side), StructureTile::Path);
+
+                    let center = to_world(along_x, (u + lot_end) / 2, near + (depth / 2) * side);
+                    let (npc_type, name) = building_resident(building_type);
+                    layout.add_resident(center, npc_type, name);
+                }
+
+                u = lot_end + 2;
+            }
+        }
+    }
+
+    if style.walled {
+        let gates = [
+            IVec2::new(-radius, 0),
+            IVec2::new(radius, 0),
+            IVec2::new(0, -radius),
+            IVec2::new(0, radius),
+        ];
+        layout.stamp_enclosure(radius, &gates);
+        for gate in gates {
+            // 卫兵站在城门内侧
+            let inward = -gate.signum();
+            layout.add_resident(origin + gate + inward, NpcType::Guard, "守城卫兵");
+        }
+    }
+
+    layout
+}
+
+/// 寺庙布局：院墙围合，南面开山门，大殿居北，两侧配殿
+fn temple_layout(
+    placement: &ScenePlacement,
+    ground_height: f32,
+    rng: &mut ChaCha8Rng,
+) -> StructureLayout {
+    let radius = TEMPLE_RADIUS;
+    let mut layout = StructureLayout::new(*placement, radius, ground_height);
+    let origin = placement.origin;
+
+    let gate = IVec2::new(0, -radius);
+    layout.stamp_enclosure(radius, &[gate]);
+
+    // 大殿
+    let hall_min = origin + IVec2::new(-4, 1);
+    let hall_door = origin + IVec2::new(0, 1);
+    layout.stamp_building(hall_min, IVec2::new(9, 7), hall_door);
+    layout.stamp_path(origin + gate + IVec2::Y, hall_door - IVec2::Y);
+    layout.add_resident(origin + IVec2::new(0, 4), NpcType::Villager, "住持");
+
+    // 配殿门朝中轴开
+    for side in [-1, 1] {
+        let top = rng.gen_range(-5..=-3);
+        let outer_x = side * (radius - 1);
+        let inner_x = side * (radius - 4);
+        let min = origin + IVec2::new(outer_x.min(inner_x), top - 3);
+        let door = origin + IVec2::new(inner_x, top - 1);
+        layout.stamp_building(min, IVec2::new(4, 4), door);
+        layout.stamp_path(door - IVec2::new(side, 0), origin + IVec2::new(0, top - 1));
+        layout.add_resident(door + IVec2::new(side, 0), NpcType::Villager, "僧人");
+    }
+
+    for side in [-1, 1] {
+        layout.add_resident(origin + gate + IVec2::new(side, 1), NpcType::Guard, "武僧");
+    }
+
+    layout
+}
+
+/// 按场景类型挑选建筑功能
+fn pick_building(scene_type: SceneType, rng: &mut ChaCha8Rng) -> BuildingType {
+    let roll = rng.gen::<f32>();
+    match scene_type {
+        SceneType::Town if roll < 0.2 => BuildingType::Shop,
+        SceneType::Town if roll < 0.35 => BuildingType::Smithy,
+        SceneType::Village if roll < 0.1 => BuildingType::Shop,
+        _ => BuildingType::House,
+    }
+}
+
+/// 建筑里的居民
+fn building_resident(building_type: BuildingType) -> (NpcType, &'static str) {
+    match building_type {
+        BuildingType::Shop => (NpcType::Merchant, "掌柜"),
+        BuildingType::Smithy => (NpcType::Blacksmith, "铁匠"),
+        _ => (NpcType::Villager, "村民"),
+    }
 }
@@ -1,13 +1,11 @@
-mod area;
 mod building;
+mod prefab;
 mod scene;
 mod spatial;
-mod special;
 mod terrain;
 
-pub use area::*;
 pub use building::*;
+pub use prefab::*;
 pub use scene::*;
 pub use spatial::*;
-pub use special::*;
 pub use terrain::*;
@@ -0,0 +1,285 @@
+use bevy::asset::{io::Reader, Asset, AssetId, AssetLoader, Handle, LoadContext, LoadedFolder};
+use bevy::ecs::{component::Component, entity::Entity, event::Event, system::Resource};
+use bevy::math::IVec2;
+use bevy::reflect::TypePath;
+use serde::Deserialize;
+use std::collections::HashMap;
+use thiserror::Error;
+
+use super::building::{ScenePlacement, StructureLayout, StructureTile, TriggerSpawn};
+use super::scene::SceneType;
+use super::spatial::SpatialIndex;
+use crate::world::entity::NpcType;
+use crate::world::map::Decoration;
+
+/// 预制场景所在的资源目录（相对于 assets）
+pub const SCENE_PREFAB_FOLDER: &str = "prefabs";
+
+/// 预制场景中的装饰物，植被或摆件
+#[derive(Debug, Clone, Deserialize)]
+pub struct PrefabDecoration {
+    pub x: i32,
+    pub y: i32,
+    pub kind: Decoration,
+}
+
+/// 预制场景中的NPC
+#[derive(Debug, Clone, Deserialize)]
+pub struct PrefabNpc {
+    pub x: i32,
+    pub y: i32,
+    pub npc_type: NpcType,
+    pub name: String,
+}
+
+/// 预制场景中的触发区
+#[derive(Debug, Clone, Deserialize)]
+pub struct PrefabTrigger {
+    pub x: i32,
+    pub y: i32,
+    /// 触发半径（瓦片）
+    #[serde(default = "default_trigger_radius")]
+    pub radius: f32,
+    /// 触发器ID，进入时随事件发出
+    pub id: String,
+}
+
+fn default_trigger_radius() -> f32 {
+    2.0
+}
+
+/// 默认的布局字符
+fn default_legend() -> HashMap<char, StructureTile> {
+    HashMap::from([
+        ('#', StructureTile::Wall),
+        ('.', StructureTile::Floor),
+        ('+', StructureTile::Door),
+        ('=', StructureTile::Path),
+    ])
+}
+
+/// 预制场景
+///
+/// # 设计思路
+/// 1. 数据驱动：放在 assets/prefabs 下的 `*.prefab.json`，新增寺庙、村落不需要重新编译
+/// 2. 所见即所得：瓦片布局用字符画描述，第一行在最北边，字符含义由图例决定
+/// 3. 统一出口：转换成与程序化生成相同的结构布局，区块按同一流程印入
+///
+/// 布局、装饰物、NPC和触发区的坐标都是字符画中的 (列, 行)
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct ScenePrefab {
+    /// 预制ID，固定场景通过它引用
+    pub id: String,
+    /// 显示名称
+    pub name: String,
+    /// 场景类型
+    pub scene_type: SceneType,
+    /// 对准场景落点的格子 [列, 行]，默认为布局中心
+    #[serde(default)]
+    pub anchor: Option<[i32; 2]>,
+    /// 瓦片布局
+    pub layout: Vec<String>,
+    /// 布局字符到结构瓦片的映射，未列出的字符（如空格）保留原地形
+    #[serde(default = "default_legend")]
+    pub legend: HashMap<char, StructureTile>,
+    /// 装饰物
+    #[serde(default)]
+    pub decorations: Vec<PrefabDecoration>,
+    /// NPC
+    #[serde(default)]
+    pub npcs: Vec<PrefabNpc>,
+    /// 触发区
+    #[serde(default)]
+    pub triggers: Vec<PrefabTrigger>,
+}
+
+impl ScenePrefab {
+    /// 布局尺寸（列数, 行数）
+    pub fn size(&self) -> IVec2 {
+        let width = self
+            .layout
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        IVec2::new(width as i32, self.layout.len() as i32)
+    }
+
+    /// 对准场景落点的格子
+    pub fn anchor(&self) -> IVec2 {
+        self.anchor
+            .map(IVec2::from_array)
+            .unwrap_or_else(|| self.size() / 2)
+    }
+
+    /// 从落点到布局最远边缘的距离（瓦片）
+    pub fn radius(&self) -> i32 {
+        let size = self.size();
+        let anchor = self.anchor();
+        anchor.max(size - IVec2::ONE - anchor).max_element().max(0)
+    }
+
+    /// 把布局格子换算成世界瓦片坐标，行号向下增长而世界 y 向北增长
+    fn to_world(&self, origin: IVec2, x: i32, y: i32) -> IVec2 {
+        let anchor = self.anchor();
+        origin + IVec2::new(x - anchor.x, anchor.y - y)
+    }
+
+    /// 以 origin 为落点生成结构布局
+    pub fn layout(&self, origin: IVec2, ground_height: f32) -> StructureLayout {
+        let size = self.size();
+        let corner_a = self.to_world(origin, 0, 0);
+        let corner_b = self.to_world(origin, size.x - 1, size.y - 1);
+        let placement = ScenePlacement {
+            scene_type: self.scene_type,
+            origin,
+            seed: 0,
+        };
+        let mut layout = StructureLayout::with_bounds(
+            placement,
+            corner_a.min(corner_b),
+            corner_a.max(corner_b),
+            ground_height,
+        );
+        layout.name = Some(self.name.clone());
+
+        for (row, line) in self.layout.iter().enumerate() {
+            for (column, symbol) in line.chars().enumerate() {
+                if let Some(tile) = self.legend.get(&symbol) {
+                    layout.set(self.to_world(origin, column as i32, row as i32), *tile);
+                }
+            }
+        }
+
+        for decoration in &self.decorations {
+            let position = self.to_world(origin, decoration.x, decoration.y);
+            layout.decorations.replace(position, decoration.kind);
+        }
+
+        for npc in &self.npcs {
+            let position = self.to_world(origin, npc.x, npc.y);
+            layout.add_resident(position, npc.npc_type, &npc.name);
+        }
+
+        for trigger in &self.triggers {
+            let position = self.to_world(origin, trigger.x, trigger.y);
+            layout.triggers.insert(
+                position,
+                TriggerSpawn {
+                    position,
+                    radius: trigger.radius,
+                    id: trigger.id.clone(),
+                },
+            );
+        }
+
+        layout
+    }
+}
+
+/// 预制场景加载错误
+#[derive(Debug, Error)]
+pub enum ScenePrefabLoaderError {
+    #[error("读取预制场景失败: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("解析预制场景失败: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// 预制场景加载器
+#[derive(Default)]
+pub struct ScenePrefabLoader;
+
+impl AssetLoader for ScenePrefabLoader {
+    type Asset = ScenePrefab;
+    type Settings = ();
+    type Error = ScenePrefabLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<ScenePrefab, ScenePrefabLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["prefab.json"]
+    }
+}
+
+/// 预制场景注册表
+///
+/// 按预制ID索引已加载的预制场景。资源热重载时随资源事件更新，
+/// 只影响之后生成的区块
+#[derive(Resource, Default)]
+pub struct ScenePrefabRegistry {
+    /// 预制目录句柄，保持目录中的资源不被卸载
+    pub folder: Option<Handle<LoadedFolder>>,
+    /// 资源ID到预制ID的映射，用于处理修改和移除
+    pub ids: HashMap<AssetId<ScenePrefab>, String>,
+    /// 已加载的预制场景
+    pub prefabs: HashMap<String, ScenePrefab>,
+    /// 预制目录是否已加载完毕（失败也算完毕）
+    pub ready: bool,
+}
+
+impl ScenePrefabRegistry {
+    /// 按预制ID查找
+    pub fn get(&self, id: &str) -> Option<&ScenePrefab> {
+        self.prefabs.get(id)
+    }
+}
+
+/// 场景触发区组件，随区块生成
+#[derive(Component, Debug, Clone)]
+pub struct SceneTrigger {
+    pub id: String,
+    /// 触发半径（像素）
+    pub radius: f32,
+    /// 玩家当前是否在区域内，只在进入时触发一次
+    pub inside: bool,
+}
+
+/// 场景触发区索引
+///
+/// 触发区随区块生成与卸载，按所在瓦片建立空间索引，
+/// 每帧只检查玩家附近的触发区
+#[derive(Resource, Debug, Default)]
+pub struct SceneTriggerIndex {
+    /// 触发区实体，按所在世界瓦片坐标索引
+    pub index: SpatialIndex<Entity>,
+    /// 触发区实体所在的瓦片，用于移除
+    pub tiles: HashMap<Entity, IVec2>,
+    /// 已索引触发区中最大的半径（像素）
+    pub max_radius: f32,
+    /// 玩家当前所在的触发区
+    pub occupied: Vec<Entity>,
+}
+
+impl SceneTriggerIndex {
+    /// 加入或移动触发区
+    pub fn insert(&mut self, entity: Entity, tile: IVec2, radius: f32) {
+        self.remove(entity);
+        self.index.insert(tile, entity);
+        self.tiles.insert(entity, tile);
+        self.max_radius = self.max_radius.max(radius);
+    }
+
+    /// 移除触发区
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some(tile) = self.tiles.remove(&entity) {
+            self.index.remove_where(tile, |indexed| *indexed == entity);
+        }
+        self.occupied.retain(|occupied| *occupied != entity);
+    }
+}
+
+/// 玩家进入场景触发区事件
+#[derive(Event, Debug, Clone)]
+pub struct SceneTriggerEntered {
+    pub id: String,
+}
@@ -1,12 +1,12 @@
-use crate::world::map::EnvironmentRequirements;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
 
-use super::super::{assets::AssetItem, npc::Npc, quest::QuestTrigger};
-use super::{building::Building, terrain::TerrainCompatibility};
-use bevy::math::{IVec2, Rect};
-use std::collections::HashMap;
+/// 固定场景配置文件路径
+pub const FIXED_SCENES_PATH: &str = "src/config/fixed_scenes.json";
 
 /// 场景类型枚举
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SceneType {
     Village,     // 村落
     Town,        // 城镇
@@ -21,59 +21,44 @@ pub enum SceneType {
     SecretRealm, // 秘境
 }
 
-/// 固定场景结构
-#[derive(Debug, Clone)]
-pub struct FixedScene {
-    /// 场景类型
-    pub scene_type: SceneType,
-    /// 占用区域
-    pub bounds: Rect,
-    /// NPC配置
-    pub npcs: Vec<Npc>,
-    /// 建筑配置
-    pub buildings: Vec<Building>,
-    /// 特殊物品
-    pub items: Vec<AssetItem>,
-    /// 任务触发器
-    pub quest_triggers: Vec<QuestTrigger>,
+/// 固定场景引用
+///
+/// 固定场景既可以使用内置的场景类型按规则生成，
+/// 也可以引用预制场景的ID，按设计好的布局原样摆放
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixedSceneRef {
+    /// 内置场景类型
+    Builtin(SceneType),
+    /// 预制场景ID
+    Prefab(String),
 }
 
-/// 场景分布规则
-#[derive(Debug, Clone)]
-pub struct SceneRules {
-    /// 场景密度 (0.0-1.0)
-    pub density: f32,
-
-    /// 最小场景间距
-    pub min_distance: f32,
-
-    /// 场景类型权重
-    pub type_weights: HashMap<SceneType, f32>,
+impl From<SceneType> for FixedSceneRef {
+    fn from(scene_type: SceneType) -> Self {
+        FixedSceneRef::Builtin(scene_type)
+    }
+}
 
-    /// 场景环境要求
-    pub environment_requirements: HashMap<SceneType, EnvironmentRequirements>,
+/// 固定场景配置项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixedSceneEntry {
+    /// 场景中心的世界瓦片坐标
+    pub position: [i32; 2],
+    /// 场景内容
+    pub scene: FixedSceneRef,
 }
 
-impl Default for SceneRules {
-    fn default() -> Self {
-        Self {
-            density: 0.5,
-            min_distance: 10.0,
-            type_weights: HashMap::new(),
-            environment_requirements: HashMap::new(),
-        }
-    }
+/// 固定场景配置表
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FixedSceneTable {
+    pub scenes: Vec<FixedSceneEntry>,
 }
 
-/// 场景分布控制
-#[derive(Debug, Clone)]
-pub struct SceneDistribution {
-    /// 场景密度参数
-    pub density: f32,
-    /// 最小场景间距
-    pub min_distance: f32,
-    /// 场景类型权重
-    pub type_weights: HashMap<SceneType, f32>,
-    /// 地形适应性规则
-    pub terrain_compatibility: HashMap<SceneType, TerrainCompatibility>,
+impl FixedSceneTable {
+    /// 从 JSON 文件加载固定场景配置
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
 }
@@ -1,75 +1,187 @@
-use rand_chacha::{rand_core::SeedableRng, ChaChaRng};
+use bevy::math::IVec2;
+use std::collections::HashMap;
 
-/// 生成一个以位置为种子的随机数生成器
-pub fn make_rng_from_position(x: i32, y: i32, seed: u64) -> ChaChaRng {
-    // 通过位置和种子生成一个新的种子
-    let combined_seed = seed
-        .wrapping_add(x as u64)
-        .wrapping_mul(31)
-        .wrapping_add(y as u64);
-
-    ChaChaRng::seed_from_u64(combined_seed)
+/// 检查点是否在矩形内
+pub fn point_in_rect(x: i32, y: i32, rect_x: i32, rect_y: i32, width: i32, height: i32) -> bool {
+    x >= rect_x && x < rect_x + width && y >= rect_y && y < rect_y + height
 }
 
-/// 计算两点间的曼哈顿距离
-pub fn manhattan_distance(x1: i32, y1: i32, x2: i32, y2: i32) -> i32 {
-    (x2 - x1).abs() + (y2 - y1).abs()
-}
+/// 空间索引的默认格子边长（瓦片），与区块同宽
+pub const DEFAULT_SPATIAL_CELL_SIZE: i32 = 32;
 
-/// 计算两点间的欧几里得距离
-pub fn euclidean_distance(x1: f32, y1: f32, x2: f32, y2: f32) -> f32 {
-    ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt()
+/// 均匀网格空间索引
+///
+/// # 设计思路
+/// 1. 按固定边长把世界瓦片坐标划入网格，查询只访问范围覆盖的格子
+/// 2. 同一坐标可以放多个条目，需要一格一项时用 `replace`
+/// 3. 查询范围覆盖的格子比已占用的格子还多时，改为遍历已占用的格子，
+///    大范围查询不会退化成逐格空转
+#[derive(Debug, Clone)]
+pub struct SpatialIndex<T> {
+    cell_size: i32,
+    cells: HashMap<IVec2, Vec<(IVec2, T)>>,
+    len: usize,
 }
 
-/// 检查点是否在矩形内
-pub fn point_in_rect(x: i32, y: i32, rect_x: i32, rect_y: i32, width: i32, height: i32) -> bool {
-    x >= rect_x && x < rect_x + width && y >= rect_y && y < rect_y + height
+impl<T> Default for SpatialIndex<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_SPATIAL_CELL_SIZE)
+    }
 }
 
-/// 计算两个矩形是否相交
-pub fn rects_intersect(
-    rect1_x: i32,
-    rect1_y: i32,
-    rect1_width: i32,
-    rect1_height: i32,
-    rect2_x: i32,
-    rect2_y: i32,
-    rect2_width: i32,
-    rect2_height: i32,
-) -> bool {
-    rect1_x < rect2_x + rect2_width
-        && rect1_x + rect1_width > rect2_x
-        && rect1_y < rect2_y + rect2_height
-        && rect1_y + rect1_height > rect2_y
-}
+impl<T> SpatialIndex<T> {
+    /// 以给定的格子边长创建空索引
+    pub fn new(cell_size: i32) -> Self {
+        Self {
+            cell_size: cell_size.max(1),
+            cells: HashMap::new(),
+            len: 0,
+        }
+    }
+
+    fn cell_of(&self, pos: IVec2) -> IVec2 {
+        pos.div_euclid(IVec2::splat(self.cell_size))
+    }
 
-/// 计算点到线段的最短距离
-pub fn point_to_line_distance(
-    px: f32,
-    py: f32,
-    line_x1: f32,
-    line_y1: f32,
-    line_x2: f32,
-    line_y2: f32,
-) -> f32 {
-    let line_length_squared = (line_x2 - line_x1).powi(2) + (line_y2 - line_y1).powi(2);
-
-    // 如果线段长度为0，则直接计算点到端点的距离
-    if line_length_squared == 0.0 {
-        return euclidean_distance(px, py, line_x1, line_y1);
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
 
-    // 计算投影比例
-    let t = ((px - line_x1) * (line_x2 - line_x1) + (py - line_y1) * (line_y2 - line_y1))
-        / line_length_squared;
+    /// 加入一个条目
+    pub fn insert(&mut self, pos: IVec2, item: T) {
+        let cell = self.cell_of(pos);
+        self.cells.entry(cell).or_default().push((pos, item));
+        self.len += 1;
+    }
 
-    // 限制t在[0,1]范围内，确保投影点在线段上
-    let t = t.max(0.0).min(1.0);
+    /// 替换坐标上的条目，返回原有的第一个条目
+    pub fn replace(&mut self, pos: IVec2, item: T) -> Option<T> {
+        let old = self.remove(pos).into_iter().next();
+        self.insert(pos, item);
+        old
+    }
 
-    // 计算投影点坐标
-    let projection_x = line_x1 + t * (line_x2 - line_x1);
-    let projection_y = line_y1 + t * (line_y2 - line_y1);
+    /// 移除坐标上的全部条目
+    pub fn remove(&mut self, pos: IVec2) -> Vec<T> {
+        let cell = self.cell_of(pos);
+        let Some(entries) = self.cells.get_mut(&cell) else {
+            return Vec::new();
+        };
+        let (removed, kept): (Vec<_>, Vec<_>) = entries
+            .drain(..)
+            .partition(|(entry_pos, _)| *entry_pos == pos);
+        *entries = kept;
+        if entries.is_empty() {
+            self.cells.remove(&cell);
+        }
+        self.len -= removed.len();
+        removed.into_iter().map(|(_, item)| item).collect()
+    }
 
-    // 返回点到投影点的距离
-    euclidean_distance(px, py, projection_x, projection_y)
+    /// 移除坐标上满足条件的条目
+    pub fn remove_where(&mut self, pos: IVec2, mut predicate: impl FnMut(&T) -> bool) {
+        let cell = self.cell_of(pos);
+        let Some(entries) = self.cells.get_mut(&cell) else {
+            return;
+        };
+        let before = entries.len();
+        entries.retain(|(entry_pos, item)| *entry_pos != pos || !predicate(item));
+        self.len -= before - entries.len();
+        if entries.is_empty() {
+            self.cells.remove(&cell);
+        }
+    }
+
+    /// 坐标上的第一个条目
+    pub fn get(&self, pos: IVec2) -> Option<&T> {
+        self.cells
+            .get(&self.cell_of(pos))?
+            .iter()
+            .find(|(entry_pos, _)| *entry_pos == pos)
+            .map(|(_, item)| item)
+    }
+
+    /// 全部条目
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, &T)> {
+        self.cells
+            .values()
+            .flatten()
+            .map(|(pos, item)| (*pos, item))
+    }
+
+    /// 落在矩形范围（含边界）内的条目
+    pub fn query_rect(&self, min: IVec2, max: IVec2) -> Vec<(IVec2, &T)> {
+        let mut result = Vec::new();
+        if self.is_empty() || min.x > max.x || min.y > max.y {
+            return result;
+        }
+
+        let min_cell = self.cell_of(min);
+        let max_cell = self.cell_of(max);
+        let span = (max_cell - min_cell + IVec2::ONE).as_i64vec2();
+
+        if span.x * span.y > self.cells.len() as i64 {
+            for (cell, entries) in &self.cells {
+                if cell.x >= min_cell.x
+                    && cell.x <= max_cell.x
+                    && cell.y >= min_cell.y
+                    && cell.y <= max_cell.y
+                {
+                    collect_in_rect(entries, min, max, &mut result);
+                }
+            }
+        } else {
+            for y in min_cell.y..=max_cell.y {
+                for x in min_cell.x..=max_cell.x {
+                    if let Some(entries) = self.cells.get(&IVec2::new(x, y)) {
+                        collect_in_rect(entries, min, max, &mut result);
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// 与中心的欧几里得距离不超过 `radius` 的条目
+    pub fn query_radius(&self, center: IVec2, radius: f32) -> Vec<(IVec2, &T)> {
+        if radius < 0.0 {
+            return Vec::new();
+        }
+        let reach = IVec2::splat(radius.ceil() as i32);
+        let radius_squared = radius * radius;
+        let mut result = self.query_rect(center - reach, center + reach);
+        result.retain(|(pos, _)| (*pos - center).as_vec2().length_squared() <= radius_squared);
+        result
+    }
+}
+
+/// 收集一个格子中落在矩形范围内的条目
+fn collect_in_rect<'a, T>(
+    entries: &'a [(IVec2, T)],
+    min: IVec2,
+    max: IVec2,
+    result: &mut Vec<(IVec2, &'a T)>,
+) {
+    for (pos, item) in entries {
+        if point_in_rect(
+            pos.x,
+            pos.y,
+            min.x,
+            min.y,
+            max.x - min.x + 1,
+            max.y - min.y + 1,
+        ) {
+            result.push((*pos, item));
+        }
+    }
+}
+
+impl<T> FromIterator<(IVec2, T)> for SpatialIndex<T> {
+    fn from_iter<I: IntoIterator<Item = (IVec2, T)>>(iter: I) -> Self {
+        let mut index = Self::default();
+        for (pos, item) in iter {
+            index.insert(pos, item);
+        }
+        index
+    }
 }
@@ -1,50 +1,7 @@
-use crate::world::map::EnvironmentParams;
-
-use super::super::{
-    tile::{Render as TileRender, TileType},
-    vegetation::Rule as VegetationRules,
-    WaterManager,
-};
+use super::super::tile::{Render as TileRender, TileType};
 use bevy::prelude::*;
 use noise::{NoiseFn, Perlin};
 
-/// 地形兼容性规则
-#[derive(Debug, Clone)]
-pub struct TerrainCompatibility {
-    /// 理想高度范围
-    pub ideal_height: (f32, f32),
-    /// 可接受高度范围
-    pub acceptable_height: (f32, f32),
-    /// 理想温度范围
-    pub ideal_temperature: (f32, f32),
-    /// 可接受温度范围
-    pub acceptable_temperature: (f32, f32),
-    /// 理想湿度范围
-    pub ideal_moisture: (f32, f32),
-    /// 可接受湿度范围
-    pub acceptable_moisture: (f32, f32),
-}
-
-impl Default for TerrainCompatibility {
-    fn default() -> Self {
-        Self {
-            ideal_height: (0.4, 0.6),
-            acceptable_height: (0.3, 0.7),
-            ideal_temperature: (0.4, 0.6),
-            acceptable_temperature: (0.3, 0.7),
-            ideal_moisture: (0.4, 0.6),
-            acceptable_moisture: (0.3, 0.7),
-        }
-    }
-}
-
-impl TerrainCompatibility {
-    /// 检查地形是否符合要求
-    pub fn check_compatibility(&self, env: &EnvironmentParams) -> bool {
-        false
-    }
-}
-
 /// 地形配置
 ///
 /// 定义地形生成的规则和参数
@@ -137,48 +94,10 @@ impl Default for TerrainConfig {
     }
 }
 
-impl TerrainConfig {
-    /// 创建新的地形配置
-    pub fn new() -> Self {
-        Self::default()
-    }
-
-    /// 创建山地地形配置
-    pub fn mountain() -> Self {
-        Self {
-            mountain_height: 0.8,
-            mountain_threshold: 0.4,
-            mountain_frequency: 0.008,
-            water_level: 0.25,
-            ..Default::default()
-        }
-    }
-
-    /// 创建平原地形配置
-    pub fn plains() -> Self {
-        Self {
-            plain_strength: 0.9,
-            plain_threshold: 0.3,
-            mountain_threshold: 0.8,
-            water_level: 0.2,
-            ..Default::default()
-        }
-    }
-
-    /// 创建河谷地形配置
-    pub fn river_valley() -> Self {
-        Self {
-            river_width: 0.1,
-            river_depth: 0.3,
-            river_frequency: 0.015,
-            water_level: 0.35,
-            ..Default::default()
-        }
-    }
-}
+impl TerrainConfig {}
 
 /// 地形生成器实现
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TerrainGenerator {
     /// 噪声生成器
     noise: Perlin,
@@ -202,8 +121,9 @@ impl TerrainGenerator {
         Self { noise, config }
     }
 
-    pub fn initialize(&mut self, seed: u32) {
-        self.noise = Perlin::new(seed);
+    /// 获取地形配置
+    pub fn config(&self) -> &TerrainConfig {
+        &self.config
     }
 
     /// 生成指定位置的高度值
@@ -304,11 +224,11 @@ impl TerrainGenerator {
         };
 
         // 应用生物群系变化
-        self.apply_biome_variations(base_type, height, x, y)
+        self.apply_biome_variations(base_type, x, y)
     }
 
     /// 应用生物群系变化
-    fn apply_biome_variations(&self, base_type: u8, height: f32, x: f64, y: f64) -> u8 {
+    fn apply_biome_variations(&self, base_type: u8, x: f64, y: f64) -> u8 {
         // 使用额外的噪声来确定生物群系变化
         let biome_noise = self.noise.get([
             x * self.config.biome_frequency + 3000.0,
@@ -346,22 +266,6 @@ impl TerrainGenerator {
     pub fn get_height(&self, x: f64, y: f64) -> f32 {
         self.generate_height(x, y)
     }
-
-    pub fn get_slope(&self, x: f64, y: f64) -> f32 {
-        let dx = 0.01;
-        let dy = 0.01;
-
-        let center = self.generate_height(x, y);
-        let north = self.generate_height(x, y + dy);
-        let south = self.generate_height(x, y - dy);
-        let east = self.generate_height(x + dx, y);
-        let west = self.generate_height(x - dx, y);
-
-        let dz_dx = (east - west) / (2.0 * dx as f32);
-        let dz_dy = (north - south) / (2.0 * dy as f32);
-
-        (dz_dx * dz_dx + dz_dy * dz_dy).sqrt()
-    }
 }
 
 /// 地形渲染辅助函数
@@ -377,69 +281,10 @@ pub mod terrain_render {
         let height_factor = (height - 0.2).clamp(0.0, 0.8) / 0.8;
         let shadow_factor = 1.0 - height_factor * 0.3;
 
-        Color::rgb(
+        Color::srgb(
             base_color.red * shadow_factor,
             base_color.green * shadow_factor,
             base_color.blue * shadow_factor,
         )
     }
-
-    /// 生成地形边缘效果
-    pub fn generate_edge_effect(height: f32, neighbor_height: f32) -> Color {
-        let height_diff = (height - neighbor_height).abs();
-
-        if height_diff > 0.1 {
-            // 边缘效果强度
-            let edge_strength = (height_diff - 0.1).clamp(0.0, 0.3) / 0.3;
-
-            // 如果当前高度大于邻居高度，生成阴影效果
-            if height > neighbor_height {
-                Color::rgba(0.0, 0.0, 0.0, edge_strength * 0.5)
-            }
-            // 否则生成高光效果
-            else {
-                Color::rgba(1.0, 1.0, 1.0, edge_strength * 0.3)
-            }
-        } else {
-            Color::rgba(0.0, 0.0, 0.0, 0.0) // 透明色，无效果
-        }
-    }
-}
-
-/// 地形高度类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum TerrainHeight {
-    Valley,   // 山谷(0-0.2)
-    Plain,    // 平原(0.2-0.4)
-    Hill,     // 丘陵(0.4-0.6)
-    Mountain, // 山地(0.6-0.8)
-    Peak,     // 山峰(0.8-1.0)
-}
-
-/// 地形参数配置
-#[derive(Debug, Clone)]
-pub struct TerrainParams {
-    /// 基础高度系数
-    base_height_scale: f32,
-
-    /// 山脉分布密度
-    mountain_density: f32,
-
-    /// 水系分布规则
-    water_distribution: WaterManager,
-
-    /// 植被分布规则
-    vegetation_rules: VegetationRules,
-}
-
-impl TerrainParams {
-    /// 创建武侠风格的默认地形参数
-    pub fn wuxia_default() -> Self {
-        Self {
-            base_height_scale: 1.2, // 较高的地形起伏
-            mountain_density: 0.3,  // 适中的山脉密度
-            water_distribution: WaterManager::wuxia_style(),
-            vegetation_rules: VegetationRules::default(),
-        }
-    }
 }
@@ -0,0 +1,173 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+use std::sync::Mutex;
+
+/// 缓存统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// 命中次数
+    pub hits: u64,
+    /// 未命中次数
+    pub misses: u64,
+    /// 因容量不足被淘汰的条目数
+    pub evictions: u64,
+    /// 当前条目数
+    pub len: usize,
+    /// 容量上限
+    pub capacity: usize,
+}
+
+impl CacheStats {
+    /// 命中率 (0.0-1.0)，尚无访问时为 0
+    pub fn hit_rate(&self) -> f32 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f32 / total as f32
+        }
+    }
+}
+
+/// 缓存内部状态
+struct LruState<K, V> {
+    /// 键 -> (值, 最近访问序号)
+    entries: HashMap<K, (V, u64)>,
+    /// 访问序号 -> 键，序号最小的是最久未使用的条目
+    order: BTreeMap<u64, K>,
+    /// 单调递增的访问序号
+    tick: u64,
+    stats: CacheStats,
+}
+
+/// 容量受限的 LRU 缓存
+///
+/// # 设计思路
+/// 1. 内部可变：查询接口只需 `&self`，生成器的取值方法不必改为 `&mut self`
+/// 2. 线程安全：使用 `Mutex`，命中时也要更新访问顺序，读写锁没有优势
+/// 3. 有界：超过容量时淘汰最久未使用的条目，长时间探索不会无限增长
+///
+/// 克隆得到的是同容量的空缓存：缓存内容总能重新计算，
+/// 共享缓存反而会让克隆体重新初始化时清空原对象的数据
+pub struct LruCache<K, V> {
+    capacity: usize,
+    state: Mutex<LruState<K, V>>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> LruCache<K, V> {
+    /// 创建指定容量的缓存
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            state: Mutex::new(LruState {
+                entries: HashMap::new(),
+                order: BTreeMap::new(),
+                tick: 0,
+                stats: CacheStats {
+                    capacity,
+                    ..Default::default()
+                },
+            }),
+        }
+    }
+
+    /// 查询缓存，命中时刷新访问顺序
+    pub fn get(&self, key: &K) -> Option<V> {
+        let Ok(mut state) = self.state.lock() else {
+            return None;
+        };
+        let state = &mut *state;
+
+        state.tick += 1;
+        let tick = state.tick;
+
+        match state.entries.get_mut(key) {
+            Some((value, last_used)) => {
+                state.order.remove(last_used);
+                *last_used = tick;
+                state.order.insert(tick, key.clone());
+                state.stats.hits += 1;
+                Some(value.clone())
+            }
+            None => {
+                state.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// 写入缓存，超过容量时淘汰最久未使用的条目
+    pub fn insert(&self, key: K, value: V) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let state = &mut *state;
+
+        state.tick += 1;
+        let tick = state.tick;
+
+        if let Some((_, last_used)) = state.entries.insert(key.clone(), (value, tick)) {
+            state.order.remove(&last_used);
+        }
+        state.order.insert(tick, key);
+
+        while state.entries.len() > self.capacity {
+            let Some((_, oldest)) = state.order.pop_first() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+            state.stats.evictions += 1;
+        }
+
+        state.stats.len = state.entries.len();
+    }
+
+    /// 查询缓存，未命中时计算并写入
+    pub fn get_or_insert_with(&self, key: K, compute: impl FnOnce() -> V) -> V {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+        let value = compute();
+        self.insert(key, value.clone());
+        value
+    }
+
+    /// 清空缓存，统计数据保留
+    pub fn clear(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.entries.clear();
+            state.order.clear();
+            state.stats.len = 0;
+        }
+    }
+
+    /// 获取统计数据
+    pub fn stats(&self) -> CacheStats {
+        self.state
+            .lock()
+            .map(|state| state.stats)
+            .unwrap_or_default()
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Clone for LruCache<K, V> {
+    fn clone(&self) -> Self {
+        Self::new(self.capacity)
+    }
+}
+
+impl<K, V> fmt::Debug for LruCache<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stats = self
+            .state
+            .lock()
+            .map(|state| state.stats)
+            .unwrap_or_default();
+        f.debug_struct("LruCache")
+            .field("capacity", &self.capacity)
+            .field("stats", &stats)
+            .finish()
+    }
+}
@@ -0,0 +1,87 @@
+use bevy::math::IVec2;
+use noise::{NoiseFn, Perlin};
+
+use super::{classify_zone, Zone};
+
+/// 区块气候采样结果
+///
+/// 按行优先保存区块内每个瓦片的温度与湿度 (0.0-1.0)
+#[derive(Debug, Clone)]
+pub struct ClimateChunkSample {
+    size: usize,
+    temperature: Vec<f32>,
+    moisture: Vec<f32>,
+}
+
+impl ClimateChunkSample {
+    pub(super) fn new(size: usize, temperature: Vec<f32>, moisture: Vec<f32>) -> Self {
+        Self {
+            size,
+            temperature,
+            moisture,
+        }
+    }
+
+    /// 区块内局部坐标处的温度
+    pub fn temperature(&self, x: usize, y: usize) -> f32 {
+        self.temperature[y * self.size + x]
+    }
+
+    /// 区块内局部坐标处的湿度
+    pub fn moisture(&self, x: usize, y: usize) -> f32 {
+        self.moisture[y * self.size + x]
+    }
+
+    /// 区块内局部坐标处的气候区域，高度决定是否划为山地
+    pub fn zone(&self, x: usize, y: usize, height: f32) -> Zone {
+        classify_zone(self.temperature(x, y), self.moisture(x, y), height)
+    }
+}
+
+/// 按固定间距采样的噪声格点，区块内任意位置由四个相邻格点双线性插值
+pub(super) struct NoiseLattice {
+    stride: usize,
+    points: usize,
+    values: Vec<f64>,
+}
+
+impl NoiseLattice {
+    /// 采样覆盖 `origin` 起 `size` 个瓦片的格点（含远端边界）
+    pub(super) fn sample(
+        noise: &Perlin,
+        frequency: f64,
+        origin: IVec2,
+        size: usize,
+        stride: usize,
+    ) -> Self {
+        let stride = stride.max(1);
+        let points = size.div_ceil(stride) + 1;
+        let mut values = Vec::with_capacity(points * points);
+        for j in 0..points {
+            let y = origin.y as f64 + (j * stride) as f64;
+            for i in 0..points {
+                let x = origin.x as f64 + (i * stride) as f64;
+                values.push(noise.get([x * frequency, y * frequency]));
+            }
+        }
+
+        Self {
+            stride,
+            points,
+            values,
+        }
+    }
+
+    /// 局部坐标处的插值结果
+    pub(super) fn at(&self, x: usize, y: usize) -> f64 {
+        let i = (x / self.stride).min(self.points - 2);
+        let j = (y / self.stride).min(self.points - 2);
+        let tx = (x - i * self.stride) as f64 / self.stride as f64;
+        let ty = (y - j * self.stride) as f64 / self.stride as f64;
+
+        let value = |i: usize, j: usize| self.values[j * self.points + i];
+        let bottom = value(i, j) + (value(i + 1, j) - value(i, j)) * tx;
+        let top = value(i, j + 1) + (value(i + 1, j + 1) - value(i, j + 1)) * tx;
+        bottom + (top - bottom) * ty
+    }
+}
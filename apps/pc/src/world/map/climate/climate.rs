@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 /// 气候配置系统
 ///
 /// # 设计理念
@@ -18,20 +16,6 @@ use std::collections::HashMap;
 /// 3. 天气系统增加游戏随机性
 #[derive(Debug, Clone)]
 pub struct Climate {
-    /// 温度基准值
-    pub base_temperature: f32,
-    /// 温度变化范围
-    pub temperature_range: f32,
-    /// 温度噪声频率
-    pub temperature_frequency: f32,
-
-    /// 湿度基准值
-    pub base_humidity: f32,
-    /// 湿度变化范围
-    pub humidity_range: f32,
-    /// 湿度噪声频率
-    pub humidity_frequency: f32,
-
     /// 风力基准值
     pub base_wind: f32,
     /// 风力变化范围
@@ -44,10 +28,6 @@ pub struct Climate {
     // 季节参数
     /// 是否启用季节变化
     pub enable_seasons: bool,
-    /// 季节变化速度
-    pub season_speed: f32,
-    /// 季节影响强度
-    pub season_strength: f32,
 
     // 天气参数
     /// 是否启用天气系统
@@ -65,22 +45,12 @@ pub struct Climate {
 impl Default for Climate {
     fn default() -> Self {
         Self {
-            base_temperature: 20.0,
-            temperature_range: 10.0,
-            temperature_frequency: 0.01,
-
-            base_humidity: 0.5,
-            humidity_range: 0.3,
-            humidity_frequency: 0.02,
-
             base_wind: 1.0,
             wind_range: 2.0,
             wind_frequency: 0.05,
             wind_direction: 0.0, // 东风
 
             enable_seasons: true,
-            season_speed: 1.0,
-            season_strength: 0.8,
 
             enable_weather: true,
             rain_probability: 0.3,
@@ -90,52 +60,3 @@ impl Default for Climate {
         }
     }
 }
-
-impl Climate {
-    /// 创建新的气候配置
-    pub fn new() -> Self {
-        Self::default()
-    }
-
-    /// 创建温暖湿润的气候配置
-    pub fn warm_humid() -> Self {
-        Self {
-            base_temperature: 25.0,
-            base_humidity: 0.8,
-            rain_probability: 0.5,
-            fog_probability: 0.3,
-            ..Default::default()
-        }
-    }
-
-    /// 创建寒冷干燥的气候配置
-    pub fn cold_dry() -> Self {
-        Self {
-            base_temperature: 5.0,
-            temperature_range: 15.0,
-            base_humidity: 0.2,
-            rain_probability: 0.1,
-            ..Default::default()
-        }
-    }
-
-    /// 创建多风的气候配置
-    pub fn windy() -> Self {
-        Self {
-            base_wind: 3.0,
-            wind_range: 4.0,
-            wind_frequency: 0.1,
-            ..Default::default()
-        }
-    }
-
-    /// 创建多雾的气候配置
-    pub fn foggy() -> Self {
-        Self {
-            fog_probability: 0.6,
-            fog_density: 0.7,
-            base_humidity: 0.7,
-            ..Default::default()
-        }
-    }
-}
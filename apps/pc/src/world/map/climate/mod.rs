@@ -1,9 +1,12 @@
+mod chunk_sample;
+#[allow(clippy::module_inception)]
 mod climate;
 mod climate_params;
 mod season;
 mod system;
 mod zone;
 
+pub use chunk_sample::*;
 pub use climate::*;
 pub use climate_params::*;
 pub use season::*;
@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 /// 季节系统
 ///
 /// # 设计目标
@@ -10,10 +12,39 @@
 /// - Summer: 炎热干燥，适合探索远方
 /// - Autumn: 收获的季节，资源丰富
 /// - Winter: 生存考验，需要特殊策略
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Season {
     Spring,
     Summer,
     Autumn,
     Winter,
 }
+
+impl Season {
+    /// 季节名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Season::Spring => "春",
+            Season::Summer => "夏",
+            Season::Autumn => "秋",
+            Season::Winter => "冬",
+        }
+    }
+
+    /// 植被颜色的季节目标色 (r, g, b) 与混合强度
+    ///
+    /// 常青植被（如竹林）只做轻微调整
+    pub fn vegetation_tint(&self, evergreen: bool) -> ([f32; 3], f32) {
+        let (target, strength) = match self {
+            Season::Spring => ([0.45, 0.8, 0.3], 0.25),
+            Season::Summer => ([0.0, 0.45, 0.05], 0.0),
+            Season::Autumn => ([0.8, 0.5, 0.15], 0.55),
+            Season::Winter => ([0.85, 0.88, 0.92], 0.5),
+        };
+        if evergreen {
+            (target, strength * 0.3)
+        } else {
+            (target, strength)
+        }
+    }
+}
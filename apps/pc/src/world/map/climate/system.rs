@@ -1,7 +1,26 @@
-use bevy::utils::HashMap;
+use bevy::math::IVec2;
 use noise::{NoiseFn, Perlin};
 
-use super::{ClimateParams, Season, Zone};
+use super::super::{CacheStats, LruCache};
+use super::{ClimateChunkSample, ClimateParams, NoiseLattice, Season, Zone};
+
+/// 气候缓存容量（瓦片数）
+const CLIMATE_CACHE_CAPACITY: usize = 65536;
+
+/// 基础温度噪声频率
+const TEMPERATURE_FREQUENCY: f64 = 0.02;
+/// 模拟海拔噪声频率
+const ALTITUDE_FREQUENCY: f64 = 0.01;
+/// 基础湿度噪声频率
+const MOISTURE_FREQUENCY: f64 = 0.015;
+/// 水源距离噪声频率
+const WATER_FREQUENCY: f64 = 0.005;
+
+/// 批量采样时低频噪声的格点间距（瓦片）
+///
+/// 海拔与水源两层噪声在一个区块内变化不到半个噪声周期，
+/// 按格点采样后双线性插值，与逐瓦片计算的差异远小于气候区的划分阈值
+const LATTICE_STRIDE: usize = 8;
 
 /// 气候系统实现
 ///
@@ -31,7 +50,7 @@ pub struct System {
     /// 当前季节
     pub current_season: Season,
     /// 气候缓存
-    climate_cache: HashMap<(i32, i32), (f32, f32)>, // (temperature, moisture)
+    climate_cache: LruCache<(i32, i32), (f32, f32)>, // (temperature, moisture)
     /// 种子
     pub seed: u64,
 }
@@ -43,7 +62,7 @@ impl Default for System {
             temperature_noise: Perlin::new(1),
             moisture_noise: Perlin::new(2),
             current_season: Season::Summer,
-            climate_cache: HashMap::new(),
+            climate_cache: LruCache::new(CLIMATE_CACHE_CAPACITY),
             seed: 12345,
         }
     }
@@ -67,15 +86,46 @@ impl System {
 
     /// 获取指定位置的温度值 (0.0-1.0)
     pub fn get_temperature(&self, x: i32, y: i32) -> f32 {
-        // 检查缓存
-        if let Some(&(temp, _)) = self.climate_cache.get(&(x, y)) {
-            return temp;
-        }
+        self.climate_at(x, y).0
+    }
 
-        // 计算基础温度
-        let nx = x as f64 * 0.02;
-        let ny = y as f64 * 0.02;
-        let base_temperature = self.temperature_noise.get([nx, ny]) as f32;
+    /// 获取指定位置的湿度值 (0.0-1.0)
+    pub fn get_moisture(&self, x: i32, y: i32) -> f32 {
+        self.climate_at(x, y).1
+    }
+
+    /// 获取缓存统计
+    pub fn cache_stats(&self) -> CacheStats {
+        self.climate_cache.stats()
+    }
+
+    /// 查询温度与湿度，两者一起计算、一起缓存
+    fn climate_at(&self, x: i32, y: i32) -> (f32, f32) {
+        self.climate_cache.get_or_insert_with((x, y), || {
+            (
+                self.calculate_temperature(x, y),
+                self.calculate_moisture(x, y),
+            )
+        })
+    }
+
+    /// 计算温度，内部函数
+    fn calculate_temperature(&self, x: i32, y: i32) -> f32 {
+        let base = self.temperature_noise.get([
+            x as f64 * TEMPERATURE_FREQUENCY,
+            y as f64 * TEMPERATURE_FREQUENCY,
+        ]);
+        // 高度影响温度（假设外部会提供高度信息）
+        // 这里简化实现，使用噪声模拟高度
+        let altitude = self
+            .temperature_noise
+            .get([x as f64 * ALTITUDE_FREQUENCY, y as f64 * ALTITUDE_FREQUENCY]);
+        self.combine_temperature(base, altitude, y)
+    }
+
+    /// 由基础温度噪声与模拟海拔噪声合成温度
+    fn combine_temperature(&self, base_noise: f64, altitude_noise: f64, y: i32) -> f32 {
+        let base_temperature = base_noise as f32;
 
         // 纬度影响（北高南低）
         let world_height = 10000.0; // 假设世界总高度
@@ -89,11 +139,7 @@ impl System {
             Season::Winter => -0.2,
         };
 
-        // 高度影响温度（假设外部会提供高度信息）
-        // 这里简化实现，使用噪声模拟高度
-        let alt_nx = x as f64 * 0.01;
-        let alt_ny = y as f64 * 0.01;
-        let simulated_height = (self.temperature_noise.get([alt_nx, alt_ny]) + 1.0) * 0.5;
+        let simulated_height = (altitude_noise + 1.0) * 0.5;
         let altitude_effect = -simulated_height as f32 * self.params.altitude_temperature_factor;
 
         // 生成最终温度
@@ -105,43 +151,24 @@ impl System {
             + self.params.temperature_offset;
 
         // 标准化到0.0-1.0范围
-        let normalized_temp = temperature.min(1.0).max(0.0);
-
-        // 同时生成湿度
-        let moisture = self.calculate_moisture(x, y);
-
-        // 更新缓存
-        let mut cache = self.climate_cache.clone();
-        cache.insert((x, y), (normalized_temp, moisture));
-
-        normalized_temp
-    }
-
-    /// 获取指定位置的湿度值 (0.0-1.0)
-    pub fn get_moisture(&self, x: i32, y: i32) -> f32 {
-        // 检查缓存
-        if let Some(&(_, moisture)) = self.climate_cache.get(&(x, y)) {
-            return moisture;
-        }
-
-        // 没有缓存，同时计算温度和湿度
-        let temperature = self.get_temperature(x, y);
-
-        // 通过缓存获取刚才计算的湿度
-        if let Some(&(_, moisture)) = self.climate_cache.get(&(x, y)) {
-            return moisture;
-        }
-
-        // 如果还是没有，单独计算湿度
-        self.calculate_moisture(x, y)
+        temperature.clamp(0.0, 1.0)
     }
 
     /// 计算湿度，内部函数
     fn calculate_moisture(&self, x: i32, y: i32) -> f32 {
-        // 计算基础湿度
-        let nx = x as f64 * 0.015;
-        let ny = y as f64 * 0.015;
-        let base_moisture = self.moisture_noise.get([nx, ny]) as f32;
+        let base = self
+            .moisture_noise
+            .get([x as f64 * MOISTURE_FREQUENCY, y as f64 * MOISTURE_FREQUENCY]);
+        // 距水源距离影响（简化模拟）
+        let water = self
+            .moisture_noise
+            .get([x as f64 * WATER_FREQUENCY, y as f64 * WATER_FREQUENCY]);
+        self.combine_moisture(base, water, y)
+    }
+
+    /// 由基础湿度噪声与水源距离噪声合成湿度
+    fn combine_moisture(&self, base_noise: f64, water_noise: f64, y: i32) -> f32 {
+        let base_moisture = base_noise as f32;
 
         // 纬度影响（低纬度地区通常更湿润）
         let world_height = 10000.0; // 假设世界总高度
@@ -156,10 +183,7 @@ impl System {
             Season::Winter => -0.2, // 冬季更干燥
         };
 
-        // 距水源距离影响（简化模拟）
-        let water_nx = x as f64 * 0.005;
-        let water_ny = y as f64 * 0.005;
-        let water_proximity = (self.moisture_noise.get([water_nx, water_ny]) + 1.0) * 0.5;
+        let water_proximity = (water_noise + 1.0) * 0.5;
         let water_effect = water_proximity as f32 * 0.3;
 
         // 生成最终湿度
@@ -171,35 +195,84 @@ impl System {
             + self.params.moisture_offset;
 
         // 标准化到0.0-1.0范围
-        moisture.min(1.0).max(0.0)
+        moisture.clamp(0.0, 1.0)
+    }
+
+    /// 一次性采样整个区块的温度与湿度
+    ///
+    /// 基础噪声逐瓦片计算，低频的海拔与水源噪声在格点上采样后插值复用；
+    /// 结果不写入逐瓦片缓存，区块生成完即可丢弃
+    pub fn sample_chunk(&self, coord: IVec2, chunk_size: usize) -> ClimateChunkSample {
+        let origin = coord * chunk_size as i32;
+        let altitude = NoiseLattice::sample(
+            &self.temperature_noise,
+            ALTITUDE_FREQUENCY,
+            origin,
+            chunk_size,
+            LATTICE_STRIDE,
+        );
+        let water = NoiseLattice::sample(
+            &self.moisture_noise,
+            WATER_FREQUENCY,
+            origin,
+            chunk_size,
+            LATTICE_STRIDE,
+        );
+
+        let mut temperature = Vec::with_capacity(chunk_size * chunk_size);
+        let mut moisture = Vec::with_capacity(chunk_size * chunk_size);
+        for local_y in 0..chunk_size {
+            let y = origin.y + local_y as i32;
+            for local_x in 0..chunk_size {
+                let x = origin.x + local_x as i32;
+                let base_temperature = self.temperature_noise.get([
+                    x as f64 * TEMPERATURE_FREQUENCY,
+                    y as f64 * TEMPERATURE_FREQUENCY,
+                ]);
+                let base_moisture = self
+                    .moisture_noise
+                    .get([x as f64 * MOISTURE_FREQUENCY, y as f64 * MOISTURE_FREQUENCY]);
+
+                temperature.push(self.combine_temperature(
+                    base_temperature,
+                    altitude.at(local_x, local_y),
+                    y,
+                ));
+                moisture.push(self.combine_moisture(base_moisture, water.at(local_x, local_y), y));
+            }
+        }
+
+        ClimateChunkSample::new(chunk_size, temperature, moisture)
     }
 
     /// 获取指定位置的气候区域类型
     pub fn get_climate_zone(&self, x: i32, y: i32, height: f32) -> Zone {
-        let temperature = self.get_temperature(x, y);
-        let moisture = self.get_moisture(x, y);
+        classify_zone(self.get_temperature(x, y), self.get_moisture(x, y), height)
+    }
+}
 
-        // 根据高度确定是否为山地
-        if height > 0.7 {
-            return Zone::Mountains;
-        }
+/// 根据温度、湿度与高度划分气候区域
+pub fn classify_zone(temperature: f32, moisture: f32, height: f32) -> Zone {
+    // 根据高度确定是否为山地
+    if height > 0.7 {
+        return Zone::Mountains;
+    }
 
-        // 根据温度和湿度确定气候区域
-        match (temperature, moisture) {
-            // 极地 - 寒冷
-            (t, _) if t < 0.2 => Zone::Polar,
+    // 根据温度和湿度确定气候区域
+    match (temperature, moisture) {
+        // 极地 - 寒冷
+        (t, _) if t < 0.2 => Zone::Polar,
 
-            // 沙漠 - 炎热干燥
-            (t, m) if t > 0.7 && m < 0.3 => Zone::Desert,
+        // 沙漠 - 炎热干燥
+        (t, m) if t > 0.7 && m < 0.3 => Zone::Desert,
 
-            // 热带 - 温暖潮湿
-            (t, m) if t > 0.6 && m > 0.5 => Zone::Tropical,
+        // 热带 - 温暖潮湿
+        (t, m) if t > 0.6 && m > 0.5 => Zone::Tropical,
 
-            // 大陆性 - 温度变化大，较干燥
-            (t, m) if t > 0.3 && t < 0.7 && m < 0.5 => Zone::Continental,
+        // 大陆性 - 温度变化大，较干燥
+        (t, m) if t > 0.3 && t < 0.7 && m < 0.5 => Zone::Continental,
 
-            // 温带 - 温和适中
-            _ => Zone::Temperate,
-        }
+        // 温带 - 温和适中
+        _ => Zone::Temperate,
     }
 }
@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 /// 气候区域系统
 ///
 /// # 设计目的
@@ -12,7 +14,7 @@
 /// - Polar: 高难度区域，特殊资源
 /// - Desert: 极端环境，独特玩法
 /// - Mountains: 战略要地，稀有资源
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Zone {
     Tropical,    // 热带
     Temperate,   // 温带
@@ -21,3 +23,50 @@ pub enum Zone {
     Desert,      // 沙漠
     Mountains,   // 高山
 }
+
+impl Zone {
+    pub const ALL: [Zone; 6] = [
+        Zone::Tropical,
+        Zone::Temperate,
+        Zone::Continental,
+        Zone::Polar,
+        Zone::Desert,
+        Zone::Mountains,
+    ];
+
+    /// 区域名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Zone::Tropical => "热带",
+            Zone::Temperate => "温带",
+            Zone::Continental => "大陆",
+            Zone::Polar => "极地",
+            Zone::Desert => "大漠",
+            Zone::Mountains => "高山",
+        }
+    }
+
+    /// 区域在预览图上的颜色
+    pub fn color(&self) -> (u8, u8, u8) {
+        match self {
+            Zone::Tropical => (46, 160, 67),
+            Zone::Temperate => (140, 190, 90),
+            Zone::Continental => (170, 150, 90),
+            Zone::Polar => (225, 235, 245),
+            Zone::Desert => (225, 195, 120),
+            Zone::Mountains => (120, 110, 105),
+        }
+    }
+
+    /// 区域地形瓦片的贴图集
+    pub fn tileset_path(&self) -> &'static str {
+        match self {
+            Zone::Tropical => "textures/tiles/tropical.png",
+            Zone::Temperate => "textures/tiles/temperate.png",
+            Zone::Continental => "textures/tiles/continental.png",
+            Zone::Polar => "textures/tiles/polar.png",
+            Zone::Desert => "textures/tiles/desert.png",
+            Zone::Mountains => "textures/tiles/mountains.png",
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::VegetationType;
+
+/// 摆件在区块装饰层中的编号从这里开始，之前的编号留给植被，旧存档中的植被编号不变
+const PROP_ID_BASE: u8 = 128;
+
+/// 摆件：岩石、灯笼、石碑等不会生长的装饰物
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PropType {
+    Pebbles,           // 碎石
+    Rock,              // 岩石
+    Boulder,           // 巨石
+    Lantern,           // 灯笼（点亮）
+    LanternUnlit,      // 灯笼（熄灭）
+    StoneLantern,      // 石灯（点亮）
+    StoneLanternUnlit, // 石灯（熄灭）
+    Signpost,          // 路牌
+    Stele,             // 石碑
+    Well,              // 水井
+}
+
+/// 摆件的交互方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropInteraction {
+    /// 点亮或熄灭，切换为对应的另一种摆件
+    ToggleLight,
+    /// 查看，显示摆件的描述
+    Examine,
+}
+
+impl PropType {
+    const ALL: [PropType; 10] = [
+        PropType::Pebbles,
+        PropType::Rock,
+        PropType::Boulder,
+        PropType::Lantern,
+        PropType::LanternUnlit,
+        PropType::StoneLantern,
+        PropType::StoneLanternUnlit,
+        PropType::Signpost,
+        PropType::Stele,
+        PropType::Well,
+    ];
+
+    /// 从区块装饰层存储的编号还原摆件类型
+    pub fn from_u8(value: u8) -> Option<Self> {
+        value
+            .checked_sub(PROP_ID_BASE)
+            .and_then(|index| Self::ALL.get(index as usize))
+            .copied()
+    }
+
+    /// 在区块装饰层中存储的编号
+    pub fn id(self) -> u8 {
+        PROP_ID_BASE + self as u8
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PropType::Pebbles => "碎石",
+            PropType::Rock => "岩石",
+            PropType::Boulder => "巨石",
+            PropType::Lantern | PropType::LanternUnlit => "灯笼",
+            PropType::StoneLantern | PropType::StoneLanternUnlit => "石灯",
+            PropType::Signpost => "路牌",
+            PropType::Stele => "石碑",
+            PropType::Well => "水井",
+        }
+    }
+
+    /// 精灵的基础尺寸（像素）与颜色
+    pub fn visual(self) -> (Vec2, Color) {
+        match self {
+            PropType::Pebbles => (Vec2::new(14.0, 6.0), Color::srgb(0.55, 0.53, 0.5)),
+            PropType::Rock => (Vec2::new(22.0, 16.0), Color::srgb(0.5, 0.48, 0.45)),
+            PropType::Boulder => (Vec2::new(36.0, 28.0), Color::srgb(0.42, 0.4, 0.38)),
+            PropType::Lantern => (Vec2::new(10.0, 36.0), Color::srgb(0.95, 0.35, 0.2)),
+            PropType::LanternUnlit => (Vec2::new(10.0, 36.0), Color::srgb(0.55, 0.2, 0.15)),
+            PropType::StoneLantern => (Vec2::new(16.0, 30.0), Color::srgb(0.75, 0.72, 0.65)),
+            PropType::StoneLanternUnlit => (Vec2::new(16.0, 30.0), Color::srgb(0.6, 0.58, 0.55)),
+            PropType::Signpost => (Vec2::new(14.0, 28.0), Color::srgb(0.5, 0.36, 0.22)),
+            PropType::Stele => (Vec2::new(18.0, 34.0), Color::srgb(0.45, 0.45, 0.48)),
+            PropType::Well => (Vec2::new(30.0, 26.0), Color::srgb(0.48, 0.46, 0.44)),
+        }
+    }
+
+    /// 碰撞体的半宽，碎石可以直接走过
+    ///
+    /// 精灵以底边中点为锚点，碰撞体只取底部，角色可以走到高大摆件的后面
+    pub fn collider_half_width(self) -> Option<f32> {
+        match self {
+            PropType::Pebbles => None,
+            PropType::Lantern | PropType::LanternUnlit => Some(3.0),
+            PropType::Signpost => Some(4.0),
+            PropType::StoneLantern | PropType::StoneLanternUnlit => Some(7.0),
+            PropType::Rock | PropType::Stele => Some(9.0),
+            PropType::Well => Some(14.0),
+            PropType::Boulder => Some(16.0),
+        }
+    }
+
+    pub fn interaction(self) -> Option<PropInteraction> {
+        match self {
+            PropType::Lantern
+            | PropType::LanternUnlit
+            | PropType::StoneLantern
+            | PropType::StoneLanternUnlit => Some(PropInteraction::ToggleLight),
+            PropType::Signpost | PropType::Stele | PropType::Well => Some(PropInteraction::Examine),
+            PropType::Pebbles | PropType::Rock | PropType::Boulder => None,
+        }
+    }
+
+    /// 交互提示中的动作
+    pub fn interaction_verb(self) -> &'static str {
+        match self {
+            PropType::Lantern | PropType::StoneLantern => "熄灭",
+            PropType::LanternUnlit | PropType::StoneLanternUnlit => "点亮",
+            _ => "查看",
+        }
+    }
+
+    /// 点亮与熄灭互相切换
+    pub fn toggled(self) -> Option<PropType> {
+        match self {
+            PropType::Lantern => Some(PropType::LanternUnlit),
+            PropType::LanternUnlit => Some(PropType::Lantern),
+            PropType::StoneLantern => Some(PropType::StoneLanternUnlit),
+            PropType::StoneLanternUnlit => Some(PropType::StoneLantern),
+            _ => None,
+        }
+    }
+
+    /// 是否发光
+    pub fn is_lit(self) -> bool {
+        matches!(self, PropType::Lantern | PropType::StoneLantern)
+    }
+
+    /// 查看时显示的描述
+    pub fn description(self) -> &'static str {
+        match self {
+            PropType::Signpost => "路牌上的字迹被风雨磨得模糊，只认得出几处地名",
+            PropType::Stele => "石碑上刻着前人的功绩，落款年代已不可考",
+            PropType::Well => "井水清凉，井绳上结着青苔",
+            _ => "",
+        }
+    }
+}
+
+/// 区块装饰层中的一项：植被或摆件
+///
+/// 预制场景里按名称书写，植被与摆件的名称互不重复，例如 `"Pine"`、`"StoneLantern"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Decoration {
+    Plant(VegetationType),
+    Prop(PropType),
+}
+
+impl Decoration {
+    /// 在区块装饰层中存储的编号
+    pub fn id(self) -> u8 {
+        match self {
+            Decoration::Plant(kind) => kind as u8,
+            Decoration::Prop(kind) => kind.id(),
+        }
+    }
+}
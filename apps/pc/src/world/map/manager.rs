@@ -1,10 +1,16 @@
 use bevy::prelude::*;
+use std::collections::HashMap;
 
-use super::{area::TerrainConfig, Climate, Vegetation, Water};
+use super::{
+    area::{FixedSceneRef, TerrainConfig},
+    climate::System as ClimateSystem,
+    vegetation::System as VegetationSystem,
+    Climate, Season, Vegetation, Water,
+};
 
 /// 地图管理器
 /// 负责管理地图的核心组件和规则
-#[derive(Resource)]
+#[derive(Resource, Clone)]
 pub struct MapManager {
     /// 地图种子
     pub seed: u32,
@@ -16,6 +22,12 @@ pub struct MapManager {
     pub vegetation_config: Vegetation,
     /// 气候配置
     pub climate_config: Climate,
+    /// 运行时气候系统，随历法切换季节
+    climate_system: ClimateSystem,
+    /// 植被分布系统，区块生成时决定植被类型
+    vegetation_system: VegetationSystem,
+    /// 固定场景，键为场景中心的世界瓦片坐标
+    fixed_scenes: HashMap<IVec2, FixedSceneRef>,
     /// 高度缩放因子
     pub height_scale: f32,
     /// 是否启用2.5D效果
@@ -30,6 +42,9 @@ impl Default for MapManager {
             water_config: Water::default(),
             vegetation_config: Vegetation::default(),
             climate_config: Climate::default(),
+            climate_system: ClimateSystem::default(),
+            vegetation_system: VegetationSystem::default(),
+            fixed_scenes: HashMap::new(),
             height_scale: 0.5,
             enable_2_5d: true,
         }
@@ -39,10 +54,25 @@ impl Default for MapManager {
 impl MapManager {
     /// 创建新的地图管理器
     pub fn new(seed: u32) -> Self {
-        Self {
+        let mut manager = Self {
             seed,
             ..Default::default()
-        }
+        };
+        manager.climate_system.initialize(seed as u64);
+        manager
+            .vegetation_system
+            .initialize((seed as u64).wrapping_add(2));
+        manager
+    }
+
+    /// 换用新的种子，保留各项配置、固定场景与当前季节
+    ///
+    /// 联机加入主机时使用，已加载的区块需要由调用方卸载后重新生成
+    pub fn reseed(&mut self, seed: u32) {
+        self.seed = seed;
+        self.climate_system.initialize(seed as u64);
+        self.vegetation_system
+            .initialize((seed as u64).wrapping_add(2));
     }
 
     /// 获取指定位置的高度值
@@ -82,6 +112,42 @@ impl MapManager {
         &self.climate_config
     }
 
+    /// 获取气候系统
+    pub fn climate_system(&self) -> &ClimateSystem {
+        &self.climate_system
+    }
+
+    /// 获取植被系统
+    pub fn vegetation_system(&self) -> &VegetationSystem {
+        &self.vegetation_system
+    }
+
+    /// 获取固定场景
+    pub fn fixed_scenes(&self) -> &HashMap<IVec2, FixedSceneRef> {
+        &self.fixed_scenes
+    }
+
+    /// 添加固定场景，可以是内置场景类型或预制场景ID
+    pub fn add_fixed_scene(&mut self, pos: IVec2, scene: impl Into<FixedSceneRef>) {
+        self.fixed_scenes.insert(pos, scene.into());
+    }
+
+    /// 当前季节
+    pub fn current_season(&self) -> Season {
+        self.climate_system.current_season
+    }
+
+    /// 切换季节，未启用季节时忽略
+    ///
+    /// 季节影响温度与湿度，切换时气候系统会清空缓存
+    pub fn set_season(&mut self, season: Season) -> bool {
+        if !self.climate_config.enable_seasons || self.climate_system.current_season == season {
+            return false;
+        }
+        self.climate_system.set_season(season);
+        true
+    }
+
     /// 更新地形配置
     pub fn update_terrain_config(&mut self, config: TerrainConfig) {
         self.terrain_config = config;
@@ -94,6 +160,7 @@ impl MapManager {
 
     /// 更新植被配置
     pub fn update_vegetation_config(&mut self, config: Vegetation) {
+        self.vegetation_system.params.density_factor = config.density;
         self.vegetation_config = config;
     }
 
@@ -1,13 +1,11 @@
 pub mod area;
-pub mod assets;
+pub mod cache;
 pub mod climate;
-pub mod effect;
+pub mod decoration;
 pub mod environment;
 pub mod manager;
-pub mod map_generator;
-pub mod map_noise;
-pub mod map_rules;
 pub mod npc;
+pub mod preview;
 pub mod quest;
 pub mod systems;
 pub mod tile;
@@ -16,15 +14,13 @@ pub mod water;
 pub mod world_config;
 
 pub use area::*;
-pub use assets::*;
+pub use cache::*;
 pub use climate::*;
-pub use effect::*;
+pub use decoration::*;
 pub use environment::*;
 pub use manager::*;
-pub use map_generator::*;
-pub use map_noise::*;
-pub use map_rules::*;
 pub use npc::*;
+pub use preview::*;
 pub use quest::*;
 pub use systems::*;
 pub use tile::*;
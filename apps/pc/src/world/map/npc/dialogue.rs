@@ -1,5 +1,192 @@
-#[derive(Debug, Clone)]
+use bevy::asset::Asset;
+use bevy::reflect::TypePath;
+use serde::{Deserialize, Serialize};
+
+use super::Reputation;
+use crate::items::Inventory;
+use crate::scripting::{ScriptHost, ScriptWorld};
+use crate::world::entity::AiState;
+use crate::world::map::quest::{QuestManager, QuestStatus};
+
+/// 对话条件，不满足时选项不显示，入口不生效
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DialogueCondition {
+    /// 任务尚未接取
+    QuestNotStarted { quest_id: String },
+    /// 任务进行中，指定阶段时还需处于该阶段
+    QuestActive {
+        quest_id: String,
+        #[serde(default)]
+        stage: Option<String>,
+    },
+    /// 任务已完成
+    QuestCompleted { quest_id: String },
+    /// 背包中持有足够数量的物品
+    HasItem { item_id: String, count: u32 },
+    /// 身上的银两足够
+    HasMoney { amount: u32 },
+    /// 在某一方的声望不低于给定值
+    Reputation { faction: String, min: i32 },
+    /// 脚本返回 true，脚本只能读取状态
+    Script {
+        script: String,
+        #[serde(default)]
+        function: Option<String>,
+    },
+}
+
+impl DialogueCondition {
+    /// 按当下的任务进度、背包与声望判断条件是否成立，脚本未启用时脚本条件不成立
+    pub fn holds(
+        &self,
+        quests: &QuestManager,
+        inventory: Option<&Inventory>,
+        reputation: &Reputation,
+        scripts: Option<&ScriptHost>,
+    ) -> bool {
+        match self {
+            DialogueCondition::QuestNotStarted { quest_id } => quests.state(quest_id).is_none(),
+            DialogueCondition::QuestActive { quest_id, stage } => {
+                let Some(state) = quests
+                    .state(quest_id)
+                    .filter(|state| state.status == QuestStatus::Active)
+                else {
+                    return false;
+                };
+                stage.as_ref().is_none_or(|stage| {
+                    quests
+                        .quest(quest_id)
+                        .and_then(|quest| quest.stage(state.stage))
+                        .is_some_and(|current| &current.id == stage)
+                })
+            }
+            DialogueCondition::QuestCompleted { quest_id } => quests
+                .state(quest_id)
+                .is_some_and(|state| state.status == QuestStatus::Completed),
+            DialogueCondition::HasItem { item_id, count } => {
+                inventory.is_some_and(|inventory| inventory.count(item_id) >= *count)
+            }
+            DialogueCondition::HasMoney { amount } => {
+                inventory.is_some_and(|inventory| inventory.money >= *amount)
+            }
+            DialogueCondition::Reputation { faction, min } => reputation.get(faction) >= *min,
+            DialogueCondition::Script { script, function } => scripts.is_some_and(|scripts| {
+                let world = ScriptWorld::capture(quests, inventory, reputation);
+                scripts.check(script, function.as_deref(), world)
+            }),
+        }
+    }
+}
+
+/// 对话效果，进入节点或选择选项时执行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DialogueEffect {
+    /// 接取任务
+    StartQuest { quest_id: String },
+    /// 推进任务到下一阶段
+    AdvanceQuest { quest_id: String },
+    /// 给予物品
+    GiveItem { item_id: String, count: u32 },
+    /// 收走物品
+    TakeItem { item_id: String, count: u32 },
+    /// 给予银两
+    GiveMoney { amount: u32 },
+    /// 收取银两，不足时全部收走
+    TakeMoney { amount: u32 },
+    /// 改变在某一方的声望
+    ChangeReputation { faction: String, amount: i32 },
+    /// 对话结束后NPC转入的AI状态
+    SetAiState { state: AiState },
+    /// 执行脚本，与任务效果中的脚本相同
+    Script {
+        script: String,
+        #[serde(default)]
+        function: Option<String>,
+    },
+}
+
+/// 对话选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DialogueChoice {
+    pub text: String,
+    #[serde(default)]
+    pub conditions: Vec<DialogueCondition>,
+    #[serde(default)]
+    pub effects: Vec<DialogueEffect>,
+    /// 跳转的节点ID，为空时结束对话
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+/// 对话节点
+///
+/// 有选项时等待玩家选择；没有选项时按交互键前往 next，next 为空则结束对话
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DialogueNode {
+    pub id: String,
+    /// 说话人，为空时沿用对话的说话人
+    #[serde(default)]
+    pub speaker: Option<String>,
+    /// 头像图片路径（相对于 assets），为空时沿用对话的头像
+    #[serde(default)]
+    pub portrait: Option<String>,
+    pub text: String,
+    /// 进入节点时执行的效果
+    #[serde(default)]
+    pub effects: Vec<DialogueEffect>,
+    #[serde(default)]
+    pub choices: Vec<DialogueChoice>,
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+/// 按条件选择的对话入口
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DialogueEntry {
+    pub conditions: Vec<DialogueCondition>,
+    pub node: String,
+}
+
+/// 对话图
+///
+/// 从第一个条件全部满足的入口开始，都不满足时从 start 开始
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
 pub struct Dialogue {
     pub id: String,
-    pub content: String,
+    /// 默认说话人
+    pub speaker: String,
+    /// 默认头像
+    #[serde(default)]
+    pub portrait: Option<String>,
+    /// 使用这段对话的NPC名字
+    #[serde(default)]
+    pub npcs: Vec<String>,
+    #[serde(default)]
+    pub entries: Vec<DialogueEntry>,
+    pub start: String,
+    pub nodes: Vec<DialogueNode>,
+}
+
+impl Dialogue {
+    pub fn node(&self, id: &str) -> Option<&DialogueNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// 检查节点跳转，返回引用了不存在节点的描述
+    pub fn validate(&self) -> Vec<String> {
+        let targets = std::iter::once(&self.start)
+            .chain(self.entries.iter().map(|entry| &entry.node))
+            .chain(self.nodes.iter().filter_map(|node| node.next.as_ref()))
+            .chain(
+                self.nodes
+                    .iter()
+                    .flat_map(|node| node.choices.iter())
+                    .filter_map(|choice| choice.next.as_ref()),
+            );
+
+        targets
+            .filter(|target| self.node(target).is_none())
+            .map(|target| format!("对话 {} 跳转到不存在的节点 {}", self.id, target))
+            .collect()
+    }
 }
@@ -0,0 +1,67 @@
+use bevy::asset::{io::Reader, AssetId, AssetLoader, Handle, LoadContext, LoadedFolder};
+use bevy::ecs::system::Resource;
+use std::collections::HashMap;
+use thiserror::Error;
+
+use super::Dialogue;
+
+/// 对话资源所在的目录（相对于 assets）
+pub const DIALOGUE_FOLDER: &str = "dialogues";
+
+/// 对话资源加载错误
+#[derive(Debug, Error)]
+pub enum DialogueLoaderError {
+    #[error("读取对话失败: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("解析对话失败: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// 对话资源加载器，读取 `*.dialogue.json`
+#[derive(Default)]
+pub struct DialogueLoader;
+
+impl AssetLoader for DialogueLoader {
+    type Asset = Dialogue;
+    type Settings = ();
+    type Error = DialogueLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Dialogue, DialogueLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["dialogue.json"]
+    }
+}
+
+/// 已加载的对话
+#[derive(Resource, Default)]
+pub struct DialogueLibrary {
+    /// 对话目录句柄，保持目录中的资源不被卸载
+    pub folder: Option<Handle<LoadedFolder>>,
+    /// 资源ID到对话ID的映射，用于处理修改和移除
+    pub ids: HashMap<AssetId<Dialogue>, String>,
+    /// 按对话ID索引的对话
+    pub dialogues: HashMap<String, Dialogue>,
+}
+
+impl DialogueLibrary {
+    pub fn get(&self, id: &str) -> Option<&Dialogue> {
+        self.dialogues.get(id)
+    }
+
+    /// 指定名字的NPC使用的对话
+    pub fn for_npc(&self, name: &str) -> Option<&Dialogue> {
+        self.dialogues
+            .values()
+            .find(|dialogue| dialogue.npcs.iter().any(|npc| npc == name))
+    }
+}
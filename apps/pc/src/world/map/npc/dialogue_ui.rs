@@ -0,0 +1,165 @@
+use bevy::prelude::*;
+
+use super::{ActiveDialogue, DialogueLibrary, Reputation};
+use crate::items::Inventory;
+use crate::scripting::ScriptHost;
+use crate::world::entity::Player;
+use crate::world::map::quest::QuestManager;
+
+/// 对话框根节点
+#[derive(Component)]
+pub struct DialogueUi;
+
+/// 对话框中的台词文本
+#[derive(Component)]
+pub struct DialogueText;
+
+/// 选项按钮，记录选项在节点中的序号
+#[derive(Component, Debug, Clone, Copy)]
+pub struct DialogueChoiceButton(pub usize);
+
+/// 对话框上一次显示的内容：对话、节点以及是否已打完字
+type Shown = Option<(String, String, bool)>;
+
+/// 对话变化时重建对话框，打字过程中只更新文本
+///
+/// 选项在台词打完后才出现
+#[allow(clippy::too_many_arguments)]
+pub fn update_dialogue_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    library: Res<DialogueLibrary>,
+    quests: Res<QuestManager>,
+    reputation: Res<Reputation>,
+    scripts: Option<Res<ScriptHost>>,
+    active: Res<ActiveDialogue>,
+    players: Query<&Inventory, With<Player>>,
+    ui: Query<Entity, With<DialogueUi>>,
+    mut texts: Query<&mut Text, With<DialogueText>>,
+    mut shown: Local<Shown>,
+) {
+    if !active.is_changed() {
+        return;
+    }
+
+    let current = active.session.as_ref().and_then(|session| {
+        let dialogue = library.get(&session.dialogue_id)?;
+        let node = dialogue.node(&session.node)?;
+        Some((session, dialogue, node))
+    });
+    let Some((session, dialogue, node)) = current else {
+        for entity in ui.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        *shown = None;
+        return;
+    };
+
+    let typed = session.revealed as usize >= node.text.chars().count();
+    let visible: String = node.text.chars().take(session.revealed as usize).collect();
+    let key = (session.dialogue_id.clone(), session.node.clone(), typed);
+    if shown.as_ref() == Some(&key) {
+        for mut text in texts.iter_mut() {
+            text.0 = visible.clone();
+        }
+        return;
+    }
+    *shown = Some(key);
+
+    for entity in ui.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let inventory = players.get_single().ok();
+    let choices: Vec<(usize, &str)> = if typed {
+        node.choices
+            .iter()
+            .enumerate()
+            .filter(|(_, choice)| {
+                choice.conditions.iter().all(|condition| {
+                    condition.holds(&quests, inventory, &reputation, scripts.as_deref())
+                })
+            })
+            .map(|(index, choice)| (index, choice.text.as_str()))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let speaker = node.speaker.as_ref().unwrap_or(&dialogue.speaker);
+    let portrait = node.portrait.as_ref().or(dialogue.portrait.as_ref());
+
+    commands
+        .spawn((
+            DialogueUi,
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Percent(15.0),
+                right: Val::Percent(15.0),
+                bottom: Val::Px(24.0),
+                padding: UiRect::all(Val::Px(12.0)),
+                column_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.08, 0.06, 0.9)),
+        ))
+        .with_children(|root| {
+            if let Some(portrait) = portrait {
+                root.spawn((
+                    ImageNode::new(asset_server.load(portrait.clone())),
+                    Node {
+                        width: Val::Px(96.0),
+                        height: Val::Px(96.0),
+                        flex_shrink: 0.0,
+                        ..default()
+                    },
+                ));
+            }
+
+            root.spawn(Node {
+                flex_direction: FlexDirection::Column,
+                flex_grow: 1.0,
+                row_gap: Val::Px(6.0),
+                ..default()
+            })
+            .with_children(|column| {
+                column.spawn((
+                    Text::new(speaker.clone()),
+                    TextFont {
+                        font_size: 18.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.95, 0.8, 0.5)),
+                ));
+                column.spawn((
+                    DialogueText,
+                    Text::new(visible),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                ));
+
+                for (index, text) in choices {
+                    column
+                        .spawn((
+                            Button,
+                            DialogueChoiceButton(index),
+                            Node {
+                                padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgba(0.25, 0.2, 0.15, 0.8)),
+                        ))
+                        .with_children(|button| {
+                            button.spawn((
+                                Text::new(text),
+                                TextFont {
+                                    font_size: 14.0,
+                                    ..default()
+                                },
+                            ));
+                        });
+                }
+            });
+        });
+}
@@ -1,9 +1,22 @@
-mod behavior;
+/// NPC模块
+///
+/// # 模块组成
+/// 1. dialogue：对话图、对话条件与效果
+/// 2. dialogue_asset：对话资源加载器，对话文件放在 assets/dialogues
+/// 3. session：进行中的对话
+/// 4. reputation：玩家在各门派、势力中的声望
+/// 5. dialogue_ui：带头像、打字机效果与选项的对话框
+/// 6. systems：对话插件，与NPC交谈时打开对话并推进
 mod dialogue;
-mod npc;
-mod npc_type;
+mod dialogue_asset;
+mod dialogue_ui;
+mod reputation;
+mod session;
+mod systems;
 
-pub use behavior::*;
 pub use dialogue::*;
-pub use npc::*;
-pub use npc_type::*;
+pub use dialogue_asset::*;
+pub use dialogue_ui::*;
+pub use reputation::*;
+pub use session::*;
+pub use systems::{DialoguePlugin, DialogueSettings};
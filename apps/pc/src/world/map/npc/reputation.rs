@@ -0,0 +1,43 @@
+use bevy::ecs::system::Resource;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// 声望存档路径
+pub const REPUTATION_SAVE_PATH: &str = "saves/reputation.json";
+
+/// 玩家在各门派、势力中的声望，随存档保存
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Reputation {
+    standing: HashMap<String, i32>,
+}
+
+impl Reputation {
+    /// 从存档读取
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 写入存档
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// 某一方的声望，从未打过交道为 0
+    pub fn get(&self, faction: &str) -> i32 {
+        self.standing.get(faction).copied().unwrap_or(0)
+    }
+
+    /// 改变声望，返回新的值
+    pub fn change(&mut self, faction: &str, amount: i32) -> i32 {
+        let value = self.standing.entry(faction.to_string()).or_insert(0);
+        *value = value.saturating_add(amount);
+        *value
+    }
+}
@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+
+use crate::world::entity::AiState;
+
+/// 进行中的对话
+#[derive(Debug, Clone)]
+pub struct DialogueSession {
+    /// 对话的NPC
+    pub npc: Entity,
+    pub dialogue_id: String,
+    /// 当前节点ID
+    pub node: String,
+    /// 当前节点的效果是否已执行
+    pub entered: bool,
+    /// 打字机效果已显示的字数
+    pub revealed: f32,
+    /// 对话结束后NPC恢复的AI状态
+    pub resume_state: AiState,
+}
+
+/// 当前对话，同一时间只和一个NPC说话
+#[derive(Resource, Debug, Default)]
+pub struct ActiveDialogue {
+    pub session: Option<DialogueSession>,
+}
@@ -0,0 +1,406 @@
+use bevy::prelude::*;
+use std::path::Path;
+
+use super::{
+    update_dialogue_ui, ActiveDialogue, Dialogue, DialogueChoiceButton, DialogueEffect,
+    DialogueLibrary, DialogueLoader, DialogueSession, Reputation, DIALOGUE_FOLDER,
+    REPUTATION_SAVE_PATH,
+};
+use crate::analytics::PlaytestSample;
+use crate::interaction::PanelInteraction;
+use crate::items::{Inventory, ItemDatabase, ItemInstance};
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::gameplay_running;
+use crate::scripting::ScriptHost;
+use crate::world::entity::{AiState, Character, Npc, Player};
+use crate::world::map::quest::{
+    detect_npc_talk, run_quests, Effect, QuestEffectRequest, QuestManager, TalkedToNpc,
+};
+
+/// 对话设置
+///
+/// - chars_per_second: 打字机效果每秒显示的字数
+/// - leave_range: 玩家离开NPC超过这个距离时结束对话
+#[derive(Resource, Debug, Clone)]
+pub struct DialogueSettings {
+    pub chars_per_second: f32,
+    pub leave_range: f32,
+    /// 声望存档路径
+    pub save_path: String,
+}
+
+impl Default for DialogueSettings {
+    fn default() -> Self {
+        Self {
+            chars_per_second: 30.0,
+            leave_range: 96.0,
+            save_path: REPUTATION_SAVE_PATH.to_string(),
+        }
+    }
+}
+
+/// 对话插件
+pub struct DialoguePlugin;
+
+impl Plugin for DialoguePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Dialogue>()
+            .init_asset_loader::<DialogueLoader>()
+            .init_resource::<DialogueSettings>()
+            .init_resource::<DialogueLibrary>()
+            .init_resource::<ActiveDialogue>()
+            .init_resource::<Reputation>()
+            .add_systems(Startup, load_dialogues)
+            .add_systems(Update, (sync_dialogues, assign_dialogues).chain())
+            // 要在任务记录这次交谈之前查看任务台词，否则看到的是下一阶段的
+            .add_systems(
+                Update,
+                open_dialogue
+                    .after(detect_npc_talk)
+                    .before(run_quests)
+                    .run_if(gameplay_running),
+            )
+            .add_systems(
+                Update,
+                (advance_dialogue, update_dialogue_ui)
+                    .chain()
+                    .after(open_dialogue)
+                    .run_if(gameplay_running),
+            );
+    }
+}
+
+/// 开始加载对话目录并读取声望存档
+fn load_dialogues(
+    settings: Res<DialogueSettings>,
+    asset_server: Res<AssetServer>,
+    mut library: ResMut<DialogueLibrary>,
+    mut reputation: ResMut<Reputation>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    library.folder = Some(asset_server.load_folder(DIALOGUE_FOLDER));
+    if Path::new(&settings.save_path).exists() {
+        match Reputation::load(&settings.save_path) {
+            Ok(loaded) => *reputation = loaded,
+            Err(e) => {
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(LogLevel::Error, &format!("声望存档读取失败: {}", e));
+                }
+            }
+        }
+    }
+}
+
+/// 根据资源事件更新对话
+fn sync_dialogues(
+    mut library: ResMut<DialogueLibrary>,
+    mut events: EventReader<AssetEvent<Dialogue>>,
+    assets: Res<Assets<Dialogue>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for event in events.read() {
+        match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                let Some(dialogue) = assets.get(*id) else {
+                    continue;
+                };
+                if let Some(previous) = library.ids.insert(*id, dialogue.id.clone()) {
+                    library.dialogues.remove(&previous);
+                }
+                library
+                    .dialogues
+                    .insert(dialogue.id.clone(), dialogue.clone());
+
+                if let Some(logger) = logger.as_mut() {
+                    for error in dialogue.validate() {
+                        logger.log(LogLevel::Error, &error);
+                    }
+                    logger.log(LogLevel::Debug, &format!("对话已加载: {}", dialogue.id));
+                }
+            }
+            AssetEvent::Removed { id } => {
+                if let Some(previous) = library.ids.remove(id) {
+                    library.dialogues.remove(&previous);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 按名字给没有指定对话的NPC分配对话
+fn assign_dialogues(library: Res<DialogueLibrary>, mut npcs: Query<(&Character, &mut Npc)>) {
+    let refresh = library.is_changed();
+    for (character, mut npc) in npcs.iter_mut() {
+        if npc.dialogue_id.is_some() || !(refresh || npc.is_added()) {
+            continue;
+        }
+        if let Some(dialogue) = library.for_npc(&character.name) {
+            npc.dialogue_id = Some(dialogue.id.clone());
+        }
+    }
+}
+
+/// 与NPC交谈时打开对话，进行中任务的对话优先于NPC自己的对话
+#[allow(clippy::too_many_arguments)]
+fn open_dialogue(
+    library: Res<DialogueLibrary>,
+    quests: Res<QuestManager>,
+    reputation: Res<Reputation>,
+    scripts: Option<Res<ScriptHost>>,
+    mut active: ResMut<ActiveDialogue>,
+    mut talks: EventReader<TalkedToNpc>,
+    mut npcs: Query<&mut Npc>,
+    players: Query<&Inventory, With<Player>>,
+) {
+    if active.session.is_some() {
+        // 对话中按交互键是翻页，不再重新打开
+        talks.clear();
+        return;
+    }
+    let inventory = players.get_single().ok();
+
+    for talk in talks.read() {
+        let Ok(mut npc) = npcs.get_mut(talk.npc) else {
+            continue;
+        };
+        let dialogue = quests
+            .dialogue_for(&talk.name)
+            .into_iter()
+            .find_map(|(_, hook)| hook.dialogue_id.as_deref())
+            .or(npc.dialogue_id.as_deref())
+            .and_then(|id| library.get(id));
+        let Some(dialogue) = dialogue else {
+            continue;
+        };
+
+        let node = dialogue
+            .entries
+            .iter()
+            .find(|entry| {
+                entry.conditions.iter().all(|condition| {
+                    condition.holds(&quests, inventory, &reputation, scripts.as_deref())
+                })
+            })
+            .map_or(&dialogue.start, |entry| &entry.node);
+
+        active.session = Some(DialogueSession {
+            npc: talk.npc,
+            dialogue_id: dialogue.id.clone(),
+            node: node.clone(),
+            entered: false,
+            revealed: 0.0,
+            resume_state: npc.ai_state,
+        });
+        npc.ai_state = AiState::Talk;
+        break;
+    }
+}
+
+/// 对话接下来的走向
+enum Step {
+    Stay,
+    Goto(Option<String>),
+}
+
+/// 推进对话：执行节点效果、打字机显示、翻页与选择选项
+///
+/// 玩家走远、对话或节点被删除时结束对话；结束后NPC恢复原来的AI状态
+#[allow(clippy::too_many_arguments)]
+pub fn advance_dialogue(
+    time: Res<Time>,
+    mut panel_events: EventReader<PanelInteraction>,
+    settings: Res<DialogueSettings>,
+    database: Res<ItemDatabase>,
+    library: Res<DialogueLibrary>,
+    quests: Res<QuestManager>,
+    scripts: Option<Res<ScriptHost>>,
+    mut active: ResMut<ActiveDialogue>,
+    mut reputation: ResMut<Reputation>,
+    mut players: Query<(&Transform, &mut Inventory), With<Player>>,
+    mut npcs: Query<(&Transform, &mut Npc), Without<Player>>,
+    buttons: Query<(&Interaction, &DialogueChoiceButton), Changed<Interaction>>,
+    mut quest_effects: EventWriter<QuestEffectRequest>,
+    mut samples: EventWriter<PlaytestSample>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let pressed = panel_events.read().count() > 0;
+    if active.session.is_none() {
+        return;
+    }
+    let Ok((player_transform, mut inventory)) = players.get_single_mut() else {
+        return;
+    };
+    let Some(session) = active.session.as_mut() else {
+        return;
+    };
+
+    let node = library
+        .get(&session.dialogue_id)
+        .and_then(|dialogue| dialogue.node(&session.node));
+    let in_range = npcs.get(session.npc).is_ok_and(|(transform, _)| {
+        transform
+            .translation
+            .truncate()
+            .distance(player_transform.translation.truncate())
+            <= settings.leave_range
+    });
+
+    let step = match node {
+        // 进入节点的这一帧只执行效果，打开对话的那次按键不会顺带跳过打字
+        Some(node) if in_range && !session.entered => {
+            session.entered = true;
+            for effect in &node.effects {
+                apply_effect(
+                    effect,
+                    session,
+                    &database,
+                    &mut inventory,
+                    &mut reputation,
+                    &mut quest_effects,
+                    &mut samples,
+                    &mut logger,
+                );
+            }
+            Step::Stay
+        }
+        Some(node) if in_range => {
+            let length = node.text.chars().count() as f32;
+            let typing = session.revealed < length;
+            if typing {
+                session.revealed =
+                    (session.revealed + settings.chars_per_second * time.delta_secs()).min(length);
+            }
+
+            let chosen = buttons
+                .iter()
+                .find(|(interaction, _)| **interaction == Interaction::Pressed)
+                .and_then(|(_, button)| node.choices.get(button.0))
+                .filter(|choice| {
+                    choice.conditions.iter().all(|condition| {
+                        condition.holds(&quests, Some(&*inventory), &reputation, scripts.as_deref())
+                    })
+                });
+            let has_choices = node.choices.iter().any(|choice| {
+                choice.conditions.iter().all(|condition| {
+                    condition.holds(&quests, Some(&*inventory), &reputation, scripts.as_deref())
+                })
+            });
+
+            if let Some(choice) = chosen.filter(|_| !typing) {
+                for effect in &choice.effects {
+                    apply_effect(
+                        effect,
+                        session,
+                        &database,
+                        &mut inventory,
+                        &mut reputation,
+                        &mut quest_effects,
+                        &mut samples,
+                        &mut logger,
+                    );
+                }
+                Step::Goto(choice.next.clone())
+            } else if pressed {
+                if typing {
+                    session.revealed = length;
+                    Step::Stay
+                } else if has_choices {
+                    Step::Stay
+                } else {
+                    Step::Goto(node.next.clone())
+                }
+            } else {
+                Step::Stay
+            }
+        }
+        _ => Step::Goto(None),
+    };
+
+    match step {
+        Step::Stay => {}
+        Step::Goto(Some(next)) => {
+            session.node = next;
+            session.entered = false;
+            session.revealed = 0.0;
+        }
+        Step::Goto(None) => {
+            if let Ok((_, mut npc)) = npcs.get_mut(session.npc) {
+                npc.ai_state = session.resume_state;
+            }
+            active.session = None;
+            if let Err(e) = reputation.save(&settings.save_path) {
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(LogLevel::Error, &format!("声望存档写入失败: {}", e));
+                }
+            }
+        }
+    }
+}
+
+/// 执行一个对话效果，任务相关的效果交给任务系统
+#[allow(clippy::too_many_arguments)]
+fn apply_effect(
+    effect: &DialogueEffect,
+    session: &mut DialogueSession,
+    database: &ItemDatabase,
+    inventory: &mut Inventory,
+    reputation: &mut Reputation,
+    quest_effects: &mut EventWriter<QuestEffectRequest>,
+    samples: &mut EventWriter<PlaytestSample>,
+    logger: &mut Option<ResMut<GameLogger>>,
+) {
+    match effect {
+        DialogueEffect::StartQuest { quest_id } => {
+            quest_effects.send(QuestEffectRequest {
+                effect: Effect::StartQuest {
+                    quest_id: quest_id.clone(),
+                },
+            });
+        }
+        DialogueEffect::AdvanceQuest { quest_id } => {
+            quest_effects.send(QuestEffectRequest {
+                effect: Effect::AdvanceQuest {
+                    quest_id: quest_id.clone(),
+                },
+            });
+        }
+        DialogueEffect::GiveItem { item_id, count } => {
+            inventory.add(database, ItemInstance::new(database, item_id, *count));
+        }
+        DialogueEffect::TakeItem { item_id, count } => {
+            inventory.remove(item_id, *count);
+        }
+        DialogueEffect::GiveMoney { amount } => {
+            inventory.money = inventory.money.saturating_add(*amount);
+            samples.send(PlaytestSample::GoldEarned {
+                amount: *amount,
+                source: format!("dialogue:{}", session.dialogue_id),
+            });
+        }
+        DialogueEffect::TakeMoney { amount } => {
+            // 钱不够时只扣到零，按实际扣掉的数上报
+            let spent = (*amount).min(inventory.money);
+            inventory.money -= spent;
+            samples.send(PlaytestSample::GoldSpent {
+                amount: spent,
+                sink: format!("dialogue:{}", session.dialogue_id),
+            });
+        }
+        DialogueEffect::ChangeReputation { faction, amount } => {
+            let value = reputation.change(faction, *amount);
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Info, &format!("{}声望：{}", faction, value));
+            }
+        }
+        DialogueEffect::SetAiState { state } => session.resume_state = *state,
+        DialogueEffect::Script { script, function } => {
+            quest_effects.send(QuestEffectRequest {
+                effect: Effect::Script {
+                    script: script.clone(),
+                    function: function.clone(),
+                },
+            });
+        }
+    }
+}
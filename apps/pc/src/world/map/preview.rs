@@ -0,0 +1,321 @@
+use bevy::prelude::*;
+use image::{Rgb, RgbImage};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{blend_colors, height_to_color, MapManager, TerrainGenerator, WaterManager, Zone};
+use crate::logging::{GameLogger, LogLevel};
+use crate::world::chunk::{world_to_tile, CHUNK_SIZE};
+use crate::world::entity::Player;
+
+/// 地图预览图的默认输出目录
+pub const MAP_PREVIEW_DIR: &str = "reports/map_preview";
+
+/// 水深达到该值时预览图上为最深的颜色
+const PREVIEW_FULL_DEPTH: f32 = 0.2;
+
+/// 单张预览图的最大边长（像素），超出时须加大缩放
+const MAX_PREVIEW_PIXELS: u32 = 8192;
+
+/// 预览图层
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewLayer {
+    Height,
+    Temperature,
+    Moisture,
+    /// 气候区域
+    Biome,
+    Water,
+}
+
+impl PreviewLayer {
+    pub const ALL: [PreviewLayer; 5] = [
+        PreviewLayer::Height,
+        PreviewLayer::Temperature,
+        PreviewLayer::Moisture,
+        PreviewLayer::Biome,
+        PreviewLayer::Water,
+    ];
+
+    /// 图层名，用于文件名与命令参数
+    pub fn name(&self) -> &'static str {
+        match self {
+            PreviewLayer::Height => "height",
+            PreviewLayer::Temperature => "temperature",
+            PreviewLayer::Moisture => "moisture",
+            PreviewLayer::Biome => "biome",
+            PreviewLayer::Water => "water",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|layer| layer.name() == name)
+    }
+}
+
+/// 预览范围
+///
+/// # 参数说明
+/// - origin: 左下角的世界瓦片坐标
+/// - size: 宽高（瓦片）
+/// - scale: 每个像素代表的瓦片边长，大范围预览时降低分辨率
+#[derive(Debug, Clone, Copy)]
+pub struct MapPreviewSpec {
+    pub origin: IVec2,
+    pub size: UVec2,
+    pub scale: u32,
+}
+
+impl MapPreviewSpec {
+    /// 以某个瓦片为中心、边长 2 * radius 的正方形
+    pub fn around(center: IVec2, radius: u32, scale: u32) -> Self {
+        Self {
+            origin: center - IVec2::splat(radius as i32),
+            size: UVec2::splat(radius * 2),
+            scale,
+        }
+    }
+
+    /// 图片尺寸（像素）
+    fn pixel_size(&self) -> UVec2 {
+        let scale = self.scale.max(1);
+        (self.size + UVec2::splat(scale - 1)) / scale
+    }
+}
+
+/// 一个瓦片的采样结果
+struct TileSample {
+    height: f32,
+    temperature: f32,
+    moisture: f32,
+    zone: Zone,
+    water_depth: f32,
+}
+
+/// 一个区块内的湖泊水深与河道下切深度，按区块内坐标索引
+struct ChunkWater {
+    lakes: HashMap<(usize, usize), f32>,
+    rivers: HashMap<(usize, usize), f32>,
+}
+
+/// 按世界坐标采样地形、气候与水系，与区块生成使用同一套生成器与规则
+struct MapSampler<'a> {
+    map_manager: &'a MapManager,
+    terrain: TerrainGenerator,
+    water: WaterManager,
+    /// 当前一行区块的水系，按行采样，换行时清空，大范围预览不会积累
+    chunks: HashMap<IVec2, ChunkWater>,
+    row: i32,
+}
+
+impl<'a> MapSampler<'a> {
+    fn new(map_manager: &'a MapManager) -> Self {
+        let config = map_manager.terrain_config().clone();
+        let mut water = WaterManager::default();
+        water.set_water_level(config.water_level);
+        water.initialize(map_manager.seed);
+        Self {
+            map_manager,
+            terrain: TerrainGenerator::new(map_manager.seed, config),
+            water,
+            chunks: HashMap::new(),
+            row: i32::MIN,
+        }
+    }
+
+    fn sample(&mut self, world: IVec2) -> TileSample {
+        let size = CHUNK_SIZE as i32;
+        let chunk = world.div_euclid(IVec2::splat(size));
+        let local = world.rem_euclid(IVec2::splat(size));
+        let local = (local.x as usize, local.y as usize);
+
+        let water_level = self.terrain.config().water_level;
+        let mut height = self.terrain.generate_height(world.x as f64, world.y as f64);
+        let mut water_depth = (water_level - height).max(0.0);
+        // 与区块生成一致，气候区域按河道下切前的高度划分
+        let zone = self
+            .map_manager
+            .climate_system()
+            .get_climate_zone(world.x, world.y, height);
+
+        let water = self.chunk_water(chunk);
+        if let Some(depth) = water.lakes.get(&local) {
+            water_depth = *depth;
+        }
+        if let Some(carve) = water.rivers.get(&local) {
+            height -= carve;
+            water_depth = water_depth.max(*carve);
+        }
+
+        let climate = self.map_manager.climate_system();
+        TileSample {
+            height,
+            temperature: climate.get_temperature(world.x, world.y),
+            moisture: climate.get_moisture(world.x, world.y),
+            zone,
+            water_depth,
+        }
+    }
+
+    /// 区块内的湖泊与河道，同一行内的区块只计算一次
+    fn chunk_water(&mut self, chunk: IVec2) -> &ChunkWater {
+        if chunk.y != self.row {
+            self.chunks.clear();
+            self.row = chunk.y;
+        }
+        let (terrain, water) = (&self.terrain, &self.water);
+        let water_config = self.map_manager.water_config();
+        self.chunks.entry(chunk).or_insert_with(|| {
+            let size = CHUNK_SIZE as i32;
+            let lakes = if water_config.generate_lakes {
+                water
+                    .lakes_in_chunk(chunk, size, terrain)
+                    .into_iter()
+                    .map(|cell| ((cell.local_x, cell.local_y), cell.depth))
+                    .collect()
+            } else {
+                HashMap::new()
+            };
+            let rivers = if water_config.generate_rivers {
+                let river_depth = terrain.config().river_depth;
+                water
+                    .rivers_in_chunk(chunk, size, terrain)
+                    .into_iter()
+                    .map(|cell| {
+                        // 与区块生成一致：河床中心更深，岸边逐渐变浅
+                        let carve = river_depth * (1.0 - cell.bank_factor * cell.bank_factor);
+                        ((cell.local_x, cell.local_y), carve)
+                    })
+                    .collect()
+            } else {
+                HashMap::new()
+            };
+            ChunkWater { lakes, rivers }
+        })
+    }
+}
+
+/// 导出地图预览图
+///
+/// # 设计思路
+/// 1. 直接按世界坐标采样生成器，不需要加载区块，可以预览任意范围
+/// 2. 各图层共用一次采样，每个瓦片的地形、气候与水系只计算一次
+/// 3. 图片上方为北，与游戏内小地图及预生成的预览图方向一致
+pub fn export_map_preview(
+    map_manager: &MapManager,
+    spec: &MapPreviewSpec,
+    layers: &[PreviewLayer],
+    dir: &Path,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let pixels = spec.pixel_size();
+    if pixels.x == 0 || pixels.y == 0 {
+        return Err("预览范围为空".into());
+    }
+    if pixels.max_element() > MAX_PREVIEW_PIXELS {
+        return Err(format!(
+            "预览图 {}x{} 像素过大（上限 {}），请加大缩放",
+            pixels.x, pixels.y, MAX_PREVIEW_PIXELS
+        )
+        .into());
+    }
+
+    let mut images: Vec<(PreviewLayer, RgbImage)> = layers
+        .iter()
+        .map(|layer| (*layer, RgbImage::new(pixels.x, pixels.y)))
+        .collect();
+    let mut sampler = MapSampler::new(map_manager);
+    let scale = spec.scale.max(1) as i32;
+    for py in 0..pixels.y {
+        for px in 0..pixels.x {
+            let world = spec.origin + IVec2::new(px as i32, py as i32) * scale;
+            let sample = sampler.sample(world);
+            // 图片的行从上到下，世界坐标的 y 向上
+            let row = pixels.y - 1 - py;
+            for (layer, image) in images.iter_mut() {
+                image.put_pixel(px, row, Rgb(layer_color(*layer, &sample)));
+            }
+        }
+    }
+
+    fs::create_dir_all(dir)?;
+    let prefix = format!(
+        "seed{}_{}_{}_{}x{}",
+        map_manager.seed, spec.origin.x, spec.origin.y, spec.size.x, spec.size.y
+    );
+    let mut paths = Vec::with_capacity(images.len());
+    for (layer, image) in images {
+        let path = dir.join(format!("{}_{}.png", prefix, layer.name()));
+        image.save(&path)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+fn layer_color(layer: PreviewLayer, sample: &TileSample) -> [u8; 3] {
+    let (r, g, b) = match layer {
+        PreviewLayer::Height => height_to_color(sample.height),
+        PreviewLayer::Temperature => blend_colors((40, 80, 200), (220, 60, 40), sample.temperature),
+        PreviewLayer::Moisture => blend_colors((190, 160, 100), (30, 90, 200), sample.moisture),
+        PreviewLayer::Biome => sample.zone.color(),
+        PreviewLayer::Water => water_color(sample.water_depth, sample.height),
+    };
+    [r, g, b]
+}
+
+/// 陆地按高度显示为灰度，水面由浅到深显示为蓝色
+fn water_color(depth: f32, height: f32) -> (u8, u8, u8) {
+    if depth <= 0.0 {
+        let gray = (40.0 + height.clamp(0.0, 1.0) * 80.0) as u8;
+        return (gray, gray, gray);
+    }
+    blend_colors((120, 200, 255), (0, 50, 150), depth / PREVIEW_FULL_DEPTH)
+}
+
+/// 请求导出玩家周围的地图预览图
+#[derive(Event, Debug, Clone)]
+pub struct MapPreviewRequest {
+    /// 预览范围的半边长（瓦片）
+    pub radius: u32,
+    /// 每个像素代表的瓦片边长
+    pub scale: u32,
+}
+
+/// 在后台线程导出玩家周围的预览图，不卡住游戏
+pub fn export_requested_previews(
+    mut requests: EventReader<MapPreviewRequest>,
+    map_manager: Res<MapManager>,
+    players: Query<&Transform, With<Player>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let Some(request) = requests.read().last() else {
+        return;
+    };
+    let center = players
+        .get_single()
+        .map(|transform| world_to_tile(transform.translation.truncate()))
+        .unwrap_or(IVec2::ZERO);
+    let spec = MapPreviewSpec::around(center, request.radius, request.scale);
+    if let Some(logger) = logger.as_mut() {
+        logger.log(
+            LogLevel::Info,
+            &format!(
+                "开始导出以 ({}, {}) 为中心、半径 {} 瓦片的地图预览",
+                center.x, center.y, request.radius
+            ),
+        );
+    }
+
+    let map_manager = map_manager.clone();
+    std::thread::spawn(move || {
+        match export_map_preview(
+            &map_manager,
+            &spec,
+            &PreviewLayer::ALL,
+            Path::new(MAP_PREVIEW_DIR),
+        ) {
+            Ok(paths) => info!("地图预览已写入 {}（{} 张）", MAP_PREVIEW_DIR, paths.len()),
+            Err(e) => error!("导出地图预览失败: {}", e),
+        }
+    });
+}
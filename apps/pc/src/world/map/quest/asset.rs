@@ -0,0 +1,92 @@
+use bevy::asset::{io::Reader, Asset, AssetId, AssetLoader, Handle, LoadContext, LoadedFolder};
+use bevy::ecs::system::Resource;
+use bevy::reflect::TypePath;
+use serde::Deserialize;
+use std::collections::HashMap;
+use thiserror::Error;
+
+use super::{Quest, QuestTrigger};
+
+/// 任务资源所在的目录（相对于 assets）
+pub const QUEST_FOLDER: &str = "quests";
+
+/// 任务资源
+///
+/// # 设计思路
+/// 1. 一个任务一个文件：assets/quests 下的 `*.quest.json`，策划改完保存即可在游戏里看到
+/// 2. 接取任务的触发器与任务写在一起，触发器未写关联任务时默认关联本任务
+/// 3. 开发模式下监听文件变化，修改后替换任务定义，已有进度保留
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct QuestAsset {
+    #[serde(flatten)]
+    pub quest: Quest,
+    #[serde(default)]
+    pub triggers: Vec<QuestTrigger>,
+}
+
+/// 任务资源加载错误
+#[derive(Debug, Error)]
+pub enum QuestAssetLoaderError {
+    #[error("读取任务失败: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("解析任务失败: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// 任务资源加载器
+#[derive(Default)]
+pub struct QuestAssetLoader;
+
+impl AssetLoader for QuestAssetLoader {
+    type Asset = QuestAsset;
+    type Settings = ();
+    type Error = QuestAssetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<QuestAsset, QuestAssetLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut asset: QuestAsset = serde_json::from_slice(&bytes)?;
+        for trigger in &mut asset.triggers {
+            if trigger.quest_id.is_empty() {
+                trigger.quest_id = asset.quest.id.clone();
+            }
+        }
+        Ok(asset)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["quest.json"]
+    }
+}
+
+/// 已加载的任务资源
+#[derive(Resource, Default)]
+pub struct QuestLibrary {
+    /// 任务目录句柄，保持目录中的资源不被卸载
+    pub folder: Option<Handle<LoadedFolder>>,
+    /// 资源ID到任务ID的映射，用于处理修改和移除
+    pub ids: HashMap<AssetId<QuestAsset>, String>,
+    /// 按任务ID索引的任务资源
+    pub quests: HashMap<String, QuestAsset>,
+}
+
+impl QuestLibrary {
+    /// 全部任务与触发器，按任务ID排序保证顺序稳定
+    pub fn definitions(&self) -> (Vec<Quest>, Vec<QuestTrigger>) {
+        let mut ids: Vec<&String> = self.quests.keys().collect();
+        ids.sort();
+        let mut quests = Vec::new();
+        let mut triggers = Vec::new();
+        for id in ids {
+            let asset = &self.quests[id];
+            quests.push(asset.quest.clone());
+            triggers.extend(asset.triggers.iter().cloned());
+        }
+        (quests, triggers)
+    }
+}
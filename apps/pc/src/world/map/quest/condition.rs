@@ -1,6 +1,38 @@
-#[derive(Debug, Clone)]
-pub struct Condition {
-    pub id: String,
-    pub title: String,
-    pub description: String,
+use serde::{Deserialize, Serialize};
+
+use crate::world::entity::NpcType;
+
+/// 任务条件
+///
+/// 进入区域、对话、击杀、护送属于一次性的事情，在阶段进行中发生才计数；
+/// 收集物品与任务状态按当下的情况判断
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Condition {
+    /// 进入场景触发区
+    EnterArea { trigger_id: String },
+    /// 与指定名字的NPC交谈
+    TalkTo { npc_name: String },
+    /// 背包中持有足够数量的物品
+    CollectItem { item_id: String, count: u32 },
+    /// 击杀指定类型的NPC
+    Kill { npc_type: NpcType, count: u32 },
+    /// 全程护送指定商队抵达某个站点
+    EscortCaravan { caravan_id: String, stop: String },
+    /// 某个任务已完成
+    QuestCompleted { quest_id: String },
+    /// 某个任务尚未接取
+    QuestNotStarted { quest_id: String },
+}
+
+impl Condition {
+    /// 需要累计的次数，按当下情况判断的条件为 0
+    pub fn required(&self) -> u32 {
+        match self {
+            Condition::EnterArea { .. }
+            | Condition::TalkTo { .. }
+            | Condition::EscortCaravan { .. } => 1,
+            Condition::Kill { count, .. } => (*count).max(1),
+            _ => 0,
+        }
+    }
 }
@@ -1,6 +1,26 @@
-#[derive(Debug, Clone)]
-pub struct Effect {
-    pub id: String,
-    pub title: String,
-    pub description: String,
+use serde::{Deserialize, Serialize};
+
+/// 任务效果，由触发器或阶段完成时执行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Effect {
+    /// 接取任务
+    StartQuest { quest_id: String },
+    /// 直接推进任务到下一阶段
+    AdvanceQuest { quest_id: String },
+    /// 任务失败
+    FailQuest { quest_id: String },
+    /// 给予物品，背包放不下的部分丢弃
+    GiveItem { item_id: String, count: u32 },
+    /// 收走物品，数量不足时不收
+    TakeItem { item_id: String, count: u32 },
+    /// 给予银两
+    GiveMoney { amount: u32 },
+    /// 显示一条提示
+    Message { text: String },
+    /// 执行脚本，function 为空时执行脚本的顶层语句；脚本提出的指令随后落实
+    Script {
+        script: String,
+        #[serde(default)]
+        function: Option<String>,
+    },
 }
@@ -0,0 +1,425 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use super::{Condition, DialogueHook, Quest, QuestTrigger};
+use crate::items::Inventory;
+use crate::world::entity::NpcType;
+
+/// 任务进度存档路径
+pub const QUEST_SAVE_PATH: &str = "saves/quests.json";
+
+/// 任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuestStatus {
+    Active,
+    Completed,
+    Failed,
+}
+
+/// 单个任务的进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestState {
+    /// 当前阶段
+    pub stage: u32,
+    /// 当前阶段各目标的累计次数，与阶段目标一一对应
+    pub counters: Vec<u32>,
+    pub status: QuestStatus,
+    /// 接取时的累计游玩时长（秒），旧进度存档没有记录
+    #[serde(default)]
+    pub started_at: Option<f64>,
+}
+
+/// 驱动任务的游戏事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuestSignal {
+    EnteredArea(String),
+    TalkedTo(String),
+    Killed(NpcType),
+    /// 护送的商队到站：商队编号与站点名
+    EscortedCaravan(String, String),
+}
+
+impl QuestSignal {
+    /// 是否满足需要累计的条件
+    fn matches(&self, condition: &Condition) -> bool {
+        match (self, condition) {
+            (QuestSignal::EnteredArea(id), Condition::EnterArea { trigger_id }) => id == trigger_id,
+            (QuestSignal::TalkedTo(name), Condition::TalkTo { npc_name }) => name == npc_name,
+            (QuestSignal::Killed(kind), Condition::Kill { npc_type, .. }) => kind == npc_type,
+            (
+                QuestSignal::EscortedCaravan(caravan, arrived),
+                Condition::EscortCaravan { caravan_id, stop },
+            ) => caravan == caravan_id && arrived == stop,
+            _ => false,
+        }
+    }
+}
+
+/// 任务进度变化事件
+#[derive(Event, Debug, Clone)]
+pub struct QuestUpdated {
+    pub quest_id: String,
+    pub stage: u32,
+    pub status: QuestStatus,
+    /// 由其他玩家的进度同步而来，不再转发
+    pub remote: bool,
+}
+
+/// 阶段完成的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageOutcome {
+    /// 达成的分支，阶段没有分支时为 None
+    pub branch: Option<usize>,
+}
+
+/// 任务进度存档格式
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct QuestSaveFile {
+    states: HashMap<String, QuestState>,
+    /// 已触发过的一次性触发器
+    fired: HashSet<String>,
+}
+
+/// 任务管理器
+///
+/// # 设计思路
+/// 1. 定义与进度分开：定义来自任务资源，进度单独存档，修改任务文本不影响存档
+/// 2. 事件驱动：进入区域、对话、击杀作为信号累计到当前阶段的目标上，
+///    物品与任务状态类条件在检查时按当下情况判断
+/// 3. 触发器负责接取任务等剧情跳转，阶段目标负责任务内部的推进
+#[derive(Resource, Debug, Default)]
+pub struct QuestManager {
+    quests: HashMap<String, Quest>,
+    triggers: Vec<QuestTrigger>,
+    states: HashMap<String, QuestState>,
+    fired: HashSet<String>,
+    /// 触发器剩余冷却（秒）
+    cooldowns: HashMap<String, f32>,
+}
+
+impl QuestManager {
+    /// 替换任务定义，已有进度保留
+    ///
+    /// 热重载后阶段可能增删，进度停在已不存在的阶段时回到最后一个阶段，
+    /// 目标数量变化时该阶段的进度重新累计
+    pub fn set_definitions(&mut self, quests: Vec<Quest>, mut triggers: Vec<QuestTrigger>) {
+        self.quests = quests
+            .into_iter()
+            .map(|quest| (quest.id.clone(), quest))
+            .collect();
+        triggers.sort_by_key(|trigger| Reverse(trigger.priority));
+        self.triggers = triggers;
+
+        for (id, state) in self.states.iter_mut() {
+            let Some(quest) = self.quests.get(id) else {
+                continue;
+            };
+            if state.status != QuestStatus::Active || quest.stages.is_empty() {
+                continue;
+            }
+            state.stage = state.stage.min(quest.stages.len() as u32 - 1);
+            let counters = stage_counters(quest, state.stage);
+            if counters.len() != state.counters.len() {
+                state.counters = counters;
+            }
+        }
+    }
+
+    /// 读取进度存档
+    pub fn load_progress(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let data: QuestSaveFile = serde_json::from_str(&content)?;
+        self.states = data.states;
+        self.fired = data.fired;
+        Ok(())
+    }
+
+    /// 清空进度，开始新游戏时调用，任务定义保留
+    pub fn reset_progress(&mut self) {
+        self.states.clear();
+        self.fired.clear();
+        self.cooldowns.clear();
+    }
+
+    /// 写入进度存档
+    pub fn save_progress(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let data = QuestSaveFile {
+            states: self.states.clone(),
+            fired: self.fired.clone(),
+        };
+        fs::write(path, serde_json::to_string_pretty(&data)?)?;
+        Ok(())
+    }
+
+    pub fn quest(&self, id: &str) -> Option<&Quest> {
+        self.quests.get(id)
+    }
+
+    pub fn state(&self, id: &str) -> Option<&QuestState> {
+        self.states.get(id)
+    }
+
+    /// 记下任务的接取时间，已记录过的不覆盖
+    pub fn mark_started(&mut self, id: &str, playtime: f64) {
+        if let Some(state) = self.states.get_mut(id) {
+            state.started_at.get_or_insert(playtime);
+        }
+    }
+
+    /// 已接取的全部任务的进度
+    pub fn states(&self) -> impl Iterator<Item = (&String, &QuestState)> {
+        self.states.iter()
+    }
+
+    /// 进行中的任务
+    pub fn active(&self) -> impl Iterator<Item = (&Quest, &QuestState)> {
+        self.states
+            .iter()
+            .filter(|(_, state)| state.status == QuestStatus::Active)
+            .filter_map(|(id, state)| Some((self.quests.get(id)?, state)))
+    }
+
+    /// 接取任务，已接取过或不存在时返回 None
+    pub fn start(&mut self, id: &str) -> Option<QuestUpdated> {
+        if self.states.contains_key(id) {
+            return None;
+        }
+        let quest = self.quests.get(id)?;
+        let counters = stage_counters(quest, 0);
+        self.states.insert(
+            id.to_string(),
+            QuestState {
+                stage: 0,
+                counters,
+                status: QuestStatus::Active,
+                started_at: None,
+            },
+        );
+        Some(update(id, 0, QuestStatus::Active))
+    }
+
+    /// 把信号累计到进行中任务的当前阶段目标上
+    pub fn record(&mut self, signal: &QuestSignal) {
+        for (id, state) in self.states.iter_mut() {
+            if state.status != QuestStatus::Active {
+                continue;
+            }
+            let Some(stage) = self
+                .quests
+                .get(id)
+                .and_then(|quest| quest.stage(state.stage))
+            else {
+                continue;
+            };
+            for (objective, counter) in stage.all_objectives().zip(state.counters.iter_mut()) {
+                if signal.matches(objective) && *counter < objective.required() {
+                    *counter += 1;
+                }
+            }
+        }
+    }
+
+    /// 当前阶段是否完成，完成时返回走的分支
+    pub fn stage_outcome(&self, id: &str, inventory: Option<&Inventory>) -> Option<StageOutcome> {
+        let state = self.states.get(id)?;
+        if state.status != QuestStatus::Active {
+            return None;
+        }
+        let stage = self
+            .quests
+            .get(id)
+            .and_then(|quest| quest.stage(state.stage))?;
+        let met = |index: usize, objective: &Condition| match objective.required() {
+            0 => self.holds(objective, inventory),
+            required => state.counters.get(index).copied().unwrap_or(0) >= required,
+        };
+
+        let all_met = stage
+            .objectives
+            .iter()
+            .enumerate()
+            .all(|(index, objective)| met(index, objective));
+        if !all_met {
+            return None;
+        }
+        if stage.branches.is_empty() {
+            return Some(StageOutcome { branch: None });
+        }
+
+        let mut offset = stage.objectives.len();
+        for (branch_index, branch) in stage.branches.iter().enumerate() {
+            let branch_met = branch
+                .objectives
+                .iter()
+                .enumerate()
+                .all(|(index, objective)| met(offset + index, objective));
+            if branch_met {
+                return Some(StageOutcome {
+                    branch: Some(branch_index),
+                });
+            }
+            offset += branch.objectives.len();
+        }
+        None
+    }
+
+    /// 进行中任务当前阶段在该NPC处的台词
+    pub fn dialogue_for(&self, npc_name: &str) -> Vec<(&Quest, &DialogueHook)> {
+        self.active()
+            .filter_map(|(quest, state)| Some((quest, quest.stage(state.stage)?)))
+            .flat_map(|(quest, stage)| {
+                stage
+                    .dialogue
+                    .iter()
+                    .filter(move |hook| hook.npc_name == npc_name)
+                    .map(move |hook| (quest, hook))
+            })
+            .collect()
+    }
+
+    /// 按当下情况判断的条件是否成立
+    fn holds(&self, condition: &Condition, inventory: Option<&Inventory>) -> bool {
+        match condition {
+            Condition::CollectItem { item_id, count } => {
+                inventory.is_some_and(|inventory| inventory.count(item_id) >= *count)
+            }
+            Condition::QuestCompleted { quest_id } => self
+                .states
+                .get(quest_id)
+                .is_some_and(|state| state.status == QuestStatus::Completed),
+            Condition::QuestNotStarted { quest_id } => !self.states.contains_key(quest_id),
+            _ => false,
+        }
+    }
+
+    /// 按当前阶段的去向推进
+    pub fn advance(&mut self, id: &str) -> Option<QuestUpdated> {
+        let next = self
+            .states
+            .get(id)
+            .and_then(|state| self.quests.get(id)?.stage(state.stage))
+            .and_then(|stage| stage.next.clone());
+        self.advance_to(id, next.as_deref())
+    }
+
+    /// 推进到指定阶段，为空时进入下一阶段；越过最后一个阶段时任务完成
+    pub fn advance_to(&mut self, id: &str, next: Option<&str>) -> Option<QuestUpdated> {
+        let quest = self.quests.get(id)?;
+        let state = self.states.get_mut(id)?;
+        if state.status != QuestStatus::Active {
+            return None;
+        }
+        state.stage = next
+            .and_then(|next| quest.stage_index(next))
+            .unwrap_or(state.stage + 1);
+        if state.stage as usize >= quest.stages.len() {
+            state.status = QuestStatus::Completed;
+            state.counters.clear();
+        } else {
+            state.counters = stage_counters(quest, state.stage);
+        }
+        Some(update(id, state.stage, state.status))
+    }
+
+    /// 任务失败
+    pub fn fail(&mut self, id: &str) -> Option<QuestUpdated> {
+        let state = self.states.get_mut(id)?;
+        if state.status != QuestStatus::Active {
+            return None;
+        }
+        state.status = QuestStatus::Failed;
+        Some(update(id, state.stage, state.status))
+    }
+
+    /// 同步其他玩家的进度，只会向前推进
+    pub fn sync(&mut self, id: &str, stage: u32, completed: bool) -> Option<QuestUpdated> {
+        let quest = self.quests.get(id)?;
+        let started = self.states.contains_key(id);
+        let state = self.states.entry(id.to_string()).or_insert(QuestState {
+            stage: 0,
+            counters: stage_counters(quest, 0),
+            status: QuestStatus::Active,
+            started_at: None,
+        });
+        let behind =
+            state.status == QuestStatus::Active && (completed || stage > state.stage || !started);
+        if !behind {
+            return None;
+        }
+        if completed {
+            state.stage = quest.stages.len() as u32;
+            state.status = QuestStatus::Completed;
+            state.counters.clear();
+        } else {
+            state.stage = stage;
+            state.counters = stage_counters(quest, stage);
+        }
+        let mut updated = update(id, state.stage, state.status);
+        updated.remote = true;
+        Some(updated)
+    }
+
+    /// 推进冷却计时，返回本次满足条件的触发器
+    ///
+    /// 触发器的累计类条件只看本帧的信号，不跨帧累计
+    pub fn ready_triggers(
+        &mut self,
+        delta: f32,
+        signals: &[QuestSignal],
+        inventory: Option<&Inventory>,
+    ) -> Vec<QuestTrigger> {
+        self.cooldowns.retain(|_, remaining| {
+            *remaining -= delta;
+            *remaining > 0.0
+        });
+
+        let ready: Vec<QuestTrigger> = self
+            .triggers
+            .iter()
+            .filter(|trigger| !(trigger.one_time && self.fired.contains(&trigger.id)))
+            .filter(|trigger| !self.cooldowns.contains_key(&trigger.id))
+            .filter(|trigger| {
+                trigger
+                    .conditions
+                    .iter()
+                    .all(|condition| match condition.required() {
+                        0 => self.holds(condition, inventory),
+                        _ => signals.iter().any(|signal| signal.matches(condition)),
+                    })
+            })
+            .cloned()
+            .collect();
+
+        for trigger in &ready {
+            if trigger.one_time {
+                self.fired.insert(trigger.id.clone());
+            }
+            if trigger.cooldown > 0.0 {
+                self.cooldowns.insert(trigger.id.clone(), trigger.cooldown);
+            }
+        }
+        ready
+    }
+}
+
+fn stage_counters(quest: &Quest, stage: u32) -> Vec<u32> {
+    quest
+        .stage(stage)
+        .map_or(Vec::new(), |stage| vec![0; stage.all_objectives().count()])
+}
+
+fn update(id: &str, stage: u32, status: QuestStatus) -> QuestUpdated {
+    QuestUpdated {
+        quest_id: id.to_string(),
+        stage,
+        status,
+        remote: false,
+    }
+}
@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+/// 任务标记
+///
+/// 任务目标在地图上的位置，由任务逻辑登记，地图与罗盘只负责显示
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuestMarker {
+    /// 所属任务ID
+    pub quest_id: String,
+    /// 显示文字
+    pub label: String,
+    /// 世界瓦片坐标
+    pub position: IVec2,
+}
+
+/// 当前所有任务标记
+#[derive(Resource, Debug, Default)]
+pub struct QuestMarkers {
+    markers: Vec<QuestMarker>,
+    /// 正在追踪的任务
+    tracked: Option<String>,
+}
+
+impl QuestMarkers {
+    /// 替换某个任务的全部标记
+    pub fn set(&mut self, quest_id: &str, markers: impl IntoIterator<Item = (String, IVec2)>) {
+        self.clear(quest_id);
+        self.markers
+            .extend(markers.into_iter().map(|(label, position)| QuestMarker {
+                quest_id: quest_id.to_string(),
+                label,
+                position,
+            }));
+    }
+
+    /// 移除某个任务的标记，正在追踪时一并取消追踪
+    pub fn clear(&mut self, quest_id: &str) {
+        self.markers.retain(|marker| marker.quest_id != quest_id);
+        if self.tracked.as_deref() == Some(quest_id) {
+            self.tracked = None;
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &QuestMarker> {
+        self.markers.iter()
+    }
+
+    /// 追踪某个任务，传入空值取消追踪
+    pub fn track(&mut self, quest_id: Option<&str>) {
+        self.tracked = quest_id.map(str::to_string);
+    }
+
+    pub fn tracked(&self) -> Option<&str> {
+        self.tracked.as_deref()
+    }
+
+    /// 正在追踪的任务的标记
+    pub fn tracked_markers(&self) -> impl Iterator<Item = &QuestMarker> {
+        self.markers
+            .iter()
+            .filter(|marker| Some(marker.quest_id.as_str()) == self.tracked.as_deref())
+    }
+}
@@ -1,11 +1,31 @@
+/// 任务模块
+///
+/// # 模块组成
+/// 1. quest：任务、阶段与分支定义
+/// 2. condition / effect / reward / trigger：条件、效果、奖励与触发器
+/// 3. asset：任务资源与加载器，开发模式下可热重载
+/// 4. marker：地图上的任务标记
+/// 5. manager：任务进度与推进规则
+/// 6. systems：任务插件，把游戏事件转成任务信号
+mod asset;
 mod condition;
 mod effect;
+mod manager;
+mod marker;
+#[allow(clippy::module_inception)]
 mod quest;
 mod reward;
+mod systems;
 mod trigger;
 
+pub use asset::*;
 pub use condition::*;
 pub use effect::*;
+pub use manager::*;
+pub use marker::*;
 pub use quest::*;
 pub use reward::*;
+pub use systems::{
+    detect_npc_talk, run_quests, QuestEffectRequest, QuestPlugin, QuestSettings, TalkedToNpc,
+};
 pub use trigger::*;
@@ -1,9 +1,132 @@
-use super::Reward;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+use super::{Condition, Effect, Reward};
+
+/// 阶段目标在地图上的标记
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageMarker {
+    pub label: String,
+    /// 世界瓦片坐标
+    pub position: [i32; 2],
+}
+
+/// 阶段进行中与NPC交谈时说的话，对话系统据此插入任务台词
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DialogueHook {
+    pub npc_name: String,
+    /// 台词，按顺序说完
+    #[serde(default)]
+    pub lines: Vec<String>,
+    /// 改用对话数据中的整段对话
+    #[serde(default)]
+    pub dialogue_id: Option<String>,
+}
+
+/// 阶段分支
+///
+/// 玩家的不同选择通向不同的阶段，先达成哪个分支的目标就走哪条路
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageBranch {
+    pub description: String,
+    #[serde(default)]
+    pub objectives: Vec<Condition>,
+    /// 走这个分支时额外执行的效果
+    #[serde(default)]
+    pub effects: Vec<Effect>,
+    /// 跳转到的阶段ID，为空时沿用阶段的去向
+    #[serde(default)]
+    pub next: Option<String>,
+    /// 走这个分支任务失败
+    #[serde(default)]
+    pub fails: bool,
+}
+
+/// 任务阶段
+///
+/// 目标全部达成后执行阶段效果，进入下一阶段；有分支时还需达成其中一个分支。
+/// 去向为空时按顺序进入下一阶段，最后一个阶段完成即任务完成
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestStage {
+    /// 阶段ID，供分支跳转引用
+    #[serde(default)]
+    pub id: String,
+    pub description: String,
+    /// 目标，需全部达成
+    #[serde(default)]
+    pub objectives: Vec<Condition>,
+    /// 分支，需达成其中一个
+    #[serde(default)]
+    pub branches: Vec<StageBranch>,
+    /// 阶段完成时执行的效果
+    #[serde(default)]
+    pub effects: Vec<Effect>,
+    /// 下一阶段的ID
+    #[serde(default)]
+    pub next: Option<String>,
+    /// 进行该阶段时显示的地图标记
+    #[serde(default)]
+    pub markers: Vec<StageMarker>,
+    /// 任务台词
+    #[serde(default)]
+    pub dialogue: Vec<DialogueHook>,
+}
+
+impl QuestStage {
+    /// 全部需要记录进度的条件，先是阶段目标，再依次是各分支的目标
+    pub fn all_objectives(&self) -> impl Iterator<Item = &Condition> {
+        self.objectives.iter().chain(
+            self.branches
+                .iter()
+                .flat_map(|branch| branch.objectives.iter()),
+        )
+    }
+}
+
+/// 任务定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Quest {
     pub id: String,
     pub title: String,
     pub description: String,
+    pub stages: Vec<QuestStage>,
+    #[serde(default)]
     pub rewards: Vec<Reward>,
 }
+
+impl Quest {
+    pub fn stage(&self, index: u32) -> Option<&QuestStage> {
+        self.stages.get(index as usize)
+    }
+
+    /// 按阶段ID查找阶段序号
+    pub fn stage_index(&self, id: &str) -> Option<u32> {
+        self.stages
+            .iter()
+            .position(|stage| stage.id == id)
+            .map(|index| index as u32)
+    }
+
+    /// 检查阶段跳转，返回引用了不存在阶段的描述
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (index, stage) in self.stages.iter().enumerate() {
+            let targets = stage.next.iter().chain(
+                stage
+                    .branches
+                    .iter()
+                    .filter_map(|branch| branch.next.as_ref()),
+            );
+            for target in targets {
+                if self.stage_index(target).is_none() {
+                    errors.push(format!(
+                        "任务 {} 第{}阶段跳转到不存在的阶段 {}",
+                        self.id,
+                        index + 1,
+                        target
+                    ));
+                }
+            }
+        }
+        errors
+    }
+}
@@ -1,6 +1,10 @@
-#[derive(Debug, Clone)]
-pub struct Reward {
-    pub id: String,
-    pub title: String,
-    pub description: String,
+use serde::{Deserialize, Serialize};
+
+/// 任务奖励，任务完成时发放
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Reward {
+    Money { amount: u32 },
+    Experience { amount: u32 },
+    SkillPoints { amount: u32 },
+    Item { item_id: String, count: u32 },
 }
@@ -0,0 +1,548 @@
+use bevy::prelude::*;
+use std::path::Path;
+
+use super::{
+    Effect, QuestAsset, QuestAssetLoader, QuestLibrary, QuestManager, QuestMarkers, QuestSignal,
+    QuestStatus, QuestUpdated, Reward, StageOutcome, QUEST_FOLDER, QUEST_SAVE_PATH,
+};
+use crate::analytics::PlaytestSample;
+use crate::audio::{DialogueAudio, MomentEvent, MomentKind};
+use crate::combat::DeathEvent;
+use crate::coop::{LocalQuestProgress, RemoteQuestProgress};
+use crate::interaction::{Interacted, InteractionKind};
+use crate::items::{Inventory, ItemDatabase, ItemInstance};
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::gameplay_running;
+use crate::save::{LoadEvent, Playtime, SaveEvent, SaveSet};
+use crate::scripting::ScriptCall;
+use crate::world::caravan::CaravanEvent;
+use crate::world::entity::{Character, Npc, Player};
+use crate::world::map::SceneTriggerEntered;
+
+/// 任务配置
+#[derive(Resource, Debug, Clone)]
+pub struct QuestSettings {
+    /// 与NPC交谈的距离
+    pub talk_range: f32,
+    /// 任务台词每个字显示的秒数
+    pub seconds_per_char: f32,
+    /// 进度存档路径
+    pub save_path: String,
+}
+
+impl Default for QuestSettings {
+    fn default() -> Self {
+        Self {
+            talk_range: 48.0,
+            seconds_per_char: 0.15,
+            save_path: QUEST_SAVE_PATH.to_string(),
+        }
+    }
+}
+
+/// 玩家与NPC交谈
+#[derive(Event, Debug, Clone)]
+pub struct TalkedToNpc {
+    pub npc: Entity,
+    pub name: String,
+}
+
+/// 由任务以外的玩法（如对话）请求执行的任务效果
+#[derive(Event, Debug, Clone)]
+pub struct QuestEffectRequest {
+    pub effect: Effect,
+}
+
+/// 任务插件
+pub struct QuestPlugin;
+
+impl Plugin for QuestPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<QuestAsset>()
+            .init_asset_loader::<QuestAssetLoader>()
+            .init_resource::<QuestSettings>()
+            .init_resource::<QuestLibrary>()
+            .init_resource::<QuestManager>()
+            .init_resource::<QuestMarkers>()
+            .add_event::<QuestUpdated>()
+            .add_event::<TalkedToNpc>()
+            .add_event::<QuestEffectRequest>()
+            .add_event::<SceneTriggerEntered>()
+            .add_systems(Startup, load_quests)
+            .add_systems(
+                Update,
+                (
+                    sync_quest_assets,
+                    detect_npc_talk,
+                    run_quests,
+                    sync_remote_quest_progress,
+                    publish_quest_updates,
+                )
+                    .chain()
+                    .run_if(gameplay_running),
+            )
+            .add_systems(PreUpdate, flush_quest_progress.in_set(SaveSet::Flush));
+    }
+}
+
+/// 开始加载任务目录并读取进度存档
+fn load_quests(
+    settings: Res<QuestSettings>,
+    asset_server: Res<AssetServer>,
+    mut library: ResMut<QuestLibrary>,
+    mut manager: ResMut<QuestManager>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    library.folder = Some(asset_server.load_folder(QUEST_FOLDER));
+    if Path::new(&settings.save_path).exists() {
+        if let Err(e) = manager.load_progress(&settings.save_path) {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("任务存档读取失败: {}", e));
+            }
+        }
+    }
+}
+
+/// 根据资源事件更新任务定义
+fn sync_quest_assets(
+    mut library: ResMut<QuestLibrary>,
+    mut events: EventReader<AssetEvent<QuestAsset>>,
+    assets: Res<Assets<QuestAsset>>,
+    mut manager: ResMut<QuestManager>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let mut changed = false;
+    for event in events.read() {
+        match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                let Some(asset) = assets.get(*id) else {
+                    continue;
+                };
+                let quest_id = asset.quest.id.clone();
+                if let Some(previous) = library.ids.insert(*id, quest_id.clone()) {
+                    library.quests.remove(&previous);
+                }
+                library.quests.insert(quest_id, asset.clone());
+                changed = true;
+
+                if let Some(logger) = logger.as_mut() {
+                    for error in asset.quest.validate() {
+                        logger.log(LogLevel::Error, &error);
+                    }
+                    logger.log(
+                        LogLevel::Info,
+                        &format!("任务已加载: {} ({})", asset.quest.title, asset.quest.id),
+                    );
+                }
+            }
+            AssetEvent::Removed { id } => {
+                if let Some(previous) = library.ids.remove(id) {
+                    library.quests.remove(&previous);
+                    changed = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if changed {
+        let (quests, triggers) = library.definitions();
+        manager.set_definitions(quests, triggers);
+    }
+}
+
+/// 交互键作用到NPC时与其交谈
+pub fn detect_npc_talk(
+    mut interacted: EventReader<Interacted>,
+    npcs: Query<&Character, With<Npc>>,
+    mut talked: EventWriter<TalkedToNpc>,
+) {
+    for event in interacted.read() {
+        if event.kind != InteractionKind::Talk {
+            continue;
+        }
+        if let Ok(character) = npcs.get(event.entity) {
+            talked.send(TalkedToNpc {
+                npc: event.entity,
+                name: character.name.clone(),
+            });
+        }
+    }
+}
+
+/// 收集信号，执行触发器，推进任务阶段并发放奖励
+#[allow(clippy::too_many_arguments)]
+pub fn run_quests(
+    time: Res<Time>,
+    settings: Res<QuestSettings>,
+    database: Res<ItemDatabase>,
+    mut manager: ResMut<QuestManager>,
+    mut areas: EventReader<SceneTriggerEntered>,
+    mut talks: EventReader<TalkedToNpc>,
+    mut deaths: EventReader<DeathEvent>,
+    mut caravans: EventReader<CaravanEvent>,
+    mut requests: EventReader<QuestEffectRequest>,
+    npcs: Query<&Npc>,
+    mut players: Query<(Entity, &mut Player, &mut Inventory)>,
+    mut updates: EventWriter<QuestUpdated>,
+    mut dialogue: EventWriter<DialogueAudio>,
+    mut scripts: EventWriter<ScriptCall>,
+    mut samples: EventWriter<PlaytestSample>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let mut player = players.get_single_mut().ok();
+    let player_entity = player.as_ref().map(|(entity, _, _)| *entity);
+
+    let mut signals: Vec<QuestSignal> = Vec::new();
+    for entered in areas.read() {
+        signals.push(QuestSignal::EnteredArea(entered.id.clone()));
+    }
+    for talk in talks.read() {
+        // 先说当前阶段的台词，再把交谈计入目标
+        for (_, hook) in manager.dialogue_for(&talk.name) {
+            for line in &hook.lines {
+                dialogue.send(DialogueAudio {
+                    speaker: talk.name.clone(),
+                    text: line.clone(),
+                    duration: 1.0 + line.chars().count() as f32 * settings.seconds_per_char,
+                });
+            }
+        }
+        signals.push(QuestSignal::TalkedTo(talk.name.clone()));
+    }
+    for death in deaths.read() {
+        if death.killer.is_none() || death.killer != player_entity {
+            continue;
+        }
+        if let Ok(npc) = npcs.get(death.entity) {
+            signals.push(QuestSignal::Killed(npc.npc_type));
+        }
+    }
+    for event in caravans.read() {
+        if let CaravanEvent::Arrived {
+            caravan,
+            stop,
+            escorted: true,
+        } = event
+        {
+            signals.push(QuestSignal::EscortedCaravan(caravan.clone(), stop.clone()));
+        }
+    }
+
+    for signal in &signals {
+        manager.record(signal);
+    }
+
+    let mut pending: Vec<Effect> = requests
+        .read()
+        .map(|request| request.effect.clone())
+        .collect();
+    let inventory = player.as_ref().map(|(_, _, inventory)| &**inventory);
+    for trigger in manager.ready_triggers(time.delta_secs(), &signals, inventory) {
+        pending.extend(trigger.effects);
+    }
+
+    // 效果可能接取或推进其他任务，循环到没有新的变化为止；
+    // 限制轮数，防止任务之间互相推进导致死循环
+    for _ in 0..8 {
+        for effect in pending.drain(..) {
+            let inventory = player.as_mut().map(|(_, _, inventory)| &mut **inventory);
+            let Some(update) = apply_effect(
+                &mut manager,
+                &database,
+                inventory,
+                &effect,
+                &mut scripts,
+                &mut samples,
+                &mut logger,
+            ) else {
+                continue;
+            };
+            if update.status == QuestStatus::Completed {
+                if let (Some(quest), Some((_, player, inventory))) =
+                    (manager.quest(&update.quest_id), player.as_mut())
+                {
+                    for reward in &quest.rewards {
+                        grant_reward(
+                            &database,
+                            player,
+                            inventory,
+                            &quest.id,
+                            reward,
+                            &mut samples,
+                        );
+                    }
+                }
+            }
+            updates.send(update);
+        }
+
+        let inventory = player.as_ref().map(|(_, _, inventory)| &**inventory);
+        let done: Vec<(String, StageOutcome)> = manager
+            .active()
+            .filter_map(|(quest, _)| {
+                let outcome = manager.stage_outcome(&quest.id, inventory)?;
+                Some((quest.id.clone(), outcome))
+            })
+            .collect();
+        if done.is_empty() {
+            break;
+        }
+
+        for (id, outcome) in done {
+            let Some(quest) = manager.quest(&id).cloned() else {
+                continue;
+            };
+            let Some(stage) = manager
+                .state(&id)
+                .and_then(|state| quest.stage(state.stage))
+            else {
+                continue;
+            };
+            pending.extend(stage.effects.iter().cloned());
+
+            let branch = outcome.branch.and_then(|index| stage.branches.get(index));
+            let update = match branch {
+                Some(branch) => {
+                    pending.extend(branch.effects.iter().cloned());
+                    if branch.fails {
+                        manager.fail(&id)
+                    } else {
+                        let next = branch.next.as_ref().or(stage.next.as_ref());
+                        manager.advance_to(&id, next.map(String::as_str))
+                    }
+                }
+                None => manager.advance(&id),
+            };
+            let Some(update) = update else {
+                continue;
+            };
+            if update.status == QuestStatus::Completed {
+                if let Some((_, player, inventory)) = player.as_mut() {
+                    for reward in &quest.rewards {
+                        grant_reward(
+                            &database,
+                            player,
+                            inventory,
+                            &quest.id,
+                            reward,
+                            &mut samples,
+                        );
+                    }
+                }
+            }
+            updates.send(update);
+        }
+    }
+}
+
+/// 执行一个任务效果
+fn apply_effect(
+    manager: &mut QuestManager,
+    database: &ItemDatabase,
+    inventory: Option<&mut Inventory>,
+    effect: &Effect,
+    scripts: &mut EventWriter<ScriptCall>,
+    samples: &mut EventWriter<PlaytestSample>,
+    logger: &mut Option<ResMut<GameLogger>>,
+) -> Option<QuestUpdated> {
+    match effect {
+        Effect::StartQuest { quest_id } => manager.start(quest_id),
+        Effect::AdvanceQuest { quest_id } => manager.advance(quest_id),
+        Effect::FailQuest { quest_id } => manager.fail(quest_id),
+        Effect::GiveItem { item_id, count } => {
+            if let Some(inventory) = inventory {
+                inventory.add(database, ItemInstance::new(database, item_id, *count));
+            }
+            None
+        }
+        Effect::TakeItem { item_id, count } => {
+            if let Some(inventory) = inventory {
+                inventory.remove(item_id, *count);
+            }
+            None
+        }
+        Effect::GiveMoney { amount } => {
+            if let Some(inventory) = inventory {
+                inventory.money = inventory.money.saturating_add(*amount);
+                samples.send(PlaytestSample::GoldEarned {
+                    amount: *amount,
+                    source: "quest_effect".to_string(),
+                });
+            }
+            None
+        }
+        Effect::Message { text } => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Info, text);
+            }
+            None
+        }
+        Effect::Script { script, function } => {
+            scripts.send(ScriptCall {
+                script: script.clone(),
+                function: function.clone(),
+            });
+            None
+        }
+    }
+}
+
+/// 发放一项任务奖励
+fn grant_reward(
+    database: &ItemDatabase,
+    player: &mut Player,
+    inventory: &mut Inventory,
+    quest_id: &str,
+    reward: &Reward,
+    samples: &mut EventWriter<PlaytestSample>,
+) {
+    match reward {
+        Reward::Money { amount } => {
+            inventory.money = inventory.money.saturating_add(*amount);
+            samples.send(PlaytestSample::GoldEarned {
+                amount: *amount,
+                source: format!("quest:{}", quest_id),
+            });
+        }
+        Reward::Experience { amount } => {
+            player.experience = player.experience.saturating_add(*amount)
+        }
+        Reward::SkillPoints { amount } => {
+            player.skill_points = player.skill_points.saturating_add(*amount)
+        }
+        Reward::Item { item_id, count } => {
+            inventory.add(database, ItemInstance::new(database, item_id, *count));
+        }
+    }
+}
+
+/// 按共享规则同步其他玩家推进的任务
+fn sync_remote_quest_progress(
+    mut manager: ResMut<QuestManager>,
+    mut remote: EventReader<RemoteQuestProgress>,
+    mut updates: EventWriter<QuestUpdated>,
+) {
+    for progress in remote.read() {
+        if let Some(update) = manager.sync(&progress.quest_id, progress.stage, progress.completed) {
+            updates.send(update);
+        }
+    }
+}
+
+/// 任务进度变化后更新地图标记、转发给联机玩家、上报完成用时并写入存档，
+/// 完成任务时发出完成时刻，奏响短乐
+///
+/// 接取时记下累计游玩时长，完成时用它算出用时；旧存档里没有接取时间的任务不上报
+#[allow(clippy::too_many_arguments)]
+fn publish_quest_updates(
+    settings: Res<QuestSettings>,
+    playtime: Res<Playtime>,
+    mut manager: ResMut<QuestManager>,
+    mut markers: ResMut<QuestMarkers>,
+    mut updates: EventReader<QuestUpdated>,
+    mut local: EventWriter<LocalQuestProgress>,
+    mut samples: EventWriter<PlaytestSample>,
+    mut moments: EventWriter<MomentEvent>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let mut changed = false;
+    for update in updates.read() {
+        changed = true;
+        if update.status == QuestStatus::Active {
+            manager.mark_started(&update.quest_id, playtime.seconds);
+        }
+        let Some(quest) = manager.quest(&update.quest_id) else {
+            continue;
+        };
+
+        if update.status == QuestStatus::Completed {
+            moments.send(MomentEvent::with_label(
+                MomentKind::QuestCompleted,
+                quest.title.clone(),
+            ));
+        }
+
+        if update.status == QuestStatus::Completed && !update.remote {
+            let started_at = manager
+                .state(&update.quest_id)
+                .and_then(|state| state.started_at);
+            if let Some(started_at) = started_at {
+                samples.send(PlaytestSample::QuestCompleted {
+                    quest: quest.id.clone(),
+                    duration_secs: (playtime.seconds - started_at).max(0.0) as f32,
+                });
+            }
+        }
+
+        match update.status {
+            QuestStatus::Active => {
+                let stage_markers = quest.stage(update.stage).map_or(Vec::new(), |stage| {
+                    stage
+                        .markers
+                        .iter()
+                        .map(|marker| (marker.label.clone(), IVec2::from_array(marker.position)))
+                        .collect()
+                });
+                markers.set(&quest.id, stage_markers);
+                // 没有追踪中的任务时自动追踪刚推进的任务，罗盘与地图才有目标可指
+                if markers.tracked().is_none() {
+                    markers.track(Some(&quest.id));
+                }
+            }
+            QuestStatus::Completed | QuestStatus::Failed => markers.clear(&quest.id),
+        }
+
+        if !update.remote {
+            local.send(LocalQuestProgress {
+                quest_id: update.quest_id.clone(),
+                stage: update.stage,
+                completed: update.status == QuestStatus::Completed,
+            });
+        }
+
+        if let Some(logger) = logger.as_mut() {
+            let message = match update.status {
+                QuestStatus::Active if update.stage == 0 => format!("接取任务：{}", quest.title),
+                QuestStatus::Active => {
+                    let description = quest
+                        .stage(update.stage)
+                        .map_or("", |stage| stage.description.as_str());
+                    format!("{}：{}", quest.title, description)
+                }
+                QuestStatus::Completed => format!("完成任务：{}", quest.title),
+                QuestStatus::Failed => format!("任务失败：{}", quest.title),
+            };
+            logger.log(LogLevel::Info, &message);
+        }
+    }
+
+    if changed {
+        if let Err(e) = manager.save_progress(&settings.save_path) {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("任务存档写入失败: {}", e));
+            }
+        }
+    }
+}
+
+/// 存档或读档时把任务进度写入进度存档
+///
+/// 存档前写一次，进度存档与存档槽保持一致；读档后写一次，重新启动游戏时接着读入的进度
+fn flush_quest_progress(
+    settings: Res<QuestSettings>,
+    manager: Res<QuestManager>,
+    mut saves: EventReader<SaveEvent>,
+    mut loads: EventReader<LoadEvent>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let flush = saves.read().count() + loads.read().count() > 0;
+    if !flush {
+        return;
+    }
+    if let Err(e) = manager.save_progress(&settings.save_path) {
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Error, &format!("任务存档写入失败: {}", e));
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 use super::{Condition, Effect};
 
 /// 任务触发器
@@ -6,18 +8,24 @@ use super::{Condition, Effect};
 /// 1. 定义触发条件和效果
 /// 2. 控制任务的流程
 /// 3. 管理任务状态
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuestTrigger {
+    /// 触发器ID，用于记录一次性触发与冷却
+    pub id: String,
     /// 触发条件
     pub conditions: Vec<Condition>,
     /// 触发效果
     pub effects: Vec<Effect>,
-    /// 任务ID
+    /// 关联的任务ID
+    #[serde(default)]
     pub quest_id: String,
-    /// 触发优先级
+    /// 触发优先级，同一时刻满足条件时高的先执行
+    #[serde(default)]
     pub priority: i32,
-    /// 触发冷却时间
+    /// 触发冷却时间（秒）
+    #[serde(default)]
     pub cooldown: f32,
     /// 是否一次性触发
+    #[serde(default)]
     pub one_time: bool,
 }
@@ -1,4 +1,16 @@
-use super::{area::TerrainConfig, Climate, MapManager, Vegetation, Water};
+use super::{
+    area::TerrainConfig, export_requested_previews, Climate, FixedSceneRef, FixedSceneTable,
+    MapManager, MapPreviewRequest, QuestMarkers, ScenePrefab, ScenePrefabLoader,
+    ScenePrefabRegistry, SceneTrigger, SceneTriggerEntered, SceneTriggerIndex, Vegetation, Water,
+    FIXED_SCENES_PATH, SCENE_PREFAB_FOLDER,
+};
+use crate::config::resolve_data_path;
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::gameplay_running;
+use crate::time::{GameCalendar, SeasonChanged};
+use crate::world::chunk::TILE_PIXELS;
+use crate::world::entity::Player;
+use bevy::asset::RecursiveDependencyLoadState;
 use bevy::prelude::*;
 
 /// 地图系统插件
@@ -8,12 +20,37 @@ impl Plugin for MapSystemPlugin {
     fn build(&self, app: &mut App) {
         // 注册资源
         app.init_resource::<MapManager>()
-            .add_systems(Startup, setup_map_system);
+            .add_systems(Startup, setup_map_system)
+            .add_systems(Update, apply_season_change);
+
+        // 任务标记
+        app.init_resource::<QuestMarkers>();
+
+        // 地图预览导出
+        app.add_event::<MapPreviewRequest>()
+            .add_systems(Update, export_requested_previews);
+
+        // 预制场景
+        app.init_asset::<ScenePrefab>()
+            .init_asset_loader::<ScenePrefabLoader>()
+            .init_resource::<ScenePrefabRegistry>()
+            .init_resource::<SceneTriggerIndex>()
+            .add_event::<SceneTriggerEntered>()
+            .add_systems(Startup, load_scene_prefabs)
+            .add_systems(
+                Update,
+                (
+                    sync_scene_prefabs,
+                    (index_scene_triggers, detect_scene_triggers)
+                        .chain()
+                        .run_if(gameplay_running),
+                ),
+            );
     }
 }
 
 /// 设置地图系统
-fn setup_map_system(mut commands: Commands, mut map_manager: ResMut<MapManager>) {
+fn setup_map_system(mut map_manager: ResMut<MapManager>, calendar: Option<Res<GameCalendar>>) {
     // 设置随机种子
     let seed = rand::random::<u32>();
     *map_manager = MapManager::new(seed);
@@ -34,9 +71,203 @@ fn setup_map_system(mut commands: Commands, mut map_manager: ResMut<MapManager>)
     let climate_config = Climate::default();
     map_manager.update_climate_config(climate_config);
 
+    // 季节与历法保持一致
+    if let Some(calendar) = calendar {
+        map_manager.set_season(calendar.season());
+    }
+
+    // 固定场景
+    match FixedSceneTable::load(&resolve_data_path(FIXED_SCENES_PATH)) {
+        Ok(table) => {
+            for entry in table.scenes {
+                map_manager.add_fixed_scene(IVec2::from_array(entry.position), entry.scene);
+            }
+        }
+        Err(e) => warn!("加载固定场景配置失败: {}", e),
+    }
+
     // 启用2.5D效果
     map_manager.set_enable_2_5d(true);
     map_manager.set_height_scale(0.5);
 
     info!("地图系统已初始化，种子: {}", seed);
 }
+
+/// 历法换季时更新气候系统
+fn apply_season_change(
+    mut map_manager: ResMut<MapManager>,
+    mut season_events: EventReader<SeasonChanged>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for event in season_events.read() {
+        if !map_manager.set_season(event.current) {
+            continue;
+        }
+
+        if let Some(logger) = logger.as_mut() {
+            logger.log(
+                LogLevel::Info,
+                &format!(
+                    "第{}年入{}，气候缓存已清空",
+                    event.year,
+                    event.current.name()
+                ),
+            );
+        }
+    }
+}
+
+/// 预制场景是否已加载完毕，区块生成要等固定场景的预制布局就绪
+pub fn scene_prefabs_ready(registry: Option<Res<ScenePrefabRegistry>>) -> bool {
+    registry.is_none_or(|registry| registry.ready)
+}
+
+/// 开始加载预制场景目录
+fn load_scene_prefabs(asset_server: Res<AssetServer>, mut registry: ResMut<ScenePrefabRegistry>) {
+    registry.folder = Some(asset_server.load_folder(SCENE_PREFAB_FOLDER));
+}
+
+/// 根据资源事件更新预制场景注册表
+fn sync_scene_prefabs(
+    mut registry: ResMut<ScenePrefabRegistry>,
+    mut events: EventReader<AssetEvent<ScenePrefab>>,
+    prefabs: Res<Assets<ScenePrefab>>,
+    asset_server: Res<AssetServer>,
+    map_manager: Res<MapManager>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    for event in events.read() {
+        match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                let Some(prefab) = prefabs.get(*id) else {
+                    continue;
+                };
+                if let Some(previous) = registry.ids.insert(*id, prefab.id.clone()) {
+                    registry.prefabs.remove(&previous);
+                }
+                registry.prefabs.insert(prefab.id.clone(), prefab.clone());
+
+                if let Some(logger) = logger.as_mut() {
+                    logger.log(
+                        LogLevel::Info,
+                        &format!("预制场景已加载: {} ({})", prefab.name, prefab.id),
+                    );
+                }
+            }
+            AssetEvent::Removed { id } => {
+                if let Some(previous) = registry.ids.remove(id) {
+                    registry.prefabs.remove(&previous);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if registry.ready {
+        return;
+    }
+    let Some(folder) = registry.folder.clone() else {
+        return;
+    };
+
+    match asset_server.get_recursive_dependency_load_state(&folder) {
+        Some(RecursiveDependencyLoadState::Loaded) => {
+            registry.ready = true;
+
+            // 检查固定场景引用的预制是否都存在
+            for (pos, scene) in map_manager.fixed_scenes() {
+                if let FixedSceneRef::Prefab(id) = scene {
+                    if registry.get(id).is_none() {
+                        if let Some(logger) = logger.as_mut() {
+                            logger.log(
+                                LogLevel::Error,
+                                &format!(
+                                    "固定场景 ({}, {}) 引用了不存在的预制: {}",
+                                    pos.x, pos.y, id
+                                ),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        Some(RecursiveDependencyLoadState::Failed(error)) => {
+            registry.ready = true;
+            if let Some(logger) = logger.as_mut() {
+                logger.log(
+                    LogLevel::Error,
+                    &format!("预制场景加载失败: {}，引用预制的固定场景将被跳过", error),
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 维护场景触发区索引
+///
+/// 触发区是区块的子实体，世界坐标在变换传播后才确定，因此按全局变换的变化登记
+fn index_scene_triggers(
+    mut index: ResMut<SceneTriggerIndex>,
+    triggers: Query<(Entity, &SceneTrigger, &GlobalTransform), Changed<GlobalTransform>>,
+    mut removed: RemovedComponents<SceneTrigger>,
+) {
+    for entity in removed.read() {
+        index.remove(entity);
+    }
+    for (entity, trigger, transform) in triggers.iter() {
+        let tile = (transform.translation().truncate() / TILE_PIXELS)
+            .floor()
+            .as_ivec2();
+        index.insert(entity, tile, trigger.radius);
+    }
+}
+
+/// 检测玩家进入场景触发区，只检查索引中玩家附近的触发区
+fn detect_scene_triggers(
+    mut index: ResMut<SceneTriggerIndex>,
+    mut triggers: Query<(&mut SceneTrigger, &GlobalTransform)>,
+    player: Query<&Transform, With<Player>>,
+    mut entered: EventWriter<SceneTriggerEntered>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let position = player.translation.truncate();
+    let tile = (position / TILE_PIXELS).floor().as_ivec2();
+
+    // 玩家与触发区各自取整到瓦片，多留两格余量
+    let reach = index.max_radius / TILE_PIXELS + 2.0;
+    let mut nearby: Vec<Entity> = index
+        .index
+        .query_radius(tile, reach)
+        .into_iter()
+        .map(|(_, entity)| *entity)
+        .collect();
+    // 刚离开的触发区也要复位
+    for occupied in &index.occupied {
+        if !nearby.contains(occupied) {
+            nearby.push(*occupied);
+        }
+    }
+
+    let mut occupied = Vec::new();
+    for entity in nearby {
+        let Ok((mut trigger, transform)) = triggers.get_mut(entity) else {
+            continue;
+        };
+        let inside = position.distance(transform.translation().truncate()) <= trigger.radius;
+        if inside && !trigger.inside {
+            entered.send(SceneTriggerEntered {
+                id: trigger.id.clone(),
+            });
+        }
+        if trigger.inside != inside {
+            trigger.inside = inside;
+        }
+        if inside {
+            occupied.push(entity);
+        }
+    }
+    index.occupied = occupied;
+}
@@ -1,13 +1,9 @@
 mod physics;
-mod properties;
 mod render;
-mod tile;
 mod tile_type;
 mod util;
 
 pub use physics::*;
-pub use properties::*;
 pub use render::*;
-pub use tile::*;
 pub use tile_type::*;
 pub use util::*;
@@ -10,112 +10,69 @@ use super::TileType;
 #[derive(Component)]
 pub struct Render {
     pub color: Color,
-    pub z_index: f32,
-    pub variant: u8, // 用于选择不同的贴图变体
 }
 
 impl Default for Render {
     fn default() -> Self {
         Self {
             color: Color::WHITE,
-            z_index: 0.0,
-            variant: 0,
         }
     }
 }
 
 impl Render {
-    /// 获取瓦片颜色
-    pub fn get_color(&self) -> Color {
-        self.color
-    }
-
-    /// 获取瓦片Z轴索引
-    pub fn get_z_index(&self) -> f32 {
-        self.z_index
-    }
-
-    /// 获取瓦片变体
-    pub fn get_variant(&self) -> u8 {
-        self.variant
-    }
-
     pub fn from_tile_type(tile_type: TileType) -> Self {
         match tile_type {
             TileType::Empty => Self {
                 color: Color::BLACK,
-                z_index: 0.0,
-                variant: 0,
             },
             TileType::Ground => Self {
-                color: Color::rgb(0.0, 0.5, 0.0),
-                z_index: 0.0,
-                variant: 0,
+                color: Color::srgb(0.0, 0.5, 0.0),
             },
             TileType::Wall => Self {
-                color: Color::rgb(0.5, 0.5, 0.5),
-                z_index: 0.0,
-                variant: 0,
+                color: Color::srgb(0.5, 0.5, 0.5),
             },
             TileType::Water => Self {
-                color: Color::rgb(0.0, 0.0, 0.5),
-                z_index: 0.0,
-                variant: 0,
+                color: Color::srgb(0.0, 0.0, 0.5),
             },
             TileType::Grass => Self {
-                color: Color::rgb(0.0, 0.5, 0.0),
-                z_index: 0.0,
-                variant: 0,
+                color: Color::srgb(0.0, 0.5, 0.0),
             },
             TileType::Sand => Self {
-                color: Color::rgb(0.8, 0.8, 0.0),
-                z_index: 0.0,
-                variant: 0,
+                color: Color::srgb(0.8, 0.8, 0.0),
             },
             TileType::Rock => Self {
-                color: Color::rgb(0.5, 0.5, 0.5),
-                z_index: 0.0,
-                variant: 0,
+                color: Color::srgb(0.5, 0.5, 0.5),
             },
             TileType::Snow => Self {
-                color: Color::rgb(1.0, 1.0, 1.0),
-                z_index: 0.0,
-                variant: 0,
+                color: Color::srgb(1.0, 1.0, 1.0),
             },
             TileType::Forest => Self {
-                color: Color::rgb(0.0, 0.5, 0.0),
-                z_index: 0.0,
-                variant: 0,
+                color: Color::srgb(0.0, 0.5, 0.0),
             },
             TileType::Path => Self {
-                color: Color::rgb(0.0, 0.0, 0.0),
-                z_index: 0.0,
-                variant: 0,
+                color: Color::srgb(0.0, 0.0, 0.0),
             },
             TileType::Plains => Self {
-                color: Color::rgb(0.0, 0.5, 0.0),
-                z_index: 0.0,
-                variant: 0,
+                color: Color::srgb(0.0, 0.5, 0.0),
             },
             TileType::Wasteland => Self {
-                color: Color::rgb(0.5, 0.5, 0.5),
-                z_index: 0.0,
-                variant: 0,
+                color: Color::srgb(0.5, 0.5, 0.5),
             },
             TileType::Bamboo => Self {
-                color: Color::rgb(0.0, 0.5, 0.0),
-                z_index: 0.0,
-                variant: 0,
+                color: Color::srgb(0.0, 0.5, 0.0),
             },
             TileType::DenseForest => Self {
-                color: Color::rgb(0.0, 0.5, 0.0),
-                z_index: 0.0,
-                variant: 0,
+                color: Color::srgb(0.0, 0.5, 0.0),
             },
             TileType::Mountain => Self {
-                color: Color::rgb(0.5, 0.5, 0.5),
-                z_index: 0.0,
-                variant: 0,
+                color: Color::srgb(0.5, 0.5, 0.5),
+            },
+            TileType::Floor => Self {
+                color: Color::srgb(0.7, 0.55, 0.35),
+            },
+            TileType::Door => Self {
+                color: Color::srgb(0.45, 0.28, 0.15),
             },
         }
     }
@@ -1,5 +1,7 @@
+use serde::{Deserialize, Serialize};
+
 /// 地图瓦片基础类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TileType {
     Empty,       // 空地块
     Ground,      // 一般地面
@@ -16,4 +18,44 @@ pub enum TileType {
     Bamboo,      // 竹林
     DenseForest, // 密林
     Mountain,    // 山地
+    Floor,       // 室内地板
+    Door,        // 门
+}
+
+impl TileType {
+    /// 从区块数据中存储的编号还原瓦片类型
+    pub fn from_u8(value: u8) -> Option<Self> {
+        const ALL: [TileType; 17] = [
+            TileType::Empty,
+            TileType::Ground,
+            TileType::Wall,
+            TileType::Water,
+            TileType::Grass,
+            TileType::Sand,
+            TileType::Rock,
+            TileType::Snow,
+            TileType::Forest,
+            TileType::Path,
+            TileType::Plains,
+            TileType::Wasteland,
+            TileType::Bamboo,
+            TileType::DenseForest,
+            TileType::Mountain,
+            TileType::Floor,
+            TileType::Door,
+        ];
+        ALL.get(value as usize).copied()
+    }
+
+    /// 是否为植被瓦片，植被颜色随季节变化
+    pub fn is_vegetation(&self) -> bool {
+        matches!(
+            self,
+            TileType::Grass
+                | TileType::Forest
+                | TileType::Plains
+                | TileType::Bamboo
+                | TileType::DenseForest
+        )
+    }
 }
@@ -1,10 +1,9 @@
-mod density;
 mod rule;
 mod system;
+#[allow(clippy::module_inception)]
 mod vegetation;
 mod vegetation_type;
 
-pub use density::*;
 pub use rule::*;
 pub use system::*;
 pub use vegetation::*;
@@ -1,9 +1,9 @@
 use super::super::EnvironmentCompatibility;
-use super::{density::VegetationDensity, vegetation_type::VegetationType, Rule};
+use super::{vegetation_type::VegetationType, Rule};
 use bevy::utils::HashMap;
 use rand::Rng;
 use rand_chacha::rand_core::SeedableRng;
-use rand_chacha::{rand_core::RngCore, ChaCha8Rng};
+use rand_chacha::ChaCha8Rng;
 
 /// 植被系统
 ///
@@ -169,6 +169,9 @@ impl System {
             return None;
         }
 
+        // 规则表的遍历顺序每次启动都可能不同，先按类型排好，同一位置每次都选出同样的植被
+        candidates.sort_by_key(|(veg_type, _)| *veg_type as u8);
+
         // 保存最适合的候选项（排序前）
         let best_candidate = candidates[0];
 
@@ -191,45 +194,6 @@ impl System {
         Some(best_candidate.0)
     }
 
-    /// 获取指定区域的植被密度
-    pub fn get_density(
-        &self,
-        x: i32,
-        y: i32,
-        radius: i32,
-        height: f32,
-        temperature: f32,
-        moisture: f32,
-    ) -> VegetationDensity {
-        let mut count = 0;
-        let total = (2 * radius + 1).pow(2);
-
-        for dx in -radius..=radius {
-            for dy in -radius..=radius {
-                if self
-                    .get_vegetation_at(x + dx, y + dy, height, temperature, moisture)
-                    .is_some()
-                {
-                    count += 1;
-                }
-            }
-        }
-
-        let density_ratio = count as f32 / total as f32;
-
-        if density_ratio < 0.05 {
-            VegetationDensity::None
-        } else if density_ratio < 0.2 {
-            VegetationDensity::Sparse
-        } else if density_ratio < 0.4 {
-            VegetationDensity::Medium
-        } else if density_ratio < 0.7 {
-            VegetationDensity::Dense
-        } else {
-            VegetationDensity::VeryDense
-        }
-    }
-
     /// 检查植被是否与环境兼容
     fn check_compatibility(
         &self,
@@ -308,13 +272,21 @@ impl System {
 
         if value < ideal_min {
             // 从最低可生存值到理想最低值之间线性插值
-            return (value - survive_min) / (ideal_min - survive_min);
+            (value - survive_min) / (ideal_min - survive_min)
         } else {
             // 从理想最高值到最高可生存值之间线性插值
-            return (survive_max - value) / (survive_max - ideal_max);
+            (survive_max - value) / (survive_max - ideal_max)
         }
     }
 
+    /// 位置相关的确定性随机值 (0.0-1.0)，与植被类型的判定相互独立
+    ///
+    /// 供区块生成时决定是否放置、放置偏移等使用，同一位置每次结果相同
+    pub fn placement_roll(&self, x: i32, y: i32) -> f32 {
+        let mut rng = self.make_rng_from_position(x.wrapping_add(7919), y.wrapping_sub(104_729));
+        rng.gen::<f32>()
+    }
+
     /// 生成位置相关的随机数生成器
     fn make_rng_from_position(&self, x: i32, y: i32) -> ChaCha8Rng {
         let combined_seed = self
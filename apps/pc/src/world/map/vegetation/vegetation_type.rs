@@ -1,5 +1,7 @@
+use serde::{Deserialize, Serialize};
+
 /// 植被类型枚举
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum VegetationType {
     Grass,    // 草地
     Flower,   // 花丛
@@ -11,3 +13,39 @@ pub enum VegetationType {
     Willow,   // 柳树
     DeadTree, // 枯树
 }
+
+impl VegetationType {
+    /// 从区块装饰层存储的编号还原植被类型
+    pub fn from_u8(value: u8) -> Option<Self> {
+        const ALL: [VegetationType; 9] = [
+            VegetationType::Grass,
+            VegetationType::Flower,
+            VegetationType::Bush,
+            VegetationType::Bamboo,
+            VegetationType::Pine,
+            VegetationType::Oak,
+            VegetationType::Maple,
+            VegetationType::Willow,
+            VegetationType::DeadTree,
+        ];
+        ALL.get(value as usize).copied()
+    }
+
+    /// 是否为树木（含竹子）
+    pub fn is_tree(&self) -> bool {
+        matches!(
+            self,
+            VegetationType::Bamboo
+                | VegetationType::Pine
+                | VegetationType::Oak
+                | VegetationType::Maple
+                | VegetationType::Willow
+                | VegetationType::DeadTree
+        )
+    }
+
+    /// 是否四季常青，常青植被换季时变色较弱
+    pub fn is_evergreen(&self) -> bool {
+        matches!(self, VegetationType::Bamboo | VegetationType::Pine)
+    }
+}
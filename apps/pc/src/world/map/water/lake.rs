@@ -6,10 +6,6 @@ pub struct Lake {
     pub min_size: i32,
     /// 最大湖泊尺寸
     pub max_size: i32,
-    /// 深度变化
-    pub depth_variation: f32,
-    /// 岸线复杂度
-    pub shore_complexity: f32,
 }
 
 impl Default for Lake {
@@ -18,8 +14,6 @@ impl Default for Lake {
             frequency: 0.05,
             min_size: 5,
             max_size: 15,
-            depth_variation: 0.2,
-            shore_complexity: 0.3,
         }
     }
 }
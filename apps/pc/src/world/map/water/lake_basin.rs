@@ -0,0 +1,378 @@
+use bevy::math::IVec2;
+use rand::Rng;
+use rand_chacha::{rand_core::SeedableRng, ChaChaRng};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::sync::Arc;
+
+use super::super::{area::TerrainGenerator, LruCache};
+use super::Lake;
+
+/// 湖泊种子区域边长（瓦片）
+pub const LAKE_REGION_SIZE: i32 = 128;
+
+/// 区域湖泊缓存容量，超出后淘汰最久未访问的区域
+const LAKE_REGION_CACHE_CAPACITY: usize = 128;
+
+/// 从种子点下行寻找洼地的最大步数
+///
+/// 与湖泊最大半径一起决定一个区域的湖泊可能覆盖的范围，
+/// 两者之和必须小于 `LAKE_REGION_SIZE`，查询时只需检查相邻区域
+const MAX_DESCENT_STEPS: u32 = 48;
+
+/// 8邻域偏移
+const NEIGHBORS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+/// 一个湖盆
+#[derive(Debug, Clone)]
+pub struct LakeBasin {
+    /// 湖面覆盖的瓦片及其水深
+    pub cells: HashMap<IVec2, f32>,
+    /// 包围盒（含）
+    pub min: IVec2,
+    pub max: IVec2,
+}
+
+impl LakeBasin {
+    /// 包围盒是否与矩形相交
+    fn intersects(&self, min: IVec2, max: IVec2) -> bool {
+        self.min.x <= max.x && self.max.x >= min.x && self.min.y <= max.y && self.max.y >= min.y
+    }
+}
+
+/// 落在某个区块内的湖泊瓦片
+#[derive(Debug, Clone, Copy)]
+pub struct LakeCell {
+    /// 区块内坐标
+    pub local_x: usize,
+    pub local_y: usize,
+    /// 水深
+    pub depth: f32,
+}
+
+/// 洪水填充用的优先队列元素，按高度升序出队
+#[derive(Debug, Clone, Copy)]
+struct FillEntry {
+    height: f32,
+    position: IVec2,
+}
+
+impl PartialEq for FillEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for FillEntry {}
+
+impl PartialOrd for FillEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FillEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // BinaryHeap 是大顶堆，反转比较得到最低点优先
+        other.height.total_cmp(&self.height).then_with(|| {
+            (other.position.x, other.position.y).cmp(&(self.position.x, self.position.y))
+        })
+    }
+}
+
+/// 基于真实高度图的湖泊生成器
+///
+/// # 设计思路
+/// 1. 选点：每个区域用确定性随机数撒下若干种子点
+/// 2. 寻洼：种子点沿最陡下坡走到局部最低点
+/// 3. 填充：从最低点按高度优先向外扩张，水位随之上升，
+///    一旦出队的瓦片低于当前水位，说明越过了湖盆的出水口，填充结束
+///
+/// 这样得到的湖面是一个等高面，湖岸线与地形等高线一致，
+/// 不会出现噪声阈值带来的零散水点
+#[derive(Debug, Clone)]
+pub struct LakeNetwork {
+    /// 种子
+    pub seed: u64,
+    /// 海平面，低于该高度的洼地已经是海，不再生成湖泊
+    pub water_level: f32,
+    /// 湖泊参数
+    pub params: Lake,
+    /// 区域湖泊缓存
+    region_cache: Arc<LruCache<IVec2, Arc<Vec<LakeBasin>>>>,
+}
+
+impl Default for LakeNetwork {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            water_level: 0.3,
+            params: Lake::default(),
+            region_cache: Arc::new(LruCache::new(LAKE_REGION_CACHE_CAPACITY)),
+        }
+    }
+}
+
+impl LakeNetwork {
+    /// 使用新的种子初始化，清空缓存
+    pub fn initialize(&mut self, seed: u64) {
+        self.seed = seed;
+        self.region_cache.clear();
+    }
+
+    /// 查询一个区块内的湖泊瓦片
+    pub fn cells_in_chunk(
+        &self,
+        chunk: IVec2,
+        chunk_size: i32,
+        terrain: &TerrainGenerator,
+    ) -> Vec<LakeCell> {
+        let origin = chunk * chunk_size;
+        let max = origin + IVec2::splat(chunk_size - 1);
+
+        let mut cells: HashMap<(usize, usize), LakeCell> = HashMap::new();
+
+        for basins in self.basins_near(origin, max, terrain) {
+            for basin in basins.iter().filter(|b| b.intersects(origin, max)) {
+                for (position, depth) in &basin.cells {
+                    if position.x < origin.x
+                        || position.x > max.x
+                        || position.y < origin.y
+                        || position.y > max.y
+                    {
+                        continue;
+                    }
+
+                    let local = (
+                        (position.x - origin.x) as usize,
+                        (position.y - origin.y) as usize,
+                    );
+                    let cell = LakeCell {
+                        local_x: local.0,
+                        local_y: local.1,
+                        depth: *depth,
+                    };
+
+                    // 相邻区域可能找到同一个湖盆，保留更深的记录
+                    cells
+                        .entry(local)
+                        .and_modify(|existing| {
+                            if cell.depth > existing.depth {
+                                *existing = cell;
+                            }
+                        })
+                        .or_insert(cell);
+                }
+            }
+        }
+
+        cells.into_values().collect()
+    }
+
+    /// 查询某个世界坐标的湖水深度，不在湖中返回 None
+    pub fn depth_at(&self, world: IVec2, terrain: &TerrainGenerator) -> Option<f32> {
+        self.basins_near(world, world, terrain)
+            .iter()
+            .flat_map(|basins| basins.iter())
+            .filter_map(|basin| basin.cells.get(&world).copied())
+            .reduce(f32::max)
+    }
+
+    /// 可能覆盖矩形范围的全部湖盆
+    fn basins_near(
+        &self,
+        min: IVec2,
+        max: IVec2,
+        terrain: &TerrainGenerator,
+    ) -> Vec<Arc<Vec<LakeBasin>>> {
+        let min_region = Self::region_of(min) - IVec2::ONE;
+        let max_region = Self::region_of(max) + IVec2::ONE;
+
+        let mut result = Vec::new();
+        for ry in min_region.y..=max_region.y {
+            for rx in min_region.x..=max_region.x {
+                result.push(self.region_lakes(IVec2::new(rx, ry), terrain));
+            }
+        }
+        result
+    }
+
+    /// 获取某个区域内的全部湖泊（带缓存）
+    pub fn region_lakes(&self, region: IVec2, terrain: &TerrainGenerator) -> Arc<Vec<LakeBasin>> {
+        self.region_cache
+            .get_or_insert_with(region, || Arc::new(self.generate_region(region, terrain)))
+    }
+
+    /// 世界坐标所在的区域
+    pub fn region_of(world: IVec2) -> IVec2 {
+        IVec2::new(
+            world.x.div_euclid(LAKE_REGION_SIZE),
+            world.y.div_euclid(LAKE_REGION_SIZE),
+        )
+    }
+
+    /// 生成一个区域内的湖泊
+    fn generate_region(&self, region: IVec2, terrain: &TerrainGenerator) -> Vec<LakeBasin> {
+        let mut rng = self.make_rng_for_region(region);
+        let origin = region * LAKE_REGION_SIZE;
+
+        // 频率换算为每个区域的种子点数
+        let attempts = ((self.params.frequency * 100.0).round() as u32).max(1);
+
+        let mut basins: Vec<LakeBasin> = Vec::new();
+        let mut used_minima: HashSet<IVec2> = HashSet::new();
+
+        for _ in 0..attempts {
+            let seed_point = origin
+                + IVec2::new(
+                    rng.gen_range(0..LAKE_REGION_SIZE),
+                    rng.gen_range(0..LAKE_REGION_SIZE),
+                );
+
+            let lowest = self.descend(seed_point, terrain);
+            if !used_minima.insert(lowest) {
+                continue;
+            }
+
+            // 已被其他湖泊淹没的洼地不再重复填充
+            if basins.iter().any(|b| b.cells.contains_key(&lowest)) {
+                continue;
+            }
+
+            if let Some(basin) = self.flood_fill(lowest, terrain) {
+                basins.push(basin);
+            }
+        }
+
+        basins
+    }
+
+    /// 沿最陡下坡走到局部最低点
+    fn descend(&self, start: IVec2, terrain: &TerrainGenerator) -> IVec2 {
+        let height = |p: IVec2| terrain.generate_height(p.x as f64, p.y as f64);
+
+        let mut current = start;
+        let mut current_height = height(current);
+
+        for _ in 0..MAX_DESCENT_STEPS {
+            let next = NEIGHBORS
+                .iter()
+                .map(|offset| {
+                    let p = current + *offset;
+                    (p, height(p))
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+
+            match next {
+                Some((p, h)) if h < current_height => {
+                    current = p;
+                    current_height = h;
+                }
+                _ => break,
+            }
+        }
+
+        current
+    }
+
+    /// 从洼地最低点按高度优先填充，直到溢出或达到最大尺寸
+    fn flood_fill(&self, lowest: IVec2, terrain: &TerrainGenerator) -> Option<LakeBasin> {
+        let mut heights: HashMap<IVec2, f32> = HashMap::new();
+        let mut height = |p: IVec2| {
+            *heights
+                .entry(p)
+                .or_insert_with(|| terrain.generate_height(p.x as f64, p.y as f64))
+        };
+
+        let lowest_height = height(lowest);
+
+        // 海平面以下的洼地属于海
+        if lowest_height < self.water_level {
+            return None;
+        }
+
+        let max_radius = self.params.max_size.max(1) as f32 * 2.0;
+        let max_cells = (std::f32::consts::PI * max_radius * max_radius) as usize;
+        let min_cells = (self.params.min_size.max(1) * self.params.min_size.max(1)) as usize;
+
+        let mut visited: HashSet<IVec2> = HashSet::new();
+        let mut heap = BinaryHeap::new();
+        let mut filled: Vec<(IVec2, f32)> = Vec::new();
+        let mut level = lowest_height;
+
+        visited.insert(lowest);
+        heap.push(FillEntry {
+            height: lowest_height,
+            position: lowest,
+        });
+
+        while let Some(entry) = heap.pop() {
+            // 出队瓦片低于水位：越过了出水口，湖面就停在当前水位
+            if entry.height < level {
+                break;
+            }
+
+            // 达到最大尺寸时停止上涨，此时湖面低于湖盆边缘
+            if filled.len() >= max_cells
+                || (entry.position - lowest).as_vec2().length() > max_radius
+            {
+                break;
+            }
+
+            level = entry.height;
+            filled.push((entry.position, entry.height));
+
+            for offset in NEIGHBORS {
+                let next = entry.position + offset;
+                if visited.insert(next) {
+                    heap.push(FillEntry {
+                        height: height(next),
+                        position: next,
+                    });
+                }
+            }
+        }
+
+        // 水位线上的瓦片是湖岸，只有低于水位的瓦片才有水
+        let cells: HashMap<IVec2, f32> = filled
+            .into_iter()
+            .filter(|(_, h)| *h < level)
+            .map(|(p, h)| (p, level - h))
+            .collect();
+
+        if cells.len() < min_cells {
+            return None;
+        }
+
+        let mut min = lowest;
+        let mut max = lowest;
+        for position in cells.keys() {
+            min = min.min(*position);
+            max = max.max(*position);
+        }
+
+        Some(LakeBasin { cells, min, max })
+    }
+
+    /// 为区域创建确定性随机数生成器
+    fn make_rng_for_region(&self, region: IVec2) -> ChaChaRng {
+        let combined_seed = self
+            .seed
+            .wrapping_add(0x4C41_4B45)
+            .wrapping_add(region.x as u64)
+            .wrapping_mul(31)
+            .wrapping_add(region.y as u64)
+            .wrapping_mul(0x9E37_79B9_7F4A_7C15);
+
+        ChaChaRng::seed_from_u64(combined_seed)
+    }
+}
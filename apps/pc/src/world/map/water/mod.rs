@@ -1,11 +1,16 @@
 mod lake;
+mod lake_basin;
 mod river;
+mod river_network;
+#[allow(clippy::module_inception)]
 mod water;
 mod water_manager;
 mod waterfall;
 
 pub use lake::*;
+pub use lake_basin::*;
 pub use river::*;
+pub use river_network::*;
 pub use water::*;
 pub use water_manager::*;
 pub use waterfall::*;
@@ -6,10 +6,6 @@ pub struct River {
     pub max_width: i32,
     /// 河流蜿蜒程度 (0.0-1.0)
     pub meandering: f32,
-    /// 分支概率
-    pub branch_probability: f32,
-    /// 最大分支数
-    pub max_branches: i32,
 }
 
 impl Default for River {
@@ -18,8 +14,6 @@ impl Default for River {
             min_width: 1,
             max_width: 3,
             meandering: 0.3,
-            branch_probability: 0.15,
-            max_branches: 2,
         }
     }
 }
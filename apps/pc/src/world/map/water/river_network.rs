@@ -0,0 +1,493 @@
+use bevy::math::{IVec2, Vec2};
+use rand::Rng;
+use rand_chacha::{rand_core::SeedableRng, ChaChaRng};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use super::super::{area::TerrainGenerator, LruCache};
+use super::River;
+
+/// 河网源点区域边长（瓦片）
+///
+/// 源点按区域确定性生成，区域越大，每次查询需要追踪的河流越少，
+/// 但单条河流可以跨越的区块数也受 `max_length` 限制
+pub const RIVER_REGION_SIZE: i32 = 256;
+
+/// 区域河流缓存容量，超出后淘汰最久未访问的区域
+const RIVER_REGION_CACHE_CAPACITY: usize = 64;
+
+/// 河道上的一个节点
+#[derive(Debug, Clone, Copy)]
+pub struct RiverNode {
+    /// 世界坐标
+    pub position: IVec2,
+    /// 河道宽度（瓦片）
+    pub width: f32,
+    /// 累积流量，源头为1，沿途递增
+    pub flow: f32,
+}
+
+/// 一条完整的河流路径（源头 -> 入水口）
+#[derive(Debug, Clone)]
+pub struct RiverPath {
+    pub nodes: Vec<RiverNode>,
+}
+
+/// 瀑布检测时比较的节点间隔
+const WATERFALL_PROBE: usize = 3;
+
+/// 同一条河上相邻瀑布的最小节点间隔
+const WATERFALL_MIN_SPACING: usize = 8;
+
+/// 河道上检测到的瀑布位置
+#[derive(Debug, Clone, Copy)]
+pub struct WaterfallSite {
+    /// 瀑布顶端的世界坐标
+    pub position: IVec2,
+    /// 水流方向（顶端指向底端）
+    pub direction: IVec2,
+    /// 落差
+    pub drop: f32,
+    /// 该处河道宽度
+    pub width: f32,
+}
+
+/// 落在某个区块内的河道瓦片
+#[derive(Debug, Clone, Copy)]
+pub struct RiverCell {
+    /// 区块内坐标
+    pub local_x: usize,
+    pub local_y: usize,
+    /// 所属河段的流量
+    pub flow: f32,
+    /// 距河道中心的归一化距离 (0.0 为中心，1.0 为岸边)
+    pub bank_factor: f32,
+    /// 该处河段的流向（归一化）
+    pub direction: Vec2,
+}
+
+/// 世界空间河网生成器
+///
+/// # 设计思路
+/// 1. 确定性：源点由世界种子和区域坐标决定，与区块加载顺序无关
+/// 2. 跨区块：河流在世界坐标中沿高度图下行追踪，不受区块边界限制
+/// 3. 按需计算：区域的河流在第一次被查询时追踪并缓存
+///
+/// # 流量与宽度
+/// 同一区域内的河流在汇合处合并流量，下游河道随流量变宽。
+/// 不同区域之间不合并流量，保证任意区块只依赖固定的区域集合，结果稳定
+#[derive(Debug, Clone)]
+pub struct RiverNetwork {
+    /// 种子
+    pub seed: u64,
+    /// 每个区域尝试的源点数
+    pub sources_per_region: u32,
+    /// 单条河流的最大长度（瓦片）
+    pub max_length: usize,
+    /// 源点最低高度（相对水位的增量）
+    pub min_source_elevation: f32,
+    /// 允许逆坡越过的最大步数，用于跨过小洼地
+    pub max_uphill_steps: u32,
+    /// 水位，河流流到该高度以下视为入海/入湖
+    pub water_level: f32,
+    /// 河道参数
+    pub params: River,
+    /// 区域河流缓存
+    region_cache: Arc<LruCache<IVec2, Arc<Vec<RiverPath>>>>,
+}
+
+impl Default for RiverNetwork {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            sources_per_region: 3,
+            max_length: 600,
+            min_source_elevation: 0.35,
+            max_uphill_steps: 6,
+            water_level: 0.3,
+            params: River::default(),
+            region_cache: Arc::new(LruCache::new(RIVER_REGION_CACHE_CAPACITY)),
+        }
+    }
+}
+
+/// 8邻域偏移
+const NEIGHBORS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+impl RiverNetwork {
+    /// 重新设置种子，并清空缓存
+    pub fn initialize(&mut self, seed: u64) {
+        self.seed = seed;
+        self.region_cache.clear();
+    }
+
+    /// 查询一个区块内的河道瓦片
+    ///
+    /// # 参数
+    /// * `chunk` - 区块坐标
+    /// * `chunk_size` - 区块边长（瓦片）
+    /// * `terrain` - 提供高度图的地形生成器
+    pub fn cells_in_chunk(
+        &self,
+        chunk: IVec2,
+        chunk_size: i32,
+        terrain: &TerrainGenerator,
+    ) -> Vec<RiverCell> {
+        let origin = chunk * chunk_size;
+        let max_x = origin.x + chunk_size;
+        let max_y = origin.y + chunk_size;
+
+        let mut cells: HashMap<(usize, usize), RiverCell> = HashMap::new();
+
+        for paths in self.paths_reaching_chunk(chunk, chunk_size, terrain) {
+            for path in paths.iter() {
+                for (index, node) in path.nodes.iter().enumerate() {
+                    let radius = node.width * 0.5;
+                    let reach = radius.ceil() as i32;
+
+                    // 快速剔除不可能覆盖区块的节点
+                    if node.position.x + reach < origin.x
+                        || node.position.x - reach >= max_x
+                        || node.position.y + reach < origin.y
+                        || node.position.y - reach >= max_y
+                    {
+                        continue;
+                    }
+
+                    // 流向取相邻节点的连线，入水口沿用上一段的方向
+                    let direction = match (path.nodes.get(index + 1), index.checked_sub(1)) {
+                        (Some(next), _) => next.position - node.position,
+                        (None, Some(prev)) => node.position - path.nodes[prev].position,
+                        (None, None) => IVec2::ZERO,
+                    }
+                    .as_vec2()
+                    .normalize_or_zero();
+
+                    for dy in -reach..=reach {
+                        for dx in -reach..=reach {
+                            let distance = ((dx * dx + dy * dy) as f32).sqrt();
+                            if distance > radius.max(0.5) {
+                                continue;
+                            }
+
+                            let world = node.position + IVec2::new(dx, dy);
+                            if world.x < origin.x
+                                || world.x >= max_x
+                                || world.y < origin.y
+                                || world.y >= max_y
+                            {
+                                continue;
+                            }
+
+                            let local =
+                                ((world.x - origin.x) as usize, (world.y - origin.y) as usize);
+                            let bank_factor = (distance / radius.max(0.5)).min(1.0);
+                            let cell = RiverCell {
+                                local_x: local.0,
+                                local_y: local.1,
+                                flow: node.flow,
+                                bank_factor,
+                                direction,
+                            };
+
+                            // 多条河道重叠时保留流量更大的一条
+                            cells
+                                .entry(local)
+                                .and_modify(|existing| {
+                                    if cell.flow > existing.flow {
+                                        *existing = cell;
+                                    }
+                                })
+                                .or_insert(cell);
+                        }
+                    }
+                }
+            }
+        }
+
+        cells.into_values().collect()
+    }
+
+    /// 查询一个区块内的瀑布位置
+    ///
+    /// 沿河道比较相隔 `WATERFALL_PROBE` 个节点的地形高度，落差超过阈值即为瀑布。
+    /// 检测在世界空间的完整河道上进行，跨区块的落差同样能被发现，
+    /// 结果只保留瀑布顶端落在该区块内的部分
+    ///
+    /// # 参数
+    /// * `chunk` - 区块坐标
+    /// * `chunk_size` - 区块边长（瓦片）
+    /// * `terrain` - 提供高度图的地形生成器
+    /// * `min_drop` - 形成瀑布所需的最小落差（与高度图同一尺度）
+    pub fn waterfalls_in_chunk(
+        &self,
+        chunk: IVec2,
+        chunk_size: i32,
+        terrain: &TerrainGenerator,
+        min_drop: f32,
+    ) -> Vec<WaterfallSite> {
+        let origin = chunk * chunk_size;
+        let contains = |p: IVec2| {
+            p.x >= origin.x
+                && p.x < origin.x + chunk_size
+                && p.y >= origin.y
+                && p.y < origin.y + chunk_size
+        };
+
+        let mut sites = Vec::new();
+
+        for paths in self.paths_reaching_chunk(chunk, chunk_size, terrain) {
+            for path in paths.iter() {
+                let mut index = 0;
+                while index + WATERFALL_PROBE < path.nodes.len() {
+                    let top = path.nodes[index];
+                    let bottom = path.nodes[index + WATERFALL_PROBE];
+
+                    let top_height =
+                        terrain.generate_height(top.position.x as f64, top.position.y as f64);
+                    let bottom_height =
+                        terrain.generate_height(bottom.position.x as f64, bottom.position.y as f64);
+                    let drop = top_height - bottom_height;
+
+                    // 落入水面以下的是入海/入湖口，不算瀑布
+                    if drop >= min_drop && bottom_height >= self.water_level {
+                        if contains(top.position) {
+                            sites.push(WaterfallSite {
+                                position: top.position,
+                                direction: bottom.position - top.position,
+                                drop,
+                                width: top.width,
+                            });
+                        }
+                        // 同一段陡坡只生成一个瀑布
+                        index += WATERFALL_MIN_SPACING;
+                    } else {
+                        index += 1;
+                    }
+                }
+            }
+        }
+
+        sites
+    }
+
+    /// 可能经过某个区块的全部河流
+    ///
+    /// 河流最长 `max_length`，只需检查源点区域在该距离内的区域
+    fn paths_reaching_chunk(
+        &self,
+        chunk: IVec2,
+        chunk_size: i32,
+        terrain: &TerrainGenerator,
+    ) -> Vec<Arc<Vec<RiverPath>>> {
+        let origin = chunk * chunk_size;
+        let reach = (self.max_length as i32 + RIVER_REGION_SIZE - 1) / RIVER_REGION_SIZE;
+        let min_region = Self::region_of(origin) - IVec2::splat(reach);
+        let max_region =
+            Self::region_of(origin + IVec2::splat(chunk_size - 1)) + IVec2::splat(reach);
+
+        let mut result = Vec::new();
+        for ry in min_region.y..=max_region.y {
+            for rx in min_region.x..=max_region.x {
+                result.push(self.region_rivers(IVec2::new(rx, ry), terrain));
+            }
+        }
+        result
+    }
+
+    /// 获取某个区域内发源的全部河流（带缓存）
+    pub fn region_rivers(&self, region: IVec2, terrain: &TerrainGenerator) -> Arc<Vec<RiverPath>> {
+        self.region_cache
+            .get_or_insert_with(region, || Arc::new(self.trace_region(region, terrain)))
+    }
+
+    /// 世界坐标所在的区域
+    pub fn region_of(world: IVec2) -> IVec2 {
+        IVec2::new(
+            world.x.div_euclid(RIVER_REGION_SIZE),
+            world.y.div_euclid(RIVER_REGION_SIZE),
+        )
+    }
+
+    /// 追踪一个区域内的全部河流
+    fn trace_region(&self, region: IVec2, terrain: &TerrainGenerator) -> Vec<RiverPath> {
+        let mut rng = self.make_rng_for_region(region);
+        let origin = region * RIVER_REGION_SIZE;
+
+        // 区域内的流量累积表与下游指针，用于河流汇合
+        let mut flow_map: HashMap<IVec2, f32> = HashMap::new();
+        let mut downstream: HashMap<IVec2, IVec2> = HashMap::new();
+        let mut raw_paths: Vec<Vec<IVec2>> = Vec::new();
+
+        for _ in 0..self.sources_per_region {
+            // 无论是否采用都消耗随机数，保证源点序列稳定
+            let source = origin
+                + IVec2::new(
+                    rng.gen_range(0..RIVER_REGION_SIZE),
+                    rng.gen_range(0..RIVER_REGION_SIZE),
+                );
+            let meander_seed = rng.gen::<u64>();
+
+            let source_height = terrain.get_height(source.x as f64, source.y as f64);
+            if source_height < self.water_level + self.min_source_elevation {
+                continue;
+            }
+
+            let path = self.trace_downhill(source, terrain, meander_seed, &flow_map);
+
+            // 最后一个点若已在表中，说明汇入了先前的河道
+            let joined = path
+                .last()
+                .filter(|cell| flow_map.contains_key(*cell))
+                .copied();
+            let new_cells = if joined.is_some() {
+                &path[..path.len() - 1]
+            } else {
+                &path[..]
+            };
+
+            for (index, cell) in new_cells.iter().enumerate() {
+                flow_map.insert(*cell, (index + 1) as f32);
+            }
+            for pair in path.windows(2) {
+                downstream.entry(pair[0]).or_insert(pair[1]);
+            }
+
+            // 支流的流量叠加到汇合点以下的整条河道
+            if let Some(mut cell) = joined {
+                let added = new_cells.len() as f32;
+                for _ in 0..self.max_length * 2 {
+                    if let Some(flow) = flow_map.get_mut(&cell) {
+                        *flow += added;
+                    }
+                    match downstream.get(&cell) {
+                        Some(next) => cell = *next,
+                        None => break,
+                    }
+                }
+            }
+
+            raw_paths.push(path);
+        }
+
+        raw_paths
+            .into_iter()
+            .filter(|path| path.len() > 1)
+            .map(|path| RiverPath {
+                nodes: path
+                    .into_iter()
+                    .map(|position| {
+                        let flow = flow_map.get(&position).copied().unwrap_or(1.0);
+                        RiverNode {
+                            position,
+                            width: self.width_for_flow(flow),
+                            flow,
+                        }
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// 从源点沿最陡下坡方向追踪河道
+    fn trace_downhill(
+        &self,
+        source: IVec2,
+        terrain: &TerrainGenerator,
+        meander_seed: u64,
+        existing: &HashMap<IVec2, f32>,
+    ) -> Vec<IVec2> {
+        let mut rng = ChaChaRng::seed_from_u64(meander_seed);
+        let mut heights: HashMap<IVec2, f32> = HashMap::new();
+        let mut height_at = |pos: IVec2| -> f32 {
+            *heights
+                .entry(pos)
+                .or_insert_with(|| terrain.get_height(pos.x as f64, pos.y as f64))
+        };
+
+        let mut path = vec![source];
+        let mut visited: HashSet<IVec2> = HashSet::from([source]);
+        let mut current = source;
+        let mut uphill_steps = 0;
+
+        while path.len() < self.max_length {
+            let current_height = height_at(current);
+
+            // 到达水位以下：入海/入湖
+            if current_height < self.water_level {
+                break;
+            }
+
+            // 汇入已有河道后由那条河继续向下游
+            if current != source && existing.contains_key(&current) {
+                break;
+            }
+
+            // 选择下一个点：下坡落差加上少量蜿蜒扰动
+            let mut best: Option<(IVec2, f32, f32)> = None;
+            for offset in NEIGHBORS {
+                let next = current + offset;
+                if visited.contains(&next) {
+                    continue;
+                }
+                let next_height = height_at(next);
+                let drop = (current_height - next_height) / offset.as_vec2().length();
+                let jitter = rng.gen_range(0.0..=self.params.meandering) * 0.01;
+                let score = drop + jitter;
+                if best.is_none_or(|(_, best_score, _)| score > best_score) {
+                    best = Some((next, score, next_height));
+                }
+            }
+
+            let Some((next, _, next_height)) = best else {
+                break;
+            };
+
+            // 没有下坡时允许短暂逆坡，越过小洼地；否则河流终止于洼地
+            if next_height >= current_height {
+                uphill_steps += 1;
+                if uphill_steps > self.max_uphill_steps {
+                    break;
+                }
+            } else {
+                uphill_steps = 0;
+            }
+
+            visited.insert(next);
+            path.push(next);
+            current = next;
+        }
+
+        path
+    }
+
+    /// 根据流量计算河道宽度
+    fn width_for_flow(&self, flow: f32) -> f32 {
+        let min = self.params.min_width as f32;
+        let max = self.params.max_width as f32;
+        // 流量越大越接近最大宽度，形如 f/(f+k) 的饱和曲线
+        let t = flow / (flow + 120.0);
+        min + (max - min) * t
+    }
+
+    /// 区域相关的确定性随机数生成器
+    fn make_rng_for_region(&self, region: IVec2) -> ChaChaRng {
+        let combined_seed = self
+            .seed
+            .wrapping_add(region.x as u64)
+            .wrapping_mul(31)
+            .wrapping_add(region.y as u64)
+            .wrapping_mul(0x9E37_79B9_7F4A_7C15);
+
+        ChaChaRng::seed_from_u64(combined_seed)
+    }
+}
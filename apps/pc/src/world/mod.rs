@@ -1,4 +1,8 @@
+pub mod caravan;
 pub mod chunk;
+pub mod entity;
+pub mod glider;
+pub mod harvest;
 /// 世界模块
 ///
 /// 包含地图和区块两个主要子模块，负责游戏世界的生成和管理
@@ -8,6 +12,13 @@ pub mod chunk;
 /// 2. 模块化：不同功能独立管理
 /// 3. 数据驱动：通过配置文件和参数控制世界生成
 pub mod map;
+pub mod physics;
+pub mod poi;
+pub mod pointer;
+pub mod population;
+pub mod schedule;
+pub mod traversal;
+pub mod weather;
 
 use bevy::prelude::*;
 
@@ -22,6 +33,42 @@ impl Plugin for WorldPlugin {
         // 添加区块系统插件
         app.add_plugins(chunk::ChunkSystemPlugin);
 
+        // 添加天气系统插件
+        app.add_plugins(weather::WeatherPlugin);
+
+        // 添加采集系统插件
+        app.add_plugins(harvest::HarvestPlugin);
+
+        // 添加商队插件
+        app.add_plugins(caravan::CaravanPlugin);
+
+        // 添加玩家操作插件
+        app.add_plugins(entity::PlayerPlugin);
+
+        // 添加NPC AI插件
+        app.add_plugins(entity::NpcAiPlugin);
+
+        // 添加NPC作息插件
+        app.add_plugins(schedule::SchedulePlugin);
+
+        // 添加兴趣点插件
+        app.add_plugins(poi::PoiPlugin);
+
+        // 添加NPC分布插件
+        app.add_plugins(population::PopulationPlugin);
+
+        // 添加滑翔插件
+        app.add_plugins(glider::GliderPlugin);
+
+        // 添加骑马与轻功插件
+        app.add_plugins(traversal::TraversalPlugin);
+
+        // 添加鼠标操作插件
+        app.add_plugins(pointer::PointerPlugin);
+
+        // 添加物理插件
+        app.add_plugins(physics::PhysicsPlugin);
+
         info!("世界系统已初始化");
     }
 }
@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::world::chunk::TILE_PIXELS;
+use crate::world::map::SpatialIndex;
+
+/// 静态碰撞体，例如树干、岩石与建筑
+///
+/// 轴对齐矩形，中心在实体位置加上偏移处
+#[derive(Component, Debug, Clone, Copy)]
+pub struct StaticCollider {
+    /// 半宽与半高（像素）
+    pub half_size: Vec2,
+    /// 相对实体位置的偏移（像素）
+    pub offset: Vec2,
+}
+
+impl StaticCollider {
+    pub fn new(half_size: Vec2) -> Self {
+        Self {
+            half_size,
+            offset: Vec2::ZERO,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+/// 已登记的碰撞体矩形，世界坐标
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColliderRect {
+    pub center: Vec2,
+    pub half_size: Vec2,
+}
+
+impl ColliderRect {
+    /// 是否与圆相交
+    pub fn overlaps_circle(&self, center: Vec2, radius: f32) -> bool {
+        let closest = center.clamp(self.center - self.half_size, self.center + self.half_size);
+        closest.distance_squared(center) < radius * radius
+    }
+}
+
+/// 静态碰撞体索引
+///
+/// 碰撞体大多是区块的子实体，世界坐标在变换传播后才确定，
+/// 因此与场景触发区一样按全局变换的变化登记
+#[derive(Resource, Debug, Default)]
+pub struct ColliderIndex {
+    /// 碰撞体实体，按中心所在瓦片索引
+    index: SpatialIndex<Entity>,
+    rects: HashMap<Entity, (IVec2, ColliderRect)>,
+    /// 已登记碰撞体中最大的半边长（像素），决定查询范围
+    max_extent: f32,
+}
+
+impl ColliderIndex {
+    /// 加入或移动碰撞体
+    pub fn insert(&mut self, entity: Entity, rect: ColliderRect) {
+        self.remove(entity);
+        let tile = (rect.center / TILE_PIXELS).floor().as_ivec2();
+        self.index.insert(tile, entity);
+        self.rects.insert(entity, (tile, rect));
+        self.max_extent = self.max_extent.max(rect.half_size.max_element());
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some((tile, _)) = self.rects.remove(&entity) {
+            self.index.remove_where(tile, |indexed| *indexed == entity);
+        }
+    }
+
+    /// 与圆相交的碰撞体
+    pub fn overlapping(&self, center: Vec2, radius: f32) -> impl Iterator<Item = &ColliderRect> {
+        let tile = (center / TILE_PIXELS).floor().as_ivec2();
+        // 碰撞体按中心登记，查询范围要加上最大的半边长，再多留一格余量
+        let reach = (radius + self.max_extent) / TILE_PIXELS + 1.0;
+        self.index
+            .query_radius(tile, reach)
+            .into_iter()
+            .filter_map(|(_, entity)| self.rects.get(entity))
+            .map(|(_, rect)| rect)
+            .filter(move |rect| rect.overlaps_circle(center, radius))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ColliderRect> {
+        self.rects.values().map(|(_, rect)| rect)
+    }
+}
+
+/// 维护静态碰撞体索引
+#[allow(clippy::type_complexity)]
+pub fn index_static_colliders(
+    mut index: ResMut<ColliderIndex>,
+    colliders: Query<
+        (Entity, &StaticCollider, &GlobalTransform),
+        Or<(Changed<GlobalTransform>, Changed<StaticCollider>)>,
+    >,
+    mut removed: RemovedComponents<StaticCollider>,
+) {
+    for entity in removed.read() {
+        index.remove(entity);
+    }
+    for (entity, collider, transform) in colliders.iter() {
+        index.insert(
+            entity,
+            ColliderRect {
+                center: transform.translation().truncate() + collider.offset,
+                half_size: collider.half_size,
+            },
+        );
+    }
+}
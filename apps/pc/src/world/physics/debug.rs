@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+
+use super::{ColliderIndex, MovementBody};
+use crate::world::chunk::{world_to_tile, NavGrid, TILE_PIXELS};
+use crate::world::entity::Player;
+
+/// 玩家周围绘制不可通行瓦片的范围（瓦片）
+const DEBUG_TILE_RANGE: i32 = 8;
+
+const BODY_COLOR: Color = Color::srgb(0.2, 0.9, 0.4);
+const AIRBORNE_COLOR: Color = Color::srgb(0.3, 0.6, 1.0);
+const COLLIDER_COLOR: Color = Color::srgb(1.0, 0.6, 0.1);
+const BLOCKED_TILE_COLOR: Color = Color::srgba(0.9, 0.2, 0.2, 0.6);
+const WATER_TILE_COLOR: Color = Color::srgba(0.2, 0.5, 1.0, 0.6);
+
+/// 绘制碰撞形状
+///
+/// 移动体画成圆，离地时在上方再画一个圆表示高度；
+/// 静态碰撞体画成矩形；玩家附近不可行走的瓦片与水面画出轮廓
+pub fn draw_physics_debug(
+    mut gizmos: Gizmos,
+    colliders: Res<ColliderIndex>,
+    nav_grid: Option<Res<NavGrid>>,
+    bodies: Query<(&GlobalTransform, &MovementBody)>,
+    player: Query<&GlobalTransform, With<Player>>,
+) {
+    for (transform, body) in bodies.iter() {
+        let position = transform.translation().truncate();
+        gizmos.circle_2d(position, body.radius, BODY_COLOR);
+        if body.elevation > 0.0 {
+            let raised = position + Vec2::Y * body.elevation;
+            gizmos.line_2d(position, raised, AIRBORNE_COLOR);
+            gizmos.circle_2d(raised, body.radius, AIRBORNE_COLOR);
+        }
+    }
+
+    for rect in colliders.iter() {
+        gizmos.rect_2d(rect.center, rect.half_size * 2.0, COLLIDER_COLOR);
+    }
+
+    let (Some(nav_grid), Ok(player)) = (nav_grid, player.get_single()) else {
+        return;
+    };
+    let center = world_to_tile(player.translation().truncate());
+    for y in -DEBUG_TILE_RANGE..=DEBUG_TILE_RANGE {
+        for x in -DEBUG_TILE_RANGE..=DEBUG_TILE_RANGE {
+            let tile = center + IVec2::new(x, y);
+            let Some(cell) = nav_grid.cell(tile) else {
+                continue;
+            };
+            let color = if cell.water {
+                WATER_TILE_COLOR
+            } else if !cell.walkable {
+                BLOCKED_TILE_COLOR
+            } else {
+                continue;
+            };
+            let tile_center = (tile.as_vec2() + 0.5) * TILE_PIXELS;
+            gizmos.rect_2d(tile_center, Vec2::splat(TILE_PIXELS), color);
+        }
+    }
+}
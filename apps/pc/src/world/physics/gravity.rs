@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+
+use super::MovementBody;
+use crate::world::chunk::TILE_PIXELS;
+use crate::world::entity::{Character, CharacterState};
+use crate::world::glider::Gliding;
+
+/// 物理参数，对应游戏设置中的物理选项
+#[derive(Resource, Debug, Clone)]
+pub struct PhysicsOptions {
+    /// 固定步长（秒）
+    pub timestep: f32,
+    /// 重力加速度（瓦片/秒²），向下为负
+    pub gravity: f32,
+    /// 绘制碰撞形状
+    pub debug_draw: bool,
+}
+
+impl Default for PhysicsOptions {
+    fn default() -> Self {
+        Self {
+            timestep: 1.0 / 64.0,
+            gravity: -9.81,
+            debug_draw: false,
+        }
+    }
+}
+
+impl MovementBody {
+    /// 以给定的初速度（像素/秒）起跳
+    pub fn launch(&mut self, character: &mut Character, speed: f32) {
+        self.vertical_velocity = speed;
+        character.is_grounded = false;
+        character.state = CharacterState::Jumping;
+    }
+}
+
+/// 对跳跃与下落中的角色施加重力
+///
+/// # 设计思路
+/// 1. 高度与竖直速度记在移动体上，平面位置仍由碰撞求解决定
+/// 2. 竖直速度转为向下时从跳跃切换为下落，高度回到地面时落地并恢复站立
+/// 3. 滑翔中的角色由滑翔系统按自己的下沉速度处理，这里跳过
+pub fn apply_gravity(
+    time: Res<Time>,
+    options: Res<PhysicsOptions>,
+    mut bodies: Query<(&mut MovementBody, &mut Character), Without<Gliding>>,
+) {
+    let delta = time.delta_secs();
+    let gravity = options.gravity * TILE_PIXELS;
+
+    for (mut body, mut character) in bodies.iter_mut() {
+        if !matches!(
+            character.state,
+            CharacterState::Jumping | CharacterState::Falling
+        ) {
+            continue;
+        }
+
+        body.vertical_velocity += gravity * delta;
+        body.elevation += body.vertical_velocity * delta;
+        if body.vertical_velocity < 0.0 && character.state == CharacterState::Jumping {
+            character.state = CharacterState::Falling;
+        }
+
+        if body.elevation <= 0.0 {
+            body.elevation = 0.0;
+            body.vertical_velocity = 0.0;
+            character.is_grounded = true;
+            character.state = CharacterState::Idle;
+        }
+    }
+}
@@ -0,0 +1,24 @@
+/// 物理模块
+///
+/// 角色移动与地形、静态障碍物之间的碰撞，以及跳跃下落的重力
+///
+/// # 模块组成
+/// 1. collider：静态碰撞体及其空间索引
+/// 2. movement：移动意图与碰撞求解
+/// 3. gravity：物理参数与重力
+/// 4. swimming：游泳的体力消耗与溺水
+/// 5. debug：碰撞形状的调试绘制
+/// 6. systems：物理插件
+mod collider;
+mod debug;
+mod gravity;
+mod movement;
+mod swimming;
+mod systems;
+
+pub use collider::*;
+pub use debug::*;
+pub use gravity::*;
+pub use movement::*;
+pub use swimming::*;
+pub use systems::PhysicsPlugin;
@@ -0,0 +1,227 @@
+use bevy::prelude::*;
+
+use super::ColliderIndex;
+use crate::world::chunk::{world_to_tile, NavGrid};
+
+/// 碰撞配置
+#[derive(Resource, Debug, Clone)]
+pub struct CollisionSettings {
+    /// 游泳时的移速倍率
+    pub swim_speed_multiplier: f32,
+    /// 浅水中涉水的移速倍率
+    pub wade_speed_multiplier: f32,
+    /// 水深达到这个值（与地形高度同一单位）就得游泳
+    pub deep_water_depth: f32,
+    /// 单步最大位移占身体半径的比例，防止高速移动穿墙
+    pub max_step_ratio: f32,
+    /// 未加载的区域是否阻挡移动
+    pub block_unloaded: bool,
+}
+
+impl Default for CollisionSettings {
+    fn default() -> Self {
+        Self {
+            swim_speed_multiplier: 0.5,
+            wade_speed_multiplier: 0.8,
+            deep_water_depth: 0.05,
+            max_step_ratio: 0.5,
+            block_unloaded: true,
+        }
+    }
+}
+
+/// 移动方式，决定哪些地形可以通过
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TraversalMode {
+    /// 步行，水面游过去
+    #[default]
+    OnFoot,
+    /// 骑马，进不了密林
+    Mounted,
+    /// 施展轻功，踏水而过、攀上峭壁
+    Qinggong,
+}
+
+/// 参与碰撞的移动体
+///
+/// 输入与 AI 只累加移动意图，由碰撞系统统一求解后写回变换
+#[derive(Component, Debug, Clone)]
+pub struct MovementBody {
+    /// 碰撞半径（像素），以脚下为圆心
+    pub radius: f32,
+    /// 本帧想要移动的位移（像素），求解后清零
+    pub intent: Vec2,
+    /// 是否在水中
+    pub in_water: bool,
+    /// 所在水面的水深，不在水中为 0
+    pub water_depth: f32,
+    /// 是否在深水中游泳
+    pub swimming: bool,
+    /// 把水面当作障碍，不会水的NPC不下水
+    pub avoid_water: bool,
+    /// 本帧的移动是否被挡住过
+    pub blocked: bool,
+    /// 离地高度（像素），跳跃与下落时大于零
+    pub elevation: f32,
+    /// 竖直速度（像素/秒），向上为正
+    pub vertical_velocity: f32,
+    /// 移动方式
+    pub mode: TraversalMode,
+}
+
+impl Default for MovementBody {
+    fn default() -> Self {
+        Self {
+            radius: 10.0,
+            intent: Vec2::ZERO,
+            in_water: false,
+            water_depth: 0.0,
+            swimming: false,
+            avoid_water: false,
+            blocked: false,
+            elevation: 0.0,
+            vertical_velocity: 0.0,
+            mode: TraversalMode::OnFoot,
+        }
+    }
+}
+
+/// 移动实体
+///
+/// 有移动体时只记录意图，交给碰撞系统；没有时直接平移
+pub fn move_by(transform: &mut Transform, body: Option<&mut MovementBody>, movement: Vec2) {
+    match body {
+        Some(body) => body.intent += movement,
+        None => {
+            transform.translation.x += movement.x;
+            transform.translation.y += movement.y;
+        }
+    }
+}
+
+/// 某个位置能否容纳给定半径的圆
+///
+/// 圆覆盖的每个瓦片都必须按移动方式可以通过，并且不与静态碰撞体相交
+fn is_free(
+    nav_grid: &NavGrid,
+    colliders: &ColliderIndex,
+    settings: &CollisionSettings,
+    mode: TraversalMode,
+    avoid_water: bool,
+    center: Vec2,
+    radius: f32,
+) -> bool {
+    let min = world_to_tile(center - Vec2::splat(radius));
+    let max = world_to_tile(center + Vec2::splat(radius));
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            let passable = match nav_grid.cell(IVec2::new(x, y)) {
+                Some(cell) => {
+                    let water = cell.water && !avoid_water;
+                    match mode {
+                        TraversalMode::OnFoot => cell.walkable || water,
+                        TraversalMode::Mounted => (cell.walkable || water) && !cell.dense_forest,
+                        TraversalMode::Qinggong => cell.walkable || cell.water || cell.cliff,
+                    }
+                }
+                None => !settings.block_unloaded,
+            };
+            if !passable {
+                return false;
+            }
+        }
+    }
+    colliders.overlapping(center, radius).next().is_none()
+}
+
+/// 求解移动与碰撞
+///
+/// # 设计思路
+/// 1. 位移按身体半径切成小步，每步先尝试整体移动，被挡时分别尝试横向与纵向，实现贴墙滑动
+/// 2. 水面可以进入，浅水涉水、深水游泳，分别减速；施展轻功时踏水而过，不算落水
+/// 3. 起点已经卡在障碍里时（例如区块刚加载出墙）放行，避免角色永远无法脱困
+pub fn resolve_movement(
+    nav_grid: Res<NavGrid>,
+    colliders: Res<ColliderIndex>,
+    settings: Res<CollisionSettings>,
+    mut bodies: Query<(&mut Transform, &mut MovementBody)>,
+) {
+    for (mut transform, mut body) in bodies.iter_mut() {
+        let radius = body.radius.max(1.0);
+        let mut position = transform.translation.truncate();
+        let mode = body.mode;
+        let avoid_water = body.avoid_water;
+        let multiplier = if body.swimming {
+            settings.swim_speed_multiplier
+        } else if body.in_water {
+            settings.wade_speed_multiplier
+        } else {
+            1.0
+        };
+        let delta = body.intent * multiplier;
+        body.intent = Vec2::ZERO;
+        body.blocked = false;
+
+        if delta != Vec2::ZERO {
+            let stuck = !is_free(
+                &nav_grid,
+                &colliders,
+                &settings,
+                mode,
+                avoid_water,
+                position,
+                radius,
+            );
+            let free = |point: Vec2| {
+                stuck
+                    || is_free(
+                        &nav_grid,
+                        &colliders,
+                        &settings,
+                        mode,
+                        avoid_water,
+                        point,
+                        radius,
+                    )
+            };
+
+            let max_step = (radius * settings.max_step_ratio).max(1.0);
+            let steps = (delta.length() / max_step).ceil().max(1.0) as u32;
+            let step = delta / steps as f32;
+            for _ in 0..steps {
+                let target = position + step;
+                if free(target) {
+                    position = target;
+                    continue;
+                }
+                body.blocked = true;
+                let slide_x = position + Vec2::new(step.x, 0.0);
+                let slide_y = position + Vec2::new(0.0, step.y);
+                if step.x != 0.0 && free(slide_x) {
+                    position = slide_x;
+                } else if step.y != 0.0 && free(slide_y) {
+                    position = slide_y;
+                } else {
+                    break;
+                }
+            }
+
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
+        }
+
+        let depth = nav_grid
+            .cell(world_to_tile(position))
+            .filter(|cell| cell.water && mode != TraversalMode::Qinggong)
+            .map(|cell| cell.depth);
+        let in_water = depth.is_some();
+        let swimming = depth.is_some_and(|depth| depth >= settings.deep_water_depth);
+        let water_depth = depth.unwrap_or(0.0);
+        if body.in_water != in_water || body.swimming != swimming || body.water_depth != water_depth
+        {
+            body.in_water = in_water;
+            body.swimming = swimming;
+            body.water_depth = water_depth;
+        }
+    }
+}
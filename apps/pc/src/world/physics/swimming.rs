@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+
+use super::MovementBody;
+use crate::combat::{DamageEvent, SkillBook};
+use crate::world::entity::{Character, CharacterState};
+
+/// 游泳设置
+///
+/// - stamina_drain: 深水中每秒消耗的体力，水越深消耗越多
+/// - depth_drain: 每单位水深额外增加的消耗倍率
+/// - regen_delay: 上岸后多久开始恢复体力（秒）
+/// - npc_stamina_regen: 没有技能书的角色在岸上每秒恢复的体力
+/// - drown_damage / drown_interval: 体力耗尽后每次溺水的伤害与间隔（秒）
+#[derive(Resource, Debug, Clone)]
+pub struct SwimSettings {
+    pub stamina_drain: f32,
+    pub depth_drain: f32,
+    pub regen_delay: f32,
+    pub npc_stamina_regen: f32,
+    pub drown_damage: f32,
+    pub drown_interval: f32,
+}
+
+impl Default for SwimSettings {
+    fn default() -> Self {
+        Self {
+            stamina_drain: 6.0,
+            depth_drain: 10.0,
+            regen_delay: 1.0,
+            npc_stamina_regen: 10.0,
+            drown_damage: 8.0,
+            drown_interval: 1.0,
+        }
+    }
+}
+
+/// 溺水计时，体力耗尽时开始计时
+#[derive(Component, Debug, Clone, Default)]
+pub struct Drowning {
+    pub timer: f32,
+}
+
+/// 深水中游泳消耗体力，体力耗尽后溺水掉血
+///
+/// # 设计思路
+/// 1. 是否游泳由碰撞求解按所在瓦片的水深判断，这里只结算状态、体力与溺水
+/// 2. 游泳时体力不恢复；有技能书的角色上岸后交给技能系统恢复，NPC在这里慢慢恢复
+/// 3. 跳跃下落中的角色不改状态，落入深水后才转为游泳
+#[allow(clippy::type_complexity)]
+pub fn update_swimming(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<SwimSettings>,
+    mut swimmers: Query<(
+        Entity,
+        &MovementBody,
+        &mut Character,
+        Option<&mut SkillBook>,
+        Option<&mut Drowning>,
+    )>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    let delta = time.delta_secs();
+
+    for (entity, body, mut character, book, drowning) in swimmers.iter_mut() {
+        if character.state == CharacterState::Dead {
+            continue;
+        }
+
+        if !body.swimming {
+            if drowning.is_some() {
+                commands.entity(entity).remove::<Drowning>();
+            }
+            if character.state == CharacterState::Swimming {
+                character.state = CharacterState::Idle;
+            }
+            if book.is_none() {
+                character.stamina = (character.stamina + settings.npc_stamina_regen * delta)
+                    .min(character.max_stamina);
+            }
+            continue;
+        }
+
+        if !matches!(
+            character.state,
+            CharacterState::Jumping | CharacterState::Falling
+        ) {
+            character.state = CharacterState::Swimming;
+        }
+        let drain = settings.stamina_drain * (1.0 + body.water_depth * settings.depth_drain);
+        character.stamina = (character.stamina - drain * delta).max(0.0);
+        if let Some(mut book) = book {
+            book.stamina_delay = book.stamina_delay.max(settings.regen_delay);
+        }
+
+        if character.stamina > 0.0 {
+            if drowning.is_some() {
+                commands.entity(entity).remove::<Drowning>();
+            }
+            continue;
+        }
+        match drowning {
+            Some(mut drowning) => {
+                drowning.timer += delta;
+                if drowning.timer >= settings.drown_interval {
+                    drowning.timer -= settings.drown_interval;
+                    damage_events.send(DamageEvent::damage(
+                        entity,
+                        None,
+                        "溺水",
+                        settings.drown_damage,
+                    ));
+                }
+            }
+            None => {
+                commands.entity(entity).insert(Drowning::default());
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+
+use super::{
+    apply_gravity, draw_physics_debug, index_static_colliders, resolve_movement, update_swimming,
+    ColliderIndex, CollisionSettings, PhysicsOptions, SwimSettings,
+};
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::gameplay_running;
+use crate::world::entity::{handle_player_input, update_npc_ai};
+
+/// 物理插件
+///
+/// # 设计思路
+/// 1. 导航网格由区块插件维护，这里只登记静态碰撞体、施加重力并求解移动
+/// 2. 输入与 AI 在 Update 中累加移动意图，重力与碰撞在 FixedUpdate 中按固定步长结算，
+///    步长由游戏设置决定，帧率波动时跳跃高度与穿墙判定保持一致
+/// 3. 是否在深水中由碰撞求解判断，游泳状态、体力消耗与溺水在输入与 AI 之后结算
+/// 4. 调试绘制只在物理选项开启时运行
+/// 5. 暂停时虚拟时间冻结，固定步长本就不再累加，仍显式挂上运行条件，主菜单与加载时同样不结算
+pub struct PhysicsPlugin;
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ColliderIndex>()
+            .init_resource::<CollisionSettings>()
+            .init_resource::<PhysicsOptions>()
+            .init_resource::<SwimSettings>()
+            .add_systems(Startup, apply_physics_timestep)
+            .add_systems(Update, index_static_colliders)
+            .add_systems(
+                Update,
+                update_swimming
+                    .after(handle_player_input)
+                    .after(update_npc_ai)
+                    .run_if(gameplay_running),
+            )
+            .add_systems(
+                FixedUpdate,
+                (apply_gravity, resolve_movement)
+                    .chain()
+                    .run_if(gameplay_running),
+            )
+            .add_systems(
+                PostUpdate,
+                draw_physics_debug
+                    .after(TransformSystem::TransformPropagate)
+                    .run_if(debug_draw_enabled),
+            );
+    }
+}
+
+fn debug_draw_enabled(options: Res<PhysicsOptions>) -> bool {
+    options.debug_draw
+}
+
+/// 按物理选项设置固定步长
+fn apply_physics_timestep(
+    options: Res<PhysicsOptions>,
+    mut fixed: ResMut<Time<Fixed>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    if options.timestep > 0.0 {
+        fixed.set_timestep_seconds(options.timestep as f64);
+    }
+    if let Some(logger) = logger.as_mut() {
+        logger.log(
+            LogLevel::Info,
+            &format!(
+                "物理步长 {:.4} 秒，重力 {:.2} 瓦片/秒²，碰撞调试绘制{}",
+                fixed.timestep().as_secs_f32(),
+                options.gravity,
+                if options.debug_draw {
+                    "开启"
+                } else {
+                    "关闭"
+                }
+            ),
+        );
+    }
+}
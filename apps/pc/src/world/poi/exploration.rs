@@ -0,0 +1,165 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::world::chunk::{ChunkCoord, ChunkData, CHUNK_SIZE};
+
+/// 探索进度存档路径
+pub const EXPLORATION_SAVE_PATH: &str = "saves/exploration.json";
+
+/// 区块缩略图边长（格），每格对应 `CHUNK_SIZE / THUMBNAIL_SIZE` 个瓦片
+pub const THUMBNAIL_SIZE: usize = 8;
+
+/// 已探索区块的缩略图
+///
+/// 区块卸载后世界地图仍要能画出来，探索时按格采样瓦片类型与高度记下来
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkThumbnail {
+    /// 每格中心瓦片的类型编号，按行从下到上排列
+    pub tiles: Vec<u8>,
+    /// 每格中心瓦片的高度，量化到 0-255
+    pub heights: Vec<u8>,
+}
+
+impl ChunkThumbnail {
+    /// 从区块数据采样
+    pub fn from_chunk(data: &ChunkData) -> Self {
+        let step = CHUNK_SIZE / THUMBNAIL_SIZE;
+        let mut tiles = Vec::with_capacity(THUMBNAIL_SIZE * THUMBNAIL_SIZE);
+        let mut heights = Vec::with_capacity(THUMBNAIL_SIZE * THUMBNAIL_SIZE);
+        for y in 0..THUMBNAIL_SIZE {
+            for x in 0..THUMBNAIL_SIZE {
+                let (tile_x, tile_y) = (x * step + step / 2, y * step + step / 2);
+                tiles.push(data.get_tile(tile_x, tile_y).unwrap_or(0));
+                heights.push((data.get_height(tile_x, tile_y).clamp(0.0, 1.0) * 255.0) as u8);
+            }
+        }
+        Self { tiles, heights }
+    }
+
+    /// 区块内瓦片坐标所在格的瓦片类型编号与高度
+    pub fn sample(&self, local: UVec2) -> (u8, f32) {
+        let step = (CHUNK_SIZE / THUMBNAIL_SIZE) as u32;
+        let x = (local.x / step).min(THUMBNAIL_SIZE as u32 - 1) as usize;
+        let y = (local.y / step).min(THUMBNAIL_SIZE as u32 - 1) as usize;
+        let index = y * THUMBNAIL_SIZE + x;
+        (
+            self.tiles.get(index).copied().unwrap_or(0),
+            self.heights.get(index).copied().unwrap_or(0) as f32 / 255.0,
+        )
+    }
+}
+
+/// 存档中的单个区块，缩略图以十六进制字符串保存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExploredChunkEntry {
+    x: i32,
+    y: i32,
+    tiles: String,
+    heights: String,
+}
+
+/// 探索进度存档格式
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ExplorationSaveFile {
+    chunks: Vec<ExploredChunkEntry>,
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(text.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+/// 已探索的区块，世界地图据此绘制战争迷雾
+#[derive(Resource, Debug, Default)]
+pub struct ExploredChunks {
+    chunks: HashMap<ChunkCoord, ChunkThumbnail>,
+    /// 有未写入存档的变化
+    dirty: bool,
+}
+
+impl ExploredChunks {
+    /// 从存档读取，损坏的条目直接跳过
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let file: ExplorationSaveFile = serde_json::from_str(&content)?;
+        let cells = THUMBNAIL_SIZE * THUMBNAIL_SIZE;
+        let chunks = file
+            .chunks
+            .into_iter()
+            .filter_map(|entry| {
+                let tiles = decode_hex(&entry.tiles).filter(|v| v.len() == cells)?;
+                let heights = decode_hex(&entry.heights).filter(|v| v.len() == cells)?;
+                Some((
+                    ChunkCoord {
+                        x: entry.x,
+                        y: entry.y,
+                    },
+                    ChunkThumbnail { tiles, heights },
+                ))
+            })
+            .collect();
+        Ok(Self {
+            chunks,
+            dirty: false,
+        })
+    }
+
+    /// 写入存档
+    pub fn save(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let chunks = self
+            .chunks
+            .iter()
+            .map(|(coord, thumbnail)| ExploredChunkEntry {
+                x: coord.x,
+                y: coord.y,
+                tiles: encode_hex(&thumbnail.tiles),
+                heights: encode_hex(&thumbnail.heights),
+            })
+            .collect();
+        fs::write(
+            path,
+            serde_json::to_string(&ExplorationSaveFile { chunks })?,
+        )?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// 记录区块已探索，首次探索时返回 true
+    pub fn explore(&mut self, coord: ChunkCoord, data: &ChunkData) -> bool {
+        if self.chunks.contains_key(&coord) {
+            return false;
+        }
+        self.chunks.insert(coord, ChunkThumbnail::from_chunk(data));
+        self.dirty = true;
+        true
+    }
+
+    pub fn is_explored(&self, coord: ChunkCoord) -> bool {
+        self.chunks.contains_key(&coord)
+    }
+
+    /// 已探索区块的缩略图
+    pub fn thumbnail(&self, coord: ChunkCoord) -> Option<&ChunkThumbnail> {
+        self.chunks.get(&coord)
+    }
+
+    /// 是否有未写入存档的变化
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+}
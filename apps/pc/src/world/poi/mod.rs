@@ -0,0 +1,18 @@
+/// 兴趣点模块
+///
+/// 登记世界中生成的村落、寺庙、瀑布等场景及其发现状态，供地图标记与任务目标查询
+///
+/// # 模块组成
+/// 1. registry：兴趣点注册表、查询接口与发现状态存档
+/// 2. exploration：已探索区块的缩略图与探索进度存档，供世界地图绘制战争迷雾
+/// 3. pin：玩家放置的地图标注及其存档
+/// 4. systems：兴趣点插件，负责从区块登记兴趣点、发现检测与探索记录
+mod exploration;
+mod pin;
+mod registry;
+mod systems;
+
+pub use exploration::*;
+pub use pin::*;
+pub use registry::*;
+pub use systems::*;
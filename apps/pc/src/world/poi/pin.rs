@@ -0,0 +1,231 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// 地图标注存档路径
+pub const MAP_PIN_SAVE_PATH: &str = "saves/map_pins.json";
+
+/// 默认的标注数量上限
+pub const DEFAULT_PIN_LIMIT: usize = 32;
+
+/// 备注最多字数，按字符计，中文一个字算一个
+pub const MAX_PIN_NOTE_CHARS: usize = 24;
+
+/// 标注图标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum PinIcon {
+    #[default]
+    Flag,
+    Star,
+    Treasure,
+    Danger,
+    Camp,
+    Question,
+}
+
+impl PinIcon {
+    /// 全部图标，按切换顺序排列
+    pub const ALL: [PinIcon; 6] = [
+        PinIcon::Flag,
+        PinIcon::Star,
+        PinIcon::Treasure,
+        PinIcon::Danger,
+        PinIcon::Camp,
+        PinIcon::Question,
+    ];
+
+    /// 地图上显示的符号
+    pub fn glyph(self) -> &'static str {
+        match self {
+            PinIcon::Flag => "⚑",
+            PinIcon::Star => "★",
+            PinIcon::Treasure => "◎",
+            PinIcon::Danger => "✕",
+            PinIcon::Camp => "△",
+            PinIcon::Question => "？",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PinIcon::Flag => "旗标",
+            PinIcon::Star => "要地",
+            PinIcon::Treasure => "宝藏",
+            PinIcon::Danger => "危险",
+            PinIcon::Camp => "歇脚",
+            PinIcon::Question => "存疑",
+        }
+    }
+
+    /// 下一个图标，到末尾后回到第一个
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|icon| *icon == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// 玩家在地图上放置的标注
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapPin {
+    pub id: u32,
+    /// 世界瓦片坐标
+    pub position: IVec2,
+    pub icon: PinIcon,
+    /// 备注
+    pub note: String,
+    /// 是否显示在罗盘上
+    pub on_compass: bool,
+}
+
+/// 截断备注，按字符截断以免切坏多字节文字
+pub fn clamp_pin_note(note: &str) -> String {
+    note.trim().chars().take(MAX_PIN_NOTE_CHARS).collect()
+}
+
+/// 存档中的单个标注
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SavedPin {
+    id: u32,
+    x: i32,
+    y: i32,
+    icon: PinIcon,
+    #[serde(default)]
+    note: String,
+    #[serde(default)]
+    on_compass: bool,
+}
+
+/// 标注存档格式
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct MapPinSaveFile {
+    next_id: u32,
+    pins: Vec<SavedPin>,
+}
+
+/// 地图标注
+#[derive(Resource, Debug)]
+pub struct MapPins {
+    pins: Vec<MapPin>,
+    next_id: u32,
+    /// 数量上限
+    pub limit: usize,
+}
+
+impl Default for MapPins {
+    fn default() -> Self {
+        Self {
+            pins: Vec::new(),
+            next_id: 1,
+            limit: DEFAULT_PIN_LIMIT,
+        }
+    }
+}
+
+impl MapPins {
+    /// 从存档读取
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let file: MapPinSaveFile = serde_json::from_str(&content)?;
+        // 旧存档的编号可能不连续，下一个编号取两者较大值
+        let next_id = file
+            .pins
+            .iter()
+            .map(|pin| pin.id + 1)
+            .max()
+            .unwrap_or(1)
+            .max(file.next_id);
+        let pins = file
+            .pins
+            .into_iter()
+            .map(|pin| MapPin {
+                id: pin.id,
+                position: IVec2::new(pin.x, pin.y),
+                icon: pin.icon,
+                note: clamp_pin_note(&pin.note),
+                on_compass: pin.on_compass,
+            })
+            .collect();
+        Ok(Self {
+            pins,
+            next_id,
+            ..Default::default()
+        })
+    }
+
+    /// 写入存档
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = MapPinSaveFile {
+            next_id: self.next_id,
+            pins: self
+                .pins
+                .iter()
+                .map(|pin| SavedPin {
+                    id: pin.id,
+                    x: pin.position.x,
+                    y: pin.position.y,
+                    icon: pin.icon,
+                    note: pin.note.clone(),
+                    on_compass: pin.on_compass,
+                })
+                .collect(),
+        };
+        fs::write(path, serde_json::to_string_pretty(&file)?)?;
+        Ok(())
+    }
+
+    /// 放置标注，返回新标注的编号
+    pub fn add(&mut self, position: IVec2, icon: PinIcon, note: &str) -> Result<u32, String> {
+        if self.pins.len() >= self.limit {
+            return Err(format!("标注已达上限 {} 个", self.limit));
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.pins.push(MapPin {
+            id,
+            position,
+            icon,
+            note: clamp_pin_note(note),
+            on_compass: false,
+        });
+        Ok(id)
+    }
+
+    /// 修改标注的图标、备注与罗盘显示
+    pub fn update(&mut self, id: u32, icon: PinIcon, note: &str, on_compass: bool) -> bool {
+        let Some(pin) = self.pins.iter_mut().find(|pin| pin.id == id) else {
+            return false;
+        };
+        pin.icon = icon;
+        pin.note = clamp_pin_note(note);
+        pin.on_compass = on_compass;
+        true
+    }
+
+    /// 删除标注
+    pub fn remove(&mut self, id: u32) -> bool {
+        let before = self.pins.len();
+        self.pins.retain(|pin| pin.id != id);
+        self.pins.len() != before
+    }
+
+    pub fn get(&self, id: u32) -> Option<&MapPin> {
+        self.pins.iter().find(|pin| pin.id == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &MapPin> {
+        self.pins.iter()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.pins.len() >= self.limit
+    }
+
+    /// 需要显示在罗盘上的标注
+    pub fn compass_pins(&self) -> impl Iterator<Item = &MapPin> {
+        self.pins.iter().filter(|pin| pin.on_compass)
+    }
+}
@@ -0,0 +1,206 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::world::chunk::{ChunkCoord, ChunkData, ChunkStructure, CHUNK_SIZE};
+use crate::world::map::{SceneType, SpatialIndex};
+
+/// 兴趣点发现状态存档路径
+pub const POI_SAVE_PATH: &str = "saves/poi.json";
+
+/// 兴趣点
+#[derive(Debug, Clone)]
+pub struct PointOfInterest {
+    /// 世界瓦片坐标，同时作为兴趣点的标识
+    pub position: IVec2,
+    pub kind: SceneType,
+    pub name: String,
+    /// 发现的时刻（历法总小时数），未发现为空
+    pub discovered_at: Option<f64>,
+}
+
+impl PointOfInterest {
+    pub fn is_discovered(&self) -> bool {
+        self.discovered_at.is_some()
+    }
+}
+
+/// 场景类型的默认名称
+pub fn default_poi_name(kind: SceneType) -> &'static str {
+    match kind {
+        SceneType::Village => "村落",
+        SceneType::Town => "城镇",
+        SceneType::City => "主城",
+        SceneType::Temple => "寺庙",
+        SceneType::Waterfall => "瀑布",
+        SceneType::Lake => "湖泊",
+        SceneType::Forest => "森林",
+        SceneType::Mountain => "山峰",
+        SceneType::Cave => "洞窟",
+        SceneType::BattleField => "古战场",
+        SceneType::SecretRealm => "秘境",
+    }
+}
+
+/// 已发现的兴趣点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DiscoveredPoi {
+    x: i32,
+    y: i32,
+    discovered_at: f64,
+}
+
+/// 兴趣点存档格式
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PoiSaveFile {
+    discovered: Vec<DiscoveredPoi>,
+}
+
+/// 兴趣点注册表
+///
+/// # 设计思路
+/// 1. 兴趣点来自区块生成时记录的场景中心与瀑布，区块卸载后仍然保留，地图标记不随区块消失
+/// 2. 存档只记录发现状态，兴趣点本身随世界确定性生成，重新加载区块时再次登记
+/// 3. 按坐标建立空间索引，供发现检测、地图标记与任务目标查询
+#[derive(Resource, Debug, Default)]
+pub struct PoiRegistry {
+    points: SpatialIndex<PointOfInterest>,
+    /// 存档中已发现、但所在区块尚未生成的兴趣点
+    pending: HashMap<IVec2, f64>,
+}
+
+impl PoiRegistry {
+    /// 从存档读取发现状态
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let file: PoiSaveFile = serde_json::from_str(&content)?;
+        Ok(Self {
+            points: SpatialIndex::default(),
+            pending: file
+                .discovered
+                .into_iter()
+                .map(|poi| (IVec2::new(poi.x, poi.y), poi.discovered_at))
+                .collect(),
+        })
+    }
+
+    /// 写入存档
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let discovered = self
+            .points
+            .iter()
+            .filter_map(|(position, poi)| poi.discovered_at.map(|time| (position, time)))
+            .chain(
+                self.pending
+                    .iter()
+                    .map(|(position, time)| (*position, *time)),
+            )
+            .map(|(position, discovered_at)| DiscoveredPoi {
+                x: position.x,
+                y: position.y,
+                discovered_at,
+            })
+            .collect();
+        fs::write(
+            path,
+            serde_json::to_string_pretty(&PoiSaveFile { discovered })?,
+        )?;
+        Ok(())
+    }
+
+    /// 登记兴趣点，已登记的坐标保持原有状态
+    pub fn register(&mut self, position: IVec2, kind: SceneType, name: Option<String>) {
+        if self.points.get(position).is_some() {
+            return;
+        }
+        let discovered_at = self.pending.remove(&position);
+        self.points.insert(
+            position,
+            PointOfInterest {
+                position,
+                kind,
+                name: name.unwrap_or_else(|| default_poi_name(kind).to_string()),
+                discovered_at,
+            },
+        );
+    }
+
+    /// 登记区块中的场景与瀑布，区块重新加载时不会重复登记
+    pub fn register_chunk(&mut self, coord: ChunkCoord, data: &ChunkData) {
+        let origin = IVec2::new(coord.x, coord.y) * CHUNK_SIZE as i32;
+        for structure in data.structures() {
+            match structure {
+                ChunkStructure::Landmark {
+                    local_x,
+                    local_y,
+                    scene_type,
+                    name,
+                } => {
+                    let position = origin + IVec2::new(*local_x as i32, *local_y as i32);
+                    self.register(position, *scene_type, name.clone());
+                }
+                ChunkStructure::Waterfall {
+                    local_x, local_y, ..
+                } => {
+                    let position = origin + IVec2::new(*local_x as i32, *local_y as i32);
+                    self.register(position, SceneType::Waterfall, None);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// 标记兴趣点已发现，首次发现时返回 true
+    pub fn discover(&mut self, position: IVec2, now: f64) -> bool {
+        let Some(poi) = self.points.remove(position).into_iter().next() else {
+            return false;
+        };
+        let first = poi.discovered_at.is_none();
+        self.points.insert(
+            position,
+            PointOfInterest {
+                discovered_at: poi.discovered_at.or(Some(now)),
+                ..poi
+            },
+        );
+        first
+    }
+
+    /// 按坐标查找
+    pub fn get(&self, position: IVec2) -> Option<&PointOfInterest> {
+        self.points.get(position)
+    }
+
+    /// 全部已登记的兴趣点
+    pub fn iter(&self) -> impl Iterator<Item = &PointOfInterest> {
+        self.points.iter().map(|(_, poi)| poi)
+    }
+
+    /// 已发现的兴趣点，供地图标记使用
+    pub fn discovered(&self) -> impl Iterator<Item = &PointOfInterest> {
+        self.iter().filter(|poi| poi.is_discovered())
+    }
+
+    /// 矩形范围（世界瓦片坐标，含边界）内的兴趣点
+    pub fn in_rect(&self, min: IVec2, max: IVec2) -> Vec<&PointOfInterest> {
+        self.points
+            .query_rect(min, max)
+            .into_iter()
+            .map(|(_, poi)| poi)
+            .collect()
+    }
+
+    /// 半径范围（瓦片）内的兴趣点
+    pub fn within(&self, center: IVec2, radius: f32) -> Vec<&PointOfInterest> {
+        self.points
+            .query_radius(center, radius)
+            .into_iter()
+            .map(|(_, poi)| poi)
+            .collect()
+    }
+}
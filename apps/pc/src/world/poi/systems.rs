@@ -0,0 +1,257 @@
+use bevy::prelude::*;
+use std::path::Path;
+
+use super::{
+    ExploredChunks, MapPins, PoiRegistry, EXPLORATION_SAVE_PATH, MAP_PIN_SAVE_PATH, POI_SAVE_PATH,
+};
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::gameplay_running;
+use crate::time::GameCalendar;
+use crate::world::chunk::{Chunk, ChunkCoord, ChunkManager, CHUNK_SIZE, TILE_PIXELS};
+use crate::world::entity::Player;
+use crate::world::map::SceneType;
+
+/// 兴趣点配置
+#[derive(Resource, Debug, Clone)]
+pub struct PoiSettings {
+    /// 玩家走到多近算发现（瓦片）
+    pub discovery_radius: f32,
+    /// 存档路径
+    pub save_path: String,
+    /// 玩家所在区块周围多少圈区块算已探索
+    pub reveal_radius: i32,
+    /// 探索进度存档路径
+    pub exploration_save_path: String,
+    /// 探索进度写入存档的最短间隔（秒）
+    pub exploration_save_interval: f32,
+    /// 地图标注存档路径
+    pub pin_save_path: String,
+}
+
+impl Default for PoiSettings {
+    fn default() -> Self {
+        Self {
+            discovery_radius: 12.0,
+            save_path: POI_SAVE_PATH.to_string(),
+            reveal_radius: 1,
+            exploration_save_path: EXPLORATION_SAVE_PATH.to_string(),
+            exploration_save_interval: 10.0,
+            pin_save_path: MAP_PIN_SAVE_PATH.to_string(),
+        }
+    }
+}
+
+/// 首次发现兴趣点事件
+#[derive(Event, Debug, Clone)]
+pub struct PoiDiscovered {
+    /// 世界瓦片坐标
+    pub position: IVec2,
+    pub kind: SceneType,
+    pub name: String,
+}
+
+/// 兴趣点插件
+pub struct PoiPlugin;
+
+impl Plugin for PoiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PoiSettings>()
+            .add_event::<PoiDiscovered>();
+
+        app.add_systems(
+            Startup,
+            (load_poi_registry, load_explored_chunks, load_map_pins),
+        )
+        .add_systems(
+            Update,
+            (
+                register_chunk_pois,
+                discover_pois,
+                explore_chunks,
+                save_explored_chunks,
+            )
+                .chain()
+                .run_if(gameplay_running),
+        );
+    }
+}
+
+/// 读取兴趣点存档，没有存档时全部未发现
+fn load_poi_registry(
+    mut commands: Commands,
+    settings: Res<PoiSettings>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let registry = if Path::new(&settings.save_path).exists() {
+        PoiRegistry::load(&settings.save_path).unwrap_or_else(|e| {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("兴趣点存档读取失败: {}", e));
+            }
+            PoiRegistry::default()
+        })
+    } else {
+        PoiRegistry::default()
+    };
+    commands.insert_resource(registry);
+}
+
+/// 读取探索进度存档，没有存档时从零开始
+fn load_explored_chunks(
+    mut commands: Commands,
+    settings: Res<PoiSettings>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let explored = if Path::new(&settings.exploration_save_path).exists() {
+        ExploredChunks::load(&settings.exploration_save_path).unwrap_or_else(|e| {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("探索进度存档读取失败: {}", e));
+            }
+            ExploredChunks::default()
+        })
+    } else {
+        ExploredChunks::default()
+    };
+    commands.insert_resource(explored);
+}
+
+/// 读取地图标注存档
+fn load_map_pins(
+    mut commands: Commands,
+    settings: Res<PoiSettings>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let pins = if Path::new(&settings.pin_save_path).exists() {
+        MapPins::load(&settings.pin_save_path).unwrap_or_else(|e| {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("地图标注存档读取失败: {}", e));
+            }
+            MapPins::default()
+        })
+    } else {
+        MapPins::default()
+    };
+    commands.insert_resource(pins);
+}
+
+/// 新加载的区块登记其中的场景与瀑布
+pub fn register_chunk_pois(mut registry: ResMut<PoiRegistry>, chunks: Query<&Chunk, Added<Chunk>>) {
+    for chunk in chunks.iter() {
+        if let Some(data) = &chunk.data {
+            registry.register_chunk(chunk.coord, data);
+        }
+    }
+}
+
+/// 玩家首次走近兴趣点时标记为已发现并写入存档
+fn discover_pois(
+    settings: Res<PoiSettings>,
+    calendar: Res<GameCalendar>,
+    mut registry: ResMut<PoiRegistry>,
+    players: Query<&Transform, With<Player>>,
+    mut discovered: EventWriter<PoiDiscovered>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let Ok(transform) = players.get_single() else {
+        return;
+    };
+    let tile = (transform.translation.truncate() / TILE_PIXELS)
+        .floor()
+        .as_ivec2();
+
+    let found: Vec<IVec2> = registry
+        .within(tile, settings.discovery_radius)
+        .into_iter()
+        .filter(|poi| !poi.is_discovered())
+        .map(|poi| poi.position)
+        .collect();
+    if found.is_empty() {
+        return;
+    }
+
+    let now = calendar.total_hours();
+    for position in found {
+        if !registry.discover(position, now) {
+            continue;
+        }
+        let Some(poi) = registry.get(position) else {
+            continue;
+        };
+        if let Some(logger) = logger.as_mut() {
+            logger.log(
+                LogLevel::Info,
+                &format!("发现{}（{}, {}）", poi.name, position.x, position.y),
+            );
+        }
+        discovered.send(PoiDiscovered {
+            position,
+            kind: poi.kind,
+            name: poi.name.clone(),
+        });
+    }
+
+    if let Err(e) = registry.save(&settings.save_path) {
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Error, &format!("兴趣点存档写入失败: {}", e));
+        }
+    }
+}
+
+/// 玩家所在区块及周围区块标记为已探索
+fn explore_chunks(
+    settings: Res<PoiSettings>,
+    chunk_manager: Res<ChunkManager>,
+    mut explored: ResMut<ExploredChunks>,
+    players: Query<&Transform, With<Player>>,
+    chunks: Query<&Chunk>,
+) {
+    let Ok(transform) = players.get_single() else {
+        return;
+    };
+    let tile = (transform.translation.truncate() / TILE_PIXELS)
+        .floor()
+        .as_ivec2();
+    let center = tile.div_euclid(IVec2::splat(CHUNK_SIZE as i32));
+
+    for y in -settings.reveal_radius..=settings.reveal_radius {
+        for x in -settings.reveal_radius..=settings.reveal_radius {
+            let coord = ChunkCoord {
+                x: center.x + x,
+                y: center.y + y,
+            };
+            if explored.is_explored(coord) {
+                continue;
+            }
+            let data = chunk_manager
+                .get_chunk_entity(coord)
+                .and_then(|entity| chunks.get(entity).ok())
+                .and_then(|chunk| chunk.data.as_ref());
+            if let Some(data) = data {
+                explored.explore(coord, data);
+            }
+        }
+    }
+}
+
+/// 探索进度有变化时按间隔写入存档
+fn save_explored_chunks(
+    time: Res<Time>,
+    settings: Res<PoiSettings>,
+    mut explored: ResMut<ExploredChunks>,
+    mut since_save: Local<f32>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    *since_save += time.delta_secs();
+    if !explored.is_dirty() || *since_save < settings.exploration_save_interval {
+        return;
+    }
+    *since_save = 0.0;
+    // 写存档不算探索进度变化，避免地图无谓重绘
+    if let Err(e) = explored
+        .bypass_change_detection()
+        .save(&settings.exploration_save_path)
+    {
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Error, &format!("探索进度存档写入失败: {}", e));
+        }
+    }
+}
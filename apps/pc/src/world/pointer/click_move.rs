@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use super::PointerTarget;
+use crate::world::chunk::{world_to_tile, NavGrid, TILE_PIXELS};
+
+/// 点击移动的路径
+///
+/// 挂在玩家身上，走完、走到要交互的对象跟前或玩家改用按键移动时移除
+#[derive(Component, Debug, Clone)]
+pub struct MovePath {
+    /// 剩下的路点（世界坐标）
+    pub waypoints: VecDeque<Vec2>,
+    /// 走到跟前后要交互的对象
+    pub interact: Option<PointerTarget>,
+    /// 走完仍够不着对象时重新寻路的次数，对象走动时追上去
+    pub repaths: u32,
+}
+
+impl MovePath {
+    /// 由寻路结果生成，第一格是玩家脚下的瓦片，不再走回它的中心
+    pub fn from_tiles(tiles: &[IVec2], interact: Option<PointerTarget>) -> Self {
+        Self {
+            waypoints: tiles
+                .iter()
+                .skip(1)
+                .map(|tile| tile_center(*tile))
+                .collect(),
+            interact,
+            repaths: 0,
+        }
+    }
+
+    /// 终点，路径已走完时为空
+    pub fn destination(&self) -> Option<Vec2> {
+        self.waypoints.back().copied()
+    }
+}
+
+/// 瓦片中心的世界坐标
+pub fn tile_center(tile: IVec2) -> Vec2 {
+    (tile.as_vec2() + Vec2::splat(0.5)) * TILE_PIXELS
+}
+
+/// 走向对象时的落脚点
+///
+/// 对象所在的瓦片可以走时就是这一格，否则取四周离对象最近的可行走瓦片，
+/// 例如长在岩石上的矿脉只能站在旁边采
+pub fn approach_tile(nav_grid: &NavGrid, target: Vec2) -> Option<IVec2> {
+    let tile = world_to_tile(target);
+    if nav_grid.is_walkable(tile) {
+        return Some(tile);
+    }
+    (-1..=1)
+        .flat_map(|dy| (-1..=1).map(move |dx| tile + IVec2::new(dx, dy)))
+        .filter(|neighbor| *neighbor != tile && nav_grid.is_walkable(*neighbor))
+        .min_by(|a, b| {
+            let a = tile_center(*a).distance_squared(target);
+            let b = tile_center(*b).distance_squared(target);
+            a.total_cmp(&b)
+        })
+}
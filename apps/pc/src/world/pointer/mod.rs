@@ -0,0 +1,16 @@
+/// 鼠标操作模块
+///
+/// 光标经2.5D投影换算到世界坐标与瓦片，点击地面寻路走过去，
+/// 指向NPC或资源点时高亮，右键点选后走到跟前交谈或采集
+///
+/// # 模块组成
+/// 1. picking：光标位置、指向的对象与光标下的瓦片
+/// 2. click_move：点击移动的路径与落脚点
+/// 3. systems：鼠标操作插件，负责点选、沿路径移动与高亮
+mod click_move;
+mod picking;
+mod systems;
+
+pub use click_move::*;
+pub use picking::*;
+pub use systems::*;
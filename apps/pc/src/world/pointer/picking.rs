@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+use crate::world::chunk::{
+    calculate_height_offset, world_to_tile, Chunk, ChunkCoord, ChunkManager, RenderSettings,
+    CHUNK_SIZE, DEFAULT_NAV_SEARCH_LIMIT,
+};
+
+/// 由光标反推瓦片时最多迭代的次数
+const HEIGHT_ITERATIONS: usize = 4;
+
+/// 鼠标操作设置
+///
+/// - pick_radius: 光标离NPC或资源点多近算指向它（像素）
+/// - arrive_distance: 离路点多近算走到（像素）
+/// - search_limit: 点击寻路的搜索节点上限
+#[derive(Resource, Debug, Clone)]
+pub struct PointerSettings {
+    pub pick_radius: f32,
+    pub arrive_distance: f32,
+    pub search_limit: usize,
+}
+
+impl Default for PointerSettings {
+    fn default() -> Self {
+        Self {
+            pick_radius: 24.0,
+            arrive_distance: 6.0,
+            search_limit: DEFAULT_NAV_SEARCH_LIMIT,
+        }
+    }
+}
+
+/// 光标指向的可交互对象
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerTarget {
+    Npc(Entity),
+    Harvest(Entity),
+}
+
+impl PointerTarget {
+    pub fn entity(self) -> Entity {
+        match self {
+            PointerTarget::Npc(entity) | PointerTarget::Harvest(entity) => entity,
+        }
+    }
+}
+
+/// 光标在世界中的位置，每帧更新
+#[derive(Resource, Debug, Default)]
+pub struct CursorWorld {
+    /// 光标下的世界坐标，光标不在窗口内时为空
+    pub position: Option<Vec2>,
+    /// 光标下的瓦片，已扣除地形高度带来的2.5D偏移
+    pub tile: Option<IVec2>,
+    /// 光标指向的对象
+    pub hovered: Option<PointerTarget>,
+    /// 光标停在界面按钮上，点击交给界面处理
+    pub over_ui: bool,
+}
+
+/// 瓦片的地形高度，所在区块未加载时为空
+pub fn tile_height(tile: IVec2, manager: &ChunkManager, chunks: &Query<&Chunk>) -> Option<f32> {
+    let size = CHUNK_SIZE as i32;
+    let coord = ChunkCoord {
+        x: tile.x.div_euclid(size),
+        y: tile.y.div_euclid(size),
+    };
+    let data = manager
+        .get_chunk_entity(coord)
+        .and_then(|entity| chunks.get(entity).ok())
+        .and_then(|chunk| chunk.data.as_ref())?;
+    Some(data.get_height(
+        tile.x.rem_euclid(size) as usize,
+        tile.y.rem_euclid(size) as usize,
+    ))
+}
+
+/// 光标下的瓦片
+///
+/// 瓦片按高度沿视角方向偏移后绘制，光标点到的位置要扣掉这段偏移才是瓦片本身的位置。
+/// 偏移又取决于瓦片高度，所以先按零高度取一格，再用这一格的高度重新扣除偏移，
+/// 几次之内就会停在画在光标下的那一格
+pub fn cursor_tile(
+    position: Vec2,
+    settings: &RenderSettings,
+    height_at: impl Fn(IVec2) -> Option<f32>,
+) -> IVec2 {
+    let mut tile = world_to_tile(position);
+    for _ in 0..HEIGHT_ITERATIONS {
+        let height = height_at(tile).unwrap_or(0.0);
+        let next = world_to_tile(position - calculate_height_offset(height, settings));
+        if next == tile {
+            break;
+        }
+        tile = next;
+    }
+    tile
+}
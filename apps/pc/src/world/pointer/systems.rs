@@ -0,0 +1,381 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use super::{
+    approach_tile, cursor_tile, tile_height, CursorWorld, MovePath, PointerSettings, PointerTarget,
+};
+use crate::events::input::{handle_input_events, GameAction};
+use crate::logging::{GameLogger, LogLevel};
+use crate::render::camera::CameraController;
+use crate::render::components::{LayerComponent, RenderLayer};
+use crate::resources::{gameplay_running, InputState};
+use crate::time::GameCalendar;
+use crate::world::chunk::{calculate_height_offset, world_to_tile, Chunk, ChunkManager, NavGrid};
+use crate::world::entity::{handle_player_input, Character, Npc, Player};
+use crate::world::harvest::{HarvestRegistry, HarvestRequested, HarvestSettings, Harvestable};
+use crate::world::map::quest::{detect_npc_talk, QuestSettings, TalkedToNpc};
+
+/// 够不着要交互的对象时最多重新寻路的次数
+const MAX_REPATHS: u32 = 3;
+
+/// 按下任意一个就放弃点击移动的动作
+const MOVE_ACTIONS: [GameAction; 4] = [
+    GameAction::MoveForward,
+    GameAction::MoveBackward,
+    GameAction::MoveLeft,
+    GameAction::MoveRight,
+];
+
+/// 高亮框比NPC精灵大出的像素
+const HIGHLIGHT_MARGIN: Vec2 = Vec2::new(8.0, 8.0);
+
+/// 高亮框画在对象身后，只露出一圈边
+const HIGHLIGHT_DEPTH: f32 = 0.001;
+
+/// 指向NPC或资源点时的高亮框
+#[derive(Component)]
+pub struct HoverHighlight;
+
+/// 点击移动的目的地标记
+#[derive(Component)]
+pub struct MoveMarker;
+
+/// 鼠标操作插件
+///
+/// # 设计思路
+/// 1. 光标先由相机换算到世界坐标，再按地形高度扣除2.5D偏移得到瓦片，点选与寻路都用这一格
+/// 2. 点击移动只给出移动方向，速度、碰撞与移动状态仍由玩家移动系统结算，按键移动随时打断
+/// 3. 右键交互走到跟前后交给原有的交谈与采集流程，只是指定了对象，不再取最近的
+/// 4. 点击与交互键一样走改键，光标停在界面按钮上或玩家不能移动时不响应
+pub struct PointerPlugin;
+
+impl Plugin for PointerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PointerSettings>()
+            .init_resource::<CursorWorld>()
+            .add_systems(Startup, spawn_pointer_markers)
+            .add_systems(
+                Update,
+                (
+                    update_cursor_world,
+                    handle_pointer_clicks,
+                    follow_move_path,
+                    update_pointer_markers,
+                )
+                    .chain()
+                    .run_if(gameplay_running)
+                    .after(handle_input_events)
+                    .before(handle_player_input)
+                    .before(detect_npc_talk),
+            );
+    }
+}
+
+fn spawn_pointer_markers(mut commands: Commands) {
+    commands.spawn((
+        HoverHighlight,
+        Sprite {
+            color: Color::srgba(1.0, 0.85, 0.3, 0.45),
+            ..default()
+        },
+        Transform::default(),
+        Visibility::Hidden,
+    ));
+    commands.spawn((
+        MoveMarker,
+        Sprite {
+            color: Color::srgba(0.9, 0.95, 1.0, 0.5),
+            custom_size: Some(Vec2::splat(12.0)),
+            ..default()
+        },
+        Transform::from_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+        Visibility::Hidden,
+        LayerComponent {
+            layer: RenderLayer::Effects,
+            sub_order: 0,
+        },
+    ));
+}
+
+/// 更新光标下的世界坐标、瓦片与指向的对象
+///
+/// 相机会转动与缩放，由相机换算比自己按窗口尺寸推算可靠；
+/// 已采集、还没长出来的资源点不算可交互
+#[allow(clippy::too_many_arguments)]
+fn update_cursor_world(
+    settings: Res<PointerSettings>,
+    chunk_manager: Res<ChunkManager>,
+    calendar: Res<GameCalendar>,
+    registry: Option<Res<HarvestRegistry>>,
+    mut cursor: ResMut<CursorWorld>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<CameraController>>,
+    chunks: Query<&Chunk>,
+    buttons: Query<&Interaction>,
+    npcs: Query<(Entity, &GlobalTransform), With<Npc>>,
+    nodes: Query<(Entity, &Harvestable, &GlobalTransform)>,
+) {
+    cursor.over_ui = buttons
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+
+    let position = windows
+        .get_single()
+        .ok()
+        .and_then(Window::cursor_position)
+        .zip(cameras.get_single().ok())
+        .and_then(|(screen, (camera, transform))| {
+            camera.viewport_to_world_2d(transform, screen).ok()
+        });
+    cursor.position = position;
+    let Some(position) = position else {
+        cursor.tile = None;
+        cursor.hovered = None;
+        return;
+    };
+
+    cursor.tile = Some(cursor_tile(
+        position,
+        chunk_manager.render_settings(),
+        |tile| tile_height(tile, &chunk_manager, &chunks),
+    ));
+
+    let now = calendar.total_hours();
+    let npc_targets = npcs
+        .iter()
+        .map(|(entity, transform)| (PointerTarget::Npc(entity), transform));
+    let node_targets = nodes
+        .iter()
+        .filter(|(_, node, _)| {
+            registry
+                .as_ref()
+                .is_none_or(|registry| registry.is_available(node.key, now))
+        })
+        .map(|(entity, _, transform)| (PointerTarget::Harvest(entity), transform));
+    cursor.hovered = npc_targets
+        .chain(node_targets)
+        .map(|(target, transform)| {
+            (
+                target,
+                transform.translation().truncate().distance(position),
+            )
+        })
+        .filter(|(_, distance)| *distance <= settings.pick_radius)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(target, _)| target);
+}
+
+/// 左键点地面寻路走过去，右键点NPC或资源点走到跟前交互
+#[allow(clippy::too_many_arguments)]
+fn handle_pointer_clicks(
+    mut commands: Commands,
+    input_state: Res<InputState>,
+    cursor: Res<CursorWorld>,
+    settings: Res<PointerSettings>,
+    nav_grid: Res<NavGrid>,
+    players: Query<(Entity, &Transform, &Character), With<Player>>,
+    targets: Query<&GlobalTransform>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let context = input_state.is_action_just_pressed(GameAction::ContextInteract);
+    if cursor.over_ui || !(context || input_state.is_action_just_pressed(GameAction::ClickMove)) {
+        return;
+    }
+    let Ok((player, transform, character)) = players.get_single() else {
+        return;
+    };
+    // 打开地图、菜单或对话时玩家不能移动，点击留给界面
+    if !character.can_move {
+        return;
+    }
+
+    let (goal, interact) = if context {
+        let Some(target) = cursor.hovered else {
+            return;
+        };
+        let Ok(target_transform) = targets.get(target.entity()) else {
+            return;
+        };
+        let Some(goal) = approach_tile(&nav_grid, target_transform.translation().truncate()) else {
+            return;
+        };
+        (goal, Some(target))
+    } else {
+        let Some(tile) = cursor.tile else {
+            return;
+        };
+        (tile, None)
+    };
+
+    let from = world_to_tile(transform.translation.truncate());
+    match nav_grid.find_path(from, goal, settings.search_limit) {
+        Some(tiles) => {
+            commands
+                .entity(player)
+                .insert(MovePath::from_tiles(&tiles, interact));
+        }
+        None => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(
+                    LogLevel::Debug,
+                    &format!("无法走到瓦片 ({}, {})", goal.x, goal.y),
+                );
+            }
+        }
+    }
+}
+
+/// 沿点击移动的路径给出移动方向，走到要交互的对象跟前后交谈或采集
+#[allow(clippy::too_many_arguments)]
+fn follow_move_path(
+    mut commands: Commands,
+    mut input_state: ResMut<InputState>,
+    settings: Res<PointerSettings>,
+    quest_settings: Res<QuestSettings>,
+    harvest_settings: Res<HarvestSettings>,
+    nav_grid: Res<NavGrid>,
+    mut players: Query<(Entity, &Transform, &mut Character, &mut MovePath), With<Player>>,
+    targets: Query<(&GlobalTransform, Option<&Character>), Without<Player>>,
+    mut talks: EventWriter<TalkedToNpc>,
+    mut harvests: EventWriter<HarvestRequested>,
+) {
+    let Ok((player, transform, mut character, mut path)) = players.get_single_mut() else {
+        return;
+    };
+    // 改用按键或摇杆移动时放弃路径
+    if input_state.move_axis != Vec2::ZERO
+        || MOVE_ACTIONS
+            .iter()
+            .any(|action| input_state.is_action_active(*action))
+    {
+        commands.entity(player).remove::<MovePath>();
+        return;
+    }
+    // 打开菜单时停在原地，关闭后接着走
+    if !character.can_move {
+        return;
+    }
+    let position = transform.translation.truncate();
+
+    if let Some(target) = path.interact {
+        match targets.get(target.entity()) {
+            Ok((target_transform, target_character)) => {
+                let target_position = target_transform.translation().truncate();
+                let distance = target_position.distance(position);
+                let reached = match target {
+                    PointerTarget::Npc(npc) if distance <= quest_settings.talk_range => {
+                        if let Some(target_character) = target_character {
+                            talks.send(TalkedToNpc {
+                                npc,
+                                name: target_character.name.clone(),
+                            });
+                        }
+                        true
+                    }
+                    PointerTarget::Harvest(node) if distance <= harvest_settings.interact_range => {
+                        harvests.send(HarvestRequested { node });
+                        true
+                    }
+                    _ => false,
+                };
+                if reached {
+                    commands.entity(player).remove::<MovePath>();
+                    return;
+                }
+                // 路走完了还够不着，多半是NPC走开了，朝它现在的位置重新寻路
+                if path.waypoints.is_empty() && path.repaths < MAX_REPATHS {
+                    let from = world_to_tile(position);
+                    let tiles = approach_tile(&nav_grid, target_position)
+                        .and_then(|goal| nav_grid.find_path(from, goal, settings.search_limit));
+                    if let Some(tiles) = tiles {
+                        let repaths = path.repaths + 1;
+                        *path = MovePath::from_tiles(&tiles, Some(target));
+                        path.repaths = repaths;
+                    }
+                }
+            }
+            // 对象已卸载或消失，照样走到原定的地方
+            Err(_) => path.interact = None,
+        }
+    }
+
+    while path
+        .waypoints
+        .front()
+        .is_some_and(|waypoint| waypoint.distance(position) <= settings.arrive_distance)
+    {
+        path.waypoints.pop_front();
+    }
+    let Some(waypoint) = path.waypoints.front().copied() else {
+        commands.entity(player).remove::<MovePath>();
+        return;
+    };
+
+    let direction = (waypoint - position).normalize_or_zero();
+    input_state.move_axis = direction;
+    if direction.x != 0.0 {
+        character.direction.x = direction.x.signum();
+    }
+}
+
+/// 把高亮框移到指向的对象身后，目的地标记放在路径终点
+#[allow(clippy::type_complexity)]
+fn update_pointer_markers(
+    cursor: Res<CursorWorld>,
+    chunk_manager: Res<ChunkManager>,
+    chunks: Query<&Chunk>,
+    targets: Query<
+        (&GlobalTransform, Option<&Sprite>),
+        (Without<HoverHighlight>, Without<MoveMarker>),
+    >,
+    paths: Query<&MovePath, With<Player>>,
+    mut highlights: Query<
+        (&mut Transform, &mut Sprite, &mut Visibility),
+        (With<HoverHighlight>, Without<MoveMarker>),
+    >,
+    mut markers: Query<
+        (&mut Transform, &mut Visibility),
+        (With<MoveMarker>, Without<HoverHighlight>),
+    >,
+) {
+    if let Ok((mut transform, mut sprite, mut visibility)) = highlights.get_single_mut() {
+        let hovered = cursor
+            .hovered
+            .and_then(|target| targets.get(target.entity()).ok());
+        match hovered {
+            Some((target_transform, target_sprite)) => {
+                let size = target_sprite
+                    .and_then(|sprite| sprite.custom_size)
+                    .unwrap_or(Vec2::new(32.0, 64.0));
+                sprite.custom_size = Some(size + HIGHLIGHT_MARGIN);
+                transform.translation = target_transform.translation() - Vec3::Z * HIGHLIGHT_DEPTH;
+                visibility.set_if_neq(Visibility::Visible);
+            }
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+
+    if let Ok((mut transform, mut visibility)) = markers.get_single_mut() {
+        let destination = paths
+            .get_single()
+            .ok()
+            .filter(|path| path.interact.is_none())
+            .and_then(MovePath::destination);
+        match destination {
+            Some(destination) => {
+                // 与地面瓦片一样按高度偏移，标记才落在点中的那一格上
+                let height =
+                    tile_height(world_to_tile(destination), &chunk_manager, &chunks).unwrap_or(0.0);
+                let offset = calculate_height_offset(height, chunk_manager.render_settings());
+                let depth = transform.translation.z;
+                transform.translation = (destination + offset).extend(depth);
+                visibility.set_if_neq(Visibility::Visible);
+            }
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+}
@@ -0,0 +1,18 @@
+/// NPC分布模块
+///
+/// 按区块的地形、气候与附近场景生成相应的NPC，随区块卸载，有名有姓的NPC跨区块重载保留状态
+///
+/// # 模块组成
+/// 1. rules：NPC分布规则与规则库
+/// 2. placement：按区块数据确定性地放置NPC
+/// 3. registry：独特NPC的生死、生命与位置存档
+/// 4. systems：NPC分布插件，负责生成、恢复与存档
+mod placement;
+mod registry;
+mod rules;
+mod systems;
+
+pub use placement::*;
+pub use registry::*;
+pub use rules::*;
+pub use systems::*;
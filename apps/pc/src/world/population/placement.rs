@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::PopulationLibrary;
+use crate::world::chunk::{ChunkCoord, ChunkData, CHUNK_SIZE};
+use crate::world::map::{get_tile_physics, TileType};
+use crate::world::poi::PoiRegistry;
+
+/// 每个NPC最多尝试的落点次数
+const PLACEMENT_ATTEMPTS: usize = 8;
+
+/// 放置在区块中的NPC
+#[derive(Debug, Clone)]
+pub struct PlacedNpc {
+    /// 规则在规则库中的下标
+    pub rule: usize,
+    pub local_x: usize,
+    pub local_y: usize,
+    pub name: String,
+}
+
+impl PlacedNpc {
+    /// 独特NPC的存档键，由规则与出生的世界瓦片坐标确定
+    pub fn key(&self, library: &PopulationLibrary, coord: ChunkCoord) -> String {
+        format!(
+            "{}@{},{}",
+            library.rules[self.rule].id,
+            coord.x * CHUNK_SIZE as i32 + self.local_x as i32,
+            coord.y * CHUNK_SIZE as i32 + self.local_y as i32
+        )
+    }
+}
+
+/// 按区块数据确定性地放置NPC
+///
+/// # 设计思路
+/// 1. 以世界种子、区块坐标与规则下标生成随机数，同一区块每次加载得到相同的NPC
+/// 2. 场景条件查询兴趣点注册表，场景中心所在区块需已生成过
+/// 3. 同一瓦片只放一个NPC，找不到合适落点时这个NPC不生成
+pub fn place_population(
+    library: &PopulationLibrary,
+    coord: ChunkCoord,
+    data: &ChunkData,
+    pois: &PoiRegistry,
+    seed: u64,
+) -> Vec<PlacedNpc> {
+    let mut placed: Vec<PlacedNpc> = Vec::new();
+    let origin = IVec2::new(coord.x, coord.y) * CHUNK_SIZE as i32;
+
+    for (index, rule) in library.rules.iter().enumerate() {
+        if rule.names.is_empty() || rule.density <= 0.0 {
+            continue;
+        }
+        let mut rng = chunk_rng(seed, coord, index);
+        let mut count = rule.density.floor() as u32;
+        if rng.gen::<f32>() < rule.density.fract() {
+            count += 1;
+        }
+        let count = count.min(rule.max_per_chunk);
+
+        for _ in 0..count {
+            let spot = (0..PLACEMENT_ATTEMPTS).find_map(|_| {
+                let x = rng.gen_range(0..CHUNK_SIZE);
+                let y = rng.gen_range(0..CHUNK_SIZE);
+                let tile = data.get_tile(x, y).and_then(TileType::from_u8)?;
+                let free = tile != TileType::Water
+                    && get_tile_physics(tile).walkable
+                    && rule.suits(tile, data.get_climate_zone(x, y))
+                    && !placed
+                        .iter()
+                        .any(|npc| npc.local_x == x && npc.local_y == y);
+                let near_scene = rule.scenes.is_empty()
+                    || pois
+                        .within(origin + IVec2::new(x as i32, y as i32), rule.scene_radius)
+                        .iter()
+                        .any(|poi| rule.scenes.contains(&poi.kind));
+                (free && near_scene).then_some((x, y))
+            });
+            let Some((x, y)) = spot else {
+                continue;
+            };
+            let name = rule.names[rng.gen_range(0..rule.names.len())].clone();
+            placed.push(PlacedNpc {
+                rule: index,
+                local_x: x,
+                local_y: y,
+                name,
+            });
+        }
+    }
+
+    placed
+}
+
+/// 区块与规则独立的随机数生成器
+fn chunk_rng(seed: u64, coord: ChunkCoord, rule: usize) -> ChaCha8Rng {
+    let hash = (coord.x as i64 as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (coord.y as i64 as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ (rule as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+    ChaCha8Rng::seed_from_u64(seed ^ hash)
+}
@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// 独特NPC状态存档路径
+pub const POPULATION_SAVE_PATH: &str = "saves/npc_population.json";
+
+/// 需要存档的NPC
+///
+/// 区块卸载时NPC随之移除，再次加载时按存档恢复生死、生命与位置
+#[derive(Component, Debug, Clone)]
+pub struct PersistentNpc {
+    /// 存档键
+    pub key: String,
+    /// 死后重新出现所需的游戏小时数，为空时不再出现
+    pub respawn_hours: Option<f32>,
+}
+
+/// 独特NPC的存档状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistentNpcState {
+    pub position: [f32; 2],
+    pub health: f32,
+    /// 死亡的时刻（历法总小时数），活着为空
+    #[serde(default)]
+    pub died_at: Option<f64>,
+}
+
+/// 独特NPC存档格式
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PopulationSaveFile {
+    npcs: HashMap<String, PersistentNpcState>,
+}
+
+/// 独特NPC状态
+///
+/// 普通NPC随区块确定性生成，不需要存档；只有有名有姓的NPC记录状态
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PopulationRegistry {
+    npcs: HashMap<String, PersistentNpcState>,
+    dirty: bool,
+}
+
+impl PopulationRegistry {
+    /// 从存档读取
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let file: PopulationSaveFile = serde_json::from_str(&content)?;
+        Ok(Self {
+            npcs: file.npcs,
+            dirty: false,
+        })
+    }
+
+    /// 写入存档
+    pub fn save(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = PopulationSaveFile {
+            npcs: self.npcs.clone(),
+        };
+        fs::write(path, serde_json::to_string_pretty(&file)?)?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&PersistentNpcState> {
+        self.npcs.get(key)
+    }
+
+    /// 记录活着的NPC当前的状态
+    pub fn record(&mut self, key: &str, position: Vec2, health: f32) {
+        let state = PersistentNpcState {
+            position: position.to_array(),
+            health,
+            died_at: None,
+        };
+        if self.npcs.get(key) != Some(&state) {
+            self.npcs.insert(key.to_string(), state);
+            self.dirty = true;
+        }
+    }
+
+    /// 记录NPC死亡
+    pub fn mark_dead(&mut self, key: &str, position: Vec2, now: f64) {
+        self.npcs.insert(
+            key.to_string(),
+            PersistentNpcState {
+                position: position.to_array(),
+                health: 0.0,
+                died_at: Some(now),
+            },
+        );
+        self.dirty = true;
+    }
+
+    /// 死去的NPC是否仍未重新出现，到时间的记录会被清除
+    pub fn is_dead(&mut self, key: &str, respawn_hours: Option<f32>, now: f64) -> bool {
+        let Some(died_at) = self.npcs.get(key).and_then(|state| state.died_at) else {
+            return false;
+        };
+        let respawned = respawn_hours.is_some_and(|hours| died_at + hours as f64 <= now);
+        if respawned {
+            self.npcs.remove(key);
+            self.dirty = true;
+        }
+        !respawned
+    }
+
+    /// 是否有未写入存档的变化
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+}
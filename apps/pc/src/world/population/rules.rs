@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::world::entity::NpcType;
+use crate::world::map::{SceneType, TileType, Zone};
+
+/// NPC分布数据文件路径
+pub const POPULATION_DATA_PATH: &str = "src/config/npc_population.json";
+
+fn default_scene_radius() -> f32 {
+    48.0
+}
+
+fn default_max_per_chunk() -> u32 {
+    4
+}
+
+/// NPC分布规则
+///
+/// 每个区块按密度掷出数量，再在符合瓦片、气候与场景条件的位置上生成
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnRule {
+    pub id: String,
+    pub npc_type: NpcType,
+    /// 随机取用的名字
+    pub names: Vec<String>,
+    /// 每个区块的期望数量，小数部分按概率取整
+    pub density: f32,
+    #[serde(default = "default_max_per_chunk")]
+    pub max_per_chunk: u32,
+    /// 可出现的瓦片类型，为空时不限（不可行走的瓦片除外）
+    #[serde(default)]
+    pub tiles: Vec<TileType>,
+    /// 可出现的气候区域，为空时不限
+    #[serde(default)]
+    pub zones: Vec<Zone>,
+    /// 需要在这些场景附近，为空时不限
+    #[serde(default)]
+    pub scenes: Vec<SceneType>,
+    /// 场景附近的范围（瓦片）
+    #[serde(default = "default_scene_radius")]
+    pub scene_radius: f32,
+    /// 有名有姓的NPC，生死与位置写入存档
+    #[serde(default)]
+    pub unique: bool,
+    /// 独特NPC死后重新出现所需的游戏小时数，为空时不再出现
+    #[serde(default)]
+    pub respawn_hours: Option<f32>,
+}
+
+impl SpawnRule {
+    /// 瓦片环境是否适合该规则，场景条件由调用方另行判断
+    pub fn suits(&self, tile: TileType, zone: Option<Zone>) -> bool {
+        (self.tiles.is_empty() || self.tiles.contains(&tile))
+            && (self.zones.is_empty() || zone.is_some_and(|zone| self.zones.contains(&zone)))
+    }
+}
+
+/// NPC分布数据文件格式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PopulationDataFile {
+    rules: Vec<SpawnRule>,
+}
+
+/// NPC分布规则库
+///
+/// 规则的先后顺序参与确定性放置，调整顺序会改变已有世界中的NPC分布
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PopulationLibrary {
+    pub rules: Vec<SpawnRule>,
+}
+
+impl PopulationLibrary {
+    /// 从数据文件加载
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let data: PopulationDataFile = serde_json::from_str(&content)?;
+        Ok(Self { rules: data.rules })
+    }
+}
@@ -0,0 +1,221 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use std::path::Path;
+
+use super::{
+    place_population, PersistentNpc, PopulationLibrary, PopulationRegistry, POPULATION_DATA_PATH,
+    POPULATION_SAVE_PATH,
+};
+use crate::combat::DeathEvent;
+use crate::config::resolve_data_path;
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::gameplay_running;
+use crate::time::GameCalendar;
+use crate::world::chunk::{Chunk, ChunkResident, CHUNK_SIZE, TILE_PIXELS};
+use crate::world::entity::{spawn_npc, Character, CharacterState};
+use crate::world::map::MapManager;
+use crate::world::poi::{register_chunk_pois, PoiRegistry};
+
+/// NPC分布设置
+///
+/// - save_interval: 独特NPC状态有变化时写入存档的间隔（秒）
+#[derive(Resource, Debug, Clone)]
+pub struct PopulationSettings {
+    pub save_interval: f32,
+    pub save_path: String,
+}
+
+impl Default for PopulationSettings {
+    fn default() -> Self {
+        Self {
+            save_interval: 30.0,
+            save_path: POPULATION_SAVE_PATH.to_string(),
+        }
+    }
+}
+
+/// NPC分布插件
+///
+/// # 设计思路
+/// 1. 区块加载时按地形、气候与附近场景确定性地生成NPC，荒地有山贼、寺庙附近有僧人
+/// 2. 生成的NPC标记为区块居民，随区块一起卸载
+/// 3. 有名有姓的NPC记录生死、生命与位置，区块重新加载时恢复，村镇中的居民同样适用
+pub struct PopulationPlugin;
+
+impl Plugin for PopulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PopulationSettings>()
+            .add_systems(PreStartup, load_population_library)
+            .add_systems(Startup, load_population_registry)
+            .add_systems(
+                Update,
+                (
+                    spawn_chunk_population.after(register_chunk_pois),
+                    restore_persistent_npcs,
+                    record_persistent_deaths,
+                    track_persistent_npcs,
+                    save_population,
+                )
+                    .chain()
+                    .run_if(gameplay_running),
+            );
+    }
+}
+
+/// 加载分布规则，失败时不额外生成NPC
+fn load_population_library(mut commands: Commands, mut logger: Option<ResMut<GameLogger>>) {
+    let library = match PopulationLibrary::load(&resolve_data_path(POPULATION_DATA_PATH)) {
+        Ok(library) => library,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("NPC分布数据加载失败: {}", e));
+            }
+            PopulationLibrary::default()
+        }
+    };
+    commands.insert_resource(library);
+}
+
+/// 读取独特NPC存档，没有存档时全部按初始状态生成
+fn load_population_registry(
+    mut commands: Commands,
+    settings: Res<PopulationSettings>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let registry = if Path::new(&settings.save_path).exists() {
+        PopulationRegistry::load(&settings.save_path).unwrap_or_else(|e| {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("NPC存档读取失败: {}", e));
+            }
+            PopulationRegistry::default()
+        })
+    } else {
+        PopulationRegistry::default()
+    };
+    commands.insert_resource(registry);
+}
+
+/// 新加载的区块按分布规则生成NPC
+fn spawn_chunk_population(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    library: Res<PopulationLibrary>,
+    map_manager: Res<MapManager>,
+    pois: Res<PoiRegistry>,
+    chunks: Query<&Chunk, Added<Chunk>>,
+) {
+    let seed = (map_manager.seed as u64).wrapping_add(5);
+
+    for chunk in chunks.iter() {
+        let Some(data) = &chunk.data else {
+            continue;
+        };
+
+        for npc in place_population(&library, chunk.coord, data, &pois, seed) {
+            let rule = &library.rules[npc.rule];
+            let position = Vec3::new(
+                (chunk.coord.x * CHUNK_SIZE as i32 + npc.local_x as i32) as f32 * TILE_PIXELS,
+                (chunk.coord.y * CHUNK_SIZE as i32 + npc.local_y as i32) as f32 * TILE_PIXELS,
+                1.0,
+            );
+            let entity = spawn_npc(
+                &mut commands,
+                &asset_server,
+                position,
+                rule.npc_type,
+                &npc.name,
+            );
+            commands
+                .entity(entity)
+                .insert(ChunkResident { chunk: chunk.coord });
+            if rule.unique {
+                commands.entity(entity).insert(PersistentNpc {
+                    key: npc.key(&library, chunk.coord),
+                    respawn_hours: rule.respawn_hours,
+                });
+            }
+        }
+    }
+}
+
+/// 按存档恢复刚生成的独特NPC，已死且未到重新出现时间的直接移除
+fn restore_persistent_npcs(
+    mut commands: Commands,
+    calendar: Res<GameCalendar>,
+    mut registry: ResMut<PopulationRegistry>,
+    mut npcs: Query<(Entity, &PersistentNpc, &mut Character, &mut Transform), Added<PersistentNpc>>,
+) {
+    let now = calendar.total_hours();
+    for (entity, persistent, mut character, mut transform) in npcs.iter_mut() {
+        if registry.is_dead(&persistent.key, persistent.respawn_hours, now) {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        if let Some(state) = registry.get(&persistent.key) {
+            character.health = state.health.min(character.max_health);
+            transform.translation.x = state.position[0];
+            transform.translation.y = state.position[1];
+        }
+    }
+}
+
+/// 独特NPC死亡时写入存档
+fn record_persistent_deaths(
+    calendar: Res<GameCalendar>,
+    mut registry: ResMut<PopulationRegistry>,
+    mut deaths: EventReader<DeathEvent>,
+    npcs: Query<(&PersistentNpc, &Transform)>,
+) {
+    for death in deaths.read() {
+        if let Ok((persistent, transform)) = npcs.get(death.entity) {
+            registry.mark_dead(
+                &persistent.key,
+                transform.translation.truncate(),
+                calendar.total_hours(),
+            );
+        }
+    }
+}
+
+/// 记录活着的独特NPC的生命与位置
+#[allow(clippy::type_complexity)]
+fn track_persistent_npcs(
+    mut registry: ResMut<PopulationRegistry>,
+    npcs: Query<
+        (&PersistentNpc, &Character, &Transform),
+        Or<(Changed<Character>, Changed<Transform>)>,
+    >,
+) {
+    for (persistent, character, transform) in npcs.iter() {
+        if character.state == CharacterState::Dead {
+            continue;
+        }
+        registry.record(
+            &persistent.key,
+            transform.translation.truncate(),
+            character.health,
+        );
+    }
+}
+
+/// 独特NPC状态有变化时按间隔写入存档，退出游戏时立即写入
+fn save_population(
+    time: Res<Time>,
+    settings: Res<PopulationSettings>,
+    mut registry: ResMut<PopulationRegistry>,
+    mut exits: EventReader<AppExit>,
+    mut since_save: Local<f32>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    *since_save += time.delta_secs();
+    let exiting = exits.read().count() > 0;
+    if !registry.is_dirty() || (*since_save < settings.save_interval && !exiting) {
+        return;
+    }
+    *since_save = 0.0;
+    if let Err(e) = registry.save(&settings.save_path) {
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Error, &format!("NPC存档写入失败: {}", e));
+        }
+    }
+}
@@ -0,0 +1,12 @@
+/// NPC作息模块
+///
+/// 村民白天下地、入夜回家，守卫分班轮值，作息写在数据文件中
+///
+/// # 模块组成
+/// 1. timetable：作息数据、NPC作息组件与休眠标记
+/// 2. systems：作息插件，按时辰安排活动并让未加载区块中的NPC休眠
+mod systems;
+mod timetable;
+
+pub use systems::*;
+pub use timetable::*;
@@ -0,0 +1,228 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use super::{Activity, Dormant, NpcRoutine, ScheduleLibrary, SCHEDULE_DATA_PATH};
+use crate::config::resolve_data_path;
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::gameplay_running;
+use crate::time::GameCalendar;
+use crate::world::caravan::CaravanMember;
+use crate::world::chunk::{Chunk, ChunkCoord, CHUNK_SIZE, TILE_PIXELS};
+use crate::world::entity::{AiState, Character, Npc};
+
+/// 走到目标多近算到达（像素）
+const ARRIVE_DISTANCE: f32 = 12.0;
+
+/// NPC作息插件
+///
+/// # 设计思路
+/// 1. 作息表按时辰安排活动，时辰变化时改写NPC的AI状态与巡逻点，移动仍交给NPC的AI
+/// 2. 追击、逃跑、交谈等状态优先，结束后按当前时辰重新安排
+/// 3. 回家睡觉或走进未加载区块的NPC进入休眠，隐藏且不参与AI更新
+pub struct SchedulePlugin;
+
+impl Plugin for SchedulePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreStartup, load_schedule_library)
+            .add_systems(
+                Update,
+                (assign_npc_routines, sleep_unloaded_npcs, run_npc_schedules)
+                    .chain()
+                    .run_if(gameplay_running),
+            );
+    }
+}
+
+/// 加载作息数据，失败时NPC没有作息
+fn load_schedule_library(mut commands: Commands, mut logger: Option<ResMut<GameLogger>>) {
+    let library = match ScheduleLibrary::load(&resolve_data_path(SCHEDULE_DATA_PATH)) {
+        Ok(library) => library,
+        Err(e) => {
+            if let Some(logger) = logger.as_mut() {
+                logger.log(LogLevel::Error, &format!("作息数据加载失败: {}", e));
+            }
+            ScheduleLibrary::default()
+        }
+    };
+    commands.insert_resource(library);
+}
+
+/// 给新生成的NPC分配作息，商队成员随商队行动，不分配
+#[allow(clippy::type_complexity)]
+fn assign_npc_routines(
+    mut commands: Commands,
+    library: Res<ScheduleLibrary>,
+    npcs: Query<
+        (Entity, &Npc, &Character, &Transform),
+        (Added<Npc>, Without<NpcRoutine>, Without<CaravanMember>),
+    >,
+    mut assigned: Local<HashMap<usize, usize>>,
+) {
+    for (entity, npc, character, transform) in npcs.iter() {
+        let Some(schedule) = library.find(&character.name, npc.npc_type) else {
+            continue;
+        };
+        let count = assigned.entry(schedule).or_insert(0);
+        let timetable = *count % library.schedules[schedule].timetables.len().max(1);
+        *count += 1;
+
+        commands.entity(entity).insert(NpcRoutine {
+            schedule,
+            timetable,
+            home: transform.translation,
+            current: None,
+        });
+    }
+}
+
+/// 所在区块未加载的NPC休眠，区块重新加载后醒来并按当前时辰重新安排
+fn sleep_unloaded_npcs(
+    mut commands: Commands,
+    chunks: Query<&Chunk>,
+    mut npcs: Query<(
+        Entity,
+        &Transform,
+        &mut NpcRoutine,
+        &mut Visibility,
+        Option<&Dormant>,
+    )>,
+) {
+    let loaded: HashSet<ChunkCoord> = chunks
+        .iter()
+        .filter(|chunk| chunk.data.is_some())
+        .map(|chunk| chunk.coord)
+        .collect();
+
+    for (entity, transform, mut routine, mut visibility, dormant) in npcs.iter_mut() {
+        let tile = (transform.translation.truncate() / TILE_PIXELS)
+            .floor()
+            .as_ivec2();
+        let chunk = ChunkCoord {
+            x: tile.x.div_euclid(CHUNK_SIZE as i32),
+            y: tile.y.div_euclid(CHUNK_SIZE as i32),
+        };
+
+        match (loaded.contains(&chunk), dormant) {
+            (false, None | Some(Dormant::Asleep)) => {
+                commands.entity(entity).insert(Dormant::Unloaded);
+                *visibility = Visibility::Hidden;
+            }
+            (true, Some(Dormant::Unloaded)) => {
+                commands.entity(entity).remove::<Dormant>();
+                *visibility = Visibility::Inherited;
+                routine.current = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 按时辰安排NPC的活动
+#[allow(clippy::type_complexity)]
+fn run_npc_schedules(
+    mut commands: Commands,
+    calendar: Res<GameCalendar>,
+    library: Res<ScheduleLibrary>,
+    mut npcs: Query<(
+        Entity,
+        &mut Npc,
+        &mut NpcRoutine,
+        &Transform,
+        &mut Visibility,
+        Option<&Dormant>,
+    )>,
+) {
+    let hour = calendar.hour();
+
+    for (entity, mut npc, mut routine, transform, mut visibility, dormant) in npcs.iter_mut() {
+        if dormant == Some(&Dormant::Unloaded) {
+            continue;
+        }
+        let Some(schedule) = library.schedules.get(routine.schedule) else {
+            continue;
+        };
+        let Some(entry) = schedule.entry_at(routine.timetable, hour) else {
+            continue;
+        };
+        let Some(activity) = schedule.activity(routine.timetable, entry) else {
+            continue;
+        };
+
+        if matches!(
+            npc.ai_state,
+            AiState::Chase | AiState::Attack | AiState::Flee | AiState::Talk
+        ) {
+            routine.current = None;
+            continue;
+        }
+
+        if routine.current != Some(entry) {
+            if dormant == Some(&Dormant::Asleep) {
+                commands.entity(entity).remove::<Dormant>();
+                *visibility = Visibility::Inherited;
+            }
+            start_activity(&mut npc, routine.home, activity);
+            routine.current = Some(entry);
+            continue;
+        }
+
+        // 到达目的地后停下；NPC的AI空闲久了会自己闲逛，要待在原地的活动把它拉回来
+        match activity {
+            Activity::GoTo { .. } | Activity::Sleep => {
+                let arrived = npc.patrol_points.first().is_some_and(|target| {
+                    target.truncate().distance(transform.translation.truncate()) <= ARRIVE_DISTANCE
+                });
+                if npc.ai_state == AiState::Patrol && arrived {
+                    npc.ai_state = AiState::Idle;
+                    if *activity == Activity::Sleep && dormant.is_none() {
+                        commands.entity(entity).insert(Dormant::Asleep);
+                        *visibility = Visibility::Hidden;
+                    }
+                } else if npc.ai_state == AiState::Wander {
+                    npc.ai_state = AiState::Idle;
+                }
+            }
+            Activity::Idle if npc.ai_state == AiState::Wander => npc.ai_state = AiState::Idle,
+            _ => {}
+        }
+    }
+}
+
+/// 改写AI状态与巡逻点，开始一项活动
+fn start_activity(npc: &mut Npc, home: Vec3, activity: &Activity) {
+    let point = |offset: &[i32; 2]| {
+        home + Vec3::new(
+            offset[0] as f32 * TILE_PIXELS,
+            offset[1] as f32 * TILE_PIXELS,
+            0.0,
+        )
+    };
+
+    npc.current_patrol_index = 0;
+    match activity {
+        Activity::Idle => {
+            npc.patrol_points.clear();
+            npc.ai_state = AiState::Idle;
+        }
+        Activity::Wander => {
+            npc.patrol_points.clear();
+            npc.ai_state = AiState::Wander;
+        }
+        Activity::GoTo { offset } => {
+            npc.patrol_points = vec![point(offset)];
+            npc.ai_state = AiState::Patrol;
+        }
+        Activity::Patrol { route } => {
+            npc.patrol_points = route.iter().map(point).collect();
+            npc.ai_state = if npc.patrol_points.is_empty() {
+                AiState::Idle
+            } else {
+                AiState::Patrol
+            };
+        }
+        Activity::Sleep => {
+            npc.patrol_points = vec![home];
+            npc.ai_state = AiState::Patrol;
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::fs;
+
+use crate::world::entity::NpcType;
+
+/// 作息数据路径
+pub const SCHEDULE_DATA_PATH: &str = "src/config/npc_schedules.json";
+
+/// 作息中的一项活动
+///
+/// 位置都是相对住处的瓦片偏移，住处即NPC生成的位置
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum Activity {
+    /// 在原地待着
+    Idle,
+    /// 四处闲逛
+    Wander,
+    /// 走到某处后待着
+    GoTo { offset: [i32; 2] },
+    /// 在几个地点之间来回走动，如下地干活、巡逻
+    Patrol { route: Vec<[i32; 2]> },
+    /// 回住处睡觉，到家后不再活动
+    Sleep,
+}
+
+/// 作息表中的一项，从 hour 时起做 activity，直到下一项开始
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimetableEntry {
+    pub hour: u32,
+    pub activity: Activity,
+}
+
+/// 作息定义
+///
+/// 先按名字匹配，没有按名字匹配的再按NPC类型匹配。
+/// 有多张作息表时按生成顺序轮流分配，用于守卫换班
+#[derive(Debug, Clone, Deserialize)]
+pub struct NpcSchedule {
+    pub id: String,
+    #[serde(default)]
+    pub names: Vec<String>,
+    #[serde(default)]
+    pub npc_types: Vec<NpcType>,
+    pub timetables: Vec<Vec<TimetableEntry>>,
+}
+
+impl NpcSchedule {
+    /// 指定作息表在 hour 时的活动序号
+    ///
+    /// 取开始时刻不晚于 hour 的最后一项，子夜之后、第一项之前沿用前一天的最后一项
+    pub fn entry_at(&self, timetable: usize, hour: u32) -> Option<usize> {
+        let entries = self.timetables.get(timetable)?;
+        entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.hour <= hour)
+            .max_by_key(|(_, entry)| entry.hour)
+            .or_else(|| {
+                entries
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|(_, entry)| entry.hour)
+            })
+            .map(|(index, _)| index)
+    }
+
+    pub fn activity(&self, timetable: usize, entry: usize) -> Option<&Activity> {
+        self.timetables
+            .get(timetable)?
+            .get(entry)
+            .map(|entry| &entry.activity)
+    }
+}
+
+/// 全部作息数据
+#[derive(Resource, Debug, Clone, Default, Deserialize)]
+pub struct ScheduleLibrary {
+    pub schedules: Vec<NpcSchedule>,
+}
+
+impl ScheduleLibrary {
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let library: ScheduleLibrary = serde_json::from_str(&content)?;
+        if let Some(schedule) = library
+            .schedules
+            .iter()
+            .find(|schedule| schedule.timetables.is_empty())
+        {
+            return Err(format!("作息 {} 没有作息表", schedule.id).into());
+        }
+        Ok(library)
+    }
+
+    /// 为NPC查找作息，名字优先于类型
+    pub fn find(&self, name: &str, npc_type: NpcType) -> Option<usize> {
+        self.schedules
+            .iter()
+            .position(|schedule| schedule.names.iter().any(|n| n == name))
+            .or_else(|| {
+                self.schedules.iter().position(|schedule| {
+                    schedule.names.is_empty() && schedule.npc_types.contains(&npc_type)
+                })
+            })
+    }
+}
+
+/// NPC的日常作息
+#[derive(Component, Debug, Clone)]
+pub struct NpcRoutine {
+    /// 作息下标
+    pub schedule: usize,
+    /// 所用作息表下标
+    pub timetable: usize,
+    /// 住处（世界坐标）
+    pub home: Vec3,
+    /// 已安排的活动序号，被战斗等打断时为 None，空闲后重新安排
+    pub current: Option<usize>,
+}
+
+/// 休眠的NPC：在家睡觉或所在区块未加载，不参与AI更新
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dormant {
+    Asleep,
+    Unloaded,
+}
@@ -0,0 +1,13 @@
+/// 骑马与轻功模块
+///
+/// 骑马跑得快但进不了密林，轻功消耗内力踏水而过、攀上峭壁
+///
+/// # 模块组成
+/// 1. traversal：设置、坐骑与骑手组件
+/// 2. systems：骑马与轻功插件，负责马厩、上下马与轻功
+mod systems;
+#[allow(clippy::module_inception)]
+mod traversal;
+
+pub use systems::*;
+pub use traversal::*;
@@ -0,0 +1,229 @@
+use bevy::prelude::*;
+
+use super::{spawn_mount, Mount, Rider, TraversalSettings};
+use crate::events::input::GameAction;
+use crate::items::Encumbrance;
+use crate::logging::{GameLogger, LogLevel};
+use crate::resources::{gameplay_running, InputState};
+use crate::world::chunk::{
+    world_to_tile, Chunk, ChunkResident, ChunkStructure, NavGrid, CHUNK_SIZE, TILE_PIXELS,
+};
+use crate::world::entity::{handle_player_input, Character, CharacterState, Player};
+use crate::world::map::SceneType;
+use crate::world::physics::{MovementBody, TraversalMode};
+
+/// 马厩在场景中心旁边的偏移（瓦片）
+const STABLE_OFFSET: Vec2 = Vec2::new(2.0, -1.0);
+
+/// 骑马与轻功插件
+///
+/// # 设计思路
+/// 1. 移动方式记在移动体上，碰撞求解据此决定能走的地形：骑马进不了密林，轻功踏水攀崖
+/// 2. 村镇的马厩拴着马，骑上后马不再随区块卸载，下马后留在原地
+/// 3. 按住轻功键移动时持续消耗内力，起跳是一次纵跃；内力耗尽或松开按键后落地即恢复步行
+pub struct TraversalPlugin;
+
+impl Plugin for TraversalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TraversalSettings>().add_systems(
+            Update,
+            (
+                spawn_stable_mounts,
+                toggle_mount,
+                update_qinggong,
+                carry_riders,
+            )
+                .chain()
+                .run_if(gameplay_running)
+                .before(handle_player_input),
+        );
+    }
+}
+
+/// 新加载区块中的村镇在场景中心旁拴一匹马，随区块卸载
+fn spawn_stable_mounts(
+    mut commands: Commands,
+    settings: Res<TraversalSettings>,
+    chunks: Query<&Chunk, Added<Chunk>>,
+) {
+    for chunk in chunks.iter() {
+        let Some(data) = &chunk.data else {
+            continue;
+        };
+        for structure in data.structures() {
+            let ChunkStructure::Landmark {
+                local_x,
+                local_y,
+                scene_type: SceneType::Village | SceneType::Town | SceneType::City,
+                ..
+            } = structure
+            else {
+                continue;
+            };
+            let tile = Vec2::new(
+                (chunk.coord.x * CHUNK_SIZE as i32 + *local_x as i32) as f32,
+                (chunk.coord.y * CHUNK_SIZE as i32 + *local_y as i32) as f32,
+            ) + STABLE_OFFSET;
+            let mount = spawn_mount(
+                &mut commands,
+                (tile * TILE_PIXELS).extend(1.0),
+                "驿马",
+                settings.mount_speed_multiplier,
+            );
+            commands
+                .entity(mount)
+                .insert(ChunkResident { chunk: chunk.coord });
+        }
+    }
+}
+
+/// 按上马键骑上附近的马，骑着时下马
+#[allow(clippy::type_complexity)]
+fn toggle_mount(
+    mut commands: Commands,
+    input_state: Res<InputState>,
+    settings: Res<TraversalSettings>,
+    nav_grid: Option<Res<NavGrid>>,
+    mut players: Query<
+        (
+            Entity,
+            &mut Character,
+            &Transform,
+            &mut MovementBody,
+            Option<&Rider>,
+        ),
+        With<Player>,
+    >,
+    mut mounts: Query<(Entity, &mut Mount, &Transform), Without<Player>>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    if !input_state.is_action_just_pressed(GameAction::Mount) {
+        return;
+    }
+    let Ok((player, mut character, transform, mut body, rider)) = players.get_single_mut() else {
+        return;
+    };
+    if !character.is_grounded || !character.can_move {
+        return;
+    }
+
+    if let Some(rider) = rider {
+        if let Ok((_, mut mount, _)) = mounts.get_mut(rider.mount) {
+            mount.rider = None;
+        }
+        commands.entity(player).remove::<Rider>();
+        body.mode = TraversalMode::OnFoot;
+        character.state = CharacterState::Idle;
+        if let Some(logger) = logger.as_mut() {
+            logger.log(LogLevel::Info, "下马");
+        }
+        return;
+    }
+
+    // 水里和密林里上不了马
+    let position = transform.translation.truncate();
+    let blocked = body.in_water
+        || nav_grid.as_ref().is_some_and(|nav_grid| {
+            nav_grid
+                .cell(world_to_tile(position))
+                .is_some_and(|cell| cell.dense_forest)
+        });
+    if blocked {
+        return;
+    }
+
+    let nearest = mounts
+        .iter_mut()
+        .filter(|(_, mount, _)| mount.rider.is_none())
+        .map(|(entity, mount, mount_transform)| {
+            let distance = mount_transform.translation.truncate().distance(position);
+            (entity, mount, distance)
+        })
+        .filter(|(_, _, distance)| *distance <= settings.mount_range)
+        .min_by(|a, b| a.2.total_cmp(&b.2));
+    let Some((entity, mut mount, _)) = nearest else {
+        return;
+    };
+
+    mount.rider = Some(player);
+    // 骑走的马归玩家所有，不再随区块卸载
+    commands.entity(entity).remove::<ChunkResident>();
+    commands.entity(player).insert(Rider {
+        mount: entity,
+        speed_multiplier: mount.speed_multiplier,
+    });
+    body.mode = TraversalMode::Mounted;
+    character.state = CharacterState::Riding;
+    if let Some(logger) = logger.as_mut() {
+        logger.log(LogLevel::Info, &format!("骑上{}", mount.name));
+    }
+}
+
+/// 是否在按方向键
+fn is_moving(input_state: &InputState) -> bool {
+    [
+        GameAction::MoveForward,
+        GameAction::MoveBackward,
+        GameAction::MoveLeft,
+        GameAction::MoveRight,
+    ]
+    .into_iter()
+    .any(|action| input_state.is_action_active(action))
+}
+
+/// 按住轻功键施展轻功，移动时消耗内力，起跳为纵跃
+///
+/// 纵跃在空中时保持轻功，落地后才按按键与内力决定是否继续
+#[allow(clippy::type_complexity)]
+fn update_qinggong(
+    time: Res<Time>,
+    input_state: Res<InputState>,
+    settings: Res<TraversalSettings>,
+    mut players: Query<
+        (&mut Character, &mut MovementBody, Option<&Encumbrance>),
+        (With<Player>, Without<Rider>),
+    >,
+) {
+    let Ok((mut character, mut body, encumbrance)) = players.get_single_mut() else {
+        return;
+    };
+    if !character.is_grounded {
+        return;
+    }
+
+    // 超重时施展不了轻功
+    let active = input_state.is_action_active(GameAction::Qinggong)
+        && character.can_move
+        && character.qi >= settings.min_qi
+        && encumbrance.is_none_or(|e| e.can_use_qinggong());
+    if !active {
+        if body.mode == TraversalMode::Qinggong {
+            body.mode = TraversalMode::OnFoot;
+        }
+        return;
+    }
+    body.mode = TraversalMode::Qinggong;
+
+    if is_moving(&input_state) {
+        character.qi = (character.qi - settings.qinggong_qi_drain * time.delta_secs()).max(0.0);
+    }
+    if input_state.is_action_just_pressed(GameAction::Jump) && character.qi >= settings.leap_qi_cost
+    {
+        character.qi -= settings.leap_qi_cost;
+        body.launch(&mut character, settings.leap_speed);
+    }
+}
+
+/// 马跟着骑手走
+fn carry_riders(
+    riders: Query<(&Rider, &Transform), Without<Mount>>,
+    mut mounts: Query<&mut Transform, With<Mount>>,
+) {
+    for (rider, transform) in riders.iter() {
+        if let Ok(mut mount) = mounts.get_mut(rider.mount) {
+            mount.translation.x = transform.translation.x;
+            // 马在骑手身下，深度由排序系统按层级统一计算
+            mount.translation.y = transform.translation.y - 8.0;
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+
+use crate::render::components::{LayerComponent, RenderLayer};
+use crate::render::sorting::YSort;
+
+/// 坐骑的精灵尺寸
+const MOUNT_SIZE: Vec2 = Vec2::new(44.0, 30.0);
+
+/// 坐骑的颜色
+const MOUNT_COLOR: Color = Color::srgb(0.55, 0.38, 0.22);
+
+/// 骑马与轻功设置
+///
+/// - mount_range: 能骑上坐骑的距离（像素）
+/// - qinggong_speed_multiplier: 施展轻功时的移速倍率
+/// - qinggong_qi_drain: 施展轻功移动时每秒消耗的内力
+/// - leap_qi_cost / leap_speed: 轻功纵跃消耗的内力与起跳速度（像素/秒）
+/// - min_qi: 内力低于这个值时施展不出轻功
+#[derive(Resource, Debug, Clone)]
+pub struct TraversalSettings {
+    pub mount_range: f32,
+    /// 马匹的移速倍率
+    pub mount_speed_multiplier: f32,
+    pub qinggong_speed_multiplier: f32,
+    pub qinggong_qi_drain: f32,
+    pub leap_qi_cost: f32,
+    pub leap_speed: f32,
+    pub min_qi: f32,
+}
+
+impl Default for TraversalSettings {
+    fn default() -> Self {
+        Self {
+            mount_range: 40.0,
+            mount_speed_multiplier: 2.0,
+            qinggong_speed_multiplier: 2.2,
+            qinggong_qi_drain: 8.0,
+            leap_qi_cost: 15.0,
+            leap_speed: 320.0,
+            min_qi: 5.0,
+        }
+    }
+}
+
+/// 坐骑
+#[derive(Component, Debug, Clone)]
+pub struct Mount {
+    pub name: String,
+    pub speed_multiplier: f32,
+    /// 骑在上面的角色
+    pub rider: Option<Entity>,
+}
+
+/// 骑着坐骑的角色
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Rider {
+    pub mount: Entity,
+    pub speed_multiplier: f32,
+}
+
+/// 生成一匹马
+pub fn spawn_mount(commands: &mut Commands, position: Vec3, name: &str, speed: f32) -> Entity {
+    commands
+        .spawn((
+            Mount {
+                name: name.to_string(),
+                speed_multiplier: speed,
+                rider: None,
+            },
+            Name::new(format!("Mount: {}", name)),
+            Sprite {
+                color: MOUNT_COLOR,
+                custom_size: Some(MOUNT_SIZE),
+                ..default()
+            },
+            Transform::from_translation(position),
+            // 与骑手同一纵坐标时画在骑手后面
+            LayerComponent {
+                layer: RenderLayer::Character,
+                sub_order: -1,
+            },
+            YSort {
+                foot_offset: -MOUNT_SIZE.y * 0.5,
+                elevation: 0.0,
+            },
+        ))
+        .id()
+}
@@ -0,0 +1,11 @@
+/// 天气模块
+///
+/// # 模块组成
+/// 1. weather：天气类型、当前天气状态与地面状况
+/// 2. systems：天气插件，负责掷骰、表现与玩法影响
+mod systems;
+#[allow(clippy::module_inception)]
+mod weather;
+
+pub use systems::*;
+pub use weather::*;
@@ -0,0 +1,334 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::{
+    is_exposed_ground, precipitation_is_snow, zone_weather_factors, FogOverlay, GroundCondition,
+    PrecipitationLayer, WeatherChanged, WeatherKind, WeatherSettings, WeatherState,
+};
+use crate::logging::{GameLogger, LogLevel};
+use crate::render::components::ParticleEmitter;
+use crate::resources::gameplay_running;
+use crate::time::TimeSkipped;
+use crate::world::chunk::{
+    Chunk, ChunkCoord, ChunkData, ChunkManager, CHUNK_SIZE, LAYER_MOISTURE, LAYER_SNOW_COVER,
+    TILE_PIXELS,
+};
+use crate::world::entity::Player;
+use crate::world::map::{MapManager, TileType};
+
+/// 天气插件
+///
+/// # 设计思路
+/// 1. 按固定间隔，根据玩家所在气候区与气候配置中的概率掷骰决定天气
+/// 2. 天气变化时发出事件，表现层据此生成降水粒子与雾气遮罩
+/// 3. 玩法影响通过地面状况与视野倍率体现，由移动与 AI 系统读取
+/// 4. 积雪与地面湿度记在区块的附加数据层里，雨雪停后逐渐消退
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WeatherSettings>()
+            .init_resource::<WeatherState>()
+            .add_event::<WeatherChanged>();
+
+        app.add_systems(
+            Update,
+            (
+                roll_weather,
+                update_weather_visuals,
+                update_ground_cover,
+                update_ground_conditions,
+            )
+                .chain()
+                .run_if(gameplay_running),
+        );
+    }
+}
+
+/// 查询世界坐标所在的瓦片，返回瓦片类型、所在区块的数据与区块内坐标，区块未加载时返回 None
+fn sample_tile<'a>(
+    position: Vec3,
+    chunk_manager: &ChunkManager,
+    chunks: &'a Query<&Chunk>,
+) -> Option<(TileType, &'a ChunkData, usize, usize)> {
+    let chunk_size = CHUNK_SIZE as i32;
+    let tile_x = (position.x / TILE_PIXELS).floor() as i32;
+    let tile_y = (position.y / TILE_PIXELS).floor() as i32;
+    let coord = ChunkCoord {
+        x: tile_x.div_euclid(chunk_size),
+        y: tile_y.div_euclid(chunk_size),
+    };
+
+    let data = chunk_manager
+        .get_chunk_entity(coord)
+        .and_then(|entity| chunks.get(entity).ok())
+        .and_then(|chunk| chunk.data.as_ref())?;
+
+    let local_x = tile_x.rem_euclid(chunk_size) as usize;
+    let local_y = tile_y.rem_euclid(chunk_size) as usize;
+    let tile = data
+        .get_tile(local_x, local_y)
+        .and_then(TileType::from_u8)?;
+    Some((tile, data, local_x, local_y))
+}
+
+/// 定期根据玩家所在气候区掷骰决定天气
+///
+/// 休息等跳过时间后立即重掷，醒来时看到的是新的天气
+#[allow(clippy::too_many_arguments)]
+fn roll_weather(
+    time: Res<Time>,
+    mut time_skips: EventReader<TimeSkipped>,
+    settings: Res<WeatherSettings>,
+    map_manager: Res<MapManager>,
+    chunk_manager: Res<ChunkManager>,
+    chunks: Query<&Chunk>,
+    players: Query<&Transform, With<Player>>,
+    mut weather: ResMut<WeatherState>,
+    mut weather_events: EventWriter<WeatherChanged>,
+    mut logger: Option<ResMut<GameLogger>>,
+) {
+    let skipped = time_skips.read().count() > 0;
+    weather.timer.tick(time.delta());
+    if !skipped && !weather.timer.finished() {
+        return;
+    }
+    weather.timer = Timer::from_seconds(settings.roll_interval, TimerMode::Once);
+
+    let Ok(player) = players.get_single() else {
+        return;
+    };
+
+    let climate = map_manager.climate_config();
+    let climate_system = map_manager.climate_system();
+    let tile_x = (player.translation.x / TILE_PIXELS).floor() as i32;
+    let tile_y = (player.translation.y / TILE_PIXELS).floor() as i32;
+    let height = sample_tile(player.translation, &chunk_manager, &chunks)
+        .map_or(0.5, |(_, data, x, y)| data.get_height(x, y));
+    let zone = climate_system.get_climate_zone(tile_x, tile_y, height);
+
+    let (kind, intensity) = if climate.enable_weather {
+        let (precipitation_factor, fog_factor) = zone_weather_factors(zone);
+        let precipitation_chance = (climate.rain_probability * precipitation_factor).min(1.0);
+        let fog_chance = (climate.fog_probability * fog_factor).min(1.0 - precipitation_chance);
+
+        let mut rng = rand::thread_rng();
+        let roll = rng.gen::<f32>();
+        let variation = rng.gen_range(0.6..1.4);
+
+        if roll < precipitation_chance {
+            let kind = if precipitation_is_snow(zone, climate_system.current_season) {
+                WeatherKind::Snow
+            } else {
+                WeatherKind::Rain
+            };
+            (kind, (climate.rain_intensity * variation).clamp(0.1, 1.0))
+        } else if roll < precipitation_chance + fog_chance {
+            (
+                WeatherKind::Fog,
+                (climate.fog_density * variation).clamp(0.1, 1.0),
+            )
+        } else {
+            (WeatherKind::Clear, 0.0)
+        }
+    } else {
+        (WeatherKind::Clear, 0.0)
+    };
+
+    let previous = weather.kind;
+    weather.zone = Some(zone);
+    if kind == previous && (intensity - weather.intensity).abs() < 0.05 {
+        return;
+    }
+
+    weather.kind = kind;
+    weather.intensity = intensity;
+    weather_events.send(WeatherChanged {
+        current: kind,
+        intensity,
+    });
+
+    if let Some(logger) = logger.as_mut() {
+        logger.log(
+            LogLevel::Info,
+            &format!(
+                "天气变化：{} -> {}（强度 {:.2}，气候区 {:?}）",
+                previous.name(),
+                kind.name(),
+                intensity,
+                zone
+            ),
+        );
+    }
+}
+
+/// 天气变化时重建降水粒子层与雾气遮罩
+#[allow(clippy::type_complexity)]
+fn update_weather_visuals(
+    mut commands: Commands,
+    mut weather_events: EventReader<WeatherChanged>,
+    cameras: Query<Entity, With<Camera2d>>,
+    layers: Query<Entity, Or<(With<PrecipitationLayer>, With<FogOverlay>)>>,
+) {
+    let Some(event) = weather_events.read().last().copied() else {
+        return;
+    };
+
+    for entity in layers.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    match event.current {
+        WeatherKind::Rain | WeatherKind::Snow => {
+            // 粒子层挂在相机下，始终覆盖整个画面
+            let Ok(camera) = cameras.get_single() else {
+                return;
+            };
+            let emitter = if event.current == WeatherKind::Rain {
+                ParticleEmitter {
+                    rate: 400.0 * event.intensity,
+                    lifetime: 0.6,
+                    velocity: Vec2::new(-40.0, -600.0),
+                    spread: 640.0,
+                    color: Color::srgba(0.7, 0.8, 0.95, 0.6),
+                    active: true,
+                }
+            } else {
+                ParticleEmitter {
+                    rate: 150.0 * event.intensity,
+                    lifetime: 4.0,
+                    velocity: Vec2::new(-20.0, -80.0),
+                    spread: 640.0,
+                    color: Color::srgba(1.0, 1.0, 1.0, 0.85),
+                    active: true,
+                }
+            };
+            let layer = commands
+                .spawn((
+                    PrecipitationLayer,
+                    Name::new("Precipitation"),
+                    emitter,
+                    Transform::from_xyz(0.0, 400.0, 50.0),
+                ))
+                .id();
+            commands.entity(camera).add_child(layer);
+        }
+        WeatherKind::Fog => {
+            commands.spawn((
+                FogOverlay,
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.8, 0.82, 0.85, 0.55 * event.intensity)),
+            ));
+        }
+        WeatherKind::Clear => {}
+    }
+}
+
+/// 按天气积累或消退已加载区块的积雪与地面湿度
+///
+/// 只改附加数据层，不把区块标记为已修改：玩家改动过的区块连同积雪一起存档，
+/// 其余区块卸载后重新生成，地面恢复干燥
+fn update_ground_cover(
+    time: Res<Time>,
+    settings: Res<WeatherSettings>,
+    weather: Res<WeatherState>,
+    mut elapsed: Local<f32>,
+    mut chunks: Query<&mut Chunk>,
+) {
+    *elapsed += time.delta_secs();
+    if *elapsed < settings.ground_cover_interval {
+        return;
+    }
+    let step = std::mem::take(&mut *elapsed);
+
+    let (snow, moisture) = match weather.kind {
+        WeatherKind::Snow => (
+            settings.snow_accumulation * weather.intensity,
+            -settings.moisture_evaporation,
+        ),
+        WeatherKind::Rain => (
+            -settings.snow_melt,
+            settings.moisture_gain * weather.intensity,
+        ),
+        WeatherKind::Fog | WeatherKind::Clear => {
+            (-settings.snow_melt, -settings.moisture_evaporation)
+        }
+    };
+
+    for mut chunk in chunks.iter_mut() {
+        // 绕过变化检测修改图层，只有数值真的变了才标记区块已变化，
+        // 积满或干透的区块不会每次都被当作变化
+        let Some(data) = chunk.bypass_change_detection().data.as_mut() else {
+            continue;
+        };
+        let mut changed = false;
+        for (name, rate) in [(LAYER_SNOW_COVER, snow), (LAYER_MOISTURE, moisture)] {
+            if rate > 0.0 || data.layers().contains(name) {
+                changed |= apply_ground_cover(data, name, rate * step);
+            }
+        }
+        if changed {
+            chunk.set_changed();
+        }
+    }
+}
+
+/// 按变化量更新露天瓦片的地面图层，全部归零时移除图层，存档中不留空图层
+///
+/// 返回图层是否有变化
+fn apply_ground_cover(data: &mut ChunkData, name: &str, delta: f32) -> bool {
+    let mut covered = false;
+    let mut changed = false;
+    for y in 0..CHUNK_SIZE {
+        for x in 0..CHUNK_SIZE {
+            let exposed = data
+                .get_tile(x, y)
+                .and_then(TileType::from_u8)
+                .is_some_and(is_exposed_ground);
+            if !exposed {
+                continue;
+            }
+            let layers = data.layers_mut();
+            let previous = layers.get::<f32>(name, x, y);
+            let value = (previous + delta).clamp(0.0, 1.0);
+            if value != previous {
+                layers.set(name, x, y, value);
+                changed = true;
+            }
+            covered |= value > 0.0;
+        }
+    }
+    if !covered {
+        changed |= data.layers_mut().remove(name).is_some();
+    }
+    changed
+}
+
+/// 根据天气、脚下瓦片与地面积雪、湿度更新地面状况
+fn update_ground_conditions(
+    settings: Res<WeatherSettings>,
+    weather: Res<WeatherState>,
+    chunk_manager: Res<ChunkManager>,
+    chunks: Query<&Chunk>,
+    mut walkers: Query<(&Transform, &mut GroundCondition)>,
+) {
+    for (transform, mut ground) in walkers.iter_mut() {
+        let sample = sample_tile(transform.translation, &chunk_manager, &chunks);
+
+        ground.tile = sample.map(|(tile, ..)| tile);
+        ground.movement_multiplier = sample.map_or(1.0, |(tile, data, x, y)| {
+            let layers = data.layers();
+            weather.movement_multiplier(
+                tile,
+                layers.get(LAYER_SNOW_COVER, x, y),
+                layers.get(LAYER_MOISTURE, x, y),
+                &settings,
+            )
+        });
+    }
+}
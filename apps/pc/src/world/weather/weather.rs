@@ -0,0 +1,202 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::world::map::{Season, TileType, Zone};
+
+/// 天气类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeatherKind {
+    Clear,
+    Rain,
+    Snow,
+    Fog,
+}
+
+impl WeatherKind {
+    /// 天气名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            WeatherKind::Clear => "晴",
+            WeatherKind::Rain => "雨",
+            WeatherKind::Snow => "雪",
+            WeatherKind::Fog => "雾",
+        }
+    }
+}
+
+/// 天气配置
+#[derive(Resource, Debug, Clone)]
+pub struct WeatherSettings {
+    /// 重新掷骰的间隔（游戏时间秒数）
+    pub roll_interval: f32,
+    /// 雨天湿滑地面的最大减速比例
+    pub wet_slowdown: f32,
+    /// 雪天地面的最大减速比例
+    pub snow_slowdown: f32,
+    /// 浓雾时视野的最大缩减比例
+    pub fog_visibility_loss: f32,
+    /// 积雪与地面湿度的更新间隔（秒）
+    pub ground_cover_interval: f32,
+    /// 强度为 1 的降雪每秒积累的积雪厚度
+    pub snow_accumulation: f32,
+    /// 不下雪时每秒融化的积雪厚度
+    pub snow_melt: f32,
+    /// 强度为 1 的降雨每秒增加的地面湿度
+    pub moisture_gain: f32,
+    /// 不下雨时每秒蒸发的地面湿度
+    pub moisture_evaporation: f32,
+}
+
+impl Default for WeatherSettings {
+    fn default() -> Self {
+        Self {
+            roll_interval: 180.0,
+            wet_slowdown: 0.25,
+            snow_slowdown: 0.35,
+            fog_visibility_loss: 0.6,
+            ground_cover_interval: 5.0,
+            snow_accumulation: 0.01,
+            snow_melt: 0.004,
+            moisture_gain: 0.02,
+            moisture_evaporation: 0.005,
+        }
+    }
+}
+
+/// 各气候区的天气倾向 (降水倍率, 起雾倍率)
+pub fn zone_weather_factors(zone: Zone) -> (f32, f32) {
+    match zone {
+        Zone::Tropical => (1.6, 1.2),
+        Zone::Temperate => (1.0, 1.0),
+        Zone::Continental => (0.7, 0.8),
+        Zone::Polar => (1.0, 0.6),
+        Zone::Desert => (0.1, 0.2),
+        Zone::Mountains => (1.2, 1.8),
+    }
+}
+
+/// 降水是否以雪的形式落下
+pub fn precipitation_is_snow(zone: Zone, season: Season) -> bool {
+    match zone {
+        Zone::Polar => true,
+        Zone::Tropical | Zone::Desert => false,
+        Zone::Mountains => season != Season::Summer,
+        _ => season == Season::Winter,
+    }
+}
+
+/// 当前天气
+#[derive(Resource, Debug, Clone)]
+pub struct WeatherState {
+    /// 天气类型
+    pub kind: WeatherKind,
+    /// 强度 (0.0-1.0)
+    pub intensity: f32,
+    /// 掷骰时玩家所在的气候区
+    pub zone: Option<Zone>,
+    /// 距离下次掷骰的计时器
+    pub timer: Timer,
+}
+
+impl Default for WeatherState {
+    fn default() -> Self {
+        Self {
+            kind: WeatherKind::Clear,
+            intensity: 0.0,
+            zone: None,
+            // 开局很快掷一次骰，随后按配置的间隔
+            timer: Timer::from_seconds(1.0, TimerMode::Once),
+        }
+    }
+}
+
+impl WeatherState {
+    /// 视野倍率，用于缩小 NPC 的侦测范围
+    pub fn visibility_multiplier(&self, settings: &WeatherSettings) -> f32 {
+        let loss = match self.kind {
+            WeatherKind::Clear => 0.0,
+            WeatherKind::Fog => settings.fog_visibility_loss,
+            WeatherKind::Rain => settings.fog_visibility_loss * 0.3,
+            WeatherKind::Snow => settings.fog_visibility_loss * 0.4,
+        };
+        1.0 - loss * self.intensity
+    }
+
+    /// 在某种地面上的移速倍率
+    ///
+    /// 地面残留的积雪与积水在雨雪停后仍会拖慢脚步，取两者中较重的一项
+    pub fn movement_multiplier(
+        &self,
+        tile_type: TileType,
+        snow_cover: f32,
+        moisture: f32,
+        settings: &WeatherSettings,
+    ) -> f32 {
+        let falling = match self.kind {
+            WeatherKind::Rain if is_wet_ground(tile_type) => settings.wet_slowdown,
+            WeatherKind::Snow if tile_type != TileType::Water => settings.snow_slowdown,
+            _ => 0.0,
+        } * self.intensity;
+        let wet = if is_wet_ground(tile_type) {
+            settings.wet_slowdown * moisture
+        } else {
+            0.0
+        };
+        let lingering = wet.max(settings.snow_slowdown * snow_cover);
+        1.0 - falling.max(lingering)
+    }
+}
+
+/// 雨天会变得泥泞湿滑的地面
+fn is_wet_ground(tile_type: TileType) -> bool {
+    matches!(
+        tile_type,
+        TileType::Ground
+            | TileType::Path
+            | TileType::Grass
+            | TileType::Plains
+            | TileType::Sand
+            | TileType::Wasteland
+    )
+}
+
+/// 露天的地面，会积雪、被雨打湿
+pub fn is_exposed_ground(tile_type: TileType) -> bool {
+    !matches!(
+        tile_type,
+        TileType::Empty | TileType::Wall | TileType::Water | TileType::Floor | TileType::Door
+    )
+}
+
+/// 天气变化事件
+#[derive(Event, Debug, Clone, Copy)]
+pub struct WeatherChanged {
+    pub current: WeatherKind,
+    pub intensity: f32,
+}
+
+/// 实体脚下的地面状况，由天气系统更新
+#[derive(Component, Debug, Clone)]
+pub struct GroundCondition {
+    /// 脚下的瓦片，所在区块未加载时为 None
+    pub tile: Option<TileType>,
+    /// 天气造成的移速倍率
+    pub movement_multiplier: f32,
+}
+
+impl Default for GroundCondition {
+    fn default() -> Self {
+        Self {
+            tile: None,
+            movement_multiplier: 1.0,
+        }
+    }
+}
+
+/// 降水粒子层标记
+#[derive(Component)]
+pub struct PrecipitationLayer;
+
+/// 雾气遮罩标记
+#[derive(Component)]
+pub struct FogOverlay;